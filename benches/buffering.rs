@@ -34,6 +34,7 @@ fn benchmark_buffers(c: &mut Criterion) {
                 config.sinks["out"].buffer = BufferConfig::Memory {
                     max_events: 100,
                     when_full: Default::default(),
+                    storage: Default::default(),
                 };
 
                 let mut rt = runtime();
@@ -56,6 +56,7 @@ fn benchmark_http(c: &mut Criterion) {
                                 encoding: sinks::http::Encoding::Text.into(),
                                 request: Default::default(),
                                 tls: Default::default(),
+                                idempotency: Default::default(),
                             },
                         );
 
@@ -1,16 +1,33 @@
 fn main() {
+    println!("cargo:rerun-if-changed=proto/bigquery-storage.proto");
     println!("cargo:rerun-if-changed=proto/event.proto");
+    println!("cargo:rerun-if-changed=proto/kinesis-aggregation.proto");
+    println!("cargo:rerun-if-changed=proto/opentelemetry");
     println!("cargo:rerun-if-changed=proto/prometheus-remote.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-types.proto");
+    println!("cargo:rerun-if-changed=proto/vector.proto");
     let mut prost_build = prost_build::Config::new();
     prost_build.btree_map(&["."]);
     // It would be nice to just add these derives to all the types, but
     // prost automatically adds them already to enums, which causes the
     // extra derives to conflict with itself.
     prost_build.type_attribute(".prometheus.Label", "#[derive(Eq, Hash, Ord, PartialOrd)]");
-    prost_build
-        .compile_protos(
-            &["proto/event.proto", "proto/prometheus-remote.proto"],
+    tonic_build::configure()
+        .compile_with_config(
+            prost_build,
+            &[
+                "proto/bigquery-storage.proto",
+                "proto/event.proto",
+                "proto/kinesis-aggregation.proto",
+                "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                "proto/opentelemetry/proto/common/v1/common.proto",
+                "proto/opentelemetry/proto/logs/v1/logs.proto",
+                "proto/opentelemetry/proto/metrics/v1/metrics.proto",
+                "proto/opentelemetry/proto/resource/v1/resource.proto",
+                "proto/prometheus-remote.proto",
+                "proto/vector.proto",
+            ],
             &["proto/"],
         )
         .unwrap();
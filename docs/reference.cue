@@ -25,7 +25,7 @@ _values: {
 //
 // * `none` - compression is not applied
 // * `gzip` - gzip compression applied
-#CompressionAlgorithm: "none" | "gzip" | "lz4" | "snappy" | "zstd"
+#CompressionAlgorithm: "none" | "gzip" | "lz4" | "snappy" | "snappy_framed" | "zstd"
 
 #CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=9
 
@@ -58,7 +58,7 @@ _values: {
 // * `deprecated` - The component will be removed in a future version.
 #DevelopmentStatus: "beta" | "stable" | "deprecated"
 
-#EncodingCodec: "json" | "ndjson" | "text"
+#EncodingCodec: "cef" | "json" | "leef" | "ndjson" | "text"
 
 #Endpoint: {
 	description: string
@@ -49,6 +49,64 @@ api: {
 				of the address set using the `bind` parameter.
 				"""
 		}
+		cors: {
+			common:   false
+			required: false
+			type: object: {
+				examples: []
+				options: {
+					allowed_origins: {
+						common:      true
+						description: "The origins allowed to make requests, where `*` allows any origin."
+						required:    true
+						warnings: []
+						type: array: items: type: string: examples: ["https://example.com", "*"]
+					}
+					allowed_headers: {
+						common:      false
+						description: "The headers, besides the ones allowed for any request, the requests may include."
+						required:    false
+						warnings: []
+						type: array: {
+							default: []
+							items: type: string: examples: ["Content-Type", "Authorization"]
+						}
+					}
+					max_age_secs: {
+						common:      false
+						description: "How long browsers may cache the response to a preflight request."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+			description: """
+				Options for [cross-origin resource sharing](\(urls.cors)). By default,
+				pages from any origin may query the API.
+				"""
+		}
+		overrides_path: {
+			common:   false
+			required: false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector/overrides.toml"]
+			}
+			description: """
+				The file the overrides of runtime tunable options are loaded from
+				on start and persisted to by the `setComponentOption` mutation.
+
+				The sampler `rate`, and the `batch.max_events`,
+				`batch.timeout_secs` and fixed `request.concurrency` of batching
+				sinks can be changed at runtime through the API, without reloading
+				the configuration. Overriding `batch.max_events` can only lower the
+				size of batches.
+				"""
+		}
 	}
 
 	endpoints: {
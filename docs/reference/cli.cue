@@ -155,6 +155,18 @@ cli: {
 			type:    "string"
 			env_var: "VECTOR_CONFIG_YAML"
 		}
+		"pipeline": {
+			description: """
+				Run configuration files as a pipeline with its own topology and
+				data_dir, given as `<name>=<path>[,<path>...]`. Repeat to run
+				several pipelines in one process. The components of a pipeline
+				are reported as `<name>.<component>` in the metrics and the API.
+				Each pipeline must set its own `data_dir`, as only one of them
+				can use the default. Wildcard paths are supported. Can't be
+				combined with `--config`.
+				"""
+			type: "string"
+		}
 		"threads": {
 			_short: "t"
 			description: """
@@ -164,6 +176,23 @@ cli: {
 			type:    "integer"
 			env_var: "VECTOR_THREADS"
 		}
+		"pid-file": {
+			description: """
+				Write the PID of the process to this file, which is removed
+				on exit
+				"""
+			type:    "string"
+			env_var: "VECTOR_PID_FILE"
+		}
+		"open-files-limit": {
+			description: """
+				Raise the limit of open files of the process to this value at
+				startup, up to the hard limit unless Vector is allowed to
+				raise it. Only supported on Unix
+				"""
+			type:    "integer"
+			env_var: "VECTOR_OPEN_FILES_LIMIT"
+		}
 		"log-format": {
 			description: "Set the logging format [default: text]"
 			default:     "text"
@@ -175,6 +204,143 @@ cli: {
 	}
 
 	commands: {
+		"analyze": {
+			description: """
+				Report the types, cardinality, null rate and size distribution of each field
+				of sample events, then exit. Useful to design remap programs and sink schemas
+				for unfamiliar streams. The elements of arrays are reported as one field, such
+				as `tags[]`
+				"""
+
+			flags: _default_flags & {
+				"json": {
+					description: "Print the report as JSON"
+				}
+			}
+
+			options: {
+				"limit": {
+					_short:      "n"
+					description: "Analyze at most this many events"
+					type:        "integer"
+				}
+				"max-distinct": {
+					description: """
+						Number of distinct values to count per field, beyond which the
+						cardinality of the field is reported as a lower bound
+						"""
+					type:    "integer"
+					default: 10000
+				}
+			}
+
+			args: {
+				input: {
+					description: """
+						File of sample events, one per line, or `-` to read them from stdin.
+						Lines holding a JSON object become log events with its fields, other
+						lines log events with the line as their message
+						"""
+					type:    "string"
+					default: "-"
+				}
+			}
+		}
+
+		"buffer": {
+			description: """
+				List, inspect, export and migrate the disk buffers of the data directory,
+				then exit. `ls` reports the size, event count and age of the oldest event
+				of each buffer, `inspect <sink>` the details of the buffer of a sink,
+				`export <sink>` writes its events as JSON, one per line, and `migrate`
+				brings buffers written by older versions of Vector to the current format.
+				The buffers must not be in use by a running Vector
+				"""
+
+			flags: _default_flags & {
+				"json": {
+					description: "Print the reports of `ls` and `inspect` as JSON"
+				}
+			}
+
+			options: {
+				"data-dir": {
+					_short:      "d"
+					description: "The data directory holding the disk buffers"
+					type:        "string"
+					default:     "/var/lib/vector/"
+				}
+				"output": {
+					_short:      "o"
+					description: "File `export` writes the events to, instead of stdout"
+					type:        "string"
+				}
+			}
+
+			args: {
+				command: {
+					description: "The buffer command to run: `ls`, `inspect`, `export` or `migrate`"
+					type:        "string"
+				}
+				sink: {
+					description: "The sink whose buffer is inspected, exported or migrated"
+					type:        "string"
+				}
+			}
+		}
+
+		"config-schema": {
+			description: """
+				Print a JSON Schema of the configuration, derived from the options of the
+				compiled-in components, then exit. Useful for editors to complete config
+				files and for CI to check them without running Vector. The example configs
+				of most components don't list all their options, so only the types of the
+				options they list are checked
+				"""
+
+			flags: _default_flags & {
+				"compact": {
+					description: "Print the schema on a single line"
+				}
+			}
+		}
+
+		"dry-run": {
+			description: """
+				Run sample events through the transforms of the target config, without
+				starting its sources and sinks, and print what each transform received
+				and emitted, then exit. The events are inserted at all sources, or at the
+				sources or transforms given with `--insert-at`
+				"""
+
+			flags: _default_flags & {
+				"json": {
+					description: "Print the trace of each transform as a JSON object per line"
+				}
+			}
+
+			options: {
+				"events": {
+					_short: "e"
+					description: """
+						File of sample events, one per line. Lines holding a JSON object
+						become log events with its fields, other lines log events with the
+						line as their message
+						"""
+					type: "string"
+				}
+			}
+
+			args: {
+				paths: _paths_arg & {
+					description: """
+						Any number of Vector config files to run. If none are specified
+						the default config path `/etc/vector/vector.toml` will be targeted
+						"""
+				}
+			}
+		}
+
 		"generate": {
 			description: "Generate a Vector configuration containing a list of components"
 
@@ -208,7 +374,14 @@ cli: {
 		"list": {
 			description: "List available components, then exit"
 
-			flags: _default_flags
+			flags: _default_flags & {
+				"options": {
+					description: """
+						Include the options of the example config of each component, with their
+						types and example values
+						"""
+				}
+			}
 
 			options: {
 				"format": {
@@ -330,6 +330,8 @@ components: {
 			if enabled {
 				adaptive_concurrency:       bool | *true
 				concurrency:                uint8 | *5
+				headers:                    bool | *true
+				ordered:                    bool | *false
 				rate_limit_duration_secs:   uint8
 				rate_limit_num:             uint16
 				retry_initial_backoff_secs: uint8
@@ -657,6 +659,15 @@ components: {
 							examples: ["/path/to/certificate_authority.crt"]
 						}
 					}
+					extra_ca_file: {
+						common:      false
+						description: "Absolute path to a CA certificate file, in DER or PEM format (X.509), or an inline CA certificate in PEM format, trusted on top of the system CA certificates, or of `ca_file` if set. Useful to trust the certificates of an internal gateway without losing the system ones."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["/path/to/internal_ca.crt"]
+						}
+					}
 					crt_file: {
 						common:      true
 						description: "Absolute path to a certificate file used to identify this connection, in DER or PEM format (X.509) or PKCS#12, or an inline certificate in PEM format. If this is set and is not a PKCS#12 archive, `key_file` must also be set."
@@ -685,6 +696,16 @@ components: {
 						}
 					}
 
+					server_name: {
+						common:      false
+						description: "The name sent to the remote host with Server Name Indication (SNI), and verified against its TLS certificate if `verify_hostname` is `true`, instead of the host connected to. Useful when the remote host is reached through an IP address or a gateway whose certificate doesn't match the connect address."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["logs.example.com"]
+						}
+					}
+
 					if Args.can_verify_certificate {
 						verify_certificate: {
 							common:      false
@@ -782,6 +803,45 @@ components: {
 				}
 			}
 
+			_http_cors: {
+				common:      false
+				description: "Options for [cross-origin resource sharing](\(urls.cors)), so that pages of browser-based apps can send requests. Preflight requests are answered automatically."
+				required:    false
+				warnings: []
+				type: object: {
+					examples: []
+					options: {
+						allowed_origins: {
+							common:      true
+							description: "The origins allowed to make requests, where `*` allows any origin."
+							required:    true
+							warnings: []
+							type: array: items: type: string: examples: ["https://example.com", "*"]
+						}
+						allowed_headers: {
+							common:      false
+							description: "The headers, besides the ones allowed for any request, the requests may include."
+							required:    false
+							warnings: []
+							type: array: {
+								default: []
+								items: type: string: examples: ["Content-Type", "Authorization"]
+							}
+						}
+						max_age_secs: {
+							common:      false
+							description: "How long browsers may cache the response to a preflight request."
+							required:    false
+							warnings: []
+							type: uint: {
+								default: null
+								unit:    "seconds"
+							}
+						}
+					}
+				}
+			}
+
 			_types: {
 				common:      true
 				description: "Key/value pairs representing mapped log field names and types. This is used to coerce log fields into their proper types."
@@ -811,6 +871,37 @@ components: {
 				}
 			}
 
+			if Kind == "transform" {
+				on_panic: {
+					common:      false
+					description: "Configures what happens when the transform panics."
+					required:    false
+					type: object: options: {
+						max_restarts: {
+							common:      false
+							description: "The maximum number of times the transform is restarted. Once it panics again, Vector shuts down as with the `abort` policy."
+							required:    false
+							type: uint: {
+								default: 5
+								unit:    null
+							}
+						}
+						policy: {
+							common:      true
+							description: "The policy to apply when the transform panics."
+							required:    false
+							type: string: {
+								default: "abort"
+								enum: {
+									abort:   "Shuts down Vector."
+									restart: "Rebuilds the transform after a backoff, starting at one second and doubling up to a minute, and lets it resume with the remaining events. The event being processed during the panic is lost. Only the panic exhausting the restarts writes a crash report."
+								}
+							}
+						}
+					}
+				}
+			}
+
 			"type": {
 				description: "The component type. This is a required field for all components and tells Vector which component to use."
 				required:    true
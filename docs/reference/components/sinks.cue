@@ -83,6 +83,20 @@ components: sinks: [Name=string]: {
 								unit: "bytes"
 							}
 						}
+						storage: {
+							common:        false
+							description:   "How events are held in the memory buffer. Serialized storage fits several times as many events in the same amount of memory, at the cost of encoding and decoding each event."
+							required:      false
+							relevant_when: "type = \"memory\""
+							type: string: {
+								default: "events"
+								enum: {
+									events:     "Stores events as they are. This is the most performant."
+									serialized: "Stores events serialized in blocks, in the format of the disk buffer."
+									lz4:        "Stores events serialized in blocks that are compressed with [LZ4](\(urls.lz4)), trading more CPU for less memory."
+								}
+							}
+						}
 						type: {
 							common:      true
 							description: "The buffer's type and storage mechanism."
@@ -127,6 +141,18 @@ components: sinks: [Name=string]: {
 							if list.Contains(sinks[Name].features.send.compression.algorithms, "gzip") {
 								gzip: "[Gzip](\(urls.gzip)) standard DEFLATE compression."
 							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "lz4") {
+								lz4: "[LZ4](\(urls.lz4)) compression in the frame format."
+							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "snappy") {
+								snappy: "[Snappy](\(urls.snappy)) compression of the whole request as a single block."
+							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "snappy_framed") {
+								snappy_framed: "[Snappy](\(urls.snappy)) compression in the framing format."
+							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "zstd") {
+								zstd: "[Zstandard](\(urls.zstd)) compression."
+							}
 						}
 					}
 				}
@@ -145,6 +171,154 @@ components: sinks: [Name=string]: {
 								required:    true
 								type: string: examples: sinks[Name].features.send.encoding.codec.enum
 							}
+
+							if sinks[Name].features.send.encoding.codec.enum != null {
+								if list.Contains(sinks[Name].features.send.encoding.codec.enum, "cef") {
+									cef: {
+										common:      false
+										description: "The headers and extensions of the [CEF](\(urls.cef)) messages written by the `cef` codec."
+										required:    false
+										type: object: {
+											examples: []
+											options: {
+												device_event_class_id_field: {
+													common:      false
+													description: "The field holding the Device Event Class ID. `-` is written if it is missing."
+													required:    false
+													type: string: default: "event_id"
+												}
+												device_product: {
+													common:      true
+													description: "The Device Product header."
+													required:    false
+													type: string: {
+														default: "Vector"
+														examples: ["Threat Manager"]
+													}
+												}
+												device_vendor: {
+													common:      true
+													description: "The Device Vendor header."
+													required:    false
+													type: string: {
+														default: "Vector"
+														examples: ["Security Co"]
+													}
+												}
+												device_version: {
+													common:      true
+													description: "The Device Version header, the version of Vector by default."
+													required:    false
+													type: string: {
+														default: null
+														examples: ["1.0"]
+													}
+												}
+												extensions: {
+													common:      true
+													description: "The extension keys, and the fields holding their values. Missing fields are skipped. By default, the message is written as `msg`."
+													required:    false
+													type: object: {
+														examples: [{src: "client.ip", act: "action", msg: "message"}]
+														options: {}
+													}
+												}
+												name_field: {
+													common:      false
+													description: "The field holding the Name header. `-` is written if it is missing."
+													required:    false
+													type: string: {
+														default: "message"
+														examples: ["description"]
+													}
+												}
+												severity_field: {
+													common:      false
+													description: "The field holding the Severity header. `Unknown` is written if it is missing."
+													required:    false
+													type: string: default: "severity"
+												}
+											}
+										}
+									}
+								}
+
+								if list.Contains(sinks[Name].features.send.encoding.codec.enum, "leef") {
+									leef: {
+										common:      false
+										description: "The headers and attributes of the [LEEF](\(urls.leef)) messages written by the `leef` codec."
+										required:    false
+										type: object: {
+											examples: []
+											options: {
+												attributes: {
+													common:      true
+													description: "The attribute keys, and the fields holding their values. Missing fields are skipped. By default, the message is written as `msg`."
+													required:    false
+													type: object: {
+														examples: [{src: "client.ip", act: "action", msg: "message"}]
+														options: {}
+													}
+												}
+												delimiter: {
+													common:      false
+													description: "The character separating the attributes. LEEF 1.0 always uses a tab."
+													required:    false
+													type: string: {
+														default: "\t"
+														examples: ["^"]
+													}
+												}
+												event_id_field: {
+													common:      false
+													description: "The field holding the EventID header. `-` is written if it is missing."
+													required:    false
+													type: string: default: "event_id"
+												}
+												product: {
+													common:      true
+													description: "The Product Name header."
+													required:    false
+													type: string: {
+														default: "Vector"
+														examples: ["StealthWatch"]
+													}
+												}
+												product_version: {
+													common:      true
+													description: "The Product Version header, the version of Vector by default."
+													required:    false
+													type: string: {
+														default: null
+														examples: ["1.0"]
+													}
+												}
+												vendor: {
+													common:      true
+													description: "The Vendor header."
+													required:    false
+													type: string: {
+														default: "Vector"
+														examples: ["Lancope"]
+													}
+												}
+												version: {
+													common:      false
+													description: "The version of the LEEF format."
+													required:    false
+													type: string: {
+														default: "2.0"
+														enum: {
+															"1.0": "LEEF 1.0, with tab separated attributes."
+															"2.0": "LEEF 2.0, with the attribute delimiter in the header."
+														}
+													}
+												}
+											}
+										}
+									}
+								}
+							}
 						}
 
 						if sinks[Name].features.healthcheck.enabled {except_fields: {
@@ -167,6 +341,29 @@ components: sinks: [Name=string]: {
 								}
 							}
 
+							schema: {
+								common:      false
+								description: """
+									Coerces the specified fields to the given type before encoding the events, so that strict
+									backends don't reject a whole batch for a single field of the wrong type. Each field maps
+									to its type (`string`, `integer`, `float`, `boolean` or `timestamp`), or to a table with
+									the `type` of the field, what to do when a value can't be coerced (`on_error`: `null`,
+									the default, `drop` or `keep`), what to do with null values (`on_null`: `keep`, the
+									default, or `drop`), and a `default` used for null and missing values.
+									"""
+								required:    false
+								type: object: {
+									examples: [
+										{
+											status:   "integer"
+											duration: {type: "float", on_error: "drop"}
+											user: {type: "string", default: "anonymous"}
+										},
+									]
+									options: {}
+								}
+							}
+
 							timestamp_format: {
 								common:      false
 								description: "How to format event timestamps."
@@ -196,6 +393,23 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		max_event_age_secs: {
+			common: false
+			description: """
+				Events whose timestamp is older than this are dropped before
+				reaching the sink, and counted by the `events_expired_total`
+				internal metric. They aren't sent to any other output. Useful to
+				skip stale data after a long outage rather than delay fresh data
+				while the backlog drains. Overrides the global [`max_event_age_secs`](\(urls.vector_configuration)#max_event_age_secs).
+				"""
+			required: false
+			type: uint: {
+				default:  null
+				examples: [3600, 86400]
+				unit:     "seconds"
+			}
+		}
+
 		if sinks[Name].features.send != _|_ {
 			if sinks[Name].features.send.request.enabled {
 				request: {
@@ -247,6 +461,23 @@ components: sinks: [Name=string]: {
 									unit:    "requests"
 								}
 							}
+							if sinks[Name].features.send.request.headers {
+								headers: {
+									common:      false
+									description: "Headers added to the requests, overriding the global `request.headers` of the same name. Values are either strings, or secrets read when the sink starts, from an environment variable with `{ env = \"NAME\" }` or a file with `{ file = \"/path\" }`, which keeps them out of the configuration and out of the logs of the requests. Headers set by the sink itself take precedence."
+									required:    false
+									type: object: {
+										examples: [{"X-Team": "logs", "X-Api-Key": {env: "INTAKE_API_KEY"}}]
+										options: {}
+									}
+								}
+							}
+							ordered: {
+								common:      false
+								description: "Sends a request only once the previous request of the same partition, including its retries, completed, so the service receives the requests of each partition in order. Requests of different partitions are still sent concurrently, up to `concurrency`."
+								required:    false
+								type: bool: default: sinks[Name].features.send.request.ordered
+							}
 							rate_limit_duration_secs: {
 								common:      true
 								description: "The time window, in seconds, used for the `rate_limit_num` option."
@@ -41,6 +41,7 @@ components: sinks: aws_cloudwatch_logs: components._aws & {
 				enabled:                    true
 				adaptive_concurrency:       false
 				concurrency:                5
+				headers:                    false
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -40,6 +40,7 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 			request: {
 				enabled:                    true
 				concurrency:                5
+				headers:                    false
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -81,6 +82,38 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 	}
 
 	configuration: {
+		aggregation: {
+			common:      false
+			description: "Packs multiple events into each Firehose record, each event terminated by a newline, reducing the number of records billed."
+			required:    false
+			warnings: []
+			type: object: options: {
+				enabled: {
+					common:      true
+					description: "Enables the aggregation of events into records."
+					required:    false
+					type: bool: default: false
+				}
+				max_bytes: {
+					common:      false
+					description: "The maximum size of the events packed into one record. Records are never larger than the 1,000 KiB Firehose allows."
+					required:    false
+					type: uint: {
+						default: 1024000
+						unit:    "bytes"
+					}
+				}
+				max_events: {
+					common:      false
+					description: "The maximum number of events packed into one record."
+					required:    false
+					type: uint: {
+						default: null
+						unit:    "events"
+					}
+				}
+			}
+		}
 		stream_name: {
 			description: "The [stream name](\(urls.aws_cloudwatch_logs_stream_name)) of the target Kinesis Firehose delivery stream."
 			required:    true
@@ -95,4 +128,18 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		aggregation: {
+			title: "Aggregation"
+			body:  """
+				Firehose bills the data put in increments of 5 KB per record. With
+				`aggregation.enabled`, Vector packs the events of a batch into records of
+				newline-delimited events, up to `aggregation.max_events` events or
+				`aggregation.max_bytes` bytes per record, which the destinations of the
+				delivery stream receive as they would receive single events ending with a
+				newline.
+				"""
+		}
+	}
 }
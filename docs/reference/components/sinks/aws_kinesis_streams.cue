@@ -40,6 +40,7 @@ components: sinks: aws_kinesis_streams: components._aws & {
 			request: {
 				enabled:                    true
 				concurrency:                5
+				headers:                    false
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -81,9 +82,52 @@ components: sinks: aws_kinesis_streams: components._aws & {
 	}
 
 	configuration: {
-		partition_key_field: {
+		aggregation: {
+			common:      false
+			description: "Packs multiple events into each Kinesis record in the [aggregated record format](\(urls.aws_kinesis_aggregation)) of the Kinesis Producer Library, reducing the number of records billed."
+			required:    false
+			warnings: ["Consumers must deaggregate the records, as the Kinesis Client Library does."]
+			type: object: options: {
+				enabled: {
+					common:      true
+					description: "Enables the aggregation of events into records."
+					required:    false
+					type: bool: default: false
+				}
+				max_bytes: {
+					common:      false
+					description: "The maximum size of the events packed into one record. Records are never larger than the 1 MiB Kinesis allows."
+					required:    false
+					type: uint: {
+						default: 51200
+						unit:    "bytes"
+					}
+				}
+				max_events: {
+					common:      false
+					description: "The maximum number of events packed into one record."
+					required:    false
+					type: uint: {
+						default: null
+						unit:    "events"
+					}
+				}
+			}
+		}
+		partition_key: {
 			common:      true
-			description: "The log field used as the Kinesis record's partition key value."
+			description: "The Kinesis record's partition key value. Events missing the fields of the template are dropped."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ user_id }}", "{{ host }}-{{ service }}"]
+				templateable: true
+			}
+		}
+		partition_key_field: {
+			common:      false
+			description: "The log field used as the Kinesis record's partition key value. Superseded by `partition_key`, and cannot be set along with it."
 			required:    false
 			warnings: []
 			type: string: {
@@ -107,6 +151,22 @@ components: sinks: aws_kinesis_streams: components._aws & {
 	}
 
 	how_it_works: {
+		aggregation: {
+			title: "Aggregation"
+			body:  """
+				Kinesis bills every record put, and each shard accepts at most 1,000
+				records per second. With `aggregation.enabled`, Vector packs the events of
+				a batch into records in the
+				[aggregated record format](\(urls.aws_kinesis_aggregation)) of the Kinesis
+				Producer Library, up to `aggregation.max_events` events or
+				`aggregation.max_bytes` bytes per record. Events are only packed together
+				with events of the same partition key, so they still end up on the same
+				shard, in order. Aggregates of a single event are sent as plain records.
+
+				Consumers using the Kinesis Client Library, or the deaggregation modules of
+				the Kinesis Producer Library, unpack the records again.
+				"""
+		}
 		partitioning: {
 			title: "Partitioning"
 			body:  """
@@ -114,10 +174,10 @@ components: sinks: aws_kinesis_streams: components._aws & {
 				[Kinesis record's partition key](\(urls.aws_kinesis_partition_key)), evenly
 				distributing records across your Kinesis partitions. Depending on your use case
 				this might not be sufficient since random distribution does not preserve order.
-				To override this, you can supply the `partition_key_field` option. This option
-				presents an alternate field on your event to use as the partition key value instead.
-				This is useful if you have a field already on your event, and it also pairs
-				nicely with the [`add_fields` transform][docs.transforms.add_fields].
+				To override this, you can supply the `partition_key` option, a template
+				rendered for each event, such as `{{ user_id }}`. This is useful if you
+				have a field already on your event, and it also pairs nicely with the
+				[`add_fields` transform][docs.transforms.add_fields].
 				"""
 			sub_sections: [
 				{
@@ -126,7 +186,7 @@ components: sinks: aws_kinesis_streams: components._aws & {
 						Kenesis requires a value for the partition key and therefore if the key is
 						missing or the value is blank the event will be dropped and a
 						[`warning` level log event][docs.monitoring#logs] will be logged. As such,
-						the field specified in the `partition_key_field` option should always contain
+						the fields of the `partition_key` template should always contain
 						a value.
 						"""
 				},
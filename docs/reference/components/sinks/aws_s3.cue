@@ -34,12 +34,13 @@ components: sinks: aws_s3: components._aws & {
 				codec: {
 					enabled: true
 					default: "text"
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "parquet", "text"]
 				}
 			}
 			request: {
 				enabled:                    true
 				concurrency:                50
+				headers:                    false
 				rate_limit_duration_secs:   1
 				rate_limit_num:             250
 				retry_initial_backoff_secs: 1
@@ -128,6 +129,16 @@ components: sinks: aws_s3: components._aws & {
 				default: "text/x-log"
 			}
 		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the sequence numbers of the partitions, with `filename_strategy` set to `sequence`. Defaults to the global `data_dir` option."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
 		filename_append_uuid: {
 			category:    "File Naming"
 			common:      false
@@ -146,6 +157,45 @@ components: sinks: aws_s3: components._aws & {
 				default: "log"
 			}
 		}
+		filename_sequence_width: {
+			category:    "File Naming"
+			common:      false
+			description: "The number of digits the sequence numbers are zero padded to, with `filename_strategy` set to `sequence`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		filename_strategy: {
+			category:    "File Naming"
+			common:      false
+			description: "How the object written for each batch is named, after the `key_prefix`. Strategies other than `time_uuid` can't be used with multipart uploads."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "time_uuid"
+				enum: {
+					time_uuid:    "The time of the batch formatted with `filename_time_format`, followed by a UUID v4 unless `filename_append_uuid` is disabled."
+					content_hash: "The hex encoded SHA-256 hash of the object, so that the same batch always gets the same name."
+					sequence:     "The sequence number of the batch in its partition, zero padded to `filename_sequence_width` digits and persisted in the `data_dir`."
+					template:     "The rendered `filename_template`. A key recently written by the sink gets a `-<n>` suffix instead of being overwritten."
+				}
+			}
+		}
+		filename_template: {
+			category:    "File Naming"
+			common:      false
+			description: "The name of the objects, with `filename_strategy` set to `template`. Events rendering different names are batched separately."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ application_id }}-%H", "{{ host }}/%Y%m%d%H"]
+				templateable: true
+			}
+		}
 		filename_time_format: {
 			category:    "File Naming"
 			common:      false
@@ -212,6 +262,87 @@ components: sinks: aws_s3: components._aws & {
 				templateable: true
 			}
 		}
+		multipart: {
+			common:      false
+			description: "Streams the batches of each partition into a multipart upload as they are ready, instead of writing every batch as an object of its own. This allows writing very large objects without holding them in memory."
+			required:    false
+			warnings: [
+				"Events are acknowledged once the part holding them is uploaded, or the upload is completed, so that events of partitions with little traffic can wait up to `object_timeout_secs` for their acknowledgement. Requests waiting for it count against `request.concurrency`.",
+				"Uploads that fail to complete are aborted, which loses the events of their uploaded parts. Uploads that can't be aborted either are left incomplete in the bucket, a lifecycle rule aborting incomplete multipart uploads should be configured to clean them up.",
+			]
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Enables multipart uploads."
+						required:    false
+						type: bool: default: false
+					}
+					object_max_bytes: {
+						common:      false
+						description: "The size an object is completed at, after which the following batches start a new one."
+						required:    false
+						type: uint: {
+							default: 5000000000
+							unit:    "bytes"
+						}
+					}
+					object_timeout_secs: {
+						common:      false
+						description: "The maximum age of an upload before it is completed, making its object visible."
+						required:    false
+						type: uint: {
+							default: 3600
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
+		parquet: {
+			common:      false
+			description: "Options for the `parquet` codec, writing each batch as an [Apache Parquet][urls.apache_parquet] file."
+			required:    false
+			warnings: ["The `parquet` codec can't be used with multipart uploads."]
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      true
+						description: "The codec the column chunks of the files are compressed with. The `compression` option of the sink is ignored with the `parquet` codec."
+						required:    false
+						type: string: {
+							default: "snappy"
+							enum: {
+								none:   "The column chunks are not compressed."
+								snappy: "The column chunks are compressed with Snappy."
+								gzip:   "The column chunks are compressed with gzip."
+								zstd:   "The column chunks are compressed with Zstandard."
+							}
+						}
+					}
+					row_group_size: {
+						common:      false
+						description: "The maximum number of rows of the row groups of the files."
+						required:    false
+						type: uint: {
+							default: 10000
+							unit:    "events"
+						}
+					}
+					schema: {
+						common:      true
+						description: "The columns of the files, named after the path of the fields they hold, and their type, in the order of the columns. The fields not part of the schema are dropped, and the values not matching the type of their column are written as nulls. If unset, the schema of each file is inferred from its events."
+						required:    false
+						type: object: {
+							examples: [{"timestamp": "timestamp", "status": "int64", "message": "string", "http.duration": "double"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
 		server_side_encryption: {
 			category:    "Encryption"
 			common:      false
@@ -274,6 +405,27 @@ components: sinks: aws_s3: components._aws & {
 	}
 
 	how_it_works: {
+		parquet: {
+			title: "Parquet files"
+			body: """
+				With `encoding.codec` set to `parquet`, each batch is written as an
+				[Apache Parquet][urls.apache_parquet] file, with the `.parquet`
+				extension, ready to be queried by Athena or Spark. Nested fields
+				become columns named after their path, such as `http.status`, and
+				arrays are written as JSON strings.
+
+				Without an explicit `parquet.schema`, the type of each column is
+				inferred from the values of the batch: columns holding integers and
+				floats become `double` columns, strings in the RFC 3339 format
+				become `timestamp` columns, and columns holding values of other
+				mixed types become `string` columns. As the events of two batches
+				may differ, so may the schemas of their files, which is why an
+				explicit schema is recommended for tables with a fixed schema.
+
+				The `timestamp` type holds the milliseconds since the Unix epoch,
+				and all the columns are optional.
+				"""
+		}
 		cross_account: {
 			title: "Cross account object writing"
 			body:  """
@@ -355,6 +507,23 @@ components: sinks: aws_s3: components._aws & {
 
 				You can control the resulting name via the `key_prefix`, `filename_time_format`,
 				and `filename_append_uuid` options.
+
+				When downstream deduplication relies on deterministic object names, the
+				`filename_strategy` option names the objects after the SHA-256 hash of their
+				content, after a sequence number of their partition, which is persisted in the
+				`data_dir` so that numbers aren't reused after a restart, or after the
+				`filename_template` only:
+
+				```text
+				<key_prefix><sha256>.log
+				<key_prefix><sequence>.log
+				<key_prefix><filename_template>.log
+				```
+
+				As a template can render the same name for several batches, a name already used
+				by the sink gets a `-<n>` suffix, such as `billing-1.log`, rather than overwriting
+				the earlier object. Names are only tracked in memory, for about an hour or for the
+				last 100,000 of them, so names used before a restart or long ago can be reused.
 				"""
 		}
 
@@ -8,7 +8,7 @@ components: sinks: aws_sqs: components._aws & {
 		commonly_used: false
 		delivery:      "at_least_once"
 		development:   "beta"
-		egress_method: "stream"
+		egress_method: "batch"
 		service_providers: ["AWS"]
 	}
 
@@ -16,6 +16,13 @@ components: sinks: aws_sqs: components._aws & {
 		buffer: enabled:      true
 		healthcheck: enabled: true
 		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    262144
+				max_events:   10
+				timeout_secs: 1
+			}
 			compression: enabled: false
 			encoding: {
 				enabled: true
@@ -28,6 +35,7 @@ components: sinks: aws_sqs: components._aws & {
 			request: {
 				enabled:                    true
 				concurrency:                5
+				headers:                    false
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -82,14 +90,26 @@ components: sinks: aws_sqs: components._aws & {
 				examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
 			}
 		}
+		message_deduplication_id: {
+			common:      false
+			description: "The [deduplication ID](\(urls.aws_sqs_fifo_deduplication)) of the messages, which SQS delivers only once within five minutes. Can be applied only to FIFO queues, and is required unless content-based deduplication is enabled on the queue. Events missing the fields of the template are dropped."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ message_id }}"]
+				templateable: true
+			}
+		}
 		message_group_id: {
 			common:      false
-			description: "The tag that specifies that a message belongs to a specific message group. Can be applied only to FIFO queues."
+			description: "The tag that specifies that a message belongs to a specific message group. Can be applied only to FIFO queues, and is required for them. Events missing the fields of the template are dropped."
 			required:    false
 			warnings: []
 			type: string: {
 				default: null
-				examples: ["vector", "vector-%Y-%m-%d"]
+				examples: ["vector", "vector-%Y-%m-%d", "{{ host }}"]
+				templateable: true
 			}
 		}
 	}
@@ -98,4 +118,29 @@ components: sinks: aws_sqs: components._aws & {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body:  """
+				Vector sends up to 10 messages, of at most 256 KiB in total, with each
+				`SendMessageBatch` request. When some of the messages of a batch fail
+				for reasons on the side of SQS, only those are sent again, up to 3
+				times, so the messages already delivered aren't duplicated. The whole
+				batch is only retried when none of it was delivered. Messages rejected
+				as invalid, or still failing after these attempts, are dropped with
+				an error.
+				"""
+		}
+		fifo_queues: {
+			title: "FIFO queues"
+			body:  """
+				Queues with a URL ending with `.fifo` are [FIFO queues](\(urls.aws_sqs_fifo)),
+				which require a `message_group_id`, and take a `message_deduplication_id`.
+				Both are templates, rendered for each event, such as `{{ user_id }}`.
+				Vector sends the batches of FIFO queues one at a time, so the messages of
+				each group are delivered in the order Vector received them.
+				"""
+		}
+	}
 }
@@ -0,0 +1,230 @@
+package metadata
+
+components: sinks: azure_blob: {
+	title:       "Azure Blob Storage"
+	description: "[Azure Blob Storage][urls.azure_blob_storage] is Microsoft's object storage solution for the cloud. Blob storage is optimized for storing massive amounts of unstructured data, which makes it a prime candidate for log data."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Azure"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10485760
+				max_events:   null
+				timeout_secs: 300
+			}
+			compression: {
+				enabled: true
+				default: "gzip"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["ndjson", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				concurrency:                25
+				headers:                    false
+				rate_limit_duration_secs:   1
+				rate_limit_num:             1000
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.azure_blob_storage
+
+				interface: {
+					socket: {
+						api: {
+							title: "Azure Blob Service REST API"
+							url:   urls.azure_blob_storage_rest_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		blob_append_uuid: {
+			category:    "File Naming"
+			common:      false
+			description: "Whether or not to append a UUID v4 token to the end of the blob name. This ensures there are no name collisions in high volume use cases. Defaults to `true` with `blob_type` set to `block`, and to `false` with `blob_type` set to `append` so that batches are appended to the same blob."
+			required:    false
+			warnings: []
+			type: bool: default: null
+		}
+		blob_prefix: {
+			category:    "File Naming"
+			common:      true
+			description: "A prefix to apply to all blob names. This should be used to partition your blobs, and it's important to end this value with a `/` if you want this to be the root \"folder\" of the blobs."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "blob/%F/"
+				examples: ["date/%F/", "date/%F/hour/%H/", "year=%Y/month=%m/day=%d/", "kubernetes/{{ kubernetes.pod_name }}/"]
+				templateable: true
+			}
+		}
+		blob_time_format: {
+			category:    "File Naming"
+			common:      false
+			description: "The format of the timestamp of the blob names. [`strftime` specifiers][urls.strptime_specifiers] are supported. Defaults to `%s` with `blob_type` set to `block`, and to `%F` with `blob_type` set to `append`, appending the batches of a day to the same blob."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["%s", "%F", "%F-%H"]
+			}
+		}
+		blob_type: {
+			common:      false
+			description: "How the batches are written to the blobs."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "block"
+				enum: {
+					block:  "Upload each batch as a block blob of its own."
+					append: "Append each batch as a block of an append blob, created by the first batch appended to it. The batches can't exceed 4 MiB."
+				}
+			}
+		}
+		connection_string: {
+			category:    "Auth"
+			common:      true
+			description: "The [connection string][urls.azure_storage_connection_string] of the storage account, holding either its account key or a shared access signature. Either this or `storage_account` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net", "BlobEndpoint=https://mylogstorage.blob.core.windows.net/;SharedAccessSignature=sv=2019-12-12&ss=b&srt=co&sp=cw&sig=signature", "UseDevelopmentStorage=true"]
+			}
+		}
+		container_name: {
+			description: "The name of the container the blobs are written to. The containers must exist, Vector does not create them."
+			required:    true
+			warnings: ["Events whose rendered container name isn't a valid container name, of 3 to 63 lowercase letters, digits and single hyphens, are dropped."]
+			type: string: {
+				examples: ["logs", "logs-{{ application }}"]
+				templateable: true
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint of the Blob service, overriding the one of the connection string or of the storage account, such as an emulator."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["http://127.0.0.1:10000/devstoreaccount1"]
+			}
+		}
+		storage_account: {
+			category:    "Auth"
+			common:      true
+			description: "The storage account to write the blobs to, authenticating the requests with the [managed identity][urls.azure_managed_identities] of the Azure resource Vector runs on. Either this or `connection_string` must be set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["mylogstorage"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		authentication: {
+			title: "Authentication"
+			body: """
+				With `connection_string` set, the requests are signed with the
+				account key of the connection string, or authorized with its shared
+				access signature. With `storage_account` set instead, Vector fetches
+				an access token for the managed identity of the virtual machine, or
+				other Azure resource, it runs on from the Azure Instance Metadata
+				Service, and renews it before it expires. The identity must be
+				granted the `Storage Blob Data Contributor` role on the containers.
+				"""
+		}
+		blob_naming: {
+			title: "Blob Naming"
+			body: """
+				By default, Vector will name your blobs in the following format:
+
+				```text
+				<blob_prefix><timestamp>-<uuidv4>.log.gz
+				```
+
+				For example:
+
+				```text
+				blob/2019-06-18/1560886634-fddd7a0e-fad9-4f7e-9bce-00ae5debc563.log.gz
+				```
+
+				The `.gz` extension is dropped with `compression` set to `none`. You
+				can control the resulting name via the `blob_prefix`,
+				`blob_time_format`, and `blob_append_uuid` options.
+				"""
+		}
+		append_blobs: {
+			title: "Append Blobs"
+			body: """
+				With `blob_type` set to `append`, the batches are appended to
+				[append blobs][urls.azure_blob_storage_append_blobs] rather than
+				uploaded as blobs of their own, so that the events of a day, or of
+				any period set with `blob_time_format`, end up in a single blob. The
+				blobs are created by the first batch appended to them. An append blob
+				holds at most 50,000 blocks, so the period should be short enough for
+				its batches to stay below this limit. Compressed batches are appended
+				as separate gzip members, which gzip decompresses as a whole.
+				"""
+		}
+	}
+}
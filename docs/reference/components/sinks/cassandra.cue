@@ -0,0 +1,233 @@
+package metadata
+
+components: sinks: cassandra: {
+	title:       "Cassandra"
+	description: "[Apache Cassandra][urls.cassandra] is a distributed wide column database, built for high write throughput across many nodes. [ScyllaDB][urls.scylladb] implements the same [CQL native protocol][urls.cql_native_protocol] and is supported as well."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    40000
+				max_events:   100
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				headers:                    false
+				rate_limit_duration_secs:   1
+				rate_limit_num:             1000
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    3600
+				timeout_secs:               30
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.cassandra
+
+				interface: {
+					socket: {
+						api: {
+							title: "CQL native protocol"
+							url:   urls.cql_native_protocol
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"Cassandra 2.2 or later, or ScyllaDB, supporting version 4 of the CQL native protocol.",
+		]
+		warnings: [
+			"""
+				With `token_aware` enabled, Vector connects to the nodes by the
+				addresses they advertise as `rpc_address`. When TLS is enabled,
+				their certificates must be valid for these addresses, or
+				`tls.verify_hostname` must be disabled.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		auth: {
+			category:    "Auth"
+			common:      false
+			description: "The credentials of the `PasswordAuthenticator` of the cluster."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					password: {
+						description: "The password of the role."
+						required:    true
+						warnings: []
+						type: string: examples: ["${CASSANDRA_PASSWORD}"]
+					}
+					username: {
+						description: "The name of the role."
+						required:    true
+						warnings: []
+						type: string: examples: ["vector"]
+					}
+				}
+			}
+		}
+		columns: {
+			description: "The fields of the events written to each column of the table. Events missing a field leave its column unset, and `null` values delete it."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						host:      "host"
+						message:   "message"
+						timestamp: "timestamp"
+					},
+				]
+				options: {}
+			}
+		}
+		consistency: {
+			common:      false
+			description: "The consistency level the batches are written with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "local_quorum"
+				enum: {
+					any:          "Written to at least one node, possibly as a hint."
+					one:          "Written to at least one replica."
+					two:          "Written to at least two replicas."
+					three:        "Written to at least three replicas."
+					quorum:       "Written to a quorum of the replicas."
+					all:          "Written to all replicas."
+					local_quorum: "Written to a quorum of the replicas of the local datacenter."
+					each_quorum:  "Written to a quorum of the replicas of each datacenter."
+					local_one:    "Written to at least one replica of the local datacenter."
+				}
+			}
+		}
+		endpoints: {
+			description: "The contact points of the cluster, as `host:port`. Vector connects to the first one reachable and discovers the other nodes from it."
+			required:    true
+			warnings: []
+			type: array: items: type: string: examples: ["127.0.0.1:9042", "scylla.example.com:9042"]
+		}
+		keyspace: {
+			description: "The keyspace of the table."
+			required:    true
+			warnings: []
+			type: string: examples: ["logs"]
+		}
+		table: {
+			description: "The table the events are inserted into."
+			required:    true
+			warnings: []
+			type: string: examples: ["events"]
+		}
+		token_aware: {
+			common:      false
+			description: "Sends the rows to the node owning their partition, as read from the token ring of the cluster when the sink starts, rather than to the contact point."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		ttl_field: {
+			common:      false
+			description: "The field holding the time to live of each row, in seconds. Events without it are written with `ttl_secs`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["ttl"]
+			}
+		}
+		ttl_secs: {
+			common:      false
+			description: "The time to live of the rows, after which Cassandra expires them. Defaults to the `default_time_to_live` of the table."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [86400]
+				unit: "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		prepared_statements: {
+			title: "Prepared statements"
+			body: """
+				Vector prepares an `INSERT` statement of the mapped columns
+				when the sink starts, and sends each batch as an unlogged
+				`BATCH` of it. The values of the events are converted to the
+				types of the columns: strings are parsed into numbers,
+				timestamps, UUIDs and addresses, and maps and arrays are
+				written to text columns as JSON. Events whose values can't be
+				converted, or that miss a partition key column, are dropped.
+				`decimal` columns are not supported.
+				"""
+		}
+		token_aware_batching: {
+			title: "Token aware batching"
+			body: """
+				As unlogged batches spanning several partitions are
+				coordinated by the node receiving them, Vector computes the
+				`Murmur3Partitioner` token of the partition key of each row and
+				batches the rows by the node owning it. Each batch is then sent
+				straight to a replica of its rows. Batches are kept under the
+				50 KiB of the default `batch_size_fail_threshold_in_kb` of
+				Cassandra.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		encode_errors_total:    components.sources.internal_metrics.output.metrics.encode_errors_total
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -31,7 +31,11 @@ components: sinks: clickhouse: {
 			}
 			encoding: {
 				enabled: true
-				codec: enabled: false
+				codec: {
+					enabled: true
+					default: "default"
+					enum: ["default", "row_binary"]
+				}
 			}
 			request: {
 				enabled:                    true
@@ -87,10 +91,27 @@ components: sinks: clickhouse: {
 	}
 
 	configuration: {
+		async_insert: {
+			common:      false
+			description: "Whether ClickHouse buffers the rows of several inserts and writes them to the table together, as [asynchronous inserts](\(urls.clickhouse_async_insert))."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		auth: configuration._http_auth & {_args: {
 			password_example: "${CLICKHOUSE_PASSWORD}"
 			username_example: "${CLICKHOUSE_USERNAME}"
 		}}
+		columns: {
+			common:      false
+			description: "The columns of the table the fields of the events are inserted into, with their ClickHouse types. Required by the `row_binary` codec, which encodes the events as rows of the [RowBinary](\(urls.clickhouse_row_binary)) format. Null or missing fields are inserted as the default of their type, unless the column is `Nullable`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"timestamp": "DateTime64(3)", "host": "LowCardinality(String)", "status": "UInt16", "message": "String"}]
+				options: {}
+			}
+		}
 		database: {
 			common:      true
 			description: "The database that contains the stable that data will be inserted into."
@@ -108,6 +129,24 @@ components: sinks: clickhouse: {
 				examples: ["http://localhost:8123"]
 			}
 		}
+		insert_block_size: {
+			common:      false
+			description: "The maximum number of rows of the blocks ClickHouse splits the rows of each insert into, as the `max_insert_block_size` setting. Defaults to the setting of the server."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1048576]
+				unit: null
+			}
+		}
+		metrics: {
+			common:      false
+			description: "Whether the sink inserts metrics rather than logs. Counters, gauges and sets are inserted as rows of the `name`, `namespace`, `tags`, `kind`, `type`, `value` and `timestamp` columns, the value of sets being their number of elements. The other metrics are dropped. Can't be used with the `row_binary` codec."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		table: {
 			description: "The table that data will be inserted into."
 			required:    true
@@ -116,10 +155,48 @@ components: sinks: clickhouse: {
 				examples: ["mytable"]
 			}
 		}
+		wait_for_async_insert: {
+			common:      false
+			description: "Whether the inserts wait for their rows to be written to the table when `async_insert` is set, rather than only buffered. Defaults to the setting of the server."
+			required:    false
+			warnings: []
+			type: bool: default: null
+		}
 	}
 
 	input: {
-		logs:    true
-		metrics: null
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: false
+			gauge:        true
+			histogram:    false
+			set:          true
+			summary:      false
+		}
+	}
+
+	how_it_works: {
+		metrics: {
+			title: "Metrics"
+			body: """
+				With the `metrics` option set, the metrics of each batch are laid out in columns
+				and encoded as rows of the JSONEachRow format directly, into a table such as:
+
+				```sql
+				CREATE TABLE metrics (
+				  name LowCardinality(String),
+				  namespace Nullable(String),
+				  tags Map(String, String),
+				  kind LowCardinality(String),
+				  type LowCardinality(String),
+				  value Float64,
+				  timestamp DateTime64(6)
+				) ENGINE = MergeTree() ORDER BY (name, timestamp)
+				```
+
+				The metrics without a timestamp are inserted with the time of their request.
+				"""
+		}
 	}
 }
@@ -21,7 +21,7 @@ components: sinks: console: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "text"]
+					enum: ["json", "logfmt", "pretty", "table", "text"]
 				}
 			}
 			request: enabled: false
@@ -50,6 +50,35 @@ components: sinks: console: {
 	}
 
 	configuration: {
+		color: {
+			common:      false
+			description: "Whether the `pretty` and `table` codecs colorize their output. By default they do if the target is a terminal."
+			required:    false
+			warnings: []
+			type: bool: default: null
+		}
+		column_width: {
+			common:      false
+			description: "The width of the columns of the `table` codec. Longer values are truncated, except in the last column."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 24
+				unit:    null
+			}
+		}
+		columns: {
+			common:      false
+			description: "The fields shown as the columns of the `table` codec. By default the timestamp, host and message, as named by the global `log_schema`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["timestamp", "level", "message"]
+				}
+			}
+		}
 		target: {
 			common:      true
 			description: "The [standard stream](\(urls.standard_streams)) to write to."
@@ -77,6 +106,28 @@ components: sinks: console: {
 		}
 	}
 
+	how_it_works: {
+		codecs: {
+			title: "Codecs"
+			body: """
+				Besides the `json` and `text` codecs, suited to be read by other
+				programs, the following make the output easier to read when
+				debugging a pipeline interactively:
+
+				* `pretty` writes each event as indented JSON, colorized when
+				  `color` is enabled.
+				* `table` writes the `columns` of log events aligned under a
+				  header, with `-` for missing fields.
+				* `logfmt` writes the flattened fields of log events as
+				  `key=value` pairs, quoting values where needed.
+
+				Metric events are written as with the `text` codec by the `table`
+				and `logfmt` codecs. To keep the colors when paging the output,
+				set `color` to `true` and use `less -R`.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
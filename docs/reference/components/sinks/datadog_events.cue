@@ -0,0 +1,157 @@
+package metadata
+
+components: sinks: datadog_events: {
+	title: "Datadog Events"
+
+	description: sinks._datadog.description
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["Datadog"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: enabled:       false
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			to: {
+				service: services.datadog_events
+
+				interface: {
+					socket: {
+						api: {
+							title: "Datadog events API"
+							url:   urls.datadog_events_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: sinks._datadog.support
+
+	configuration: {
+		api_key:  sinks._datadog.configuration.api_key
+		endpoint: sinks._datadog.configuration.endpoint
+		region:   sinks._datadog.configuration.region
+		aggregation_key: {
+			common:      true
+			description: "The aggregation key of the events. Datadog groups the events sharing a key together."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ service }}", "{{ host }}-disk"]
+				templateable: true
+			}
+		}
+		alert_type: {
+			common:      true
+			description: "The alert type of the events, one of `error`, `warning`, `info` or `success`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["error", "{{ level }}"]
+				templateable: true
+			}
+		}
+		priority: {
+			common:      false
+			description: "The priority of the events, `normal` or `low`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["normal", "low"]
+				templateable: true
+			}
+		}
+		source_type_name: {
+			common:      false
+			description: "The type of source the events are from."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector"]
+			}
+		}
+		tags: {
+			common:      true
+			description: "The tags of the events, each rendered as `key:value`. Tags that fail to render are left out."
+			required:    false
+			warnings: []
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["env:{{ environment }}", "team:storage"]
+					templateable: true
+				}
+			}
+		}
+		text: {
+			common:      true
+			description: "The text of the events. Defaults to the message of the log events."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ message }}", "Disk usage of {{ host }} is at {{ usage }}%"]
+				templateable: true
+			}
+		}
+		title: {
+			description: "The title of the events. Log events the title fails to render for are dropped."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["{{ title }}", "Disk full on {{ host }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		event_mapping: {
+			title: "Event Mapping"
+			body:  """
+				Each log event is posted to the Events API as an event of its
+				own, with the `title`, `text`, `tags` and `aggregation_key`
+				rendered from it. The host and timestamp of the log event are
+				sent as the host and the date of the Datadog event.
+				"""
+		}
+	}
+}
@@ -91,6 +91,28 @@ components: sinks: elasticsearch: {
 			type: object: {
 				examples: []
 				options: {
+					api_key: {
+						common:        false
+						description:   "The [API key](\(urls.elasticsearch_api_keys)), encoded as returned by the create API key API, or only the key itself if `id` is set. Either this or `api_key_file` must be set."
+						required:      false
+						relevant_when: "strategy = \"api_key\""
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["${ELASTICSEARCH_API_KEY}"]
+						}
+					}
+					api_key_file: {
+						common:        false
+						description:   "A file holding the API key, as `api_key` would. The file is checked for modifications every 10 seconds and read again in the background, so that the key can be rotated without reloading Vector."
+						required:      false
+						relevant_when: "strategy = \"api_key\""
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/run/secrets/elasticsearch_api_key"]
+						}
+					}
 					assume_role: {
 						common:      false
 						description: "The ARN of an [IAM role](\(urls.aws_iam_role)) to assume at startup."
@@ -101,9 +123,21 @@ components: sinks: elasticsearch: {
 							examples: ["arn:aws:iam::123456789098:role/my_role"]
 						}
 					}
+					id: {
+						common:        false
+						description:   "The id of the API key, when `api_key` holds only the key itself."
+						required:      false
+						relevant_when: "strategy = \"api_key\""
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["VuaCfGcBCdbkQm-e5aOx"]
+						}
+					}
 					password: {
-						description: "The basic authentication password."
-						required:    true
+						description:   "The basic authentication password."
+						required:      true
+						relevant_when: "strategy = \"basic\""
 						warnings: []
 						type: string: {
 							examples: ["${ELASTICSEARCH_PASSWORD}", "password"]
@@ -115,14 +149,39 @@ components: sinks: elasticsearch: {
 						warnings: []
 						type: string: {
 							enum: {
-								aws:   "Authentication strategy used for [AWS' hosted Elasticsearch service](\(urls.aws_elasticsearch))."
-								basic: "The [basic authentication strategy](\(urls.basic_auth))."
+								api_key: "Authentication with an [API key](\(urls.elasticsearch_api_keys)) in the `ApiKey` authorization header."
+								aws:     "Authentication strategy used for [AWS' hosted Elasticsearch service](\(urls.aws_elasticsearch))."
+								basic:   "The [basic authentication strategy](\(urls.basic_auth))."
+								bearer:  "Authentication with a bearer token, such as a [service account token](\(urls.elasticsearch_service_tokens))."
 							}
 						}
 					}
+					token: {
+						common:        false
+						description:   "The bearer token. Either this or `token_file` must be set."
+						required:      false
+						relevant_when: "strategy = \"bearer\""
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["${ELASTICSEARCH_SERVICE_TOKEN}"]
+						}
+					}
+					token_file: {
+						common:        false
+						description:   "A file holding the bearer token. The file is checked for modifications every 10 seconds and read again in the background, so that the token can be rotated without reloading Vector."
+						required:      false
+						relevant_when: "strategy = \"bearer\""
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["/run/secrets/elasticsearch_token"]
+						}
+					}
 					user: {
-						description: "The basic authentication user name."
-						required:    true
+						description:   "The basic authentication user name."
+						required:      true
+						relevant_when: "strategy = \"basic\""
 						warnings: []
 						type: string: {
 							examples: ["${ELASTICSEARCH_USERNAME}", "username"]
@@ -152,6 +211,50 @@ components: sinks: elasticsearch: {
 				}
 			}
 		}
+		bulk_action: {
+			common:      false
+			description: "The [bulk action][urls.elasticsearch_bulk] each event is written with, one of `index`, `create`, `update` or `delete`. Updates merge the fields of the event into the document, creating it if missing, and deletes only send the action. Both require the `_id` set from `id_key`. Events rendering another action are dropped."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "index"
+				examples: ["create", "{{ action }}"]
+				templateable: true
+			}
+		}
+		data_stream: {
+			common:      false
+			description: "Writes the events to the `logs-<dataset>-<namespace>` [data stream][urls.elasticsearch_data_streams] with the `create` action, instead of to `index`. Can't be set along with `index`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					dataset: {
+						common:      true
+						description: "The dataset part of the data stream name."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "generic"
+							examples: ["nginx", "{{ service }}"]
+							templateable: true
+						}
+					}
+					namespace: {
+						common:      true
+						description: "The namespace part of the data stream name."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "default"
+							examples: ["production", "{{ environment }}"]
+							templateable: true
+						}
+					}
+				}
+			}
+		}
 		doc_type: {
 			common:      false
 			description: "The `doc_type` for your index data. This is only relevant for Elasticsearch <= 6.X. If you are using >= 7.0 you do not need to set this option since Elasticsearch has removed it."
@@ -225,6 +328,17 @@ components: sinks: elasticsearch: {
 				options: {}
 			}
 		}
+		routing: {
+			common:      false
+			description: "The routing value of each event, sending the documents sharing it to the same shard."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ tenant_id }}"]
+				templateable: true
+			}
+		}
 	}
 
 	input: {
@@ -238,8 +352,23 @@ components: sinks: elasticsearch: {
 			body: """
 				Vector [batches](#buffers--batches) data flushes it to Elasticsearch's
 				[`_bulk` API endpoint][urls.elasticsearch_bulk]. All events are inserted
-				via the `index` action. In the case of an conflict, such as a document with the
-				same `id`, Vector will add or _replace_ the document as necessary.
+				via the `index` action, unless `bulk_action` is set. In the case of an conflict,
+				such as a document with the same `id`, Vector will add or _replace_ the document
+				as necessary, while `create` actions fail and `update` actions merge the fields
+				into the document.
+				"""
+		}
+
+		data_streams: {
+			title: "Data streams"
+			body: """
+				With the `data_stream` option set, the events are written to the
+				`logs-<dataset>-<namespace>` [data stream][urls.elasticsearch_data_streams]
+				with the `create` action data streams require, and without a `_type`.
+				As data streams require an `@timestamp` field, the timestamp of the
+				events is moved to it, or set to the current time if they have none.
+				The data stream is created by Elasticsearch from its matching index
+				template on the first write.
 				"""
 		}
 
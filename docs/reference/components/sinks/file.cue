@@ -19,7 +19,7 @@ components: sinks: file: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -70,6 +70,61 @@ components: sinks: file: {
 				templateable: true
 			}
 		}
+		rotation: {
+			common:      false
+			description: "Rotates the files once they reach a size or age, and deletes the rotated files after a retention period. See [Rotation](#rotation) for more info."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					interval_secs: {
+						common:      true
+						description: "Rotates the files every interval, aligned to the Unix epoch, so that with `3600` each file holds the events written in one hour."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [3600, 86400]
+							unit: "seconds"
+						}
+					}
+					max_bytes: {
+						common:      true
+						description: "Rotates a file before an event would grow it beyond this size. With compression, the events written since the file was opened count with their size before compression."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [104857600]
+							unit: "bytes"
+						}
+					}
+					retention_secs: {
+						common:      false
+						description: "Deletes the rotated files last modified longer ago than this."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [604800]
+							unit: "seconds"
+						}
+					}
+					suffix: {
+						common:      false
+						description: "The suffix inserted before the extension of a file when it is rotated, rendered with the event whose write triggered the rotation."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "-%Y-%m-%d-%H%M%S"
+							examples: ["-%Y-%m-%d", "-{{ host }}-%s"]
+							templateable: true
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -87,5 +142,34 @@ components: sinks: file: {
 				to create and write to files in the specified directories.
 				"""
 		}
+
+		rotation: {
+			title: "Rotation"
+			body: """
+				With `rotation.max_bytes` or `rotation.interval_secs`, a file due
+				for rotation is closed, renamed and replaced by a new file before
+				the next event is written to it, so no events are lost in between
+				as with an external logrotate. The rotated file is named after the
+				file with the rendered `rotation.suffix` inserted before its
+				extension, `app-2020-10-14-120000.log` for `app.log`, followed by a
+				counter if that name is taken. Files idle for a whole interval are
+				rotated when the next event is written to them.
+
+				With `rotation.retention_secs`, the rotated files of the files
+				written to are deleted once they get older than the retention,
+				checked at every rotation and every minute. Files next to the
+				written files are taken for rotated files when their name matches
+				the name of the file with the suffix inserted, whatever the values
+				of the fields and time specifiers of the suffix, so make sure no
+				other files there match.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		file_rotation_errors_total:       components.sources.internal_metrics.output.metrics.file_rotation_errors_total
+		files_rotated_total:              components.sources.internal_metrics.output.metrics.files_rotated_total
+		rotated_file_delete_errors_total: components.sources.internal_metrics.output.metrics.rotated_file_delete_errors_total
+		rotated_files_deleted_total:      components.sources.internal_metrics.output.metrics.rotated_files_deleted_total
 	}
 }
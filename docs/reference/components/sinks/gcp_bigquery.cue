@@ -0,0 +1,169 @@
+package metadata
+
+components: sinks: gcp_bigquery: {
+	title:       "GCP BigQuery"
+	description: "[GCP BigQuery][urls.gcp_bigquery] is a serverless, highly scalable data warehouse. Logs are written with the [Storage Write API][urls.gcp_bigquery_storage_write_api]."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    8388608
+				max_events:   10000
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				headers:                    false
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.gcp_bigquery
+
+				interface: {
+					socket: {
+						api: {
+							title: "BigQuery Storage Write API"
+							url:   urls.gcp_bigquery_storage_write_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the tables. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			required:    false
+			warnings: ["The Storage Write API does not accept API keys, so `api_key` cannot be used."]
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		dataset: {
+			description: "The dataset of the table to write logs to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["logs", "logs-{{ environment }}"]
+				templateable: true
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint of the Storage Write API."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://bigquerystorage.googleapis.com"
+				examples: ["https://bigquerystorage.googleapis.com"]
+			}
+		}
+		project: {
+			description: "The project of the datasets."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		schema: {
+			description: "The columns of the table, mapped to their `type` and the `field` of the events they're written from, which defaults to the name of the column. The types are `string`, `int64`, `float64`, `bool`, `timestamp` and `json`, which writes the field serialized as JSON to a `STRING` or `JSON` column."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: [{"timestamp": {"type": "timestamp"}, "message": {"type": "string"}, "labels": {"type": "json", "field": "kubernetes.pod_labels"}}]
+				options: {}
+			}
+		}
+		table: {
+			description: "The table to write logs to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["logs", "logs_{{ application }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		exactly_once: {
+			title: "Exactly-Once Delivery"
+			body:  """
+				The rows of each batch are appended in a single request to a
+				committed stream, which is created once for each table and
+				kept, at the offset the stream ends at. A batch is written
+				either entirely or not at all, and its retries are appended at
+				the same offset of the same stream, so rows appended twice are
+				rejected. The batches of a table are appended one at a time,
+				and when a batch may have been written without a response, the
+				next batches of the table are appended to a new stream.
+				"""
+		}
+		schema_mapping: {
+			title: "Schema Mapping"
+			body:  """
+				Each row is written from the fields named in `schema`. Missing
+				fields, and fields that can't be converted to the type of their
+				column, are written as `NULL`, while fields not in the schema
+				are not written. Timestamps can be written from timestamps or
+				RFC 3339 strings.
+				"""
+		}
+	}
+}
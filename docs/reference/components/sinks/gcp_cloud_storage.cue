@@ -174,6 +174,44 @@ components: sinks: gcp_cloud_storage: {
 				examples: []
 			}
 		}
+		resumable: {
+			common:      false
+			description: "Streams the batches of each partition into a resumable upload as they are ready, instead of writing every batch as an object of its own. This allows writing very large objects without holding them in memory. The batches of a partition are written one at a time."
+			required:    false
+			warnings: [
+				"Events are acknowledged once the chunks holding them are written, or the upload is finalized, so that events of partitions with little traffic can wait up to `object_timeout_secs` for their acknowledgement. Requests waiting for it count against `request.concurrency`.",
+				"Uploads that fail to be finalized are cancelled, which loses the events of their written chunks.",
+			]
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Enables resumable uploads."
+						required:    false
+						type: bool: default: false
+					}
+					object_max_bytes: {
+						common:      false
+						description: "The size an object is finalized at, after which the following batches start a new one."
+						required:    false
+						type: uint: {
+							default: 5000000000
+							unit:    "bytes"
+						}
+					}
+					object_timeout_secs: {
+						common:      false
+						description: "The maximum age of an upload before it is finalized, making its object visible."
+						required:    false
+						type: uint: {
+							default: 3600
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
 		storage_class: {
 			category:    "Storage"
 			common:      false
@@ -88,6 +88,28 @@ components: sinks: gcp_pubsub: {
 				examples: ["${GCP_API_KEY}", "ef8d5de700e7989468166c40fc8a0ccd"]
 			}
 		}
+		attributes: {
+			common:      false
+			description: "Attributes added to the published messages."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"app": "{{ app }}", "source": "vector"}]
+				options: {
+					"*": {
+						common:      false
+						description: "An attribute of the messages. Events missing the fields of the template are dropped."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{{ app }}", "vector"]
+							templateable: true
+						}
+					}
+				}
+			}
+		}
 		credentials_path: {
 			common:      true
 			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the pubsub project and topic. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
@@ -108,6 +130,17 @@ components: sinks: gcp_pubsub: {
 				examples: ["https://us-central1-pubsub.googleapis.com"]
 			}
 		}
+		ordering_key: {
+			common:      false
+			description: "The [ordering key][urls.gcp_pubsub_ordering] of the published messages. Events missing the fields of the template are dropped."
+			required:    false
+			warnings: ["Only one request is sent at a time when set, so that the messages of each ordering key are published in order."]
+			type: string: {
+				default: null
+				examples: ["{{ host }}", "{{ kubernetes.pod_name }}"]
+				templateable: true
+			}
+		}
 		project: {
 			description: "The project name to which to publish logs."
 			required:    true
@@ -130,4 +163,18 @@ components: sinks: gcp_pubsub: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		message_ordering: {
+			title: "Message ordering"
+			body: """
+				Pub/Sub delivers the messages with the same `ordering_key` in the
+				order they were published to subscriptions with message ordering
+				enabled. Vector keeps that order by publishing the batches one at
+				a time, retrying a failed batch before publishing the next one.
+				Ordered messages must be published to a regional `endpoint`, such
+				as `https://us-central1-pubsub.googleapis.com`.
+				"""
+		}
+	}
 }
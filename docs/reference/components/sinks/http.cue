@@ -26,7 +26,7 @@ components: sinks: http: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "lz4", "snappy", "snappy_framed"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -34,7 +34,7 @@ components: sinks: http: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "ndjson", "text"]
+					enum: ["cef", "json", "leef", "ndjson", "text"]
 				}
 			}
 			request: {
@@ -107,6 +107,56 @@ components: sinks: http: {
 				options: {}
 			}
 		}
+		idempotency: {
+			common:      false
+			description: "Sets a header to a checksum of each request body, allowing HTTP servers supporting it to deduplicate retried requests. As the body of a batch is the same on every retry, so is its key."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					algorithm: {
+						common:      false
+						description: "The algorithm the hex encoded checksum is computed with."
+						required:    false
+						type: string: {
+							default: "sha256"
+							enum: {
+								md5:    "MD5 checksum."
+								sha1:   "SHA-1 checksum."
+								sha256: "SHA-256 checksum."
+							}
+						}
+					}
+					field: {
+						common:      false
+						description: "Also sets this field of every event to a checksum of the event, for servers deduplicating single events rather than whole requests. The checksum is computed before the field is set and with the same `algorithm`."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["event_id"]
+						}
+					}
+					header: {
+						common:      false
+						description: "The name of the header the checksum is set in."
+						required:    false
+						type: string: {
+							default: "Idempotency-Key"
+							examples: ["X-Request-Checksum"]
+						}
+					}
+				}
+			}
+		}
+		trace_context_key: {
+			common:      false
+			description: "The field holding the [W3C trace context][urls.w3c_trace_context] of the events, as set by the `http` source, sent as the `traceparent` and `tracestate` headers of their requests. As a request can only be part of a single trace, the context of the first event of each batch carrying one is used."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["trace"]
+			}
+		}
 		uri: {
 			description: "The full URI to make HTTP requests to. This should include the protocol and host, but can also include the port, path, and any other valid part of a URI."
 			required:    true
@@ -53,14 +53,49 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		headers: {
+			common:      false
+			description: "Headers set on each message, rendered from the fields of its event. Headers referring to fields missing from an event are left out of its message."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"content-type": "application/json"
+						"schema":       "{{ schema_name }}"
+					},
+				]
+				options: {}
+			}
+		}
+		key: {
+			common:      false
+			description: "A template of the message key, as an alternative to `key_field`. If it refers to fields missing from an event, a blank key is used."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ tenant_id }}-{{ user_id }}"]
+				templateable: true
+			}
+		}
 		key_field: {
-			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used."
-			required:    true
+			common:      true
+			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used. Can't be set along with `key`."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["user_id"]
 			}
 		}
+		idempotence: {
+			common:      false
+			description: "Makes the producer idempotent, so that retries don't write duplicate messages to the topic. This sets the `enable.idempotence` librdkafka option, and is implied by `transaction`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		librdkafka_options: components._kafka.configuration.librdkafka_options
 		message_timeout_ms: {
 			common:      false
@@ -122,6 +157,45 @@ components: sinks: kafka: {
 			}
 		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
+		transaction: {
+			common:      false
+			description: "Produces the events in transactions, committed periodically, for exactly-once delivery into Kafka. The events are only acknowledged once their transaction is committed, and a transaction which fails to commit is aborted, stopping the sink, so that its events are sent again from the buffer once Vector restarts. Consumers must read with the `read_committed` isolation level to only see committed messages."
+			required:    false
+			warnings: ["Requires a disk buffer for the events of aborted transactions to survive a restart."]
+			type: object: {
+				examples: []
+				options: {
+					id: {
+						description: "The `transactional.id` of the producer. It must be unique among the producers of the cluster, and stable across restarts, so that the transactions left open by a previous instance are aborted."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["vector-aggregator-1"]
+						}
+					}
+					commit_interval_ms: {
+						common:      false
+						description: "The interval at which the open transaction is committed, lower than `timeout_ms`."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1000
+							unit:    "milliseconds"
+						}
+					}
+					timeout_ms: {
+						common:      false
+						description: "The time after which an open transaction is aborted by the broker, setting the `transaction.timeout.ms` librdkafka option."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 60000
+							unit:    "milliseconds"
+						}
+					}
+				}
+			}
+		}
 		topic: {
 			description: "The Kafka topic name to write events to."
 			required:    true
@@ -0,0 +1,145 @@
+package metadata
+
+components: sinks: line_protocol: {
+	title:       "Line Protocol"
+	description: "Writes metrics, and optionally logs, as [InfluxDB line protocol](\(urls.influxdb_line_protocol)) over TCP, as accepted by [QuestDB](\(urls.questdb)) and other databases that don't implement the Influx HTTP API."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			keepalive: enabled: true
+			request: enabled:   false
+			tls: sinks.socket.features.send.tls
+			to: {
+				service: {
+					name:     "QuestDB"
+					thing:    "a \(name) database"
+					url:      urls.questdb
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "QuestDB InfluxDB Line Protocol"
+							url:   urls.questdb_ilp
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: sinks.socket.support
+
+	configuration: {
+		address: {
+			description: "The address to connect to. The address _must_ include a port."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["127.0.0.1:9009", "questdb.example.com:9009"]
+			}
+		}
+		default_namespace: {
+			common: true
+			description: """
+				Used as a namespace for metrics that don't have it.
+				A namespace will be prefixed to a metric's name.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+			}
+		}
+		log_measurement: {
+			common:      true
+			description: "The measurement logs are written to. Logs are only accepted if it is set."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["logs"]
+			}
+		}
+		measurements: {
+			common:      false
+			description: "How the lines of each measurement, named after the namespaced metric or `log_measurement`, are written."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"service.requests": {"tags": {"host": "hostname"}}}]
+				options: {}
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "Quantiles to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into a summary."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
+			}
+		}
+		tags: {
+			common:      false
+			description: "Tags added to all the lines."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"region": "eu-central-1"}]
+				options: {}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		tag_mapping: {
+			title: "Tag Mapping"
+			body:  """
+				The tags of the lines of a measurement are set with
+				`measurements.<measurement>.tags`, mapping names to the tags
+				written in the line. The tags of metrics are renamed, while the
+				fields of logs are written as tags rather than fields, besides
+				the `host` and `source_type` fields which are always tags.
+				QuestDB stores tags as `SYMBOL` columns, so fields of a high
+				cardinality are better left as fields.
+
+				Metrics are written as they are received, so incremental
+				counters are written as their increments.
+				"""
+		}
+	}
+}
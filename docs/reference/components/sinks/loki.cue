@@ -35,6 +35,7 @@ components: sinks: loki: {
 			request: {
 				enabled:                    true
 				concurrency:                5
+				ordered:                    true
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -165,7 +166,10 @@ components: sinks: loki: {
 				their `timestamp`. This is to ensure that logs will be
 				accepted by Loki. If no timestamp is supplied with events
 				then the Loki sink will supply its own monotonically
-				increasing timestamp.
+				increasing timestamp. The requests of each tenant are sent
+				one at a time, retries included, so a later batch never
+				reaches Loki before an earlier one, unless `request.ordered`
+				is disabled.
 				"""
 		}
 	}
@@ -62,6 +62,72 @@ components: sinks: nats: {
 	}
 
 	configuration: {
+		headers: {
+			common:      false
+			description: "Headers added to the published messages. Requires a NATS server of version 2.2 or later. A header whose template can't be rendered for an event is left out of its message."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"X-App": "{{ app }}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "A header to add to the messages."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["{{ app }}", "vector"]
+							templateable: true
+						}
+					}
+				}
+			}
+		}
+		jetstream: {
+			common:      false
+			description: "Options for publishing to [JetStream][urls.nats_jetstream] streams."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					ack_timeout_secs: {
+						common:      false
+						description: "How long to wait for the acknowledgement of a message, after which it is published again."
+						required:    false
+						type: uint: {
+							default: 5
+							unit:    "seconds"
+						}
+					}
+					enabled: {
+						common:      true
+						description: "Waits for the stream to acknowledge storing each message before marking its event as delivered, publishing it again until it does or rejects it."
+						required:    false
+						type: bool: default: false
+					}
+					max_in_flight: {
+						common:      false
+						description: "The most messages published before the stream acknowledged storing them."
+						required:    false
+						type: uint: {
+							default: 256
+							unit:    null
+						}
+					}
+					stream: {
+						common:      false
+						description: "The stream the subjects must be bound to. Messages to subjects of other streams are rejected."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["LOGS"]
+						}
+					}
+				}
+			}
+		}
 		url: {
 			description: "The NATS URL to connect to. The url _must_ take the form of `nats://server:port`."
 			groups: ["tcp"]
@@ -95,4 +161,23 @@ components: sinks: nats: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		jetstream: {
+			title: "JetStream"
+			body: """
+				With `jetstream.enabled`, Vector publishes each message with a
+				reply subject and waits for the stream's acknowledgement before
+				marking the event as delivered. Messages that aren't
+				acknowledged are published again with the same `Nats-Msg-Id`
+				header, so that JetStream discards the copies within its
+				duplicate window, giving at-least-once delivery. Up to
+				`jetstream.max_in_flight` messages are published before their
+				acknowledgements, and events are marked as delivered in order.
+				Messages JetStream rejects, as no stream is bound to their
+				subject or with a `4xx` error code, are dropped with an error
+				rather than published again.
+				"""
+		}
+	}
 }
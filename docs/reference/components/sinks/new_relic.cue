@@ -0,0 +1,166 @@
+package metadata
+
+components: sinks: new_relic: {
+	title:       "New Relic"
+	description: components.sinks.new_relic_logs.description
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["New Relic"]
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1000000
+				max_events:   null
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "gzip"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                100
+				rate_limit_duration_secs:   1
+				rate_limit_num:             100
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "New Relic"
+					thing:    "a \(name) account"
+					url:      urls.new_relic
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "New Relic Log API and Metric API"
+							url:   urls.new_relic_metric_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		common_attributes: {
+			common:      false
+			description: "Attributes added to all the logs and metrics sent."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"environment": "production", "service.name": "checkout"}]
+				options: {}
+			}
+		}
+		license_key: {
+			description: "Your New Relic license key."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["xxxx", "${NEW_RELIC_LICENSE_KEY}"]
+			}
+		}
+		region: {
+			common:      true
+			description: "The region of the account."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "us"
+				enum: {
+					us: "United States"
+					eu: "Europe"
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    false
+			set:          true
+			summary:      false
+		}
+	}
+
+	how_it_works: {
+		attributes: {
+			title: "Attributes"
+			body:  """
+				Logs are sent to the [Log API][urls.new_relic_log_api] with their
+				message and timestamp, and all their other fields as attributes,
+				named by their path, such as `http.status`. Metrics are sent to
+				the [Metric API][urls.new_relic_metric_api] with their tags as
+				attributes.
+				"""
+		}
+		metric_types: {
+			title: "Metric Types"
+			body:  """
+				Incremental counters are sent as counts over the time since the
+				previous batch of metrics, while absolute counters and gauges are
+				sent as gauges, and sets as gauges of their size. Distributions
+				are sent as summaries of their count, sum, minimum and maximum.
+				Aggregated histograms and summaries are dropped.
+				"""
+		}
+		payload_limits: {
+			title: "Payload Limits"
+			body:  """
+				Both APIs reject payloads over 1MB, so batches are limited to
+				1MB before compression, and larger `batch.max_bytes` are
+				rejected when the sink is built.
+				"""
+		}
+	}
+}
@@ -0,0 +1,204 @@
+package metadata
+
+components: sinks: opentelemetry: {
+	title:       "OpenTelemetry"
+	description: "The [OpenTelemetry protocol][urls.opentelemetry_otlp] (OTLP) is the protocol of the OpenTelemetry project to deliver telemetry data, supported by the OpenTelemetry collector and a growing number of observability backends."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    4194304
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: {
+				enabled: true
+				default: "gzip"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                10
+				rate_limit_duration_secs:   1
+				rate_limit_num:             1000
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               30
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "OTLP receiver"
+					thing:    "an \(name)"
+					url:      urls.opentelemetry_otlp
+					versions: ">= 1.0"
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "OTLP"
+							url:   urls.opentelemetry_otlp
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${OTLP_PASSWORD}"
+			username_example: "${OTLP_USERNAME}"
+		}}
+		endpoint: {
+			description: "The base URL of the OTLP receiver. With OTLP/HTTP, the logs are sent to its `/v1/logs` path, and the metrics to its `/v1/metrics` path."
+			required:    true
+			warnings: []
+			type: string: examples: ["http://localhost:4318", "https://otlp.example.com"]
+		}
+		headers: {
+			common:      false
+			description: "Additional headers to send with the requests, such as the API key of the backend."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"api-key": "${OTLP_API_KEY}"}]
+				options: {}
+			}
+		}
+		protocol: {
+			common:      true
+			description: "The transport of OTLP the events are exported with. OTLP/gRPC messages can only be sent uncompressed, so the sink fails to start with `protocol` set to `grpc` and `compression` set to anything but `none`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "http"
+				enum: {
+					http: "[OTLP/HTTP](\(urls.opentelemetry_otlp_http)), usually served on port 4318."
+					grpc: "[OTLP/gRPC](\(urls.opentelemetry_otlp_grpc)), usually served on port 4317."
+				}
+			}
+		}
+		resource: {
+			common:      true
+			description: "The attributes of the resource the events are reported for."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"service.name": "checkout", "deployment.environment": "production"}]
+				options: {}
+			}
+		}
+		resource_fields: {
+			common:      true
+			description: "The resource attributes to take from the events, mapped to the log field, or metric tag, holding them. The fields are removed from the events, and override the attributes of the same name in `resource`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"host.name": "host", "k8s.pod.name": "kubernetes.pod_name"}]
+				options: {}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			summary:      true
+			set:          true
+		}
+	}
+
+	how_it_works: {
+		logs: {
+			title: "Log records"
+			body: """
+				The message of a log event becomes the body of its log record, and
+				its timestamp the time of the record. The other fields become the
+				attributes of the record, with nested fields as key-value lists.
+				"""
+		}
+		metrics: {
+			title: "Metrics"
+			body: """
+				The namespace and name of a metric are joined with a `.`, and its
+				tags become the attributes of its data point. Counters are exported
+				as monotonic sums, with a delta temporality for incremental
+				metrics and a cumulative one for absolute metrics, and sets as
+				gauges of their number of values. Histograms and summaries keep
+				their buckets and quantiles, while distributions are exported as
+				histograms with their count and sum only.
+				"""
+		}
+		encoding: {
+			title: "Encoding"
+			body: """
+				The requests are encoded with the protobuf encoding of OTLP over
+				both transports. The records the receiver rejects in a partial
+				success of OTLP/gRPC are logged rather than retried.
+				"""
+		}
+		resources: {
+			title: "Resources"
+			body: """
+				The events of each request share the same resource attributes,
+				so the batches are partitioned by the resource attributes the
+				events are mapped to with `resource_fields`.
+				"""
+		}
+		traces: {
+			title: "Traces"
+			body: """
+				Vector has no trace events, so only logs and metrics are exported.
+				The sink fails to start with an `endpoint` of the OTLP/HTTP path of
+				traces, `/v1/traces`, as the logs and metrics paths are appended to it.
+				"""
+		}
+	}
+}
@@ -36,7 +36,7 @@ components: sinks: prometheus_exporter: {
 						direction: "incoming"
 						port:      _port
 						protocols: ["http"]
-						ssl: "disabled"
+						ssl: "optional"
 					}
 				}
 			}
@@ -75,6 +75,10 @@ components: sinks: prometheus_exporter: {
 				examples: ["0.0.0.0:\(_port)"]
 			}
 		}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${PROMETHEUS_PASSWORD}"
+			username_example: "${PROMETHEUS_USERNAME}"
+		}}
 		buckets: {
 			common:      false
 			description: "Default buckets to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into histograms."
@@ -120,6 +124,39 @@ components: sinks: prometheus_exporter: {
 				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
 			}
 		}
+		replica_tag: {
+			common:      false
+			description: """
+				The tag identifying the replica, such as an agent, a metric comes
+				from. When set, the tag is removed and the series of all replicas
+				which are otherwise identical are exposed as one: counters and
+				histograms are summed, the highest gauge is kept, and sets and
+				distributions are merged. Summaries can't be merged, so only one
+				replica's is exposed.
+				"""
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["host"]
+			}
+		}
+		tls: configuration._tls_accept & {_args: {
+			can_enable:             true
+			can_verify_certificate: true
+			enabled_default:        false
+		}}
+		tls_reload_secs: {
+			common:      false
+			description: "How often to check the `tls.crt_file`, `tls.key_file` and `tls.ca_file` files for changes. Changed files are reloaded and served to new connections, while a certificate failing to load keeps the previous one in use. By default they're only loaded on start."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [300]
+				unit: "seconds"
+			}
+		}
 	}
 
 	input: {
@@ -334,6 +371,21 @@ components: sinks: prometheus_exporter: {
 			]
 		}
 
+		securing_scrapes: {
+			title: "Securing Scrapes"
+			body: """
+				By default the metrics are served to anyone over plain HTTP, so
+				the sink should only be exposed to trusted networks. To expose it
+				beyond them, enable `tls` and require the scrapes to authenticate
+				with basic or bearer `auth`, which Prometheus supports with the
+				`basic_auth` and `bearer_token` scrape options. Scrapes without
+				valid credentials are rejected with `401 Unauthorized` and counted
+				by the `authentication_errors_total` metric. Certificates
+				renewed in place, such as by cert-manager, are picked up without
+				restarting with `tls_reload_secs`.
+				"""
+		}
+
 		memory_usage: {
 			title: "Memory Usage"
 			body: """
@@ -346,4 +398,8 @@ components: sinks: prometheus_exporter: {
 				"""
 		}
 	}
+
+	telemetry: metrics: {
+		authentication_errors_total: components.sources.internal_metrics.output.metrics.authentication_errors_total
+	}
 }
@@ -0,0 +1,192 @@
+package metadata
+
+components: sinks: redis: {
+	title:       "Redis"
+	description: "[Redis](\(urls.redis)) is an in-memory data structure store, used as a database, cache, and message broker. Its lists, channels and streams are commonly used as queues between services."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    null
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				headers:                    false
+				rate_limit_duration_secs:   1
+				rate_limit_num:             1000
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    3600
+				timeout_secs:               30
+			}
+			tls: enabled: false
+			to: {
+				service: {
+					name:     "Redis"
+					thing:    "a \(name) server"
+					url:      urls.redis
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "disabled"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"Redis 5.0 or later when writing to streams.",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		data_type: {
+			common:      true
+			description: "The Redis data type the events are written to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "list"
+				enum: {
+					list:    "Push the events to the list at `key`."
+					channel: "Publish the events to the channel `key` with `PUBLISH`. Events published while no client is subscribed are lost."
+					stream:  "Add the events as entries of the [stream][urls.redis_streams] at `key` with `XADD`."
+				}
+			}
+		}
+		key: {
+			description: "The list, channel or stream the events are written to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "logs:{{ app }}"]
+				templateable: true
+			}
+		}
+		list: {
+			common:      false
+			description: "Options for the `list` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					method: {
+						common:      false
+						description: "The end of the list the events are pushed to."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "rpush"
+							enum: {
+								lpush: "Push to the head of the list, with `LPUSH`."
+								rpush: "Push to the tail of the list, with `RPUSH`, so that consumers popping from the head with `LPOP` or `BLPOP` receive them in order."
+							}
+						}
+					}
+				}
+			}
+		}
+		stream: {
+			common:      false
+			description: "Options for the `stream` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					field: {
+						common:      false
+						description: "The field of the stream entries holding the encoded event."
+						required:    false
+						warnings: []
+						type: string: default: "message"
+					}
+					max_len: {
+						common:      false
+						description: "Trims the stream to about this many entries as events are added, with `MAXLEN ~`."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [100000]
+							unit: null
+						}
+					}
+				}
+			}
+		}
+		url: {
+			description: "The [URL][urls.redis_urls] of the Redis server, in the form of `redis://[:<password>@]<host>[:<port>][/<database>]`."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["redis://127.0.0.1:6379/0", "redis://:${REDIS_PASSWORD}@redis.example.com"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		pipelining: {
+			title: "Pipelining"
+			body: """
+				Vector sends the commands of each batch in a single
+				[pipeline][urls.redis_pipelining], one command per event, and
+				only marks the events of a batch as delivered once Redis
+				replied to all of them. Batches that failed because of a lost
+				connection are sent again once Vector reconnected.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		missing_keys_total:     components.sources.internal_metrics.output.metrics.missing_keys_total
+		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -0,0 +1,208 @@
+package metadata
+
+components: sinks: snowflake: {
+	title:       "Snowflake"
+	description: "[Snowflake][urls.snowflake] is a cloud data warehouse. Logs are written with [Snowpipe Streaming][urls.snowpipe_streaming], without staging them to files first."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    4194304
+				max_events:   10000
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:                    true
+				concurrency:                5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.snowflake
+
+				interface: {
+					socket: {
+						api: {
+							title: "Snowpipe Streaming REST API"
+							url:   urls.snowpipe_streaming
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		account: {
+			description: "The account identifier, as `<organization>-<account>` or the account locator."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["myorg-myaccount", "xy12345.eu-central-1"]
+			}
+		}
+		channel: {
+			common:      false
+			description: "The channel rows are appended to. Each channel is written by a single writer, so the channels of several Vector instances writing to the same pipe must differ."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "vector"
+				examples: ["vector-${HOSTNAME}", "vector-{{ kubernetes.pod_name }}"]
+				templateable: true
+			}
+		}
+		columns: {
+			common:      true
+			description: "The columns of the rows, mapped to the fields of the events they're written from. Without columns, each row is written from all the fields of its event."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"MESSAGE": "message", "TS": "timestamp", "POD": "kubernetes.pod_name"}]
+				options: {}
+			}
+		}
+		database: {
+			description: "The database of the table."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["LOGS"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The URL of the account."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "https://<account>.snowflakecomputing.com"
+				examples: ["https://myorg-myaccount.privatelink.snowflakecomputing.com"]
+			}
+		}
+		pipe: {
+			common:      false
+			description: "The streaming pipe of the table, rows are written through. Defaults to the pipe Snowflake provides for the table."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "<table>-STREAMING"
+				examples: ["EVENTS_PIPE"]
+			}
+		}
+		private_key_passphrase: {
+			common:      false
+			description: "The passphrase of an encrypted private key."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["${SNOWFLAKE_PRIVATE_KEY_PASSPHRASE}"]
+			}
+		}
+		private_key_path: {
+			description: "The PEM encoded private key of the [key pair][urls.snowflake_key_pair_auth] of the user."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["/etc/vector/snowflake.p8"]
+			}
+		}
+		schema: {
+			description: "The schema of the table."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["PUBLIC"]
+			}
+		}
+		table: {
+			description: "The table to write logs to."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["EVENTS"]
+			}
+		}
+		user: {
+			description: "The user to authenticate as."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["VECTOR"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		channels: {
+			title: "Channels"
+			body:  """
+				Each rendering of the `channel` template is a channel of the pipe,
+				opened with the first batch written to it. Every batch of a
+				channel is written with an offset token, increasing for each
+				batch. When appending fails the channel is reopened, and batches
+				the channel reports as committed are not appended again.
+				"""
+		}
+		key_pair_authentication: {
+			title: "Key Pair Authentication"
+			body:  """
+				Vector signs tokens with the private key of the user, and
+				exchanges them for tokens scoped to the ingest host of the
+				account, which are renewed before they expire. The public key of
+				the pair must be set as the `RSA_PUBLIC_KEY` of the user.
+				"""
+		}
+	}
+}
@@ -21,7 +21,7 @@ components: sinks: socket: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "text"]
+					enum: ["cef", "json", "leef", "text"]
 				}
 			}
 			keepalive: enabled: true
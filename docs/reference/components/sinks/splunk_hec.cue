@@ -103,6 +103,41 @@ components: sinks: splunk_hec: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Options for [indexer acknowledgement][urls.splunk_hec_indexer_acknowledgement], marking batches as delivered once Splunk has indexed their events."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					indexer_acknowledgements_enabled: {
+						common:      true
+						description: "Enables indexer acknowledgement. It must also be enabled for the token."
+						required:    false
+						type: bool: default: false
+					}
+					query_interval: {
+						common:      false
+						description: "The time between the queries of the acknowledgement of a batch."
+						required:    false
+						type: uint: {
+							default: 10
+							unit:    "seconds"
+						}
+					}
+					retry_limit: {
+						common:      false
+						description: "The queries made for the acknowledgement of a batch, after which the batch is sent again."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		host_key: {
 			common:      true
 			description: "The name of the log field to be used as the hostname sent to Splunk HEC. This overrides the [global `host_key` option][docs.reference.global-options#host_key]."
@@ -168,6 +203,24 @@ components: sinks: splunk_hec: {
 		metrics: null
 	}
 
+	how_it_works: {
+		indexer_acknowledgement: {
+			title: "Indexer Acknowledgement"
+			body:  """
+				With `acknowledgements.indexer_acknowledgements_enabled`, each
+				batch is sent over a channel of the sink, and Splunk returns an
+				acknowledgement ID for it. The ID is queried every
+				`query_interval` seconds, and the batch is only marked as
+				delivered once Splunk reports it indexed. Batches that aren't
+				acknowledged within `retry_limit` queries are sent again, while
+				batches Splunk returns no ID for are marked as delivered right
+				away. The queries are waited for on top of
+				`request.timeout_secs`, and a batch is dropped with an error if
+				Splunk rejects the query of its acknowledgement.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		http_request_errors_total: components.sources.internal_metrics.output.metrics.http_request_errors_total
 		http_requests_total:       components.sources.internal_metrics.output.metrics.http_requests_total
@@ -0,0 +1,136 @@
+package metadata
+
+components: sinks: spool: {
+	title:       "Spool"
+	description: "Spools events to segments of a local directory, for a [`spool` source][docs.sources.spool] to forward them once the destination can be reached. This gives sites with intermittent connectivity durable store-and-forward delivery, with the size and age of the spooled data capped."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      false
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: enabled:    false
+			request: enabled:     false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		max_age_secs: {
+			common:      false
+			description: "Discards the segments spooled longer than this, whether or not they were forwarded. By default, segments are kept until they are forwarded or the spool is full."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [604800]
+				unit: "seconds"
+			}
+		}
+		max_segment_age_secs: {
+			common:      false
+			description: "The longest a segment is kept open. Segments are only forwarded once closed, so this is the longest events wait before the source can forward them."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+		max_segment_bytes: {
+			common:      false
+			description: "The size at which segments are closed, and a new one opened."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 8388608
+				unit:    "bytes"
+			}
+		}
+		max_size_bytes: {
+			common:      true
+			description: "The largest size of the spooled segments. Once reached, the oldest segments are discarded to make room for the new events."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1073741824
+				unit:    "bytes"
+			}
+		}
+		path: {
+			description: "The directory the segments are spooled to, which the `spool` source forwards them from. It is created if it doesn't exist."
+			required:    true
+			warnings: []
+			type: string: examples: ["/var/lib/vector/spool"]
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			summary:      true
+			set:          true
+		}
+	}
+
+	how_it_works: {
+		segments: {
+			title: "Segments"
+			body: """
+				The events are appended to an open segment, in the
+				[event protobuf](\(urls.event_proto)) encoding of the disk
+				buffers. Once it reaches `max_segment_bytes` or
+				`max_segment_age_secs`, the segment is synced to disk and
+				renamed, handing it over to the source. Segments left open when
+				Vector stopped are closed when the sink starts again, keeping
+				the events written in full.
+				"""
+		}
+		store_and_forward: {
+			title: "Store and forward"
+			body: """
+				Unlike disk buffers, which are tied to a single sink and only
+				hold events while it is behind, the spool holds all events
+				until a `spool` source, in the same Vector instance or another
+				one sharing the directory, forwarded them. The source only
+				deletes a segment once the sinks after it processed all its
+				log events, so they aren't lost while the destination can't be
+				reached.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:  components.sources.internal_metrics.output.metrics.processed_events_total
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -0,0 +1,206 @@
+package metadata
+
+components: sinks: syslog: {
+	title: "Syslog"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: "text"
+					enum: ["cef", "leef", "text"]
+				}
+			}
+			keepalive: enabled: true
+			request: enabled:   false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "Syslog server"
+					thing:    "a \(name)"
+					url:      urls.syslog
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp", "udp", "unix"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address to connect to. The address _must_ include a port."
+			groups: ["tcp", "udp"]
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["127.0.0.1:514"]
+			}
+		}
+		appname: {
+			common:      false
+			description: "The APP-NAME of RFC 5424 messages, and the TAG of RFC 3164 messages. It is written as `-`, or left out of RFC 3164 messages, if the template can't be rendered."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "{{ appname }}"
+				examples: ["vector", "{{ service }}"]
+				templateable: true
+			}
+		}
+		facility: {
+			common:      false
+			description: "The facility of the messages, by name or code. Events for which the template doesn't render to a valid facility are sent with the `user` facility."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "{{ facility }}"
+				examples: ["local0", "{{ syslog.facility }}"]
+				templateable: true
+			}
+		}
+		framing: {
+			common:      false
+			description: "How the messages are delimited in the `tcp` and `unix` modes."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "newline_delimited"
+				enum: {
+					newline_delimited: "Each message is followed by a newline."
+					octet_counted:     "Each message is preceded by its length in bytes and a space, as described in [RFC 6587](\(urls.syslog_6587)). Newlines can then be part of the messages. Not supported in the `udp` mode."
+				}
+			}
+		}
+		mode: {
+			description: "The type of socket to use."
+			groups: ["tcp", "udp", "unix"]
+			required: true
+			warnings: []
+			type: string: {
+				enum: {
+					tcp:  "TCP socket"
+					udp:  "UDP socket"
+					unix: "Unix domain socket"
+				}
+			}
+		}
+		path: {
+			description: "The unix socket path. This should be the absolute path."
+			groups: ["unix"]
+			required: true
+			warnings: []
+			type: string: {
+				examples: ["/dev/log"]
+			}
+		}
+		rfc: {
+			common:      true
+			description: "The format of the messages."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "rfc5424"
+				enum: {
+					rfc3164: "The BSD syslog format of [RFC 3164](\(urls.syslog_3164)), expected by legacy receivers."
+					rfc5424: "The format of [RFC 5424](\(urls.syslog_5424)), with structured data."
+				}
+			}
+		}
+		severity: {
+			common:      false
+			description: "The severity of the messages, by name or code. Events for which the template doesn't render to a valid severity are sent with the `info` severity."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "{{ severity }}"
+				examples: ["notice", "{{ level }}"]
+				templateable: true
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		message_format: {
+			title: "Message Format"
+			body: """
+				Events are written as [RFC 5424][urls.syslog_5424] messages or, with
+				`rfc = "rfc3164"`, as [RFC 3164][urls.syslog_3164] messages. The
+				facility, severity and appname are rendered from their templates, which
+				default to the fields decoded by the [`syslog` source][docs.sources.syslog],
+				and the `hostname` (or `host`), `procid` and `msgid` fields complete the
+				header, followed by the `message` field. Missing header fields are
+				written as `-`. RFC 3164 messages have no structured data, and their
+				timestamp is written in UTC, without year.
+				"""
+		}
+
+		siem_formats: {
+			title: "CEF and LEEF"
+			body: """
+				With the `cef` and `leef` codecs, the message part is the event encoded
+				as a [CEF][urls.cef] or [LEEF][urls.leef] message instead of the
+				`message` field, as expected by ArcSight and QRadar, and no structured
+				data is written. The headers and extensions are configured with the
+				`encoding.cef` and `encoding.leef` options.
+				"""
+		}
+
+		structured_data: {
+			title: "Structured Data"
+			body: """
+				Every other root level map of the event is written as a structured data
+				element, using the field name as SD-ID and the entries of the map as
+				parameters. Array values are written as repeated parameters. Fields whose
+				name is not a valid SD-ID are left out, in particular IDs of the form
+				`name@<private enterprise number>` must have a numeric enterprise number,
+				optionally followed by dot separated sub-identifiers.
+				"""
+		}
+	}
+}
@@ -15,6 +15,13 @@ components: sinks: vector: {
 		buffer: enabled:      true
 		healthcheck: enabled: true
 		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10000000
+				max_events:   1000
+				timeout_secs: 1
+			}
 			compression: enabled: false
 			encoding: {
 				enabled: true
@@ -84,9 +91,65 @@ components: sinks: vector: {
 				examples: ["92.12.333.224:5000"]
 			}
 		}
+		compression: {
+			common:      false
+			description: "The algorithm to compress batches with. `true` and `false` are accepted as well, for `gzip` and `none` respectively. Only applies to version 2."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "none"
+				enum: {
+					none: "Batches are sent uncompressed."
+					gzip: "Batches are compressed with gzip."
+					zstd: "Batches are compressed with [zstd][urls.zstd], which compresses better and faster than gzip."
+				}
+			}
+		}
+		delta_encoding: {
+			common:      false
+			description: "Strips the fields of log events that are the same as in the previous log event of the batch, such as the host or the Kubernetes metadata, before compressing the batch. The downstream source restores them. Only applies to version 2, and requires the downstream Vector instance to support it."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		pre_shared_key: {
+			common:      false
+			description: "Authenticates with the downstream `vector` source with this key, which must match its `pre_shared_key`, and requires the source to authenticate with it as well. Only applies to version 2."
+			required:    false
+			warnings: ["The key is never sent, but the events are only kept confidential with `tls` enabled."]
+			type: string: {
+				default: null
+				examples: ["${VECTOR_PRE_SHARED_KEY}"]
+			}
+		}
+		version: {
+			common:      true
+			description: "The version of the protocol, which must match the version of the downstream `vector` source. Version 2 does not support the `keepalive` option, but supports `batch` and `request` options."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "1"
+				enum: {
+					"1": "Length delimited events over TCP."
+					"2": "Acknowledged batches of events over gRPC."
+				}
+			}
+		}
 	}
 
-	how_it_works: components.sources.vector.how_it_works
+	how_it_works: components.sources.vector.how_it_works & {
+		bandwidth: {
+			title: "Reducing Bandwidth"
+			body: """
+				Per-event framing of version 1 sends each event, including the
+				fields it shares with the other events, on its own. For links with
+				limited bandwidth, such as from edge sites to a central aggregator,
+				use version 2 with `compression = "zstd"` and `delta_encoding = true`,
+				and raise `batch.timeout_secs` and `batch.max_events` for larger
+				batches, which compress better at the cost of latency.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		protobuf_decode_errors_total: components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
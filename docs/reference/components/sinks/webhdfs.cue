@@ -0,0 +1,229 @@
+package metadata
+
+components: sinks: webhdfs: {
+	title:       "WebHDFS"
+	description: "The [Hadoop Distributed File System][urls.hdfs] (HDFS) is the distributed file system of Hadoop, storing the data of data lakes for batch processing. Its [WebHDFS REST API][urls.webhdfs_rest_api], served by the name nodes or by [HttpFS][urls.hadoop_httpfs] gateways, writes files over HTTP."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10485760
+				max_events:   null
+				timeout_secs: 300
+			}
+			compression: {
+				enabled: true
+				default: "gzip"
+				algorithms: ["none", "gzip", "lz4", "snappy", "snappy_framed"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["ndjson", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				concurrency:                25
+				headers:                    false
+				rate_limit_duration_secs:   1
+				rate_limit_num:             250
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.hdfs
+
+				interface: {
+					socket: {
+						api: {
+							title: "WebHDFS REST API"
+							url:   urls.webhdfs_rest_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: {
+			category:    "Auth"
+			common:      false
+			description: "Options for the authentication of the requests."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					service_principal: {
+						common:        false
+						description:   "The service principal of the name node or gateway, as `service@host`. Defaults to `HTTP@` followed by the host of the `endpoint`."
+						relevant_when: "strategy = \"kerberos\""
+						required:      false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["HTTP@namenode.example.com"]
+						}
+					}
+					strategy: {
+						common:      true
+						description: "How the requests are authenticated."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "simple"
+							enum: {
+								simple:   "Names the user with the `user.name` parameter of the requests, as the simple authentication of Hadoop expects."
+								kerberos: "Authenticates with [Kerberos SPNEGO][urls.hadoop_kerberos], from the Kerberos credentials cache or the keytab of the `KRB5_CLIENT_KTNAME` environment variable. Requires Vector built with the `gssapi` feature."
+							}
+						}
+					}
+				}
+			}
+		}
+		endpoint: {
+			description: "The base URL of the WebHDFS REST API of the name node, or of an HttpFS gateway."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["http://namenode.example.com:9870", "https://httpfs.example.com:14000"]
+			}
+		}
+		filename_append_uuid: {
+			category:    "File Naming"
+			common:      false
+			description: "Whether or not to append a UUID v4 token to the end of the file names. This ensures there are no name collisions in high volume use cases."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		filename_time_format: {
+			category:    "File Naming"
+			common:      false
+			description: "The format of the timestamp of the file names. [`strftime` specifiers][urls.strptime_specifiers] are supported."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "%s"
+				examples: ["%s", "%F-%H%M%S"]
+			}
+		}
+		prefix: {
+			category:    "File Naming"
+			common:      true
+			description: "The directory the files are written to, which partitions them. It should end with a `/`. Missing directories are created along with the files."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/vector/%F/"
+				examples: ["/data/logs/date=%F/", "/data/logs/{{ application }}/year=%Y/month=%m/day=%d/"]
+				templateable: true
+			}
+		}
+		user: {
+			category:    "Auth"
+			common:      true
+			description: "The user the files are written as. With `auth.strategy` set to `kerberos`, the authenticated principal impersonates this user, which it must be allowed to as a [proxy user][urls.hadoop_proxy_users]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		authentication: {
+			title: "Authentication"
+			body: """
+				By default, the requests name the user the files are written as
+				with the `user.name` parameter, which clusters with simple
+				authentication trust. Secured clusters authenticate the requests
+				with Kerberos: with `auth.strategy` set to `kerberos`, Vector
+				negotiates a SPNEGO token for each request, from the tickets of
+				the Kerberos credentials cache, such as those obtained with
+				`kinit`, or from the keytab named by the `KRB5_CLIENT_KTNAME`
+				environment variable.
+				"""
+		}
+		file_naming: {
+			title: "File Naming"
+			body: """
+				By default, Vector will name your files in the following format:
+
+				```text
+				<prefix><timestamp>-<uuidv4>.log.gz
+				```
+
+				For example:
+
+				```text
+				/vector/2019-06-18/1560886634-fddd7a0e-fad9-4f7e-9bce-00ae5debc563.log.gz
+				```
+
+				The extension follows the `compression`, and is `.log` with
+				`compression` set to `none`. You can control the resulting name
+				via the `prefix`, `filename_time_format`, and
+				`filename_append_uuid` options.
+				"""
+		}
+		writing: {
+			title: "Writing Files"
+			body: """
+				Each batch is written to a file of its own, in the two steps of
+				the WebHDFS `CREATE` operation: the name node, or the HttpFS
+				gateway, redirects the creation to the data node the batch is
+				then sent to. Existing files are never overwritten.
+				"""
+		}
+	}
+}
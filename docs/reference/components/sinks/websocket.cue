@@ -0,0 +1,146 @@
+package metadata
+
+components: sinks: websocket: {
+	title:       "WebSocket"
+	description: "Streams events over a [WebSocket](\(urls.websocket)) connection, for example to push live event feeds to dashboards."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: {
+					name:     "WebSocket"
+					thing:    "a \(name) server"
+					url:      urls.websocket
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${WEBSOCKET_PASSWORD}"
+			username_example: "${WEBSOCKET_USERNAME}"
+		}}
+		headers: {
+			common:      false
+			description: "Headers added to the handshake request."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"Authorization": "${WEBSOCKET_TOKEN}"
+						"X-Powered-By":  "Vector"
+					},
+				]
+				options: {}
+			}
+		}
+		uri: {
+			description: "The URI to connect to. The `wss` scheme enables TLS."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["ws://127.0.0.1:9000/events", "wss://dashboard.example.com/feed"]
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		frames: {
+			title: "Frames"
+			body: """
+				Each event is sent as a text frame, encoded as configured with
+				`encoding.codec`. The connection is opened once there are events
+				to send.
+				"""
+		}
+
+		reconnects: {
+			title: "Reconnects"
+			body: """
+				When the connection can't be opened, or is lost or closed by the
+				server, Vector connects again with an exponentially growing delay
+				of up to a minute. Meanwhile events are held in the buffer. An
+				event is only marked as delivered once its frame was written, and
+				the frame whose write failed is sent again over the next
+				connection, so frames in flight when a connection breaks may be
+				received twice, or, if the server stopped reading before
+				closing the connection, lost.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		connection_established_total: components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_errors_total:      components.sources.internal_metrics.output.metrics.connection_errors_total
+		connection_failed_total:      components.sources.internal_metrics.output.metrics.connection_failed_total
+		connection_shutdown_total:    components.sources.internal_metrics.output.metrics.connection_shutdown_total
+		open_connections:             components.sources.internal_metrics.output.metrics.open_connections
+		processed_bytes_total:        components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:       components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
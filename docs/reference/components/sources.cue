@@ -18,6 +18,17 @@ components: sources: [Name=string]: {
 			}
 		}
 
+		max_in_flight_events: {
+			common:      false
+			description: "The maximum number of log events of the source held in memory downstream, by the transforms, buffers and sinks they flow through. Past it, the source stops reading until some of these events are processed, bounding the memory used behind slow sinks. Events derived from a log event, such as the copies sent to several sinks, count as that single event."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [10_000]
+				unit: "events"
+			}
+		}
+
 		if sources[Name].features.multiline.enabled {
 			multiline: {
 				common:      false
@@ -60,6 +71,37 @@ components: sources: [Name=string]: {
 			}
 		}
 
+		timestamp: {
+			common:      false
+			description: "Parses the timestamp of the log events from one of their fields, before they reach any transform, so that they carry the time they happened rather than the time they were ingested without a transform doing so."
+			required:    false
+			type: object: options: {
+				field: {
+					description: "The field holding the timestamp."
+					required:    true
+					type: string: examples: ["time", "meta.ts"]
+				}
+				formats: {
+					common:      true
+					description: "The [`strftime`](\(urls.strptime_specifiers)) formats the timestamp is parsed with, tried in order until one matches. Formats with `%s` parse seconds since the epoch."
+					required:    false
+					type: array: {
+						default: ["%+"]
+						items: type: string: examples: ["%d/%b/%Y:%T %z", "%F %T", "%s"]
+					}
+				}
+				timezone: {
+					common:      false
+					description: "The time zone of timestamps parsed with formats without zone information, either `local` or an [IANA time zone name](\(urls.iana_time_zones))."
+					required:    false
+					type: string: {
+						default: "local"
+						examples: ["local", "Europe/Berlin"]
+					}
+				}
+			}
+		}
+
 		if sources[Name].features.collect != _|_ {
 			if sources[Name].features.collect.tls != _|_ {
 				if sources[Name].features.collect.tls.enabled {
@@ -155,6 +197,32 @@ components: sources: [Name=string]: {
 			}
 		}
 
+		in_flight_events: {
+			title: "Limiting events in flight"
+			body: """
+				With the `max_in_flight_events` option set, the source stops reading
+				once that many of its log events are held in memory downstream,
+				and resumes as they are processed. Connection based sources stop
+				reading from their connections, and the `file` and `kafka` sources
+				stop reading their files and topics. Events written to a disk buffer
+				are no longer in flight. The `in_flight_limit_reached_total` internal
+				metric counts the times the source was paused.
+				"""
+		}
+
+		timestamp_decoding: {
+			title: "Timestamp decoding"
+			body: """
+				With the `timestamp` option set, the source replaces the time of
+				ingestion of its log events with the timestamp parsed from
+				`timestamp.field`, before they reach any transform. Events whose
+				field can't be parsed with any of the formats keep the time of
+				ingestion and are counted by the `event_timestamp_parse_errors_total`
+				internal metric, labeled with the source. Events without the field
+				are left as they are.
+				"""
+		}
+
 		context: {
 			title: "Context"
 			body:  """
@@ -0,0 +1,317 @@
+package metadata
+
+components: sources: api_poller: {
+	title:       "API Poller"
+	description: "Periodically polls an HTTP API returning JSON records, such as the audit log APIs of SaaS products, and keeps track of its position in the record stream."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "HTTP API"
+					thing:    "an \(name)"
+					url:      urls.http
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		auth: {
+			common:      false
+			description: "Configures the authentication strategy."
+			required:    false
+			type: object: options: {
+				client_id: {
+					description: "The OAuth 2.0 client ID."
+					required:    true
+					relevant_when: "strategy = \"oauth2\""
+					type: string: examples: ["${CLIENT_ID}"]
+				}
+				client_secret: {
+					description: "The OAuth 2.0 client secret."
+					required:    true
+					relevant_when: "strategy = \"oauth2\""
+					type: string: examples: ["${CLIENT_SECRET}"]
+				}
+				password: {
+					description: "The basic authentication password."
+					required:    true
+					relevant_when: "strategy = \"basic\""
+					type: string: examples: ["${API_PASSWORD}"]
+				}
+				scopes: {
+					common:      false
+					description: "The OAuth 2.0 scopes to request."
+					required:    false
+					relevant_when: "strategy = \"oauth2\""
+					type: array: {
+						default: []
+						items: type: string: examples: ["events:read"]
+					}
+				}
+				strategy: {
+					description: "The authentication strategy to use."
+					required:    true
+					type: string: enum: {
+						basic:  "The [basic authentication strategy](\(urls.basic_auth))."
+						bearer: "The bearer token authentication strategy."
+						oauth2: "Bearer tokens obtained through the [OAuth 2.0 client credentials grant](\(urls.oauth2_client_credentials)). Tokens are refreshed shortly before they expire."
+					}
+				}
+				token: {
+					description: "The bearer token."
+					required:    true
+					relevant_when: "strategy = \"bearer\""
+					type: string: examples: ["${API_TOKEN}"]
+				}
+				token_url: {
+					description: "The token endpoint of the OAuth 2.0 authorization server."
+					required:    true
+					relevant_when: "strategy = \"oauth2\""
+					type: string: examples: ["https://auth.example.com/oauth/token"]
+				}
+				user: {
+					description: "The basic authentication user name."
+					required:    true
+					relevant_when: "strategy = \"basic\""
+					type: string: examples: ["${API_USERNAME}"]
+				}
+			}
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the cursor between restarts. Defaults to the global `data_dir` option."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		headers: {
+			common:      false
+			description: "Headers sent with every request."
+			required:    false
+			type: object: {
+				examples: [{"X-Api-Key": "${API_KEY}"}]
+				options: {}
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between polls."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		message_field: {
+			common:      false
+			description: "The dot separated path of the record field copied into the `message` field."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["description", "events.0.name"]
+			}
+		}
+		pagination: {
+			common:      true
+			description: "How to move through the records of the API."
+			required:    false
+			type: object: options: {
+				field: {
+					common:      false
+					description: "The dot separated path of the record field holding the cursor. For `page_token` the greatest value of a poll is the cursor of the next poll."
+					required:    false
+					relevant_when: "strategy = \"last_record\" or strategy = \"page_token\""
+					type: string: {
+						default: null
+						examples: ["id", "created_at"]
+					}
+				}
+				param: {
+					common:      false
+					description: "The query parameter the cursor is passed as. Can be left out if the `url` contains a `{{ cursor }}` placeholder."
+					required:    false
+					relevant_when: "strategy = \"last_record\" or strategy = \"page_token\""
+					type: string: {
+						default: null
+						examples: ["since"]
+					}
+				}
+				strategy: {
+					common:      true
+					description: "The pagination strategy to use."
+					required:    false
+					type: string: {
+						default: "none"
+						enum: {
+							none:        "Fetch a single page per poll."
+							link_header: "Follow the [`Link` header](\(urls.ietf_rfc_8288)) with `rel=\"next\"`. The last link is the cursor."
+							last_record: "Use the `field` of the last record of a page as the cursor of the next request, until a page adds no records."
+							page_token:  "Follow the page token at `token_field` of the response until there is none."
+						}
+					}
+				}
+				token_field: {
+					description: "The dot separated path of the page token in the response."
+					required:    true
+					relevant_when: "strategy = \"page_token\""
+					type: string: examples: ["nextPageToken", "meta.next"]
+				}
+				token_param: {
+					common:      false
+					description: "The query parameter the page token is passed as. Can be left out if the `url` contains a `{{ page_token }}` placeholder."
+					required:    false
+					relevant_when: "strategy = \"page_token\""
+					type: string: {
+						default: null
+						examples: ["pageToken"]
+					}
+				}
+			}
+		}
+		rate_limit: {
+			common:      false
+			description: "The response headers the API reports its rate limit with. Vector waits for the rate limit to reset when no requests are remaining, and honors the retry header of `429 Too Many Requests` responses."
+			required:    false
+			type: object: options: {
+				remaining_header: {
+					common:      false
+					description: "The header holding the number of requests left."
+					required:    false
+					type: string: default: "x-ratelimit-remaining"
+				}
+				reset_header: {
+					common:      false
+					description: "The header holding when the rate limit resets, either as a Unix timestamp or as seconds from now."
+					required:    false
+					type: string: default: "x-ratelimit-reset"
+				}
+				retry_after_header: {
+					common:      false
+					description: "The header holding the seconds to wait before retrying."
+					required:    false
+					type: string: default: "retry-after"
+				}
+			}
+		}
+		records_path: {
+			common:      true
+			description: "A [JSONPath](\(urls.jsonpath)) expression selecting the records in the response. A path selecting a single array selects its elements. Supports fields, indices and `*` wildcards."
+			required:    false
+			type: string: {
+				default: "$"
+				examples: ["$.data", "$.results[*].events[*]"]
+			}
+		}
+		timestamp_field: {
+			common:      false
+			description: "The dot separated path of the record field holding the RFC 3339 timestamp of the record. The time of the poll is used if unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["created_at"]
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		url: {
+			description: "The URL to poll. The `{{ cursor }}` and `{{ page_token }}` placeholders are replaced with their URL encoded values, query parameters referencing a missing value are left out."
+			required:    true
+			type: string: examples: ["https://api.example.com/v1/events?since={{ cursor }}&limit=100"]
+		}
+	}
+
+	output: logs: event: {
+		description: "A record returned by the API."
+		fields: {
+			message: {
+				description: "The `message_field` of the record, if configured."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["User logged in"]
+				}
+			}
+			timestamp: {
+				description: "The `timestamp_field` of the record, or the time of the poll."
+				required:    true
+				type: timestamp: {}
+			}
+			"*": {
+				common:      false
+				description: "Any field of the record."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["user.login"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				The cursor of the last page is stored in the data directory after
+				every page, so that Vector resumes where it stopped after a
+				restart. Records of a page that was only partially delivered
+				before a restart may be emitted twice.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		http_request_errors_total:     components.sources.internal_metrics.output.metrics.http_request_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+		rate_limited_total:            components.sources.internal_metrics.output.metrics.rate_limited_total
+	}
+}
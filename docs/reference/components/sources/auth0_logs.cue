@@ -0,0 +1,145 @@
+package metadata
+
+components: sources: auth0_logs: {
+	title:       "Auth0 Logs"
+	description: "[Auth0][urls.auth0] is an authentication and authorization platform. Its [log events][urls.auth0_logs_api] record the actions taken in an Auth0 tenant."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "Auth0"
+					thing:    "an \(name) tenant"
+					url:      urls.auth0
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Auth0 Management API log events"
+							url:   urls.auth0_logs_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"A Management API token with the `read:logs` scope.",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the `log_id` of the last pulled event between restarts. Defaults to the global `data_dir` option."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			description: "The URL of the Auth0 tenant."
+			required:    true
+			type: string: examples: ["https://example.auth0.com"]
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between polls."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		token: {
+			description: "The Auth0 Management API token."
+			required:    true
+			type: string: examples: ["${AUTH0_API_TOKEN}"]
+		}
+	}
+
+	output: logs: event: {
+		description: "An Auth0 log event."
+		fields: {
+			message: {
+				description: "The `description` of the event."
+				required:    true
+				type: string: examples: ["Wrong email or password."]
+			}
+			timestamp: {
+				description: "The `date` of the event."
+				required:    true
+				type: timestamp: {}
+			}
+			"*": {
+				common:      false
+				description: "Any field of the log event, such as `log_id`, `type` or `user_id`."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["fp"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				On its first run Vector starts at the most recent log event. It then
+				requests the events following the `log_id` of the last pulled event,
+				which is stored in its data directory so that it resumes where it
+				stopped after a restart. When the API asks Vector to slow down, it
+				waits until the rate limit resets.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		http_request_errors_total:     components.sources.internal_metrics.output.metrics.http_request_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+		rate_limited_total:            components.sources.internal_metrics.output.metrics.rate_limited_total
+	}
+}
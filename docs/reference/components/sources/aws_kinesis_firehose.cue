@@ -92,6 +92,18 @@ components: sources: aws_kinesis_firehose: components._aws & {
 				examples: ["A94A8FE5CCB19BA61C4C08"]
 			}
 		}
+		record_format: {
+			common:      true
+			description: "How the data of the incoming records is turned into events."
+			required:    false
+			type: string: {
+				default: "raw"
+				enum: {
+					raw:             "One event is published per record, with the decoded data as `message`."
+					cloudwatch_logs: "The records are parsed as [AWS CloudWatch Logs subscription](\(urls.aws_cloudwatch_logs_subscriptions)) messages, and one event is published per contained log event. Control messages are dropped."
+				}
+			}
+		}
 	}
 
 	output: logs: {
@@ -116,6 +128,56 @@ components: sources: aws_kinesis_firehose: components._aws & {
 				}
 			}
 		}
+		cloudwatch_logs: {
+			description: "With `record_format = \"cloudwatch_logs\"`, one event will be published per log event of the incoming AWS CloudWatch Logs subscription messages."
+			fields: {
+				timestamp: {
+					description: "The time the log event was ingested by AWS CloudWatch Logs."
+					required:    true
+					type: timestamp: {}
+				}
+				message: {
+					description: "The message of the log event."
+					required:    true
+					type: string: examples: ["Started GET / for 127.0.0.1 at 2012-03-10 14:28:14 +0100"]
+				}
+				id: {
+					description: "The ID of the log event."
+					required:    true
+					type: string: examples: ["35683658089614582423604394983260738922885519999578275840"]
+				}
+				log_group: {
+					description: "The log group the log event was published to."
+					required:    true
+					type: string: examples: ["/lambda/test"]
+				}
+				log_stream: {
+					description: "The log stream the log event was published to."
+					required:    true
+					type: string: examples: ["3E1E61C5B3D24D33B6F4F4B4A7F0B4E1"]
+				}
+				owner: {
+					description: "The ID of the AWS account of the log group."
+					required:    true
+					type: string: examples: ["111111111111"]
+				}
+				subscription_filters: {
+					description: "The subscription filters that matched the log event."
+					required:    true
+					type: array: items: type: string: examples: ["Destination"]
+				}
+				request_id: {
+					description: "The AWS Kinesis Firehose request ID, value of the `X-Amz-Firehose-Request-Id` header."
+					required:    true
+					type: string: examples: ["ed1d787c-b9e2-4631-92dc-8e7c9d26d804"]
+				}
+				source_arn: {
+					description: "The AWS Kinises Firehose delivery stream that issued the request, value of the `X-Amz-Firehose-Source-Arn` header."
+					required:    true
+					type: string: examples: ["arn:aws:firehose:us-east-1:111111111111:deliverystream/test"]
+				}
+			}
+		}
 	}
 
 	examples: [
@@ -159,11 +221,10 @@ components: sources: aws_kinesis_firehose: components._aws & {
 				set this up:
 
 				1. Deploy vector with a publicly exposed HTTP endpoint using
-				   this source. You will likely also want to use the
-				   [`aws_cloudwatch_logs_subscription_parser`][vector_transform_aws_cloudwatch_logs_subscription_parser]
-				   transform to extract the log events.. Make sure to set
-				   the `access_key` to secure this endpoint. Your
-				   configuration might look something like:
+				   this source, with `record_format = "cloudwatch_logs"` to
+				   extract the log events. Make sure to set the `access_key`
+				   to secure this endpoint. Your configuration might look
+				   something like:
 
 				   ```toml
 						[sources.firehose]
@@ -171,17 +232,18 @@ components: sources: aws_kinesis_firehose: components._aws & {
 						  type = "aws_kinesis_firehose"
 						  address = "127.0.0.1:9000"
 						  access_key = "secret"
-
-						[transforms.cloudwatch]
-						  type = "aws_cloudwatch_logs_subscription_parser"
-						  inputs = ["firehose"]
+						  record_format = "cloudwatch_logs"
 
 						[sinks.console]
 						  type = "console"
-						  inputs = ["cloudwatch"]
+						  inputs = ["firehose"]
 						  encoding.codec = "json"
 				   ```
 
+				   Alternatively, the raw records can be forwarded to the
+				   [`aws_cloudwatch_logs_subscription_parser`][vector_transform_aws_cloudwatch_logs_subscription_parser]
+				   transform.
+
 				2. Create a Kinesis Firewatch delivery stream in the region
 				   where the CloudWatch Logs groups exist that you want to
 				   ingest.
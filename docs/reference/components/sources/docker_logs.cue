@@ -118,6 +118,43 @@ components: sources: docker_logs: {
 			required: false
 			type: bool: default: true
 		}
+		exclude_containers: {
+			common: false
+			description: """
+				A list of container IDs _or_ names to exclude. Prefix matches
+				are supported, the same as for `include_containers`. Excludes
+				take precedence over includes.
+				"""
+			required: false
+			type: array: {
+				default: null
+				items: type: string: examples: ["serene_", "serene_leakey", "ad08cc418cf9"]
+			}
+		}
+		exclude_images: {
+			common: false
+			description: """
+				A list of image names to exclude. An image given without a tag
+				excludes every tag of that image.
+				"""
+			required: false
+			type: array: {
+				default: null
+				items: type: string: examples: ["busybox", "redis:6.0"]
+			}
+		}
+		exclude_labels: {
+			common: false
+			description: """
+				A list of container object labels to exclude, either as `key`
+				to exclude every container with that label, or as `key=value`.
+				"""
+			required: false
+			type: array: {
+				default: null
+				items: type: string: examples: ["com.example.vector.exclude", "com.example.tier=debug"]
+			}
+		}
 		include_containers: {
 			common: true
 			description: """
@@ -260,7 +297,9 @@ components: sources: docker_logs: {
 				these messages into a single message. You can turn this off via the
 				`auto_partial_merge` option. Furthermore, you can adjust the marker
 				that we use to determine if an event is partial via the
-				`partial_event_marker_field` option.
+				`partial_event_marker_field` option. Partial messages are merged
+				per stream, so a split `stdout` line is never joined with output
+				written to `stderr` in the meantime.
 				"""
 		}
 	}
@@ -0,0 +1,216 @@
+package metadata
+
+components: sources: elasticsearch: {
+	title:       "Elasticsearch"
+	description: "[Elasticsearch][urls.elasticsearch] is a search engine based on the Lucene library. It provides a distributed, multitenant-capable full-text search engine with an HTTP web interface and schema-free JSON documents. This source reads the documents of indices, to re-index them or to migrate them to other systems."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "Elasticsearch"
+					thing:    "an \(name) cluster"
+					url:      urls.elasticsearch
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Elasticsearch search API"
+							url:   urls.elasticsearch_point_in_time
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"The `point_in_time` mode requires Elasticsearch 7.10 or later, use the `scroll` mode for older clusters and for [OpenSearch][urls.opensearch].",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${ELASTICSEARCH_PASSWORD}"
+			username_example: "${ELASTICSEARCH_USERNAME}"
+		}}
+		batch_size: {
+			common:      false
+			description: "The number of documents requested per page."
+			required:    false
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the position of the read between restarts. Defaults to the global `data_dir` option."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			description: "The base URL of the cluster."
+			required:    true
+			type: string: examples: ["http://localhost:9200"]
+		}
+		id_key: {
+			common:      false
+			description: "The field the `_id` of the documents is stored in."
+			required:    false
+			type: string: default: "_id"
+		}
+		index: {
+			description: "The index, alias or data stream to read, which may be a comma separated list or contain wildcards."
+			required:    true
+			type: string: examples: ["logs-*", "orders,customers"]
+		}
+		index_key: {
+			common:      false
+			description: "The field the `_index` of the documents is stored in."
+			required:    false
+			type: string: default: "_index"
+		}
+		keep_alive: {
+			common:      false
+			description: "How long the cluster keeps the point in time or scroll alive between two pages, in the [time units][urls.elasticsearch_point_in_time] of Elasticsearch."
+			required:    false
+			type: string: default: "5m"
+		}
+		mode: {
+			common:      true
+			description: "How the documents are paged through."
+			required:    false
+			type: string: {
+				default: "point_in_time"
+				enum: {
+					point_in_time: "Search a [point in time][urls.elasticsearch_point_in_time] of the index, paging with `search_after`."
+					scroll:        "Use the [scroll API][urls.elasticsearch_scroll]."
+				}
+			}
+		}
+		query: {
+			common:      false
+			description: "The [query][urls.elasticsearch_query_dsl] selecting the documents to read. Defaults to all documents."
+			required:    false
+			type: object: {
+				examples: [{"range": {"@timestamp": {"gte": "now-7d"}}}]
+				options: {}
+			}
+		}
+		slices: {
+			common:      false
+			description: "The number of [slices][urls.elasticsearch_slicing] the index is read in concurrently."
+			required:    false
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
+		sort_field: {
+			common:      false
+			description: "A field the documents are read in the order of, such as a timestamp, in `point_in_time` mode. It lets reads resume after the point in time expired, see [Checkpointing](#checkpointing)."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["@timestamp"]
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+	}
+
+	output: logs: document: {
+		description: "A document of the index."
+		fields: {
+			"_id": {
+				description: "The ID of the document, in the field set by `id_key`."
+				required:    true
+				type: string: examples: ["hUm5FHcBfBYtXdCZmVgG"]
+			}
+			"_index": {
+				description: "The index of the document, in the field set by `index_key`."
+				required:    true
+				type: string: examples: ["logs-2021.02.01"]
+			}
+			"*": {
+				common:      false
+				description: "The fields of the `_source` of the document."
+				required:    false
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				After every page Vector stores the position of each slice in
+				its data directory, so that it resumes where it stopped after a
+				restart, and stops once every slice was read. Remove the
+				checkpoint to read the index again.
+
+				When the point in time expired, Vector opens a new one and
+				continues after the last document read, in the order of
+				`sort_field`. Documents added meanwhile may be read, and without
+				a `sort_field` the read may skip or repeat documents. An
+				expired scroll can't be resumed, so its slice is read again
+				from the beginning.
+				"""
+		}
+		reindexing: {
+			title: "Re-indexing"
+			body: """
+				Together with an `elasticsearch` sink whose `id_key` is `_id`,
+				documents keep their IDs, so that reading them again overwrites
+				the copies instead of duplicating them.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		http_request_errors_total:     components.sources.internal_metrics.output.metrics.http_request_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
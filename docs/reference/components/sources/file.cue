@@ -180,6 +180,19 @@ components: sources: file: {
 				unit: "bytes"
 			}
 		}
+		now_source: {
+			common:      false
+			description: "Where the timestamps of the lines come from, as they carry none of their own."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "system"
+				enum: {
+					system: "The clock of the host Vector runs on, when the line is read."
+					none:   "The `timestamp` field of the events is left unset."
+				}
+			}
+		}
 		oldest_first: {
 			category:    "Reading"
 			common:      false
@@ -527,6 +540,7 @@ components: sources: file: {
 		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
 		checkpoints_total:             components.sources.internal_metrics.output.metrics.checkpoints_total
 		checksum_errors_total:         components.sources.internal_metrics.output.metrics.checksum_errors_total
+		file_bytes_behind:             components.sources.internal_metrics.output.metrics.file_bytes_behind
 		file_delete_errors_total:      components.sources.internal_metrics.output.metrics.file_delete_errors_total
 		file_watch_errors_total:       components.sources.internal_metrics.output.metrics.file_watch_errors_total
 		files_added_total:             components.sources.internal_metrics.output.metrics.files_added_total
@@ -0,0 +1,286 @@
+package metadata
+
+components: sources: gcp_cloud_logging: {
+	title:       "GCP Cloud Logging"
+	description: "[GCP Cloud Logging][urls.gcp_cloud_logging] is a fully managed service that stores and queries the logs of the Google Cloud Platform and of the applications running on it."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "GCP Cloud Logging"
+					thing:    "a \(name) project"
+					url:      urls.gcp_cloud_logging
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "GCP Pub/Sub and Cloud Logging APIs"
+							url:   urls.gcp_cloud_logging_entries_list
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"With `mode` set to `pubsub`, a [Cloud Logging sink][urls.gcp_cloud_logging_sinks] routing the entries to a Pub/Sub topic, and a pull subscription to that topic.",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		api_key: {
+			common:      false
+			description: "A [Google Cloud API key][urls.gcp_authentication_api_key] used to authenticate the requests. Either this or `credentials_path` must be set."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${GCP_API_KEY}", "ef8d5de700e7989468166c40fc8a0ccd"]
+			}
+		}
+		batch_size: {
+			common:      false
+			description: "The maximum number of messages pulled, or entries listed, per request."
+			required:    false
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate the requests. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the position in the entry stream between restarts, with `mode` set to `entries_list`. Defaults to the global `data_dir` option."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The URL of the Pub/Sub API, or of the Cloud Logging API with `mode` set to `entries_list`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["https://pubsub.googleapis.com", "https://logging.googleapis.com"]
+			}
+		}
+		filter: {
+			common:      false
+			description: "An [advanced logs filter][urls.gcp_cloud_logging_filters] selecting the entries to list, with `mode` set to `entries_list`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["severity >= WARNING", "resource.type = \"k8s_container\""]
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between pulls, or polls with `mode` set to `entries_list`. Pulls are repeated right away while the subscription has a backlog."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		mode: {
+			common:      true
+			description: "How the log entries are pulled."
+			required:    false
+			type: string: {
+				default: "pubsub"
+				enum: {
+					pubsub:       "Pull the entries a Cloud Logging sink routes to a Pub/Sub topic from a subscription to it, acknowledging them once processed."
+					entries_list: "Poll the [`entries.list`][urls.gcp_cloud_logging_entries_list] API for the entries newer than the last one seen."
+				}
+			}
+		}
+		project: {
+			description: "The project of the subscription, or of the entries to list."
+			required:    true
+			type: string: examples: ["vector-123456"]
+		}
+		resource_names: {
+			common:      false
+			description: "The resources to list the entries of, with `mode` set to `entries_list`. Defaults to the `project`."
+			required:    false
+			type: array: {
+				default: null
+				items: type: string: examples: ["projects/vector-123456", "organizations/123456789", "folders/123456789"]
+			}
+		}
+		skip_authentication: {
+			common:      false
+			description: "Skip the authentication of the requests, such as when pulling from the Pub/Sub emulator."
+			required:    false
+			type: bool: default: false
+		}
+		subscription: {
+			common:      true
+			description: "The Pub/Sub subscription to pull the entries from. Required with `mode` set to `pubsub`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-logs"]
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+	}
+
+	output: logs: event: {
+		description: "A Cloud Logging [`LogEntry`][urls.gcp_cloud_logging_log_entry]."
+		fields: {
+			message: {
+				description: "The `textPayload` of the entry."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["Started server on port 8080"]
+				}
+			}
+			timestamp: {
+				description: "The `timestamp` of the entry."
+				required:    true
+				type: timestamp: {}
+			}
+			severity: {
+				description: "The severity of the entry."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["INFO", "ERROR"]
+				}
+			}
+			log_name: {
+				description: "The name of the log the entry belongs to."
+				required:    true
+				type: string: examples: ["projects/vector-123456/logs/stdout"]
+			}
+			insert_id: {
+				description: "The unique identifier of the entry."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["42f7ysf2sc2fbqm"]
+				}
+			}
+			resource: {
+				description: "The monitored resource that produced the entry, with its `type` and `labels`."
+				required:    true
+				type: object: {
+					examples: [{type: "k8s_container", labels: {namespace_name: "default"}}]
+					options: {}
+				}
+			}
+			labels: {
+				description: "The labels of the entry."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"compute.googleapis.com/resource_name": "instance-1"}]
+					options: {}
+				}
+			}
+			"*": {
+				common:      false
+				description: "The fields of the `jsonPayload` of the entry, and the other fields of the entry under their snake case name, such as `http_request`, `trace`, `span_id`, `source_location` or `proto_payload`."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["projects/vector-123456/traces/06796866738c859f2f19b7cfb3214824"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		pubsub: {
+			title: "Pulling from Pub/Sub"
+			body: """
+				With `mode` set to `pubsub`, the entries are pulled from a
+				subscription to the Pub/Sub topic a [Cloud Logging sink][urls.gcp_cloud_logging_sinks]
+				routes them to. The messages are acknowledged once their events are
+				processed, so entries pulled by Vector right before a crash are
+				redelivered. This is the recommended mode for high volumes of logs, as
+				the `entries.list` API is subject to a low request quota.
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				With `mode` set to `entries_list`, each poll lists the entries since
+				the newest entry of the previous poll, whose timestamp and insert ID
+				are stored in the data directory so that Vector resumes where it
+				stopped after a restart. Without a checkpoint, Vector starts with the
+				entries from the time it starts.
+				"""
+		}
+		json_payload: {
+			title: "Structured payloads"
+			body: """
+				The fields of the `jsonPayload` of the entries become the fields of
+				the events, so payloads holding a `message` field populate the
+				message of the events. The other fields of the entries, such as
+				`severity` or `resource`, are added under their snake case name and
+				take precedence over payload fields of the same name.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		http_request_errors_total:     components.sources.internal_metrics.output.metrics.http_request_errors_total
+		parse_errors_total:            components.sources.internal_metrics.output.metrics.parse_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
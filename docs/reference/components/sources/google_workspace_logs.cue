@@ -0,0 +1,161 @@
+package metadata
+
+components: sources: google_workspace_logs: {
+	title:       "Google Workspace Logs"
+	description: "[Google Workspace][urls.google_workspace] is a suite of productivity applications. The [Reports API][urls.google_workspace_reports_api] provides the audit activities of a Google Workspace domain."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "Google Workspace"
+					thing:    "a \(name) domain"
+					url:      urls.google_workspace
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Google Workspace Reports API"
+							url:   urls.google_workspace_reports_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"A service account with [domain-wide delegation](\(urls.google_workspace_domain_wide_delegation)) of the `https://www.googleapis.com/auth/admin.reports.audit.readonly` scope.",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		application: {
+			description: "The application to pull the activities of."
+			required:    true
+			type: string: examples: ["login", "admin", "drive", "token"]
+		}
+		credentials_path: {
+			description: "The path to the JSON key file of the service account."
+			required:    true
+			type: string: examples: ["/path/to/credentials.json"]
+		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the time of the most recent pulled activity between restarts. Defaults to the global `data_dir` option."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The URL of the Admin SDK API."
+			required:    false
+			type: string: default: "https://admin.googleapis.com"
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between polls."
+			required:    false
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+		subject: {
+			description: "The email address of the administrator the service account acts on behalf of."
+			required:    true
+			type: string: examples: ["admin@example.com"]
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		user_key: {
+			common:      false
+			description: "The profile ID or email of the user to pull the activities of, or `all` for all users."
+			required:    false
+			type: string: default: "all"
+		}
+	}
+
+	output: logs: event: {
+		description: "A Google Workspace activity."
+		fields: {
+			message: {
+				description: "The `name` of the first event of the activity."
+				required:    true
+				type: string: examples: ["login_success"]
+			}
+			timestamp: {
+				description: "The time of the activity."
+				required:    true
+				type: timestamp: {}
+			}
+			"*": {
+				common:      false
+				description: "Any field of the activity, such as `actor`, `events` or `ipAddress`."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["user@example.com"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				Each poll pages through the activities since the most recent
+				activity of the previous poll, whose time is stored in the data
+				directory so that Vector resumes where it stopped after a restart.
+				Activities sharing the checkpointed time may be emitted twice.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		http_request_errors_total:     components.sources.internal_metrics.output.metrics.http_request_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+		rate_limited_total:            components.sources.internal_metrics.output.metrics.rate_limited_total
+	}
+}
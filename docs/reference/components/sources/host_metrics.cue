@@ -50,7 +50,7 @@ components: sources: host_metrics: {
 			common:      true
 			required:    false
 			type: array: {
-				default: ["cpu", "disk", "filesystem", "load", "memory", "network"]
+				default: ["cpu", "disk", "filesystem", "load", "memory", "network", "nfs", "pressure", "process", "tcp", "udp"]
 				items: type: string: enum: {
 					cpu:        "Metrics related to CPU utilization."
 					disk:       "Metrics related to disk I/O utilization."
@@ -58,6 +58,11 @@ components: sources: host_metrics: {
 					load:       "Load average metrics (UNIX only)."
 					memory:     "Metrics related to memory utilization."
 					network:    "Metrics related to network utilization."
+					nfs:        "Metrics related to NFS client RPC calls (Linux only)."
+					pressure:   "Pressure stall information of CPU, I/O and memory (Linux 4.20 and later only)."
+					process:    "Metrics related to the resource utilization of selected processes."
+					tcp:        "The number of TCP connections by state (Linux only)."
+					udp:        "Metrics related to UDP sockets and datagrams (Linux only)."
 				}
 			}
 		}
@@ -76,6 +81,38 @@ components: sources: host_metrics: {
 				unit:    "seconds"
 			}
 		}
+		cpu: {
+			common:      false
+			description: #"Options for the "cpu" metrics collector."#
+			required:    false
+			type: object: options: {
+				scrape_interval_secs: {
+					common:      false
+					required:    false
+					description: "The interval between scrapes of the CPU metrics, in seconds. Defaults to the `scrape_interval_secs` of the source."
+					type: uint: {
+						default: null
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+		memory: {
+			common:      false
+			description: #"Options for the "memory" metrics collector."#
+			required:    false
+			type: object: options: {
+				scrape_interval_secs: {
+					common:      false
+					required:    false
+					description: "The interval between scrapes of the memory and swap metrics, in seconds. Defaults to the `scrape_interval_secs` of the source."
+					type: uint: {
+						default: null
+						unit:    "seconds"
+					}
+				}
+			}
+		}
 		disk: {
 			common:      false
 			description: #"Options for the "disk" metrics collector."#
@@ -114,6 +151,15 @@ components: sources: host_metrics: {
 						}
 					}
 				}
+				scrape_interval_secs: {
+					common:      false
+					required:    false
+					description: "The interval between scrapes of the disk metrics, in seconds. Defaults to the `scrape_interval_secs` of the source."
+					type: uint: {
+						default: null
+						unit:    "seconds"
+					}
+				}
 			}
 		}
 		filesystem: {
@@ -215,11 +261,20 @@ components: sources: host_metrics: {
 								"""
 							type: array: {
 								default: []
-								items: type: string: examples: ["/home", "/raid*"]
+								items: type: string: examples: ["/var/lib/docker/overlay2/*", "/run/*"]
 							}
 						}
 					}
 				}
+				scrape_interval_secs: {
+					common:      false
+					required:    false
+					description: "The interval between scrapes of the filesystem metrics, in seconds. Defaults to the `scrape_interval_secs` of the source."
+					type: uint: {
+						default: null
+						unit:    "seconds"
+					}
+				}
 			}
 		}
 		network: {
@@ -255,11 +310,45 @@ components: sources: host_metrics: {
 								"""
 							type: array: {
 								default: []
-								items: type: string: examples: ["sda", "dm-*"]
+								items: type: string: examples: ["veth*", "docker*"]
 							}
 						}
 					}
 				}
+				scrape_interval_secs: {
+					common:      false
+					required:    false
+					description: "The interval between scrapes of the network metrics, in seconds. Defaults to the `scrape_interval_secs` of the source."
+					type: uint: {
+						default: null
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+		process: {
+			common:      false
+			description: #"Options for the "process" metrics collector. No processes are monitored unless at least one pattern is given."#
+			required:    false
+			type: object: options: {
+				cmdlines: {
+					common:      false
+					required:    false
+					description: "The list of regular expressions matched against the command line of a process, with arguments separated by spaces."
+					type: array: {
+						default: []
+						items: type: string: examples: ["^/usr/bin/java .*kafka"]
+					}
+				}
+				names: {
+					common:      false
+					required:    false
+					description: "The list of regular expressions matched against the name of a process."
+					type: array: {
+						default: []
+						items: type: string: examples: ["^nginx$", "^postgres"]
+					}
+				}
 			}
 		}
 	}
@@ -342,6 +431,77 @@ components: sources: host_metrics: {
 		network_transmit_packets_drop_total: _host & _network_nomac & {description: "The number of packets dropped during transmits on this interface."}
 		network_transmit_packets_total:      _host & _network_nomac & {description: "The number of packets transmitted on this interface."}
 
+		// Host NFS
+		nfs_rpc_auth_refreshes_total:  _host & _nfs_counter & {description: "The number of RPC calls of the NFS client that required refreshing their authentication."}
+		nfs_rpc_calls_total:           _host & _nfs_counter & {description: "The number of RPC calls made by the NFS client."}
+		nfs_rpc_retransmissions_total: _host & _nfs_counter & {description: "The number of RPC calls of the NFS client that were retransmitted."}
+
+		// Host pressure stall information
+		pressure_stall_ratio: _host & _pressure & {
+			description: "The share of time in which some or all tasks were stalled on the resource, averaged over the given window."
+			type:        "gauge"
+			tags:        _pressure_tags & {
+				window: {
+					description: "The window the share is averaged over."
+					required:    true
+					examples: ["10s", "60s", "300s"]
+				}
+			}
+		}
+		pressure_stall_seconds_total: _host & _pressure & {
+			description: "The accumulated time in which some or all tasks were stalled on the resource."
+			type:        "counter"
+			tags:        _pressure_tags
+		}
+
+		// Host processes
+		process_cpu_seconds_total: _host & _process & {
+			description: "The number of CPU seconds used by the process in different operating modes."
+			type:        "counter"
+			tags:        _process_tags & {
+				mode: {
+					description: "Which mode the CPU was running in during the given time."
+					required:    true
+					examples: ["system", "user"]
+				}
+			}
+		}
+		process_io_read_bytes_total:    _host & _process_counter & _process_linux & {description: "The number of bytes the process read from storage."}
+		process_io_written_bytes_total: _host & _process_counter & _process_linux & {description: "The number of bytes the process wrote to storage."}
+		process_memory_rss_bytes:       _host & _process_gauge & {description:                   "The number of bytes of main memory held by the process."}
+		process_memory_virtual_bytes:   _host & _process_gauge & {description:                   "The number of bytes of virtual memory used by the process."}
+		process_open_fds:               _host & _process_gauge & _process_linux & {description:   "The number of file descriptors the process has open."}
+
+		// Host TCP
+		tcp_connections: _host & {
+			description:   "The number of TCP connections in the given state."
+			type:          "gauge"
+			relevant_when: "OS is Linux"
+			tags:          _host_metrics_tags & {
+				collector: examples: ["tcp"]
+				state: {
+					description: "The state of the connections."
+					required:    true
+					examples: ["established", "listen", "time_wait"]
+				}
+			}
+		}
+
+		// Host UDP
+		udp_datagrams_received_total:    _host & _udp_counter & {description: "The number of UDP datagrams delivered to sockets."}
+		udp_datagrams_sent_total:        _host & _udp_counter & {description: "The number of UDP datagrams sent."}
+		udp_receive_buffer_errors_total: _host & _udp_counter & {description: "The number of UDP datagrams dropped because the receive buffer of the socket was full."}
+		udp_receive_errors_total:        _host & _udp_counter & {description: "The number of UDP datagrams that could not be delivered for reasons other than a missing socket."}
+		udp_send_buffer_errors_total:    _host & _udp_counter & {description: "The number of UDP datagrams dropped because the send buffer of the socket was full."}
+		udp_sockets: _host & {
+			description:   "The number of open UDP sockets."
+			type:          "gauge"
+			relevant_when: "OS is Linux"
+			tags:          _host_metrics_tags & {
+				collector: examples: ["udp"]
+			}
+		}
+
 		// Helpers
 		_host: {
 			default_namespace: "host"
@@ -405,5 +565,52 @@ components: sources: host_metrics: {
 			}
 		}
 		_network_nomac: _network_gauge & {relevant_when: "OS is not macOS"}
+		_nfs_counter: {
+			type:          "counter"
+			relevant_when: "OS is Linux"
+			tags:          _host_metrics_tags & {
+				collector: examples: ["nfs"]
+			}
+		}
+		_pressure: {relevant_when: "OS is Linux"}
+		_pressure_tags: _host_metrics_tags & {
+			collector: examples: ["pressure"]
+			kind: {
+				description: "Whether `some` or `full`, meaning all non-idle, tasks were stalled."
+				required:    true
+				examples: ["some", "full"]
+			}
+			resource: {
+				description: "The stalled resource."
+				required:    true
+				examples: ["cpu", "io", "memory"]
+			}
+		}
+		_process_tags: _host_metrics_tags & {
+			collector: examples: ["process"]
+			name: {
+				description: "The name of the process."
+				required:    true
+				examples: ["nginx"]
+			}
+			pid: {
+				description: "The process ID."
+				required:    true
+				examples: ["4242"]
+			}
+		}
+		_process: {
+			tags: _process_tags
+		}
+		_process_counter: _process & {type: "counter"}
+		_process_gauge:   _process & {type: "gauge"}
+		_process_linux: {relevant_when: "OS is Linux"}
+		_udp_counter: {
+			type:          "counter"
+			relevant_when: "OS is Linux"
+			tags:          _host_metrics_tags & {
+				collector: examples: ["udp"]
+			}
+		}
 	}
 }
@@ -91,6 +91,88 @@ components: sources: http: {
 			}
 		}
 		auth: configuration._http_basic_auth
+		cors: configuration._http_cors
+		openapi: {
+			common:      false
+			description: "Publish an [OpenAPI][urls.openapi] document describing the accepted requests on `GET /openapi.json`, and validate the received events against the declared fields."
+			required:    false
+			type: object: options: {
+				description: {
+					common:      false
+					description: "The description of the API."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["The checkout events of the shop."]
+					}
+				}
+				fields: {
+					common:      true
+					description: "The fields of the events, by name. Fields not declared are accepted as well."
+					required:    false
+					type: object: {
+						examples: [{user_id: {type: "string", required: true}}]
+						options: {
+							"*": {
+								description: "A field of the events."
+								required:    true
+								type: object: options: {
+									description: {
+										common:      false
+										description: "The description of the field."
+										required:    false
+										type: string: {
+											default: null
+											examples: ["The ID of the user placing the order."]
+										}
+									}
+									required: {
+										common:      true
+										description: "Whether the events must hold the field."
+										required:    false
+										type: bool: default: false
+									}
+									type: {
+										common:      true
+										description: "The type of the values of the field. Values of any type are accepted if unset."
+										required:    false
+										type: string: {
+											default: null
+											enum: {
+												string:  "A string."
+												integer: "An integer."
+												number:  "An integer or a floating point number."
+												boolean: "A boolean."
+												object:  "A JSON object."
+												array:   "A JSON array."
+											}
+										}
+									}
+								}
+							}
+						}
+					}
+				}
+				title: {
+					common:      true
+					description: "The title of the API."
+					required:    false
+					type: string: default: "Vector HTTP source"
+				}
+				validate: {
+					common:      false
+					description: "Reject the requests holding events that miss a required field, or hold a field of another type, with a `400` response. Only applies to the `json` and `ndjson` encodings."
+					required:    false
+					type: bool: default: true
+				}
+				version: {
+					common:      false
+					description: "The version of the API."
+					required:    false
+					type: string: default: "1.0.0"
+				}
+			}
+		}
 		query_parameters: {
 			common:      false
 			description: "A list of URL query parameters to include in the log event. These will override any values included in the body with conflicting names."
@@ -100,6 +182,15 @@ components: sources: http: {
 				items: type: string: examples: ["application", "source"]
 			}
 		}
+		trace_context_key: {
+			common:      false
+			description: "The field the [W3C trace context][urls.w3c_trace_context] of the request is stored in, as a map of its `traceparent` and `tracestate` headers. Requests without a valid `traceparent` header don't set the field."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["trace"]
+			}
+		}
 	}
 
 	output: logs: {
@@ -189,6 +280,33 @@ components: sources: http: {
 		},
 	]
 
+	how_it_works: {
+		openapi: {
+			title: "OpenAPI"
+			body: """
+				With the `openapi` option set, the source serves an [OpenAPI 3][urls.openapi]
+				document on `GET /openapi.json`, describing the encoding, the
+				declared fields, the headers and query parameters and the
+				authentication the source expects. Producers can hand it to their
+				tooling to generate clients or to check their payloads.
+
+				Unless `validate` is disabled, each event decoded from a request is
+				checked against the declared fields, and the whole request is
+				rejected with a `400` response naming the first offending event and
+				field if any of them is invalid.
+				"""
+		}
+		trace_context: {
+			title: "Trace context"
+			body: """
+				With the `trace_context_key` option set, the `traceparent` and
+				`tracestate` headers of each request are stored in that field of
+				all the events decoded from it, letting sinks with the same option
+				continue the trace of the producer downstream.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		http_bad_requests_total: components.sources.internal_metrics.output.metrics.http_bad_requests_total
 	}
@@ -0,0 +1,127 @@
+package metadata
+
+components: sources: internal_logs: {
+	title:       "Internal Logs"
+	description: "The internal logs source exposes all log and trace messages emitted by the running Vector instance."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator", "daemon", "sidecar"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: service: {
+				name:     "Vector instance"
+				thing:    "a \(name)"
+				url:      urls.vector_docs
+				versions: ">= 0.12.0"
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		notices: []
+		requirements: []
+		warnings: [
+			"""
+				Logs emitted by the components receiving the events of this source, such as errors of a sink, are
+				fed back into this source. Keep that in mind when routing these logs to a sink that may log on
+				every event it sends.
+				""",
+		]
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: "The key name added to each event representing the current host. This can also be globally set via the [global `host_key` option][docs.reference.global-options#host_key]."
+			required:    false
+			warnings: []
+			type: string: default: "host"
+		}
+	}
+
+	output: logs: line: {
+		description: "An individual log or trace message emitted by Vector."
+		fields: {
+			host: fields._local_host
+			message: {
+				description: "The textual message of the log."
+				required:    true
+				type: string: examples: ["Vector has started."]
+			}
+			timestamp: fields._current_timestamp
+			"metadata.kind": {
+				description: "The kind of the tracing record, always `event`."
+				required:    true
+				type: string: examples: ["event"]
+			}
+			"metadata.level": {
+				description: "The level of the log."
+				required:    true
+				type: string: enum: {
+					TRACE: "The most verbose level."
+					DEBUG: "Information useful when debugging Vector."
+					INFO:  "Regular operational information."
+					WARN:  "Unexpected situations Vector recovered from."
+					ERROR: "Errors Vector could not recover from."
+				}
+			}
+			"metadata.module_path": {
+				description: "The Rust module the log was emitted from."
+				required:    true
+				type: string: examples: ["vector::app"]
+			}
+			"metadata.target": {
+				description: "The target of the log, usually the module path."
+				required:    true
+				type: string: examples: ["vector::app"]
+			}
+			"*": {
+				common:      false
+				description: "Any other field of the log, such as the error of a failure."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["Connection refused."]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		lagging: {
+			title: "Lagging"
+			body: """
+				The logs are kept in a bounded in-memory buffer. If this source falls behind, for example
+				because of backpressure from its downstream components, the oldest logs are dropped and
+				counted in the `events_discarded_total` metric.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -141,6 +141,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		authentication_errors_total: {
+			description:       "The total number of requests rejected for failing authentication."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		buffer_events: {
+			description:       "The number of events in the buffer of this sink."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		checkpoint_write_errors_total: {
 			description:       "The total number of errors writing checkpoints."
 			type:              "counter"
@@ -179,6 +191,30 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		component_errors_total: {
+			description:       "The total number of errors logged by this component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		component_restarts_total: {
+			description:       "The total number of times this transform was restarted after panicking."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		connection_established_total: {
+			description:       "The total number of times a connection has been established."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		connection_failed_total: {
+			description:       "The total number of times a connection has failed to be established."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		connection_read_errors_total: {
 			description:       "The total number of errors reading datagram."
 			type:              "counter"
@@ -193,12 +229,24 @@ components: sources: internal_metrics: {
 				}
 			}
 		}
+		connection_shutdown_total: {
+			description:       "The total number of times a connection has been closed by the remote end."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		consumer_offset_updates_failed_total: {
 			description:       "The total number of failures to update a Kafka consumer offset."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		consumer_rebalances_total: {
+			description:       "The total number of times partitions were assigned to the consumer after a rebalance of its group."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		container_processed_events_total: {
 			description:       "The total number of container events processed."
 			type:              "counter"
@@ -241,12 +289,58 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		decompress_errors_total: {
+			description:       "The total number of requests that could not be decompressed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		duplicate_events_total: {
+			description:       "The total number of duplicate events redelivered by an upstream API that have been skipped."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		encode_errors_total: {
+			description:       "The total number of events that could not be encoded for their destination."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		events_discarded_total: {
 			description:       "The total number of events discarded by this component."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		events_expired_total: {
+			description:       "The total number of events dropped by a sink for being older than `max_event_age_secs`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		event_timestamp_parse_errors_total: {
+			description:       "The total number of events whose `timestamp` field couldn't be parsed with any of the configured formats."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		event_timestamp_skew_exceeded_total: {
+			description:       "The total number of events whose timestamp is further from the time of ingestion than the configured `timestamp_skew` thresholds."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				direction: _skew_direction
+			}
+		}
+		event_timestamp_skew_seconds: {
+			description:       "The difference between the time events are ingested by a source and their timestamp, when `timestamp_skew` is enabled."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				direction: _skew_direction
+			}
+		}
 		events_failed_total: {
 			description:       "The total number of failures to read a Kafka message."
 			type:              "counter"
@@ -261,6 +355,14 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		file_bytes_behind: {
+			description:       "The number of bytes written to a file that haven't been read yet. Not reported for compressed files."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				file: _file
+			}
+		}
 		file_delete_errors_total: {
 			description:       "The total number of failures to delete a file."
 			type:              "counter"
@@ -269,6 +371,12 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		file_rotation_errors_total: {
+			description:       "The total number of failures to rotate a file."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		file_watch_errors_total: {
 			description:       "The total number of errors encountered when watching files."
 			type:              "counter"
@@ -301,6 +409,12 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		files_rotated_total: {
+			description:       "The total number of files rotated."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		files_unwatched_total: {
 			description:       "The total number of times Vector has stopped watching a file."
 			type:              "counter"
@@ -353,6 +467,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		journald_entries_behind: {
+			description:       "The number of journal entries written after the last entry read. Only reported when the newest entry is from the same journal."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		logging_driver_errors_total: {
 			description: """
 				The total number of logging driver errors encountered caused by not using either
@@ -362,6 +482,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		mbean_read_errors_total: {
+			description:       "The total number of MBeans the Jolokia agent failed to read."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		memory_used_bytes: {
 			description:       "The total memory currently being used by Vector (in bytes)."
 			type:              "gauge"
@@ -418,6 +544,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		rate_limited_total: {
+			description:       "The total number of times the upstream API asked to back off."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		request_errors_total: {
 			description:       "The total number of requests errors for this component."
 			type:              "counter"
@@ -442,6 +574,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		rotated_file_delete_errors_total: {
+			description:       "The total number of failures to delete rotated files older than the retention."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		rotated_files_deleted_total: {
+			description:       "The total number of rotated files deleted for being older than the retention."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		sqs_message_delete_failed_total: {
 			description:       "The total number of failures to delete SQS messages."
 			type:              "counter"
@@ -609,6 +753,7 @@ components: sources: internal_metrics: {
 
 		// Helpful tag groupings
 		_component_tags: _internal_metrics_tags & {
+			component_id:   _component_id
 			component_kind: _component_kind
 			component_name: _component_name
 			component_type: _component_type
@@ -624,6 +769,11 @@ components: sources: internal_metrics: {
 			description: "Which collector this metric comes from."
 			required:    true
 		}
+		_component_id: {
+			description: "The Vector component ID."
+			required:    true
+			examples: ["file_source", "splunk_sink"]
+		}
 		_component_kind: {
 			description: "The Vector component kind."
 			required:    true
@@ -683,5 +833,9 @@ components: sources: internal_metrics: {
 			required:    true
 			default:     "vector"
 		}
+		_skew_direction: {
+			description: "Whether the event timestamp lies in the `past` or the `future` of the ingestion time."
+			required:    true
+		}
 	}
 }
@@ -0,0 +1,200 @@
+package metadata
+
+components: sources: jmx_metrics: {
+	title:       "JMX Metrics"
+	description: "[Java Management Extensions][urls.jmx] (JMX) expose the metrics of Java applications, such as Kafka, Cassandra or Tomcat, and of the JVM itself as the attributes of MBeans."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon", "sidecar"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: {
+					name:     "Jolokia agent"
+					thing:    "a \(name)"
+					url:      urls.jolokia
+					versions: ">= 1.0"
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Jolokia protocol"
+							url:   urls.jolokia_protocol
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"The [Jolokia][urls.jolokia] agent should be attached to the JVM, or deployed as a proxy to its JMX remote connector.",
+		]
+
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		endpoints: {
+			description: "The HTTP/HTTPS endpoints of the Jolokia agents."
+			required:    true
+			type: array: {
+				items: type: string: examples: ["http://localhost:8778/jolokia"]
+			}
+		}
+		scrape_interval_secs: {
+			description: "The interval between scrapes."
+			common:      true
+			required:    false
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			description: "The namespace of metrics. Disabled if empty."
+			common:      false
+			required:    false
+			type: string: default: "jmx"
+		}
+		rules: {
+			description: "The MBeans to read, and how to map their attributes to metrics."
+			required:    true
+			type: array: items: type: object: options: {
+				mbean: {
+					description: "The name of the MBean, or a pattern matching any number of MBeans."
+					required:    true
+					type: string: examples: ["java.lang:type=Memory", "kafka.server:type=BrokerTopicMetrics,name=*,topic=*"]
+				}
+				attributes: {
+					description: "The attributes to read. All attributes are read if empty."
+					common:      true
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: examples: ["HeapMemoryUsage", "Count"]
+					}
+				}
+				name: {
+					description: "The name of the metrics, rendered with the keys of the MBean name, `domain` and `attribute`. Characters other than letters, digits and `_` are replaced with `_`."
+					common:      true
+					required:    false
+					type: string: {
+						default: "{{ domain }}_{{ type }}_{{ attribute }}"
+						examples: ["kafka_server_{{ name }}_total"]
+						templateable: true
+					}
+				}
+				type: {
+					description: "The type of the metrics."
+					common:      true
+					required:    false
+					type: string: {
+						default: "gauge"
+						enum: {
+							gauge:   "Absolute values, such as memory usage."
+							counter: "Monotonically increasing values, such as the number of processed requests."
+						}
+					}
+				}
+				tags: {
+					description: "The tags of the metrics, rendered like the `name`. Tags using keys missing from the MBean name are omitted. By default, the keys of the MBean name not used in the `name` are the tags."
+					common:      false
+					required:    false
+					type: object: {
+						examples: [{topic: "{{ topic }}"}]
+						options: {}
+					}
+				}
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             true
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${HTTP_PASSWORD}"
+			username_example: "${HTTP_USERNAME}"
+		}}
+	}
+
+	how_it_works: {
+		jolokia: {
+			title: "Jolokia"
+			body: """
+				The MBeans of all rules are read in a single bulk request to the
+				[Jolokia protocol][urls.jolokia_protocol] endpoint of each agent.
+				MBeans or attributes the agent fails to read, for example because
+				they don't exist, are logged and skipped without failing the
+				other rules.
+				"""
+		}
+		attribute_mapping: {
+			title: "Attribute mapping"
+			body: """
+				Each numeric attribute becomes a metric. Booleans are reported as
+				`1` or `0`, and other values such as strings are skipped. The values
+				of composite attributes, such as `HeapMemoryUsage`, become one
+				metric each, with their key appended to the attribute, such as
+				`HeapMemoryUsage.used`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		collect_completed_total:      components.sources.internal_metrics.output.metrics.collect_completed_total
+		collect_duration_nanoseconds: components.sources.internal_metrics.output.metrics.collect_duration_nanoseconds
+		http_request_errors_total:    components.sources.internal_metrics.output.metrics.http_request_errors_total
+		mbean_read_errors_total:      components.sources.internal_metrics.output.metrics.mbean_read_errors_total
+		parse_errors_total:           components.sources.internal_metrics.output.metrics.parse_errors_total
+	}
+
+	output: metrics: {
+		up: {
+			description:       "If the Jolokia agent is up or not."
+			type:              "gauge"
+			default_namespace: "jmx"
+			tags: {
+				endpoint: {
+					description: "The Jolokia endpoint."
+					required:    true
+					examples: ["http://localhost:8778/jolokia"]
+				}
+				host: {
+					description: "The hostname of the Jolokia agent."
+					required:    true
+					examples: [_values.local_host]
+				}
+			}
+		}
+	}
+}
@@ -200,5 +200,6 @@ components: sources: journald: {
 	telemetry: metrics: {
 		invalid_record_total:       components.sources.internal_metrics.output.metrics.invalid_record_total
 		invalid_record_bytes_total: components.sources.internal_metrics.output.metrics.invalid_record_bytes_total
+		journald_entries_behind:    components.sources.internal_metrics.output.metrics.journald_entries_behind
 	}
 }
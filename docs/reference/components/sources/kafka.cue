@@ -178,8 +178,19 @@ components: sources: kafka: {
 			}
 		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
+		topic_refresh_interval_ms: {
+			common:      false
+			description: "How often the topic metadata is refreshed, which is when new topics matching a topic pattern are subscribed to. Defaults to the librdkafka default of 5 minutes."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [30000]
+				unit: "milliseconds"
+			}
+		}
 		topics: {
-			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`.\n"
+			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`, in which case newly created topics matching it are read from as soon as they are discovered, without reloading the config. Set the `topic_key` option to route the events of these topics downstream.\n"
 			required:    true
 			warnings: []
 			type: array: items: type: string: examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
@@ -0,0 +1,152 @@
+package metadata
+
+components: sources: kubernetes_events: {
+	title:       "Kubernetes Events"
+	description: "[Kubernetes Events](\(urls.kubernetes_events)) report what is happening inside a cluster, such as decisions made by the scheduler or why pods were evicted."
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: {
+					name:     "Kubernetes"
+					thing:    "a \(name) cluster"
+					url:      urls.kubernetes
+					versions: ">= 1.14"
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Kubernetes Events API"
+							url:   urls.kubernetes_events
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"Vector must run inside the cluster with a service account allowed to `watch` `events`.",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: "kubernetes"
+	}
+
+	configuration: {
+		api: {
+			common:      false
+			description: "Which of the Kubernetes Events APIs to watch."
+			required:    false
+			type: string: {
+				default: "core"
+				enum: {
+					core:   "The `v1` core API `Event` objects."
+					events: "The `events.k8s.io/v1beta1` API `Event` objects."
+				}
+			}
+		}
+		field_selector: {
+			common:      false
+			description: "Specifies the [field selector](\(urls.kubernetes_field_selector)) to filter the events with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["type!=Normal"]
+			}
+		}
+		include_previous_events: {
+			common:      false
+			description: "Emit the events that already existed when the source started. By default only the events that occurred after the start are emitted."
+			required:    false
+			type: bool: default: false
+		}
+		label_selector: {
+			common:      false
+			description: "Specifies the [label selector](\(urls.kubernetes_label_selector)) to filter the events with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["my_custom_label!=my_value"]
+			}
+		}
+		namespace: {
+			common:      true
+			description: "The namespace to watch the events in. All namespaces are watched when unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["default"]
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A Kubernetes event object."
+		fields: {
+			message: {
+				description: "The `message` (or `note` for `events.k8s.io`) of the event."
+				required:    true
+				type: string: examples: ["Successfully assigned default/nginx to node-1"]
+			}
+			timestamp: {
+				description: "The time the event was most recently observed."
+				required:    true
+				type: timestamp: {}
+			}
+			"*": {
+				common:      false
+				description: "Any field of the Kubernetes `Event` object, such as `reason`, `type`, `involvedObject` or `metadata`."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["Scheduled", "Warning"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		deduplication: {
+			title: "De-duplication"
+			body: """
+				The watch API redelivers objects when a watch has to be restarted.
+				Vector remembers the `resourceVersion` of the most recently emitted
+				version of each event and skips redelivered objects, while updates
+				to an event (for example an increased `count`) are emitted again.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		duplicate_events_total: components.sources.internal_metrics.output.metrics.duplicate_events_total
+		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
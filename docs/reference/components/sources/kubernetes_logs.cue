@@ -305,6 +305,20 @@ components: sources: kubernetes_logs: {
 					"""
 		}
 
+		log_formats: {
+			title: "Log formats"
+			body:  """
+					Vector reads both the `json` format of the Docker log driver and the
+					CRI format of containerd and CRI-O, detecting the format of each line,
+					so a node may run containers of both kinds of runtimes.
+
+					Container runtimes split long lines, at 16KiB, into partial lines,
+					which Vector merges back into a single event, unless the
+					`auto_partial_merge` option is disabled. The partial lines of the
+					`stdout` and `stderr` streams of a container are merged separately.
+					"""
+		}
+
 		filtering: {
 			title: "Filtering"
 			body: """
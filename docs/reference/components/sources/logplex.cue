@@ -77,8 +77,35 @@ components: sources: logplex: {
 	}
 
 	configuration: {
-		address:          sources.http.configuration.address
-		auth:             sources.http.configuration.auth
+		address: sources.http.configuration.address
+		app_name_query_parameter: {
+			common:      false
+			description: "The query parameter of the drain URL naming the Heroku app the drain was added to. Its value is added to the events as `heroku_app`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["app"]
+			}
+		}
+		auth: sources.http.configuration.auth
+		drain_tokens: {
+			common:      false
+			description: "The tokens of the drains to accept the requests of, as sent in the `Logplex-Drain-Token` header. The requests of other drains are rejected with a `401` response. The requests of all drains are accepted if empty."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["d.fc6b856b-3332-4546-93de-7d0ee272c3bd"]
+			}
+		}
+		dyno_query_parameter: {
+			common:      false
+			description: "The query parameter of the drain URL naming the dyno, or dyno type, the drain was set up for. Its value is added to the events as `heroku_dyno`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["dyno"]
+			}
+		}
 		query_parameters: sources.http.configuration.query_parameters
 	}
 
@@ -90,6 +117,26 @@ components: sources: logplex: {
 				required:    true
 				type: string: examples: ["erlang"]
 			}
+			heroku_app: {
+				description:   "The Heroku app named by the `app_name_query_parameter` of the drain URL."
+				relevant_when: "app_name_query_parameter is set"
+				required:      false
+				common:        false
+				type: string: {
+					default: null
+					examples: ["my-shop"]
+				}
+			}
+			heroku_dyno: {
+				description:   "The dyno named by the `dyno_query_parameter` of the drain URL."
+				relevant_when: "dyno_query_parameter is set"
+				required:      false
+				common:        false
+				type: string: {
+					default: null
+					examples: ["web"]
+				}
+			}
 			host: fields._local_host
 			message: {
 				description: "The message field, containing the plain text message."
@@ -105,7 +152,26 @@ components: sources: logplex: {
 		}
 	}
 
+	how_it_works: {
+		multiple_apps: {
+			title: "Serving multiple apps"
+			body: """
+				A single source can receive the logs of many Heroku apps. To tell
+				them apart, add the name of the app to the query of the drain URL,
+				and set `app_name_query_parameter` to the name of that parameter:
+
+				```bash
+				heroku drains:add "https://<user>:<pass>@<address>/events?app=<app>" -a <app>
+				```
+
+				To only accept the requests of known drains, list their tokens,
+				as shown by `heroku drains -a <app> --json`, in `drain_tokens`.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
+		http_bad_requests_total:   components.sources.internal_metrics.output.metrics.http_bad_requests_total
 		request_read_errors_total: components.sources.internal_metrics.output.metrics.request_read_errors_total
 		requests_received_total:   components.sources.internal_metrics.output.metrics.requests_received_total
 	}
@@ -0,0 +1,203 @@
+package metadata
+
+components: sources: loki: {
+	title:       "Loki"
+	description: "[Loki][urls.loki] is a horizontally-scalable, highly-available, multi-tenant log aggregation system inspired by Prometheus."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: {
+					name:     "Loki"
+					thing:    "a \(name) database"
+					url:      urls.loki
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Loki HTTP API"
+							url:   urls.loki_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${LOKI_PASSWORD}"
+			username_example: "${LOKI_USERNAME}"
+		}}
+		delay_for_secs: {
+			common:      false
+			description: "How long Loki waits before sending entries of the tail, so that entries arriving slightly out of order are sent in order. Only used in `tail` mode."
+			required:    false
+			type: uint: {
+				default: 0
+				unit:    "seconds"
+			}
+		}
+		end: {
+			common:      false
+			description: "The end of the time range to query, exclusive. Defaults to the time Vector starts. Only used in `query` mode."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["2021-03-01T00:00:00Z"]
+			}
+		}
+		endpoint: {
+			description: "The base URL of the Loki instance."
+			required:    true
+			type: string: examples: ["http://localhost:3100"]
+		}
+		labels_key: {
+			common:      false
+			description: "The field the label set of the stream of each entry is stored in."
+			required:    false
+			type: string: {
+				default: "labels"
+			}
+		}
+		limit: {
+			common:      false
+			description: "The maximum number of entries per request in `query` mode, and per batch in `tail` mode."
+			required:    false
+			type: uint: {
+				default: 1000
+				unit:    null
+			}
+		}
+		mode: {
+			common:      true
+			description: "How the entries are read."
+			required:    false
+			type: string: {
+				default: "tail"
+				enum: {
+					tail:  "Stream the entries matching the query as Loki receives them, through the tail WebSocket endpoint."
+					query: "Page through the entries matching the query between `start` and `end` through the `query_range` endpoint, then stop. Used to backfill or migrate data."
+				}
+			}
+		}
+		query: {
+			description: "The [LogQL][urls.logql] log query selecting the entries. Metric queries are not supported."
+			required:    true
+			type: string: examples: ["{job=\"varlogs\"}", "{app=\"api\"} |= \"error\""]
+		}
+		start: {
+			common:      false
+			description: "The time to read entries from, inclusive. Defaults to one hour before `end` in `query` mode and to the time Vector starts in `tail` mode."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["2021-02-01T00:00:00Z"]
+			}
+		}
+		tenant_id: {
+			common:      false
+			description: "The tenant to read the entries of, sent as the `X-Scope-OrgID` header. You can read more about tenant id's [here][urls.loki_multi_tenancy]."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["some_tenant_id"]
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+	}
+
+	output: logs: entry: {
+		description: "An entry of a Loki stream."
+		fields: {
+			labels: {
+				description: "The label set of the stream of the entry, in the field set by `labels_key`."
+				required:    true
+				type: object: {
+					examples: [{"job": "varlogs", "filename": "/var/log/syslog"}]
+					options: {}
+				}
+			}
+			message: {
+				description: "The log line of the entry."
+				required:    true
+				type: string: examples: ["Started Session 1 of user root."]
+			}
+			timestamp: {
+				description: "The timestamp of the entry, with nanosecond precision."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		migrating: {
+			title: "Migrating and mirroring"
+			body: """
+				Entries keep their line, timestamp and labels, so a `query` mode
+				source feeding a `loki` sink whose `labels` are templated from
+				the `labels` field copies data between Loki instances, while a
+				`tail` mode source mirrors the entries as they arrive.
+				"""
+		}
+		resuming: {
+			title: "Resuming"
+			body: """
+				Vector remembers the timestamp of the last entry it read. It
+				starts each page of a query, and reconnects the tail after the
+				connection drops, from that timestamp, skipping the entries at
+				it which it already emitted. The position is not persisted
+				across restarts. When Loki can't keep up with the tail it drops
+				entries, which Vector reports in the `events_discarded_total`
+				metric.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total:    components.sources.internal_metrics.output.metrics.events_discarded_total
+		http_request_errors_total: components.sources.internal_metrics.output.metrics.http_request_errors_total
+		processed_bytes_total:     components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:    components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
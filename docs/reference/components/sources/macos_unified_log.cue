@@ -0,0 +1,131 @@
+package metadata
+
+components: sources: macos_unified_log: {
+	title:       "macOS Unified Log"
+	description: "The [macOS unified logging system](\(urls.macos_unified_log)) provides a single, efficient API for capturing messaging across all levels of the system, replacing ASL and the macOS syslog."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: {
+					name:     "macOS Unified Log"
+					thing:    "the \(name)"
+					url:      urls.macos_unified_log
+					versions: ">= 10.12"
+				}
+
+				interface: binary: {
+					name: "log"
+					permissions: unix: group: "admin"
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  false
+			"aarch64-unknown-linux-musl": false
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      false
+			"x86_64-unknown-linux-gnu":   false
+			"x86_64-unknown-linux-musl":  false
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		level: {
+			common:      true
+			description: "The minimum level of messages to include, passed to `log stream --level`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "default"
+				enum: {
+					default: "Include default, error and fault messages."
+					info:    "Additionally include info messages."
+					debug:   "Additionally include info and debug messages."
+				}
+			}
+		}
+		log_path: {
+			common:      false
+			description: "The full path of the `log` executable."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/usr/bin/log"
+				examples: ["/usr/local/bin/log"]
+			}
+		}
+		predicate: {
+			common:      true
+			description: "An `NSPredicate` filter expression, passed to `log stream --predicate`, that limits which messages are collected."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["subsystem == \"com.apple.example\"", "process == \"sshd\" AND messageType == error"]
+			}
+		}
+	}
+
+	output: logs: {
+		event: {
+			description: "A macOS unified log event"
+			fields: {
+				message: {
+					description: "The `eventMessage` of the log record."
+					required:    true
+					type: string: examples: ["Connection established"]
+				}
+				timestamp: fields._current_timestamp
+				"*": {
+					common:      false
+					description: "Any field of the `log stream --style ndjson` record, such as `subsystem`, `category`, `messageType` or `processImagePath`."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["com.apple.example", "/usr/libexec/exampled"]
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		communication_strategy: {
+			title: "Communication Strategy"
+			body: """
+				Vector reads the unified log by spawning `log stream --style ndjson`
+				as a [subprocess][urls.rust_subprocess] and parsing each line it
+				outputs. The process is restarted if it exits unexpectedly.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		invalid_record_total:       components.sources.internal_metrics.output.metrics.invalid_record_total
+		invalid_record_bytes_total: components.sources.internal_metrics.output.metrics.invalid_record_bytes_total
+		processed_bytes_total:      components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:     components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -51,6 +51,7 @@ components: sources: mongodb_metrics: {
 
 		requirements: [
 			"User from endpoint should have enough privileges for running [serverStatus][urls.mongodb_command_server_status] command",
+			"User from endpoint should have enough privileges for running the [dbStats][urls.mongodb_command_db_stats] command on every database, and the [replSetGetStatus][urls.mongodb_command_repl_set_get_status] command on replica set members",
 		]
 
 		warnings: []
@@ -87,6 +88,15 @@ components: sources: mongodb_metrics: {
 	}
 
 	how_it_works: {
+		db_stats: {
+			title: "MongoDB `dbStats` command"
+			body: """
+				The [dbStats][urls.mongodb_command_db_stats] command is run for
+				every database to report its size, and on replica set members the
+				[replSetGetStatus][urls.mongodb_command_repl_set_get_status]
+				command to report how far the members are behind the primary.
+				"""
+		}
 		mod_status: {
 			title: "MongoDB `serverStatus` command"
 			body: """
@@ -109,6 +119,13 @@ components: sources: mongodb_metrics: {
 
 	output: metrics: {
 		// Default MongoDB tags
+		_db_stats_tags: {
+			database: {
+				description: "The name of the database."
+				required:    true
+				examples: ["admin"]
+			}
+		}
 		_mongodb_metrics_tags: {
 			endpoint: {
 				description: "The absolute path of the originating file."
@@ -152,6 +169,42 @@ components: sources: mongodb_metrics: {
 				}
 			}
 		}
+		db_stats_collections: {
+			description:       "The number of collections in the database."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & _db_stats_tags
+		}
+		db_stats_data_size_bytes: {
+			description:       "The size of the uncompressed data held in the database."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & _db_stats_tags
+		}
+		db_stats_index_size_bytes: {
+			description:       "The size of all indexes of the database."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & _db_stats_tags
+		}
+		db_stats_indexes: {
+			description:       "The number of indexes across all collections of the database."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & _db_stats_tags
+		}
+		db_stats_objects: {
+			description:       "The number of documents in the database."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & _db_stats_tags
+		}
+		db_stats_storage_size_bytes: {
+			description:       "The storage allocated for documents of the database."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & _db_stats_tags
+		}
 		extra_info_heap_usage_bytes: {
 			description:       "The total size in bytes of heap space used by the database process."
 			relevant_when:     "Unix/Linux"
@@ -457,6 +510,29 @@ components: sources: mongodb_metrics: {
 				}
 			}
 		}
+		mongod_repl_lag_seconds: {
+			description:       "The time the member is behind the primary in applying operations."
+			relevant_when:     "Node is a `replica set` member and the set has a primary."
+			type:              "gauge"
+			default_namespace: "mongodb"
+			tags:              _mongodb_metrics_tags & {
+				set: {
+					description: "The name of the replica set."
+					required:    true
+					examples: ["vector"]
+				}
+				member: {
+					description: "The address of the member."
+					required:    true
+					examples: ["mongo1:27017"]
+				}
+				state: {
+					description: "The state of the member."
+					required:    true
+					examples: ["PRIMARY", "SECONDARY"]
+				}
+			}
+		}
 		mongod_storage_engine: {
 			description:       "The name of the current storage engine."
 			type:              "gauge"
@@ -50,7 +50,7 @@ components: sources: nginx_metrics: {
 		}
 
 		requirements: [
-			"Module `ngx_http_stub_status_module` should be enabled.",
+			"Module `ngx_http_stub_status_module` should be enabled, or the NGINX Plus API when `api` is `plus`.",
 		]
 
 		warnings: []
@@ -62,6 +62,18 @@ components: sources: nginx_metrics: {
 	}
 
 	configuration: {
+		api: {
+			description: "The status module of the endpoints."
+			common:      false
+			required:    false
+			type: string: {
+				default: "stub_status"
+				enum: {
+					stub_status: "The text page of the `ngx_http_stub_status_module` module."
+					plus:        "The JSON [NGINX Plus API][urls.nginx_plus_api], with the endpoints being its versioned base path such as `/api/6`."
+				}
+			}
+		}
 		endpoints: {
 			description: "HTTP/HTTPS endpoint to Nginx server with enabled `ngx_http_stub_status_module` module."
 			required:    true
@@ -105,6 +117,16 @@ components: sources: nginx_metrics: {
 				information is a simple web page with text data.
 				"""
 		}
+		plus_api: {
+			title: "NGINX Plus API"
+			body: """
+				With `api` set to `plus`, the `/connections` and `/http/requests`
+				endpoints of the [NGINX Plus API][urls.nginx_plus_api] are read
+				instead. These don't report the `reading`, `writing` and
+				`handled` counts, but report the dropped connections and the
+				requests in progress.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -171,6 +193,18 @@ components: sources: nginx_metrics: {
 			default_namespace: "nginx"
 			tags:              _nginx_metrics_tags
 		}
+		connections_dropped_total: {
+			description:       "The total number of dropped client connections. Only reported by the NGINX Plus API."
+			type:              "counter"
+			default_namespace: "nginx"
+			tags:              _nginx_metrics_tags
+		}
+		http_requests_current: {
+			description:       "The current number of client requests. Only reported by the NGINX Plus API."
+			type:              "gauge"
+			default_namespace: "nginx"
+			tags:              _nginx_metrics_tags
+		}
 		connections_waiting: {
 			description:       "The current number of idle client connections waiting for a request."
 			type:              "gauge"
@@ -0,0 +1,153 @@
+package metadata
+
+components: sources: okta_logs: {
+	title:       "Okta Logs"
+	description: "[Okta][urls.okta] is an identity and access management service. Its [System Log][urls.okta_system_log_api] records the audit events of an Okta organization."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: {
+					name:     "Okta"
+					thing:    "an \(name) organization"
+					url:      urls.okta
+					versions: null
+				}
+
+				interface: {
+					socket: {
+						api: {
+							title: "Okta System Log API"
+							url:   urls.okta_system_log_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: [
+			"An Okta API token of an administrator with read access to the System Log.",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the position in the System Log between restarts. Defaults to the global `data_dir` option."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+			}
+		}
+		endpoint: {
+			description: "The URL of the Okta organization."
+			required:    true
+			type: string: examples: ["https://example.okta.com"]
+		}
+		filter: {
+			common:      false
+			description: "An [Okta filter expression](\(urls.okta_system_log_api)) selecting the events to pull."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["eventType eq \"user.session.start\""]
+			}
+		}
+		interval_secs: {
+			common:      true
+			description: "The interval between polls."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             false
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		token: {
+			description: "The Okta API token."
+			required:    true
+			type: string: examples: ["${OKTA_API_TOKEN}", "00QCjAl4MlV-WPXM...0HmjFx-vbGua"]
+		}
+	}
+
+	output: logs: event: {
+		description: "An Okta System Log event."
+		fields: {
+			message: {
+				description: "The `displayMessage` of the event."
+				required:    true
+				type: string: examples: ["User login to Okta"]
+			}
+			timestamp: {
+				description: "The time the event was `published`."
+				required:    true
+				type: timestamp: {}
+			}
+			"*": {
+				common:      false
+				description: "Any field of the System Log event, such as `eventType`, `actor` or `outcome`."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["user.session.start"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				Vector follows the `next` link returned by the System Log API and
+				stores the most recent link in its data directory, so that it
+				resumes where it stopped after a restart. When the API asks
+				Vector to slow down, it waits until the rate limit resets.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		checkpoint_write_errors_total: components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		http_request_errors_total:     components.sources.internal_metrics.output.metrics.http_request_errors_total
+		processed_bytes_total:         components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:        components.sources.internal_metrics.output.metrics.processed_events_total
+		rate_limited_total:            components.sources.internal_metrics.output.metrics.rate_limited_total
+	}
+}
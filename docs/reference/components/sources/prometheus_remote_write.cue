@@ -69,6 +69,16 @@ components: sources: prometheus_remote_write: {
 			type: string: examples: ["0.0.0.0:9090"]
 		}
 		auth: configuration._http_basic_auth
+		path: {
+			common:      false
+			description: "The URL path remote write requests are accepted on."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "/"
+				examples: ["/api/v1/write"]
+			}
+		}
 	}
 
 	output: metrics: {
@@ -66,13 +66,144 @@ components: sources: prometheus_scrape: {
 
 	configuration: {
 		endpoints: {
-			description: "Endpoints to scrape metrics from."
-			required:    true
+			description: "Endpoints to scrape metrics from. Required unless `kubernetes` is set, in which case these are scraped alongside the discovered targets."
+			required:    false
 			warnings: ["You must explicitly add the path to your endpoints. Vector will _not_ automatically add `/metics`."]
 			type: array: {
+				default: []
 				items: type: string: examples: ["http://localhost:9090/metrics"]
 			}
 		}
+		kubernetes: {
+			common:      false
+			description: "Discovers the targets to scrape from the Kubernetes API. Vector must run inside the cluster."
+			required:    false
+			warnings: []
+			type: object: options: {
+				annotations: {
+					common:      true
+					description: "Only scrape the targets annotated with `prometheus.io/scrape: \"true\"`, and honor the `prometheus.io/port`, `prometheus.io/path` and `prometheus.io/scheme` annotations."
+					required:    false
+					warnings: []
+					type: bool: default: true
+				}
+				field_selector: {
+					common:      false
+					description: "Specifies the [field selector](\(urls.kubernetes_field_selector)) to filter the discovered objects with."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["status.phase=Running"]
+					}
+				}
+				label_selector: {
+					common:      false
+					description: "Specifies the [label selector](\(urls.kubernetes_label_selector)) to filter the discovered objects with."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["app.kubernetes.io/part-of=shop"]
+					}
+				}
+				namespace: {
+					common:      false
+					description: "The namespace to discover the targets in. All namespaces are watched when unset."
+					required:    false
+					warnings: []
+					type: string: {
+						default: null
+						examples: ["default"]
+					}
+				}
+				relabel: {
+					common:      false
+					description: "Rules applied to the labels of every discovered target, in order, like the [relabeling](\(urls.prometheus_relabel_config)) of Prometheus."
+					required:    false
+					warnings: []
+					type: array: {
+						default: []
+						items: type: object: options: {
+							action: {
+								common:      true
+								description: "The action to perform."
+								required:    false
+								warnings: []
+								type: string: {
+									default: "replace"
+									enum: {
+										drop:      "Drop the targets the regex matches."
+										keep:      "Drop the targets the regex doesn't match."
+										labeldrop: "Remove the labels whose names match the regex."
+										labelkeep: "Remove the labels whose names don't match the regex."
+										labelmap:  "Copy the labels whose names match the regex to the label named by `replacement`."
+										replace:   "Set `target_label` to `replacement` if the regex matches."
+									}
+								}
+							}
+							regex: {
+								common:      true
+								description: "The regex matched against the joined `source_labels`, or the label names. It is anchored at both ends."
+								required:    false
+								warnings: []
+								type: string: {
+									default: "(.*)"
+									examples: ["kube-.*", "__meta_kubernetes_pod_label_(.+)"]
+								}
+							}
+							replacement: {
+								common:      false
+								description: "The value written by `replace` and the label name written by `labelmap`. Capture groups are referenced as `$1`."
+								required:    false
+								warnings: []
+								type: string: default: "$1"
+							}
+							separator: {
+								common:      false
+								description: "The separator placed between the values of the `source_labels`."
+								required:    false
+								warnings: []
+								type: string: default: ";"
+							}
+							source_labels: {
+								common:      true
+								description: "The labels whose values are joined and matched against the regex."
+								required:    false
+								warnings: []
+								type: array: {
+									default: []
+									items: type: string: examples: ["__meta_kubernetes_namespace"]
+								}
+							}
+							target_label: {
+								common:      true
+								description: "The label written by the `replace` action."
+								required:    false
+								warnings: []
+								type: string: {
+									default: null
+									examples: ["pod"]
+								}
+							}
+						}
+					}
+				}
+				role: {
+					common:      true
+					description: "The Kubernetes objects the targets are discovered from."
+					required:    false
+					warnings: []
+					type: string: {
+						default: "pod"
+						enum: {
+							endpoints: "Every ready address and port of the `Endpoints` objects is a target."
+							pod:       "Every running pod is a target, on its first declared container port."
+						}
+					}
+				}
+			}
+		}
 		scrape_interval_secs: {
 			common:      true
 			description: "The interval between scrapes, in seconds."
@@ -89,6 +220,29 @@ components: sources: prometheus_scrape: {
 		}}
 	}
 
+	how_it_works: {
+		kubernetes_discovery: {
+			title: "Kubernetes Service Discovery"
+			body: """
+				With the `kubernetes` option, Vector watches the pods or endpoints
+				of the cluster and scrapes every target they describe. Each target
+				starts with the `__address__`, `__scheme__` and `__metrics_path__`
+				labels, and `__meta_kubernetes_*` labels named like their
+				Prometheus counterparts, such as `__meta_kubernetes_namespace`,
+				`__meta_kubernetes_pod_name`, `__meta_kubernetes_pod_label_<name>`
+				and `__meta_kubernetes_pod_annotation_<name>`, where the name has
+				every character other than letters and digits replaced with `_`.
+
+				The `prometheus.io/*` annotations are applied first, then the
+				`relabel` rules. The labels left that don't start with `__` are
+				added as tags to the scraped metrics, along with an `instance` tag
+				holding the address of the target. Tags already present on a
+				scraped metric are kept. For the `endpoints` role the annotations
+				are read from the `Endpoints` object itself.
+				"""
+		}
+	}
+
 	output: metrics: {
 		counter:   output._passthrough_counter
 		gauge:     output._passthrough_gauge
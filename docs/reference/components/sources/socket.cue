@@ -117,6 +117,26 @@ components: sources: socket: {
 				examples: ["/path/to/socket"]
 			}
 		}
+		proxy_protocol: {
+			common:      false
+			description: "Requires connections to start with a [PROXY protocol](\(urls.haproxy_proxy_protocol)) header, version 1 or 2, as sent by load balancers such as HAProxy and AWS NLB. The address of the original client is used as the host of the events, and the addresses of the original connection are added as the `client_metadata.source_ip`, `client_metadata.source_port`, `client_metadata.destination_ip` and `client_metadata.destination_port` fields. Connections without a valid header are closed."
+			groups: ["tcp"]
+			required: false
+			warnings: []
+			type: bool: default: false
+		}
+		receive_buffer_bytes: {
+			common:        false
+			description:   "The size of the receive buffer of the socket, which holds the datagrams not read yet. Defaults to the system default, which may be too small for bursts of messages."
+			groups: ["unix"]
+			relevant_when: "mode == \"unix_datagram\""
+			required:      false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    "bytes"
+			}
+		}
 		shutdown_timeout_secs: {
 			common:      false
 			description: "The timeout before a connection is forcefully closed during shutdown."
@@ -159,6 +179,23 @@ components: sources: socket: {
 		},
 	]
 
+	how_it_works: {
+		client_certificates: {
+			title: "Client Certificates"
+			body: """
+				When connections over TCP are required to present a certificate, by
+				enabling `tls.verify_certificate`, the details of the certificate are
+				added to the events of the connection, so that transforms can authorize
+				or route them by their sender: the `client_metadata.certificate.subject`
+				and `client_metadata.certificate.issuer` fields hold the names, such as
+				`CN=localhost`, `client_metadata.certificate.subject_alt_names` holds the
+				alternative names, such as `DNS:example.com`, and
+				`client_metadata.certificate.fingerprint_sha256` holds the hex encoded
+				SHA-256 fingerprint of the certificate.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		connection_errors_total: components.sources.internal_metrics.output.metrics.connection_errors_total
 	}
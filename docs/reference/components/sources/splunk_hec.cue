@@ -66,6 +66,54 @@ components: sources: splunk_hec: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Indexer acknowledgement options. When enabled, the responses to requests contain an `ackId` which clients query the status of on the `/services/collector/ack` endpoint. A request is acknowledged once all of its events have been processed by the sinks, or written to a disk buffer, and never if the request failed. Requests must set the `X-Splunk-Request-Channel` header."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					enabled: {
+						common:      true
+						description: "Whether to enable indexer acknowledgements."
+						required:    false
+						warnings: []
+						type: bool: default: false
+					}
+					max_channels: {
+						common:      false
+						description: "The maximum number of channels acknowledgements are kept for. The least recently used channel is forgotten beyond that, along with its acknowledgements."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1000
+							unit:    null
+						}
+					}
+					max_idle_secs: {
+						common:      false
+						description: "Channels not used, and acknowledgements not queried, for this long are forgotten. Forgotten acknowledgements are reported as not acknowledged."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+					max_pending_acks_per_channel: {
+						common:      false
+						description: "The maximum number of acknowledgements kept per channel until they are queried. The oldest ones are forgotten beyond that, and reported as not acknowledged."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1000
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		address: {
 			common:      true
 			description: "The address to accept connections on."
@@ -0,0 +1,108 @@
+package metadata
+
+components: sources: spool: {
+	title:       "Spool"
+	description: "Forwards the events spooled to a local directory by a [`spool` sink][docs.sinks.spool], deleting each segment once its events were processed by the sinks."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: {
+					name:     "Vector"
+					thing:    "a \(name) `spool` sink"
+					url:      urls.vector_sink
+					versions: null
+				}
+
+				interface: file_system: {
+					directory: "/var/lib/vector/spool"
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		path: {
+			description: "The directory the `spool` sink spools the segments to."
+			required:    true
+			warnings: []
+			type: string: examples: ["/var/lib/vector/spool"]
+		}
+		poll_interval_secs: {
+			common:      false
+			description: "How often the directory is checked for closed segments."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A spooled Vector event"
+		fields: {
+			"*": {
+				description: "The `spool` source forwards the events as the `spool` sink received them, without modifying or adding fields."
+				required:    true
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		delivery: {
+			title: "Delivery"
+			body: """
+				Segments are forwarded oldest first, one at a time, and deleted
+				once all their log events were processed, that is acknowledged
+				by all the sinks receiving them, written to their disk
+				buffers, or dropped on the way, so a segment is only deleted
+				once the sinks delivered its events or gave up on them. Metric events
+				aren't tracked, so segments only holding metrics are deleted
+				once they are sent. A segment being forwarded when Vector stops
+				is kept, and forwarded again in full once it starts, so its
+				events may be delivered more than once.
+				Segments whose end can't be decoded are forwarded up to the
+				first invalid event.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processed_bytes_total:        components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:       components.sources.internal_metrics.output.metrics.processed_events_total
+		processing_errors_total:      components.sources.internal_metrics.output.metrics.processing_errors_total
+		protobuf_decode_errors_total: components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
+	}
+}
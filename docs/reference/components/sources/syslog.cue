@@ -50,7 +50,31 @@ components: sources: syslog: {
 		platform_name: null
 	}
 
-	configuration: sources.socket.configuration
+	configuration: sources.socket.configuration & {
+		now_source: {
+			common:      false
+			description: "Where the timestamps of events without a timestamp in their message come from."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "system"
+				enum: {
+					system: "The clock of the host Vector runs on, when the event is received."
+					none:   "The `timestamp` field of the events is left unset."
+				}
+			}
+		}
+		timezone: {
+			common:      false
+			description: "The time zone of timestamps without zone information, such as those of [RFC 3164][urls.syslog_3164] messages. This is either `local`, for the time zone of the host Vector runs on, or a [TZ database name][urls.iana_time_zone_format], such as `Europe/Berlin`, see the [list of names][urls.iana_time_zones]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "local"
+				examples: ["local", "America/New_York", "Europe/Berlin"]
+			}
+		}
+	}
 
 	output: logs: line: {
 		description: "An individual Syslog event"
@@ -122,9 +146,12 @@ components: sources: syslog: {
 				}
 			}
 			"*": {
-				description: "In addition to the defined fields, any Syslog 5424 structured fields are parsed and inserted as root level fields."
+				description: "In addition to the defined fields, any Syslog 5424 structured data elements are inserted as root level maps keyed by their SD-ID. See [Structured Data](#structured-data)."
 				required:    true
-				type: string: examples: ["hello world"]
+				type: object: {
+					examples: [{"exampleSDID@32473": {"iut": "3"}}]
+					options: {}
+				}
 			}
 		}
 	}
@@ -157,15 +184,32 @@ components: sources: syslog: {
 				appname:     _app_name
 				procid:      _procid
 				msgid:       _msgid
-				iut:         _iut
-				eventSource: _event_source
-				eventID:     _event_id
-				message:     _message
+				"exampleSDID@32473": {
+					iut:         _iut
+					eventSource: _event_source
+					eventID:     _event_id
+				}
+				message: _message
 			}
 		},
 	]
 
 	how_it_works: {
+		client_certificates: {
+			title: "Client Certificates"
+			body: """
+				When connections over TCP are required to present a certificate, by
+				enabling `tls.verify_certificate`, the details of the certificate are
+				added to the events of the connection, so that transforms can authorize
+				or route them by their sender: the `client_metadata.certificate.subject`
+				and `client_metadata.certificate.issuer` fields hold the names, such as
+				`CN=localhost`, `client_metadata.certificate.subject_alt_names` holds the
+				alternative names, such as `DNS:example.com`, and
+				`client_metadata.certificate.fingerprint_sha256` holds the hex encoded
+				SHA-256 fingerprint of the certificate.
+				"""
+		}
+
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
@@ -173,6 +217,35 @@ components: sources: syslog: {
 				"""
 		}
 
+		time_zones: {
+			title: "Time Zones"
+			body:  """
+				[RFC 3164][urls.syslog_3164] timestamps, such as `Feb 13 20:07:26`, have
+				neither a year nor a time zone. They are read in the `timezone` of the
+				source, so appliances logging in the local time of their site can send to
+				a source of their own, and get the current year of that time zone, except
+				for timestamps in December read in January, which get the previous year.
+				Timestamps with a zone, such as those of [RFC 5424][urls.syslog_5424]
+				messages, are not affected.
+				"""
+		}
+
+		structured_data: {
+			title: "Structured Data"
+			body: """
+				Each [RFC 5424][urls.syslog_5424] structured data element is inserted as a
+				map named after its SD-ID, holding the parameters of the element. SD-IDs and
+				parameter names are used as they are, so the dotted private enterprise
+				number of an ID like `exampleSDID@32473.1` stays part of the field name.
+				Parameters repeated within one element, such as the `ip` parameter of the
+				`origin` element, are collected into an array. Elements without parameters
+				are omitted.
+
+				The [`syslog` sink][docs.sinks.syslog] writes these maps back as
+				structured data.
+				"""
+		}
+
 		parsing: {
 			title: "Parsing"
 			body:  """
@@ -71,9 +71,29 @@ components: sources: vector: {
 				examples: ["0.0.0.0:\(_port)", "systemd", "systemd#1"]
 			}
 		}
+		max_batch_bytes: {
+			common:      false
+			description: "The largest batch accepted once decompressed, larger ones are rejected without being decompressed further. Only applies to version 2."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 100000000
+				unit:    "bytes"
+			}
+		}
+		pre_shared_key: {
+			common:      false
+			description: "Requires the upstream `vector` sinks to authenticate with this key, which must match their `pre_shared_key`. The source authenticates itself to the sinks with the key as well. Only applies to version 2."
+			required:    false
+			warnings: ["The key is never sent, but the events are only kept confidential with `tls` enabled."]
+			type: string: {
+				default: null
+				examples: ["${VECTOR_PRE_SHARED_KEY}"]
+			}
+		}
 		shutdown_timeout_secs: {
 			common:      false
-			description: "The timeout before a connection is forcefully closed during shutdown."
+			description: "The timeout before a connection is forcefully closed during shutdown. Only applies to version 1."
 			required:    false
 			warnings: []
 			type: uint: {
@@ -81,6 +101,19 @@ components: sources: vector: {
 				unit:    "seconds"
 			}
 		}
+		version: {
+			common:      true
+			description: "The version of the protocol, which must match the version of the upstream `vector` sinks. Version 2 only accepts a socket address."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "1"
+				enum: {
+					"1": "Length delimited events over TCP."
+					"2": "Acknowledged batches of events over gRPC."
+				}
+			}
+		}
 	}
 
 	output: logs: event: {
@@ -106,21 +139,44 @@ components: sources: vector: {
 			title: "Communication Protocol"
 			body: """
 				Upstream Vector instances forward data to downstream Vector
-				instances via the TCP protocol.
+				instances via the TCP protocol. With `version = "2"`, batches of
+				events are sent as gRPC requests over HTTP/2 instead, which
+				multiplexes concurrent requests over a single connection and
+				supports compression.
 				"""
 		}
 		message_acknowledgement: {
 			title: "Message Acknowledgement"
 			body: """
-				Currently, Vector does not perform any application level message
-				acknowledgement. While rare, this means the individual message
-				could be lost.
+				Version 1 of the protocol does not perform any application level
+				message acknowledgement. While rare, this means the individual
+				message could be lost.
+
+				With version 2, the source responds to a batch once its events
+				have been passed on, and the sink retries batches that were not
+				acknowledged.
+				"""
+		}
+		pre_shared_key_authentication: {
+			title: "Pre-shared Key Authentication"
+			body: """
+				With `version = "2"` and `pre_shared_key` set on both ends, the
+				sink and the source prove to each other that they know the key
+				before any events are sent, for deployments without certificates
+				to verify each other with. The sink obtains a session signed by
+				the source, which expires after an hour and is renewed
+				transparently, and then signs the events of each request along
+				with it, so requests captured without `tls` can't be altered to
+				send other events, only sent again as they are until their
+				session expires. Requests without a valid session and
+				signature are rejected and counted by the
+				`authentication_errors_total` metric.
 				"""
 		}
-
 	}
 
 	telemetry: metrics: {
+		authentication_errors_total:  components.sources.internal_metrics.output.metrics.authentication_errors_total
 		protobuf_decode_errors_total: components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
 	}
 }
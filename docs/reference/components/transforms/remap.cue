@@ -35,6 +35,20 @@ components: transforms: remap: {
 	}
 
 	configuration: {
+		abort_reason_field: {
+			common:      false
+			description: "The field the reason given to `abort` is written to, on the events of the aborted output. Events aborted without a reason don't have the field."
+			required:    false
+			warnings: []
+			type: string: default: "abort_reason"
+		}
+		reroute_aborted: {
+			common:      false
+			description: "Whether the events the program aborted are sent to the `<name>.aborted` output of the transform rather than discarded. Failed events aren't part of it."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		source: {
 			description: "The remap source/instruction set to execute for each event"
 			required:    true
@@ -114,6 +128,24 @@ components: transforms: remap: {
 	]
 
 	how_it_works: {
+		aborting: {
+			title: "Aborting"
+			body: #"""
+				Programs can discard an event on purpose with `abort`, optionally giving a
+				reason, such as `abort(["denied", .status])`. Aborted events are not errors:
+				they are discarded whatever the value of `drop_on_err`, and aren't reported
+				as processing errors.
+
+				With `reroute_aborted` set, the aborted events are sent, with their reason
+				in the `abort_reason_field`, to the `<name>.aborted` output, which other
+				components can use as an input, for example
+				`inputs = ["my_remap.aborted"]`. The other events are still the output of
+				`<name>`. The aborted output runs the program on each event too, so the
+				programs rerouting their aborted events shouldn't depend on `now()` or
+				`uuid_v4()` to abort.
+				"""#
+		}
+
 		remap_language: {
 			title: "Remap Language"
 			body: #"""
@@ -0,0 +1,134 @@
+package metadata
+
+components: transforms: sql: {
+	title: "SQL"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		filter: {}
+		reduce: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		allowed_lateness_ms: {
+			common:      false
+			description: "How long the results of a window are held back after the window ended, to include events that arrive late. Events arriving after the results were emitted produce another, partial result for their window."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		flush_period_ms: {
+			common:      false
+			description: "Controls the frequency that Vector checks for (and flushes) ended windows. The period is rounded up to whole seconds."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		query: {
+			description: "The `SELECT` statement to run against the stream of events. See [the query syntax](#query-syntax) for the supported dialect."
+			required:    true
+			warnings: []
+			type: string: examples: [
+				"SELECT count(*), service FROM input WHERE level = 'error' GROUP BY tumble(interval '1m'), service",
+				"SELECT message, status AS code FROM input WHERE status >= 500",
+			]
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	examples: [
+		{
+			title: "Count errors per service"
+			configuration: {
+				query: "SELECT count(*), service FROM input WHERE level = 'error' GROUP BY tumble(interval '1m'), service"
+			}
+			input: [
+				{log: {timestamp: "2020-12-01T10:00:05Z", level: "error", service: "api", message: "Connection refused"}},
+				{log: {timestamp: "2020-12-01T10:00:12Z", level: "info", service: "api", message: "Request received"}},
+				{log: {timestamp: "2020-12-01T10:00:31Z", level: "error", service: "api", message: "Connection refused"}},
+			]
+			output: log: {
+				timestamp: "2020-12-01T10:00:00Z"
+				count:     2
+				service:   "api"
+			}
+		},
+	]
+
+	how_it_works: {
+		query_syntax: {
+			title: "Query Syntax"
+			body: """
+				Queries take the form
+				`SELECT <columns> FROM <name> [WHERE <condition>] [GROUP BY <groups>]`.
+				Events are read from the `inputs` of the transform, the name in the
+				`FROM` clause is only there for readability. Keywords are case
+				insensitive, fields are referenced by their path, such as
+				`request.status`, and may be double quoted, such as `"user-agent"`.
+
+				* Columns are either fields or one of the aggregate functions
+				  `count(*)`, `count(<field>)`, `sum`, `avg`, `min` and `max`.
+				  `window_start()` and `window_end()` select the bounds of the
+				  window. Every column is written to a field of the same name, which
+				  can be changed with `AS <name>`.
+				* Conditions compare fields and literals with `=`, `!=`, `<>`, `<`,
+				  `<=`, `>` and `>=`, and support `IS [NOT] NULL`, `[NOT] IN (...)`,
+				  `AND`, `OR`, `NOT` and parentheses. Comparisons involving missing
+				  fields or values of different types are false, timestamps compare
+				  with RFC 3339 strings.
+				* `GROUP BY` takes a `tumble([<field>,] interval '<size>')` window
+				  and the fields to group by, sizes such as `'30s'`, `'1m'` or
+				  `'1' hour` are accepted.
+
+				Queries without a window filter and project every event on its own,
+				`SELECT *` forwards events unchanged.
+				"""
+		}
+
+		windows: {
+			title: "Windows"
+			body: """
+				Aggregating queries group events into consecutive, non-overlapping
+				windows by their `timestamp`, or by the field given to `tumble`. Events
+				without a timestamp are assigned to the current window. Once a window
+				ended, and `allowed_lateness_ms` passed, one event is emitted for every
+				group of the window, timestamped with the start of the window. Aggregates
+				without any values, such as the `sum` of a missing field, are left out.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
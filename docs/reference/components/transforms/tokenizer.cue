@@ -59,6 +59,16 @@ components: transforms: tokenizer: {
 			warnings: []
 			type: array: items: type: string: examples: ["timestamp", "level", "message", "parent.child"]
 		}
+		timezone: {
+			common:      false
+			description: "The time zone of timestamps converted by `types` from formats without zone information. This is either `local`, for the time zone of the host Vector runs on, or a [TZ database name][urls.iana_time_zone_format], such as `Europe/Berlin`, see the [list of names][urls.iana_time_zones]."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "local"
+				examples: ["local", "America/New_York", "Europe/Berlin"]
+			}
+		}
 		types: configuration._types
 	}
 
@@ -10,6 +10,11 @@ configuration: {
 			as on-disk buffers, file checkpoints, and more.
 			Please make sure the Vector project has write
 			permissions to this directory.
+
+			When Vector panics, a crash report holding its version,
+			a hash of the configuration, its components and the
+			backtrace of the panic is written to the `crash_reports`
+			subdirectory.
 			"""
 		required: false
 		type: string: {
@@ -17,4 +22,179 @@ configuration: {
 			examples: ["/var/lib/vector", "/var/local/lib/vector/", "/home/user/vector/"]
 		}
 	}
+
+	hostname: {
+		common: false
+		description: """
+			Controls how Vector determines the name of the host it runs on.
+			The hostname is resolved at startup, and again when a reload
+			changes the strategy, and used by every component that stamps
+			the local hostname on events or metrics, such as the `file`,
+			`stdin` and `host_metrics` sources. Components unchanged by a
+			reload keep the hostname they were started with.
+			"""
+		required: false
+		type: object: options: {
+			endpoint: {
+				common:        false
+				description:   "Overrides the address of the instance metadata service."
+				relevant_when: "strategy = \"cloud_metadata\""
+				required:      false
+				type: string: {
+					default: null
+					examples: ["http://169.254.169.254"]
+				}
+			}
+			provider: {
+				description:   "The cloud provider whose instance metadata service is queried."
+				relevant_when: "strategy = \"cloud_metadata\""
+				required:      true
+				type: string: enum: {
+					aws:   "The local hostname of the EC2 instance, fetched with IMDSv2."
+					azure: "The name of the Azure virtual machine."
+					gcp:   "The hostname of the Google Compute Engine instance."
+				}
+			}
+			strategy: {
+				common:      true
+				description: "The strategy used to determine the hostname."
+				required:    false
+				type: string: {
+					default: "system"
+					enum: {
+						cloud_metadata: "The hostname reported by the instance metadata service of `provider`."
+						env:            "The value of the environment variable `var`."
+						fqdn:           "The fully qualified domain name the resolver returns for the system hostname, falling back to the system hostname."
+						short:          "The system hostname up to the first dot."
+						system:         "The hostname as reported by the operating system."
+					}
+				}
+			}
+			var: {
+				common:        false
+				description:   "The environment variable holding the hostname."
+				relevant_when: "strategy = \"env\""
+				required:      false
+				type: string: default: "HOSTNAME"
+			}
+		}
+	}
+
+	max_event_age_secs: {
+		common: false
+		description: """
+			Sinks drop events whose timestamp is older than this, unless they
+			set their own `max_event_age_secs`. Dropped events are counted by
+			the `events_expired_total` internal metric. Events without a
+			timestamp are always delivered.
+			"""
+		required: false
+		type: uint: {
+			default:  null
+			examples: [3600, 86400]
+			unit:     "seconds"
+		}
+	}
+
+	request: {
+		common: false
+		description: """
+			Options of the requests of every HTTP based sink.
+			"""
+		required: false
+		type: object: options: {
+			headers: {
+				common: false
+				description: """
+					Headers added to the requests of every HTTP based sink,
+					such as the API keys of intakes. Sinks override them with
+					their own `request.headers` of the same name. Values are
+					either strings, or secrets read when the sinks start, from
+					an environment variable with `{ env = "NAME" }` or a file
+					with `{ file = "/path" }`, which keeps them out of the
+					configuration and out of the logs of the requests. Several
+					configuration files may only set a header to the same value.
+					"""
+				required: false
+				type: object: {
+					examples: [{"X-Api-Key": {file: "/run/secrets/intake_api_key"}}]
+					options: {}
+				}
+			}
+		}
+	}
+
+	tag_events: {
+		common: false
+		description: """
+			Also adds the `tags` to the events of every source: as fields of
+			log events and as tags of metric events. Values the events already
+			carry are kept.
+			"""
+		required: false
+		type: bool: default: false
+	}
+
+	tags: {
+		common: false
+		description: """
+			Tags added to all the metrics of the `internal_metrics` source,
+			such as the environment, cluster or role of the instance, which
+			lets dashboards covering a whole fleet slice by them. Tags set by
+			the metrics themselves take precedence. Several configuration
+			files may only set a tag to the same value.
+			"""
+		required: false
+		type: object: {
+			examples: [{environment: "production", cluster: "eu-west-1", role: "aggregator"}]
+			options: {}
+		}
+	}
+
+	timestamp_skew: {
+		common: false
+		description: """
+			Tracks the difference between the time events are ingested by a
+			source and the timestamp they carry, as the
+			`event_timestamp_skew_seconds` internal metric labeled with the
+			source. Events beyond the thresholds are logged and counted, which
+			helps to spot producers with broken clocks.
+			"""
+		required: false
+		type: object: options: {
+			enabled: {
+				common:      true
+				description: "Enables tracking of the timestamp skew."
+				required:    false
+				type: bool: default: false
+			}
+			field: {
+				common:      false
+				description: "The field the skew is written to, in seconds. Positive values are events from the past."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["timestamp_skew"]
+				}
+			}
+			max_future_secs: {
+				common:      false
+				description: "Events timestamped further than this in the future are reported."
+				required:    false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			max_past_secs: {
+				common:      false
+				description: "Events older than this are reported."
+				required:    false
+				type: uint: {
+					default: 3600
+					unit:    "seconds"
+				}
+			}
+		}
+	}
 }
@@ -50,7 +50,7 @@ remap: {
 
 			arguments: [...#Argument] // Allow for empty list
 			return: [#RemapReturnTypes, ...#RemapReturnTypes]
-			category:    "coerce" | "numeric" | "object" | "parse" | "text" | "hash" | "event" | "networking"
+			category:    "coerce" | "numeric" | "object" | "parse" | "text" | "hash" | "event" | "networking" | "codec"
 			description: string
 			examples: [#RemapExample, ...#RemapExample]
 			name: Name
@@ -0,0 +1,39 @@
+package metadata
+
+remap: functions: abort: {
+	arguments: [
+		{
+			name:        "reason"
+			description: "The reason of the abort, written to the `abort_reason_field` of the event if the transform reroutes its aborted events."
+			required:    false
+			type: ["any"]
+		},
+	]
+	return: ["null"]
+	category: "event"
+	description: #"""
+			Stops the program and discards the event, without the error raised by a failed program.
+			Transforms with `reroute_aborted` set send the event, as it was when aborted, to their
+			`<name>.aborted` output instead.
+		"""#
+	examples: [
+		{
+			title: "Abort with a reason"
+			configuration: {
+				reroute_aborted: "true"
+			}
+			input: {
+				status: 403
+			}
+			source: #"""
+				if .status == 403 {
+					abort(["denied", .status])
+				}
+				"""#
+			output: {
+				status: 403
+				abort_reason: ["denied", 403]
+			}
+		},
+	]
+}
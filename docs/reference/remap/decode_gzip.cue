@@ -0,0 +1,45 @@
+package metadata
+
+remap: functions: decode_gzip: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The gzip compressed string to decompress."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["string"]
+	category: "codec"
+	description: #"""
+		Decompresses a string compressed with [gzip](\(urls.gzip)), such as a payload compressed by the
+		sender of the event. An error is raised if the string isn't valid gzip data. or decompresses
+		to more than 10MiB.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.message = decode_gzip(encode_gzip(.message))
+				"""#
+			output: {
+				message: "hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.decompressed = decode_gzip(.message)
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
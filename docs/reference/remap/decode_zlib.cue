@@ -0,0 +1,45 @@
+package metadata
+
+remap: functions: decode_zlib: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The zlib compressed string to decompress."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["string"]
+	category: "codec"
+	description: #"""
+		Decompresses a string compressed with [zlib](\(urls.zlib)), such as a payload compressed by the
+		sender of the event. An error is raised if the string isn't valid zlib data. or decompresses
+		to more than 10MiB.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.message = decode_zlib(encode_zlib(.message))
+				"""#
+			output: {
+				message: "hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.decompressed = decode_zlib(.message)
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
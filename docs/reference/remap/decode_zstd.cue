@@ -0,0 +1,45 @@
+package metadata
+
+remap: functions: decode_zstd: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The zstd compressed string to decompress."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["string"]
+	category: "codec"
+	description: #"""
+		Decompresses a string compressed with [zstd](\(urls.zstd)), such as a payload compressed by the
+		sender of the event. An error is raised if the string isn't valid zstd data. or decompresses
+		to more than 10MiB.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.message = decode_zstd(encode_zstd(.message))
+				"""#
+			output: {
+				message: "hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.decompressed = decode_zstd(.message)
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
@@ -0,0 +1,49 @@
+package metadata
+
+remap: functions: encode_gzip: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to compress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "compression_level"
+			description: "The compression level, from 0 (fastest) to 9 (smallest). Defaults to 6."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	return: ["string"]
+	category: "codec"
+	description: #"""
+		Compresses a string with [gzip](\(urls.gzip)). The result is binary, and is usually decompressed with `decode_gzip`, or sent as is by a sink.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.message = decode_gzip(encode_gzip(.message, compression_level = 9))
+				"""#
+			output: {
+				message: "hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: 42
+			}
+			source: #"""
+				.compressed = encode_gzip(.message)
+				"""#
+			output: {
+				error: remap.errors.ArgumentError
+			}
+		},
+	]
+}
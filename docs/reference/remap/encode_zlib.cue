@@ -0,0 +1,49 @@
+package metadata
+
+remap: functions: encode_zlib: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to compress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "compression_level"
+			description: "The compression level, from 0 (fastest) to 9 (smallest). Defaults to 6."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	return: ["string"]
+	category: "codec"
+	description: #"""
+		Compresses a string with [zlib](\(urls.zlib)). The result is binary, and is usually decompressed with `decode_zlib`, or sent as is by a sink.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.message = decode_zlib(encode_zlib(.message))
+				"""#
+			output: {
+				message: "hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: 42
+			}
+			source: #"""
+				.compressed = encode_zlib(.message)
+				"""#
+			output: {
+				error: remap.errors.ArgumentError
+			}
+		},
+	]
+}
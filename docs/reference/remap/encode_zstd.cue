@@ -0,0 +1,49 @@
+package metadata
+
+remap: functions: encode_zstd: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to compress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "compression_level"
+			description: "The compression level, from 1 (fastest) to 21 (smallest). Defaults to 3."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	return: ["string"]
+	category: "codec"
+	description: #"""
+		Compresses a string with [zstd](\(urls.zstd)). The result is binary, and is usually decompressed with `decode_zstd`, or sent as is by a sink.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"hello world"#
+			}
+			source: #"""
+				.message = decode_zstd(encode_zstd(.message))
+				"""#
+			output: {
+				message: "hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: 42
+			}
+			source: #"""
+				.compressed = encode_zstd(.message)
+				"""#
+			output: {
+				error: remap.errors.ArgumentError
+			}
+		},
+	]
+}
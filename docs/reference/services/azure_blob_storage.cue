@@ -0,0 +1,8 @@
+package metadata
+
+services: azure_blob_storage: {
+	name:     "Azure Blob Storage"
+	thing:    "an \(name) container"
+	url:      urls.azure_blob_storage
+	versions: null
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: cassandra: {
+	name:     "Cassandra"
+	thing:    "a \(name) cluster"
+	url:      urls.cassandra
+	versions: null
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: datadog_events: {
+	name:     "Datadog events"
+	thing:    "a \(name) stream"
+	url:      urls.datadog_events
+	versions: null
+}
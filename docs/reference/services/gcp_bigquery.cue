@@ -0,0 +1,8 @@
+package metadata
+
+services: gcp_bigquery: {
+	name:     "GCP BigQuery"
+	thing:    "a \(name) table"
+	url:      urls.gcp_bigquery
+	versions: null
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: hdfs: {
+	name:     "HDFS"
+	thing:    "a \(name) cluster"
+	url:      urls.hdfs
+	versions: null
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: snowflake: {
+	name:     "Snowflake"
+	thing:    "a \(name) table"
+	url:      urls.snowflake
+	versions: null
+}
@@ -3,12 +3,17 @@ package metadata
 urls: {
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
+	amqp:                                                     "https://www.rabbitmq.com/tutorials/amqp-concepts.html"
+	amqp_uri:                                                 "https://www.rabbitmq.com/uri-spec.html"
 	apache:                                                   "https://httpd.apache.org/"
 	apache_extended_status:                                   "https://httpd.apache.org/docs/current/mod/core.html#extendedstatus"
 	apache_install:                                           "https://httpd.apache.org/docs/current/install.html"
 	apache_mod_status:                                        "http://httpd.apache.org/docs/current/mod/mod_status.html"
+	apache_parquet:                                           "https://parquet.apache.org/"
 	apt:                                                      "https://en.wikipedia.org/wiki/APT_(software)"
 	arm:                                                      "https://en.wikipedia.org/wiki/ARM_architecture"
+	auth0:                                                    "https://auth0.com/"
+	auth0_logs_api:                                           "https://auth0.com/docs/api/management/v2#!/Logs/get_logs"
 	aws_arm_g2_announcement:                                  "https://aws.amazon.com/about-aws/whats-new/2019/12/announcing-new-amazon-ec2-m6g-c6g-and-r6g-instances-powered-by-next-generation-arm-based-aws-graviton2-processors/"
 	aws_athena:                                               "https://aws.amazon.com/athena/"
 	aws_athena_console:                                       "https://console.aws.amazon.com/athena/home"
@@ -38,6 +43,7 @@ urls: {
 	aws_iam:                                                  "https://docs.aws.amazon.com/IAM/latest/UserGuide/introduction.html"
 	aws_iam_role:                                             "https://docs.aws.amazon.com/IAM/latest/UserGuide/id_roles.html"
 	aws_imds_v1_security_problems:                            "https://aws.amazon.com/blogs/security/defense-in-depth-open-firewalls-reverse-proxies-ssrf-vulnerabilities-ec2-instance-metadata-service/"
+	aws_kinesis_aggregation:                                  "https://github.com/awslabs/amazon-kinesis-producer/blob/master/aggregation-format.md"
 	aws_kinesis_firehose:                                     "https://aws.amazon.com/kinesis/data-firehose/"
 	aws_kinesis_firehose_http_protocol:                       "https://docs.aws.amazon.com/firehose/latest/dev/create-destination.html#create-destination-http"
 	aws_firehose_http_request_spec:                           "https://docs.aws.amazon.com/firehose/latest/dev/httpdeliveryrequestresponse.html"
@@ -64,23 +70,38 @@ urls: {
 	aws_s3_tags:                                              "https://docs.aws.amazon.com/AmazonS3/latest/user-guide/add-object-tags.html"
 	aws_sqs:                                                  "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                              "https://docs.aws.amazon.com/AWSSimpleQueueService/latest/APIReference/Welcome.html"
+	aws_sqs_fifo:                                             "https://docs.aws.amazon.com/AWSSimpleQueueService/latest/SQSDeveloperGuide/FIFO-queues.html"
+	aws_sqs_fifo_deduplication:                               "https://docs.aws.amazon.com/AWSSimpleQueueService/latest/SQSDeveloperGuide/using-messagededuplicationid-property.html"
+	azure_blob_storage:                                       "https://azure.microsoft.com/en-us/services/storage/blobs/"
+	azure_blob_storage_append_blobs:                          "https://docs.microsoft.com/en-us/rest/api/storageservices/understanding-block-blobs--append-blobs--and-page-blobs#about-append-blobs"
+	azure_blob_storage_rest_api:                              "https://docs.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api"
+	azure_managed_identities:                                 "https://docs.microsoft.com/en-us/azure/active-directory/managed-identities-azure-resources/overview"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
+	azure_storage_connection_string:                          "https://docs.microsoft.com/en-us/azure/storage/common/storage-configure-connection-string"
 	basic_auth:                                               "https://en.wikipedia.org/wiki/Basic_access_authentication"
 	big_query_streaming:                                      "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	cargo_audit:                                              "https://github.com/RustSec/cargo-audit"
+	cassandra:                                                "https://cassandra.apache.org/"
+	cef:                                                      "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	centos:                                                   "https://www.centos.org/"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
 	clickhouse:                                               "https://clickhouse.yandex/"
+	clickhouse_async_insert:                                  "https://clickhouse.com/docs/en/optimize/asynchronous-inserts"
 	clickhouse_http:                                          "https://clickhouse.yandex/docs/en/interfaces/http/"
+	clickhouse_row_binary:                                    "https://clickhouse.yandex/docs/en/interfaces/formats/#rowbinary"
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	console:                                                  "https://en.wikipedia.org/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "https://github.com/timberio/vector/blob/master/CONTRIBUTING.md#setup"
+	cors:                                                     "https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS"
+	cql_native_protocol:                                      "https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec"
 	crc:                                                      "https://en.wikipedia.org/wiki/Cyclic_redundancy_check"
 	cue:                                                      "https://cuelang.org/"
 	datadog:                                                  "https://www.datadoghq.com"
 	datadog_distribution:                                     "https://docs.datadoghq.com/developers/metrics/types/?tab=distribution#definition"
+	datadog_events:                                           "https://docs.datadoghq.com/events/"
+	datadog_events_api:                                       "https://docs.datadoghq.com/api/latest/events/#post-an-event"
 	datadog_logs:                                             "https://docs.datadoghq.com/logs/"
 	datadog_logs_endpoints:                                   "https://docs.datadoghq.com/logs/log_collection/?tab=http#datadog-logs-endpoints"
 	datadog_metrics:                                          "https://docs.datadoghq.com/metrics/"
@@ -113,10 +134,17 @@ urls: {
 	dry_code:                                                 "https://en.wikipedia.org/wiki/Don%27t_repeat_yourself"
 	cidr:                                                     "https://en.wikipedia.org/wiki/Classless_Inter-Domain_Routing"
 	elasticsearch:                                            "https://www.elastic.co/products/elasticsearch"
+	elasticsearch_api_keys:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/security-api-create-api-key.html"
 	elasticsearch_bulk:                                       "https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html"
+	elasticsearch_data_streams:                               "https://www.elastic.co/guide/en/elasticsearch/reference/current/data-streams.html"
 	elasticsearch_id_field:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-id-field.html"
 	elasticsearch_id_performance:                             "https://www.elastic.co/guide/en/elasticsearch/reference/master/tune-for-indexing-speed.html#_use_auto_generated_ids"
 	elasticsearch_ignore_malformed:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ignore-malformed.html"
+	elasticsearch_point_in_time:                              "https://www.elastic.co/guide/en/elasticsearch/reference/current/point-in-time-api.html"
+	elasticsearch_query_dsl:                                  "https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl.html"
+	elasticsearch_scroll:                                     "https://www.elastic.co/guide/en/elasticsearch/reference/current/paginate-search-results.html#scroll-search-results"
+	elasticsearch_service_tokens:                             "https://www.elastic.co/guide/en/elasticsearch/reference/current/service-accounts.html"
+	elasticsearch_slicing:                                    "https://www.elastic.co/guide/en/elasticsearch/reference/current/paginate-search-results.html#slice-scroll"
 	endler_dev:                                               "https://endler.dev/"
 	etsy:                                                     "https://www.etsy.com"
 	event_proto:                                              "https://github.com/timberio/vector/blob/master/proto/event.proto"
@@ -129,9 +157,17 @@ urls: {
 	gcp_authentication_api_key:                               "https://cloud.google.com/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:                      "https://cloud.google.com/docs/authentication/production"
 	gcp_authentication_service_account:                       "https://cloud.google.com/docs/authentication/production#obtaining_and_providing_service_account_credentials_manually"
+	gcp_bigquery:                                             "https://cloud.google.com/bigquery"
+	gcp_bigquery_storage_write_api:                           "https://cloud.google.com/bigquery/docs/write-api"
+	gcp_cloud_logging:                                        "https://cloud.google.com/logging"
+	gcp_cloud_logging_entries_list:                           "https://cloud.google.com/logging/docs/reference/v2/rest/v2/entries/list"
+	gcp_cloud_logging_filters:                                "https://cloud.google.com/logging/docs/view/advanced-queries"
+	gcp_cloud_logging_log_entry:                              "https://cloud.google.com/logging/docs/reference/v2/rest/v2/LogEntry"
+	gcp_cloud_logging_sinks:                                  "https://cloud.google.com/logging/docs/export/configure_export_v2"
 	gcp_cloud_storage:                                        "https://cloud.google.com/storage"
 	gcp_folders:                                              "https://cloud.google.com/resource-manager/docs/creating-managing-folders"
 	gcp_pubsub:                                               "https://cloud.google.com/pubsub/"
+	gcp_pubsub_ordering:                                      "https://cloud.google.com/pubsub/docs/ordering"
 	gcp_pubsub_rest:                                          "https://cloud.google.com/pubsub/docs/reference/rest/"
 	gcp_projects:                                             "https://cloud.google.com/resource-manager/docs/creating-managing-projects"
 	gcp_resources:                                            "https://cloud.google.com/monitoring/api/resources"
@@ -147,13 +183,21 @@ urls: {
 	github_protected_branches:                                "https://help.github.com/en/github/administering-a-repository/about-protected-branches"
 	github_sign_commits:                                      "https://help.github.com/en/github/authenticating-to-github/signing-commits"
 	globbing:                                                 "https://en.wikipedia.org/wiki/Glob_(programming)"
+	google_workspace:                                         "https://workspace.google.com/"
+	google_workspace_domain_wide_delegation:                  "https://developers.google.com/admin-sdk/directory/v1/guides/delegation"
+	google_workspace_reports_api:                             "https://developers.google.com/admin-sdk/reports/v1/reference/activities/list"
 	graphql:                                                  "https://graphql.org"
 	graphql_playground:                                       "https://github.com/graphql/graphql-playground"
 	grok:                                                     "https://grokdebug.herokuapp.com/"
 	grok_debugger:                                            "https://grokdebug.herokuapp.com/"
 	grok_patterns:                                            "https://github.com/daschl/grok/tree/master/patterns"
 	gzip:                                                     "https://www.gzip.org/"
+	hadoop_httpfs:                                            "https://hadoop.apache.org/docs/stable/hadoop-hdfs-httpfs/index.html"
+	hadoop_kerberos:                                          "https://hadoop.apache.org/docs/stable/hadoop-project-dist/hadoop-common/HttpAuthentication.html"
+	hadoop_proxy_users:                                       "https://hadoop.apache.org/docs/stable/hadoop-project-dist/hadoop-common/Superusers.html"
 	haproxy:                                                  "https://www.haproxy.org/"
+	haproxy_proxy_protocol:                                   "https://www.haproxy.org/download/2.3/doc/proxy-protocol.txt"
+	hdfs:                                                     "https://hadoop.apache.org/docs/stable/hadoop-project-dist/hadoop-hdfs/HdfsDesign.html"
 	helm:                                                     "https://helm.sh/"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	homebrew:                                                 "https://brew.sh/"
@@ -172,6 +216,7 @@ urls: {
 	iana_time_zones:                                          "https://en.wikipedia.org/wiki/List_of_tz_database_time_zones"
 	ieee_754:                                                 "https://en.wikipedia.org/wiki/IEEE_754"
 	ietf_rfc_6750:                                            "https://tools.ietf.org/html/rfc6750"
+	ietf_rfc_8288:                                            "https://tools.ietf.org/html/rfc8288"
 	initd:                                                    "https://bash.cyberciti.biz/guide//etc/init.d"
 	influxdb:                                                 "https://www.influxdata.com/products/influxdb-overview/"
 	influxdb_http_api_v1:                                     "https://docs.influxdata.com/influxdb/latest/tools/api/#write-http-endpoint"
@@ -182,11 +227,15 @@ urls: {
 	iso3166_2:                                                "https://en.wikipedia.org/wiki/ISO_3166-2"
 	issue_1694:                                               "https://github.com/timberio/vector/issues/1694"
 	jemalloc:                                                 "https://github.com/jemalloc/jemalloc"
+	jmx:                                                      "https://docs.oracle.com/javase/8/docs/technotes/guides/jmx/"
+	jolokia:                                                  "https://jolokia.org"
+	jolokia_protocol:                                         "https://jolokia.org/reference/html/protocol.html"
 	journald:                                                 "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html"
 	json:                                                     "https://en.wikipedia.org/wiki/JSON"
 	json:                                                     "https://en.wikipedia.org/wiki/JSON"
 	json_types:                                               "https://en.wikipedia.org/wiki/JSON#Data_types_and_syntax"
 	jsonnet:                                                  "https://jsonnet.org/"
+	jsonpath:                                                 "https://goessner.net/articles/JsonPath/"
 	kafka:                                                    "https://kafka.apache.org/"
 	kafka_partitioning_docs:                                  "https://cwiki.apache.org/confluence/display/KAFKA/A+Guide+To+The+Kafka+Protocol#AGuideToTheKafkaProtocol-Partitioningandbootstrapping"
 	kafka_protocol:                                           "https://kafka.apache.org/protocol"
@@ -198,12 +247,16 @@ urls: {
 	kubernetes_api_server:                                    "https://kubernetes.io/docs/reference/command-line-tools-reference/kube-apiserver/"
 	kubernetes_authorization:                                 "https://kubernetes.io/docs/reference/access-authn-authz/authorization/"
 	kubernetes_daemonset:                                     "https://kubernetes.io/docs/concepts/workloads/controllers/daemonset/"
+	kubernetes_events:                                        "https://kubernetes.io/docs/reference/generated/kubernetes-api/v1.16/#event-v1-core"
 	kubernetes_example_daemonset:                             "https://github.com/timberio/vector/blob/master/config/kubernetes/vector-daemonset.yaml"
+	kubernetes_field_selector:                                "https://kubernetes.io/docs/concepts/overview/working-with-objects/field-selectors/"
+	kubernetes_label_selector:                                "https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors"
 	kubernetes_limit_resources:                               "https://kubernetes.io/docs/tasks/configure-pod-container/assign-cpu-resource/"
 	kubernetes_logging_architecture:                          "https://kubernetes.io/docs/concepts/cluster-administration/logging/"
 	kubernetes_rbac:                                          "https://kubernetes.io/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                                 "https://kubernetes.io/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                                     "https://kubernetes.io/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
+	leef:                                                     "https://www.ibm.com/docs/en/dsm?topic=leef-overview"
 	leveldb:                                                  "https://github.com/google/leveldb"
 	leveldb_sys_2:                                            "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                            "https://github.com/timberio/leveldb-sys/tree/v3.0.0"
@@ -211,7 +264,9 @@ urls: {
 	librdkafka_config:                                        "https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md"
 	logdna:                                                   "https://logdna.com/"
 	logfmt:                                                   "https://brandur.org/logfmt"
+	logql:                                                    "https://grafana.com/docs/loki/latest/logql/"
 	loki:                                                     "https://grafana.com/oss/loki/"
+	loki_api:                                                 "https://grafana.com/docs/loki/latest/api/"
 	loki_multi_tenancy:                                       "https://github.com/grafana/loki/blob/master/docs/operations/multi-tenancy.md"
 	log_event_source:                                         "https://github.com/timberio/vector/blob/master/src/event/"
 	logplex:                                                  "https://devcenter.heroku.com/articles/logplex"
@@ -237,6 +292,7 @@ urls: {
 	lua_tonumber:                                             "https://www.lua.org/manual/5.3/manual.html#pdf-tonumber"
 	lz4:                                                      "https://lz4.github.io/lz4/"
 	macos:                                                    "https://en.wikipedia.org/wiki/MacOS"
+	macos_unified_log:                                        "https://developer.apple.com/documentation/os/logging"
 	mailing_list:                                             "https://vector.dev/community/"
 	maxmind_db_file_format:                                   "https://maxmind.github.io/MaxMind-DB/"
 	maxmind_geoip2_city:                                      "https://www.maxmind.com/en/geoip2-city"
@@ -245,29 +301,46 @@ urls: {
 	maxmind_geolite2_city:                                    "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
 	metric_event_source:                                      "https://github.com/timberio/vector/blob/master/src/event/metric.rs"
 	mongodb:                                                  "https://www.mongodb.com"
+	mongodb_command_db_stats:                                 "https://docs.mongodb.com/manual/reference/command/dbStats/"
+	mongodb_command_repl_set_get_status:                      "https://docs.mongodb.com/manual/reference/command/replSetGetStatus/"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
 	musl_builder_docker_image:                                "https://github.com/timberio/vector/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	nats:                                                     "https://nats.io/"
+	nats_jetstream:                                           "https://docs.nats.io/jetstream/jetstream"
 	new_bug_report:                                           "https://github.com/timberio/vector/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
 	new_relic:                                                "https://newrelic.com/"
 	new_relic_log_api:                                        "https://docs.newrelic.com/docs/logs/new-relic-logs/log-api/introduction-log-api"
+	new_relic_metric_api:                                     "https://docs.newrelic.com/docs/telemetry-data-platform/ingest-apis/introduction-metric-api/"
 	new_security_report:                                      "https://github.com/timberio/vector/issues/new?labels=domain%3A+security"
 	new_sink:                                                 "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
 	new_source:                                               "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
 	new_target:                                               "https://github.com/timberio/vector/issues/new?labels=type%3A+task&labels=domain%3A+operations"
 	new_transform:                                            "https://github.com/timberio/vector/issues/new?labels=type%3A+new+feature"
 	nginx:                                                    "https://www.nginx.com/"
+	nginx_plus_api:                                           "http://nginx.org/en/docs/http/ngx_http_api_module.html"
 	nginx_stub_status_module:                                 "http://nginx.org/en/docs/http/ngx_http_stub_status_module.html"
 	nix:                                                      "https://nixos.org/nix/"
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "https://github.com/NixOS/nixpkgs/issues/9682"
+	oauth2_client_credentials:                                "https://tools.ietf.org/html/rfc6749#section-4.4"
+	okta:                                                     "https://www.okta.com/"
+	okta_system_log_api:                                      "https://developer.okta.com/docs/reference/api/system-log/"
+	openapi:                                                  "https://spec.openapis.org/oas/v3.0.3"
+	opensearch:                                               "https://opensearch.org/"
 	openssl:                                                  "https://www.openssl.org/"
+	opentelemetry_otlp:                                       "https://opentelemetry.io/docs/specs/otlp/"
+	opentelemetry_otlp_grpc:                                  "https://opentelemetry.io/docs/specs/otlp/#otlpgrpc"
+	opentelemetry_otlp_http:                                  "https://opentelemetry.io/docs/specs/otlp/#otlphttp"
 	papertrail:                                               "https://www.papertrail.com/"
 	papertrail_syslog:                                        "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                             "https://www.perl.org/get.html#win32"
+	postgresql:                                               "https://www.postgresql.org/"
+	postgresql_connection_string:                             "https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNSTRING"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
+	postgresql_monitoring_stats:                              "https://www.postgresql.org/docs/current/monitoring-stats.html"
+	postgresql_peer_authentication:                           "https://www.postgresql.org/docs/current/auth-peer.html"
 	prometheus:                                               "https://prometheus.io/"
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
 	prometheus_counter:                                       "https://prometheus.io/docs/concepts/metric_types/#counter"
@@ -275,6 +348,7 @@ urls: {
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
 	prometheus_histograms_guide:                              "https://prometheus.io/docs/practices/histograms/"
+	prometheus_relabel_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config"
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "https://github.com/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
@@ -283,8 +357,16 @@ urls: {
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
+	questdb:                                                  "https://questdb.io/"
+	questdb_ilp:                                              "https://questdb.io/docs/reference/api/ilp/overview/"
+	rabbitmq:                                                 "https://www.rabbitmq.com/"
+	rabbitmq_confirms:                                        "https://www.rabbitmq.com/confirms.html#publisher-confirms"
 	raspbian:                                                 "https://www.raspbian.org/"
 	rdkafka:                                                  "https://github.com/edenhill/librdkafka"
+	redis:                                                    "https://redis.io/"
+	redis_pipelining:                                         "https://redis.io/topics/pipelining"
+	redis_streams:                                            "https://redis.io/topics/streams-intro"
+	redis_urls:                                               "https://www.iana.org/assignments/uri-schemes/prov/redis"
 	regex:                                                    "https://en.wikipedia.org/wiki/Regular_expression"
 	regex_grouping_and_flags:                                 "https://docs.rs/regex/1.3.9/regex/#grouping-and-flags"
 	regex_tester:                                             "https://rustexp.lpil.uk/"
@@ -305,6 +387,7 @@ urls: {
 	rust_target_triples:                                      "https://forge.rust-lang.org/platform-support.html"
 	rust_tier_1_platform:                                     "https://forge.rust-lang.org/release/platform-support.html#tier-1"
 	rustup:                                                   "https://rustup.rs"
+	scylladb:                                                 "https://www.scylladb.com/"
 	sematext:                                                 "https://sematext.com"
 	sematext_create_logs_app:                                 "https://apps.sematext.com/ui/integrations"
 	sematext_es:                                              "https://sematext.com/docs/logs/index-events-via-elasticsearch-api/"
@@ -313,11 +396,15 @@ urls: {
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
 	snappy:                                                   "https://google.github.io/snappy/"
+	snowflake:                                                "https://www.snowflake.com/"
+	snowflake_key_pair_auth:                                  "https://docs.snowflake.com/en/user-guide/key-pair-auth"
+	snowpipe_streaming:                                       "https://docs.snowflake.com/en/user-guide/snowpipe-streaming/snowpipe-streaming-high-performance-overview"
 	socket:                                                   "https://en.wikipedia.org/wiki/Network_socket"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
 	splunk_hec_event_endpoint:                                "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fevent"
 	splunk_hec_indexed_fields:                                "https://docs.splunk.com/Documentation/Splunk/8.0.0/Data/IFXandHEC"
+	splunk_hec_indexer_acknowledgement:                       "https://docs.splunk.com/Documentation/Splunk/latest/Data/AboutHECIDXAck"
 	splunk_hec_protocol:                                      "https://docs.splunk.com/Documentation/Splunk/8.0.0/Data/HECRESTendpoints"
 	splunk_hec_raw_endpoint:                                  "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                                         "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
@@ -421,7 +508,10 @@ urls: {
 	vector_version_branches:                                  "https://github.com/timberio/vector/branches/all?query=v"
 	vector_website:                                           "https://vector.dev"
 	vote_feature:                                             "https://github.com/timberio/vector/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
+	w3c_trace_context:                                        "https://www.w3.org/TR/trace-context/"
 	wasm:                                                     "https://webassembly.org/"
+	webhdfs_rest_api:                                         "https://hadoop.apache.org/docs/stable/hadoop-project-dist/hadoop-hdfs/WebHDFS.html"
+	websocket:                                                "https://tools.ietf.org/html/rfc6455"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
 	windows_installer:                                        "https://en.wikipedia.org/wiki/Windows_Installer"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
@@ -258,6 +258,12 @@ where
                         }
                     }
                 }
+
+                for watcher in fp_map.values() {
+                    if let Some(bytes_behind) = watcher.bytes_behind() {
+                        self.emitter.emit_file_lag(&watcher.path, bytes_behind);
+                    }
+                }
                 stats.record("discovery", start.elapsed());
             }
 
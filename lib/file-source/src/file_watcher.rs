@@ -22,6 +22,9 @@ pub struct FileWatcher {
     pub path: PathBuf,
     findable: bool,
     reader: Box<dyn BufRead>,
+    /// The file read, to compare its size with the position, unless it's
+    /// compressed and the position counts decompressed bytes.
+    uncompressed: Option<File>,
     file_position: FilePosition,
     devno: u64,
     inode: u64,
@@ -47,6 +50,7 @@ impl FileWatcher {
         let f = fs::File::open(&path)?;
         let (devno, ino) = (f.portable_dev()?, f.portable_ino()?);
         let metadata = f.metadata()?;
+        let uncompressed = f.try_clone()?;
         let mut reader = io::BufReader::new(f);
 
         let too_old = if let (Some(ignore_before), Ok(modified_time)) = (
@@ -58,8 +62,8 @@ impl FileWatcher {
             false
         };
 
-        let (reader, file_position): (Box<dyn BufRead>, FilePosition) = if is_gzipped(&mut reader)?
-        {
+        let gzipped = is_gzipped(&mut reader)?;
+        let (reader, file_position): (Box<dyn BufRead>, FilePosition) = if gzipped {
             if file_position != 0 || too_old {
                 // We can't accurately seek into gzipped files without manually scanning through
                 // the entire thing, so for now we simply refuse to read gzipped files for which we
@@ -92,6 +96,7 @@ impl FileWatcher {
             path,
             findable: true,
             reader,
+            uncompressed: if gzipped { None } else { Some(uncompressed) },
             file_position,
             devno,
             inode: ino,
@@ -108,6 +113,11 @@ impl FileWatcher {
         if (file_handle.portable_dev()?, file_handle.portable_ino()?) != (self.devno, self.inode) {
             let mut reader = io::BufReader::new(fs::File::open(&path)?);
             let gzipped = is_gzipped(&mut reader)?;
+            self.uncompressed = if gzipped {
+                None
+            } else {
+                Some(file_handle.try_clone()?)
+            };
             let new_reader: Box<dyn BufRead> = if gzipped {
                 if self.file_position != 0 {
                     Box::new(null_reader())
@@ -146,6 +156,14 @@ impl FileWatcher {
         self.file_position
     }
 
+    /// The number of bytes written to the file which haven't been read yet,
+    /// unknown for compressed files.
+    pub fn bytes_behind(&self) -> Option<u64> {
+        let file = self.uncompressed.as_ref()?;
+        let size = file.metadata().ok()?.len();
+        Some(size.saturating_sub(self.file_position))
+    }
+
     /// Read a single line from the underlying file
     ///
     /// This function will attempt to read a new line from its file, blocking,
@@ -281,9 +299,9 @@ fn read_until_with_max_size<R: BufRead + ?Sized>(
 
 #[cfg(test)]
 mod test {
-    use super::read_until_with_max_size;
+    use super::{read_until_with_max_size, FileWatcher};
     use bytes::BytesMut;
-    use std::io::Cursor;
+    use std::io::{Cursor, Write};
 
     #[test]
     fn test_read_until_with_max_size() {
@@ -336,4 +354,19 @@ mod test {
         assert_eq!(p, None);
         assert_eq!(&*v, [0; 0]);
     }
+
+    #[test]
+    fn test_bytes_behind() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"first\nsecond\n").unwrap();
+
+        let mut watcher = FileWatcher::new(file.path().to_owned(), 0, None, 1000).unwrap();
+        assert_eq!(watcher.bytes_behind(), Some(13));
+
+        assert_eq!(&*watcher.read_line().unwrap().unwrap(), b"first");
+        assert_eq!(watcher.bytes_behind(), Some(7));
+
+        file.write_all(b"third\n").unwrap();
+        assert_eq!(watcher.bytes_behind(), Some(13));
+    }
 }
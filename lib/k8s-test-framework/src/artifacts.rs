@@ -0,0 +1,225 @@
+//! Collect the state of the cluster when a test fails.
+//!
+//! Failed tests tear their namespaces down right away, which removes the pods,
+//! their logs and the events that would explain the failure. So before that,
+//! the pod logs, the events and the resource manifests of the namespace are
+//! written into the artifacts directory, under the name of the test, along
+//! with an `index.json` file listing what was collected.
+
+use super::Result;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// Resources whose manifests are collected.
+const RESOURCES: &str = "all,configmaps,serviceaccounts,roles,rolebindings";
+
+/// Collects the state of namespaces into the artifacts directory if the test
+/// has failed.
+#[derive(Debug, Clone)]
+pub struct Collector {
+    kubectl_command: String,
+    artifacts_dir: PathBuf,
+    failed: Arc<AtomicBool>,
+    collected: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Collector {
+    /// Create a new [`Collector`] writing into `artifacts_dir` and using the
+    /// specified `kubectl_command`.
+    pub fn new(kubectl_command: &str, artifacts_dir: &Path) -> Self {
+        Self {
+            kubectl_command: kubectl_command.to_owned(),
+            artifacts_dir: artifacts_dir.to_owned(),
+            failed: Arc::new(AtomicBool::new(false)),
+            collected: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Record that an operation of the test has failed, for the test
+    /// returning an error rather than panicking.
+    pub fn mark_failed(&self) {
+        self.failed.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the test has failed, either by panicking or by an operation
+    /// that was marked as failed.
+    pub fn has_failed(&self) -> bool {
+        thread::panicking() || self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Write the pod logs, the events and the resource manifests of the
+    /// `namespace`, and add them to the index of the test.
+    /// A namespace is only collected once, by the first resource of it to be
+    /// turned down, while all of its pods are still there.
+    /// Returns the directory of the test.
+    pub fn collect(&self, namespace: &str) -> Result<PathBuf> {
+        let test_dir = self.artifacts_dir.join(test_name());
+        {
+            let mut collected = self
+                .collected
+                .lock()
+                .map_err(|_| "artifacts collector lock poisoned")?;
+            if collected.is_empty() && test_dir.exists() {
+                // Stale artifacts of a previous run of the test.
+                fs::remove_dir_all(&test_dir)?;
+            }
+            if !collected.insert(namespace.to_owned()) {
+                return Ok(test_dir);
+            }
+        }
+
+        let namespace_dir = test_dir.join(namespace);
+        fs::create_dir_all(namespace_dir.join("logs"))?;
+
+        let mut errors = Vec::new();
+        let mut record = |file: &Path, result: Result<()>| match result {
+            Ok(()) => Some(relative(&test_dir, file)),
+            Err(error) => {
+                errors.push(format!("{}: {}", relative(&test_dir, file), error));
+                None
+            }
+        };
+
+        let manifests = namespace_dir.join("manifests.yaml");
+        let manifests = record(
+            &manifests,
+            self.kubectl_to_file(
+                &["get", RESOURCES, "-n", namespace, "-o", "yaml"],
+                &manifests,
+            ),
+        );
+
+        let events = namespace_dir.join("events.txt");
+        let events = record(
+            &events,
+            self.kubectl_to_file(
+                &[
+                    "get",
+                    "events",
+                    "-n",
+                    namespace,
+                    "-o",
+                    "wide",
+                    "--sort-by=.lastTimestamp",
+                ],
+                &events,
+            ),
+        );
+
+        let mut logs = Vec::new();
+        for pod in self.pods(namespace)? {
+            let file = namespace_dir.join("logs").join(format!("{}.log", pod));
+            let resource = format!("pod/{}", pod);
+            let result = self.kubectl_to_file(
+                &[
+                    "logs",
+                    "-n",
+                    namespace,
+                    &resource,
+                    "--all-containers",
+                    "--timestamps",
+                ],
+                &file,
+            );
+            if let Some(path) = record(&file, result) {
+                logs.push(serde_json::json!({ "pod": pod, "path": path }));
+            }
+        }
+
+        let entry = serde_json::json!({
+            "namespace": namespace,
+            "manifests": manifests,
+            "events": events,
+            "logs": logs,
+            "errors": errors,
+        });
+        self.add_to_index(&test_dir, entry)?;
+
+        Ok(test_dir)
+    }
+
+    fn pods(&self, namespace: &str) -> Result<Vec<String>> {
+        let output = self.kubectl(&[
+            "get",
+            "pods",
+            "-n",
+            namespace,
+            "-o",
+            "jsonpath={.items[*].metadata.name}",
+        ])?;
+        Ok(String::from_utf8(output)?
+            .split_whitespace()
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    fn kubectl(&self, args: &[&str]) -> Result<Vec<u8>> {
+        let output = Command::new(&self.kubectl_command)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "kubectl {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(output.stdout)
+    }
+
+    fn kubectl_to_file(&self, args: &[&str], file: &Path) -> Result<()> {
+        let output = self.kubectl(args)?;
+        fs::write(file, output)?;
+        Ok(())
+    }
+
+    /// Add the `entry` of a namespace to the index of the test.
+    fn add_to_index(&self, test_dir: &Path, entry: serde_json::Value) -> Result<()> {
+        let path = test_dir.join("index.json");
+        let mut namespaces = match fs::read(&path) {
+            Ok(index) => {
+                let mut index: serde_json::Value = serde_json::from_slice(&index)?;
+                match index["namespaces"].take() {
+                    serde_json::Value::Array(namespaces) => namespaces,
+                    _ => Vec::new(),
+                }
+            }
+            Err(_) => Vec::new(),
+        };
+        namespaces.push(entry);
+
+        let index = serde_json::json!({
+            "test": test_name(),
+            "namespaces": namespaces,
+        });
+        fs::write(path, serde_json::to_vec_pretty(&index)?)?;
+        Ok(())
+    }
+}
+
+/// The name of the running test, which the test harness names its thread
+/// after.
+fn test_name() -> String {
+    thread::current()
+        .name()
+        .unwrap_or("unknown")
+        .replace("::", "__")
+}
+
+fn relative(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,44 @@
+//! Create or delete the ephemeral cluster of a test run.
+//!
+//! The cluster is configured with the process environment, see
+//! [`k8s_test_framework::cluster::Config::from_env`]:
+//!
+//! - `VECTOR_TEST_CLUSTER_PROVIDER` - `kind` or `k3d`;
+//! - `VECTOR_TEST_CLUSTER_NAME` - the name of the cluster, `vector-e2e` by
+//!   default;
+//! - `VECTOR_TEST_CLUSTER_KUBECONFIG` - the file to write the kubeconfig of
+//!   the cluster to;
+//! - `VECTOR_TEST_CLUSTER_NODE_IMAGE` - the node image to use instead of the
+//!   default one of the provider;
+//! - `VECTOR_TEST_CLUSTER_REGISTRY_PORT` - the port of the local registry,
+//!   `5000` by default, or empty to not start one.
+
+#![deny(missing_docs)]
+
+use k8s_test_framework::cluster;
+use std::{env, process};
+
+fn main() {
+    let config = match cluster::Config::from_env() {
+        Some(Ok(config)) => config,
+        Some(Err(error)) => exit(&format!("Invalid cluster configuration: {}", error)),
+        None => exit("VECTOR_TEST_CLUSTER_PROVIDER is not set"),
+    };
+
+    let result = match env::args().nth(1).as_deref() {
+        Some("up") => cluster::create(&config),
+        Some("down") => cluster::delete(&config),
+        _ => exit("Usage: k8s-test-cluster <up|down>"),
+    };
+    if let Err(error) = result {
+        exit(&format!(
+            "Failed to provision the {:?} cluster: {}",
+            config.name, error
+        ));
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
@@ -0,0 +1,326 @@
+//! Provision ephemeral clusters.
+//!
+//! Rather than running against whatever cluster the current `kubectl` context
+//! points at, a test run can create a fresh cluster with [kind] or [k3d], and
+//! delete it once done. The kubeconfig of the cluster is written to a separate
+//! file, leaving the kubeconfig of the user untouched.
+//!
+//! To make the Vector image built for the test run available to the cluster
+//! without a remote repo, a local docker registry is started alongside it,
+//! and the cluster nodes pull the images of `localhost:<port>` from it. The
+//! image is pushed to `localhost:<port>` from the host, so the same image name
+//! works both for pushing it and for deploying it.
+//!
+//! [kind]: https://kind.sigs.k8s.io
+//! [k3d]: https://k3d.io
+
+use super::{temp_file::TempFile, util::run_command_blocking, Result};
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    str::FromStr,
+};
+
+/// The tool to provision the cluster with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// [kind](https://kind.sigs.k8s.io), running the nodes as docker
+    /// containers.
+    Kind,
+    /// [k3d](https://k3d.io), running [k3s](https://k3s.io) nodes as docker
+    /// containers.
+    K3d,
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "kind" => Ok(Self::Kind),
+            "k3d" => Ok(Self::K3d),
+            _ => Err(format!(
+                "unknown cluster provider {:?}, expected `kind` or `k3d`",
+                s
+            )),
+        }
+    }
+}
+
+/// A local docker registry the cluster pulls the `localhost:<port>` images
+/// from.
+#[derive(Debug, Clone)]
+pub struct Registry {
+    /// The name of the registry container.
+    pub name: String,
+
+    /// The port of the host the registry listens on.
+    pub port: u16,
+}
+
+impl Registry {
+    /// The repo prefix of the images to push to the registry, such as
+    /// `localhost:5000`.
+    pub fn repo(&self) -> String {
+        format!("localhost:{}", self.port)
+    }
+
+    /// The endpoint of the registry within the docker network of the cluster.
+    fn endpoint(&self) -> String {
+        format!("http://{}:5000", self.name)
+    }
+}
+
+/// The parameters of the ephemeral cluster.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The tool to provision the cluster with.
+    pub provider: Provider,
+
+    /// The name of the cluster.
+    pub name: String,
+
+    /// The file to write the kubeconfig of the cluster to.
+    pub kubeconfig: PathBuf,
+
+    /// The node image to use instead of the default one of the provider, to
+    /// test against another Kubernetes version.
+    pub node_image: Option<String>,
+
+    /// The local registry to mirror, if any.
+    pub registry: Option<Registry>,
+
+    /// A `docker` command used to manage the registry.
+    pub docker_command: String,
+}
+
+impl Config {
+    /// Create a new [`Config`] with the parameters obtained from the process
+    /// environment, or `None` if no provider is specified.
+    pub fn from_env() -> Option<Result<Self>> {
+        let provider = env::var("VECTOR_TEST_CLUSTER_PROVIDER").ok()?;
+        Some(Self::from_env_with_provider(&provider))
+    }
+
+    fn from_env_with_provider(provider: &str) -> Result<Self> {
+        let provider = provider.parse()?;
+        let name = env::var("VECTOR_TEST_CLUSTER_NAME").unwrap_or_else(|_| "vector-e2e".to_owned());
+        let kubeconfig = env::var_os("VECTOR_TEST_CLUSTER_KUBECONFIG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| env::temp_dir().join(format!("{}.kubeconfig", name)));
+        let registry = match env::var("VECTOR_TEST_CLUSTER_REGISTRY_PORT").as_deref() {
+            Ok("") => None,
+            Ok(port) => Some(port.parse()?),
+            Err(_) => Some(5000),
+        }
+        .map(|port| Registry {
+            name: format!("{}-registry", name),
+            port,
+        });
+
+        Ok(Self {
+            provider,
+            name,
+            kubeconfig,
+            node_image: env::var("VECTOR_TEST_CLUSTER_NODE_IMAGE").ok(),
+            registry,
+            docker_command: env::var("VECTOR_TEST_DOCKER").unwrap_or_else(|_| "docker".to_owned()),
+        })
+    }
+
+    /// The docker network the nodes of the cluster are attached to.
+    fn network(&self) -> String {
+        match self.provider {
+            Provider::Kind => "kind".to_owned(),
+            Provider::K3d => format!("k3d-{}", self.name),
+        }
+    }
+}
+
+/// Create the cluster, along with its registry, and write its kubeconfig.
+pub fn create(config: &Config) -> Result<()> {
+    if let Some(registry) = &config.registry {
+        start_registry(config, registry)?;
+    }
+
+    match config.provider {
+        Provider::Kind => create_kind(config)?,
+        Provider::K3d => create_k3d(config)?,
+    }
+
+    if let Some(registry) = &config.registry {
+        connect_registry(config, registry)?;
+    }
+    Ok(())
+}
+
+/// Delete the cluster and its registry.
+pub fn delete(config: &Config) -> Result<()> {
+    let mut command = match config.provider {
+        Provider::Kind => {
+            let mut command = Command::new("kind");
+            command
+                .args(&["delete", "cluster", "--name", &config.name, "--kubeconfig"])
+                .arg(&config.kubeconfig);
+            command
+        }
+        Provider::K3d => {
+            let mut command = Command::new("k3d");
+            command.args(&["cluster", "delete", &config.name]);
+            command
+        }
+    };
+    command.stdin(Stdio::null());
+    let result = run_command_blocking(command);
+
+    if let Some(registry) = &config.registry {
+        let mut command = Command::new(&config.docker_command);
+        command
+            .args(&["rm", "--force", "--volumes", &registry.name])
+            .stdin(Stdio::null());
+        run_command_blocking(command)?;
+    }
+    if config.kubeconfig.exists() {
+        fs::remove_file(&config.kubeconfig)?;
+    }
+    result
+}
+
+fn create_kind(config: &Config) -> Result<()> {
+    let mut kind_config = "kind: Cluster\napiVersion: kind.x-k8s.io/v1alpha4\n".to_owned();
+    if let Some(registry) = &config.registry {
+        kind_config.push_str(&format!(
+            concat!(
+                "containerdConfigPatches:\n",
+                "- |-\n",
+                "  [plugins.\"io.containerd.grpc.v1.cri\".registry.mirrors.\"{}\"]\n",
+                "    endpoint = [\"{}\"]\n",
+            ),
+            registry.repo(),
+            registry.endpoint()
+        ));
+    }
+    let kind_config = TempFile::new("kind-config.yaml", &kind_config)?;
+
+    let mut command = Command::new("kind");
+    command
+        .args(&["create", "cluster", "--name", &config.name, "--wait", "5m"])
+        .arg("--config")
+        .arg(kind_config.path())
+        .arg("--kubeconfig")
+        .arg(&config.kubeconfig)
+        .stdin(Stdio::null());
+    if let Some(image) = &config.node_image {
+        command.args(&["--image", image]);
+    }
+    run_command_blocking(command)
+}
+
+fn create_k3d(config: &Config) -> Result<()> {
+    let registries_config = config
+        .registry
+        .as_ref()
+        .map(|registry| {
+            TempFile::new(
+                "registries.yaml",
+                &format!(
+                    "mirrors:\n  \"{}\":\n    endpoint:\n      - {}\n",
+                    registry.repo(),
+                    registry.endpoint()
+                ),
+            )
+        })
+        .transpose()?;
+
+    let mut command = Command::new("k3d");
+    command
+        .args(&[
+            "cluster",
+            "create",
+            &config.name,
+            "--wait",
+            "--kubeconfig-update-default=false",
+            "--kubeconfig-switch-context=false",
+        ])
+        .stdin(Stdio::null());
+    if let Some(image) = &config.node_image {
+        command.args(&["--image", image]);
+    }
+    if let Some(registries_config) = &registries_config {
+        command
+            .arg("--registry-config")
+            .arg(registries_config.path());
+    }
+    run_command_blocking(command)?;
+
+    let kubeconfig = output(Command::new("k3d").args(&["kubeconfig", "get", &config.name]))?;
+    fs::write(&config.kubeconfig, kubeconfig)?;
+    Ok(())
+}
+
+/// Start the registry container, unless it's already running from a previous
+/// test run.
+fn start_registry(config: &Config, registry: &Registry) -> Result<()> {
+    let running = output(Command::new(&config.docker_command).args(&[
+        "inspect",
+        "--format",
+        "{{.State.Running}}",
+        &registry.name,
+    ]));
+    if let Ok(running) = running {
+        if running.trim() == "true" {
+            return Ok(());
+        }
+    }
+
+    let mut command = Command::new(&config.docker_command);
+    command
+        .args(&[
+            "run",
+            "--detach",
+            "--restart=always",
+            "--name",
+            &registry.name,
+        ])
+        .arg("--publish")
+        .arg(format!("127.0.0.1:{}:5000", registry.port))
+        .arg("registry:2")
+        .stdin(Stdio::null());
+    run_command_blocking(command)
+}
+
+/// Attach the registry to the docker network of the cluster for the nodes to
+/// reach it.
+fn connect_registry(config: &Config, registry: &Registry) -> Result<()> {
+    let network = config.network();
+    let networks = output(Command::new(&config.docker_command).args(&[
+        "inspect",
+        "--format",
+        "{{json .NetworkSettings.Networks}}",
+        &registry.name,
+    ]))?;
+    let networks: serde_json::Value = serde_json::from_str(&networks)?;
+    if networks.get(&network).is_some() {
+        return Ok(());
+    }
+
+    let mut command = Command::new(&config.docker_command);
+    command
+        .args(&["network", "connect", &network, &registry.name])
+        .stdin(Stdio::null());
+    run_command_blocking(command)
+}
+
+fn output(command: &mut Command) -> Result<String> {
+    let output = command.stdin(Stdio::null()).output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "exec failed: {:?}: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
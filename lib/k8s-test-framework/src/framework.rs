@@ -1,21 +1,47 @@
 //! The test framework main entry point.
 
 use super::{
-    exec_tail, kubernetes_version, log_lookup, namespace, port_forward, test_pod, up_down, vector,
-    wait_for_resource, wait_for_rollout, Interface, PortForwarder, Reader, Result,
+    artifacts, exec_tail, kubernetes_version, log_lookup, namespace, port_forward, test_pod,
+    up_down, vector, wait_for_resource, wait_for_rollout, Interface, PortForwarder, Reader, Result,
 };
 
 /// Framework wraps the interface to the system with an easy-to-use rust API
 /// optimized for implementing test cases.
+///
+/// If the test fails, be it by panicking or by an operation of the framework
+/// returning an error, the state of the namespaces of the managed resources
+/// is collected into the artifacts directory of the interface before they are
+/// turned down.
 #[derive(Debug)]
 pub struct Framework {
     interface: Interface,
+    artifacts: artifacts::Collector,
 }
 
 impl Framework {
     /// Create a new [`Framework`] powered by the passed interface.
     pub fn new(interface: Interface) -> Self {
-        Self { interface }
+        let artifacts =
+            artifacts::Collector::new(&interface.kubectl_command, &interface.artifacts_dir);
+        Self {
+            interface,
+            artifacts,
+        }
+    }
+
+    /// Mark the test as failed if the `result` of an operation is an error.
+    fn track<T>(&self, result: Result<T>) -> Result<T> {
+        if result.is_err() {
+            self.artifacts.mark_failed();
+        }
+        result
+    }
+
+    /// Collect the state of the `namespace` into the artifacts directory,
+    /// regardless of whether the test has failed.
+    /// Returns the directory the artifacts of the test are written to.
+    pub fn collect_artifacts(&self, namespace: &str) -> Result<std::path::PathBuf> {
+        self.artifacts.collect(namespace)
     }
 
     /// Deploy `vector` into a cluster.
@@ -25,13 +51,15 @@ impl Framework {
         helm_chart: &str,
         config: vector::Config<'_>,
     ) -> Result<up_down::Manager<vector::CommandBuilder>> {
-        let mut manager = vector::manager(
-            self.interface.deploy_vector_command.as_str(),
-            namespace,
-            helm_chart,
-            config,
-        )?;
-        manager.up().await?;
+        let mut manager = self
+            .track(vector::manager(
+                self.interface.deploy_vector_command.as_str(),
+                namespace,
+                helm_chart,
+                config,
+            ))?
+            .with_artifacts(self.artifacts.clone(), namespace);
+        self.track(manager.up().await)?;
         Ok(manager)
     }
 
@@ -40,8 +68,9 @@ impl Framework {
         &self,
         namespace: &str,
     ) -> Result<up_down::Manager<namespace::CommandBuilder>> {
-        let mut manager = namespace::manager(&self.interface.kubectl_command, namespace);
-        manager.up().await?;
+        let mut manager = namespace::manager(&self.interface.kubectl_command, namespace)
+            .with_artifacts(self.artifacts.clone(), namespace);
+        self.track(manager.up().await)?;
         Ok(manager)
     }
 
@@ -50,8 +79,14 @@ impl Framework {
         &self,
         config: test_pod::Config,
     ) -> Result<up_down::Manager<test_pod::CommandBuilder>> {
+        let namespace = config.namespace().map(ToOwned::to_owned);
         let mut manager = test_pod::manager(&self.interface.kubectl_command, config);
-        manager.up().await?;
+        if let Some(namespace) = namespace {
+            // Collect the namespace while the test pod is still there to
+            // read the logs of.
+            manager = manager.with_artifacts(self.artifacts.clone(), &namespace);
+        }
+        self.track(manager.up().await)?;
         Ok(manager)
     }
 
@@ -102,14 +137,16 @@ impl Framework {
         wait_for: wait_for_resource::WaitFor<&'_ str>,
         extra: impl IntoIterator<Item = &'a str>,
     ) -> Result<()> {
-        wait_for_resource::namespace(
-            &self.interface.kubectl_command,
-            namespace,
-            resources,
-            wait_for,
-            extra,
+        self.track(
+            wait_for_resource::namespace(
+                &self.interface.kubectl_command,
+                namespace,
+                resources,
+                wait_for,
+                extra,
+            )
+            .await,
         )
-        .await
     }
 
     /// Wait for a set of `resources` in any namespace to achieve `wait_for`
@@ -121,13 +158,15 @@ impl Framework {
         wait_for: wait_for_resource::WaitFor<&'_ str>,
         extra: impl IntoIterator<Item = &'a str>,
     ) -> Result<()> {
-        wait_for_resource::all_namespaces(
-            &self.interface.kubectl_command,
-            resources,
-            wait_for,
-            extra,
+        self.track(
+            wait_for_resource::all_namespaces(
+                &self.interface.kubectl_command,
+                resources,
+                wait_for,
+                extra,
+            )
+            .await,
         )
-        .await
     }
 
     /// Wait for a rollout of a `resource` to complete.
@@ -138,6 +177,9 @@ impl Framework {
         resource: &str,
         extra: impl IntoIterator<Item = &'a str>,
     ) -> Result<()> {
-        wait_for_rollout::run(&self.interface.kubectl_command, namespace, resource, extra).await
+        self.track(
+            wait_for_rollout::run(&self.interface.kubectl_command, namespace, resource, extra)
+                .await,
+        )
     }
 }
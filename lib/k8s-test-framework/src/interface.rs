@@ -1,6 +1,6 @@
 //! An interface into the system.
 
-use std::env;
+use std::{env, path::PathBuf};
 
 /// An interface between the test framework and external CLI commands and test
 /// utilities.
@@ -12,6 +12,10 @@ pub struct Interface {
 
     /// A `kubectl` command used for generic cluster interaction.
     pub kubectl_command: String,
+
+    /// A directory to collect the state of the cluster into when a test
+    /// fails.
+    pub artifacts_dir: PathBuf,
 }
 
 impl Interface {
@@ -22,6 +26,9 @@ impl Interface {
             deploy_vector_command: env::var("KUBE_TEST_DEPLOY_COMMAND").ok()?,
             kubectl_command: env::var("VECTOR_TEST_KUBECTL")
                 .unwrap_or_else(|_| "kubectl".to_owned()),
+            artifacts_dir: env::var_os("VECTOR_TEST_ARTIFACTS_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| env::temp_dir().join("vector-k8s-e2e-artifacts")),
         })
     }
 }
@@ -15,6 +15,8 @@
     missing_docs
 )]
 
+pub mod artifacts;
+pub mod cluster;
 mod exec_tail;
 pub mod framework;
 mod helm_values_file;
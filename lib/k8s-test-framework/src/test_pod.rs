@@ -9,12 +9,15 @@ use std::process::{Command, Stdio};
 #[derive(Debug)]
 pub struct Config {
     test_pod_resource_file: ResourceFile,
+    namespace: Option<String>,
 }
 
 impl Config {
     /// Create a [`Config`] using a structured [`Pod`] object.
     pub fn from_pod(pod: &Pod) -> Result<Self> {
-        Self::from_resource_string(serde_json::to_string(pod)?.as_str())
+        let mut config = Self::from_resource_string(serde_json::to_string(pod)?.as_str())?;
+        config.namespace = pod.metadata.namespace.clone();
+        Ok(config)
     }
 
     /// Create a [`Config`] using an unstructured resource string.
@@ -22,8 +25,14 @@ impl Config {
         let test_pod_resource_file = ResourceFile::new(resource)?;
         Ok(Self {
             test_pod_resource_file,
+            namespace: None,
         })
     }
+
+    /// The namespace of the test `Pod`, if it's known.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
 }
 
 /// Parameters required to build a `kubectl` command to manage the test `Pod`.
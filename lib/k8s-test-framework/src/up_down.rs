@@ -1,4 +1,4 @@
-use super::Result;
+use super::{artifacts::Collector, Result};
 use crate::util::{run_command, run_command_blocking};
 use std::process::Command;
 
@@ -19,6 +19,7 @@ where
 {
     command_builder: B,
     needs_drop: bool,
+    artifacts: Option<(Collector, String)>,
 }
 
 impl<B> Manager<B>
@@ -29,9 +30,17 @@ where
         Self {
             command_builder,
             needs_drop: false,
+            artifacts: None,
         }
     }
 
+    /// Collect the artifacts of the `namespace` with the `collector` before
+    /// turning down, if the test has failed.
+    pub fn with_artifacts(mut self, collector: Collector, namespace: &str) -> Self {
+        self.artifacts = Some((collector, namespace.to_owned()));
+        self
+    }
+
     pub async fn up(&mut self) -> Result<()> {
         self.needs_drop = true;
         self.exec(CommandToBuild::Up).await
@@ -73,6 +82,18 @@ where
 {
     fn drop(&mut self) {
         if self.needs_drop {
+            if let Some((collector, namespace)) = &self.artifacts {
+                if collector.has_failed() {
+                    match collector.collect(namespace) {
+                        Ok(dir) => {
+                            eprintln!("Collected artifacts of {:?} into {:?}", namespace, dir)
+                        }
+                        Err(error) => {
+                            eprintln!("Failed to collect artifacts of {:?}: {}", namespace, error)
+                        }
+                    }
+                }
+            }
             self.down_blocking().expect("turndown failed");
         }
     }
@@ -2,11 +2,13 @@ use snafu::ResultExt;
 use std::collections::BTreeMap;
 
 mod line;
+mod serialize;
 
 pub use line::ErrorKind;
 use line::Line;
 use line::Metric;
 use line::MetricKind;
+pub use serialize::{serialize, Format};
 
 #[derive(Debug, snafu::Snafu, PartialEq)]
 pub enum ParserError {
@@ -123,12 +123,13 @@ impl Metric {
         ))
     }
 
-    /// Float value, and +Inf, -Int, Nan.
+    /// Float value, and +Inf, -Int, NaN.
     pub fn parse_value(input: &str) -> IResult<f64> {
         let input = trim_space(input);
         alt((
             value(f64::INFINITY, tag("+Inf")),
             value(f64::NEG_INFINITY, tag("-Inf")),
+            value(f64::NAN, tag("NaN")),
             value(f64::NAN, tag("Nan")),
             double,
         ))(input)
@@ -550,6 +551,11 @@ mod test {
         assert_eq!(left, tail);
         assert!(r.is_nan());
 
+        let input = wrap("NaN");
+        let (left, r) = Metric::parse_value(&input).unwrap();
+        assert_eq!(left, tail);
+        assert!(r.is_nan());
+
         let tests = [
             ("0", 0.0f64),
             ("0.25", 0.25f64),
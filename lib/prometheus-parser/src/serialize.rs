@@ -0,0 +1,262 @@
+//! Renders metric groups back into the text exposition format, with the
+//! groups sorted by name and their metrics by labels, so the same metrics
+//! always render the same text.
+
+use crate::{GroupKind, HistogramMetricValue, MetricGroup, OtherMetric, SummaryMetricValue};
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Write};
+
+type Labels = BTreeMap<String, String>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// The text format of Prometheus, version 0.0.4.
+    Prometheus,
+    /// The text format of OpenMetrics, which names counter samples with a
+    /// `_total` suffix and ends with `# EOF`.
+    OpenMetrics,
+}
+
+impl Format {
+    /// The `Content-Type` of responses exposing metrics in the format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Prometheus => "text/plain; version=0.0.4",
+            Format::OpenMetrics => "application/openmetrics-text; version=1.0.0; charset=utf-8",
+        }
+    }
+}
+
+/// The buckets or quantiles of one series of a histogram or summary.
+#[derive(Default)]
+struct Series {
+    points: Vec<(f64, f64)>,
+    sum: Option<f64>,
+    count: Option<u32>,
+}
+
+pub fn serialize(groups: &[MetricGroup], format: Format) -> String {
+    let mut groups = groups.iter().collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut output = String::new();
+    for group in groups {
+        write_group(&mut output, group, format);
+    }
+    if format == Format::OpenMetrics {
+        output.push_str("# EOF\n");
+    }
+    output
+}
+
+fn write_group(output: &mut String, group: &MetricGroup, format: Format) {
+    let name = group.name.as_str();
+    match &group.metrics {
+        GroupKind::Counter(metrics) => match format {
+            Format::Prometheus => write_others(output, name, "counter", name, metrics),
+            Format::OpenMetrics => {
+                let family = name.strip_suffix("_total").unwrap_or(name);
+                let sample = format!("{}_total", family);
+                write_others(output, family, "counter", &sample, metrics)
+            }
+        },
+        GroupKind::Gauge(metrics) => write_others(output, name, "gauge", name, metrics),
+        GroupKind::Untyped(metrics) => {
+            let kind = match format {
+                Format::Prometheus => "untyped",
+                Format::OpenMetrics => "unknown",
+            };
+            write_others(output, name, kind, name, metrics)
+        }
+        GroupKind::Histogram(metrics) => {
+            let mut series = BTreeMap::<&Labels, Series>::new();
+            for metric in metrics {
+                let series = series.entry(&metric.labels).or_default();
+                match metric.value {
+                    HistogramMetricValue::Bucket { bucket, count } => {
+                        series.points.push((bucket, count as f64))
+                    }
+                    HistogramMetricValue::Sum { sum } => series.sum = Some(sum),
+                    HistogramMetricValue::Count { count } => series.count = Some(count),
+                }
+            }
+            write_type(output, name, "histogram");
+            write_series(output, &format!("{}_bucket", name), "le", name, series);
+        }
+        GroupKind::Summary(metrics) => {
+            let mut series = BTreeMap::<&Labels, Series>::new();
+            for metric in metrics {
+                let series = series.entry(&metric.labels).or_default();
+                match metric.value {
+                    SummaryMetricValue::Quantile { quantile, value } => {
+                        series.points.push((quantile, value))
+                    }
+                    SummaryMetricValue::Sum { sum } => series.sum = Some(sum),
+                    SummaryMetricValue::Count { count } => series.count = Some(count),
+                }
+            }
+            write_type(output, name, "summary");
+            write_series(output, name, "quantile", name, series);
+        }
+    }
+}
+
+fn write_type(output: &mut String, name: &str, kind: &str) {
+    let _ = writeln!(output, "# TYPE {} {}", name, kind);
+}
+
+fn write_others(
+    output: &mut String,
+    family: &str,
+    kind: &str,
+    sample: &str,
+    metrics: &[OtherMetric],
+) {
+    let mut metrics = metrics.iter().collect::<Vec<_>>();
+    metrics.sort_by(|a, b| a.labels.cmp(&b.labels));
+
+    write_type(output, family, kind);
+    for metric in metrics {
+        write_sample(output, sample, &metric.labels, None, metric.value);
+    }
+}
+
+fn write_series(
+    output: &mut String,
+    sample: &str,
+    point_label: &str,
+    name: &str,
+    series: BTreeMap<&Labels, Series>,
+) {
+    for (labels, mut series) in series {
+        series
+            .points
+            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        for (point, value) in series.points {
+            write_sample(output, sample, labels, Some((point_label, point)), value);
+        }
+        if let Some(sum) = series.sum {
+            write_sample(output, &format!("{}_sum", name), labels, None, sum);
+        }
+        if let Some(count) = series.count {
+            let count = count as f64;
+            write_sample(output, &format!("{}_count", name), labels, None, count);
+        }
+    }
+}
+
+fn write_sample(
+    output: &mut String,
+    name: &str,
+    labels: &Labels,
+    extra: Option<(&str, f64)>,
+    value: f64,
+) {
+    output.push_str(name);
+    if !labels.is_empty() || extra.is_some() {
+        let labels = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+            .chain(extra.map(|(key, value)| format!("{}=\"{}\"", key, format_value(value))))
+            .collect::<Vec<_>>();
+        let _ = write!(output, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(output, " {}", format_value(value));
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value == f64::INFINITY {
+        "+Inf".into()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".into()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group_metrics;
+
+    const INPUT: &str = r##"
+        # TYPE rpc_duration_seconds summary
+        rpc_duration_seconds{service="b",quantile="0.5"} 4773
+        rpc_duration_seconds{service="a",quantile="0.99"} 76656
+        rpc_duration_seconds{service="a",quantile="0.5"} 4773
+        rpc_duration_seconds_sum{service="a"} 1.7560473e+07
+        rpc_duration_seconds_count{service="a"} 2693
+        # TYPE http_request_duration_seconds histogram
+        http_request_duration_seconds_bucket{le="+Inf"} 144320
+        http_request_duration_seconds_bucket{le="0.05"} 24054
+        http_request_duration_seconds_sum 53423
+        http_request_duration_seconds_count 144320
+        # TYPE http_requests_total counter
+        http_requests_total{method="post",code="400"} 3
+        http_requests_total{method="post",code="200"} 1027
+        msdos_file_access_time_seconds{error="Cannot find file:\n\"FILE.TXT\"",path="C:\\DIR\\FILE.TXT"} 1.458255915e9
+        "##;
+
+    #[test]
+    fn serializes_prometheus_text() {
+        let groups = group_metrics(INPUT).unwrap();
+        assert_eq!(
+            serialize(&groups, Format::Prometheus),
+            r##"# TYPE http_request_duration_seconds histogram
+http_request_duration_seconds_bucket{le="0.05"} 24054
+http_request_duration_seconds_bucket{le="+Inf"} 144320
+http_request_duration_seconds_sum 53423
+http_request_duration_seconds_count 144320
+# TYPE http_requests_total counter
+http_requests_total{code="200",method="post"} 1027
+http_requests_total{code="400",method="post"} 3
+# TYPE msdos_file_access_time_seconds untyped
+msdos_file_access_time_seconds{error="Cannot find file:\n\"FILE.TXT\"",path="C:\\DIR\\FILE.TXT"} 1458255915
+# TYPE rpc_duration_seconds summary
+rpc_duration_seconds{service="a",quantile="0.5"} 4773
+rpc_duration_seconds{service="a",quantile="0.99"} 76656
+rpc_duration_seconds_sum{service="a"} 17560473
+rpc_duration_seconds_count{service="a"} 2693
+rpc_duration_seconds{service="b",quantile="0.5"} 4773
+"##
+        );
+    }
+
+    #[test]
+    fn serializes_openmetrics_text() {
+        let groups = group_metrics(
+            r##"
+            # TYPE http_requests_total counter
+            http_requests_total{code="200"} 1027
+            # TYPE temperature gauge
+            temperature NaN
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            serialize(&groups, Format::OpenMetrics),
+            r##"# TYPE http_requests counter
+http_requests_total{code="200"} 1027
+# TYPE temperature gauge
+temperature NaN
+# EOF
+"##
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let groups = group_metrics(INPUT).unwrap();
+        let text = serialize(&groups, Format::Prometheus);
+        let parsed = group_metrics(&text).unwrap();
+        assert_eq!(serialize(&parsed, Format::Prometheus), text);
+    }
+}
@@ -32,6 +32,9 @@ pub enum Error {
     #[error("assertion failed: {0}")]
     Assert(String),
 
+    #[error("program aborted")]
+    Abort(value::Value),
+
     #[error("path error")]
     Path(#[from] path::Error),
 
@@ -125,6 +128,17 @@ impl fmt::Display for Rule {
 #[derive(Debug, PartialEq)]
 pub struct RemapError(Error);
 
+impl RemapError {
+    /// The reason given to `abort`, if the program was aborted rather than
+    /// failed. The reason of programs aborted without one is null.
+    pub fn abort_reason(&self) -> Option<&value::Value> {
+        match &self.0 {
+            Error::Abort(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
 impl StdError for RemapError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         Some(&self.0)
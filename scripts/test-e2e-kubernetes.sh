@@ -43,6 +43,42 @@ is_minikube_cache_enabled() {
   fi
 }
 
+# Assign a default test run ID if none is provided by the user.
+TEST_RUN_ID="${TEST_RUN_ID:-"$(date +%s)-$(random-string)"}"
+
+# Provision an ephemeral cluster for the test run if a provider is specified.
+# The cluster is created with `kind` or `k3d` before the tests and deleted
+# after them, instead of using the cluster of the current `kubectl` context.
+# The cluster pulls `localhost:<port>` images from a local registry started
+# alongside it, so the test image is pushed there instead of a remote repo.
+if [[ -n "${CLUSTER_PROVIDER:-}" ]]; then
+  export VECTOR_TEST_CLUSTER_PROVIDER="$CLUSTER_PROVIDER"
+  export VECTOR_TEST_CLUSTER_NAME="${VECTOR_TEST_CLUSTER_NAME:-"vector-e2e-$TEST_RUN_ID"}"
+  export VECTOR_TEST_CLUSTER_KUBECONFIG="${VECTOR_TEST_CLUSTER_KUBECONFIG:-"$(pwd)/target/$VECTOR_TEST_CLUSTER_NAME.kubeconfig"}"
+  export VECTOR_TEST_CLUSTER_REGISTRY_PORT="${VECTOR_TEST_CLUSTER_REGISTRY_PORT:-"5000"}"
+
+  CLUSTER_MANIFEST_PATH="$(pwd)/lib/k8s-test-framework/Cargo.toml"
+  k8s-test-cluster() {
+    cargo run --quiet --manifest-path "$CLUSTER_MANIFEST_PATH" --bin k8s-test-cluster -- "$@"
+  }
+
+  mkdir -p target
+  k8s-test-cluster up
+  if [[ -z "${KEEP_CLUSTER:-}" ]]; then
+    trap 'k8s-test-cluster down' EXIT
+  fi
+
+  # Point `kubectl`, and the deployment command, to the new cluster.
+  export KUBECONFIG="$VECTOR_TEST_CLUSTER_KUBECONFIG"
+
+  # The image is pushed to the local registry rather than loaded into
+  # minikube.
+  USE_MINIKUBE_CACHE="false"
+  if [[ -n "$VECTOR_TEST_CLUSTER_REGISTRY_PORT" ]]; then
+    CONTAINER_IMAGE_REPO="${CONTAINER_IMAGE_REPO:-"localhost:$VECTOR_TEST_CLUSTER_REGISTRY_PORT/vector-test"}"
+  fi
+fi
+
 # Build a docker image if it wasn't provided.
 if [[ -z "${CONTAINER_IMAGE:-}" ]]; then
   # Require a repo to put the container image at.
@@ -72,9 +108,6 @@ if [[ -z "${CONTAINER_IMAGE:-}" ]]; then
     CONTAINER_IMAGE_REPO="${CONTAINER_IMAGE_REPO:?"You have to specify CONTAINER_IMAGE_REPO to upload the test image to."}"
   fi
 
-  # Assign a default test run ID if none is provided by the user.
-  TEST_RUN_ID="${TEST_RUN_ID:-"$(date +%s)-$(random-string)"}"
-
   if [[ "${QUICK_BUILD:-"false"}" == "true" ]]; then
     # Build in debug mode.
     cargo build
@@ -129,6 +162,10 @@ export CONTAINER_IMAGE
 KUBE_TEST_DEPLOY_COMMAND="$(pwd)/scripts/deploy-kubernetes-test.sh"
 export KUBE_TEST_DEPLOY_COMMAND
 
+# Set the directory to collect the cluster state of failed tests into.
+VECTOR_TEST_ARTIFACTS_DIR="${VECTOR_TEST_ARTIFACTS_DIR:-"$(pwd)/target/k8s-e2e-artifacts"}"
+export VECTOR_TEST_ARTIFACTS_DIR
+
 # Prepare args.
 CARGO_TEST_ARGS_CARGO=()
 CARGO_TEST_ARGS_PASSED=()
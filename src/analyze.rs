@@ -0,0 +1,395 @@
+use crate::{
+    dry_run::parse_event,
+    event::{LogEvent, Value},
+};
+use bytes::Bytes;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// File of sample events, one per line, or `-` to read them from stdin.
+    /// Lines holding a JSON object become log events with its fields, other
+    /// lines log events with the line as their message.
+    #[structopt(default_value = "-")]
+    input: PathBuf,
+
+    /// Analyze at most this many events.
+    #[structopt(short = "n", long)]
+    limit: Option<usize>,
+
+    /// Number of distinct values to count per field, beyond which the
+    /// cardinality of the field is reported as a lower bound.
+    #[structopt(long, default_value = "10000")]
+    max_distinct: usize,
+
+    /// Print the report as JSON.
+    #[structopt(long)]
+    json: bool,
+}
+
+/// Reads the sample events and reports the types, cardinality, null rate and
+/// size distribution of each of their fields.
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let input: Box<dyn BufRead> = if opts.input.as_os_str() == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        match File::open(&opts.input) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(error) => {
+                error!(message = "Failed to open events.", path = ?opts.input, %error);
+                return exitcode::NOINPUT;
+            }
+        }
+    };
+
+    let mut analysis = Analysis::new(opts.max_distinct);
+    for line in input.lines() {
+        if Some(analysis.events) == opts.limit {
+            break;
+        }
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                error!(message = "Failed to read events.", path = ?opts.input, %error);
+                return exitcode::IOERR;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        analysis.add(parse_event(line).as_log());
+    }
+
+    let report = analysis.report();
+    if opts.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("Reports serialize to JSON.")
+        );
+    } else {
+        print_report(&report);
+    }
+
+    exitcode::OK
+}
+
+#[derive(Debug, Default)]
+struct FieldStats {
+    present: usize,
+    last_event: usize,
+    values: usize,
+    nulls: usize,
+    types: BTreeMap<String, usize>,
+    distinct: HashSet<Bytes>,
+    distinct_capped: bool,
+    sizes: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct Analysis {
+    events: usize,
+    max_distinct: usize,
+    fields: BTreeMap<String, FieldStats>,
+}
+
+impl Analysis {
+    fn new(max_distinct: usize) -> Self {
+        Self {
+            events: 0,
+            max_distinct,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, log: &LogEvent) {
+        self.events += 1;
+        for (path, value) in log.all_fields() {
+            let stats = self.fields.entry(normalize_path(&path)).or_default();
+            // The elements of arrays share a path, but count once per event.
+            if stats.last_event != self.events {
+                stats.last_event = self.events;
+                stats.present += 1;
+            }
+
+            stats.values += 1;
+            *stats.types.entry(value.kind().to_owned()).or_default() += 1;
+            if let Value::Null = value {
+                stats.nulls += 1;
+                continue;
+            }
+
+            let bytes = value.as_bytes();
+            stats.sizes.push(bytes.len());
+            if stats.distinct.len() < self.max_distinct {
+                stats.distinct.insert(bytes);
+            } else if !stats.distinct.contains(&bytes) {
+                stats.distinct_capped = true;
+            }
+        }
+    }
+
+    fn report(mut self) -> Report {
+        let events = self.events;
+        let fields = self
+            .fields
+            .iter_mut()
+            .map(|(path, stats)| {
+                stats.sizes.sort_unstable();
+                FieldReport {
+                    path: path.clone(),
+                    types: stats
+                        .types
+                        .iter()
+                        .map(|(kind, count)| (kind.clone(), ratio(*count, stats.values)))
+                        .collect(),
+                    present: ratio(stats.present, events),
+                    null: ratio(stats.nulls, stats.values),
+                    distinct: stats.distinct.len(),
+                    distinct_capped: stats.distinct_capped,
+                    size: SizeReport::new(&stats.sizes),
+                }
+            })
+            .collect();
+        Report { events, fields }
+    }
+}
+
+/// Replaces the indices of the path with `[]`, as the elements of arrays
+/// are the same field for the purpose of the schema.
+fn normalize_path(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+    let mut in_index = false;
+    for c in path.chars() {
+        match c {
+            '[' => {
+                in_index = true;
+                normalized.push_str("[]");
+            }
+            ']' if in_index => in_index = false,
+            _ if in_index => (),
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    events: usize,
+    fields: Vec<FieldReport>,
+}
+
+#[derive(Debug, Serialize)]
+struct FieldReport {
+    path: String,
+    /// The share of the values of each type.
+    types: BTreeMap<String, f64>,
+    /// The share of the events holding the field.
+    present: f64,
+    /// The share of the values being null.
+    null: f64,
+    distinct: usize,
+    /// Whether there are more distinct values than counted.
+    distinct_capped: bool,
+    /// The distribution of the sizes of the values, in bytes, if there are
+    /// any that aren't null.
+    size: Option<SizeReport>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct SizeReport {
+    min: usize,
+    p50: usize,
+    p90: usize,
+    p99: usize,
+    max: usize,
+}
+
+impl SizeReport {
+    /// The distribution of `sizes`, which must be sorted.
+    fn new(sizes: &[usize]) -> Option<Self> {
+        let quantile = |q: f64| sizes[((sizes.len() - 1) as f64 * q).round() as usize];
+        if sizes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            min: sizes[0],
+            p50: quantile(0.5),
+            p90: quantile(0.9),
+            p99: quantile(0.99),
+            max: sizes[sizes.len() - 1],
+        })
+    }
+}
+
+fn print_report(report: &Report) {
+    println!("Analyzed {} events.", report.events);
+    if report.fields.is_empty() {
+        return;
+    }
+    println!();
+
+    let percent = |ratio: f64| format!("{:.1}%", ratio * 100.0);
+    let rows = report
+        .fields
+        .iter()
+        .map(|field| {
+            let mut types = field.types.iter().collect::<Vec<_>>();
+            types.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal));
+            let types = if types.len() == 1 {
+                types[0].0.clone()
+            } else {
+                types
+                    .iter()
+                    .map(|(kind, ratio)| format!("{} {}", kind, percent(**ratio)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            vec![
+                field.path.clone(),
+                types,
+                percent(field.present),
+                percent(field.null),
+                if field.distinct_capped {
+                    format!(">{}", field.distinct)
+                } else {
+                    field.distinct.to_string()
+                },
+                field.size.as_ref().map_or_else(
+                    || "-".to_owned(),
+                    |size| {
+                        format!(
+                            "{}/{}/{}/{}/{}",
+                            size.min, size.p50, size.p90, size.p99, size.max
+                        )
+                    },
+                ),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let header = [
+        "FIELD",
+        "TYPES",
+        "PRESENT",
+        "NULL",
+        "DISTINCT",
+        "SIZE (MIN/P50/P90/P99/MAX)",
+    ];
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain(Some(title.len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let print_row = |cells: &[&str]| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = *width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+
+    print_row(&header);
+    for row in &rows {
+        print_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(lines: &[&str], max_distinct: usize) -> Report {
+        let mut analysis = Analysis::new(max_distinct);
+        for line in lines {
+            analysis.add(parse_event(line.to_string()).as_log());
+        }
+        analysis.report()
+    }
+
+    fn field<'a>(report: &'a Report, path: &str) -> &'a FieldReport {
+        report
+            .fields
+            .iter()
+            .find(|field| field.path == path)
+            .unwrap()
+    }
+
+    #[test]
+    fn analyzes_fields() {
+        let report = analyze(
+            &[
+                r#"{"status": 200, "user": "alice", "tags": ["a", "b"]}"#,
+                r#"{"status": 404, "user": null, "tags": ["a"]}"#,
+                r#"{"status": "ok"}"#,
+                r#"{"status": 200, "user": "bob"}"#,
+            ],
+            100,
+        );
+        assert_eq!(report.events, 4);
+
+        let status = field(&report, "status");
+        assert_eq!(status.present, 1.0);
+        assert_eq!(status.types["integer"], 0.75);
+        assert_eq!(status.types["string"], 0.25);
+        assert_eq!(status.distinct, 3);
+        assert_eq!(
+            status.size,
+            Some(SizeReport {
+                min: 2,
+                p50: 3,
+                p90: 3,
+                p99: 3,
+                max: 3,
+            })
+        );
+
+        let user = field(&report, "user");
+        assert_eq!(user.present, 0.75);
+        assert!((user.null - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(user.distinct, 2);
+
+        let tags = field(&report, "tags[]");
+        assert_eq!(tags.present, 0.5);
+        assert_eq!(tags.distinct, 2);
+        assert_eq!(tags.size.as_ref().map(|size| size.max), Some(1));
+    }
+
+    #[test]
+    fn caps_distinct_values() {
+        let report = analyze(&["a", "b", "c", "a"], 2);
+        let message = field(&report, "message");
+        assert_eq!(message.distinct, 2);
+        assert!(message.distinct_capped);
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize_path("a[0].b[12]"), "a[].b[]");
+        assert_eq!(normalize_path("a.b"), "a.b");
+    }
+}
@@ -344,42 +344,60 @@ fn get_component_names() -> HashSet<String> {
 
 /// Update the 'global' configuration that will be consumed by component queries
 pub fn update_config(config: &Config) {
+    update_configs(&[config])
+}
+
+/// Update the 'global' configuration with the configs of all the pipelines of
+/// the process, whose components are named after their pipeline.
+pub fn update_configs(configs: &[&Config]) {
     let mut new_components = HashMap::new();
 
-    // Sources
-    for (name, source) in config.sources.iter() {
-        new_components.insert(
-            name.to_owned(),
-            Component::Source(Source(SourceData {
-                name: name.to_owned(),
-                component_type: source.source_type().to_string(),
-                output_type: source.output_type(),
-            })),
-        );
-    }
+    for config in configs {
+        let inputs = |inputs: &[String]| {
+            inputs
+                .iter()
+                .map(|input| config.qualified_name(input))
+                .collect::<Vec<_>>()
+        };
+
+        // Sources
+        for (name, source) in config.sources.iter() {
+            let name = config.qualified_name(name);
+            new_components.insert(
+                name.clone(),
+                Component::Source(Source(SourceData {
+                    name,
+                    component_type: source.inner.source_type().to_string(),
+                    output_type: source.inner.output_type(),
+                })),
+            );
+        }
 
-    // Transforms
-    for (name, transform) in config.transforms.iter() {
-        new_components.insert(
-            name.to_string(),
-            Component::Transform(Transform(TransformData {
-                name: name.to_owned(),
-                component_type: transform.inner.transform_type().to_string(),
-                inputs: transform.inputs.clone(),
-            })),
-        );
-    }
+        // Transforms
+        for (name, transform) in config.transforms.iter() {
+            let name = config.qualified_name(name);
+            new_components.insert(
+                name.clone(),
+                Component::Transform(Transform(TransformData {
+                    name,
+                    component_type: transform.inner.transform_type().to_string(),
+                    inputs: inputs(&transform.inputs),
+                })),
+            );
+        }
 
-    // Sinks
-    for (name, sink) in config.sinks.iter() {
-        new_components.insert(
-            name.to_string(),
-            Component::Sink(Sink(SinkData {
-                name: name.to_owned(),
-                component_type: sink.inner.sink_type().to_string(),
-                inputs: sink.inputs.clone(),
-            })),
-        );
+        // Sinks
+        for (name, sink) in config.sinks.iter() {
+            let name = config.qualified_name(name);
+            new_components.insert(
+                name.clone(),
+                Component::Sink(Sink(SinkData {
+                    name,
+                    component_type: sink.inner.sink_type().to_string(),
+                    inputs: inputs(&sink.inputs),
+                })),
+            );
+        }
     }
 
     // Get the names of existing components
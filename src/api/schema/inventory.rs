@@ -0,0 +1,71 @@
+use crate::config::{
+    component::ComponentOption, SinkDescription, SourceDescription, TransformDescription,
+};
+use async_graphql::{Json, Object, SimpleObject};
+
+#[derive(SimpleObject)]
+pub struct ComponentTypeOption {
+    /// Option name
+    name: String,
+
+    /// TOML type of the option, such as `string` or `table`
+    value_type: String,
+
+    /// Value of the option in the example config, which is its default for most components
+    example: Option<Json<serde_json::Value>>,
+
+    /// Options of a table
+    options: Vec<ComponentTypeOption>,
+}
+
+impl From<ComponentOption> for ComponentTypeOption {
+    fn from(option: ComponentOption) -> Self {
+        Self {
+            name: option.name,
+            value_type: option.value_type.to_owned(),
+            example: option.example.map(Json),
+            options: option.options.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentType {
+    /// Component type, such as `http` or `remap`
+    name: String,
+
+    /// Options of the example config of the component
+    options: Vec<ComponentTypeOption>,
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentTypes {
+    sources: Vec<ComponentType>,
+    transforms: Vec<ComponentType>,
+    sinks: Vec<ComponentType>,
+}
+
+fn component_types(types: Vec<(&'static str, Vec<ComponentOption>)>) -> Vec<ComponentType> {
+    types
+        .into_iter()
+        .map(|(name, options)| ComponentType {
+            name: name.to_owned(),
+            options: options.into_iter().map(Into::into).collect(),
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct InventoryQuery;
+
+#[Object]
+impl InventoryQuery {
+    /// Component types compiled into Vector, with the options of their example configs
+    async fn component_types(&self) -> ComponentTypes {
+        ComponentTypes {
+            sources: component_types(SourceDescription::types_with_options()),
+            transforms: component_types(TransformDescription::types_with_options()),
+            sinks: component_types(SinkDescription::types_with_options()),
+        }
+    }
+}
@@ -61,3 +61,44 @@ impl ComponentErrorsTotal {
         ErrorsTotal::new(self.metric.clone())
     }
 }
+
+pub struct ComponentError {
+    name: String,
+    code: String,
+    count: f64,
+    timestamp: DateTime<Utc>,
+}
+
+impl ComponentError {
+    pub fn new(name: String, code: String, count: f64) -> Self {
+        Self {
+            name,
+            code,
+            count,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+#[Object]
+impl ComponentError {
+    /// Component name
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Error type, or the name of the error counter for errors without a type
+    async fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Errors counted since the last emission of the error
+    async fn count(&self) -> i64 {
+        self.count as i64
+    }
+
+    /// Emission timestamp
+    async fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
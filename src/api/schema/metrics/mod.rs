@@ -20,7 +20,7 @@ use tokio::{
     time::Duration,
 };
 
-pub use errors::{ComponentErrorsTotal, ErrorsTotal};
+pub use errors::{ComponentError, ComponentErrorsTotal, ErrorsTotal};
 pub use host::HostMetrics;
 pub use processed_bytes::{
     ComponentProcessedBytesThroughput, ComponentProcessedBytesTotal, ProcessedBytesTotal,
@@ -187,6 +187,20 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component errors, deduplicated over `window` milliseconds. Yields the
+    /// errors of each window with errors, counted by component and type.
+    async fn component_errors(
+        &self,
+        #[graphql(default = 10_000, validator(IntRange(min = "100", max = "600_000")))] window: i32,
+    ) -> impl Stream<Item = Vec<ComponentError>> {
+        component_error_counts(window).map(|errors| {
+            errors
+                .into_iter()
+                .map(|((name, code), count)| ComponentError::new(name, code, count))
+                .collect()
+        })
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
@@ -312,6 +326,46 @@ pub fn component_counter_metrics(
     })
 }
 
+/// Returns a stream of the errors of components, counted by component and error type as the
+/// increase of their `*_errors_total` counters over each `window` milliseconds. Windows without
+/// errors are skipped, and the errors counted before the first window aren't reported, so that
+/// only errors occurring while subscribed are.
+fn component_error_counts(window: i32) -> impl Stream<Item = BTreeMap<(String, String), f64>> {
+    let mut cache = BTreeMap::new();
+
+    metrics_sorted(window)
+        .map(move |m| error_counts(&mut cache, m))
+        // The first window only records the counters.
+        .skip(1)
+        .filter(|errors| !errors.is_empty())
+}
+
+/// Counts the errors of `metrics` since the counters recorded in `cache`.
+fn error_counts(
+    cache: &mut BTreeMap<(String, String, String), f64>,
+    metrics: Vec<Metric>,
+) -> BTreeMap<(String, String), f64> {
+    let mut errors = BTreeMap::new();
+    for m in metrics
+        .into_iter()
+        .filter(|m| m.name.ends_with("_errors_total"))
+    {
+        let (component_name, value) = match (m.tag_value("component_name"), &m.value) {
+            (Some(component_name), MetricValue::Counter { value }) => (component_name, *value),
+            _ => continue,
+        };
+        let code = m.tag_value("error_type").unwrap_or_else(|| m.name.clone());
+
+        let key = (component_name, m.name, code);
+        let last = cache.insert(key.clone(), value).unwrap_or(0.00);
+        if value > last {
+            let (component_name, _, code) = key;
+            *errors.entry((component_name, code)).or_insert(0.00) += value - last;
+        }
+    }
+    errors
+}
+
 /// Returns the throughput of a 'counter' metric, sampled over `interval` millseconds
 /// and filtered by the provided `filter_fn`.
 fn counter_throughput(
@@ -0,0 +1,63 @@
+use crate::tuning;
+use async_graphql::{Object, SimpleObject};
+
+#[derive(SimpleObject)]
+pub struct TunableOption {
+    /// Component name
+    component_name: String,
+
+    /// Option name, such as `batch.timeout_secs`
+    option: String,
+
+    /// Value overriding the configured one, if any
+    value: Option<i64>,
+}
+
+#[derive(Default)]
+pub struct TuningQuery;
+
+#[Object]
+impl TuningQuery {
+    /// Options of the running components which can be changed at runtime
+    async fn tunable_options(&self) -> Vec<TunableOption> {
+        tuning::options()
+            .into_iter()
+            .map(|(component_name, option, value)| TunableOption {
+                component_name,
+                option,
+                value: value.map(|value| value as i64),
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct TuningMutation;
+
+#[Object]
+impl TuningMutation {
+    /// Overrides an option of a running component, or resets it to the configured value if
+    /// `value` is null. With `persist`, the overrides are also written to `api.overrides_path`
+    /// to be applied after restarts
+    async fn set_component_option(
+        &self,
+        component_name: String,
+        option: String,
+        value: Option<i64>,
+        #[graphql(default)] persist: bool,
+    ) -> async_graphql::Result<TunableOption> {
+        if value.map_or(false, |value| value < 0) {
+            return Err(tuning::TuningError::NotPositive { option }.into());
+        }
+        tuning::set_override(&component_name, &option, value.map(|value| value as u64))?;
+        if persist {
+            tuning::persist()?;
+        }
+
+        Ok(TunableOption {
+            component_name,
+            option,
+            value,
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use super::{handler, schema};
-use crate::config;
+use crate::{config, http::CorsConfig};
 use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
     Request, Schema,
@@ -19,18 +19,24 @@ impl Server {
     /// Start the API server. This creates the routes and spawns a Warp server. The server is
     /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing
     pub fn start(config: &config::Config) -> Self {
-        let routes = make_routes(config.api.playground);
+        Self::start_with_configs(&config.api, &[config])
+    }
+
+    /// Start the API server with the `options` of the pipeline serving it, for the
+    /// components of all the pipelines of the process.
+    pub fn start_with_configs(options: &config::api::Options, configs: &[&config::Config]) -> Self {
+        let routes = make_routes(options.playground, options.cors.as_ref());
 
         let (_shutdown, rx) = oneshot::channel();
         let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
-            config.api.address.expect("No socket address"),
+            options.address.expect("No socket address"),
             async {
                 rx.await.ok();
             },
         );
 
-        // Update component schema with the config before starting the server
-        schema::components::update_config(config);
+        // Update component schema with the configs before starting the server
+        schema::components::update_configs(configs);
 
         // Spawn the server in the background
         tokio::spawn(server);
@@ -49,9 +55,14 @@ impl Server {
     pub fn update_config(&self, config: &config::Config) {
         schema::components::update_config(config)
     }
+
+    /// Update the configurations of the pipelines of a running server.
+    pub fn update_configs(&self, configs: &[&config::Config]) {
+        schema::components::update_configs(configs)
+    }
 }
 
-fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
+fn make_routes(playground: bool, cors: Option<&CorsConfig>) -> BoxedFilter<(impl Reply,)> {
     // Build the GraphQL schema
     let schema = schema::build_schema().finish();
 
@@ -87,29 +98,35 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
         not_found.boxed()
     };
 
+    let methods = ["POST", "GET"];
+    let cors = match cors {
+        Some(cors) => cors
+            .build(&methods)
+            .expect("CORS options are validated with the config"),
+        None => warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec![
+                "User-Agent",
+                "Sec-Fetch-Mode",
+                "Referer",
+                "Origin",
+                "Access-Control-Request-Method",
+                "Access-Control-Allow-Origin",
+                "Access-Control-Request-Headers",
+                "Content-Type",
+                "X-Apollo-Tracing", // for Apollo GraphQL clients
+                "Pragma",
+                "Host",
+                "Connection",
+                "Cache-Control",
+            ])
+            .allow_methods(methods.iter().copied()),
+    };
+
     health
         .or(graphql_handler)
         .or(graphql_playground)
         .or(not_found)
-        .with(
-            warp::cors()
-                .allow_any_origin()
-                .allow_headers(vec![
-                    "User-Agent",
-                    "Sec-Fetch-Mode",
-                    "Referer",
-                    "Origin",
-                    "Access-Control-Request-Method",
-                    "Access-Control-Allow-Origin",
-                    "Access-Control-Request-Headers",
-                    "Content-Type",
-                    "X-Apollo-Tracing", // for Apollo GraphQL clients
-                    "Pragma",
-                    "Host",
-                    "Connection",
-                    "Cache-Control",
-                ])
-                .allow_methods(vec!["POST", "GET"]),
-        )
+        .with(cors)
         .boxed()
 }
@@ -1,18 +1,17 @@
 use crate::cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubCommand};
+use crate::pipelines::{self, RunningPipeline};
 use crate::signal::SignalTo;
-use crate::topology::RunningTopology;
 use crate::{
-    config, generate, heartbeat, list, metrics, signal, topology, trace, unit_test, validate,
+    analyze, config, config_schema, dry_run, generate, heartbeat, list, metrics, process, signal,
+    trace, unit_test, validate,
 };
 use std::cmp::max;
-use std::path::PathBuf;
+use std::collections::HashSet;
 
-use futures::{
-    compat::{Future01CompatExt, Stream01CompatExt},
-    StreamExt,
-};
-use futures01::sync::mpsc;
+use futures::{compat::Future01CompatExt, future, StreamExt};
 
+#[cfg(feature = "leveldb")]
+use crate::buffers;
 #[cfg(feature = "api-client")]
 use crate::top;
 #[cfg(feature = "api")]
@@ -22,18 +21,18 @@ use crate::{api, internal_events::ApiStarted};
 use crate::service;
 
 use crate::internal_events::{
-    VectorConfigLoadFailed, VectorQuit, VectorRecoveryFailed, VectorReloadFailed, VectorReloaded,
-    VectorStarted, VectorStopped,
+    PipelineStopped, VectorConfigLoadFailed, VectorQuit, VectorRecoveryFailed, VectorReloadFailed,
+    VectorReloaded, VectorStarted, VectorStopped,
 };
 use tokio::runtime;
 use tokio::runtime::Runtime;
 
 pub struct ApplicationConfig {
-    pub config_paths: Vec<(PathBuf, config::FormatHint)>,
-    pub topology: RunningTopology,
-    pub graceful_crash: mpsc::UnboundedReceiver<()>,
+    /// The config given with `--config`, or the pipelines given with `--pipeline`.
+    pub pipelines: Vec<RunningPipeline>,
     #[cfg(feature = "api")]
     pub api: config::api::Options,
+    pub pid_file: Option<process::PidFile>,
 }
 
 pub struct Application {
@@ -92,6 +91,18 @@ impl Application {
             }
         }
 
+        if let Some(limit) = root_opts.open_files_limit {
+            match process::raise_open_files_limit(limit) {
+                Ok(current) if current < limit => warn!(
+                    message = "Unable to raise the limit of open files beyond the hard limit.",
+                    requested = limit,
+                    current
+                ),
+                Ok(current) => info!(message = "Raised the limit of open files.", current),
+                Err(error) => warn!(message = "Unable to raise the limit of open files.", %error),
+            }
+        }
+
         let mut rt = {
             let threads = root_opts.threads.unwrap_or_else(|| max(1, num_cpus::get()));
             runtime::Builder::new()
@@ -104,16 +115,23 @@ impl Application {
 
         let config = {
             let config_paths = root_opts.config_paths_with_formats();
+            let pipelines = root_opts.pipelines.clone();
             let watch_config = root_opts.watch_config;
             let require_healthy = root_opts.require_healthy;
+            let pid_file = root_opts.pid_file.clone();
 
             rt.block_on(async move {
                 if let Some(s) = sub_command {
                     let code = match s {
                         SubCommand::Validate(v) => validate::validate(&v, color).await,
                         SubCommand::List(l) => list::cmd(&l),
+                        SubCommand::ConfigSchema(c) => config_schema::cmd(&c),
                         SubCommand::Test(t) => unit_test::cmd(&t).await,
+                        SubCommand::DryRun(d) => dry_run::cmd(&d).await,
+                        SubCommand::Analyze(a) => analyze::cmd(&a),
                         SubCommand::Generate(g) => generate::cmd(&g),
+                        #[cfg(feature = "leveldb")]
+                        SubCommand::Buffer(b) => buffers::cmd::cmd(&b),
                         #[cfg(feature = "api-client")]
                         SubCommand::Top(t) => top::cmd(&t).await,
                         #[cfg(windows)]
@@ -125,46 +143,128 @@ impl Application {
 
                 info!(message = "Log level is enabled.", level = ?level);
 
-                let config_paths = config::process_paths(&config_paths).ok_or(exitcode::CONFIG)?;
+                let specs = if pipelines.is_empty() {
+                    vec![(None, config_paths)]
+                } else if !config_paths.is_empty() {
+                    error!(
+                        message = "Configs can't be given both as pipelines and with `--config`."
+                    );
+                    return Err(exitcode::CONFIG);
+                } else {
+                    let mut names = HashSet::new();
+                    if let Some(pipeline) = pipelines
+                        .iter()
+                        .find(|pipeline| !names.insert(&pipeline.name))
+                    {
+                        error!(message = "Pipeline given more than once.", name = %pipeline.name);
+                        return Err(exitcode::CONFIG);
+                    }
+                    pipelines
+                        .into_iter()
+                        .map(|pipeline| {
+                            let paths = pipeline
+                                .paths
+                                .into_iter()
+                                .map(|path| (path, None))
+                                .collect();
+                            (Some(pipeline.name), paths)
+                        })
+                        .collect()
+                };
+
+                let mut loaded = Vec::new();
+                for (name, path_patterns) in specs {
+                    let config_paths =
+                        config::process_paths(&path_patterns).ok_or(exitcode::CONFIG)?;
+                    loaded.push((name, path_patterns, config_paths));
+                }
 
                 if watch_config {
                     // Start listening for config changes immediately.
-                    config::watcher::spawn_thread(config_paths.iter().map(|(path, _)| path), None)
-                        .map_err(|error| {
-                            error!(message = "Unable to start config watcher.", %error);
-                            exitcode::CONFIG
-                        })?;
+                    let paths = loaded
+                        .iter()
+                        .flat_map(|(_, _, config_paths)| config_paths.iter().map(|(path, _)| path));
+                    config::watcher::spawn_thread(paths, None).map_err(|error| {
+                        error!(message = "Unable to start config watcher.", %error);
+                        exitcode::CONFIG
+                    })?;
                 }
 
-                info!(
-                    message = "Loading configs.",
-                    path = ?config_paths
-                );
+                let mut configs = Vec::new();
+                for (name, _, config_paths) in &loaded {
+                    info!(
+                        message = "Loading configs.",
+                        pipeline = ?name,
+                        path = ?config_paths
+                    );
+
+                    let mut config = config::load_from_paths(&config_paths, false)
+                        .map_err(handle_config_errors)?;
+                    config.pipeline = name.clone();
+                    configs.push(config);
+                }
+                pipelines::check(&configs.iter().collect::<Vec<_>>())
+                    .map_err(handle_config_errors)?;
 
-                let config =
-                    config::load_from_paths(&config_paths, false).map_err(handle_config_errors)?;
+                // The configs agree on the options shared by the process.
+                let config = &configs[0];
 
                 config::LOG_SCHEMA
                     .set(config.global.log_schema.clone())
                     .expect("Couldn't set schema");
 
-                let diff = config::ConfigDiff::initial(&config);
-                let pieces = topology::build_or_log_errors(&config, &diff)
-                    .await
-                    .ok_or(exitcode::CONFIG)?;
+                let hostname = config.global.hostname.resolve().await.map_err(|error| {
+                    error!(message = "Unable to determine hostname.", %error);
+                    exitcode::CONFIG
+                })?;
+                config::set_resolved_hostname(hostname);
+
+                process::update_crash_context(&config);
+                process::install_crash_handler();
+
+                let pid_file = pid_file
+                    .map(|path| {
+                        process::PidFile::create(path.clone()).map_err(|error| {
+                            error!(message = "Unable to write PID file.", ?path, %error);
+                            exitcode::CANTCREAT
+                        })
+                    })
+                    .transpose()?;
+
+                // The API is served by the pipeline enabling it, if any.
+                #[cfg(feature = "api")]
+                let api = configs
+                    .iter()
+                    .map(|config| &config.api)
+                    .find(|api| api.enabled)
+                    .unwrap_or(&config.api)
+                    .clone();
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                if let Some(path) = &api.overrides_path {
+                    crate::tuning::load_overrides(path.clone()).map_err(|error| {
+                        error!(message = "Unable to load overrides of tunable options.", %error);
+                        exitcode::CONFIG
+                    })?;
+                }
 
-                let result = topology::start_validated(config, diff, pieces, require_healthy).await;
-                let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
+                let mut pipelines = Vec::new();
+                for ((_, path_patterns, config_paths), config) in loaded.into_iter().zip(configs) {
+                    let pipeline = RunningPipeline::start(
+                        path_patterns,
+                        config_paths,
+                        config,
+                        require_healthy,
+                    )
+                    .await;
+                    pipelines.push(pipeline.ok_or(exitcode::CONFIG)?);
+                }
 
                 Ok(ApplicationConfig {
-                    config_paths,
-                    topology,
-                    graceful_crash,
+                    pipelines,
                     #[cfg(feature = "api")]
                     api,
+                    pid_file,
                 })
             })
         }?;
@@ -179,10 +279,10 @@ impl Application {
     pub fn run(self) {
         let mut rt = self.runtime;
 
-        let graceful_crash = self.config.graceful_crash;
-        let mut topology = self.config.topology;
+        let mut pipelines = self.config.pipelines;
 
-        let mut config_paths = self.config.config_paths;
+        // Removed once the topology is stopped.
+        let _pid_file = self.config.pid_file;
 
         let opts = self.opts;
 
@@ -201,7 +301,7 @@ impl Application {
                     playground: api_config.playground
                 });
 
-                Some(api::Server::start(topology.config()))
+                Some(api::Server::start_with_configs(&api_config, &configs(&pipelines)))
             } else {
                 info!(message="API is disabled, enable by setting `api.enabled` to `true` and use commands like `vector top`.");
                 None
@@ -209,59 +309,114 @@ impl Application {
 
             let signals = signal::signals();
             tokio::pin!(signals);
-            let mut sources_finished = topology.sources_finished();
-            let mut graceful_crash = graceful_crash.compat();
 
             let signal = loop {
-                tokio::select! {
-                Some(signal) = signals.next() => {
-                    if signal == SignalTo::Reload {
-                        // Reload paths
-                        config_paths = config::process_paths(&opts.config_paths_with_formats()).unwrap_or(config_paths);
-                        // Reload config
-                        let new_config = config::load_from_paths(&config_paths, false).map_err(handle_config_errors).ok();
-
-                        if let Some(new_config) = new_config {
-                            match topology
-                                .reload_config_and_respawn(new_config, opts.require_healthy)
-                                .await
-                            {
-                                Ok(true) => {
-                                    #[cfg(feature="api")]
-                                    if let Some(ref api_server) = api_server {
-                                        api_server.update_config(topology.config())
+                let finished = {
+                    let finished = future::select_all(
+                        pipelines.iter_mut().map(|pipeline| Box::pin(pipeline.finished())),
+                    );
+                    tokio::select! {
+                        Some(signal) = signals.next() => Err(signal),
+                        (_, index, _) = finished => Ok(index),
+                        else => unreachable!("Signal streams never end"),
+                    }
+                };
+
+                match finished {
+                    // Trigger graceful shutdown if a component crashed, or all sources have
+                    // ended, stopping only its pipeline if there are others.
+                    Ok(_) if pipelines.len() == 1 => break SignalTo::Shutdown,
+                    Ok(index) => {
+                        let pipeline = pipelines.remove(index);
+                        emit!(PipelineStopped { name: pipeline.name.as_deref().unwrap_or_default() });
+                        pipeline.topology.stop().compat().await.ok();
+
+                        #[cfg(feature="api")]
+                        if let Some(ref api_server) = api_server {
+                            api_server.update_configs(&configs(&pipelines))
+                        }
+                    }
+                    Err(SignalTo::Reload) => {
+                        // Reload paths and configs
+                        let mut new_configs = Vec::new();
+                        for pipeline in &mut pipelines {
+                            pipeline.config_paths = config::process_paths(&pipeline.path_patterns).unwrap_or_else(|| pipeline.config_paths.clone());
+                            new_configs.push(config::load_from_paths(&pipeline.config_paths, false).map_err(handle_config_errors).ok());
+                        }
+                        let new_configs = match new_configs.into_iter().collect::<Option<Vec<_>>>() {
+                            Some(mut new_configs) => {
+                                for (config, pipeline) in new_configs.iter_mut().zip(&pipelines) {
+                                    config.pipeline = pipeline.name.clone();
+                                }
+                                match pipelines::check(&new_configs.iter().collect::<Vec<_>>()) {
+                                    Ok(()) => new_configs,
+                                    Err(errors) => {
+                                        handle_config_errors(errors);
+                                        emit!(VectorConfigLoadFailed);
+                                        continue;
                                     }
+                                }
+                            }
+                            None => {
+                                emit!(VectorConfigLoadFailed);
+                                continue;
+                            }
+                        };
+
+                        // Resolve a changed hostname strategy before the components using
+                        // it are rebuilt.
+                        let strategy = &new_configs[0].global.hostname;
+                        if *strategy != pipelines[0].topology.config().global.hostname {
+                            match strategy.resolve().await {
+                                Ok(hostname) => config::set_resolved_hostname(hostname),
+                                Err(error) => {
+                                    error!(message = "Unable to determine hostname.", %error);
+                                    emit!(VectorConfigLoadFailed);
+                                    continue;
+                                }
+                            }
+                        }
 
-                                    emit!(VectorReloaded { config_paths: &config_paths })
-                                },
+                        let mut failed = Vec::new();
+                        for (index, (pipeline, new_config)) in pipelines.iter_mut().zip(new_configs).enumerate() {
+                            match pipeline.reload(new_config, opts.require_healthy).await {
+                                Ok(true) => emit!(VectorReloaded { config_paths: &pipeline.config_paths }),
                                 Ok(false) => emit!(VectorReloadFailed),
-                                // Trigger graceful shutdown for what remains of the topology
                                 Err(()) => {
                                     emit!(VectorReloadFailed);
                                     emit!(VectorRecoveryFailed);
-                                    break SignalTo::Shutdown;
+                                    failed.push(index);
                                 }
                             }
-                            sources_finished = topology.sources_finished();
-                        } else {
-                            emit!(VectorConfigLoadFailed);
                         }
-                    } else {
-                        break signal;
+                        // Trigger graceful shutdown for what remains of the topologies
+                        // which failed to recover, or of all of them if none are left.
+                        if failed.len() == pipelines.len() {
+                            break SignalTo::Shutdown;
+                        }
+                        for index in failed.into_iter().rev() {
+                            let pipeline = pipelines.remove(index);
+                            emit!(PipelineStopped { name: pipeline.name.as_deref().unwrap_or_default() });
+                            pipeline.topology.stop().compat().await.ok();
+                        }
+
+                        process::update_crash_context(pipelines[0].topology.config());
+
+                        #[cfg(feature="api")]
+                        if let Some(ref api_server) = api_server {
+                            api_server.update_configs(&configs(&pipelines))
+                        }
                     }
+                    Err(signal) => break signal,
                 }
-                // Trigger graceful shutdown if a component crashed, or all sources have ended.
-                _ = graceful_crash.next() => break SignalTo::Shutdown,
-                _ = &mut sources_finished => break SignalTo::Shutdown,
-                else => unreachable!("Signal streams never end"),
-            }
             };
 
             match signal {
                 SignalTo::Shutdown => {
                     emit!(VectorStopped);
+                    let stopped = future::join_all(pipelines.into_iter().map(|pipeline| pipeline.topology.stop().compat()));
                     tokio::select! {
-                    _ = topology.stop().compat() => (), // Graceful shutdown finished
+                    _ = stopped => (), // Graceful shutdown finished
                     _ = signals.next() => {
                         // It is highly unlikely that this event will exit from topology.
                         emit!(VectorQuit);
@@ -272,10 +427,19 @@ impl Application {
                 SignalTo::Quit => {
                     // It is highly unlikely that this event will exit from topology.
                     emit!(VectorQuit);
-                    drop(topology);
+                    drop(pipelines);
                 }
                 SignalTo::Reload => unreachable!(),
             }
         });
     }
 }
+
+/// The configs of the running pipelines.
+#[cfg(feature = "api")]
+fn configs(pipelines: &[RunningPipeline]) -> Vec<&config::Config> {
+    pipelines
+        .iter()
+        .map(|pipeline| pipeline.topology.config())
+        .collect()
+}
@@ -0,0 +1,197 @@
+use super::disk::{inspect, version, Error};
+use crate::config;
+use chrono::Utc;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The data directory holding the disk buffers, defaulting to the default
+    /// `data_dir`. The buffers must not be in use by a running Vector.
+    #[structopt(short, long)]
+    data_dir: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+enum Command {
+    /// List the disk buffers with their size, event count and the age of their oldest event.
+    Ls {
+        /// Print the buffers as JSON.
+        #[structopt(long)]
+        json: bool,
+    },
+
+    /// Report the size, event count and event ages of the disk buffer of a sink.
+    Inspect {
+        /// The name of the sink.
+        sink: String,
+
+        /// Print the report as JSON.
+        #[structopt(long)]
+        json: bool,
+    },
+
+    /// Write the events of the disk buffer of a sink as JSON, one per line.
+    Export {
+        /// The name of the sink.
+        sink: String,
+
+        /// File to write the events to, instead of stdout.
+        #[structopt(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Migrate disk buffers written by older versions of Vector to the current format.
+    Migrate {
+        /// The name of the sink, instead of migrating every buffer.
+        sink: Option<String>,
+    },
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let data_dir = match opts.data_dir.clone().or_else(config::default_data_dir) {
+        Some(data_dir) => data_dir,
+        None => {
+            error!(message = "A data directory is required.");
+            return exitcode::USAGE;
+        }
+    };
+
+    let result = match &opts.command {
+        Command::Ls { json } => inspect::list(&data_dir).and_then(|buffers| {
+            let infos = buffers
+                .iter()
+                .map(|(sink, path)| inspect::inspect(sink, path))
+                .collect::<Result<Vec<_>, _>>()?;
+            if *json {
+                print_json(&infos);
+            } else {
+                print_buffers(&infos);
+            }
+            Ok(())
+        }),
+        Command::Inspect { sink, json } => inspect::inspect(sink, &inspect::path(&data_dir, sink))
+            .map(|info| {
+                if *json {
+                    print_json(&info);
+                } else {
+                    print_buffer(&info);
+                }
+            }),
+        Command::Export { sink, output } => {
+            let path = inspect::path(&data_dir, sink);
+            let mut output: Box<dyn Write> = match output {
+                Some(output) => match File::create(output) {
+                    Ok(file) => Box::new(BufWriter::new(file)),
+                    Err(error) => {
+                        error!(message = "Failed to create output file.", path = ?output, %error);
+                        return exitcode::CANTCREAT;
+                    }
+                },
+                None => Box::new(BufWriter::new(io::stdout())),
+            };
+            inspect::export(&path, &mut output).and_then(|events| {
+                output
+                    .flush()
+                    .map_err(|source| Error::BufferIo { path, source })?;
+                info!(message = "Exported events.", %events, sink = %sink);
+                Ok(())
+            })
+        }
+        Command::Migrate { sink } => {
+            let buffers = match sink {
+                Some(sink) => Ok(vec![(sink.clone(), inspect::path(&data_dir, sink))]),
+                None => inspect::list(&data_dir),
+            };
+            buffers.and_then(|buffers| {
+                for (sink, path) in buffers {
+                    match version::read(&path)? {
+                        Some(from) if from < version::FORMAT_VERSION => {
+                            let migration = version::migrate(&path, from)?;
+                            info!(message = "Migrated disk buffer.", sink = %sink, from, ?migration);
+                        }
+                        _ => info!(message = "Disk buffer is up to date.", sink = %sink),
+                    }
+                }
+                Ok(())
+            })
+        }
+    };
+
+    match result {
+        Ok(()) => exitcode::OK,
+        Err(error) => {
+            error!(message = "Buffer command failed.", %error);
+            exitcode::IOERR
+        }
+    }
+}
+
+fn print_json<T: serde::Serialize>(value: &T) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("Buffer reports serialize to JSON.")
+    );
+}
+
+fn print_buffers(infos: &[inspect::BufferInfo]) {
+    println!(
+        "{:<32} {:>8} {:>12} {:>14} {:>12}",
+        "SINK", "VERSION", "EVENTS", "SIZE", "OLDEST"
+    );
+    for info in infos {
+        println!(
+            "{:<32} {:>8} {:>12} {:>14} {:>12}",
+            info.sink,
+            info.version,
+            info.events,
+            info.disk_bytes,
+            age(info)
+        );
+    }
+}
+
+fn print_buffer(info: &inspect::BufferInfo) {
+    println!("Sink:          {}", info.sink);
+    println!("Path:          {}", info.path.display());
+    println!("Version:       {}", info.version);
+    println!("Size:          {} bytes", info.disk_bytes);
+    println!("Events:        {}", info.events);
+    println!("Event bytes:   {}", info.event_bytes);
+    println!("Undecodable:   {}", info.undecodable);
+    if let (Some(first), Some(last)) = (info.first_offset, info.last_offset) {
+        println!("Offsets:       {} - {}", first, last);
+    }
+    if let Some(oldest) = info.oldest_event {
+        println!("Oldest event:  {} ({} old)", oldest.to_rfc3339(), age(info));
+    }
+    if let Some(newest) = info.newest_event {
+        println!("Newest event:  {}", newest.to_rfc3339());
+    }
+}
+
+/// The age of the oldest event of the buffer, in the largest unit it has.
+fn age(info: &inspect::BufferInfo) -> String {
+    let age = match info.oldest_event {
+        Some(oldest) => Utc::now() - oldest,
+        None => return "-".into(),
+    };
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else if age.num_minutes() > 0 {
+        format!("{}m", age.num_minutes())
+    } else {
+        format!("{}s", age.num_seconds().max(0))
+    }
+}
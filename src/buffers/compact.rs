@@ -0,0 +1,296 @@
+//! A memory buffer storing events serialized in blocks, optionally compressed
+//! with LZ4, which fits several times as many events in the same amount of
+//! memory at the cost of encoding them.
+
+use crate::event::{proto, Event};
+use futures01::{
+    sync::mpsc,
+    task::{self, Task},
+    Async, AsyncSink, Poll, Sink, StartSend, Stream,
+};
+use prost::Message;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Blocks are sent to the reader once they reach this size, or when the
+/// writer is flushed.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+struct Block {
+    events: usize,
+    compressed: bool,
+    data: Vec<u8>,
+}
+
+struct Shared {
+    max_events: usize,
+    /// The number of events in the buffer, including the ones not sent in a
+    /// block yet.
+    events: AtomicUsize,
+    /// Writers waiting for room in the buffer.
+    blocked: Mutex<Vec<Task>>,
+}
+
+impl Shared {
+    fn is_full(&self) -> bool {
+        self.events.load(Ordering::Acquire) >= self.max_events
+    }
+
+    fn release(&self, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let previous = self.events.fetch_sub(count, Ordering::AcqRel);
+        if previous >= self.max_events {
+            for task in self.blocked.lock().unwrap().drain(..) {
+                task.notify();
+            }
+        }
+    }
+}
+
+pub fn channel(max_events: usize, compress: bool) -> (Writer, Reader) {
+    let (tx, rx) = mpsc::unbounded();
+    let shared = Arc::new(Shared {
+        max_events,
+        events: AtomicUsize::new(0),
+        blocked: Mutex::new(Vec::new()),
+    });
+    let writer = Writer {
+        tx,
+        shared: Arc::clone(&shared),
+        compress,
+        pending: Vec::new(),
+        pending_events: 0,
+    };
+    let reader = Reader {
+        rx,
+        shared,
+        events: VecDeque::new(),
+    };
+    (writer, reader)
+}
+
+pub struct Writer {
+    tx: mpsc::UnboundedSender<Block>,
+    shared: Arc<Shared>,
+    compress: bool,
+    pending: Vec<u8>,
+    pending_events: usize,
+}
+
+impl Clone for Writer {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            shared: Arc::clone(&self.shared),
+            compress: self.compress,
+            pending: Vec::new(),
+            pending_events: 0,
+        }
+    }
+}
+
+impl Writer {
+    fn flush(&mut self) -> Result<(), ()> {
+        if self.pending_events == 0 {
+            return Ok(());
+        }
+
+        let events = std::mem::replace(&mut self.pending_events, 0);
+        let data = std::mem::take(&mut self.pending);
+        let block = if self.compress {
+            match lz4::block::compress(&data, None, true) {
+                Ok(compressed) => Block {
+                    events,
+                    compressed: true,
+                    data: compressed,
+                },
+                Err(error) => {
+                    error!(
+                        message = "Failed to compress buffer block, storing it uncompressed.",
+                        %error,
+                        rate_limit_secs = 30
+                    );
+                    Block {
+                        events,
+                        compressed: false,
+                        data,
+                    }
+                }
+            }
+        } else {
+            Block {
+                events,
+                compressed: false,
+                data,
+            }
+        };
+
+        self.tx
+            .unbounded_send(block)
+            .map_err(|error| error!(message = "Sender error.", %error))
+    }
+}
+
+impl Sink for Writer {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(&mut self, event: Event) -> StartSend<Event, ()> {
+        if self.shared.is_full() {
+            // The pending events have to be readable to make room.
+            self.flush()?;
+            {
+                let mut blocked = self.shared.blocked.lock().unwrap();
+                if !blocked.iter().any(Task::will_notify_current) {
+                    blocked.push(task::current());
+                }
+            }
+            // Room may have been made before the task was registered.
+            if self.shared.is_full() {
+                return Ok(AsyncSink::NotReady(event));
+            }
+        }
+
+        proto::EventWrapper::from(event)
+            .encode_length_delimited(&mut self.pending)
+            .unwrap(); // This will not error when writing to a Vec
+        self.pending_events += 1;
+        self.shared.events.fetch_add(1, Ordering::AcqRel);
+
+        if self.pending.len() >= BLOCK_SIZE {
+            self.flush()?;
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), ()> {
+        self.flush()?;
+        Ok(Async::Ready(()))
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+pub struct Reader {
+    rx: mpsc::UnboundedReceiver<Block>,
+    shared: Arc<Shared>,
+    events: VecDeque<Event>,
+}
+
+impl Reader {
+    fn decode(&mut self, block: Block) {
+        let data = if block.compressed {
+            match lz4::block::decompress(&block.data, None) {
+                Ok(data) => data,
+                Err(error) => {
+                    error!(
+                        message = "Failed to decompress buffer block, dropping its events.",
+                        %error,
+                        count = block.events
+                    );
+                    self.shared.release(block.events);
+                    return;
+                }
+            }
+        } else {
+            block.data
+        };
+
+        let mut buf = &data[..];
+        let mut decoded = 0;
+        while !buf.is_empty() {
+            match proto::EventWrapper::decode_length_delimited(&mut buf) {
+                Ok(event) => {
+                    self.events.push_back(event.into());
+                    decoded += 1;
+                }
+                Err(error) => {
+                    error!(message = "Error deserializing proto.", %error);
+                    break;
+                }
+            }
+        }
+        self.shared.release(block.events.saturating_sub(decoded));
+    }
+}
+
+impl Stream for Reader {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Event>, ()> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                self.shared.release(1);
+                return Ok(Async::Ready(Some(event)));
+            }
+
+            match self.rx.poll()? {
+                Async::Ready(Some(block)) => self.decode(block),
+                Async::Ready(None) => return Ok(Async::Ready(None)),
+                Async::NotReady => return Ok(Async::NotReady),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::compat::Future01CompatExt;
+    use futures01::future;
+
+    async fn round_trip(compress: bool) {
+        future::lazy(move || {
+            let (mut tx, mut rx) = channel(2, compress);
+            let events = (0..3)
+                .map(|i| Event::from(format!("event {}", i)))
+                .collect::<Vec<_>>();
+
+            assert_eq!(tx.start_send(events[0].clone()), Ok(AsyncSink::Ready));
+            assert_eq!(tx.start_send(events[1].clone()), Ok(AsyncSink::Ready));
+            // The buffer is full, which makes the pending events readable.
+            assert_eq!(
+                tx.start_send(events[2].clone()),
+                Ok(AsyncSink::NotReady(events[2].clone()))
+            );
+
+            assert_eq!(rx.poll(), Ok(Async::Ready(Some(events[0].clone()))));
+            assert_eq!(tx.start_send(events[2].clone()), Ok(AsyncSink::Ready));
+            assert_eq!(rx.poll(), Ok(Async::Ready(Some(events[1].clone()))));
+            assert_eq!(rx.poll(), Ok(Async::NotReady));
+
+            assert_eq!(tx.poll_complete(), Ok(Async::Ready(())));
+            assert_eq!(rx.poll(), Ok(Async::Ready(Some(events[2].clone()))));
+
+            drop(tx);
+            assert_eq!(rx.poll(), Ok(Async::Ready(None)));
+
+            future::ok::<(), ()>(())
+        })
+        .compat()
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn serialized_round_trip() {
+        round_trip(false).await;
+    }
+
+    #[tokio::test]
+    async fn compressed_round_trip() {
+        round_trip(true).await;
+    }
+}
@@ -0,0 +1,240 @@
+use super::{version, BufferIo, Error};
+use crate::{config::log_schema, event::Event};
+use chrono::{DateTime, Utc};
+use leveldb::database::{iterator::Iterable, options::ReadOptions};
+use serde::Serialize;
+use snafu::ResultExt;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Suffix of the directories of disk buffers, named after their sink.
+const BUFFER_SUFFIX: &str = "_buffer";
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct BufferInfo {
+    pub sink: String,
+    pub path: PathBuf,
+    pub version: u32,
+    /// The size of the files of the buffer.
+    pub disk_bytes: u64,
+    pub events: usize,
+    /// The size of the encoded events.
+    pub event_bytes: usize,
+    pub undecodable: usize,
+    pub first_offset: Option<usize>,
+    pub last_offset: Option<usize>,
+    pub oldest_event: Option<DateTime<Utc>>,
+    pub newest_event: Option<DateTime<Utc>>,
+}
+
+/// Lists the sinks of the disk buffers in `data_dir`, with the path of each buffer.
+pub fn list(data_dir: &Path) -> Result<Vec<(String, PathBuf)>, Error> {
+    let mut buffers = Vec::new();
+    for entry in fs::read_dir(data_dir).context(BufferIo { path: data_dir })? {
+        let path = entry.context(BufferIo { path: data_dir })?.path();
+        let sink = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| name.ends_with(BUFFER_SUFFIX))
+            .map(|name| name.trim_end_matches(BUFFER_SUFFIX).to_string());
+        if let Some(sink) = sink {
+            if path.is_dir() {
+                buffers.push((sink, path));
+            }
+        }
+    }
+    buffers.sort();
+    Ok(buffers)
+}
+
+/// The path of the disk buffer of `sink`.
+pub fn path(data_dir: &Path, sink: &str) -> PathBuf {
+    data_dir.join(format!("{}{}", sink, BUFFER_SUFFIX))
+}
+
+/// Reads every record of the buffer at `path`, calling `f` with each event,
+/// or with the error its record failed to decode with.
+fn records(
+    path: &Path,
+    mut f: impl FnMut(usize, usize, Result<Event, prost::DecodeError>) -> io::Result<()>,
+) -> Result<u32, Error> {
+    let version = check(path)?;
+    let db = version::open(path, false)?;
+    for (key, value) in db.iter(ReadOptions::new()) {
+        let size = value.len();
+        f(key.0, size, version::decode(version, value)).context(BufferIo { path })?;
+    }
+    Ok(version)
+}
+
+fn check(path: &Path) -> Result<u32, Error> {
+    match version::read(path)? {
+        Some(version) if version > version::FORMAT_VERSION => Err(Error::UnsupportedVersion {
+            path: path.into(),
+            version,
+        }),
+        Some(version) => Ok(version),
+        None => Err(Error::BufferIo {
+            path: path.into(),
+            source: io::ErrorKind::NotFound.into(),
+        }),
+    }
+}
+
+/// Reports the size, event count and event ages of the buffer of `sink`.
+pub fn inspect(sink: &str, path: &Path) -> Result<BufferInfo, Error> {
+    let mut info = BufferInfo {
+        sink: sink.into(),
+        path: path.into(),
+        disk_bytes: disk_size(path).context(BufferIo { path })?,
+        ..Default::default()
+    };
+
+    let version = records(path, |offset, size, event| {
+        info.first_offset = info.first_offset.or(Some(offset));
+        info.last_offset = Some(offset);
+        match event {
+            Ok(event) => {
+                info.events += 1;
+                info.event_bytes += size;
+                if let Some(timestamp) = timestamp(&event) {
+                    info.oldest_event = Some(
+                        info.oldest_event
+                            .map_or(timestamp, |oldest| oldest.min(timestamp)),
+                    );
+                    info.newest_event = Some(
+                        info.newest_event
+                            .map_or(timestamp, |newest| newest.max(timestamp)),
+                    );
+                }
+            }
+            Err(_) => info.undecodable += 1,
+        }
+        Ok(())
+    })?;
+    info.version = version;
+
+    Ok(info)
+}
+
+/// Writes the events of the buffer at `path` as JSON, one per line, returning
+/// how many were written. Records that fail to decode are skipped.
+pub fn export(path: &Path, output: &mut dyn io::Write) -> Result<usize, Error> {
+    let mut events = 0;
+    records(path, |offset, _, event| {
+        match event {
+            Ok(Event::Log(log)) => serde_json::to_writer(&mut *output, &log)?,
+            Ok(Event::Metric(metric)) => serde_json::to_writer(&mut *output, &metric)?,
+            Err(error) => {
+                warn!(message = "Skipping undecodable buffer record.", offset, %error);
+                return Ok(());
+            }
+        }
+        events += 1;
+        output.write_all(b"\n")
+    })?;
+    Ok(events)
+}
+
+fn timestamp(event: &Event) -> Option<DateTime<Utc>> {
+    match event {
+        Event::Log(log) => log
+            .get(log_schema().timestamp_key())
+            .and_then(|value| value.as_timestamp())
+            .copied(),
+        Event::Metric(metric) => metric.timestamp,
+    }
+}
+
+fn disk_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        buffers::disk,
+        event::metric::{Metric, MetricKind, MetricValue},
+        test_util::temp_dir,
+    };
+    use chrono::TimeZone;
+    use futures01::{Future, Sink};
+
+    fn buffer(events: Vec<Event>) -> PathBuf {
+        let data_dir = temp_dir();
+        fs::create_dir_all(&data_dir).unwrap();
+        let (writer, _reader, _acker) = disk::open(&data_dir, "out_buffer", 1_000_000).unwrap();
+        writer
+            .send_all(futures01::stream::iter_ok(events))
+            .wait()
+            .unwrap();
+        data_dir
+    }
+
+    fn log(message: &str, timestamp: i64) -> Event {
+        let mut event = Event::from(message);
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), Utc.timestamp(timestamp, 0));
+        event
+    }
+
+    #[test]
+    fn inspects_buffers() {
+        let metric = Event::Metric(Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: Some(Utc.timestamp(1_600_000_100, 0)),
+            tags: None,
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        });
+        let data_dir = buffer(vec![
+            log("first", 1_600_000_050),
+            log("second", 1_600_000_000),
+            metric,
+        ]);
+
+        let buffers = list(&data_dir).unwrap();
+        assert_eq!(buffers, vec![("out".into(), path(&data_dir, "out"))]);
+
+        let info = inspect("out", &buffers[0].1).unwrap();
+        assert_eq!(info.version, version::FORMAT_VERSION);
+        assert_eq!(info.events, 3);
+        assert_eq!(info.undecodable, 0);
+        assert_eq!(info.first_offset, Some(0));
+        assert_eq!(info.last_offset, Some(2));
+        assert_eq!(info.oldest_event, Some(Utc.timestamp(1_600_000_000, 0)));
+        assert_eq!(info.newest_event, Some(Utc.timestamp(1_600_000_100, 0)));
+        assert!(info.disk_bytes > 0);
+    }
+
+    #[test]
+    fn exports_events() {
+        let data_dir = buffer(vec![
+            log("first", 1_600_000_000),
+            log("second", 1_600_000_000),
+        ]);
+
+        let mut output = Vec::new();
+        let events = export(&path(&data_dir, "out"), &mut output).unwrap();
+        assert_eq!(events, 2);
+
+        let lines = String::from_utf8(output).unwrap();
+        let messages = lines
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["message"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["first", "second"]);
+    }
+}
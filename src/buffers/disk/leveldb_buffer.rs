@@ -28,7 +28,7 @@ use super::{DataDirOpenError, Error};
 use crate::buffers::Acker;
 
 #[derive(Copy, Clone, Debug)]
-struct Key(pub usize);
+pub(super) struct Key(pub usize);
 
 impl db_key::Key for Key {
     fn from_u8(key: &[u8]) -> Self {
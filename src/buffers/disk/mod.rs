@@ -6,7 +6,9 @@ use snafu::Snafu;
 use std::io;
 use std::path::{Path, PathBuf};
 
+pub mod inspect;
 pub mod leveldb_buffer;
+pub mod version;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -24,6 +26,26 @@ pub enum Error {
         data_dir: PathBuf,
         source: leveldb::database::error::Error,
     },
+    #[snafu(display("Unable to update the buffer at {:?}: {}", path, source))]
+    BufferIo { path: PathBuf, source: io::Error },
+    #[snafu(display("Invalid format version in {:?}", path))]
+    InvalidVersion { path: PathBuf },
+    #[snafu(display(
+        "The buffer at {:?} has format version {}, newer than the {} this version of Vector reads. Run the version of Vector that wrote it until the buffer is drained.",
+        path,
+        version,
+        version::FORMAT_VERSION
+    ))]
+    UnsupportedVersion { path: PathBuf, version: u32 },
+    #[snafu(display(
+        "Unable to migrate the records of the buffer to {:?}: {}",
+        path,
+        source
+    ))]
+    MigrateRecords {
+        path: PathBuf,
+        source: leveldb::database::error::Error,
+    },
 }
 
 pub trait DiskBuffer {
@@ -99,6 +121,30 @@ pub fn open(
             }
         })?;
 
-    let (writer, reader, acker) = leveldb_buffer::Buffer::build(path, max_size)?;
+    check_version(&path)?;
+    let (writer, reader, acker) = leveldb_buffer::Buffer::build(path.clone(), max_size)?;
+    version::write(&path)?;
     Ok((Writer { inner: writer }, Box::new(reader), acker))
 }
+
+/// Migrates buffers written by older versions of Vector, and refuses to read
+/// those written by newer ones.
+fn check_version(path: &Path) -> Result<(), Error> {
+    match version::read(path)? {
+        Some(version) if version > version::FORMAT_VERSION => Err(Error::UnsupportedVersion {
+            path: path.into(),
+            version,
+        }),
+        Some(version) if version < version::FORMAT_VERSION => {
+            let migration = version::migrate(path, version)?;
+            info!(
+                message = "Migrated disk buffer.",
+                ?path,
+                from = version,
+                ?migration
+            );
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
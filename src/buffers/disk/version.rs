@@ -0,0 +1,196 @@
+use super::{leveldb_buffer::Key, BufferIo, DataDirOpenError, Error, MigrateRecords};
+use crate::event::{proto, Event};
+use bytes::Bytes;
+use leveldb::database::{
+    batch::{Batch, Writebatch},
+    iterator::Iterable,
+    options::{Options, ReadOptions, WriteOptions},
+    Database,
+};
+use prost::Message;
+use snafu::ResultExt;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The format of the records of disk buffers. Bump it whenever the encoding
+/// of the records changes, and teach `decode` to read the previous one.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Written to the directory of each buffer, next to the files of the
+/// database, which leaves files it doesn't know about alone.
+const VERSION_FILE: &str = "VECTOR_BUFFER_VERSION";
+
+const RECORDS_PER_BATCH: usize = 100;
+
+#[derive(Debug, PartialEq)]
+pub enum Migration {
+    /// The records were already encoded like the current format.
+    Stamped,
+    /// The records were rewritten with the current format. The original
+    /// buffer is kept as `backup` when some of its records couldn't be read.
+    Rewritten {
+        records: usize,
+        undecodable: usize,
+        backup: Option<PathBuf>,
+    },
+}
+
+/// Reads the format version of the buffer at `path`, which is `None` for
+/// buffers that don't exist yet and 0 for those written before the format
+/// was versioned.
+pub fn read(path: &Path) -> Result<Option<u32>, Error> {
+    let version_path = path.join(VERSION_FILE);
+    match fs::read_to_string(&version_path) {
+        Ok(contents) => contents
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::InvalidVersion { path: version_path }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            Ok(if path.exists() { Some(0) } else { None })
+        }
+        Err(source) => Err(Error::BufferIo {
+            path: version_path,
+            source,
+        }),
+    }
+}
+
+/// Stamps the buffer at `path` with the current format version.
+pub fn write(path: &Path) -> Result<(), Error> {
+    let version_path = path.join(VERSION_FILE);
+    fs::write(&version_path, format!("{}\n", FORMAT_VERSION))
+        .context(BufferIo { path: version_path })
+}
+
+/// Decodes a record of a buffer of the given format version.
+pub fn decode(version: u32, value: Vec<u8>) -> Result<Event, prost::DecodeError> {
+    debug_assert!(version <= FORMAT_VERSION);
+    proto::EventWrapper::decode(Bytes::from(value)).map(Event::from)
+}
+
+/// Brings the buffer at `path`, with records of format version `from`, up to
+/// the current format. Rewritten records are written to a database next to
+/// the buffer, which only replaces it once complete, so an interrupted
+/// migration leaves the buffer as it was.
+pub fn migrate(path: &Path, from: u32) -> Result<Migration, Error> {
+    if encoded_like_current(from) {
+        write(path)?;
+        return Ok(Migration::Stamped);
+    }
+
+    let migrating = sibling(path, "migrating");
+    let backup = sibling(path, &format!("v{}", from));
+    if migrating.exists() {
+        // Left behind by an interrupted migration.
+        fs::remove_dir_all(&migrating).context(BufferIo { path: &migrating })?;
+    }
+
+    let (records, undecodable) = {
+        let source = open(path, false)?;
+        let target = open(&migrating, true)?;
+
+        let mut records = 0;
+        let mut undecodable = 0;
+        let mut batch = Writebatch::new();
+        let mut batch_size = 0;
+        for (key, value) in source.iter(ReadOptions::new()) {
+            match decode(from, value) {
+                Ok(event) => {
+                    let mut value = vec![];
+                    proto::EventWrapper::from(event).encode(&mut value).unwrap(); // This will not error when writing to a Vec
+                    batch.put(key, &value);
+                    batch_size += 1;
+                    records += 1;
+                }
+                Err(error) => {
+                    warn!(message = "Dropping undecodable buffer record.", offset = key.0, %error);
+                    undecodable += 1;
+                }
+            }
+
+            if batch_size >= RECORDS_PER_BATCH {
+                target
+                    .write(WriteOptions::new(), &batch)
+                    .context(MigrateRecords { path: &migrating })?;
+                batch = Writebatch::new();
+                batch_size = 0;
+            }
+        }
+        target
+            .write(WriteOptions::new(), &batch)
+            .context(MigrateRecords { path: &migrating })?;
+
+        (records, undecodable)
+    };
+    write(&migrating)?;
+
+    fs::rename(path, &backup).context(BufferIo { path })?;
+    fs::rename(&migrating, path).context(BufferIo { path: &migrating })?;
+    let backup = if undecodable > 0 {
+        Some(backup)
+    } else {
+        fs::remove_dir_all(&backup).context(BufferIo { path: &backup })?;
+        None
+    };
+
+    Ok(Migration::Rewritten {
+        records,
+        undecodable,
+        backup,
+    })
+}
+
+/// Whether records of the format version are encoded like the current ones,
+/// so buffers are migrated from it without rewriting them.
+fn encoded_like_current(version: u32) -> bool {
+    // Buffers written before the format was versioned share its encoding.
+    matches!(version, 0 | FORMAT_VERSION)
+}
+
+pub(super) fn open(path: &Path, create: bool) -> Result<Database<Key>, Error> {
+    let mut options = Options::new();
+    options.create_if_missing = create;
+    Database::open(path, options).with_context(|| DataDirOpenError {
+        data_dir: path.parent().expect("always a parent"),
+    })
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().expect("always a name").to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn reads_the_version_of_buffers() {
+        let path = temp_dir();
+        assert_eq!(read(&path).unwrap(), None);
+
+        fs::create_dir_all(&path).unwrap();
+        assert_eq!(read(&path).unwrap(), Some(0));
+
+        write(&path).unwrap();
+        assert_eq!(read(&path).unwrap(), Some(FORMAT_VERSION));
+
+        fs::write(path.join(VERSION_FILE), "next").unwrap();
+        assert!(matches!(read(&path), Err(Error::InvalidVersion { .. })));
+    }
+
+    #[test]
+    fn stamps_buffers_encoded_like_the_current_format() {
+        let path = temp_dir();
+        drop(open(&path, true).unwrap());
+
+        assert_eq!(migrate(&path, 0).unwrap(), Migration::Stamped);
+        assert_eq!(read(&path).unwrap(), Some(FORMAT_VERSION));
+    }
+}
@@ -1,12 +1,21 @@
-use crate::Event;
+use crate::{
+    event::in_flight::InFlightToken,
+    internal_events::{BufferEventDiscarded, BufferEventsUpdated},
+    Event,
+};
 use futures01::{sync::mpsc, task::AtomicTask, AsyncSink, Poll, Sink, StartSend, Stream};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
-    Arc,
+    Arc, Mutex,
 };
+use tracing::Span;
 
+#[cfg(feature = "leveldb")]
+pub mod cmd;
+pub mod compact;
 #[cfg(feature = "leveldb")]
 pub mod disk;
 
@@ -19,6 +28,8 @@ pub enum BufferConfig {
         max_events: usize,
         #[serde(default)]
         when_full: WhenFull,
+        #[serde(default)]
+        storage: MemoryStorage,
     },
     #[cfg(feature = "leveldb")]
     Disk {
@@ -33,6 +44,7 @@ impl Default for BufferConfig {
         BufferConfig::Memory {
             max_events: BufferConfig::memory_max_events(),
             when_full: Default::default(),
+            storage: Default::default(),
         }
     }
 }
@@ -50,40 +62,125 @@ impl Default for WhenFull {
     }
 }
 
-pub enum BufferInputCloner {
-    Memory(mpsc::Sender<Event>, WhenFull),
+/// How events are held in memory buffers.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryStorage {
+    Events,
+    Serialized,
+    Lz4,
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        MemoryStorage::Events
+    }
+}
+
+enum BufferSender {
+    Memory(mpsc::Sender<Event>),
+    Compact(compact::Writer),
     #[cfg(feature = "leveldb")]
-    Disk(disk::Writer, WhenFull),
+    Disk(disk::Writer),
+}
+
+pub struct BufferInputCloner {
+    sender: BufferSender,
+    when_full: WhenFull,
+    usage: Option<BufferUsage>,
 }
 
 impl BufferInputCloner {
-    pub fn get(&self) -> Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> {
-        match self {
-            BufferInputCloner::Memory(tx, when_full) => {
-                let inner = tx
-                    .clone()
-                    .sink_map_err(|error| error!(message = "Sender error.", %error));
-                if when_full == &WhenFull::DropNewest {
-                    Box::new(DropWhenFull { inner })
-                } else {
-                    Box::new(inner)
-                }
-            }
+    pub fn memory(tx: mpsc::Sender<Event>, when_full: WhenFull) -> Self {
+        Self::new(BufferSender::Memory(tx), when_full)
+    }
 
+    fn new(sender: BufferSender, when_full: WhenFull) -> Self {
+        Self {
+            sender,
+            when_full,
+            usage: None,
+        }
+    }
+
+    /// Track the events sent into and discarded by the buffer.
+    pub fn with_usage(self, usage: BufferUsage) -> Self {
+        Self {
+            usage: Some(usage),
+            ..self
+        }
+    }
+
+    pub fn get(&self) -> Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> {
+        let inner: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> = match &self.sender {
+            BufferSender::Memory(tx) => Box::new(
+                tx.clone()
+                    .sink_map_err(|error| error!(message = "Sender error.", %error)),
+            ),
+            BufferSender::Compact(writer) => Box::new(writer.clone()),
             #[cfg(feature = "leveldb")]
-            BufferInputCloner::Disk(writer, when_full) => {
-                if when_full == &WhenFull::DropNewest {
-                    Box::new(DropWhenFull {
-                        inner: writer.clone(),
-                    })
-                } else {
-                    Box::new(writer.clone())
-                }
-            }
+            BufferSender::Disk(writer) => Box::new(writer.clone()),
+        };
+
+        let inner: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> = match &self.usage {
+            Some(usage) => Box::new(CountUsage {
+                inner,
+                usage: usage.clone(),
+            }),
+            None => inner,
+        };
+
+        if self.when_full == WhenFull::DropNewest {
+            Box::new(DropWhenFull {
+                inner,
+                usage: self.usage.clone(),
+            })
+        } else {
+            inner
         }
     }
 }
 
+/// Tracks the number of events in the buffer of a sink. The metrics are
+/// emitted in the span of the sink, as events are sent into the buffer from
+/// the tasks of the upstream components.
+///
+/// Disk buffers only count the events buffered since Vector started.
+#[derive(Clone)]
+pub struct BufferUsage {
+    events: Arc<AtomicUsize>,
+    span: Span,
+}
+
+impl BufferUsage {
+    pub fn new(span: Span) -> Self {
+        Self {
+            events: Arc::new(AtomicUsize::new(0)),
+            span,
+        }
+    }
+
+    fn push(&self) {
+        let events = self.events.fetch_add(1, Ordering::Relaxed) + 1;
+        self.span.in_scope(|| emit!(BufferEventsUpdated { events }));
+    }
+
+    pub fn pop(&self) {
+        let events = self
+            .events
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |events| {
+                Some(events.saturating_sub(1))
+            })
+            .unwrap_or(0)
+            .saturating_sub(1);
+        self.span.in_scope(|| emit!(BufferEventsUpdated { events }));
+    }
+
+    fn discard(&self) {
+        self.span.in_scope(|| emit!(BufferEventDiscarded));
+    }
+}
+
 impl BufferConfig {
     #[inline]
     const fn memory_max_events() -> usize {
@@ -107,9 +204,22 @@ impl BufferConfig {
             BufferConfig::Memory {
                 max_events,
                 when_full,
+                storage: MemoryStorage::Events,
             } => {
                 let (tx, rx) = mpsc::channel(*max_events);
-                let tx = BufferInputCloner::Memory(tx, *when_full);
+                let tx = BufferInputCloner::new(BufferSender::Memory(tx), *when_full);
+                let rx = Box::new(rx);
+                Ok((tx, rx, Acker::Null))
+            }
+
+            BufferConfig::Memory {
+                max_events,
+                when_full,
+                storage,
+            } => {
+                let compress = *storage == MemoryStorage::Lz4;
+                let (tx, rx) = compact::channel(*max_events, compress);
+                let tx = BufferInputCloner::new(BufferSender::Compact(tx), *when_full);
                 let rx = Box::new(rx);
                 Ok((tx, rx, Acker::Null))
             }
@@ -126,7 +236,7 @@ impl BufferConfig {
 
                 let (tx, rx, acker) = disk::open(&data_dir, buffer_dir.as_ref(), *max_size)
                     .map_err(|error| error.to_string())?;
-                let tx = BufferInputCloner::Disk(tx, *when_full);
+                let tx = BufferInputCloner::new(BufferSender::Disk(tx), *when_full);
                 let rx = Box::new(rx);
                 Ok((tx, rx, acker))
             }
@@ -137,6 +247,8 @@ impl BufferConfig {
 #[derive(Debug, Clone)]
 pub enum Acker {
     Disk(Arc<AtomicUsize>, Arc<AtomicTask>),
+    Skipping(Arc<SkippingAcker>),
+    Holding(Arc<HoldingAcker>),
     Null,
 }
 
@@ -156,10 +268,63 @@ impl Acker {
                     counter.fetch_add(num, Ordering::Relaxed);
                     notifier.notify();
                 }
+                Acker::Skipping(acker) => acker.ack(num),
+                Acker::Holding(acker) => acker.ack(num),
             }
         }
     }
 
+    /// Wraps the acker to hold the in flight tokens notifying the sources of
+    /// the events until the sink acked them, see `hold`.
+    pub fn holding(self) -> Self {
+        Acker::Holding(Arc::new(HoldingAcker {
+            inner: self,
+            held: Mutex::new(VecDeque::new()),
+        }))
+    }
+
+    /// Takes the tokens notifying the sources of the next event of the input
+    /// stream, releasing them once the sink acked the event.
+    pub fn hold(&self, event: &mut Event) {
+        if let Acker::Holding(acker) = self {
+            let tokens = match event {
+                Event::Log(log) => log.take_notifying_in_flight(),
+                Event::Metric(_) => Vec::new(),
+            };
+            acker.held.lock().unwrap().push_back(tokens);
+        }
+    }
+
+    /// Wraps the acker for a sink that doesn't receive every event of its
+    /// input stream, see `pass` and `skip`.
+    pub fn skipping(self) -> Self {
+        match self {
+            Acker::Null => Acker::Null,
+            inner => Acker::Skipping(Arc::new(SkippingAcker {
+                inner,
+                state: Mutex::new(SkippingState::default()),
+            })),
+        }
+    }
+
+    /// Notes that the next event of the input stream is passed on to the
+    /// sink, which acks it.
+    pub fn pass(&self) {
+        if let Acker::Skipping(acker) = self {
+            acker.state.lock().unwrap().passed += 1;
+        }
+    }
+
+    /// Notes that the next event of the input stream is not passed on to the
+    /// sink. It's acked once the sink acked all the events passed before it,
+    /// so the acks stay in the order of the input stream.
+    pub fn skip(&self) {
+        match self {
+            Acker::Skipping(acker) => acker.skip(),
+            acker => acker.ack(1),
+        }
+    }
+
     pub fn new_for_testing() -> (Self, Arc<AtomicUsize>) {
         let ack_counter = Arc::new(AtomicUsize::new(0));
         let notifier = Arc::new(AtomicTask::new());
@@ -169,8 +334,71 @@ impl Acker {
     }
 }
 
+#[derive(Debug)]
+pub struct SkippingAcker {
+    inner: Acker,
+    state: Mutex<SkippingState>,
+}
+
+#[derive(Debug, Default)]
+struct SkippingState {
+    /// The number of events passed on to the sink.
+    passed: usize,
+    /// The number of the passed events acked by the sink.
+    acked: usize,
+    /// The number of events passed before each skipped event not acked yet.
+    skipped: VecDeque<usize>,
+}
+
+impl SkippingAcker {
+    fn ack(&self, num: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.acked += num;
+        let mut acks = num;
+        while let Some(&passed) = state.skipped.front() {
+            if passed > state.acked {
+                break;
+            }
+            state.skipped.pop_front();
+            acks += 1;
+        }
+        self.inner.ack(acks);
+    }
+
+    fn skip(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.passed == state.acked {
+            self.inner.ack(1);
+        } else {
+            let passed = state.passed;
+            state.skipped.push_back(passed);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HoldingAcker {
+    inner: Acker,
+    /// The tokens of each event of the input stream not acked yet.
+    held: Mutex<VecDeque<Vec<InFlightToken>>>,
+}
+
+impl HoldingAcker {
+    fn ack(&self, num: usize) {
+        let released = {
+            let mut held = self.held.lock().unwrap();
+            let num = num.min(held.len());
+            held.drain(..num).collect::<Vec<_>>()
+        };
+        // Releasing the tokens notifies their sources, outside of the lock.
+        drop(released);
+        self.inner.ack(num);
+    }
+}
+
 pub struct DropWhenFull<S> {
     inner: S,
+    usage: Option<BufferUsage>,
 }
 
 impl<S: Sink> Sink for DropWhenFull<S> {
@@ -180,10 +408,10 @@ impl<S: Sink> Sink for DropWhenFull<S> {
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
         match self.inner.start_send(item) {
             Ok(AsyncSink::NotReady(_)) => {
-                debug!(
-                    message = "Shedding load; dropping event.",
-                    rate_limit_secs = 10
-                );
+                match &self.usage {
+                    Some(usage) => usage.discard(),
+                    None => emit!(BufferEventDiscarded),
+                }
                 Ok(AsyncSink::Ready)
             }
             other => other,
@@ -195,20 +423,52 @@ impl<S: Sink> Sink for DropWhenFull<S> {
     }
 }
 
+struct CountUsage<S> {
+    inner: S,
+    usage: BufferUsage,
+}
+
+impl<S: Sink> Sink for CountUsage<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        let result = self.inner.start_send(item);
+        if let Ok(AsyncSink::Ready) = result {
+            self.usage.push();
+        }
+        result
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.inner.poll_complete()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{Acker, BufferConfig, DropWhenFull, WhenFull};
+    use super::{
+        Acker, BufferConfig, BufferInputCloner, BufferUsage, DropWhenFull, MemoryStorage, WhenFull,
+    };
+    use crate::Event;
     use futures::compat::Future01CompatExt;
     use futures01::{future, sync::mpsc, task::AtomicTask, Async, AsyncSink, Sink, Stream};
-    use std::sync::{atomic::AtomicUsize, Arc};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
     use tokio01_test::task::MockTask;
+    use tracing::Span;
 
     #[tokio::test]
     async fn drop_when_full() {
         future::lazy(|| {
             let (tx, mut rx) = mpsc::channel(2);
 
-            let mut tx = DropWhenFull { inner: tx };
+            let mut tx = DropWhenFull {
+                inner: tx,
+                usage: None,
+            };
 
             assert_eq!(tx.start_send(1), Ok(AsyncSink::Ready));
             assert_eq!(tx.start_send(2), Ok(AsyncSink::Ready));
@@ -227,6 +487,32 @@ mod test {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn tracks_usage() {
+        future::lazy(|| {
+            let (tx, mut rx) = mpsc::channel(1);
+            let usage = BufferUsage::new(Span::none());
+            let cloner =
+                BufferInputCloner::memory(tx, WhenFull::DropNewest).with_usage(usage.clone());
+            let mut tx = cloner.get();
+
+            for _ in 0..3 {
+                assert_eq!(tx.start_send(Event::from("event")), Ok(AsyncSink::Ready));
+            }
+            // The last event has been discarded.
+            assert_eq!(usage.events.load(Ordering::Relaxed), 2);
+
+            assert!(matches!(rx.poll(), Ok(Async::Ready(Some(_)))));
+            usage.pop();
+            assert_eq!(usage.events.load(Ordering::Relaxed), 1);
+
+            future::ok::<(), ()>(())
+        })
+        .compat()
+        .await
+        .unwrap();
+    }
+
     #[test]
     fn ack_with_none() {
         let counter = Arc::new(AtomicUsize::new(0));
@@ -244,6 +530,56 @@ mod test {
         assert!(mock.is_notified());
     }
 
+    #[test]
+    fn skipping_acks_in_order() {
+        let (acker, counter) = Acker::new_for_testing();
+        let acker = acker.skipping();
+
+        // Skipping the first event acks it right away.
+        acker.skip();
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+
+        acker.pass();
+        acker.pass();
+        acker.skip();
+        acker.pass();
+        acker.skip();
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+
+        acker.ack(1);
+        assert_eq!(counter.load(Ordering::Relaxed), 2);
+        // The skipped event after the second passed one is acked with it.
+        acker.ack(1);
+        assert_eq!(counter.load(Ordering::Relaxed), 4);
+        acker.ack(1);
+        assert_eq!(counter.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    fn holding_releases_acked_tokens() {
+        let (acker, counter) = Acker::new_for_testing();
+        let acker = acker.holding();
+
+        let (token, mut released) = InFlightToken::notify();
+        let mut first = Event::from("first");
+        first.as_mut_log().add_in_flight(token.clone());
+        let mut second = Event::from("second");
+        second.as_mut_log().add_in_flight(token);
+        acker.hold(&mut first);
+        acker.hold(&mut second);
+
+        // The sink dropping the events doesn't release them.
+        drop(first);
+        drop(second);
+        assert!(released.try_recv().unwrap().is_none());
+
+        acker.ack(1);
+        assert!(released.try_recv().unwrap().is_none());
+        acker.ack(1);
+        assert_eq!(released.try_recv().unwrap(), Some(()));
+        assert_eq!(counter.load(Ordering::Relaxed), 2);
+    }
+
     #[test]
     fn config_default_values() {
         fn check(source: &str, config: BufferConfig) {
@@ -258,6 +594,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 500,
                 when_full: WhenFull::Block,
+                storage: MemoryStorage::Events,
             },
         );
 
@@ -269,6 +606,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 100,
                 when_full: WhenFull::Block,
+                storage: MemoryStorage::Events,
             },
         );
 
@@ -280,6 +618,19 @@ mod test {
             BufferConfig::Memory {
                 max_events: 500,
                 when_full: WhenFull::DropNewest,
+                storage: MemoryStorage::Events,
+            },
+        );
+
+        check(
+            r#"
+          type = "memory"
+          storage = "lz4"
+          "#,
+            BufferConfig::Memory {
+                max_events: 500,
+                when_full: WhenFull::Block,
+                storage: MemoryStorage::Lz4,
             },
         );
 
@@ -1,6 +1,11 @@
+#[cfg(feature = "leveldb")]
+use crate::buffers;
+use crate::pipelines::PipelineOpt;
 #[cfg(feature = "api-client")]
 use crate::top;
-use crate::{config, generate, get_version, list, unit_test, validate};
+use crate::{
+    analyze, config, config_schema, dry_run, generate, get_version, list, unit_test, validate,
+};
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
@@ -32,7 +37,10 @@ impl Opts {
         let (quiet_level, verbose_level) = match self.sub_command {
             Some(SubCommand::Validate(_))
             | Some(SubCommand::Generate(_))
-            | Some(SubCommand::List(_)) => {
+            | Some(SubCommand::List(_))
+            | Some(SubCommand::ConfigSchema(_))
+            | Some(SubCommand::DryRun(_))
+            | Some(SubCommand::Analyze(_)) => {
                 if self.root.verbose == 0 {
                     (self.root.quiet + 1, self.root.verbose)
                 } else {
@@ -79,6 +87,12 @@ pub struct RootOpts {
     #[structopt(name = "config-yaml", long, env = "VECTOR_CONFIG_YAML")]
     pub config_paths_yaml: Vec<PathBuf>,
 
+    /// Run configuration files as a pipeline, with its own topology and data_dir, given as
+    /// `<name>=<path>[,<path>...]`. Repeat to run several pipelines in the process. Wildcard
+    /// paths are supported. Can't be combined with the `--config` options.
+    #[structopt(name = "pipeline", long, number_of_values = 1)]
+    pub pipelines: Vec<PipelineOpt>,
+
     /// Exit on startup if any sinks fail healthchecks
     #[structopt(short, long, env = "VECTOR_REQUIRE_HEALTHY")]
     pub require_healthy: bool,
@@ -87,6 +101,15 @@ pub struct RootOpts {
     #[structopt(short, long, env = "VECTOR_THREADS")]
     pub threads: Option<usize>,
 
+    /// Write the PID of the process to this file, removed on exit.
+    #[structopt(long, env = "VECTOR_PID_FILE")]
+    pub pid_file: Option<PathBuf>,
+
+    /// Raise the limit of open files of the process to this value at startup.
+    /// Only supported on Unix.
+    #[structopt(long, env = "VECTOR_OPEN_FILES_LIMIT")]
+    pub open_files_limit: Option<u64>,
+
     /// Enable more detailed internal logging. Repeat to increase level. Overridden by `--quiet`.
     #[structopt(short, long, parse(from_occurrences))]
     pub verbose: u8,
@@ -138,10 +161,26 @@ pub enum SubCommand {
     /// List available components, then exit.
     List(list::Opts),
 
+    /// Print a JSON Schema of the configuration, derived from the options of the compiled-in
+    /// components, then exit.
+    ConfigSchema(config_schema::Opts),
+
     /// Run Vector config unit tests, then exit. This command is experimental and therefore subject to change.
     /// For guidance on how to write unit tests check out: https://vector.dev/docs/setup/guides/unit-testing/
     Test(unit_test::Opts),
 
+    /// Run sample events through the transforms of the target config, without starting its
+    /// sources and sinks, and print what each transform received and emitted, then exit.
+    DryRun(dry_run::Opts),
+
+    /// Report the types, cardinality, null rate and size distribution of each field of sample
+    /// events, then exit.
+    Analyze(analyze::Opts),
+
+    /// List, inspect, export and migrate the disk buffers of the data directory, then exit.
+    #[cfg(feature = "leveldb")]
+    Buffer(buffers::cmd::Opts),
+
     /// Display topology and metrics in the console, for a local or remote Vector instance
     #[cfg(feature = "api-client")]
     Top(top::Opts),
@@ -1,7 +1,11 @@
+use crate::http::CorsConfig;
 use serde::{Deserialize, Serialize};
-use std::net::{Ipv4Addr, SocketAddr};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -12,6 +16,13 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    /// Replaces the default of allowing any origin.
+    pub cors: Option<CorsConfig>,
+
+    /// The file the overrides of runtime tunable options are loaded from and
+    /// persisted to.
+    pub overrides_path: Option<PathBuf>,
 }
 
 impl Default for Options {
@@ -20,6 +31,8 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            cors: None,
+            overrides_path: None,
         }
     }
 }
@@ -60,10 +73,31 @@ impl Options {
             }
         };
 
+        let cors = match (self.cors.clone(), other.cors) {
+            (None, b) => b,
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(_), Some(_)) => return Err("Conflicting `api` cors options.".to_owned()),
+        };
+
+        let overrides_path = match (self.overrides_path.clone(), other.overrides_path) {
+            (None, b) => b,
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(a), Some(b)) => {
+                return Err(format!(
+                    "Conflicting `api` overrides_path: {:?}, {:?} .",
+                    a, b
+                ))
+            }
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            cors,
+            overrides_path,
         };
 
         *self = options;
@@ -77,6 +111,8 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        cors: None,
+        overrides_path: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -87,6 +123,8 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            cors: None,
+            overrides_path: None,
         }
     );
 }
@@ -98,6 +136,8 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        cors: None,
+        overrides_path: None,
     };
 
     a.merge(Options::default()).unwrap();
@@ -108,6 +148,8 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            cors: None,
+            overrides_path: None,
         }
     );
 }
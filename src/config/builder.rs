@@ -1,8 +1,9 @@
 #[cfg(feature = "api")]
 use super::api;
 use super::{
-    compiler, default_data_dir, Config, GlobalOptions, SinkConfig, SinkOuter, SourceConfig,
-    TestDefinition, TransformConfig, TransformOuter,
+    compiler, default_data_dir, Config, GlobalOptions, HostnameStrategy, SinkConfig, SinkOuter,
+    SourceConfig, SourceOuter, TestDefinition, TimestampSkewOptions, TransformConfig,
+    TransformOuter,
 };
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
@@ -16,7 +17,7 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub api: api::Options,
     #[serde(default)]
-    pub sources: IndexMap<String, Box<dyn SourceConfig>>,
+    pub sources: IndexMap<String, SourceOuter>,
     #[serde(default)]
     pub sinks: IndexMap<String, SinkOuter>,
     #[serde(default)]
@@ -47,7 +48,8 @@ impl ConfigBuilder {
     }
 
     pub fn add_source<S: SourceConfig + 'static, T: Into<String>>(&mut self, name: T, source: S) {
-        self.sources.insert(name.into(), Box::new(source));
+        self.sources
+            .insert(name.into(), SourceOuter::new(Box::new(source)));
     }
 
     pub fn add_sink<S: SinkConfig + 'static, T: Into<String>>(
@@ -62,6 +64,7 @@ impl ConfigBuilder {
             healthcheck: true,
             inner: Box::new(sink),
             inputs,
+            max_event_age_secs: None,
         };
 
         self.sinks.insert(name.into(), sink);
@@ -77,6 +80,7 @@ impl ConfigBuilder {
         let transform = TransformOuter {
             inner: Box::new(transform),
             inputs,
+            on_panic: Default::default(),
         };
 
         self.transforms.insert(name.into(), transform);
@@ -100,12 +104,52 @@ impl ConfigBuilder {
             errors.push("conflicting values for 'data_dir' found".to_owned());
         }
 
+        if self.global.hostname == HostnameStrategy::default() {
+            self.global.hostname = with.global.hostname;
+        } else if with.global.hostname != HostnameStrategy::default()
+            && self.global.hostname != with.global.hostname
+        {
+            errors.push("conflicting values for 'hostname' found".to_owned());
+        }
+
+        if self.global.timestamp_skew == TimestampSkewOptions::default() {
+            self.global.timestamp_skew = with.global.timestamp_skew;
+        } else if with.global.timestamp_skew != TimestampSkewOptions::default()
+            && self.global.timestamp_skew != with.global.timestamp_skew
+        {
+            errors.push("conflicting values for 'timestamp_skew' found".to_owned());
+        }
+
+        if self.global.max_event_age_secs.is_none() {
+            self.global.max_event_age_secs = with.global.max_event_age_secs;
+        } else if with.global.max_event_age_secs.is_some()
+            && self.global.max_event_age_secs != with.global.max_event_age_secs
+        {
+            errors.push("conflicting values for 'max_event_age_secs' found".to_owned());
+        }
+
+        for (key, value) in with.global.tags {
+            match self.global.tags.get(&key) {
+                Some(existing) if *existing != value => {
+                    errors.push(format!("conflicting values for tag '{}' found", key));
+                }
+                _ => {
+                    self.global.tags.insert(key, value);
+                }
+            }
+        }
+        self.global.tag_events |= with.global.tag_events;
+
         // If the user has multiple config files, we must *merge* log schemas until we meet a
         // conflict, then we are allowed to error.
         if let Err(merge_errors) = self.global.log_schema.merge(with.global.log_schema) {
             errors.extend(merge_errors);
         }
 
+        if let Err(merge_errors) = self.global.request.merge(with.global.request) {
+            errors.extend(merge_errors);
+        }
+
         with.sources.keys().for_each(|k| {
             if self.sources.contains_key(k) {
                 errors.push(format!("duplicate source name found: {}", k));
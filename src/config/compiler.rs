@@ -11,6 +11,7 @@ pub fn compile(raw: ConfigBuilder, deny_warnings: bool) -> Result<Config, Vec<St
         transforms: raw.transforms,
         tests: raw.tests,
         expansions: Default::default(),
+        pipeline: None,
     };
 
     let mut errors = Vec::new();
@@ -33,6 +34,13 @@ pub fn compile(raw: ConfigBuilder, deny_warnings: bool) -> Result<Config, Vec<St
         errors.extend(type_errors);
     }
 
+    #[cfg(feature = "api")]
+    if let Some(cors) = &config.api.cors {
+        if let Err(error) = cors.build(&[]) {
+            errors.push(format!("API: {}", error));
+        }
+    }
+
     if errors.is_empty() {
         Ok(config)
     } else {
@@ -57,11 +65,17 @@ pub(super) fn expand_macros(config: &mut Config) -> Result<(), Vec<String>> {
         } {
             let mut children = Vec::new();
             for (name, child) in expanded {
-                let full_name = format!("{}.{}", k, name);
+                // A child without a name keeps the name of the transform.
+                let full_name = if name.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", k, name)
+                };
                 expanded_transforms.insert(
                     full_name.clone(),
                     TransformOuter {
                         inputs: t.inputs.clone(),
+                        on_panic: t.on_panic,
                         inner: child,
                     },
                 );
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use snafu::Snafu;
 use std::marker::PhantomData;
 use toml::Value;
@@ -12,6 +13,55 @@ pub enum ExampleError {
     DoesNotExist { type_str: String },
 }
 
+/// An option of a component, as found in its example config.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentOption {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub value_type: &'static str,
+    /// The value of the option in the example config, which is its default
+    /// for most components. Tables have their options instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<ComponentOption>,
+}
+
+impl ComponentOption {
+    /// The options of a table, sorted by name. Options without a value in the
+    /// example config, as most optional ones, aren't part of it.
+    pub fn from_table(value: &Value) -> Vec<Self> {
+        let mut options = value
+            .as_table()
+            .map(|table| {
+                table
+                    .iter()
+                    .map(|(name, value)| Self::new(name.clone(), value))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        options.sort_by(|a, b| a.name.cmp(&b.name));
+        options
+    }
+
+    fn new(name: String, value: &Value) -> Self {
+        match value {
+            Value::Table(_) => Self {
+                name,
+                value_type: "table",
+                example: None,
+                options: Self::from_table(value),
+            },
+            _ => Self {
+                name,
+                value_type: value.type_str(),
+                example: serde_json::to_value(value).ok(),
+                options: Vec::new(),
+            },
+        }
+    }
+}
+
 /// Describes a component plugin storing its type name, an example config, and
 /// other useful information about the plugin.
 pub struct ComponentDescription<T: Sized> {
@@ -47,6 +97,20 @@ where
             .and_then(|t| (t.example_value)().ok_or(ExampleError::MissingExample))
     }
 
+    /// Returns the options of the example config of a plugin identified by its type.
+    pub fn options(type_str: &str) -> Result<Vec<ComponentOption>, ExampleError> {
+        Self::example(type_str).map(|example| ComponentOption::from_table(&example))
+    }
+
+    /// Returns all plugins registered of a type, sorted, with the options of
+    /// their example config, if they have one.
+    pub fn types_with_options() -> Vec<(&'static str, Vec<ComponentOption>)> {
+        Self::types()
+            .into_iter()
+            .map(|type_str| (type_str, Self::options(type_str).unwrap_or_default()))
+            .collect()
+    }
+
     /// Returns a sorted Vec of all plugins registered of a type.
     pub fn types() -> Vec<&'static str> {
         let mut types = Vec::new();
@@ -57,3 +121,57 @@ where
         types
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_the_options_of_examples() {
+        let example: Value = toml::from_str(
+            r#"
+            address = "0.0.0.0:80"
+            max_length = 102400
+            batch = { max_events = 10, timeout_secs = 1.5 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ComponentOption::from_table(&example),
+            vec![
+                ComponentOption {
+                    name: "address".into(),
+                    value_type: "string",
+                    example: Some("0.0.0.0:80".into()),
+                    options: vec![],
+                },
+                ComponentOption {
+                    name: "batch".into(),
+                    value_type: "table",
+                    example: None,
+                    options: vec![
+                        ComponentOption {
+                            name: "max_events".into(),
+                            value_type: "integer",
+                            example: Some(10.into()),
+                            options: vec![],
+                        },
+                        ComponentOption {
+                            name: "timeout_secs".into(),
+                            value_type: "float",
+                            example: Some(1.5.into()),
+                            options: vec![],
+                        },
+                    ],
+                },
+                ComponentOption {
+                    name: "max_length".into(),
+                    value_type: "integer",
+                    example: Some(102400.into()),
+                    options: vec![],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Support for enabling components depending on the environment.
+//!
+//! Every source, transform and sink accepts an `enabled` option, which is
+//! usually set through an environment variable, such as
+//! `enabled = "${ENABLE_DEBUG_SINK:-false}"`. Disabled components are removed
+//! before the config is deserialized, along with any references to them from
+//! the `inputs` of other components.
+
+use super::{SinkOuter, SourceOuter, TransformOuter};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+const ENABLED: &str = "enabled";
+const COMPONENT_KINDS: &[&str] = &["sources", "transforms", "sinks"];
+
+/// Remove the disabled components from a config, and replace the boolean
+/// strings environment variables expand to in the `enabled` options of both
+/// components and option blocks with booleans.
+///
+/// Returns whether anything has been changed.
+pub fn apply(config: &mut Value) -> Result<bool, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut changed = false;
+    let mut disabled = Vec::new();
+
+    if let Value::Object(config) = config {
+        for kind in COMPONENT_KINDS {
+            let components = match config.get_mut(*kind) {
+                Some(Value::Object(components)) => components,
+                _ => continue,
+            };
+
+            let mut names = Vec::new();
+            for (name, component) in components.iter_mut() {
+                let option = match component {
+                    Value::Object(component) => component.remove(ENABLED),
+                    _ => None,
+                };
+                let option = match option {
+                    Some(option) => option,
+                    None => continue,
+                };
+                match parse_enabled(&option) {
+                    Ok(true) => (),
+                    Ok(false) => names.push(name.clone()),
+                    Err(value) => errors.push(format!(
+                        "invalid value {} for 'enabled' of {} {:?}, expected a boolean",
+                        value,
+                        kind.trim_end_matches('s'),
+                        name
+                    )),
+                }
+                changed = true;
+            }
+
+            for name in &names {
+                components.remove(name);
+            }
+            disabled.extend(names);
+        }
+
+        for kind in COMPONENT_KINDS {
+            if let Some(Value::Object(components)) = config.get_mut(*kind) {
+                for component in components.values_mut() {
+                    if let Some(Value::Array(inputs)) = component.get_mut("inputs") {
+                        let len = inputs.len();
+                        inputs.retain(|input| {
+                            input
+                                .as_str()
+                                .map_or(true, |input| !disabled.iter().any(|name| name == input))
+                        });
+                        changed |= inputs.len() != len;
+                    }
+                }
+            }
+        }
+
+        changed |= coerce_enabled(config);
+    }
+
+    if errors.is_empty() {
+        Ok(changed)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Whether a config may have `enabled` options, so that those without any are
+/// deserialized directly rather than through a JSON value.
+pub fn may_apply(config: &str) -> bool {
+    config.contains(ENABLED)
+}
+
+/// The errors of deserializing a config after `apply`, located by the
+/// components they are in, as the errors of a JSON value have no line.
+pub fn locate(config: &Value, error: serde_json::Error) -> Vec<String> {
+    let mut errors = Vec::new();
+    for kind in COMPONENT_KINDS {
+        let components = match config.get(*kind) {
+            Some(Value::Object(components)) => components,
+            _ => continue,
+        };
+        for (name, component) in components {
+            let result = match *kind {
+                "sources" => SourceOuter::deserialize(component).map(drop),
+                "transforms" => TransformOuter::deserialize(component).map(drop),
+                _ => SinkOuter::deserialize(component).map(drop),
+            };
+            if let Err(error) = result {
+                errors.push(format!(
+                    "{} {:?}: {}",
+                    kind.trim_end_matches('s'),
+                    name,
+                    error
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        errors.push(error.to_string());
+    }
+    errors
+}
+
+/// Replace boolean strings in nested `enabled` options, such as the one of
+/// the `tls` block, with booleans.
+fn coerce_enabled(map: &mut Map<String, Value>) -> bool {
+    let mut changed = false;
+    for (key, value) in map.iter_mut() {
+        match value {
+            Value::String(_) if key == ENABLED => {
+                let enabled = parse_enabled(value).ok();
+                if let Some(enabled) = enabled {
+                    *value = Value::Bool(enabled);
+                    changed = true;
+                }
+            }
+            Value::Object(map) => changed |= coerce_enabled(map),
+            Value::Array(values) => {
+                for value in values {
+                    if let Value::Object(map) = value {
+                        changed |= coerce_enabled(map);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    changed
+}
+
+fn parse_enabled(value: &Value) -> Result<bool, &Value> {
+    match value {
+        Value::Bool(enabled) => Ok(*enabled),
+        Value::String(enabled) => match enabled.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(true),
+            "false" | "no" | "off" | "0" => Ok(false),
+            _ => Err(value),
+        },
+        _ => Err(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn removes_disabled_components() {
+        let mut config = json!({
+            "sources": {
+                "in": { "type": "stdin" },
+                "debug_in": { "type": "stdin", "enabled": "false" }
+            },
+            "sinks": {
+                "out": { "type": "console", "inputs": ["in", "debug_in"], "enabled": true },
+                "debug_out": { "type": "console", "inputs": ["in"], "enabled": "no" }
+            }
+        });
+
+        assert!(apply(&mut config).unwrap());
+        assert_eq!(
+            config,
+            json!({
+                "sources": { "in": { "type": "stdin" } },
+                "sinks": { "out": { "type": "console", "inputs": ["in"] } }
+            })
+        );
+    }
+
+    #[test]
+    fn coerces_nested_enabled() {
+        let mut config = json!({
+            "sinks": {
+                "out": {
+                    "type": "socket",
+                    "inputs": ["in"],
+                    "tls": { "enabled": "TRUE" }
+                }
+            }
+        });
+
+        assert!(apply(&mut config).unwrap());
+        assert_eq!(config["sinks"]["out"]["tls"]["enabled"], json!(true));
+    }
+
+    #[test]
+    fn leaves_config_without_enabled_unchanged() {
+        let mut config = json!({
+            "sources": { "in": { "type": "stdin" } },
+            "sinks": { "out": { "type": "console", "inputs": ["in"] } }
+        });
+        let expected = config.clone();
+
+        assert!(!apply(&mut config).unwrap());
+        assert_eq!(config, expected);
+    }
+
+    #[cfg(all(feature = "sources-stdin", feature = "sinks-console"))]
+    #[test]
+    fn locates_errors_by_component() {
+        let config = json!({
+            "sources": { "in": { "type": "stdin" } },
+            "sinks": { "out": { "type": "console", "inputs": ["in"], "encoding": 1 } }
+        });
+        let error = serde_json::from_str::<Value>("[").unwrap_err();
+
+        let errors = locate(&config, error);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with(r#"sink "out": "#), "{}", errors[0]);
+    }
+
+    #[test]
+    fn rejects_invalid_enabled() {
+        let mut config = json!({
+            "sources": { "in": { "type": "stdin", "enabled": "" } }
+        });
+
+        let errors = apply(&mut config).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![r#"invalid value "" for 'enabled' of source "in", expected a boolean"#.to_owned()]
+        );
+    }
+}
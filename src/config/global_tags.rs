@@ -0,0 +1,116 @@
+use crate::{
+    event::{Event, Metric},
+    transforms::FunctionTransform,
+};
+use std::collections::BTreeMap;
+
+/// The tags of the `tags` global option, added to the internal metrics and,
+/// with `tag_events`, to the output of every source. Events keep the values
+/// they already have.
+#[derive(Clone, Debug, Default)]
+pub struct GlobalTags {
+    tags: BTreeMap<String, String>,
+}
+
+impl GlobalTags {
+    pub fn new(tags: BTreeMap<String, String>) -> Self {
+        Self { tags }
+    }
+
+    pub fn tag(&self, event: &mut Event) {
+        match event {
+            Event::Log(log) => {
+                for (key, value) in &self.tags {
+                    if !log.contains(key) {
+                        log.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Event::Metric(metric) => self.tag_metric(metric),
+        }
+    }
+
+    pub fn tag_metric(&self, metric: &mut Metric) {
+        if self.tags.is_empty() {
+            return;
+        }
+        let tags = metric.tags.get_or_insert_with(BTreeMap::new);
+        for (key, value) in &self.tags {
+            tags.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+impl FunctionTransform for GlobalTags {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        self.tag(&mut event);
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{MetricKind, MetricValue};
+
+    fn tags() -> GlobalTags {
+        GlobalTags::new(
+            vec![
+                ("environment".to_owned(), "production".to_owned()),
+                ("cluster".to_owned(), "eu-1".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn tags_logs() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("cluster", "us-1");
+
+        tags().tag(&mut event);
+
+        let log = event.as_log();
+        assert_eq!(log["environment"], "production".into());
+        assert_eq!(log["cluster"], "us-1".into());
+    }
+
+    #[test]
+    fn tags_metrics() {
+        let mut metric = Metric {
+            name: "hits_total".into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                vec![("cluster".to_owned(), "us-1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+
+        tags().tag_metric(&mut metric);
+
+        let tags = metric.tags.unwrap();
+        assert_eq!(tags["environment"], "production");
+        assert_eq!(tags["cluster"], "us-1");
+    }
+
+    #[test]
+    fn leaves_untagged_metrics_alone() {
+        let mut metric = Metric {
+            name: "hits_total".into(),
+            namespace: None,
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+
+        GlobalTags::default().tag_metric(&mut metric);
+
+        assert_eq!(metric.tags, None);
+    }
+}
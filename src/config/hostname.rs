@@ -0,0 +1,316 @@
+use crate::{
+    http::{HttpClient, HttpError},
+    tls::{TlsError, TlsSettings},
+};
+use http::{Method, Request};
+use hyper::Body;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{sync::RwLock, time::Duration};
+
+/// The hostname resolved with the configured strategy, replaced when a
+/// reload changes the strategy.
+static HOSTNAME: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+/// The hostname resolved with the configured strategy, if the
+/// configuration has been loaded.
+pub fn resolved_hostname() -> Option<String> {
+    HOSTNAME.read().unwrap().clone()
+}
+
+pub fn set_resolved_hostname(hostname: String) {
+    *HOSTNAME.write().unwrap() = Some(hostname);
+}
+
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Snafu)]
+pub enum HostnameError {
+    #[snafu(display("Unable to read the system hostname: {}", source))]
+    System { source: std::io::Error },
+    #[snafu(display("Environment variable {:?} is not set", var))]
+    MissingEnv { var: String },
+    #[snafu(display("Unable to build the metadata request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Unable to configure TLS for the metadata client: {}", source))]
+    Tls { source: TlsError },
+    #[snafu(display("Unable to create the metadata client: {}", source))]
+    Client { source: HttpError },
+    #[snafu(display("Metadata request failed: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Unable to read the metadata response: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Metadata request timed out"))]
+    Timeout,
+    #[snafu(display("Metadata request failed with status {}", status))]
+    Status { status: http::StatusCode },
+    #[snafu(display("Metadata service returned an empty hostname"))]
+    Empty,
+}
+
+/// How the `host` stamped on events is determined.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum HostnameStrategy {
+    /// The hostname as reported by the operating system.
+    System,
+    /// The system hostname up to the first dot.
+    Short,
+    /// The canonical name the resolver returns for the system hostname.
+    Fqdn,
+    /// The value of an environment variable.
+    Env {
+        #[serde(default = "default_env_var")]
+        var: String,
+    },
+    /// The hostname reported by the instance metadata service of a cloud
+    /// provider.
+    CloudMetadata {
+        provider: CloudProvider,
+        /// Overrides the address of the metadata service.
+        endpoint: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudProvider {
+    Aws,
+    Azure,
+    Gcp,
+}
+
+fn default_env_var() -> String {
+    "HOSTNAME".to_owned()
+}
+
+impl Default for HostnameStrategy {
+    fn default() -> Self {
+        HostnameStrategy::System
+    }
+}
+
+impl HostnameStrategy {
+    pub async fn resolve(&self) -> Result<String, HostnameError> {
+        match self {
+            HostnameStrategy::System => system_hostname(),
+            HostnameStrategy::Short => {
+                let hostname = system_hostname()?;
+                Ok(hostname.split('.').next().unwrap_or_default().to_owned())
+            }
+            HostnameStrategy::Fqdn => {
+                let hostname = system_hostname()?;
+                let lookup = hostname.clone();
+                let canonical = tokio::task::spawn_blocking(move || canonical_name(&lookup))
+                    .await
+                    .ok()
+                    .flatten();
+                if canonical.is_none() {
+                    warn!(
+                        message = "Unable to resolve the fully qualified hostname, using the system hostname.",
+                        %hostname
+                    );
+                }
+                Ok(canonical.unwrap_or(hostname))
+            }
+            HostnameStrategy::Env { var } => std::env::var(var)
+                .ok()
+                .filter(|hostname| !hostname.is_empty())
+                .ok_or_else(|| HostnameError::MissingEnv { var: var.clone() }),
+            HostnameStrategy::CloudMetadata { provider, endpoint } => {
+                tokio::time::timeout(METADATA_TIMEOUT, provider.hostname(endpoint.as_deref()))
+                    .await
+                    .map_err(|_| HostnameError::Timeout)?
+            }
+        }
+    }
+}
+
+impl CloudProvider {
+    fn default_endpoint(self) -> &'static str {
+        match self {
+            CloudProvider::Aws | CloudProvider::Azure => "http://169.254.169.254",
+            CloudProvider::Gcp => "http://metadata.google.internal",
+        }
+    }
+
+    async fn hostname(self, endpoint: Option<&str>) -> Result<String, HostnameError> {
+        let endpoint = endpoint
+            .unwrap_or_else(|| self.default_endpoint())
+            .trim_end_matches('/');
+        let tls = TlsSettings::from_options(&None).context(Tls)?;
+        let client = HttpClient::new(tls).context(Client)?;
+
+        let request = match self {
+            CloudProvider::Aws => {
+                // IMDSv2 requires a session token.
+                let request = Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("{}/latest/api/token", endpoint))
+                    .header("X-aws-ec2-metadata-token-ttl-seconds", "60")
+                    .body(Body::empty())
+                    .context(BuildRequest)?;
+                let token = fetch(&client, request).await?;
+                Request::get(format!("{}/latest/meta-data/local-hostname", endpoint))
+                    .header("X-aws-ec2-metadata-token", token)
+                    .body(Body::empty())
+            }
+            CloudProvider::Azure => Request::get(format!(
+                "{}/metadata/instance/compute/name?api-version=2020-09-01&format=text",
+                endpoint
+            ))
+            .header("Metadata", "true")
+            .body(Body::empty()),
+            CloudProvider::Gcp => {
+                Request::get(format!("{}/computeMetadata/v1/instance/hostname", endpoint))
+                    .header("Metadata-Flavor", "Google")
+                    .body(Body::empty())
+            }
+        }
+        .context(BuildRequest)?;
+
+        let hostname = fetch(&client, request).await?;
+        if hostname.is_empty() {
+            Err(HostnameError::Empty)
+        } else {
+            Ok(hostname)
+        }
+    }
+}
+
+async fn fetch(client: &HttpClient, request: Request<Body>) -> Result<String, HostnameError> {
+    let response = client.send(request).await.context(SendRequest)?;
+    let (parts, body) = response.into_parts();
+    if !parts.status.is_success() {
+        return Err(HostnameError::Status {
+            status: parts.status,
+        });
+    }
+    let body = hyper::body::to_bytes(body).await.context(ReadBody)?;
+    Ok(String::from_utf8_lossy(&body).trim().to_owned())
+}
+
+fn system_hostname() -> Result<String, HostnameError> {
+    Ok(hostname::get().context(System)?.to_string_lossy().into())
+}
+
+#[cfg(unix)]
+fn canonical_name(hostname: &str) -> Option<String> {
+    use std::ffi::{CStr, CString};
+
+    let node = CString::new(hostname).ok()?;
+    // Safety: the hints are a valid, zeroed `addrinfo` apart from the flags,
+    // and the result list is only read if the lookup succeeded and is freed
+    // exactly once.
+    unsafe {
+        let mut hints: libc::addrinfo = std::mem::zeroed();
+        hints.ai_family = libc::AF_UNSPEC;
+        hints.ai_flags = libc::AI_CANONNAME;
+        let mut result = std::ptr::null_mut();
+        if libc::getaddrinfo(node.as_ptr(), std::ptr::null(), &hints, &mut result) != 0
+            || result.is_null()
+        {
+            return None;
+        }
+        let canonname = (*result).ai_canonname;
+        let name = if canonname.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(canonname).to_string_lossy().into_owned())
+        };
+        libc::freeaddrinfo(result);
+        name
+    }
+}
+
+#[cfg(not(unix))]
+fn canonical_name(_hostname: &str) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+
+    #[test]
+    fn parses_strategies() {
+        let parse = |toml: &str| toml::from_str::<HostnameStrategy>(toml).unwrap();
+
+        assert_eq!(parse(r#"strategy = "short""#), HostnameStrategy::Short);
+        assert_eq!(
+            parse(r#"strategy = "env""#),
+            HostnameStrategy::Env {
+                var: "HOSTNAME".to_owned()
+            }
+        );
+        assert_eq!(
+            parse(
+                r#"strategy = "cloud_metadata"
+                provider = "gcp""#
+            ),
+            HostnameStrategy::CloudMetadata {
+                provider: CloudProvider::Gcp,
+                endpoint: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_short_hostname() {
+        let hostname = HostnameStrategy::Short.resolve().await.unwrap();
+        assert!(!hostname.contains('.'));
+        assert!(system_hostname().unwrap().starts_with(&hostname));
+    }
+
+    #[tokio::test]
+    async fn resolves_from_env() {
+        std::env::set_var("VECTOR_TEST_HOSTNAME", "node-1.example.com");
+        let strategy = HostnameStrategy::Env {
+            var: "VECTOR_TEST_HOSTNAME".to_owned(),
+        };
+        assert_eq!(strategy.resolve().await.unwrap(), "node-1.example.com");
+
+        let strategy = HostnameStrategy::Env {
+            var: "VECTOR_TEST_HOSTNAME_MISSING".to_owned(),
+        };
+        assert!(strategy.resolve().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_from_aws_metadata() {
+        let addr = next_addr();
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, crate::Error>(service_fn(|request: Request<Body>| async move {
+                let body = match (request.method(), request.uri().path()) {
+                    (&Method::PUT, "/latest/api/token") => "token",
+                    (&Method::GET, "/latest/meta-data/local-hostname") => {
+                        assert_eq!(request.headers()["x-aws-ec2-metadata-token"], "token");
+                        "ip-10-0-0-1.ec2.internal\n"
+                    }
+                    _ => panic!("Unexpected request {:?}", request),
+                };
+                Ok::<_, crate::Error>(Response::new(Body::from(body)))
+            }))
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let strategy = HostnameStrategy::CloudMetadata {
+            provider: CloudProvider::Aws,
+            endpoint: Some(format!("http://{}", addr)),
+        };
+        assert_eq!(
+            strategy.resolve().await.unwrap(),
+            "ip-10-0-0-1.ec2.internal"
+        );
+    }
+}
@@ -1,6 +1,9 @@
-use super::{builder::ConfigBuilder, format, handle_warnings, vars, Config, Format, FormatHint};
+use super::{
+    builder::ConfigBuilder, conditional, format, handle_warnings, vars, Config, Format, FormatHint,
+};
 use glob::glob;
 use lazy_static::lazy_static;
+use serde::Deserialize;
 use std::{
     collections::HashMap,
     fs::File,
@@ -166,5 +169,17 @@ fn load(
     let (with_vars, warnings) = vars::interpolate(&source_string, &vars);
     handle_warnings(warnings, deny_warnings)?;
 
+    // Configs that don't enable components conditionally are deserialized
+    // directly, which keeps the locations in the errors of the format. The
+    // errors of the others are located by the component they are in.
+    if conditional::may_apply(&with_vars) {
+        if let Ok(mut value) = format::deserialize::<serde_json::Value>(&with_vars, format) {
+            if conditional::apply(&mut value)? {
+                return ConfigBuilder::deserialize(&value)
+                    .map_err(|error| conditional::locate(&value, error));
+            }
+        }
+    }
+
     format::deserialize(&with_vars, format)
 }
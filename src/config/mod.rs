@@ -1,27 +1,40 @@
 use crate::{
-    buffers::Acker, conditions, event::Metric, shutdown::ShutdownSignal, sinks, sources,
-    transforms, Pipeline,
+    buffers::Acker,
+    conditions,
+    event::Metric,
+    shutdown::ShutdownSignal,
+    sinks, sources,
+    transforms::{self, FunctionTransform},
+    Pipeline,
 };
 use async_trait::async_trait;
 use component::ComponentDescription;
 use indexmap::IndexMap; // IndexMap preserves insertion order, allowing us to output errors in the same order they are present in the file
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::fs::DirBuilder;
 use std::hash::Hash;
 use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 pub mod api;
 mod builder;
 mod compiler;
 pub mod component;
+mod conditional;
 mod diff;
 mod format;
+mod global_tags;
+mod hostname;
 mod loading;
 mod log_schema;
+mod request;
+mod secret;
+mod timestamp_decoding;
+mod timestamp_skew;
 mod unit_test;
 mod validation;
 mod vars;
@@ -30,9 +43,17 @@ pub mod watcher;
 pub use builder::ConfigBuilder;
 pub use diff::ConfigDiff;
 pub use format::{Format, FormatHint};
+pub use global_tags::GlobalTags;
+pub use hostname::{resolved_hostname, set_resolved_hostname, CloudProvider, HostnameStrategy};
 pub use loading::{load_from_paths, load_from_str, merge_path_lists, process_paths, CONFIG_PATHS};
 pub use log_schema::{log_schema, LogSchema, LOG_SCHEMA};
-pub use unit_test::build_unit_tests_main as build_unit_tests;
+pub use request::RequestOptions;
+pub use secret::{Secret, SecretError, SecretString};
+pub use timestamp_decoding::TimestampDecoding;
+pub use timestamp_skew::TimestampSkewOptions;
+pub use unit_test::{
+    build_dry_run, build_unit_tests_main as build_unit_tests, DryRun, DryRunTrace,
+};
 pub use validation::warnings;
 
 #[derive(Debug, Default)]
@@ -40,11 +61,14 @@ pub struct Config {
     pub global: GlobalOptions,
     #[cfg(feature = "api")]
     pub api: api::Options,
-    pub sources: IndexMap<String, Box<dyn SourceConfig>>,
+    pub sources: IndexMap<String, SourceOuter>,
     pub sinks: IndexMap<String, SinkOuter>,
     pub transforms: IndexMap<String, TransformOuter>,
     tests: Vec<TestDefinition>,
     expansions: IndexMap<String, Vec<String>>,
+    /// The name of the pipeline the config is run as, if the process runs
+    /// several of them.
+    pub pipeline: Option<String>,
 }
 
 #[derive(Default, Debug, Deserialize, Serialize)]
@@ -56,6 +80,33 @@ pub struct GlobalOptions {
         default
     )]
     pub log_schema: LogSchema,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub hostname: HostnameStrategy,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub timestamp_skew: TimestampSkewOptions,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub request: RequestOptions,
+    /// Sinks drop events older than this, unless they set their own.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_event_age_secs: Option<u64>,
+    /// Tags added to all internal metrics.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub tags: BTreeMap<String, String>,
+    /// Whether the `tags` are also added to the events of every source.
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub tag_events: bool,
 }
 
 pub fn default_data_dir() -> Option<PathBuf> {
@@ -84,6 +135,20 @@ pub enum DataDirError {
 }
 
 impl GlobalOptions {
+    pub fn global_tags(&self) -> GlobalTags {
+        GlobalTags::new(self.tags.clone())
+    }
+
+    /// The inline transform adding the `tags` to the output of every source,
+    /// if enabled.
+    pub fn event_tags(&self) -> Option<Box<dyn FunctionTransform>> {
+        if self.tag_events && !self.tags.is_empty() {
+            Some(Box::new(self.global_tags()))
+        } else {
+            None
+        }
+    }
+
     /// Resolve the `data_dir` option in either the global or local
     /// config, and validate that it exists and is writable.
     pub fn resolve_and_validate_data_dir(
@@ -150,6 +215,28 @@ macro_rules! impl_generate_config_from_default {
     };
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SourceOuter {
+    /// The maximum number of log events of the source in flight downstream,
+    /// past which it stops reading until some of them are processed.
+    pub max_in_flight_events: Option<NonZeroUsize>,
+    /// Parses the timestamp of the events from one of their fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<TimestampDecoding>,
+    #[serde(flatten)]
+    pub inner: Box<dyn SourceConfig>,
+}
+
+impl SourceOuter {
+    pub fn new(inner: Box<dyn SourceConfig>) -> Self {
+        Self {
+            max_in_flight_events: None,
+            timestamp: None,
+            inner,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 #[async_trait]
 #[typetag::serde(tag = "type")]
@@ -183,6 +270,9 @@ pub struct SinkOuter {
     #[serde(default = "healthcheck_default")]
     pub healthcheck: bool,
     pub inputs: Vec<String>,
+    /// Events older than this are dropped instead of being sent.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_event_age_secs: Option<u64>,
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
@@ -208,17 +298,40 @@ pub trait SinkConfig: core::fmt::Debug + Send + Sync {
 #[derive(Debug, Clone)]
 pub struct SinkContext {
     pub(super) acker: Acker,
+    pub(super) name: String,
+    pub(super) data_dir: Option<PathBuf>,
+    pub(super) request: RequestOptions,
 }
 
 impl SinkContext {
     #[cfg(test)]
     pub fn new_test() -> Self {
-        Self { acker: Acker::Null }
+        Self {
+            acker: Acker::Null,
+            name: "test".to_owned(),
+            data_dir: None,
+            request: RequestOptions::default(),
+        }
     }
 
     pub fn acker(&self) -> Acker {
         self.acker.clone()
     }
+
+    /// The global `request` options, applied to every HTTP based sink.
+    pub fn request(&self) -> &RequestOptions {
+        &self.request
+    }
+
+    /// Resolve the `data_dir` option of the sink against the global one, and
+    /// ensure a subdirectory named after the sink exists inside of it.
+    pub fn resolve_and_make_data_subdir(&self, local: Option<&PathBuf>) -> crate::Result<PathBuf> {
+        let globals = GlobalOptions {
+            data_dir: self.data_dir.clone(),
+            ..Default::default()
+        };
+        globals.resolve_and_make_data_subdir(local, &self.name)
+    }
 }
 
 pub type SinkDescription = ComponentDescription<Box<dyn SinkConfig>>;
@@ -228,10 +341,38 @@ inventory::collect!(SinkDescription);
 #[derive(Deserialize, Serialize, Debug)]
 pub struct TransformOuter {
     pub inputs: Vec<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub on_panic: PanicOptions,
     #[serde(flatten)]
     pub inner: Box<dyn TransformConfig>,
 }
 
+/// How the topology handles a panic of a transform.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct PanicOptions {
+    pub policy: PanicPolicy,
+    /// How many times the transform is restarted before shutting down.
+    #[derivative(Default(value = "5"))]
+    pub max_restarts: u32,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PanicPolicy {
+    /// Shut down Vector, or the pipeline of the transform.
+    #[derivative(Default)]
+    Abort,
+    /// Build the transform again and let it take over the input and output
+    /// of the panicked one, after a backoff.
+    Restart,
+}
+
 #[async_trait]
 #[typetag::serde(tag = "type")]
 pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
@@ -245,7 +386,8 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
 
     /// Allows a transform configuration to expand itself into multiple "child"
     /// transformations to replace it. This allows a transform to act as a macro
-    /// for various patterns.
+    /// for various patterns. A child named with an empty string keeps the name
+    /// of the transform, for transforms adding outputs to their own.
     fn expand(&mut self) -> crate::Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
         Ok(None)
     }
@@ -390,6 +532,15 @@ impl Config {
             .cloned()
             .unwrap_or_else(|| vec![String::from(identifier)])
     }
+
+    /// The name a component is reported under, in the metrics, the API and the
+    /// tunable options, which is prefixed with the name of its pipeline.
+    pub fn qualified_name(&self, name: &str) -> String {
+        match &self.pipeline {
+            Some(pipeline) => format!("{}.{}", pipeline, name),
+            None => name.to_owned(),
+        }
+    }
 }
 
 fn handle_warnings(warnings: Vec<String>, deny_warnings: bool) -> Result<(), Vec<String>> {
@@ -416,7 +567,7 @@ fn healthcheck_default() -> bool {
     feature = "transforms-json_parser"
 ))]
 mod test {
-    use super::{builder::ConfigBuilder, format, load_from_str, Format};
+    use super::{builder::ConfigBuilder, format, load_from_str, Format, PanicPolicy};
     use std::path::PathBuf;
 
     #[test]
@@ -497,6 +648,109 @@ mod test {
         assert_eq!("then", config.global.log_schema.timestamp_key().to_string());
     }
 
+    #[test]
+    fn max_in_flight_events() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+            max_in_flight_events = 1000
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        let source = &config.sources["in"];
+        assert_eq!(source.inner.source_type(), "file");
+        assert_eq!(source.max_in_flight_events.map(|max| max.get()), Some(1000));
+
+        let zero = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+            max_in_flight_events = 0
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        );
+        assert!(zero.is_err());
+    }
+
+    #[test]
+    fn timestamp_decoding() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+            timestamp.field = "time"
+            timestamp.formats = ["%F %T", "%s"]
+            timestamp.timezone = "Europe/Berlin"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        let source = &config.sources["in"];
+        assert_eq!(source.inner.source_type(), "file");
+        let timestamp = source.timestamp.as_ref().unwrap();
+        assert_eq!(timestamp.field, "time");
+        assert_eq!(timestamp.formats, vec!["%F %T", "%s"]);
+        assert_eq!(timestamp.timezone.to_string(), "Europe/Berlin");
+    }
+
+    #[test]
+    fn on_panic() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+
+            [transforms.default]
+            type = "json_parser"
+            inputs = ["in"]
+
+            [transforms.restart]
+            type = "json_parser"
+            inputs = ["in"]
+            on_panic.policy = "restart"
+            on_panic.max_restarts = 3
+
+            [sinks.out]
+            type = "console"
+            inputs = ["default", "restart"]
+            encoding = "json"
+            "#,
+            Some(Format::TOML),
+        )
+        .unwrap();
+
+        let default = &config.transforms["default"].on_panic;
+        assert_eq!(default.policy, PanicPolicy::Abort);
+        assert_eq!(default.max_restarts, 5);
+
+        let restart = &config.transforms["restart"].on_panic;
+        assert_eq!(restart.policy, PanicPolicy::Restart);
+        assert_eq!(restart.max_restarts, 3);
+    }
+
     #[test]
     fn config_append() {
         let mut config: ConfigBuilder = format::deserialize(
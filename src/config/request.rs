@@ -0,0 +1,69 @@
+use super::SecretString;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Options of the requests of every HTTP based sink.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct RequestOptions {
+    /// Headers added to the requests, which sinks override with their own
+    /// `request.headers` of the same name.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, SecretString>,
+}
+
+impl RequestOptions {
+    /// Merges the headers of several configs, which may only set the same
+    /// header to the same value.
+    pub fn merge(&mut self, other: Self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for (name, value) in other.headers {
+            match self.headers.get(&name) {
+                Some(existing) if existing != &value => errors.push(format!(
+                    "conflicting values for 'request.headers.{}' found",
+                    name
+                )),
+                _ => {
+                    self.headers.insert(name, value);
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_headers() {
+        let mut options = RequestOptions::default();
+        options.headers.insert("X-Team".into(), "logs".into());
+
+        let mut other = RequestOptions::default();
+        other.headers.insert("X-Team".into(), "logs".into());
+        other.headers.insert("X-Site".into(), "fra".into());
+        options.merge(other).unwrap();
+        assert_eq!(
+            options.headers.keys().collect::<Vec<_>>(),
+            vec!["X-Team", "X-Site"]
+        );
+
+        let mut conflicting = RequestOptions::default();
+        conflicting
+            .headers
+            .insert("X-Team".into(), "metrics".into());
+        assert_eq!(
+            options.merge(conflicting),
+            Err(vec![
+                "conflicting values for 'request.headers.X-Team' found".to_owned()
+            ])
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{fs, path::PathBuf};
+
+/// A value given either in the configuration, or as a secret read when the
+/// component is built, so that it stays out of the configuration.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum SecretString {
+    Value(String),
+    Secret(Secret),
+}
+
+/// Where a secret is read from.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum Secret {
+    /// An environment variable.
+    Env(String),
+    /// A file, such as those mounted by orchestrators, without its trailing
+    /// newline.
+    File(PathBuf),
+}
+
+#[derive(Debug, Snafu)]
+pub enum SecretError {
+    #[snafu(display("Environment variable {:?} of secret is not set", name))]
+    MissingEnv { name: String },
+    #[snafu(display("Could not read secret from {:?}: {}", path, source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl SecretString {
+    pub fn is_secret(&self) -> bool {
+        matches!(self, Self::Secret(_))
+    }
+
+    pub fn resolve(&self) -> Result<String, SecretError> {
+        match self {
+            Self::Value(value) => Ok(value.clone()),
+            Self::Secret(Secret::Env(name)) => {
+                std::env::var(name).map_err(|_| SecretError::MissingEnv { name: name.clone() })
+            }
+            Self::Secret(Secret::File(path)) => fs::read_to_string(path)
+                .map(|value| value.trim_end_matches(&['\r', '\n'][..]).to_owned())
+                .context(ReadFile { path }),
+        }
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self::Value(value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_file;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn resolves_secrets() {
+        let path = temp_file();
+        fs::write(&path, "from file\n").unwrap();
+        std::env::set_var("VECTOR_TEST_SECRET", "from env");
+
+        let values: IndexMap<String, SecretString> = toml::from_str(&format!(
+            r#"
+            value = "in config"
+            env = {{ env = "VECTOR_TEST_SECRET" }}
+            file = {{ file = {:?} }}
+            missing = {{ env = "VECTOR_TEST_MISSING_SECRET" }}
+            "#,
+            path
+        ))
+        .unwrap();
+
+        assert!(!values["value"].is_secret());
+        assert_eq!(values["value"].resolve().unwrap(), "in config");
+        assert!(values["env"].is_secret());
+        assert_eq!(values["env"].resolve().unwrap(), "from env");
+        assert_eq!(values["file"].resolve().unwrap(), "from file");
+        assert!(matches!(
+            values["missing"].resolve(),
+            Err(SecretError::MissingEnv { .. })
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    config::log_schema,
+    event::{Event, Value},
+    internal_events::EventTimestampParseFailed,
+    sources::util::TimeZone,
+    transforms::FunctionTransform,
+    types::format_has_zone,
+};
+use chrono::{DateTime, NaiveDateTime, TimeZone as _, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Parsing of the timestamp of the events of a source from one of their
+/// fields, before they reach any transform.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TimestampDecoding {
+    /// The field holding the timestamp.
+    pub field: String,
+    /// The `strftime` formats tried in order.
+    #[serde(default = "default_formats")]
+    pub formats: Vec<String>,
+    /// The time zone of timestamps parsed with formats without zone
+    /// information.
+    #[serde(default)]
+    pub timezone: TimeZone,
+}
+
+fn default_formats() -> Vec<String> {
+    vec!["%+".to_owned()]
+}
+
+impl TimestampDecoding {
+    /// The inline transform to apply to the output of the source.
+    pub fn build(&self) -> crate::Result<Box<dyn FunctionTransform>> {
+        if self.formats.is_empty() {
+            return Err("Timestamp decoding requires at least one format".into());
+        }
+        let formats = self
+            .formats
+            .iter()
+            .map(|format| {
+                let kind = if format_has_zone(format) {
+                    FormatKind::Zoned
+                } else if format.contains("%s") {
+                    FormatKind::Epoch
+                } else {
+                    FormatKind::Local
+                };
+                (format.clone(), kind)
+            })
+            .collect();
+
+        Ok(Box::new(TimestampDecoder {
+            field: self.field.clone(),
+            formats,
+            timezone: self.timezone,
+        }))
+    }
+}
+
+/// How the timestamps of a format relate to the configured time zone.
+#[derive(Clone, Copy, Debug)]
+enum FormatKind {
+    /// Timestamps carrying their own offset.
+    Zoned,
+    /// Seconds since the epoch, which are absolute.
+    Epoch,
+    /// Wall-clock times of the configured time zone.
+    Local,
+}
+
+#[derive(Clone, Debug)]
+struct TimestampDecoder {
+    field: String,
+    formats: Vec<(String, FormatKind)>,
+    timezone: TimeZone,
+}
+
+impl TimestampDecoder {
+    fn parse(&self, value: &Value) -> Option<DateTime<Utc>> {
+        let text = match value {
+            Value::Timestamp(timestamp) => return Some(*timestamp),
+            Value::Bytes(_) | Value::Integer(_) | Value::Float(_) => value.to_string_lossy(),
+            _ => return None,
+        };
+        self.formats.iter().find_map(|(format, kind)| match kind {
+            FormatKind::Zoned => DateTime::parse_from_str(&text, format)
+                .ok()
+                .map(|datetime| datetime.with_timezone(&Utc)),
+            FormatKind::Epoch => Utc.datetime_from_str(&text, format).ok(),
+            FormatKind::Local => NaiveDateTime::parse_from_str(&text, format)
+                .ok()
+                .and_then(|datetime| self.timezone.localize(datetime)),
+        })
+    }
+}
+
+impl FunctionTransform for TimestampDecoder {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        if let Event::Log(log) = &mut event {
+            let parsed = log.get(&self.field).map(|value| self.parse(value));
+            match parsed {
+                Some(Some(timestamp)) => {
+                    log.insert(log_schema().timestamp_key(), timestamp);
+                }
+                Some(None) => emit!(EventTimestampParseFailed { field: &self.field }),
+                None => (),
+            }
+        }
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Tz;
+
+    fn decode(decoding: &TimestampDecoding, value: impl Into<Value>) -> Option<Value> {
+        let mut event = Event::from("hello");
+        event.as_mut_log().remove(log_schema().timestamp_key());
+        event.as_mut_log().insert("time", value.into());
+
+        let mut output = Vec::new();
+        decoding.build().unwrap().transform(&mut output, event);
+        assert_eq!(output.len(), 1);
+        output[0]
+            .as_log()
+            .get(log_schema().timestamp_key())
+            .cloned()
+    }
+
+    fn decoding(formats: &[&str]) -> TimestampDecoding {
+        TimestampDecoding {
+            field: "time".to_owned(),
+            formats: formats.iter().map(|format| format.to_string()).collect(),
+            timezone: TimeZone::Named(Tz::Europe__Berlin),
+        }
+    }
+
+    #[test]
+    fn parses_rfc3339_by_default() {
+        let decoding: TimestampDecoding = toml::from_str(r#"field = "time""#).unwrap();
+        assert_eq!(
+            decode(&decoding, "2020-10-14T08:09:10.5+02:00"),
+            Some(Utc.ymd(2020, 10, 14).and_hms_milli(6, 9, 10, 500).into())
+        );
+    }
+
+    #[test]
+    fn tries_formats_in_order() {
+        let decoding = decoding(&["%d/%b/%Y:%T %z", "%F %T", "%s"]);
+
+        assert_eq!(
+            decode(&decoding, "14/Oct/2020:08:09:10 +0000"),
+            Some(Utc.ymd(2020, 10, 14).and_hms(8, 9, 10).into())
+        );
+        assert_eq!(
+            decode(&decoding, "2020-10-14 08:09:10"),
+            Some(Utc.ymd(2020, 10, 14).and_hms(6, 9, 10).into())
+        );
+        assert_eq!(
+            decode(&decoding, 1602662950i64),
+            Some(Utc.ymd(2020, 10, 14).and_hms(8, 9, 10).into())
+        );
+    }
+
+    #[test]
+    fn keeps_events_failing_to_parse() {
+        let decoding = decoding(&["%F %T"]);
+        assert_eq!(decode(&decoding, "yesterday"), None);
+    }
+
+    #[test]
+    fn requires_formats() {
+        assert!(decoding(&[]).build().is_err());
+    }
+}
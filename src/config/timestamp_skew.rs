@@ -0,0 +1,135 @@
+use crate::{
+    config::log_schema,
+    event::{Event, Value},
+    internal_events::{EventTimestampSkew, EventTimestampSkewExceeded},
+    transforms::FunctionTransform,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Tracking of the difference between the time events are ingested by a
+/// source and the timestamp they carry.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct TimestampSkewOptions {
+    pub enabled: bool,
+    /// Field the skew is written to, in seconds. Positive values are events
+    /// from the past.
+    pub field: Option<String>,
+    /// Events older than this are reported.
+    pub max_past_secs: Option<u64>,
+    /// Events timestamped further than this in the future are reported.
+    pub max_future_secs: Option<u64>,
+}
+
+impl Default for TimestampSkewOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            field: None,
+            max_past_secs: Some(3600),
+            max_future_secs: Some(60),
+        }
+    }
+}
+
+impl TimestampSkewOptions {
+    /// The inline transform to apply to the output of every source.
+    pub fn build(&self) -> Option<Box<dyn FunctionTransform>> {
+        if self.enabled {
+            Some(Box::new(TimestampSkew {
+                options: self.clone(),
+            }))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TimestampSkew {
+    options: TimestampSkewOptions,
+}
+
+impl FunctionTransform for TimestampSkew {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        if let Event::Log(log) = &mut event {
+            let skew = match log.get(log_schema().timestamp_key()) {
+                Some(Value::Timestamp(timestamp)) => {
+                    Some((Utc::now() - *timestamp).num_milliseconds() as f64 / 1000.0)
+                }
+                _ => None,
+            };
+
+            if let Some(skew) = skew {
+                emit!(EventTimestampSkew { skew });
+
+                let threshold = if skew < 0.0 {
+                    self.options.max_future_secs
+                } else {
+                    self.options.max_past_secs
+                };
+                if let Some(threshold) = threshold {
+                    if skew.abs() > threshold as f64 {
+                        emit!(EventTimestampSkewExceeded { skew, threshold });
+                    }
+                }
+
+                if let Some(field) = &self.options.field {
+                    log.insert(field.clone(), skew);
+                }
+            }
+        }
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn skew_of(options: TimestampSkewOptions, offset: Duration) -> Option<Value> {
+        let mut event = Event::from("hello");
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), Utc::now() - offset);
+
+        let mut output = Vec::new();
+        options.build().unwrap().transform(&mut output, event);
+        assert_eq!(output.len(), 1);
+        output[0].as_log().get("skew").cloned()
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(TimestampSkewOptions::default().build().is_none());
+    }
+
+    #[test]
+    fn annotates_skew() {
+        let options = TimestampSkewOptions {
+            enabled: true,
+            field: Some("skew".to_owned()),
+            ..TimestampSkewOptions::default()
+        };
+
+        match skew_of(options.clone(), Duration::hours(2)) {
+            Some(Value::Float(skew)) => assert!((7199.0..7201.0).contains(&skew)),
+            other => panic!("unexpected skew {:?}", other),
+        }
+        match skew_of(options, Duration::minutes(-5)) {
+            Some(Value::Float(skew)) => assert!((-301.0..-299.0).contains(&skew)),
+            other => panic!("unexpected skew {:?}", other),
+        }
+    }
+
+    #[test]
+    fn annotation_is_optional() {
+        let options = TimestampSkewOptions {
+            enabled: true,
+            ..TimestampSkewOptions::default()
+        };
+        assert_eq!(skew_of(options, Duration::seconds(10)), None);
+    }
+}
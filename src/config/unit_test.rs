@@ -36,6 +36,7 @@ async fn build_unit_tests(builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<S
         transforms: builder.transforms,
         tests: builder.tests,
         expansions: Default::default(),
+        pipeline: None,
     };
 
     super::compiler::expand_macros(&mut config)?;
@@ -567,6 +568,140 @@ async fn build_unit_test(
     }
 }
 
+//------------------------------------------------------------------------------
+
+/// The transforms of a config, to run sample events through without starting
+/// its sources and sinks.
+pub struct DryRun {
+    inputs: Vec<(Vec<String>, Event)>,
+    transforms: IndexMap<String, UnitTestTransform>,
+}
+
+/// The events a transform received and emitted during a dry run.
+pub struct DryRunTrace {
+    pub name: String,
+    pub inputs: Vec<Event>,
+    pub outputs: Vec<Event>,
+}
+
+impl DryRun {
+    /// Runs the events through the transforms, returning the traces of the
+    /// transforms they reached in the order of the config.
+    pub fn run(&mut self) -> Vec<DryRunTrace> {
+        let mut results = HashMap::new();
+
+        let mut inputs_by_target = IndexMap::new();
+        for (targets, event) in &self.inputs {
+            for target in targets {
+                inputs_by_target
+                    .entry(target.clone())
+                    .or_insert_with(Vec::new)
+                    .push(event.clone());
+            }
+        }
+
+        for (target, inputs) in inputs_by_target {
+            walk(&target, inputs, &mut self.transforms, &mut results);
+        }
+
+        self.transforms
+            .keys()
+            .filter_map(|name| {
+                let (inputs, outputs) = results.remove(name)?;
+                Some(DryRunTrace {
+                    name: name.clone(),
+                    inputs,
+                    outputs,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Builds the transforms of the config, with the events inserted at the
+/// sources or transforms `insert_at`, or at all sources if it's empty.
+pub async fn build_dry_run(
+    config: &Config,
+    insert_at: &[String],
+    events: Vec<Event>,
+) -> Result<DryRun, Vec<String>> {
+    let mut errors = vec![];
+
+    let consumers = |name: &str| {
+        config
+            .transforms
+            .iter()
+            .filter(|(_, transform)| transform.inputs.iter().any(|input| input == name))
+            .map(|(consumer, _)| consumer.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let insert_at = if insert_at.is_empty() {
+        config.sources.keys().cloned().collect()
+    } else {
+        insert_at.to_vec()
+    };
+    let mut targets = Vec::new();
+    for name in insert_at {
+        let names = if config.sources.contains_key(&name) {
+            // The events of a source are received by the transforms consuming it.
+            consumers(&name)
+        } else {
+            let names = config.get_inputs(&name);
+            if !names
+                .iter()
+                .all(|expanded| config.transforms.contains_key(expanded))
+            {
+                errors.push(format!(
+                    "unable to locate source or transform '{}' to insert events at",
+                    name
+                ));
+            }
+            names
+        };
+        for name in names {
+            if !targets.contains(&name) {
+                targets.push(name);
+            }
+        }
+    }
+
+    let mut transforms = IndexMap::new();
+    for (name, transform_config) in &config.transforms {
+        match transform_config.inner.build().await {
+            Ok(transform) => {
+                transforms.insert(
+                    name.clone(),
+                    UnitTestTransform {
+                        transform,
+                        config: transform_config.inner.clone(),
+                        next: consumers(name),
+                    },
+                );
+            }
+            Err(err) => {
+                errors.push(format!(
+                    "failed to build transform '{}': {:#}",
+                    name,
+                    anyhow::anyhow!(err)
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(DryRun {
+            inputs: events
+                .into_iter()
+                .map(|event| (targets.clone(), event))
+                .collect(),
+            transforms,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
 #[cfg(all(
     test,
     feature = "transforms-add_fields",
@@ -1501,4 +1636,55 @@ mod tests {
                     ]);
                 */
     }
+
+    #[cfg(all(feature = "sources-file", feature = "sinks-console"))]
+    #[tokio::test]
+    async fn dry_run_traces_transforms() {
+        let config = crate::config::load_from_str(
+            r#"
+[sources.in]
+  type = "file"
+  include = ["/var/log/messages"]
+
+[transforms.bar]
+  inputs = ["in"]
+  type = "add_fields"
+  [transforms.bar.fields]
+    new_field = "string value"
+
+[transforms.baz]
+  inputs = ["bar"]
+  type = "add_fields"
+  [transforms.baz.fields]
+    other_field = "other value"
+
+[sinks.out]
+  type = "console"
+  inputs = ["baz"]
+  encoding = "json"
+      "#,
+            Some(crate::config::Format::TOML),
+        )
+        .unwrap();
+
+        let mut dry_run = build_dry_run(&config, &[], vec![Event::from("message")])
+            .await
+            .unwrap();
+        let traces = dry_run.run();
+
+        assert_eq!(
+            traces
+                .iter()
+                .map(|trace| (trace.name.as_str(), trace.inputs.len(), trace.outputs.len()))
+                .collect::<Vec<_>>(),
+            vec![("bar", 1, 1), ("baz", 1, 1)]
+        );
+        let output = traces[1].outputs[0].as_log();
+        assert_eq!(output["new_field"], "string value".into());
+        assert_eq!(output["other_field"], "other value".into());
+
+        assert!(build_dry_run(&config, &["missing".to_owned()], vec![])
+            .await
+            .is_err());
+    }
 }
@@ -50,7 +50,7 @@ pub fn check_resources(config: &Config) -> Result<(), Vec<String>> {
     let source_resources = config
         .sources
         .iter()
-        .map(|(name, config)| (name, config.resources()));
+        .map(|(name, config)| (name, config.inner.resources()));
     let sink_resources = config
         .sinks
         .iter()
@@ -229,7 +229,7 @@ impl From<&Config> for Graph {
 
         // TODO: validate that node names are unique across sources/transforms/sinks?
         for (name, config) in config.sources.iter() {
-            graph.add_source(name, config.output_type());
+            graph.add_source(name, config.inner.output_type());
         }
 
         for (name, config) in config.transforms.iter() {
@@ -0,0 +1,251 @@
+//! A JSON Schema of the configuration, derived from the options of the
+//! example configs of the compiled-in components and the defaults of the
+//! global options, for editors and CI to check config files against.
+//!
+//! The example configs don't list all the options of most components, so the
+//! schema only constrains the type of the options it knows of and allows any
+//! others.
+
+use crate::config::{
+    component::ComponentOption, HostnameStrategy, LogSchema, SinkDescription, SourceDescription,
+    TimestampSkewOptions, TransformDescription,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Print the schema on a single line.
+    #[structopt(long)]
+    compact: bool,
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let schema = schema();
+    let schema = if opts.compact {
+        serde_json::to_string(&schema)
+    } else {
+        serde_json::to_string_pretty(&schema)
+    };
+    println!(
+        "{}",
+        schema.expect("Serializing a JSON value doesn't fail.")
+    );
+
+    exitcode::OK
+}
+
+/// The schema of the whole configuration.
+pub fn schema() -> Value {
+    let inputs = json!({
+        "type": "array",
+        "items": { "type": "string" },
+    });
+
+    let mut sink_options = Map::new();
+    sink_options.insert("inputs".into(), inputs.clone());
+    sink_options.insert("healthcheck".into(), json!({ "type": "boolean" }));
+    sink_options.insert("buffer".into(), json!({ "type": "object" }));
+    let mut transform_options = Map::new();
+    transform_options.insert("inputs".into(), inputs);
+
+    let mut properties = Map::new();
+    properties.insert("data_dir".into(), json!({ "type": "string" }));
+    properties.insert("log_schema".into(), defaults_schema(LogSchema::default()));
+    properties.insert(
+        "hostname".into(),
+        defaults_schema(HostnameStrategy::default()),
+    );
+    properties.insert(
+        "timestamp_skew".into(),
+        defaults_schema(TimestampSkewOptions::default()),
+    );
+    properties.insert(
+        "tags".into(),
+        json!({
+            "type": "object",
+            "additionalProperties": { "type": "string" },
+        }),
+    );
+    properties.insert("tag_events".into(), json!({ "type": "boolean" }));
+    #[cfg(feature = "api")]
+    properties.insert(
+        "api".into(),
+        defaults_schema(crate::config::api::Options::default()),
+    );
+    properties.insert(
+        "sources".into(),
+        components_schema(SourceDescription::types_with_options(), Map::new(), &[]),
+    );
+    properties.insert(
+        "transforms".into(),
+        components_schema(
+            TransformDescription::types_with_options(),
+            transform_options,
+            &["inputs"],
+        ),
+    );
+    properties.insert(
+        "sinks".into(),
+        components_schema(
+            SinkDescription::types_with_options(),
+            sink_options,
+            &["inputs"],
+        ),
+    );
+    properties.insert(
+        "tests".into(),
+        json!({
+            "type": "array",
+            "items": { "type": "object" },
+        }),
+    );
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Vector configuration",
+        "type": "object",
+        "properties": properties,
+    })
+}
+
+/// The schema of the table of a kind of component, keyed by the names of the
+/// components. The options of each type apply to the components of that type.
+fn components_schema(
+    types: Vec<(&'static str, Vec<ComponentOption>)>,
+    mut common: Map<String, Value>,
+    required: &[&str],
+) -> Value {
+    let names = types.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    common.insert("type".into(), json!({ "type": "string", "enum": names }));
+
+    let types = types
+        .iter()
+        .map(|(name, options)| {
+            json!({
+                "if": { "properties": { "type": { "const": name } } },
+                "then": { "properties": options_schema(options) },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut required = required.to_vec();
+    required.insert(0, "type");
+    json!({
+        "type": "object",
+        "additionalProperties": {
+            "type": "object",
+            "required": required,
+            "properties": common,
+            "allOf": types,
+        },
+    })
+}
+
+/// The schema of a table with the options of `defaults`.
+fn defaults_schema(defaults: impl Serialize) -> Value {
+    let options = toml::Value::try_from(defaults)
+        .map(|defaults| ComponentOption::from_table(&defaults))
+        .unwrap_or_default();
+    json!({
+        "type": "object",
+        "properties": options_schema(&options),
+    })
+}
+
+fn options_schema(options: &[ComponentOption]) -> Map<String, Value> {
+    options
+        .iter()
+        // The type of a component is set by the table it's part of.
+        .filter(|option| option.name != "type")
+        .map(|option| (option.name.clone(), option_schema(option)))
+        .collect()
+}
+
+fn option_schema(option: &ComponentOption) -> Value {
+    let mut schema = match option.value_type {
+        "table" => {
+            return json!({
+                "type": "object",
+                "properties": options_schema(&option.options),
+            })
+        }
+        // TOML dates and times can only be written as strings in JSON and YAML.
+        "datetime" => json!({ "type": "string" }),
+        // Integers are valid floats.
+        "float" => json!({ "type": "number" }),
+        value_type => json!({ "type": value_type }),
+    };
+    if let Some(example) = &option.example {
+        schema["examples"] = json!([example]);
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_the_options_of_components() {
+        let options = ComponentOption::from_table(
+            &toml::from_str(
+                r#"
+                type = "ignored"
+                address = "0.0.0.0:80"
+                batch = { timeout_secs = 1.5 }
+                "#,
+            )
+            .unwrap(),
+        );
+
+        let schema = components_schema(vec![("socket", options)], Map::new(), &[]);
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "type": "string", "enum": ["socket"] },
+                    },
+                    "allOf": [{
+                        "if": { "properties": { "type": { "const": "socket" } } },
+                        "then": {
+                            "properties": {
+                                "address": { "type": "string", "examples": ["0.0.0.0:80"] },
+                                "batch": {
+                                    "type": "object",
+                                    "properties": {
+                                        "timeout_secs": { "type": "number", "examples": [1.5] },
+                                    },
+                                },
+                            },
+                        },
+                    }],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn describes_the_global_options() {
+        let schema = schema();
+        let properties = &schema["properties"];
+        assert_eq!(
+            properties["log_schema"]["properties"]["message_key"],
+            json!({ "type": "string", "examples": ["message"] })
+        );
+        assert_eq!(
+            properties["sinks"]["additionalProperties"]["required"],
+            json!(["type", "inputs"])
+        );
+        assert_eq!(
+            properties["sources"]["additionalProperties"]["properties"]["type"]["enum"],
+            json!(SourceDescription::types())
+        );
+    }
+}
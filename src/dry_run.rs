@@ -0,0 +1,313 @@
+use crate::{
+    cli::handle_config_errors,
+    config::{self, DryRunTrace},
+    event::{Event, LogEvent, Value},
+};
+use colored::*;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// File of sample events, one per line. Lines holding a JSON object become
+    /// log events with its fields, other lines log events with the line as
+    /// their message.
+    #[structopt(short, long)]
+    events: PathBuf,
+
+    /// Sources or transforms to insert the events at. If none are specified
+    /// the events are inserted at all sources.
+    #[structopt(long)]
+    insert_at: Vec<String>,
+
+    /// Print the trace of each transform as a JSON object per line.
+    #[structopt(long)]
+    json: bool,
+
+    /// Vector config files in TOML format to run.
+    #[structopt(name = "config-toml", long)]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format to run.
+    #[structopt(name = "config-json", long)]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format to run.
+    #[structopt(name = "config-yaml", long)]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Any number of Vector config files to run. If none are specified the
+    /// default config path `/etc/vector/vector.toml` will be targeted.
+    paths: Vec<PathBuf>,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<(PathBuf, config::FormatHint)> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::TOML)),
+            (&self.paths_json, Some(config::Format::JSON)),
+            (&self.paths_yaml, Some(config::Format::YAML)),
+        ])
+    }
+}
+
+/// Runs the sample events through the transforms of the config, without
+/// starting its sources and sinks, and prints what each transform received
+/// and emitted.
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let paths = match config::process_paths(&opts.paths_with_formats()) {
+        Some(paths) => paths,
+        None => return exitcode::CONFIG,
+    };
+    let config = match config::load_from_paths(&paths, false) {
+        Ok(config) => config,
+        Err(errors) => return handle_config_errors(errors),
+    };
+    config::LOG_SCHEMA
+        .set(config.global.log_schema.clone())
+        .ok();
+
+    let events = match read_events(&opts.events) {
+        Ok(events) => events,
+        Err(error) => {
+            error!(message = "Failed to read events.", path = ?opts.events, %error);
+            return exitcode::NOINPUT;
+        }
+    };
+
+    let mut dry_run = match config::build_dry_run(&config, &opts.insert_at, events).await {
+        Ok(dry_run) => dry_run,
+        Err(errors) => return handle_config_errors(errors),
+    };
+
+    for trace in dry_run.run() {
+        if opts.json {
+            println!(
+                "{}",
+                serde_json::to_string(&JsonTrace::from(&trace)).expect("Traces serialize to JSON.")
+            );
+        } else {
+            print_trace(&trace);
+        }
+    }
+
+    exitcode::OK
+}
+
+fn read_events(path: &Path) -> io::Result<Vec<Event>> {
+    let mut events = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        events.push(parse_event(line));
+    }
+    Ok(events)
+}
+
+/// Parses a line holding a JSON object into a log event with its fields, and
+/// other lines into a log event with the line as its message.
+pub(crate) fn parse_event(line: String) -> Event {
+    serde_json::from_str::<serde_json::Value>(&line)
+        .ok()
+        .and_then(|value| Event::try_from(value).ok())
+        .unwrap_or_else(|| Event::from(line))
+}
+
+#[derive(Debug, PartialEq)]
+enum FieldChange<'a> {
+    Added(String, &'a Value),
+    Removed(String, &'a Value),
+    Changed(String, &'a Value, &'a Value),
+}
+
+/// The changes of the fields from `input` to `output`, by path.
+fn diff<'a>(input: &'a LogEvent, output: &'a LogEvent) -> Vec<FieldChange<'a>> {
+    let mut before = input.all_fields().collect::<BTreeMap<_, _>>();
+    let mut changes = Vec::new();
+    for (path, value) in output.all_fields() {
+        match before.remove(&path) {
+            None => changes.push(FieldChange::Added(path, value)),
+            Some(old) if old != value => changes.push(FieldChange::Changed(path, old, value)),
+            Some(_) => (),
+        }
+    }
+    changes.extend(
+        before
+            .into_iter()
+            .map(|(path, value)| FieldChange::Removed(path, value)),
+    );
+    changes
+}
+
+/// Pairs the inputs and outputs of transforms which emit an event per event
+/// they receive, as the others can't be told apart.
+fn paired_logs(trace: &DryRunTrace) -> Option<Vec<(&LogEvent, &LogEvent)>> {
+    if trace.inputs.len() != trace.outputs.len() {
+        return None;
+    }
+    trace
+        .inputs
+        .iter()
+        .zip(trace.outputs.iter())
+        .map(|pair| match pair {
+            (Event::Log(input), Event::Log(output)) => Some((input, output)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn to_json(value: &impl Serialize) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".into())
+}
+
+fn event_to_json(event: &Event) -> String {
+    match event {
+        Event::Log(log) => to_json(log),
+        Event::Metric(metric) => to_json(metric),
+    }
+}
+
+fn print_trace(trace: &DryRunTrace) {
+    println!(
+        "transform '{}': {} input(s), {} output(s)",
+        trace.name.bold(),
+        trace.inputs.len(),
+        trace.outputs.len()
+    );
+
+    match paired_logs(trace) {
+        Some(pairs) => {
+            for (index, (input, output)) in pairs.into_iter().enumerate() {
+                let changes = diff(input, output);
+                if changes.is_empty() {
+                    println!("  event[{}]: unchanged", index);
+                    continue;
+                }
+                println!("  event[{}]:", index);
+                for change in changes {
+                    match change {
+                        FieldChange::Added(path, value) => {
+                            println!("{}", format!("    + {}: {}", path, to_json(value)).green())
+                        }
+                        FieldChange::Removed(path, value) => {
+                            println!("{}", format!("    - {}: {}", path, to_json(value)).red())
+                        }
+                        FieldChange::Changed(path, old, new) => println!(
+                            "{}",
+                            format!("    ~ {}: {} -> {}", path, to_json(old), to_json(new))
+                                .yellow()
+                        ),
+                    }
+                }
+            }
+        }
+        None => {
+            for input in &trace.inputs {
+                println!("  input:  {}", event_to_json(input));
+            }
+            for output in &trace.outputs {
+                println!("  output: {}", event_to_json(output));
+            }
+        }
+    }
+    println!();
+}
+
+#[derive(Serialize)]
+struct JsonTrace<'a> {
+    component: &'a str,
+    inputs: Vec<serde_json::Value>,
+    outputs: Vec<serde_json::Value>,
+    /// The changes of each event, if the inputs and outputs pair up.
+    changes: Option<Vec<JsonChanges<'a>>>,
+}
+
+#[derive(Default, Serialize)]
+struct JsonChanges<'a> {
+    added: BTreeMap<String, &'a Value>,
+    removed: Vec<String>,
+    changed: BTreeMap<String, (&'a Value, &'a Value)>,
+}
+
+impl<'a> From<&'a DryRunTrace> for JsonTrace<'a> {
+    fn from(trace: &'a DryRunTrace) -> Self {
+        let to_values = |events: &[Event]| {
+            events
+                .iter()
+                .map(|event| match event {
+                    Event::Log(log) => serde_json::to_value(log),
+                    Event::Metric(metric) => serde_json::to_value(metric),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_default()
+        };
+        let changes = paired_logs(trace).map(|pairs| {
+            pairs
+                .into_iter()
+                .map(|(input, output)| {
+                    let mut changes = JsonChanges::default();
+                    for change in diff(input, output) {
+                        match change {
+                            FieldChange::Added(path, value) => {
+                                changes.added.insert(path, value);
+                            }
+                            FieldChange::Removed(path, _) => changes.removed.push(path),
+                            FieldChange::Changed(path, old, new) => {
+                                changes.changed.insert(path, (old, new));
+                            }
+                        }
+                    }
+                    changes
+                })
+                .collect()
+        });
+
+        Self {
+            component: &trace.name,
+            inputs: to_values(&trace.inputs),
+            outputs: to_values(&trace.outputs),
+            changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_fields() {
+        let mut input = LogEvent::default();
+        input.insert("message", "GET /");
+        input.insert("host", "localhost");
+        input.insert("raw", "x");
+        let mut output = input.clone();
+        output.insert("http.method", "GET");
+        output.insert("host", "example.com");
+        output.remove("raw");
+
+        assert_eq!(
+            diff(&input, &output),
+            vec![
+                FieldChange::Changed(
+                    "host".into(),
+                    &Value::from("localhost"),
+                    &Value::from("example.com")
+                ),
+                FieldChange::Added("http.method".into(), &Value::from("GET")),
+                FieldChange::Removed("raw".into(), &Value::from("x")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Tracking of the log events of a source which are still in flight, that is
+//! held in memory by the channels, buffers, transforms and sinks downstream,
+//! to stop the source from reading more of them past a limit, or to notify
+//! the source once they have all been processed.
+
+use futures::channel::oneshot;
+use futures01::{
+    task::{self, Task},
+    Async,
+};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+const INVARIANT: &str = "In flight limit lock poisoned.";
+
+/// The number of events of a source in flight, and the tasks of the source
+/// waiting for some of them to be released.
+#[derive(Debug)]
+pub struct InFlightLimit {
+    max: usize,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    count: usize,
+    waiters: Vec<Task>,
+}
+
+impl InFlightLimit {
+    pub fn new(max: usize) -> Arc<Self> {
+        Arc::new(Self {
+            max,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().expect(INVARIANT).count
+    }
+
+    /// Takes a slot for an event, or registers the current task to be
+    /// notified once a slot is released.
+    pub fn poll_acquire(self: &Arc<Self>) -> Async<InFlightToken> {
+        let mut state = self.state.lock().expect(INVARIANT);
+        if state.count < self.max {
+            state.count += 1;
+            Async::Ready(InFlightToken(Arc::new(Slot::Limit(Arc::clone(self)))))
+        } else {
+            if !state.waiters.iter().any(Task::will_notify_current) {
+                state.waiters.push(task::current());
+            }
+            Async::NotReady
+        }
+    }
+
+    fn release(&self) {
+        let waiters = {
+            let mut state = self.state.lock().expect(INVARIANT);
+            state.count -= 1;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters {
+            waiter.notify();
+        }
+    }
+}
+
+/// The slot of an event in flight, shared by the events derived from it and
+/// released once all of them are dropped.
+#[derive(Clone)]
+pub struct InFlightToken(Arc<Slot>);
+
+impl InFlightToken {
+    /// A token not counted against any limit, which resolves the returned
+    /// receiver once all the events holding it are dropped.
+    pub fn notify() -> (Self, oneshot::Receiver<()>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Arc::new(Slot::Notify(Some(tx)))), rx)
+    }
+
+    /// Whether the token notifies its source, rather than counting against
+    /// a limit.
+    pub fn notifies(&self) -> bool {
+        matches!(*self.0, Slot::Notify(_))
+    }
+}
+
+enum Slot {
+    Limit(Arc<InFlightLimit>),
+    Notify(Option<oneshot::Sender<()>>),
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        match self {
+            Slot::Limit(limit) => limit.release(),
+            Slot::Notify(tx) => {
+                if let Some(tx) = tx.take() {
+                    // The receiver may not be interested anymore.
+                    let _ = tx.send(());
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for InFlightToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InFlightToken")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::compat::Future01CompatExt;
+    use futures01::future::poll_fn;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn releases_slots_of_dropped_tokens() {
+        let limit = InFlightLimit::new(2);
+        let acquire = || {
+            let limit = Arc::clone(&limit);
+            poll_fn(move || Ok::<_, ()>(limit.poll_acquire())).compat()
+        };
+
+        let first = acquire().await.unwrap();
+        let derived = first.clone();
+        let _second = acquire().await.unwrap();
+        assert_eq!(limit.in_flight(), 2);
+
+        let mut third = Box::pin(acquire());
+        assert!(futures::poll!(&mut third).is_pending());
+
+        drop(first);
+        assert_eq!(limit.in_flight(), 2);
+        drop(derived);
+        let _third = tokio::time::timeout(Duration::from_secs(1), third)
+            .await
+            .expect("Waiting task wasn't notified.")
+            .unwrap();
+        assert_eq!(limit.in_flight(), 2);
+    }
+
+    #[tokio::test]
+    async fn notifies_once_tokens_are_dropped() {
+        let (token, mut released) = InFlightToken::notify();
+        let derived = token.clone();
+
+        drop(token);
+        assert!(released.try_recv().unwrap().is_none());
+        drop(derived);
+        assert_eq!(released.try_recv().unwrap(), Some(()));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::event::{lookup::Segment, util, Lookup, PathComponent, Value};
+use crate::event::{in_flight::InFlightToken, lookup::Segment, util, Lookup, PathComponent, Value};
 use serde::{Serialize, Serializer};
 use std::{
     collections::{btree_map::Entry, BTreeMap, HashMap},
@@ -7,12 +7,31 @@ use std::{
     iter::FromIterator,
 };
 
-#[derive(PartialEq, Debug, Clone, Default)]
+#[derive(Derivative, Clone, Default)]
+#[derivative(PartialEq, Debug)]
 pub struct LogEvent {
     fields: BTreeMap<String, Value>,
+    #[derivative(PartialEq = "ignore", Debug = "ignore")]
+    in_flight: Vec<InFlightToken>,
 }
 
 impl LogEvent {
+    /// Counts the event, and the events derived from it, as in flight until
+    /// they are all dropped.
+    pub(crate) fn add_in_flight(&mut self, token: InFlightToken) {
+        self.in_flight.push(token);
+    }
+
+    /// Takes the tokens notifying the sources of the event, for the sink to
+    /// release them once it acked the event rather than dropped it.
+    pub(crate) fn take_notifying_in_flight(&mut self) -> Vec<InFlightToken> {
+        let (notifying, limits) = std::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition(InFlightToken::notifies);
+        self.in_flight = limits;
+        notifying
+    }
+
     #[instrument(level = "trace", skip(self, key), fields(key = %key.as_ref()))]
     pub fn get(&self, key: impl AsRef<str>) -> Option<&Value> {
         util::log::get(&self.fields, key.as_ref())
@@ -139,13 +158,16 @@ impl LogEvent {
 
 impl From<BTreeMap<String, Value>> for LogEvent {
     fn from(map: BTreeMap<String, Value>) -> Self {
-        LogEvent { fields: map }
+        LogEvent {
+            fields: map,
+            in_flight: Vec::new(),
+        }
     }
 }
 
 impl Into<BTreeMap<String, Value>> for LogEvent {
     fn into(self) -> BTreeMap<String, Value> {
-        let Self { fields } = self;
+        let Self { fields, .. } = self;
         fields
     }
 }
@@ -154,6 +176,7 @@ impl From<HashMap<String, Value>> for LogEvent {
     fn from(map: HashMap<String, Value>) -> Self {
         LogEvent {
             fields: map.into_iter().collect(),
+            in_flight: Vec::new(),
         }
     }
 }
@@ -186,7 +209,7 @@ impl TryInto<serde_json::Value> for LogEvent {
     type Error = crate::Error;
 
     fn try_into(self) -> Result<serde_json::Value, Self::Error> {
-        let Self { fields } = self;
+        let Self { fields, .. } = self;
         Ok(serde_json::to_value(fields)?)
     }
 }
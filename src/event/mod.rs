@@ -6,6 +6,7 @@ use std::collections::{BTreeMap, HashMap};
 use std::iter::FromIterator;
 
 pub mod discriminant;
+pub mod in_flight;
 pub mod merge;
 pub mod merge_state;
 pub mod metric;
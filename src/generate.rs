@@ -265,6 +265,7 @@ fn generate_example(
                         .unwrap_or_else(|| vec!["component-name".to_owned()]),
                     buffer: crate::buffers::BufferConfig::default(),
                     healthcheck: true,
+                    max_event_age_secs: None,
                     inner: example,
                 },
             );
@@ -1,11 +1,13 @@
 use crate::{
+    config::{SecretError, SecretString},
     dns::Resolver,
+    event::{Event, Value},
     internal_events::http_client,
     tls::{tls_connector_builder, MaybeTlsSettings, TlsError},
 };
 use futures::future::BoxFuture;
 use headers::{Authorization, HeaderMapExt};
-use http::header::HeaderValue;
+use http::header::{HeaderName, HeaderValue, InvalidHeaderName, InvalidHeaderValue};
 use http::request::Builder;
 use http::HeaderMap;
 use http::Request;
@@ -14,11 +16,14 @@ use hyper::{
     client::{Client, HttpConnector},
 };
 use hyper_openssl::HttpsConnector;
+use indexmap::IndexMap;
 use percent_encoding::percent_decode;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
+    collections::BTreeMap,
     fmt,
+    sync::Arc,
     task::{Context, Poll},
 };
 use tower::Service;
@@ -35,12 +40,50 @@ pub enum HttpError {
     CallRequest { source: hyper::Error },
 }
 
+#[derive(Debug, Snafu)]
+pub enum HeaderError {
+    #[snafu(display("Invalid header name {:?}: {}", name, source))]
+    InvalidName {
+        name: String,
+        source: InvalidHeaderName,
+    },
+    #[snafu(display("Invalid value of header {:?}: {}", name, source))]
+    InvalidValue {
+        name: String,
+        source: InvalidHeaderValue,
+    },
+    #[snafu(display("Could not resolve header {:?}: {}", name, source))]
+    ResolveSecret { name: String, source: SecretError },
+}
+
+/// Builds the custom headers of `configs`, where later configs override the
+/// headers of the same name of earlier ones. Values of secrets are marked as
+/// sensitive, which redacts them from the logs of the requests.
+pub fn build_headers<'a>(
+    configs: impl IntoIterator<Item = &'a IndexMap<String, SecretString>>,
+) -> Result<HeaderMap, HeaderError> {
+    let mut headers = HeaderMap::new();
+    for config in configs {
+        for (name, value) in config {
+            let header_name =
+                HeaderName::from_bytes(name.as_bytes()).context(InvalidName { name })?;
+            let resolved = value.resolve().context(ResolveSecret { name })?;
+            let mut header_value =
+                HeaderValue::from_str(&resolved).context(InvalidValue { name })?;
+            header_value.set_sensitive(value.is_secret());
+            headers.insert(header_name, header_value);
+        }
+    }
+    Ok(headers)
+}
+
 pub type HttpClientFuture = <HttpClient as Service<http::Request<Body>>>::Future;
 
 pub struct HttpClient<B = Body> {
     client: Client<HttpsConnector<HttpConnector<Resolver>>, B>,
     span: Span,
     user_agent: HeaderValue,
+    headers: Arc<HeaderMap>,
 }
 
 impl<B> HttpClient<B>
@@ -60,7 +103,7 @@ where
         let settings = settings.tls().cloned();
         https.set_callback(move |c, _uri| {
             if let Some(settings) = &settings {
-                settings.apply_connect_configuration(c);
+                settings.apply_connect_configuration(c)?;
             }
 
             Ok(())
@@ -78,15 +121,29 @@ where
             client,
             span,
             user_agent,
+            headers: Default::default(),
         })
     }
 
+    /// Adds `headers` to the requests sent, unless they set headers of the
+    /// same name themselves.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = Arc::new(headers);
+        self
+    }
+
     pub fn send(
         &self,
         mut request: Request<B>,
     ) -> BoxFuture<'static, Result<http::Response<Body>, HttpError>> {
         let _enter = self.span.enter();
 
+        for (name, value) in self.headers.iter() {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+
         if !request.headers().contains_key("User-Agent") {
             request
                 .headers_mut()
@@ -159,6 +216,7 @@ impl<B> Clone for HttpClient<B> {
             client: self.client.clone(),
             span: self.span.clone(),
             user_agent: self.user_agent.clone(),
+            headers: Arc::clone(&self.headers),
         }
     }
 }
@@ -168,6 +226,7 @@ impl<B> fmt::Debug for HttpClient<B> {
         f.debug_struct("HttpClient")
             .field("client", &self.client)
             .field("user_agent", &self.user_agent)
+            .field("headers", &self.headers)
             .finish()
     }
 }
@@ -240,10 +299,189 @@ impl Auth {
     }
 }
 
+/// Cross-origin resource sharing for the servers, so that browsers allow
+/// pages from other origins to make requests to them.
+#[cfg(any(feature = "api", feature = "sources-utils-http"))]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins such as `https://example.com` allowed to make requests, where
+    /// `*` allows any.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Headers, in addition to the simple ones, the requests may have.
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+    /// How long the browsers may cache the response to a preflight request.
+    pub max_age_secs: Option<u64>,
+}
+
+#[cfg(any(feature = "api", feature = "sources-utils-http"))]
+#[derive(Debug, Snafu)]
+pub enum CorsError {
+    #[snafu(display("Invalid CORS origin {:?}", origin))]
+    InvalidOrigin { origin: String },
+    #[snafu(display("Invalid CORS header {:?}: {}", header, source))]
+    InvalidAllowedHeader {
+        header: String,
+        source: http::header::InvalidHeaderName,
+    },
+}
+
+#[cfg(any(feature = "api", feature = "sources-utils-http"))]
+impl CorsConfig {
+    /// Builds the filter answering the preflight requests, which is checked
+    /// upfront as warp panics on invalid origins and headers.
+    pub fn build(&self, methods: &[&str]) -> Result<warp::filters::cors::Builder, CorsError> {
+        let mut cors = warp::cors().allow_methods(methods.iter().copied());
+
+        for origin in &self.allowed_origins {
+            if origin == "*" {
+                cors = cors.allow_any_origin();
+                continue;
+            }
+            let uri = origin
+                .parse::<http::Uri>()
+                .map_err(|_| CorsError::InvalidOrigin {
+                    origin: origin.clone(),
+                })?;
+            if uri.scheme().is_none() || uri.host().is_none() || uri.path() != "/" {
+                return Err(CorsError::InvalidOrigin {
+                    origin: origin.clone(),
+                });
+            }
+            cors = cors.allow_origin(origin.as_str());
+        }
+
+        for header in &self.allowed_headers {
+            http::header::HeaderName::from_bytes(header.as_bytes())
+                .context(InvalidAllowedHeader { header })?;
+        }
+        cors = cors.allow_headers(self.allowed_headers.iter().map(String::as_str));
+
+        if let Some(max_age_secs) = self.max_age_secs {
+            cors = cors.max_age(max_age_secs);
+        }
+
+        Ok(cors)
+    }
+}
+
+pub const TRACEPARENT: &str = "traceparent";
+pub const TRACESTATE: &str = "tracestate";
+
+/// A [W3C Trace Context](https://www.w3.org/TR/trace-context/), carried by
+/// the `traceparent` and `tracestate` headers, and stored in the events as a
+/// map of both.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceContext {
+    traceparent: String,
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    fn new(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let traceparent = traceparent.trim();
+        if !is_valid_traceparent(traceparent) {
+            return None;
+        }
+        Some(Self {
+            traceparent: traceparent.to_owned(),
+            tracestate: tracestate
+                .map(str::trim)
+                .filter(|tracestate| !tracestate.is_empty())
+                .map(str::to_owned),
+        })
+    }
+
+    /// The trace context of the headers, if their `traceparent` is valid.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let traceparent = headers.get(TRACEPARENT)?.to_str().ok()?;
+        // Multiple `tracestate` headers are combined as a single list.
+        let tracestate = headers
+            .get_all(TRACESTATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        Self::new(traceparent, Some(&tracestate))
+    }
+
+    /// The trace context stored in the `key` field of the event.
+    pub fn from_event(event: &Event, key: &str) -> Option<Self> {
+        match event.as_log().get(key)? {
+            Value::Map(map) => {
+                let traceparent = map.get(TRACEPARENT)?.to_string_lossy();
+                let tracestate = map.get(TRACESTATE).map(Value::to_string_lossy);
+                Self::new(&traceparent, tracestate.as_deref())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut map = BTreeMap::new();
+        map.insert(
+            TRACEPARENT.to_owned(),
+            Value::from(self.traceparent.as_str()),
+        );
+        if let Some(tracestate) = &self.tracestate {
+            map.insert(TRACESTATE.to_owned(), Value::from(tracestate.as_str()));
+        }
+        Value::Map(map)
+    }
+
+    pub fn apply_headers_map(&self, headers: &mut HeaderMap) {
+        if let Ok(traceparent) = HeaderValue::from_str(&self.traceparent) {
+            headers.insert(TRACEPARENT, traceparent);
+        }
+        match self
+            .tracestate
+            .as_ref()
+            .and_then(|tracestate| HeaderValue::from_str(tracestate).ok())
+        {
+            Some(tracestate) => {
+                headers.insert(TRACESTATE, tracestate);
+            }
+            None => {
+                headers.remove(TRACESTATE);
+            }
+        }
+    }
+}
+
+/// Checks the `version-trace_id-parent_id-flags` format of `traceparent`,
+/// allowing the fields added by future versions.
+fn is_valid_traceparent(traceparent: &str) -> bool {
+    fn is_hex(field: &str, len: usize) -> bool {
+        field.len() == len
+            && field
+                .bytes()
+                .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+    }
+
+    let fields = traceparent.split('-').collect::<Vec<_>>();
+    match fields.as_slice() {
+        [version, trace_id, parent_id, flags, rest @ ..] => {
+            is_hex(version, 2)
+                && *version != "ff"
+                && (*version != "00" || rest.is_empty())
+                && is_hex(trace_id, 32)
+                && trace_id.bytes().any(|byte| byte != b'0')
+                && is_hex(parent_id, 16)
+                && parent_id.bytes().any(|byte| byte != b'0')
+                && is_hex(flags, 2)
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Auth;
+    use super::{build_headers, Auth, HeaderError, TraceContext};
+    use crate::config::SecretString;
     use http::HeaderMap;
+    use indexmap::IndexMap;
 
     fn test_basic_auth(url: &str) -> (String, Option<String>) {
         let (url, auth) = Auth::get_and_strip_basic_auth(url);
@@ -257,6 +495,90 @@ mod tests {
         )
     }
 
+    const TRACEPARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    #[test]
+    fn trace_context_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", TRACEPARENT.parse().unwrap());
+        headers.append("tracestate", "congo=t61rcWkgMzE".parse().unwrap());
+        headers.append("tracestate", "rojo=00f067aa0ba902b7".parse().unwrap());
+
+        let context = TraceContext::from_headers(&headers).unwrap();
+        let mut forwarded = HeaderMap::new();
+        context.apply_headers_map(&mut forwarded);
+        assert_eq!(forwarded["traceparent"], TRACEPARENT);
+        assert_eq!(
+            forwarded["tracestate"],
+            "congo=t61rcWkgMzE,rojo=00f067aa0ba902b7"
+        );
+    }
+
+    #[test]
+    fn trace_context_invalid_traceparent() {
+        for traceparent in &[
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331",
+            "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-00",
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert("traceparent", traceparent.parse().unwrap());
+            assert_eq!(
+                TraceContext::from_headers(&headers),
+                None,
+                "{}",
+                traceparent
+            );
+        }
+    }
+
+    #[test]
+    fn trace_context_event_roundtrip() {
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", TRACEPARENT.parse().unwrap());
+        let context = TraceContext::from_headers(&headers).unwrap();
+
+        let mut event = crate::Event::from("message");
+        event.as_mut_log().insert("trace", context.to_value());
+        assert_eq!(TraceContext::from_event(&event, "trace"), Some(context));
+    }
+
+    #[test]
+    fn builds_headers() {
+        std::env::set_var("VECTOR_TEST_HEADER_SECRET", "s3cr3t");
+        let global: IndexMap<String, SecretString> = toml::from_str(
+            r#"
+            X-Team = "logs"
+            X-Site = "fra"
+            "#,
+        )
+        .unwrap();
+        let sink: IndexMap<String, SecretString> = toml::from_str(
+            r#"
+            X-Site = "ber"
+            X-Api-Key = { env = "VECTOR_TEST_HEADER_SECRET" }
+            "#,
+        )
+        .unwrap();
+
+        let headers = build_headers(vec![&global, &sink]).unwrap();
+        assert_eq!(headers["x-team"], "logs");
+        assert_eq!(headers["x-site"], "ber");
+        assert_eq!(headers["x-api-key"], "s3cr3t");
+        assert!(headers["x-api-key"].is_sensitive());
+        assert!(!format!("{:?}", headers).contains("s3cr3t"));
+
+        let mut invalid = IndexMap::new();
+        invalid.insert("X Team".to_owned(), SecretString::from("logs"));
+        assert!(matches!(
+            build_headers(vec![&invalid]),
+            Err(HeaderError::InvalidName { .. })
+        ));
+    }
+
     #[test]
     fn basic_auth_url() {
         assert_eq!(
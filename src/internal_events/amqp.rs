@@ -0,0 +1,75 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct AmqpEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for AmqpEventSent {
+    fn emit_logs(&self) {
+        trace!(message = "Processed one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpConnectionFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for AmqpConnectionFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to publish the message, connecting again.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpDeliveryFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for AmqpDeliveryFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "The broker didn't confirm the message, publishing it again.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpEventMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for AmqpEventMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
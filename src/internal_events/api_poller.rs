@@ -0,0 +1,65 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct ApiPollerRecordsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ApiPollerRecordsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Records received.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiPollerRequestError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for ApiPollerRequestError {
+    fn emit_logs(&self) {
+        error!(message = "API poll failed.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiPollerRateLimited {
+    pub wait: Duration,
+}
+
+impl InternalEvent for ApiPollerRateLimited {
+    fn emit_logs(&self) {
+        warn!(message = "Rate limited, waiting before next request.", wait = ?self.wait);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("rate_limited_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiPollerCheckpointError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ApiPollerCheckpointError {
+    fn emit_logs(&self) {
+        error!(message = "Unable to write checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
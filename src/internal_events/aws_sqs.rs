@@ -19,11 +19,11 @@ impl InternalEvent for AwsSqsEventSent<'_> {
 }
 
 #[derive(Debug)]
-pub struct AwsSqsMessageGroupIdMissingKeys<'a> {
+pub struct AwsSqsMissingKeys<'a> {
     pub keys: &'a [String],
 }
 
-impl<'a> InternalEvent for AwsSqsMessageGroupIdMissingKeys<'a> {
+impl<'a> InternalEvent for AwsSqsMissingKeys<'a> {
     fn emit_logs(&self) {
         warn!(
             message = "Keys do not exist on the event; dropping event.",
@@ -0,0 +1,29 @@
+use super::InternalEvent;
+use metrics::{counter, gauge};
+
+#[derive(Debug)]
+pub struct BufferEventsUpdated {
+    pub events: usize,
+}
+
+impl InternalEvent for BufferEventsUpdated {
+    fn emit_metrics(&self) {
+        gauge!("buffer_events", self.events as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct BufferEventDiscarded;
+
+impl InternalEvent for BufferEventDiscarded {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Shedding load; dropping event.",
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -0,0 +1,35 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct CassandraEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for CassandraEventSent {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct CassandraInvalidValue<'a> {
+    pub column: &'a str,
+    pub error: String,
+}
+
+impl<'a> InternalEvent for CassandraInvalidValue<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid column value; dropping event.",
+            column = %self.column,
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("encode_errors_total", 1);
+    }
+}
@@ -0,0 +1,35 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct DatadogEventsProcessed {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for DatadogEventsProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DatadogEventsMissingKeys<'a> {
+    pub field: &'static str,
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for DatadogEventsMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to render template, leaving it out of the event.",
+            field = self.field,
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
@@ -36,3 +36,85 @@ impl<'a> InternalEvent for ElasticSearchMissingKeys<'a> {
         counter!("missing_keys_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticSearchInvalidBulkAction<'a> {
+    pub action: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticSearchInvalidBulkAction<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid bulk action; dropping event.",
+            action = %self.action,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchMissingId;
+
+impl InternalEvent for ElasticSearchMissingId {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event has no id to update or delete; dropping event.",
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchDocumentsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for ElasticSearchDocumentsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Documents received.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchReadError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for ElasticSearchReadError {
+    fn emit_logs(&self) {
+        error!(message = "Search failed, retrying.", error = %self.error, rate_limit_secs = 30);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchCheckpointError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ElasticSearchCheckpointError {
+    fn emit_logs(&self) {
+        error!(message = "Unable to write checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
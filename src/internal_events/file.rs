@@ -1,6 +1,8 @@
 use super::InternalEvent;
 use metrics::gauge;
 
+#[cfg(feature = "sinks-file")]
+pub use self::sink::*;
 #[cfg(any(feature = "sources-file", feature = "sources-kubernetes-logs"))]
 pub(crate) use self::source::*;
 
@@ -15,11 +17,98 @@ impl InternalEvent for FileOpen {
     }
 }
 
+#[cfg(feature = "sinks-file")]
+mod sink {
+    use super::InternalEvent;
+    use metrics::counter;
+    use std::{io::Error, path::Path};
+
+    #[derive(Debug)]
+    pub struct FileRotated<'a> {
+        pub path: &'a Path,
+        pub rotated_path: &'a Path,
+    }
+
+    impl<'a> InternalEvent for FileRotated<'a> {
+        fn emit_logs(&self) {
+            debug!(
+                message = "File rotated.",
+                path = ?self.path,
+                rotated_path = ?self.rotated_path,
+            );
+        }
+
+        fn emit_metrics(&self) {
+            counter!("files_rotated_total", 1);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct FileRotationFailed<'a> {
+        pub path: &'a Path,
+        pub error: Error,
+    }
+
+    impl<'a> InternalEvent for FileRotationFailed<'a> {
+        fn emit_logs(&self) {
+            error!(
+                message = "Failed to rotate file, writing on to it.",
+                path = ?self.path,
+                error = %self.error,
+                rate_limit_secs = 30,
+            );
+        }
+
+        fn emit_metrics(&self) {
+            counter!("file_rotation_errors_total", 1);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct FileRetentionDeleted<'a> {
+        pub path: &'a Path,
+    }
+
+    impl<'a> InternalEvent for FileRetentionDeleted<'a> {
+        fn emit_logs(&self) {
+            info!(
+                message = "Deleted file older than the retention.",
+                path = ?self.path,
+            );
+        }
+
+        fn emit_metrics(&self) {
+            counter!("rotated_files_deleted_total", 1);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct FileRetentionFailed<'a> {
+        pub path: &'a Path,
+        pub error: Error,
+    }
+
+    impl<'a> InternalEvent for FileRetentionFailed<'a> {
+        fn emit_logs(&self) {
+            warn!(
+                message = "Failed to delete files older than the retention.",
+                path = ?self.path,
+                error = %self.error,
+                rate_limit_secs = 30,
+            );
+        }
+
+        fn emit_metrics(&self) {
+            counter!("rotated_file_delete_errors_total", 1);
+        }
+    }
+}
+
 #[cfg(any(feature = "sources-file", feature = "sources-kubernetes-logs"))]
 mod source {
     use super::{FileOpen, InternalEvent};
     use file_source::FileSourceInternalEvents;
-    use metrics::counter;
+    use metrics::{counter, gauge};
     use std::{io::Error, path::Path, time::Duration};
 
     #[derive(Debug)]
@@ -261,6 +350,21 @@ mod source {
         }
     }
 
+    #[derive(Debug)]
+    pub struct FileLag<'a> {
+        pub path: &'a Path,
+        pub bytes_behind: u64,
+    }
+
+    impl<'a> InternalEvent for FileLag<'a> {
+        fn emit_metrics(&self) {
+            gauge!(
+                "file_bytes_behind", self.bytes_behind as f64,
+                "file" => self.path.to_string_lossy().into_owned(),
+            );
+        }
+    }
+
     #[derive(Clone)]
     pub struct FileSourceInternalEventsEmitter;
 
@@ -311,5 +415,9 @@ mod source {
         fn emit_files_open(&self, count: usize) {
             emit!(FileOpen { count });
         }
+
+        fn emit_file_lag(&self, path: &Path, bytes_behind: u64) {
+            emit!(FileLag { path, bytes_behind });
+        }
     }
 }
@@ -0,0 +1,58 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct GcpBigqueryEventEncoded {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for GcpBigqueryEventEncoded {
+    fn emit_logs(&self) {
+        trace!(message = "Encoded row.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GcpBigqueryMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for GcpBigqueryMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GcpBigqueryInvalidField<'a> {
+    pub column: &'a str,
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for GcpBigqueryInvalidField<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field doesn't match the type of its column; writing null.",
+            column = %self.column,
+            field = %self.field,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "invalid_field");
+    }
+}
@@ -0,0 +1,64 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct GcpCloudLoggingEntriesReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for GcpCloudLoggingEntriesReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Log entries received.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct GcpCloudLoggingRequestError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for GcpCloudLoggingRequestError {
+    fn emit_logs(&self) {
+        error!(message = "Failed to pull log entries.", error = %self.error, rate_limit_secs = 30);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GcpCloudLoggingEntryParseError {
+    pub error: String,
+}
+
+impl InternalEvent for GcpCloudLoggingEntryParseError {
+    fn emit_logs(&self) {
+        warn!(message = "Failed to parse log entry.", error = %self.error, rate_limit_secs = 30);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GcpCloudLoggingCheckpointError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for GcpCloudLoggingCheckpointError {
+    fn emit_logs(&self) {
+        error!(message = "Unable to write checkpoint.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("checkpoint_write_errors_total", 1);
+    }
+}
@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct InFlightLimitReached {
+    pub max: usize,
+}
+
+impl InternalEvent for InFlightLimitReached {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Too many events in flight; pausing source.",
+            max_in_flight_events = %self.max,
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("in_flight_limit_reached_total", 1);
+    }
+}
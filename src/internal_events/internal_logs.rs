@@ -0,0 +1,32 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct InternalLogsEventReceived;
+
+impl InternalEvent for InternalLogsEventReceived {
+    // Logging here would produce another event for every event received.
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct InternalLogsEventsDropped {
+    pub count: u64,
+}
+
+impl InternalEvent for InternalLogsEventsDropped {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Source fell behind, dropping the oldest logs.",
+            count = %self.count,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count);
+    }
+}
@@ -0,0 +1,75 @@
+use super::InternalEvent;
+use crate::sources::jmx_metrics::parser::ParseError;
+use metrics::{counter, histogram};
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct JmxMetricsCollectCompleted {
+    pub start: Instant,
+    pub end: Instant,
+}
+
+impl InternalEvent for JmxMetricsCollectCompleted {
+    fn emit_logs(&self) {
+        debug!(message = "Collection completed.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("collect_completed_total", 1);
+        histogram!("collect_duration_nanoseconds", self.end - self.start);
+    }
+}
+
+pub struct JmxMetricsRequestError<'a> {
+    pub error: crate::Error,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for JmxMetricsRequestError<'a> {
+    fn emit_logs(&self) {
+        error!(message = "Jolokia request error.", endpoint = %self.endpoint, error = ?self.error)
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
+
+pub struct JmxMetricsParseError<'a> {
+    pub error: ParseError,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for JmxMetricsParseError<'a> {
+    fn emit_logs(&self) {
+        error!(message = "Jolokia response parse error.", endpoint = %self.endpoint, error = ?self.error)
+    }
+
+    fn emit_metrics(&self) {
+        counter!("parse_errors_total", 1);
+    }
+}
+
+pub struct JmxMetricsReadError<'a> {
+    pub mbean: &'a str,
+    pub status: u16,
+    pub error: &'a str,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for JmxMetricsReadError<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Jolokia failed to read MBean.",
+            endpoint = %self.endpoint,
+            mbean = %self.mbean,
+            status = %self.status,
+            error = %self.error,
+            rate_limit_secs = 60,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("mbean_read_errors_total", 1);
+    }
+}
@@ -1,5 +1,5 @@
 use super::InternalEvent;
-use metrics::counter;
+use metrics::{counter, gauge};
 
 #[derive(Debug)]
 pub(crate) struct JournaldEventReceived {
@@ -33,3 +33,14 @@ impl InternalEvent for JournaldInvalidRecord {
         counter!("invalid_record_bytes_total", self.text.len() as u64);
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct JournaldLag {
+    pub entries_behind: u64,
+}
+
+impl InternalEvent for JournaldLag {
+    fn emit_metrics(&self) {
+        gauge!("journald_entries_behind", self.entries_behind as f64);
+    }
+}
@@ -57,3 +57,32 @@ impl InternalEvent for KafkaKeyExtractionFailed<'_> {
         error!(message = "Failed to extract key.", key_field = %self.key_field);
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaPartitionsAssigned {
+    pub partitions: usize,
+    pub topics: Vec<String>,
+}
+
+impl InternalEvent for KafkaPartitionsAssigned {
+    fn emit_logs(&self) {
+        info!(
+            message = "Partitions assigned.",
+            partitions = %self.partitions,
+            topics = ?self.topics
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("consumer_rebalances_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaPartitionsRevoked;
+
+impl InternalEvent for KafkaPartitionsRevoked {
+    fn emit_logs(&self) {
+        debug!(message = "Partitions revoked.");
+    }
+}
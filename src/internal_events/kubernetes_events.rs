@@ -0,0 +1,37 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct KubernetesEventsEventReceived<'a> {
+    pub name: &'a str,
+}
+
+impl InternalEvent for KubernetesEventsEventReceived<'_> {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", name = %self.name);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesEventsDuplicate<'a> {
+    pub uid: &'a str,
+    pub resource_version: &'a str,
+}
+
+impl InternalEvent for KubernetesEventsDuplicate<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Skipping already emitted event.",
+            uid = %self.uid,
+            resource_version = %self.resource_version,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("duplicate_events_total", 1);
+    }
+}
@@ -0,0 +1,53 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct LokiEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for LokiEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Entries received.", count = %self.count, byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct LokiRequestError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for LokiRequestError {
+    fn emit_logs(&self) {
+        error!(message = "Loki request failed, retrying.", error = %self.error, rate_limit_secs = 30);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("http_request_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct LokiTailDroppedEntries {
+    pub count: usize,
+}
+
+impl InternalEvent for LokiTailDroppedEntries {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Loki dropped entries of the tail as it couldn't keep up.",
+            count = %self.count,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count as u64);
+    }
+}
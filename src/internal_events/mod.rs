@@ -3,11 +3,15 @@ use std::borrow::Cow;
 mod adaptive_concurrency;
 mod add_fields;
 mod add_tags;
+#[cfg(feature = "sinks-amqp")]
+mod amqp;
 mod ansi_stripper;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
+#[cfg(feature = "sources-utils-api-poller")]
+mod api_poller;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 mod aws_cloudwatch_logs_subscription_parser;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -23,17 +27,28 @@ pub(crate) mod aws_s3;
 #[cfg(feature = "sinks-aws_sqs")]
 mod aws_sqs;
 mod blackhole;
+mod buffers;
+#[cfg(feature = "sinks-cassandra")]
+mod cassandra;
 #[cfg(feature = "transforms-coercer")]
 mod coercer;
 #[cfg(feature = "transforms-concat")]
 mod concat;
 #[cfg(feature = "sinks-console")]
 mod console;
+#[cfg(feature = "sinks-datadog")]
+mod datadog_events;
 #[cfg(feature = "transforms-dedupe")]
 mod dedupe;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
 mod elasticsearch;
+#[cfg(feature = "sinks-gcp_bigquery")]
+mod gcp_bigquery;
+#[cfg(feature = "sources-gcp_cloud_logging")]
+mod gcp_cloud_logging;
+#[cfg(feature = "sinks-gcp")]
+mod gcp_pubsub;
 #[cfg(feature = "sources-generator")]
 mod generator;
 #[cfg(feature = "transforms-geoip")]
@@ -45,6 +60,11 @@ mod heartbeat;
 mod host_metrics;
 mod http;
 pub mod http_client;
+mod in_flight;
+#[cfg(feature = "sources-internal_logs")]
+mod internal_logs;
+#[cfg(feature = "sources-jmx_metrics")]
+mod jmx_metrics;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
@@ -53,6 +73,8 @@ mod json_parser;
 mod kafka;
 #[cfg(feature = "transforms-key_value_parser")]
 mod key_value_parser;
+#[cfg(feature = "sources-kubernetes-events")]
+mod kubernetes_events;
 #[cfg(feature = "sources-kubernetes-logs")]
 mod kubernetes_logs;
 #[cfg(feature = "transforms-log_to_metric")]
@@ -60,21 +82,31 @@ mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
 mod logfmt_parser;
 mod logplex;
+#[cfg(feature = "sources-loki")]
+mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+#[cfg(all(target_os = "macos", feature = "sources-macos_unified_log"))]
+mod macos_unified_log;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
 #[cfg(feature = "sinks-nats")]
 mod nats;
+#[cfg(feature = "sinks-new_relic")]
+mod new_relic;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
+#[cfg(feature = "sources-postgresql_metrics")]
+mod postgresql_metrics;
 mod process;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 mod prometheus;
 mod pulsar;
+#[cfg(feature = "sinks-redis")]
+mod redis;
 #[cfg(feature = "transforms-reduce")]
 mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
@@ -89,10 +121,16 @@ mod rename_fields;
 mod sampler;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sinks-snowflake")]
+mod snowflake;
 mod socket;
 mod split;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(any(feature = "sources-spool", feature = "sinks-spool"))]
+mod spool;
+#[cfg(feature = "transforms-sql")]
+mod sql;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "sources-statsd")]
@@ -104,24 +142,33 @@ mod syslog;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
 mod tcp;
+mod timestamp_decoding;
+mod timestamp_skew;
 #[cfg(feature = "transforms-tokenizer")]
 mod tokenizer;
+mod topology;
 mod udp;
 mod unix;
 mod vector;
 #[cfg(feature = "wasm")]
 mod wasm;
+#[cfg(feature = "sinks-websocket")]
+mod websocket;
 
 pub mod kubernetes;
 
 pub use self::adaptive_concurrency::*;
 pub use self::add_fields::*;
 pub use self::add_tags::*;
+#[cfg(feature = "sinks-amqp")]
+pub(crate) use self::amqp::*;
 pub use self::ansi_stripper::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub use self::api::*;
+#[cfg(feature = "sources-utils-api-poller")]
+pub(crate) use self::api_poller::*;
 #[cfg(feature = "transforms-aws_cloudwatch_logs_subscription_parser")]
 pub(crate) use self::aws_cloudwatch_logs_subscription_parser::*;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -135,12 +182,17 @@ pub use self::aws_kinesis_streams::*;
 #[cfg(feature = "sinks-aws_sqs")]
 pub use self::aws_sqs::*;
 pub use self::blackhole::*;
+pub use self::buffers::*;
+#[cfg(feature = "sinks-cassandra")]
+pub(crate) use self::cassandra::*;
 #[cfg(feature = "transforms-coercer")]
 pub(crate) use self::coercer::*;
 #[cfg(feature = "transforms-concat")]
 pub use self::concat::*;
 #[cfg(feature = "sinks-console")]
 pub use self::console::*;
+#[cfg(feature = "sinks-datadog")]
+pub(crate) use self::datadog_events::*;
 #[cfg(feature = "transforms-dedupe")]
 pub(crate) use self::dedupe::*;
 #[cfg(feature = "sources-docker_logs")]
@@ -152,6 +204,12 @@ pub use self::elasticsearch::*;
     feature = "sinks-file",
 ))]
 pub use self::file::*;
+#[cfg(feature = "sinks-gcp_bigquery")]
+pub(crate) use self::gcp_bigquery::*;
+#[cfg(feature = "sources-gcp_cloud_logging")]
+pub(crate) use self::gcp_cloud_logging::*;
+#[cfg(feature = "sinks-gcp")]
+pub(crate) use self::gcp_pubsub::*;
 #[cfg(feature = "sources-generator")]
 pub use self::generator::*;
 #[cfg(feature = "transforms-geoip")]
@@ -163,6 +221,11 @@ pub use self::heartbeat::*;
 pub(crate) use self::host_metrics::*;
 #[cfg(any(feature = "sources-utils-http", feature = "sinks-http"))]
 pub(crate) use self::http::*;
+pub use self::in_flight::*;
+#[cfg(feature = "sources-internal_logs")]
+pub(crate) use self::internal_logs::*;
+#[cfg(feature = "sources-jmx_metrics")]
+pub(crate) use self::jmx_metrics::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
@@ -171,6 +234,8 @@ pub(crate) use self::json_parser::*;
 pub use self::kafka::*;
 #[cfg(feature = "transforms-key_value_parser")]
 pub(crate) use self::key_value_parser::*;
+#[cfg(feature = "sources-kubernetes-events")]
+pub use self::kubernetes_events::*;
 #[cfg(feature = "sources-kubernetes-logs")]
 pub use self::kubernetes_logs::*;
 #[cfg(feature = "transforms-log_to_metric")]
@@ -178,19 +243,29 @@ pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "transforms-logfmt_parser")]
 pub use self::logfmt_parser::*;
 pub use self::logplex::*;
+#[cfg(feature = "sources-loki")]
+pub(crate) use self::loki::*;
 #[cfg(feature = "transforms-lua")]
 pub use self::lua::*;
+#[cfg(all(target_os = "macos", feature = "sources-macos_unified_log"))]
+pub(crate) use self::macos_unified_log::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
 #[cfg(feature = "sinks-nats")]
 pub use self::nats::*;
+#[cfg(feature = "sinks-new_relic")]
+pub(crate) use self::new_relic::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 pub use self::open::*;
+#[cfg(feature = "sources-postgresql_metrics")]
+pub(crate) use self::postgresql_metrics::*;
 pub use self::process::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
 pub use self::pulsar::*;
+#[cfg(feature = "sinks-redis")]
+pub(crate) use self::redis::*;
 #[cfg(feature = "transforms-reduce")]
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-regex_parser")]
@@ -205,10 +280,16 @@ pub use self::rename_fields::*;
 pub use self::sampler::*;
 #[cfg(feature = "sinks-sematext")]
 pub use self::sematext_metrics::*;
+#[cfg(feature = "sinks-snowflake")]
+pub(crate) use self::snowflake::*;
 pub(crate) use self::socket::*;
 pub use self::split::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(any(feature = "sources-spool", feature = "sinks-spool"))]
+pub(crate) use self::spool::*;
+#[cfg(feature = "transforms-sql")]
+pub(crate) use self::sql::*;
 #[cfg(feature = "sinks-statsd")]
 pub use self::statsd_sink::*;
 #[cfg(feature = "sources-statsd")]
@@ -220,13 +301,18 @@ pub use self::syslog::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
 pub use self::tcp::*;
+pub use self::timestamp_decoding::*;
+pub use self::timestamp_skew::*;
 #[cfg(feature = "transforms-tokenizer")]
 pub(crate) use self::tokenizer::*;
+pub(crate) use self::topology::*;
 pub use self::udp::*;
 pub use self::unix::*;
 pub use self::vector::*;
 #[cfg(feature = "wasm")]
 pub use self::wasm::*;
+#[cfg(feature = "sinks-websocket")]
+pub use self::websocket::*;
 #[cfg(windows)]
 pub use self::windows::*;
 #[cfg(feature = "sources-mongodb_metrics")]
@@ -33,6 +33,45 @@ impl InternalEvent for NatsEventSendFail {
     }
 }
 
+#[derive(Debug)]
+pub struct NatsJetStreamPublishFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for NatsJetStreamPublishFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "JetStream didn't acknowledge the message, publishing it again.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct NatsJetStreamMessageDropped {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for NatsJetStreamMessageDropped {
+    fn emit_logs(&self) {
+        error!(
+            message = "JetStream rejected the message; dropping it.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("send_errors_total", 1);
+        counter!("events_discarded_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct NatsEventMissingKeys<'a> {
     pub keys: &'a [String],
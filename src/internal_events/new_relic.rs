@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct NewRelicMetricUnsupported<'a> {
+    pub name: &'a str,
+}
+
+impl<'a> InternalEvent for NewRelicMetricUnsupported<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Dropping aggregated histogram or summary, which the Metric API doesn't accept.",
+            name = self.name,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
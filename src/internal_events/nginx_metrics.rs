@@ -42,7 +42,7 @@ pub struct NginxMetricsStubStatusParseError<'a> {
 
 impl<'a> InternalEvent for NginxMetricsStubStatusParseError<'a> {
     fn emit_logs(&self) {
-        error!(message = "NginxStubStatus parse error.", endpoint = %self.endpoint, error = ?self.error)
+        error!(message = "Nginx status parse error.", endpoint = %self.endpoint, error = ?self.error)
     }
 
     fn emit_metrics(&self) {
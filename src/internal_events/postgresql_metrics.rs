@@ -0,0 +1,36 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct PostgresqlMetricsCollectCompleted {
+    pub start: Instant,
+    pub end: Instant,
+}
+
+impl InternalEvent for PostgresqlMetricsCollectCompleted {
+    fn emit_logs(&self) {
+        debug!(message = "Collection completed.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("collect_completed_total", 1);
+        histogram!("collect_duration_nanoseconds", self.end - self.start);
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlMetricsRequestError<'a> {
+    pub error: tokio_postgres::Error,
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for PostgresqlMetricsRequestError<'a> {
+    fn emit_logs(&self) {
+        error!(message = "PostgreSQL request error.", endpoint = %self.endpoint, error = ?self.error)
+    }
+
+    fn emit_metrics(&self) {
+        counter!("request_errors_total", 1);
+    }
+}
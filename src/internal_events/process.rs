@@ -58,6 +58,25 @@ impl InternalEvent for VectorStopped {
     }
 }
 
+#[derive(Debug)]
+pub struct PipelineStopped<'a> {
+    pub name: &'a str,
+}
+
+impl InternalEvent for PipelineStopped<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            target: "vector",
+            message = "Pipeline has stopped, other pipelines keep running.",
+            pipeline = %self.name
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("stopped_total", 1, "pipeline" => self.name.to_owned());
+    }
+}
+
 #[derive(Debug)]
 pub struct VectorQuit;
 
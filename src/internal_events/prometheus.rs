@@ -179,3 +179,19 @@ impl InternalEvent for PrometheusServerRequestComplete {
         counter!("requests_received_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct PrometheusServerAuthenticationFailed;
+
+impl InternalEvent for PrometheusServerAuthenticationFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rejected scrape without valid credentials.",
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("authentication_errors_total", 1);
+    }
+}
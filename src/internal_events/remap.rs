@@ -54,3 +54,32 @@ impl InternalEvent for RemapConditionExecutionError {
         counter!("processing_errors_total", 1);
     }
 }
+
+#[derive(Debug, Copy, Clone)]
+pub struct RemapEventAborted {
+    /// If set to true, the event is sent to the aborted output of the remap
+    /// transform rather than dropped.
+    pub rerouted: bool,
+}
+
+impl InternalEvent for RemapEventAborted {
+    fn emit_logs(&self) {
+        if self.rerouted {
+            debug!(
+                message = "Mapping aborted; rerouting event.",
+                rate_limit_secs = 30
+            );
+        } else {
+            debug!(
+                message = "Mapping aborted; discarding event.",
+                rate_limit_secs = 30
+            );
+        }
+    }
+
+    fn emit_metrics(&self) {
+        if !self.rerouted {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SnowflakeEventEncoded {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for SnowflakeEventEncoded {
+    fn emit_logs(&self) {
+        trace!(message = "Encoded row.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SnowflakeMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for SnowflakeMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SnowflakeChannelOpened<'a> {
+    pub channel: &'a str,
+    pub last_committed_offset: Option<u64>,
+}
+
+impl<'a> InternalEvent for SnowflakeChannelOpened<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Opened channel.",
+            channel = %self.channel,
+            last_committed_offset = ?self.last_committed_offset,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("channels_opened_total", 1);
+    }
+}
@@ -36,6 +36,41 @@ impl InternalEvent for SplunkEventEncodeError {
     }
 }
 
+#[derive(Debug)]
+pub(crate) struct SplunkIndexerAcknowledgementUnavailable;
+
+impl InternalEvent for SplunkIndexerAcknowledgementUnavailable {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Splunk didn't return an acknowledgement ID, marking the batch as delivered. Indexer acknowledgement may be disabled for the token.",
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("indexer_acknowledgements_unavailable_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SplunkIndexerAcknowledgementQueryFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for SplunkIndexerAcknowledgementQueryFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed querying the indexer acknowledgement.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("indexer_acknowledgement_query_errors_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct SplunkSourceTypeMissingKeys<'a> {
     pub keys: &'a [String],
@@ -0,0 +1,115 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::{io, path::Path};
+
+#[derive(Debug)]
+pub struct SpoolEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for SpoolEventSent {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SpoolEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for SpoolEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SpoolSegmentForwarded<'a> {
+    pub path: &'a Path,
+    pub events: usize,
+}
+
+impl<'a> InternalEvent for SpoolSegmentForwarded<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Forwarded and deleted segment.",
+            path = ?self.path,
+            events = %self.events,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SpoolSegmentDiscarded<'a> {
+    pub path: &'a Path,
+    pub events: usize,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for SpoolSegmentDiscarded<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Discarding spooled segment.",
+            path = ?self.path,
+            events = %self.events,
+            reason = %self.reason,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.events as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SpoolSegmentCorrupted<'a> {
+    pub path: &'a Path,
+    pub events: usize,
+    pub error: prost::DecodeError,
+}
+
+impl<'a> InternalEvent for SpoolSegmentCorrupted<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to decode spooled segment; forwarding the events before the error.",
+            path = ?self.path,
+            events = %self.events,
+            error = %self.error,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("protobuf_decode_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SpoolIoError<'a> {
+    pub path: &'a Path,
+    pub operation: &'static str,
+    pub error: io::Error,
+}
+
+impl<'a> InternalEvent for SpoolIoError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Spool directory operation failed.",
+            path = ?self.path,
+            operation = %self.operation,
+            error = %self.error,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "io");
+    }
+}
@@ -0,0 +1,32 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub(crate) struct SqlEventProcessed;
+
+impl InternalEvent for SqlEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SqlAggregateValueInvalid<'a> {
+    pub function: &'static str,
+    pub field: &'a str,
+}
+
+impl InternalEvent for SqlAggregateValueInvalid<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field value must be numeric, ignoring it.",
+            function = %self.function,
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "value_invalid");
+    }
+}
@@ -66,6 +66,23 @@ impl InternalEvent for TcpSocketConnectionError {
     }
 }
 
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+#[derive(Debug)]
+pub struct ProxyProtocolHeaderError {
+    pub error: crate::sources::util::ProxyProtocolError,
+}
+
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+impl InternalEvent for ProxyProtocolHeaderError {
+    fn emit_logs(&self) {
+        warn!(message = "Closing connection without a valid PROXY protocol header.", error = %self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1, "mode" => "tcp");
+    }
+}
+
 #[derive(Debug)]
 pub struct TcpSocketError {
     pub error: std::io::Error,
@@ -0,0 +1,21 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct EventTimestampParseFailed<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for EventTimestampParseFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Unable to parse the timestamp; keeping the time of ingestion.",
+            field = %self.field,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("event_timestamp_parse_errors_total", 1);
+    }
+}
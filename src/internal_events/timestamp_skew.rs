@@ -0,0 +1,43 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+
+#[derive(Debug)]
+pub struct EventTimestampSkew {
+    pub skew: f64,
+}
+
+impl InternalEvent for EventTimestampSkew {
+    fn emit_metrics(&self) {
+        histogram!("event_timestamp_skew_seconds", self.skew.abs(), "direction" => direction(self.skew));
+    }
+}
+
+#[derive(Debug)]
+pub struct EventTimestampSkewExceeded {
+    pub skew: f64,
+    pub threshold: u64,
+}
+
+impl InternalEvent for EventTimestampSkewExceeded {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event timestamp skew exceeds the threshold.",
+            skew_secs = %self.skew,
+            threshold_secs = %self.threshold,
+            direction = direction(self.skew),
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("event_timestamp_skew_exceeded_total", 1, "direction" => direction(self.skew));
+    }
+}
+
+fn direction(skew: f64) -> &'static str {
+    if skew < 0.0 {
+        "future"
+    } else {
+        "past"
+    }
+}
@@ -0,0 +1,60 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct ComponentRestarted {
+    pub restarts: u32,
+    pub max_restarts: u32,
+    pub backoff: Duration,
+}
+
+impl InternalEvent for ComponentRestarted {
+    fn emit_logs(&self) {
+        error!(
+            message = "Component panicked; restarting.",
+            restarts = %self.restarts,
+            max_restarts = %self.max_restarts,
+            backoff_secs = %self.backoff.as_secs(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_restarts_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ComponentRestartsExhausted {
+    pub max_restarts: u32,
+}
+
+impl InternalEvent for ComponentRestartsExhausted {
+    fn emit_logs(&self) {
+        error!(
+            message = "Component panicked too often; shutting down.",
+            max_restarts = %self.max_restarts,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EventExpired {
+    pub age: Duration,
+    pub max_age: Duration,
+}
+
+impl InternalEvent for EventExpired {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event is older than the maximum age; dropping event.",
+            age_secs = %self.age.as_secs(),
+            max_age_secs = %self.max_age.as_secs(),
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_expired_total", 1);
+    }
+}
@@ -44,3 +44,53 @@ impl InternalEvent for VectorProtoDecodeError {
         counter!("protobuf_decode_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct VectorRequestDecompressError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for VectorRequestDecompressError {
+    fn emit_logs(&self) {
+        error!(message = "Failed to decompress request.", error = %self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("decompress_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorRequestTooLarge {
+    pub max_bytes: usize,
+}
+
+impl InternalEvent for VectorRequestTooLarge {
+    fn emit_logs(&self) {
+        error!(
+            message = "Rejected request, its decompressed batch is too large.",
+            max_bytes = %self.max_bytes,
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("decompress_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorAuthenticationFailed;
+
+impl InternalEvent for VectorAuthenticationFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rejected request without a valid session of the pre-shared key.",
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("authentication_errors_total", 1);
+    }
+}
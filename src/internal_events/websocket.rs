@@ -0,0 +1,99 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct WebSocketConnectionEstablished;
+
+impl InternalEvent for WebSocketConnectionEstablished {
+    fn emit_logs(&self) {
+        debug!(message = "Connected.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_established_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionFailed<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WebSocketConnectionFailed<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        error!(message = "Unable to connect.", error = %self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionShutdown;
+
+impl InternalEvent for WebSocketConnectionShutdown {
+    fn emit_logs(&self) {
+        warn!(message = "Closed by the server, reconnecting.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_shutdown_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionError<E> {
+    pub error: E,
+}
+
+impl<E> InternalEvent for WebSocketConnectionError<E>
+where
+    E: std::error::Error,
+{
+    fn emit_logs(&self) {
+        warn!(message = "Connection error, reconnecting.", error = %self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("connection_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for WebSocketEventSent {
+    fn emit_logs(&self) {
+        trace!(message = "Sent one event.");
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processed_events_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+impl<'a> InternalEvent for WebSocketFieldMissing<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field does not exist on the event; dropping event.",
+            field = %self.field,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
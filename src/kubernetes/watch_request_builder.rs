@@ -29,6 +29,16 @@ use k8s_openapi::{
     Metadata, RequestError, ResponseBody, WatchOptional, WatchResponse,
 };
 use serde::de::DeserializeOwned;
+use std::{fmt::Debug, marker::PhantomData};
+
+/// The watch request as returned by the `k8s_openapi` request builders.
+pub type WatchRequestResult<T> = Result<
+    (
+        Request<Vec<u8>>,
+        fn(StatusCode) -> ResponseBody<WatchResponse<T>>,
+    ),
+    RequestError,
+>;
 
 /// Build a watch request for the k8s API.
 ///
@@ -100,3 +110,51 @@ where
         Ok(request)
     }
 }
+
+/// An object that can be watched either across all namespaces or in a single
+/// namespace.
+pub trait Watchable:
+    Metadata<Ty = ObjectMeta> + DeserializeOwned + Send + Unpin + Debug + 'static
+{
+    /// Build a request to watch the objects across all namespaces.
+    fn watch_all(optional: WatchOptional<'_>) -> WatchRequestResult<Self>;
+
+    /// Build a request to watch the objects in a single namespace.
+    fn watch_namespaced(namespace: &str, optional: WatchOptional<'_>) -> WatchRequestResult<Self>;
+}
+
+/// Builds the watch requests of a [`Watchable`] object for either all
+/// namespaces or a single one.
+pub struct NamespaceWatchRequestBuilder<T> {
+    namespace: Option<String>,
+    _object: PhantomData<fn() -> T>,
+}
+
+impl<T> NamespaceWatchRequestBuilder<T> {
+    /// Create a new builder, watching all namespaces when `namespace` is
+    /// unset.
+    pub fn new(namespace: Option<String>) -> Self {
+        Self {
+            namespace,
+            _object: PhantomData,
+        }
+    }
+}
+
+impl<T> WatchRequestBuilder for NamespaceWatchRequestBuilder<T>
+where
+    T: Watchable,
+{
+    type Object = T;
+
+    fn build<'a>(
+        &self,
+        watch_optional: WatchOptional<'a>,
+    ) -> Result<Request<Vec<u8>>, RequestError> {
+        let (request, _) = match self.namespace {
+            Some(ref namespace) => T::watch_namespaced(namespace, watch_optional)?,
+            None => T::watch_all(watch_optional)?,
+        };
+        Ok(request)
+    }
+}
@@ -23,10 +23,13 @@ static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 #[macro_use]
 pub mod config;
+pub mod analyze;
 pub mod buffers;
 pub mod cli;
 pub mod conditions;
+pub mod config_schema;
 pub mod dns;
+pub mod dry_run;
 pub mod event;
 pub mod expiring_hash_map;
 pub mod generate;
@@ -48,8 +51,12 @@ pub mod list;
 pub mod mapping;
 pub mod metrics;
 pub(crate) mod pipeline;
+pub mod pipelines;
+pub mod process;
 #[cfg(any(feature = "sinks-prometheus", feature = "sources-prometheus"))]
 pub(crate) mod prometheus;
+#[cfg(any(feature = "sinks-vector", feature = "sources-vector"))]
+pub mod proto;
 pub mod remap;
 #[cfg(feature = "rusoto_core")]
 pub mod rusoto;
@@ -60,6 +67,8 @@ pub mod signal;
 pub mod sink;
 pub mod sinks;
 pub mod sources;
+#[cfg(any(feature = "sinks-spool", feature = "sources-spool"))]
+pub(crate) mod spool;
 pub mod stream;
 pub mod tcp;
 pub mod template;
@@ -70,6 +79,7 @@ pub mod top;
 pub mod topology;
 pub mod trace;
 pub mod transforms;
+pub mod tuning;
 pub mod types;
 pub mod unit_test;
 pub mod validate;
@@ -112,8 +122,13 @@ mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
+/// The hostname resolved with the global `hostname` strategy, or the system
+/// hostname before the configuration is loaded.
 pub fn get_hostname() -> std::io::Result<String> {
-    Ok(hostname::get()?.to_string_lossy().into())
+    match config::resolved_hostname() {
+        Some(hostname) => Ok(hostname),
+        None => Ok(hostname::get()?.to_string_lossy().into()),
+    }
 }
 
 // This is a private implementation of the unstable `bool_to_option`
@@ -1,4 +1,6 @@
-use crate::config::{SinkDescription, SourceDescription, TransformDescription};
+use crate::config::{
+    component::ComponentOption, SinkDescription, SourceDescription, TransformDescription,
+};
 use serde::Serialize;
 use structopt::StructOpt;
 
@@ -8,6 +10,11 @@ pub struct Opts {
     /// Format the list in an encoding scheme.
     #[structopt(long, default_value = "text", possible_values = &["text", "json", "avro"])]
     format: Format,
+
+    /// Include the options of the example config of each component, with their types and
+    /// example values.
+    #[structopt(long)]
+    options: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,47 +41,69 @@ impl std::str::FromStr for Format {
 }
 
 #[derive(Serialize)]
-pub struct EncodedList {
-    sources: Vec<&'static str>,
-    transforms: Vec<&'static str>,
-    sinks: Vec<&'static str>,
+pub struct EncodedList<T> {
+    sources: Vec<T>,
+    transforms: Vec<T>,
+    sinks: Vec<T>,
+}
+
+#[derive(Serialize)]
+pub struct EncodedComponent {
+    name: &'static str,
+    options: Vec<ComponentOption>,
 }
 
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
-    let sources = SourceDescription::types();
-    let transforms = TransformDescription::types();
-    let sinks = SinkDescription::types();
+    // Generating the example configs is only worth it if the options are listed.
+    let list = if opts.options {
+        EncodedList {
+            sources: SourceDescription::types_with_options(),
+            transforms: TransformDescription::types_with_options(),
+            sinks: SinkDescription::types_with_options(),
+        }
+    } else {
+        let without_options =
+            |types: Vec<&'static str>| types.into_iter().map(|name| (name, Vec::new())).collect();
+        EncodedList {
+            sources: without_options(SourceDescription::types()),
+            transforms: without_options(TransformDescription::types()),
+            sinks: without_options(SinkDescription::types()),
+        }
+    };
 
     match opts.format {
         Format::Text => {
             println!("Sources:");
-            for name in sources {
-                println!("- {}", name);
-            }
+            print_components(&list.sources, opts.options);
 
             println!("\nTransforms:");
-            for name in transforms {
-                println!("- {}", name);
-            }
+            print_components(&list.transforms, opts.options);
 
             println!("\nSinks:");
-            for name in sinks {
-                println!("- {}", name);
-            }
+            print_components(&list.sinks, opts.options);
         }
-        Format::Json => {
-            let list = EncodedList {
-                sources,
-                transforms,
-                sinks,
+        Format::Json | Format::Avro if opts.options => {
+            let encode = |components: Vec<(&'static str, Vec<ComponentOption>)>| {
+                components
+                    .into_iter()
+                    .map(|(name, options)| EncodedComponent { name, options })
+                    .collect()
+            };
+            let list: EncodedList<EncodedComponent> = EncodedList {
+                sources: encode(list.sources),
+                transforms: encode(list.transforms),
+                sinks: encode(list.sinks),
             };
             println!("{}", serde_json::to_string(&list).unwrap());
         }
-        Format::Avro => {
-            let list = EncodedList {
-                sources,
-                transforms,
-                sinks,
+        Format::Json | Format::Avro => {
+            let names = |components: Vec<(&'static str, Vec<ComponentOption>)>| {
+                components.into_iter().map(|(name, _)| name).collect()
+            };
+            let list: EncodedList<&str> = EncodedList {
+                sources: names(list.sources),
+                transforms: names(list.transforms),
+                sinks: names(list.sinks),
             };
             println!("{}", serde_json::to_string(&list).unwrap());
         }
@@ -82,3 +111,26 @@ pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
 
     exitcode::OK
 }
+
+fn print_components(components: &[(&'static str, Vec<ComponentOption>)], with_options: bool) {
+    for (name, options) in components {
+        println!("- {}", name);
+        if with_options {
+            print_options(options, 1);
+        }
+    }
+}
+
+fn print_options(options: &[ComponentOption], depth: usize) {
+    for option in options {
+        let indent = "  ".repeat(depth);
+        match &option.example {
+            Some(example) => println!(
+                "{}- {} ({}, example: {})",
+                indent, option.name, option.value_type, example
+            ),
+            None => println!("{}- {} ({})", indent, option.name, option.value_type),
+        }
+        print_options(&option.options, depth + 1);
+    }
+}
@@ -1,13 +1,21 @@
 use crate::{event::Metric, Event};
-use metrics::{Key, KeyData, Label, Recorder, Unit};
+use metrics::{counter, Key, KeyData, Label, Recorder, Unit};
 use metrics_tracing_context::{LabelFilter, TracingContextLayer};
 use metrics_util::layers::Layer;
 use metrics_util::{CompositeKey, Handle, MetricKind, Registry};
 use once_cell::sync::OnceCell;
-use std::sync::{
-    atomic::{AtomicU64, Ordering},
-    Arc,
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
+use tracing::{
+    field::{Field, Visit},
+    span, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
 
 static CONTROLLER: OnceCell<Controller> = OnceCell::new();
 
@@ -137,7 +145,82 @@ struct VectorLabelFilter;
 impl LabelFilter for VectorLabelFilter {
     fn should_include_label(&self, label: &Label) -> bool {
         let key = label.key();
-        key == "component_name" || key == "component_type" || key == "component_kind"
+        key == "component_id"
+            || key == "component_name"
+            || key == "component_type"
+            || key == "component_kind"
+    }
+}
+
+/// The identity of the component a span belongs to, taken from the fields of
+/// the spans the topology runs components in.
+#[derive(Clone, Debug, Default)]
+struct ComponentSpan {
+    id: Option<String>,
+    kind: Option<String>,
+    component_type: Option<String>,
+}
+
+impl Visit for ComponentSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "component_id" => self.id = Some(value.to_owned()),
+            "component_kind" => self.kind = Some(value.to_owned()),
+            "component_type" => self.component_type = Some(value.to_owned()),
+            _ => (),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value))
+    }
+}
+
+/// Counts the errors logged by each component as `component_errors_total`.
+///
+/// The recorder can't look up the current span while an event is being
+/// dispatched, so the component tags are set explicitly.
+pub struct ComponentErrorsLayer;
+
+impl<S> tracing_subscriber::Layer<S> for ComponentErrorsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut component = ComponentSpan::default();
+        attrs.record(&mut component);
+        if let (Some(span), Some(_)) = (ctx.span(id), &component.id) {
+            span.extensions_mut().insert(component);
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR {
+            return;
+        }
+
+        let span = match ctx.lookup_current() {
+            Some(span) => span,
+            None => return,
+        };
+        let parents = span.parents();
+        let component = std::iter::once(span)
+            .chain(parents)
+            .find_map(|span| span.extensions().get::<ComponentSpan>().cloned());
+
+        if let Some(ComponentSpan {
+            id: Some(id),
+            kind,
+            component_type,
+        }) = component
+        {
+            counter!("component_errors_total", 1,
+                "component_id" => id.clone(),
+                "component_name" => id,
+                "component_kind" => kind.unwrap_or_default(),
+                "component_type" => component_type.unwrap_or_default(),
+            );
+        }
     }
 }
 
@@ -185,6 +268,7 @@ mod tests {
         let span = span!(
             Level::ERROR,
             "my span",
+            component_id = "my_component_name",
             component_name = "my_component_name",
             component_type = "my_component_type",
             component_kind = "my_component_kind",
@@ -205,6 +289,7 @@ mod tests {
 
         let expected_tags = Some(
             vec![
+                ("component_id".to_owned(), "my_component_name".to_owned()),
                 ("component_name".to_owned(), "my_component_name".to_owned()),
                 ("component_type".to_owned(), "my_component_type".to_owned()),
                 ("component_kind".to_owned(), "my_component_kind".to_owned()),
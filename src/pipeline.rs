@@ -1,9 +1,12 @@
-use crate::{transforms::FunctionTransform, Event};
+use crate::{
+    event::in_flight::InFlightLimit, internal_events::InFlightLimitReached,
+    transforms::FunctionTransform, Event,
+};
 use futures01::{
     sync::mpsc::{channel, Receiver, SendError, Sender},
     Async, AsyncSink, Poll, Sink,
 };
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::Arc};
 
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
@@ -13,6 +16,7 @@ pub struct Pipeline {
     #[derivative(Debug = "ignore")]
     inlines: Vec<Box<dyn FunctionTransform>>,
     enqueued: VecDeque<Event>,
+    in_flight: Option<Arc<InFlightLimit>>,
 }
 
 impl Pipeline {
@@ -38,6 +42,19 @@ impl Sink for Pipeline {
         match self.try_flush() {
             Ok(Async::NotReady) => Ok(AsyncSink::NotReady(item)),
             Ok(Async::Ready(())) => {
+                let mut item = item;
+                if let (Some(in_flight), Event::Log(_)) = (&self.in_flight, &item) {
+                    match in_flight.poll_acquire() {
+                        Async::Ready(token) => item.as_mut_log().add_in_flight(token),
+                        Async::NotReady => {
+                            emit!(InFlightLimitReached {
+                                max: in_flight.max()
+                            });
+                            return Ok(AsyncSink::NotReady(item));
+                        }
+                    }
+                }
+
                 // Note how this gets **swapped** with `new_working_set` in the loop.
                 // At the end of the loop, it will only contain finalized events.
                 let mut working_set = vec![item];
@@ -88,9 +105,17 @@ impl Pipeline {
             // We ensure the buffer is sufficient that it is unlikely to require reallocations.
             // There is a possibility a component might blow this queue size.
             enqueued: VecDeque::with_capacity(10),
+            in_flight: None,
         }
     }
 
+    /// Stops accepting log events while `in_flight` has no slot left for
+    /// them, which are shared with the clones of the pipeline.
+    pub fn with_in_flight_limit(mut self, in_flight: Option<Arc<InFlightLimit>>) -> Self {
+        self.in_flight = in_flight;
+        self
+    }
+
     pub fn poll_ready(&mut self) -> Poll<(), SendError<()>> {
         self.inner.poll_ready()
     }
@@ -103,10 +128,14 @@ mod test {
         transforms::{add_fields::AddFields, filter::Filter},
         Event, Value,
     };
-    use futures::compat::Future01CompatExt;
+    use futures::{
+        compat::{Future01CompatExt, Stream01CompatExt},
+        StreamExt,
+    };
     use futures01::Stream;
     use serde_json::json;
     use std::convert::TryFrom;
+    use tokio::time::{timeout, Duration};
 
     const KEYS: [&str; 2] = ["booper", "swooper"];
 
@@ -167,4 +196,25 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn in_flight_limit() {
+        let (pipeline, receiver) = Pipeline::new_test();
+        let pipeline = pipeline.with_in_flight_limit(Some(InFlightLimit::new(1)));
+        let mut receiver = receiver.compat();
+
+        let pipeline = pipeline.send(Event::from("first")).compat().await.unwrap();
+        let mut second = Box::pin(pipeline.send(Event::from("second")).compat());
+        assert!(timeout(Duration::from_millis(100), &mut second)
+            .await
+            .is_err());
+
+        let first = receiver.next().await.unwrap().unwrap();
+        assert_eq!(first.as_log()["message"], "first".into());
+        drop(first);
+
+        second.await.unwrap();
+        let second = receiver.next().await.unwrap().unwrap();
+        assert_eq!(second.as_log()["message"], "second".into());
+    }
 }
@@ -0,0 +1,301 @@
+//! Running several independent configs in one process, each as a pipeline with
+//! its own topology and data_dir, rather than a process per config.
+//!
+//! The components of a pipeline are reported in the metrics, the API and the
+//! tunable options as `<pipeline>.<component>`, so that the resources of each
+//! pipeline can be told apart. The log schema and hostname are shared by the
+//! whole process, so all the pipelines must agree on them.
+
+use crate::{
+    config::{self, Config, FormatHint, Resource},
+    topology::{self, RunningTopology},
+    trace::Instrument,
+};
+use futures::{
+    compat::{Compat01As03, Stream01CompatExt},
+    future::BoxFuture,
+    StreamExt,
+};
+use futures01::sync::mpsc;
+use std::{path::PathBuf, str::FromStr};
+use tracing::Span;
+
+/// A pipeline given on the command line, as `<name>=<path>[,<path>...]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineOpt {
+    pub name: String,
+    pub paths: Vec<PathBuf>,
+}
+
+impl FromStr for PipelineOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(name), Some(paths)) if is_valid_name(name) && !paths.is_empty() => Ok(Self {
+                name: name.to_owned(),
+                paths: paths.split(',').map(PathBuf::from).collect(),
+            }),
+            _ => Err(format!(
+                "{:?} is not a valid pipeline, expected `<name>=<path>[,<path>...]` with a name of letters, digits, `-` and `_`",
+                s
+            )),
+        }
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The span the components of a pipeline are built and run in.
+pub fn span(name: Option<&str>) -> Span {
+    match name {
+        Some(name) => info_span!("pipeline", pipeline = %name),
+        None => Span::none(),
+    }
+}
+
+/// Checks that the configs of the pipelines can run side by side.
+pub fn check(configs: &[&Config]) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    let name = |config: &Config| config.pipeline.clone().unwrap_or_default();
+
+    if let Some((first, others)) = configs.split_first() {
+        for config in others {
+            if config.global.log_schema != first.global.log_schema {
+                errors.push(format!(
+                    "Pipelines {:?} and {:?} have different log schemas, which all pipelines share.",
+                    name(first),
+                    name(config)
+                ));
+            }
+            if config.global.hostname != first.global.hostname {
+                errors.push(format!(
+                    "Pipelines {:?} and {:?} have different hostname strategies, which all pipelines share.",
+                    name(first),
+                    name(config)
+                ));
+            }
+        }
+    }
+
+    // Pipelines without a data_dir share the default one.
+    let data_dir = |config: &Config| {
+        config
+            .global
+            .data_dir
+            .clone()
+            .or_else(config::default_data_dir)
+    };
+    for (index, config) in configs.iter().enumerate() {
+        for other in &configs[index + 1..] {
+            if let Some(shared) =
+                data_dir(config).filter(|dir| Some(dir) == data_dir(other).as_ref())
+            {
+                errors.push(format!(
+                    "Pipelines {:?} and {:?} have the same data_dir {:?}; set a data_dir for each pipeline.",
+                    name(config),
+                    name(other),
+                    shared
+                ));
+            }
+        }
+    }
+
+    let resources = configs.iter().flat_map(|config| {
+        let sources = config
+            .sources
+            .iter()
+            .map(move |(name, source)| (config.qualified_name(name), source.inner.resources()));
+        let sinks = config
+            .sinks
+            .iter()
+            .map(move |(name, sink)| (config.qualified_name(name), sink.inner.resources()));
+        sources.chain(sinks)
+    });
+    for (resource, components) in Resource::conflicts(resources) {
+        errors.push(format!(
+            "Resource `{}` is claimed by multiple components: {:?}",
+            resource, components
+        ));
+    }
+
+    #[cfg(feature = "api")]
+    {
+        let serving = configs
+            .iter()
+            .filter(|config| config.api.enabled)
+            .map(|config| name(config))
+            .collect::<Vec<_>>();
+        if serving.len() > 1 {
+            errors.push(format!(
+                "The API is enabled in pipelines {:?}, of which only one can serve it for all of them.",
+                serving
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// The running topology of a pipeline, or of the only config of the process.
+pub struct RunningPipeline {
+    pub name: Option<String>,
+    /// The config paths as given, which may be glob patterns, expanded again
+    /// on reload.
+    pub path_patterns: Vec<(PathBuf, FormatHint)>,
+    pub config_paths: Vec<(PathBuf, FormatHint)>,
+    pub topology: RunningTopology,
+    graceful_crash: Compat01As03<mpsc::UnboundedReceiver<()>>,
+    sources_finished: BoxFuture<'static, ()>,
+}
+
+impl RunningPipeline {
+    /// Builds and starts the topology of the config, named after its pipeline.
+    pub async fn start(
+        path_patterns: Vec<(PathBuf, FormatHint)>,
+        config_paths: Vec<(PathBuf, FormatHint)>,
+        config: Config,
+        require_healthy: bool,
+    ) -> Option<Self> {
+        let name = config.pipeline.clone();
+        let span = span(name.as_deref());
+
+        let diff = config::ConfigDiff::initial(&config);
+        let pieces = topology::build_or_log_errors(&config, &diff)
+            .instrument(span.clone())
+            .await?;
+        let (topology, graceful_crash) =
+            topology::start_validated(config, diff, pieces, require_healthy)
+                .instrument(span)
+                .await?;
+
+        Some(Self {
+            name,
+            path_patterns,
+            config_paths,
+            sources_finished: topology.sources_finished(),
+            topology,
+            graceful_crash: graceful_crash.compat(),
+        })
+    }
+
+    /// Resolves once a component of the pipeline crashed, or all its sources
+    /// have ended.
+    pub async fn finished(&mut self) {
+        tokio::select! {
+            _ = self.graceful_crash.next() => (),
+            _ = &mut self.sources_finished => (),
+        }
+    }
+
+    /// Reloads the topology with the config, named after the pipeline.
+    pub async fn reload(&mut self, mut config: Config, require_healthy: bool) -> Result<bool, ()> {
+        config.pipeline = self.name.clone();
+        let result = self
+            .topology
+            .reload_config_and_respawn(config, require_healthy)
+            .instrument(span(self.name.as_deref()))
+            .await;
+        self.sources_finished = self.topology.sources_finished();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pipelines() {
+        assert_eq!(
+            "ingest=/etc/vector/ingest.toml,/etc/vector/common/*.toml".parse::<PipelineOpt>(),
+            Ok(PipelineOpt {
+                name: "ingest".into(),
+                paths: vec![
+                    "/etc/vector/ingest.toml".into(),
+                    "/etc/vector/common/*.toml".into()
+                ],
+            })
+        );
+        assert!("/etc/vector/ingest.toml".parse::<PipelineOpt>().is_err());
+        assert!("ingest=".parse::<PipelineOpt>().is_err());
+        assert!("in.gest=/etc/vector/ingest.toml"
+            .parse::<PipelineOpt>()
+            .is_err());
+    }
+
+    fn config(pipeline: &str, toml: &str) -> Config {
+        let mut config = config::load_from_str(toml, Some(config::Format::TOML)).unwrap();
+        config.pipeline = Some(pipeline.to_owned());
+        config
+    }
+
+    #[test]
+    fn checks_pipelines_are_isolated() {
+        let source = |address: &str| {
+            format!(
+                r#"
+                [sources.in]
+                type = "socket"
+                mode = "tcp"
+                address = "{}"
+
+                [sinks.out]
+                type = "blackhole"
+                inputs = ["in"]
+                print_amount = 1000
+                "#,
+                address
+            )
+        };
+        let first = config(
+            "first",
+            &format!(
+                "data_dir = \"/var/lib/vector/first\"\n{}",
+                source("127.0.0.1:9000")
+            ),
+        );
+        let second = config(
+            "second",
+            &format!(
+                "data_dir = \"/var/lib/vector/second\"\n{}",
+                source("127.0.0.1:9001")
+            ),
+        );
+        assert_eq!(check(&[&first, &second]), Ok(()));
+
+        let conflicting = config(
+            "conflicting",
+            &format!(
+                "data_dir = \"/var/lib/vector/first\"\n{}",
+                source("127.0.0.1:9000")
+            ),
+        );
+        let errors = check(&[&first, &conflicting]).unwrap_err();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("same data_dir"));
+        assert!(errors[1].contains("first.in"));
+        assert!(errors[1].contains("conflicting.in"));
+
+        let mut unset = config("unset", &source("127.0.0.1:9002"));
+        let mut other = config("other", &source("127.0.0.1:9003"));
+        let errors = check(&[&unset, &other]).unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("same data_dir"));
+
+        unset.global.data_dir = None;
+        assert!(check(&[&unset, &other]).is_err());
+        other.global.data_dir = None;
+        assert!(check(&[&unset, &other]).is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Management of the Vector process itself: its PID file, its limit of open
+//! files, and the crash reports written when it panics.
+
+use crate::config::Config;
+use chrono::Utc;
+use futures::future::{self, Future};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    cell::Cell,
+    fs, io,
+    panic::{self, PanicInfo},
+    path::{Path, PathBuf},
+    sync::{Mutex, Once},
+};
+
+thread_local! {
+    /// Whether the thread is polling a future whose panics are recovered
+    /// from.
+    static RECOVERING: Cell<bool> = Cell::new(false);
+}
+
+/// A file holding the PID of the process, removed once dropped.
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    pub fn create(path: PathBuf) -> io::Result<Self> {
+        fs::write(&path, format!("{}\n", std::process::id()))?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.path) {
+            warn!(message = "Failed to remove PID file.", path = ?self.path, %error);
+        }
+    }
+}
+
+/// Raises the soft limit of open files of the process to `limit`, and the
+/// hard limit along with it if the process is allowed to. Returns the soft
+/// limit in effect, which is left untouched if already higher.
+#[cfg(unix)]
+pub fn raise_open_files_limit(limit: u64) -> io::Result<u64> {
+    let mut current = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut current) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let limit = limit as libc::rlim_t;
+    if current.rlim_cur >= limit {
+        return Ok(current.rlim_cur as u64);
+    }
+
+    let raised = libc::rlimit {
+        rlim_cur: limit,
+        rlim_max: current.rlim_max.max(limit),
+    };
+    if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &raised) } == 0 {
+        return Ok(limit as u64);
+    }
+
+    // Unprivileged processes can't raise the hard limit, but can still raise
+    // the soft limit up to it.
+    let capped = libc::rlimit {
+        rlim_cur: limit.min(current.rlim_max),
+        rlim_max: current.rlim_max,
+    };
+    if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &capped) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(capped.rlim_cur as u64)
+}
+
+#[cfg(not(unix))]
+pub fn raise_open_files_limit(_limit: u64) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "the limit of open files can only be raised on Unix",
+    ))
+}
+
+lazy_static::lazy_static! {
+    static ref CRASH_CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+}
+
+/// What is known of the running configuration when the process panics.
+#[derive(Debug, Clone, PartialEq)]
+struct CrashContext {
+    dir: PathBuf,
+    config_hash: Option<String>,
+    components: Vec<Component>,
+}
+
+impl CrashContext {
+    /// The context of `config`, or `None` if there is no data directory to
+    /// write the crash reports to.
+    fn new(config: &Config) -> Option<Self> {
+        let dir = config.global.data_dir.as_ref()?.join("crash_reports");
+        let components = config
+            .sources
+            .iter()
+            .map(|(name, source)| Component::new("source", name, source.inner.source_type()))
+            .chain(config.transforms.iter().map(|(name, transform)| {
+                Component::new("transform", name, transform.inner.transform_type())
+            }))
+            .chain(
+                config
+                    .sinks
+                    .iter()
+                    .map(|(name, sink)| Component::new("sink", name, sink.inner.sink_type())),
+            )
+            .collect();
+
+        Some(Self {
+            dir,
+            config_hash: config_hash(config),
+            components,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Component {
+    kind: &'static str,
+    name: String,
+    #[serde(rename = "type")]
+    component_type: &'static str,
+}
+
+impl Component {
+    fn new(kind: &'static str, name: &str, component_type: &'static str) -> Self {
+        Self {
+            kind,
+            name: name.to_owned(),
+            component_type,
+        }
+    }
+}
+
+/// The SHA-256 of the components of `config`, telling whether two reports
+/// come from the same configuration without exposing its secrets.
+fn config_hash(config: &Config) -> Option<String> {
+    let serialized =
+        serde_json::to_vec(&(&config.sources, &config.transforms, &config.sinks)).ok()?;
+    Some(hex::encode(Sha256::digest(&serialized)))
+}
+
+#[derive(Debug, Serialize)]
+struct CrashReport<'a> {
+    timestamp: String,
+    version: String,
+    pid: u32,
+    thread: Option<&'a str>,
+    message: &'a str,
+    location: Option<String>,
+    config_hash: Option<&'a str>,
+    components: &'a [Component],
+    backtrace: String,
+}
+
+/// Records the configuration to describe in the crash reports, and where to
+/// write them.
+pub fn update_crash_context(config: &Config) {
+    let context = CrashContext::new(config);
+    if let Some(context) = &context {
+        if let Err(error) = fs::create_dir_all(&context.dir) {
+            warn!(message = "Failed to create crash report directory.", path = ?context.dir, %error);
+        }
+    }
+    *CRASH_CONTEXT.lock().expect("Crash context lock poisoned.") = context;
+}
+
+/// Installs a panic hook writing a crash report to the data directory, before
+/// running the previous hook.
+pub fn install_crash_handler() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Don't wait on a context being updated by the panicking thread.
+            let context = CRASH_CONTEXT
+                .try_lock()
+                .ok()
+                .and_then(|context| context.clone())
+                .filter(|_| !RECOVERING.with(Cell::get));
+            if let Some(context) = context {
+                match write_crash_report(&context, info) {
+                    Ok(path) => error!(message = "Wrote crash report.", ?path),
+                    Err(error) => error!(message = "Failed to write crash report.", %error),
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Runs the future without writing crash reports for its panics, as the
+/// caller recovers from those.
+pub fn without_crash_reports<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let mut future = Box::pin(future);
+    future::poll_fn(move |cx| {
+        let _guard = RecoveringGuard::enter();
+        future.as_mut().poll(cx)
+    })
+}
+
+/// Marks the thread as recovering from panics until dropped, including
+/// while unwinding.
+struct RecoveringGuard {
+    previous: bool,
+}
+
+impl RecoveringGuard {
+    fn enter() -> Self {
+        Self {
+            previous: RECOVERING.with(|recovering| recovering.replace(true)),
+        }
+    }
+}
+
+impl Drop for RecoveringGuard {
+    fn drop(&mut self) {
+        let previous = self.previous;
+        RECOVERING.with(|recovering| recovering.set(previous));
+    }
+}
+
+fn write_crash_report(context: &CrashContext, info: &PanicInfo<'_>) -> io::Result<PathBuf> {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<Any>");
+    let location = info.location().map(|location| {
+        format!(
+            "{}:{}:{}",
+            location.file(),
+            location.line(),
+            location.column()
+        )
+    });
+    let thread = std::thread::current();
+    let backtrace = format!("{:?}", backtrace::Backtrace::new());
+
+    let report = crash_report(context, thread.name(), message, location, backtrace);
+    write_report(&context.dir, &report)
+}
+
+fn crash_report<'a>(
+    context: &'a CrashContext,
+    thread: Option<&'a str>,
+    message: &'a str,
+    location: Option<String>,
+    backtrace: String,
+) -> CrashReport<'a> {
+    CrashReport {
+        timestamp: Utc::now().to_rfc3339(),
+        version: crate::get_version(),
+        pid: std::process::id(),
+        thread,
+        message,
+        location,
+        config_hash: context.config_hash.as_deref(),
+        components: &context.components,
+        backtrace,
+    }
+}
+
+fn write_report(dir: &Path, report: &CrashReport<'_>) -> io::Result<PathBuf> {
+    let path = dir.join(format!(
+        "crash-{}-{}.json",
+        Utc::now().format("%Y%m%dT%H%M%S%3fZ"),
+        report.pid
+    ));
+    let report = serde_json::to_vec_pretty(report).map_err(io::Error::from)?;
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_pid_file_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vector.pid");
+
+        let pid_file = PidFile::create(path.clone()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n", std::process::id())
+        );
+
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_higher_open_files_limit() {
+        let current = raise_open_files_limit(1).unwrap();
+        assert!(current >= 1);
+        assert_eq!(raise_open_files_limit(current).unwrap(), current);
+    }
+
+    #[cfg(all(feature = "sources-stdin", feature = "sinks-console"))]
+    #[test]
+    fn writes_crash_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let load = |config: &str, data_dir: &Path| {
+            let mut config =
+                crate::config::load_from_str(config, Some(crate::config::Format::TOML)).unwrap();
+            config.global.data_dir = Some(data_dir.to_owned());
+            config
+        };
+        let config = load(
+            r#"
+            [sources.in]
+            type = "stdin"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "json"
+            "#,
+            dir.path(),
+        );
+        let context = CrashContext::new(&config).unwrap();
+        assert_eq!(context.dir, dir.path().join("crash_reports"));
+        assert_eq!(
+            context.components,
+            vec![
+                Component::new("source", "in", "stdin"),
+                Component::new("sink", "out", "console"),
+            ]
+        );
+
+        let other = load(
+            r#"
+            [sources.in]
+            type = "stdin"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding = "text"
+            "#,
+            dir.path(),
+        );
+        assert!(context.config_hash.is_some());
+        assert_eq!(context.config_hash, config_hash(&config));
+        assert_ne!(context.config_hash, config_hash(&other));
+
+        fs::create_dir_all(&context.dir).unwrap();
+        let report = crash_report(
+            &context,
+            Some("main"),
+            "oops",
+            Some("src/main.rs:1:1".to_owned()),
+            "backtrace".to_owned(),
+        );
+        let path = write_report(&context.dir, &report).unwrap();
+
+        let report: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(report["message"], "oops");
+        assert_eq!(report["location"], "src/main.rs:1:1");
+        assert_eq!(report["thread"], "main");
+        assert_eq!(report["pid"], std::process::id());
+        assert_eq!(
+            report["config_hash"],
+            context.config_hash.as_deref().unwrap()
+        );
+        assert_eq!(report["components"][1]["name"], "out");
+        assert_eq!(report["components"][1]["type"], "console");
+        assert_eq!(report["backtrace"], "backtrace");
+    }
+}
@@ -0,0 +1,5 @@
+//! The gRPC service of version 2 of the `vector` sink and source protocol.
+
+pub mod auth;
+
+include!(concat!(env!("OUT_DIR"), "/vector.rs"));
@@ -0,0 +1,163 @@
+//! Mutual authentication of the `vector` sink and source with a pre-shared
+//! key, for deployments without certificates to verify each other with.
+//!
+//! The sink sends a random nonce to `Authenticate`. The source answers with a
+//! session, and proves knowing the key by signing the nonce along with the
+//! session. The sink then proves knowing the key by sending the signature of
+//! the session and the payload along with each request, so captured proofs
+//! can't be used to send other payloads. Sessions are signed by the source and
+//! expire, so the source doesn't keep track of them. The key itself is never
+//! sent, but the requests are only kept confidential with TLS.
+
+use openssl::{
+    hash::MessageDigest,
+    memcmp,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
+use std::{
+    convert::TryInto,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The metadata holding the session of a request.
+pub const SESSION_KEY: &str = "x-vector-session-bin";
+/// The metadata holding the signature of the session and the payload by the
+/// sink.
+pub const PROOF_KEY: &str = "x-vector-proof-bin";
+
+pub const NONCE_LEN: usize = 16;
+const SESSION_DURATION: Duration = Duration::from_secs(3600);
+const SIGNATURE_LEN: usize = 32;
+/// The expiry in seconds since the epoch, a nonce and the signature of both.
+const SESSION_LEN: usize = 8 + NONCE_LEN + SIGNATURE_LEN;
+
+#[derive(Clone)]
+pub struct PreSharedKey {
+    key: PKey<Private>,
+}
+
+impl PreSharedKey {
+    pub fn new(key: &str) -> crate::Result<Self> {
+        if key.is_empty() {
+            return Err("The pre-shared key can't be empty".into());
+        }
+        Ok(Self {
+            key: PKey::hmac(key.as_bytes())?,
+        })
+    }
+
+    pub fn nonce() -> Vec<u8> {
+        rand::random::<[u8; NONCE_LEN]>().to_vec()
+    }
+
+    fn sign(&self, label: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+        let mut signer =
+            Signer::new(MessageDigest::sha256(), &self.key).expect("HMAC keys can sign");
+        signer.update(label).expect("HMAC signing can't fail");
+        for part in parts {
+            signer.update(part).expect("HMAC signing can't fail");
+        }
+        signer.sign_to_vec().expect("HMAC signing can't fail")
+    }
+
+    fn verify(&self, label: &[u8], parts: &[&[u8]], signature: &[u8]) -> bool {
+        let expected = self.sign(label, parts);
+        expected.len() == signature.len() && memcmp::eq(&expected, signature)
+    }
+
+    /// Issues a session to the sink which sent `nonce`, along with the proof
+    /// of the source.
+    pub fn issue_session(&self, nonce: &[u8], now: SystemTime) -> (Vec<u8>, Vec<u8>) {
+        let expires = (now + SESSION_DURATION)
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let mut session = expires.to_be_bytes().to_vec();
+        session.extend(Self::nonce());
+        let signature = self.sign(b"vector session", &[&session]);
+        session.extend(signature);
+
+        let proof = self.sign(b"vector source", &[nonce, &session]);
+        (session, proof)
+    }
+
+    /// Whether the source which issued `session` for `nonce` knows the key.
+    pub fn verify_source(&self, nonce: &[u8], session: &[u8], proof: &[u8]) -> bool {
+        self.verify(b"vector source", &[nonce, session], proof)
+    }
+
+    /// The proof of the sink sent along with the request of `session`
+    /// carrying `payload`.
+    pub fn sink_proof(&self, session: &[u8], payload: &[u8]) -> Vec<u8> {
+        self.sign(b"vector sink", &[session, payload])
+    }
+
+    /// Whether `session` was issued with the key and is still valid at `now`,
+    /// and the sink sending it along with `payload` knows the key.
+    pub fn verify_sink(
+        &self,
+        session: &[u8],
+        payload: &[u8],
+        proof: &[u8],
+        now: SystemTime,
+    ) -> bool {
+        if session.len() != SESSION_LEN {
+            return false;
+        }
+        let (issued, signature) = session.split_at(8 + NONCE_LEN);
+        self.verify(b"vector session", &[issued], signature)
+            && expires(session).map_or(false, |expires| now < expires)
+            && self.verify(b"vector sink", &[session, payload], proof)
+    }
+}
+
+/// The time `session` expires at.
+pub fn expires(session: &[u8]) -> Option<SystemTime> {
+    let expires = session.get(..8)?.try_into().ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::from_be_bytes(expires)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authenticates_both_sides() {
+        let source = PreSharedKey::new("secret").unwrap();
+        let sink = PreSharedKey::new("secret").unwrap();
+        let now = SystemTime::now();
+
+        let nonce = PreSharedKey::nonce();
+        let (session, proof) = source.issue_session(&nonce, now);
+        assert!(sink.verify_source(&nonce, &session, &proof));
+        assert!(!sink.verify_source(&PreSharedKey::nonce(), &session, &proof));
+
+        let proof = sink.sink_proof(&session, b"events");
+        assert!(source.verify_sink(&session, b"events", &proof, now));
+        assert!(!source.verify_sink(&session, b"events", &proof, now + SESSION_DURATION));
+        assert!(!source.verify_sink(&session, b"events", &session, now));
+        // Proofs can't be used for other payloads.
+        assert!(!source.verify_sink(&session, b"other events", &proof, now));
+    }
+
+    #[test]
+    fn rejects_other_keys() {
+        let source = PreSharedKey::new("secret").unwrap();
+        let other = PreSharedKey::new("guess").unwrap();
+        let now = SystemTime::now();
+
+        let nonce = PreSharedKey::nonce();
+        let (session, proof) = source.issue_session(&nonce, now);
+        assert!(!other.verify_source(&nonce, &session, &proof));
+        let proof = other.sink_proof(&session, b"events");
+        assert!(!source.verify_sink(&session, b"events", &proof, now));
+
+        // Sessions can't be extended without the key.
+        let mut forged = session.clone();
+        forged[0] = 0xff;
+        let proof = source.sink_proof(&forged, b"events");
+        assert!(!source.verify_sink(&forged, b"events", &proof, now));
+
+        assert!(PreSharedKey::new("").is_err());
+    }
+}
@@ -1,11 +1,18 @@
 #![macro_use]
 
+mod abort;
 mod assert;
 mod ceil;
 mod compact;
 mod contains;
+mod decode_gzip;
+mod decode_zlib;
+mod decode_zstd;
 mod del;
 mod downcase;
+mod encode_gzip;
+mod encode_zlib;
+mod encode_zstd;
 mod ends_with;
 mod exists;
 mod flatten;
@@ -54,11 +61,18 @@ pub use self::md5::Md5;
 pub use self::sha1::Sha1;
 pub use self::sha2::Sha2;
 pub use self::sha3::Sha3;
+pub use abort::Abort;
 pub use ceil::Ceil;
 pub use compact::Compact;
 pub use contains::Contains;
+pub use decode_gzip::DecodeGzip;
+pub use decode_zlib::DecodeZlib;
+pub use decode_zstd::DecodeZstd;
 pub use del::Del;
 pub use downcase::Downcase;
+pub use encode_gzip::EncodeGzip;
+pub use encode_zlib::EncodeZlib;
+pub use encode_zstd::EncodeZstd;
 pub use ends_with::EndsWith;
 pub use exists::Exists;
 pub use flatten::Flatten;
@@ -99,6 +113,26 @@ pub use upcase::Upcase;
 pub use uuid_v4::UuidV4;
 
 use remap::{Result, Value};
+use std::io::Read;
+
+/// The largest value the decoding functions decompress to, so that small
+/// compressed values can't take up all the memory.
+const MAX_DECODED_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Reads the decompressed value, failing once it's larger than
+/// `MAX_DECODED_BYTES`.
+#[inline]
+fn read_decoded(reader: impl Read, format: &str) -> Result<Value> {
+    let mut decoded = Vec::new();
+    reader
+        .take(MAX_DECODED_BYTES + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|error| format!("unable to decode value as {}: {}", format, error))?;
+    if decoded.len() as u64 > MAX_DECODED_BYTES {
+        return Err(format!("decoded value is larger than {} bytes", MAX_DECODED_BYTES).into());
+    }
+    Ok(Value::from(decoded))
+}
 
 #[inline]
 fn convert_value_or_default(
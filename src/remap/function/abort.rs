@@ -0,0 +1,88 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Abort;
+
+impl Function for Abort {
+    fn identifier(&self) -> &'static str {
+        "abort"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "reason",
+            accepts: |_| true,
+            required: false,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let reason = arguments.optional("reason").map(Expr::boxed);
+
+        Ok(Box::new(AbortFn { reason }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AbortFn {
+    reason: Option<Box<dyn Expression>>,
+}
+
+impl AbortFn {
+    #[cfg(test)]
+    fn new(reason: Option<Box<dyn Expression>>) -> Self {
+        Self { reason }
+    }
+}
+
+impl Expression for AbortFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let reason = match self.reason.as_ref() {
+            Some(reason) => reason.execute(state, object)?,
+            None => Value::Null,
+        };
+
+        Err(Error::Abort(reason))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef {
+            fallible: true,
+            kind: value::Kind::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn abort() {
+        let cases = vec![
+            (
+                map!["code": 403],
+                Err(Error::Abort(Value::from(map!["code": 403]))),
+                AbortFn::new(Some(Box::new(Literal::from(Value::from(
+                    map!["code": 403],
+                ))))),
+            ),
+            (
+                map!["code": 403],
+                Err(Error::Abort(Value::from(403))),
+                AbortFn::new(Some(Box::new(Path::from("code")))),
+            ),
+            (map![], Err(Error::Abort(Value::Null)), AbortFn::new(None)),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object = Value::Map(object);
+            let got = func.execute(&mut state, &mut object);
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use super::read_decoded;
+use flate2::read::GzDecoder;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeGzip;
+
+impl Function for DecodeGzip {
+    fn identifier(&self) -> &'static str {
+        "decode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(DecodeGzipFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecodeGzipFn {
+    value: Box<dyn Expression>,
+}
+
+impl DecodeGzipFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for DecodeGzipFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+
+        read_decoded(GzDecoder::new(&value[..]), "gzip")
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // the value may not be gzip data
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    use value::Kind;
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| DecodeGzipFn { value: Literal::from("foo").boxed() },
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+
+        value_non_string {
+            expr: |_| DecodeGzipFn { value: Literal::from(1).boxed() },
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+    ];
+
+    #[test]
+    fn decode_gzip() {
+        let cases = vec![
+            (
+                map!["foo": Value::from(compress(b"hello world"))],
+                Ok(Value::from("hello world")),
+                DecodeGzipFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "hello world"],
+                Err(
+                    "function call error: unable to decode value as gzip: invalid gzip header"
+                        .into(),
+                ),
+                DecodeGzipFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map![
+                    "foo":
+                        Value::from(
+                            compress(&vec![0; super::super::MAX_DECODED_BYTES as usize + 1]),
+                        )
+                ],
+                Err(format!(
+                    "function call error: decoded value is larger than {} bytes",
+                    super::super::MAX_DECODED_BYTES
+                )),
+                DecodeGzipFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object: Value = object.into();
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+}
@@ -0,0 +1,110 @@
+use super::read_decoded;
+use flate2::read::ZlibDecoder;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeZlib;
+
+impl Function for DecodeZlib {
+    fn identifier(&self) -> &'static str {
+        "decode_zlib"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(DecodeZlibFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecodeZlibFn {
+    value: Box<dyn Expression>,
+}
+
+impl DecodeZlibFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for DecodeZlibFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+
+        read_decoded(ZlibDecoder::new(&value[..]), "zlib")
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // the value may not be zlib data
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+    use value::Kind;
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| DecodeZlibFn { value: Literal::from("foo").boxed() },
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+
+        value_non_string {
+            expr: |_| DecodeZlibFn { value: Literal::from(1).boxed() },
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+    ];
+
+    #[test]
+    fn decode_zlib() {
+        let cases = vec![
+            (
+                map!["foo": Value::from(compress(b"hello world"))],
+                Ok(Value::from("hello world")),
+                DecodeZlibFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "hello world"],
+                Err(
+                    "function call error: unable to decode value as zlib: corrupt deflate stream"
+                        .into(),
+                ),
+                DecodeZlibFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object: Value = object.into();
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+}
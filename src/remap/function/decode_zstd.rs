@@ -0,0 +1,107 @@
+use super::read_decoded;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeZstd;
+
+impl Function for DecodeZstd {
+    fn identifier(&self) -> &'static str {
+        "decode_zstd"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Bytes(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+
+        Ok(Box::new(DecodeZstdFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecodeZstdFn {
+    value: Box<dyn Expression>,
+}
+
+impl DecodeZstdFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for DecodeZstdFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+
+        let decoder = zstd::stream::read::Decoder::new(&value[..])
+            .map_err(|error| format!("unable to decode value as zstd: {}", error))?;
+        read_decoded(decoder, "zstd")
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value
+            .type_def(state)
+            .into_fallible(true) // the value may not be zstd data
+            .with_constraint(value::Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use value::Kind;
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| DecodeZstdFn { value: Literal::from("foo").boxed() },
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+
+        value_non_string {
+            expr: |_| DecodeZstdFn { value: Literal::from(1).boxed() },
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+    ];
+
+    #[test]
+    fn decode_zstd() {
+        let cases = vec![
+            (
+                map!["foo": Value::from(compress(b"hello world"))],
+                Ok(Value::from("hello world")),
+                DecodeZstdFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "hello world"],
+                Err(
+                    "function call error: unable to decode value as zstd: Unknown frame descriptor"
+                        .into(),
+                ),
+                DecodeZstdFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object: Value = object.into();
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        zstd::stream::encode_all(bytes, 0).unwrap()
+    }
+}
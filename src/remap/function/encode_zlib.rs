@@ -0,0 +1,160 @@
+use flate2::{write::ZlibEncoder, Compression};
+use remap::prelude::*;
+use std::io::Write;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeZlib;
+
+impl Function for EncodeZlib {
+    fn identifier(&self) -> &'static str {
+        "encode_zlib"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Bytes(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                accepts: |v| matches!(v, Value::Integer(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required("value")?.boxed();
+        let compression_level = arguments.optional("compression_level").map(Expr::boxed);
+
+        Ok(Box::new(EncodeZlibFn {
+            value,
+            compression_level,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeZlibFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl EncodeZlibFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, compression_level: Option<i64>) -> Self {
+        let compression_level = compression_level.map(|level| Literal::from(level).boxed());
+
+        Self {
+            value,
+            compression_level,
+        }
+    }
+}
+
+impl Expression for EncodeZlibFn {
+    fn execute(&self, state: &mut state::Program, object: &mut dyn Object) -> Result<Value> {
+        let value = self.value.execute(state, object)?.try_bytes()?;
+
+        let level = match &self.compression_level {
+            Some(level) => level.execute(state, object)?.try_integer()?,
+            None => 6,
+        };
+        if !(0..=9).contains(&level) {
+            return Err(format!("compression level must be between 0 and 9, got {}", level).into());
+        }
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::new(level as u32));
+        encoder
+            .write_all(&value)
+            .and_then(|_| encoder.finish())
+            .map(Value::from)
+            .map_err(|error| format!("unable to encode value as zlib: {}", error).into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        use value::Kind;
+
+        self.value
+            .type_def(state)
+            .fallible_unless(Kind::Bytes)
+            .merge_optional(self.compression_level.as_ref().map(|level| {
+                level
+                    .type_def(state)
+                    .fallible_unless(Kind::Integer)
+                    .into_fallible(true) // the level may be out of range
+            }))
+            .with_constraint(Kind::Bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+    use value::Kind;
+
+    remap::test_type_def![
+        value_string {
+            expr: |_| EncodeZlibFn::new(Literal::from("foo").boxed(), None),
+            def: TypeDef { kind: Kind::Bytes, ..Default::default() },
+        }
+
+        value_non_string {
+            expr: |_| EncodeZlibFn::new(Literal::from(1).boxed(), None),
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+
+        with_compression_level {
+            expr: |_| EncodeZlibFn::new(Literal::from("foo").boxed(), Some(9)),
+            def: TypeDef { fallible: true, kind: Kind::Bytes },
+        }
+    ];
+
+    #[test]
+    fn encode_zlib() {
+        let cases = vec![
+            (
+                map!["foo": "hello world"],
+                Ok(Value::from("hello world")),
+                EncodeZlibFn::new(Box::new(Path::from("foo")), None),
+            ),
+            (
+                map!["foo": "hello world"],
+                Ok(Value::from("hello world")),
+                EncodeZlibFn::new(Box::new(Path::from("foo")), Some(9)),
+            ),
+            (
+                map!["foo": "hello world"],
+                Err(
+                    "function call error: compression level must be between 0 and 9, got 10".into(),
+                ),
+                EncodeZlibFn::new(Box::new(Path::from("foo")), Some(10)),
+            ),
+        ];
+
+        let mut state = state::Program::default();
+
+        for (object, exp, func) in cases {
+            let mut object: Value = object.into();
+            let got = func
+                .execute(&mut state, &mut object)
+                .map(|compressed| Value::from(decompress(&compressed.unwrap_bytes())))
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    fn decompress(bytes: &[u8]) -> Vec<u8> {
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        decompressed
+    }
+}
@@ -6,6 +6,7 @@ use lazy_static::lazy_static;
 lazy_static! {
     // List of immutable functions that can be loaded into a remap-lang program.
     pub(crate) static ref FUNCTIONS: Vec<Box<dyn remap::Function>> = vec![
+        Box::new(Abort),
         Box::new(Assert),
         Box::new(Split),
         Box::new(ToString),
@@ -52,6 +53,12 @@ lazy_static! {
         Box::new(Flatten),
         Box::new(Merge),
         Box::new(Redact),
+        Box::new(EncodeGzip),
+        Box::new(DecodeGzip),
+        Box::new(EncodeZlib),
+        Box::new(DecodeZlib),
+        Box::new(EncodeZstd),
+        Box::new(DecodeZstd),
     ];
 
     // List of both mutable, and immutable functions that can be loaded into a
@@ -0,0 +1,471 @@
+use crate::{
+    buffers::Acker,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    emit,
+    event::Event,
+    internal_events::{
+        AmqpConnectionFailed, AmqpDeliveryFailed, AmqpEventMissingKeys, AmqpEventSent,
+    },
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        StreamSink,
+    },
+    template::{Template, TemplateError},
+    tls::{TlsOptions, TlsSettings},
+};
+use async_trait::async_trait;
+use futures::{
+    future::BoxFuture,
+    stream::{BoxStream, FuturesUnordered},
+    FutureExt, StreamExt,
+};
+use lapin::{
+    options::{BasicPublishOptions, ConfirmSelectOptions, ExchangeDeclareOptions},
+    publisher_confirm::Confirmation,
+    tcp::{OwnedIdentity, OwnedTLSConfig},
+    types::FieldTable,
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::HashSet, convert::TryFrom, time::Duration};
+use tokio::time::delay_for;
+use tokio_amqp::LapinTokioExt;
+
+/// How many events may wait for the confirmation of their messages, or of
+/// the messages of earlier events, at once.
+const MAX_PENDING_CONFIRMS: usize = 1000;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The delivery modes of AMQP 0-9-1 messages.
+const TRANSIENT: u8 = 1;
+const PERSISTENT: u8 = 2;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid routing key template: {}", source))]
+    RoutingKeyTemplate { source: TemplateError },
+}
+
+#[derive(Debug, Snafu)]
+enum ConnectError {
+    #[snafu(display("Invalid TLS configuration: {}", source))]
+    Tls { source: crate::tls::TlsError },
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: lapin::Error },
+    #[snafu(display("Failed to open a channel: {}", source))]
+    OpenChannel { source: lapin::Error },
+    #[snafu(display("Failed to enable publisher confirms: {}", source))]
+    ConfirmSelect { source: lapin::Error },
+    #[snafu(display("Exchange {:?} is not available: {}", exchange, source))]
+    Exchange {
+        exchange: String,
+        source: lapin::Error,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpSinkConfig {
+    /// The AMQP URI, `amqps://` connecting with TLS.
+    pub url: String,
+    /// The exchange published to, the default exchange if empty.
+    #[serde(default)]
+    pub exchange: String,
+    #[serde(default)]
+    pub routing_key: String,
+    pub encoding: EncodingConfig<Encoding>,
+    /// Publish the messages with the persistent delivery mode, so brokers
+    /// write them to disk in durable queues.
+    #[serde(default = "crate::serde::default_true")]
+    pub persistent: bool,
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+impl Encoding {
+    fn content_type(self) -> &'static str {
+        match self {
+            Encoding::Text => "text/plain",
+            Encoding::Json => "application/json",
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<AmqpSinkConfig>("amqp")
+}
+
+impl GenerateConfig for AmqpSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"url = "amqp://127.0.0.1:5672/%2f"
+            exchange = "logs"
+            routing_key = "{{ host }}"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "amqp")]
+impl SinkConfig for AmqpSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let sink = AmqpSink::new(self.clone(), cx.acker())?;
+        let healthcheck = healthcheck(self.clone()).boxed();
+        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "amqp"
+    }
+}
+
+impl AmqpSinkConfig {
+    async fn connect(&self) -> Result<Connection, ConnectError> {
+        let properties = ConnectionProperties::default().with_tokio();
+        let tls = TlsSettings::from_options(&self.tls).context(Tls)?;
+        let config = OwnedTLSConfig {
+            identity: tls.identity_pkcs12().map(|(der, password)| OwnedIdentity {
+                der: der.to_vec(),
+                password: password.to_owned(),
+            }),
+            cert_chain: tls.authorities_pem().context(Tls)?,
+        };
+        Connection::connect_with_config(&self.url, properties, config)
+            .await
+            .context(Connect)
+    }
+
+    /// Opens a channel on a new connection, which confirms the messages
+    /// published to it.
+    async fn open_channel(&self) -> Result<Channel, ConnectError> {
+        let channel = self
+            .connect()
+            .await?
+            .create_channel()
+            .await
+            .context(OpenChannel)?;
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await
+            .context(ConfirmSelect)?;
+        Ok(channel)
+    }
+}
+
+async fn healthcheck(config: AmqpSinkConfig) -> crate::Result<()> {
+    let channel = config.open_channel().await?;
+    // The default exchange always exists and can't be declared.
+    if !config.exchange.is_empty() {
+        let options = ExchangeDeclareOptions {
+            passive: true,
+            ..Default::default()
+        };
+        channel
+            .exchange_declare(
+                &config.exchange,
+                ExchangeKind::Direct,
+                options,
+                FieldTable::default(),
+            )
+            .await
+            .context(Exchange {
+                exchange: config.exchange.clone(),
+            })?;
+    }
+    Ok(())
+}
+
+struct Message {
+    routing_key: String,
+    payload: Vec<u8>,
+}
+
+type PendingConfirm = BoxFuture<'static, (usize, Message, lapin::Result<Confirmation>)>;
+
+pub struct AmqpSink {
+    config: AmqpSinkConfig,
+    routing_key: Template,
+    properties: BasicProperties,
+    channel: Option<Channel>,
+    acker: Acker,
+    seq_head: usize,
+    seq_tail: usize,
+    pending_acks: HashSet<usize>,
+}
+
+impl AmqpSink {
+    fn new(config: AmqpSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let routing_key =
+            Template::try_from(config.routing_key.as_str()).context(RoutingKeyTemplate)?;
+        let delivery_mode = if config.persistent {
+            PERSISTENT
+        } else {
+            TRANSIENT
+        };
+        let properties = BasicProperties::default()
+            .with_delivery_mode(delivery_mode)
+            .with_content_type(config.encoding.codec().content_type().into());
+
+        Ok(Self {
+            config,
+            routing_key,
+            properties,
+            channel: None,
+            acker,
+            seq_head: 0,
+            seq_tail: 0,
+            pending_acks: HashSet::new(),
+        })
+    }
+
+    fn encode_event(&self, mut event: Event) -> Result<Message, Vec<String>> {
+        let routing_key = self.routing_key.render_string(&event)?;
+
+        self.config.encoding.apply_rules(&mut event);
+        let log = event.into_log();
+        let payload = match self.config.encoding.codec() {
+            Encoding::Json => serde_json::to_vec(&log).unwrap(),
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|value| value.as_bytes().to_vec())
+                .unwrap_or_default(),
+        };
+
+        Ok(Message {
+            routing_key,
+            payload,
+        })
+    }
+
+    /// Marks the event as delivered once the events before it are, as the
+    /// messages are confirmed in any order.
+    fn ack(&mut self, seqno: usize) {
+        self.pending_acks.insert(seqno);
+        let mut count = 0;
+        while self.pending_acks.remove(&self.seq_tail) {
+            self.seq_tail += 1;
+            count += 1;
+        }
+        if count > 0 {
+            self.acker.ack(count);
+        }
+    }
+
+    /// Publishes the message, connecting again until the broker accepts it
+    /// for confirmation.
+    async fn publish(&mut self, seqno: usize, message: Message) -> PendingConfirm {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            if self.channel.is_none() {
+                match self.config.open_channel().await {
+                    Ok(channel) => self.channel = Some(channel),
+                    Err(error) => {
+                        emit!(AmqpConnectionFailed {
+                            error: error.into()
+                        });
+                        delay_for(backoff).await;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        continue;
+                    }
+                }
+            }
+            let channel = self.channel.as_ref().expect("No channel opened.");
+
+            let result = channel
+                .basic_publish(
+                    &self.config.exchange,
+                    &message.routing_key,
+                    BasicPublishOptions::default(),
+                    message.payload.clone(),
+                    self.properties.clone(),
+                )
+                .await;
+            match result {
+                Ok(confirm) => return confirm.map(move |result| (seqno, message, result)).boxed(),
+                Err(error) => {
+                    emit!(AmqpConnectionFailed {
+                        error: error.into()
+                    });
+                    self.channel = None;
+                    delay_for(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    /// Acknowledges the confirmed message, or publishes it again.
+    async fn confirmed(
+        &mut self,
+        seqno: usize,
+        message: Message,
+        result: lapin::Result<Confirmation>,
+        pending: &mut FuturesUnordered<PendingConfirm>,
+    ) {
+        match result {
+            Ok(Confirmation::Ack(_)) | Ok(Confirmation::NotRequested) => {
+                emit!(AmqpEventSent {
+                    byte_size: message.payload.len()
+                });
+                self.ack(seqno);
+            }
+            Ok(Confirmation::Nack(_)) => {
+                emit!(AmqpDeliveryFailed {
+                    error: "The broker rejected the message.".into()
+                });
+                pending.push(self.publish(seqno, message).await);
+            }
+            Err(error) => {
+                emit!(AmqpDeliveryFailed {
+                    error: error.into()
+                });
+                self.channel = None;
+                pending.push(self.publish(seqno, message).await);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink for AmqpSink {
+    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let mut input = input.fuse();
+        let mut pending = FuturesUnordered::new();
+
+        loop {
+            let unacked = self.seq_head - self.seq_tail;
+            tokio::select! {
+                Some((seqno, message, result)) = pending.next(), if !pending.is_empty() => {
+                    self.confirmed(seqno, message, result, &mut pending).await;
+                }
+                event = input.next(), if unacked < MAX_PENDING_CONFIRMS => match event {
+                    Some(event) => {
+                        let seqno = self.seq_head;
+                        self.seq_head += 1;
+                        match self.encode_event(event) {
+                            Ok(message) => pending.push(self.publish(seqno, message).await),
+                            Err(missing_keys) => {
+                                emit!(AmqpEventMissingKeys {
+                                    keys: &missing_keys
+                                });
+                                self.ack(seqno);
+                            }
+                        }
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        while let Some((seqno, message, result)) = pending.next().await {
+            self.confirmed(seqno, message, result, &mut pending).await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AmqpSinkConfig>();
+    }
+
+    fn sink(config: &str) -> AmqpSink {
+        let config: AmqpSinkConfig = toml::from_str(config).unwrap();
+        let (acker, _) = Acker::new_for_testing();
+        AmqpSink::new(config, acker).unwrap()
+    }
+
+    #[test]
+    fn encodes_events_with_routing_keys() {
+        let sink = sink(
+            r#"
+            url = "amqp://127.0.0.1:5672/%2f"
+            exchange = "logs"
+            routing_key = "logs.{{ app }}"
+            encoding.codec = "json"
+            encoding.only_fields = ["message"]
+            "#,
+        );
+        assert_eq!(sink.properties.delivery_mode(), &Some(PERSISTENT));
+
+        let mut event = Event::from("foo");
+        event.as_mut_log().insert("app", "api");
+        let message = sink.encode_event(event).unwrap();
+        assert_eq!(message.routing_key, "logs.api");
+        assert_eq!(message.payload, br#"{"message":"foo"}"#.to_vec());
+
+        assert_eq!(
+            sink.encode_event(Event::from("foo")).err(),
+            Some(vec!["app".to_owned()])
+        );
+    }
+
+    #[test]
+    fn acknowledges_events_in_order() {
+        let config: AmqpSinkConfig = toml::from_str(
+            r#"
+            url = "amqp://127.0.0.1:5672/%2f"
+            routing_key = "logs"
+            encoding.codec = "text"
+            "#,
+        )
+        .unwrap();
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let mut sink = AmqpSink::new(config, acker).unwrap();
+        sink.seq_head = 3;
+
+        sink.ack(1);
+        sink.ack(2);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 0);
+
+        sink.ack(0);
+        assert_eq!(ack_counter.load(Ordering::Relaxed), 3);
+        assert!(sink.pending_acks.is_empty());
+    }
+
+    #[test]
+    fn encodes_text_messages() {
+        let sink = sink(
+            r#"
+            url = "amqp://127.0.0.1:5672/%2f"
+            routing_key = "logs"
+            encoding.codec = "text"
+            persistent = false
+            "#,
+        );
+        assert_eq!(sink.properties.delivery_mode(), &Some(TRANSIENT));
+        assert_eq!(
+            sink.properties
+                .content_type()
+                .as_ref()
+                .map(|ty| ty.as_str()),
+            Some("text/plain")
+        );
+
+        let message = sink.encode_event(Event::from("foo")).unwrap();
+        assert_eq!(message.payload, b"foo".to_vec());
+    }
+}
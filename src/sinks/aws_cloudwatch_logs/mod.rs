@@ -160,7 +160,8 @@ impl CloudwatchLogsSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(CloudWatchLogsClient::new_with_client(client, region))
     }
 }
@@ -193,6 +194,7 @@ impl SinkConfig for CloudwatchLogsSinkConfig {
         let encoding = self.encoding.clone();
         let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
         let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .ordered(request.ordered)
             .sink_map_err(|error| error!(message = "Fatal cloudwatchlogs sink error.", %error))
             .with_flat_map(move |event| {
                 stream::iter(partition_encode(event, &encoding, &log_group, &log_stream)).map(Ok)
@@ -116,7 +116,8 @@ impl CloudWatchMetricsSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(CloudWatchClient::new_with_client(client, region))
     }
 }
@@ -141,6 +142,7 @@ impl CloudWatchMetricsSvc {
         let buffer = PartitionBuffer::new(MetricBuffer::new(batch.size));
 
         let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .ordered(request.ordered)
             .sink_map_err(|error| error!(message = "Fatal CloudwatchMetrics sink error.", %error))
             .with_flat_map(move |mut event: Event| {
                 let namespace = event
@@ -3,10 +3,12 @@ use crate::{
     event::Event,
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
+        batch::BatchError,
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
+        Batch, BatchConfig, BatchSettings, BatchSize, Compression, EncodedLength, PushResult,
+        TowerRequestConfig, VecBuffer,
     },
 };
 use bytes::Bytes;
@@ -21,12 +23,17 @@ use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
     convert::TryInto,
-    fmt,
+    fmt, mem,
     task::{Context, Poll},
 };
 use tower::Service;
 use tracing_futures::Instrument;
 
+/// The most records a `PutRecordBatch` request may contain.
+const MAX_RECORDS: usize = 500;
+/// The most bytes of data a record may contain.
+const MAX_RECORD_BYTES: usize = 1_024_000;
+
 #[derive(Clone)]
 pub struct KinesisFirehoseService {
     client: KinesisFirehoseClient,
@@ -47,6 +54,20 @@ pub struct KinesisFirehoseSinkConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub assume_role: Option<String>,
+    #[serde(default)]
+    pub aggregation: AggregationConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct AggregationConfig {
+    pub enabled: bool,
+    /// The most events packed into one record.
+    pub max_events: Option<usize>,
+    /// The most bytes of events packed into one record.
+    #[derivative(Default(value = "1_024_000"))]
+    pub max_bytes: usize,
 }
 
 lazy_static! {
@@ -129,7 +150,8 @@ impl KinesisFirehoseSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(KinesisFirehoseClient::new_with_client(client, region))
     }
 }
@@ -140,13 +162,14 @@ impl KinesisFirehoseService {
         client: KinesisFirehoseClient,
         cx: SinkContext,
     ) -> crate::Result<impl Sink<Event, Error = ()>> {
-        let batch = BatchSettings::default()
-            .bytes(4_000_000)
-            .events(500)
-            .timeout(1)
-            .parse_config(config.batch)?;
+        let mut batch = BatchSettings::default().bytes(4_000_000).timeout(1);
+        if !config.aggregation.enabled {
+            batch = batch.events(500);
+        }
+        let batch = batch.parse_config(config.batch)?;
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding.clone();
+        let buffer = AggregationBuffer::new(batch.size, &config.aggregation);
 
         let kinesis = KinesisFirehoseService { client, config };
 
@@ -154,7 +177,7 @@ impl KinesisFirehoseService {
             .batch_sink(
                 KinesisFirehoseRetryLogic,
                 kinesis,
-                VecBuffer::new(batch.size),
+                buffer,
                 batch.timeout,
                 cx.acker(),
             )
@@ -237,6 +260,120 @@ enum HealthcheckError {
     StreamNamesMismatch { name: String, stream_name: String },
 }
 
+/// Packs the events of a batch into records, each event terminated by a
+/// newline, so fewer records are billed. Without aggregation, each event is
+/// sent as a record of its own.
+pub struct AggregationBuffer {
+    records: Vec<Record>,
+    current: Vec<u8>,
+    current_events: usize,
+    events: usize,
+    bytes: usize,
+    settings: BatchSize<Self>,
+    aggregate: bool,
+    max_events: usize,
+    max_bytes: usize,
+}
+
+impl AggregationBuffer {
+    fn new(settings: BatchSize<Self>, config: &AggregationConfig) -> Self {
+        Self {
+            records: Vec::new(),
+            current: Vec::new(),
+            current_events: 0,
+            events: 0,
+            bytes: 0,
+            settings,
+            aggregate: config.enabled,
+            max_events: config.max_events.unwrap_or(usize::max_value()),
+            max_bytes: config.max_bytes.min(MAX_RECORD_BYTES),
+        }
+    }
+
+    fn num_records(&self) -> usize {
+        self.records.len() + (self.current_events > 0) as usize
+    }
+
+    fn is_full(&self) -> bool {
+        self.events >= self.settings.events
+            || self.bytes >= self.settings.bytes
+            || self.num_records() >= MAX_RECORDS
+    }
+
+    fn close_current(&mut self) {
+        if self.current_events > 0 {
+            let data = Bytes::from(mem::take(&mut self.current));
+            self.records.push(Record { data });
+            self.current_events = 0;
+        }
+    }
+}
+
+impl Batch for AggregationBuffer {
+    type Input = Record;
+    type Output = Vec<Record>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        VecBuffer::<Record>::get_settings_defaults(config, defaults.into())
+            .map(|settings| settings.into())
+    }
+
+    fn push(&mut self, record: Self::Input) -> PushResult<Self::Input> {
+        let bytes = record.encoded_length();
+        if self.is_empty() && bytes > self.settings.bytes {
+            error!(message = "Event larger than batch size, dropping.", length = %bytes, rate_limit_secs = 1);
+            return PushResult::Ok(false);
+        }
+        if self.is_full() || self.bytes + bytes > self.settings.bytes {
+            return PushResult::Overflow(record);
+        }
+
+        if self.aggregate {
+            if self.current_events >= self.max_events
+                || self.current.len() + record.data.len() + 1 > self.max_bytes
+            {
+                self.close_current();
+            }
+            self.current.extend_from_slice(&record.data);
+            self.current.push(b'\n');
+            self.current_events += 1;
+        } else {
+            self.records.push(record);
+        }
+
+        self.events += 1;
+        self.bytes += bytes;
+        PushResult::Ok(self.is_full())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events == 0
+    }
+
+    fn fresh(&self) -> Self {
+        Self {
+            records: Vec::new(),
+            current: Vec::new(),
+            current_events: 0,
+            events: 0,
+            bytes: 0,
+            ..*self
+        }
+    }
+
+    fn finish(mut self) -> Self::Output {
+        self.close_current();
+        self.records
+    }
+
+    fn num_items(&self) -> usize {
+        self.events
+    }
+}
+
 fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<Record> {
     encoding.apply_rules(&mut event);
     let log = event.into_log();
@@ -287,6 +424,31 @@ mod tests {
         );
         assert_eq!(map["key"], "value".to_string());
     }
+
+    #[test]
+    fn firehose_aggregates_records() {
+        let config = AggregationConfig {
+            enabled: true,
+            max_events: Some(2),
+            ..Default::default()
+        };
+        let settings = BatchSettings::default().bytes(4_000_000).size;
+        let mut buffer = AggregationBuffer::new(settings, &config);
+        for data in &["1", "2", "3"] {
+            let record = Record {
+                data: Bytes::from(data.to_string()),
+            };
+            assert_eq!(buffer.push(record), PushResult::Ok(false));
+        }
+        assert_eq!(buffer.num_items(), 3);
+
+        let records = buffer.finish();
+        let data = records
+            .iter()
+            .map(|record| &record.data[..])
+            .collect::<Vec<_>>();
+        assert_eq!(data, vec![&b"1\n2\n"[..], &b"3\n"[..]]);
+    }
 }
 
 #[cfg(feature = "aws-kinesis-firehose-integration-tests")]
@@ -333,6 +495,7 @@ mod integration_tests {
                 ..Default::default()
             },
             assume_role: None,
+            aggregation: Default::default(),
         };
 
         let cx = SinkContext::new_test();
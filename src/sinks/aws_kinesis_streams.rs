@@ -1,18 +1,24 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
-    internal_events::AwsKinesisStreamsEventSent,
+    internal_events::{AwsKinesisStreamsEventSent, AwsKinesisStreamsMissingKeys},
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
+        batch::BatchError,
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
+        Batch, BatchConfig, BatchSettings, BatchSize, Compression, EncodedLength, PushResult,
+        TowerRequestConfig, VecBuffer,
     },
+    template::{Template, TemplateError},
 };
 use bytes::Bytes;
 use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt, TryFutureExt};
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
+use md5::{Digest, Md5};
+use prost::Message;
 use rand::random;
 use rusoto_core::RusotoError;
 use rusoto_kinesis::{
@@ -20,15 +26,26 @@ use rusoto_kinesis::{
     PutRecordsOutput, PutRecordsRequestEntry,
 };
 use serde::{Deserialize, Serialize};
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
 use std::{
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     fmt,
     task::{Context, Poll},
 };
 use tower::Service;
 use tracing_futures::Instrument;
 
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/kinesis.rs"));
+}
+
+/// The most records a `PutRecords` request may contain.
+const MAX_RECORDS: usize = 500;
+/// The most bytes of data and partition key a record may contain.
+const MAX_RECORD_BYTES: usize = 1_048_576;
+/// The magic number starting the data of aggregated records.
+const AGGREGATION_MAGIC: [u8; 4] = [0xF3, 0x89, 0x9A, 0xC2];
+
 #[derive(Clone)]
 pub struct KinesisService {
     client: KinesisClient,
@@ -39,6 +56,7 @@ pub struct KinesisService {
 #[serde(deny_unknown_fields)]
 pub struct KinesisSinkConfig {
     pub stream_name: String,
+    pub partition_key: Option<String>,
     pub partition_key_field: Option<String>,
     #[serde(flatten)]
     pub region: RegionOrEndpoint,
@@ -50,6 +68,20 @@ pub struct KinesisSinkConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub assume_role: Option<String>,
+    #[serde(default)]
+    pub aggregation: AggregationConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct AggregationConfig {
+    pub enabled: bool,
+    /// The most events packed into one record.
+    pub max_events: Option<usize>,
+    /// The most bytes of events packed into one record.
+    #[derivative(Default(value = "51_200"))]
+    pub max_bytes: usize,
 }
 
 lazy_static! {
@@ -132,7 +164,8 @@ impl KinesisSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(KinesisClient::new_with_client(client, region))
     }
 }
@@ -143,14 +176,26 @@ impl KinesisService {
         client: KinesisClient,
         cx: SinkContext,
     ) -> crate::Result<impl Sink<Event, Error = ()>> {
-        let batch = BatchSettings::default()
-            .bytes(5_000_000)
-            .events(500)
-            .timeout(1)
-            .parse_config(config.batch)?;
+        let mut batch = BatchSettings::default().bytes(5_000_000).timeout(1);
+        if !config.aggregation.enabled {
+            batch = batch.events(500);
+        }
+        let batch = batch.parse_config(config.batch)?;
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding.clone();
-        let partition_key_field = config.partition_key_field.clone();
+        let partition_key = match (&config.partition_key, &config.partition_key_field) {
+            (Some(_), Some(_)) => return Err(BuildError::PartitionKeyConflict.into()),
+            (Some(template), None) => Some(template.clone()),
+            (None, Some(field)) => Some(format!("{{{{ {} }}}}", field)),
+            (None, None) => None,
+        };
+        let partition_key = partition_key
+            .as_deref()
+            .map(Template::try_from)
+            .transpose()
+            .context(PartitionKeyTemplate)?;
+        let buffer =
+            AggregationBuffer::new(batch.size, &config.aggregation, partition_key.is_some());
 
         let kinesis = KinesisService { client, config };
 
@@ -158,13 +203,13 @@ impl KinesisService {
             .batch_sink(
                 KinesisRetryLogic,
                 kinesis,
-                VecBuffer::new(batch.size),
+                buffer,
                 batch.timeout,
                 cx.acker(),
             )
             .sink_map_err(|error| error!(message = "Fatal kinesis streams sink error.", %error))
             .with_flat_map(move |e| {
-                stream::iter(encode_event(e, &partition_key_field, &encoding)).map(Ok)
+                stream::iter(encode_event(e, &partition_key, &encoding)).map(Ok)
             });
 
         Ok(sink)
@@ -247,6 +292,14 @@ impl RetryLogic for KinesisRetryLogic {
     }
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid partition key template: {}", source))]
+    PartitionKeyTemplate { source: TemplateError },
+    #[snafu(display("Only one of `partition_key` and `partition_key_field` can be set"))]
+    PartitionKeyConflict,
+}
+
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
     #[snafu(display("DescribeStream failed: {}", source))]
@@ -264,20 +317,19 @@ enum HealthcheckError {
 
 fn encode_event(
     mut event: Event,
-    partition_key_field: &Option<String>,
+    partition_key: &Option<Template>,
     encoding: &EncodingConfig<Encoding>,
 ) -> Option<PutRecordsRequestEntry> {
-    let partition_key = if let Some(partition_key_field) = partition_key_field {
-        if let Some(v) = event.as_log().get(&partition_key_field) {
-            v.to_string_lossy()
-        } else {
-            warn!(
-                message = "Partition key does not exist; dropping event.",
-                %partition_key_field,
-                rate_limit_secs = 30,
-            );
+    let partition_key = if let Some(partition_key) = partition_key {
+        let partition_key = partition_key
+            .render_string(&event)
+            .map_err(|keys| emit!(AwsKinesisStreamsMissingKeys { keys: &keys }))
+            .ok()?;
+        if partition_key.is_empty() {
+            emit!(AwsKinesisStreamsMissingKeys { keys: &[] });
             return None;
         }
+        partition_key
     } else {
         gen_partition_key()
     };
@@ -306,6 +358,199 @@ fn encode_event(
     })
 }
 
+/// Packs the records of a batch into aggregated records in the format of the
+/// Kinesis Producer Library, which consumers using the KCL, or the
+/// deaggregation modules of the KPL, unpack again. Records are packed per
+/// partition key, so the records of a key still end up on a single shard.
+/// Without aggregation, each aggregate holds a single record sent as is.
+pub struct AggregationBuffer {
+    records: Vec<PutRecordsRequestEntry>,
+    aggregates: IndexMap<String, Aggregate>,
+    events: usize,
+    bytes: usize,
+    settings: BatchSize<Self>,
+    max_events: usize,
+    max_bytes: usize,
+    group_by_key: bool,
+}
+
+struct Aggregate {
+    partition_key: String,
+    data: Vec<Bytes>,
+    bytes: usize,
+}
+
+impl AggregationBuffer {
+    fn new(settings: BatchSize<Self>, config: &AggregationConfig, group_by_key: bool) -> Self {
+        let (max_events, max_bytes) = if config.enabled {
+            (
+                config.max_events.unwrap_or(usize::max_value()),
+                config.max_bytes.min(MAX_RECORD_BYTES),
+            )
+        } else {
+            (1, MAX_RECORD_BYTES)
+        };
+        Self {
+            records: Vec::new(),
+            aggregates: IndexMap::new(),
+            events: 0,
+            bytes: 0,
+            settings,
+            max_events,
+            max_bytes,
+            group_by_key,
+        }
+    }
+
+    fn num_records(&self) -> usize {
+        self.records.len() + self.aggregates.len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.events >= self.settings.events
+            || self.bytes >= self.settings.bytes
+            || self.num_records() >= MAX_RECORDS
+    }
+}
+
+impl Batch for AggregationBuffer {
+    type Input = PutRecordsRequestEntry;
+    type Output = Vec<PutRecordsRequestEntry>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        VecBuffer::<PutRecordsRequestEntry>::get_settings_defaults(config, defaults.into())
+            .map(|settings| settings.into())
+    }
+
+    fn push(&mut self, entry: Self::Input) -> PushResult<Self::Input> {
+        let bytes = entry.encoded_length();
+        if self.is_empty() && bytes > self.settings.bytes {
+            error!(message = "Event larger than batch size, dropping.", length = %bytes, rate_limit_secs = 1);
+            return PushResult::Ok(false);
+        }
+        if self.is_full() || self.bytes + bytes > self.settings.bytes {
+            return PushResult::Overflow(entry);
+        }
+
+        let PutRecordsRequestEntry {
+            data,
+            partition_key,
+            ..
+        } = entry;
+        let key = if self.group_by_key {
+            partition_key.clone()
+        } else {
+            String::new()
+        };
+        let full = self.aggregates.get(&key).map_or(false, |aggregate| {
+            aggregate.data.len() >= self.max_events
+                || aggregate.bytes + Aggregate::record_size(&data) > self.max_bytes
+        });
+        if full {
+            let aggregate = self
+                .aggregates
+                .shift_remove(&key)
+                .expect("aggregate exists");
+            self.records.push(aggregate.into_entry());
+        }
+
+        self.events += 1;
+        self.bytes += bytes;
+        self.aggregates
+            .entry(key)
+            .or_insert_with(|| Aggregate::new(partition_key))
+            .push(data);
+        PushResult::Ok(self.is_full())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events == 0
+    }
+
+    fn fresh(&self) -> Self {
+        Self {
+            records: Vec::new(),
+            aggregates: IndexMap::new(),
+            events: 0,
+            bytes: 0,
+            ..*self
+        }
+    }
+
+    fn finish(self) -> Self::Output {
+        let mut records = self.records;
+        records.extend(
+            self.aggregates
+                .into_iter()
+                .map(|(_, aggregate)| aggregate.into_entry()),
+        );
+        records
+    }
+
+    fn num_items(&self) -> usize {
+        self.events
+    }
+}
+
+impl Aggregate {
+    fn new(partition_key: String) -> Self {
+        Self {
+            bytes: AGGREGATION_MAGIC.len() + 16 + partition_key.len() + 3,
+            partition_key,
+            data: Vec::new(),
+        }
+    }
+
+    /// The size of the data in an aggregated record, with the fields around
+    /// it.
+    fn record_size(data: &Bytes) -> usize {
+        data.len() + 16
+    }
+
+    fn push(&mut self, data: Bytes) {
+        self.bytes += Self::record_size(&data);
+        self.data.push(data);
+    }
+
+    fn into_entry(mut self) -> PutRecordsRequestEntry {
+        let data = if self.data.len() == 1 {
+            self.data.remove(0)
+        } else {
+            let record = proto::AggregatedRecord {
+                partition_key_table: vec![self.partition_key.clone()],
+                explicit_hash_key_table: Vec::new(),
+                records: self
+                    .data
+                    .into_iter()
+                    .map(|data| proto::Record {
+                        partition_key_index: 0,
+                        explicit_hash_key_index: None,
+                        data: data.to_vec(),
+                        tags: Vec::new(),
+                    })
+                    .collect(),
+            };
+            let mut data = Vec::with_capacity(self.bytes);
+            data.extend_from_slice(&AGGREGATION_MAGIC);
+            record
+                .encode(&mut data)
+                .expect("Vec<u8> has enough capacity");
+            let digest = Md5::digest(&data[AGGREGATION_MAGIC.len()..]);
+            data.extend_from_slice(&digest);
+            Bytes::from(data)
+        };
+
+        PutRecordsRequestEntry {
+            data,
+            partition_key: self.partition_key,
+            ..Default::default()
+        }
+    }
+}
+
 fn gen_partition_key() -> String {
     random::<[char; 16]>()
         .iter()
@@ -351,7 +596,7 @@ mod tests {
     fn kinesis_encode_event_custom_partition_key() {
         let mut event = Event::from("hello world");
         event.as_mut_log().insert("key", "some_key");
-        let event = encode_event(event, &Some("key".into()), &Encoding::Text.into()).unwrap();
+        let event = encode_event(event, &template("{{ key }}"), &Encoding::Text.into()).unwrap();
 
         assert_eq!(&event.data[..], b"hello world");
         assert_eq!(&event.partition_key, &"some_key".to_string());
@@ -361,7 +606,7 @@ mod tests {
     fn kinesis_encode_event_custom_partition_key_limit() {
         let mut event = Event::from("hello world");
         event.as_mut_log().insert("key", random_string(300));
-        let event = encode_event(event, &Some("key".into()), &Encoding::Text.into()).unwrap();
+        let event = encode_event(event, &template("{{ key }}"), &Encoding::Text.into()).unwrap();
 
         assert_eq!(&event.data[..], b"hello world");
         assert_eq!(event.partition_key.len(), 256);
@@ -375,12 +620,92 @@ mod tests {
         let mut encoding: EncodingConfig<_> = Encoding::Json.into();
         encoding.except_fields = Some(vec!["key".into()]);
 
-        let event = encode_event(event, &Some("key".into()), &encoding).unwrap();
+        let event = encode_event(event, &template("{{ key }}"), &encoding).unwrap();
         let map: BTreeMap<String, String> = serde_json::from_slice(&event.data[..]).unwrap();
 
         assert_eq!(&event.partition_key, &"some_key".to_string());
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kinesis_encode_event_partition_key_template() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "vector");
+        let partition_key = template("{{ app }}-%Y");
+
+        let encoded = encode_event(event.clone(), &partition_key, &Encoding::Text.into()).unwrap();
+        assert!(encoded.partition_key.starts_with("vector-"));
+
+        event.as_mut_log().remove("app");
+        assert!(encode_event(event, &partition_key, &Encoding::Text.into()).is_none());
+    }
+
+    #[test]
+    fn kinesis_aggregates_records_per_partition_key() {
+        let config = AggregationConfig {
+            enabled: true,
+            max_events: Some(2),
+            ..Default::default()
+        };
+        let settings = BatchSettings::default().bytes(5_000_000).size;
+        let mut buffer = AggregationBuffer::new(settings, &config, true);
+        for (key, data) in &[("a", "1"), ("b", "2"), ("a", "3"), ("a", "4")] {
+            let entry = PutRecordsRequestEntry {
+                data: Bytes::from(data.to_string()),
+                partition_key: key.to_string(),
+                ..Default::default()
+            };
+            assert_eq!(buffer.push(entry), PushResult::Ok(false));
+        }
+        assert_eq!(buffer.num_items(), 4);
+
+        let records = buffer.finish();
+        assert_eq!(records.len(), 3);
+
+        assert_eq!(records[0].partition_key, "a");
+        let data = &records[0].data[..];
+        assert_eq!(data[..4], AGGREGATION_MAGIC);
+        let (message, digest) = data[4..].split_at(data.len() - 20);
+        assert_eq!(&Md5::digest(message)[..], digest);
+        let aggregated = proto::AggregatedRecord::decode(message).unwrap();
+        assert_eq!(aggregated.partition_key_table, vec!["a".to_string()]);
+        let data = aggregated
+            .records
+            .into_iter()
+            .map(|record| record.data)
+            .collect::<Vec<_>>();
+        assert_eq!(data, vec![b"1".to_vec(), b"3".to_vec()]);
+
+        // Aggregates of a single record are sent as is.
+        assert_eq!(records[1].partition_key, "b");
+        assert_eq!(&records[1].data[..], b"2");
+        assert_eq!(&records[2].data[..], b"4");
+    }
+
+    #[test]
+    fn kinesis_sends_records_as_is_without_aggregation() {
+        let settings = BatchSettings::default().bytes(5_000_000).size;
+        let mut buffer = AggregationBuffer::new(settings, &Default::default(), false);
+        for data in &["1", "2"] {
+            let entry = PutRecordsRequestEntry {
+                data: Bytes::from(data.to_string()),
+                partition_key: gen_partition_key(),
+                ..Default::default()
+            };
+            assert_eq!(buffer.push(entry), PushResult::Ok(false));
+        }
+
+        let records = buffer.finish();
+        let data = records
+            .iter()
+            .map(|record| &record.data[..])
+            .collect::<Vec<_>>();
+        assert_eq!(data, vec![&b"1"[..], &b"2"[..]]);
+    }
+
+    fn template(template: &str) -> Option<Template> {
+        Some(Template::try_from(template).unwrap())
+    }
 }
 
 #[cfg(feature = "aws-kinesis-streams-integration-tests")]
@@ -410,6 +735,7 @@ mod integration_tests {
 
         let config = KinesisSinkConfig {
             stream_name: stream.clone(),
+            partition_key: None,
             partition_key_field: None,
             region: RegionOrEndpoint::with_endpoint("http://localhost:4566".into()),
             encoding: Encoding::Text.into(),
@@ -420,6 +746,7 @@ mod integration_tests {
             },
             request: Default::default(),
             assume_role: None,
+            aggregation: Default::default(),
         };
 
         let cx = SinkContext::new_test();
@@ -0,0 +1,352 @@
+//! The strategies naming the object written for each batch. Besides the
+//! default of a timestamp and a UUID, objects can be named after the hash of
+//! their content, after a sequence number of their partition, or after a
+//! template only, which downstream deduplication relies on being deterministic.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+const SEQUENCES_FILE: &str = "sequences.json";
+
+/// How long the keys of the template filename strategy are remembered for.
+const USED_KEYS_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// The number of keys remembered in a window; more start a new one early.
+const MAX_USED_KEYS: usize = 100_000;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum FilenameStrategy {
+    /// The time of the batch, followed by a UUID if `filename_append_uuid` is set.
+    #[derivative(Default)]
+    TimeUuid,
+    /// The hex encoded SHA-256 hash of the body of the object.
+    ContentHash,
+    /// The zero padded sequence number of the batch in its partition.
+    Sequence,
+    /// The rendered `filename_template`, which is part of the partition.
+    Template,
+}
+
+/// Names the objects of the batches of each partition.
+#[derive(Debug)]
+pub enum Filenames {
+    TimeUuid { time_format: String, uuid: bool },
+    ContentHash,
+    Sequence(Sequences),
+    Template(UsedKeys),
+}
+
+impl Filenames {
+    /// The key of the object holding `body`, a batch of the partition.
+    pub fn key(&self, partition: &str, body: &[u8], extension: &str) -> String {
+        match self {
+            Self::TimeUuid { time_format, uuid } => {
+                // TODO: pull the seconds from the last event
+                let seconds = Utc::now().format(time_format);
+                if *uuid {
+                    let uuid = Uuid::new_v4();
+                    format!(
+                        "{}{}-{}.{}",
+                        partition,
+                        seconds,
+                        uuid.to_hyphenated(),
+                        extension
+                    )
+                } else {
+                    format!("{}{}.{}", partition, seconds, extension)
+                }
+            }
+            Self::ContentHash => format!(
+                "{}{}.{}",
+                partition,
+                hex::encode(Sha256::digest(body)),
+                extension
+            ),
+            Self::Sequence(sequences) => format!(
+                "{}{:0width$}.{}",
+                partition,
+                sequences.next(partition),
+                extension,
+                width = sequences.width
+            ),
+            Self::Template(used) => used.claim(partition, extension),
+        }
+    }
+
+    /// Waits for the state behind the keys returned so far to be persisted,
+    /// for the strategies that persist any.
+    pub fn persisted(&self) -> Option<Persisted> {
+        match self {
+            Self::Sequence(sequences) => Some(sequences.persisted()),
+            _ => None,
+        }
+    }
+}
+
+/// The next sequence number of each partition, persisted in the data
+/// directory of the sink so that numbers aren't reused after a restart.
+#[derive(Debug)]
+pub struct Sequences {
+    width: usize,
+    next: Mutex<(HashMap<String, u64>, u64)>,
+    /// The numbers of each change, written to the file by a task in order.
+    changes: watch::Sender<(u64, Vec<u8>)>,
+    written: watch::Receiver<u64>,
+}
+
+impl Sequences {
+    pub fn load(data_dir: PathBuf, width: usize) -> crate::Result<Self> {
+        let path = data_dir.join(SEQUENCES_FILE);
+        let next = match fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|error| format!("Invalid sequence numbers file {:?}: {}", path, error))?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => {
+                return Err(
+                    format!("Failed to read sequence numbers file {:?}: {}", path, error).into(),
+                )
+            }
+        };
+
+        let (changes, mut pending) = watch::channel((0, Vec::new()));
+        let (persisted, written) = watch::channel(0);
+        tokio::spawn(async move {
+            // Only the latest change is written when they pile up, as it
+            // includes the earlier ones.
+            while let Some((version, contents)) = pending.recv().await {
+                if version == 0 {
+                    continue;
+                }
+                if let Err(error) = persist(&path, contents).await {
+                    error!(
+                        message = "Failed to persist sequence numbers.",
+                        ?path,
+                        %error,
+                        rate_limit_secs = 30,
+                    );
+                }
+                if persisted.broadcast(version).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            width,
+            next: Mutex::new((next, 0)),
+            changes,
+            written,
+        })
+    }
+
+    /// Takes the next sequence number of the partition. The object is only
+    /// written once it is persisted, see `persisted`, so a failed upload
+    /// leaves a gap rather than an object that could be overwritten later.
+    fn next(&self, partition: &str) -> u64 {
+        let mut state = self.next.lock().expect("Sequences lock poisoned.");
+        let (next, version) = &mut *state;
+        let number = next.get(partition).copied().unwrap_or(0);
+        next.insert(partition.to_owned(), number + 1);
+        *version += 1;
+        let contents = serde_json::to_vec(next).expect("Serializing a map doesn't fail.");
+        // The task only stops once the sequences are dropped.
+        let _ = self.changes.broadcast((*version, contents));
+        number
+    }
+
+    /// Waits for the numbers taken so far to be persisted.
+    fn persisted(&self) -> Persisted {
+        let (_, version) = *self.next.lock().expect("Sequences lock poisoned.");
+        Persisted {
+            written: self.written.clone(),
+            version,
+        }
+    }
+}
+
+async fn persist(path: &Path, contents: Vec<u8>) -> std::io::Result<()> {
+    let temporary = path.with_extension("json.tmp");
+    tokio::fs::write(&temporary, contents).await?;
+    tokio::fs::rename(&temporary, path).await
+}
+
+/// Resolves once the sequence numbers are persisted up to a change.
+#[derive(Debug, Clone)]
+pub struct Persisted {
+    written: watch::Receiver<u64>,
+    version: u64,
+}
+
+impl Persisted {
+    pub async fn wait(mut self) {
+        while *self.written.borrow() < self.version {
+            if self.written.recv().await.is_none() {
+                break;
+            }
+        }
+    }
+}
+
+/// The keys of the objects recently written by the sink, which a rendered
+/// template can't be trusted not to produce again. Keys are remembered for
+/// one to two windows, and only in memory.
+#[derive(Debug)]
+pub struct UsedKeys {
+    window: Duration,
+    max_keys: usize,
+    used: Mutex<Generations>,
+}
+
+/// The keys and the next suffix of each partition used in the current and in
+/// the previous window.
+#[derive(Debug, Default)]
+struct Generations {
+    started: Option<Instant>,
+    keys: HashSet<String>,
+    suffixes: HashMap<String, usize>,
+    previous_keys: HashSet<String>,
+    previous_suffixes: HashMap<String, usize>,
+}
+
+impl Default for UsedKeys {
+    fn default() -> Self {
+        Self::new(USED_KEYS_WINDOW, MAX_USED_KEYS)
+    }
+}
+
+impl UsedKeys {
+    fn new(window: Duration, max_keys: usize) -> Self {
+        Self {
+            window,
+            max_keys,
+            used: Mutex::new(Generations::default()),
+        }
+    }
+
+    /// The key of the partition, with a numbered suffix if it was used already.
+    fn claim(&self, partition: &str, extension: &str) -> String {
+        let mut used = self.used.lock().expect("Used keys lock poisoned.");
+        let now = Instant::now();
+        let expired = used
+            .started
+            .map_or(true, |started| now.duration_since(started) >= self.window);
+        if expired || used.keys.len() >= self.max_keys {
+            used.rotate(now);
+        }
+
+        let mut suffix = used.suffix(partition);
+        let mut key = used_key(partition, suffix, extension);
+        while used.contains(&key) {
+            suffix += 1;
+            key = used_key(partition, suffix, extension);
+        }
+        if suffix > 0 {
+            warn!(
+                message = "Object key already used; adding a suffix.",
+                %key,
+                rate_limit_secs = 30,
+            );
+        }
+        used.suffixes.insert(partition.to_owned(), suffix + 1);
+        used.keys.insert(key.clone());
+        key
+    }
+}
+
+impl Generations {
+    fn rotate(&mut self, now: Instant) {
+        self.started = Some(now);
+        self.previous_keys = mem::take(&mut self.keys);
+        self.previous_suffixes = mem::take(&mut self.suffixes);
+    }
+
+    /// The first suffix to try for the partition, so that the keys of a
+    /// partition aren't probed from the start again.
+    fn suffix(&self, partition: &str) -> usize {
+        self.suffixes
+            .get(partition)
+            .or_else(|| self.previous_suffixes.get(partition))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key) || self.previous_keys.contains(key)
+    }
+}
+
+fn used_key(partition: &str, suffix: usize, extension: &str) -> String {
+    match suffix {
+        0 => format!("{}.{}", partition, extension),
+        _ => format!("{}-{}.{}", partition, suffix, extension),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_objects_after_their_content() {
+        let filenames = Filenames::ContentHash;
+        let key = filenames.key("date=2020-12-01/", b"hello", "log");
+        assert_eq!(
+            key,
+            "date=2020-12-01/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824.log"
+        );
+        assert_eq!(filenames.key("date=2020-12-01/", b"hello", "log"), key);
+    }
+
+    #[tokio::test]
+    async fn persists_sequence_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let filenames = Filenames::Sequence(Sequences::load(dir.path().into(), 4).unwrap());
+        assert_eq!(filenames.key("a/", b"", "log"), "a/0000.log");
+        assert_eq!(filenames.key("a/", b"", "log"), "a/0001.log");
+        assert_eq!(filenames.key("b/", b"", "log"), "b/0000.log");
+        filenames.persisted().unwrap().wait().await;
+
+        let filenames = Filenames::Sequence(Sequences::load(dir.path().into(), 4).unwrap());
+        assert_eq!(filenames.key("a/", b"", "log"), "a/0002.log");
+        assert_eq!(filenames.key("b/", b"", "log"), "b/0001.log");
+    }
+
+    #[test]
+    fn suffixes_colliding_template_keys() {
+        let filenames = Filenames::Template(UsedKeys::default());
+        assert_eq!(filenames.key("app/events", b"", "log"), "app/events.log");
+        assert_eq!(filenames.key("app/events", b"", "log"), "app/events-1.log");
+        assert_eq!(filenames.key("app/other", b"", "log"), "app/other.log");
+        assert_eq!(filenames.key("app/events", b"", "log"), "app/events-2.log");
+        assert_eq!(
+            filenames.key("app/events-1", b"", "log"),
+            "app/events-1-1.log"
+        );
+    }
+
+    #[test]
+    fn forgets_template_keys_of_old_windows() {
+        let filenames = Filenames::Template(UsedKeys::new(Duration::from_secs(3600), 2));
+        assert_eq!(filenames.key("a", b"", "log"), "a.log");
+        assert_eq!(filenames.key("b", b"", "log"), "b.log");
+        // The previous window is still remembered.
+        assert_eq!(filenames.key("a", b"", "log"), "a-1.log");
+        assert_eq!(filenames.key("c", b"", "log"), "c.log");
+        // The first window is forgotten, but not the suffixes of "a".
+        assert_eq!(filenames.key("b", b"", "log"), "b.log");
+        assert_eq!(filenames.key("a", b"", "log"), "a-2.log");
+    }
+}
@@ -6,14 +6,14 @@ use crate::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
-        PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        BatchConfig, BatchSettings, Buffer, CloseWith, Compression, Concurrency,
+        PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt,
+        TowerRequestConfig,
     },
     template::Template,
     Event,
 };
 use bytes::Bytes;
-use chrono::Utc;
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
 use http::StatusCode;
 use lazy_static::lazy_static;
@@ -26,11 +26,21 @@ use snafu::Snafu;
 use std::{
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
+    path::PathBuf,
+    sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 use tower::{Service, ServiceBuilder};
 use tracing_futures::Instrument;
-use uuid::Uuid;
+
+mod filename;
+mod multipart;
+mod parquet;
+
+use self::filename::{FilenameStrategy, Filenames, Persisted, Sequences, UsedKeys};
+use self::parquet::{ParquetConfig, ParquetEncoder};
+use multipart::{MultipartConfig, MultipartRetryLogic, MultipartSink};
 
 #[derive(Clone)]
 pub struct S3Sink {
@@ -45,6 +55,11 @@ pub struct S3SinkConfig {
     pub filename_time_format: Option<String>,
     pub filename_append_uuid: Option<bool>,
     pub filename_extension: Option<String>,
+    #[serde(default)]
+    pub filename_strategy: FilenameStrategy,
+    pub filename_template: Option<String>,
+    pub filename_sequence_width: Option<usize>,
+    pub data_dir: Option<PathBuf>,
     #[serde(flatten)]
     options: S3Options,
     #[serde(flatten)]
@@ -61,6 +76,10 @@ pub struct S3SinkConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub assume_role: Option<String>,
+    #[serde(default)]
+    pub multipart: MultipartConfig,
+    #[serde(default)]
+    parquet: ParquetConfig,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -132,6 +151,7 @@ pub enum Encoding {
     #[derivative(Default)]
     Text,
     Ndjson,
+    Parquet,
 }
 
 inventory::submit! {
@@ -175,14 +195,23 @@ enum HealthcheckError {
 impl S3SinkConfig {
     pub fn new(&self, client: S3Client, cx: SinkContext) -> crate::Result<super::VectorSink> {
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let ordered = request.ordered;
         let encoding = self.encoding.clone();
 
-        let compression = self.compression;
-        let filename_time_format = self
-            .filename_time_format
-            .clone()
-            .unwrap_or_else(|| "%s".into());
-        let filename_append_uuid = self.filename_append_uuid.unwrap_or(true);
+        let parquet = match self.encoding.codec() {
+            Encoding::Parquet if self.multipart.enabled => {
+                return Err("The parquet encoding can't be used with multipart uploads.".into())
+            }
+            Encoding::Parquet => Some(ParquetEncoder::new(&self.parquet)?),
+            Encoding::Text | Encoding::Ndjson => None,
+        };
+        // Parquet files are compressed by their own codec.
+        let compression = if parquet.is_some() {
+            Compression::None
+        } else {
+            self.compression
+        };
+        let filenames = Arc::new(self.filenames(&cx)?);
         let batch = BatchSettings::default()
             .bytes(10_000_000)
             .timeout(300)
@@ -190,35 +219,118 @@ impl S3SinkConfig {
 
         let key_prefix = self.key_prefix.as_deref().unwrap_or("date=%F/");
         let key_prefix = Template::try_from(key_prefix)?;
+        let filename_template = match self.filename_strategy {
+            FilenameStrategy::Template => Some(Template::try_from(
+                self.filename_template.as_deref().unwrap_or_default(),
+            )?),
+            _ => None,
+        };
 
-        let s3 = S3Sink { client };
-
-        let filename_extension = self.filename_extension.clone();
+        let filename_extension = self
+            .filename_extension
+            .clone()
+            .or_else(|| parquet.as_ref().map(|_| "parquet".to_owned()));
         let bucket = self.bucket.clone();
-        let options = self.options.clone();
-
-        let svc = ServiceBuilder::new()
-            .map(move |req| {
-                build_request(
-                    req,
-                    filename_time_format.clone(),
-                    filename_extension.clone(),
-                    filename_append_uuid,
-                    compression,
-                    bucket.clone(),
-                    options.clone(),
-                )
-            })
-            .settings(request, S3RetryLogic)
-            .service(s3);
+        let mut options = self.options.clone();
+        if parquet.is_some() && options.content_type.is_none() {
+            options.content_type = Some("application/octet-stream".to_owned());
+        }
 
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, self.compression));
+        let build = move |req| {
+            build_request(
+                req,
+                &filenames,
+                filename_extension.clone(),
+                compression,
+                bucket.clone(),
+                options.clone(),
+                parquet.as_ref(),
+            )
+        };
+        let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
+
+        let sink = if self.multipart.enabled {
+            // Requests wait for the part holding their data, or for the
+            // completion of their upload.
+            let mut request = request;
+            request.timeout += Duration::from_secs(self.multipart.object_timeout_secs);
+            let multipart = MultipartSink::new(client, &self.multipart);
+            let closer = multipart.closer();
+            let svc = ServiceBuilder::new()
+                .map(build)
+                .settings(request, MultipartRetryLogic)
+                .service(multipart);
+            let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                .ordered(ordered)
+                .with_flat_map(move |e| {
+                    stream::iter(encode_event(
+                        e,
+                        &key_prefix,
+                        filename_template.as_ref(),
+                        &encoding,
+                    ))
+                    .map(Ok)
+                })
+                .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+            super::VectorSink::Sink(Box::new(CloseWith::new(sink, closer)))
+        } else {
+            let svc = ServiceBuilder::new()
+                .map(build)
+                .settings(request, S3RetryLogic)
+                .service(S3Sink { client });
+            let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                .ordered(ordered)
+                .with_flat_map(move |e| {
+                    stream::iter(encode_event(
+                        e,
+                        &key_prefix,
+                        filename_template.as_ref(),
+                        &encoding,
+                    ))
+                    .map(Ok)
+                })
+                .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+            super::VectorSink::Sink(Box::new(sink))
+        };
 
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .with_flat_map(move |e| stream::iter(encode_event(e, &key_prefix, &encoding)).map(Ok))
-            .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+        Ok(sink)
+    }
 
-        Ok(super::VectorSink::Sink(Box::new(sink)))
+    fn filenames(&self, cx: &SinkContext) -> crate::Result<Filenames> {
+        let strategy = self.filename_strategy;
+        if self.multipart.enabled && strategy != FilenameStrategy::TimeUuid {
+            return Err(format!(
+                "The {:?} filename strategy can't be used with multipart uploads.",
+                to_string(strategy)
+            )
+            .into());
+        }
+        if self.filename_template.is_some() && strategy != FilenameStrategy::Template {
+            return Err(
+                "The filename_template option requires the template filename strategy.".into(),
+            );
+        }
+        Ok(match strategy {
+            FilenameStrategy::TimeUuid => Filenames::TimeUuid {
+                time_format: self
+                    .filename_time_format
+                    .clone()
+                    .unwrap_or_else(|| "%s".into()),
+                uuid: self.filename_append_uuid.unwrap_or(true),
+            },
+            FilenameStrategy::ContentHash => Filenames::ContentHash,
+            FilenameStrategy::Sequence => {
+                let data_dir = cx.resolve_and_make_data_subdir(self.data_dir.as_ref())?;
+                let width = self.filename_sequence_width.unwrap_or(10);
+                Filenames::Sequence(Sequences::load(data_dir, width)?)
+            }
+            FilenameStrategy::Template if self.filename_template.is_none() => {
+                return Err(
+                    "The template filename strategy requires the filename_template option.".into(),
+                )
+            }
+            FilenameStrategy::Template => Filenames::Template(UsedKeys::default()),
+        })
     }
 
     pub async fn healthcheck(self, client: S3Client) -> crate::Result<()> {
@@ -262,44 +374,18 @@ impl Service<Request> for S3Sink {
     }
 
     fn call(&mut self, request: Request) -> Self::Future {
-        let options = request.options;
-
-        let content_encoding = request.content_encoding;
-        let content_encoding = options
-            .content_encoding
-            .or_else(|| content_encoding.map(|ce| ce.to_string()));
-        let content_type = options
-            .content_type
-            .or_else(|| Some("text/x-log".to_owned()));
-
-        let mut tagging = url::form_urlencoded::Serializer::new(String::new());
-        if let Some(tags) = options.tags {
-            for (p, v) in tags {
-                tagging.append_pair(&p, &v);
-            }
-        }
-        let tagging = tagging.finish();
-
         let client = self.client.clone();
+        let put = put_object_request(&request);
+        let persisted = request.persisted;
         let request = PutObjectRequest {
             body: Some(request.body.into()),
-            bucket: request.bucket,
-            key: request.key,
-            content_encoding,
-            content_type,
-            acl: options.acl.map(to_string),
-            grant_full_control: options.grant_full_control,
-            grant_read: options.grant_read,
-            grant_read_acp: options.grant_read_acp,
-            grant_write_acp: options.grant_write_acp,
-            server_side_encryption: options.server_side_encryption.map(to_string),
-            ssekms_key_id: options.ssekms_key_id,
-            storage_class: options.storage_class.map(to_string),
-            tagging: Some(tagging),
-            ..Default::default()
+            ..put
         };
 
         Box::pin(async move {
+            if let Some(persisted) = persisted {
+                persisted.wait().await;
+            }
             client
                 .put_object(request)
                 .instrument(info_span!("request"))
@@ -308,32 +394,64 @@ impl Service<Request> for S3Sink {
     }
 }
 
+/// Everything about an object except for its body.
+fn put_object_request(request: &Request) -> PutObjectRequest {
+    let options = request.options.clone();
+
+    let content_encoding = options
+        .content_encoding
+        .or_else(|| request.content_encoding.map(|ce| ce.to_string()));
+    let content_type = options
+        .content_type
+        .or_else(|| Some("text/x-log".to_owned()));
+
+    let mut tagging = url::form_urlencoded::Serializer::new(String::new());
+    if let Some(tags) = options.tags {
+        for (p, v) in tags {
+            tagging.append_pair(&p, &v);
+        }
+    }
+    let tagging = tagging.finish();
+
+    PutObjectRequest {
+        bucket: request.bucket.clone(),
+        key: request.key.clone(),
+        content_encoding,
+        content_type,
+        acl: options.acl.map(to_string),
+        grant_full_control: options.grant_full_control,
+        grant_read: options.grant_read,
+        grant_read_acp: options.grant_read_acp,
+        grant_write_acp: options.grant_write_acp,
+        server_side_encryption: options.server_side_encryption.map(to_string),
+        ssekms_key_id: options.ssekms_key_id,
+        storage_class: options.storage_class.map(to_string),
+        tagging: Some(tagging),
+        ..Default::default()
+    }
+}
+
 fn build_request(
     req: PartitionInnerBuffer<Vec<u8>, Bytes>,
-    time_format: String,
+    filenames: &Filenames,
     extension: Option<String>,
-    uuid: bool,
     compression: Compression,
     bucket: String,
     options: S3Options,
+    parquet: Option<&ParquetEncoder>,
 ) -> Request {
     let (inner, key) = req.into_parts();
-
-    // TODO: pull the seconds from the last event
-    let filename = {
-        let seconds = Utc::now().format(&time_format);
-
-        if uuid {
-            let uuid = Uuid::new_v4();
-            format!("{}-{}", seconds, uuid.to_hyphenated())
-        } else {
-            seconds.to_string()
-        }
+    let inner = match parquet {
+        Some(encoder) => encoder
+            .encode(&inner)
+            .expect("Writing Parquet files to memory doesn't fail."),
+        None => inner,
     };
 
     let extension = extension.unwrap_or_else(|| compression.extension().into());
-    let key = String::from_utf8_lossy(&key[..]).into_owned();
-    let key = format!("{}{}.{}", key, filename, extension);
+    let prefix = String::from_utf8_lossy(&key[..]).into_owned();
+    let key = filenames.key(&prefix, &inner, &extension);
+    let persisted = filenames.persisted();
 
     debug!(
         message = "Sending events.",
@@ -346,8 +464,10 @@ fn build_request(
         body: inner,
         bucket,
         key,
+        prefix,
         content_encoding: compression.content_encoding(),
         options,
+        persisted,
     }
 }
 
@@ -356,8 +476,13 @@ struct Request {
     body: Vec<u8>,
     bucket: String,
     key: String,
+    /// The rendered `key_prefix`, followed by the rendered `filename_template`
+    /// if any, which identifies the partition.
+    prefix: String,
     content_encoding: Option<&'static str>,
     options: S3Options,
+    /// Resolves once the key can't be taken again after a restart.
+    persisted: Option<Persisted>,
 }
 
 impl Response for PutObjectOutput {}
@@ -377,24 +502,31 @@ impl RetryLogic for S3RetryLogic {
 fn encode_event(
     mut event: Event,
     key_prefix: &Template,
+    filename_template: Option<&Template>,
     encoding: &EncodingConfigWithDefault<Encoding>,
 ) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
-    let key = key_prefix
-        .render_string(&event)
-        .map_err(|missing_keys| {
-            warn!(
-                message = "Keys do not exist on the event; dropping event.",
-                ?missing_keys,
-                rate_limit_secs = 30,
-            );
-        })
-        .ok()?;
+    let render = |template: &Template| {
+        template
+            .render_string(&event)
+            .map_err(|missing_keys| {
+                warn!(
+                    message = "Keys do not exist on the event; dropping event.",
+                    ?missing_keys,
+                    rate_limit_secs = 30,
+                );
+            })
+            .ok()
+    };
+    let mut key = render(key_prefix)?;
+    if let Some(filename_template) = filename_template {
+        key.push_str(&render(filename_template)?);
+    }
 
     encoding.apply_rules(&mut event);
 
     let log = event.into_log();
     let bytes = match encoding.codec() {
-        Encoding::Ndjson => serde_json::to_vec(&log)
+        Encoding::Ndjson | Encoding::Parquet => serde_json::to_vec(&log)
             .map(|mut b| {
                 b.push(b'\n');
                 b
@@ -429,6 +561,7 @@ mod tests {
         let bytes = encode_event(
             message.clone().into(),
             &batch_time_format,
+            None,
             &Encoding::Text.into(),
         )
         .unwrap();
@@ -445,7 +578,8 @@ mod tests {
         event.as_mut_log().insert("key", "value");
 
         let batch_time_format = Template::try_from("date=%F").unwrap();
-        let bytes = encode_event(event, &batch_time_format, &Encoding::Ndjson.into()).unwrap();
+        let bytes =
+            encode_event(event, &batch_time_format, None, &Encoding::Ndjson.into()).unwrap();
 
         let (bytes, _) = bytes.into_parts();
         let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
@@ -468,7 +602,7 @@ mod tests {
             ..Default::default()
         };
 
-        let bytes = encode_event(event, &key_prefix, &encoding_config).unwrap();
+        let bytes = encode_event(event, &key_prefix, None, &encoding_config).unwrap();
 
         let (bytes, _) = bytes.into_parts();
         let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
@@ -483,49 +617,115 @@ mod tests {
 
         let req = build_request(
             buf.clone(),
-            "date".into(),
+            &Filenames::TimeUuid {
+                time_format: "date".into(),
+                uuid: false,
+            },
             Some("ext".into()),
-            false,
             Compression::None,
             "bucket".into(),
             S3Options::default(),
+            None,
         );
         assert_eq!(req.key, "key/date.ext".to_string());
 
         let req = build_request(
             buf.clone(),
-            "date".into(),
+            &Filenames::TimeUuid {
+                time_format: "date".into(),
+                uuid: false,
+            },
             None,
-            false,
             Compression::None,
             "bucket".into(),
             S3Options::default(),
+            None,
         );
         assert_eq!(req.key, "key/date.log".to_string());
 
         let req = build_request(
             buf.clone(),
-            "date".into(),
+            &Filenames::TimeUuid {
+                time_format: "date".into(),
+                uuid: false,
+            },
             None,
-            false,
             Compression::gzip_default(),
             "bucket".into(),
             S3Options::default(),
+            None,
         );
         assert_eq!(req.key, "key/date.log.gz".to_string());
 
         let req = build_request(
             buf,
-            "date".into(),
+            &Filenames::TimeUuid {
+                time_format: "date".into(),
+                uuid: true,
+            },
             None,
-            true,
             Compression::gzip_default(),
             "bucket".into(),
             S3Options::default(),
+            None,
         );
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
 
+    #[test]
+    fn s3_encode_event_filename_template() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "billing");
+
+        let key_prefix = Template::try_from("logs/").unwrap();
+        let filename_template = Template::try_from("{{ app }}/events").unwrap();
+        let bytes = encode_event(
+            event.clone(),
+            &key_prefix,
+            Some(&filename_template),
+            &Encoding::Text.into(),
+        )
+        .unwrap();
+
+        let (_, key) = bytes.into_parts();
+        assert_eq!(&key[..], b"logs/billing/events");
+
+        let filename_template = Template::try_from("{{ missing }}").unwrap();
+        assert!(encode_event(
+            event,
+            &key_prefix,
+            Some(&filename_template),
+            &Encoding::Text.into()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn s3_filename_strategy_options() {
+        let cx = SinkContext::new_test();
+        let config = |toml: &str| -> S3SinkConfig {
+            toml::from_str(&format!("bucket = \"bucket\"\n{}", toml)).unwrap()
+        };
+
+        assert!(config(r#"filename_strategy = "content_hash""#)
+            .filenames(&cx)
+            .is_ok());
+        assert!(config(r#"filename_strategy = "template""#)
+            .filenames(&cx)
+            .is_err());
+        assert!(config(r#"filename_template = "{{ app }}""#)
+            .filenames(&cx)
+            .is_err());
+        assert!(config(
+            r#"
+            filename_strategy = "sequence"
+            multipart.enabled = true
+            "#
+        )
+        .filenames(&cx)
+        .is_err());
+    }
+
     #[test]
     fn storage_class_names() {
         for &(name, storage_class) in &[
@@ -666,6 +866,31 @@ mod integration_tests {
         assert_eq!(lines, response_lines.await);
     }
 
+    #[tokio::test]
+    async fn s3_multipart() {
+        let cx = SinkContext::new_test();
+
+        let config = S3SinkConfig {
+            multipart: MultipartConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..config(1000).await
+        };
+        let prefix = config.key_prefix.clone();
+        let client = config.create_client().unwrap();
+        let sink = config.new(client, cx).unwrap();
+
+        let (lines, events) = random_lines_with_stream(100, 100);
+        // The upload is completed before the sink finishes.
+        sink.run(events).await.unwrap();
+
+        let keys = get_keys(prefix.unwrap()).await;
+        assert_eq!(keys.len(), 1);
+        let response_lines = get_lines(get_object(keys[0].clone()).await).await;
+        assert_eq!(lines, response_lines);
+    }
+
     #[tokio::test]
     async fn s3_healthchecks() {
         let config = config(1).await;
@@ -0,0 +1,577 @@
+//! Streams the batches of each partition into S3 multipart uploads, so that
+//! objects are never held in memory as a whole. An upload is completed, which
+//! makes its object visible, once it reaches `object_max_bytes` or is older
+//! than `object_timeout_secs`.
+//!
+//! The events of a request are acked once the part holding them is uploaded,
+//! or for the last part, once the upload is completed. Requests exceed no
+//! part's size, so the events of a failed part are sent again by the retries
+//! of their requests, into a new upload. An upload whose completion fails is
+//! aborted rather than left behind in the bucket.
+
+use super::{put_object_request, Request};
+use crate::{rusoto, sinks::util::retries::RetryLogic};
+use futures::future::{BoxFuture, FutureExt};
+use rusoto_core::RusotoError;
+use rusoto_s3::{
+    AbortMultipartUploadRequest, CompleteMultipartUploadError, CompleteMultipartUploadRequest,
+    CompletedMultipartUpload, CompletedPart, CreateMultipartUploadError,
+    CreateMultipartUploadRequest, S3Client, UploadPartError, UploadPartRequest, S3,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{sync::oneshot, task::JoinHandle, time};
+use tower::Service;
+use tracing_futures::Instrument;
+
+/// S3 rejects parts smaller than this, except for the last one of an upload.
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// How often completing an upload is tried before it's aborted.
+const COMPLETE_ATTEMPTS: usize = 5;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct MultipartConfig {
+    pub enabled: bool,
+    pub object_max_bytes: usize,
+    pub object_timeout_secs: u64,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            object_max_bytes: 5_000_000_000,
+            object_timeout_secs: 3600,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum MultipartError {
+    #[snafu(display("Failed to create multipart upload: {}", source))]
+    CreateUpload {
+        source: RusotoError<CreateMultipartUploadError>,
+    },
+    #[snafu(display("Failed to upload part: {}", source))]
+    UploadPart {
+        source: RusotoError<UploadPartError>,
+    },
+    #[snafu(display("Failed to complete multipart upload: {}", source))]
+    CompleteUpload {
+        source: RusotoError<CompleteMultipartUploadError>,
+    },
+    #[snafu(display("The upload failed before the events were written"))]
+    Incomplete,
+}
+
+#[derive(Debug, Clone)]
+pub struct MultipartRetryLogic;
+
+impl RetryLogic for MultipartRetryLogic {
+    type Error = MultipartError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            MultipartError::CreateUpload { source } => rusoto::is_retriable_error(source),
+            MultipartError::UploadPart { source } => rusoto::is_retriable_error(source),
+            MultipartError::CompleteUpload { source } => rusoto::is_retriable_error(source),
+            MultipartError::Incomplete => true,
+        }
+    }
+}
+
+struct Upload {
+    bucket: String,
+    key: String,
+    upload_id: String,
+    started: Instant,
+    state: Mutex<UploadState>,
+}
+
+#[derive(Default)]
+struct UploadState {
+    /// Data too small to be uploaded as a part yet.
+    pending: Vec<u8>,
+    /// Told whether the data of the requests in `pending` was written.
+    waiters: Vec<oneshot::Sender<bool>>,
+    next_part: i64,
+    parts: Vec<CompletedPart>,
+    bytes: usize,
+    in_flight: usize,
+    /// No more data is added to closed uploads, they are completed as soon as
+    /// their parts in flight are uploaded.
+    closed: bool,
+    completing: bool,
+}
+
+/// A part taken from the pending data of an upload.
+struct Part {
+    number: i64,
+    body: Vec<u8>,
+    waiters: Vec<oneshot::Sender<bool>>,
+}
+
+type Uploads = Mutex<HashMap<String, Arc<Upload>>>;
+
+#[derive(Clone)]
+pub struct MultipartSink {
+    client: S3Client,
+    object_max_bytes: usize,
+    uploads: Arc<Uploads>,
+    /// Set once the input ended, after which uploads are completed right away.
+    closing: Arc<AtomicBool>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Lets the task completing timed out uploads know when the sink is gone.
+    _alive: Arc<()>,
+}
+
+impl MultipartSink {
+    pub fn new(client: S3Client, config: &MultipartConfig) -> Self {
+        let uploads = Arc::new(Mutex::new(HashMap::new()));
+        let closing = Arc::new(AtomicBool::new(false));
+        let alive = Arc::new(());
+        let task = tokio::spawn(complete_timed_out(
+            client.clone(),
+            Arc::clone(&uploads),
+            Arc::clone(&closing),
+            Arc::downgrade(&alive),
+            Duration::from_secs(config.object_timeout_secs),
+        ));
+        Self {
+            client,
+            object_max_bytes: config.object_max_bytes,
+            uploads,
+            closing,
+            task: Arc::new(Mutex::new(Some(task))),
+            _alive: alive,
+        }
+    }
+
+    /// Returns the function completing all uploads once the input ended, its
+    /// future resolves when the open uploads are completed or aborted.
+    pub fn closer(&self) -> impl FnOnce() -> BoxFuture<'static, ()> + Send + 'static {
+        let closing = Arc::clone(&self.closing);
+        let task = Arc::clone(&self.task);
+        move || {
+            closing.store(true, Ordering::Relaxed);
+            let task = task.lock().unwrap().take();
+            async move {
+                if let Some(task) = task {
+                    let _ = task.await;
+                }
+            }
+            .boxed()
+        }
+    }
+
+    async fn upload_for(&self, request: &Request) -> Result<Arc<Upload>, MultipartError> {
+        if let Some(upload) = self.uploads.lock().unwrap().get(&request.prefix) {
+            return Ok(Arc::clone(upload));
+        }
+
+        let put = put_object_request(request);
+        let output = self
+            .client
+            .create_multipart_upload(CreateMultipartUploadRequest {
+                bucket: put.bucket,
+                key: put.key,
+                acl: put.acl,
+                content_encoding: put.content_encoding,
+                content_type: put.content_type,
+                grant_full_control: put.grant_full_control,
+                grant_read: put.grant_read,
+                grant_read_acp: put.grant_read_acp,
+                grant_write_acp: put.grant_write_acp,
+                server_side_encryption: put.server_side_encryption,
+                ssekms_key_id: put.ssekms_key_id,
+                storage_class: put.storage_class,
+                tagging: put.tagging,
+                ..Default::default()
+            })
+            .await
+            .context(CreateUpload)?;
+        let upload = Arc::new(Upload {
+            bucket: request.bucket.clone(),
+            key: request.key.clone(),
+            upload_id: output.upload_id.unwrap_or_default(),
+            started: Instant::now(),
+            state: Mutex::new(UploadState {
+                next_part: 1,
+                ..Default::default()
+            }),
+        });
+
+        // Another request of the same partition may have created one meanwhile.
+        let existing = {
+            let mut uploads = self.uploads.lock().unwrap();
+            match uploads.get(&request.prefix) {
+                Some(existing) => Some(Arc::clone(existing)),
+                None => {
+                    uploads.insert(request.prefix.clone(), Arc::clone(&upload));
+                    None
+                }
+            }
+        };
+        match existing {
+            Some(existing) => {
+                upload.state.lock().unwrap().closed = true;
+                finish(&self.client, &upload).await;
+                Ok(existing)
+            }
+            None => Ok(upload),
+        }
+    }
+
+    async fn send(self, request: Request) -> Result<(), MultipartError> {
+        let (upload, part, written) = loop {
+            let upload = self.upload_for(&request).await?;
+            let pushed = upload.state.lock().unwrap().push(
+                &request.body,
+                self.object_max_bytes,
+                self.closing.load(Ordering::Relaxed),
+            );
+            // Closed meanwhile, the next lookup creates a new upload.
+            if let Some((part, written)) = pushed {
+                break (upload, part, written);
+            }
+        };
+        if upload.state.lock().unwrap().closed {
+            close(&self.uploads, &request.prefix, &upload);
+        }
+
+        if let Some(part) = part {
+            match upload_part(&self.client, &upload, part.number, part.body).await {
+                Ok(()) => notify(part.waiters, true),
+                Err(error) => {
+                    warn!(
+                        message = "Failed to upload part, its events are sent again.",
+                        %error,
+                        key = %upload.key,
+                        rate_limit_secs = 10
+                    );
+                    notify(part.waiters, false);
+                    // The earlier parts are kept by completing the upload.
+                    upload.state.lock().unwrap().closed = true;
+                    close(&self.uploads, &request.prefix, &upload);
+                }
+            }
+        }
+
+        finish(&self.client, &upload).await;
+        match written.await {
+            Ok(true) => Ok(()),
+            _ => Err(MultipartError::Incomplete),
+        }
+    }
+}
+
+impl Service<Request> for MultipartSink {
+    type Response = ();
+    type Error = MultipartError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let sink = self.clone();
+        Box::pin(sink.send(request).instrument(info_span!("request")))
+    }
+}
+
+impl UploadState {
+    /// Add the data of a request unless the upload is closed, returning the
+    /// part to upload now, if any, and the receiver told whether the data was
+    /// written.
+    fn push(
+        &mut self,
+        body: &[u8],
+        max_bytes: usize,
+        closing: bool,
+    ) -> Option<(Option<Part>, oneshot::Receiver<bool>)> {
+        if self.closed {
+            return None;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.extend_from_slice(body);
+        self.waiters.push(tx);
+        self.bytes += body.len();
+        if self.bytes >= max_bytes || closing {
+            self.closed = true;
+        }
+        Some((self.take_part(MIN_PART_SIZE), rx))
+    }
+
+    /// Take the pending data as a part, if it's large enough or the upload is
+    /// closing, registering it as in flight.
+    fn take_part(&mut self, min_size: usize) -> Option<Part> {
+        if self.pending.is_empty() || (self.pending.len() < min_size && !self.closed) {
+            return None;
+        }
+
+        let number = self.next_part;
+        self.next_part += 1;
+        self.in_flight += 1;
+        Some(Part {
+            number,
+            body: mem::take(&mut self.pending),
+            waiters: mem::take(&mut self.waiters),
+        })
+    }
+}
+
+async fn upload_part(
+    client: &S3Client,
+    upload: &Upload,
+    number: i64,
+    body: Vec<u8>,
+) -> Result<(), MultipartError> {
+    let result = client
+        .upload_part(UploadPartRequest {
+            bucket: upload.bucket.clone(),
+            key: upload.key.clone(),
+            upload_id: upload.upload_id.clone(),
+            part_number: number,
+            content_length: Some(body.len() as i64),
+            body: Some(body.into()),
+            ..Default::default()
+        })
+        .await
+        .context(UploadPart);
+
+    let mut state = upload.state.lock().unwrap();
+    state.in_flight -= 1;
+    let output = result?;
+    state.parts.push(CompletedPart {
+        e_tag: output.e_tag,
+        part_number: Some(number),
+    });
+    Ok(())
+}
+
+fn notify(waiters: Vec<oneshot::Sender<bool>>, written: bool) {
+    for waiter in waiters {
+        let _ = waiter.send(written);
+    }
+}
+
+fn close(uploads: &Uploads, prefix: &str, upload: &Arc<Upload>) {
+    let mut uploads = uploads.lock().unwrap();
+    if uploads
+        .get(prefix)
+        .map_or(false, |current| Arc::ptr_eq(current, upload))
+    {
+        uploads.remove(prefix);
+    }
+}
+
+/// Complete a closed upload once none of its parts are in flight anymore, or
+/// abort it if it has no data or can't be completed. The events of its last
+/// part are acked once it's completed.
+async fn finish(client: &S3Client, upload: &Upload) {
+    let last_part = {
+        let mut state = upload.state.lock().unwrap();
+        if !state.closed || state.in_flight > 0 || state.completing {
+            return;
+        }
+        state.completing = true;
+        state.take_part(0)
+    };
+
+    let mut waiters = Vec::new();
+    if let Some(part) = last_part {
+        match upload_part(client, upload, part.number, part.body).await {
+            Ok(()) => waiters = part.waiters,
+            Err(error) => {
+                warn!(
+                    message = "Failed to upload the last part, its events are sent again.",
+                    %error,
+                    key = %upload.key,
+                    rate_limit_secs = 10
+                );
+                notify(part.waiters, false);
+            }
+        }
+    }
+
+    let mut parts = mem::take(&mut upload.state.lock().unwrap().parts);
+    if parts.is_empty() {
+        abort(client, upload).await;
+        return;
+    }
+
+    parts.sort_by_key(|part| part.part_number);
+    match complete(client, upload, parts).await {
+        Ok(()) => {
+            debug!(message = "Completed multipart upload.", key = %upload.key);
+            notify(waiters, true);
+        }
+        Err(error) => {
+            error!(
+                message = "Failed to complete multipart upload, the events of its earlier parts are lost.",
+                %error,
+                key = %upload.key
+            );
+            abort(client, upload).await;
+            notify(waiters, false);
+        }
+    }
+}
+
+async fn complete(
+    client: &S3Client,
+    upload: &Upload,
+    parts: Vec<CompletedPart>,
+) -> Result<(), MultipartError> {
+    let mut backoff = Duration::from_secs(1);
+    let mut attempts = 1;
+    loop {
+        let request = CompleteMultipartUploadRequest {
+            bucket: upload.bucket.clone(),
+            key: upload.key.clone(),
+            upload_id: upload.upload_id.clone(),
+            multipart_upload: Some(CompletedMultipartUpload {
+                parts: Some(parts.clone()),
+            }),
+            ..Default::default()
+        };
+        match client
+            .complete_multipart_upload(request)
+            .await
+            .context(CompleteUpload)
+        {
+            Ok(_) => return Ok(()),
+            Err(error)
+                if attempts < COMPLETE_ATTEMPTS
+                    && MultipartRetryLogic.is_retriable_error(&error) =>
+            {
+                warn!(
+                    message = "Retrying to complete multipart upload.",
+                    %error,
+                    key = %upload.key,
+                    rate_limit_secs = 10
+                );
+                time::delay_for(backoff).await;
+                backoff *= 2;
+                attempts += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+async fn abort(client: &S3Client, upload: &Upload) {
+    let request = AbortMultipartUploadRequest {
+        bucket: upload.bucket.clone(),
+        key: upload.key.clone(),
+        upload_id: upload.upload_id.clone(),
+        ..Default::default()
+    };
+    if let Err(error) = client.abort_multipart_upload(request).await {
+        warn!(message = "Failed to abort multipart upload.", %error, key = %upload.key);
+    }
+}
+
+/// Complete the uploads older than `timeout`, and all of them once the input
+/// ended or the sink is gone.
+async fn complete_timed_out(
+    client: S3Client,
+    uploads: Arc<Uploads>,
+    closing: Arc<AtomicBool>,
+    alive: Weak<()>,
+    timeout: Duration,
+) {
+    let mut interval = time::interval(Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let shutdown = closing.load(Ordering::Relaxed) || alive.upgrade().is_none();
+
+        let timed_out = {
+            let mut uploads = uploads.lock().unwrap();
+            let prefixes = uploads
+                .iter()
+                .filter(|(_, upload)| shutdown || upload.started.elapsed() >= timeout)
+                .map(|(prefix, _)| prefix.clone())
+                .collect::<Vec<_>>();
+            prefixes
+                .into_iter()
+                .filter_map(|prefix| uploads.remove(&prefix))
+                .collect::<Vec<_>>()
+        };
+        for upload in timed_out {
+            upload.state.lock().unwrap().closed = true;
+            finish(&client, &upload).await;
+        }
+
+        if shutdown {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_parts() {
+        let mut state = UploadState {
+            next_part: 1,
+            ..Default::default()
+        };
+
+        state.pending.extend_from_slice(b"small");
+        assert!(state.take_part(10).is_none());
+
+        state.pending.extend_from_slice(b" and large");
+        let part = state.take_part(10).unwrap();
+        assert_eq!(part.number, 1);
+        assert_eq!(part.body, b"small and large");
+        assert!(state.pending.is_empty());
+        assert_eq!(state.in_flight, 1);
+
+        // Any data is a part once the upload is closed.
+        state.closed = true;
+        state.pending.extend_from_slice(b"last");
+        assert_eq!(state.take_part(10).unwrap().number, 2);
+        assert!(state.take_part(10).is_none());
+    }
+
+    #[test]
+    fn pushes_until_closed() {
+        let mut state = UploadState {
+            next_part: 1,
+            ..Default::default()
+        };
+
+        let (part, _written) = state.push(b"data", 8, false).unwrap();
+        assert!(part.is_none());
+        assert_eq!(state.waiters.len(), 1);
+
+        // Reaching the maximum closes the upload, taking all data as its part.
+        let (part, mut written) = state.push(b"more", 8, false).unwrap();
+        let part = part.unwrap();
+        assert!(state.closed);
+        assert_eq!(part.body, b"datamore");
+        assert_eq!(part.waiters.len(), 2);
+
+        notify(part.waiters, true);
+        assert_eq!(written.try_recv(), Ok(true));
+        assert!(state.push(b"late", 8, false).is_none());
+    }
+}
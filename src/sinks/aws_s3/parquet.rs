@@ -0,0 +1,430 @@
+//! Encoding of the batches as [Parquet](https://parquet.apache.org) files.
+//!
+//! The events are buffered as NDJSON like with the `ndjson` codec, so that
+//! the batches are sized the same way, and the lines of a batch are turned
+//! into the rows of a file once it's flushed. Nested fields become columns
+//! named after their path, such as `http.status`, and arrays are written as
+//! JSON strings.
+
+use chrono::DateTime;
+use indexmap::IndexMap;
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    errors::ParquetError,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, InMemoryWriteableCursor, RowGroupWriter, SerializedFileWriter},
+    },
+    schema::types::{Type, TypePtr},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetConfig {
+    #[serde(default)]
+    pub compression: ParquetCompression,
+    #[serde(default = "default_row_group_size")]
+    pub row_group_size: usize,
+    /// The columns of the files and their type, inferred from the events of
+    /// each batch if unset.
+    pub schema: Option<IndexMap<String, ColumnType>>,
+}
+
+impl Default for ParquetConfig {
+    fn default() -> Self {
+        Self {
+            compression: Default::default(),
+            row_group_size: default_row_group_size(),
+            schema: None,
+        }
+    }
+}
+
+fn default_row_group_size() -> usize {
+    10_000
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    None,
+    #[derivative(Default)]
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip => Compression::GZIP,
+            ParquetCompression::Zstd => Compression::ZSTD,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Boolean,
+    Int64,
+    Double,
+    String,
+    /// Milliseconds since the epoch, parsed from RFC 3339 strings.
+    Timestamp,
+}
+
+impl ColumnType {
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::Bool(_) => Some(Self::Boolean),
+            Value::Number(number) if number.is_i64() => Some(Self::Int64),
+            Value::Number(_) => Some(Self::Double),
+            Value::String(string) if DateTime::parse_from_rfc3339(string).is_ok() => {
+                Some(Self::Timestamp)
+            }
+            Value::String(_) | Value::Array(_) | Value::Object(_) => Some(Self::String),
+        }
+    }
+
+    /// The type of a column holding values of both types.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Int64, Self::Double) | (Self::Double, Self::Int64) => Self::Double,
+            _ => Self::String,
+        }
+    }
+}
+
+/// Writes the NDJSON batches as Parquet files.
+#[derive(Clone, Debug)]
+pub struct ParquetEncoder {
+    properties: Arc<WriterProperties>,
+    row_group_size: usize,
+    schema: Option<(Vec<(String, ColumnType)>, TypePtr)>,
+}
+
+impl ParquetEncoder {
+    pub fn new(config: &ParquetConfig) -> Result<Self, ParquetError> {
+        if config.row_group_size == 0 {
+            return Err(ParquetError::General(
+                "row_group_size must be greater than 0".into(),
+            ));
+        }
+        let properties = WriterProperties::builder()
+            .set_compression(config.compression.into())
+            .build();
+        let schema = config
+            .schema
+            .as_ref()
+            .map(|schema| {
+                let columns = schema
+                    .iter()
+                    .map(|(name, kind)| (name.clone(), *kind))
+                    .collect::<Vec<_>>();
+                build_schema(&columns).map(|schema| (columns, schema))
+            })
+            .transpose()?;
+        Ok(Self {
+            properties: Arc::new(properties),
+            row_group_size: config.row_group_size,
+            schema,
+        })
+    }
+
+    pub fn encode(&self, lines: &[u8]) -> Result<Vec<u8>, ParquetError> {
+        let rows = lines
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .filter_map(|line| serde_json::from_slice(line).ok())
+            .map(|row: Value| {
+                let mut fields = BTreeMap::new();
+                flatten(&mut fields, String::new(), row);
+                fields
+            })
+            .collect::<Vec<_>>();
+
+        let inferred;
+        let (columns, schema) = match &self.schema {
+            Some((columns, schema)) => (columns, schema),
+            None => {
+                let columns = infer_columns(&rows);
+                let schema = build_schema(&columns)?;
+                inferred = (columns, schema);
+                (&inferred.0, &inferred.1)
+            }
+        };
+
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer = SerializedFileWriter::new(
+            cursor.clone(),
+            Arc::clone(schema),
+            Arc::clone(&self.properties),
+        )?;
+        for rows in rows.chunks(self.row_group_size) {
+            let mut row_group = writer.next_row_group()?;
+            let mut columns = columns.iter();
+            while let Some(mut column) = row_group.next_column()? {
+                let (name, kind) = columns.next().expect("Columns follow the schema.");
+                let values = rows.iter().map(|row| row.get(name)).collect::<Vec<_>>();
+                write_column(&mut column, *kind, &values)?;
+                row_group.close_column(column)?;
+            }
+            writer.close_row_group(row_group)?;
+        }
+        writer.close()?;
+
+        Ok(cursor.data())
+    }
+}
+
+/// Inserts the leaves of `value` into `fields`, under their dotted path.
+fn flatten(fields: &mut BTreeMap<String, Value>, path: String, value: Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let path = if path.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten(fields, path, value);
+            }
+        }
+        // Parquet columns can't be unnamed.
+        _ if path.is_empty() => (),
+        value => {
+            fields.insert(path, value);
+        }
+    }
+}
+
+fn infer_columns(rows: &[BTreeMap<String, Value>]) -> Vec<(String, ColumnType)> {
+    let mut columns = BTreeMap::<&str, Option<ColumnType>>::new();
+    for row in rows {
+        for (name, value) in row {
+            let column = columns.entry(name.as_str()).or_insert(None);
+            *column = match (*column, ColumnType::of(value)) {
+                (Some(a), Some(b)) => Some(a.merge(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+    columns
+        .into_iter()
+        .map(|(name, kind)| (name.to_owned(), kind.unwrap_or(ColumnType::String)))
+        .collect()
+}
+
+fn build_schema(columns: &[(String, ColumnType)]) -> Result<TypePtr, ParquetError> {
+    let mut fields = columns
+        .iter()
+        .map(|(name, kind)| {
+            let (physical, logical) = match kind {
+                ColumnType::Boolean => (PhysicalType::BOOLEAN, LogicalType::NONE),
+                ColumnType::Int64 => (PhysicalType::INT64, LogicalType::NONE),
+                ColumnType::Double => (PhysicalType::DOUBLE, LogicalType::NONE),
+                ColumnType::String => (PhysicalType::BYTE_ARRAY, LogicalType::UTF8),
+                ColumnType::Timestamp => (PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS),
+            };
+            Type::primitive_type_builder(name, physical)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = Type::group_type_builder("vector")
+        .with_fields(&mut fields)
+        .build()?;
+    Ok(Arc::new(schema))
+}
+
+/// Writes the values of a column, the ones missing or not matching its type
+/// as nulls.
+fn write_column(
+    writer: &mut ColumnWriter,
+    kind: ColumnType,
+    values: &[Option<&Value>],
+) -> Result<(), ParquetError> {
+    let mut definition_levels = Vec::with_capacity(values.len());
+    match (writer, kind) {
+        (ColumnWriter::BoolColumnWriter(writer), ColumnType::Boolean) => {
+            let values = present(values, &mut definition_levels, Value::as_bool);
+            writer.write_batch(&values, Some(&definition_levels), None)?;
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ColumnType::Int64) => {
+            let values = present(values, &mut definition_levels, Value::as_i64);
+            writer.write_batch(&values, Some(&definition_levels), None)?;
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ColumnType::Timestamp) => {
+            let values = present(values, &mut definition_levels, |value| match value {
+                Value::String(string) => DateTime::parse_from_rfc3339(string)
+                    .ok()
+                    .map(|timestamp| timestamp.timestamp_millis()),
+                value => value.as_i64(),
+            });
+            writer.write_batch(&values, Some(&definition_levels), None)?;
+        }
+        (ColumnWriter::DoubleColumnWriter(writer), ColumnType::Double) => {
+            let values = present(values, &mut definition_levels, Value::as_f64);
+            writer.write_batch(&values, Some(&definition_levels), None)?;
+        }
+        (ColumnWriter::ByteArrayColumnWriter(writer), ColumnType::String) => {
+            let values = present(values, &mut definition_levels, |value| match value {
+                Value::Null => None,
+                Value::String(string) => Some(ByteArray::from(string.as_str())),
+                value => Some(ByteArray::from(value.to_string().into_bytes())),
+            });
+            writer.write_batch(&values, Some(&definition_levels), None)?;
+        }
+        _ => unreachable!("Column writers follow the schema."),
+    }
+    Ok(())
+}
+
+/// The values converted to the type of the column, along with their
+/// definition levels.
+fn present<T>(
+    values: &[Option<&Value>],
+    definition_levels: &mut Vec<i16>,
+    convert: impl Fn(&Value) -> Option<T>,
+) -> Vec<T> {
+    values
+        .iter()
+        .filter_map(|value| {
+            let value = value.and_then(&convert);
+            definition_levels.push(if value.is_some() { 1 } else { 0 });
+            value
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::Field,
+        util::cursor::SliceableCursor,
+    };
+
+    const LINES: &[u8] = br#"{"message":"first","status":200,"timestamp":"2020-11-24T12:00:00.250Z","http":{"method":"GET"}}
+{"message":"second","status":"unknown","duration":1.5,"timestamp":"2020-11-24T12:00:01Z"}
+{"message":"third","status":404,"tags":["a","b"],"timestamp":"2020-11-24T12:00:02Z","duration":2}
+"#;
+
+    fn read(
+        file: Vec<u8>,
+    ) -> (
+        SerializedFileReader<SliceableCursor>,
+        Vec<BTreeMap<String, Field>>,
+    ) {
+        let reader = SerializedFileReader::new(SliceableCursor::new(file)).unwrap();
+        let rows = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                row.get_column_iter()
+                    .map(|(name, field)| (name.clone(), field.clone()))
+                    .collect()
+            })
+            .collect();
+        (reader, rows)
+    }
+
+    #[test]
+    fn infers_schema() {
+        let encoder = ParquetEncoder::new(&ParquetConfig::default()).unwrap();
+        let (reader, rows) = read(encoder.encode(LINES).unwrap());
+
+        let columns = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| (column.name().to_owned(), column.physical_type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("duration".to_owned(), PhysicalType::DOUBLE),
+                ("http.method".to_owned(), PhysicalType::BYTE_ARRAY),
+                ("message".to_owned(), PhysicalType::BYTE_ARRAY),
+                ("status".to_owned(), PhysicalType::BYTE_ARRAY),
+                ("tags".to_owned(), PhysicalType::BYTE_ARRAY),
+                ("timestamp".to_owned(), PhysicalType::INT64),
+            ]
+        );
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["duration"], Field::Null);
+        assert_eq!(rows[0]["http.method"], Field::Str("GET".into()));
+        assert_eq!(rows[0]["status"], Field::Str("200".into()));
+        assert_eq!(
+            rows[0]["timestamp"],
+            Field::TimestampMillis(1_606_219_200_250)
+        );
+        assert_eq!(rows[1]["duration"], Field::Double(1.5));
+        assert_eq!(rows[1]["status"], Field::Str("unknown".into()));
+        assert_eq!(rows[2]["duration"], Field::Double(2.0));
+        assert_eq!(rows[2]["tags"], Field::Str(r#"["a","b"]"#.into()));
+    }
+
+    #[test]
+    fn follows_explicit_schema() {
+        let config: ParquetConfig = toml::from_str(
+            r#"
+            compression = "zstd"
+            row_group_size = 2
+            schema.timestamp = "timestamp"
+            schema.status = "int64"
+            schema.message = "string"
+            "#,
+        )
+        .unwrap();
+        let encoder = ParquetEncoder::new(&config).unwrap();
+        let (reader, rows) = read(encoder.encode(LINES).unwrap());
+
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+        assert_eq!(
+            reader.metadata().row_group(0).column(0).compression(),
+            Compression::ZSTD
+        );
+        let columns = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| column.name().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec!["timestamp", "status", "message"]);
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["status"], Field::Long(200));
+        assert_eq!(rows[1]["status"], Field::Null);
+        assert_eq!(rows[2]["status"], Field::Long(404));
+        assert_eq!(rows[2]["message"], Field::Str("third".into()));
+    }
+
+    #[test]
+    fn rejects_empty_row_groups() {
+        let config = ParquetConfig {
+            row_group_size: 0,
+            ..Default::default()
+        };
+        assert!(ParquetEncoder::new(&config).is_err());
+    }
+}
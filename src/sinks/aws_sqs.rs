@@ -1,22 +1,23 @@
 use crate::{
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    internal_events::{AwsSqsEventSent, AwsSqsMessageGroupIdMissingKeys},
+    internal_events::{AwsSqsEventSent, AwsSqsMissingKeys},
     rusoto,
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
-        retries::RetryLogic,
+        retries::{RetryAction, RetryLogic},
         sink::Response,
-        BatchSettings, EncodedLength, TowerRequestConfig, VecBuffer,
+        BatchConfig, BatchSettings, EncodedLength, TowerRequestConfig, VecBuffer,
     },
     template::{Template, TemplateError},
     Event,
 };
-use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt, TryFutureExt};
+use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt};
 use lazy_static::lazy_static;
 use rusoto_core::RusotoError;
 use rusoto_sqs::{
-    GetQueueAttributesError, GetQueueAttributesRequest, SendMessageError, SendMessageRequest,
-    SendMessageResult, Sqs, SqsClient,
+    BatchResultErrorEntry, GetQueueAttributesError, GetQueueAttributesRequest,
+    SendMessageBatchError, SendMessageBatchRequest, SendMessageBatchRequestEntry,
+    SendMessageBatchResult, Sqs, SqsClient,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -24,6 +25,7 @@ use std::{
     convert::{TryFrom, TryInto},
     task::{Context, Poll},
 };
+use tokio::time::{delay_for, Duration};
 use tower::Service;
 use tracing_futures::Instrument;
 
@@ -33,10 +35,22 @@ enum BuildError {
     MessageGroupIdMissing,
     #[snafu(display("`message_group_id` is not allowed with non-FIFO queue."))]
     MessageGroupIdNotAllowed,
+    #[snafu(display("`message_deduplication_id` is not allowed with non-FIFO queue."))]
+    MessageDeduplicationIdNotAllowed,
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("invalid message deduplication id template: {}", source))]
+    MessageDeduplicationIdTemplate { source: TemplateError },
 }
 
+/// The most messages a `SendMessageBatch` request may contain.
+const MAX_BATCH_EVENTS: usize = 10;
+/// The most bytes of messages a `SendMessageBatch` request may contain.
+const MAX_BATCH_BYTES: usize = 262_144;
+/// How often the messages of a batch that failed, but not for a fault of the
+/// sender, are sent again on their own, after 1, 2, 4... seconds.
+const FAILED_ENTRY_ATTEMPTS: u32 = 3;
+
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
     #[snafu(display("GetQueueAttributes failed: {}", source))]
@@ -59,6 +73,9 @@ pub struct SqsSinkConfig {
     pub region: rusoto::RegionOrEndpoint,
     pub encoding: EncodingConfig<Encoding>,
     pub message_group_id: Option<String>,
+    pub message_deduplication_id: Option<String>,
+    #[serde(default)]
+    pub batch: BatchConfig,
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub assume_role: Option<String>,
@@ -144,12 +161,17 @@ impl SqsSink {
         cx: SinkContext,
         client: SqsClient,
     ) -> crate::Result<impl Sink<Event, Error = ()>> {
-        // Currently we do not use batching, so this mostly for future. Also implement `Service` is simpler than `Sink`.
         // https://docs.aws.amazon.com/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-batch-api-actions.html
         // Up to 10 events, not more than 256KB as total size.
-        let batch = BatchSettings::default().events(1).bytes(262_144);
-
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let mut batch = BatchSettings::default()
+            .events(MAX_BATCH_EVENTS)
+            .bytes(MAX_BATCH_BYTES as u64)
+            .timeout(1)
+            .parse_config(config.batch)?;
+        batch.size.events = batch.size.events.min(MAX_BATCH_EVENTS);
+        batch.size.bytes = batch.size.bytes.min(MAX_BATCH_BYTES);
+
+        let mut request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding;
         let fifo = config.queue_url.ends_with(".fifo");
         let message_group_id = match (config.message_group_id, fifo) {
@@ -158,6 +180,20 @@ impl SqsSink {
             (None, true) => return Err(Box::new(BuildError::MessageGroupIdMissing)),
             (None, false) => None,
         };
+        let message_deduplication_id = match (config.message_deduplication_id, fifo) {
+            (Some(value), true) => {
+                Some(Template::try_from(value).context(MessageDeduplicationIdTemplate)?)
+            }
+            (Some(_), false) => return Err(Box::new(BuildError::MessageDeduplicationIdNotAllowed)),
+            (None, _) => None,
+        };
+        if fifo {
+            // Sending one batch at a time keeps the messages of each group in
+            // the order they were received.
+            request.concurrency = Some(1);
+        }
+        // Requests include sending their failed messages again.
+        request.timeout += Duration::from_secs((1 << FAILED_ENTRY_ATTEMPTS) - 1);
 
         let sqs = SqsSink {
             client,
@@ -174,7 +210,13 @@ impl SqsSink {
             )
             .sink_map_err(|error| error!(message = "Fatal sqs sink error.", %error))
             .with_flat_map(move |event| {
-                stream::iter(encode_event(event, &encoding, message_group_id.as_ref())).map(Ok)
+                stream::iter(encode_event(
+                    event,
+                    &encoding,
+                    message_group_id.as_ref(),
+                    message_deduplication_id.as_ref(),
+                ))
+                .map(Ok)
             });
 
         Ok(sink)
@@ -182,39 +224,107 @@ impl SqsSink {
 }
 
 impl Service<Vec<SendMessageEntry>> for SqsSink {
-    type Response = SendMessageResult;
-    type Error = RusotoError<SendMessageError>;
+    type Response = SendMessageBatchResult;
+    type Error = RusotoError<SendMessageBatchError>;
     type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, mut entries: Vec<SendMessageEntry>) -> Self::Future {
-        assert_eq!(entries.len(), 1, "Sending batch is not supported.");
+    fn call(&mut self, entries: Vec<SendMessageEntry>) -> Self::Future {
+        debug!(
+            message = "Sending messages.",
+            messages = %entries.len(),
+        );
 
-        let entry = entries.remove(0);
-        let byte_size = entry.message_body.len();
+        let byte_sizes: Vec<usize> = entries
+            .iter()
+            .map(|entry| entry.message_body.len())
+            .collect();
 
         let client = self.client.clone();
-        let request = SendMessageRequest {
-            message_body: entry.message_body,
-            message_group_id: entry.message_group_id,
-            queue_url: self.queue_url.clone(),
-            ..Default::default()
-        };
+        let queue_url = self.queue_url.clone();
+        let mut entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(id, entry)| SendMessageBatchRequestEntry {
+                id: id.to_string(),
+                message_body: entry.message_body,
+                message_group_id: entry.message_group_id,
+                message_deduplication_id: entry.message_deduplication_id,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
 
         Box::pin(async move {
-            client
-                .send_message(request)
-                .inspect_ok(|result| {
+            // Only the failed messages are sent again, so that the messages
+            // sent successfully aren't duplicated.
+            let mut sent = Vec::new();
+            let mut rejected = Vec::new();
+            let mut attempt = 0;
+            loop {
+                let request = SendMessageBatchRequest {
+                    entries: entries.clone(),
+                    queue_url: queue_url.clone(),
+                };
+                let result = match client
+                    .send_message_batch(request)
+                    .instrument(info_span!("request"))
+                    .await
+                {
+                    Ok(result) => result,
+                    // Nothing was sent yet, so the whole batch can be retried.
+                    Err(error) if sent.is_empty() && rejected.is_empty() => return Err(error),
+                    Err(error) => SendMessageBatchResult {
+                        failed: entries
+                            .iter()
+                            .map(|entry| BatchResultErrorEntry {
+                                code: "RequestFailed".into(),
+                                id: entry.id.clone(),
+                                message: Some(error.to_string()),
+                                sender_fault: false,
+                            })
+                            .collect(),
+                        ..Default::default()
+                    },
+                };
+
+                for entry in &result.successful {
+                    let byte_size = entry
+                        .id
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|id| byte_sizes.get(id).copied())
+                        .unwrap_or_default();
                     emit!(AwsSqsEventSent {
                         byte_size,
-                        message_id: result.message_id.as_ref()
-                    })
-                })
-                .instrument(info_span!("request"))
-                .await
+                        message_id: Some(&entry.message_id)
+                    });
+                }
+                sent.extend(result.successful);
+
+                let (permanent, transient): (Vec<_>, Vec<_>) = result
+                    .failed
+                    .into_iter()
+                    .partition(|failed| failed.sender_fault);
+                rejected.extend(permanent);
+                if transient.is_empty() || attempt == FAILED_ENTRY_ATTEMPTS {
+                    rejected.extend(transient);
+                    return Ok(SendMessageBatchResult {
+                        successful: sent,
+                        failed: rejected,
+                    });
+                }
+
+                entries.retain(|entry| transient.iter().any(|failed| failed.id == entry.id));
+                debug!(
+                    message = "Sending failed messages again.",
+                    messages = %entries.len(),
+                );
+                delay_for(Duration::from_secs(1 << attempt)).await;
+                attempt += 1;
+            }
         })
     }
 }
@@ -223,6 +333,7 @@ impl Service<Vec<SendMessageEntry>> for SqsSink {
 struct SendMessageEntry {
     message_body: String,
     message_group_id: Option<String>,
+    message_deduplication_id: Option<String>,
 }
 
 impl EncodedLength for SendMessageEntry {
@@ -231,39 +342,66 @@ impl EncodedLength for SendMessageEntry {
     }
 }
 
-impl Response for SendMessageResult {}
+impl Response for SendMessageBatchResult {}
 
 #[derive(Debug, Clone)]
 struct SqsRetryLogic;
 
 impl RetryLogic for SqsRetryLogic {
-    type Error = RusotoError<SendMessageError>;
-    type Response = SendMessageResult;
+    type Error = RusotoError<SendMessageBatchError>;
+    type Response = SendMessageBatchResult;
 
     fn is_retriable_error(&self, error: &Self::Error) -> bool {
         rusoto::is_retriable_error(error)
     }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        // The failed messages were already sent again on their own, and the
+        // whole batch is only sent again if none of it was sent, so that no
+        // message is duplicated.
+        let reasons = response
+            .failed
+            .iter()
+            .map(|failed| match &failed.message {
+                Some(message) => format!("{}: {}", failed.code, message),
+                None => failed.code.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if response.failed.is_empty() {
+            RetryAction::Successful
+        } else if response.successful.is_empty()
+            && response.failed.iter().all(|failed| !failed.sender_fault)
+        {
+            RetryAction::Retry(reasons)
+        } else {
+            RetryAction::DontRetry(reasons)
+        }
+    }
 }
 
 fn encode_event(
     mut event: Event,
     encoding: &EncodingConfig<Encoding>,
     message_group_id: Option<&Template>,
+    message_deduplication_id: Option<&Template>,
 ) -> Option<SendMessageEntry> {
     encoding.apply_rules(&mut event);
 
-    let message_group_id = match message_group_id {
+    let render = |template: Option<&Template>| match template {
         Some(tpl) => match tpl.render_string(&event) {
-            Ok(value) => Some(value),
+            Ok(value) => Ok(Some(value)),
             Err(missing_keys) => {
-                emit!(AwsSqsMessageGroupIdMissingKeys {
+                emit!(AwsSqsMissingKeys {
                     keys: &missing_keys
                 });
-                return None;
+                Err(())
             }
         },
-        None => None,
+        None => Ok(None),
     };
+    let message_group_id = render(message_group_id).ok()?;
+    let message_deduplication_id = render(message_deduplication_id).ok()?;
 
     let log = event.into_log();
     let message_body = match encoding.codec() {
@@ -277,18 +415,21 @@ fn encode_event(
     Some(SendMessageEntry {
         message_body,
         message_group_id,
+        message_deduplication_id,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rusoto_sqs::SendMessageBatchResultEntry;
     use std::collections::BTreeMap;
 
     #[test]
     fn sqs_encode_event_text() {
         let message = "hello world".to_string();
-        let event = encode_event(message.clone().into(), &Encoding::Text.into(), None).unwrap();
+        let event =
+            encode_event(message.clone().into(), &Encoding::Text.into(), None, None).unwrap();
 
         assert_eq!(&event.message_body, &message);
     }
@@ -298,13 +439,79 @@ mod tests {
         let message = "hello world".to_string();
         let mut event = Event::from(message.clone());
         event.as_mut_log().insert("key", "value");
-        let event = encode_event(event, &Encoding::Json.into(), None).unwrap();
+        let event = encode_event(event, &Encoding::Json.into(), None, None).unwrap();
 
         let map: BTreeMap<String, String> = serde_json::from_str(&event.message_body).unwrap();
 
         assert_eq!(map[&log_schema().message_key().to_string()], message);
         assert_eq!(map["key"], "value".to_string());
     }
+
+    #[test]
+    fn sqs_encode_event_fifo() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("group", "vector");
+        event.as_mut_log().insert("id", "42");
+        let message_group_id = Template::try_from("{{ group }}").unwrap();
+        let message_deduplication_id = Template::try_from("{{ id }}").unwrap();
+
+        let entry = encode_event(
+            event.clone(),
+            &Encoding::Text.into(),
+            Some(&message_group_id),
+            Some(&message_deduplication_id),
+        )
+        .unwrap();
+        assert_eq!(entry.message_group_id.as_deref(), Some("vector"));
+        assert_eq!(entry.message_deduplication_id.as_deref(), Some("42"));
+
+        event.as_mut_log().remove("id");
+        assert!(encode_event(
+            event,
+            &Encoding::Text.into(),
+            Some(&message_group_id),
+            Some(&message_deduplication_id),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn sqs_retries_failed_entries() {
+        let failed = |sender_fault| BatchResultErrorEntry {
+            code: "InternalError".into(),
+            id: "0".into(),
+            message: None,
+            sender_fault,
+        };
+        let logic = SqsRetryLogic;
+
+        let response = SendMessageBatchResult::default();
+        assert!(matches!(
+            logic.should_retry_response(&response),
+            RetryAction::Successful
+        ));
+
+        let response = SendMessageBatchResult {
+            failed: vec![failed(false)],
+            ..Default::default()
+        };
+        assert!(logic.should_retry_response(&response).is_retryable());
+
+        let response = SendMessageBatchResult {
+            failed: vec![failed(false), failed(true)],
+            ..Default::default()
+        };
+        assert!(!logic.should_retry_response(&response).is_retryable());
+
+        let response = SendMessageBatchResult {
+            failed: vec![failed(false)],
+            successful: vec![SendMessageBatchResultEntry {
+                id: "1".into(),
+                ..Default::default()
+            }],
+        };
+        assert!(!logic.should_retry_response(&response).is_retryable());
+    }
 }
 
 #[cfg(feature = "aws-sqs-integration-tests")]
@@ -337,6 +544,8 @@ mod integration_tests {
             region: rusoto::RegionOrEndpoint::with_endpoint("http://localhost:4566".into()),
             encoding: Encoding::Text.into(),
             message_group_id: None,
+            message_deduplication_id: None,
+            batch: Default::default(),
             request: Default::default(),
             assume_role: None,
         };
@@ -0,0 +1,1121 @@
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    http::{HttpClient, HttpError},
+    serde::to_string,
+    sinks::{
+        util::{
+            encoding::{EncodingConfig, EncodingConfiguration},
+            retries::{RetryAction, RetryLogic},
+            BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
+            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        },
+        Healthcheck, HealthcheckError, VectorSink,
+    },
+    template::{Template, TemplateError},
+    tls::{TlsOptions, TlsSettings},
+    Event,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{
+    future::{AbortHandle, Abortable, BoxFuture},
+    stream, FutureExt, SinkExt, StreamExt,
+};
+use http::{
+    header::{self, InvalidHeaderValue},
+    uri::InvalidUri,
+    Method, StatusCode, Uri,
+};
+use hyper::{header::HeaderValue, Body, Request, Response};
+use lazy_static::lazy_static;
+use openssl::{base64, hash, pkey, sign};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    sync::{Arc, RwLock, Weak},
+    task::Poll,
+    time::Duration,
+};
+use tower::{Service, ServiceBuilder};
+use uuid::Uuid;
+
+const NAME: &str = "azure_blob";
+/// Storage service version of the requests.
+const API_VERSION: &str = "2019-12-12";
+/// Largest block appended to an append blob in a single request.
+const MAX_APPEND_BLOCK_BYTES: u64 = 4 * 1024 * 1024;
+const MANAGED_IDENTITY_TOKEN_URL: &str = "http://169.254.169.254/metadata/identity/oauth2/token?api-version=2018-02-01&resource=https%3A%2F%2Fstorage.azure.com%2F";
+/// Account and key of the storage emulator, used with `UseDevelopmentStorage=true`.
+const DEVELOPMENT_ACCOUNT: &str = "devstoreaccount1";
+const DEVELOPMENT_KEY: &str =
+    "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+const DEVELOPMENT_ENDPOINT: &str = "http://127.0.0.1:10000/devstoreaccount1";
+
+/// The characters of blob names escaped in request paths, keeping the `/`
+/// separating their virtual directories.
+const BLOB_NAME: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AzureBlobSinkConfig {
+    connection_string: Option<String>,
+    storage_account: Option<String>,
+    endpoint: Option<String>,
+    container_name: String,
+    blob_prefix: Option<String>,
+    blob_time_format: Option<String>,
+    blob_append_uuid: Option<bool>,
+    #[serde(default)]
+    blob_type: BlobType,
+    encoding: EncodingConfig<Encoding>,
+    #[serde(default = "Compression::gzip_default")]
+    compression: Compression,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsOptions>,
+}
+
+#[cfg(test)]
+fn default_config(e: Encoding) -> AzureBlobSinkConfig {
+    AzureBlobSinkConfig {
+        connection_string: Default::default(),
+        storage_account: Default::default(),
+        endpoint: Default::default(),
+        container_name: Default::default(),
+        blob_prefix: Default::default(),
+        blob_time_format: Default::default(),
+        blob_append_uuid: Default::default(),
+        blob_type: Default::default(),
+        encoding: e.into(),
+        compression: Compression::gzip_default(),
+        batch: Default::default(),
+        request: Default::default(),
+        tls: Default::default(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum BlobType {
+    #[derivative(Default)]
+    Block,
+    Append,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(25),
+        rate_limit_num: Some(1000),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Encoding {
+    Text,
+    Ndjson,
+}
+
+impl Encoding {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Text => "text/plain",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<AzureBlobSinkConfig>(NAME)
+}
+
+impl GenerateConfig for AzureBlobSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"connection_string = "DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"
+            container_name = "logs"
+            encoding.codec = "ndjson""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_blob")]
+impl SinkConfig for AzureBlobSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let sink = AzureBlobSink::new(self).await?;
+        let healthcheck = sink.clone().healthcheck().boxed();
+        let service = sink.service(self, &cx)?;
+
+        Ok((service, healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        NAME
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum AzureBlobError {
+    #[snafu(display("One of connection_string or storage_account must be set"))]
+    MissingAuth,
+    #[snafu(display("Only one of connection_string or storage_account can be set"))]
+    ConflictingAuth,
+    #[snafu(display("Invalid connection string setting: {:?}", setting))]
+    InvalidConnectionString { setting: String },
+    #[snafu(display(
+        "The connection string holds neither an account key nor a shared access signature"
+    ))]
+    MissingConnectionStringCredentials,
+    #[snafu(display("The connection string names no account"))]
+    MissingAccountName,
+    #[snafu(display("Invalid account key: {}", source))]
+    InvalidAccountKey { source: openssl::error::ErrorStack },
+    #[snafu(display(
+        "Append blobs can't be appended more than {} bytes per request, batch.max_bytes is {}",
+        MAX_APPEND_BLOCK_BYTES,
+        max_bytes
+    ))]
+    AppendBatchTooLarge { max_bytes: usize },
+    #[snafu(display("container_name template parse error: {}", source))]
+    ContainerNameTemplate { source: TemplateError },
+    #[snafu(display("blob_prefix template parse error: {}", source))]
+    BlobPrefixTemplate { source: TemplateError },
+    #[snafu(display("Failed to get managed identity token: {}", source))]
+    GetToken { source: HttpError },
+    #[snafu(display("Failed to get managed identity token, response status: {}", status))]
+    GetTokenStatus { status: StatusCode },
+    #[snafu(display("Failed to read managed identity token: {}", source))]
+    GetTokenBytes { source: hyper::Error },
+    #[snafu(display("Failed to parse managed identity token: {}", source))]
+    ParseToken { source: serde_json::Error },
+    #[snafu(display("Invalid credentials"))]
+    InvalidCredentials,
+    #[snafu(display("Container {:?} not found", container))]
+    ContainerNotFound { container: String },
+    #[snafu(display("Invalid container name: {:?}", name))]
+    InvalidContainerName { name: String },
+    #[snafu(display("Invalid endpoint: {}", source))]
+    InvalidEndpoint { source: InvalidUri },
+    #[snafu(display("Invalid managed identity token: {}", source))]
+    InvalidToken { source: InvalidHeaderValue },
+    #[snafu(display("Invalid request URI: {}", source))]
+    InvalidRequestUri { source: InvalidUri },
+    #[snafu(display("Invalid request header: {}", source))]
+    InvalidRequestHeader { source: InvalidHeaderValue },
+    #[snafu(display("Failed to sign request: {}", source))]
+    SignRequest { source: openssl::error::ErrorStack },
+    #[snafu(display("Failed to send request: {}", source))]
+    SendRequest { source: HttpError },
+}
+
+/// The settings of a storage connection string, such as
+/// `DefaultEndpointsProtocol=https;AccountName=...;AccountKey=...`.
+#[derive(Debug, Default, PartialEq)]
+struct ConnectionString {
+    account_name: Option<String>,
+    account_key: Option<String>,
+    shared_access_signature: Option<String>,
+    blob_endpoint: Option<String>,
+    endpoint_suffix: Option<String>,
+    protocol: Option<String>,
+}
+
+impl ConnectionString {
+    fn parse(connection_string: &str) -> Result<Self, AzureBlobError> {
+        let mut parsed = Self::default();
+        for setting in connection_string
+            .split(';')
+            .filter(|s| !s.trim().is_empty())
+        {
+            let mut parts = setting.splitn(2, '=');
+            let name = parts.next().unwrap_or_default().trim();
+            let value = parts.next().map(|value| value.trim().to_owned());
+            let value = value.ok_or_else(|| AzureBlobError::InvalidConnectionString {
+                setting: name.to_owned(),
+            })?;
+            match name {
+                "AccountName" => parsed.account_name = Some(value),
+                "AccountKey" => parsed.account_key = Some(value),
+                "SharedAccessSignature" => parsed.shared_access_signature = Some(value),
+                "BlobEndpoint" => parsed.blob_endpoint = Some(value),
+                "EndpointSuffix" => parsed.endpoint_suffix = Some(value),
+                "DefaultEndpointsProtocol" => parsed.protocol = Some(value),
+                "UseDevelopmentStorage" if value == "true" => {
+                    parsed.account_name = Some(DEVELOPMENT_ACCOUNT.to_owned());
+                    parsed.account_key = Some(DEVELOPMENT_KEY.to_owned());
+                    parsed.blob_endpoint = Some(DEVELOPMENT_ENDPOINT.to_owned());
+                }
+                // The settings of the other storage services.
+                "QueueEndpoint" | "TableEndpoint" | "FileEndpoint" => (),
+                _ => return InvalidConnectionString { setting: name }.fail(),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn blob_endpoint(&self) -> Result<String, AzureBlobError> {
+        match (&self.blob_endpoint, &self.account_name) {
+            (Some(endpoint), _) => Ok(endpoint.clone()),
+            (None, Some(account)) => Ok(format!(
+                "{}://{}.blob.{}",
+                self.protocol.as_deref().unwrap_or("https"),
+                account,
+                self.endpoint_suffix
+                    .as_deref()
+                    .unwrap_or("core.windows.net")
+            )),
+            (None, None) => Err(AzureBlobError::MissingAccountName),
+        }
+    }
+
+    fn credentials(&self) -> Result<Credentials, AzureBlobError> {
+        match (&self.account_key, &self.shared_access_signature) {
+            (Some(key), _) => {
+                let account = self
+                    .account_name
+                    .clone()
+                    .ok_or(AzureBlobError::MissingAccountName)?;
+                let key = base64::decode_block(key).context(InvalidAccountKey)?;
+                let key = pkey::PKey::hmac(&key).context(InvalidAccountKey)?;
+                Ok(Credentials::SharedKey { account, key })
+            }
+            (None, Some(signature)) => Ok(Credentials::SharedAccessSignature(
+                signature.trim_start_matches('?').to_owned(),
+            )),
+            (None, None) => Err(AzureBlobError::MissingConnectionStringCredentials),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Credentials {
+    SharedKey {
+        account: String,
+        key: pkey::PKey<pkey::Private>,
+    },
+    SharedAccessSignature(String),
+    ManagedIdentity(Arc<ManagedIdentity>),
+}
+
+/// The authorization of a managed identity, renewed in the background until
+/// the sink is gone.
+struct ManagedIdentity {
+    authorization: RwLock<HeaderValue>,
+    renewal: AbortHandle,
+}
+
+impl Drop for ManagedIdentity {
+    fn drop(&mut self) {
+        self.renewal.abort();
+    }
+}
+
+#[derive(Deserialize)]
+struct Token {
+    access_token: String,
+    expires_in: String,
+}
+
+async fn get_managed_identity_token(client: &HttpClient) -> Result<Token, AzureBlobError> {
+    let request = Request::get(MANAGED_IDENTITY_TOKEN_URL)
+        .header("Metadata", "true")
+        .body(Body::empty())
+        .unwrap();
+    let response = client.send(request).await.context(GetToken)?;
+    let status = response.status();
+    if !status.is_success() {
+        return GetTokenStatus { status }.fail();
+    }
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .context(GetTokenBytes)?;
+    serde_json::from_slice(&body).context(ParseToken)
+}
+
+/// The authorization header of requests made with `token`.
+fn bearer(token: &Token) -> Result<HeaderValue, AzureBlobError> {
+    HeaderValue::from_str(&format!("Bearer {}", token.access_token)).context(InvalidToken)
+}
+
+impl Credentials {
+    async fn managed_identity(client: &HttpClient) -> crate::Result<Self> {
+        let token = get_managed_identity_token(client).await?;
+        let period = (token.expires_in.parse::<u64>().unwrap_or(3600) / 2).max(1);
+        let (renewal, registration) = AbortHandle::new_pair();
+        let identity = Arc::new(ManagedIdentity {
+            authorization: RwLock::new(bearer(&token)?),
+            renewal,
+        });
+
+        // The token is renewed well before it expires, as requests can't
+        // wait on it.
+        let renewed = Arc::downgrade(&identity);
+        let client = client.clone();
+        let task = tokio::time::interval_at(
+            tokio::time::Instant::now() + Duration::from_secs(period),
+            Duration::from_secs(period),
+        )
+        .for_each(move |_| {
+            let renewed = Weak::clone(&renewed);
+            let client = client.clone();
+            async move {
+                debug!("Renewing Azure managed identity token.");
+                let result = get_managed_identity_token(&client)
+                    .await
+                    .and_then(|token| bearer(&token));
+                match result {
+                    Ok(authorization) => {
+                        if let Some(identity) = renewed.upgrade() {
+                            *identity.authorization.write().unwrap() = authorization;
+                        }
+                    }
+                    Err(error) => error!(
+                        message = "Failed to renew Azure managed identity token.",
+                        %error
+                    ),
+                }
+            }
+        });
+        tokio::spawn(Abortable::new(task, registration));
+
+        Ok(Self::ManagedIdentity(identity))
+    }
+
+    /// The query appended to the URIs of the requests.
+    fn query(&self) -> Option<&str> {
+        match self {
+            Self::SharedAccessSignature(signature) => Some(signature),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, request: &mut Request<Body>) -> Result<(), AzureBlobError> {
+        let authorization = match self {
+            Self::SharedKey { account, key } => {
+                let mut signer =
+                    sign::Signer::new(hash::MessageDigest::sha256(), key).context(SignRequest)?;
+                signer
+                    .update(string_to_sign(account, request).as_bytes())
+                    .context(SignRequest)?;
+                let signature = base64::encode_block(&signer.sign_to_vec().context(SignRequest)?);
+                HeaderValue::from_str(&format!("SharedKey {}:{}", account, signature))
+                    .context(InvalidRequestHeader)?
+            }
+            Self::SharedAccessSignature(_) => return Ok(()),
+            Self::ManagedIdentity(identity) => identity.authorization.read().unwrap().clone(),
+        };
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, authorization);
+        Ok(())
+    }
+}
+
+/// The string signed with the account key to authorize `request`, see
+/// https://docs.microsoft.com/en-us/rest/api/storageservices/authorize-with-shared-key.
+fn string_to_sign(account: &str, request: &Request<Body>) -> String {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    };
+    let content_length = match header("content-length") {
+        "0" => "",
+        length => length,
+    };
+
+    let mut ms_headers = request
+        .headers()
+        .iter()
+        .filter(|(name, _)| name.as_str().starts_with("x-ms-"))
+        .map(|(name, value)| format!("{}:{}\n", name, value.to_str().unwrap_or_default().trim()))
+        .collect::<Vec<_>>();
+    ms_headers.sort();
+
+    let mut resource = format!("/{}{}", account, request.uri().path());
+    let mut query = request
+        .uri()
+        .query()
+        .map(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    query.sort();
+    for (name, value) in query {
+        resource.push_str(&format!("\n{}:{}", name.to_lowercase(), value));
+    }
+
+    format!(
+        "{}\n{}\n\n{}\n\n{}\n\n\n\n{}\n\n\n{}{}",
+        request.method(),
+        header("content-encoding"),
+        content_length,
+        header("content-type"),
+        header("if-none-match"),
+        ms_headers.concat(),
+        resource
+    )
+}
+
+#[derive(Clone)]
+struct AzureBlobSink {
+    client: HttpClient,
+    endpoint: String,
+    container_name: Template,
+    credentials: Credentials,
+    settings: RequestSettings,
+}
+
+impl AzureBlobSink {
+    async fn new(config: &AzureBlobSinkConfig) -> crate::Result<Self> {
+        let tls = TlsSettings::from_options(&config.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let (endpoint, credentials) = match (&config.connection_string, &config.storage_account) {
+            (Some(connection_string), None) => {
+                let connection_string = ConnectionString::parse(connection_string)?;
+                (
+                    connection_string.blob_endpoint()?,
+                    connection_string.credentials()?,
+                )
+            }
+            (None, Some(account)) => (
+                format!("https://{}.blob.core.windows.net", account),
+                Credentials::managed_identity(&client).await?,
+            ),
+            (None, None) => return Err(AzureBlobError::MissingAuth.into()),
+            (Some(_), Some(_)) => return Err(AzureBlobError::ConflictingAuth.into()),
+        };
+        let endpoint = config
+            .endpoint
+            .clone()
+            .unwrap_or(endpoint)
+            .trim_end_matches('/')
+            .to_owned();
+        format!("{}/", endpoint)
+            .parse::<Uri>()
+            .context(InvalidEndpoint)?;
+
+        let container_name =
+            Template::try_from(config.container_name.as_str()).context(ContainerNameTemplate)?;
+        if !container_name.is_dynamic() && !valid_container_name(container_name.get_ref()) {
+            return Err(AzureBlobError::InvalidContainerName {
+                name: config.container_name.clone(),
+            }
+            .into());
+        }
+        let settings = RequestSettings::new(config)?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            container_name,
+            credentials,
+            settings,
+        })
+    }
+
+    fn service(self, config: &AzureBlobSinkConfig, cx: &SinkContext) -> crate::Result<VectorSink> {
+        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let ordered = request.ordered;
+        let encoding = config.encoding.clone();
+
+        let max_bytes = match config.blob_type {
+            BlobType::Block => bytesize::mib(10u64),
+            BlobType::Append => MAX_APPEND_BLOCK_BYTES,
+        };
+        let batch = BatchSettings::default()
+            .bytes(max_bytes)
+            .timeout(300)
+            .parse_config(config.batch)?;
+        if config.blob_type == BlobType::Append && batch.size.bytes as u64 > MAX_APPEND_BLOCK_BYTES
+        {
+            return Err(AzureBlobError::AppendBatchTooLarge {
+                max_bytes: batch.size.bytes,
+            }
+            .into());
+        }
+
+        let blob_prefix = config.blob_prefix.as_deref().unwrap_or("blob/%F/");
+        let blob_prefix = Template::try_from(blob_prefix).context(BlobPrefixTemplate)?;
+        let container_name = self.container_name.clone();
+
+        let settings = self.settings.clone();
+
+        let svc = ServiceBuilder::new()
+            .map(move |req| RequestWrapper::new(req, &settings))
+            .settings(request, AzureBlobRetryLogic)
+            .service(self);
+
+        let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
+
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .ordered(ordered)
+            .sink_map_err(|error| error!(message = "Fatal azure_blob error.", %error))
+            .with_flat_map(move |e| {
+                stream::iter(encode_event(e, &container_name, &blob_prefix, &encoding)).map(Ok)
+            });
+
+        Ok(VectorSink::Sink(Box::new(sink)))
+    }
+
+    /// Checks the properties of the container, or lists the containers of the
+    /// account if the container name is templated.
+    async fn healthcheck(self) -> crate::Result<()> {
+        let (method, uri, container) = if self.container_name.is_dynamic() {
+            let uri = self.uri("", None, &["comp=list", "maxresults=1"])?;
+            (Method::GET, uri, None)
+        } else {
+            let container = self.container_name.get_ref().to_owned();
+            let uri = self.uri(&container, None, &["restype=container"])?;
+            (Method::HEAD, uri, Some(container))
+        };
+        let request = self.request(method, uri, Vec::new(), Bytes::new())?;
+
+        let response = self.client.send(request).await?;
+        match (response.status(), container) {
+            (StatusCode::OK, _) => Ok(()),
+            (StatusCode::FORBIDDEN, _) => Err(AzureBlobError::InvalidCredentials.into()),
+            (StatusCode::NOT_FOUND, Some(container)) => {
+                Err(AzureBlobError::ContainerNotFound { container }.into())
+            }
+            (status, _) => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+        }
+    }
+
+    /// The URI of a container or blob. Container names are validated, while
+    /// blob names are escaped.
+    fn uri(
+        &self,
+        container: &str,
+        blob: Option<&str>,
+        query: &[&str],
+    ) -> Result<Uri, AzureBlobError> {
+        let mut uri = format!("{}/{}", self.endpoint, container);
+        if let Some(blob) = blob {
+            uri.push('/');
+            uri.extend(utf8_percent_encode(blob, BLOB_NAME));
+        }
+        let query = query
+            .iter()
+            .copied()
+            .chain(self.credentials.query())
+            .collect::<Vec<_>>();
+        if !query.is_empty() {
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+        uri.parse().context(InvalidRequestUri)
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        uri: Uri,
+        headers: Vec<(&'static str, HeaderValue)>,
+        body: Bytes,
+    ) -> Result<Request<Body>, AzureBlobError> {
+        let date =
+            HeaderValue::from_str(&Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+                .context(InvalidRequestHeader)?;
+        let content_length = HeaderValue::from(body.len());
+
+        let mut request = Request::new(Body::from(body));
+        *request.method_mut() = method;
+        *request.uri_mut() = uri;
+        let request_headers = request.headers_mut();
+        request_headers.insert("x-ms-date", date);
+        request_headers.insert("x-ms-version", HeaderValue::from_static(API_VERSION));
+        request_headers.insert(header::CONTENT_LENGTH, content_length);
+        for (name, value) in headers {
+            request_headers.insert(name, value);
+        }
+
+        self.credentials.apply(&mut request)?;
+        Ok(request)
+    }
+
+    async fn send(&self, request: Request<Body>) -> Result<Response<Body>, AzureBlobError> {
+        self.client.send(request).await.context(SendRequest)
+    }
+
+    /// Uploads the batch as a block blob.
+    fn put_block_blob(&self, request: &RequestWrapper) -> Result<Request<Body>, AzureBlobError> {
+        let mut headers = self.content_headers();
+        headers.push(("x-ms-blob-type", HeaderValue::from_static("BlockBlob")));
+        let uri = self.uri(&request.container, Some(&request.blob), &[])?;
+        self.request(Method::PUT, uri, headers, request.body.clone())
+    }
+
+    /// Creates an empty append blob, unless it already exists.
+    fn create_append_blob(
+        &self,
+        request: &RequestWrapper,
+    ) -> Result<Request<Body>, AzureBlobError> {
+        let mut headers = self.content_headers();
+        headers.push(("x-ms-blob-type", HeaderValue::from_static("AppendBlob")));
+        headers.push(("if-none-match", HeaderValue::from_static("*")));
+        let uri = self.uri(&request.container, Some(&request.blob), &[])?;
+        self.request(Method::PUT, uri, headers, Bytes::new())
+    }
+
+    /// Appends the batch to an append blob as a block.
+    fn append_block(&self, request: &RequestWrapper) -> Result<Request<Body>, AzureBlobError> {
+        let uri = self.uri(
+            &request.container,
+            Some(&request.blob),
+            &["comp=appendblock"],
+        )?;
+        self.request(Method::PUT, uri, Vec::new(), request.body.clone())
+    }
+
+    fn content_headers(&self) -> Vec<(&'static str, HeaderValue)> {
+        let mut headers = vec![("content-type", self.settings.content_type.clone())];
+        if let Some(content_encoding) = &self.settings.content_encoding {
+            headers.push(("content-encoding", content_encoding.clone()));
+        }
+        headers
+    }
+}
+
+impl Service<RequestWrapper> for AzureBlobSink {
+    type Response = Response<Body>;
+    type Error = AzureBlobError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestWrapper) -> Self::Future {
+        let sink = self.clone();
+        Box::pin(async move {
+            match sink.settings.blob_type {
+                BlobType::Block => sink.send(sink.put_block_blob(&request)?).await,
+                BlobType::Append => {
+                    let response = sink.send(sink.append_block(&request)?).await?;
+                    if response.status() != StatusCode::NOT_FOUND {
+                        return Ok(response);
+                    }
+
+                    // The blob is created by the first batch appended to it,
+                    // or concurrently by another request, with a conflict.
+                    let response = sink.send(sink.create_append_blob(&request)?).await?;
+                    let status = response.status();
+                    if !status.is_success() && status != StatusCode::CONFLICT {
+                        return Ok(response);
+                    }
+                    sink.send(sink.append_block(&request)?).await
+                }
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct PartitionKey {
+    container: String,
+    prefix: String,
+}
+
+#[derive(Clone, Debug)]
+struct RequestWrapper {
+    body: Bytes,
+    container: String,
+    blob: String,
+}
+
+impl RequestWrapper {
+    fn new(req: PartitionInnerBuffer<Vec<u8>, PartitionKey>, settings: &RequestSettings) -> Self {
+        let (body, key) = req.into_parts();
+
+        let filename = {
+            let time = Utc::now().format(&settings.time_format);
+
+            if settings.append_uuid {
+                let uuid = Uuid::new_v4();
+                format!("{}-{}", time, uuid.to_hyphenated())
+            } else {
+                time.to_string()
+            }
+        };
+
+        let blob = format!("{}{}.{}", key.prefix, filename, settings.extension);
+
+        debug!(message = "Sending events.", bytes = ?body.len(), container = ?key.container, blob = ?blob);
+
+        Self {
+            body: body.into(),
+            container: key.container,
+            blob,
+        }
+    }
+}
+
+// Settings required to produce a request that do not change per
+// request. All possible values are pre-computed for direct use in
+// producing a request.
+#[derive(Clone, Debug)]
+struct RequestSettings {
+    blob_type: BlobType,
+    content_type: HeaderValue,
+    content_encoding: Option<HeaderValue>,
+    extension: String,
+    time_format: String,
+    append_uuid: bool,
+}
+
+impl RequestSettings {
+    fn new(config: &AzureBlobSinkConfig) -> crate::Result<Self> {
+        let content_type = HeaderValue::from_str(config.encoding.codec().content_type()).unwrap();
+        let content_encoding = config
+            .compression
+            .content_encoding()
+            .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
+        let extension = config.compression.extension().into();
+        // Batches are appended to the blob of the day by default, rather
+        // than uploaded to blobs of their own.
+        let (time_format, append_uuid) = match config.blob_type {
+            BlobType::Block => ("%s", true),
+            BlobType::Append => ("%F", false),
+        };
+        let time_format = config
+            .blob_time_format
+            .clone()
+            .unwrap_or_else(|| time_format.into());
+        let append_uuid = config.blob_append_uuid.unwrap_or(append_uuid);
+        Ok(Self {
+            blob_type: config.blob_type,
+            content_type,
+            content_encoding,
+            extension,
+            time_format,
+            append_uuid,
+        })
+    }
+}
+
+fn encode_event(
+    mut event: Event,
+    container_name: &Template,
+    blob_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, PartitionKey>> {
+    let key = container_name
+        .render_string(&event)
+        .and_then(|container| {
+            blob_prefix
+                .render_string(&event)
+                .map(|prefix| PartitionKey { container, prefix })
+        })
+        .map_err(|missing_keys| {
+            warn!(
+                message = "Keys do not exist on the event; dropping event.",
+                ?missing_keys,
+                rate_limit_secs = 30,
+            );
+        })
+        .ok()?;
+    if !valid_container_name(&key.container) {
+        warn!(
+            message = "Invalid container name; dropping event.",
+            container = %key.container,
+            rate_limit_secs = 30,
+        );
+        return None;
+    }
+    encoding.apply_rules(&mut event);
+    let log = event.into_log();
+    let bytes = match encoding.codec() {
+        Encoding::Ndjson => serde_json::to_vec(&log)
+            .map(|mut b| {
+                b.push(b'\n');
+                b
+            })
+            .expect("Failed to encode event as json, this is a bug!"),
+        Encoding::Text => {
+            let mut bytes = log
+                .get(crate::config::log_schema().message_key())
+                .map(|v| v.as_bytes().to_vec())
+                .unwrap_or_default();
+            bytes.push(b'\n');
+            bytes
+        }
+    };
+
+    Some(PartitionInnerBuffer::new(bytes, key))
+}
+
+/// Whether `name` is a valid container name, see
+/// https://docs.microsoft.com/en-us/rest/api/storageservices/naming-and-referencing-containers--blobs--and-metadata.
+fn valid_container_name(name: &str) -> bool {
+    matches!(name, "$root" | "$web" | "$logs")
+        || ((3..=63).contains(&name.len())
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+            && !name.starts_with('-')
+            && !name.ends_with('-')
+            && !name.contains("--"))
+}
+
+#[derive(Clone)]
+struct AzureBlobRetryLogic;
+
+impl RetryLogic for AzureBlobRetryLogic {
+    type Error = AzureBlobError;
+    type Response = Response<Body>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        matches!(error, AzureBlobError::SendRequest { .. })
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        let status = response.status();
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            StatusCode::NOT_IMPLEMENTED => {
+                RetryAction::DontRetry("endpoint not implemented".into())
+            }
+            _ if status.is_server_error() => RetryAction::Retry(format!("{}", status)),
+            _ if status.is_success() => RetryAction::Successful,
+            _ => RetryAction::DontRetry(format!("response status: {}", status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::next_addr,
+    };
+    use bytes::Buf;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AzureBlobSinkConfig>();
+    }
+
+    #[test]
+    fn parses_connection_strings() {
+        let parsed = ConnectionString::parse(
+            "DefaultEndpointsProtocol=https;AccountName=logs;AccountKey=a2V5;EndpointSuffix=core.chinacloudapi.cn",
+        )
+        .unwrap();
+        assert_eq!(parsed.account_name.as_deref(), Some("logs"));
+        assert_eq!(parsed.account_key.as_deref(), Some("a2V5"));
+        assert_eq!(
+            parsed.blob_endpoint().unwrap(),
+            "https://logs.blob.core.chinacloudapi.cn"
+        );
+        assert!(matches!(
+            parsed.credentials().unwrap(),
+            Credentials::SharedKey { account, .. } if account == "logs"
+        ));
+
+        let parsed = ConnectionString::parse(
+            "BlobEndpoint=https://logs.blob.core.windows.net/;SharedAccessSignature=sv=2019-12-12&sig=abc",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.blob_endpoint().unwrap(),
+            "https://logs.blob.core.windows.net/"
+        );
+        assert!(matches!(
+            parsed.credentials().unwrap(),
+            Credentials::SharedAccessSignature(signature) if signature == "sv=2019-12-12&sig=abc"
+        ));
+
+        let parsed = ConnectionString::parse("UseDevelopmentStorage=true").unwrap();
+        assert_eq!(parsed.blob_endpoint().unwrap(), DEVELOPMENT_ENDPOINT);
+
+        assert!(ConnectionString::parse("AccountName").is_err());
+        assert!(ConnectionString::parse("Unknown=value").is_err());
+        assert!(ConnectionString::parse("AccountName=logs")
+            .unwrap()
+            .credentials()
+            .is_err());
+    }
+
+    #[test]
+    fn builds_string_to_sign() {
+        let request = Request::put(
+            "https://logs.blob.core.windows.net/container/blob/2020-11-24.log.gz?comp=appendblock",
+        )
+        .header("content-length", "11")
+        .header("content-encoding", "gzip")
+        .header("x-ms-version", API_VERSION)
+        .header("x-ms-date", "Tue, 24 Nov 2020 12:00:00 GMT")
+        .body(Body::empty())
+        .unwrap();
+
+        assert_eq!(
+            string_to_sign("logs", &request),
+            concat!(
+                "PUT\ngzip\n\n11\n\n\n\n\n\n\n\n\n",
+                "x-ms-date:Tue, 24 Nov 2020 12:00:00 GMT\n",
+                "x-ms-version:2019-12-12\n",
+                "/logs/container/blob/2020-11-24.log.gz\n",
+                "comp:appendblock"
+            )
+        );
+    }
+
+    #[test]
+    fn azure_blob_encode_event_partitions() {
+        crate::test_util::trace_init();
+
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "api");
+
+        let container_name = Template::try_from("logs-{{ app }}").unwrap();
+        let blob_prefix = Template::try_from("date=%F/").unwrap();
+        let encoded = encode_event(
+            event.clone(),
+            &container_name,
+            &blob_prefix,
+            &Encoding::Text.into(),
+        )
+        .unwrap();
+
+        let (bytes, key) = encoded.into_parts();
+        assert_eq!(&bytes[..], b"hello world\n");
+        assert_eq!(key.container, "logs-api");
+        assert!(key.prefix.starts_with("date="));
+
+        let container_name = Template::try_from("logs-{{ missing }}").unwrap();
+        let encoded = encode_event(
+            event.clone(),
+            &container_name,
+            &blob_prefix,
+            &Encoding::Text.into(),
+        );
+        assert!(encoded.is_none());
+
+        let container_name = Template::try_from("Logs_{{ app }}").unwrap();
+        let encoded = encode_event(event, &container_name, &blob_prefix, &Encoding::Text.into());
+        assert!(encoded.is_none());
+    }
+
+    #[test]
+    fn validates_container_names() {
+        assert!(valid_container_name("logs-2020"));
+        assert!(valid_container_name("$root"));
+        assert!(!valid_container_name("lo"));
+        assert!(!valid_container_name("Logs"));
+        assert!(!valid_container_name("logs/api"));
+        assert!(!valid_container_name("-logs"));
+        assert!(!valid_container_name("logs--api"));
+    }
+
+    #[test]
+    fn azure_blob_build_blob_names() {
+        let key = PartitionKey {
+            container: "logs".into(),
+            prefix: "blob/".into(),
+        };
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], key);
+        let settings = |blob_type, uuid| {
+            RequestSettings::new(&AzureBlobSinkConfig {
+                blob_time_format: Some("date".into()),
+                blob_append_uuid: uuid,
+                blob_type,
+                ..default_config(Encoding::Ndjson)
+            })
+            .unwrap()
+        };
+
+        let req = RequestWrapper::new(buf.clone(), &settings(BlobType::Append, None));
+        assert_eq!(req.container, "logs");
+        assert_eq!(req.blob, "blob/date.log.gz");
+
+        let req = RequestWrapper::new(buf, &settings(BlobType::Block, None));
+        assert_ne!(req.blob, "blob/date.log.gz");
+        assert!(req.blob.starts_with("blob/date-"));
+    }
+
+    #[tokio::test]
+    async fn azure_blob_uploads_blobs() {
+        let in_addr = next_addr();
+        let (config, cx) = load_sink::<AzureBlobSinkConfig>(&format!(
+            r#"
+            connection_string = "AccountName=logs;AccountKey=a2V5"
+            endpoint = "http://{}"
+            container_name = "logs-{{{{ app }}}}"
+            blob_prefix = "blob/"
+            blob_type = "append"
+            encoding.codec = "ndjson"
+            batch.max_events = 10
+            batch.timeout_secs = 1
+            "#,
+            in_addr
+        ))
+        .unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        let events = (0..4).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            event
+                .as_mut_log()
+                .insert("app", if i % 2 == 0 { "api" } else { "web" });
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let mut requests = rx
+            .map(|(parts, body)| {
+                assert_eq!(parts.method, Method::PUT);
+                assert_eq!(parts.uri.query(), Some("comp=appendblock"));
+                assert_eq!(parts.headers["x-ms-version"], API_VERSION);
+                assert!(parts.headers["authorization"]
+                    .to_str()
+                    .unwrap()
+                    .starts_with("SharedKey logs:"));
+
+                let mut lines = String::new();
+                GzDecoder::new(body.reader())
+                    .read_to_string(&mut lines)
+                    .unwrap();
+                (parts.uri.path().to_owned(), lines)
+            })
+            .collect::<Vec<_>>()
+            .await;
+        requests.sort();
+
+        assert_eq!(requests.len(), 2);
+        let today = Utc::now().format("%F");
+        assert_eq!(requests[0].0, format!("/logs-api/blob/{}.log.gz", today));
+        assert_eq!(requests[0].1.lines().count(), 2);
+        assert!(requests[0].1.contains("line 2"));
+        assert_eq!(requests[1].0, format!("/logs-web/blob/{}.log.gz", today));
+    }
+}
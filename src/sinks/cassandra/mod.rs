@@ -0,0 +1,927 @@
+mod protocol;
+mod ring;
+
+use self::{
+    protocol::{BoundValue, Column, ColumnType, Connection, Consistency, CqlError, Prepared},
+    ring::{murmur3_token, routing_key, Ring},
+};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    dns::Resolver,
+    event::{Event, Value},
+    internal_events::{CassandraEventSent, CassandraInvalidValue},
+    sinks::util::{
+        retries::RetryLogic, BatchConfig, BatchSettings, EncodedLength, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, VecBuffer,
+    },
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsConfig},
+};
+use chrono::{NaiveDate, Timelike};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{net::TcpStream, sync::Mutex};
+use tower::Service;
+
+/// The name of the bind marker of the TTL of the rows.
+const TTL_MARKER: &str = "[ttl]";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one endpoint must be set"))]
+    MissingEndpoints,
+    #[snafu(display("Invalid endpoint {:?}, expected host:port", endpoint))]
+    InvalidEndpoint { endpoint: String },
+    #[snafu(display("At least one column must be mapped"))]
+    MissingColumns,
+    #[snafu(display("Failed to connect to {}: {}", endpoint, source))]
+    Connect { endpoint: String, source: CqlError },
+    #[snafu(display("Failed to read the token ring: {}", source))]
+    ReadRing { source: CqlError },
+    #[snafu(display("Column {:?} has the unsupported type {:?}", column, column_type))]
+    UnsupportedColumnType {
+        column: String,
+        column_type: ColumnType,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CassandraSinkConfig {
+    pub endpoints: Vec<String>,
+    pub keyspace: String,
+    pub table: String,
+    /// The fields of the events written to each column.
+    pub columns: BTreeMap<String, String>,
+    pub ttl_secs: Option<u32>,
+    pub ttl_field: Option<String>,
+    #[serde(default)]
+    pub consistency: Consistency,
+    #[serde(default = "crate::serde::default_true")]
+    pub token_aware: bool,
+    pub auth: Option<CassandraAuth>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CassandraAuth {
+    pub username: String,
+    pub password: String,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        timeout_secs: Some(30),
+        rate_limit_num: Some(1000),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<CassandraSinkConfig>("cassandra")
+}
+
+impl GenerateConfig for CassandraSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoints = ["127.0.0.1:9042"]
+            keyspace = "logs"
+            table = "events"
+            columns.host = "host"
+            columns.message = "message"
+            columns.timestamp = "timestamp""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "cassandra")]
+impl SinkConfig for CassandraSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let connector = Connector::new(self)?;
+        let (contact, mut session) = connector.connect_any(&self.endpoints).await?;
+        let encoder = Encoder::new(self, &session.prepared)?;
+
+        let mut ring = Ring::default();
+        if self.token_aware {
+            for table in &["system.local", "system.peers"] {
+                let query = format!("SELECT rpc_address, tokens FROM {}", table);
+                let rows = session
+                    .connection
+                    .query(&query, Consistency::One)
+                    .await
+                    .context(ReadRing)?;
+                ring.add_nodes(&rows, contact.addr);
+            }
+        }
+
+        let service = CassandraService::new(connector, contact, session, &ring, self.consistency);
+        let healthcheck = service.clone().healthcheck().boxed();
+        let sink = service.into_sink(self, encoder.with_ring(ring), cx)?;
+
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "cassandra"
+    }
+}
+
+/// Quotes an identifier, keeping its case.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+impl CassandraSinkConfig {
+    fn insert_statement(&self) -> String {
+        let columns = self.columns.keys().map(|column| quote(column));
+        let mut statement = format!(
+            "INSERT INTO {}.{} ({}) VALUES ({})",
+            quote(&self.keyspace),
+            quote(&self.table),
+            columns.collect::<Vec<_>>().join(", "),
+            vec!["?"; self.columns.len()].join(", ")
+        );
+        if self.ttl_secs.is_some() || self.ttl_field.is_some() {
+            statement.push_str(" USING TTL ?");
+        }
+        statement
+    }
+}
+
+fn parse_endpoint(endpoint: &str) -> Result<(String, u16), BuildError> {
+    let mut parts = endpoint.rsplitn(2, ':');
+    let port = parts.next().and_then(|port| port.parse().ok());
+    let host = parts
+        .next()
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']'));
+    match (host, port) {
+        (Some(host), Some(port)) if !host.is_empty() => Ok((host.to_owned(), port)),
+        _ => Err(BuildError::InvalidEndpoint {
+            endpoint: endpoint.to_owned(),
+        }),
+    }
+}
+
+/// A connection to a node, with the insert statement prepared on it.
+struct Session {
+    connection: Connection<MaybeTlsStream<TcpStream>>,
+    prepared: Prepared,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    host: String,
+    addr: SocketAddr,
+}
+
+#[derive(Clone)]
+struct Connector {
+    tls: MaybeTlsSettings,
+    auth: Option<CassandraAuth>,
+    insert: String,
+}
+
+impl Connector {
+    fn new(config: &CassandraSinkConfig) -> crate::Result<Self> {
+        if config.columns.is_empty() {
+            return Err(BuildError::MissingColumns.into());
+        }
+        Ok(Self {
+            tls: MaybeTlsSettings::from_config(&config.tls, false)?,
+            auth: config.auth.clone(),
+            insert: config.insert_statement(),
+        })
+    }
+
+    async fn connect(&self, node: &Node) -> Result<Session, CqlError> {
+        let stream = self
+            .tls
+            .connect(&node.host, &node.addr)
+            .await
+            .map_err(|source| CqlError::Connect { source })?;
+        let auth = self
+            .auth
+            .as_ref()
+            .map(|auth| (auth.username.as_str(), auth.password.as_str()));
+        let mut connection = Connection::startup(stream, auth).await?;
+        let prepared = connection.prepare(&self.insert).await?;
+        Ok(Session {
+            connection,
+            prepared,
+        })
+    }
+
+    /// Connects to the first endpoint reachable.
+    async fn connect_any(&self, endpoints: &[String]) -> crate::Result<(Node, Session)> {
+        let mut last_error = None;
+        for endpoint in endpoints {
+            let (host, port) = parse_endpoint(endpoint)?;
+            let ips = match Resolver.lookup_ip(host.clone()).await {
+                Ok(ips) => ips,
+                Err(error) => {
+                    warn!(message = "Failed to resolve endpoint.", %endpoint, %error);
+                    continue;
+                }
+            };
+            for ip in ips {
+                let node = Node {
+                    host: host.clone(),
+                    addr: SocketAddr::new(ip, port),
+                };
+                match self.connect(&node).await {
+                    Ok(session) => return Ok((node, session)),
+                    Err(source) => {
+                        warn!(message = "Failed to connect to endpoint.", %endpoint, error = %source);
+                        last_error = Some(BuildError::Connect {
+                            endpoint: endpoint.clone(),
+                            source,
+                        });
+                    }
+                }
+            }
+        }
+        Err(last_error.unwrap_or(BuildError::MissingEndpoints).into())
+    }
+}
+
+/// Encodes the events into the values of the statement, routed to the node
+/// owning their partition.
+struct Encoder {
+    fields: Vec<String>,
+    columns: Vec<Column>,
+    partition_key: Vec<usize>,
+    ttl_secs: Option<u32>,
+    ttl_field: Option<String>,
+    ring: Ring,
+    contact: Option<SocketAddr>,
+}
+
+impl Encoder {
+    fn new(config: &CassandraSinkConfig, prepared: &Prepared) -> crate::Result<Self> {
+        for column in &prepared.columns {
+            if !is_supported(&column.column_type) {
+                return Err(BuildError::UnsupportedColumnType {
+                    column: column.name.clone(),
+                    column_type: column.column_type.clone(),
+                }
+                .into());
+            }
+        }
+        Ok(Self {
+            fields: config.columns.values().cloned().collect(),
+            columns: prepared.columns.clone(),
+            partition_key: prepared.partition_key.clone(),
+            ttl_secs: config.ttl_secs,
+            ttl_field: config.ttl_field.clone(),
+            ring: Ring::default(),
+            contact: None,
+        })
+    }
+
+    fn with_ring(self, ring: Ring) -> Self {
+        Self { ring, ..self }
+    }
+
+    fn encode(&self, event: Event) -> Option<PartitionInnerBuffer<Row, Option<SocketAddr>>> {
+        let log = event.into_log();
+
+        let mut values = Vec::with_capacity(self.columns.len());
+        for (column, field) in self.columns.iter().zip(&self.fields) {
+            let value = match log.get(field) {
+                // Missing fields leave the columns unset, rather than writing
+                // tombstones.
+                None => BoundValue::Unset,
+                Some(Value::Null) => BoundValue::Null,
+                Some(value) => match serialize(value, &column.column_type) {
+                    Ok(value) => BoundValue::Value(value.into()),
+                    Err(error) => {
+                        emit!(CassandraInvalidValue {
+                            column: &column.name,
+                            error,
+                        });
+                        return None;
+                    }
+                },
+            };
+            values.push(value);
+        }
+        if self.columns.len() > self.fields.len() {
+            let ttl = match self.ttl_field.as_ref().and_then(|field| log.get(field)) {
+                Some(ttl) => Some(serialize(ttl, &ColumnType::Int).ok().or_else(|| {
+                    emit!(CassandraInvalidValue {
+                        column: TTL_MARKER,
+                        error: "TTL is not an integer".into(),
+                    });
+                    None
+                })?),
+                None => self.ttl_secs.map(|ttl| (ttl as i32).to_be_bytes().to_vec()),
+            };
+            values.push(ttl.map_or(BoundValue::Unset, |ttl| BoundValue::Value(ttl.into())));
+        }
+
+        let key = self
+            .partition_key
+            .iter()
+            .filter_map(|index| values.get(*index))
+            .collect::<Vec<_>>();
+        let key = match routing_key(&key) {
+            Some(key) => key,
+            None => {
+                emit!(CassandraInvalidValue {
+                    column: "partition key",
+                    error: "Missing partition key value".into(),
+                });
+                return None;
+            }
+        };
+        let node = self.ring.owner(murmur3_token(&key)).or(self.contact);
+
+        Some(PartitionInnerBuffer::new(Row { values }, node))
+    }
+}
+
+fn is_supported(column_type: &ColumnType) -> bool {
+    match column_type {
+        ColumnType::Decimal | ColumnType::Other(_) => false,
+        ColumnType::List(element) | ColumnType::Set(element) => is_supported(element),
+        ColumnType::Map(key, value) => is_supported(key) && is_supported(value),
+        _ => true,
+    }
+}
+
+fn integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(integer) => Some(*integer),
+        Value::Float(float) if float.fract() == 0.0 => Some(*float as i64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(*float),
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Map(_) | Value::Array(_) => serde_json::to_string(value).unwrap_or_default(),
+        value => value.to_string_lossy(),
+    }
+}
+
+/// The shortest two's complement representation of the integer.
+fn varint(integer: i64) -> Vec<u8> {
+    let mut bytes = integer.to_be_bytes().to_vec();
+    while bytes.len() > 1
+        && ((bytes[0] == 0x00 && bytes[1] & 0x80 == 0)
+            || (bytes[0] == 0xff && bytes[1] & 0x80 != 0))
+    {
+        bytes.remove(0);
+    }
+    bytes
+}
+
+fn put_element(buf: &mut Vec<u8>, value: &Value, column_type: &ColumnType) -> Result<(), String> {
+    let element = serialize(value, column_type)?;
+    buf.extend_from_slice(&(element.len() as i32).to_be_bytes());
+    buf.extend_from_slice(&element);
+    Ok(())
+}
+
+/// Serializes the value of an event as the type of a column, converting the
+/// strings of the events to the numbers, timestamps, UUIDs and addresses
+/// they hold.
+fn serialize(value: &Value, column_type: &ColumnType) -> Result<Vec<u8>, String> {
+    let invalid = || {
+        format!(
+            "Can't write {:?} to a {:?} column",
+            text(value),
+            column_type
+        )
+    };
+    let as_integer = || integer(value).ok_or_else(invalid);
+    let as_float = || float(value).ok_or_else(invalid);
+
+    Ok(match column_type {
+        ColumnType::Ascii | ColumnType::Text => text(value).into_bytes(),
+        ColumnType::Blob => value.as_bytes().to_vec(),
+        ColumnType::Boolean => match value {
+            Value::Boolean(boolean) => vec![*boolean as u8],
+            Value::Bytes(bytes) if &bytes[..] == b"true" => vec![1],
+            Value::Bytes(bytes) if &bytes[..] == b"false" => vec![0],
+            _ => return Err(invalid()),
+        },
+        ColumnType::Bigint | ColumnType::Counter => as_integer()?.to_be_bytes().to_vec(),
+        ColumnType::Int => i32::try_from(as_integer()?)
+            .map_err(|_| invalid())?
+            .to_be_bytes()
+            .to_vec(),
+        ColumnType::Smallint => i16::try_from(as_integer()?)
+            .map_err(|_| invalid())?
+            .to_be_bytes()
+            .to_vec(),
+        ColumnType::Tinyint => i8::try_from(as_integer()?)
+            .map_err(|_| invalid())?
+            .to_be_bytes()
+            .to_vec(),
+        ColumnType::Varint => varint(as_integer()?),
+        ColumnType::Float => (as_float()? as f32).to_be_bytes().to_vec(),
+        ColumnType::Double => as_float()?.to_be_bytes().to_vec(),
+        ColumnType::Timestamp => match value {
+            Value::Timestamp(timestamp) => timestamp.timestamp_millis(),
+            Value::Integer(millis) => *millis,
+            value => chrono::DateTime::parse_from_rfc3339(&text(value))
+                .map_err(|_| invalid())?
+                .timestamp_millis(),
+        }
+        .to_be_bytes()
+        .to_vec(),
+        ColumnType::Date => {
+            let date = match value {
+                Value::Timestamp(timestamp) => timestamp.naive_utc().date(),
+                value => {
+                    NaiveDate::parse_from_str(&text(value), "%Y-%m-%d").map_err(|_| invalid())?
+                }
+            };
+            let days = date
+                .signed_duration_since(NaiveDate::from_ymd(1970, 1, 1))
+                .num_days();
+            // Dates are counted from 2^31 days before the epoch.
+            ((days + (1 << 31)) as u32).to_be_bytes().to_vec()
+        }
+        ColumnType::Time => match value {
+            Value::Timestamp(timestamp) => {
+                let time = timestamp.time();
+                time.num_seconds_from_midnight() as i64 * 1_000_000_000 + time.nanosecond() as i64
+            }
+            _ => as_integer()?,
+        }
+        .to_be_bytes()
+        .to_vec(),
+        ColumnType::Uuid | ColumnType::Timeuuid => uuid::Uuid::parse_str(&text(value))
+            .map_err(|_| invalid())?
+            .as_bytes()
+            .to_vec(),
+        ColumnType::Inet => match text(value).parse::<IpAddr>().map_err(|_| invalid())? {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        },
+        ColumnType::List(element) | ColumnType::Set(element) => match value {
+            Value::Array(values) => {
+                let mut buf = (values.len() as i32).to_be_bytes().to_vec();
+                for value in values {
+                    put_element(&mut buf, value, element)?;
+                }
+                buf
+            }
+            _ => return Err(invalid()),
+        },
+        ColumnType::Map(key_type, value_type) => match value {
+            Value::Map(map) => {
+                let mut buf = (map.len() as i32).to_be_bytes().to_vec();
+                for (key, value) in map {
+                    put_element(&mut buf, &Value::from(key.as_str()), key_type)?;
+                    put_element(&mut buf, value, value_type)?;
+                }
+                buf
+            }
+            _ => return Err(invalid()),
+        },
+        ColumnType::Decimal | ColumnType::Other(_) => return Err(invalid()),
+    })
+}
+
+/// The values of a statement of a batch.
+#[derive(Clone, Debug, PartialEq)]
+struct Row {
+    values: Vec<BoundValue>,
+}
+
+impl EncodedLength for Row {
+    fn encoded_length(&self) -> usize {
+        self.values.iter().map(|value| value.size() + 4).sum()
+    }
+}
+
+/// The node and its session, connected when first used and after errors.
+struct NodeSession {
+    node: Node,
+    session: Mutex<Option<Session>>,
+}
+
+#[derive(Clone)]
+struct CassandraService {
+    connector: Connector,
+    contact: Arc<NodeSession>,
+    nodes: Arc<HashMap<SocketAddr, Arc<NodeSession>>>,
+    consistency: Consistency,
+}
+
+impl CassandraService {
+    fn new(
+        connector: Connector,
+        contact: Node,
+        session: Session,
+        ring: &Ring,
+        consistency: Consistency,
+    ) -> Self {
+        let contact = Arc::new(NodeSession {
+            node: contact,
+            session: Mutex::new(Some(session)),
+        });
+        let nodes = ring
+            .nodes()
+            .into_iter()
+            .map(|addr| {
+                let session = if addr == contact.node.addr {
+                    Arc::clone(&contact)
+                } else {
+                    // The addresses of the nodes are their names.
+                    Arc::new(NodeSession {
+                        node: Node {
+                            host: addr.ip().to_string(),
+                            addr,
+                        },
+                        session: Mutex::new(None),
+                    })
+                };
+                (addr, session)
+            })
+            .collect();
+        Self {
+            connector,
+            contact,
+            nodes: Arc::new(nodes),
+            consistency,
+        }
+    }
+
+    fn into_sink(
+        self,
+        config: &CassandraSinkConfig,
+        encoder: Encoder,
+        cx: SinkContext,
+    ) -> crate::Result<impl futures::Sink<Event, Error = ()>> {
+        // Batches stay below the 50 KiB at which Cassandra fails them by default.
+        let batch = BatchSettings::default()
+            .events(100)
+            .bytes(40_000)
+            .timeout(1)
+            .parse_config(config.batch)?;
+        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        let sink = request
+            .partition_sink(
+                CassandraRetryLogic,
+                self,
+                PartitionBuffer::new(VecBuffer::new(batch.size)),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal cassandra sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encoder.encode(event)).map(Ok));
+
+        Ok(sink)
+    }
+
+    async fn healthcheck(self) -> crate::Result<()> {
+        let mut session = self.contact.session.lock().await;
+        let mut current = match session.take() {
+            Some(current) => current,
+            None => self.connector.connect(&self.contact.node).await?,
+        };
+        current
+            .connection
+            .query("SELECT release_version FROM system.local", Consistency::One)
+            .await?;
+        *session = Some(current);
+        Ok(())
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<Row>, Option<SocketAddr>>> for CassandraService {
+    type Response = ();
+    type Error = CqlError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(
+        &mut self,
+        request: PartitionInnerBuffer<Vec<Row>, Option<SocketAddr>>,
+    ) -> Self::Future {
+        let (rows, node) = request.into_parts();
+        let node = node
+            .and_then(|node| self.nodes.get(&node))
+            .unwrap_or(&self.contact)
+            .clone();
+        let connector = self.connector.clone();
+        let consistency = self.consistency;
+
+        debug!(
+            message = "Sending events.",
+            events = %rows.len(),
+            node = %node.node.addr,
+        );
+
+        Box::pin(async move {
+            let sizes = rows.iter().map(Row::encoded_length).collect::<Vec<_>>();
+            let rows = rows.into_iter().map(|row| row.values).collect::<Vec<_>>();
+
+            // The session is only put back once its response is read, so that
+            // the requests timed out don't leave responses behind.
+            let mut session = node.session.lock().await;
+            let mut current = match session.take() {
+                Some(current) => current,
+                None => connector.connect(&node.node).await?,
+            };
+            let result = match current
+                .connection
+                .batch(&current.prepared, &rows, consistency)
+                .await
+            {
+                Err(CqlError::Server { code, .. }) if code == protocol::ERROR_UNPREPARED => {
+                    current.prepared = current.connection.prepare(&connector.insert).await?;
+                    current
+                        .connection
+                        .batch(&current.prepared, &rows, consistency)
+                        .await
+                }
+                result => result,
+            };
+            if !matches!(result, Err(CqlError::Io { .. })) {
+                *session = Some(current);
+            }
+            result?;
+
+            for byte_size in sizes {
+                emit!(CassandraEventSent { byte_size });
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CassandraRetryLogic;
+
+impl RetryLogic for CassandraRetryLogic {
+    type Error = CqlError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_retriable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<CassandraSinkConfig>();
+    }
+
+    fn config(extra: &str) -> CassandraSinkConfig {
+        toml::from_str(&format!(
+            r#"
+            endpoints = ["127.0.0.1:9042"]
+            keyspace = "logs"
+            table = "Events"
+            columns.host = "host"
+            columns.message = "message"
+            {}
+            "#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    fn column(name: &str, column_type: ColumnType) -> Column {
+        Column {
+            name: name.to_owned(),
+            column_type,
+        }
+    }
+
+    #[test]
+    fn builds_insert_statements() {
+        assert_eq!(
+            config("").insert_statement(),
+            r#"INSERT INTO "logs"."Events" ("host", "message") VALUES (?, ?)"#
+        );
+        assert_eq!(
+            config("ttl_secs = 3600").insert_statement(),
+            r#"INSERT INTO "logs"."Events" ("host", "message") VALUES (?, ?) USING TTL ?"#
+        );
+    }
+
+    #[test]
+    fn parses_endpoints() {
+        assert_eq!(
+            parse_endpoint("scylla:9042").unwrap(),
+            ("scylla".to_owned(), 9042)
+        );
+        assert_eq!(
+            parse_endpoint("[::1]:9042").unwrap(),
+            ("::1".to_owned(), 9042)
+        );
+        assert!(parse_endpoint("scylla").is_err());
+        assert!(parse_endpoint(":9042").is_err());
+    }
+
+    #[test]
+    fn serializes_values() {
+        let timestamp = Utc.ymd(2020, 12, 1).and_hms_milli(10, 0, 0, 500);
+        let cases: Vec<(Value, ColumnType, Vec<u8>)> = vec![
+            ("hello".into(), ColumnType::Text, b"hello".to_vec()),
+            (Value::Integer(1), ColumnType::Text, b"1".to_vec()),
+            ("42".into(), ColumnType::Int, vec![0, 0, 0, 42]),
+            (Value::Integer(-1), ColumnType::Smallint, vec![0xff, 0xff]),
+            (Value::Integer(128), ColumnType::Varint, vec![0x00, 0x80]),
+            (Value::Integer(-129), ColumnType::Varint, vec![0xff, 0x7f]),
+            (Value::Boolean(true), ColumnType::Boolean, vec![1]),
+            (
+                Value::Float(1.5),
+                ColumnType::Double,
+                1.5f64.to_be_bytes().to_vec(),
+            ),
+            (
+                timestamp.into(),
+                ColumnType::Timestamp,
+                timestamp.timestamp_millis().to_be_bytes().to_vec(),
+            ),
+            (
+                "1970-01-02".into(),
+                ColumnType::Date,
+                ((1u32 << 31) + 1).to_be_bytes().to_vec(),
+            ),
+            ("10.0.0.1".into(), ColumnType::Inet, vec![10, 0, 0, 1]),
+            (
+                "7b4cee9c-4f1f-11eb-ae93-0242ac130002".into(),
+                ColumnType::Uuid,
+                vec![
+                    0x7b, 0x4c, 0xee, 0x9c, 0x4f, 0x1f, 0x11, 0xeb, 0xae, 0x93, 0x02, 0x42, 0xac,
+                    0x13, 0x00, 0x02,
+                ],
+            ),
+            (
+                Value::Array(vec!["a".into()]),
+                ColumnType::Set(Box::new(ColumnType::Text)),
+                vec![0, 0, 0, 1, 0, 0, 0, 1, b'a'],
+            ),
+        ];
+        for (value, column_type, expected) in cases {
+            assert_eq!(
+                serialize(&value, &column_type),
+                Ok(expected),
+                "{:?} as {:?}",
+                value,
+                column_type
+            );
+        }
+
+        assert!(serialize(&Value::Integer(1 << 40), &ColumnType::Int).is_err());
+        assert!(serialize(&"nope".into(), &ColumnType::Uuid).is_err());
+        assert!(serialize(&"a".into(), &ColumnType::List(Box::new(ColumnType::Int))).is_err());
+    }
+
+    fn encoder(extra: &str) -> Encoder {
+        let mut columns = vec![
+            column("host", ColumnType::Text),
+            column("message", ColumnType::Text),
+        ];
+        if !extra.is_empty() {
+            columns.push(column(TTL_MARKER, ColumnType::Int));
+        }
+        let prepared = Prepared {
+            id: "id".into(),
+            columns,
+            partition_key: vec![0],
+        };
+        Encoder::new(&config(extra), &prepared).unwrap()
+    }
+
+    #[test]
+    fn encodes_events_into_rows() {
+        let node = |last| SocketAddr::new([10, 0, 0, last].into(), 9042);
+        let token = murmur3_token(b"web-1");
+        let encoder = encoder("ttl_field = \"ttl\"\nttl_secs = 60")
+            .with_ring(Ring::new(vec![(token - 1, node(1)), (token, node(2))]));
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("host", "web-1");
+        let (row, routed_to) = encoder.encode(event.clone()).unwrap().into_parts();
+        assert_eq!(routed_to, Some(node(2)));
+        assert_eq!(
+            row.values,
+            vec![
+                BoundValue::Value("web-1".into()),
+                BoundValue::Value("hello".into()),
+                BoundValue::Value(vec![0, 0, 0, 60].into()),
+            ]
+        );
+
+        event.as_mut_log().insert("ttl", 10);
+        event.as_mut_log().remove("message");
+        let (row, _) = encoder.encode(event).unwrap().into_parts();
+        assert_eq!(row.values[1], BoundValue::Unset);
+        assert_eq!(row.values[2], BoundValue::Value(vec![0, 0, 0, 10].into()));
+
+        // The rows without partition key are dropped.
+        assert!(encoder.encode(Event::from("hello")).is_none());
+    }
+
+    #[test]
+    fn rejects_unsupported_columns() {
+        let prepared = Prepared {
+            id: "id".into(),
+            columns: vec![column("amount", ColumnType::Decimal)],
+            partition_key: vec![0],
+        };
+        assert!(Encoder::new(&config(""), &prepared).is_err());
+    }
+}
+
+#[cfg(feature = "cassandra-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, random_string, trace_init};
+
+    #[tokio::test]
+    async fn cassandra_inserts_rows() {
+        trace_init();
+
+        let table = format!("test_{}", random_string(10).to_lowercase());
+        let config = CassandraSinkConfig {
+            table: table.clone(),
+            ..toml::from_str(
+                r#"
+                endpoints = ["127.0.0.1:9042"]
+                keyspace = "vector"
+                table = ""
+                columns.id = "message"
+                ttl_secs = 3600
+                "#,
+            )
+            .unwrap()
+        };
+
+        let connector = Connector::new(&config).unwrap();
+        let mut admin = connector.clone();
+        admin.insert = "SELECT now() FROM system.local".into();
+        let (_, mut session) = admin.connect_any(&config.endpoints).await.unwrap();
+        for query in &[
+            "CREATE KEYSPACE IF NOT EXISTS vector WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1}".to_owned(),
+            format!("CREATE TABLE vector.{} (id text PRIMARY KEY)", table),
+        ] {
+            session.connection.query(query, Consistency::One).await.unwrap();
+        }
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (input, events) = random_lines_with_stream(100, 100);
+        sink.run(events).await.unwrap();
+
+        let rows = session
+            .connection
+            .query(
+                &format!("SELECT id FROM vector.{}", table),
+                Consistency::One,
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.rows.len(), input.len());
+    }
+}
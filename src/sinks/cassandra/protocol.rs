@@ -0,0 +1,604 @@
+//! A client of version 4 of the CQL native protocol, limited to what the sink
+//! needs: authenticating, preparing statements, reading the ring and sending
+//! batches of prepared statements. Requests are sent one at a time, on the
+//! stream 0 of the connection.
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const REQUEST_VERSION: u8 = 0x04;
+const RESPONSE_VERSION: u8 = 0x84;
+const HEADER_LEN: usize = 9;
+/// The largest frame the protocol allows.
+const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
+
+const OP_ERROR: u8 = 0x00;
+const OP_STARTUP: u8 = 0x01;
+const OP_READY: u8 = 0x02;
+const OP_AUTHENTICATE: u8 = 0x03;
+const OP_QUERY: u8 = 0x07;
+const OP_RESULT: u8 = 0x08;
+const OP_PREPARE: u8 = 0x09;
+const OP_BATCH: u8 = 0x0D;
+const OP_AUTH_RESPONSE: u8 = 0x0F;
+const OP_AUTH_SUCCESS: u8 = 0x10;
+
+const RESULT_VOID: i32 = 0x0001;
+const RESULT_ROWS: i32 = 0x0002;
+const RESULT_PREPARED: i32 = 0x0004;
+
+const METADATA_GLOBAL_TABLES_SPEC: i32 = 0x0001;
+const METADATA_HAS_MORE_PAGES: i32 = 0x0002;
+const METADATA_NO_METADATA: i32 = 0x0004;
+
+const BATCH_UNLOGGED: u8 = 0x01;
+const BATCH_KIND_PREPARED: u8 = 0x01;
+
+/// The error codes the servers may recover from.
+pub const ERROR_UNAVAILABLE: i32 = 0x1000;
+pub const ERROR_OVERLOADED: i32 = 0x1001;
+pub const ERROR_IS_BOOTSTRAPPING: i32 = 0x1002;
+pub const ERROR_WRITE_TIMEOUT: i32 = 0x1100;
+/// The statement isn't prepared on the node, which lost it on restart.
+pub const ERROR_UNPREPARED: i32 = 0x2500;
+
+#[derive(Debug, Snafu)]
+pub enum CqlError {
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: crate::tls::TlsError },
+    #[snafu(display("Connection failed: {}", source))]
+    Io { source: std::io::Error },
+    #[snafu(display("Server error {:#06x}: {}", code, message))]
+    Server { code: i32, message: String },
+    #[snafu(display("Unexpected response opcode {:#04x}", opcode))]
+    UnexpectedResponse { opcode: u8 },
+    #[snafu(display("Malformed response: {}", reason))]
+    Malformed { reason: &'static str },
+    #[snafu(display("Response frame of {} bytes is too large", len))]
+    FrameTooLarge { len: usize },
+    #[snafu(display(
+        "Authentication required by {}, but no credentials are set",
+        authenticator
+    ))]
+    AuthenticationRequired { authenticator: String },
+}
+
+impl CqlError {
+    /// Whether sending the request again may succeed.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            CqlError::Connect { .. } | CqlError::Io { .. } => true,
+            CqlError::Server { code, .. } => matches!(
+                *code,
+                ERROR_UNAVAILABLE
+                    | ERROR_OVERLOADED
+                    | ERROR_IS_BOOTSTRAPPING
+                    | ERROR_WRITE_TIMEOUT
+                    | ERROR_UNPREPARED
+            ),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Consistency {
+    Any,
+    One,
+    Two,
+    Three,
+    Quorum,
+    All,
+    LocalQuorum,
+    EachQuorum,
+    LocalOne,
+}
+
+impl Consistency {
+    fn code(self) -> u16 {
+        match self {
+            Consistency::Any => 0x0000,
+            Consistency::One => 0x0001,
+            Consistency::Two => 0x0002,
+            Consistency::Three => 0x0003,
+            Consistency::Quorum => 0x0004,
+            Consistency::All => 0x0005,
+            Consistency::LocalQuorum => 0x0006,
+            Consistency::EachQuorum => 0x0007,
+            Consistency::LocalOne => 0x000A,
+        }
+    }
+}
+
+impl Default for Consistency {
+    fn default() -> Self {
+        Consistency::LocalQuorum
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ColumnType {
+    Ascii,
+    Bigint,
+    Blob,
+    Boolean,
+    Counter,
+    Date,
+    Decimal,
+    Double,
+    Float,
+    Inet,
+    Int,
+    Smallint,
+    Text,
+    Time,
+    Timestamp,
+    Timeuuid,
+    Tinyint,
+    Uuid,
+    Varint,
+    List(Box<ColumnType>),
+    Map(Box<ColumnType>, Box<ColumnType>),
+    Set(Box<ColumnType>),
+    /// The custom, tuple and user defined types, which events aren't
+    /// written to.
+    Other(u16),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub column_type: ColumnType,
+}
+
+/// A statement prepared by a node, with the types of its bind markers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prepared {
+    pub id: Bytes,
+    pub columns: Vec<Column>,
+    /// The bind markers of the partition key, in its order.
+    pub partition_key: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rows {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Option<Bytes>>>,
+}
+
+/// The value bound to a marker.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BoundValue {
+    Value(Bytes),
+    Null,
+    /// Leaves the column as it is, rather than writing a tombstone.
+    Unset,
+}
+
+impl BoundValue {
+    pub fn size(&self) -> usize {
+        match self {
+            BoundValue::Value(value) => value.len(),
+            _ => 0,
+        }
+    }
+}
+
+pub struct Connection<S> {
+    stream: S,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Connection<S> {
+    /// Starts the connection, authenticating with the plain text credentials
+    /// if the server asks for them.
+    pub async fn startup(stream: S, credentials: Option<(&str, &str)>) -> Result<Self, CqlError> {
+        let mut connection = Self { stream };
+
+        let mut body = BytesMut::new();
+        body.put_u16(1);
+        put_string(&mut body, "CQL_VERSION");
+        put_string(&mut body, "3.0.0");
+        let (opcode, mut body) = connection.request(OP_STARTUP, body).await?;
+        match opcode {
+            OP_READY => Ok(connection),
+            OP_AUTHENTICATE => {
+                let authenticator = read_string(&mut body)?;
+                let (username, password) =
+                    credentials.ok_or(CqlError::AuthenticationRequired { authenticator })?;
+
+                let mut token = BytesMut::new();
+                token.put_u8(0);
+                token.put_slice(username.as_bytes());
+                token.put_u8(0);
+                token.put_slice(password.as_bytes());
+                let mut body = BytesMut::new();
+                put_bytes(&mut body, &BoundValue::Value(token.freeze()));
+                match connection.request(OP_AUTH_RESPONSE, body).await?.0 {
+                    OP_AUTH_SUCCESS => Ok(connection),
+                    opcode => Err(CqlError::UnexpectedResponse { opcode }),
+                }
+            }
+            opcode => Err(CqlError::UnexpectedResponse { opcode }),
+        }
+    }
+
+    pub async fn prepare(&mut self, query: &str) -> Result<Prepared, CqlError> {
+        let mut body = BytesMut::new();
+        put_long_string(&mut body, query);
+        let mut result = self.result(OP_PREPARE, body).await?;
+        match result.get_i32() {
+            RESULT_PREPARED => parse_prepared(&mut result),
+            _ => Err(CqlError::Malformed {
+                reason: "expected a prepared result",
+            }),
+        }
+    }
+
+    pub async fn query(&mut self, query: &str, consistency: Consistency) -> Result<Rows, CqlError> {
+        let mut body = BytesMut::new();
+        put_long_string(&mut body, query);
+        body.put_u16(consistency.code());
+        body.put_u8(0);
+        let mut result = self.result(OP_QUERY, body).await?;
+        match result.get_i32() {
+            RESULT_ROWS => parse_rows(&mut result),
+            _ => Ok(Rows::default()),
+        }
+    }
+
+    /// Executes the prepared statement once per row, in an unlogged batch.
+    pub async fn batch(
+        &mut self,
+        prepared: &Prepared,
+        rows: &[Vec<BoundValue>],
+        consistency: Consistency,
+    ) -> Result<(), CqlError> {
+        let body = encode_batch(prepared, rows, consistency);
+        let mut result = self.result(OP_BATCH, body).await?;
+        match result.get_i32() {
+            RESULT_VOID => Ok(()),
+            _ => Err(CqlError::Malformed {
+                reason: "expected a void result",
+            }),
+        }
+    }
+
+    /// Sends a request expecting a result, returning the body of the result
+    /// with its kind at least.
+    async fn result(&mut self, opcode: u8, body: BytesMut) -> Result<Bytes, CqlError> {
+        match self.request(opcode, body).await? {
+            (OP_RESULT, body) if body.len() >= 4 => Ok(body),
+            (OP_RESULT, _) => Err(CqlError::Malformed {
+                reason: "truncated result",
+            }),
+            (opcode, _) => Err(CqlError::UnexpectedResponse { opcode }),
+        }
+    }
+
+    async fn request(&mut self, opcode: u8, body: BytesMut) -> Result<(u8, Bytes), CqlError> {
+        let mut frame = BytesMut::with_capacity(HEADER_LEN + body.len());
+        frame.put_u8(REQUEST_VERSION);
+        frame.put_u8(0);
+        frame.put_i16(0);
+        frame.put_u8(opcode);
+        frame.put_u32(body.len() as u32);
+        frame.put_slice(&body);
+        self.stream.write_all(&frame).await.context(Io)?;
+        self.stream.flush().await.context(Io)?;
+
+        loop {
+            let mut header = [0u8; HEADER_LEN];
+            self.stream.read_exact(&mut header).await.context(Io)?;
+            let mut header = &header[..];
+            let version = header.get_u8();
+            let _flags = header.get_u8();
+            let stream = header.get_i16();
+            let opcode = header.get_u8();
+            let len = header.get_u32() as usize;
+            if version != RESPONSE_VERSION {
+                return Err(CqlError::Malformed {
+                    reason: "unsupported protocol version",
+                });
+            }
+            if len > MAX_FRAME_LEN {
+                return Err(CqlError::FrameTooLarge { len });
+            }
+
+            let mut body = vec![0u8; len];
+            self.stream.read_exact(&mut body).await.context(Io)?;
+            // Events are only pushed to connections registered for them,
+            // skipping them keeps the responses in step regardless.
+            if stream != 0 {
+                continue;
+            }
+
+            let mut body = Bytes::from(body);
+            return match opcode {
+                OP_ERROR => {
+                    let code = read_int(&mut body)?;
+                    let message = read_string(&mut body)?;
+                    Err(CqlError::Server { code, message })
+                }
+                opcode => Ok((opcode, body)),
+            };
+        }
+    }
+}
+
+fn encode_batch(
+    prepared: &Prepared,
+    rows: &[Vec<BoundValue>],
+    consistency: Consistency,
+) -> BytesMut {
+    let mut body = BytesMut::new();
+    body.put_u8(BATCH_UNLOGGED);
+    body.put_u16(rows.len() as u16);
+    for values in rows {
+        body.put_u8(BATCH_KIND_PREPARED);
+        body.put_u16(prepared.id.len() as u16);
+        body.put_slice(&prepared.id);
+        body.put_u16(values.len() as u16);
+        for value in values {
+            put_bytes(&mut body, value);
+        }
+    }
+    body.put_u16(consistency.code());
+    body.put_u8(0);
+    body
+}
+
+fn put_string(buf: &mut BytesMut, value: &str) {
+    buf.put_u16(value.len() as u16);
+    buf.put_slice(value.as_bytes());
+}
+
+fn put_long_string(buf: &mut BytesMut, value: &str) {
+    buf.put_i32(value.len() as i32);
+    buf.put_slice(value.as_bytes());
+}
+
+pub fn put_bytes(buf: &mut BytesMut, value: &BoundValue) {
+    match value {
+        BoundValue::Value(value) => {
+            buf.put_i32(value.len() as i32);
+            buf.put_slice(value);
+        }
+        BoundValue::Null => buf.put_i32(-1),
+        BoundValue::Unset => buf.put_i32(-2),
+    }
+}
+
+fn ensure(buf: &Bytes, len: usize) -> Result<(), CqlError> {
+    if buf.remaining() < len {
+        Err(CqlError::Malformed {
+            reason: "truncated response",
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn read_short(buf: &mut Bytes) -> Result<u16, CqlError> {
+    ensure(buf, 2)?;
+    Ok(buf.get_u16())
+}
+
+pub fn read_int(buf: &mut Bytes) -> Result<i32, CqlError> {
+    ensure(buf, 4)?;
+    Ok(buf.get_i32())
+}
+
+fn read_string(buf: &mut Bytes) -> Result<String, CqlError> {
+    let len = read_short(buf)? as usize;
+    ensure(buf, len)?;
+    String::from_utf8(buf.split_to(len).to_vec()).map_err(|_| CqlError::Malformed {
+        reason: "invalid UTF-8 string",
+    })
+}
+
+fn read_short_bytes(buf: &mut Bytes) -> Result<Bytes, CqlError> {
+    let len = read_short(buf)? as usize;
+    ensure(buf, len)?;
+    Ok(buf.split_to(len))
+}
+
+pub fn read_bytes(buf: &mut Bytes) -> Result<Option<Bytes>, CqlError> {
+    let len = read_int(buf)?;
+    if len < 0 {
+        return Ok(None);
+    }
+    ensure(buf, len as usize)?;
+    Ok(Some(buf.split_to(len as usize)))
+}
+
+fn read_type(buf: &mut Bytes) -> Result<ColumnType, CqlError> {
+    let id = read_short(buf)?;
+    Ok(match id {
+        0x0000 => {
+            read_string(buf)?;
+            ColumnType::Other(id)
+        }
+        0x0001 => ColumnType::Ascii,
+        0x0002 => ColumnType::Bigint,
+        0x0003 => ColumnType::Blob,
+        0x0004 => ColumnType::Boolean,
+        0x0005 => ColumnType::Counter,
+        0x0006 => ColumnType::Decimal,
+        0x0007 => ColumnType::Double,
+        0x0008 => ColumnType::Float,
+        0x0009 => ColumnType::Int,
+        0x000B => ColumnType::Timestamp,
+        0x000C => ColumnType::Uuid,
+        0x000D => ColumnType::Text,
+        0x000E => ColumnType::Varint,
+        0x000F => ColumnType::Timeuuid,
+        0x0010 => ColumnType::Inet,
+        0x0011 => ColumnType::Date,
+        0x0012 => ColumnType::Time,
+        0x0013 => ColumnType::Smallint,
+        0x0014 => ColumnType::Tinyint,
+        0x0020 => ColumnType::List(Box::new(read_type(buf)?)),
+        0x0021 => ColumnType::Map(Box::new(read_type(buf)?), Box::new(read_type(buf)?)),
+        0x0022 => ColumnType::Set(Box::new(read_type(buf)?)),
+        0x0030 => {
+            read_string(buf)?;
+            read_string(buf)?;
+            for _ in 0..read_short(buf)? {
+                read_string(buf)?;
+                read_type(buf)?;
+            }
+            ColumnType::Other(id)
+        }
+        0x0031 => {
+            for _ in 0..read_short(buf)? {
+                read_type(buf)?;
+            }
+            ColumnType::Other(id)
+        }
+        _ => {
+            return Err(CqlError::Malformed {
+                reason: "unknown column type",
+            })
+        }
+    })
+}
+
+fn read_columns(buf: &mut Bytes, flags: i32, count: usize) -> Result<Vec<Column>, CqlError> {
+    if flags & METADATA_GLOBAL_TABLES_SPEC != 0 {
+        read_string(buf)?;
+        read_string(buf)?;
+    }
+    (0..count)
+        .map(|_| {
+            if flags & METADATA_GLOBAL_TABLES_SPEC == 0 {
+                read_string(buf)?;
+                read_string(buf)?;
+            }
+            Ok(Column {
+                name: read_string(buf)?,
+                column_type: read_type(buf)?,
+            })
+        })
+        .collect()
+}
+
+fn parse_prepared(buf: &mut Bytes) -> Result<Prepared, CqlError> {
+    let id = read_short_bytes(buf)?;
+    let flags = read_int(buf)?;
+    let count = read_int(buf)? as usize;
+    let partition_key = (0..read_int(buf)?)
+        .map(|_| read_short(buf).map(usize::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    let columns = read_columns(buf, flags, count)?;
+    Ok(Prepared {
+        id,
+        columns,
+        partition_key,
+    })
+}
+
+fn parse_rows(buf: &mut Bytes) -> Result<Rows, CqlError> {
+    let flags = read_int(buf)?;
+    let count = read_int(buf)? as usize;
+    if flags & METADATA_HAS_MORE_PAGES != 0 {
+        read_bytes(buf)?;
+    }
+    let columns = if flags & METADATA_NO_METADATA == 0 {
+        read_columns(buf, flags, count)?
+    } else {
+        Vec::new()
+    };
+    let rows = (0..read_int(buf)?)
+        .map(|_| (0..count).map(|_| read_bytes(buf)).collect())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Rows { columns, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put_type(buf: &mut BytesMut, id: u16) {
+        buf.put_u16(id);
+    }
+
+    #[test]
+    fn parses_prepared_results() {
+        let mut buf = BytesMut::new();
+        buf.put_u16(2);
+        buf.put_slice(b"id");
+        buf.put_i32(METADATA_GLOBAL_TABLES_SPEC);
+        buf.put_i32(3);
+        buf.put_i32(1);
+        buf.put_u16(1);
+        put_string(&mut buf, "logs");
+        put_string(&mut buf, "events");
+        put_string(&mut buf, "message");
+        put_type(&mut buf, 0x000D);
+        put_string(&mut buf, "host");
+        put_type(&mut buf, 0x000D);
+        put_string(&mut buf, "tags");
+        put_type(&mut buf, 0x0021);
+        put_type(&mut buf, 0x000D);
+        put_type(&mut buf, 0x0009);
+
+        let prepared = parse_prepared(&mut buf.freeze()).unwrap();
+        assert_eq!(&prepared.id[..], b"id");
+        assert_eq!(prepared.partition_key, vec![1]);
+        assert_eq!(prepared.columns[1].name, "host");
+        assert_eq!(
+            prepared.columns[2].column_type,
+            ColumnType::Map(Box::new(ColumnType::Text), Box::new(ColumnType::Int))
+        );
+    }
+
+    #[test]
+    fn parses_rows() {
+        let mut buf = BytesMut::new();
+        buf.put_i32(0);
+        buf.put_i32(2);
+        for name in &["peer", "tokens"] {
+            put_string(&mut buf, "system");
+            put_string(&mut buf, "peers");
+            put_string(&mut buf, name);
+            put_type(&mut buf, 0x0010);
+        }
+        buf.put_i32(1);
+        put_bytes(&mut buf, &BoundValue::Value(Bytes::from(vec![10, 0, 0, 1])));
+        put_bytes(&mut buf, &BoundValue::Null);
+
+        let rows = parse_rows(&mut buf.freeze()).unwrap();
+        assert_eq!(rows.columns.len(), 2);
+        assert_eq!(
+            rows.rows,
+            vec![vec![Some(Bytes::from(vec![10, 0, 0, 1])), None]]
+        );
+
+        assert!(parse_rows(&mut Bytes::from(vec![0, 0])).is_err());
+    }
+
+    #[test]
+    fn encodes_batches() {
+        let prepared = Prepared {
+            id: Bytes::from("id"),
+            columns: Vec::new(),
+            partition_key: Vec::new(),
+        };
+        let rows = vec![vec![
+            BoundValue::Value(Bytes::from("a")),
+            BoundValue::Null,
+            BoundValue::Unset,
+        ]];
+        let body = encode_batch(&prepared, &rows, Consistency::LocalQuorum);
+        assert_eq!(
+            &body[..],
+            &[
+                1, 0, 1, 1, 0, 2, b'i', b'd', 0, 3, 0, 0, 0, 1, b'a', 255, 255, 255, 255, 255, 255,
+                255, 254, 0, 6, 0
+            ][..]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! The token ring of the cluster, routing the rows to the node owning the
+//! range of their partition key, as placed by the `Murmur3Partitioner`.
+
+use super::protocol::{read_bytes, read_int, BoundValue, Rows};
+use bytes::{BufMut, Bytes, BytesMut};
+use std::{
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ring {
+    /// The tokens of the nodes, sorted, each owning the range from the token
+    /// before it.
+    tokens: Vec<(i64, SocketAddr)>,
+}
+
+impl Ring {
+    #[cfg(test)]
+    pub fn new(mut tokens: Vec<(i64, SocketAddr)>) -> Self {
+        tokens.sort();
+        Self { tokens }
+    }
+
+    /// Reads the tokens of the nodes from the `system.local` and
+    /// `system.peers` tables, whose rows hold the address of the nodes and
+    /// their tokens. The unspecified addresses nodes listen on are replaced by
+    /// the one they were reached at.
+    pub fn add_nodes(&mut self, rows: &Rows, reached_at: SocketAddr) {
+        for row in &rows.rows {
+            let address = match row.get(0).and_then(|address| address.as_ref()) {
+                Some(address) => match decode_inet(address) {
+                    Some(ip) if !ip.is_unspecified() => SocketAddr::new(ip, reached_at.port()),
+                    _ => reached_at,
+                },
+                None => continue,
+            };
+            let tokens = row
+                .get(1)
+                .and_then(|tokens| tokens.clone())
+                .and_then(decode_text_set)
+                .unwrap_or_default();
+            for token in tokens {
+                if let Ok(token) = token.parse::<i64>() {
+                    self.tokens.push((token, address));
+                }
+            }
+        }
+        self.tokens.sort();
+    }
+
+    pub fn nodes(&self) -> Vec<SocketAddr> {
+        let mut nodes = self
+            .tokens
+            .iter()
+            .map(|(_, node)| *node)
+            .collect::<Vec<_>>();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    /// The node owning the token, the one of the first token not below it,
+    /// wrapping around the ring.
+    pub fn owner(&self, token: i64) -> Option<SocketAddr> {
+        let index = match self.tokens.binary_search_by(|(t, _)| t.cmp(&token)) {
+            Ok(index) => index,
+            Err(index) => index % self.tokens.len().max(1),
+        };
+        self.tokens.get(index).map(|(_, node)| *node)
+    }
+}
+
+/// The routing key of a row, its serialized partition key, with each
+/// component of composite keys prefixed by its length and followed by a zero
+/// byte.
+pub fn routing_key(values: &[&BoundValue]) -> Option<Bytes> {
+    match values {
+        [BoundValue::Value(value)] => Some(value.clone()),
+        values => {
+            let mut key = BytesMut::new();
+            for value in values {
+                match value {
+                    BoundValue::Value(value) => {
+                        key.put_u16(value.len() as u16);
+                        key.put_slice(value);
+                        key.put_u8(0);
+                    }
+                    _ => return None,
+                }
+            }
+            Some(key.freeze())
+        }
+    }
+}
+
+const C1: u64 = 0x87c3_7b91_1142_53d5;
+const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+/// The token of a partition key, the first half of its 128 bits
+/// MurmurHash3, as computed by Cassandra: the bytes of the tail are sign
+/// extended, like the Java bytes are.
+pub fn murmur3_token(key: &[u8]) -> i64 {
+    let len = key.len();
+    let (mut h1, mut h2) = (0u64, 0u64);
+
+    let mut blocks = key.chunks_exact(16);
+    for block in &mut blocks {
+        let k1 = u64::from_le_bytes(<[u8; 8]>::try_from(&block[..8]).unwrap());
+        let k2 = u64::from_le_bytes(<[u8; 8]>::try_from(&block[8..]).unwrap());
+
+        h1 ^= mix_k1(k1);
+        h1 = h1
+            .rotate_left(27)
+            .wrapping_add(h2)
+            .wrapping_mul(5)
+            .wrapping_add(0x52dc_e729);
+        h2 ^= mix_k2(k2);
+        h2 = h2
+            .rotate_left(31)
+            .wrapping_add(h1)
+            .wrapping_mul(5)
+            .wrapping_add(0x3849_5ab5);
+    }
+
+    let tail = blocks.remainder();
+    let byte = |i: usize| tail[i] as i8 as i64 as u64;
+    if tail.len() > 8 {
+        let k2 = (8..tail.len()).fold(0, |k2, i| k2 ^ (byte(i) << ((i - 8) * 8)));
+        h2 ^= mix_k2(k2);
+    }
+    if !tail.is_empty() {
+        let k1 = (0..tail.len().min(8)).fold(0, |k1, i| k1 ^ (byte(i) << (i * 8)));
+        h1 ^= mix_k1(k1);
+    }
+
+    h1 ^= len as u64;
+    h2 ^= len as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix(h1);
+    h2 = fmix(h2);
+
+    // The minimum token is reserved for the ring itself.
+    match h1.wrapping_add(h2) as i64 {
+        i64::MIN => i64::MAX,
+        token => token,
+    }
+}
+
+fn mix_k1(k1: u64) -> u64 {
+    k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2)
+}
+
+fn mix_k2(k2: u64) -> u64 {
+    k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1)
+}
+
+fn fmix(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^ (k >> 33)
+}
+
+fn decode_inet(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => {
+            let mut octets = [0u8; 4];
+            octets.copy_from_slice(bytes);
+            Some(Ipv4Addr::from(octets).into())
+        }
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(bytes);
+            Some(Ipv6Addr::from(octets).into())
+        }
+        _ => None,
+    }
+}
+
+fn decode_text_set(mut bytes: Bytes) -> Option<Vec<String>> {
+    let count = read_int(&mut bytes).ok()?;
+    (0..count)
+        .map(|_| {
+            let element = read_bytes(&mut bytes).ok()??;
+            String::from_utf8(element.to_vec()).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::cassandra::protocol::put_bytes;
+
+    #[test]
+    fn computes_cassandra_tokens() {
+        assert_eq!(murmur3_token(b"123"), -7468325962851647638);
+        assert_eq!(
+            murmur3_token(&b"\x00\xff\x10\xfa\x99".repeat(10)),
+            5837342703291459765
+        );
+        assert_eq!(murmur3_token(&[0xfe; 8]), -8927430733708461935);
+        assert_eq!(murmur3_token(&[0x10; 8]), 1446172840243228796);
+        assert_eq!(murmur3_token(b"9223372036854775807"), 7162290910810015547);
+    }
+
+    #[test]
+    fn builds_routing_keys() {
+        let a = BoundValue::Value(Bytes::from("a"));
+        let bc = BoundValue::Value(Bytes::from("bc"));
+        assert_eq!(routing_key(&[&a]), Some(Bytes::from("a")));
+        assert_eq!(
+            routing_key(&[&a, &bc]),
+            Some(Bytes::from(&b"\x00\x01a\x00\x00\x02bc\x00"[..]))
+        );
+        assert_eq!(routing_key(&[&a, &BoundValue::Unset]), None);
+    }
+
+    #[test]
+    fn routes_tokens_to_owners() {
+        let node = |last| SocketAddr::new(Ipv4Addr::new(10, 0, 0, last).into(), 9042);
+        let ring = Ring::new(vec![(100, node(2)), (-100, node(1)), (0, node(3))]);
+
+        assert_eq!(ring.owner(-200), Some(node(1)));
+        assert_eq!(ring.owner(-100), Some(node(1)));
+        assert_eq!(ring.owner(-99), Some(node(3)));
+        assert_eq!(ring.owner(50), Some(node(2)));
+        assert_eq!(ring.owner(101), Some(node(1)));
+        assert_eq!(Ring::default().owner(0), None);
+    }
+
+    #[test]
+    fn reads_nodes_from_rows() {
+        let mut tokens = BytesMut::new();
+        tokens.put_i32(2);
+        put_bytes(&mut tokens, &BoundValue::Value(Bytes::from("-5")));
+        put_bytes(&mut tokens, &BoundValue::Value(Bytes::from("5")));
+        let rows = Rows {
+            columns: Vec::new(),
+            rows: vec![
+                vec![Some(Bytes::from(vec![0, 0, 0, 0])), Some(tokens.freeze())],
+                vec![Some(Bytes::from(vec![10, 0, 0, 2])), None],
+            ],
+        };
+        let reached_at = "10.0.0.1:9043".parse().unwrap();
+
+        let mut ring = Ring::default();
+        ring.add_nodes(&rows, reached_at);
+        assert_eq!(ring.nodes(), vec![reached_at]);
+        assert_eq!(ring.owner(0), Some(reached_at));
+    }
+}
@@ -1,23 +1,30 @@
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{metric::Metric, Event},
     http::{Auth, HttpClient},
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         http::{BatchedHttpSink, HttpRetryLogic, HttpSink},
         retries::{RetryAction, RetryLogic},
-        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig,
+        BatchConfig, BatchSettings, Buffer, Compression, MetricBuffer, MetricColumns, ScalarType,
+        TowerRequestConfig, TowerRequestSettings,
     },
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
+mod row_binary;
+
+use row_binary::Column;
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ClickhouseConfig {
@@ -39,6 +46,20 @@ pub struct ClickhouseConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub tls: Option<TlsOptions>,
+    /// The columns the events are inserted into, with their ClickHouse types,
+    /// required by the `row_binary` codec.
+    #[serde(default)]
+    pub columns: IndexMap<String, String>,
+    /// Lets ClickHouse buffer the inserts of several requests into one.
+    #[serde(default)]
+    pub async_insert: bool,
+    pub wait_for_async_insert: Option<bool>,
+    /// The maximum number of rows of the blocks the inserted rows are split into.
+    pub insert_block_size: Option<u64>,
+    /// Inserts metrics rather than logs, as rows of the `name`, `namespace`,
+    /// `tags`, `kind`, `type`, `value` and `timestamp` columns.
+    #[serde(default)]
+    pub metrics: bool,
 }
 
 lazy_static! {
@@ -57,8 +78,19 @@ impl_generate_config_from_default!(ClickhouseConfig);
 #[serde(rename_all = "snake_case")]
 #[derivative(Default)]
 pub enum Encoding {
+    /// Rows of the JSONEachRow format.
     #[derivative(Default)]
     Default,
+    /// Rows of the RowBinary format, encoded as the types of `columns`.
+    RowBinary,
+}
+
+/// The sink, with the parsed columns of the `row_binary` codec.
+#[derive(Clone)]
+struct ClickhouseSink {
+    config: ClickhouseConfig,
+    columns: Vec<Column>,
+    uri: Uri,
 }
 
 #[async_trait::async_trait]
@@ -74,10 +106,38 @@ impl SinkConfig for ClickhouseConfig {
             .parse_config(self.batch)?;
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
+
+        if self.metrics {
+            return self.build_metrics(cx, client, request);
+        }
+
+        let columns = match self.encoding.codec() {
+            Encoding::RowBinary if self.columns.is_empty() => {
+                return Err("The row_binary codec requires the columns option.".into())
+            }
+            Encoding::RowBinary => self
+                .columns
+                .iter()
+                .map(|(name, column_type)| Column::new(name.clone(), column_type.clone()))
+                .collect::<Result<Vec<_>, _>>()?,
+            Encoding::Default => Vec::new(),
+        };
+        let uri = encode_uri(
+            &self.endpoint,
+            self.database.as_deref().unwrap_or("default"),
+            &self.table,
+            &columns,
+            &self.settings(),
+        )?;
+        let sink = ClickhouseSink {
+            config: self.clone(),
+            columns,
+            uri,
+        };
 
         let sink = BatchedHttpSink::with_retry_logic(
-            self.clone(),
+            sink,
             Buffer::new(batch.size, self.compression),
             ClickhouseRetryLogic::default(),
             request,
@@ -93,7 +153,11 @@ impl SinkConfig for ClickhouseConfig {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Log
+        if self.metrics {
+            DataType::Metric
+        } else {
+            DataType::Log
+        }
     }
 
     fn sink_type(&self) -> &'static str {
@@ -101,44 +165,202 @@ impl SinkConfig for ClickhouseConfig {
     }
 }
 
+impl ClickhouseConfig {
+    /// The settings of the insert queries, passed as parameters.
+    fn settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = Vec::new();
+        if self.async_insert {
+            settings.push(("async_insert", "1".to_owned()));
+            if let Some(wait) = self.wait_for_async_insert {
+                settings.push(("wait_for_async_insert", (wait as u8).to_string()));
+            }
+        }
+        if let Some(size) = self.insert_block_size {
+            settings.push(("max_insert_block_size", size.to_string()));
+        }
+        settings
+    }
+
+    fn build_metrics(
+        &self,
+        cx: SinkContext,
+        client: HttpClient,
+        request: TowerRequestSettings,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if self.encoding.codec() == &Encoding::RowBinary {
+            return Err("The row_binary codec can't be used to insert metrics.".into());
+        }
+
+        let batch = BatchSettings::default()
+            .events(10_000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let uri = encode_uri(
+            &self.endpoint,
+            self.database.as_deref().unwrap_or("default"),
+            &self.table,
+            &[],
+            &self.settings(),
+        )?;
+        let sink = ClickhouseMetricsSink {
+            config: self.clone(),
+            uri,
+        };
+
+        let sink = BatchedHttpSink::with_retry_logic(
+            sink,
+            MetricBuffer::new(batch.size),
+            ClickhouseRetryLogic::default(),
+            request,
+            batch.timeout,
+            client.clone(),
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal clickhouse sink error.", %error));
+
+        let healthcheck = healthcheck(client, self.clone()).boxed();
+
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn insert_request(
+        &self,
+        uri: &Uri,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> http::Request<Vec<u8>> {
+        let mut builder = Request::post(uri).header("Content-Type", content_type);
+
+        if let Some(ce) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", ce);
+        }
+
+        let mut request = builder.body(body).unwrap();
+
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        request
+    }
+}
+
 #[async_trait::async_trait]
-impl HttpSink for ClickhouseConfig {
+impl HttpSink for ClickhouseSink {
     type Input = Vec<u8>;
     type Output = Vec<u8>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        self.encoding.apply_rules(&mut event);
+        self.config.encoding.apply_rules(&mut event);
 
-        let mut body =
-            serde_json::to_vec(&event.as_log().all_fields()).expect("Events should be valid json!");
-        body.push(b'\n');
+        match self.config.encoding.codec() {
+            Encoding::Default => {
+                let mut body = serde_json::to_vec(&event.as_log().all_fields())
+                    .expect("Events should be valid json!");
+                body.push(b'\n');
 
-        Some(body)
+                Some(body)
+            }
+            Encoding::RowBinary => {
+                let mut body = Vec::new();
+                row_binary::encode_row(&self.columns, event.as_log(), &mut body)
+                    .map_err(|error| {
+                        warn!(
+                            message = "Failed to encode event; dropping event.",
+                            %error,
+                            rate_limit_secs = 30,
+                        )
+                    })
+                    .ok()?;
+
+                Some(body)
+            }
+        }
     }
 
     async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
-        let database = if let Some(database) = &self.database {
-            database.as_str()
-        } else {
-            "default"
+        let content_type = match self.config.encoding.codec() {
+            Encoding::Default => "application/x-ndjson",
+            Encoding::RowBinary => "application/octet-stream",
         };
 
-        let uri = encode_uri(&self.endpoint, database, &self.table).expect("Unable to encode uri");
+        Ok(self.config.insert_request(&self.uri, content_type, events))
+    }
+}
 
-        let mut builder = Request::post(&uri).header("Content-Type", "application/x-ndjson");
+/// The sink of the `metrics` option, which encodes each batch from its
+/// columnar layout.
+#[derive(Clone)]
+struct ClickhouseMetricsSink {
+    config: ClickhouseConfig,
+    uri: Uri,
+}
 
-        if let Some(ce) = self.compression.content_encoding() {
-            builder = builder.header("Content-Encoding", ce);
-        }
+#[async_trait::async_trait]
+impl HttpSink for ClickhouseMetricsSink {
+    type Input = Metric;
+    type Output = Vec<Metric>;
 
-        let mut request = builder.body(events).unwrap();
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        Some(event.into_metric())
+    }
 
-        if let Some(auth) = &self.auth {
-            auth.apply(&mut request);
+    async fn build_request(&self, metrics: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        let columns = MetricColumns::from(metrics);
+        if !columns.others().is_empty() {
+            warn!(
+                message = "Metrics without a single value can't be inserted; dropping metrics.",
+                count = columns.others().len(),
+                rate_limit_secs = 30,
+            );
         }
 
-        Ok(request)
+        let body = encode_metric_rows(&columns, Utc::now());
+        let body = self.config.compression.compress(&body);
+
+        Ok(self
+            .config
+            .insert_request(&self.uri, "application/x-ndjson", body))
+    }
+}
+
+/// Encodes the metrics held in columns as rows of the JSONEachRow format, the
+/// ones without a timestamp being stamped with `now`.
+fn encode_metric_rows(columns: &MetricColumns, now: DateTime<Utc>) -> Vec<u8> {
+    let mut body = Vec::new();
+    for row in columns.rows() {
+        body.extend_from_slice(b"{\"name\":");
+        serde_json::to_writer(&mut body, row.name()).expect("Writing to Vec can't fail");
+        body.extend_from_slice(b",\"namespace\":");
+        serde_json::to_writer(&mut body, &row.namespace()).expect("Writing to Vec can't fail");
+        body.extend_from_slice(b",\"tags\":{");
+        for (index, (key, value)) in row.tags().enumerate() {
+            if index > 0 {
+                body.push(b',');
+            }
+            serde_json::to_writer(&mut body, key).expect("Writing to Vec can't fail");
+            body.push(b':');
+            serde_json::to_writer(&mut body, value).expect("Writing to Vec can't fail");
+        }
+        body.extend_from_slice(b"},\"kind\":");
+        serde_json::to_writer(&mut body, row.kind()).expect("Writing to Vec can't fail");
+        let r#type = match row.r#type() {
+            ScalarType::Counter => "counter",
+            ScalarType::Gauge => "gauge",
+            ScalarType::Set => "set",
+        };
+        body.extend_from_slice(format!(",\"type\":\"{}\",\"value\":", r#type).as_bytes());
+        serde_json::to_writer(&mut body, &row.value()).expect("Writing to Vec can't fail");
+        let timestamp = row.timestamp().unwrap_or(now);
+        body.extend_from_slice(
+            format!(
+                ",\"timestamp\":\"{}\"}}\n",
+                timestamp.format("%Y-%m-%d %H:%M:%S%.6f")
+            )
+            .as_bytes(),
+        );
     }
+    body
 }
 
 async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Result<()> {
@@ -158,18 +380,40 @@ async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Res
     }
 }
 
-fn encode_uri(host: &str, database: &str, table: &str) -> crate::Result<Uri> {
-    let query = url::form_urlencoded::Serializer::new(String::new())
-        .append_pair(
-            "query",
-            format!(
-                "INSERT INTO \"{}\".\"{}\" FORMAT JSONEachRow",
-                database,
-                table.replace("\"", "\\\"")
-            )
-            .as_str(),
+/// The URI of the insert queries. The rows of the `row_binary` codec have the
+/// values of `columns` only, which are listed in the query.
+fn encode_uri(
+    host: &str,
+    database: &str,
+    table: &str,
+    columns: &[Column],
+    settings: &[(&str, String)],
+) -> crate::Result<Uri> {
+    let quote = |name: &str| format!("\"{}\"", name.replace("\"", "\\\""));
+    let insert = if columns.is_empty() {
+        format!(
+            "INSERT INTO \"{}\".{} FORMAT JSONEachRow",
+            database,
+            quote(table)
         )
-        .finish();
+    } else {
+        let columns = columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<_>>();
+        format!(
+            "INSERT INTO \"{}\".{} ({}) FORMAT RowBinary",
+            database,
+            quote(table),
+            columns.join(", ")
+        )
+    };
+    let mut query = url::form_urlencoded::Serializer::new(String::new());
+    query.append_pair("query", &insert);
+    for (name, value) in settings {
+        query.append_pair(name, value);
+    }
+    let query = query.finish();
 
     let url = if host.ends_with('/') {
         format!("{}?{}", host, query)
@@ -230,16 +474,144 @@ mod tests {
 
     #[test]
     fn encode_valid() {
-        let uri = encode_uri("http://localhost:80", "my_database", "my_table").unwrap();
+        let uri = encode_uri("http://localhost:80", "my_database", "my_table", &[], &[]).unwrap();
         assert_eq!(uri, "http://localhost:80/?query=INSERT+INTO+%22my_database%22.%22my_table%22+FORMAT+JSONEachRow");
 
-        let uri = encode_uri("http://localhost:80", "my_database", "my_\"table\"").unwrap();
+        let uri = encode_uri(
+            "http://localhost:80",
+            "my_database",
+            "my_\"table\"",
+            &[],
+            &[],
+        )
+        .unwrap();
         assert_eq!(uri, "http://localhost:80/?query=INSERT+INTO+%22my_database%22.%22my_%5C%22table%5C%22%22+FORMAT+JSONEachRow");
     }
 
     #[test]
     fn encode_invalid() {
-        encode_uri("localhost:80", "my_database", "my_table").unwrap_err();
+        encode_uri("localhost:80", "my_database", "my_table", &[], &[]).unwrap_err();
+    }
+
+    #[test]
+    fn encode_row_binary_uri() {
+        let config: ClickhouseConfig = toml::from_str(
+            r#"
+            endpoint = "http://localhost:8123"
+            table = "logs"
+            async_insert = true
+            wait_for_async_insert = false
+            insert_block_size = 100000
+            "#,
+        )
+        .unwrap();
+        let columns = vec![
+            Column::new("host".into(), "LowCardinality(String)".into()).unwrap(),
+            Column::new("status".into(), "UInt16".into()).unwrap(),
+        ];
+
+        let uri = encode_uri(
+            &config.endpoint,
+            "default",
+            &config.table,
+            &columns,
+            &config.settings(),
+        )
+        .unwrap();
+        assert_eq!(uri, "http://localhost:8123/?query=INSERT+INTO+%22default%22.%22logs%22+%28%22host%22%2C+%22status%22%29+FORMAT+RowBinary&async_insert=1&wait_for_async_insert=0&max_insert_block_size=100000");
+    }
+
+    #[test]
+    fn encode_metrics_as_rows() {
+        use crate::event::metric::{MetricKind, MetricValue, StatisticKind};
+        use chrono::TimeZone;
+
+        let timestamp = Utc.ymd(2021, 1, 2).and_hms_micro(3, 4, 5, 6);
+        let columns = MetricColumns::from(vec![
+            Metric {
+                name: "requests".into(),
+                namespace: Some("vector".into()),
+                timestamp: Some(timestamp),
+                tags: Some(
+                    vec![
+                        ("region".to_owned(), "eu".to_owned()),
+                        ("host".to_owned(), "a\"b".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 1.5 },
+            },
+            Metric {
+                name: "latency".into(),
+                namespace: None,
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Absolute,
+                value: MetricValue::Distribution {
+                    values: vec![1.0],
+                    sample_rates: vec![1],
+                    statistic: StatisticKind::Histogram,
+                },
+            },
+            Metric {
+                name: "users".into(),
+                namespace: None,
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Absolute,
+                value: MetricValue::Gauge { value: 2.0 },
+            },
+        ]);
+
+        let now = Utc.ymd(2021, 1, 2).and_hms(3, 4, 6);
+        let body = String::from_utf8(encode_metric_rows(&columns, now)).unwrap();
+        assert_eq!(
+            body,
+            concat!(
+                r#"{"name":"requests","namespace":"vector","tags":{"host":"a\"b","region":"eu"},"kind":"incremental","type":"counter","value":1.5,"timestamp":"2021-01-02 03:04:05.000006"}"#,
+                "\n",
+                r#"{"name":"users","namespace":null,"tags":{},"kind":"absolute","type":"gauge","value":2.0,"timestamp":"2021-01-02 03:04:06.000000"}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn metrics_reject_row_binary() {
+        let config: ClickhouseConfig = toml::from_str(
+            r#"
+            endpoint = "http://localhost:8123"
+            table = "metrics"
+            metrics = true
+            encoding.codec = "row_binary"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.input_type(), DataType::Metric);
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn row_binary_requires_columns() {
+        let config: ClickhouseConfig = toml::from_str(
+            r#"
+            endpoint = "http://localhost:8123"
+            table = "logs"
+            encoding.codec = "row_binary"
+            "#,
+        )
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+
+        let config = ClickhouseConfig {
+            columns: vec![("status".to_owned(), "Decimal(1, 1)".to_owned())]
+                .into_iter()
+                .collect(),
+            ..config
+        };
+        assert!(config.build(SinkContext::new_test()).await.is_err());
     }
 }
 
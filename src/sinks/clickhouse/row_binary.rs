@@ -0,0 +1,438 @@
+//! Encoding of the events as rows of the RowBinary format, with the value of
+//! each field converted to the type of its column, instead of leaving
+//! ClickHouse to parse the JSON representation of the values.
+
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, NaiveDate, Utc};
+use snafu::Snafu;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+#[derive(Debug, Snafu)]
+pub enum ColumnTypeError {
+    #[snafu(display("Unsupported column type {:?}", column_type))]
+    Unsupported { column_type: String },
+}
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum EncodeError {
+    #[snafu(display(
+        "Value of kind {} of field {:?} doesn't fit column type {}",
+        kind,
+        column,
+        column_type
+    ))]
+    InvalidValue {
+        column: String,
+        column_type: String,
+        kind: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnType {
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    String,
+    FixedString(usize),
+    Uuid,
+    Date,
+    DateTime,
+    /// The precision, as the number of digits of the fractions of seconds.
+    DateTime64(u32),
+    Nullable(Box<ColumnType>),
+    Array(Box<ColumnType>),
+}
+
+impl ColumnType {
+    fn parse(column_type: &str) -> Result<Self, ColumnTypeError> {
+        let column_type = column_type.trim();
+        let unsupported = || ColumnTypeError::Unsupported {
+            column_type: column_type.to_owned(),
+        };
+        let (name, args) = match column_type.find('(') {
+            Some(start) if column_type.ends_with(')') => (
+                &column_type[..start],
+                Some(&column_type[start + 1..column_type.len() - 1]),
+            ),
+            Some(_) => return Err(unsupported()),
+            None => (column_type, None),
+        };
+        Ok(match (name, args) {
+            ("UInt8", None) => Self::UInt8,
+            ("UInt16", None) => Self::UInt16,
+            ("UInt32", None) => Self::UInt32,
+            ("UInt64", None) => Self::UInt64,
+            ("Int8", None) => Self::Int8,
+            ("Int16", None) => Self::Int16,
+            ("Int32", None) => Self::Int32,
+            ("Int64", None) => Self::Int64,
+            ("Float32", None) => Self::Float32,
+            ("Float64", None) => Self::Float64,
+            ("Bool", None) | ("Boolean", None) => Self::Bool,
+            ("String", None) => Self::String,
+            ("FixedString", Some(length)) => {
+                Self::FixedString(length.trim().parse().map_err(|_| unsupported())?)
+            }
+            ("UUID", None) => Self::Uuid,
+            ("Date", None) => Self::Date,
+            // The time zone only matters to ClickHouse.
+            ("DateTime", _) => Self::DateTime,
+            ("DateTime64", Some(args)) => {
+                let precision = args.split(',').next().unwrap_or_default().trim();
+                match precision.parse() {
+                    Ok(precision) if precision <= 9 => Self::DateTime64(precision),
+                    _ => return Err(unsupported()),
+                }
+            }
+            ("Nullable", Some(inner)) => Self::Nullable(Box::new(Self::parse(inner)?)),
+            ("Array", Some(inner)) => Self::Array(Box::new(Self::parse(inner)?)),
+            // Encoded as the type of its values.
+            ("LowCardinality", Some(inner)) => Self::parse(inner)?,
+            _ => return Err(unsupported()),
+        })
+    }
+}
+
+/// A column of the table the rows are inserted into.
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    type_name: String,
+    column_type: ColumnType,
+}
+
+impl Column {
+    pub fn new(name: String, type_name: String) -> Result<Self, ColumnTypeError> {
+        let column_type = ColumnType::parse(&type_name)?;
+        Ok(Self {
+            name,
+            type_name,
+            column_type,
+        })
+    }
+}
+
+/// Appends the row of the columns to `buffer`. Missing and null fields of
+/// columns which aren't nullable are set to the default of their type, as
+/// ClickHouse does for the text formats.
+pub fn encode_row(
+    columns: &[Column],
+    log: &LogEvent,
+    buffer: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    for column in columns {
+        let value = log.get(&column.name).unwrap_or(&Value::Null);
+        encode_value(&column.column_type, value, buffer).map_err(|kind| {
+            EncodeError::InvalidValue {
+                column: column.name.clone(),
+                column_type: column.type_name.clone(),
+                kind: kind.to_owned(),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Fails with the kind of the value, or of one of its elements, which can't be
+/// converted to the type.
+fn encode_value<'a>(
+    column_type: &ColumnType,
+    value: &'a Value,
+    buffer: &mut Vec<u8>,
+) -> Result<(), &'a str> {
+    if let Value::Null = value {
+        encode_default(column_type, buffer);
+        return Ok(());
+    }
+    let invalid = move || value.kind();
+    match column_type {
+        ColumnType::UInt8 => buffer.push(to_integer(value).ok_or_else(invalid)?),
+        ColumnType::UInt16 => {
+            buffer.extend_from_slice(&to_integer::<u16>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::UInt32 => {
+            buffer.extend_from_slice(&to_integer::<u32>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::UInt64 => {
+            buffer.extend_from_slice(&to_integer::<u64>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::Int8 => {
+            buffer.extend_from_slice(&to_integer::<i8>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::Int16 => {
+            buffer.extend_from_slice(&to_integer::<i16>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::Int32 => {
+            buffer.extend_from_slice(&to_integer::<i32>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::Int64 => {
+            buffer.extend_from_slice(&to_integer::<i64>(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::Float32 => {
+            buffer.extend_from_slice(&(to_float(value).ok_or_else(invalid)? as f32).to_le_bytes())
+        }
+        ColumnType::Float64 => {
+            buffer.extend_from_slice(&to_float(value).ok_or_else(invalid)?.to_le_bytes())
+        }
+        ColumnType::Bool => buffer.push(to_bool(value).ok_or_else(invalid)? as u8),
+        ColumnType::String => {
+            let bytes = value.as_bytes();
+            write_varint(bytes.len() as u64, buffer);
+            buffer.extend_from_slice(&bytes);
+        }
+        ColumnType::FixedString(length) => {
+            let bytes = value.as_bytes();
+            if bytes.len() > *length {
+                return Err(invalid());
+            }
+            buffer.extend_from_slice(&bytes);
+            buffer.resize(buffer.len() + length - bytes.len(), 0);
+        }
+        ColumnType::Uuid => {
+            let uuid = match value {
+                Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|uuid| Uuid::parse_str(uuid).ok()),
+                _ => None,
+            }
+            .ok_or_else(invalid)?;
+            // Both halves are little endian.
+            let (high, low) = uuid.as_bytes().split_at(8);
+            buffer.extend(high.iter().rev());
+            buffer.extend(low.iter().rev());
+        }
+        ColumnType::Date => {
+            let days = to_timestamp(value)
+                .map(|timestamp| timestamp.timestamp().div_euclid(86_400))
+                .or_else(|| match value {
+                    Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                        .ok()
+                        .and_then(|date| NaiveDate::parse_from_str(date.trim(), "%F").ok())
+                        .map(|date| date.and_hms(0, 0, 0).timestamp() / 86_400),
+                    _ => None,
+                })
+                .and_then(|days| u16::try_from(days).ok())
+                .ok_or_else(invalid)?;
+            buffer.extend_from_slice(&days.to_le_bytes());
+        }
+        ColumnType::DateTime => {
+            let seconds = to_timestamp(value)
+                .and_then(|timestamp| u32::try_from(timestamp.timestamp()).ok())
+                .ok_or_else(invalid)?;
+            buffer.extend_from_slice(&seconds.to_le_bytes());
+        }
+        ColumnType::DateTime64(precision) => {
+            let ticks = to_timestamp(value)
+                .and_then(|timestamp| {
+                    let scale = 10i64.pow(*precision);
+                    let fraction =
+                        timestamp.timestamp_subsec_nanos() as i64 / 10i64.pow(9 - *precision);
+                    timestamp
+                        .timestamp()
+                        .checked_mul(scale)
+                        .and_then(|seconds| seconds.checked_add(fraction))
+                })
+                .ok_or_else(invalid)?;
+            buffer.extend_from_slice(&ticks.to_le_bytes());
+        }
+        ColumnType::Nullable(inner) => {
+            buffer.push(0);
+            encode_value(inner, value, buffer)?;
+        }
+        ColumnType::Array(inner) => match value {
+            Value::Array(values) => {
+                write_varint(values.len() as u64, buffer);
+                for value in values {
+                    encode_value(inner, value, buffer)?;
+                }
+            }
+            _ => return Err(invalid()),
+        },
+    }
+    Ok(())
+}
+
+fn encode_default(column_type: &ColumnType, buffer: &mut Vec<u8>) {
+    let zeros = match column_type {
+        ColumnType::UInt8 | ColumnType::Int8 | ColumnType::Bool => 1,
+        ColumnType::UInt16 | ColumnType::Int16 | ColumnType::Date => 2,
+        ColumnType::UInt32 | ColumnType::Int32 | ColumnType::Float32 | ColumnType::DateTime => 4,
+        ColumnType::UInt64 | ColumnType::Int64 | ColumnType::Float64 => 8,
+        ColumnType::DateTime64(_) => 8,
+        ColumnType::Uuid => 16,
+        ColumnType::FixedString(length) => *length,
+        // An empty string or array.
+        ColumnType::String | ColumnType::Array(_) => 1,
+        ColumnType::Nullable(_) => {
+            buffer.push(1);
+            return;
+        }
+    };
+    buffer.resize(buffer.len() + zeros, 0);
+}
+
+fn to_integer<T: TryFrom<i64> + std::str::FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::Integer(integer) => T::try_from(*integer).ok(),
+        Value::Float(float) if float.fract() == 0.0 => T::try_from(*float as i64).ok(),
+        Value::Boolean(boolean) => T::try_from(*boolean as i64).ok(),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(*float),
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Boolean(boolean) => Some(*boolean),
+        Value::Integer(0) => Some(false),
+        Value::Integer(1) => Some(true),
+        Value::Bytes(bytes) => match &bytes[..] {
+            b"true" => Some(true),
+            b"false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn to_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        Value::Bytes(bytes) => {
+            DateTime::parse_from_rfc3339(std::str::from_utf8(bytes).ok()?.trim())
+                .ok()
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+        }
+        _ => None,
+    }
+}
+
+/// Writes an unsigned LEB128 encoded integer, as the lengths of strings and arrays are.
+fn write_varint(mut value: u64, buffer: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn columns(columns: &[(&str, &str)]) -> Vec<Column> {
+        columns
+            .iter()
+            .map(|(name, type_name)| Column::new(name.to_string(), type_name.to_string()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parses_column_types() {
+        assert_eq!(
+            ColumnType::parse("Array(Nullable(LowCardinality(String)))").unwrap(),
+            ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(ColumnType::String))))
+        );
+        assert_eq!(
+            ColumnType::parse("DateTime64(3, 'UTC')").unwrap(),
+            ColumnType::DateTime64(3)
+        );
+        assert_eq!(
+            ColumnType::parse("DateTime('Europe/Berlin')").unwrap(),
+            ColumnType::DateTime
+        );
+        assert!(ColumnType::parse("Decimal(10, 2)").is_err());
+        assert!(ColumnType::parse("Array(String").is_err());
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let columns = columns(&[
+            ("status", "UInt16"),
+            ("delta", "Int32"),
+            ("ratio", "Float64"),
+            ("ok", "Bool"),
+            ("message", "String"),
+            ("code", "FixedString(4)"),
+            ("at", "DateTime"),
+            ("at_ms", "DateTime64(3)"),
+            ("tags", "Array(String)"),
+            ("user", "Nullable(String)"),
+            ("missing", "UInt32"),
+        ]);
+        let mut log = LogEvent::default();
+        log.insert("status", "200");
+        log.insert("delta", -2);
+        log.insert("ratio", 0.5);
+        log.insert("ok", true);
+        log.insert("message", "hi");
+        log.insert("code", "ab");
+        log.insert("at", Utc.ymd(2021, 1, 1).and_hms_milli(0, 0, 1, 500));
+        log.insert("at_ms", Utc.ymd(2021, 1, 1).and_hms_milli(0, 0, 1, 500));
+        log.insert("tags", vec![Value::from("a"), Value::from("bc")]);
+
+        let mut buffer = Vec::new();
+        encode_row(&columns, &log, &mut buffer).unwrap();
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&200u16.to_le_bytes());
+        expected.extend_from_slice(&(-2i32).to_le_bytes());
+        expected.extend_from_slice(&0.5f64.to_le_bytes());
+        expected.push(1);
+        expected.extend_from_slice(b"\x02hi");
+        expected.extend_from_slice(b"ab\0\0");
+        expected.extend_from_slice(&1_609_459_201u32.to_le_bytes());
+        expected.extend_from_slice(&1_609_459_201_500i64.to_le_bytes());
+        expected.extend_from_slice(b"\x02\x01a\x02bc");
+        expected.push(1);
+        expected.extend_from_slice(&0u32.to_le_bytes());
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn rejects_values_not_fitting_their_column() {
+        let columns = columns(&[("status", "UInt8")]);
+        let mut log = LogEvent::default();
+        log.insert("status", 300);
+
+        assert_eq!(
+            encode_row(&columns, &log, &mut Vec::new()),
+            Err(EncodeError::InvalidValue {
+                column: "status".into(),
+                column_type: "UInt8".into(),
+                kind: "integer".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn writes_varints() {
+        let mut buffer = Vec::new();
+        write_varint(300, &mut buffer);
+        assert_eq!(buffer, vec![0xac, 0x02]);
+    }
+}
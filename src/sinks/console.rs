@@ -1,7 +1,7 @@
 use crate::{
     buffers::Acker,
-    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, LogEvent},
     internal_events::{ConsoleEventProcessed, ConsoleFieldNotFound},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -15,7 +15,7 @@ use futures::{
     FutureExt,
 };
 use serde::{Deserialize, Serialize};
-
+use std::fmt::Write;
 use tokio::io::{self, AsyncWriteExt};
 
 #[derive(Debug, Derivative, Deserialize, Serialize)]
@@ -33,6 +33,22 @@ pub struct ConsoleSinkConfig {
     #[serde(default)]
     pub target: Target,
     pub encoding: EncodingConfig<Encoding>,
+    /// The fields shown as the columns of the `table` encoding, by default
+    /// the timestamp, host and message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    /// The width of the columns of the `table` encoding but the last one,
+    /// which is never truncated.
+    #[serde(default = "default_column_width")]
+    pub column_width: usize,
+    /// Whether the `pretty` and `table` encodings are colorized, by default
+    /// if the target is a terminal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<bool>,
+}
+
+const fn default_column_width() -> usize {
+    24
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
@@ -40,6 +56,9 @@ pub struct ConsoleSinkConfig {
 pub enum Encoding {
     Text,
     Json,
+    Pretty,
+    Table,
+    Logfmt,
 }
 
 inventory::submit! {
@@ -51,6 +70,9 @@ impl GenerateConfig for ConsoleSinkConfig {
         toml::Value::try_from(Self {
             target: Target::Stdout,
             encoding: Encoding::Json.into(),
+            columns: None,
+            column_width: default_column_width(),
+            color: None,
         })
         .unwrap()
     }
@@ -65,15 +87,30 @@ impl SinkConfig for ConsoleSinkConfig {
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let encoding = self.encoding.clone();
 
-        let output: Box<dyn io::AsyncWrite + Send + Sync + Unpin> = match self.target {
-            Target::Stdout => Box::new(io::stdout()),
-            Target::Stderr => Box::new(io::stderr()),
+        let (output, stream): (Box<dyn io::AsyncWrite + Send + Sync + Unpin>, _) = match self.target
+        {
+            Target::Stdout => (Box::new(io::stdout()), atty::Stream::Stdout),
+            Target::Stderr => (Box::new(io::stderr()), atty::Stream::Stderr),
+        };
+
+        let layout = Layout {
+            columns: self.columns.clone().unwrap_or_else(|| {
+                vec![
+                    log_schema().timestamp_key().to_owned(),
+                    log_schema().host_key().to_owned(),
+                    log_schema().message_key().to_owned(),
+                ]
+            }),
+            column_width: self.column_width,
+            color: self.color.unwrap_or_else(|| atty::is(stream)),
         };
 
         let sink = WriterSink {
             acker: cx.acker(),
             output,
             encoding,
+            layout,
+            header_written: false,
         };
 
         Ok((
@@ -91,7 +128,167 @@ impl SinkConfig for ConsoleSinkConfig {
     }
 }
 
-fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<String> {
+/// How the `pretty` and `table` encodings lay out events.
+#[derive(Clone, Debug)]
+struct Layout {
+    columns: Vec<String>,
+    column_width: usize,
+    color: bool,
+}
+
+const BOLD: &str = "1";
+const CYAN: &str = "36";
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+const DIMMED: &str = "2";
+
+impl Layout {
+    fn paint(&self, out: &mut String, style: &str, text: &str) {
+        if self.color {
+            let _ = write!(out, "\x1b[{}m{}\x1b[0m", style, text);
+        } else {
+            out.push_str(text);
+        }
+    }
+
+    /// The names of the columns, aligned as the rows of `table`.
+    fn header(&self) -> String {
+        let mut header = String::new();
+        let row = self.row(self.columns.iter().map(String::as_str));
+        self.paint(&mut header, BOLD, &row);
+        header
+    }
+
+    fn table(&self, log: &LogEvent) -> String {
+        let values = self
+            .columns
+            .iter()
+            .map(|column| match log.get(column) {
+                Some(value) => value.to_string_lossy().replace(char::is_control, " "),
+                None => String::from("-"),
+            })
+            .collect::<Vec<_>>();
+        self.row(values.iter().map(String::as_str))
+    }
+
+    fn row<'a>(&self, cells: impl Iterator<Item = &'a str>) -> String {
+        let mut cells = cells.peekable();
+        let mut row = String::new();
+        while let Some(cell) = cells.next() {
+            if cells.peek().is_none() {
+                row.push_str(cell);
+            } else if cell.chars().count() > self.column_width {
+                let cut = cell
+                    .chars()
+                    .take(self.column_width.saturating_sub(1))
+                    .collect::<String>();
+                let _ = write!(row, "{}…  ", cut);
+            } else {
+                let _ = write!(row, "{:width$}  ", cell, width = self.column_width);
+            }
+        }
+        row
+    }
+
+    fn pretty(&self, value: &serde_json::Value) -> String {
+        let mut out = String::new();
+        if self.color {
+            self.pretty_value(&mut out, value, 0);
+        } else {
+            out = serde_json::to_string_pretty(value).expect("JSON values serialize");
+        }
+        out
+    }
+
+    fn pretty_value(&self, out: &mut String, value: &serde_json::Value, depth: usize) {
+        let indent = |out: &mut String, depth: usize| {
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth));
+        };
+        match value {
+            serde_json::Value::Object(map) if !map.is_empty() => {
+                out.push('{');
+                for (index, (key, value)) in map.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    indent(out, depth + 1);
+                    self.paint(
+                        out,
+                        CYAN,
+                        &serde_json::Value::from(key.as_str()).to_string(),
+                    );
+                    out.push_str(": ");
+                    self.pretty_value(out, value, depth + 1);
+                }
+                indent(out, depth);
+                out.push('}');
+            }
+            serde_json::Value::Array(array) if !array.is_empty() => {
+                out.push('[');
+                for (index, value) in array.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    indent(out, depth + 1);
+                    self.pretty_value(out, value, depth + 1);
+                }
+                indent(out, depth);
+                out.push(']');
+            }
+            serde_json::Value::String(_) => self.paint(out, GREEN, &value.to_string()),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+                self.paint(out, YELLOW, &value.to_string())
+            }
+            serde_json::Value::Null => self.paint(out, DIMMED, "null"),
+            _ => out.push_str(&value.to_string()),
+        }
+    }
+}
+
+/// Encodes a log event as `key=value` pairs of its flattened fields, with
+/// the values quoted if needed.
+fn logfmt(log: &LogEvent) -> String {
+    let mut out = String::new();
+    for (key, value) in log.all_fields() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        let value = value.to_string_lossy();
+        let quote = value.is_empty()
+            || value
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control() || c == '=' || c == '"');
+        if quote {
+            let _ = write!(
+                out,
+                "{}=\"{}\"",
+                key,
+                value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+            );
+        } else {
+            let _ = write!(out, "{}={}", key, value);
+        }
+    }
+    out
+}
+
+fn to_json<T: Serialize>(value: &T) -> Option<serde_json::Value> {
+    serde_json::to_value(value)
+        .map_err(|error| {
+            error!(message = "Error encoding json.", %error);
+        })
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    encoding: &EncodingConfig<Encoding>,
+    layout: &Layout,
+) -> Option<String> {
     encoding.apply_rules(&mut event);
     match event {
         Event::Log(log) => match encoding.codec() {
@@ -100,6 +297,9 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
                     error!(message = "Error encoding json.", %error);
                 })
                 .ok(),
+            Encoding::Pretty => to_json(&log).map(|value| layout.pretty(&value)),
+            Encoding::Table => Some(layout.table(&log)),
+            Encoding::Logfmt => Some(logfmt(&log)),
             Encoding::Text => {
                 let field = crate::config::log_schema().message_key();
                 match log.get(field) {
@@ -119,7 +319,8 @@ fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option
                     error!(message = "Error encoding json.", %error);
                 })
                 .ok(),
-            Encoding::Text => Some(format!("{}", metric)),
+            Encoding::Pretty => to_json(&metric).map(|value| layout.pretty(&value)),
+            Encoding::Text | Encoding::Table | Encoding::Logfmt => Some(format!("{}", metric)),
         },
     }
 }
@@ -128,6 +329,8 @@ struct WriterSink {
     acker: Acker,
     output: Box<dyn io::AsyncWrite + Send + Sync + Unpin>,
     encoding: EncodingConfig<Encoding>,
+    layout: Layout,
+    header_written: bool,
 }
 
 #[async_trait]
@@ -135,7 +338,20 @@ impl StreamSink for WriterSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         while let Some(event) = input.next().await {
             self.acker.ack(1);
-            if let Some(mut buf) = encode_event(event, &self.encoding) {
+            let header = match (&event, self.encoding.codec()) {
+                (Event::Log(_), Encoding::Table) if !self.header_written => {
+                    Some(self.layout.header())
+                }
+                _ => None,
+            };
+            if let Some(encoded) = encode_event(event, &self.encoding, &self.layout) {
+                let mut buf = match header {
+                    Some(header) => {
+                        self.header_written = true;
+                        header + "\n" + &encoded
+                    }
+                    None => encoded,
+                };
                 buf.push('\n');
                 if let Err(error) = self.output.write_all(buf.as_bytes()).await {
                     // Error when writing to stdout/stderr is likely irrecoverable,
@@ -155,11 +371,28 @@ impl StreamSink for WriterSink {
 
 #[cfg(test)]
 mod test {
-    use super::{encode_event, ConsoleSinkConfig, Encoding, EncodingConfig};
+    use super::{encode_event, ConsoleSinkConfig, Encoding, EncodingConfig, Layout};
     use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
     use crate::event::{Event, Value};
     use chrono::{offset::TimeZone, Utc};
 
+    fn layout() -> Layout {
+        Layout {
+            columns: vec!["timestamp".into(), "level".into(), "message".into()],
+            column_width: 8,
+            color: false,
+        }
+    }
+
+    fn log_event() -> Event {
+        let mut event = Event::from("hello world");
+        let log = event.as_mut_log();
+        log.insert("timestamp", Utc.ymd(2020, 10, 14).and_hms(8, 9, 10));
+        log.insert("level", "info");
+        log.insert("http.status", 200);
+        event
+    }
+
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<ConsoleSinkConfig>();
@@ -170,7 +403,7 @@ mod test {
         let event = Event::from("foo");
         assert_eq!(
             "foo",
-            encode_event(event, &EncodingConfig::from(Encoding::Text)).unwrap()
+            encode_event(event, &EncodingConfig::from(Encoding::Text), &layout()).unwrap()
         );
     }
 
@@ -182,7 +415,7 @@ mod test {
         log.insert("z", Value::from(25));
         log.insert("a", Value::from("0"));
 
-        let encoded = encode_event(event, &EncodingConfig::from(Encoding::Json));
+        let encoded = encode_event(event, &EncodingConfig::from(Encoding::Json), &layout());
         let expected = r#"{"a":"0","x":"23","z":25}"#;
         assert_eq!(encoded.unwrap(), expected);
     }
@@ -207,7 +440,7 @@ mod test {
         });
         assert_eq!(
             r#"{"name":"foos","namespace":"vector","timestamp":"2018-11-14T08:09:10.000000011Z","tags":{"Key3":"Value3","key1":"value1","key2":"value2"},"kind":"incremental","counter":{"value":100.0}}"#,
-            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+            encode_event(event, &EncodingConfig::from(Encoding::Json), &layout()).unwrap()
         );
     }
 
@@ -225,7 +458,7 @@ mod test {
         });
         assert_eq!(
             r#"{"name":"users","kind":"incremental","set":{"values":["bob"]}}"#,
-            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+            encode_event(event, &EncodingConfig::from(Encoding::Json), &layout()).unwrap()
         );
     }
 
@@ -245,7 +478,7 @@ mod test {
         });
         assert_eq!(
             r#"{"name":"glork","kind":"incremental","distribution":{"values":[10.0],"sample_rates":[1],"statistic":"histogram"}}"#,
-            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+            encode_event(event, &EncodingConfig::from(Encoding::Json), &layout()).unwrap()
         );
     }
 
@@ -263,7 +496,55 @@ mod test {
         });
         assert_eq!(
             "users{} + bob",
-            encode_event(event, &EncodingConfig::from(Encoding::Text)).unwrap()
+            encode_event(event, &EncodingConfig::from(Encoding::Text), &layout()).unwrap()
+        );
+    }
+
+    #[test]
+    fn encodes_pretty_logs() {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("a.b", "c");
+        event.as_mut_log().insert("n", 1);
+
+        let encoded = encode_event(event.clone(), &Encoding::Pretty.into(), &layout());
+        assert_eq!(
+            encoded.unwrap(),
+            "{\n  \"a\": {\n    \"b\": \"c\"\n  },\n  \"n\": 1\n}"
+        );
+
+        let colored = Layout {
+            color: true,
+            ..layout()
+        };
+        let encoded = encode_event(event, &Encoding::Pretty.into(), &colored);
+        assert_eq!(
+            encoded.unwrap(),
+            "{\n  \x1b[36m\"a\"\x1b[0m: {\n    \x1b[36m\"b\"\x1b[0m: \x1b[32m\"c\"\x1b[0m\n  },\n  \x1b[36m\"n\"\x1b[0m: \x1b[33m1\x1b[0m\n}"
+        );
+    }
+
+    #[test]
+    fn encodes_table_rows() {
+        let layout = layout();
+        assert_eq!(layout.header(), "timesta…  level     message");
+
+        let encoded = encode_event(log_event(), &Encoding::Table.into(), &layout);
+        assert_eq!(encoded.unwrap(), "2020-10…  info      hello world");
+
+        let encoded = encode_event(Event::from("bye"), &Encoding::Table.into(), &layout);
+        assert_eq!(encoded.unwrap(), "-         -         bye");
+    }
+
+    #[test]
+    fn encodes_logfmt() {
+        let mut event = log_event();
+        event.as_mut_log().insert("quote", r#"say "hi""#);
+        event.as_mut_log().insert("empty", "");
+
+        let encoded = encode_event(event, &Encoding::Logfmt.into(), &layout());
+        assert_eq!(
+            encoded.unwrap(),
+            r#"empty="" http.status=200 level=info message="hello world" quote="say \"hi\"" timestamp=2020-10-14T08:09:10Z"#
         );
     }
 }
@@ -0,0 +1,311 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, LogEvent, Value},
+    http::HttpClient,
+    internal_events::{DatadogEventsMissingKeys, DatadogEventsProcessed},
+    sinks::{
+        util::{
+            http::{BatchedHttpSink, HttpSink},
+            BatchSettings, TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, HealthcheckError, UriParseError, VectorSink,
+    },
+    template::Template,
+    tls::{MaybeTlsSettings, TlsConfig},
+};
+use futures::{FutureExt, SinkExt};
+use http::{Request, StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use snafu::ResultExt;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DatadogEventsConfig {
+    pub endpoint: Option<String>,
+    pub region: Option<super::Region>,
+    pub api_key: String,
+    /// The title of the Datadog events.
+    pub title: Template,
+    /// The text of the Datadog events, defaulting to the message of the log
+    /// events.
+    pub text: Option<Template>,
+    /// The tags of the Datadog events, each rendered as `key:value`.
+    #[serde(default)]
+    pub tags: Vec<Template>,
+    /// Events sharing an aggregation key are grouped by Datadog.
+    pub aggregation_key: Option<Template>,
+    pub alert_type: Option<Template>,
+    pub priority: Option<Template>,
+    pub source_type_name: Option<String>,
+    pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+inventory::submit! {
+    SinkDescription::new::<DatadogEventsConfig>("datadog_events")
+}
+
+impl GenerateConfig for DatadogEventsConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"api_key = "${DATADOG_API_KEY_ENV_VAR}"
+            title = "{{ title }}""#,
+        )
+        .unwrap()
+    }
+}
+
+impl DatadogEventsConfig {
+    fn get_endpoint(&self) -> &str {
+        self.endpoint
+            .as_deref()
+            .unwrap_or_else(|| match self.region {
+                Some(super::Region::Eu) => "https://api.datadoghq.eu",
+                None | Some(super::Region::Us) => "https://api.datadoghq.com",
+            })
+    }
+
+    fn render(&self, field: &'static str, template: &Template, event: &Event) -> Option<String> {
+        template
+            .render_string(event)
+            .map_err(|keys| {
+                emit!(DatadogEventsMissingKeys { field, keys: &keys });
+            })
+            .ok()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "datadog_events")]
+impl SinkConfig for DatadogEventsConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+
+        let tls_settings = MaybeTlsSettings::from_config(
+            &Some(self.tls.clone().unwrap_or_else(TlsConfig::enabled)),
+            false,
+        )?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
+        let healthcheck = healthcheck(self.clone(), client.clone()).boxed();
+
+        // The Events API accepts a single event per request.
+        let batch = BatchSettings::default().events(1).timeout(1);
+        let sink = BatchedHttpSink::new(
+            self.clone(),
+            VecBuffer::new(batch.size),
+            request_settings,
+            batch.timeout,
+            client,
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal datadog_events sink error.", %error));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "datadog_events"
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for DatadogEventsConfig {
+    type Input = serde_json::Value;
+    type Output = Vec<serde_json::Value>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        // Events without a title are rejected by Datadog.
+        let title = self.render("title", &self.title, &event)?;
+        let text = match &self.text {
+            Some(text) => self.render("text", text, &event)?,
+            None => event
+                .as_log()
+                .get(log_schema().message_key())
+                .map(|message| message.to_string_lossy())
+                .unwrap_or_default(),
+        };
+        let tags = self
+            .tags
+            .iter()
+            .filter_map(|tag| self.render("tags", tag, &event))
+            .collect::<Vec<_>>();
+
+        let mut body = json!({
+            "title": title,
+            "text": text,
+            "tags": tags,
+        });
+        if let Some(aggregation_key) = &self.aggregation_key {
+            if let Some(key) = self.render("aggregation_key", aggregation_key, &event) {
+                body["aggregation_key"] = json!(key);
+            }
+        }
+        if let Some(alert_type) = &self.alert_type {
+            if let Some(alert_type) = self.render("alert_type", alert_type, &event) {
+                body["alert_type"] = json!(alert_type);
+            }
+        }
+        if let Some(priority) = &self.priority {
+            if let Some(priority) = self.render("priority", priority, &event) {
+                body["priority"] = json!(priority);
+            }
+        }
+        if let Some(source_type_name) = &self.source_type_name {
+            body["source_type_name"] = json!(source_type_name);
+        }
+
+        let log = event.as_log();
+        if let Some(host) = log.get(log_schema().host_key()) {
+            body["host"] = json!(host.to_string_lossy());
+        }
+        if let Some(timestamp) = date_happened(log) {
+            body["date_happened"] = json!(timestamp);
+        }
+
+        emit!(DatadogEventsProcessed {
+            byte_size: body.to_string().len(),
+        });
+
+        Some(body)
+    }
+
+    async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let uri = format!("{}/api/v1/events", self.get_endpoint());
+        let body = match events.into_iter().next() {
+            Some(event) => serde_json::to_vec(&event)?,
+            None => Vec::new(),
+        };
+
+        Request::post(uri)
+            .header("Content-Type", "application/json")
+            .header("DD-API-KEY", self.api_key.clone())
+            .body(body)
+            .map_err(Into::into)
+    }
+}
+
+fn date_happened(log: &LogEvent) -> Option<i64> {
+    match log.get(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(timestamp)) => Some(timestamp.timestamp()),
+        _ => None,
+    }
+}
+
+async fn healthcheck(config: DatadogEventsConfig, client: HttpClient) -> crate::Result<()> {
+    let uri = format!("{}/api/v1/validate", config.get_endpoint())
+        .parse::<Uri>()
+        .context(UriParseError)?;
+
+    let request = Request::get(uri)
+        .header("DD-API-KEY", config.api_key)
+        .body(hyper::Body::empty())
+        .unwrap();
+
+    let response = client.send(request).await?;
+
+    match response.status() {
+        StatusCode::OK => Ok(()),
+        other => Err(HealthcheckError::UnexpectedStatus { status: other }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::next_addr,
+    };
+    use chrono::{TimeZone, Utc};
+    use futures::{stream, StreamExt};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<DatadogEventsConfig>();
+    }
+
+    fn config() -> DatadogEventsConfig {
+        load_sink::<DatadogEventsConfig>(
+            r#"
+            api_key = "atoken"
+            title = "{{ title }}"
+            tags = ["env:{{ environment }}", "team:{{ team }}"]
+            aggregation_key = "{{ service }}"
+            alert_type = "{{ level }}"
+            source_type_name = "vector"
+            "#,
+        )
+        .unwrap()
+        .0
+    }
+
+    #[test]
+    fn encodes_event_fields() {
+        let mut event = Event::from("disk is full");
+        let log = event.as_mut_log();
+        log.insert("title", "Disk full");
+        log.insert("environment", "production");
+        log.insert("service", "db");
+        log.insert("level", "error");
+        log.insert("host", "db-1");
+        log.insert("timestamp", Utc.timestamp(1_600_000_000, 0));
+
+        let body = config().encode_event(event).unwrap();
+
+        assert_eq!(
+            body,
+            json!({
+                "title": "Disk full",
+                "text": "disk is full",
+                "tags": ["env:production"],
+                "aggregation_key": "db",
+                "alert_type": "error",
+                "source_type_name": "vector",
+                "host": "db-1",
+                "date_happened": 1_600_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn drops_events_without_title() {
+        let event = Event::from("disk is full");
+
+        assert!(config().encode_event(event).is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_each_event_in_a_request() {
+        let addr = next_addr();
+        let mut config = config();
+        config.endpoint = Some(format!("http://{}", addr));
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = (0..3).map(|i| {
+            let mut event = Event::from(format!("message {}", i));
+            event.as_mut_log().insert("title", format!("title {}", i));
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let output = rx.take(3).collect::<Vec<_>>().await;
+        for (i, (parts, body)) in output.into_iter().enumerate() {
+            assert_eq!(parts.uri.path(), "/api/v1/events");
+            assert_eq!(parts.headers.get("DD-API-KEY").unwrap(), "atoken");
+
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["title"], format!("title {}", i));
+            assert_eq!(body["text"], format!("message {}", i));
+        }
+    }
+}
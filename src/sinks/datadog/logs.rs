@@ -16,13 +16,12 @@ use crate::{
     tls::{MaybeTlsSettings, TlsConfig},
 };
 use bytes::Bytes;
-use flate2::write::GzEncoder;
 use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode};
 use hyper::body::Body;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{io::Write, time::Duration};
+use std::time::Duration;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -109,7 +108,7 @@ impl DatadogLogsConfig {
             false,
         )?;
 
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
         let healthcheck = healthcheck(service.clone(), client.clone()).boxed();
         let sink = BatchedHttpSink::new(
             service,
@@ -137,21 +136,14 @@ impl DatadogLogsConfig {
 
         let compression = self.compression.unwrap_or(Compression::Gzip(None));
 
-        let (request, body) = match compression {
-            Compression::None => (request, body),
-            Compression::Gzip(level) => {
-                // Default the compression level to 6, which is similar to datadog agent.
-                // https://docs.datadoghq.com/agent/logs/log_transport/?tab=https#log-compression
-                let level = level.unwrap_or(6);
-                let mut encoder =
-                    GzEncoder::new(Vec::new(), flate2::Compression::new(level as u32));
-
-                encoder.write_all(&body)?;
-                (
-                    request.header("Content-Encoding", "gzip"),
-                    encoder.finish()?,
-                )
-            }
+        // The default gzip level of 6 is similar to the datadog agent.
+        // https://docs.datadoghq.com/agent/logs/log_transport/?tab=https#log-compression
+        let (request, body) = match compression.content_encoding() {
+            None => (request, body),
+            Some(ce) => (
+                request.header("Content-Encoding", ce),
+                compression.compress(&body),
+            ),
         };
 
         request
@@ -1,9 +1,12 @@
 use crate::{
-    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
     emit,
-    event::Event,
+    event::{Event, Value},
     http::{Auth, HttpClient},
-    internal_events::{ElasticSearchEventEncoded, ElasticSearchMissingKeys},
+    internal_events::{
+        ElasticSearchEventEncoded, ElasticSearchInvalidBulkAction, ElasticSearchMissingId,
+        ElasticSearchMissingKeys,
+    },
     rusoto::{self, region_from_endpoint, RegionOrEndpoint},
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
@@ -15,9 +18,10 @@ use crate::{
     tls::{TlsOptions, TlsSettings},
 };
 use bytes::Bytes;
+use chrono::Utc;
 use futures::{FutureExt, SinkExt};
 use http::{
-    header::{HeaderName, HeaderValue},
+    header::{HeaderName, HeaderValue, AUTHORIZATION},
     uri::InvalidUri,
     Request, StatusCode, Uri,
 };
@@ -31,6 +35,10 @@ use serde_json::json;
 use snafu::{ResultExt, Snafu};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -42,6 +50,10 @@ pub struct ElasticSearchConfig {
     pub doc_type: Option<String>,
     pub id_key: Option<String>,
     pub pipeline: Option<String>,
+    /// A template rendering the bulk action of each event.
+    pub bulk_action: Option<String>,
+    /// A template rendering the routing of each event.
+    pub routing: Option<String>,
 
     #[serde(default)]
     pub compression: Compression,
@@ -61,6 +73,59 @@ pub struct ElasticSearchConfig {
 
     pub aws: Option<RegionOrEndpoint>,
     pub tls: Option<TlsOptions>,
+
+    /// Writes the events to the `logs-<dataset>-<namespace>` data stream,
+    /// instead of `index`.
+    pub data_stream: Option<DataStreamConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DataStreamConfig {
+    #[serde(default = "default_data_stream_dataset")]
+    pub dataset: String,
+    #[serde(default = "default_data_stream_namespace")]
+    pub namespace: String,
+}
+
+fn default_data_stream_dataset() -> String {
+    "generic".into()
+}
+
+fn default_data_stream_namespace() -> String {
+    "default".into()
+}
+
+impl Default for DataStreamConfig {
+    fn default() -> Self {
+        Self {
+            dataset: default_data_stream_dataset(),
+            namespace: default_data_stream_namespace(),
+        }
+    }
+}
+
+/// The templates of the name of the data stream of the events.
+#[derive(Debug)]
+struct DataStreamTemplates {
+    dataset: Template,
+    namespace: Template,
+}
+
+impl DataStreamTemplates {
+    fn render(&self, event: &Event) -> Result<String, Vec<String>> {
+        let dataset = self.dataset.render_string(event);
+        let namespace = self.namespace.render_string(event);
+        match (dataset, namespace) {
+            (Ok(dataset), Ok(namespace)) => Ok(format!("logs-{}-{}", dataset, namespace)),
+            (dataset, namespace) => Err(dataset
+                .err()
+                .into_iter()
+                .chain(namespace.err())
+                .flatten()
+                .collect()),
+        }
+    }
 }
 
 lazy_static! {
@@ -69,6 +134,35 @@ lazy_static! {
     };
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum BulkAction {
+    Index,
+    Create,
+    Update,
+    Delete,
+}
+
+impl BulkAction {
+    fn parse(action: &str) -> Option<Self> {
+        match action {
+            "index" => Some(Self::Index),
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Index => "index",
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
 #[serde(rename_all = "snake_case")]
 #[derivative(Default)]
@@ -80,8 +174,25 @@ pub enum Encoding {
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
 pub enum ElasticSearchAuth {
-    Basic { user: String, password: String },
-    Aws { assume_role: Option<String> },
+    Basic {
+        user: String,
+        password: String,
+    },
+    Aws {
+        assume_role: Option<String>,
+    },
+    /// An API key, either encoded as returned by the create API key API or,
+    /// with `id` set, only the key itself.
+    ApiKey {
+        id: Option<String>,
+        api_key: Option<String>,
+        api_key_file: Option<PathBuf>,
+    },
+    /// A bearer token, such as a service account token.
+    Bearer {
+        token: Option<String>,
+        token_file: Option<PathBuf>,
+    },
 }
 
 inventory::submit! {
@@ -98,7 +209,8 @@ impl SinkConfig for ElasticSearchConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let common = ElasticSearchCommon::parse_config(&self)?;
-        let client = HttpClient::new(common.tls_settings.clone())?;
+        let client =
+            HttpClient::new(common.tls_settings.clone())?.with_headers(self.request.headers(&cx)?);
 
         let healthcheck = healthcheck(client.clone(), common).boxed();
 
@@ -137,9 +249,12 @@ impl SinkConfig for ElasticSearchConfig {
 pub struct ElasticSearchCommon {
     pub base_url: String,
     bulk_uri: Uri,
-    authorization: Option<Auth>,
+    authorization: Option<Authorization>,
     credentials: Option<rusoto::AwsCredentialsProvider>,
     index: Template,
+    data_stream: Option<DataStreamTemplates>,
+    bulk_action: Option<Template>,
+    routing: Option<Template>,
     doc_type: String,
     tls_settings: TlsSettings,
     config: ElasticSearchConfig,
@@ -158,6 +273,171 @@ enum ParseError {
     AWSCredentialsGenerateFailed { source: CredentialsError },
     #[snafu(display("Index template parse error: {}", source))]
     IndexTemplate { source: TemplateError },
+    #[snafu(display("Data stream template parse error: {}", source))]
+    DataStreamTemplate { source: TemplateError },
+    #[snafu(display("Only one of index and data_stream can be set"))]
+    DataStreamIndexConflict,
+    #[snafu(display("Bulk action template parse error: {}", source))]
+    BulkActionTemplate { source: TemplateError },
+    #[snafu(display(
+        "Invalid bulk action {:?}, expected index, create, update or delete",
+        action
+    ))]
+    InvalidBulkAction { action: String },
+    #[snafu(display("Data streams only accept the create bulk action"))]
+    DataStreamBulkAction,
+    #[snafu(display("Routing template parse error: {}", source))]
+    RoutingTemplate { source: TemplateError },
+    #[snafu(display("Exactly one of {} and {}_file must be set", option, option))]
+    AuthCredentials { option: &'static str },
+    #[snafu(display("Could not read credentials from {:?}: {}", path, source))]
+    CredentialsFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// The authorization of the requests sent to Elasticsearch, other than with
+/// AWS request signing.
+#[derive(Debug)]
+enum Authorization {
+    Auth(Auth),
+    ApiKey {
+        id: Option<String>,
+        api_key: Credentials,
+    },
+    Bearer(Credentials),
+}
+
+impl Authorization {
+    fn apply_builder(&self, builder: http::request::Builder) -> http::request::Builder {
+        match self {
+            Self::Auth(auth) => auth.apply_builder(builder),
+            Self::ApiKey { id, api_key } => {
+                let api_key = api_key.get();
+                let api_key = match id {
+                    Some(id) => base64::encode(format!("{}:{}", id, api_key)),
+                    None => api_key,
+                };
+                builder.header(AUTHORIZATION, format!("ApiKey {}", api_key))
+            }
+            Self::Bearer(token) => builder.header(AUTHORIZATION, format!("Bearer {}", token.get())),
+        }
+    }
+}
+
+/// How often a credentials file is checked for modifications.
+const CREDENTIALS_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Credentials given in the configuration, or read from a file which is read
+/// again in the background once it is modified, so that they can be rotated
+/// by replacing the file without reloading the configuration.
+#[derive(Derivative)]
+#[derivative(Debug)]
+enum Credentials {
+    Inline(#[derivative(Debug = "ignore")] String),
+    File {
+        path: PathBuf,
+        #[derivative(Debug = "ignore")]
+        state: Arc<Mutex<FileCredentials>>,
+    },
+}
+
+/// The credentials last read from a file, which requests use while the file
+/// is read again.
+struct FileCredentials {
+    modified: Option<SystemTime>,
+    credentials: String,
+    checked: Instant,
+    reloading: bool,
+}
+
+impl Credentials {
+    fn new(
+        option: &'static str,
+        inline: &Option<String>,
+        file: &Option<PathBuf>,
+    ) -> crate::Result<Self> {
+        match (inline, file) {
+            (Some(inline), None) => Ok(Self::Inline(inline.clone())),
+            (None, Some(path)) => {
+                let (modified, credentials) =
+                    Self::read(path).with_context(|| CredentialsFile { path })?;
+                Ok(Self::File {
+                    path: path.clone(),
+                    state: Arc::new(Mutex::new(FileCredentials {
+                        modified,
+                        credentials,
+                        checked: Instant::now(),
+                        reloading: false,
+                    })),
+                })
+            }
+            _ => Err(ParseError::AuthCredentials { option }.into()),
+        }
+    }
+
+    fn read(path: &Path) -> std::io::Result<(Option<SystemTime>, String)> {
+        let modified = std::fs::metadata(path)?.modified().ok();
+        let contents = std::fs::read_to_string(path)?;
+        Ok((modified, contents.trim().to_owned()))
+    }
+
+    async fn read_async(path: &Path) -> std::io::Result<(Option<SystemTime>, String)> {
+        let modified = tokio::fs::metadata(path).await?.modified().ok();
+        let contents = tokio::fs::read_to_string(path).await?;
+        Ok((modified, contents.trim().to_owned()))
+    }
+
+    /// The current credentials, checking the file for modifications in the
+    /// background if it wasn't checked recently.
+    fn get(&self) -> String {
+        match self {
+            Self::Inline(credentials) => credentials.clone(),
+            Self::File { path, state } => {
+                let mut file = state.lock().expect("Credentials lock poisoned.");
+                if !file.reloading && file.checked.elapsed() >= CREDENTIALS_CHECK_INTERVAL {
+                    file.reloading = true;
+                    tokio::spawn(Self::reload(path.clone(), Arc::clone(state)));
+                }
+                file.credentials.clone()
+            }
+        }
+    }
+
+    /// Reads the file again if it was modified since it was last read.
+    async fn reload(path: PathBuf, state: Arc<Mutex<FileCredentials>>) {
+        let modified = tokio::fs::metadata(&path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let previous = state.lock().expect("Credentials lock poisoned.").modified;
+        let read = if modified.is_none() || modified != previous {
+            Some(Self::read_async(&path).await)
+        } else {
+            None
+        };
+
+        let mut file = state.lock().expect("Credentials lock poisoned.");
+        match read {
+            Some(Ok((modified, credentials))) => {
+                if credentials != file.credentials {
+                    info!(message = "Reloaded credentials.", ?path);
+                }
+                file.modified = modified;
+                file.credentials = credentials;
+            }
+            Some(Err(error)) => error!(
+                message = "Could not read credentials, using the previous ones.",
+                ?path,
+                %error,
+                rate_limit_secs = 30,
+            ),
+            None => (),
+        }
+        file.checked = Instant::now();
+        file.reloading = false;
+    }
 }
 
 #[async_trait::async_trait]
@@ -166,35 +446,72 @@ impl HttpSink for ElasticSearchCommon {
     type Output = Vec<u8>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        let index = self
-            .index
-            .render_string(&event)
-            .map_err(|missing_keys| {
-                emit!(ElasticSearchMissingKeys {
-                    keys: &missing_keys
-                });
-            })
-            .ok()?;
-
-        let mut action = json!({
-            "index": {
-                "_index": index,
-                "_type": self.doc_type,
+        let index = match &self.data_stream {
+            Some(data_stream) => data_stream.render(&event),
+            None => self.index.render_string(&event),
+        }
+        .map_err(|missing_keys| {
+            emit!(ElasticSearchMissingKeys {
+                keys: &missing_keys
+            });
+        })
+        .ok()?;
+
+        let action = match &self.bulk_action {
+            Some(template) => {
+                let action = render(template, &event)?;
+                match BulkAction::parse(&action) {
+                    Some(action) => action,
+                    None => {
+                        emit!(ElasticSearchInvalidBulkAction { action: &action });
+                        return None;
+                    }
+                }
             }
-        });
-        maybe_set_id(
-            self.config.id_key.as_ref(),
-            action.pointer_mut("/index").unwrap(),
-            &mut event,
-        );
+            None if self.data_stream.is_some() => BulkAction::Create,
+            None => BulkAction::Index,
+        };
+        let routing = match &self.routing {
+            Some(template) => Some(render(template, &event)?),
+            None => None,
+        };
 
-        let mut body = serde_json::to_vec(&action).unwrap();
+        // Data streams have no types.
+        let mut metadata = json!({ "_index": index });
+        if self.data_stream.is_some() {
+            set_data_stream_timestamp(&mut event);
+        } else {
+            metadata["_type"] = json!(self.doc_type);
+        }
+        if let Some(routing) = routing {
+            metadata["routing"] = json!(routing);
+        }
+        maybe_set_id(self.config.id_key.as_ref(), &mut metadata, &mut event);
+        if matches!(action, BulkAction::Update | BulkAction::Delete)
+            && metadata.get("_id").is_none()
+        {
+            emit!(ElasticSearchMissingId);
+            return None;
+        }
+
+        let mut body = serde_json::to_vec(&json!({ action.as_str(): metadata })).unwrap();
         body.push(b'\n');
 
         self.config.encoding.apply_rules(&mut event);
 
-        serde_json::to_writer(&mut body, &event.into_log()).unwrap();
-        body.push(b'\n');
+        match action {
+            BulkAction::Index | BulkAction::Create => {
+                serde_json::to_writer(&mut body, &event.into_log()).unwrap();
+                body.push(b'\n');
+            }
+            // Updates merge the fields into the document, creating it if missing.
+            BulkAction::Update => {
+                let update = json!({ "doc": event.into_log(), "doc_as_upsert": true });
+                serde_json::to_writer(&mut body, &update).unwrap();
+                body.push(b'\n');
+            }
+            BulkAction::Delete => (),
+        }
 
         emit!(ElasticSearchEventEncoded {
             byte_size: body.len(),
@@ -267,6 +584,7 @@ struct ESResultResponse {
 }
 #[derive(Deserialize, Debug)]
 struct ESResultItem {
+    #[serde(alias = "create", alias = "update", alias = "delete")]
     index: ESIndexResult,
 }
 #[derive(Deserialize, Debug)]
@@ -346,13 +664,34 @@ impl ElasticSearchCommon {
             .into());
         }
 
-        let (base_url, mut authorization) = Auth::get_and_strip_basic_auth(&config.endpoint);
+        let (base_url, endpoint_authorization) = Auth::get_and_strip_basic_auth(&config.endpoint);
+        let mut authorization = endpoint_authorization.map(Authorization::Auth);
 
-        if let Some(ElasticSearchAuth::Basic { user, password }) = config.auth.clone() {
+        let configured = match &config.auth {
+            Some(ElasticSearchAuth::Basic { user, password }) => {
+                Some(Authorization::Auth(Auth::Basic {
+                    user: user.clone(),
+                    password: password.clone(),
+                }))
+            }
+            Some(ElasticSearchAuth::ApiKey {
+                id,
+                api_key,
+                api_key_file,
+            }) => Some(Authorization::ApiKey {
+                id: id.clone(),
+                api_key: Credentials::new("api_key", api_key, api_key_file)?,
+            }),
+            Some(ElasticSearchAuth::Bearer { token, token_file }) => Some(Authorization::Bearer(
+                Credentials::new("token", token, token_file)?,
+            )),
+            Some(ElasticSearchAuth::Aws { .. }) | None => None,
+        };
+        if configured.is_some() {
             if authorization.is_some() {
                 warn!("Overwriting authorization config in `endpoint`.");
             }
-            authorization = Some(Auth::Basic { user, password });
+            authorization = configured;
         }
 
         let region = match &config.aws {
@@ -361,16 +700,59 @@ impl ElasticSearchCommon {
         };
 
         let credentials = match &config.auth {
-            Some(ElasticSearchAuth::Basic { .. }) | None => None,
             Some(ElasticSearchAuth::Aws { assume_role }) => Some(
                 rusoto::AwsCredentialsProvider::new(&region, assume_role.clone())?,
             ),
+            _ => None,
         };
 
         let compression = config.compression;
         let index = config.index.as_deref().unwrap_or("vector-%Y.%m.%d");
         let index = Template::try_from(index).context(IndexTemplate)?;
 
+        let data_stream = match &config.data_stream {
+            Some(_) if config.index.is_some() => {
+                return Err(ParseError::DataStreamIndexConflict.into())
+            }
+            Some(data_stream) => Some(DataStreamTemplates {
+                dataset: Template::try_from(data_stream.dataset.as_str())
+                    .context(DataStreamTemplate)?,
+                namespace: Template::try_from(data_stream.namespace.as_str())
+                    .context(DataStreamTemplate)?,
+            }),
+            None => None,
+        };
+
+        let bulk_action = match &config.bulk_action {
+            Some(action) => {
+                let template = Template::try_from(action.as_str()).context(BulkActionTemplate)?;
+                // Static actions are checked upfront, rather than on every event.
+                if !template.is_dynamic() {
+                    match BulkAction::parse(action) {
+                        None => {
+                            return Err(ParseError::InvalidBulkAction {
+                                action: action.clone(),
+                            }
+                            .into())
+                        }
+                        Some(BulkAction::Create) => (),
+                        Some(_) if config.data_stream.is_some() => {
+                            return Err(ParseError::DataStreamBulkAction.into())
+                        }
+                        Some(_) => (),
+                    }
+                }
+                Some(template)
+            }
+            None => None,
+        };
+        let routing = config
+            .routing
+            .as_deref()
+            .map(Template::try_from)
+            .transpose()
+            .context(RoutingTemplate)?;
+
         let doc_type = config.doc_type.clone().unwrap_or_else(|| "_doc".into());
 
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
@@ -398,6 +780,9 @@ impl ElasticSearchCommon {
             authorization,
             credentials,
             index,
+            data_stream,
+            bulk_action,
+            routing,
             doc_type,
             tls_settings,
             config,
@@ -467,6 +852,30 @@ async fn finish_signer(
     Ok(builder)
 }
 
+/// Moves the timestamp of the event to the `@timestamp` field required by
+/// data streams, setting it to the current time if the event has none.
+fn set_data_stream_timestamp(event: &mut Event) {
+    let log = event.as_mut_log();
+    if log.contains("@timestamp") {
+        return;
+    }
+    let timestamp = log
+        .remove(log_schema().timestamp_key())
+        .unwrap_or_else(|| Value::Timestamp(Utc::now()));
+    log.insert("@timestamp", timestamp);
+}
+
+fn render(template: &Template, event: &Event) -> Option<String> {
+    template
+        .render_string(event)
+        .map_err(|missing_keys| {
+            emit!(ElasticSearchMissingKeys {
+                keys: &missing_keys
+            });
+        })
+        .ok()
+}
+
 fn maybe_set_id(key: Option<impl AsRef<str>>, doc: &mut serde_json::Value, event: &mut Event) {
     if let Some(val) = key.and_then(|k| event.as_mut_log().remove(k)) {
         let val = val.to_string_lossy();
@@ -565,6 +974,232 @@ mod tests {
 "#;
         assert_eq!(std::str::from_utf8(&encoded).unwrap(), &expected[..]);
     }
+
+    #[test]
+    fn encodes_data_stream_events() {
+        let config: ElasticSearchConfig = toml::from_str(
+            r#"
+            endpoint = "https://example.com"
+            id_key = "id"
+            data_stream.dataset = "{{ service }}"
+            "#,
+        )
+        .unwrap();
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+        let mut event = Event::from("hello there");
+        event.as_mut_log().insert("service", "checkout");
+        event.as_mut_log().insert("id", "42");
+        let timestamp = event.as_log()[log_schema().timestamp_key()].clone();
+
+        let encoded = es.encode_event(event).unwrap();
+        let mut lines = std::str::from_utf8(&encoded).unwrap().lines();
+        let action: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(
+            action,
+            json!({ "create": { "_id": "42", "_index": "logs-checkout-default" } })
+        );
+        let document: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(document["@timestamp"], json!(timestamp));
+        assert!(document.get(log_schema().timestamp_key()).is_none());
+
+        let event = Event::from("no service");
+        assert!(es.encode_event(event).is_none());
+    }
+
+    #[test]
+    fn rejects_data_stream_with_index() {
+        let config: ElasticSearchConfig = toml::from_str(
+            r#"
+            endpoint = "https://example.com"
+            index = "vector"
+            data_stream = {}
+            "#,
+        )
+        .unwrap();
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+    }
+
+    #[test]
+    fn handles_create_error_response() {
+        let json = r#"{"took":3,"errors":true,"items":[{"create":{"_index":"logs-generic-default","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [@timestamp]"}}}]}"#;
+        assert_eq!(
+            get_error_reason(json),
+            "error type: mapper_parsing_exception, reason: failed to parse field [@timestamp]"
+        );
+    }
+
+    fn encode_lines(es: &ElasticSearchCommon, event: Event) -> Option<Vec<serde_json::Value>> {
+        let encoded = es.encode_event(event)?;
+        Some(
+            std::str::from_utf8(&encoded)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn encodes_bulk_actions_per_event() {
+        let config: ElasticSearchConfig = toml::from_str(
+            r#"
+            endpoint = "https://example.com"
+            index = "vector"
+            id_key = "id"
+            bulk_action = "{{ action }}"
+            routing = "{{ tenant }}"
+            "#,
+        )
+        .unwrap();
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+        let event = |action: &str, id: Option<&str>| {
+            let mut event = Event::from("hello");
+            event.as_mut_log().insert("action", action);
+            event.as_mut_log().insert("tenant", "acme");
+            if let Some(id) = id {
+                event.as_mut_log().insert("id", id);
+            }
+            event
+        };
+
+        let lines = encode_lines(&es, event("update", Some("42"))).unwrap();
+        assert_eq!(
+            lines[0],
+            json!({ "update": { "_index": "vector", "_type": "_doc", "_id": "42", "routing": "acme" } })
+        );
+        assert_eq!(lines[1]["doc"]["message"], "hello");
+        assert_eq!(lines[1]["doc_as_upsert"], true);
+
+        let lines = encode_lines(&es, event("delete", Some("42"))).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["delete"]["_id"], "42");
+
+        let lines = encode_lines(&es, event("create", None)).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0]["create"].get("_id").is_none());
+
+        assert!(es.encode_event(event("delete", None)).is_none());
+        assert!(es.encode_event(event("upsert", Some("42"))).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_bulk_actions() {
+        let parse = |config: &str| {
+            let config: ElasticSearchConfig = toml::from_str(config).unwrap();
+            ElasticSearchCommon::parse_config(&config).map(|_| ())
+        };
+        assert!(parse(
+            r#"
+            endpoint = "https://example.com"
+            bulk_action = "upsert"
+            "#
+        )
+        .is_err());
+        assert!(parse(
+            r#"
+            endpoint = "https://example.com"
+            bulk_action = "index"
+            data_stream = {}
+            "#
+        )
+        .is_err());
+        assert!(parse(
+            r#"
+            endpoint = "https://example.com"
+            bulk_action = "create"
+            data_stream = {}
+            "#
+        )
+        .is_ok());
+    }
+
+    async fn authorization_header(common: &ElasticSearchCommon) -> String {
+        let request = common.build_request(Vec::new()).await.unwrap();
+        request.headers()[AUTHORIZATION]
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[tokio::test]
+    async fn authorizes_with_api_keys() {
+        let config: ElasticSearchConfig = toml::from_str(
+            r#"
+            endpoint = "https://example.com"
+            auth.strategy = "api_key"
+            auth.id = "VuaCfGcBCdbkQm-e5aOx"
+            auth.api_key = "ui2lp2axTNmsyakw9tvNnw"
+            "#,
+        )
+        .unwrap();
+        let common = ElasticSearchCommon::parse_config(&config).unwrap();
+        assert_eq!(
+            authorization_header(&common).await,
+            "ApiKey VnVhQ2ZHY0JDZGJrUW0tZTVhT3g6dWkybHAyYXhUTm1zeWFrdzl0dk5udw=="
+        );
+
+        let config: ElasticSearchConfig = toml::from_str(
+            r#"
+            endpoint = "https://example.com"
+            auth.strategy = "api_key"
+            auth.api_key = "VnVhQ2ZHY0JDZGJrUW0tZTVhT3g6dWkybHAyYXhUTm1zeWFrdzl0dk5udw=="
+            auth.api_key_file = "/run/secrets/api_key"
+            "#,
+        )
+        .unwrap();
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn reloads_rotated_token_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "first\n").unwrap();
+
+        let config = ElasticSearchConfig {
+            endpoint: "https://example.com".into(),
+            auth: Some(ElasticSearchAuth::Bearer {
+                token: None,
+                token_file: Some(path.clone()),
+            }),
+            ..Default::default()
+        };
+        let common = ElasticSearchCommon::parse_config(&config).unwrap();
+        assert_eq!(authorization_header(&common).await, "Bearer first");
+
+        // Modification times may only have a resolution of a second.
+        std::thread::sleep(Duration::from_millis(1100));
+        std::fs::write(&path, "second").unwrap();
+        // The file isn't checked again before the interval.
+        assert_eq!(authorization_header(&common).await, "Bearer first");
+
+        tokio::time::pause();
+        tokio::time::advance(CREDENTIALS_CHECK_INTERVAL).await;
+        assert_eq!(reloaded_header(&common).await, "Bearer second");
+
+        std::fs::remove_file(&path).unwrap();
+        tokio::time::advance(CREDENTIALS_CHECK_INTERVAL).await;
+        assert_eq!(reloaded_header(&common).await, "Bearer second");
+    }
+
+    /// The authorization header once the credentials file was checked again.
+    async fn reloaded_header(common: &ElasticSearchCommon) -> String {
+        // Starts the check.
+        authorization_header(common).await;
+        let state = match &common.authorization {
+            Some(Authorization::Bearer(Credentials::File { state, .. })) => Arc::clone(state),
+            _ => panic!("Expected a bearer token file."),
+        };
+        for _ in 0..100 {
+            if !state.lock().unwrap().reloading {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            tokio::task::yield_now().await;
+        }
+        authorization_header(common).await
+    }
 }
 
 #[cfg(test)]
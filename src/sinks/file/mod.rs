@@ -3,14 +3,14 @@ use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
-    internal_events::FileOpen,
+    internal_events::{FileOpen, FileRotated, FileRotationFailed},
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         StreamSink,
     },
     template::Template,
 };
-use async_compression::tokio_02::write::GzipEncoder;
+use async_compression::tokio_02::write::{GzipEncoder, ZstdEncoder};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{
@@ -19,7 +19,10 @@ use futures::{
     FutureExt,
 };
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant, SystemTime},
+};
 
 use tokio::{
     fs::{self, File},
@@ -28,6 +31,12 @@ use tokio::{
 mod bytes_path;
 use bytes_path::BytesPath;
 use std::convert::TryFrom;
+mod rotation;
+pub use rotation::RotationConfig;
+
+/// How often files older than the retention are looked for, in addition to
+/// when a file is rotated.
+const RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -44,6 +53,7 @@ pub struct FileSinkConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub compression: Compression,
+    pub rotation: Option<RotationConfig>,
 }
 
 inventory::submit! {
@@ -57,6 +67,7 @@ impl GenerateConfig for FileSinkConfig {
             idle_timeout_secs: None,
             encoding: Default::default(),
             compression: Default::default(),
+            rotation: None,
         })
         .unwrap()
     }
@@ -79,6 +90,7 @@ impl Default for Encoding {
 #[serde(rename_all = "snake_case")]
 pub enum Compression {
     Gzip,
+    Zstd,
     None,
 }
 
@@ -91,6 +103,7 @@ impl Default for Compression {
 enum OutFile {
     Regular(File),
     Gzip(GzipEncoder<File>),
+    Zstd(ZstdEncoder<File>),
 }
 
 impl OutFile {
@@ -98,6 +111,7 @@ impl OutFile {
         match compression {
             Compression::None => OutFile::Regular(file),
             Compression::Gzip => OutFile::Gzip(GzipEncoder::new(file)),
+            Compression::Zstd => OutFile::Zstd(ZstdEncoder::new(file)),
         }
     }
 
@@ -105,6 +119,7 @@ impl OutFile {
         match self {
             OutFile::Regular(file) => file.sync_all().await,
             OutFile::Gzip(gzip) => gzip.get_mut().sync_all().await,
+            OutFile::Zstd(zstd) => zstd.get_mut().sync_all().await,
         }
     }
 
@@ -112,6 +127,7 @@ impl OutFile {
         match self {
             OutFile::Regular(file) => file.shutdown().await,
             OutFile::Gzip(gzip) => gzip.shutdown().await,
+            OutFile::Zstd(zstd) => zstd.shutdown().await,
         }
     }
 
@@ -119,6 +135,7 @@ impl OutFile {
         match self {
             OutFile::Regular(file) => file.write_all(src).await,
             OutFile::Gzip(gzip) => gzip.write_all(src).await,
+            OutFile::Zstd(zstd) => zstd.write_all(src).await,
         }
     }
 
@@ -130,6 +147,38 @@ impl OutFile {
     }
 }
 
+/// An open file, with the size and time of the last write its rotation
+/// depends on. The size of compressed files counts the data written before
+/// compression.
+struct ActiveFile {
+    file: OutFile,
+    size: u64,
+    modified: SystemTime,
+}
+
+impl ActiveFile {
+    async fn open(path: Bytes, compression: Compression) -> Result<Self, std::io::Error> {
+        let file = open_file(BytesPath::new(path)).await?;
+        let metadata = file.metadata().await?;
+        Ok(Self {
+            file: OutFile::new(file, compression),
+            size: metadata.len(),
+            modified: metadata.modified().unwrap_or_else(|_| SystemTime::now()),
+        })
+    }
+
+    async fn write_all(&mut self, src: &[u8]) -> Result<(), std::io::Error> {
+        self.file.write_all(src).await?;
+        self.size += src.len() as u64;
+        self.modified = SystemTime::now();
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), std::io::Error> {
+        self.file.close().await
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "file")]
 impl SinkConfig for FileSinkConfig {
@@ -137,6 +186,9 @@ impl SinkConfig for FileSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if let Some(rotation) = &self.rotation {
+            rotation.validate()?;
+        }
         let sink = FileSink::new(&self, cx.acker());
         Ok((
             super::VectorSink::Stream(Box::new(sink)),
@@ -159,8 +211,12 @@ pub struct FileSink {
     path: Template,
     encoding: EncodingConfigWithDefault<Encoding>,
     idle_timeout: Duration,
-    files: ExpiringHashMap<Bytes, OutFile>,
+    files: ExpiringHashMap<Bytes, ActiveFile>,
     compression: Compression,
+    rotation: Option<RotationConfig>,
+    /// The paths written to, of which rotated files older than the
+    /// retention are deleted.
+    retained: HashSet<Bytes>,
 }
 
 impl FileSink {
@@ -172,6 +228,8 @@ impl FileSink {
             idle_timeout: Duration::from_secs(config.idle_timeout_secs.unwrap_or(30)),
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            rotation: config.rotation.clone(),
+            retained: HashSet::new(),
         }
     }
 
@@ -199,6 +257,9 @@ impl FileSink {
     }
 
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> crate::Result<()> {
+        let mut retention_check = tokio::time::interval(RETENTION_CHECK_INTERVAL);
+        let retention = self.rotation.as_ref().and_then(RotationConfig::retention);
+
         loop {
             tokio::select! {
                 event = input.next() => {
@@ -251,6 +312,9 @@ impl FileSink {
                         ),
                     }
                 }
+                _ = retention_check.tick(), if retention.is_some() => {
+                    self.delete_expired().await;
+                }
             }
         }
 
@@ -272,34 +336,118 @@ impl FileSink {
         let next_deadline = self.deadline_at();
         trace!(message = "Computed next deadline.", next_deadline = ?next_deadline, path = ?path);
 
-        let file = if let Some(file) = self.files.reset_at(&path, next_deadline) {
+        if self.files.reset_at(&path, next_deadline).is_some() {
             trace!(message = "Working with an already opened file.", path = ?path);
-            file
         } else {
             trace!(message = "Opening new file.", ?path);
-            let file = match open_file(BytesPath::new(path.clone())).await {
-                Ok(file) => file,
-                Err(error) => {
-                    // We couldn't open the file for this event.
-                    // Maybe other events will work though! Just log
-                    // the error and skip this event.
-                    error!(message = "Unable to open the file.", path = ?path, %error);
-                    return;
+            if !self.open(&path, next_deadline).await {
+                return;
+            }
+        }
+
+        // The suffix is rendered before the event is consumed by the encoding.
+        let suffix = self
+            .rotation
+            .as_ref()
+            .map(|rotation| rotation.suffix.render_string(&event));
+        let mut buf = encode_event(&self.encoding, event);
+        buf.push(b'\n');
+
+        let file = self.files.get(&path).unwrap();
+        let due = self.rotation.as_ref().map_or(false, |rotation| {
+            rotation.is_due(file.size, file.modified, buf.len(), SystemTime::now())
+        });
+        if due {
+            match suffix {
+                Some(Ok(suffix)) => {
+                    if !self.rotate(&path, &suffix, next_deadline).await {
+                        return;
+                    }
                 }
-            };
+                Some(Err(missing_keys)) => warn!(
+                    message =
+                        "Keys of the rotation suffix do not exist on the event; not rotating.",
+                    ?missing_keys,
+                    rate_limit_secs = 30,
+                ),
+                None => unreachable!(),
+            }
+        }
 
-            let outfile = OutFile::new(file, self.compression);
+        trace!(message = "Writing an event to file.", path = ?path);
+        let file = self.files.get_mut(&path).unwrap();
+        if let Err(error) = file.write_all(&buf[..]).await {
+            error!(message = "Failed to write file.", path = ?path, %error);
+        }
+    }
 
-            self.files.insert_at(path.clone(), outfile, next_deadline);
-            emit!(FileOpen {
-                count: self.files.len()
-            });
-            self.files.get_mut(&path).unwrap()
+    /// Opens the file at `path`, returning whether it could be opened.
+    async fn open(&mut self, path: &Bytes, deadline: Instant) -> bool {
+        let file = match ActiveFile::open(path.clone(), self.compression).await {
+            Ok(file) => file,
+            Err(error) => {
+                // We couldn't open the file for this event.
+                // Maybe other events will work though! Just log
+                // the error and skip this event.
+                error!(message = "Unable to open the file.", path = ?path, %error);
+                return false;
+            }
         };
 
-        trace!(message = "Writing an event to file.", path = ?path);
-        if let Err(error) = write_event_to_file(file, event, &self.encoding).await {
-            error!(message = "Failed to write file.", path = ?path, %error);
+        self.files.insert_at(path.clone(), file, deadline);
+        emit!(FileOpen {
+            count: self.files.len()
+        });
+        if self
+            .rotation
+            .as_ref()
+            .and_then(RotationConfig::retention)
+            .is_some()
+        {
+            self.retained.insert(path.clone());
+        }
+        true
+    }
+
+    /// Closes the file at `path`, renames it with the `suffix` and opens a
+    /// new one in its place, returning whether a file is open at `path`
+    /// after. If it can't be renamed, writing to it goes on.
+    async fn rotate(&mut self, path: &Bytes, suffix: &str, deadline: Instant) -> bool {
+        let (mut file, _) = self.files.remove(path).expect("file is open");
+        if let Err(error) = file.close().await {
+            error!(message = "Failed to close file.", path = ?path, %error);
+        }
+        drop(file);
+
+        let file_path = BytesPath::new(path.clone());
+        let rotated_path = rotation::rotated_path(file_path.as_ref(), suffix).await;
+        match fs::rename(file_path.as_ref(), &rotated_path).await {
+            Ok(()) => emit!(FileRotated {
+                path: file_path.as_ref(),
+                rotated_path: &rotated_path,
+            }),
+            Err(error) => emit!(FileRotationFailed {
+                path: file_path.as_ref(),
+                error,
+            }),
+        }
+
+        let config = self.rotation.as_ref().expect("rotation is configured");
+        if let Some(retention) = config.retention() {
+            rotation::delete_expired(file_path.as_ref(), &config.suffix, retention).await;
+        }
+
+        self.open(path, deadline).await
+    }
+
+    /// Deletes the rotated files older than the retention of all the paths
+    /// written to.
+    async fn delete_expired(&self) {
+        let config = self.rotation.as_ref().expect("rotation is configured");
+        let retention = config.retention().expect("retention is configured");
+        for path in &self.retained {
+            let path = BytesPath::new(path.clone());
+            rotation::delete_expired(path.as_ref(), &config.suffix, retention).await;
         }
     }
 }
@@ -332,16 +480,6 @@ pub fn encode_event(encoding: &EncodingConfigWithDefault<Encoding>, mut event: E
     }
 }
 
-async fn write_event_to_file(
-    file: &mut OutFile,
-    event: Event,
-    encoding: &EncodingConfigWithDefault<Encoding>,
-) -> Result<(), std::io::Error> {
-    let mut buf = encode_event(encoding, event);
-    buf.push(b'\n');
-    file.write_all(&buf[..]).await
-}
-
 #[async_trait]
 impl StreamSink for FileSink {
     async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
@@ -354,8 +492,8 @@ impl StreamSink for FileSink {
 mod tests {
     use super::*;
     use crate::test_util::{
-        lines_from_file, lines_from_gzip_file, random_events_with_stream, random_lines_with_stream,
-        temp_dir, temp_file, trace_init,
+        lines_from_file, lines_from_gzip_file, lines_from_zstd_file, random_events_with_stream,
+        random_lines_with_stream, temp_dir, temp_file, trace_init,
     };
     use futures::stream;
     use std::convert::TryInto;
@@ -376,6 +514,7 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotation: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -401,6 +540,7 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::Gzip,
+            rotation: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -415,6 +555,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn single_partition_zstd() {
+        trace_init();
+
+        let template = temp_file();
+
+        let config = FileSinkConfig {
+            path: template.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::Zstd,
+            rotation: None,
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _) = random_lines_with_stream(100, 64);
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        let output = lines_from_zstd_file(template);
+        assert_eq!(input, output);
+    }
+
     #[tokio::test]
     async fn many_partitions() {
         trace_init();
@@ -431,6 +595,7 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotation: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -512,6 +677,7 @@ mod tests {
             idle_timeout_secs: Some(1),
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotation: None,
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -541,4 +707,88 @@ mod tests {
         let output = lines_from_file(template);
         assert_eq!(input, output);
     }
+
+    fn rotation(max_bytes: u64, retention_secs: Option<u64>) -> Option<RotationConfig> {
+        let rotation: RotationConfig = toml::from_str(&format!(
+            r#"
+            max_bytes = {}
+            suffix = "-{{{{ part }}}}"
+            "#,
+            max_bytes
+        ))
+        .unwrap();
+        Some(RotationConfig {
+            retention_secs,
+            ..rotation
+        })
+    }
+
+    #[tokio::test]
+    async fn rotates_by_size() {
+        trace_init();
+
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        let config = FileSinkConfig {
+            path: path.to_str().unwrap().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::None,
+            rotation: rotation(100, None),
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _) = random_lines_with_stream(10, 20);
+
+        let events = input.clone().into_iter().map(|line| {
+            let mut event = Event::from(line);
+            event.as_mut_log().insert("part", "old");
+            event
+        });
+        sink.run(Box::pin(stream::iter(events))).await.unwrap();
+
+        assert_eq!(lines_from_file(directory.join("app-old.log")), &input[..9]);
+        assert_eq!(
+            lines_from_file(directory.join("app-old.1.log")),
+            &input[9..18]
+        );
+        assert_eq!(lines_from_file(&path), &input[18..]);
+    }
+
+    #[tokio::test]
+    async fn deletes_rotated_files_after_retention() {
+        trace_init();
+
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("other.log"), "other\n").unwrap();
+
+        let config = FileSinkConfig {
+            path: path.to_str().unwrap().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::None,
+            rotation: rotation(100, Some(0)),
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _) = random_lines_with_stream(10, 20);
+
+        let events = input.clone().into_iter().map(|line| {
+            let mut event = Event::from(line);
+            event.as_mut_log().insert("part", "old");
+            event
+        });
+        sink.run(Box::pin(stream::iter(events))).await.unwrap();
+
+        let mut names = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["app.log", "other.log"]);
+        assert_eq!(lines_from_file(&path), &input[18..]);
+    }
 }
@@ -0,0 +1,239 @@
+use crate::{
+    internal_events::{FileRetentionDeleted, FileRetentionFailed},
+    template::Template,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    path::{self, Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::fs;
+
+lazy_static! {
+    /// The field references and strftime specifiers of a template.
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{\{[^}]*\}\}|%[-_0^#.:\d]*[a-zA-Z%+]").unwrap();
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RotationConfig {
+    pub max_bytes: Option<u64>,
+    pub interval_secs: Option<u64>,
+    #[serde(default = "default_suffix")]
+    pub suffix: Template,
+    pub retention_secs: Option<u64>,
+}
+
+fn default_suffix() -> Template {
+    Template::try_from("-%Y-%m-%d-%H%M%S").unwrap()
+}
+
+impl RotationConfig {
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.max_bytes.is_none() && self.interval_secs.is_none() {
+            return Err("Rotation requires `max_bytes` or `interval_secs`".into());
+        }
+        if self.max_bytes == Some(0) || self.interval_secs == Some(0) {
+            return Err("Rotation `max_bytes` and `interval_secs` must be greater than 0".into());
+        }
+        Ok(())
+    }
+
+    /// Whether a file of `size` bytes, last written at `modified`, is to be
+    /// rotated before `len` more bytes are written to it at `now`. Files
+    /// are rotated once they would outgrow `max_bytes`, and when `now` falls
+    /// into another `interval_secs` long window since the epoch than the
+    /// last write did.
+    pub fn is_due(&self, size: u64, modified: SystemTime, len: usize, now: SystemTime) -> bool {
+        if size == 0 {
+            return false;
+        }
+        let full = self
+            .max_bytes
+            .map_or(false, |max_bytes| size + len as u64 > max_bytes);
+        let expired = self.interval_secs.map_or(false, |interval| {
+            window(modified, interval) != window(now, interval)
+        });
+        full || expired
+    }
+
+    pub fn retention(&self) -> Option<Duration> {
+        self.retention_secs.map(Duration::from_secs)
+    }
+}
+
+fn window(time: SystemTime, interval: u64) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() / interval)
+        .unwrap_or(0)
+}
+
+fn split_name(path: &Path) -> (String, String) {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (stem, extension)
+}
+
+/// The path `path` is renamed to when rotated, with the rendered `suffix`
+/// inserted before its extension, followed by a counter if a file of that
+/// name already exists.
+pub async fn rotated_path(path: &Path, suffix: &str) -> PathBuf {
+    let (stem, extension) = split_name(path);
+    let suffix = suffix.replace(path::is_separator, "_");
+
+    let mut count = 0;
+    loop {
+        let counter = match count {
+            0 => String::new(),
+            count => format!(".{}", count),
+        };
+        let candidate = path.with_file_name(format!("{}{}{}{}", stem, suffix, counter, extension));
+        if fs::metadata(&candidate).await.is_err() {
+            return candidate;
+        }
+        count += 1;
+    }
+}
+
+/// Matches the names of the files which may have been rotated from `path`,
+/// with any value in place of the placeholders of the `suffix` template.
+fn rotated_names(path: &Path, suffix: &Template) -> Regex {
+    let (stem, extension) = split_name(path);
+    let src = suffix.get_ref();
+
+    let mut pattern = format!("^{}", regex::escape(&stem));
+    let mut last = 0;
+    for placeholder in PLACEHOLDER.find_iter(src) {
+        pattern.push_str(&regex::escape(&src[last..placeholder.start()]));
+        pattern.push_str(".+");
+        last = placeholder.end();
+    }
+    pattern.push_str(&regex::escape(&src[last..]));
+    pattern.push_str(r"(\.\d+)?");
+    pattern.push_str(&regex::escape(&extension));
+    pattern.push('$');
+
+    Regex::new(&pattern).expect("escaped pattern is valid")
+}
+
+/// Deletes the files rotated from `path` which were last modified longer
+/// than `retention` ago.
+pub async fn delete_expired(path: &Path, suffix: &Template, retention: Duration) {
+    if let Err(error) = try_delete_expired(path, suffix, retention).await {
+        emit!(FileRetentionFailed { path, error });
+    }
+}
+
+async fn try_delete_expired(
+    path: &Path,
+    suffix: &Template,
+    retention: Duration,
+) -> std::io::Result<()> {
+    let directory = match path.parent() {
+        Some(parent) if parent != Path::new("") => parent,
+        _ => Path::new("."),
+    };
+    let names = rotated_names(path, suffix);
+
+    let mut entries = fs::read_dir(directory).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        if Some(name.as_os_str()) == path.file_name() {
+            continue;
+        }
+        if !name.to_str().map_or(false, |name| names.is_match(name)) {
+            continue;
+        }
+
+        let metadata = entry.metadata().await?;
+        let expired = metadata
+            .modified()?
+            .elapsed()
+            .map_or(false, |age| age > retention);
+        if metadata.is_file() && expired {
+            let path = entry.path();
+            fs::remove_file(&path).await?;
+            emit!(FileRetentionDeleted { path: &path });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_bytes: Option<u64>, interval_secs: Option<u64>) -> RotationConfig {
+        RotationConfig {
+            max_bytes,
+            interval_secs,
+            suffix: default_suffix(),
+            retention_secs: None,
+        }
+    }
+
+    #[test]
+    fn validates_config() {
+        assert!(config(None, None).validate().is_err());
+        assert!(config(Some(0), None).validate().is_err());
+        assert!(config(None, Some(0)).validate().is_err());
+        assert!(config(Some(100), None).validate().is_ok());
+        assert!(config(None, Some(3600)).validate().is_ok());
+    }
+
+    #[test]
+    fn rotates_when_full() {
+        let config = config(Some(100), None);
+        let now = SystemTime::now();
+        assert!(!config.is_due(0, now, 200, now));
+        assert!(!config.is_due(50, now, 50, now));
+        assert!(config.is_due(50, now, 51, now));
+    }
+
+    #[test]
+    fn rotates_per_interval() {
+        let config = config(None, Some(3600));
+        let hour = |hours: u64| UNIX_EPOCH + Duration::from_secs(hours * 3600);
+        assert!(!config.is_due(10, hour(10), 10, hour(10) + Duration::from_secs(3599)));
+        assert!(config.is_due(10, hour(10), 10, hour(11)));
+        assert!(!config.is_due(0, hour(10), 10, hour(11)));
+    }
+
+    #[test]
+    fn matches_rotated_names() {
+        let suffix = Template::try_from("-{{ host }}-%Y%m%d").unwrap();
+        let names = rotated_names(Path::new("/var/log/app.log"), &suffix);
+
+        assert!(names.is_match("app-localhost-20201014.log"));
+        assert!(names.is_match("app-localhost-20201014.2.log"));
+        assert!(!names.is_match("app.log"));
+        assert!(!names.is_match("app-localhost-20201014.log.gz"));
+        assert!(!names.is_match("other-localhost-20201014.log"));
+    }
+
+    #[tokio::test]
+    async fn numbers_rotated_paths() {
+        let directory = crate::test_util::temp_dir();
+        let path = directory.join("app.log");
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let rotated = rotated_path(&path, "-a/b").await;
+        assert_eq!(rotated, directory.join("app-a_b.log"));
+
+        std::fs::write(&rotated, "").unwrap();
+        assert_eq!(
+            rotated_path(&path, "-a/b").await,
+            directory.join("app-a_b.1.log")
+        );
+    }
+}
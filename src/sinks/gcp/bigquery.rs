@@ -0,0 +1,842 @@
+//! Writes logs to BigQuery tables with the Storage Write API. The rows of each
+//! batch are appended in a single request, so that a batch lands either
+//! entirely or not at all, to a committed stream which is kept for the table.
+//! Each append is made at the offset the stream ends at, so that the retries
+//! of a batch are rejected rather than writing the rows twice.
+
+use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    dns,
+    event::{Event, LogEvent, Value},
+    http::HttpClient,
+    internal_events::{GcpBigqueryEventEncoded, GcpBigqueryInvalidField, GcpBigqueryMissingKeys},
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, BatchSettings, PartitionBatchSink, PartitionBuffer,
+            PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, UriParseError, VectorSink,
+    },
+    template::{Template, TemplateError},
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsOptions, TlsSettings},
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use http::{Request, Uri};
+use hyper::Body;
+use prost::encoding;
+use prost_types::{field_descriptor_proto, DescriptorProto, FieldDescriptorProto};
+use proto::{
+    append_rows_request, append_rows_response, big_query_write_client::BigQueryWriteClient,
+    write_stream, AppendRowsRequest, CreateWriteStreamRequest, ProtoRows, ProtoSchema, WriteStream,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::{net::TcpStream, sync::Mutex as AsyncMutex};
+use tonic::{
+    transport::{Channel, Endpoint},
+    Code, Status,
+};
+use tower::{Service, ServiceBuilder};
+use tracing_futures::Instrument;
+
+mod proto {
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/google.cloud.bigquery.storage.v1.rs"
+    ));
+}
+
+const ENDPOINT: &str = "https://bigquerystorage.googleapis.com";
+const REST_URL: &str = "https://bigquery.googleapis.com/bigquery/v2";
+
+/// The code of `google.rpc.Status` for rows already appended at an offset.
+const ALREADY_EXISTS: i32 = 6;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct BigquerySinkConfig {
+    pub project: String,
+    pub dataset: String,
+    pub table: String,
+    /// The columns of the tables, written from the fields of the events.
+    pub schema: BTreeMap<String, ColumnConfig>,
+    pub endpoint: Option<String>,
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+    /// The field the column is written from, if not the field named after
+    /// the column.
+    pub field: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    String,
+    Int64,
+    Float64,
+    Bool,
+    Timestamp,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<BigquerySinkConfig>("gcp_bigquery")
+}
+
+impl GenerateConfig for BigquerySinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"project = "my-project"
+            dataset = "my-dataset"
+            table = "logs_{{ application }}"
+            schema.timestamp.type = "timestamp"
+            schema.message.type = "string"
+            credentials_path = "/path/to/credentials.json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("dataset template parse error: {}", source))]
+    DatasetTemplate { source: TemplateError },
+    #[snafu(display("table template parse error: {}", source))]
+    TableTemplate { source: TemplateError },
+    #[snafu(display("The schema has no columns."))]
+    EmptySchema,
+    #[snafu(display("Invalid column name {:?}, expected letters, digits and `_`.", name))]
+    InvalidColumn { name: String },
+    #[snafu(display("The Storage Write API requires credentials rather than an api_key."))]
+    MissingCredentials,
+    #[snafu(display("Invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Missing host in endpoint"))]
+    MissingHost,
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("Configured project not found"))]
+    ProjectNotFound,
+}
+
+#[derive(Debug, Snafu)]
+enum ConnectError {
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    Tls { source: crate::tls::TlsError },
+}
+
+#[derive(Debug, Snafu)]
+enum RequestError {
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: tonic::transport::Error },
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: Status },
+    #[snafu(display("Failed to append rows (code {}): {}", code, message))]
+    Append { code: i32, message: String },
+    #[snafu(display("No response to the appended rows."))]
+    NoAppendResponse,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_bigquery")]
+impl SinkConfig for BigquerySinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let encoder = RowEncoder {
+            project: self.project.clone(),
+            dataset: Template::try_from(self.dataset.as_str()).context(DatasetTemplate)?,
+            table: Template::try_from(self.table.as_str()).context(TableTemplate)?,
+            schema: Schema::new(&self.schema)?,
+        };
+        let creds = self
+            .auth
+            .make_credentials(Scope::CloudPlatform)
+            .await?
+            .ok_or(BuildError::MissingCredentials)?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let healthcheck = healthcheck(
+            HttpClient::new(tls.clone())?,
+            self.project.clone(),
+            creds.clone(),
+        )
+        .boxed();
+
+        // Append requests are limited to 10MB.
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(8u64))
+            .events(10_000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+        let ordered = request.ordered;
+
+        let client = Client::new(
+            self.endpoint.as_deref().unwrap_or(ENDPOINT),
+            tls,
+            creds,
+            encoder.schema.descriptor(),
+        )?;
+        let service = ServiceBuilder::new()
+            .map(BigqueryRequest::from)
+            .settings(request, BigqueryRetryLogic)
+            .service(client);
+
+        let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+        let sink = PartitionBatchSink::new(service, buffer, batch.timeout, cx.acker())
+            .ordered(ordered)
+            .sink_map_err(|error| error!(message = "Fatal gcp_bigquery sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encoder.encode_event(event)).map(Ok));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "gcp_bigquery"
+    }
+}
+
+async fn healthcheck(
+    client: HttpClient,
+    project: String,
+    creds: GcpCredentials,
+) -> crate::Result<()> {
+    let uri = format!("{}/projects/{}/datasets?maxResults=1", REST_URL, project)
+        .parse::<Uri>()
+        .context(UriParseError)?;
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+    creds.apply(&mut request);
+
+    let response = client.send(request).await?;
+    healthcheck_response(Some(creds), HealthcheckError::ProjectNotFound.into())(response)
+}
+
+/// The columns of the tables, in the order of the fields of the protocol
+/// buffers the rows are written as.
+#[derive(Debug)]
+struct Schema {
+    columns: Vec<Column>,
+}
+
+#[derive(Debug)]
+struct Column {
+    name: String,
+    field: String,
+    column_type: ColumnType,
+}
+
+impl Schema {
+    fn new(columns: &BTreeMap<String, ColumnConfig>) -> Result<Self, BuildError> {
+        if columns.is_empty() {
+            return Err(BuildError::EmptySchema);
+        }
+        let columns = columns
+            .iter()
+            .map(|(name, column)| {
+                let valid = !name.starts_with(|c: char| c.is_ascii_digit())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid || name.is_empty() {
+                    return Err(BuildError::InvalidColumn { name: name.clone() });
+                }
+                Ok(Column {
+                    name: name.clone(),
+                    field: column.field.clone().unwrap_or_else(|| name.clone()),
+                    column_type: column.column_type,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { columns })
+    }
+
+    fn descriptor(&self) -> DescriptorProto {
+        use field_descriptor_proto::{Label, Type};
+
+        let field = self
+            .columns
+            .iter()
+            .zip(1..)
+            .map(|(column, number)| {
+                let field_type = match column.column_type {
+                    ColumnType::String | ColumnType::Json => Type::String,
+                    ColumnType::Int64 | ColumnType::Timestamp => Type::Int64,
+                    ColumnType::Float64 => Type::Double,
+                    ColumnType::Bool => Type::Bool,
+                };
+                FieldDescriptorProto {
+                    name: Some(column.name.clone()),
+                    number: Some(number),
+                    label: Some(Label::Optional.into()),
+                    r#type: Some(field_type.into()),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        DescriptorProto {
+            name: Some("Row".into()),
+            field,
+            ..Default::default()
+        }
+    }
+
+    /// Serializes the fields of the event as a row. Missing fields, and
+    /// fields which don't match the type of their column, are written as
+    /// nulls.
+    fn encode(&self, log: &LogEvent) -> Vec<u8> {
+        let mut row = Vec::new();
+        for (column, tag) in self.columns.iter().zip(1..) {
+            let value = match log.get(&column.field) {
+                None | Some(Value::Null) => continue,
+                Some(value) => value,
+            };
+            if !column.encode(tag, value, &mut row) {
+                emit!(GcpBigqueryInvalidField {
+                    column: &column.name,
+                    field: &column.field,
+                });
+            }
+        }
+        row
+    }
+}
+
+impl Column {
+    fn encode(&self, tag: u32, value: &Value, row: &mut Vec<u8>) -> bool {
+        match self.column_type {
+            ColumnType::String => encoding::string::encode(tag, &value.to_string_lossy(), row),
+            ColumnType::Json => {
+                let json = serde_json::to_string(value).expect("Serializing a value doesn't fail.");
+                encoding::string::encode(tag, &json, row)
+            }
+            ColumnType::Int64 => match value {
+                Value::Integer(value) => encoding::int64::encode(tag, value, row),
+                Value::Bytes(bytes) => match String::from_utf8_lossy(bytes).parse() {
+                    Ok(value) => encoding::int64::encode(tag, &value, row),
+                    Err(_) => return false,
+                },
+                _ => return false,
+            },
+            ColumnType::Float64 => match value {
+                Value::Float(value) => encoding::double::encode(tag, value, row),
+                Value::Integer(value) => encoding::double::encode(tag, &(*value as f64), row),
+                Value::Bytes(bytes) => match String::from_utf8_lossy(bytes).parse() {
+                    Ok(value) => encoding::double::encode(tag, &value, row),
+                    Err(_) => return false,
+                },
+                _ => return false,
+            },
+            ColumnType::Bool => match value {
+                Value::Boolean(value) => encoding::bool::encode(tag, value, row),
+                Value::Bytes(bytes) => match String::from_utf8_lossy(bytes).parse() {
+                    Ok(value) => encoding::bool::encode(tag, &value, row),
+                    Err(_) => return false,
+                },
+                _ => return false,
+            },
+            // Timestamps are written as microseconds since the epoch.
+            ColumnType::Timestamp => {
+                let timestamp = match value {
+                    Value::Timestamp(timestamp) => *timestamp,
+                    Value::Bytes(bytes) => {
+                        match DateTime::parse_from_rfc3339(&String::from_utf8_lossy(bytes)) {
+                            Ok(timestamp) => timestamp.with_timezone(&Utc),
+                            Err(_) => return false,
+                        }
+                    }
+                    _ => return false,
+                };
+                let micros = timestamp.timestamp() * 1_000_000
+                    + i64::from(timestamp.timestamp_subsec_micros());
+                encoding::int64::encode(tag, &micros, row)
+            }
+        }
+        true
+    }
+}
+
+struct RowEncoder {
+    project: String,
+    dataset: Template,
+    table: Template,
+    schema: Schema,
+}
+
+impl RowEncoder {
+    /// The row of the event, partitioned by the table it's written to.
+    fn encode_event(&self, event: Event) -> Option<PartitionInnerBuffer<Bytes, Bytes>> {
+        let render = |template: &Template| {
+            template
+                .render_string(&event)
+                .map_err(|keys| emit!(GcpBigqueryMissingKeys { keys: &keys }))
+                .ok()
+        };
+        let table = format!(
+            "projects/{}/datasets/{}/tables/{}",
+            self.project,
+            render(&self.dataset)?,
+            render(&self.table)?
+        );
+
+        let row = self.schema.encode(event.as_log());
+        emit!(GcpBigqueryEventEncoded {
+            byte_size: row.len()
+        });
+        Some(PartitionInnerBuffer::new(row.into(), table.into()))
+    }
+}
+
+/// The rows of a batch, with the append of them shared by the retries of the
+/// batch.
+#[derive(Clone, Debug)]
+struct BigqueryRequest {
+    table: String,
+    rows: Vec<Bytes>,
+    /// The stream and offset of an append which may have been written.
+    pending: Arc<Mutex<Option<(String, i64)>>>,
+}
+
+/// The committed stream rows are appended to for a table.
+#[derive(Debug)]
+struct TableStream {
+    name: String,
+    offset: i64,
+}
+
+impl From<PartitionInnerBuffer<Vec<Bytes>, Bytes>> for BigqueryRequest {
+    fn from(buffer: PartitionInnerBuffer<Vec<Bytes>, Bytes>) -> Self {
+        let (rows, table) = buffer.into_parts();
+        Self {
+            table: String::from_utf8_lossy(&table).into_owned(),
+            rows,
+            pending: Default::default(),
+        }
+    }
+}
+
+/// Connects lazily, and shares the connection between requests as gRPC
+/// multiplexes them over it.
+#[derive(Clone)]
+struct Client {
+    endpoint: Endpoint,
+    connector: Connector,
+    creds: GcpCredentials,
+    descriptor: DescriptorProto,
+    client: Arc<Mutex<Option<BigQueryWriteClient<Channel>>>>,
+    /// The streams of the tables, locked while appending to them as each
+    /// append is made at the offset the previous one ended at.
+    streams: Arc<Mutex<HashMap<String, Arc<AsyncMutex<Option<TableStream>>>>>>,
+}
+
+impl Client {
+    fn new(
+        endpoint: &str,
+        tls: TlsSettings,
+        creds: GcpCredentials,
+        descriptor: DescriptorProto,
+    ) -> crate::Result<Self> {
+        let uri = endpoint
+            .parse::<Uri>()
+            .context(InvalidEndpoint { endpoint })?;
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_owned();
+        let (port, tls) = match uri.scheme_str() {
+            Some("http") => (80, MaybeTlsSettings::Raw(())),
+            // gRPC is only served over HTTP/2.
+            _ => (443, tls.alpn_protocols(b"\x02h2").into()),
+        };
+
+        Ok(Self {
+            endpoint: Channel::builder(uri.clone()),
+            connector: Connector {
+                host,
+                port: uri.port_u16().unwrap_or(port),
+                tls,
+            },
+            creds,
+            descriptor,
+            client: Arc::new(Mutex::new(None)),
+            streams: Default::default(),
+        })
+    }
+
+    async fn client(&self) -> Result<BigQueryWriteClient<Channel>, RequestError> {
+        let client = self.client.lock().unwrap().clone();
+        if let Some(client) = client {
+            return Ok(client);
+        }
+
+        let channel = self
+            .endpoint
+            .connect_with_connector(self.connector.clone())
+            .await
+            .context(Connect)?;
+        let client = BigQueryWriteClient::new(channel);
+        *self.client.lock().unwrap() = Some(client.clone());
+        Ok(client)
+    }
+
+    /// The request, authorized and routed to the resource it's about.
+    fn request<T>(&self, message: T, param: &str, resource: &str) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        let metadata = request.metadata_mut();
+        metadata.insert(
+            "authorization",
+            self.creds
+                .authorization()
+                .parse()
+                .expect("Tokens are valid metadata."),
+        );
+        let params = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair(param, resource)
+            .finish();
+        metadata.insert(
+            "x-goog-request-params",
+            params
+                .parse()
+                .expect("URL encoded parameters are valid metadata."),
+        );
+        request
+    }
+
+    async fn write(self, request: BigqueryRequest) -> Result<(), RequestError> {
+        let mut client = self.client().await?;
+
+        // The stream of an append which may have been written is left to the
+        // retries of its batch, which either find the rows already written
+        // at the offset or write them there.
+        let pending = request.pending.lock().unwrap().clone();
+        if let Some((write_stream, offset)) = pending {
+            return self
+                .append(&mut client, &write_stream, offset, &request.rows)
+                .await;
+        }
+
+        let slot = Arc::clone(
+            self.streams
+                .lock()
+                .unwrap()
+                .entry(request.table.clone())
+                .or_insert_with(|| Arc::new(AsyncMutex::new(None))),
+        );
+        let mut slot = slot.lock().await;
+        let table_stream = match slot.take() {
+            Some(table_stream) => table_stream,
+            None => {
+                let message = CreateWriteStreamRequest {
+                    parent: request.table.clone(),
+                    write_stream: Some(WriteStream {
+                        r#type: write_stream::Type::Committed.into(),
+                        ..Default::default()
+                    }),
+                };
+                let name = client
+                    .create_write_stream(self.request(message, "parent", &request.table))
+                    .await
+                    .context(Request)?
+                    .into_inner()
+                    .name;
+                TableStream { name, offset: 0 }
+            }
+        };
+
+        let result = self
+            .append(
+                &mut client,
+                &table_stream.name,
+                table_stream.offset,
+                &request.rows,
+            )
+            .await;
+        match &result {
+            Ok(()) => {
+                *slot = Some(TableStream {
+                    offset: table_stream.offset + request.rows.len() as i64,
+                    ..table_stream
+                });
+            }
+            // The rows were rejected, so the stream still ends at the offset.
+            Err(RequestError::Append { code, .. }) if *code == Code::InvalidArgument as i32 => {
+                *slot = Some(table_stream)
+            }
+            // The stream itself failed, and the next batches are appended to
+            // a new one.
+            Err(RequestError::Append { .. }) => (),
+            // The rows may have been written, so the stream is left to the
+            // retries of the batch and the next batches are appended to a new
+            // one.
+            Err(_) => {
+                *request.pending.lock().unwrap() = Some((table_stream.name, table_stream.offset));
+            }
+        }
+        result
+    }
+
+    async fn append(
+        &self,
+        client: &mut BigQueryWriteClient<Channel>,
+        write_stream: &str,
+        offset: i64,
+        rows: &[Bytes],
+    ) -> Result<(), RequestError> {
+        let message = AppendRowsRequest {
+            write_stream: write_stream.to_owned(),
+            // The rows of a retry are already written if they were at the
+            // same offset.
+            offset: Some(offset),
+            rows: Some(append_rows_request::Rows::ProtoRows(
+                append_rows_request::ProtoData {
+                    writer_schema: Some(ProtoSchema {
+                        proto_descriptor: Some(self.descriptor.clone()),
+                    }),
+                    rows: Some(ProtoRows {
+                        serialized_rows: rows.iter().map(|row| row.to_vec()).collect(),
+                    }),
+                },
+            )),
+            trace_id: String::new(),
+        };
+        let messages = stream::iter(vec![message]);
+
+        let mut responses = client
+            .append_rows(self.request(messages, "write_stream", write_stream))
+            .await
+            .context(Request)?
+            .into_inner();
+        match responses.message().await.context(Request)? {
+            Some(response) => match response.response {
+                Some(append_rows_response::Response::Error(error))
+                    if error.code != ALREADY_EXISTS =>
+                {
+                    Err(RequestError::Append {
+                        code: error.code,
+                        message: error.message,
+                    })
+                }
+                _ => Ok(()),
+            },
+            None => Err(RequestError::NoAppendResponse),
+        }
+    }
+}
+
+impl Service<BigqueryRequest> for Client {
+    type Response = ();
+    type Error = RequestError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: BigqueryRequest) -> Self::Future {
+        let this = self.clone();
+        Box::pin(this.write(request).instrument(info_span!("request")))
+    }
+}
+
+/// Opens the connections of the client, with the TLS settings of the sink.
+#[derive(Clone)]
+struct Connector {
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+}
+
+impl Service<Uri> for Connector {
+    type Response = MaybeTlsStream<TcpStream>;
+    type Error = ConnectError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let ip = dns::Resolver
+                .lookup_ip(this.host.clone())
+                .await
+                .context(Dns)?
+                .next()
+                .ok_or(ConnectError::NoAddresses)?;
+            let addr = SocketAddr::new(ip, this.port);
+            this.tls.connect(&this.host, &addr).await.context(Tls)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BigqueryRetryLogic;
+
+impl RetryLogic for BigqueryRetryLogic {
+    type Error = RequestError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            RequestError::Connect { .. } | RequestError::NoAppendResponse => true,
+            RequestError::Request { source } => !matches!(
+                source.code(),
+                Code::InvalidArgument
+                    | Code::NotFound
+                    | Code::PermissionDenied
+                    | Code::Unauthenticated
+                    | Code::Unimplemented
+            ),
+            // Rows not matching the schema are rejected by every attempt.
+            RequestError::Append { code, .. } => *code != Code::InvalidArgument as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::log_schema;
+    use chrono::TimeZone;
+    use prost::Message;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<BigquerySinkConfig>();
+    }
+
+    /// A row as written by a generated protocol buffer.
+    #[derive(Clone, PartialEq, Message)]
+    struct Row {
+        #[prost(string, optional, tag = "1")]
+        host: Option<String>,
+        #[prost(string, optional, tag = "2")]
+        labels: Option<String>,
+        #[prost(double, optional, tag = "3")]
+        latency: Option<f64>,
+        #[prost(int64, optional, tag = "4")]
+        status: Option<i64>,
+        #[prost(int64, optional, tag = "5")]
+        timestamp: Option<i64>,
+    }
+
+    fn schema() -> Schema {
+        let columns = toml::from_str(
+            r#"
+            host.type = "string"
+            labels = { type = "json", field = "kubernetes.labels" }
+            latency.type = "float64"
+            status.type = "int64"
+            timestamp.type = "timestamp"
+            "#,
+        )
+        .unwrap();
+        Schema::new(&columns).unwrap()
+    }
+
+    #[test]
+    fn describes_the_columns() {
+        let descriptor = schema().descriptor();
+        let fields = descriptor
+            .field
+            .iter()
+            .map(|field| (field.name().to_owned(), field.number(), field.r#type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("host".into(), 1, field_descriptor_proto::Type::String),
+                ("labels".into(), 2, field_descriptor_proto::Type::String),
+                ("latency".into(), 3, field_descriptor_proto::Type::Double),
+                ("status".into(), 4, field_descriptor_proto::Type::Int64),
+                ("timestamp".into(), 5, field_descriptor_proto::Type::Int64),
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_rows_from_fields() {
+        let mut event = Event::from("hello");
+        let log = event.as_mut_log();
+        log.insert("host", "web-1");
+        log.insert("kubernetes.labels.app", "shop");
+        log.insert("status", "404");
+        log.insert("latency", "slow");
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.ymd(2021, 3, 1).and_hms_micro(12, 0, 0, 250),
+        );
+
+        let row = Row::decode(&schema().encode(event.as_log())[..]).unwrap();
+        assert_eq!(
+            row,
+            Row {
+                host: Some("web-1".into()),
+                labels: Some(r#"{"app":"shop"}"#.into()),
+                latency: None,
+                status: Some(404),
+                timestamp: Some(1_614_600_000_000_250),
+            }
+        );
+    }
+
+    #[test]
+    fn partitions_rows_by_table() {
+        let encoder = RowEncoder {
+            project: "my-project".into(),
+            dataset: Template::try_from("logs").unwrap(),
+            table: Template::try_from("{{ application }}_events").unwrap(),
+            schema: schema(),
+        };
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("application", "shop");
+        let (_, table) = encoder.encode_event(event).unwrap().into_parts();
+        assert_eq!(
+            table,
+            "projects/my-project/datasets/logs/tables/shop_events"
+        );
+
+        assert!(encoder.encode_event(Event::from("hello")).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_columns() {
+        let columns =
+            toml::from_str::<BTreeMap<String, ColumnConfig>>(r#""user.name".type = "string""#)
+                .unwrap();
+        assert!(matches!(
+            Schema::new(&columns),
+            Err(BuildError::InvalidColumn { .. })
+        ));
+    }
+}
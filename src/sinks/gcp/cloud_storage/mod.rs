@@ -7,8 +7,9 @@ use crate::{
         util::{
             encoding::{EncodingConfig, EncodingConfiguration},
             retries::{RetryAction, RetryLogic},
-            BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
-            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+            BatchConfig, BatchSettings, Buffer, CloseWith, Compression, Concurrency,
+            PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt,
+            TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -27,10 +28,14 @@ use hyper::{
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::{collections::HashMap, convert::TryFrom, task::Poll};
+use std::{collections::HashMap, convert::TryFrom, task::Poll, time::Duration};
 use tower::{Service, ServiceBuilder};
 use uuid::Uuid;
 
+mod resumable;
+
+use resumable::{ResumableConfig, ResumableRetryLogic, ResumableSink};
+
 const NAME: &str = "gcp_cloud_storage";
 const BASE_URL: &str = "https://storage.googleapis.com/";
 
@@ -67,6 +72,8 @@ pub struct GcsSinkConfig {
     batch: BatchConfig,
     #[serde(default)]
     request: TowerRequestConfig,
+    #[serde(default)]
+    resumable: ResumableConfig,
     #[serde(flatten)]
     auth: GcpAuthConfig,
     tls: Option<TlsOptions>,
@@ -87,6 +94,7 @@ fn default_config(e: Encoding) -> GcsSinkConfig {
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
+        resumable: Default::default(),
         auth: Default::default(),
         tls: Default::default(),
     }
@@ -186,14 +194,14 @@ enum HealthcheckError {
 }
 
 impl GcsSink {
-    async fn new(config: &GcsSinkConfig, _cx: &SinkContext) -> crate::Result<Self> {
+    async fn new(config: &GcsSinkConfig, cx: &SinkContext) -> crate::Result<Self> {
         let creds = config
             .auth
             .make_credentials(Scope::DevStorageReadWrite)
             .await?;
         let settings = RequestSettings::new(config)?;
         let tls = TlsSettings::from_options(&config.tls)?;
-        let client = HttpClient::new(tls)?;
+        let client = HttpClient::new(tls)?.with_headers(config.request.headers(cx)?);
         let base_url = format!("{}{}/", BASE_URL, config.bucket);
         let bucket = config.bucket.clone();
         Ok(GcsSink {
@@ -207,6 +215,7 @@ impl GcsSink {
 
     fn service(self, config: &GcsSinkConfig, cx: &SinkContext) -> crate::Result<VectorSink> {
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let ordered = request.ordered;
         let encoding = config.encoding.clone();
 
         let batch = BatchSettings::default()
@@ -218,19 +227,43 @@ impl GcsSink {
         let key_prefix = Template::try_from(key_prefix).context(KeyPrefixTemplate)?;
 
         let settings = self.settings.clone();
-
-        let svc = ServiceBuilder::new()
-            .map(move |req| RequestWrapper::new(req, settings.clone()))
-            .settings(request, GcsRetryLogic)
-            .service(self);
+        let build = move |req| RequestWrapper::new(req, settings.clone());
 
         let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
 
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
-            .with_flat_map(move |e| stream::iter(encode_event(e, &key_prefix, &encoding)).map(Ok));
+        let sink = if config.resumable.enabled {
+            // Requests wait for the chunks holding their data to be written,
+            // or for their upload to be finalized.
+            let mut request = request;
+            request.timeout += Duration::from_secs(config.resumable.object_timeout_secs);
+            let resumable = ResumableSink::new(self, &config.resumable);
+            let closer = resumable.closer();
+            let svc = ServiceBuilder::new()
+                .map(build)
+                .settings(request, ResumableRetryLogic)
+                .service(resumable);
+            let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                .ordered(ordered)
+                .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
+                .with_flat_map(move |e| {
+                    stream::iter(encode_event(e, &key_prefix, &encoding)).map(Ok)
+                });
+            VectorSink::Sink(Box::new(CloseWith::new(sink, closer)))
+        } else {
+            let svc = ServiceBuilder::new()
+                .map(build)
+                .settings(request, GcsRetryLogic)
+                .service(self);
+            let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                .ordered(ordered)
+                .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
+                .with_flat_map(move |e| {
+                    stream::iter(encode_event(e, &key_prefix, &encoding)).map(Ok)
+                });
+            VectorSink::Sink(Box::new(sink))
+        };
 
-        Ok(VectorSink::Sink(Box::new(sink)))
+        Ok(sink)
     }
 
     async fn healthcheck(self) -> crate::Result<()> {
@@ -259,46 +292,62 @@ impl Service<RequestWrapper> for GcsSink {
     }
 
     fn call(&mut self, request: RequestWrapper) -> Self::Future {
-        let settings = request.settings;
+        let request = self.object_request(&request.key, &request.settings, request.body);
+        self.send(request)
+    }
+}
 
-        let uri = format!("{}{}", self.base_url, request.key)
-            .parse::<Uri>()
-            .unwrap();
+impl GcsSink {
+    /// A request writing `body` as the object `key`.
+    fn object_request(
+        &self,
+        key: &str,
+        settings: &RequestSettings,
+        body: Vec<u8>,
+    ) -> Request<Body> {
+        let uri = format!("{}{}", self.base_url, key).parse::<Uri>().unwrap();
         let mut builder = Request::put(uri);
         let headers = builder.headers_mut().unwrap();
-        headers.insert("content-type", settings.content_type);
+        headers.insert("content-type", settings.content_type.clone());
         headers.insert(
             "content-length",
-            HeaderValue::from_str(&format!("{}", request.body.len())).unwrap(),
+            HeaderValue::from_str(&format!("{}", body.len())).unwrap(),
         );
-        settings
-            .content_encoding
-            .map(|ce| headers.insert("content-encoding", ce));
-        settings.acl.map(|acl| headers.insert("x-goog-acl", acl));
-        headers.insert("x-goog-storage-class", settings.storage_class);
-        for (p, v) in settings.metadata {
-            headers.insert(p, v);
+        if let Some(ce) = &settings.content_encoding {
+            headers.insert("content-encoding", ce.clone());
+        }
+        if let Some(acl) = &settings.acl {
+            headers.insert("x-goog-acl", acl.clone());
+        }
+        headers.insert("x-goog-storage-class", settings.storage_class.clone());
+        for (p, v) in &settings.metadata {
+            headers.insert(p, v.clone());
         }
 
-        let mut request = builder.body(Body::from(request.body)).unwrap();
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    fn send(&self, mut request: Request<Body>) -> HttpClientFuture {
         if let Some(creds) = &self.creds {
             creds.apply(&mut request);
         }
-
-        self.client.call(request)
+        self.client.send(request)
     }
 }
 
 #[derive(Clone, Debug)]
 struct RequestWrapper {
     body: Vec<u8>,
+    /// The rendered `key_prefix` of the batch.
+    prefix: String,
     key: String,
     settings: RequestSettings,
 }
 
 impl RequestWrapper {
     fn new(req: PartitionInnerBuffer<Vec<u8>, Bytes>, settings: RequestSettings) -> Self {
-        let (body, key) = req.into_parts();
+        let (body, prefix) = req.into_parts();
+        let prefix = String::from_utf8_lossy(&prefix[..]).into_owned();
 
         // TODO: pull the seconds from the last event
         let filename = {
@@ -312,17 +361,13 @@ impl RequestWrapper {
             }
         };
 
-        let key = format!(
-            "{}{}.{}",
-            String::from_utf8_lossy(&key[..]),
-            filename,
-            settings.extension
-        );
+        let key = format!("{}{}.{}", prefix, filename, settings.extension);
 
         debug!(message = "Sending events.", bytes = ?body.len(), key = ?key);
 
         Self {
             body,
+            prefix,
             key,
             settings,
         }
@@ -0,0 +1,579 @@
+//! Streams the batches of each partition into Cloud Storage resumable
+//! uploads, so that objects are never held in memory as a whole. An upload
+//! is finalized, which makes its object visible, once it reaches
+//! `object_max_bytes` or is older than `object_timeout_secs`.
+//!
+//! The chunks of an upload have to be written in order, so the requests of a
+//! partition are written one at a time.
+//!
+//! The events of a request are acked once the chunks holding them are
+//! written, or for the last chunk, once the upload is finalized. An upload
+//! that fails to be finalized is cancelled, and the events not acked yet are
+//! sent again by the retries of their requests.
+
+use super::{GcsSink, RequestWrapper};
+use crate::{
+    http::HttpError,
+    sinks::util::retries::{RetryAction, RetryLogic},
+};
+use futures::future::{BoxFuture, FutureExt};
+use http::{header::LOCATION, StatusCode, Uri};
+use hyper::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{sync::oneshot, task::JoinHandle, time};
+use tower::Service;
+use tracing_futures::Instrument;
+
+/// Every chunk but the last one of an upload must be a multiple of this.
+const CHUNK_ALIGNMENT: usize = 256 * 1024;
+/// The size of the chunks written, as recommended by Cloud Storage.
+const CHUNK_SIZE: usize = 32 * CHUNK_ALIGNMENT;
+/// The status Cloud Storage replies with to chunks of unfinished uploads.
+const RESUME_INCOMPLETE: u16 = 308;
+/// How often writing the last chunk is tried before the upload is cancelled.
+const FINALIZE_ATTEMPTS: usize = 5;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct ResumableConfig {
+    pub enabled: bool,
+    pub object_max_bytes: usize,
+    pub object_timeout_secs: u64,
+}
+
+impl Default for ResumableConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            object_max_bytes: 5_000_000_000,
+            object_timeout_secs: 3600,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ResumableError {
+    #[snafu(display("Failed to start resumable upload: {}", source))]
+    StartUpload { source: HttpError },
+    #[snafu(display("Failed to upload chunk: {}", source))]
+    UploadChunk { source: HttpError },
+    #[snafu(display("Unexpected response status: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("Resumable upload session has no location"))]
+    MissingLocation,
+    #[snafu(display("The upload failed before the events were written"))]
+    Incomplete,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResumableRetryLogic;
+
+impl RetryLogic for ResumableRetryLogic {
+    type Error = ResumableError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            ResumableError::StartUpload { .. } | ResumableError::UploadChunk { .. } => true,
+            ResumableError::UnexpectedStatus { status } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            ResumableError::MissingLocation => false,
+            ResumableError::Incomplete => true,
+        }
+    }
+
+    fn should_retry_response(&self, _response: &Self::Response) -> RetryAction {
+        RetryAction::Successful
+    }
+}
+
+struct Upload {
+    key: String,
+    /// The session URI the chunks are written to.
+    session: Uri,
+    started: Instant,
+    state: tokio::sync::Mutex<UploadState>,
+}
+
+#[derive(Default)]
+struct UploadState {
+    /// Data not written to the upload yet.
+    pending: Vec<u8>,
+    /// The amount of data written to the upload.
+    offset: usize,
+    bytes: usize,
+    /// Told whether the data of a request was written, along with the offset
+    /// its data ends at.
+    waiters: Vec<(usize, oneshot::Sender<bool>)>,
+    /// No more data is added to closed uploads, they are finalized by the
+    /// request closing them.
+    closed: bool,
+}
+
+type Uploads = Mutex<HashMap<String, Arc<Upload>>>;
+
+#[derive(Clone)]
+pub struct ResumableSink {
+    sink: GcsSink,
+    object_max_bytes: usize,
+    uploads: Arc<Uploads>,
+    /// Set once the input ended, after which uploads are finalized right away.
+    closing: Arc<AtomicBool>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Lets the task finalizing timed out uploads know when the sink is gone.
+    _alive: Arc<()>,
+}
+
+impl ResumableSink {
+    pub fn new(sink: GcsSink, config: &ResumableConfig) -> Self {
+        let uploads = Arc::new(Mutex::new(HashMap::new()));
+        let closing = Arc::new(AtomicBool::new(false));
+        let alive = Arc::new(());
+        let task = tokio::spawn(finalize_timed_out(
+            sink.clone(),
+            Arc::clone(&uploads),
+            Arc::clone(&closing),
+            Arc::downgrade(&alive),
+            Duration::from_secs(config.object_timeout_secs),
+        ));
+        Self {
+            sink,
+            object_max_bytes: config.object_max_bytes,
+            uploads,
+            closing,
+            task: Arc::new(Mutex::new(Some(task))),
+            _alive: alive,
+        }
+    }
+
+    /// Returns the function finalizing all uploads once the input ended, its
+    /// future resolves when the open uploads are finalized or cancelled.
+    pub fn closer(&self) -> impl FnOnce() -> BoxFuture<'static, ()> + Send + 'static {
+        let closing = Arc::clone(&self.closing);
+        let task = Arc::clone(&self.task);
+        move || {
+            closing.store(true, Ordering::Relaxed);
+            let task = task.lock().unwrap().take();
+            async move {
+                if let Some(task) = task {
+                    let _ = task.await;
+                }
+            }
+            .boxed()
+        }
+    }
+
+    async fn upload_for(&self, request: &RequestWrapper) -> Result<Arc<Upload>, ResumableError> {
+        if let Some(upload) = self.uploads.lock().unwrap().get(&request.prefix) {
+            return Ok(Arc::clone(upload));
+        }
+
+        let mut start = self
+            .sink
+            .object_request(&request.key, &request.settings, Vec::new());
+        *start.method_mut() = http::Method::POST;
+        start
+            .headers_mut()
+            .insert("x-goog-resumable", "start".parse().unwrap());
+        let response = self.sink.send(start).await.context(StartUpload)?;
+        if response.status() != StatusCode::CREATED && !response.status().is_success() {
+            return Err(ResumableError::UnexpectedStatus {
+                status: response.status(),
+            });
+        }
+        let session = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| location.parse::<Uri>().ok())
+            .ok_or(ResumableError::MissingLocation)?;
+
+        let upload = Arc::new(Upload {
+            key: request.key.clone(),
+            session,
+            started: Instant::now(),
+            state: tokio::sync::Mutex::new(UploadState::default()),
+        });
+
+        // Another request of the same partition may have started one meanwhile.
+        let existing = {
+            let mut uploads = self.uploads.lock().unwrap();
+            match uploads.get(&request.prefix) {
+                Some(existing) => Some(Arc::clone(existing)),
+                None => {
+                    uploads.insert(request.prefix.clone(), Arc::clone(&upload));
+                    None
+                }
+            }
+        };
+        match existing {
+            Some(existing) => {
+                let mut state = upload.state.lock().await;
+                state.closed = true;
+                finalize(&self.sink, &upload, &mut state).await;
+                Ok(existing)
+            }
+            None => Ok(upload),
+        }
+    }
+
+    async fn send(self, request: RequestWrapper) -> Result<(), ResumableError> {
+        let written = loop {
+            let upload = self.upload_for(&request).await?;
+            let mut state = upload.state.lock().await;
+            // The upload may have timed out while waiting for it.
+            if !state.closed {
+                break self.write(&request, &upload, &mut state).await;
+            }
+        };
+        match written.await {
+            Ok(true) => Ok(()),
+            _ => Err(ResumableError::Incomplete),
+        }
+    }
+
+    /// Add the data of a request to the upload, writing the chunks ready,
+    /// and return the receiver told whether the data was written.
+    async fn write(
+        &self,
+        request: &RequestWrapper,
+        upload: &Arc<Upload>,
+        state: &mut UploadState,
+    ) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        state.pending.extend_from_slice(&request.body);
+        state.bytes += request.body.len();
+        let end = state.offset + state.pending.len();
+        state.waiters.push((end, tx));
+
+        if state.bytes >= self.object_max_bytes || self.closing.load(Ordering::Relaxed) {
+            state.closed = true;
+            close(&self.uploads, &request.prefix, upload);
+            finalize(&self.sink, upload, state).await;
+            return rx;
+        }
+
+        let size = state.pending.len() / CHUNK_ALIGNMENT * CHUNK_ALIGNMENT;
+        if size < CHUNK_SIZE {
+            return rx;
+        }
+
+        let offset = state.offset;
+        let chunk = state.pending[..size].to_vec();
+        let error = match upload_chunk(&self.sink, &upload.session, offset, chunk, None).await {
+            Ok(_) => {
+                state.advance(size);
+                return rx;
+            }
+            Err(error) => error,
+        };
+
+        // Some of the chunk may have been written all the same, the rest of
+        // it is written along with the following requests.
+        match persisted(&self.sink, &upload.session).await {
+            Ok(persisted) => {
+                state.advance(persisted.saturating_sub(offset).min(size));
+                warn!(
+                    message = "Failed to upload chunk, retrying with the next one.",
+                    %error,
+                    key = %upload.key,
+                    rate_limit_secs = 30,
+                );
+            }
+            Err(_) => {
+                warn!(
+                    message = "Failed to upload chunk, finalizing the upload.",
+                    %error,
+                    key = %upload.key,
+                    rate_limit_secs = 30,
+                );
+                state.closed = true;
+                close(&self.uploads, &request.prefix, upload);
+                finalize(&self.sink, upload, state).await;
+            }
+        }
+        rx
+    }
+}
+
+impl UploadState {
+    /// Mark `written` more bytes of the pending data as written, telling the
+    /// requests whose data is written in full so.
+    fn advance(&mut self, written: usize) {
+        self.offset += written;
+        self.pending.drain(..written);
+
+        let offset = self.offset;
+        let (done, waiting) = mem::take(&mut self.waiters)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(end, _)| *end <= offset);
+        self.waiters = waiting;
+        for (_, waiter) in done {
+            let _ = waiter.send(true);
+        }
+    }
+
+    /// Tell the requests not written yet that their data won't be.
+    fn fail(&mut self) {
+        for (_, waiter) in self.waiters.drain(..) {
+            let _ = waiter.send(false);
+        }
+    }
+}
+
+impl Service<RequestWrapper> for ResumableSink {
+    type Response = ();
+    type Error = ResumableError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestWrapper) -> Self::Future {
+        let sink = self.clone();
+        Box::pin(sink.send(request).instrument(info_span!("request")))
+    }
+}
+
+/// The `Content-Range` of a chunk written at `offset`, the total size being
+/// only known for the last one.
+fn content_range(offset: usize, len: usize, total: Option<usize>) -> String {
+    let total = total.map_or_else(|| "*".to_owned(), |total| total.to_string());
+    if len == 0 {
+        format!("bytes */{}", total)
+    } else {
+        format!("bytes {}-{}/{}", offset, offset + len - 1, total)
+    }
+}
+
+/// The amount of data written to the upload, from the `Range` Cloud Storage
+/// replies to chunks of unfinished uploads with.
+fn range_end(response: &Response<Body>) -> usize {
+    response
+        .headers()
+        .get("range")
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| range.rsplit('-').next())
+        .and_then(|end| end.parse::<usize>().ok())
+        .map_or(0, |end| end + 1)
+}
+
+async fn upload_chunk(
+    sink: &GcsSink,
+    session: &Uri,
+    offset: usize,
+    chunk: Vec<u8>,
+    total: Option<usize>,
+) -> Result<Response<Body>, ResumableError> {
+    let request = Request::put(session.clone())
+        .header("content-range", content_range(offset, chunk.len(), total))
+        .header("content-length", chunk.len())
+        .body(Body::from(chunk))
+        .unwrap();
+    let response = sink.send(request).await.context(UploadChunk)?;
+
+    let status = response.status();
+    let expected = if total.is_some() {
+        status.is_success()
+    } else {
+        status.as_u16() == RESUME_INCOMPLETE
+    };
+    if expected {
+        Ok(response)
+    } else {
+        Err(ResumableError::UnexpectedStatus { status })
+    }
+}
+
+/// Asks Cloud Storage for the amount of data written to the upload.
+async fn persisted(sink: &GcsSink, session: &Uri) -> Result<usize, ResumableError> {
+    let request = Request::put(session.clone())
+        .header("content-range", "bytes */*")
+        .header("content-length", 0)
+        .body(Body::empty())
+        .unwrap();
+    let response = sink.send(request).await.context(UploadChunk)?;
+    match response.status().as_u16() {
+        RESUME_INCOMPLETE => Ok(range_end(&response)),
+        _ => Err(ResumableError::UnexpectedStatus {
+            status: response.status(),
+        }),
+    }
+}
+
+fn close(uploads: &Uploads, prefix: &str, upload: &Arc<Upload>) {
+    let mut uploads = uploads.lock().unwrap();
+    if uploads
+        .get(prefix)
+        .map_or(false, |current| Arc::ptr_eq(current, upload))
+    {
+        uploads.remove(prefix);
+    }
+}
+
+/// Write the last chunk of a closed upload, or cancel it if it has no data or
+/// the last chunk can't be written.
+async fn finalize(sink: &GcsSink, upload: &Upload, state: &mut UploadState) {
+    if state.offset == 0 && state.pending.is_empty() {
+        cancel(sink, upload).await;
+        // Only requests without any data can be waiting.
+        state.advance(0);
+        return;
+    }
+
+    let mut backoff = Duration::from_secs(1);
+    let mut attempts = 1;
+    loop {
+        let chunk = state.pending.clone();
+        let total = state.offset + chunk.len();
+        match upload_chunk(sink, &upload.session, state.offset, chunk, Some(total)).await {
+            Ok(_) => {
+                let written = state.pending.len();
+                state.advance(written);
+                debug!(message = "Finalized resumable upload.", key = %upload.key);
+                return;
+            }
+            Err(error)
+                if attempts < FINALIZE_ATTEMPTS
+                    && ResumableRetryLogic.is_retriable_error(&error) =>
+            {
+                warn!(
+                    message = "Retrying to finalize resumable upload.",
+                    %error,
+                    key = %upload.key,
+                    rate_limit_secs = 30,
+                );
+                time::delay_for(backoff).await;
+                backoff *= 2;
+                attempts += 1;
+                // Some of the chunk may have been written all the same.
+                if let Ok(persisted) = persisted(sink, &upload.session).await {
+                    let written = persisted
+                        .saturating_sub(state.offset)
+                        .min(state.pending.len());
+                    state.advance(written);
+                }
+            }
+            Err(error) => {
+                error!(
+                    message = "Failed to finalize resumable upload, the events of its written chunks are lost.",
+                    %error,
+                    key = %upload.key
+                );
+                cancel(sink, upload).await;
+                state.fail();
+                return;
+            }
+        }
+    }
+}
+
+async fn cancel(sink: &GcsSink, upload: &Upload) {
+    let request = Request::delete(upload.session.clone())
+        .header("content-length", 0)
+        .body(Body::empty())
+        .unwrap();
+    if let Err(error) = sink.send(request).await {
+        warn!(message = "Failed to cancel resumable upload.", %error, key = %upload.key);
+    }
+}
+
+/// Finalize the uploads older than `timeout`, and all of them once the input
+/// ended or the sink is gone.
+async fn finalize_timed_out(
+    sink: GcsSink,
+    uploads: Arc<Uploads>,
+    closing: Arc<AtomicBool>,
+    alive: Weak<()>,
+    timeout: Duration,
+) {
+    let mut interval = time::interval(Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let shutdown = closing.load(Ordering::Relaxed) || alive.upgrade().is_none();
+
+        let timed_out = {
+            let mut uploads = uploads.lock().unwrap();
+            let prefixes = uploads
+                .iter()
+                .filter(|(_, upload)| shutdown || upload.started.elapsed() >= timeout)
+                .map(|(prefix, _)| prefix.clone())
+                .collect::<Vec<_>>();
+            prefixes
+                .into_iter()
+                .filter_map(|prefix| uploads.remove(&prefix))
+                .collect::<Vec<_>>()
+        };
+        for upload in timed_out {
+            let mut state = upload.state.lock().await;
+            if !state.closed {
+                state.closed = true;
+                finalize(&sink, &upload, &mut state).await;
+            }
+        }
+
+        if shutdown {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_ranges() {
+        assert_eq!(content_range(0, 10, None), "bytes 0-9/*");
+        assert_eq!(content_range(10, 5, Some(15)), "bytes 10-14/15");
+        assert_eq!(content_range(15, 0, Some(15)), "bytes */15");
+    }
+
+    #[test]
+    fn advances_waiters() {
+        let mut state = UploadState::default();
+        let (first, mut first_written) = oneshot::channel();
+        let (second, mut second_written) = oneshot::channel();
+        state.pending.extend_from_slice(b"firstsecond");
+        state.waiters.push((5, first));
+        state.waiters.push((11, second));
+
+        state.advance(8);
+        assert_eq!(state.offset, 8);
+        assert_eq!(state.pending, b"ond");
+        assert_eq!(first_written.try_recv(), Ok(true));
+        assert!(second_written.try_recv().is_err());
+
+        state.fail();
+        assert_eq!(second_written.try_recv(), Ok(false));
+    }
+
+    #[test]
+    fn range_ends() {
+        let response = |range: Option<&str>| {
+            let mut builder = Response::builder().status(RESUME_INCOMPLETE);
+            if let Some(range) = range {
+                builder = builder.header("range", range);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+        assert_eq!(range_end(&response(None)), 0);
+        assert_eq!(
+            range_end(&response(Some("bytes=0-262143"))),
+            CHUNK_ALIGNMENT
+        );
+    }
+}
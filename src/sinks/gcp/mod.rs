@@ -16,8 +16,13 @@ use snafu::{ResultExt, Snafu};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+#[cfg(feature = "sinks-gcp_bigquery")]
+pub mod bigquery;
+#[cfg(feature = "sinks-gcp")]
 pub mod cloud_storage;
+#[cfg(feature = "sinks-gcp")]
 pub mod pubsub;
+#[cfg(feature = "sinks-gcp")]
 pub mod stackdriver_logs;
 
 const SERVICE_ACCOUNT_TOKEN_URL: &str =
@@ -119,11 +124,15 @@ impl GcpCredentials {
     }
 
     pub fn apply<T>(&self, request: &mut http::Request<T>) {
-        let token = self.token.read().unwrap();
-        let value = format!("{} {}", token.token_type(), token.access_token());
         request
             .headers_mut()
-            .insert(AUTHORIZATION, value.parse().unwrap());
+            .insert(AUTHORIZATION, self.authorization().parse().unwrap());
+    }
+
+    /// The value of the `authorization` header, or metadata of gRPC requests.
+    pub fn authorization(&self) -> String {
+        let token = self.token.read().unwrap();
+        format!("{} {}", token.token_type(), token.access_token())
     }
 
     async fn regenerate_token(&self) -> crate::Result<()> {
@@ -1,8 +1,10 @@
 use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
 use crate::{
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    emit,
     event::Event,
     http::HttpClient,
+    internal_events::GcpPubsubMissingKeys,
     sinks::{
         util::{
             encoding::{EncodingConfigWithDefault, EncodingConfiguration},
@@ -11,14 +13,25 @@ use crate::{
         },
         Healthcheck, UriParseError, VectorSink,
     },
+    template::{Template, TemplateError},
     tls::{TlsOptions, TlsSettings},
 };
 use futures::{FutureExt, SinkExt};
 use http::{Request, Uri};
 use hyper::Body;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 use snafu::{ResultExt, Snafu};
+use std::convert::TryFrom;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid ordering key template: {}", source))]
+    OrderingKeyTemplate { source: TemplateError },
+    #[snafu(display("Invalid template for attribute {:?}: {}", name, source))]
+    AttributeTemplate { name: String, source: TemplateError },
+}
 
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
@@ -36,6 +49,11 @@ pub struct PubsubConfig {
     pub skip_authentication: bool,
     #[serde(flatten)]
     pub auth: GcpAuthConfig,
+    /// The ordering key of the messages, which Pub/Sub delivers in the
+    /// order they were published to subscriptions with message ordering.
+    pub ordering_key: Option<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub attributes: IndexMap<String, String>,
 
     #[serde(default)]
     pub batch: BatchConfig,
@@ -85,9 +103,13 @@ impl SinkConfig for PubsubConfig {
             .events(1000)
             .timeout(1)
             .parse_config(self.batch)?;
-        let request_settings = self.request.unwrap_with(&Default::default());
+        let mut request_settings = self.request.unwrap_with(&Default::default());
+        if self.ordering_key.is_some() {
+            // Batches sent concurrently could be published out of order.
+            request_settings.concurrency = Some(1);
+        }
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
 
         let healthcheck = healthcheck(client.clone(), sink.uri("")?, sink.creds.clone()).boxed();
 
@@ -117,6 +139,8 @@ struct PubsubSink {
     api_key: Option<String>,
     creds: Option<GcpCredentials>,
     uri_base: String,
+    ordering_key: Option<Template>,
+    attributes: Vec<(String, Template)>,
     encoding: EncodingConfigWithDefault<Encoding>,
 }
 
@@ -138,11 +162,29 @@ impl PubsubSink {
             uri_base, config.project, config.topic,
         );
 
+        let ordering_key = config
+            .ordering_key
+            .as_deref()
+            .map(Template::try_from)
+            .transpose()
+            .context(OrderingKeyTemplate)?;
+        let attributes = config
+            .attributes
+            .iter()
+            .map(|(name, value)| {
+                let template = Template::try_from(value.as_str())
+                    .context(AttributeTemplate { name: name.clone() })?;
+                Ok((name.clone(), template))
+            })
+            .collect::<crate::Result<_>>()?;
+
         Ok(Self {
             api_key: config.auth.api_key.clone(),
             encoding: config.encoding.clone(),
             creds,
             uri_base,
+            ordering_key,
+            attributes,
         })
     }
 
@@ -163,11 +205,33 @@ impl HttpSink for PubsubSink {
     type Output = Vec<BoxedRawValue>;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
+        let render = |template: &Template| {
+            template
+                .render_string(&event)
+                .map_err(|keys| emit!(GcpPubsubMissingKeys { keys: &keys }))
+                .ok()
+        };
+        let ordering_key = match &self.ordering_key {
+            Some(template) => Some(render(template)?),
+            None => None,
+        };
+        let mut attributes = Map::new();
+        for (name, template) in &self.attributes {
+            attributes.insert(name.clone(), render(template)?.into());
+        }
+
         self.encoding.apply_rules(&mut event);
         // Each event needs to be base64 encoded, and put into a JSON object
         // as the `data` item.
         let json = serde_json::to_string(&event.into_log()).unwrap();
-        Some(json!({ "data": base64::encode(&json) }))
+        let mut message = json!({ "data": base64::encode(&json) });
+        if let Some(ordering_key) = ordering_key {
+            message["orderingKey"] = ordering_key.into();
+        }
+        if !attributes.is_empty() {
+            message["attributes"] = attributes.into();
+        }
+        Some(message)
     }
 
     async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
@@ -222,6 +286,36 @@ mod tests {
             panic!("config.build failed to error");
         }
     }
+
+    #[tokio::test]
+    async fn encodes_ordering_keys_and_attributes() {
+        let config: PubsubConfig = toml::from_str(
+            r#"
+            project = "project"
+            topic = "topic"
+            skip_authentication = true
+            ordering_key = "{{ host }}"
+            attributes.app = "{{ app }}"
+            attributes.source = "vector"
+            "#,
+        )
+        .unwrap();
+        let sink = PubsubSink::from_config(&config).await.unwrap();
+
+        let mut event = Event::from("foo");
+        event.as_mut_log().insert("host", "web-1");
+        event.as_mut_log().insert("app", "api");
+        let message = sink.encode_event(event).unwrap();
+        assert_eq!(message["orderingKey"], "web-1");
+        assert_eq!(
+            message["attributes"],
+            json!({ "app": "api", "source": "vector" })
+        );
+
+        let mut event = Event::from("foo");
+        event.as_mut_log().insert("host", "web-1");
+        assert_eq!(sink.encode_event(event), None);
+    }
 }
 
 #[cfg(test)]
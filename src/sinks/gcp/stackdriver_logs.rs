@@ -120,7 +120,7 @@ impl SinkConfig for StackdriverConfig {
             .parse_config(self.batch)?;
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
 
         let sink = StackdriverSink {
             config: self.clone(),
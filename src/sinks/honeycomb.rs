@@ -58,7 +58,7 @@ impl SinkConfig for HoneycombConfig {
             .timeout(1)
             .parse_config(self.batch)?;
 
-        let client = HttpClient::new(None)?;
+        let client = HttpClient::new(None)?.with_headers(self.request.headers(&cx)?);
 
         let sink = BatchedHttpSink::new(
             self.clone(),
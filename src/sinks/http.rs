@@ -4,14 +4,13 @@ use crate::{
     http::{Auth, HttpClient},
     internal_events::{HTTPEventEncoded, HTTPEventMissingMessage},
     sinks::util::{
-        buffer::compression::GZIP_DEFAULT,
         encoding::{EncodingConfig, EncodingConfiguration},
         http::{BatchedHttpSink, HttpSink},
+        idempotency::IdempotencyConfig,
         BatchConfig, BatchSettings, Buffer, Compression, Concurrency, TowerRequestConfig, UriSerde,
     },
     tls::{TlsOptions, TlsSettings},
 };
-use flate2::write::GzEncoder;
 use futures::{future, FutureExt, SinkExt};
 use http::{
     header::{self, HeaderName, HeaderValue},
@@ -22,7 +21,6 @@ use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::io::Write;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -54,6 +52,8 @@ pub struct HttpSinkConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub tls: Option<TlsOptions>,
+    pub idempotency: Option<IdempotencyConfig>,
+    pub trace_context_key: Option<String>,
 }
 
 #[cfg(test)]
@@ -69,6 +69,8 @@ fn default_config(e: Encoding) -> HttpSinkConfig {
         encoding: e.into(),
         request: Default::default(),
         tls: Default::default(),
+        idempotency: Default::default(),
+        trace_context_key: Default::default(),
     }
 }
 
@@ -96,6 +98,8 @@ pub enum Encoding {
     Text,
     Ndjson,
     Json,
+    Cef,
+    Leef,
 }
 
 inventory::submit! {
@@ -120,8 +124,11 @@ impl SinkConfig for HttpSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         validate_headers(&self.headers, &self.auth)?;
+        if let Some(idempotency) = &self.idempotency {
+            idempotency.validate()?;
+        }
         let tls = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls)?;
+        let client = HttpClient::new(tls)?.with_headers(self.request.headers(&cx)?);
 
         let mut config = self.clone();
         config.uri = build_uri(config.uri.clone()).into();
@@ -169,7 +176,10 @@ impl HttpSink for HttpSinkConfig {
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
         self.encoding.apply_rules(&mut event);
-        let event = event.into_log();
+        let mut event = event.into_log();
+        if let Some(idempotency) = &self.idempotency {
+            idempotency.apply_field(&mut event);
+        }
 
         let body = match &self.encoding.codec() {
             Encoding::Text => {
@@ -198,6 +208,18 @@ impl HttpSink for HttpSinkConfig {
                 b.push(b',');
                 b
             }
+
+            Encoding::Cef => {
+                let mut b = self.encoding.encode_cef(&event).into_bytes();
+                b.push(b'\n');
+                b
+            }
+
+            Encoding::Leef => {
+                let mut b = self.encoding.encode_leef(&event).into_bytes();
+                b.push(b'\n');
+                b
+            }
         };
 
         emit!(HTTPEventEncoded {
@@ -207,6 +229,10 @@ impl HttpSink for HttpSinkConfig {
         Some(body)
     }
 
+    fn trace_context_key(&self) -> Option<&str> {
+        self.trace_context_key.as_deref()
+    }
+
     async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
         let method = match &self.method.clone().unwrap_or(HttpMethod::Post) {
             HttpMethod::Post => Method::POST,
@@ -215,7 +241,7 @@ impl HttpSink for HttpSinkConfig {
         let uri: Uri = self.uri.clone().into();
 
         let ct = match self.encoding.codec() {
-            Encoding::Text => "text/plain",
+            Encoding::Text | Encoding::Cef | Encoding::Leef => "text/plain",
             Encoding::Ndjson => "application/x-ndjson",
             Encoding::Json => {
                 body.insert(0, b'[');
@@ -230,16 +256,9 @@ impl HttpSink for HttpSinkConfig {
             .uri(uri)
             .header("Content-Type", ct);
 
-        match self.compression {
-            Compression::Gzip(level) => {
-                builder = builder.header("Content-Encoding", "gzip");
-
-                let level = level.unwrap_or(GZIP_DEFAULT) as u32;
-                let mut w = GzEncoder::new(Vec::new(), flate2::Compression::new(level));
-                w.write_all(&body).expect("Writing to Vec can't fail");
-                body = w.finish().expect("Writing to Vec can't fail");
-            }
-            Compression::None => {}
+        if let Some(ce) = self.compression.content_encoding() {
+            builder = builder.header("Content-Encoding", ce);
+            body = self.compression.compress(&body);
         }
 
         if let Some(headers) = &self.headers {
@@ -254,6 +273,10 @@ impl HttpSink for HttpSinkConfig {
             auth.apply(&mut request);
         }
 
+        if let Some(idempotency) = &self.idempotency {
+            idempotency.apply(&mut request);
+        }
+
         Ok(request)
     }
 }
@@ -312,7 +335,7 @@ mod tests {
         config::SinkContext,
         sinks::{
             http::HttpSinkConfig,
-            util::{http::HttpSink, test::build_test_server},
+            util::{encoding::LeefConfig, http::HttpSink, test::build_test_server},
         },
         test_util::{next_addr, random_lines_with_stream},
     };
@@ -362,6 +385,28 @@ mod tests {
         assert_eq!(output.message, "hello world".to_string());
     }
 
+    #[test]
+    fn http_encode_event_leef() {
+        let mut encoding = EncodingConfig::from(Encoding::Leef);
+        encoding.leef = Some(LeefConfig {
+            vendor: "Acme".into(),
+            product: "Gateway".into(),
+            product_version: "2.1".into(),
+            ..Default::default()
+        });
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("event_id", "login");
+
+        let mut config = default_config(Encoding::Leef);
+        config.encoding = encoding;
+        let bytes = config.encode_event(event).unwrap();
+
+        assert_eq!(
+            bytes,
+            Vec::from(&"LEEF:2.0|Acme|Gateway|2.1|login|x09|msg=hello world\n"[..])
+        );
+    }
+
     #[test]
     fn http_validates_normal_headers() {
         let config = r#"
@@ -580,6 +625,39 @@ mod tests {
         assert_eq!(input_lines, output_lines);
     }
 
+    #[tokio::test]
+    async fn http_passes_request_headers() {
+        let in_addr = next_addr();
+
+        let config = r#"
+        uri = "http://$IN_ADDR/frames"
+        encoding = "ndjson"
+        [headers]
+        foo = "bar"
+        [request.headers]
+        foo = "ignored"
+        baz = "quux"
+    "#
+        .replace("$IN_ADDR", &format!("{}", in_addr));
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+
+        let (_, events) = random_lines_with_stream(100, 10);
+        tokio::spawn(server);
+        sink.run(events).await.unwrap();
+        drop(trigger);
+
+        let requests = rx.collect::<Vec<_>>().await;
+        assert!(!requests.is_empty());
+        for (parts, _) in requests {
+            // Headers set by the sink take precedence.
+            assert_eq!(parts.headers.get("foo").unwrap(), "bar");
+            assert_eq!(parts.headers.get("baz").unwrap(), "quux");
+        }
+    }
+
     #[tokio::test]
     async fn json_compresion() {
         let num_lines = 1000;
@@ -632,4 +710,56 @@ mod tests {
         assert_eq!(num_lines, output_lines.len());
         assert_eq!(input_lines, output_lines);
     }
+
+    #[tokio::test]
+    async fn http_propagates_trace_context() {
+        let in_addr = next_addr();
+
+        let config = r#"
+        uri = "http://$IN_ADDR/frames"
+        encoding.codec = "ndjson"
+        encoding.except_fields = ["trace"]
+        trace_context_key = "trace"
+    "#
+        .replace("$IN_ADDR", &format!("{}", in_addr));
+        let config: HttpSinkConfig = toml::from_str(&config).unwrap();
+
+        let cx = SinkContext::new_test();
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+
+        let traceparent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let events = (0..3).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            if i > 0 {
+                event
+                    .as_mut_log()
+                    .insert("trace.traceparent", traceparent.to_owned());
+            }
+            event
+        });
+        let pump = sink.run(stream::iter(events));
+
+        tokio::spawn(server);
+
+        pump.await.unwrap();
+        drop(trigger);
+
+        let requests = rx.collect::<Vec<_>>().await;
+        assert_eq!(requests.len(), 1);
+        let (parts, body) = &requests[0];
+        assert_eq!(
+            Some(traceparent),
+            parts
+                .headers
+                .get("traceparent")
+                .map(|v| v.to_str().unwrap())
+        );
+        assert!(parts.headers.get("tracestate").is_none());
+        for line in body.clone().reader().lines() {
+            let val: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+            assert!(val.get("trace").is_none());
+        }
+    }
 }
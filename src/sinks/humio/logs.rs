@@ -74,7 +74,7 @@ impl HumioLogsConfig {
             encoding: self.encoding.clone().transmute(),
             compression: self.compression,
             batch: self.batch,
-            request: self.request,
+            request: self.request.clone(),
             host_key: self.host_key.clone(),
             ..Default::default()
         }
@@ -78,7 +78,7 @@ impl SinkConfig for HumioMetricsConfig {
             event_type: self.event_type.clone(),
             host_key: self.host_key.clone(),
             compression: self.compression,
-            request: self.request,
+            request: self.request.clone(),
             batch: self.batch,
         };
 
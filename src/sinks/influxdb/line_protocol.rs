@@ -0,0 +1,312 @@
+//! Writes metrics, and optionally logs, as line protocol over TCP, which
+//! QuestDB and other databases accept without the Influx HTTP API.
+
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, LogEvent, Metric, Value},
+    sinks::{
+        influxdb::{
+            encode_timestamp, influx_line_protocol,
+            metrics::{default_summary_quantiles, encode_events},
+            ProtocolVersion,
+        },
+        util::{encode_namespace, statistic::validate_quantiles, tcp::TcpSinkConfig},
+        Healthcheck, VectorSink,
+    },
+};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct LineProtocolSinkConfig {
+    #[serde(flatten)]
+    pub tcp: TcpSinkConfig,
+    #[serde(alias = "namespace")]
+    pub default_namespace: Option<String>,
+    /// The measurement logs are written to. Logs aren't accepted without it.
+    pub log_measurement: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub measurements: HashMap<String, MeasurementConfig>,
+    #[serde(default = "default_summary_quantiles")]
+    pub quantiles: Vec<f64>,
+}
+
+/// How the events of a measurement are written.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MeasurementConfig {
+    /// The tags of metrics to rename, or the fields of logs to write as tags,
+    /// mapped to the name of the tag in the line.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+inventory::submit! {
+    SinkDescription::new::<LineProtocolSinkConfig>("line_protocol")
+}
+
+impl GenerateConfig for LineProtocolSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "127.0.0.1:9009"
+            default_namespace = "service""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "line_protocol")]
+impl SinkConfig for LineProtocolSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        validate_quantiles(&self.quantiles)?;
+        let encoder = LineProtocolEncoder::new(self);
+        self.tcp.build(cx, move |event| encoder.encode_event(event))
+    }
+
+    fn input_type(&self) -> DataType {
+        if self.log_measurement.is_some() {
+            DataType::Any
+        } else {
+            DataType::Metric
+        }
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "line_protocol"
+    }
+}
+
+struct LineProtocolEncoder {
+    default_namespace: Option<String>,
+    log_measurement: Option<String>,
+    tags: Option<HashMap<String, String>>,
+    measurements: HashMap<String, MeasurementConfig>,
+    quantiles: Vec<f64>,
+}
+
+impl LineProtocolEncoder {
+    fn new(config: &LineProtocolSinkConfig) -> Self {
+        Self {
+            default_namespace: config.default_namespace.clone(),
+            log_measurement: config.log_measurement.clone(),
+            tags: config.tags.clone(),
+            measurements: config.measurements.clone(),
+            quantiles: config.quantiles.clone(),
+        }
+    }
+
+    fn encode_event(&self, event: Event) -> Option<Bytes> {
+        let mut output = match event {
+            Event::Metric(metric) => self.encode_metric(metric),
+            Event::Log(log) => self.encode_log(log)?,
+        };
+        if output.is_empty() {
+            return None;
+        }
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        Some(output.into())
+    }
+
+    fn mapped_tags(&self, measurement: &str) -> Option<&HashMap<String, String>> {
+        self.measurements
+            .get(measurement)
+            .map(|measurement| &measurement.tags)
+    }
+
+    fn encode_metric(&self, mut metric: Metric) -> String {
+        let measurement = encode_namespace(
+            metric
+                .namespace
+                .as_deref()
+                .or(self.default_namespace.as_deref()),
+            '.',
+            &metric.name,
+        );
+        if let (Some(mapped), Some(tags)) = (self.mapped_tags(&measurement), metric.tags.as_mut()) {
+            *tags = std::mem::take(tags)
+                .into_iter()
+                .map(|(key, value)| match mapped.get(&key) {
+                    Some(renamed) => (renamed.clone(), value),
+                    None => (key, value),
+                })
+                .collect();
+        }
+
+        encode_events(
+            ProtocolVersion::V1,
+            vec![metric],
+            self.default_namespace.as_deref(),
+            self.tags.as_ref(),
+            &self.quantiles,
+        )
+    }
+
+    fn encode_log(&self, mut log: LogEvent) -> Option<String> {
+        let measurement = self.log_measurement.as_ref()?;
+        let mapped = self.mapped_tags(measurement);
+
+        let timestamp = encode_timestamp(match log.remove(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(ts)) => Some(ts),
+            _ => None,
+        });
+
+        let mut tags = self
+            .tags
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+        let mut fields = HashMap::new();
+        for (key, value) in log.all_fields() {
+            let tag = mapped.and_then(|mapped| mapped.get(&key)).or_else(|| {
+                if key == log_schema().host_key() || key == log_schema().source_type_key() {
+                    Some(&key)
+                } else {
+                    None
+                }
+            });
+            match tag {
+                Some(tag) => {
+                    tags.insert(tag.clone(), value.to_string_lossy());
+                }
+                None => {
+                    fields.insert(key, value.to_field());
+                }
+            }
+        }
+
+        let mut output = String::new();
+        influx_line_protocol(
+            ProtocolVersion::V1,
+            measurement.clone(),
+            "logs",
+            Some(tags),
+            Some(fields),
+            timestamp,
+            &mut output,
+        );
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::metric::{MetricKind, MetricValue},
+        sinks::influxdb::test_util::{split_line_protocol, ts},
+        test_util::{next_addr, trace_init, CountReceiver},
+    };
+    use futures::stream;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LineProtocolSinkConfig>();
+    }
+
+    fn config(toml: &str) -> LineProtocolSinkConfig {
+        toml::from_str(&format!("address = \"127.0.0.1:9009\"\n{}", toml)).unwrap()
+    }
+
+    fn gauge(name: &str) -> Metric {
+        Metric {
+            name: name.into(),
+            namespace: None,
+            timestamp: Some(ts()),
+            tags: Some(
+                vec![("host".to_owned(), "web-1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Absolute,
+            value: MetricValue::Gauge { value: 0.5 },
+        }
+    }
+
+    #[test]
+    fn renames_the_tags_of_measurements() {
+        let config = config(
+            r#"
+            default_namespace = "app"
+            measurements."app.load".tags = { host = "hostname" }
+            "#,
+        );
+        let encoder = LineProtocolEncoder::new(&config);
+
+        let line = encoder.encode_event(gauge("load").into()).unwrap();
+        assert_eq!(
+            line,
+            "app.load,hostname=web-1,metric_type=gauge value=0.5 1542182950000000011\n"
+        );
+
+        let line = encoder.encode_event(gauge("memory").into()).unwrap();
+        assert_eq!(
+            line,
+            "app.memory,host=web-1,metric_type=gauge value=0.5 1542182950000000011\n"
+        );
+    }
+
+    #[test]
+    fn writes_logs_to_their_measurement() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("level", "info");
+        event.as_mut_log().insert("status", 200);
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), ts());
+        assert!(LineProtocolEncoder::new(&config(""))
+            .encode_event(event.clone())
+            .is_none());
+
+        let config = config(
+            r#"
+            log_measurement = "logs"
+            measurements.logs.tags = { level = "severity" }
+            "#,
+        );
+        assert_eq!(config.input_type(), DataType::Any);
+        let line = LineProtocolEncoder::new(&config)
+            .encode_event(event)
+            .unwrap();
+        let line = std::str::from_utf8(&line).unwrap();
+
+        let (name, tags, fields, timestamp) = split_line_protocol(line.trim_end());
+        assert_eq!(name, "logs");
+        assert_eq!(tags, "metric_type=logs,severity=info");
+        let mut fields = fields.split(',').collect::<Vec<_>>();
+        fields.sort_unstable();
+        assert_eq!(fields, vec!["message=\"hello\"", "status=200i"]);
+        assert_eq!(timestamp, "1542182950000000011");
+    }
+
+    #[tokio::test]
+    async fn writes_lines_over_tcp() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = LineProtocolSinkConfig {
+            tcp: TcpSinkConfig::new(addr.to_string(), None, None),
+            ..config("")
+        };
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let mut receiver = CountReceiver::receive_lines(addr);
+        let events = vec![gauge("load").into(), gauge("memory").into()];
+        sink.run(stream::iter(events)).await.unwrap();
+
+        receiver.connected().await;
+        let output = receiver.await;
+        assert_eq!(
+            output,
+            vec![
+                "load,host=web-1,metric_type=gauge value=0.5 1542182950000000011",
+                "memory,host=web-1,metric_type=gauge value=0.5 1542182950000000011",
+            ]
+        );
+    }
+}
@@ -99,7 +99,7 @@ impl SinkConfig for InfluxDBLogsConfig {
         tags.insert(log_schema().source_type_key().to_string());
 
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
         let healthcheck = self.healthcheck(client.clone())?;
 
         let batch = BatchSettings::default()
@@ -226,7 +226,7 @@ impl InfluxDBLogsConfig {
 }
 
 impl Value {
-    fn to_field(&self) -> Field {
+    pub(super) fn to_field(&self) -> Field {
         match self {
             Value::Integer(num) => Field::Int(*num),
             Value::Float(num) => Field::Float(*num),
@@ -4,14 +4,15 @@ use crate::{
     http::HttpClient,
     sinks::{
         influxdb::{
-            encode_timestamp, healthcheck, influx_line_protocol, influxdb_settings, Field,
-            InfluxDB1Settings, InfluxDB2Settings, ProtocolVersion,
+            encode_string, encode_timestamp, healthcheck, influx_line_protocol, influxdb_settings,
+            Field, InfluxDB1Settings, InfluxDB2Settings, ProtocolVersion,
         },
         util::{
             encode_namespace,
             http::{HttpBatchService, HttpRetryLogic},
             statistic::{validate_quantiles, DistributionStatistic},
-            BatchConfig, BatchSettings, MetricBuffer, TowerRequestConfig,
+            BatchConfig, BatchSettings, MetricBuffer, MetricColumns, ScalarType,
+            TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -23,6 +24,7 @@ use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
+    fmt::Write as _,
     future::ready,
     task::Poll,
 };
@@ -83,7 +85,7 @@ impl_generate_config_from_default!(InfluxDBConfig);
 impl SinkConfig for InfluxDBConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
         let healthcheck = healthcheck(
             self.clone().endpoint,
             self.clone().influxdb1_settings,
@@ -210,15 +212,76 @@ fn merge_tags(
     }
 }
 
-fn encode_events(
+/// Encodes the counters, gauges and sets straight from the columns, without
+/// allocating per metric, as they make up most of the batches.
+fn encode_scalars(
+    columns: &MetricColumns,
+    default_namespace: Option<&str>,
+    tags: Option<&HashMap<String, String>>,
+    output: &mut String,
+) {
+    let mut line_tags = Vec::new();
+    for row in columns.rows() {
+        if let Some(namespace) = row.namespace().or(default_namespace) {
+            encode_string(namespace, output);
+            output.push('.');
+        }
+        encode_string(row.name(), output);
+        output.push(',');
+
+        let metric_type = match row.r#type() {
+            ScalarType::Counter => "counter",
+            ScalarType::Gauge => "gauge",
+            ScalarType::Set => "set",
+        };
+        line_tags.clear();
+        line_tags.extend(row.tags());
+        line_tags.extend(
+            tags.into_iter()
+                .flatten()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+        line_tags.push(("metric_type", metric_type));
+        // The sort is stable, so the last of the tags with the same key is
+        // the one taking precedence.
+        line_tags.sort_by_key(|&(key, _)| key);
+        for (index, &(key, value)) in line_tags.iter().enumerate() {
+            let overridden = line_tags
+                .get(index + 1)
+                .map_or(false, |&(next, _)| next == key);
+            if overridden || key.is_empty() || value.is_empty() {
+                continue;
+            }
+            encode_string(key, output);
+            output.push('=');
+            encode_string(value, output);
+            output.push(',');
+        }
+        // remove last ','
+        output.pop();
+
+        writeln!(
+            output,
+            " value={} {}",
+            row.value(),
+            encode_timestamp(row.timestamp())
+        )
+        .expect("Writing to a String can't fail.");
+    }
+}
+
+pub(super) fn encode_events(
     protocol_version: ProtocolVersion,
     events: Vec<Metric>,
     default_namespace: Option<&str>,
     tags: Option<&HashMap<String, String>>,
     quantiles: &[f64],
 ) -> String {
+    let columns = MetricColumns::from(events);
     let mut output = String::new();
-    for event in events.into_iter() {
+    encode_scalars(&columns, default_namespace, tags, &mut output);
+
+    for event in columns.into_others() {
         let fullname = encode_namespace(
             event.namespace.as_deref().or(default_namespace),
             '.',
@@ -227,44 +290,8 @@ fn encode_events(
         let ts = encode_timestamp(event.timestamp);
         let tags = merge_tags(&event, tags);
         match event.value {
-            MetricValue::Counter { value } => {
-                let fields = to_fields(value);
-
-                influx_line_protocol(
-                    protocol_version,
-                    fullname,
-                    "counter",
-                    tags,
-                    Some(fields),
-                    ts,
-                    &mut output,
-                )
-            }
-            MetricValue::Gauge { value } => {
-                let fields = to_fields(value);
-
-                influx_line_protocol(
-                    protocol_version,
-                    fullname,
-                    "gauge",
-                    tags,
-                    Some(fields),
-                    ts,
-                    &mut output,
-                );
-            }
-            MetricValue::Set { values } => {
-                let fields = to_fields(values.len() as f64);
-
-                influx_line_protocol(
-                    protocol_version,
-                    fullname,
-                    "set",
-                    tags,
-                    Some(fields),
-                    ts,
-                    &mut output,
-                );
+            MetricValue::Counter { .. } | MetricValue::Gauge { .. } | MetricValue::Set { .. } => {
+                unreachable!("Encoded from the columns.")
             }
             MetricValue::AggregatedHistogram {
                 buckets,
@@ -370,13 +397,6 @@ fn encode_distribution(
     Some(fields)
 }
 
-fn to_fields(value: f64) -> HashMap<String, Field> {
-    let fields: HashMap<String, Field> = vec![("value".to_owned(), Field::Float(value))]
-        .into_iter()
-        .collect();
-    fields
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,4 @@
+pub mod line_protocol;
 pub mod logs;
 pub mod metrics;
 
@@ -184,7 +185,7 @@ pub(in crate::sinks) fn influx_line_protocol(
         return;
     }
 
-    encode_string(measurement, line_protocol);
+    encode_string(&measurement, line_protocol);
     line_protocol.push(',');
 
     // Tags
@@ -208,9 +209,9 @@ fn encode_tags(tags: BTreeMap<String, String>, output: &mut String) {
         if key.is_empty() || value.is_empty() {
             continue;
         }
-        encode_string(key.to_string(), output);
+        encode_string(&key, output);
         output.push('=');
-        encode_string(value.to_string(), output);
+        encode_string(&value, output);
         output.push(',');
     }
 
@@ -224,7 +225,7 @@ fn encode_fields(
     output: &mut String,
 ) {
     for (key, value) in fields.into_iter() {
-        encode_string(key.to_string(), output);
+        encode_string(&key, output);
         output.push('=');
         match value {
             Field::String(s) => {
@@ -261,7 +262,7 @@ fn encode_fields(
     output.pop();
 }
 
-fn encode_string(key: String, output: &mut String) {
+pub(in crate::sinks) fn encode_string(key: &str, output: &mut String) {
     for c in key.chars() {
         if "\\, =".contains(c) {
             output.push('\\');
@@ -691,19 +692,19 @@ mod tests {
     #[test]
     fn test_encode_string() {
         let mut value = String::new();
-        encode_string("measurement_name".to_string(), &mut value);
+        encode_string("measurement_name", &mut value);
         assert_eq!(value, "measurement_name");
 
         let mut value = String::new();
-        encode_string("measurement name".to_string(), &mut value);
+        encode_string("measurement name", &mut value);
         assert_eq!(value, "measurement\\ name");
 
         let mut value = String::new();
-        encode_string("measurement=name".to_string(), &mut value);
+        encode_string("measurement=name", &mut value);
         assert_eq!(value, "measurement\\=name");
 
         let mut value = String::new();
-        encode_string("measurement,name".to_string(), &mut value);
+        encode_string("measurement,name", &mut value);
         assert_eq!(value, "measurement\\,name");
     }
 
@@ -10,14 +10,17 @@ use crate::{
     },
     template::{Template, TemplateError},
 };
+use bytes::Bytes;
 use futures::{
     channel::oneshot::Canceled, future::BoxFuture, ready, stream::FuturesUnordered, FutureExt,
     Sink, StreamExt, TryFutureExt,
 };
+use indexmap::IndexMap;
 use rdkafka::{
     consumer::{BaseConsumer, Consumer},
-    error::{KafkaError, RDKafkaError},
-    producer::{DeliveryFuture, FutureProducer, FutureRecord},
+    error::{KafkaError, KafkaResult, RDKafkaErrorCode},
+    message::OwnedHeaders,
+    producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer},
     ClientConfig,
 };
 use serde::{Deserialize, Serialize};
@@ -29,7 +32,10 @@ use std::{
     sync::Arc,
     task::{Context, Poll},
 };
-use tokio::{sync::Notify, time::Duration};
+use tokio::{
+    sync::Notify,
+    time::{interval_at, Duration, Instant, Interval},
+};
 
 // Maximum number of futures blocked by [send_result](https://docs.rs/rdkafka/0.24.0/rdkafka/producer/future_producer/struct.FutureProducer.html#method.send_result)
 const SEND_RESULT_LIMIT: usize = 5;
@@ -40,6 +46,23 @@ enum BuildError {
     KafkaCreateFailed { source: KafkaError },
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("invalid key template: {}", source))]
+    KeyTemplate { source: TemplateError },
+    #[snafu(display("invalid template for header {:?}: {}", name, source))]
+    HeaderTemplate { name: String, source: TemplateError },
+    #[snafu(display("only one of key_field and key can be set"))]
+    KeyConflict,
+    #[snafu(display("initializing kafka transactions failed: {}", source))]
+    KafkaTransactionsFailed { source: KafkaError },
+    #[snafu(display(
+        "transaction.commit_interval_ms ({}) must be lower than transaction.timeout_ms ({})",
+        commit_interval_ms,
+        timeout_ms
+    ))]
+    TransactionCommitInterval {
+        commit_interval_ms: u64,
+        timeout_ms: u64,
+    },
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -47,6 +70,11 @@ pub struct KafkaSinkConfig {
     bootstrap_servers: String,
     topic: String,
     key_field: Option<String>,
+    /// A template of the message key, replacing `key_field`.
+    key: Option<String>,
+    /// Templates of the headers of the messages, by name.
+    #[serde(default)]
+    headers: IndexMap<String, String>,
     encoding: EncodingConfigWithDefault<Encoding>,
     /// These batching options will **not** override librdkafka_options values.
     #[serde(default)]
@@ -61,6 +89,23 @@ pub struct KafkaSinkConfig {
     message_timeout_ms: u64,
     #[serde(default)]
     librdkafka_options: HashMap<String, String>,
+    /// Makes the producer idempotent, so that its retries don't duplicate messages.
+    #[serde(default)]
+    idempotence: bool,
+    /// Produces the events in transactions, implying `idempotence`.
+    transaction: Option<KafkaTransactionConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaTransactionConfig {
+    /// The `transactional.id` of the producer, stable across restarts so
+    /// that the transactions left open by a previous instance are aborted.
+    id: String,
+    #[serde(default = "default_transaction_commit_interval_ms")]
+    commit_interval_ms: u64,
+    #[serde(default = "default_transaction_timeout_ms")]
+    timeout_ms: u64,
 }
 
 fn default_socket_timeout_ms() -> u64 {
@@ -71,6 +116,14 @@ fn default_message_timeout_ms() -> u64 {
     300000 // default in librdkafka
 }
 
+fn default_transaction_commit_interval_ms() -> u64 {
+    1000
+}
+
+fn default_transaction_timeout_ms() -> u64 {
+    60000 // default in librdkafka
+}
+
 #[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
 #[derivative(Default)]
 #[serde(rename_all = "snake_case")]
@@ -80,10 +133,17 @@ pub enum Encoding {
     Json,
 }
 
+/// Where the key of the messages is taken from.
+enum MessageKey {
+    Field(String),
+    Template(Template),
+}
+
 pub struct KafkaSink {
     producer: Arc<FutureProducer>,
     topic: Template,
-    key_field: Option<String>,
+    key: Option<MessageKey>,
+    headers: Vec<(String, Template)>,
     encoding: EncodingConfig<Encoding>,
     flush_signal: Arc<Notify>,
     delivery_fut: FuturesUnordered<BoxFuture<'static, (usize, Result<DeliveryFuture, KafkaError>)>>,
@@ -95,6 +155,19 @@ pub struct KafkaSink {
     seq_head: usize,
     seq_tail: usize,
     pending_acks: HashSet<usize>,
+    transaction: Option<Transaction>,
+}
+
+/// The transaction the events are produced in, committed every
+/// `commit_interval_ms` and when the sink is closed. Its events are only
+/// acked once it's committed.
+struct Transaction {
+    timeout: Duration,
+    interval: Interval,
+    /// The number of events delivered in the transaction.
+    delivered: usize,
+    due: bool,
+    commit: Option<BoxFuture<'static, KafkaResult<()>>>,
 }
 
 inventory::submit! {
@@ -121,6 +194,7 @@ impl SinkConfig for KafkaSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let sink = KafkaSink::new(self.clone(), cx.acker())?;
+        sink.begin_transactions().await?;
         let hc = healthcheck(self.clone()).boxed();
         Ok((super::VectorSink::Sink(Box::new(sink)), hc))
     }
@@ -187,6 +261,34 @@ impl KafkaSinkConfig {
             client_config.set(key, &batch_size.to_string());
         }
 
+        let mut producer_options = Vec::new();
+        if self.idempotence || self.transaction.is_some() {
+            producer_options.push(("enable.idempotence", "true".to_owned()));
+        }
+        if let Some(transaction) = &self.transaction {
+            if transaction.commit_interval_ms >= transaction.timeout_ms {
+                return Err(BuildError::TransactionCommitInterval {
+                    commit_interval_ms: transaction.commit_interval_ms,
+                    timeout_ms: transaction.timeout_ms,
+                }
+                .into());
+            }
+            producer_options.push(("transactional.id", transaction.id.clone()));
+            producer_options.push(("transaction.timeout.ms", transaction.timeout_ms.to_string()));
+        }
+        for (key, value) in producer_options {
+            if let Some(val) = self.librdkafka_options.get(key) {
+                return Err(format!(
+                    "The `idempotence` and `transaction` settings set `librdkafka_options.{}={}`.\
+                                    The config already sets this as `librdkafka_options.{}={}`.\
+                                    Please delete one.",
+                    key, value, key, val
+                )
+                .into());
+            }
+            client_config.set(key, &value);
+        }
+
         for (key, value) in self.librdkafka_options.iter() {
             client_config.set(key.as_str(), value.as_str());
         }
@@ -197,11 +299,40 @@ impl KafkaSinkConfig {
 
 impl KafkaSink {
     fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
-        let producer = config.to_rdkafka()?.create().context(KafkaCreateFailed)?;
+        let client_config = config.to_rdkafka()?;
+        let transaction = config.transaction.as_ref().map(|transaction| {
+            let period = Duration::from_millis(transaction.commit_interval_ms);
+            Transaction {
+                timeout: Duration::from_millis(transaction.timeout_ms),
+                interval: interval_at(Instant::now() + period, period),
+                delivered: 0,
+                due: false,
+                commit: None,
+            }
+        });
+        let key = match (config.key_field, config.key) {
+            (Some(_), Some(_)) => return Err(BuildError::KeyConflict.into()),
+            (Some(field), None) => Some(MessageKey::Field(field)),
+            (None, Some(key)) => Some(MessageKey::Template(
+                Template::try_from(key).context(KeyTemplate)?,
+            )),
+            (None, None) => None,
+        };
+        let headers = config
+            .headers
+            .into_iter()
+            .map(|(name, value)| {
+                let template =
+                    Template::try_from(value).context(HeaderTemplate { name: name.clone() })?;
+                Ok((name, template))
+            })
+            .collect::<Result<_, BuildError>>()?;
+        let producer = client_config.create().context(KafkaCreateFailed)?;
         Ok(KafkaSink {
             producer: Arc::new(producer),
             topic: Template::try_from(config.topic).context(TopicTemplate)?,
-            key_field: config.key_field,
+            key,
+            headers,
             encoding: config.encoding.into(),
             flush_signal: Arc::new(Notify::new()),
             delivery_fut: FuturesUnordered::new(),
@@ -210,9 +341,26 @@ impl KafkaSink {
             seq_head: 0,
             seq_tail: 0,
             pending_acks: HashSet::new(),
+            transaction,
         })
     }
 
+    /// Initializes the transactions of the producer, if any, and begins the
+    /// first one.
+    async fn begin_transactions(&self) -> crate::Result<()> {
+        if let Some(transaction) = &self.transaction {
+            let producer = Arc::clone(&self.producer);
+            let timeout = transaction.timeout;
+            tokio::task::spawn_blocking(move || {
+                producer.init_transactions(timeout)?;
+                producer.begin_transaction()
+            })
+            .await?
+            .context(KafkaTransactionsFailed)?;
+        }
+        Ok(())
+    }
+
     fn poll_delivery_fut(&mut self, cx: &mut Context<'_>) -> Poll<()> {
         loop {
             match ready!(self.delivery_fut.poll_next_unpin(cx)) {
@@ -237,6 +385,8 @@ impl Sink<Event> for KafkaSink {
     type Error = ();
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.poll_transaction(cx))?;
+
         match self.poll_delivery_fut(cx) {
             Poll::Pending if self.delivery_fut.len() >= SEND_RESULT_LIMIT => Poll::Pending,
             _ => Poll::Ready(Ok(())),
@@ -252,7 +402,8 @@ impl Sink<Event> for KafkaSink {
         let topic = self.topic.render_string(&item).map_err(|missing_keys| {
             error!(message = "Missing keys for topic.", missing_keys = ?missing_keys);
         })?;
-        let (key, body) = encode_event(item.clone(), &self.key_field, &self.encoding);
+        let headers = render_headers(&item, &self.headers);
+        let (key, body) = encode_event(item.clone(), &self.key, &self.encoding);
 
         let seqno = self.seq_head;
         self.seq_head += 1;
@@ -261,6 +412,9 @@ impl Sink<Event> for KafkaSink {
         let flush_signal = Arc::clone(&self.flush_signal);
         self.delivery_fut.push(Box::pin(async move {
             let mut record = FutureRecord::to(&topic).key(&key).payload(&body[..]);
+            if let Some(headers) = headers {
+                record = record.headers(headers);
+            }
             if let Some(Value::Timestamp(timestamp)) =
                 item.as_log().get(log_schema().timestamp_key())
             {
@@ -275,7 +429,7 @@ impl Sink<Event> for KafkaSink {
                     // See item 4 on GitHub: https://github.com/timberio/vector/pull/101#issue-257150924
                     // https://docs.rs/rdkafka/0.24.0/src/rdkafka/producer/future_producer.rs.html#296
                     Err((error, future_record))
-                        if error == KafkaError::MessageProduction(RDKafkaError::QueueFull) =>
+                        if error == KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull) =>
                     {
                         debug!(message = "The rdkafka queue full.", %error, %seqno, rate_limit_secs = 1);
                         record = future_record;
@@ -293,10 +447,26 @@ impl Sink<Event> for KafkaSink {
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         let this = Pin::into_inner(self);
+        ready!(this.poll_deliveries(cx))?;
+        this.poll_transaction(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = Pin::into_inner(self);
+        ready!(this.poll_deliveries(cx))?;
+        if this.transaction.is_some() {
+            ready!(this.poll_commit(cx, false))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
 
-        while !this.delivery_fut.is_empty() || !this.in_flight.is_empty() {
-            while let Poll::Ready(Some(item)) = this.in_flight.poll_next_unpin(cx) {
-                this.flush_signal.notify();
+impl KafkaSink {
+    /// Waits for the delivery of every message sent.
+    fn poll_deliveries(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        while !self.delivery_fut.is_empty() || !self.in_flight.is_empty() {
+            while let Poll::Ready(Some(item)) = self.in_flight.poll_next_unpin(cx) {
+                self.flush_signal.notify();
                 match item {
                     (seqno, Ok(result)) => {
                         match result {
@@ -306,14 +476,17 @@ impl Sink<Event> for KafkaSink {
                             Err(error) => error!(message = "Kafka error.", %error),
                         };
 
-                        this.pending_acks.insert(seqno);
+                        self.pending_acks.insert(seqno);
 
                         let mut num_to_ack = 0;
-                        while this.pending_acks.remove(&this.seq_tail) {
+                        while self.pending_acks.remove(&self.seq_tail) {
                             num_to_ack += 1;
-                            this.seq_tail += 1
+                            self.seq_tail += 1
+                        }
+                        match &mut self.transaction {
+                            Some(transaction) => transaction.delivered += num_to_ack,
+                            None => self.acker.ack(num_to_ack),
                         }
-                        this.acker.ack(num_to_ack);
                     }
                     (_seqno, Err(Canceled)) => {
                         error!(message = "Request canceled.");
@@ -322,14 +495,87 @@ impl Sink<Event> for KafkaSink {
                 }
             }
 
-            ready!(this.poll_delivery_fut(cx));
+            ready!(self.poll_delivery_fut(cx));
         }
 
         Poll::Ready(Ok(()))
     }
 
-    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.poll_flush(cx)
+    /// Commits the open transaction when `commit_interval_ms` has elapsed.
+    ///
+    /// This is polled both before sending events and when flushing, which
+    /// the sink is while no events come in, so that the events of an idle
+    /// sink are committed and acked too.
+    fn poll_transaction(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        if let Some(transaction) = &mut self.transaction {
+            if !transaction.due && transaction.interval.poll_tick(cx).is_ready() {
+                transaction.due = true;
+            }
+            if transaction.due {
+                ready!(self.poll_commit(cx, true))?;
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Commits the open transaction once its messages are delivered, and
+    /// begins the next one if `begin_next`.
+    fn poll_commit(&mut self, cx: &mut Context<'_>, begin_next: bool) -> Poll<Result<(), ()>> {
+        ready!(self.poll_deliveries(cx))?;
+
+        let producer = Arc::clone(&self.producer);
+        let transaction = self
+            .transaction
+            .as_mut()
+            .expect("No transaction to commit.");
+        let timeout = transaction.timeout;
+        let commit = transaction.commit.get_or_insert_with(|| {
+            Box::pin(async move {
+                tokio::task::spawn_blocking(move || {
+                    commit_transaction(&producer, timeout, begin_next)
+                })
+                .await
+                .expect("Kafka transaction commit panicked.")
+            })
+        });
+        let result = ready!(commit.poll_unpin(cx));
+        transaction.commit = None;
+        transaction.due = false;
+
+        match result {
+            Ok(()) => {
+                debug!(
+                    message = "Committed transaction.",
+                    count = transaction.delivered
+                );
+                self.acker.ack(transaction.delivered);
+                transaction.delivered = 0;
+                Poll::Ready(Ok(()))
+            }
+            Err(error) => {
+                // The events of the transaction are left unacked, to be sent
+                // again from the buffer once Vector restarts.
+                error!(message = "Failed to commit transaction.", %error);
+                Poll::Ready(Err(()))
+            }
+        }
+    }
+}
+
+/// Commits the open transaction, aborting it if it can't be committed.
+fn commit_transaction(
+    producer: &FutureProducer,
+    timeout: Duration,
+    begin_next: bool,
+) -> KafkaResult<()> {
+    match producer.commit_transaction(timeout) {
+        Ok(()) if begin_next => producer.begin_transaction(),
+        Ok(()) => Ok(()),
+        Err(KafkaError::Transaction(error)) if error.txn_requires_abort() => {
+            producer.abort_transaction(timeout)?;
+            Err(KafkaError::Transaction(error))
+        }
+        Err(error) => Err(error),
     }
 }
 
@@ -364,14 +610,28 @@ async fn healthcheck(config: KafkaSinkConfig) -> crate::Result<()> {
 
 fn encode_event(
     mut event: Event,
-    key_field: &Option<String>,
+    key: &Option<MessageKey>,
     encoding: &EncodingConfig<Encoding>,
 ) -> (Vec<u8>, Vec<u8>) {
-    let key = key_field
-        .as_ref()
-        .and_then(|f| event.as_log().get(f))
-        .map(|v| v.as_bytes().to_vec())
-        .unwrap_or_default();
+    let key = match key {
+        Some(MessageKey::Field(field)) => event
+            .as_log()
+            .get(field)
+            .map(|v| v.as_bytes().to_vec())
+            .unwrap_or_default(),
+        Some(MessageKey::Template(template)) => template
+            .render(&event)
+            .map(|key| key.to_vec())
+            .unwrap_or_else(|missing_keys| {
+                warn!(
+                    message = "Missing keys for message key, sending without key.",
+                    ?missing_keys,
+                    rate_limit_secs = 30
+                );
+                Vec::new()
+            }),
+        None => Vec::new(),
+    };
 
     encoding.apply_rules(&mut event);
 
@@ -387,6 +647,32 @@ fn encode_event(
     (key, body)
 }
 
+/// Renders the headers of the message of the event, leaving out the ones
+/// referring to missing fields.
+fn render_headers(event: &Event, headers: &[(String, Template)]) -> Option<OwnedHeaders> {
+    if headers.is_empty() {
+        return None;
+    }
+    let rendered = headers
+        .iter()
+        .filter_map(|(name, template)| match template.render(event) {
+            Ok(value) => Some((name, value)),
+            Err(missing_keys) => {
+                warn!(
+                    message = "Missing keys for header, sending without it.",
+                    header = %name,
+                    ?missing_keys,
+                    rate_limit_secs = 30
+                );
+                None
+            }
+        })
+        .fold(OwnedHeaders::new(), |headers, (name, value): (_, Bytes)| {
+            headers.add(name, &value[..])
+        });
+    Some(rendered)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,6 +684,35 @@ mod tests {
         crate::test_util::test_generate_config::<KafkaSinkConfig>();
     }
 
+    #[test]
+    fn kafka_transaction_options() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9091"
+            topic = "topic"
+            encoding.codec = "json"
+            transaction.id = "vector-1"
+            "#,
+        )
+        .unwrap();
+        let client_config = config.to_rdkafka().unwrap();
+        assert_eq!(client_config.get("enable.idempotence"), Some("true"));
+        assert_eq!(client_config.get("transactional.id"), Some("vector-1"));
+        assert_eq!(client_config.get("transaction.timeout.ms"), Some("60000"));
+
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9091"
+            topic = "topic"
+            encoding.codec = "json"
+            transaction.id = "vector-1"
+            transaction.commit_interval_ms = 60000
+            "#,
+        )
+        .unwrap();
+        assert!(config.to_rdkafka().is_err());
+    }
+
     #[test]
     fn kafka_encode_event_text() {
         let key = "";
@@ -421,7 +736,7 @@ mod tests {
 
         let (key, bytes) = encode_event(
             event,
-            &Some("key".into()),
+            &Some(MessageKey::Field("key".into())),
             &EncodingConfig::from(Encoding::Json),
         );
 
@@ -440,7 +755,7 @@ mod tests {
 
         let (key, bytes) = encode_event(
             event,
-            &Some("key".into()),
+            &Some(MessageKey::Field("key".into())),
             &EncodingConfigWithDefault {
                 codec: Encoding::Json,
                 except_fields: Some(vec!["key".into()]),
@@ -454,6 +769,67 @@ mod tests {
         assert_eq!(&key[..], b"value");
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kafka_encode_event_key_template() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("tenant", "acme");
+        event.as_mut_log().insert("user", "alice");
+
+        let key = MessageKey::Template(Template::try_from("{{ tenant }}/{{ user }}").unwrap());
+        let (key_bytes, _) = encode_event(
+            event.clone(),
+            &Some(key),
+            &EncodingConfig::from(Encoding::Text),
+        );
+        assert_eq!(&key_bytes[..], b"acme/alice");
+
+        let key = MessageKey::Template(Template::try_from("{{ missing }}").unwrap());
+        let (key_bytes, _) = encode_event(event, &Some(key), &EncodingConfig::from(Encoding::Text));
+        assert!(key_bytes.is_empty());
+    }
+
+    #[test]
+    fn kafka_render_headers() {
+        use rdkafka::message::Headers;
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("schema", "checkout-v2");
+
+        assert!(render_headers(&event, &[]).is_none());
+
+        let headers = vec![
+            (
+                "schema".to_owned(),
+                Template::try_from("{{ schema }}").unwrap(),
+            ),
+            ("source".to_owned(), Template::try_from("vector").unwrap()),
+            (
+                "route".to_owned(),
+                Template::try_from("{{ missing }}").unwrap(),
+            ),
+        ];
+        let headers = render_headers(&event, &headers).unwrap();
+        assert_eq!(headers.count(), 2);
+        assert_eq!(headers.get(0), Some(("schema", &b"checkout-v2"[..])));
+        assert_eq!(headers.get(1), Some(("source", &b"vector"[..])));
+    }
+
+    #[test]
+    fn kafka_key_conflict() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9091"
+            topic = "topic"
+            encoding.codec = "json"
+            key_field = "user_id"
+            key = "{{ tenant }}-{{ user_id }}"
+            "#,
+        )
+        .unwrap();
+        let (acker, _) = Acker::new_for_testing();
+        assert!(KafkaSink::new(config, acker).is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -466,12 +842,12 @@ mod integration_test {
         test_util::{random_lines_with_stream, random_string, wait_for},
         tls::TlsOptions,
     };
-    use futures::StreamExt;
+    use futures::{stream, StreamExt};
     use rdkafka::{
         consumer::{BaseConsumer, Consumer},
         Message, Offset, TopicPartitionList,
     };
-    use std::{future::ready, thread, time::Duration};
+    use std::{future::ready, sync::atomic::Ordering, thread, time::Duration};
 
     #[tokio::test]
     async fn healthcheck() {
@@ -527,6 +903,8 @@ mod integration_test {
             compression: KafkaCompression::None,
             encoding: EncodingConfigWithDefault::from(Encoding::Text),
             key_field: None,
+            key: None,
+            headers: IndexMap::new(),
             auth: KafkaAuthConfig {
                 sasl: None,
                 tls: None,
@@ -535,6 +913,8 @@ mod integration_test {
             message_timeout_ms: 300000,
             batch,
             librdkafka_options,
+            idempotence: false,
+            transaction: None,
         };
         let (acker, _ack_counter) = Acker::new_for_testing();
         KafkaSink::new(config, acker)
@@ -638,6 +1018,34 @@ mod integration_test {
         .await;
     }
 
+    #[tokio::test]
+    async fn kafka_transaction_commits_when_idle() {
+        let config = KafkaSinkConfig {
+            bootstrap_servers: "localhost:9091".into(),
+            topic: format!("test-{}", random_string(10)),
+            encoding: EncodingConfigWithDefault::from(Encoding::Text),
+            socket_timeout_ms: 60000,
+            message_timeout_ms: 300000,
+            transaction: Some(KafkaTransactionConfig {
+                id: random_string(10),
+                commit_interval_ms: 100,
+                timeout_ms: 10000,
+            }),
+            ..Default::default()
+        };
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let sink = KafkaSink::new(config, acker).unwrap();
+        sink.begin_transactions().await.unwrap();
+
+        let num_events = 10;
+        let (_input, events) = random_lines_with_stream(100, num_events);
+        // The input stays open once the events are sent, so their transaction
+        // is only committed by the sink flushing while it's idle.
+        tokio::spawn(events.chain(stream::pending()).map(Ok).forward(sink));
+
+        wait_for(|| ready(ack_counter.load(Ordering::Relaxed) == num_events)).await;
+    }
+
     async fn kafka_happy_path(
         server: &str,
         sasl: Option<KafkaSaslConfig>,
@@ -714,9 +1122,6 @@ mod integration_test {
         assert_eq!(out.len(), input.len());
         assert_eq!(out, input);
 
-        assert_eq!(
-            ack_counter.load(std::sync::atomic::Ordering::Relaxed),
-            num_events
-        );
+        assert_eq!(ack_counter.load(Ordering::Relaxed), num_events);
     }
 }
@@ -91,14 +91,19 @@ impl SinkConfig for LokiConfig {
             return Err("`labels` must include at least one label.".into());
         }
 
-        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        // Loki rejects entries older than the last one of their stream, so
+        // the requests of a tenant must not overtake each other.
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig {
+            ordered: Some(true),
+            ..Default::default()
+        });
         let batch_settings = BatchSettings::default()
             .bytes(102_400)
             .events(100_000)
             .timeout(1)
             .parse_config(self.batch)?;
         let tls = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls)?;
+        let client = HttpClient::new(tls)?.with_headers(self.request.headers(&cx)?);
 
         let sink = PartitionHttpSink::new(
             self.clone(),
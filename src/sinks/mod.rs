@@ -5,6 +5,8 @@ use std::fmt;
 
 pub mod util;
 
+#[cfg(feature = "sinks-amqp")]
+pub mod amqp;
 #[cfg(feature = "sinks-aws_cloudwatch_logs")]
 pub mod aws_cloudwatch_logs;
 #[cfg(feature = "sinks-aws_cloudwatch_metrics")]
@@ -17,10 +19,14 @@ pub mod aws_kinesis_streams;
 pub mod aws_s3;
 #[cfg(feature = "sinks-aws_sqs")]
 pub mod aws_sqs;
+#[cfg(feature = "sinks-azure_blob")]
+pub mod azure_blob;
 #[cfg(feature = "sinks-azure_monitor_logs")]
 pub mod azure_monitor_logs;
 #[cfg(feature = "sinks-blackhole")]
 pub mod blackhole;
+#[cfg(feature = "sinks-cassandra")]
+pub mod cassandra;
 #[cfg(feature = "sinks-clickhouse")]
 pub mod clickhouse;
 #[cfg(feature = "sinks-console")]
@@ -31,7 +37,7 @@ pub mod datadog;
 pub mod elasticsearch;
 #[cfg(feature = "sinks-file")]
 pub mod file;
-#[cfg(feature = "sinks-gcp")]
+#[cfg(any(feature = "sinks-gcp", feature = "sources-gcp_cloud_logging"))]
 pub mod gcp;
 #[cfg(feature = "sinks-honeycomb")]
 pub mod honeycomb;
@@ -49,24 +55,40 @@ pub mod logdna;
 pub mod loki;
 #[cfg(feature = "sinks-nats")]
 pub mod nats;
+#[cfg(feature = "sinks-new_relic")]
+pub mod new_relic;
 #[cfg(feature = "sinks-new_relic_logs")]
 pub mod new_relic_logs;
+#[cfg(feature = "sinks-opentelemetry")]
+pub mod opentelemetry;
 #[cfg(feature = "sinks-papertrail")]
 pub mod papertrail;
 #[cfg(feature = "sinks-prometheus")]
 pub mod prometheus;
 #[cfg(feature = "sinks-pulsar")]
 pub mod pulsar;
+#[cfg(feature = "sinks-redis")]
+pub mod redis;
 #[cfg(feature = "sinks-sematext")]
 pub mod sematext;
+#[cfg(feature = "sinks-snowflake")]
+pub mod snowflake;
 #[cfg(feature = "sinks-socket")]
 pub mod socket;
 #[cfg(feature = "sinks-splunk_hec")]
 pub mod splunk_hec;
+#[cfg(feature = "sinks-spool")]
+pub mod spool;
 #[cfg(feature = "sinks-statsd")]
 pub mod statsd;
+#[cfg(feature = "sinks-syslog")]
+pub mod syslog;
 #[cfg(feature = "sinks-vector")]
 pub mod vector;
+#[cfg(feature = "sinks-webhdfs")]
+pub mod webhdfs;
+#[cfg(feature = "sinks-websocket")]
+pub mod websocket;
 
 pub enum VectorSink {
     Sink(Box<dyn Sink<Event, Error = ()> + Send + Unpin>),
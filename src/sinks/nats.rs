@@ -3,21 +3,59 @@ use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     emit,
     event::Event,
-    internal_events::{NatsEventMissingKeys, NatsEventSendFail, NatsEventSendSuccess},
+    internal_events::{
+        NatsEventMissingKeys, NatsEventSendFail, NatsEventSendSuccess, NatsJetStreamMessageDropped,
+        NatsJetStreamPublishFailed,
+    },
     sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
     sinks::util::StreamSink,
     template::{Template, TemplateError},
 };
 use async_trait::async_trait;
 use futures::{stream::BoxStream, FutureExt, StreamExt, TryFutureExt};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::convert::TryFrom;
+use std::{collections::VecDeque, convert::TryFrom, time::Duration};
+use tokio::time::{delay_until, Instant};
+use uuid::Uuid;
+
+/// The longest we wait before publishing an event JetStream didn't store again.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("invalid subject template: {}", source))]
     SubjectTemplate { source: TemplateError },
+    #[snafu(display("invalid template for header {:?}: {}", name, source))]
+    HeaderTemplate { name: String, source: TemplateError },
+}
+
+#[derive(Debug, Snafu)]
+pub enum JetStreamError {
+    #[snafu(display("Failed to publish: {}", source))]
+    Publish { source: std::io::Error },
+    #[snafu(display("No acknowledgement received within {:?}", timeout))]
+    AckTimeout { timeout: Duration },
+    #[snafu(display("Acknowledgement subscription closed"))]
+    SubscriptionClosed,
+    #[snafu(display("No stream is bound to subject {:?}", subject))]
+    NoStream { subject: String },
+    #[snafu(display("JetStream rejected the message ({}): {}", code, description))]
+    Rejected { code: u16, description: String },
+    #[snafu(display("Invalid acknowledgement: {}", source))]
+    InvalidAck { source: serde_json::Error },
+}
+
+impl JetStreamError {
+    /// Whether publishing the message again can't change the outcome.
+    fn is_permanent(&self) -> bool {
+        match self {
+            JetStreamError::NoStream { .. } => true,
+            JetStreamError::Rejected { code, .. } => (400..500).contains(code),
+            _ => false,
+        }
+    }
 }
 
 /**
@@ -31,6 +69,40 @@ pub struct NatsSinkConfig {
     name: String,
     subject: String,
     url: String,
+    #[serde(default)]
+    headers: IndexMap<String, String>,
+    #[serde(default)]
+    jetstream: JetStreamConfig,
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct JetStreamConfig {
+    /// Publish to JetStream streams, only marking events as delivered once
+    /// the stream acknowledged storing them.
+    #[serde(default)]
+    pub enabled: bool,
+    /// The stream the subjects must be bound to, other streams reject the
+    /// messages.
+    pub stream: Option<String>,
+    /// How long to wait for the acknowledgement before publishing an event
+    /// again.
+    #[serde(default = "default_ack_timeout_secs")]
+    #[derivative(Default(value = "default_ack_timeout_secs()"))]
+    pub ack_timeout_secs: u64,
+    /// The messages published before their acknowledgement.
+    #[serde(default = "default_max_in_flight")]
+    #[derivative(Default(value = "default_max_in_flight()"))]
+    pub max_in_flight: usize,
+}
+
+fn default_ack_timeout_secs() -> u64 {
+    5
+}
+
+fn default_max_in_flight() -> usize {
+    256
 }
 
 fn default_name() -> String {
@@ -120,6 +192,8 @@ pub struct NatsSink {
     encoding: EncodingConfig<Encoding>,
     options: NatsOptions,
     subject: Template,
+    headers: Vec<(String, Template)>,
+    jetstream: JetStreamConfig,
     url: String,
     acker: Acker,
 }
@@ -130,6 +204,16 @@ impl NatsSink {
             acker,
             options: (&config).into(),
             subject: Template::try_from(config.subject).context(SubjectTemplate)?,
+            headers: config
+                .headers
+                .into_iter()
+                .map(|(name, value)| {
+                    let template =
+                        Template::try_from(value).context(HeaderTemplate { name: name.clone() })?;
+                    Ok((name, template))
+                })
+                .collect::<crate::Result<_>>()?,
+            jetstream: config.jetstream,
             url: config.url,
 
             // DEV: the following causes a move; needs to be last.
@@ -163,18 +247,31 @@ impl StreamSink for NatsSink {
             .connect_async(&self.url)
             .await
             .map_err(|_| ())?;
-
-        while let Some(event) = input.next().await {
-            let subject = self.subject.render_string(&event).map_err(|missing_keys| {
-                emit!(NatsEventMissingKeys {
-                    keys: &missing_keys
-                });
+        if self.jetstream.enabled {
+            let timeout = Duration::from_secs(self.jetstream.ack_timeout_secs);
+            let acks = JetStreamAcks::new(&nc, timeout).await.map_err(|error| {
+                emit!(NatsEventSendFail { error });
             })?;
+            return self.run_jetstream(&nc, acks, input).await;
+        }
 
-            let log = encode_event(event, &self.encoding);
+        while let Some(event) = input.next().await {
+            let (subject, headers, log) = match self.render_event(event) {
+                Some(message) => message,
+                None => {
+                    self.acker.ack(1);
+                    continue;
+                }
+            };
             let message_len = log.len();
 
-            match nc.publish(&subject, log).await {
+            let result = if headers.inner.is_empty() {
+                nc.publish(&subject, log).await
+            } else {
+                nc.publish_with_reply_or_headers(&subject, None, Some(&headers), log)
+                    .await
+            };
+            match result {
                 Ok(_) => {
                     emit!(NatsEventSendSuccess {
                         byte_size: message_len,
@@ -191,6 +288,300 @@ impl StreamSink for NatsSink {
     }
 }
 
+impl NatsSink {
+    /// Publishes up to `max_in_flight` messages before their
+    /// acknowledgements, and marks the events as delivered in order once
+    /// their messages are stored or dropped.
+    async fn run_jetstream(
+        &mut self,
+        nc: &nats::asynk::Connection,
+        mut acks: JetStreamAcks,
+        mut input: BoxStream<'_, Event>,
+    ) -> Result<(), ()> {
+        let max_in_flight = self.jetstream.max_in_flight.max(1);
+        let mut in_flight = VecDeque::new();
+        let mut input_done = false;
+
+        loop {
+            let done = take_done(&mut in_flight);
+            if done > 0 {
+                self.acker.ack(done);
+            }
+            if input_done && in_flight.is_empty() {
+                return Ok(());
+            }
+
+            let deadline = in_flight
+                .iter()
+                .filter(|message| message.stage != Stage::Done)
+                .map(|message| message.deadline)
+                .min();
+
+            tokio::select! {
+                event = input.next(), if !input_done && in_flight.len() < max_in_flight => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => {
+                            input_done = true;
+                            continue;
+                        }
+                    };
+                    let (subject, mut headers, payload) = match self.render_event(event) {
+                        Some(message) => message,
+                        None => {
+                            // Acked in order with the messages before it.
+                            in_flight.push_back(InFlight::dropped());
+                            continue;
+                        }
+                    };
+                    // The same message ID lets JetStream discard the copies of
+                    // messages we publish again after their acknowledgement was
+                    // lost.
+                    insert_header(&mut headers, "Nats-Msg-Id", Uuid::new_v4().to_string());
+                    if let Some(stream) = &self.jetstream.stream {
+                        insert_header(&mut headers, "Nats-Expected-Stream", stream.clone());
+                    }
+
+                    let mut message = InFlight {
+                        subject,
+                        headers,
+                        payload,
+                        reply: String::new(),
+                        stage: Stage::Awaiting,
+                        deadline: Instant::now(),
+                        backoff: Duration::from_secs(1),
+                    };
+                    acks.publish(nc, &mut message).await;
+                    in_flight.push_back(message);
+                }
+                reply = acks.subscription.next() => {
+                    let reply = reply.ok_or_else(|| {
+                        emit!(NatsJetStreamPublishFailed {
+                            error: JetStreamError::SubscriptionClosed.into()
+                        });
+                    })?;
+                    // Late acknowledgements of messages that timed out match
+                    // none of the messages, as those were published again with
+                    // another reply subject.
+                    if let Some(message) = in_flight.iter_mut().find(|message| {
+                        message.stage == Stage::Awaiting && message.reply == reply.subject
+                    }) {
+                        let result = parse_ack(&message.subject, &reply.data);
+                        message.resolve(result.map(|_| ()));
+                    }
+                }
+                _ = delay_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    let now = Instant::now();
+                    for message in in_flight.iter_mut() {
+                        if message.stage == Stage::Done || message.deadline > now {
+                            continue;
+                        }
+                        match message.stage {
+                            Stage::Awaiting => message.resolve(Err(JetStreamError::AckTimeout {
+                                timeout: acks.timeout,
+                            })),
+                            _ => acks.publish(nc, message).await,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The subject, headers and payload of the message of the event, or none
+    /// if the event is dropped.
+    fn render_event(&self, event: Event) -> Option<(String, nats::Headers, String)> {
+        let subject = self
+            .subject
+            .render_string(&event)
+            .map_err(|missing_keys| {
+                emit!(NatsEventMissingKeys {
+                    keys: &missing_keys
+                });
+            })
+            .ok()?;
+        let headers = self.render_headers(&event);
+
+        Some((subject, headers, encode_event(event, &self.encoding)))
+    }
+
+    fn render_headers(&self, event: &Event) -> nats::Headers {
+        let mut headers = nats::Headers::default();
+        for (name, template) in &self.headers {
+            match template.render_string(event) {
+                Ok(value) => insert_header(&mut headers, name, value),
+                Err(missing_keys) => warn!(
+                    message = "Missing keys for header, sending without it.",
+                    header = %name,
+                    ?missing_keys,
+                    rate_limit_secs = 30
+                ),
+            }
+        }
+        headers
+    }
+}
+
+fn insert_header(headers: &mut nats::Headers, name: &str, value: String) {
+    headers
+        .inner
+        .entry(name.to_owned())
+        .or_default()
+        .insert(value);
+}
+
+/// A message published to JetStream, kept until it's stored or dropped.
+struct InFlight {
+    subject: String,
+    headers: nats::Headers,
+    payload: String,
+    /// The reply subject of the latest publish of the message.
+    reply: String,
+    stage: Stage,
+    /// When the acknowledgement times out, or the message is published again.
+    deadline: Instant,
+    backoff: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Awaiting,
+    Backoff,
+    Done,
+}
+
+impl InFlight {
+    /// A dropped event, which is done right away.
+    fn dropped() -> Self {
+        Self {
+            subject: String::new(),
+            headers: nats::Headers::default(),
+            payload: String::new(),
+            reply: String::new(),
+            stage: Stage::Done,
+            deadline: Instant::now(),
+            backoff: Duration::from_secs(1),
+        }
+    }
+
+    fn resolve(&mut self, result: Result<(), JetStreamError>) {
+        match result {
+            Ok(()) => {
+                emit!(NatsEventSendSuccess {
+                    byte_size: self.payload.len(),
+                });
+                self.stage = Stage::Done;
+            }
+            Err(error) if error.is_permanent() => {
+                emit!(NatsJetStreamMessageDropped {
+                    error: error.into()
+                });
+                self.stage = Stage::Done;
+            }
+            Err(error) => {
+                emit!(NatsJetStreamPublishFailed {
+                    error: error.into()
+                });
+                self.stage = Stage::Backoff;
+                self.deadline = Instant::now() + self.backoff;
+                self.backoff = (self.backoff * 2).min(MAX_RETRY_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Removes the messages at the front which are done, returning how many
+/// events can be marked as delivered.
+fn take_done(in_flight: &mut VecDeque<InFlight>) -> usize {
+    let done = in_flight
+        .iter()
+        .take_while(|message| message.stage == Stage::Done)
+        .count();
+    in_flight.drain(..done);
+    done
+}
+
+/// Publishes messages to JetStream, with the acknowledgement of each
+/// received on a subscription to replies of the connection.
+struct JetStreamAcks {
+    inbox: String,
+    subscription: nats::asynk::Subscription,
+    sequence: u64,
+    timeout: Duration,
+}
+
+impl JetStreamAcks {
+    async fn new(nc: &nats::asynk::Connection, timeout: Duration) -> std::io::Result<Self> {
+        let inbox = format!("_INBOX.{}", Uuid::new_v4().to_simple());
+        let subscription = nc.subscribe(&format!("{}.*", inbox)).await?;
+        Ok(Self {
+            inbox,
+            subscription,
+            sequence: 0,
+            timeout,
+        })
+    }
+
+    /// Publishes the message with a new reply subject, awaiting its
+    /// acknowledgement until the timeout.
+    async fn publish(&mut self, nc: &nats::asynk::Connection, message: &mut InFlight) {
+        self.sequence += 1;
+        message.reply = format!("{}.{}", self.inbox, self.sequence);
+        message.stage = Stage::Awaiting;
+        message.deadline = Instant::now() + self.timeout;
+
+        let result = nc
+            .publish_with_reply_or_headers(
+                &message.subject,
+                Some(&message.reply),
+                Some(&message.headers),
+                &message.payload,
+            )
+            .await
+            .context(Publish);
+        if let Err(error) = result {
+            message.resolve(Err(error));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct PublishAck {
+    stream: String,
+    seq: u64,
+    #[serde(default)]
+    duplicate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PublishResponse {
+    Ack(PublishAck),
+    Error { error: ApiError },
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: u16,
+    description: String,
+}
+
+fn parse_ack(subject: &str, data: &[u8]) -> Result<PublishAck, JetStreamError> {
+    // Servers reply without a body when no stream is bound to the subject.
+    if data.is_empty() {
+        return Err(JetStreamError::NoStream {
+            subject: subject.to_owned(),
+        });
+    }
+    match serde_json::from_slice(data).context(InvalidAck)? {
+        PublishResponse::Ack(ack) => Ok(ack),
+        PublishResponse::Error { error } => Err(JetStreamError::Rejected {
+            code: error.code,
+            description: error.description,
+        }),
+    }
+}
+
 fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> String {
     encoding.apply_rules(&mut event);
 
@@ -215,6 +606,107 @@ mod tests {
         crate::test_util::test_generate_config::<NatsSinkConfig>();
     }
 
+    #[test]
+    fn renders_headers() {
+        let config: NatsSinkConfig = toml::from_str(
+            r#"
+            encoding.codec = "json"
+            subject = "logs.{{ app }}"
+            url = "nats://127.0.0.1:4222"
+            headers.X-App = "{{ app }}"
+            jetstream.enabled = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.jetstream.enabled);
+        assert_eq!(config.jetstream.ack_timeout_secs, 5);
+
+        let (acker, _) = Acker::new_for_testing();
+        let sink = NatsSink::new(config, acker).unwrap();
+        let mut event = Event::from("foo");
+        event.as_mut_log().insert("app", "api");
+        let headers = sink.render_headers(&event);
+        assert!(headers.inner["X-App"].contains("api"));
+
+        let event = Event::from("foo");
+        assert!(sink.render_headers(&event).inner.is_empty());
+        assert!(sink.render_event(event).is_none());
+    }
+
+    #[test]
+    fn parses_acks() {
+        assert_eq!(
+            parse_ack("logs", br#"{"stream":"LOGS","seq":7}"#).unwrap(),
+            PublishAck {
+                stream: "LOGS".to_owned(),
+                seq: 7,
+                duplicate: false,
+            }
+        );
+        assert!(matches!(
+            parse_ack(
+                "logs",
+                br#"{"error":{"code":400,"description":"expected stream does not match"}}"#
+            ),
+            Err(JetStreamError::Rejected { code: 400, .. })
+        ));
+        assert!(matches!(
+            parse_ack("logs", b""),
+            Err(JetStreamError::NoStream { .. })
+        ));
+    }
+
+    #[test]
+    fn drops_rejected_messages() {
+        assert!(JetStreamError::NoStream {
+            subject: "logs".to_owned()
+        }
+        .is_permanent());
+        assert!(JetStreamError::Rejected {
+            code: 400,
+            description: "expected stream does not match".to_owned()
+        }
+        .is_permanent());
+        assert!(!JetStreamError::Rejected {
+            code: 503,
+            description: "unavailable".to_owned()
+        }
+        .is_permanent());
+        assert!(!JetStreamError::AckTimeout {
+            timeout: Duration::from_secs(5)
+        }
+        .is_permanent());
+    }
+
+    #[test]
+    fn acknowledges_done_messages_in_order() {
+        let message = |stage| InFlight {
+            subject: "logs".to_owned(),
+            headers: nats::Headers::default(),
+            payload: "foo".to_owned(),
+            reply: String::new(),
+            stage,
+            deadline: Instant::now(),
+            backoff: Duration::from_secs(1),
+        };
+        let mut in_flight = vec![
+            message(Stage::Done),
+            message(Stage::Done),
+            message(Stage::Awaiting),
+            message(Stage::Done),
+        ]
+        .into_iter()
+        .collect::<VecDeque<_>>();
+
+        assert_eq!(take_done(&mut in_flight), 2);
+        assert_eq!(in_flight.len(), 2);
+        assert_eq!(take_done(&mut in_flight), 0);
+
+        in_flight[0].stage = Stage::Done;
+        assert_eq!(take_done(&mut in_flight), 2);
+        assert!(in_flight.is_empty());
+    }
+
     #[test]
     fn encodes_raw_logs() {
         let event = Event::from("foo");
@@ -0,0 +1,429 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
+    http::HttpClient,
+    internal_events::NewRelicMetricUnsupported,
+    sinks::{
+        util::{
+            encode_namespace,
+            http::{HttpBatchService, HttpRetryLogic},
+            BatchConfig, BatchSettings, BoxedRawValue, Compression, Concurrency, JsonArrayBuffer,
+            PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{TlsOptions, TlsSettings},
+};
+use chrono::Utc;
+use futures::{future, stream, FutureExt, SinkExt, StreamExt};
+use http::Request;
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map};
+use snafu::Snafu;
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc,
+};
+
+// The Log and Metric APIs accept payloads up to 1MB (10^6 bytes).
+const MAX_PAYLOAD_SIZE: usize = 1_000_000;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display(
+        "Too high batch max size. The value must be {} bytes or less",
+        MAX_PAYLOAD_SIZE
+    ))]
+    BatchMaxSize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum NewRelicRegion {
+    #[derivative(Default)]
+    Us,
+    Eu,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NewRelicConfig {
+    pub license_key: String,
+    #[serde(default)]
+    pub region: NewRelicRegion,
+    /// Attributes added to all the logs and metrics sent.
+    #[serde(default)]
+    pub common_attributes: IndexMap<String, String>,
+    #[serde(default = "Compression::gzip_default")]
+    pub compression: Compression,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(100),
+        rate_limit_num: Some(100),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<NewRelicConfig>("new_relic")
+}
+
+impl GenerateConfig for NewRelicConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"license_key = "${NEW_RELIC_LICENSE_KEY}""#).unwrap()
+    }
+}
+
+/// The API events are sent to, which batches are partitioned by.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum NewRelicApi {
+    Logs,
+    Metrics,
+}
+
+impl NewRelicApi {
+    fn uri(self, region: NewRelicRegion) -> &'static str {
+        match (self, region) {
+            (Self::Logs, NewRelicRegion::Us) => "https://log-api.newrelic.com/log/v1",
+            (Self::Logs, NewRelicRegion::Eu) => "https://log-api.eu.newrelic.com/log/v1",
+            (Self::Metrics, NewRelicRegion::Us) => "https://metric-api.newrelic.com/metric/v1",
+            (Self::Metrics, NewRelicRegion::Eu) => "https://metric-api.eu.newrelic.com/metric/v1",
+        }
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "new_relic")]
+impl SinkConfig for NewRelicConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let batch = BatchSettings::default()
+            .bytes(MAX_PAYLOAD_SIZE as u64)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        if batch.size.bytes > MAX_PAYLOAD_SIZE {
+            return Err(Box::new(BuildError::BatchMaxSize));
+        }
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?.with_headers(self.request.headers(&cx)?);
+
+        let sink = NewRelicSink {
+            config: self.clone(),
+            last_metrics_sent: Arc::new(AtomicI64::new(Utc::now().timestamp_millis())),
+        };
+        let svc = request.service(
+            HttpRetryLogic,
+            HttpBatchService::new(client, move |request| {
+                future::ready(sink.build_request(request))
+            }),
+        );
+
+        let buffer = PartitionBuffer::new(JsonArrayBuffer::new(batch.size));
+
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .ordered(request.ordered)
+            .sink_map_err(|error| error!(message = "Fatal new_relic sink error.", %error))
+            .with_flat_map(|event| stream::iter(encode_event(event)).map(Ok));
+
+        // New Relic doesn't provide an endpoint to check the license key against.
+        Ok((VectorSink::Sink(Box::new(sink)), future::ok(()).boxed()))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "new_relic"
+    }
+}
+
+struct NewRelicSink {
+    config: NewRelicConfig,
+    /// The counts of each metrics request are over the interval since the
+    /// time in milliseconds the previous one was built.
+    last_metrics_sent: Arc<AtomicI64>,
+}
+
+impl NewRelicSink {
+    fn build_request(
+        &self,
+        events: PartitionInnerBuffer<Vec<BoxedRawValue>, NewRelicApi>,
+    ) -> crate::Result<Request<Vec<u8>>> {
+        let (events, api) = events.into_parts();
+
+        let mut common = json!({ "attributes": self.config.common_attributes });
+        let body = match api {
+            NewRelicApi::Logs => json!([{ "common": common, "logs": events }]),
+            NewRelicApi::Metrics => {
+                let now = Utc::now().timestamp_millis();
+                let interval = now - self.last_metrics_sent.swap(now, Ordering::SeqCst);
+                common["interval.ms"] = json!(interval.max(1));
+                json!([{ "common": common, "metrics": events }])
+            }
+        };
+        let body = serde_json::to_vec(&body)?;
+
+        let mut request = Request::post(api.uri(self.config.region))
+            .header("Content-Type", "application/json")
+            .header("Api-Key", self.config.license_key.as_str());
+        let body = match self.config.compression.content_encoding() {
+            Some(encoding) => {
+                request = request.header("Content-Encoding", encoding);
+                self.config.compression.compress(&body)
+            }
+            None => body,
+        };
+
+        request.body(body).map_err(Into::into)
+    }
+}
+
+fn encode_event(event: Event) -> Option<PartitionInnerBuffer<serde_json::Value, NewRelicApi>> {
+    match event {
+        Event::Log(log) => Some(PartitionInnerBuffer::new(
+            encode_log(log),
+            NewRelicApi::Logs,
+        )),
+        Event::Metric(metric) => encode_metric(metric)
+            .map(|metric| PartitionInnerBuffer::new(metric, NewRelicApi::Metrics)),
+    }
+}
+
+/// Encodes the message and timestamp of the log as those of a New Relic log,
+/// with all its other fields as attributes, named by their path.
+fn encode_log(mut log: LogEvent) -> serde_json::Value {
+    let message = log
+        .remove(log_schema().message_key())
+        .map(|message| message.to_string_lossy())
+        .unwrap_or_default();
+    let timestamp = match log.remove(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(timestamp)) => timestamp,
+        _ => Utc::now(),
+    };
+
+    let attributes = log
+        .all_fields()
+        .map(|(path, value)| (path, json!(value)))
+        .collect::<Map<_, _>>();
+
+    json!({
+        "message": message,
+        "timestamp": timestamp.timestamp_millis(),
+        "attributes": attributes,
+    })
+}
+
+fn encode_metric(metric: Metric) -> Option<serde_json::Value> {
+    let (kind, value) = match metric.value {
+        MetricValue::Counter { value } if metric.kind == MetricKind::Incremental => {
+            ("count", json!(value))
+        }
+        MetricValue::Counter { value } | MetricValue::Gauge { value } => ("gauge", json!(value)),
+        MetricValue::Set { values } => ("gauge", json!(values.len())),
+        MetricValue::Distribution {
+            values,
+            sample_rates,
+            ..
+        } => {
+            if values.is_empty() {
+                return None;
+            }
+            let count = sample_rates.iter().map(|&rate| rate as f64).sum::<f64>();
+            let sum = values
+                .iter()
+                .zip(&sample_rates)
+                .map(|(value, &rate)| value * rate as f64)
+                .sum::<f64>();
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            (
+                "summary",
+                json!({ "count": count, "sum": sum, "min": min, "max": max }),
+            )
+        }
+        MetricValue::AggregatedHistogram { .. } | MetricValue::AggregatedSummary { .. } => {
+            emit!(NewRelicMetricUnsupported { name: &metric.name });
+            return None;
+        }
+    };
+
+    let timestamp = metric.timestamp.unwrap_or_else(Utc::now);
+    Some(json!({
+        "name": encode_namespace(metric.namespace.as_deref(), '.', metric.name),
+        "type": kind,
+        "value": value,
+        "timestamp": timestamp.timestamp_millis(),
+        "attributes": metric.tags.unwrap_or_default(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::util::test::load_sink;
+    use chrono::TimeZone;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NewRelicConfig>();
+    }
+
+    #[test]
+    fn encodes_logs_with_attributes() {
+        let mut event = Event::from("hello");
+        let log = event.as_mut_log();
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.timestamp(1_600_000_000, 0),
+        );
+        log.insert("service", "api");
+        log.insert("http.status", 200);
+
+        let log = encode_event(event).unwrap();
+        let (log, api) = log.into_parts();
+        assert_eq!(api, NewRelicApi::Logs);
+        assert_eq!(
+            log,
+            json!({
+                "message": "hello",
+                "timestamp": 1_600_000_000_000i64,
+                "attributes": { "http.status": 200, "service": "api" },
+            })
+        );
+    }
+
+    #[test]
+    fn encodes_metrics() {
+        let mut tags = BTreeMap::new();
+        tags.insert("host".to_string(), "a".to_string());
+        let metric = |kind, value| Metric {
+            name: "requests".into(),
+            namespace: Some("app".into()),
+            timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+            tags: Some(tags.clone()),
+            kind,
+            value,
+        };
+
+        let count = encode_metric(metric(
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 2.0 },
+        ))
+        .unwrap();
+        assert_eq!(
+            count,
+            json!({
+                "name": "app.requests",
+                "type": "count",
+                "value": 2.0,
+                "timestamp": 1_600_000_000_000i64,
+                "attributes": { "host": "a" },
+            })
+        );
+
+        let gauge = encode_metric(metric(
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 5.0 },
+        ))
+        .unwrap();
+        assert_eq!(gauge["type"], "gauge");
+
+        let summary = encode_metric(metric(
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                values: vec![1.0, 3.0],
+                sample_rates: vec![2, 1],
+                statistic: crate::event::metric::StatisticKind::Histogram,
+            },
+        ))
+        .unwrap();
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(
+            summary["value"],
+            json!({ "count": 3.0, "sum": 5.0, "min": 1.0, "max": 3.0 })
+        );
+
+        assert!(encode_metric(metric(
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![1.0],
+                counts: vec![1],
+                count: 1,
+                sum: 1.0,
+            },
+        ))
+        .is_none());
+    }
+
+    #[test]
+    fn rejects_batches_over_the_payload_limit() {
+        let (config, _cx) = load_sink::<NewRelicConfig>(
+            r#"
+            license_key = "foo"
+            batch.max_bytes = 2000000
+            "#,
+        )
+        .unwrap();
+        let error = futures::executor::block_on(config.build(SinkContext::new_test()))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Too high batch max size. The value must be 1000000 bytes or less"
+        );
+    }
+
+    #[test]
+    fn builds_requests_for_each_api() {
+        let (config, _cx) = load_sink::<NewRelicConfig>(
+            r#"
+            license_key = "foo"
+            compression = "none"
+            common_attributes.environment = "production"
+            "#,
+        )
+        .unwrap();
+
+        let sink = NewRelicSink {
+            config,
+            last_metrics_sent: Arc::new(AtomicI64::new(0)),
+        };
+        let logs = vec![serde_json::value::to_raw_value(&json!({"message": "hello"})).unwrap()];
+        let request = sink
+            .build_request(PartitionInnerBuffer::new(logs, NewRelicApi::Logs))
+            .unwrap();
+        assert_eq!(request.uri(), "https://log-api.newrelic.com/log/v1");
+        assert_eq!(request.headers()["Api-Key"], "foo");
+        let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        assert_eq!(
+            body,
+            json!([{
+                "common": { "attributes": { "environment": "production" } },
+                "logs": [{ "message": "hello" }],
+            }])
+        );
+
+        let request = sink
+            .build_request(PartitionInnerBuffer::new(vec![], NewRelicApi::Metrics))
+            .unwrap();
+        assert_eq!(request.uri(), "https://metric-api.newrelic.com/metric/v1");
+        let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        assert!(body[0]["common"]["interval.ms"].as_i64().unwrap() > 0);
+    }
+}
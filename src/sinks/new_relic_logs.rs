@@ -137,7 +137,7 @@ impl NewRelicLogsConfig {
             // conservative so we crank them up for New Relic.
             concurrency: (self.request.concurrency).if_none(Concurrency::Fixed(100)),
             rate_limit_num: Some(self.request.rate_limit_num.unwrap_or(100)),
-            ..self.request
+            ..self.request.clone()
         };
 
         Ok(HttpSinkConfig {
@@ -153,6 +153,8 @@ impl NewRelicLogsConfig {
             request,
 
             tls: None,
+            idempotency: None,
+            trace_context_key: None,
         })
     }
 }
@@ -0,0 +1,1191 @@
+//! Exports logs and metrics to an OTLP endpoint, such as the one of the
+//! OpenTelemetry collector, over either [OTLP/HTTP] or [OTLP/gRPC] with the
+//! protobuf encoding of the protocol.
+//!
+//! The events of a batch share their resource, so the batches are
+//! partitioned by both the signal and the resource attributes of the events.
+//! Vector has no trace events, so traces aren't exported, and the messages of
+//! OTLP/gRPC are sent uncompressed, which tonic doesn't support yet.
+//!
+//! [OTLP/HTTP]: https://opentelemetry.io/docs/specs/otlp/#otlphttp
+//! [OTLP/gRPC]: https://opentelemetry.io/docs/specs/otlp/#otlpgrpc
+
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    dns,
+    event::{
+        metric::{MetricKind, MetricValue},
+        Event, LogEvent, Metric, Value,
+    },
+    http::{Auth, HttpClient},
+    sinks::util::{
+        http::{HttpSink, PartitionHttpSink},
+        retries::RetryLogic,
+        BatchConfig, BatchSettings, Compression, Concurrency, EncodedLength, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, UriSerde, VecBuffer,
+    },
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsOptions, TlsSettings},
+};
+use chrono::{DateTime, Utc};
+use futures::{future, future::BoxFuture, stream, FutureExt, SinkExt};
+use http::{
+    header::{HeaderName, HeaderValue},
+    HeaderMap, Request, Uri,
+};
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::net::TcpStream;
+use tonic::{
+    metadata::MetadataMap,
+    transport::{Channel, Endpoint},
+    Code, Status,
+};
+use tower::Service;
+use tracing_futures::Instrument;
+
+mod proto {
+    pub mod collector {
+        pub mod logs {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.logs.v1.rs"
+                ));
+            }
+        }
+
+        pub mod metrics {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.metrics.v1.rs"
+                ));
+            }
+        }
+    }
+
+    pub mod common {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.common.v1.rs"
+            ));
+        }
+    }
+
+    pub mod logs {
+        pub mod v1 {
+            include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.logs.v1.rs"));
+        }
+    }
+
+    pub mod metrics {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.metrics.v1.rs"
+            ));
+        }
+    }
+
+    pub mod resource {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.resource.v1.rs"
+            ));
+        }
+    }
+}
+
+use proto::{
+    collector::{
+        logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
+        metrics::v1::{metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest},
+    },
+    common::v1::{any_value, AnyValue, ArrayValue, InstrumentationScope, KeyValue, KeyValueList},
+    logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+    metrics::v1 as metrics,
+    resource::v1::Resource,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpenTelemetryConfig {
+    endpoint: UriSerde,
+    #[serde(default)]
+    protocol: Protocol,
+    #[serde(default)]
+    resource: BTreeMap<String, String>,
+    #[serde(default)]
+    resource_fields: BTreeMap<String, String>,
+    headers: Option<IndexMap<String, String>>,
+    auth: Option<Auth>,
+    /// The compression of the requests of OTLP/HTTP, gzip by default. The
+    /// messages of OTLP/gRPC can only be sent uncompressed.
+    #[serde(default)]
+    compression: Option<Compression>,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Protocol {
+    #[derivative(Default)]
+    Http,
+    Grpc,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(10),
+        timeout_secs: Some(30),
+        rate_limit_num: Some(1000),
+        ..Default::default()
+    };
+}
+
+inventory::submit! {
+    SinkDescription::new::<OpenTelemetryConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpenTelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:4318"
+            resource."service.name" = "vector"
+            resource_fields."host.name" = "host""#,
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Missing host in endpoint"))]
+    MissingHost,
+    #[snafu(display("OTLP/gRPC messages can't be compressed, set `compression` to \"none\""))]
+    GrpcCompression,
+    #[snafu(display("Traces can't be exported, as Vector has no trace events"))]
+    TracesEndpoint,
+}
+
+#[derive(Debug, Snafu)]
+enum ConnectError {
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    Tls { source: crate::tls::TlsError },
+}
+
+#[derive(Debug, Snafu)]
+enum RequestError {
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: tonic::transport::Error },
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: Status },
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SinkConfig for OpenTelemetryConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let compressed = self
+            .compression
+            .map_or(false, |compression| compression != Compression::None);
+        if self.protocol == Protocol::Grpc && compressed {
+            return Err(BuildError::GrpcCompression.into());
+        }
+        let endpoint = Uri::from(self.endpoint.clone());
+        if endpoint
+            .path()
+            .trim_end_matches('/')
+            .ends_with("/v1/traces")
+        {
+            return Err(BuildError::TracesEndpoint.into());
+        }
+
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(4u64))
+            .events(1000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+        let tls = TlsSettings::from_options(&self.tls)?;
+
+        let sink = match self.protocol {
+            Protocol::Http => {
+                let client = HttpClient::new(tls)?.with_headers(self.request.headers(&cx)?);
+                let sink = PartitionHttpSink::new(
+                    self.clone(),
+                    buffer,
+                    request,
+                    batch.timeout,
+                    client,
+                    cx.acker(),
+                )
+                .sink_map_err(|error| error!(message = "Fatal opentelemetry sink error.", %error));
+                super::VectorSink::Sink(Box::new(sink))
+            }
+            Protocol::Grpc => {
+                let client = GrpcClient::new(self, tls, self.request.headers(&cx)?)?;
+                let config = self.clone();
+                let sink = request
+                    .partition_sink(GrpcRetryLogic, client, buffer, batch.timeout, cx.acker())
+                    .sink_map_err(
+                        |error| error!(message = "Fatal opentelemetry sink error.", %error),
+                    )
+                    .with_flat_map(move |event| {
+                        stream::iter(Some(Ok(config.encode_record(event))))
+                    });
+                super::VectorSink::Sink(Box::new(sink))
+            }
+        };
+
+        // OTLP has no endpoint to check the health of the receiver with.
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((sink, healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+enum Signal {
+    Logs,
+    Metrics,
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+struct PartitionKey {
+    signal: Signal,
+    resource: BTreeMap<String, String>,
+}
+
+/// A log record or a metric, batched with the others of its signal.
+#[derive(Clone, Debug, PartialEq)]
+enum Record {
+    Log(LogRecord),
+    Metric(metrics::Metric),
+}
+
+impl EncodedLength for Record {
+    fn encoded_length(&self) -> usize {
+        match self {
+            Self::Log(record) => record.encoded_len(),
+            Self::Metric(metric) => metric.encoded_len(),
+        }
+    }
+}
+
+/// The export request of a batch, which is the same for both transports.
+enum ExportRequest {
+    Logs(ExportLogsServiceRequest),
+    Metrics(ExportMetricsServiceRequest),
+}
+
+impl ExportRequest {
+    fn new(batch: PartitionInnerBuffer<Vec<Record>, PartitionKey>) -> Self {
+        let (records, key) = batch.into_parts();
+        let resource = Some(Resource {
+            attributes: string_attributes(&key.resource),
+            dropped_attributes_count: 0,
+        });
+        let scope = Some(InstrumentationScope {
+            name: "vector".into(),
+            version: crate::get_version(),
+        });
+        match key.signal {
+            Signal::Logs => Self::Logs(ExportLogsServiceRequest {
+                resource_logs: vec![ResourceLogs {
+                    resource,
+                    scope_logs: vec![ScopeLogs {
+                        scope,
+                        log_records: records
+                            .into_iter()
+                            .filter_map(|record| match record {
+                                Record::Log(record) => Some(record),
+                                Record::Metric(_) => None,
+                            })
+                            .collect(),
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                }],
+            }),
+            Signal::Metrics => Self::Metrics(ExportMetricsServiceRequest {
+                resource_metrics: vec![metrics::ResourceMetrics {
+                    resource,
+                    scope_metrics: vec![metrics::ScopeMetrics {
+                        scope,
+                        metrics: records
+                            .into_iter()
+                            .filter_map(|record| match record {
+                                Record::Metric(metric) => Some(metric),
+                                Record::Log(_) => None,
+                            })
+                            .collect(),
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                }],
+            }),
+        }
+    }
+
+    /// The path of OTLP/HTTP the request is sent to.
+    fn path(&self) -> &'static str {
+        match self {
+            Self::Logs(_) => "v1/logs",
+            Self::Metrics(_) => "v1/metrics",
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        match self {
+            Self::Logs(request) => request.encode(&mut body),
+            Self::Metrics(request) => request.encode(&mut body),
+        }
+        .expect("Writing to a Vec can't fail.");
+        body
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for OpenTelemetryConfig {
+    type Input = PartitionInnerBuffer<Record, PartitionKey>;
+    type Output = PartitionInnerBuffer<Vec<Record>, PartitionKey>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        Some(self.encode_record(event))
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<Request<Vec<u8>>> {
+        let request = ExportRequest::new(output);
+        let mut body = request.encode();
+
+        let mut builder = Request::post(self.uri(request.path()))
+            .header("Content-Type", "application/x-protobuf");
+        let compression = self.compression.unwrap_or_else(Compression::gzip_default);
+        if let Some(ce) = compression.content_encoding() {
+            builder = builder.header("Content-Encoding", ce);
+            body = compression.compress(&body);
+        }
+        for (header, value) in self.headers.iter().flatten() {
+            builder = builder.header(header.as_str(), value.as_str());
+        }
+
+        let mut request = builder.body(body)?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+        Ok(request)
+    }
+}
+
+impl OpenTelemetryConfig {
+    fn encode_record(&self, event: Event) -> PartitionInnerBuffer<Record, PartitionKey> {
+        let (signal, resource, record) = match event {
+            Event::Log(mut log) => {
+                let resource = self.resource(|field| {
+                    log.remove(field)
+                        .map(|value| String::from_utf8_lossy(&value.as_bytes()).into_owned())
+                });
+                (Signal::Logs, resource, Record::Log(encode_log(log)))
+            }
+            Event::Metric(mut metric) => {
+                let resource =
+                    self.resource(|field| metric.tags.as_mut().and_then(|tags| tags.remove(field)));
+                (
+                    Signal::Metrics,
+                    resource,
+                    Record::Metric(encode_metric(metric)),
+                )
+            }
+        };
+
+        PartitionInnerBuffer::new(record, PartitionKey { signal, resource })
+    }
+
+    /// The resource attributes of an event, taking the values of the
+    /// `resource_fields` from the event with `take`.
+    fn resource(&self, mut take: impl FnMut(&str) -> Option<String>) -> BTreeMap<String, String> {
+        let mut resource = self.resource.clone();
+        for (attribute, field) in &self.resource_fields {
+            if let Some(value) = take(field) {
+                resource.insert(attribute.clone(), value);
+            }
+        }
+        resource
+    }
+
+    fn uri(&self, path: &str) -> Uri {
+        let endpoint = self.endpoint.to_string();
+        format!("{}/{}", endpoint.trim_end_matches('/'), path)
+            .parse()
+            .expect("The endpoint is a valid URI.")
+    }
+}
+
+/// Connects lazily, as the receiver may not be up yet, and shares the
+/// connection between the requests of both signals as gRPC multiplexes them
+/// over it.
+#[derive(Clone)]
+struct GrpcClient {
+    endpoint: Endpoint,
+    connector: Connector,
+    /// The headers and the authorization sent with each request.
+    headers: HeaderMap,
+    channel: Arc<Mutex<Option<Channel>>>,
+}
+
+impl GrpcClient {
+    fn new(
+        config: &OpenTelemetryConfig,
+        tls: TlsSettings,
+        mut headers: HeaderMap,
+    ) -> crate::Result<Self> {
+        let uri = Uri::from(config.endpoint.clone());
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_owned();
+        let (port, tls) = match uri.scheme_str() {
+            Some("https") => (443, tls.alpn_protocols(b"\x02h2").into()),
+            _ => (80, MaybeTlsSettings::Raw(())),
+        };
+
+        for (name, value) in config.headers.iter().flatten() {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        if let Some(auth) = &config.auth {
+            auth.apply_headers_map(&mut headers);
+        }
+
+        Ok(Self {
+            endpoint: Channel::builder(uri.clone()),
+            connector: Connector {
+                host,
+                port: uri.port_u16().unwrap_or(port),
+                tls,
+            },
+            headers,
+            channel: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    async fn channel(&self) -> Result<Channel, RequestError> {
+        let channel = self.channel.lock().unwrap().clone();
+        if let Some(channel) = channel {
+            return Ok(channel);
+        }
+
+        let channel = self
+            .endpoint
+            .connect_with_connector(self.connector.clone())
+            .await
+            .context(Connect)?;
+        *self.channel.lock().unwrap() = Some(channel.clone());
+        Ok(channel)
+    }
+
+    fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        *request.metadata_mut() = MetadataMap::from_headers(self.headers.clone());
+        request
+    }
+}
+
+impl Service<PartitionInnerBuffer<Vec<Record>, PartitionKey>> for GrpcClient {
+    type Response = ();
+    type Error = RequestError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, batch: PartitionInnerBuffer<Vec<Record>, PartitionKey>) -> Self::Future {
+        let request = ExportRequest::new(batch);
+        let this = self.clone();
+
+        Box::pin(
+            async move {
+                let channel = this.channel().await?;
+                let rejected = match request {
+                    ExportRequest::Logs(request) => LogsServiceClient::new(channel)
+                        .export(this.request(request))
+                        .await
+                        .context(Request)?
+                        .into_inner()
+                        .partial_success
+                        .map(|partial| (partial.rejected_log_records, partial.error_message)),
+                    ExportRequest::Metrics(request) => MetricsServiceClient::new(channel)
+                        .export(this.request(request))
+                        .await
+                        .context(Request)?
+                        .into_inner()
+                        .partial_success
+                        .map(|partial| (partial.rejected_data_points, partial.error_message)),
+                };
+
+                // The rejected records aren't retried, as the receiver
+                // wouldn't accept them again.
+                if let Some((rejected, error_message)) = rejected {
+                    if rejected > 0 {
+                        warn!(
+                            message = "Records were rejected by the receiver.",
+                            rejected,
+                            %error_message,
+                            rate_limit_secs = 30,
+                        );
+                    }
+                }
+                Ok(())
+            }
+            .instrument(info_span!("request")),
+        )
+    }
+}
+
+/// Opens the connections of the client, with the TLS settings of the sink.
+#[derive(Clone)]
+struct Connector {
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+}
+
+impl Service<Uri> for Connector {
+    type Response = MaybeTlsStream<TcpStream>;
+    type Error = ConnectError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let ip = dns::Resolver
+                .lookup_ip(this.host.clone())
+                .await
+                .context(Dns)?
+                .next()
+                .ok_or(ConnectError::NoAddresses)?;
+            let addr = SocketAddr::new(ip, this.port);
+            this.tls.connect(&this.host, &addr).await.context(Tls)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GrpcRetryLogic;
+
+impl RetryLogic for GrpcRetryLogic {
+    type Error = RequestError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            RequestError::Connect { .. } => true,
+            // The codes OTLP specifies as retryable.
+            RequestError::Request { source } => matches!(
+                source.code(),
+                Code::Cancelled
+                    | Code::DeadlineExceeded
+                    | Code::ResourceExhausted
+                    | Code::Aborted
+                    | Code::OutOfRange
+                    | Code::Unavailable
+                    | Code::DataLoss
+            ),
+        }
+    }
+}
+
+fn encode_log(mut log: LogEvent) -> LogRecord {
+    let mut record = LogRecord {
+        observed_time_unix_nano: nanos(Utc::now()),
+        ..Default::default()
+    };
+    if let Some(Value::Timestamp(timestamp)) = log.remove(log_schema().timestamp_key()) {
+        record.time_unix_nano = nanos(timestamp);
+    }
+    if let Some(message) = log.remove(log_schema().message_key()) {
+        record.body = Some(any_value(&message));
+    }
+    record.attributes = attributes(log.as_map());
+    record
+}
+
+fn encode_metric(metric: Metric) -> metrics::Metric {
+    use metrics::{metric::Data, number_data_point::Value as NumberValue};
+
+    let name = match &metric.namespace {
+        Some(namespace) => format!("{}.{}", namespace, metric.name),
+        None => metric.name.clone(),
+    };
+    let time_unix_nano = nanos(metric.timestamp.unwrap_or_else(Utc::now));
+    let attributes = metric
+        .tags
+        .as_ref()
+        .map_or_else(Vec::new, string_attributes);
+    let temporality = match metric.kind {
+        MetricKind::Incremental => metrics::AggregationTemporality::Delta,
+        MetricKind::Absolute => metrics::AggregationTemporality::Cumulative,
+    } as i32;
+    let number_point = |attributes, value| metrics::NumberDataPoint {
+        attributes,
+        start_time_unix_nano: 0,
+        time_unix_nano,
+        value: Some(value),
+    };
+
+    let data = match metric.value {
+        MetricValue::Counter { value } => Data::Sum(metrics::Sum {
+            data_points: vec![number_point(attributes, NumberValue::AsDouble(value))],
+            aggregation_temporality: temporality,
+            is_monotonic: true,
+        }),
+        MetricValue::Gauge { value } => Data::Gauge(metrics::Gauge {
+            data_points: vec![number_point(attributes, NumberValue::AsDouble(value))],
+        }),
+        MetricValue::Set { values } => Data::Gauge(metrics::Gauge {
+            data_points: vec![number_point(
+                attributes,
+                NumberValue::AsInt(values.len() as i64),
+            )],
+        }),
+        MetricValue::Distribution {
+            values,
+            sample_rates,
+            ..
+        } => {
+            // The samples are only reported as their count and sum, as there
+            // are no buckets to count them into.
+            let count = sample_rates
+                .iter()
+                .map(|rate| u64::from(*rate))
+                .sum::<u64>();
+            let sum = values
+                .iter()
+                .zip(&sample_rates)
+                .map(|(value, rate)| value * f64::from(*rate))
+                .sum::<f64>();
+            Data::Histogram(metrics::Histogram {
+                data_points: vec![metrics::HistogramDataPoint {
+                    attributes,
+                    start_time_unix_nano: 0,
+                    time_unix_nano,
+                    count,
+                    sum,
+                    bucket_counts: vec![count],
+                    explicit_bounds: vec![],
+                }],
+                aggregation_temporality: temporality,
+            })
+        }
+        MetricValue::AggregatedHistogram {
+            buckets,
+            counts,
+            count,
+            sum,
+        } => {
+            // The observations above the last bucket are counted in an
+            // implicit overflow bucket.
+            let overflow = count.saturating_sub(counts.iter().sum());
+            Data::Histogram(metrics::Histogram {
+                data_points: vec![metrics::HistogramDataPoint {
+                    attributes,
+                    start_time_unix_nano: 0,
+                    time_unix_nano,
+                    count: count.into(),
+                    sum,
+                    bucket_counts: counts
+                        .iter()
+                        .chain(Some(&overflow))
+                        .map(|count| u64::from(*count))
+                        .collect(),
+                    explicit_bounds: buckets,
+                }],
+                aggregation_temporality: temporality,
+            })
+        }
+        MetricValue::AggregatedSummary {
+            quantiles,
+            values,
+            count,
+            sum,
+        } => Data::Summary(metrics::Summary {
+            data_points: vec![metrics::SummaryDataPoint {
+                attributes,
+                start_time_unix_nano: 0,
+                time_unix_nano,
+                count: count.into(),
+                sum,
+                quantile_values: quantiles
+                    .iter()
+                    .zip(&values)
+                    .map(
+                        |(quantile, value)| metrics::summary_data_point::ValueAtQuantile {
+                            quantile: *quantile,
+                            value: *value,
+                        },
+                    )
+                    .collect(),
+            }],
+        }),
+    };
+
+    metrics::Metric {
+        name,
+        description: String::new(),
+        unit: String::new(),
+        data: Some(data),
+    }
+}
+
+/// Encodes a timestamp as its nanoseconds since the epoch.
+fn nanos(timestamp: DateTime<Utc>) -> u64 {
+    timestamp.timestamp_nanos() as u64
+}
+
+fn attributes(fields: &BTreeMap<String, Value>) -> Vec<KeyValue> {
+    fields
+        .iter()
+        .map(|(key, value)| KeyValue {
+            key: key.clone(),
+            value: Some(any_value(value)),
+        })
+        .collect()
+}
+
+fn string_attributes(fields: &BTreeMap<String, String>) -> Vec<KeyValue> {
+    fields
+        .iter()
+        .map(|(key, value)| KeyValue {
+            key: key.clone(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.clone())),
+            }),
+        })
+        .collect()
+}
+
+fn any_value(value: &Value) -> AnyValue {
+    let value = match value {
+        Value::Bytes(bytes) => Some(any_value::Value::StringValue(
+            String::from_utf8_lossy(bytes).into_owned(),
+        )),
+        Value::Timestamp(timestamp) => Some(any_value::Value::StringValue(timestamp.to_rfc3339())),
+        Value::Integer(integer) => Some(any_value::Value::IntValue(*integer)),
+        Value::Float(float) => Some(any_value::Value::DoubleValue(*float)),
+        Value::Boolean(boolean) => Some(any_value::Value::BoolValue(*boolean)),
+        Value::Map(map) => Some(any_value::Value::KvlistValue(KeyValueList {
+            values: attributes(map),
+        })),
+        Value::Array(items) => Some(any_value::Value::ArrayValue(ArrayValue {
+            values: items.iter().map(any_value).collect(),
+        })),
+        Value::Null => None,
+    };
+    AnyValue { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::{next_addr, wait_for_tcp},
+    };
+    use bytes::buf::BufExt;
+    use flate2::read::GzDecoder;
+    use futures::{channel::mpsc, StreamExt};
+    use pretty_assertions::assert_eq;
+    use proto::collector::{
+        logs::v1::{
+            logs_service_server::{LogsService, LogsServiceServer},
+            ExportLogsServiceResponse,
+        },
+        metrics::v1::{
+            metrics_service_server::{MetricsService, MetricsServiceServer},
+            ExportMetricsServiceResponse,
+        },
+    };
+    use std::io::Read;
+    use tonic::transport::Server;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpenTelemetryConfig>();
+    }
+
+    fn config() -> OpenTelemetryConfig {
+        toml::from_str(
+            r#"
+            endpoint = "http://localhost:4318/"
+            resource."service.name" = "checkout"
+            resource_fields."host.name" = "host"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn timestamp() -> DateTime<Utc> {
+        "2021-01-12T10:31:03.123456789Z".parse().unwrap()
+    }
+
+    fn string_value(value: &str) -> Option<AnyValue> {
+        Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.into())),
+        })
+    }
+
+    fn events() -> Vec<Event> {
+        (0..4)
+            .map(|i| {
+                let mut event = Event::from(format!("line {}", i));
+                event
+                    .as_mut_log()
+                    .insert("host", if i % 2 == 0 { "web-1" } else { "web-2" });
+                event
+            })
+            .chain(Some(Event::Metric(Metric {
+                name: "requests_total".into(),
+                namespace: None,
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 1.0 },
+            })))
+            .collect()
+    }
+
+    #[test]
+    fn encodes_logs() {
+        let mut event = Event::from("GET /cart 200");
+        let log = event.as_mut_log();
+        log.insert(log_schema().timestamp_key(), timestamp());
+        log.insert("host", "web-1");
+        log.insert("status", 200);
+        log.insert("http.method", "GET");
+
+        let (record, key) = config().encode_record(event).into_parts();
+        assert_eq!(key.signal, Signal::Logs);
+        assert_eq!(
+            key.resource.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("host.name".to_owned(), "web-1".to_owned()),
+                ("service.name".to_owned(), "checkout".to_owned()),
+            ]
+        );
+        let record = match record {
+            Record::Log(record) => record,
+            record => panic!("Unexpected record {:?}", record),
+        };
+        assert!(record.observed_time_unix_nano > 0);
+        assert_eq!(record.time_unix_nano, 1610447463123456789);
+        assert_eq!(record.body, string_value("GET /cart 200"));
+        assert_eq!(
+            record.attributes,
+            vec![
+                KeyValue {
+                    key: "http".into(),
+                    value: Some(AnyValue {
+                        value: Some(any_value::Value::KvlistValue(KeyValueList {
+                            values: vec![KeyValue {
+                                key: "method".into(),
+                                value: string_value("GET"),
+                            }],
+                        })),
+                    }),
+                },
+                KeyValue {
+                    key: "status".into(),
+                    value: Some(AnyValue {
+                        value: Some(any_value::Value::IntValue(200)),
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_metrics() {
+        let mut tags = BTreeMap::new();
+        tags.insert("host".to_owned(), "web-1".to_owned());
+        tags.insert("code".to_owned(), "200".to_owned());
+        let metric = Metric {
+            name: "request_duration_seconds".into(),
+            namespace: Some("checkout".into()),
+            timestamp: Some(timestamp()),
+            tags: Some(tags),
+            kind: MetricKind::Absolute,
+            value: MetricValue::AggregatedHistogram {
+                buckets: vec![0.1, 1.0],
+                counts: vec![3, 1],
+                count: 5,
+                sum: 4.5,
+            },
+        };
+
+        let (metric, key) = config().encode_record(Event::Metric(metric)).into_parts();
+        assert_eq!(key.signal, Signal::Metrics);
+        assert_eq!(key.resource["host.name"], "web-1");
+        assert_eq!(
+            metric,
+            Record::Metric(metrics::Metric {
+                name: "checkout.request_duration_seconds".into(),
+                description: String::new(),
+                unit: String::new(),
+                data: Some(metrics::metric::Data::Histogram(metrics::Histogram {
+                    data_points: vec![metrics::HistogramDataPoint {
+                        attributes: vec![KeyValue {
+                            key: "code".into(),
+                            value: string_value("200"),
+                        }],
+                        start_time_unix_nano: 0,
+                        time_unix_nano: 1610447463123456789,
+                        count: 5,
+                        sum: 4.5,
+                        bucket_counts: vec![3, 1, 1],
+                        explicit_bounds: vec![0.1, 1.0],
+                    }],
+                    aggregation_temporality: metrics::AggregationTemporality::Cumulative as i32,
+                })),
+            })
+        );
+    }
+
+    fn assert_logs(request: &ExportLogsServiceRequest, host: &str, lines: &[&str]) {
+        let resource_logs = &request.resource_logs[0];
+        assert_eq!(
+            resource_logs.resource.as_ref().unwrap().attributes,
+            vec![KeyValue {
+                key: "host.name".into(),
+                value: string_value(host),
+            }]
+        );
+        let bodies = resource_logs.scope_logs[0]
+            .log_records
+            .iter()
+            .map(|record| record.body.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bodies,
+            lines
+                .iter()
+                .map(|line| string_value(line))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn assert_metrics(request: &ExportMetricsServiceRequest) {
+        let metric = &request.resource_metrics[0].scope_metrics[0].metrics[0];
+        assert_eq!(metric.name, "requests_total");
+        match &metric.data {
+            Some(metrics::metric::Data::Sum(sum)) => {
+                assert_eq!(
+                    sum.aggregation_temporality,
+                    metrics::AggregationTemporality::Delta as i32
+                );
+                assert_eq!(
+                    sum.data_points[0].value,
+                    Some(metrics::number_data_point::Value::AsDouble(1.0))
+                );
+            }
+            data => panic!("Unexpected data {:?}", data),
+        }
+    }
+
+    #[tokio::test]
+    async fn exports_batches_per_signal_and_resource() {
+        let in_addr = next_addr();
+        let (config, cx) = load_sink::<OpenTelemetryConfig>(&format!(
+            r#"
+            endpoint = "http://{}"
+            resource_fields."host.name" = "host"
+            batch.max_events = 10
+            "#,
+            in_addr
+        ))
+        .unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, trigger, server) = build_test_server(in_addr);
+        tokio::spawn(server);
+
+        sink.run(stream::iter(events())).await.unwrap();
+        drop(trigger);
+
+        let mut requests = rx
+            .map(|(parts, body)| {
+                assert_eq!(parts.headers["Content-Type"], "application/x-protobuf");
+                assert_eq!(parts.headers["Content-Encoding"], "gzip");
+                let mut body_bytes = Vec::new();
+                GzDecoder::new(body.reader())
+                    .read_to_end(&mut body_bytes)
+                    .unwrap();
+                (parts.uri.path().to_owned(), body_bytes)
+            })
+            .collect::<Vec<_>>()
+            .await;
+        requests.sort();
+        assert_eq!(requests.len(), 3);
+
+        let mut logs = requests[..2]
+            .iter()
+            .map(|(path, body)| {
+                assert_eq!(path, "/v1/logs");
+                ExportLogsServiceRequest::decode(&body[..]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        logs.sort_by_key(|request| {
+            format!("{:?}", request.resource_logs[0].resource.as_ref().unwrap())
+        });
+        assert_logs(&logs[0], "web-1", &["line 0", "line 2"]);
+        assert_logs(&logs[1], "web-2", &["line 1", "line 3"]);
+
+        let (path, body) = &requests[2];
+        assert_eq!(path, "/v1/metrics");
+        assert_metrics(&ExportMetricsServiceRequest::decode(&body[..]).unwrap());
+    }
+
+    /// Receives the export requests of both signals over OTLP/gRPC.
+    #[derive(Clone)]
+    struct Receiver {
+        logs: mpsc::UnboundedSender<(MetadataMap, ExportLogsServiceRequest)>,
+        metrics: mpsc::UnboundedSender<ExportMetricsServiceRequest>,
+    }
+
+    #[tonic::async_trait]
+    impl LogsService for Receiver {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportLogsServiceRequest>,
+        ) -> Result<tonic::Response<ExportLogsServiceResponse>, Status> {
+            let metadata = request.metadata().clone();
+            self.logs
+                .unbounded_send((metadata, request.into_inner()))
+                .unwrap();
+            Ok(tonic::Response::new(ExportLogsServiceResponse {
+                partial_success: None,
+            }))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl MetricsService for Receiver {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportMetricsServiceRequest>,
+        ) -> Result<tonic::Response<ExportMetricsServiceResponse>, Status> {
+            self.metrics.unbounded_send(request.into_inner()).unwrap();
+            Ok(tonic::Response::new(ExportMetricsServiceResponse {
+                partial_success: None,
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn exports_over_grpc() {
+        let in_addr = next_addr();
+        let (logs_tx, logs_rx) = mpsc::unbounded();
+        let (metrics_tx, metrics_rx) = mpsc::unbounded();
+        let receiver = Receiver {
+            logs: logs_tx,
+            metrics: metrics_tx,
+        };
+        tokio::spawn(
+            Server::builder()
+                .add_service(LogsServiceServer::new(receiver.clone()))
+                .add_service(MetricsServiceServer::new(receiver))
+                .serve(in_addr),
+        );
+        wait_for_tcp(in_addr).await;
+
+        let (config, cx) = load_sink::<OpenTelemetryConfig>(&format!(
+            r#"
+            endpoint = "http://{}"
+            protocol = "grpc"
+            resource_fields."host.name" = "host"
+            headers.x-tenant = "checkout"
+            batch.max_events = 10
+            "#,
+            in_addr
+        ))
+        .unwrap();
+        let (sink, _) = config.build(cx).await.unwrap();
+        sink.run(stream::iter(events())).await.unwrap();
+
+        let mut logs = logs_rx.take(2).collect::<Vec<_>>().await;
+        for (metadata, _) in &logs {
+            assert_eq!(metadata.get("x-tenant").unwrap(), "checkout");
+        }
+        logs.sort_by_key(|(_, request)| {
+            format!("{:?}", request.resource_logs[0].resource.as_ref().unwrap())
+        });
+        assert_logs(&logs[0].1, "web-1", &["line 0", "line 2"]);
+        assert_logs(&logs[1].1, "web-2", &["line 1", "line 3"]);
+
+        let metrics = metrics_rx.take(1).collect::<Vec<_>>().await;
+        assert_metrics(&metrics[0]);
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_configs() {
+        for toml in &[
+            r#"
+            endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            compression = "gzip"
+            "#,
+            r#"
+            endpoint = "http://localhost:4318/v1/traces"
+            "#,
+        ] {
+            let (config, cx) = load_sink::<OpenTelemetryConfig>(toml).unwrap();
+            assert!(config.build(cx).await.is_err());
+        }
+
+        let (config, cx) = load_sink::<OpenTelemetryConfig>(
+            r#"
+            endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            compression = "none"
+            "#,
+        )
+        .unwrap();
+        assert!(config.build(cx).await.is_ok());
+    }
+}
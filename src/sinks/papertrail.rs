@@ -130,6 +130,8 @@ mod tests {
             &EncodingConfig {
                 codec: Encoding::Json,
                 schema: None,
+                cef: None,
+                leef: None,
                 only_fields: None,
                 except_fields: Some(vec!["magic".into()]),
                 timestamp_format: None,
@@ -1,7 +1,7 @@
 use crate::{
     event::metric::{Metric, MetricValue, StatisticKind},
     prometheus::{proto, METRIC_NAME_LABEL},
-    sinks::util::{encode_namespace, statistic::DistributionStatistic},
+    sinks::util::{encode_namespace, statistic::DistributionStatistic, MetricColumns},
 };
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write as _;
@@ -294,6 +294,36 @@ impl TimeSeries {
         labels
     }
 
+    /// Encodes the absolute counters, gauges and sets held in the columns,
+    /// building their labels from the tags directly.
+    pub(super) fn encode_columns(
+        &mut self,
+        default_namespace: Option<&str>,
+        columns: &MetricColumns,
+    ) {
+        for row in columns.rows().filter(|row| row.kind().is_absolute()) {
+            let name = encode_namespace(row.namespace().or(default_namespace), '_', row.name());
+            let mut labels = row
+                .tags()
+                .filter(|&(key, _)| key != METRIC_NAME_LABEL)
+                .map(|(key, value)| proto::Label {
+                    name: key.to_owned(),
+                    value: value.to_owned(),
+                })
+                .chain(Some(proto::Label {
+                    name: METRIC_NAME_LABEL.into(),
+                    value: name,
+                }))
+                .collect::<Labels>();
+            labels.sort();
+
+            self.buffer.entry(labels).or_default().push(proto::Sample {
+                value: row.value(),
+                timestamp: row.timestamp().map(|t| t.timestamp_millis()).unwrap_or(0),
+            });
+        }
+    }
+
     pub(super) fn finish(self) -> Vec<proto::TimeSeries> {
         self.buffer
             .into_iter()
@@ -563,4 +593,37 @@ vector_requests_count 6
         );
         assert_eq!(frame, "ns_requests{code=\"200\",quantile=\"0.5\"} 2\nns_requests{code=\"200\",quantile=\"0.75\"} 2\nns_requests{code=\"200\",quantile=\"0.9\"} 3\nns_requests{code=\"200\",quantile=\"0.95\"} 3\nns_requests{code=\"200\",quantile=\"0.99\"} 3\nns_requests_sum{code=\"200\"} 15\nns_requests_count{code=\"200\"} 8\nns_requests_min{code=\"200\"} 1\nns_requests_max{code=\"200\"} 3\nns_requests_avg{code=\"200\"} 1.875\n".to_owned());
     }
+
+    #[test]
+    fn test_encode_columns() {
+        let metrics = vec![
+            Metric {
+                name: "hits".to_owned(),
+                namespace: None,
+                timestamp: None,
+                tags: Some(tags()),
+                kind: MetricKind::Absolute,
+                value: MetricValue::Counter { value: 10.0 },
+            },
+            Metric {
+                name: "users".to_owned(),
+                namespace: Some("app".to_owned()),
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Absolute,
+                value: MetricValue::Set {
+                    values: vec!["alice".to_owned()].into_iter().collect(),
+                },
+            },
+        ];
+
+        let mut expected = TimeSeries::new();
+        for metric in &metrics {
+            expected.encode_metric(Some("vector"), &[], &[], false, metric);
+        }
+        let mut series = TimeSeries::new();
+        series.encode_columns(Some("vector"), &MetricColumns::from(metrics));
+
+        assert_eq!(series.buffer, expected.buffer);
+    }
 }
@@ -1,23 +1,27 @@
 use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, Resource, SinkConfig, SinkContext, SinkDescription},
-    event::metric::MetricKind,
-    internal_events::PrometheusServerRequestComplete,
+    event::metric::{Metric, MetricKind, MetricValue},
+    http::Auth,
+    internal_events::{PrometheusServerAuthenticationFailed, PrometheusServerRequestComplete},
     sinks::{
         util::{statistic::validate_quantiles, MetricEntry, StreamSink},
         Healthcheck, VectorSink,
     },
+    tls::{MaybeTlsSettings, TlsConfig},
     Event,
 };
 use async_trait::async_trait;
 use chrono::Utc;
 use futures::{future, stream::BoxStream, FutureExt, StreamExt, TryFutureExt};
 use hyper::{
-    header::HeaderValue,
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE},
+    server::accept,
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
 use indexmap::IndexSet;
+use openssl::memcmp;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
@@ -25,6 +29,7 @@ use std::{
     convert::Infallible,
     net::SocketAddr,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use stream_cancel::{Trigger, Tripwire};
 
@@ -36,6 +41,8 @@ const MIN_FLUSH_PERIOD_SECS: u64 = 1;
 enum BuildError {
     #[snafu(display("Flush period for sets must be greater or equal to {} secs", min))]
     FlushPeriodTooShort { min: u64 },
+    #[snafu(display("Invalid bearer token"))]
+    InvalidBearerToken,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -51,6 +58,13 @@ pub struct PrometheusExporterConfig {
     pub quantiles: Vec<f64>,
     #[serde(default = "default_flush_period_secs")]
     pub flush_period_secs: u64,
+    pub replica_tag: Option<String>,
+    pub tls: Option<TlsConfig>,
+    /// How often to check the certificate and key files for changes, which
+    /// are then served to new connections.
+    pub tls_reload_secs: Option<u64>,
+    /// The credentials the scrapes must authenticate with.
+    pub auth: Option<Auth>,
 }
 
 impl std::default::Default for PrometheusExporterConfig {
@@ -61,6 +75,10 @@ impl std::default::Default for PrometheusExporterConfig {
             buckets: super::default_histogram_buckets(),
             quantiles: super::default_summary_quantiles(),
             flush_period_secs: default_flush_period_secs(),
+            replica_tag: None,
+            tls: None,
+            tls_reload_secs: None,
+            auth: None,
         }
     }
 }
@@ -101,7 +119,10 @@ impl SinkConfig for PrometheusExporterConfig {
 
         validate_quantiles(&self.quantiles)?;
 
-        let sink = PrometheusExporter::new(self.clone(), cx.acker());
+        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let auth = self.auth.as_ref().map(ScrapeAuth::new).transpose()?;
+
+        let sink = PrometheusExporter::new(self.clone(), tls, auth, cx.acker());
         let healthcheck = future::ok(()).boxed();
 
         Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
@@ -151,17 +172,131 @@ impl SinkConfig for PrometheusCompatConfig {
 struct PrometheusExporter {
     server_shutdown_trigger: Option<Trigger>,
     config: PrometheusExporterConfig,
+    tls: MaybeTlsSettings,
+    auth: Option<ScrapeAuth>,
     metrics: Arc<RwLock<IndexSet<MetricEntry>>>,
     last_flush_timestamp: Arc<RwLock<i64>>,
     acker: Acker,
 }
 
+/// The `Authorization` header the scrapes must send.
+#[derive(Clone, Debug)]
+struct ScrapeAuth {
+    authorization: HeaderValue,
+    challenge: &'static str,
+}
+
+impl ScrapeAuth {
+    fn new(auth: &Auth) -> crate::Result<Self> {
+        let mut headers = HeaderMap::new();
+        auth.apply_headers_map(&mut headers);
+        let authorization = headers
+            .remove(AUTHORIZATION)
+            .ok_or(BuildError::InvalidBearerToken)?;
+        let challenge = match auth {
+            Auth::Basic { .. } => r#"Basic realm="vector""#,
+            Auth::Bearer { .. } => "Bearer",
+        };
+        Ok(Self {
+            authorization,
+            challenge,
+        })
+    }
+
+    fn is_valid<B>(&self, req: &Request<B>) -> bool {
+        req.headers()
+            .get(AUTHORIZATION)
+            .map_or(false, |authorization| {
+                let (expected, actual) = (self.authorization.as_bytes(), authorization.as_bytes());
+                expected.len() == actual.len() && memcmp::eq(expected, actual)
+            })
+    }
+
+    fn reject(&self) -> Response<Body> {
+        emit!(PrometheusServerAuthenticationFailed);
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, HeaderValue::from_static(self.challenge));
+        response
+    }
+}
+
+/// Merges the series which only differ by the tag identifying the replica
+/// they come from, so a single series is exposed for all of them.
+fn aggregate_replicas(metrics: &IndexSet<MetricEntry>, tag: &str) -> IndexSet<MetricEntry> {
+    let mut aggregated = IndexSet::with_capacity(metrics.len());
+    for MetricEntry(metric) in metrics {
+        let mut metric = metric.clone();
+        if let Some(tags) = metric.tags.as_mut() {
+            tags.remove(tag);
+        }
+
+        let entry = MetricEntry(metric);
+        match aggregated.shift_take(&entry) {
+            Some(MetricEntry(mut existing)) => {
+                merge_replica(&mut existing, &entry.0);
+                aggregated.insert(MetricEntry(existing));
+            }
+            None => {
+                aggregated.insert(entry);
+            }
+        }
+    }
+    aggregated
+}
+
+/// Counters and histograms are summed, as each replica counts its own
+/// observations, while the highest gauge is kept. Summaries can't be merged,
+/// so the first replica's is kept.
+fn merge_replica(existing: &mut Metric, other: &Metric) {
+    match (&mut existing.value, &other.value) {
+        (MetricValue::Counter { ref mut value }, MetricValue::Counter { value: value2 }) => {
+            *value += value2;
+        }
+        (MetricValue::Gauge { ref mut value }, MetricValue::Gauge { value: value2 }) => {
+            *value = value.max(*value2);
+        }
+        (MetricValue::Set { .. }, MetricValue::Set { .. })
+        | (MetricValue::Distribution { .. }, MetricValue::Distribution { .. }) => {
+            existing.add(&Metric {
+                kind: MetricKind::Incremental,
+                ..other.clone()
+            });
+        }
+        (
+            MetricValue::AggregatedHistogram {
+                ref mut counts,
+                ref mut count,
+                ref mut sum,
+                ..
+            },
+            MetricValue::AggregatedHistogram {
+                counts: counts2,
+                count: count2,
+                sum: sum2,
+                ..
+            },
+        ) => {
+            // The buckets are part of the series, so they are the same.
+            for (c, c2) in counts.iter_mut().zip(counts2) {
+                *c += c2;
+            }
+            *count += count2;
+            *sum += sum2;
+        }
+        _ => {}
+    }
+}
+
 fn handle(
     req: Request<Body>,
     default_namespace: Option<&str>,
     buckets: &[f64],
     quantiles: &[f64],
     expired: bool,
+    replica_tag: Option<&str>,
     metrics: &IndexSet<MetricEntry>,
 ) -> Response<Body> {
     let mut response = Response::new(Body::empty());
@@ -170,6 +305,15 @@ fn handle(
         (&Method::GET, "/metrics") => {
             let mut s = collector::StringCollector::new();
 
+            let aggregated;
+            let metrics = match replica_tag {
+                Some(tag) => {
+                    aggregated = aggregate_replicas(metrics, tag);
+                    &aggregated
+                }
+                None => metrics,
+            };
+
             // output headers only once
             let mut processed_headers = HashSet::new();
 
@@ -199,19 +343,26 @@ fn handle(
 }
 
 impl PrometheusExporter {
-    fn new(config: PrometheusExporterConfig, acker: Acker) -> Self {
+    fn new(
+        config: PrometheusExporterConfig,
+        tls: MaybeTlsSettings,
+        auth: Option<ScrapeAuth>,
+        acker: Acker,
+    ) -> Self {
         Self {
             server_shutdown_trigger: None,
             config,
+            tls,
+            auth,
             metrics: Arc::new(RwLock::new(IndexSet::new())),
             last_flush_timestamp: Arc::new(RwLock::new(Utc::now().timestamp())),
             acker,
         }
     }
 
-    fn start_server_if_needed(&mut self) {
+    async fn start_server_if_needed(&mut self) -> crate::Result<()> {
         if self.server_shutdown_trigger.is_some() {
-            return;
+            return Ok(());
         }
 
         let metrics = Arc::clone(&self.metrics);
@@ -220,6 +371,8 @@ impl PrometheusExporter {
         let quantiles = self.config.quantiles.clone();
         let last_flush_timestamp = Arc::clone(&self.last_flush_timestamp);
         let flush_period_secs = self.config.flush_period_secs;
+        let replica_tag = self.config.replica_tag.clone();
+        let auth = self.auth.clone();
 
         let new_service = make_service_fn(move |_| {
             let metrics = Arc::clone(&metrics);
@@ -228,6 +381,8 @@ impl PrometheusExporter {
             let quantiles = quantiles.clone();
             let last_flush_timestamp = Arc::clone(&last_flush_timestamp);
             let flush_period_secs = flush_period_secs;
+            let replica_tag = replica_tag.clone();
+            let auth = auth.clone();
 
             async move {
                 Ok::<_, Infallible>(service_fn(move |req| {
@@ -241,15 +396,17 @@ impl PrometheusExporter {
                         method = ?req.method(),
                         path = ?req.uri().path(),
                     )
-                    .in_scope(|| {
-                        handle(
+                    .in_scope(|| match &auth {
+                        Some(auth) if !auth.is_valid(&req) => auth.reject(),
+                        _ => handle(
                             req,
                             default_namespace.as_deref(),
                             &buckets,
                             &quantiles,
                             expired,
+                            replica_tag.as_deref(),
                             &metrics,
-                        )
+                        ),
                     });
 
                     emit!(PrometheusServerRequestComplete {
@@ -261,22 +418,31 @@ impl PrometheusExporter {
             }
         });
 
+        let mut listener = self.tls.bind(&self.config.address).await?;
+        if let (Some(tls), Some(secs)) = (&self.config.tls, self.config.tls_reload_secs) {
+            listener = listener.reload_identity(tls.options.clone(), Duration::from_secs(secs));
+        }
+
         let (trigger, tripwire) = Tripwire::new();
 
-        let server = Server::bind(&self.config.address)
+        let server = Server::builder(accept::from_stream(listener.accept_stream()))
             .serve(new_service)
             .with_graceful_shutdown(tripwire.then(crate::stream::tripwire_handler))
             .map_err(|error| eprintln!("Server error: {}", error));
 
         tokio::spawn(server);
         self.server_shutdown_trigger = Some(trigger);
+        Ok(())
     }
 }
 
 #[async_trait]
 impl StreamSink for PrometheusExporter {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
-        self.start_server_if_needed();
+        if let Err(error) = self.start_server_if_needed().await {
+            error!(message = "Failed to start the server.", %error);
+            return Err(());
+        }
         while let Some(event) = input.next().await {
             let item = event.into_metric();
             let mut metrics = self.metrics.write().unwrap();
@@ -316,11 +482,156 @@ impl StreamSink for PrometheusExporter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        http::HttpClient,
+        test_util::next_addr,
+        tls::{TlsOptions, TlsSettings},
+    };
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<PrometheusExporterConfig>();
     }
+
+    fn scrape_request(authorization: Option<&str>) -> Request<Body> {
+        let mut request = Request::get("/metrics").body(Body::empty()).unwrap();
+        if let Some(authorization) = authorization {
+            request
+                .headers_mut()
+                .insert(AUTHORIZATION, HeaderValue::from_str(authorization).unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn validates_credentials() {
+        let basic = ScrapeAuth::new(&Auth::Basic {
+            user: "user".into(),
+            password: "password".into(),
+        })
+        .unwrap();
+        assert!(basic.is_valid(&scrape_request(Some("Basic dXNlcjpwYXNzd29yZA=="))));
+        assert!(!basic.is_valid(&scrape_request(Some("Basic dXNlcjpndWVzcw=="))));
+        assert!(!basic.is_valid(&scrape_request(None)));
+
+        let bearer = ScrapeAuth::new(&Auth::Bearer {
+            token: "secret".into(),
+        })
+        .unwrap();
+        assert!(bearer.is_valid(&scrape_request(Some("Bearer secret"))));
+        assert!(!bearer.is_valid(&scrape_request(Some("Bearer secre"))));
+
+        let response = bearer.reject();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+
+        assert!(ScrapeAuth::new(&Auth::Bearer {
+            token: "new\nline".into()
+        })
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn serves_authenticated_scrapes_over_tls() {
+        let address = next_addr();
+        let config = PrometheusExporterConfig {
+            address,
+            tls: Some(TlsConfig::test_config()),
+            auth: Some(Auth::Bearer {
+                token: "secret".into(),
+            }),
+            ..Default::default()
+        };
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let gauge = Metric {
+            name: "connections".into(),
+            namespace: None,
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Absolute,
+            value: MetricValue::Gauge { value: 5.0 },
+        };
+        let events = futures::stream::iter(vec![Event::from(gauge)]);
+        tokio::spawn(sink.run(events.chain(futures::stream::pending())));
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        let tls = TlsSettings::from_options(&Some(TlsOptions::test_options())).unwrap();
+        let client = HttpClient::new(tls).unwrap();
+        let scrape = |authorization| {
+            let mut request = scrape_request(authorization);
+            *request.uri_mut() = format!("https://localhost:{}/metrics", address.port())
+                .parse()
+                .unwrap();
+            client.send(request)
+        };
+
+        let response = scrape(None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = scrape(Some("Bearer secret")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("connections 5"));
+    }
+
+    fn replica_metric(name: &str, replica: &str, value: MetricValue) -> MetricEntry {
+        MetricEntry(Metric {
+            name: name.into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                vec![
+                    ("host".to_owned(), replica.to_owned()),
+                    ("code".to_owned(), "200".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            kind: MetricKind::Absolute,
+            value,
+        })
+    }
+
+    #[test]
+    fn aggregates_replicas() {
+        let histogram = |counts: Vec<u32>, count, sum| MetricValue::AggregatedHistogram {
+            buckets: vec![1.0, 2.0],
+            counts,
+            count,
+            sum,
+        };
+        let metrics = vec![
+            replica_metric("requests", "a", MetricValue::Counter { value: 1.0 }),
+            replica_metric("requests", "b", MetricValue::Counter { value: 2.0 }),
+            replica_metric("connections", "a", MetricValue::Gauge { value: 5.0 }),
+            replica_metric("connections", "b", MetricValue::Gauge { value: 3.0 }),
+            replica_metric("latency", "a", histogram(vec![1, 2], 3, 4.0)),
+            replica_metric("latency", "b", histogram(vec![3, 0], 3, 2.0)),
+        ]
+        .into_iter()
+        .collect::<IndexSet<_>>();
+
+        let aggregated = aggregate_replicas(&metrics, "host")
+            .into_iter()
+            .map(|MetricEntry(metric)| {
+                assert_eq!(
+                    metric.tags.unwrap().into_iter().collect::<Vec<_>>(),
+                    vec![("code".to_owned(), "200".to_owned())]
+                );
+                (metric.name, metric.value)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            aggregated,
+            vec![
+                ("requests".to_owned(), MetricValue::Counter { value: 3.0 }),
+                ("connections".to_owned(), MetricValue::Gauge { value: 5.0 }),
+                ("latency".to_owned(), histogram(vec![4, 2], 6, 6.0)),
+            ]
+        );
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
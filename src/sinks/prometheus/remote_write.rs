@@ -7,7 +7,8 @@ use crate::{
     sinks::{
         self,
         util::{
-            http::HttpRetryLogic, BatchConfig, BatchSettings, MetricBuffer, TowerRequestConfig,
+            http::HttpRetryLogic, BatchConfig, BatchSettings, Compression, MetricBuffer,
+            MetricColumns, TowerRequestConfig,
         },
     },
     tls::{TlsOptions, TlsSettings},
@@ -72,7 +73,7 @@ impl SinkConfig for RemoteWriteConfig {
         let buckets = self.buckets.clone();
         let quantiles = self.quantiles.clone();
 
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
         let healthcheck = healthcheck(endpoint.clone(), client.clone()).boxed();
         let service = RemoteWriteService {
             endpoint,
@@ -127,14 +128,16 @@ struct RemoteWriteService {
 
 impl RemoteWriteService {
     fn encode_events(&self, metrics: Vec<Metric>) -> Bytes {
+        let columns = MetricColumns::from(metrics);
         let mut time_series = collector::TimeSeries::new();
-        for metric in metrics {
+        time_series.encode_columns(self.default_namespace.as_deref(), &columns);
+        for metric in columns.others() {
             time_series.encode_metric(
                 self.default_namespace.as_deref(),
                 &self.buckets,
                 &self.quantiles,
                 false,
-                &metric,
+                metric,
             );
         }
         let timeseries = time_series.finish();
@@ -157,7 +160,8 @@ impl tower::Service<Vec<Metric>> for RemoteWriteService {
 
     fn call(&mut self, events: Vec<Metric>) -> Self::Future {
         let body = self.encode_events(events);
-        let body = snap_block(body);
+        // Remote write requires raw snappy rather than its framing format.
+        let body = Compression::Snappy.compress(&body);
 
         let request = http::Request::post(self.endpoint.clone())
             .header("X-Prometheus-Remote-Write-Version", "0.1.0")
@@ -176,12 +180,6 @@ impl tower::Service<Vec<Metric>> for RemoteWriteService {
     }
 }
 
-fn snap_block(data: Bytes) -> Vec<u8> {
-    snap::raw::Encoder::new()
-        .compress_vec(&data)
-        .expect("Out of memory")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
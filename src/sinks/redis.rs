@@ -0,0 +1,450 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    internal_events::{RedisEventSent, RedisMissingKeys},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::RetryLogic,
+        BatchConfig, BatchSettings, EncodedLength, TowerRequestConfig, VecBuffer,
+    },
+    template::{Template, TemplateError},
+};
+use futures::{future::BoxFuture, stream, FutureExt, Sink, SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use redis::{aio::ConnectionManager, RedisError};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    fmt,
+    task::{Context, Poll},
+};
+use tower::Service;
+use tracing_futures::Instrument;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid key template: {}", source))]
+    KeyTemplate { source: TemplateError },
+    #[snafu(display("Invalid URL {:?}: {}", url, source))]
+    InvalidUrl { url: String, source: RedisError },
+    #[snafu(display("Failed to connect to Redis: {}", source))]
+    Connect { source: RedisError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RedisSinkConfig {
+    pub url: String,
+    pub key: String,
+    #[serde(default)]
+    pub data_type: RedisDataType,
+    #[serde(default)]
+    pub list: ListOptions,
+    #[serde(default)]
+    pub stream: StreamOptions,
+    pub encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RedisDataType {
+    /// Push the events to a list.
+    List,
+    /// Publish the events to a channel.
+    Channel,
+    /// Add the events to a stream.
+    Stream,
+}
+
+impl Default for RedisDataType {
+    fn default() -> Self {
+        Self::List
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ListOptions {
+    #[serde(default)]
+    pub method: ListMethod,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListMethod {
+    /// Push to the head of the list, with `LPUSH`.
+    Lpush,
+    /// Push to the tail of the list, with `RPUSH`.
+    Rpush,
+}
+
+impl Default for ListMethod {
+    fn default() -> Self {
+        Self::Rpush
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOptions {
+    /// The field of the stream entries holding the encoded event.
+    #[serde(default = "default_stream_field")]
+    #[derivative(Default(value = "default_stream_field()"))]
+    pub field: String,
+    /// Trim the stream to about this many entries.
+    pub max_len: Option<usize>,
+}
+
+fn default_stream_field() -> String {
+    "message".to_owned()
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        timeout_secs: Some(30),
+        rate_limit_num: Some(1000),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<RedisSinkConfig>("redis")
+}
+
+impl GenerateConfig for RedisSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl SinkConfig for RedisSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let connection = self.connect().await?;
+        let healthcheck = healthcheck(connection.clone()).boxed();
+        let sink = RedisService::new(self.clone(), connection, cx)?;
+        Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "redis"
+    }
+}
+
+impl RedisSinkConfig {
+    async fn connect(&self) -> crate::Result<ConnectionManager> {
+        let client = redis::Client::open(self.url.as_str()).context(InvalidUrl {
+            url: self.url.clone(),
+        })?;
+        // The manager reconnects when the connection is lost.
+        let connection = ConnectionManager::new(client).await.context(Connect)?;
+        Ok(connection)
+    }
+}
+
+async fn healthcheck(mut connection: ConnectionManager) -> crate::Result<()> {
+    redis::cmd("PING")
+        .query_async::<_, String>(&mut connection)
+        .await?;
+    Ok(())
+}
+
+/// An encoded event and the key it is sent to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedisEntry {
+    key: String,
+    value: Vec<u8>,
+}
+
+impl EncodedLength for RedisEntry {
+    fn encoded_length(&self) -> usize {
+        self.key.len() + self.value.len()
+    }
+}
+
+#[derive(Clone)]
+pub struct RedisService {
+    connection: ConnectionManager,
+    config: RedisSinkConfig,
+}
+
+impl RedisService {
+    pub fn new(
+        config: RedisSinkConfig,
+        connection: ConnectionManager,
+        cx: SinkContext,
+    ) -> crate::Result<impl Sink<Event, Error = ()>> {
+        let batch = BatchSettings::default()
+            .events(1000)
+            .timeout(1)
+            .parse_config(config.batch)?;
+        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let encoding = config.encoding.clone();
+        let key = Template::try_from(config.key.as_str()).context(KeyTemplate)?;
+
+        let redis = RedisService { connection, config };
+
+        let sink = request
+            .batch_sink(
+                RedisRetryLogic,
+                redis,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal redis sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encode_event(event, &key, &encoding)).map(Ok));
+
+        Ok(sink)
+    }
+}
+
+impl Service<Vec<RedisEntry>> for RedisService {
+    type Response = ();
+    type Error = RedisError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, entries: Vec<RedisEntry>) -> Self::Future {
+        debug!(
+            message = "Sending events.",
+            events = %entries.len(),
+        );
+
+        let sizes: Vec<usize> = entries.iter().map(|entry| entry.value.len()).collect();
+        let pipeline = build_pipeline(&self.config, entries);
+        let mut connection = self.connection.clone();
+
+        Box::pin(async move {
+            pipeline
+                .query_async::<_, ()>(&mut connection)
+                .instrument(info_span!("request"))
+                .await?;
+            for byte_size in sizes {
+                emit!(RedisEventSent { byte_size });
+            }
+            Ok(())
+        })
+    }
+}
+
+impl fmt::Debug for RedisService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisService")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Sends the commands of a batch in a single round trip.
+fn build_pipeline(config: &RedisSinkConfig, entries: Vec<RedisEntry>) -> redis::Pipeline {
+    let mut pipeline = redis::pipe();
+    for entry in entries {
+        let command = match config.data_type {
+            RedisDataType::List => match config.list.method {
+                ListMethod::Lpush => pipeline.cmd("LPUSH").arg(entry.key).arg(entry.value),
+                ListMethod::Rpush => pipeline.cmd("RPUSH").arg(entry.key).arg(entry.value),
+            },
+            RedisDataType::Channel => pipeline.cmd("PUBLISH").arg(entry.key).arg(entry.value),
+            RedisDataType::Stream => {
+                let command = pipeline.cmd("XADD").arg(entry.key);
+                if let Some(max_len) = config.stream.max_len {
+                    command.arg("MAXLEN").arg("~").arg(max_len);
+                }
+                command.arg("*").arg(&config.stream.field).arg(entry.value)
+            }
+        };
+        command.ignore();
+    }
+    pipeline
+}
+
+#[derive(Debug, Clone)]
+struct RedisRetryLogic;
+
+impl RetryLogic for RedisRetryLogic {
+    type Error = RedisError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_io_error() || error.is_connection_dropped() || error.is_timeout()
+    }
+}
+
+fn encode_event(
+    mut event: Event,
+    key: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<RedisEntry> {
+    let key = key
+        .render_string(&event)
+        .map_err(|missing_keys| {
+            emit!(RedisMissingKeys {
+                keys: &missing_keys
+            });
+        })
+        .ok()?;
+
+    encoding.apply_rules(&mut event);
+
+    let log = event.into_log();
+    let value = match encoding.codec() {
+        Encoding::Json => serde_json::to_vec(&log).expect("Error encoding event as json."),
+        Encoding::Text => log
+            .get(log_schema().message_key())
+            .map(|v| v.as_bytes().to_vec())
+            .unwrap_or_default(),
+    };
+
+    Some(RedisEntry { key, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisSinkConfig>();
+    }
+
+    fn config(extra: &str) -> RedisSinkConfig {
+        toml::from_str(&format!(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            encoding.codec = "text"
+            {}
+            "#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    fn entry(key: &str, value: &str) -> RedisEntry {
+        RedisEntry {
+            key: key.to_owned(),
+            value: value.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn encodes_events_with_templated_keys() {
+        let key = Template::try_from("logs:{{ app }}").unwrap();
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("app", "api");
+
+        let text = EncodingConfig::from(Encoding::Text);
+        assert_eq!(
+            encode_event(event.clone(), &key, &text),
+            Some(entry("logs:api", "hello"))
+        );
+
+        let json = EncodingConfig::from(Encoding::Json);
+        let encoded = encode_event(event, &key, &json).unwrap();
+        let map: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&encoded.value).unwrap();
+        assert_eq!(map["app"], "api");
+
+        assert_eq!(encode_event(Event::from("hello"), &key, &text), None);
+    }
+
+    #[test]
+    fn pipelines_commands() {
+        let entries = vec![entry("a", "one"), entry("b", "two")];
+
+        let list = build_pipeline(&config(""), entries.clone());
+        assert_eq!(
+            list.get_packed_pipeline(),
+            b"*3\r\n$5\r\nRPUSH\r\n$1\r\na\r\n$3\r\none\r\n\
+              *3\r\n$5\r\nRPUSH\r\n$1\r\nb\r\n$3\r\ntwo\r\n"
+                .to_vec()
+        );
+
+        let channel = build_pipeline(&config(r#"data_type = "channel""#), entries.clone());
+        assert!(channel
+            .get_packed_pipeline()
+            .starts_with(b"*3\r\n$7\r\nPUBLISH\r\n$1\r\na\r\n"));
+
+        let stream = build_pipeline(
+            &config(
+                r#"
+                data_type = "stream"
+                stream.max_len = 1000
+                "#,
+            ),
+            entries,
+        );
+        assert!(stream.get_packed_pipeline().starts_with(
+            b"*8\r\n$4\r\nXADD\r\n$1\r\na\r\n$6\r\nMAXLEN\r\n$1\r\n~\r\n$4\r\n1000\r\n\
+              $1\r\n*\r\n$7\r\nmessage\r\n$3\r\none\r\n"
+        ));
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, random_string, trace_init};
+    use redis::AsyncCommands;
+
+    #[tokio::test]
+    async fn redis_pushes_to_lists() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        let config: RedisSinkConfig = toml::from_str(&format!(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key = "{}"
+            encoding.codec = "text"
+            "#,
+            key
+        ))
+        .unwrap();
+
+        let connection = config.connect().await.unwrap();
+        let cx = SinkContext::new_test();
+        let mut sink = RedisService::new(config.clone(), connection.clone(), cx).unwrap();
+
+        let (mut input, events) = random_lines_with_stream(100, 1000);
+        let mut events = events.map(Ok);
+        sink.send_all(&mut events).await.unwrap();
+
+        let mut connection = connection;
+        let mut output: Vec<String> = connection.lrange(&key, 0, -1).await.unwrap();
+        input.sort();
+        output.sort();
+        assert_eq!(output, input);
+    }
+}
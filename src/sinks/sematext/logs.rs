@@ -69,7 +69,7 @@ impl SinkConfig for SematextLogsConfig {
             doc_type: Some("logs".to_string()),
             index: Some(self.token.clone()),
             batch: self.batch,
-            request: self.request,
+            request: self.request.clone(),
             encoding: self.encoding.clone(),
             ..Default::default()
         }
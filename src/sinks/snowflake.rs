@@ -0,0 +1,721 @@
+//! Writes logs to Snowflake tables with the REST API of Snowpipe Streaming,
+//! rather than staging them to files loaded by Snowpipe.
+//!
+//! Rows are appended to channels of the pipe of the table, a channel for each
+//! rendering of the `channel` template. Each batch carries an offset token,
+//! increasing for each channel, so that a batch committed before its channel
+//! had to be reopened isn't appended again.
+
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    http::{HttpClient, HttpError},
+    internal_events::{SnowflakeChannelOpened, SnowflakeEventEncoded, SnowflakeMissingKeys},
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, BatchSettings, PartitionBatchSink, PartitionBuffer,
+            PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig, VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::{Template, TemplateError},
+    tls::{TlsOptions, TlsSettings},
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use http::{Request, StatusCode};
+use hyper::Body;
+use indexmap::IndexMap;
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Service, ServiceBuilder};
+
+/// Snowflake accepts key pair tokens valid for at most an hour.
+const TOKEN_LIFETIME_SECS: i64 = 3600;
+/// Scoped tokens are renewed ahead of their expiry.
+const SESSION_LIFETIME: Duration = Duration::from_secs(50 * 60);
+/// The characters escaped in the names of the path of a channel.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnowflakeSinkConfig {
+    /// The account identifier, as `<organization>-<account>` or the account
+    /// locator.
+    pub account: String,
+    pub user: String,
+    /// A PEM encoded PKCS #8 private key, of which the public key is set as
+    /// the `RSA_PUBLIC_KEY` of the user.
+    pub private_key_path: PathBuf,
+    pub private_key_passphrase: Option<String>,
+    pub database: String,
+    pub schema: String,
+    pub table: String,
+    /// The streaming pipe of the table, by default the pipe Snowflake creates
+    /// for it.
+    pub pipe: Option<String>,
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    /// The columns of the rows, mapped to the fields they're written from.
+    /// Without columns, the rows are written from all the fields.
+    #[serde(default)]
+    pub columns: IndexMap<String, String>,
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+}
+
+fn default_channel() -> String {
+    "vector".into()
+}
+
+inventory::submit! {
+    SinkDescription::new::<SnowflakeSinkConfig>("snowflake")
+}
+
+impl GenerateConfig for SnowflakeSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"account = "myorg-myaccount"
+            user = "vector"
+            private_key_path = "/etc/vector/snowflake.p8"
+            database = "logs"
+            schema = "public"
+            table = "events""#,
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("channel template parse error: {}", source))]
+    ChannelTemplate { source: TemplateError },
+    #[snafu(display("Failed to read the private key {:?}: {}", path, source))]
+    ReadPrivateKey {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Failed to parse the private key {:?}: {}", path, source))]
+    ParsePrivateKey {
+        path: PathBuf,
+        source: openssl::error::ErrorStack,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum SnowflakeError {
+    #[snafu(display("Request failed: {}", source))]
+    Http { source: HttpError },
+    #[snafu(display("Failed to read the response: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Failed to {}, status {}: {}", action, status, body))]
+    Response {
+        action: &'static str,
+        status: StatusCode,
+        body: String,
+    },
+    #[snafu(display("Invalid response to {}: {}", action, source))]
+    InvalidResponse {
+        action: &'static str,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Failed to sign the token: {}", source))]
+    Sign { source: openssl::error::ErrorStack },
+    #[snafu(display("Failed to build the request to {}: {}", action, source))]
+    BuildRequest {
+        action: &'static str,
+        source: http::Error,
+    },
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snowflake")]
+impl SinkConfig for SnowflakeSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let encoder = RowEncoder {
+            channel: Template::try_from(self.channel.as_str()).context(ChannelTemplate)?,
+            columns: self.columns.clone(),
+        };
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?.with_headers(self.request.headers(&cx)?);
+        let account_url = self.endpoint.clone().unwrap_or_else(|| {
+            format!(
+                "https://{}.snowflakecomputing.com",
+                self.account.to_lowercase()
+            )
+        });
+        let auth = Arc::new(Auth {
+            key_pair: KeyPair::new(self)?,
+            account_url,
+            client: client.clone(),
+            session: Default::default(),
+        });
+
+        let healthcheck = {
+            let auth = Arc::clone(&auth);
+            async move { auth.session().await.map(drop).map_err(Into::into) }.boxed()
+        };
+
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(4u64))
+            .events(10_000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+        let ordered = request.ordered;
+
+        let pipe = self
+            .pipe
+            .clone()
+            .unwrap_or_else(|| format!("{}-STREAMING", self.table));
+        let service = SnowflakeService {
+            client,
+            auth,
+            pipe_path: format!(
+                "databases/{}/schemas/{}/pipes/{}",
+                encode_path(&self.database),
+                encode_path(&self.schema),
+                encode_path(&pipe)
+            ),
+            channels: Default::default(),
+        };
+        let service = ServiceBuilder::new()
+            .map(SnowflakeRequest::from)
+            .settings(request, SnowflakeRetryLogic)
+            .service(service);
+
+        let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
+        let sink = PartitionBatchSink::new(service, buffer, batch.timeout, cx.acker())
+            .ordered(ordered)
+            .sink_map_err(|error| error!(message = "Fatal snowflake sink error.", %error))
+            .with_flat_map(move |event| stream::iter(encoder.encode_event(event)).map(Ok));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "snowflake"
+    }
+}
+
+fn encode_path(segment: &str) -> String {
+    utf8_percent_encode(segment, PATH_SEGMENT).to_string()
+}
+
+/// Signs the tokens of the key pair authentication of the user.
+#[derive(Debug)]
+struct KeyPair {
+    issuer: String,
+    subject: String,
+    key: PKey<Private>,
+}
+
+impl KeyPair {
+    fn new(config: &SnowflakeSinkConfig) -> Result<Self, BuildError> {
+        let path = &config.private_key_path;
+        let pem = std::fs::read(path).context(ReadPrivateKey { path })?;
+        let key = match &config.private_key_passphrase {
+            Some(passphrase) => PKey::private_key_from_pem_passphrase(&pem, passphrase.as_bytes()),
+            None => PKey::private_key_from_pem(&pem),
+        }
+        .context(ParsePrivateKey { path })?;
+        let public_key = key.public_key_to_der().context(ParsePrivateKey { path })?;
+
+        // The account of the tokens excludes the region of account locators.
+        let account = config
+            .account
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .to_uppercase();
+        let subject = format!("{}.{}", account, config.user.to_uppercase());
+        let fingerprint = base64::encode(openssl::sha::sha256(&public_key));
+        Ok(Self {
+            issuer: format!("{}.SHA256:{}", subject, fingerprint),
+            subject,
+            key,
+        })
+    }
+
+    fn token(&self) -> Result<String, SnowflakeError> {
+        let encode = |json: serde_json::Value| {
+            base64::encode_config(json.to_string(), base64::URL_SAFE_NO_PAD)
+        };
+        let now = Utc::now().timestamp();
+        let message = format!(
+            "{}.{}",
+            encode(json!({ "alg": "RS256", "typ": "JWT" })),
+            encode(json!({
+                "iss": self.issuer,
+                "sub": self.subject,
+                "iat": now,
+                "exp": now + TOKEN_LIFETIME_SECS,
+            }))
+        );
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key).context(Sign)?;
+        signer.update(message.as_bytes()).context(Sign)?;
+        let signature = signer.sign_to_vec().context(Sign)?;
+        Ok(format!(
+            "{}.{}",
+            message,
+            base64::encode_config(signature, base64::URL_SAFE_NO_PAD)
+        ))
+    }
+}
+
+/// The ingest host of the account, with a token scoped to it.
+#[derive(Clone, Debug)]
+struct Session {
+    host: String,
+    token: String,
+    expires: Instant,
+}
+
+struct Auth {
+    key_pair: KeyPair,
+    account_url: String,
+    client: HttpClient,
+    session: tokio::sync::Mutex<Option<Session>>,
+}
+
+impl Auth {
+    async fn session(&self) -> Result<Session, SnowflakeError> {
+        let mut session = self.session.lock().await;
+        if let Some(session) = session.as_ref().filter(|s| s.expires > Instant::now()) {
+            return Ok(session.clone());
+        }
+
+        let token = self.key_pair.token()?;
+        let request = Request::get(format!("{}/v2/streaming/hostname", self.account_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("X-Snowflake-Authorization-Token-Type", "KEYPAIR_JWT")
+            .body(Body::empty())
+            .context(BuildRequest {
+                action: "discover the ingest host",
+            })?;
+        let host = send(&self.client, request, "discover the ingest host").await?;
+        let host = String::from_utf8_lossy(&host).trim().to_owned();
+
+        let form = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer")
+            .append_pair("scope", &host)
+            .append_pair("assertion", &token)
+            .finish();
+        let request = Request::post(format!("{}/oauth/token", self.account_url))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(Body::from(form))
+            .context(BuildRequest {
+                action: "get a scoped token",
+            })?;
+        let token = send(&self.client, request, "get a scoped token").await?;
+
+        let renewed = Session {
+            host,
+            token: String::from_utf8_lossy(&token).trim().to_owned(),
+            expires: Instant::now() + SESSION_LIFETIME,
+        };
+        *session = Some(renewed.clone());
+        Ok(renewed)
+    }
+
+    /// Forgets the session, so that the next request authenticates again.
+    async fn invalidate(&self) {
+        *self.session.lock().await = None;
+    }
+}
+
+async fn send(
+    client: &HttpClient,
+    request: Request<Body>,
+    action: &'static str,
+) -> Result<Bytes, SnowflakeError> {
+    let response = client.send(request).await.context(Http)?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .context(ReadBody)?;
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(SnowflakeError::Response {
+            action,
+            status,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}
+
+async fn send_json<T: DeserializeOwned>(
+    client: &HttpClient,
+    request: Request<Body>,
+    action: &'static str,
+) -> Result<T, SnowflakeError> {
+    let body = send(client, request, action).await?;
+    serde_json::from_slice(&body).context(InvalidResponse { action })
+}
+
+#[derive(Deserialize)]
+struct OpenChannelResponse {
+    next_continuation_token: String,
+    channel_status: ChannelStatus,
+}
+
+#[derive(Deserialize)]
+struct ChannelStatus {
+    last_committed_offset_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AppendRowsResponse {
+    next_continuation_token: String,
+}
+
+struct RowEncoder {
+    channel: Template,
+    columns: IndexMap<String, String>,
+}
+
+impl RowEncoder {
+    /// The row of the event as JSON, partitioned by its channel.
+    fn encode_event(&self, event: Event) -> Option<PartitionInnerBuffer<Bytes, Bytes>> {
+        let channel = self
+            .channel
+            .render_string(&event)
+            .map_err(|keys| emit!(SnowflakeMissingKeys { keys: &keys }))
+            .ok()?;
+
+        let log = event.as_log();
+        let row = if self.columns.is_empty() {
+            serde_json::to_vec(log)
+        } else {
+            let row = self
+                .columns
+                .iter()
+                .filter_map(|(column, field)| Some((column, log.get(field)?)))
+                .collect::<IndexMap<_, _>>();
+            serde_json::to_vec(&row)
+        }
+        .expect("Serializing a log doesn't fail.");
+
+        emit!(SnowflakeEventEncoded {
+            byte_size: row.len()
+        });
+        Some(PartitionInnerBuffer::new(row.into(), channel.into()))
+    }
+}
+
+/// The rows of a batch, with the offset token of the batch shared by its
+/// retries.
+#[derive(Clone, Debug)]
+struct SnowflakeRequest {
+    channel: String,
+    rows: Vec<Bytes>,
+    offset: Arc<Mutex<Option<u64>>>,
+}
+
+impl From<PartitionInnerBuffer<Vec<Bytes>, Bytes>> for SnowflakeRequest {
+    fn from(buffer: PartitionInnerBuffer<Vec<Bytes>, Bytes>) -> Self {
+        let (rows, channel) = buffer.into_parts();
+        Self {
+            channel: String::from_utf8_lossy(&channel).into_owned(),
+            rows,
+            offset: Default::default(),
+        }
+    }
+}
+
+/// The state of an open channel. The appends of a channel are chained by
+/// their continuation tokens, so they're sent one at a time.
+#[derive(Debug, Default)]
+struct ChannelState {
+    continuation_token: Option<String>,
+    last_committed_offset: Option<u64>,
+    next_offset: u64,
+}
+
+#[derive(Clone)]
+struct SnowflakeService {
+    client: HttpClient,
+    auth: Arc<Auth>,
+    pipe_path: String,
+    channels: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<ChannelState>>>>>,
+}
+
+impl SnowflakeService {
+    async fn write(self, request: SnowflakeRequest) -> Result<(), SnowflakeError> {
+        let channel = Arc::clone(
+            self.channels
+                .lock()
+                .unwrap()
+                .entry(request.channel.clone())
+                .or_default(),
+        );
+        let mut state = channel.lock().await;
+
+        let result = self.append(&request, &mut state).await;
+        if let Err(error) = &result {
+            // Reopening the channel tells which rows it has committed.
+            state.continuation_token = None;
+            match error {
+                SnowflakeError::Response { status, .. } if *status == StatusCode::UNAUTHORIZED => {
+                    self.auth.invalidate().await
+                }
+                // The requests are invalid because of the host or the token
+                // of the session.
+                SnowflakeError::BuildRequest { .. } => self.auth.invalidate().await,
+                _ => (),
+            }
+        }
+        result
+    }
+
+    async fn append(
+        &self,
+        request: &SnowflakeRequest,
+        state: &mut ChannelState,
+    ) -> Result<(), SnowflakeError> {
+        let session = self.auth.session().await?;
+        let channel_url = format!(
+            "https://{}/v2/streaming/{}/channels/{}",
+            session.host,
+            self.pipe_path,
+            encode_path(&request.channel)
+        );
+        let continuation_token = match state.continuation_token.take() {
+            Some(continuation_token) => continuation_token,
+            None => {
+                let request = Request::put(&channel_url)
+                    .header("Authorization", format!("Bearer {}", session.token))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from("{}"))
+                    .context(BuildRequest {
+                        action: "open the channel",
+                    })?;
+                let response: OpenChannelResponse =
+                    send_json(&self.client, request, "open the channel").await?;
+
+                let committed = response
+                    .channel_status
+                    .last_committed_offset_token
+                    .and_then(|offset| offset.parse().ok());
+                emit!(SnowflakeChannelOpened {
+                    channel: &request.channel,
+                    last_committed_offset: committed,
+                });
+                state.last_committed_offset = committed;
+                if let Some(committed) = committed {
+                    state.next_offset = state.next_offset.max(committed + 1);
+                }
+                response.next_continuation_token
+            }
+        };
+
+        let offset = {
+            let mut offset = request.offset.lock().unwrap();
+            *offset.get_or_insert_with(|| {
+                state.next_offset += 1;
+                state.next_offset - 1
+            })
+        };
+        if state.last_committed_offset >= Some(offset) {
+            // A previous attempt committed the batch.
+            state.continuation_token = Some(continuation_token);
+            return Ok(());
+        }
+
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("continuationToken", &continuation_token)
+            .append_pair("offsetToken", &offset.to_string())
+            .finish();
+        let mut body = Vec::new();
+        for row in &request.rows {
+            body.extend_from_slice(row);
+            body.push(b'\n');
+        }
+        let append = Request::post(format!(
+            "https://{}/v2/streaming/data/{}/channels/{}/rows?{}",
+            session.host,
+            self.pipe_path,
+            encode_path(&request.channel),
+            query
+        ))
+        .header("Authorization", format!("Bearer {}", session.token))
+        .header("Content-Type", "application/x-ndjson")
+        .body(Body::from(body))
+        .context(BuildRequest {
+            action: "append the rows",
+        })?;
+        let response: AppendRowsResponse =
+            send_json(&self.client, append, "append the rows").await?;
+
+        state.continuation_token = Some(response.next_continuation_token);
+        Ok(())
+    }
+}
+
+impl Service<SnowflakeRequest> for SnowflakeService {
+    type Response = ();
+    type Error = SnowflakeError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SnowflakeRequest) -> Self::Future {
+        Box::pin(self.clone().write(request))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SnowflakeRetryLogic;
+
+impl RetryLogic for SnowflakeRetryLogic {
+    type Error = SnowflakeError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            SnowflakeError::Http { .. }
+            | SnowflakeError::ReadBody { .. }
+            | SnowflakeError::BuildRequest { .. } => true,
+            // Unauthorized requests are retried with a new token, and
+            // conflicts with a reopened channel.
+            SnowflakeError::Response { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::UNAUTHORIZED
+                    || *status == StatusCode::CONFLICT
+            }
+            SnowflakeError::InvalidResponse { .. } | SnowflakeError::Sign { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, test_util::temp_file};
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnowflakeSinkConfig>();
+    }
+
+    fn config(toml: &str) -> SnowflakeSinkConfig {
+        toml::from_str(&format!(
+            r#"
+            account = "myorg-myaccount"
+            user = "vector"
+            private_key_path = "/etc/vector/snowflake.p8"
+            database = "logs"
+            schema = "public"
+            table = "events"
+            {}
+            "#,
+            toml
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn signs_key_pair_tokens() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let path = temp_file();
+        std::fs::write(&path, key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        let config = SnowflakeSinkConfig {
+            private_key_path: path,
+            account: "xy12345.eu-central-1".into(),
+            ..config("")
+        };
+
+        let key_pair = KeyPair::new(&config).unwrap();
+        assert_eq!(key_pair.subject, "XY12345.VECTOR");
+        assert!(key_pair.issuer.starts_with("XY12345.VECTOR.SHA256:"));
+
+        let token = key_pair.token().unwrap();
+        let parts = token.split('.').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3);
+        let claims: serde_json::Value = serde_json::from_slice(
+            &base64::decode_config(parts[1], base64::URL_SAFE_NO_PAD).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(claims["sub"], "XY12345.VECTOR");
+        assert_eq!(
+            claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap(),
+            TOKEN_LIFETIME_SECS
+        );
+
+        let signature = base64::decode_config(parts[2], base64::URL_SAFE_NO_PAD).unwrap();
+        let mut verifier =
+            openssl::sign::Verifier::new(MessageDigest::sha256(), &key_pair.key).unwrap();
+        verifier
+            .update(format!("{}.{}", parts[0], parts[1]).as_bytes())
+            .unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+    }
+
+    #[test]
+    fn encodes_rows_by_channel() {
+        let config = config(
+            r#"
+            channel = "vector-{{ pod }}"
+            columns = { pod = "pod", message = "message", level = "level" }
+            "#,
+        );
+        let encoder = RowEncoder {
+            channel: Template::try_from(config.channel.as_str()).unwrap(),
+            columns: config.columns,
+        };
+
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("pod", "web-1");
+        event.as_mut_log().insert(log_schema().host_key(), "node-1");
+        let (row, channel) = encoder.encode_event(event).unwrap().into_parts();
+        assert_eq!(channel, "vector-web-1");
+        assert_eq!(row, r#"{"pod":"web-1","message":"hello"}"#);
+
+        assert!(encoder.encode_event(Event::from("hello")).is_none());
+    }
+
+    #[test]
+    fn encodes_all_fields_without_columns() {
+        let encoder = RowEncoder {
+            channel: Template::try_from("vector").unwrap(),
+            columns: IndexMap::new(),
+        };
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", "hello");
+        event.as_mut_log().insert("status", 200);
+        let (row, _) = encoder.encode_event(event).unwrap().into_parts();
+        assert_eq!(row, r#"{"message":"hello","status":200}"#);
+    }
+}
@@ -1,11 +1,15 @@
 #[cfg(unix)]
 use crate::sinks::util::unix::UnixSinkConfig;
 use crate::{
-    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
     sinks::util::{
-        encode_event, encoding::EncodingConfig, tcp::TcpSinkConfig, udp::UdpSinkConfig, Encoding,
+        encoding::{EncodingConfig, EncodingConfiguration},
+        tcp::TcpSinkConfig,
+        udp::UdpSinkConfig,
     },
 };
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -26,6 +30,15 @@ pub enum Mode {
     Unix(UnixSinkConfig),
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+    Cef,
+    Leef,
+}
+
 inventory::submit! {
     SinkDescription::new::<SocketSinkConfig>("socket")
 }
@@ -80,6 +93,31 @@ impl SinkConfig for SocketSinkConfig {
     }
 }
 
+fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<Bytes> {
+    encoding.apply_rules(&mut event);
+    let log = event.into_log();
+
+    let b = match encoding.codec() {
+        Encoding::Json => serde_json::to_vec(&log),
+        Encoding::Text => {
+            let bytes = log
+                .get(log_schema().message_key())
+                .map(|v| v.as_bytes().to_vec())
+                .unwrap_or_default();
+            Ok(bytes)
+        }
+        Encoding::Cef => Ok(encoding.encode_cef(&log).into_bytes()),
+        Encoding::Leef => Ok(encoding.encode_leef(&log).into_bytes()),
+    };
+
+    b.map(|mut b| {
+        b.push(b'\n');
+        Bytes::from(b)
+    })
+    .map_err(|error| error!(message = "Unable to encode.", %error))
+    .ok()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -105,6 +143,32 @@ mod test {
         crate::test_util::test_generate_config::<SocketSinkConfig>();
     }
 
+    #[test]
+    fn encodes_cef() {
+        let config: SocketSinkConfig = toml::from_str(
+            r#"
+            address = "127.0.0.1:5000"
+            mode = "tcp"
+            encoding.codec = "cef"
+            encoding.cef.device_vendor = "Acme"
+            encoding.cef.device_product = "Gateway"
+            encoding.cef.device_version = "2.1"
+            encoding.cef.extensions = { src = "client_ip" }
+            "#,
+        )
+        .unwrap();
+
+        let mut event = Event::from("denied");
+        event.as_mut_log().insert("client_ip", "10.0.0.1");
+        event.as_mut_log().insert("event_id", "100");
+
+        let bytes = encode_event(event, &config.encoding).unwrap();
+        assert_eq!(
+            bytes,
+            Bytes::from("CEF:0|Acme|Gateway|2.1|100|denied|Unknown|src=10.0.0.1\n")
+        );
+    }
+
     async fn test_udp(addr: SocketAddr) {
         let receiver = UdpSocket::bind(addr).unwrap();
 
@@ -1,27 +1,38 @@
 use crate::{
     config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
     event::{Event, LogEvent, Value},
-    http::HttpClient,
+    http::{HttpClient, HttpError},
     internal_events::{
-        SplunkEventEncodeError, SplunkEventSent, SplunkSourceMissingKeys,
+        SplunkEventEncodeError, SplunkEventSent, SplunkIndexerAcknowledgementQueryFailed,
+        SplunkIndexerAcknowledgementUnavailable, SplunkSourceMissingKeys,
         SplunkSourceTypeMissingKeys,
     },
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink},
+        http::{BatchedHttpSink, HttpRetryLogic, HttpSink},
+        retries::{RetryAction, RetryLogic},
         BatchConfig, BatchSettings, Buffer, Compression, Concurrency, TowerRequestConfig,
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
 };
-use futures::{FutureExt, SinkExt};
-use http::{Request, StatusCode, Uri};
+use bytes::Bytes;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
+use http::{header::HeaderValue, Request, StatusCode, Uri};
 use hyper::Body;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use snafu::{ResultExt, Snafu};
-use std::convert::TryFrom;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::delay_for;
+use tower::Service;
+use uuid::Uuid;
 
 #[derive(Debug, Snafu)]
 pub enum BuildError {
@@ -55,6 +66,34 @@ pub struct HecSinkConfig {
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub tls: Option<TlsOptions>,
+    #[serde(default)]
+    pub acknowledgements: HecAcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct HecAcknowledgementsConfig {
+    /// Only mark batches as delivered once Splunk has indexed them, which
+    /// requires indexer acknowledgement to be enabled for the token.
+    #[serde(default)]
+    pub indexer_acknowledgements_enabled: bool,
+    /// The seconds between the queries of the acknowledgement of a batch.
+    #[serde(default = "default_query_interval")]
+    #[derivative(Default(value = "default_query_interval()"))]
+    pub query_interval: u64,
+    /// The queries made before a batch that wasn't acknowledged is sent again.
+    #[serde(default = "default_retry_limit")]
+    #[derivative(Default(value = "default_retry_limit()"))]
+    pub retry_limit: u32,
+}
+
+fn default_query_interval() -> u64 {
+    10
+}
+
+fn default_retry_limit() -> u32 {
+    30
 }
 
 lazy_static! {
@@ -99,20 +138,46 @@ impl SinkConfig for HecSinkConfig {
             .parse_config(self.batch)?;
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings)?.with_headers(self.request.headers(&cx)?);
+
+        let healthcheck = healthcheck(self.clone(), client.clone()).boxed();
+
+        if self.acknowledgements.indexer_acknowledgements_enabled {
+            // The acknowledgement of a batch is waited for within its request.
+            let mut request = request;
+            request.timeout += Duration::from_secs(
+                self.acknowledgements.query_interval * u64::from(self.acknowledgements.retry_limit),
+            );
+            let service = HecAckService {
+                config: self.clone(),
+                client,
+                channel: Uuid::new_v4().to_hyphenated().to_string(),
+            };
+            let encoder = self.clone();
+            let sink = request
+                .batch_sink(
+                    HecAckRetryLogic,
+                    service,
+                    Buffer::new(batch.size, self.compression),
+                    batch.timeout,
+                    cx.acker(),
+                )
+                .sink_map_err(|error| error!(message = "Fatal splunk_hec sink error.", %error))
+                .with_flat_map(move |event| stream::iter(encoder.encode_event(event)).map(Ok));
+
+            return Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck));
+        }
 
         let sink = BatchedHttpSink::new(
             self.clone(),
             Buffer::new(batch.size, self.compression),
             request,
             batch.timeout,
-            client.clone(),
+            client,
             cx.acker(),
         )
         .sink_map_err(|error| error!(message = "Fatal splunk_hec sink error.", %error));
 
-        let healthcheck = healthcheck(self.clone(), client).boxed();
-
         Ok((super::VectorSink::Sink(Box::new(sink)), healthcheck))
     }
 
@@ -245,6 +310,168 @@ impl HttpSink for HecSinkConfig {
     }
 }
 
+const CHANNEL_HEADER: &str = "X-Splunk-Request-Channel";
+
+#[derive(Deserialize, Debug)]
+struct EventResponse {
+    #[serde(rename = "ackId")]
+    ack_id: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AckResponse {
+    acks: HashMap<u64, bool>,
+}
+
+#[derive(Debug, Snafu)]
+enum AckError {
+    #[snafu(display("Failed to build the request: {}", source))]
+    BuildRequest { source: crate::Error },
+    #[snafu(display("Failed to send the request: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Failed to read the response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Invalid acknowledgement response: {}", source))]
+    InvalidAckResponse { source: serde_json::Error },
+    #[snafu(display(
+        "Splunk didn't acknowledge indexing the events after {} queries",
+        queries
+    ))]
+    Unacknowledged { queries: u32 },
+    #[snafu(display("Unexpected status of the acknowledgement query: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+}
+
+/// Sends the batches over a channel and resolves them once Splunk
+/// acknowledges having indexed their events.
+#[derive(Clone)]
+struct HecAckService {
+    config: HecSinkConfig,
+    client: HttpClient,
+    channel: String,
+}
+
+impl HecAckService {
+    async fn send(self, events: Vec<u8>) -> Result<http::Response<Bytes>, AckError> {
+        let mut request = self
+            .config
+            .build_request(events)
+            .await
+            .context(BuildRequest)?;
+        let channel =
+            HeaderValue::from_str(&self.channel).map_err(|error| AckError::BuildRequest {
+                source: error.into(),
+            })?;
+        request.headers_mut().insert(CHANNEL_HEADER, channel);
+
+        let response = self
+            .client
+            .send(request.map(Body::from))
+            .await
+            .context(SendRequest)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body).await.context(ReadResponse)?;
+        let response = http::Response::from_parts(parts, body);
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+
+        let ack_id = match serde_json::from_slice::<EventResponse>(response.body()) {
+            Ok(EventResponse {
+                ack_id: Some(ack_id),
+            }) => ack_id,
+            _ => {
+                emit!(SplunkIndexerAcknowledgementUnavailable);
+                return Ok(response);
+            }
+        };
+
+        let queries = self.config.acknowledgements.retry_limit;
+        let interval = Duration::from_secs(self.config.acknowledgements.query_interval);
+        for _ in 0..queries {
+            delay_for(interval).await;
+            match self.query_ack(ack_id).await {
+                Ok(true) => return Ok(response),
+                Ok(false) => (),
+                // Splunk rejected the query, which querying again won't change.
+                Err(error @ AckError::UnexpectedStatus { .. }) => return Err(error),
+                Err(error) => emit!(SplunkIndexerAcknowledgementQueryFailed {
+                    error: error.into()
+                }),
+            }
+        }
+
+        Err(AckError::Unacknowledged { queries })
+    }
+
+    async fn query_ack(&self, ack_id: u64) -> Result<bool, AckError> {
+        let uri = build_uri(&self.config.endpoint, "/services/collector/ack").map_err(|error| {
+            AckError::BuildRequest {
+                source: error.into(),
+            }
+        })?;
+        let request = Request::post(uri)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Splunk {}", self.config.token))
+            .header(CHANNEL_HEADER, self.channel.as_str())
+            .body(Body::from(json!({ "acks": [ack_id] }).to_string()))
+            .map_err(|error| AckError::BuildRequest {
+                source: error.into(),
+            })?;
+
+        let response = self.client.send(request).await.context(SendRequest)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AckError::UnexpectedStatus { status });
+        }
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadResponse)?;
+        let response = serde_json::from_slice::<AckResponse>(&body).context(InvalidAckResponse)?;
+        Ok(response.acks.get(&ack_id).copied().unwrap_or(false))
+    }
+}
+
+impl Service<Vec<u8>> for HecAckService {
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, events: Vec<u8>) -> Self::Future {
+        Box::pin(self.clone().send(events).map_err(Into::into))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HecAckRetryLogic;
+
+impl RetryLogic for HecAckRetryLogic {
+    type Error = AckError;
+    type Response = http::Response<Bytes>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            // As with `HttpRetryLogic`, batches that failed to be sent are
+            // sent again.
+            AckError::SendRequest { .. } | AckError::ReadResponse { .. } => true,
+            // Batches that weren't acknowledged in time are sent again.
+            AckError::Unacknowledged { .. } => true,
+            AckError::BuildRequest { .. }
+            | AckError::InvalidAckResponse { .. }
+            | AckError::UnexpectedStatus { .. } => false,
+        }
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        HttpRetryLogic.should_retry_response(response)
+    }
+}
+
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
     #[snafu(display("Invalid HEC token"))]
@@ -288,7 +515,11 @@ fn build_uri(host: &str, path: &str) -> Result<Uri, http::uri::InvalidUri> {
 mod tests {
     use super::*;
     use crate::event::Event;
-    use crate::sinks::util::{http::HttpSink, test::load_sink};
+    use crate::sinks::util::{
+        http::HttpSink,
+        test::{build_test_server, load_sink},
+    };
+    use crate::test_util::next_addr;
     use chrono::Utc;
     use serde::Deserialize;
     use std::collections::BTreeMap;
@@ -421,6 +652,68 @@ mod tests {
         assert!(uri.is_ok());
         assert_eq!(format!("{}", uri.unwrap()), "http://test.com/a");
     }
+
+    #[test]
+    fn splunk_parse_acknowledgements() {
+        let response: EventResponse =
+            serde_json::from_str(r#"{"text":"Success","code":0,"ackId":7}"#).unwrap();
+        assert_eq!(response.ack_id, Some(7));
+
+        let response: EventResponse =
+            serde_json::from_str(r#"{"text":"Success","code":0}"#).unwrap();
+        assert_eq!(response.ack_id, None);
+
+        let response: AckResponse =
+            serde_json::from_str(r#"{"acks":{"6":false,"7":true}}"#).unwrap();
+        assert_eq!(response.acks.get(&6), Some(&false));
+        assert_eq!(response.acks.get(&7), Some(&true));
+    }
+
+    #[test]
+    fn splunk_ack_retry_logic() {
+        let logic = HecAckRetryLogic;
+        assert!(logic.is_retriable_error(&AckError::Unacknowledged { queries: 30 }));
+        assert!(!logic.is_retriable_error(&AckError::UnexpectedStatus {
+            status: StatusCode::BAD_REQUEST
+        }));
+    }
+
+    #[tokio::test]
+    async fn splunk_acknowledgements_unavailable() {
+        let addr = next_addr();
+        let (mut rx, trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let (config, _cx) = load_sink::<HecSinkConfig>(&format!(
+            r#"
+            endpoint = "http://{}"
+            token = "alksjdfo"
+
+            [acknowledgements]
+            indexer_acknowledgements_enabled = true
+        "#,
+            addr
+        ))
+        .unwrap();
+        let service = HecAckService {
+            config,
+            client: HttpClient::new(None).unwrap(),
+            channel: "7fda5d0f-4ccb-4a95-a2d2-2b0a896dba85".into(),
+        };
+
+        // Without an acknowledgement ID the batch is delivered right away.
+        let response = service.send(b"{}".to_vec()).await.unwrap();
+        assert!(response.status().is_success());
+
+        let (parts, _body) = rx.next().await.unwrap();
+        assert_eq!(parts.uri.path(), "/services/collector/event");
+        assert_eq!(
+            parts.headers.get(CHANNEL_HEADER).unwrap(),
+            "7fda5d0f-4ccb-4a95-a2d2-2b0a896dba85"
+        );
+
+        drop(trigger);
+    }
 }
 
 #[cfg(test)]
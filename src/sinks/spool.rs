@@ -0,0 +1,552 @@
+use crate::{
+    buffers::Acker,
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    internal_events::{SpoolEventSent, SpoolIoError, SpoolSegmentDiscarded},
+    sinks::util::StreamSink,
+    spool,
+};
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::{
+    future,
+    stream::{BoxStream, StreamExt},
+    FutureExt,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    io, mem,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::{
+    fs::{self, File},
+    io::{AsyncWriteExt, BufWriter},
+    time,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display(
+        "max_segment_bytes ({}) must not be larger than max_size_bytes ({})",
+        max_segment_bytes,
+        max_size_bytes
+    ))]
+    SegmentLargerThanSpool {
+        max_segment_bytes: u64,
+        max_size_bytes: u64,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpoolSinkConfig {
+    pub path: PathBuf,
+    #[serde(default = "default_max_size_bytes")]
+    pub max_size_bytes: u64,
+    #[serde(default = "default_max_segment_bytes")]
+    pub max_segment_bytes: u64,
+    #[serde(default = "default_max_segment_age_secs")]
+    pub max_segment_age_secs: u64,
+    pub max_age_secs: Option<u64>,
+}
+
+const fn default_max_size_bytes() -> u64 {
+    1024 * 1024 * 1024
+}
+
+const fn default_max_segment_bytes() -> u64 {
+    8 * 1024 * 1024
+}
+
+const fn default_max_segment_age_secs() -> u64 {
+    30
+}
+
+inventory::submit! {
+    SinkDescription::new::<SpoolSinkConfig>("spool")
+}
+
+impl GenerateConfig for SpoolSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/var/lib/vector/spool".into(),
+            max_size_bytes: default_max_size_bytes(),
+            max_segment_bytes: default_max_segment_bytes(),
+            max_segment_age_secs: default_max_segment_age_secs(),
+            max_age_secs: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "spool")]
+impl SinkConfig for SpoolSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if self.max_segment_bytes > self.max_size_bytes {
+            return Err(BuildError::SegmentLargerThanSpool {
+                max_segment_bytes: self.max_segment_bytes,
+                max_size_bytes: self.max_size_bytes,
+            }
+            .into());
+        }
+        fs::create_dir_all(&self.path).await?;
+
+        let sink = SpoolSink::new(self.clone(), cx.acker());
+        Ok((
+            super::VectorSink::Stream(Box::new(sink)),
+            future::ok(()).boxed(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "spool"
+    }
+}
+
+struct OpenSegment {
+    path: PathBuf,
+    created: u64,
+    opened: Instant,
+    file: BufWriter<File>,
+    events: usize,
+    bytes: u64,
+}
+
+/// Writes the events to segments of the spool directory, for a `spool`
+/// source to forward.
+struct SpoolSink {
+    config: SpoolSinkConfig,
+    acker: Acker,
+    current: Option<OpenSegment>,
+    /// The events received since the open segment was last synced to disk,
+    /// acked once it is.
+    unacked: usize,
+    last_created: u64,
+    /// The bytes of the closed segments, as of the last time they were
+    /// listed.
+    closed_bytes: u64,
+}
+
+impl SpoolSink {
+    fn new(config: SpoolSinkConfig, acker: Acker) -> Self {
+        Self {
+            config,
+            acker,
+            current: None,
+            unacked: 0,
+            last_created: 0,
+            closed_bytes: 0,
+        }
+    }
+
+    fn current_bytes(&self) -> u64 {
+        self.current.as_ref().map_or(0, |segment| segment.bytes)
+    }
+
+    /// Closes the segments left open when Vector last stopped, so that they
+    /// are forwarded too.
+    async fn recover(&mut self) {
+        let segments = match spool::open_segments(&self.config.path).await {
+            Ok(segments) => segments,
+            Err(error) => {
+                emit!(SpoolIoError {
+                    path: &self.config.path,
+                    operation: "list",
+                    error,
+                });
+                return;
+            }
+        };
+        for (path, created) in segments {
+            let events = match fs::read(&path).await {
+                Ok(bytes) => spool::decode(bytes.into()).0.len(),
+                Err(error) => {
+                    emit!(SpoolIoError {
+                        path: &path,
+                        operation: "read",
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let closed = spool::closed_path(&self.config.path, created, events);
+            if let Err(error) = fs::rename(&path, &closed).await {
+                emit!(SpoolIoError {
+                    path: &path,
+                    operation: "close",
+                    error,
+                });
+            }
+            self.last_created = self.last_created.max(created);
+        }
+    }
+
+    async fn open(&mut self) -> io::Result<&mut OpenSegment> {
+        if self.current.is_none() {
+            // The names of the segments order them, and must be unique.
+            let created = spool::now_micros().max(self.last_created + 1);
+            let path = spool::open_path(&self.config.path, created);
+            let file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .await?;
+            self.last_created = created;
+            self.current = Some(OpenSegment {
+                path,
+                created,
+                opened: Instant::now(),
+                file: BufWriter::new(file),
+                events: 0,
+                bytes: 0,
+            });
+        }
+        Ok(self.current.as_mut().unwrap())
+    }
+
+    /// Flushes the open segment to disk, acking the events written to it.
+    async fn sync(&mut self) {
+        if self.unacked == 0 {
+            return;
+        }
+        let synced = match self.current.as_mut() {
+            Some(segment) => {
+                let result: io::Result<()> = async {
+                    segment.file.flush().await?;
+                    segment.file.get_mut().sync_data().await
+                }
+                .await;
+                match result {
+                    Ok(()) => true,
+                    Err(error) => {
+                        emit!(SpoolIoError {
+                            path: &segment.path,
+                            operation: "sync",
+                            error,
+                        });
+                        false
+                    }
+                }
+            }
+            None => true,
+        };
+        if synced {
+            self.acker.ack(mem::take(&mut self.unacked));
+        } else {
+            self.close().await;
+        }
+    }
+
+    /// Flushes the open segment to disk and renames it, handing it over to
+    /// the source. The events received until then are acked, failing to
+    /// close the segment loses them.
+    async fn close(&mut self) {
+        if let Some(mut segment) = self.current.take() {
+            let closed = spool::closed_path(&self.config.path, segment.created, segment.events);
+            let result: io::Result<()> = async {
+                segment.file.flush().await?;
+                segment.file.get_mut().sync_all().await?;
+                fs::rename(&segment.path, &closed).await
+            }
+            .await;
+            match result {
+                Ok(()) => {
+                    debug!(message = "Closed segment.", path = ?closed, events = %segment.events);
+                    self.closed_bytes += segment.bytes;
+                }
+                Err(error) => emit!(SpoolIoError {
+                    path: &segment.path,
+                    operation: "close",
+                    error,
+                }),
+            }
+        }
+        self.acker.ack(mem::take(&mut self.unacked));
+    }
+
+    /// Discards the closed segments older than `max_age_secs`, and the
+    /// oldest ones for the spool to stay within `max_size_bytes` once
+    /// `incoming` more bytes are written.
+    async fn enforce_limits(&mut self, incoming: u64) {
+        let segments = match spool::closed_segments(&self.config.path).await {
+            Ok(segments) => segments,
+            Err(error) => {
+                emit!(SpoolIoError {
+                    path: &self.config.path,
+                    operation: "list",
+                    error,
+                });
+                return;
+            }
+        };
+
+        let now = spool::now_micros();
+        let max_age = self.config.max_age_secs.map(|secs| secs * 1_000_000);
+        let budget = self
+            .config
+            .max_size_bytes
+            .saturating_sub(self.current_bytes() + incoming);
+        let mut total = segments.iter().map(|segment| segment.bytes).sum::<u64>();
+
+        for segment in segments {
+            let reason = if max_age.map_or(false, |max_age| {
+                now.saturating_sub(segment.created) > max_age
+            }) {
+                "expired"
+            } else if total > budget {
+                "full"
+            } else {
+                // The segments are listed oldest first.
+                break;
+            };
+            match fs::remove_file(&segment.path).await {
+                Ok(()) => emit!(SpoolSegmentDiscarded {
+                    path: &segment.path,
+                    events: segment.events,
+                    reason,
+                }),
+                // The segment was forwarded in the meantime.
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                Err(error) => emit!(SpoolIoError {
+                    path: &segment.path,
+                    operation: "delete",
+                    error,
+                }),
+            }
+            total -= segment.bytes;
+        }
+        self.closed_bytes = total;
+    }
+
+    async fn write(&mut self, event: Event) {
+        let mut buf = BytesMut::new();
+        spool::encode(event, &mut buf);
+        let len = buf.len() as u64;
+
+        if self.current.as_ref().map_or(false, |segment| {
+            segment.events > 0 && segment.bytes + len > self.config.max_segment_bytes
+        }) {
+            self.close().await;
+        }
+        if self.closed_bytes + self.current_bytes() + len > self.config.max_size_bytes {
+            self.enforce_limits(len).await;
+        }
+
+        let segment = match self.open().await {
+            Ok(segment) => segment,
+            Err(error) => {
+                emit!(SpoolIoError {
+                    path: &self.config.path,
+                    operation: "open",
+                    error,
+                });
+                return;
+            }
+        };
+        match segment.file.write_all(&buf).await {
+            Ok(()) => {
+                segment.events += 1;
+                segment.bytes += len;
+                emit!(SpoolEventSent {
+                    byte_size: buf.len()
+                });
+            }
+            Err(error) => {
+                emit!(SpoolIoError {
+                    path: &segment.path,
+                    operation: "write",
+                    error,
+                });
+                // A partly written event ends the segment.
+                self.close().await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink for SpoolSink {
+    async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.recover().await;
+        self.enforce_limits(0).await;
+
+        let max_segment_age = Duration::from_secs(self.config.max_segment_age_secs);
+        let mut interval = time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                event = input.next() => match event {
+                    Some(event) => {
+                        self.write(event).await;
+                        self.unacked += 1;
+                        // Events that couldn't be written are dropped.
+                        if self.current.is_none() {
+                            self.acker.ack(mem::take(&mut self.unacked));
+                        }
+                    }
+                    None => {
+                        self.close().await;
+                        break;
+                    }
+                },
+                _ = interval.tick() => {
+                    if self
+                        .current
+                        .as_ref()
+                        .map_or(false, |segment| segment.opened.elapsed() >= max_segment_age)
+                    {
+                        self.close().await;
+                    }
+                    self.sync().await;
+                    self.enforce_limits(0).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, temp_dir};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SpoolSinkConfig>();
+    }
+
+    fn config(path: PathBuf) -> SpoolSinkConfig {
+        SpoolSinkConfig {
+            path,
+            max_size_bytes: 10_000,
+            max_segment_bytes: 1_000,
+            max_segment_age_secs: 30,
+            max_age_secs: None,
+        }
+    }
+
+    async fn read_events(path: &PathBuf) -> Vec<Event> {
+        let mut events = Vec::new();
+        for segment in spool::closed_segments(path).await.unwrap() {
+            let (segment_events, error) =
+                spool::decode(fs::read(&segment.path).await.unwrap().into());
+            assert!(error.is_none());
+            assert_eq!(segment_events.len(), segment.events);
+            events.extend(segment_events);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn writes_segments() {
+        let path = temp_dir();
+        fs::create_dir_all(&path).await.unwrap();
+
+        let (input, events) = random_lines_with_stream(100, 50);
+        let mut sink = SpoolSink::new(config(path.clone()), Acker::Null);
+        sink.run(events.boxed()).await.unwrap();
+
+        let segments = spool::closed_segments(&path).await.unwrap();
+        assert!(segments.len() > 1);
+        assert!(segments.iter().all(|segment| segment.bytes <= 1_000));
+
+        let output = read_events(&path).await;
+        let messages = output
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, input);
+    }
+
+    #[tokio::test]
+    async fn discards_oldest_segments_when_full() {
+        let path = temp_dir();
+        fs::create_dir_all(&path).await.unwrap();
+
+        let (input, events) = random_lines_with_stream(100, 200);
+        let mut sink = SpoolSink::new(config(path.clone()), Acker::Null);
+        sink.run(events.boxed()).await.unwrap();
+
+        let segments = spool::closed_segments(&path).await.unwrap();
+        assert!(segments.iter().map(|segment| segment.bytes).sum::<u64>() <= 10_000);
+
+        // The newest events are kept.
+        let output = read_events(&path).await;
+        let messages = output
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert!(!messages.is_empty());
+        assert_eq!(messages[..], input[input.len() - messages.len()..]);
+    }
+
+    #[tokio::test]
+    async fn recovers_open_segments() {
+        let path = temp_dir();
+        fs::create_dir_all(&path).await.unwrap();
+
+        // A segment cut in the middle of its second event.
+        let mut buf = BytesMut::new();
+        spool::encode(Event::from("first"), &mut buf);
+        let first_len = buf.len();
+        spool::encode(Event::from("second"), &mut buf);
+        fs::write(spool::open_path(&path, 1), &buf[..first_len + 4])
+            .await
+            .unwrap();
+
+        let mut sink = SpoolSink::new(config(path.clone()), Acker::Null);
+        sink.run(futures::stream::empty().boxed()).await.unwrap();
+
+        let segments = spool::closed_segments(&path).await.unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].path, spool::closed_path(&path, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn acks_once_synced() {
+        let path = temp_dir();
+        fs::create_dir_all(&path).await.unwrap();
+
+        let (acker, acked) = Acker::new_for_testing();
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let sink = tokio::spawn(async move {
+            let mut sink = SpoolSink::new(config(path), acker);
+            sink.run(rx.boxed()).await
+        });
+
+        for message in &["one", "two"] {
+            tx.unbounded_send(Event::from(*message)).unwrap();
+        }
+        time::delay_for(Duration::from_millis(100)).await;
+        assert_eq!(acked.load(std::sync::atomic::Ordering::Relaxed), 0);
+
+        // The open segment is synced every second.
+        time::delay_for(Duration::from_millis(1500)).await;
+        assert_eq!(acked.load(std::sync::atomic::Ordering::Relaxed), 2);
+
+        tx.unbounded_send(Event::from("three")).unwrap();
+        drop(tx);
+        sink.await.unwrap().unwrap();
+        assert_eq!(acked.load(std::sync::atomic::Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn rejects_segments_larger_than_spool() {
+        let config = SpoolSinkConfig {
+            max_segment_bytes: 20_000,
+            ..config(temp_dir())
+        };
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+}
@@ -0,0 +1,639 @@
+#[cfg(unix)]
+use crate::sinks::util::unix::UnixSinkConfig;
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::{Event, LogEvent, Value},
+    sinks::util::{
+        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        tcp::TcpSinkConfig,
+        udp::UdpSinkConfig,
+    },
+    template::{Template, TemplateError},
+};
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{convert::TryFrom, fmt::Write};
+
+/// The header fields that are not written as structured data.
+const HEADER_FIELDS: &[&str] = &[
+    "appname", "facility", "hostname", "msgid", "procid", "severity", "version",
+];
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid {} template: {}", field, source))]
+    HeaderTemplate {
+        field: &'static str,
+        source: TemplateError,
+    },
+    #[snafu(display("Invalid {} {:?}.", field, value))]
+    InvalidCode { field: &'static str, value: String },
+    #[snafu(display("Octet counted framing is not supported in `udp` mode."))]
+    OctetCountingOverUdp,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+// TODO: add back when serde-rs/serde#1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct SyslogSinkConfig {
+    #[serde(flatten)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub rfc: SyslogRfc,
+    #[serde(default = "default_facility")]
+    pub facility: String,
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    #[serde(default = "default_appname")]
+    pub appname: String,
+    #[serde(default)]
+    pub framing: Framing,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Mode {
+    Tcp(TcpSinkConfig),
+    Udp(UdpSinkConfig),
+    #[cfg(unix)]
+    Unix(UnixSinkConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogRfc {
+    Rfc3164,
+    Rfc5424,
+}
+
+impl Default for SyslogRfc {
+    fn default() -> Self {
+        SyslogRfc::Rfc5424
+    }
+}
+
+/// How messages are delimited on TCP and Unix sockets.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// Messages are terminated by a newline.
+    NewlineDelimited,
+    /// Messages are prefixed with their length, as described in RFC 6587.
+    OctetCounted,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Framing::NewlineDelimited
+    }
+}
+
+/// The encoding of the message part.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Cef,
+    Leef,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Text
+    }
+}
+
+const FACILITIES: &[&str] = &[
+    "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv",
+    "ftp", "ntp", "audit", "alert", "clockd", "local0", "local1", "local2", "local3", "local4",
+    "local5", "local6", "local7",
+];
+
+const SEVERITIES: &[&str] = &[
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// The facility and severity of the events whose templates don't render to a
+/// valid one, `user` and `info`.
+const DEFAULT_FACILITY: usize = 1;
+const DEFAULT_SEVERITY: usize = 6;
+
+fn default_facility() -> String {
+    "{{ facility }}".to_owned()
+}
+
+fn default_severity() -> String {
+    "{{ severity }}".to_owned()
+}
+
+fn default_appname() -> String {
+    "{{ appname }}".to_owned()
+}
+
+inventory::submit! {
+    SinkDescription::new::<SyslogSinkConfig>("syslog")
+}
+
+impl GenerateConfig for SyslogSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "127.0.0.1:514"
+            mode = "tcp""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "syslog")]
+impl SinkConfig for SyslogSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let encoder = self.encoder()?;
+        let encode_event = move |event| Some(encoder.encode(event));
+        match &self.mode {
+            Mode::Tcp(config) => config.build(cx, encode_event),
+            Mode::Udp(config) => config.build(cx, encode_event),
+            #[cfg(unix)]
+            Mode::Unix(config) => config.build(cx, encode_event),
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "syslog"
+    }
+}
+
+impl SyslogSinkConfig {
+    fn encoder(&self) -> crate::Result<Encoder> {
+        if matches!(self.mode, Mode::Udp(_)) && self.framing == Framing::OctetCounted {
+            return Err(Box::new(BuildError::OctetCountingOverUdp));
+        }
+        Ok(Encoder {
+            rfc: self.rfc,
+            facility: code_template("facility", &self.facility, FACILITIES)?,
+            severity: code_template("severity", &self.severity, SEVERITIES)?,
+            appname: Template::try_from(self.appname.as_str())
+                .context(HeaderTemplate { field: "appname" })?,
+            framing: self.framing,
+            encoding: self.encoding.clone(),
+        })
+    }
+}
+
+/// Compile the facility or severity template, checking that a static one
+/// names a valid code.
+fn code_template(field: &'static str, template: &str, names: &[&str]) -> crate::Result<Template> {
+    let template = Template::try_from(template).context(HeaderTemplate { field })?;
+    if !template.is_dynamic() && code(template.get_ref(), names).is_none() {
+        return Err(Box::new(BuildError::InvalidCode {
+            field,
+            value: template.get_ref().to_owned(),
+        }));
+    }
+    Ok(template)
+}
+
+struct Encoder {
+    rfc: SyslogRfc,
+    facility: Template,
+    severity: Template,
+    appname: Template,
+    framing: Framing,
+    encoding: EncodingConfigWithDefault<Encoding>,
+}
+
+impl Encoder {
+    /// Encode `event` as an RFC 5424 or RFC 3164 message. The facility,
+    /// severity and appname are rendered from their templates, and the rest
+    /// of the header is taken from the fields the `syslog` source decodes.
+    fn encode(&self, mut event: Event) -> Bytes {
+        let facility = render_code(&self.facility, &event, FACILITIES).unwrap_or(DEFAULT_FACILITY);
+        let severity = render_code(&self.severity, &event, SEVERITIES).unwrap_or(DEFAULT_SEVERITY);
+        let appname = self.appname.render_string(&event).ok().map(Value::from);
+
+        self.encoding.apply_rules(&mut event);
+        let log = event.into_log();
+
+        let msg = match self.encoding.codec() {
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|msg| msg.to_string_lossy()),
+            Encoding::Cef => Some(self.encoding.encode_cef(&log)),
+            Encoding::Leef => Some(self.encoding.encode_leef(&log)),
+        };
+        let pri = facility * 8 + severity;
+        let message = match self.rfc {
+            SyslogRfc::Rfc5424 => {
+                // Only plain text messages carry structured data.
+                let structured_data = self.encoding.codec() == &Encoding::Text;
+                encode_5424(&log, pri, appname.as_ref(), structured_data, msg)
+            }
+            SyslogRfc::Rfc3164 => encode_3164(&log, pri, appname.as_ref(), msg),
+        };
+
+        match self.framing {
+            Framing::NewlineDelimited => Bytes::from(message + "\n"),
+            Framing::OctetCounted => Bytes::from(format!("{} {}", message.len(), message)),
+        }
+    }
+}
+
+fn render_code(template: &Template, event: &Event, names: &[&str]) -> Option<usize> {
+    code(&template.render_string(event).ok()?, names)
+}
+
+/// Look up the facility or severity by either its name or its code.
+fn code(value: &str, names: &[&str]) -> Option<usize> {
+    match value.parse::<usize>() {
+        Ok(code) if code < names.len() => Some(code),
+        Ok(_) => None,
+        Err(_) => names.iter().position(|name| *name == value),
+    }
+}
+
+/// Encode an RFC 5424 message, where every top level map is written as a
+/// structured data element keyed by its SD-ID.
+fn encode_5424(
+    log: &LogEvent,
+    pri: usize,
+    appname: Option<&Value>,
+    structured_data: bool,
+    msg: Option<String>,
+) -> String {
+    let timestamp = match log.get(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(timestamp)) => timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        _ => "-".to_owned(),
+    };
+    let hostname = log
+        .get("hostname")
+        .or_else(|| log.get(log_schema().host_key()));
+
+    let mut message = format!(
+        "<{}>1 {} {} {} {} {}",
+        pri,
+        timestamp,
+        header_field(hostname, 255),
+        header_field(appname, 48),
+        header_field(log.get("procid"), 128),
+        header_field(log.get("msgid"), 32),
+    );
+
+    let mut has_structured_data = false;
+    if structured_data {
+        for (id, value) in log.as_map() {
+            let params = match value {
+                Value::Map(params) if !HEADER_FIELDS.contains(&id.as_str()) => params,
+                _ => continue,
+            };
+            if !is_valid_sd_id(id) || params.is_empty() {
+                continue;
+            }
+            has_structured_data = true;
+            write!(message, " [{}", id).expect("Writing to a String cannot fail");
+            for (name, value) in params {
+                if !is_valid_sd_name(name) {
+                    continue;
+                }
+                // Repeated parameters are decoded as arrays.
+                let values = match value {
+                    Value::Array(values) => values.iter().collect(),
+                    value => vec![value],
+                };
+                for value in values {
+                    write!(message, " {}=\"{}\"", name, escape_param(value))
+                        .expect("Writing to a String cannot fail");
+                }
+            }
+            message.push(']');
+        }
+    }
+    if !has_structured_data {
+        message.push_str(" -");
+    }
+
+    if let Some(msg) = msg {
+        message.push(' ');
+        message.push_str(&msg);
+    }
+    message
+}
+
+/// Encode an RFC 3164 message, `<PRI>Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG`.
+/// The timestamp has no year nor time zone, and is written in UTC.
+fn encode_3164(log: &LogEvent, pri: usize, appname: Option<&Value>, msg: Option<String>) -> String {
+    let timestamp = match log.get(log_schema().timestamp_key()) {
+        Some(Value::Timestamp(timestamp)) => *timestamp,
+        _ => Utc::now(),
+    };
+    let hostname = log
+        .get("hostname")
+        .or_else(|| log.get(log_schema().host_key()));
+
+    let mut message = format!(
+        "<{}>{} {} ",
+        pri,
+        timestamp.format("%b %e %H:%M:%S"),
+        header_field(hostname, 255),
+    );
+
+    // The tag is up to 32 alphanumeric characters.
+    let tag = appname
+        .map(|appname| {
+            appname
+                .to_string_lossy()
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .take(32)
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    if !tag.is_empty() {
+        message.push_str(&tag);
+        if let Some(procid) = log.get("procid") {
+            write!(message, "[{}]", header_field(Some(procid), 128))
+                .expect("Writing to a String cannot fail");
+        }
+        message.push_str(": ");
+    }
+
+    if let Some(msg) = msg {
+        message.push_str(&msg);
+    }
+    message
+}
+
+/// Header fields are printable ASCII without spaces, `-` stands for a
+/// missing value.
+fn header_field(value: Option<&Value>, max_length: usize) -> String {
+    let field = value
+        .map(|value| {
+            value
+                .to_string_lossy()
+                .chars()
+                .filter(|c| ('!'..='~').contains(c))
+                .take(max_length)
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    if field.is_empty() {
+        "-".to_owned()
+    } else {
+        field
+    }
+}
+
+/// SD-NAMEs are up to 32 printable ASCII characters except `=`, space, `]`
+/// and `"`.
+fn is_valid_sd_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| ('!'..='~').contains(&c) && !matches!(c, '=' | ']' | '"'))
+}
+
+/// SD-IDs are SD-NAMEs, those not registered with IANA take the form
+/// `name@<private enterprise number>`, where the enterprise number may be
+/// followed by dot separated sub-identifiers.
+fn is_valid_sd_id(id: &str) -> bool {
+    if !is_valid_sd_name(id) {
+        return false;
+    }
+    let mut parts = id.splitn(2, '@');
+    let name = parts.next().unwrap_or_default();
+    match parts.next() {
+        None => true,
+        Some(pen) => {
+            !name.is_empty()
+                && pen
+                    .split('.')
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        }
+    }
+}
+
+fn escape_param(value: &Value) -> String {
+    let value = value.to_string_lossy();
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SyslogSinkConfig>();
+    }
+
+    fn encoder(options: &str) -> crate::Result<Encoder> {
+        let config: SyslogSinkConfig =
+            toml::from_str(&format!("address = \"127.0.0.1:514\"\n{}", options)).unwrap();
+        config.encoder()
+    }
+
+    fn encode(event: Event) -> String {
+        encode_with(event, r#"mode = "tcp""#)
+    }
+
+    fn encode_with(event: Event, options: &str) -> String {
+        let bytes = encoder(options).unwrap().encode(event);
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn encodes_header() {
+        let mut event = Event::from("i am foobar");
+        {
+            let log = event.as_mut_log();
+            log.insert(
+                log_schema().timestamp_key(),
+                Utc.ymd(2019, 2, 13).and_hms(19, 48, 34),
+            );
+            log.insert("hostname", "74794bfb6795");
+            log.insert("severity", "notice");
+            log.insert("facility", "local7");
+            log.insert("appname", "root");
+            log.insert("procid", 8449);
+        }
+
+        assert_eq!(
+            encode(event),
+            "<189>1 2019-02-13T19:48:34Z 74794bfb6795 root 8449 - - i am foobar\n"
+        );
+    }
+
+    #[test]
+    fn encodes_defaults() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().remove(log_schema().timestamp_key());
+
+        assert_eq!(encode(event), "<14>1 - - - - - - hello\n");
+    }
+
+    #[test]
+    fn encodes_structured_data() {
+        let mut event = Event::from("hello");
+        {
+            let log = event.as_mut_log();
+            log.remove(log_schema().timestamp_key());
+            log.insert_flat(
+                "exampleSDID@32473.1",
+                Value::Map(
+                    vec![
+                        ("eventID".to_owned(), Value::from("1011")),
+                        ("quote".to_owned(), Value::from(r#"a "b" ]c\"#)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+            );
+            log.insert(
+                "origin.ip",
+                Value::Array(vec!["10.0.0.1".into(), "10.0.0.2".into()]),
+            );
+            log.insert("invalid@pen.x", "dropped");
+        }
+
+        assert_eq!(
+            encode(event),
+            concat!(
+                r#"<14>1 - - - - - [exampleSDID@32473.1 eventID="1011" quote="a \"b\" \]c\\"]"#,
+                r#" [origin ip="10.0.0.1" ip="10.0.0.2"] hello"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn encodes_cef_message() {
+        let mut event = Event::from("login failed");
+        {
+            let log = event.as_mut_log();
+            log.remove(log_schema().timestamp_key());
+            log.insert("appname", "sshd");
+            log.insert("event_id", 4625);
+            log.insert("origin.ip", "10.0.0.1");
+        }
+
+        assert_eq!(
+            encode_with(
+                event,
+                r#"
+                mode = "tcp"
+                encoding.codec = "cef"
+                encoding.cef.device_vendor = "OpenBSD"
+                encoding.cef.device_product = "OpenSSH"
+                encoding.cef.device_version = "8.4"
+                encoding.cef.extensions = { src = "origin.ip" }
+                "#
+            ),
+            "<14>1 - - sshd - - - CEF:0|OpenBSD|OpenSSH|8.4|4625|login failed|Unknown|src=10.0.0.1\n"
+        );
+    }
+
+    #[test]
+    fn encodes_templated_header() {
+        let mut event = Event::from("hello");
+        {
+            let log = event.as_mut_log();
+            log.remove(log_schema().timestamp_key());
+            log.insert("level", "warning");
+            log.insert("service", "api");
+            log.insert("facility", "kern");
+        }
+
+        assert_eq!(
+            encode_with(
+                event,
+                r#"
+                mode = "tcp"
+                facility = "local0"
+                severity = "{{ level }}"
+                appname = "vector-{{ service }}"
+                "#
+            ),
+            "<132>1 - - vector-api - - - hello\n"
+        );
+    }
+
+    #[test]
+    fn encodes_rfc3164() {
+        let mut event = Event::from("i am foobar");
+        {
+            let log = event.as_mut_log();
+            log.insert(
+                log_schema().timestamp_key(),
+                Utc.ymd(2019, 2, 3).and_hms(19, 48, 34),
+            );
+            log.insert("hostname", "74794bfb6795");
+            log.insert("severity", "notice");
+            log.insert("appname", "root-cron");
+            log.insert("procid", 8449);
+        }
+
+        assert_eq!(
+            encode_with(event.clone(), "mode = \"tcp\"\nrfc = \"rfc3164\""),
+            "<13>Feb  3 19:48:34 74794bfb6795 rootcron[8449]: i am foobar\n"
+        );
+
+        event.as_mut_log().remove("appname");
+        assert_eq!(
+            encode_with(event, "mode = \"tcp\"\nrfc = \"rfc3164\""),
+            "<13>Feb  3 19:48:34 74794bfb6795 i am foobar\n"
+        );
+    }
+
+    #[test]
+    fn encodes_octet_counted() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().remove(log_schema().timestamp_key());
+
+        assert_eq!(
+            encode_with(event, "mode = \"tcp\"\nframing = \"octet_counted\""),
+            "23 <14>1 - - - - - - hello"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert!(encoder("mode = \"tcp\"\nfacility = \"local9\"").is_err());
+        assert!(encoder("mode = \"tcp\"\nseverity = \"8\"").is_err());
+        assert!(encoder("mode = \"tcp\"\nseverity = \"{{ level }}\"").is_ok());
+        assert!(encoder("mode = \"udp\"\nframing = \"octet_counted\"").is_err());
+    }
+
+    #[test]
+    fn validates_sd_ids() {
+        assert!(is_valid_sd_id("origin"));
+        assert!(is_valid_sd_id("exampleSDID@32473"));
+        assert!(is_valid_sd_id("exampleSDID@32473.1.2"));
+        assert!(!is_valid_sd_id("exampleSDID@"));
+        assert!(!is_valid_sd_id("@32473"));
+        assert!(!is_valid_sd_id("a@b@1"));
+        assert!(!is_valid_sd_id("has space"));
+        assert!(!is_valid_sd_id(&"x".repeat(33)));
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
         AdaptiveConcurrencyObservedRtt,
     },
     sinks::util::retries::{RetryAction, RetryLogic},
+    tuning::{self, Tunable},
 };
 use std::future::Future;
 use std::sync::{Arc, Mutex, MutexGuard};
@@ -23,6 +24,8 @@ use tower::timeout::error::Elapsed;
 pub(super) struct Controller<L> {
     semaphore: Arc<ShrinkableSemaphore>,
     concurrency: Option<usize>,
+    /// Only a fixed `concurrency` can be overridden.
+    concurrency_override: Tunable,
     settings: AdaptiveConcurrencySettings,
     logic: L,
     pub(super) inner: Arc<Mutex<Inner>>,
@@ -61,9 +64,14 @@ impl<L> Controller<L> {
         // mechanisms. Otherwise, the current limit is set to 1 and the
         // maximum to MAX_CONCURRENCY.
         let current_limit = concurrency.unwrap_or(1);
+        let concurrency_override = match concurrency {
+            Some(_) => tuning::register(tuning::REQUEST_CONCURRENCY),
+            None => Tunable::default(),
+        };
         Self {
             semaphore: Arc::new(ShrinkableSemaphore::new(current_limit)),
             concurrency,
+            concurrency_override,
             settings,
             logic,
             inner: Arc::new(Mutex::new(Inner {
@@ -82,6 +90,18 @@ impl<L> Controller<L> {
 
     pub(super) fn acquire(&self) -> impl Future<Output = OwnedSemaphorePermit> + Send + 'static {
         let mut inner = self.inner.lock().expect("Controller mutex is poisoned");
+        if let Some(concurrency) = self.concurrency {
+            let limit = self
+                .concurrency_override
+                .get()
+                .map_or(concurrency, |limit| limit as usize);
+            if limit > inner.current_limit {
+                self.semaphore.add_permits(limit - inner.current_limit);
+            } else if limit < inner.current_limit {
+                self.semaphore.forget_permits(inner.current_limit - limit);
+            }
+            inner.current_limit = limit;
+        }
         if inner.in_flight >= inner.current_limit {
             inner.reached_limit = true;
         }
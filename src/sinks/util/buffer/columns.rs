@@ -0,0 +1,254 @@
+//! A columnar layout of metric batches, modelled on Arrow record batches with
+//! dictionary encoded strings.
+//!
+//! Large batches mostly consist of counters and gauges sharing a handful of
+//! names and tags. Laying their values out in columns and storing each
+//! distinct string once lets the sinks encode them in a single pass without
+//! allocating per metric, which otherwise dominates at high throughput.
+
+use crate::event::metric::{Metric, MetricKind, MetricValue};
+use chrono::{DateTime, Utc};
+use indexmap::IndexSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScalarType {
+    Counter,
+    Gauge,
+    /// The value is the number of elements of the set.
+    Set,
+}
+
+/// Batch of metrics where the ones with a single value are held in columns,
+/// one entry per metric, and the others are kept as they are.
+#[derive(Debug, Default)]
+pub struct MetricColumns {
+    dictionary: IndexSet<String>,
+    names: Vec<usize>,
+    namespaces: Vec<Option<usize>>,
+    /// The tags of the metric `i` are `tags[tag_offsets[i]..tag_offsets[i + 1]]`,
+    /// sorted by key.
+    tag_offsets: Vec<usize>,
+    tags: Vec<(usize, usize)>,
+    kinds: Vec<MetricKind>,
+    types: Vec<ScalarType>,
+    values: Vec<f64>,
+    timestamps: Vec<Option<DateTime<Utc>>>,
+    others: Vec<Metric>,
+}
+
+impl MetricColumns {
+    fn intern(&mut self, value: String) -> usize {
+        match self.dictionary.get_index_of(value.as_str()) {
+            Some(index) => index,
+            None => self.dictionary.insert_full(value).0,
+        }
+    }
+
+    fn push(&mut self, metric: Metric) {
+        let (r#type, value) = match &metric.value {
+            MetricValue::Counter { value } => (ScalarType::Counter, *value),
+            MetricValue::Gauge { value } => (ScalarType::Gauge, *value),
+            MetricValue::Set { values } => (ScalarType::Set, values.len() as f64),
+            _ => {
+                self.others.push(metric);
+                return;
+            }
+        };
+
+        let name = self.intern(metric.name);
+        self.names.push(name);
+        let namespace = metric.namespace.map(|namespace| self.intern(namespace));
+        self.namespaces.push(namespace);
+        for (key, value) in metric.tags.into_iter().flatten() {
+            let tag = (self.intern(key), self.intern(value));
+            self.tags.push(tag);
+        }
+        self.tag_offsets.push(self.tags.len());
+        self.kinds.push(metric.kind);
+        self.types.push(r#type);
+        self.values.push(value);
+        self.timestamps.push(metric.timestamp);
+    }
+
+    /// The number of metrics held in columns.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = ScalarRow<'_>> {
+        (0..self.len()).map(move |index| ScalarRow {
+            columns: self,
+            index,
+        })
+    }
+
+    /// The metrics which don't have a single value, such as histograms.
+    pub fn others(&self) -> &[Metric] {
+        &self.others
+    }
+
+    pub fn into_others(self) -> Vec<Metric> {
+        self.others
+    }
+}
+
+impl From<Vec<Metric>> for MetricColumns {
+    fn from(metrics: Vec<Metric>) -> Self {
+        let mut columns = Self {
+            tag_offsets: vec![0],
+            ..Default::default()
+        };
+        for metric in metrics {
+            columns.push(metric);
+        }
+        columns
+    }
+}
+
+/// View of a single metric held in columns.
+#[derive(Clone, Copy)]
+pub struct ScalarRow<'a> {
+    columns: &'a MetricColumns,
+    index: usize,
+}
+
+impl<'a> ScalarRow<'a> {
+    fn string(&self, id: usize) -> &'a str {
+        &self.columns.dictionary[id]
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.string(self.columns.names[self.index])
+    }
+
+    pub fn namespace(&self) -> Option<&'a str> {
+        self.columns.namespaces[self.index].map(|id| self.string(id))
+    }
+
+    /// The tags, sorted by key.
+    pub fn tags(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        let columns = self.columns;
+        let range = columns.tag_offsets[self.index]..columns.tag_offsets[self.index + 1];
+        columns.tags[range].iter().map(move |&(key, value)| {
+            (
+                columns.dictionary[key].as_str(),
+                columns.dictionary[value].as_str(),
+            )
+        })
+    }
+
+    pub fn kind(&self) -> &'a MetricKind {
+        &self.columns.kinds[self.index]
+    }
+
+    pub fn r#type(&self) -> ScalarType {
+        self.columns.types[self.index]
+    }
+
+    pub fn value(&self) -> f64 {
+        self.columns.values[self.index]
+    }
+
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.columns.timestamps[self.index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::metric::StatisticKind;
+
+    fn metric(name: &str, host: &str, value: MetricValue) -> Metric {
+        Metric {
+            name: name.into(),
+            namespace: Some("vector".into()),
+            timestamp: None,
+            tags: Some(
+                vec![
+                    ("host".to_owned(), host.to_owned()),
+                    ("region".to_owned(), "eu".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            kind: MetricKind::Absolute,
+            value,
+        }
+    }
+
+    #[test]
+    fn splits_scalars_into_columns() {
+        let distribution = metric(
+            "latency",
+            "a",
+            MetricValue::Distribution {
+                values: vec![1.0],
+                sample_rates: vec![1],
+                statistic: StatisticKind::Histogram,
+            },
+        );
+        let columns = MetricColumns::from(vec![
+            metric("requests", "a", MetricValue::Counter { value: 1.0 }),
+            distribution.clone(),
+            metric("requests", "b", MetricValue::Gauge { value: 2.0 }),
+            metric(
+                "users",
+                "b",
+                MetricValue::Set {
+                    values: vec!["alice".to_owned(), "bob".to_owned()]
+                        .into_iter()
+                        .collect(),
+                },
+            ),
+        ]);
+
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns.others(), &[distribution]);
+        // "requests", "vector", "host", "a", "region", "eu", "b", "users"
+        assert_eq!(columns.dictionary.len(), 8);
+
+        let rows = columns
+            .rows()
+            .map(|row| {
+                (
+                    row.namespace(),
+                    row.name(),
+                    row.tags().collect::<Vec<_>>(),
+                    row.r#type(),
+                    row.value(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    Some("vector"),
+                    "requests",
+                    vec![("host", "a"), ("region", "eu")],
+                    ScalarType::Counter,
+                    1.0
+                ),
+                (
+                    Some("vector"),
+                    "requests",
+                    vec![("host", "b"), ("region", "eu")],
+                    ScalarType::Gauge,
+                    2.0
+                ),
+                (
+                    Some("vector"),
+                    "users",
+                    vec![("host", "b"), ("region", "eu")],
+                    ScalarType::Set,
+                    2.0
+                ),
+            ]
+        );
+    }
+}
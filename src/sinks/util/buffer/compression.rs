@@ -1,18 +1,29 @@
+use flate2::write::GzEncoder;
 use serde::{de, ser};
 use serde_json::Value;
-use std::fmt;
+#[cfg(feature = "rusoto_core")]
+use snafu::Snafu;
+use std::{fmt, io::Write};
 
 pub const GZIP_NONE: usize = 0;
 pub const GZIP_FAST: usize = 1;
 pub const GZIP_DEFAULT: usize = 6;
 pub const GZIP_BEST: usize = 9;
 
+const ALGORITHMS: &[&str] = &["none", "gzip", "snappy", "snappy_framed", "lz4"];
+
 #[derive(Debug, Derivative, Copy, Clone, Eq, PartialEq)]
 #[derivative(Default)]
 pub enum Compression {
     #[derivative(Default)]
     None,
     Gzip(Option<usize>),
+    /// Snappy without framing, as a single block.
+    Snappy,
+    /// Snappy in the framing format, which can be streamed.
+    SnappyFramed,
+    /// LZ4 in the frame format.
+    Lz4,
 }
 
 impl Compression {
@@ -24,6 +35,9 @@ impl Compression {
         match self {
             Self::None => None,
             Self::Gzip(_) => Some("gzip"),
+            Self::Snappy => Some("snappy"),
+            Self::SnappyFramed => Some("x-snappy-framed"),
+            Self::Lz4 => Some("lz4"),
         }
     }
 
@@ -31,27 +45,78 @@ impl Compression {
         match self {
             Self::None => "log",
             Self::Gzip(_) => "log.gz",
+            Self::Snappy => "log.snappy",
+            Self::SnappyFramed => "log.sz",
+            Self::Lz4 => "log.lz4",
+        }
+    }
+
+    /// Compresses the whole body of a request, with gzip at `GZIP_DEFAULT`
+    /// unless its level is set.
+    pub fn compress(&self, body: &[u8]) -> Vec<u8> {
+        match self {
+            Self::None => body.to_vec(),
+            Self::Gzip(level) => {
+                let level = level.unwrap_or(GZIP_DEFAULT) as u32;
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::new(level));
+                encoder.write_all(body).expect("Writing to Vec can't fail");
+                encoder.finish().expect("Writing to Vec can't fail")
+            }
+            Self::Snappy => snap::raw::Encoder::new()
+                .compress_vec(body)
+                .expect("Out of memory"),
+            Self::SnappyFramed => {
+                let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+                encoder.write_all(body).expect("Writing to Vec can't fail");
+                encoder.into_inner().expect("Writing to Vec can't fail")
+            }
+            Self::Lz4 => {
+                let mut encoder = lz4::EncoderBuilder::new()
+                    .build(Vec::new())
+                    .expect("Writing to Vec can't fail");
+                encoder.write_all(body).expect("Writing to Vec can't fail");
+                let (body, result) = encoder.finish();
+                result.expect("Writing to Vec can't fail");
+                body
+            }
         }
     }
 }
+
 impl fmt::Display for Compression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Compression::None => write!(f, "none"),
             Compression::Gzip(ref level) => write!(f, "gzip({})", level.unwrap_or(GZIP_DEFAULT)),
+            Compression::Snappy => write!(f, "snappy"),
+            Compression::SnappyFramed => write!(f, "snappy_framed"),
+            Compression::Lz4 => write!(f, "lz4"),
         }
     }
 }
 
 #[cfg(feature = "rusoto_core")]
-impl From<Compression> for rusoto_core::encoding::ContentEncoding {
-    fn from(compression: Compression) -> Self {
+#[derive(Debug, Snafu)]
+pub enum ContentEncodingError {
+    #[snafu(display("AWS only accepts gzip compressed requests, not {}.", compression))]
+    UnsupportedCompression { compression: Compression },
+}
+
+#[cfg(feature = "rusoto_core")]
+impl std::convert::TryFrom<Compression> for rusoto_core::encoding::ContentEncoding {
+    type Error = ContentEncodingError;
+
+    fn try_from(compression: Compression) -> Result<Self, Self::Error> {
         match compression {
-            Compression::None => rusoto_core::encoding::ContentEncoding::Identity,
+            Compression::None => Ok(rusoto_core::encoding::ContentEncoding::Identity),
             Compression::Gzip(level) => {
                 let level = level.unwrap_or(GZIP_DEFAULT);
-                rusoto_core::encoding::ContentEncoding::Gzip(None, level as u32)
+                Ok(rusoto_core::encoding::ContentEncoding::Gzip(
+                    None,
+                    level as u32,
+                ))
             }
+            compression => Err(ContentEncodingError::UnsupportedCompression { compression }),
         }
     }
 }
@@ -77,9 +142,12 @@ impl<'de> de::Deserialize<'de> for Compression {
                 match s {
                     "none" => Ok(Compression::None),
                     "gzip" => Ok(Compression::gzip_default()),
+                    "snappy" => Ok(Compression::Snappy),
+                    "snappy_framed" => Ok(Compression::SnappyFramed),
+                    "lz4" => Ok(Compression::Lz4),
                     _ => Err(de::Error::invalid_value(
                         de::Unexpected::Str(s),
-                        &r#""none" or "gzip""#,
+                        &r#""none", "gzip", "snappy", "snappy_framed" or "lz4""#,
                     )),
                 }
             }
@@ -143,7 +211,13 @@ impl<'de> de::Deserialize<'de> for Compression {
                         None => Ok(Compression::None),
                     },
                     "gzip" => Ok(Compression::Gzip(level)),
-                    algorithm => Err(de::Error::unknown_variant(algorithm, &["none", "gzip"])),
+                    algorithm @ "snappy" | algorithm @ "snappy_framed" | algorithm @ "lz4" => {
+                        match level {
+                            Some(_) => Err(de::Error::unknown_field("level", &[])),
+                            None => self.visit_str(algorithm),
+                        }
+                    }
+                    algorithm => Err(de::Error::unknown_variant(algorithm, ALGORITHMS)),
                 }
             }
         }
@@ -172,6 +246,9 @@ impl ser::Serialize for Compression {
                     level => map.serialize_entry("level", &level)?,
                 };
             }
+            Compression::Snappy => map.serialize_entry("algorithm", "snappy")?,
+            Compression::SnappyFramed => map.serialize_entry("algorithm", "snappy_framed")?,
+            Compression::Lz4 => map.serialize_entry("algorithm", "lz4")?,
         };
         map.end()
     }
@@ -195,6 +272,12 @@ mod test {
                 r#"{"algorithm": "gzip", "level": 8}"#,
                 Compression::Gzip(Some(8)),
             ),
+            (r#""snappy""#, Compression::Snappy),
+            (
+                r#"{"algorithm": "snappy_framed"}"#,
+                Compression::SnappyFramed,
+            ),
+            (r#"{"algorithm": "lz4"}"#, Compression::Lz4),
         ];
         for (sources, result) in fixtures_valid.iter() {
             let deserialized: Result<Compression, _> = serde_json::from_str(sources);
@@ -208,16 +291,20 @@ mod test {
             ),
             (
                 r#""b42""#,
-                r#"invalid value: string "b42", expected "none" or "gzip" at line 1 column 5"#,
+                r#"invalid value: string "b42", expected "none", "gzip", "snappy", "snappy_framed" or "lz4" at line 1 column 5"#,
             ),
             (
                 r#"{"algorithm": "b42"}"#,
-                r#"unknown variant `b42`, expected `none` or `gzip` at line 1 column 20"#,
+                r#"unknown variant `b42`, expected one of `none`, `gzip`, `snappy`, `snappy_framed`, `lz4` at line 1 column 20"#,
             ),
             (
                 r#"{"algorithm": "none", "level": "default"}"#,
                 r#"unknown field `level`, there are no fields at line 1 column 41"#,
             ),
+            (
+                r#"{"algorithm": "lz4", "level": "best"}"#,
+                r#"unknown field `level`, there are no fields at line 1 column 37"#,
+            ),
             (
                 r#"{"algorithm": "gzip", "level": -1}"#,
                 r#"invalid value: -1, expected 0, 1, 2, 3, 4, 5, 6, 7, 8 or 9 at line 1 column 34"#,
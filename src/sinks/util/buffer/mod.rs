@@ -4,6 +4,7 @@ use super::batch::{
 use flate2::write::GzEncoder;
 use std::io::Write;
 
+pub mod columns;
 pub mod compression;
 pub mod json;
 pub mod loki;
@@ -27,6 +28,11 @@ pub struct Buffer {
 pub enum InnerBuffer {
     Plain(Vec<u8>),
     Gzip(GzEncoder<Vec<u8>>),
+    /// Raw snappy compresses a single block, so its input is kept until the
+    /// buffer is finished.
+    Snappy(Vec<u8>),
+    SnappyFramed(snap::write::FrameEncoder<Vec<u8>>),
+    Lz4(lz4::Encoder<Vec<u8>>),
 }
 
 impl Buffer {
@@ -41,6 +47,15 @@ impl Buffer {
                     flate2::Compression::new(level as u32),
                 ))
             }
+            Compression::Snappy => InnerBuffer::Snappy(buffer),
+            Compression::SnappyFramed => {
+                InnerBuffer::SnappyFramed(snap::write::FrameEncoder::new(buffer))
+            }
+            Compression::Lz4 => InnerBuffer::Lz4(
+                lz4::EncoderBuilder::new()
+                    .build(buffer)
+                    .expect("This can't fail because the inner writer is a Vec"),
+            ),
         };
         Self {
             inner,
@@ -54,19 +69,27 @@ impl Buffer {
     pub fn push(&mut self, input: &[u8]) {
         self.num_items += 1;
         match &mut self.inner {
-            InnerBuffer::Plain(inner) => {
+            InnerBuffer::Plain(inner) | InnerBuffer::Snappy(inner) => {
                 inner.extend_from_slice(input);
             }
             InnerBuffer::Gzip(inner) => {
                 inner.write_all(input).unwrap();
             }
+            InnerBuffer::SnappyFramed(inner) => {
+                inner.write_all(input).unwrap();
+            }
+            InnerBuffer::Lz4(inner) => {
+                inner.write_all(input).unwrap();
+            }
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match &self.inner {
-            InnerBuffer::Plain(inner) => inner.is_empty(),
+            InnerBuffer::Plain(inner) | InnerBuffer::Snappy(inner) => inner.is_empty(),
             InnerBuffer::Gzip(inner) => inner.get_ref().is_empty(),
+            // These encoders write their headers before any input.
+            InnerBuffer::SnappyFramed(_) | InnerBuffer::Lz4(_) => self.num_items == 0,
         }
     }
 }
@@ -116,6 +139,15 @@ impl Batch for Buffer {
             InnerBuffer::Gzip(inner) => inner
                 .finish()
                 .expect("This can't fail because the inner writer is a Vec"),
+            InnerBuffer::Snappy(inner) => Compression::Snappy.compress(&inner),
+            InnerBuffer::SnappyFramed(inner) => inner
+                .into_inner()
+                .expect("This can't fail because the inner writer is a Vec"),
+            InnerBuffer::Lz4(inner) => {
+                let (inner, result) = inner.finish();
+                result.expect("This can't fail because the inner writer is a Vec");
+                inner
+            }
         }
     }
 
@@ -195,4 +227,47 @@ mod test {
         .take(100_000)
         .flatten()));
     }
+
+    #[test]
+    fn snappy_and_lz4() {
+        use crate::sinks::util::{Batch, PushResult};
+
+        let input = b"It's going down, I'm yelling timber, You better move, you better dance";
+        let batch_size = BatchSettings::default().bytes(100_000).events(1_000).size;
+        let compress = |compression| {
+            let mut buffer = Buffer::new(batch_size, compression);
+            assert!(buffer.is_empty());
+            for _ in 0..100 {
+                assert!(matches!(
+                    Batch::push(&mut buffer, input.to_vec()),
+                    PushResult::Ok(false)
+                ));
+            }
+            assert!(!buffer.is_empty());
+            buffer.finish()
+        };
+        let expected = input.repeat(100);
+
+        let output = compress(Compression::Snappy);
+        assert!(output.len() < expected.len());
+        assert_eq!(
+            snap::raw::Decoder::new().decompress_vec(&output).unwrap(),
+            expected
+        );
+
+        let output = compress(Compression::SnappyFramed);
+        let mut decompressed = vec![];
+        snap::read::FrameDecoder::new(output.as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, expected);
+
+        let output = compress(Compression::Lz4);
+        let mut decompressed = vec![];
+        lz4::Decoder::new(output.as_slice())
+            .unwrap()
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, expected);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{
     event::{PathComponent, PathIter},
     sinks::util::encoding::{
-        with_default::EncodingConfigWithDefault, EncodingConfiguration, TimestampFormat,
+        with_default::EncodingConfigWithDefault, CefConfig, EncodingConfiguration, LeefConfig,
+        Schema, TimestampFormat,
     },
 };
 use serde::{
@@ -22,7 +23,11 @@ use std::{
 pub struct EncodingConfig<E> {
     pub(crate) codec: E,
     #[serde(default)]
-    pub(crate) schema: Option<String>,
+    pub(crate) schema: Option<Schema>,
+    #[serde(default)]
+    pub(crate) cef: Option<CefConfig>,
+    #[serde(default)]
+    pub(crate) leef: Option<LeefConfig>,
     // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
     #[serde(default)]
     pub(crate) only_fields: Option<Vec<Vec<PathComponent>>>,
@@ -36,9 +41,15 @@ impl<E> EncodingConfiguration<E> for EncodingConfig<E> {
     fn codec(&self) -> &E {
         &self.codec
     }
-    fn schema(&self) -> &Option<String> {
+    fn schema(&self) -> &Option<Schema> {
         &self.schema
     }
+    fn cef(&self) -> &Option<CefConfig> {
+        &self.cef
+    }
+    fn leef(&self) -> &Option<LeefConfig> {
+        &self.leef
+    }
     // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
     fn only_fields(&self) -> &Option<Vec<Vec<PathComponent>>> {
         &self.only_fields
@@ -59,6 +70,8 @@ where
         EncodingConfigWithDefault {
             codec: self.codec,
             schema: self.schema,
+            cef: self.cef,
+            leef: self.leef,
             only_fields: self.only_fields,
             except_fields: self.except_fields,
             timestamp_format: self.timestamp_format,
@@ -71,6 +84,8 @@ impl<E> From<E> for EncodingConfig<E> {
         Self {
             codec,
             schema: Default::default(),
+            cef: Default::default(),
+            leef: Default::default(),
             only_fields: Default::default(),
             except_fields: Default::default(),
             timestamp_format: Default::default(),
@@ -112,6 +127,8 @@ where
                 Ok(Self::Value {
                     codec: T::deserialize(value.into_deserializer())?,
                     schema: Default::default(),
+                    cef: Default::default(),
+                    leef: Default::default(),
                     only_fields: Default::default(),
                     except_fields: Default::default(),
                     timestamp_format: Default::default(),
@@ -135,6 +152,8 @@ where
         let concrete = Self {
             codec: inner.codec,
             schema: inner.schema,
+            cef: inner.cef,
+            leef: inner.leef,
             // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
             only_fields: inner.only_fields.map(|fields| {
                 fields
@@ -155,7 +174,11 @@ where
 pub struct Inner<E> {
     codec: E,
     #[serde(default)]
-    schema: Option<String>,
+    schema: Option<Schema>,
+    #[serde(default)]
+    cef: Option<CefConfig>,
+    #[serde(default)]
+    leef: Option<LeefConfig>,
     #[serde(default)]
     only_fields: Option<Vec<String>>,
     #[serde(default)]
@@ -31,11 +31,15 @@
 
 mod config;
 pub use config::EncodingConfig;
+mod schema;
+pub use schema::{FieldSchema, FieldType, OnError, OnNull, Schema};
+mod siem;
+pub use siem::{CefConfig, LeefConfig, LeefVersion};
 mod with_default;
 pub use with_default::EncodingConfigWithDefault;
 
 use crate::{
-    event::{PathComponent, PathIter, Value},
+    event::{LogEvent, PathComponent, PathIter, Value},
     Event, Result,
 };
 use serde::{Deserialize, Serialize};
@@ -46,7 +50,9 @@ pub trait EncodingConfiguration<E> {
     // Required Accessors
 
     fn codec(&self) -> &E;
-    fn schema(&self) -> &Option<String>;
+    fn schema(&self) -> &Option<Schema>;
+    fn cef(&self) -> &Option<CefConfig>;
+    fn leef(&self) -> &Option<LeefConfig>;
     // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
     fn only_fields(&self) -> &Option<Vec<Vec<PathComponent>>>;
     fn except_fields(&self) -> &Option<Vec<String>>;
@@ -88,6 +94,14 @@ pub trait EncodingConfiguration<E> {
             }
         }
     }
+    fn apply_schema(&self, event: &mut Event) {
+        if let Some(schema) = &self.schema() {
+            match event {
+                Event::Log(log_event) => schema::apply(schema, log_event),
+                Event::Metric(_) => (), // Metrics don't get affected by this one!
+            }
+        }
+    }
     fn apply_timestamp_format(&self, event: &mut Event) {
         if let Some(timestamp_format) = &self.timestamp_format() {
             match event {
@@ -114,12 +128,27 @@ pub trait EncodingConfiguration<E> {
         }
     }
 
+    /// Encode `log` as a CEF message, with the default headers if there are no `cef` options.
+    fn encode_cef(&self, log: &LogEvent) -> String {
+        siem::encode_cef(self.cef(), log)
+    }
+
+    /// Encode `log` as a LEEF message, with the default headers if there are no `leef` options.
+    fn encode_leef(&self, log: &LogEvent) -> String {
+        siem::encode_leef(self.leef(), log)
+    }
+
     /// Check that the configuration is valid.
     ///
     /// If an error is returned, the entire encoding configuration should be considered inoperable.
     ///
     /// For example, this checks if `except_fields` and `only_fields` items are mutually exclusive.
     fn validate(&self) -> Result<()> {
+        if let Some(schema) = &self.schema() {
+            schema::validate(schema)?;
+        }
+        siem::validate(self.cef(), self.leef())?;
+
         if let (Some(only_fields), Some(except_fields)) =
             (&self.only_fields(), &self.except_fields())
         {
@@ -139,9 +168,10 @@ pub trait EncodingConfiguration<E> {
     ///
     /// Currently, this is idempotent.
     fn apply_rules(&self, event: &mut Event) {
-        // Ordering in here should not matter.
         self.apply_except_fields(event);
         self.apply_only_fields(event);
+        // The schema may coerce fields to timestamps, which are then formatted.
+        self.apply_schema(event);
         self.apply_timestamp_format(event);
     }
 }
@@ -272,6 +302,41 @@ mod tests {
         assert!(!event.as_mut_log().contains("c[0].x"));
     }
 
+    const TOML_SCHEMA: &str = r#"
+        encoding.codec = "Snoot"
+        encoding.except_fields = ["b"]
+        encoding.timestamp_format = "unix"
+        [encoding.schema]
+        a = "integer"
+        b = "integer"
+        c = { type = "timestamp", default = "2020-12-01T00:00:00Z" }
+    "#;
+    #[test]
+    fn test_schema() {
+        let config: TestConfig = toml::from_str(TOML_SCHEMA).unwrap();
+        let mut event = Event::new_empty_log();
+        {
+            let log = event.as_mut_log();
+            log.insert("a", "1");
+            log.insert("b", "2");
+        }
+        config.encoding.apply_rules(&mut event);
+        assert_eq!(event.as_log().get("a"), Some(&Value::Integer(1)));
+        assert!(!event.as_log().contains("b"));
+        assert_eq!(event.as_log().get("c"), Some(&Value::Integer(1606780800)));
+    }
+
+    const TOML_SCHEMA_INVALID_DEFAULT: &str = r#"
+        encoding.codec = "Snoot"
+        encoding.schema.a = { type = "integer", default = "one" }
+    "#;
+    #[test]
+    fn schema_invalid_default() {
+        let config: std::result::Result<TestConfig, _> =
+            toml::from_str(TOML_SCHEMA_INVALID_DEFAULT);
+        assert!(config.is_err())
+    }
+
     const TOML_TIMESTAMP_FORMAT: &str = r#"
         encoding.codec = "Snoot"
         encoding.timestamp_format = "unix"
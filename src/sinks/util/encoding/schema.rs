@@ -0,0 +1,365 @@
+//! Coercion of the fields of log events to the types expected by the sink.
+//!
+//! Strict backends reject a whole batch for a single field of the wrong type,
+//! such as a number sent as a string, so the `schema` of an encoding maps the
+//! fields to the type they are coerced to before being encoded:
+//!
+//! ```toml
+//! [sinks.my_sink.encoding.schema]
+//! status = "integer"
+//! duration = { type = "float", on_error = "drop" }
+//! user = { type = "string", default = "anonymous" }
+//! ```
+
+use crate::event::{LogEvent, Value};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The fields of the events and the types they are coerced to.
+pub type Schema = BTreeMap<String, FieldSchema>;
+
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+}
+
+/// What is done with a value which can't be coerced to the type of its field.
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnError {
+    /// The value is replaced with a null.
+    Null,
+    /// The field is removed.
+    Drop,
+    /// The value is left as is.
+    Keep,
+}
+
+impl Default for OnError {
+    fn default() -> Self {
+        Self::Null
+    }
+}
+
+/// What is done with a null or missing field, if it has no default.
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnNull {
+    /// The field is left null, or missing.
+    Keep,
+    /// The field is removed.
+    Drop,
+}
+
+impl Default for OnNull {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "FieldSchemaDef")]
+pub struct FieldSchema {
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    pub on_error: OnError,
+    pub on_null: OnNull,
+    /// Coerced to the type of the field, as a configuration value would be.
+    pub default: Option<String>,
+}
+
+/// A field schema, or only the type of the field.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FieldSchemaDef {
+    Type(FieldType),
+    Full {
+        #[serde(rename = "type")]
+        field_type: FieldType,
+        #[serde(default)]
+        on_error: OnError,
+        #[serde(default)]
+        on_null: OnNull,
+        #[serde(default)]
+        default: Option<String>,
+    },
+}
+
+impl From<FieldSchemaDef> for FieldSchema {
+    fn from(def: FieldSchemaDef) -> Self {
+        match def {
+            FieldSchemaDef::Type(field_type) => Self {
+                field_type,
+                on_error: OnError::default(),
+                on_null: OnNull::default(),
+                default: None,
+            },
+            FieldSchemaDef::Full {
+                field_type,
+                on_error,
+                on_null,
+                default,
+            } => Self {
+                field_type,
+                on_error,
+                on_null,
+                default,
+            },
+        }
+    }
+}
+
+impl FieldSchema {
+    /// The default of the field, coerced to its type.
+    fn default_value(&self) -> Option<Result<Value, ()>> {
+        self.default
+            .as_ref()
+            .map(|default| coerce(Value::from(default.as_str()), self.field_type))
+    }
+}
+
+/// Checks that the defaults of the fields can be coerced to their type.
+pub fn validate(schema: &Schema) -> crate::Result<()> {
+    for (field, field_schema) in schema {
+        if let Some(Err(())) = field_schema.default_value() {
+            return Err(format!(
+                "The default of field {:?} is not a valid {:?}.",
+                field, field_schema.field_type
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+pub fn apply(schema: &Schema, log: &mut LogEvent) {
+    for (field, field_schema) in schema {
+        let current = log.remove(field);
+        let value = match current {
+            None | Some(Value::Null) => match field_schema.default_value() {
+                Some(Ok(default)) => default,
+                _ => {
+                    if current.is_some() && field_schema.on_null == OnNull::Keep {
+                        log.insert(field, Value::Null);
+                    }
+                    continue;
+                }
+            },
+            Some(value) => value,
+        };
+        match coerce(value.clone(), field_schema.field_type) {
+            Ok(value) => {
+                log.insert(field, value);
+            }
+            Err(()) => {
+                warn!(
+                    message = "Failed to coerce field.",
+                    %field,
+                    kind = %value.kind(),
+                    target = ?field_schema.field_type,
+                    rate_limit_secs = 30
+                );
+                match field_schema.on_error {
+                    OnError::Null => {
+                        log.insert(field, Value::Null);
+                    }
+                    OnError::Drop => (),
+                    OnError::Keep => {
+                        log.insert(field, value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn coerce(value: Value, field_type: FieldType) -> Result<Value, ()> {
+    match (field_type, value) {
+        (FieldType::String, Value::Bytes(bytes)) => Ok(Value::Bytes(bytes)),
+        (FieldType::String, value) => Ok(Value::from(value.to_string_lossy())),
+
+        (FieldType::Integer, Value::Integer(int)) => Ok(Value::Integer(int)),
+        (FieldType::Integer, Value::Float(float)) => float_to_integer(float),
+        (FieldType::Integer, Value::Boolean(boolean)) => Ok(Value::Integer(boolean as i64)),
+        (FieldType::Integer, Value::Timestamp(timestamp)) => {
+            Ok(Value::Integer(timestamp.timestamp()))
+        }
+        (FieldType::Integer, Value::Bytes(bytes)) => {
+            let string = String::from_utf8_lossy(&bytes);
+            let string = string.trim();
+            match string.parse::<i64>() {
+                Ok(int) => Ok(Value::Integer(int)),
+                Err(_) => string
+                    .parse::<f64>()
+                    .map_err(|_| ())
+                    .and_then(float_to_integer),
+            }
+        }
+
+        (FieldType::Float, Value::Float(float)) => Ok(Value::Float(float)),
+        (FieldType::Float, Value::Integer(int)) => Ok(Value::Float(int as f64)),
+        (FieldType::Float, Value::Boolean(boolean)) => Ok(Value::Float(boolean as i64 as f64)),
+        (FieldType::Float, Value::Bytes(bytes)) => String::from_utf8_lossy(&bytes)
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|float| float.is_finite())
+            .map(Value::Float)
+            .ok_or(()),
+
+        (FieldType::Boolean, Value::Boolean(boolean)) => Ok(Value::Boolean(boolean)),
+        (FieldType::Boolean, Value::Integer(0)) => Ok(Value::Boolean(false)),
+        (FieldType::Boolean, Value::Integer(1)) => Ok(Value::Boolean(true)),
+        (FieldType::Boolean, Value::Bytes(bytes)) => {
+            match String::from_utf8_lossy(&bytes)
+                .trim()
+                .to_lowercase()
+                .as_str()
+            {
+                "true" | "t" | "yes" | "y" | "on" | "1" => Ok(Value::Boolean(true)),
+                "false" | "f" | "no" | "n" | "off" | "0" => Ok(Value::Boolean(false)),
+                _ => Err(()),
+            }
+        }
+
+        (FieldType::Timestamp, Value::Timestamp(timestamp)) => Ok(Value::Timestamp(timestamp)),
+        (FieldType::Timestamp, Value::Integer(seconds)) => Utc
+            .timestamp_opt(seconds, 0)
+            .single()
+            .map(Value::Timestamp)
+            .ok_or(()),
+        (FieldType::Timestamp, Value::Float(seconds)) if seconds.is_finite() => Utc
+            .timestamp_opt(
+                seconds.floor() as i64,
+                ((seconds - seconds.floor()) * 1e9) as u32,
+            )
+            .single()
+            .map(Value::Timestamp)
+            .ok_or(()),
+        (FieldType::Timestamp, Value::Bytes(bytes)) => {
+            DateTime::parse_from_rfc3339(String::from_utf8_lossy(&bytes).trim())
+                .map(|timestamp| Value::Timestamp(timestamp.with_timezone(&Utc)))
+                .map_err(|_| ())
+        }
+
+        _ => Err(()),
+    }
+}
+
+fn float_to_integer(float: f64) -> Result<Value, ()> {
+    if float.fract() == 0.0 && float >= i64::MIN as f64 && float < i64::MAX as f64 {
+        Ok(Value::Integer(float as i64))
+    } else {
+        Err(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(toml: &str) -> Schema {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn parses_short_and_full_forms() {
+        let schema = schema(
+            r#"
+            status = "integer"
+            duration = { type = "float", on_error = "drop", on_null = "drop" }
+            "#,
+        );
+        assert_eq!(schema["status"].field_type, FieldType::Integer);
+        assert_eq!(schema["status"].on_error, OnError::Null);
+        assert_eq!(schema["duration"].field_type, FieldType::Float);
+        assert_eq!(schema["duration"].on_error, OnError::Drop);
+        assert_eq!(schema["duration"].on_null, OnNull::Drop);
+    }
+
+    #[test]
+    fn coerces_fields() {
+        let schema = schema(
+            r#"
+            status = "integer"
+            ratio = "float"
+            ok = "boolean"
+            code = "string"
+            at = "timestamp"
+            "a.b" = "integer"
+            "#,
+        );
+        let mut log = LogEvent::default();
+        log.insert("status", " 200 ");
+        log.insert("ratio", 1);
+        log.insert("ok", "yes");
+        log.insert("code", 42);
+        log.insert("at", "2020-12-01T10:20:30Z");
+        log.insert("a.b", 3.0);
+
+        apply(&schema, &mut log);
+
+        assert_eq!(log.get("status"), Some(&Value::Integer(200)));
+        assert_eq!(log.get("ratio"), Some(&Value::Float(1.0)));
+        assert_eq!(log.get("ok"), Some(&Value::Boolean(true)));
+        assert_eq!(log.get("code"), Some(&Value::from("42")));
+        assert_eq!(
+            log.get("at"),
+            Some(&Value::Timestamp(Utc.ymd(2020, 12, 1).and_hms(10, 20, 30)))
+        );
+        assert_eq!(log.get("a.b"), Some(&Value::Integer(3)));
+    }
+
+    #[test]
+    fn handles_errors() {
+        let schema = schema(
+            r#"
+            nulled = "integer"
+            dropped = { type = "integer", on_error = "drop" }
+            kept = { type = "integer", on_error = "keep" }
+            "#,
+        );
+        let mut log = LogEvent::default();
+        log.insert("nulled", "abc");
+        log.insert("dropped", 1.5);
+        log.insert("kept", "abc");
+
+        apply(&schema, &mut log);
+
+        assert_eq!(log.get("nulled"), Some(&Value::Null));
+        assert!(!log.contains("dropped"));
+        assert_eq!(log.get("kept"), Some(&Value::from("abc")));
+    }
+
+    #[test]
+    fn handles_nulls() {
+        let schema = schema(
+            r#"
+            kept = "integer"
+            dropped = { type = "integer", on_null = "drop" }
+            defaulted = { type = "integer", default = "0" }
+            "#,
+        );
+        let mut log = LogEvent::default();
+        log.insert("kept", Value::Null);
+        log.insert("dropped", Value::Null);
+
+        apply(&schema, &mut log);
+
+        assert_eq!(log.get("kept"), Some(&Value::Null));
+        assert!(!log.contains("dropped"));
+        assert_eq!(log.get("defaulted"), Some(&Value::Integer(0)));
+    }
+
+    #[test]
+    fn rejects_invalid_defaults() {
+        let schema = schema(r#"status = { type = "integer", default = "none" }"#);
+        assert!(validate(&schema).is_err());
+    }
+}
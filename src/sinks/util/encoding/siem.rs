@@ -0,0 +1,410 @@
+//! Encoding of log events to the CEF and LEEF formats, which ArcSight and
+//! QRadar require on the wire.
+//!
+//! The headers are configured along the codec, and the extensions, or
+//! attributes, map their keys to the fields of the events:
+//!
+//! ```toml
+//! [sinks.my_sink.encoding]
+//! codec = "cef"
+//! cef.device_vendor = "Acme"
+//! cef.device_product = "Gateway"
+//! cef.device_version = "2.1"
+//! cef.extensions = { src = "client.ip", act = "action", msg = "message" }
+//! ```
+
+use crate::{
+    config::log_schema,
+    event::{LogEvent, Value},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+lazy_static::lazy_static! {
+    static ref DEFAULT_CEF: CefConfig = CefConfig::default();
+    static ref DEFAULT_LEEF: LeefConfig = LeefConfig::default();
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CefConfig {
+    #[serde(default = "default_vendor")]
+    pub device_vendor: String,
+    #[serde(default = "default_product")]
+    pub device_product: String,
+    #[serde(default = "default_version")]
+    pub device_version: String,
+    /// The field holding the Device Event Class ID.
+    #[serde(default = "default_event_id_field")]
+    pub device_event_class_id_field: String,
+    /// The field holding the name of the event, the message by default.
+    #[serde(default)]
+    pub name_field: Option<String>,
+    #[serde(default = "default_severity_field")]
+    pub severity_field: String,
+    /// The extension keys and the fields holding their values. By default,
+    /// the message is written as `msg`.
+    #[serde(default)]
+    pub extensions: BTreeMap<String, String>,
+}
+
+impl Default for CefConfig {
+    fn default() -> Self {
+        Self {
+            device_vendor: default_vendor(),
+            device_product: default_product(),
+            device_version: default_version(),
+            device_event_class_id_field: default_event_id_field(),
+            name_field: None,
+            severity_field: default_severity_field(),
+            extensions: BTreeMap::new(),
+        }
+    }
+}
+
+impl CefConfig {
+    /// Encode `log` as `CEF:0|vendor|product|version|class id|name|severity|extensions`.
+    pub fn encode(&self, log: &LogEvent) -> String {
+        let name = log.get(
+            self.name_field
+                .as_deref()
+                .unwrap_or_else(|| log_schema().message_key()),
+        );
+        let mut message = format!(
+            "CEF:0|{}|{}|{}|{}|{}|{}|",
+            escape_header(&self.device_vendor),
+            escape_header(&self.device_product),
+            escape_header(&self.device_version),
+            header_field(log.get(&self.device_event_class_id_field), "-"),
+            header_field(name, "-"),
+            header_field(log.get(&self.severity_field), "Unknown"),
+        );
+
+        let extensions = mapped_fields(log, &self.extensions)
+            .map(|(key, value)| format!("{}={}", key, escape_cef_extension(value)))
+            .collect::<Vec<_>>();
+        message.push_str(&extensions.join(" "));
+        message
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeefVersion {
+    #[serde(rename = "1.0")]
+    V1,
+    #[serde(rename = "2.0")]
+    V2,
+}
+
+impl Default for LeefVersion {
+    fn default() -> Self {
+        Self::V2
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LeefConfig {
+    #[serde(default)]
+    pub version: LeefVersion,
+    #[serde(default = "default_vendor")]
+    pub vendor: String,
+    #[serde(default = "default_product")]
+    pub product: String,
+    #[serde(default = "default_version")]
+    pub product_version: String,
+    /// The field holding the EventID.
+    #[serde(default = "default_event_id_field")]
+    pub event_id_field: String,
+    /// The attribute delimiter, which LEEF 1.0 fixes to a tab.
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// The attribute keys and the fields holding their values. By default,
+    /// the message is written as `msg`.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl Default for LeefConfig {
+    fn default() -> Self {
+        Self {
+            version: LeefVersion::default(),
+            vendor: default_vendor(),
+            product: default_product(),
+            product_version: default_version(),
+            event_id_field: default_event_id_field(),
+            delimiter: default_delimiter(),
+            attributes: BTreeMap::new(),
+        }
+    }
+}
+
+impl LeefConfig {
+    /// Encode `log` as `LEEF:2.0|vendor|product|version|event id|delimiter|attributes`,
+    /// or without the delimiter for LEEF 1.0.
+    pub fn encode(&self, log: &LogEvent) -> String {
+        let (version, delimiter) = match self.version {
+            LeefVersion::V1 => ("1.0", '\t'),
+            LeefVersion::V2 => ("2.0", self.delimiter),
+        };
+        let mut message = format!(
+            "LEEF:{}|{}|{}|{}|{}|",
+            version,
+            escape_header(&self.vendor),
+            escape_header(&self.product),
+            escape_header(&self.product_version),
+            header_field(log.get(&self.event_id_field), "-"),
+        );
+        if self.version == LeefVersion::V2 {
+            if delimiter.is_ascii_graphic() {
+                message.push(delimiter);
+            } else {
+                message.push_str(&format!("x{:02X}", delimiter as u32));
+            }
+            message.push('|');
+        }
+
+        let attributes = mapped_fields(log, &self.attributes)
+            .map(|(key, value)| {
+                let value = value
+                    .to_string_lossy()
+                    .replace(|c: char| c == delimiter || c == '\n' || c == '\r', " ");
+                format!("{}={}", key, value)
+            })
+            .collect::<Vec<_>>();
+        message.push_str(&attributes.join(&delimiter.to_string()));
+        message
+    }
+}
+
+pub(super) fn encode_cef(config: &Option<CefConfig>, log: &LogEvent) -> String {
+    config.as_ref().unwrap_or(&DEFAULT_CEF).encode(log)
+}
+
+pub(super) fn encode_leef(config: &Option<LeefConfig>, log: &LogEvent) -> String {
+    config.as_ref().unwrap_or(&DEFAULT_LEEF).encode(log)
+}
+
+pub(super) fn validate(cef: &Option<CefConfig>, leef: &Option<LeefConfig>) -> Result<()> {
+    if let Some(cef) = cef {
+        validate_keys(cef.extensions.keys(), "cef.extensions")?;
+    }
+    if let Some(leef) = leef {
+        validate_keys(leef.attributes.keys(), "leef.attributes")?;
+        if !leef.delimiter.is_ascii() || leef.delimiter == '=' {
+            return Err(format!(
+                "The LEEF delimiter must be an ASCII character other than `=`, found {:?}.",
+                leef.delimiter
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn validate_keys<'a>(keys: impl Iterator<Item = &'a String>, option: &str) -> Result<()> {
+    for key in keys {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!(
+                "The keys of `{}` must be alphanumeric, found {:?}.",
+                option, key
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// The keys mapped to the fields present in `log`, or the message as `msg`
+/// if there is no mapping.
+fn mapped_fields<'a>(
+    log: &'a LogEvent,
+    mapping: &'a BTreeMap<String, String>,
+) -> Box<dyn Iterator<Item = (&'a str, &'a Value)> + 'a> {
+    if mapping.is_empty() {
+        Box::new(
+            log.get(log_schema().message_key())
+                .map(|value| ("msg", value))
+                .into_iter(),
+        )
+    } else {
+        Box::new(
+            mapping
+                .iter()
+                .filter_map(move |(key, field)| Some((key.as_str(), log.get(field)?))),
+        )
+    }
+}
+
+fn header_field(value: Option<&Value>, missing: &str) -> String {
+    match value {
+        Some(value) => escape_header(&value.to_string_lossy()),
+        None => missing.to_owned(),
+    }
+}
+
+/// Pipes and backslashes are escaped in the headers, which can't span lines.
+fn escape_header(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Equal signs and backslashes are escaped in the extension values, and
+/// line breaks written as `\n` and `\r`.
+fn escape_cef_extension(value: &Value) -> String {
+    let value = value.to_string_lossy();
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '=' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn default_vendor() -> String {
+    "Vector".to_owned()
+}
+
+fn default_product() -> String {
+    "Vector".to_owned()
+}
+
+fn default_version() -> String {
+    crate::vector_version().to_string()
+}
+
+fn default_event_id_field() -> String {
+    "event_id".to_owned()
+}
+
+fn default_severity_field() -> String {
+    "severity".to_owned()
+}
+
+const fn default_delimiter() -> char {
+    '\t'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+
+    fn config<T: serde::de::DeserializeOwned>(toml: &str) -> T {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn event() -> Event {
+        let mut event = Event::from("Worm\nstopped");
+        let log = event.as_mut_log();
+        log.insert("event_id", 4000);
+        log.insert("severity", 10);
+        log.insert("client.ip", "10.0.0.1");
+        log.insert("request", "/a?b=c\\d");
+        log.insert("action", "blocked|logged");
+        event
+    }
+
+    #[test]
+    fn encodes_cef() {
+        let cef = config::<CefConfig>(
+            r#"
+            device_vendor = "Security|Co"
+            device_product = "Threat Manager"
+            device_version = "1.0"
+            extensions = { src = "client.ip", request = "request", act = "action", missing = "missing" }
+            "#,
+        );
+
+        assert_eq!(
+            cef.encode(event().as_log()),
+            concat!(
+                r"CEF:0|Security\|Co|Threat Manager|1.0|4000|Worm stopped|10|",
+                r"act=blocked|logged request=/a?b\=c\\d src=10.0.0.1"
+            )
+        );
+    }
+
+    #[test]
+    fn encodes_cef_defaults() {
+        let event = Event::from("line\nbreak");
+        let cef = CefConfig {
+            device_version: "1.0".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            cef.encode(event.as_log()),
+            r"CEF:0|Vector|Vector|1.0|-|line break|Unknown|msg=line\nbreak"
+        );
+    }
+
+    #[test]
+    fn encodes_leef() {
+        let leef = config::<LeefConfig>(
+            r#"
+            vendor = "Lancope"
+            product = "StealthWatch"
+            product_version = "1.0"
+            delimiter = "^"
+            attributes = { src = "client.ip", act = "action", msg = "message" }
+            "#,
+        );
+
+        assert_eq!(
+            leef.encode(event().as_log()),
+            r"LEEF:2.0|Lancope|StealthWatch|1.0|4000|^|act=blocked|logged^msg=Worm stopped^src=10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn encodes_leef_tabs() {
+        let mut leef = config::<LeefConfig>(
+            r#"
+            product_version = "1.0"
+            attributes = { src = "client.ip", act = "action" }
+            "#,
+        );
+
+        assert_eq!(
+            leef.encode(event().as_log()),
+            "LEEF:2.0|Vector|Vector|1.0|4000|x09|act=blocked|logged\tsrc=10.0.0.1"
+        );
+
+        leef.version = LeefVersion::V1;
+        assert_eq!(
+            leef.encode(event().as_log()),
+            "LEEF:1.0|Vector|Vector|1.0|4000|act=blocked|logged\tsrc=10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn validates_keys() {
+        let cef = config::<CefConfig>(r#"extensions = { "src ip" = "client.ip" }"#);
+        assert!(validate(&Some(cef), &None).is_err());
+
+        let leef = config::<LeefConfig>(r#"delimiter = "=""#);
+        assert!(validate(&None, &Some(leef)).is_err());
+
+        let leef = config::<LeefConfig>(r#"attributes = { src_ip = "client.ip" }"#);
+        assert!(validate(&None, &Some(leef)).is_ok());
+    }
+}
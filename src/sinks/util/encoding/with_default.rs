@@ -1,6 +1,8 @@
 use crate::{
     event::{PathComponent, PathIter},
-    sinks::util::encoding::{EncodingConfig, EncodingConfiguration, TimestampFormat},
+    sinks::util::encoding::{
+        CefConfig, EncodingConfig, EncodingConfiguration, LeefConfig, Schema, TimestampFormat,
+    },
 };
 use serde::{
     de::{self, DeserializeOwned, Deserializer, IntoDeserializer, MapAccess, Visitor},
@@ -28,7 +30,17 @@ pub struct EncodingConfigWithDefault<E: Default + PartialEq> {
         default,
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
-    pub(crate) schema: Option<String>,
+    pub(crate) schema: Option<Schema>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub(crate) cef: Option<CefConfig>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub(crate) leef: Option<LeefConfig>,
     /// Keep only the following fields of the message. (Items mutually exclusive with `except_fields`)
     #[serde(
         default,
@@ -54,9 +66,15 @@ impl<E: Default + PartialEq> EncodingConfiguration<E> for EncodingConfigWithDefa
     fn codec(&self) -> &E {
         &self.codec
     }
-    fn schema(&self) -> &Option<String> {
+    fn schema(&self) -> &Option<Schema> {
         &self.schema
     }
+    fn cef(&self) -> &Option<CefConfig> {
+        &self.cef
+    }
+    fn leef(&self) -> &Option<LeefConfig> {
+        &self.leef
+    }
     // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
     fn only_fields(&self) -> &Option<Vec<Vec<PathComponent>>> {
         &self.only_fields
@@ -81,6 +99,8 @@ where
         EncodingConfigWithDefault {
             codec: self.codec.into(),
             schema: self.schema,
+            cef: self.cef,
+            leef: self.leef,
             only_fields: self.only_fields,
             except_fields: self.except_fields,
             timestamp_format: self.timestamp_format,
@@ -94,6 +114,8 @@ where
         EncodingConfig {
             codec: self.codec.into(),
             schema: self.schema,
+            cef: self.cef,
+            leef: self.leef,
             only_fields: self.only_fields,
             except_fields: self.except_fields,
             timestamp_format: self.timestamp_format,
@@ -109,6 +131,8 @@ where
         let Self {
             codec,
             schema,
+            cef,
+            leef,
             only_fields,
             except_fields,
             timestamp_format,
@@ -116,6 +140,8 @@ where
         EncodingConfig {
             codec,
             schema,
+            cef,
+            leef,
             only_fields,
             except_fields,
             timestamp_format,
@@ -128,6 +154,8 @@ impl<E: Default + PartialEq> From<E> for EncodingConfigWithDefault<E> {
         Self {
             codec,
             schema: Default::default(),
+            cef: Default::default(),
+            leef: Default::default(),
             only_fields: Default::default(),
             except_fields: Default::default(),
             timestamp_format: Default::default(),
@@ -171,6 +199,8 @@ where
                 Ok(Self::Value {
                     codec: T::deserialize(value.into_deserializer())?,
                     schema: Default::default(),
+                    cef: Default::default(),
+                    leef: Default::default(),
                     only_fields: Default::default(),
                     except_fields: Default::default(),
                     timestamp_format: Default::default(),
@@ -194,6 +224,8 @@ where
         let concrete = Self {
             codec: inner.codec,
             schema: inner.schema,
+            cef: inner.cef,
+            leef: inner.leef,
             // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
             only_fields: inner.only_fields.map(|fields| {
                 fields
@@ -215,7 +247,11 @@ pub struct InnerWithDefault<E: Default> {
     #[serde(default)]
     codec: E,
     #[serde(default)]
-    schema: Option<String>,
+    schema: Option<Schema>,
+    #[serde(default)]
+    cef: Option<CefConfig>,
+    #[serde(default)]
+    leef: Option<LeefConfig>,
     #[serde(default)]
     only_fields: Option<Vec<String>>,
     #[serde(default)]
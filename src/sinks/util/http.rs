@@ -1,8 +1,13 @@
 use super::{
+    batch::{BatchConfig, BatchError, BatchSettings, PushResult},
     retries::{RetryAction, RetryLogic},
     sink, Batch, Partition, TowerBatchedSink, TowerPartitionSink, TowerRequestSettings,
 };
-use crate::{buffers::Acker, http::HttpClient, Event};
+use crate::{
+    buffers::Acker,
+    http::{HttpClient, TraceContext},
+    Event,
+};
 use bytes::{Buf, Bytes};
 use futures::{future::BoxFuture, ready, Sink};
 use http::StatusCode;
@@ -26,6 +31,99 @@ pub trait HttpSink: Send + Sync + 'static {
 
     fn encode_event(&self, event: Event) -> Option<Self::Input>;
     async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>>;
+
+    /// The field of the events holding the trace context sent along with
+    /// their requests, if any.
+    fn trace_context_key(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// A batch item or output, with the trace context it's sent with.
+pub type Traced<T> = (T, Option<TraceContext>);
+
+/// A batch keeping the trace context of its first event carrying one, as a
+/// request can only be part of a single trace.
+#[derive(Clone, Debug)]
+pub struct TraceContextBatch<B> {
+    inner: B,
+    trace_context: Option<TraceContext>,
+}
+
+impl<B> From<B> for TraceContextBatch<B> {
+    fn from(inner: B) -> Self {
+        Self {
+            inner,
+            trace_context: None,
+        }
+    }
+}
+
+impl<B: Batch> Batch for TraceContextBatch<B> {
+    type Input = Traced<B::Input>;
+    type Output = Traced<B::Output>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        Ok(B::get_settings_defaults(config, defaults.into())?.into())
+    }
+
+    fn push(&mut self, (item, trace_context): Self::Input) -> PushResult<Self::Input> {
+        match self.inner.push(item) {
+            PushResult::Ok(full) => {
+                if self.trace_context.is_none() {
+                    self.trace_context = trace_context;
+                }
+                PushResult::Ok(full)
+            }
+            PushResult::Overflow(item) => PushResult::Overflow((item, trace_context)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        self.inner.fresh().into()
+    }
+
+    fn finish(self) -> Self::Output {
+        (self.inner.finish(), self.trace_context)
+    }
+
+    fn num_items(&self) -> usize {
+        self.inner.num_items()
+    }
+}
+
+impl<T: Partition<K>, K> Partition<K> for Traced<T> {
+    fn partition(&self) -> K {
+        self.0.partition()
+    }
+}
+
+type RequestFuture = BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>>;
+
+/// Builds the request of a batch with the sink, along with its trace context.
+fn traced_request_builder<T: HttpSink>(
+    sink: Arc<T>,
+) -> impl Fn(Traced<T::Output>) -> RequestFuture + Send + Sync + 'static
+where
+    T::Output: Send + 'static,
+{
+    move |(batch, trace_context): Traced<T::Output>| -> RequestFuture {
+        let sink = Arc::clone(&sink);
+        Box::pin(async move {
+            let mut request = sink.build_request(batch).await?;
+            if let Some(trace_context) = trace_context {
+                trace_context.apply_headers_map(request.headers_mut());
+            }
+            Ok(request)
+        })
+    }
 }
 
 /// Provides a simple wrapper around internal tower and
@@ -51,15 +149,15 @@ where
     sink: Arc<T>,
     #[pin]
     inner: TowerBatchedSink<
-        HttpBatchService<BoxFuture<'static, crate::Result<hyper::Request<Vec<u8>>>>, B::Output>,
-        B,
+        HttpBatchService<RequestFuture, Traced<B::Output>>,
+        TraceContextBatch<B>,
         L,
-        B::Output,
+        Traced<B::Output>,
     >,
     // An empty slot is needed to buffer an item where we encoded it but
     // the inner sink is applying back pressure. This trick is used in the `WithFlatMap`
     // sink combinator. https://docs.rs/futures/0.1.29/src/futures/sink/with_flat_map.rs.html#20
-    slot: Option<B::Input>,
+    slot: Option<Traced<B::Input>>,
 }
 
 impl<T, B> BatchedHttpSink<T, B, HttpRetryLogic>
@@ -106,15 +204,15 @@ where
     ) -> Self {
         let sink = Arc::new(sink);
 
-        let sink1 = Arc::clone(&sink);
-        let request_builder =
-            move |b| -> BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> {
-                let sink = Arc::clone(&sink1);
-                Box::pin(async move { sink.build_request(b).await })
-            };
-
+        let request_builder = traced_request_builder(Arc::clone(&sink));
         let svc = HttpBatchService::new(client, request_builder);
-        let inner = request_settings.batch_sink(logic, svc, batch, batch_timeout, acker);
+        let inner = request_settings.batch_sink(
+            logic,
+            svc,
+            TraceContextBatch::from(batch),
+            batch_timeout,
+            acker,
+        );
 
         Self {
             sink,
@@ -150,8 +248,12 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
+        let trace_context = self
+            .sink
+            .trace_context_key()
+            .and_then(|key| TraceContext::from_event(&item, key));
         if let Some(item) = self.sink.encode_event(item) {
-            *self.project().slot = Some(item);
+            *self.project().slot = Some((item, trace_context));
         }
 
         Ok(())
@@ -186,13 +288,13 @@ where
     sink: Arc<T>,
     #[pin]
     inner: TowerPartitionSink<
-        HttpBatchService<BoxFuture<'static, crate::Result<hyper::Request<Vec<u8>>>>, B::Output>,
-        B,
+        HttpBatchService<RequestFuture, Traced<B::Output>>,
+        TraceContextBatch<B>,
         L,
         K,
-        B::Output,
+        Traced<B::Output>,
     >,
-    slot: Option<B::Input>,
+    slot: Option<Traced<B::Input>>,
 }
 
 impl<T, B, K> PartitionHttpSink<T, B, K, HttpRetryLogic>
@@ -243,15 +345,15 @@ where
     ) -> Self {
         let sink = Arc::new(sink);
 
-        let sink1 = Arc::clone(&sink);
-        let request_builder =
-            move |b| -> BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> {
-                let sink = Arc::clone(&sink1);
-                Box::pin(async move { sink.build_request(b).await })
-            };
-
+        let request_builder = traced_request_builder(Arc::clone(&sink));
         let svc = HttpBatchService::new(client, request_builder);
-        let inner = request_settings.partition_sink(logic, svc, batch, batch_timeout, acker);
+        let inner = request_settings.partition_sink(
+            logic,
+            svc,
+            TraceContextBatch::from(batch),
+            batch_timeout,
+            acker,
+        );
 
         Self {
             sink,
@@ -289,8 +391,12 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
+        let trace_context = self
+            .sink
+            .trace_context_key()
+            .and_then(|key| TraceContext::from_event(&item, key));
         if let Some(item) = self.sink.encode_event(item) {
-            *self.project().slot = Some(item);
+            *self.project().slot = Some((item, trace_context));
         }
 
         Ok(())
@@ -0,0 +1,138 @@
+//! Deterministic keys derived from the contents of a request, which backends
+//! supporting them use to deduplicate requests retried after a failure that
+//! did reach them, such as a timeout.
+//!
+//! Only the `http` sink takes an `idempotency` option, as the keys are meant
+//! for the servers of its users, which `new_relic_logs` leaves unset.
+//! The APIs of the other HTTP sinks don't deduplicate requests on a header,
+//! and those deduplicating documents do so by IDs of their own, such as the
+//! `id_key` of `elasticsearch`.
+
+use crate::event::LogEvent;
+use http::{header::HeaderName, HeaderValue, Request};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ChecksumAlgorithm {
+    Md5,
+    Sha1,
+    #[derivative(Default)]
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    /// The lowercase hex encoded checksum of `data`.
+    pub fn checksum(self, data: &[u8]) -> String {
+        match self {
+            Self::Md5 => hex::encode(Md5::digest(data)),
+            Self::Sha1 => hex::encode(Sha1::digest(data)),
+            Self::Sha256 => hex::encode(Sha256::digest(data)),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Derivative)]
+#[serde(deny_unknown_fields, default)]
+#[derivative(Default)]
+pub struct IdempotencyConfig {
+    #[derivative(Default(value = "default_header()"))]
+    pub header: String,
+    /// Also set this field of every event to the checksum of the event,
+    /// for backends deduplicating single events rather than requests.
+    pub field: Option<String>,
+    pub algorithm: ChecksumAlgorithm,
+}
+
+fn default_header() -> String {
+    "Idempotency-Key".to_owned()
+}
+
+impl IdempotencyConfig {
+    pub fn validate(&self) -> crate::Result<()> {
+        HeaderName::from_bytes(self.header.as_bytes())
+            .map(|_| ())
+            .map_err(|error| {
+                format!(
+                    "Invalid idempotency key header {:?}: {}",
+                    self.header, error
+                )
+                .into()
+            })
+    }
+
+    /// Set the header to the checksum of the body. As the key is derived from
+    /// the body only, a request rebuilt from the same batch gets the same key.
+    pub fn apply<B: AsRef<[u8]>>(&self, request: &mut Request<B>) {
+        let key = self.algorithm.checksum(request.body().as_ref());
+        // Both are checked when building the sink, the key being plain hex.
+        if let Ok(name) = HeaderName::from_bytes(self.header.as_bytes()) {
+            let value = HeaderValue::from_str(&key).expect("hex is a valid header value");
+            request.headers_mut().insert(name, value);
+        }
+    }
+
+    /// Set the configured field, if any, to the checksum of the event as it
+    /// is before the field is set.
+    pub fn apply_field(&self, log: &mut LogEvent) {
+        if let Some(field) = &self.field {
+            let encoded = serde_json::to_vec(log).expect("log events serialize to JSON");
+            log.insert(field, self.algorithm.checksum(&encoded));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(
+            ChecksumAlgorithm::Md5.checksum(b"vector"),
+            "6ba8844da718b4a65f60dbfd0d92d6ef"
+        );
+    }
+
+    #[test]
+    fn applies_header() {
+        let config: IdempotencyConfig = toml::from_str("algorithm = \"sha1\"").unwrap();
+        let mut first = Request::new(b"batch".to_vec());
+        let mut retry = Request::new(b"batch".to_vec());
+        config.apply(&mut first);
+        config.apply(&mut retry);
+
+        let key = &first.headers()["Idempotency-Key"];
+        assert_eq!(key, &ChecksumAlgorithm::Sha1.checksum(b"batch"));
+        assert_eq!(key, &retry.headers()["Idempotency-Key"]);
+    }
+
+    #[test]
+    fn applies_field() {
+        let config: IdempotencyConfig = toml::from_str("field = \"event_id\"").unwrap();
+        let log = |message: &str| {
+            let mut log = LogEvent::default();
+            log.insert("message", message);
+            log
+        };
+        let mut first = log("message");
+        let mut retry = log("message");
+        let mut other = log("other message");
+        config.apply_field(&mut first);
+        config.apply_field(&mut retry);
+        config.apply_field(&mut other);
+
+        assert_eq!(first["event_id"], retry["event_id"]);
+        assert_ne!(first["event_id"], other["event_id"]);
+    }
+
+    #[test]
+    fn rejects_invalid_header() {
+        let config: IdempotencyConfig = toml::from_str("header = \"Bad Header\"").unwrap();
+        assert!(config.validate().is_err());
+    }
+}
@@ -3,6 +3,7 @@ pub mod batch;
 pub mod buffer;
 pub mod encoding;
 pub mod http;
+pub mod idempotency;
 pub mod retries;
 pub mod service;
 pub mod sink;
@@ -24,6 +25,7 @@ use snafu::Snafu;
 use std::borrow::Cow;
 
 pub use batch::{Batch, BatchConfig, BatchSettings, BatchSize, PushResult};
+pub use buffer::columns::{MetricColumns, ScalarRow, ScalarType};
 pub use buffer::json::{BoxedRawValue, JsonArrayBuffer};
 pub use buffer::metrics::{MetricBuffer, MetricEntry};
 pub use buffer::partition::Partition;
@@ -33,7 +35,7 @@ pub use service::{
     Concurrency, ServiceBuilderExt, TowerBatchedSink, TowerPartitionSink, TowerRequestConfig,
     TowerRequestLayer, TowerRequestSettings,
 };
-pub use sink::{BatchSink, PartitionBatchSink, StreamSink};
+pub use sink::{BatchSink, CloseWith, PartitionBatchSink, StreamSink};
 pub use uri::UriSerde;
 
 #[derive(Debug, Snafu)]
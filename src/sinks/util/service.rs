@@ -6,7 +6,9 @@ use super::{
     sink::Response,
     Batch, BatchSink, Partition, PartitionBatchSink,
 };
-use crate::buffers::Acker;
+use crate::{buffers::Acker, config::SecretString, config::SinkContext, http::build_headers};
+use http::HeaderMap;
+use indexmap::IndexMap;
 use serde::{
     de::{self, Unexpected, Visitor},
     Deserialize, Deserializer, Serialize,
@@ -160,7 +162,7 @@ impl ConcurrencyOption for Concurrency {
 }
 
 /// Tower Request based configuration
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct TowerRequestConfig<T: ConcurrencyOption = Concurrency> {
     #[serde(default)]
     #[serde(
@@ -174,11 +176,22 @@ pub struct TowerRequestConfig<T: ConcurrencyOption = Concurrency> {
     pub retry_attempts: Option<usize>,         // max_value()
     pub retry_max_duration_secs: Option<u64>,
     pub retry_initial_backoff_secs: Option<u64>, // 1
+    /// Only sends a request of a partition once the previous one completed.
+    pub ordered: Option<bool>, // false
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    /// Headers added to the requests of HTTP based sinks.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, SecretString>,
 }
 
 impl<T: ConcurrencyOption> TowerRequestConfig<T> {
+    /// The custom headers of the requests of the sink, overriding the global
+    /// `request.headers` of the same name.
+    pub fn headers(&self, cx: &SinkContext) -> crate::Result<HeaderMap> {
+        Ok(build_headers(vec![&cx.request().headers, &self.headers])?)
+    }
+
     pub fn unwrap_with(&self, defaults: &Self) -> TowerRequestSettings {
         TowerRequestSettings {
             concurrency: self.concurrency.parse_concurrency(&defaults.concurrency),
@@ -203,6 +216,7 @@ impl<T: ConcurrencyOption> TowerRequestConfig<T> {
                     .or(defaults.retry_initial_backoff_secs)
                     .unwrap_or(1),
             ),
+            ordered: self.ordered.or(defaults.ordered).unwrap_or(false),
             adaptive_concurrency: self.adaptive_concurrency,
         }
     }
@@ -217,6 +231,7 @@ pub struct TowerRequestSettings {
     pub retry_attempts: usize,
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
+    pub ordered: bool,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
 }
 
@@ -255,6 +270,7 @@ impl TowerRequestSettings {
             batch_timeout,
             acker,
         )
+        .ordered(self.ordered)
     }
 
     pub fn batch_sink<B, L, S, Request>(
@@ -280,6 +296,7 @@ impl TowerRequestSettings {
             batch_timeout,
             acker,
         )
+        .ordered(self.ordered)
     }
 
     pub fn service<L, S, Request>(&self, retry_logic: L, service: S) -> Svc<S, L>
@@ -36,7 +36,11 @@ use super::{
     buffer::{Partition, PartitionBuffer, PartitionInnerBuffer},
     service::{Map, ServiceBuilderExt},
 };
-use crate::{buffers::Acker, Event};
+use crate::{
+    buffers::Acker,
+    tuning::{self, Tunable},
+    Event,
+};
 use async_trait::async_trait;
 use futures::{
     future::BoxFuture,
@@ -67,6 +71,59 @@ pub trait StreamSink {
     async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()>;
 }
 
+// === CloseWith ===
+
+/// Closes a sink along with the service behind it, for services holding
+/// requests open across calls, like uploads streamed over several requests.
+/// `close` is called once the input ended, before closing the sink, and the
+/// future it returns is awaited once the sink closed.
+pub struct CloseWith<S> {
+    inner: S,
+    close: Option<Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>>,
+    closing: Option<BoxFuture<'static, ()>>,
+}
+
+impl<S> CloseWith<S> {
+    pub fn new(inner: S, close: impl FnOnce() -> BoxFuture<'static, ()> + Send + 'static) -> Self {
+        Self {
+            inner,
+            close: Some(Box::new(close)),
+            closing: None,
+        }
+    }
+}
+
+impl<S, T> Sink<T> for CloseWith<S>
+where
+    S: Sink<T> + Unpin,
+{
+    type Error = S::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        Pin::new(&mut self.inner).start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(close) = self.close.take() {
+            self.closing = Some(close());
+        }
+        ready!(Pin::new(&mut self.inner).poll_close(cx))?;
+        if let Some(closing) = self.closing.as_mut() {
+            ready!(closing.poll_unpin(cx));
+            self.closing = None;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
 // === BatchSink ===
 
 /// A `Sink` interface that wraps a `Service` and a
@@ -114,6 +171,14 @@ where
         let inner = PartitionBatchSink::new(service, batch, timeout, acker);
         Self { inner }
     }
+
+    /// Sends the next request only once the previous one, and its retries,
+    /// completed, so the requests reach the service in order.
+    pub fn ordered(self, ordered: bool) -> Self {
+        Self {
+            inner: self.inner.ordered(ordered),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -170,6 +235,12 @@ where
 /// batches have been acked. This means if sequential requests r1, r2,
 /// and r3 are dispatched and r2 and r3 complete, all events contained
 /// in all requests will not be acked until r1 has completed.
+///
+/// # Ordering
+///
+/// In ordered mode, a batch of a partition is only sent once the previous
+/// request of the partition, including its retries, completed. Requests of
+/// different partitions are still sent concurrently.
 #[pin_project]
 pub struct PartitionBatchSink<S, B, K, Request>
 where
@@ -180,7 +251,14 @@ where
     batch: StatefulBatch<B>,
     partitions: HashMap<K, StatefulBatch<B>>,
     timeout: Duration,
+    timeout_override: Tunable,
+    /// Can only lower the size of the batches, as they overflow at their
+    /// configured size regardless.
+    max_events_override: Tunable,
     lingers: HashMap<K, Delay>,
+    ordered: bool,
+    /// The partitions with a request in flight, in ordered mode.
+    sending: HashMap<K, oneshot::Receiver<()>>,
     closing: bool,
 }
 
@@ -203,10 +281,21 @@ where
             batch: batch.into(),
             partitions: HashMap::new(),
             timeout,
+            timeout_override: tuning::register(tuning::BATCH_TIMEOUT_SECS),
+            max_events_override: tuning::register(tuning::BATCH_MAX_EVENTS),
             lingers: HashMap::new(),
+            ordered: false,
+            sending: HashMap::new(),
             closing: false,
         }
     }
+
+    /// Sends the next request of a partition only once the previous one,
+    /// and its retries, completed.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
 }
 
 impl<S, B, K, Request> Sink<B::Input> for PartitionBatchSink<S, B, K, Request>
@@ -239,6 +328,7 @@ where
 
     fn start_send(mut self: Pin<&mut Self>, item: B::Input) -> Result<(), Self::Error> {
         let partition = item.partition();
+        let max_events = self.max_events_override.get();
 
         let batch = loop {
             if let Some(batch) = self.partitions.get_mut(&partition) {
@@ -248,11 +338,18 @@ where
             let batch = self.batch.fresh();
             self.partitions.insert(partition.clone(), batch);
 
-            let delay = delay_for(self.timeout);
+            let timeout = self
+                .timeout_override
+                .get()
+                .map(Duration::from_secs)
+                .unwrap_or(self.timeout);
+            let delay = delay_for(timeout);
             self.lingers.insert(partition.clone(), delay);
         };
 
-        if let PushResult::Overflow(item) = batch.push(item) {
+        if max_events.map_or(false, |max| batch.num_items() as u64 >= max) {
+            self.buffer = Some((partition, item));
+        } else if let PushResult::Overflow(item) = batch.push(item) {
             self.buffer = Some((partition, item));
         }
 
@@ -269,10 +366,17 @@ where
 
             // Try send batches.
             let this = self.as_mut().project();
+            let max_events = this.max_events_override.get();
+            this.sending
+                .retain(|_, sending| sending.poll_unpin(cx).is_pending());
             let mut partitions_ready = vec![];
             for (partition, batch) in this.partitions.iter() {
+                if this.sending.contains_key(partition) {
+                    continue;
+                }
                 if (*this.closing && !batch.is_empty())
                     || batch.was_full()
+                    || max_events.map_or(false, |max| batch.num_items() as u64 >= max)
                     || matches!(
                         this.lingers
                             .get_mut(&partition)
@@ -299,7 +403,16 @@ where
 
                     let batch_size = batch.num_items();
                     let request = batch.finish();
-                    tokio::spawn(self.service.call(request, batch_size));
+                    let call = self.service.call(request, batch_size);
+                    if self.ordered {
+                        let (tx, rx) = oneshot::channel();
+                        self.sending.insert(partition.clone(), rx);
+                        tokio::spawn(call.map(move |()| {
+                            let _ = tx.send(());
+                        }));
+                    } else {
+                        tokio::spawn(call);
+                    }
 
                     batch_consumed = true;
                 } else {
@@ -867,6 +980,53 @@ mod tests {
         assert_eq!(&*output, &vec![vec![1]]);
     }
 
+    #[tokio::test]
+    async fn partition_batch_sink_ordered_sends_one_request_per_partition() {
+        let (acker, _) = Acker::new_for_testing();
+        let in_flight = Arc::new(Mutex::new(Vec::new()));
+        let sent_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let svc = tower::service_fn(|req: Vec<Partitions>| {
+            let in_flight = Arc::clone(&in_flight);
+            let sent_requests = Arc::clone(&sent_requests);
+            let partition = req[0].partition();
+            async move {
+                assert!(
+                    !in_flight.lock().unwrap().contains(&partition),
+                    "requests of a partition overlap"
+                );
+                in_flight.lock().unwrap().push(partition.clone());
+                delay_for(Duration::from_millis(10)).await;
+                in_flight.lock().unwrap().retain(|p| p != &partition);
+                sent_requests.lock().unwrap().push(req);
+                Ok::<_, std::io::Error>(())
+            }
+        });
+
+        let batch = BatchSettings::default().bytes(9999).events(1);
+        let sink =
+            PartitionBatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker).ordered(true);
+
+        let input = vec![Partitions::A, Partitions::B, Partitions::A, Partitions::A];
+        sink.sink_map_err(drop)
+            .send_all(&mut stream::iter(input).map(Ok))
+            .await
+            .unwrap();
+
+        let mut output = sent_requests.lock().unwrap();
+        assert_eq!(output.len(), 4);
+        output[..].sort();
+        assert_eq!(
+            &*output,
+            &vec![
+                vec![Partitions::A],
+                vec![Partitions::A],
+                vec![Partitions::A],
+                vec![Partitions::B]
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn service_sink_doesnt_propagate_error() {
         // We need a mock executor here because we need to ensure
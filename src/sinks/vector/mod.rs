@@ -0,0 +1,104 @@
+//! Sends events to the `vector` source of another Vector instance, see the
+//! source for the differences between the protocol versions.
+
+use crate::config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription};
+use serde::{Deserialize, Serialize};
+
+pub mod v1;
+pub mod v2;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(untagged)]
+pub enum VectorSinkConfig {
+    V1(v1::VectorSinkConfig),
+    V2(v2::VectorSinkConfig),
+}
+
+inventory::submit! {
+    SinkDescription::new::<VectorSinkConfig>("vector")
+}
+
+impl GenerateConfig for VectorSinkConfig {
+    fn generate_config() -> toml::Value {
+        v1::VectorSinkConfig::generate_config()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "vector")]
+impl SinkConfig for VectorSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        match self {
+            VectorSinkConfig::V1(v1) => v1.build(cx).await,
+            VectorSinkConfig::V2(v2) => v2.build(cx).await,
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        match self {
+            VectorSinkConfig::V1(v1) => v1.input_type(),
+            VectorSinkConfig::V2(v2) => v2.input_type(),
+        }
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "vector"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<VectorSinkConfig>();
+        crate::test_util::test_generate_config::<v2::VectorSinkConfig>();
+    }
+
+    #[test]
+    fn parses_versions() {
+        let config: VectorSinkConfig = toml::from_str(r#"address = "127.0.0.1:5000""#).unwrap();
+        assert!(matches!(config, VectorSinkConfig::V1(_)));
+
+        let config: VectorSinkConfig = toml::from_str(
+            r#"
+            version = "2"
+            address = "127.0.0.1:6000"
+            compression = true
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config, VectorSinkConfig::V2(_)));
+    }
+
+    #[test]
+    fn parses_compression() {
+        let config: v2::VectorSinkConfig = toml::from_str(
+            r#"
+            version = "2"
+            address = "127.0.0.1:6000"
+            compression = "zstd"
+            delta_encoding = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.compression,
+            v2::CompressionConfig::Algorithm(v2::CompressionAlgorithm::Zstd)
+        );
+        assert!(config.delta_encoding);
+
+        toml::from_str::<v2::VectorSinkConfig>(
+            r#"
+            version = "2"
+            address = "127.0.0.1:6000"
+            compression = "lz4"
+            "#,
+        )
+        .unwrap_err();
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{DataType, GenerateConfig, SinkContext},
     event::proto,
     internal_events::VectorEventSent,
     sinks::util::tcp::TcpSinkConfig,
@@ -15,11 +15,19 @@ use snafu::Snafu;
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct VectorSinkConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
     pub address: String,
     pub keepalive: Option<TcpKeepaliveConfig>,
     pub tls: Option<TlsConfig>,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub enum Version {
+    #[serde(rename = "1")]
+    V1,
+}
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Missing host in address field"))]
@@ -28,13 +36,10 @@ enum BuildError {
     MissingPort,
 }
 
-inventory::submit! {
-    SinkDescription::new::<VectorSinkConfig>("vector")
-}
-
 impl GenerateConfig for VectorSinkConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
+            version: None,
             address: "127.0.0.1:5000".to_string(),
             keepalive: None,
             tls: None,
@@ -43,25 +48,19 @@ impl GenerateConfig for VectorSinkConfig {
     }
 }
 
-#[async_trait::async_trait]
-#[typetag::serde(name = "vector")]
-impl SinkConfig for VectorSinkConfig {
-    async fn build(
+impl VectorSinkConfig {
+    pub(super) async fn build(
         &self,
         cx: SinkContext,
-    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+    ) -> crate::Result<(crate::sinks::VectorSink, crate::sinks::Healthcheck)> {
         let sink_config =
             TcpSinkConfig::new(self.address.clone(), self.keepalive, self.tls.clone());
         sink_config.build(cx, encode_event)
     }
 
-    fn input_type(&self) -> DataType {
+    pub(super) fn input_type(&self) -> DataType {
         DataType::Any
     }
-
-    fn sink_type(&self) -> &'static str {
-        "vector"
-    }
 }
 
 #[derive(Debug, Snafu)]
@@ -85,11 +84,3 @@ fn encode_event(event: Event) -> Option<Bytes> {
 
     Some(out.into())
 }
-
-#[cfg(test)]
-mod test {
-    #[test]
-    fn generate_config() {
-        crate::test_util::test_generate_config::<super::VectorSinkConfig>();
-    }
-}
@@ -0,0 +1,510 @@
+use crate::{
+    config::{DataType, GenerateConfig, SecretString, SinkContext},
+    dns,
+    event::proto::{event_wrapper, EventWrapper, Value},
+    internal_events::VectorEventSent,
+    proto::{
+        auth::{self, PreSharedKey, PROOF_KEY, SESSION_KEY},
+        vector_client::VectorClient,
+        AuthenticateRequest, Compression, EventBatch, FieldNames, HealthCheckRequest,
+        PushEventsRequest, ServingStatus,
+    },
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, BatchSettings, EncodedLength, TowerRequestConfig,
+            VecBuffer,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsConfig},
+    Event,
+};
+use flate2::{write::GzEncoder, Compression as GzipLevel};
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt};
+use http::uri::{InvalidUri, Uri};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+use tokio::net::TcpStream;
+use tonic::{
+    metadata::MetadataValue,
+    transport::{Channel, Endpoint},
+    Code, Status,
+};
+use tower::Service;
+use tracing_futures::Instrument;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VectorSinkConfig {
+    pub version: Version,
+    pub address: String,
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    #[serde(default)]
+    pub delta_encoding: bool,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsConfig>,
+    /// Authenticates with the source, which has to know the same key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_shared_key: Option<SecretString>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub enum Version {
+    #[serde(rename = "2")]
+    V2,
+}
+
+/// Either the compression algorithm, or a boolean enabling gzip as supported
+/// before the algorithm could be chosen.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum CompressionConfig {
+    Enabled(bool),
+    Algorithm(CompressionAlgorithm),
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self::Enabled(false)
+    }
+}
+
+impl CompressionConfig {
+    fn algorithm(self) -> CompressionAlgorithm {
+        match self {
+            Self::Enabled(false) => CompressionAlgorithm::None,
+            Self::Enabled(true) => CompressionAlgorithm::Gzip,
+            Self::Algorithm(algorithm) => algorithm,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithm {
+    None,
+    Gzip,
+    Zstd,
+}
+
+#[cfg(test)]
+impl VectorSinkConfig {
+    pub fn new(address: String, compression: bool, tls: Option<TlsConfig>) -> Self {
+        Self {
+            version: Version::V2,
+            address,
+            compression: CompressionConfig::Enabled(compression),
+            delta_encoding: false,
+            batch: BatchConfig::default(),
+            request: TowerRequestConfig::default(),
+            tls,
+            pre_shared_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid address {:?}: {}", address, source))]
+    InvalidAddress { address: String, source: InvalidUri },
+    #[snafu(display("Missing host in address field"))]
+    MissingHost,
+    #[snafu(display("Missing port in address field"))]
+    MissingPort,
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("The source is not serving"))]
+    NotServing,
+}
+
+#[derive(Debug, Snafu)]
+enum ConnectError {
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    Tls { source: crate::tls::TlsError },
+}
+
+#[derive(Debug, Snafu)]
+enum RequestError {
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: tonic::transport::Error },
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: Status },
+    #[snafu(display("The source failed to prove knowing the pre-shared key"))]
+    SourceAuthentication,
+}
+
+impl GenerateConfig for VectorSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::new("127.0.0.1:6000".to_string(), false, None)).unwrap()
+    }
+}
+
+impl VectorSinkConfig {
+    pub(super) async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let batch = BatchSettings::default()
+            .bytes(10_000_000)
+            .events(1000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+
+        let key = self
+            .pre_shared_key
+            .as_ref()
+            .map(|key| PreSharedKey::new(&key.resolve()?))
+            .transpose()?;
+        let client = Client::new(
+            &self.address,
+            &self.tls,
+            self.compression.algorithm(),
+            self.delta_encoding,
+            key,
+        )?;
+        let healthcheck = client.clone().healthcheck().boxed();
+
+        let sink = request
+            .batch_sink(
+                VectorRetryLogic,
+                client,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal vector sink error.", %error))
+            .with_flat_map(|event| stream::iter(Some(Ok(encode_event(event)))));
+
+        Ok((VectorSink::Sink(Box::new(sink)), healthcheck))
+    }
+
+    pub(super) fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+}
+
+/// Connects lazily, as the source may not be up yet, and shares the
+/// connection between requests as gRPC multiplexes them over it.
+#[derive(Clone)]
+struct Client {
+    endpoint: Endpoint,
+    connector: Connector,
+    compression: CompressionAlgorithm,
+    delta_encoding: bool,
+    client: Arc<Mutex<Option<VectorClient<Channel>>>>,
+    key: Option<PreSharedKey>,
+    current_session: Arc<Mutex<Option<Session>>>,
+}
+
+/// Sessions are opened again this long before they expire.
+const SESSION_RENEWAL: Duration = Duration::from_secs(60);
+
+/// A session opened with the pre-shared key.
+#[derive(Clone)]
+struct Session {
+    session: Vec<u8>,
+    expires: SystemTime,
+}
+
+impl Client {
+    fn new(
+        address: &str,
+        tls: &Option<TlsConfig>,
+        compression: CompressionAlgorithm,
+        delta_encoding: bool,
+        key: Option<PreSharedKey>,
+    ) -> crate::Result<Self> {
+        let uri = with_default_scheme(address)
+            .parse::<Uri>()
+            .context(InvalidAddress { address })?;
+        let host = uri.host().ok_or(BuildError::MissingHost)?.to_owned();
+        let port = uri.port_u16().ok_or(BuildError::MissingPort)?;
+        let tls = MaybeTlsSettings::from_config(tls, false)?;
+
+        Ok(Self {
+            endpoint: Channel::builder(uri),
+            connector: Connector { host, port, tls },
+            compression,
+            delta_encoding,
+            client: Arc::new(Mutex::new(None)),
+            key,
+            current_session: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    async fn client(&self) -> Result<VectorClient<Channel>, RequestError> {
+        let client = self.client.lock().unwrap().clone();
+        if let Some(client) = client {
+            return Ok(client);
+        }
+
+        let channel = self
+            .endpoint
+            .connect_with_connector(self.connector.clone())
+            .await
+            .context(Connect)?;
+        let client = VectorClient::new(channel);
+        *self.client.lock().unwrap() = Some(client.clone());
+        Ok(client)
+    }
+
+    /// The session to send requests with if authenticating, opened when
+    /// there is none or it's about to expire.
+    async fn session(
+        &self,
+        client: &mut VectorClient<Channel>,
+    ) -> Result<Option<Session>, RequestError> {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        let current = self.current_session.lock().unwrap().clone();
+        if let Some(session) = current {
+            if SystemTime::now() + SESSION_RENEWAL < session.expires {
+                return Ok(Some(session));
+            }
+        }
+
+        let nonce = PreSharedKey::nonce();
+        let response = client
+            .authenticate(AuthenticateRequest {
+                nonce: nonce.clone(),
+            })
+            .await
+            .context(Request)?
+            .into_inner();
+        if !key.verify_source(&nonce, &response.session, &response.proof) {
+            return Err(RequestError::SourceAuthentication);
+        }
+        let session = Session {
+            expires: auth::expires(&response.session).ok_or(RequestError::SourceAuthentication)?,
+            session: response.session,
+        };
+        *self.current_session.lock().unwrap() = Some(session.clone());
+        Ok(Some(session))
+    }
+
+    async fn healthcheck(self) -> crate::Result<()> {
+        let mut client = self.client().await?;
+        self.session(&mut client).await?;
+        let response = client
+            .health_check(HealthCheckRequest {})
+            .await?
+            .into_inner();
+
+        match ServingStatus::from_i32(response.status) {
+            Some(ServingStatus::Serving) => Ok(()),
+            _ => Err(HealthcheckError::NotServing.into()),
+        }
+    }
+
+    fn encode(&self, mut events: Vec<EventWrapper>) -> PushEventsRequest {
+        let removed_fields = if self.delta_encoding {
+            delta_encode(&mut events)
+        } else {
+            BTreeMap::new()
+        };
+        let mut batch = Vec::new();
+        EventBatch {
+            events,
+            delta_encoded: self.delta_encoding,
+            removed_fields,
+        }
+        .encode(&mut batch)
+        .expect("Writing to a Vec can't fail.");
+
+        let (batch, compression) = match self.compression {
+            CompressionAlgorithm::None => (batch, Compression::None),
+            CompressionAlgorithm::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+                let batch = encoder
+                    .write_all(&batch)
+                    .and_then(|()| encoder.finish())
+                    .expect("Writing to a Vec can't fail.");
+                (batch, Compression::Gzip)
+            }
+            CompressionAlgorithm::Zstd => {
+                let batch = zstd::encode_all(&batch[..], zstd::DEFAULT_COMPRESSION_LEVEL)
+                    .expect("Writing to a Vec can't fail.");
+                (batch, Compression::Zstd)
+            }
+        };
+        PushEventsRequest {
+            batch,
+            compression: compression.into(),
+        }
+    }
+}
+
+/// Strips the fields of the log events that are the same as in the previous
+/// log event, such as the host or the Kubernetes metadata, returning the
+/// names of the fields of the previous log event each event doesn't have.
+fn delta_encode(events: &mut [EventWrapper]) -> BTreeMap<u32, FieldNames> {
+    let mut removed_fields = BTreeMap::new();
+    let mut previous = BTreeMap::<String, Value>::new();
+    for (index, event) in events.iter_mut().enumerate() {
+        if let Some(event_wrapper::Event::Log(log)) = &mut event.event {
+            let fields = std::mem::take(&mut log.fields);
+            log.fields = fields
+                .iter()
+                .filter(|(name, value)| previous.get(*name) != Some(*value))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            let names = previous
+                .keys()
+                .filter(|name| !fields.contains_key(*name))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !names.is_empty() {
+                removed_fields.insert(index as u32, FieldNames { names });
+            }
+            previous = fields;
+        }
+    }
+    removed_fields
+}
+
+impl Service<Vec<EventWrapper>> for Client {
+    type Response = ();
+    type Error = RequestError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, events: Vec<EventWrapper>) -> Self::Future {
+        let request = self.encode(events);
+        let this = self.clone();
+
+        Box::pin(
+            async move {
+                let mut client = this.client().await?;
+                let session = this.session(&mut client).await?;
+                let request = with_session(request, this.key.as_ref(), session);
+                let result = client.push_events(request).await;
+                if matches!(&result, Err(status) if status.code() == Code::Unauthenticated) {
+                    // Opens a new session with the next request, in case the
+                    // key of the source changed.
+                    this.current_session.lock().unwrap().take();
+                }
+                result.context(Request)?;
+                Ok(())
+            }
+            .instrument(info_span!("request")),
+        )
+    }
+}
+
+/// Opens the connections of the client, with the TLS settings of the sink.
+#[derive(Clone)]
+struct Connector {
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+}
+
+impl Service<Uri> for Connector {
+    type Response = MaybeTlsStream<TcpStream>;
+    type Error = ConnectError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let ip = dns::Resolver
+                .lookup_ip(this.host.clone())
+                .await
+                .context(Dns)?
+                .next()
+                .ok_or(ConnectError::NoAddresses)?;
+            let addr = SocketAddr::new(ip, this.port);
+            this.tls.connect(&this.host, &addr).await.context(Tls)
+        })
+    }
+}
+
+/// Sends the session along with the request, and the proof of the sink for
+/// its batch.
+fn with_session(
+    message: PushEventsRequest,
+    key: Option<&PreSharedKey>,
+    session: Option<Session>,
+) -> tonic::Request<PushEventsRequest> {
+    let proof = match (key, &session) {
+        (Some(key), Some(session)) => Some(key.sink_proof(&session.session, &message.batch)),
+        _ => None,
+    };
+    let mut request = tonic::Request::new(message);
+    if let (Some(session), Some(proof)) = (session, proof) {
+        let metadata = request.metadata_mut();
+        metadata.insert_bin(SESSION_KEY, MetadataValue::from_bytes(&session.session));
+        metadata.insert_bin(PROOF_KEY, MetadataValue::from_bytes(&proof));
+    }
+    request
+}
+
+fn encode_event(event: Event) -> EventWrapper {
+    let event = EventWrapper::from(event);
+    emit!(VectorEventSent {
+        byte_size: event.encoded_len()
+    });
+    event
+}
+
+fn with_default_scheme(address: &str) -> String {
+    if address.contains("://") {
+        address.to_owned()
+    } else {
+        format!("http://{}", address)
+    }
+}
+
+impl EncodedLength for EventWrapper {
+    fn encoded_length(&self) -> usize {
+        self.encoded_len()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VectorRetryLogic;
+
+impl RetryLogic for VectorRetryLogic {
+    type Error = RequestError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            RequestError::Connect { .. } => true,
+            RequestError::Request { source } => !matches!(
+                source.code(),
+                Code::InvalidArgument
+                    | Code::Unimplemented
+                    | Code::Unauthenticated
+                    | Code::FailedPrecondition
+            ),
+            RequestError::SourceAuthentication => false,
+        }
+    }
+}
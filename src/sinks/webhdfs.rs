@@ -0,0 +1,687 @@
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    http::{HttpClient, HttpError},
+    sinks::{
+        util::{
+            encoding::{EncodingConfig, EncodingConfiguration},
+            retries::{RetryAction, RetryLogic},
+            BatchConfig, BatchSettings, Buffer, Compression, Concurrency, PartitionBatchSink,
+            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        },
+        Healthcheck, HealthcheckError, VectorSink,
+    },
+    template::{Template, TemplateError},
+    tls::{TlsOptions, TlsSettings},
+    Event,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use http::{header, uri::Authority, Method, StatusCode, Uri};
+use hyper::{header::HeaderValue, Body, Request, Response};
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{convert::TryFrom, task::Poll};
+use tower::{Service, ServiceBuilder};
+use uuid::Uuid;
+
+const NAME: &str = "webhdfs";
+
+/// The characters of paths escaped in request URIs, keeping the `/`
+/// separating their directories.
+const PATH: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebHdfsSinkConfig {
+    endpoint: String,
+    prefix: Option<String>,
+    filename_time_format: Option<String>,
+    filename_append_uuid: Option<bool>,
+    user: Option<String>,
+    #[serde(default)]
+    auth: Auth,
+    encoding: EncodingConfig<Encoding>,
+    #[serde(default = "Compression::gzip_default")]
+    compression: Compression,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsOptions>,
+}
+
+#[cfg(test)]
+fn default_config(e: Encoding) -> WebHdfsSinkConfig {
+    WebHdfsSinkConfig {
+        endpoint: "http://localhost:9870".into(),
+        prefix: Default::default(),
+        filename_time_format: Default::default(),
+        filename_append_uuid: Default::default(),
+        user: Default::default(),
+        auth: Default::default(),
+        encoding: e.into(),
+        compression: Compression::gzip_default(),
+        batch: Default::default(),
+        request: Default::default(),
+        tls: Default::default(),
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+enum Auth {
+    /// Names the user with the `user.name` parameter, as with the simple
+    /// authentication of Hadoop.
+    #[derivative(Default)]
+    Simple,
+    /// Authenticates with SPNEGO, from the Kerberos credentials cache or
+    /// the keytab of `KRB5_CLIENT_KTNAME`.
+    Kerberos { service_principal: Option<String> },
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        concurrency: Concurrency::Fixed(25),
+        rate_limit_num: Some(250),
+        ..Default::default()
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Encoding {
+    Text,
+    Ndjson,
+}
+
+inventory::submit! {
+    SinkDescription::new::<WebHdfsSinkConfig>(NAME)
+}
+
+impl GenerateConfig for WebHdfsSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://namenode:9870"
+            prefix = "/vector/%F/"
+            encoding.codec = "ndjson""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "webhdfs")]
+impl SinkConfig for WebHdfsSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let sink = WebHdfsSink::new(self)?;
+        let healthcheck = sink.clone().healthcheck().boxed();
+        let service = sink.service(self, &cx)?;
+
+        Ok((service, healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        NAME
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum WebHdfsError {
+    #[snafu(display("Invalid endpoint: {}", source))]
+    InvalidEndpoint { source: http::uri::InvalidUri },
+    #[snafu(display("The endpoint names no host"))]
+    MissingHost,
+    #[snafu(display("prefix template parse error: {}", source))]
+    PrefixTemplate { source: TemplateError },
+    #[snafu(display("Kerberos authentication requires Vector built with the `gssapi` feature"))]
+    GssapiUnsupported,
+    #[snafu(display("Failed to negotiate a Kerberos token: {}", message))]
+    Negotiate { message: String },
+    #[snafu(display("Request failed: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("The {} response holds no valid Location header", status))]
+    MissingLocation { status: StatusCode },
+    #[snafu(display("Invalid credentials"))]
+    InvalidCredentials,
+}
+
+#[derive(Clone, Debug)]
+enum Credentials {
+    Simple,
+    Kerberos { service_principal: String },
+}
+
+impl Credentials {
+    fn new(auth: &Auth, host: &str) -> Result<Self, WebHdfsError> {
+        match auth {
+            Auth::Simple => Ok(Self::Simple),
+            Auth::Kerberos { .. } if cfg!(not(feature = "gssapi")) => {
+                Err(WebHdfsError::GssapiUnsupported)
+            }
+            Auth::Kerberos { service_principal } => Ok(Self::Kerberos {
+                service_principal: service_principal
+                    .clone()
+                    .unwrap_or_else(|| format!("HTTP@{}", host)),
+            }),
+        }
+    }
+
+    /// The `Authorization` header of the requests, negotiated anew for each
+    /// of them as the tokens can't be replayed.
+    async fn authorization(&self) -> Result<Option<HeaderValue>, WebHdfsError> {
+        match self {
+            Self::Simple => Ok(None),
+            Self::Kerberos { service_principal } => {
+                let service_principal = service_principal.clone();
+                let token = tokio::task::spawn_blocking(move || negotiate(&service_principal))
+                    .await
+                    .map_err(|error| WebHdfsError::Negotiate {
+                        message: error.to_string(),
+                    })??;
+                let authorization = format!("Negotiate {}", base64::encode(&token));
+                Ok(Some(HeaderValue::from_str(&authorization).unwrap()))
+            }
+        }
+    }
+}
+
+/// Initiates a Kerberos security context with the service, which the first
+/// token is enough for as no mutual authentication is asked for.
+#[cfg(feature = "gssapi")]
+fn negotiate(service_principal: &str) -> Result<Vec<u8>, WebHdfsError> {
+    use libgssapi::{
+        context::{ClientCtx, CtxFlags},
+        credential::{Cred, CredUsage},
+        name::Name,
+        oid::{OidSet, GSS_MECH_KRB5, GSS_NT_HOSTBASED_SERVICE},
+    };
+
+    let negotiate = || -> Result<Vec<u8>, libgssapi::error::Error> {
+        let mut mechs = OidSet::new()?;
+        mechs.add(&GSS_MECH_KRB5)?;
+        let cred = Cred::acquire(None, None, CredUsage::Initiate, Some(&mechs))?;
+        let service = Name::new(
+            service_principal.as_bytes(),
+            Some(&GSS_NT_HOSTBASED_SERVICE),
+        )?
+        .canonicalize(Some(&GSS_MECH_KRB5))?;
+        let mut context = ClientCtx::new(cred, service, CtxFlags::empty(), Some(&GSS_MECH_KRB5));
+        Ok(context
+            .step(None)?
+            .map(|token| token.to_vec())
+            .unwrap_or_default())
+    };
+    negotiate().map_err(|error| WebHdfsError::Negotiate {
+        message: error.to_string(),
+    })
+}
+
+#[cfg(not(feature = "gssapi"))]
+fn negotiate(_service_principal: &str) -> Result<Vec<u8>, WebHdfsError> {
+    Err(WebHdfsError::GssapiUnsupported)
+}
+
+#[derive(Clone)]
+struct WebHdfsSink {
+    client: HttpClient,
+    endpoint: String,
+    authority: Option<Authority>,
+    user: Option<String>,
+    credentials: Credentials,
+    settings: RequestSettings,
+}
+
+impl WebHdfsSink {
+    fn new(config: &WebHdfsSinkConfig) -> crate::Result<Self> {
+        let tls = TlsSettings::from_options(&config.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let endpoint = config.endpoint.trim_end_matches('/').to_owned();
+        let uri = endpoint.parse::<Uri>().context(InvalidEndpoint)?;
+        let host = uri.host().ok_or(WebHdfsError::MissingHost)?;
+        let credentials = Credentials::new(&config.auth, host)?;
+        let settings = RequestSettings::new(config);
+
+        Ok(Self {
+            client,
+            endpoint,
+            authority: uri.authority().cloned(),
+            user: config.user.clone(),
+            credentials,
+            settings,
+        })
+    }
+
+    fn service(self, config: &WebHdfsSinkConfig, cx: &SinkContext) -> crate::Result<VectorSink> {
+        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let ordered = request.ordered;
+        let encoding = config.encoding.clone();
+
+        let batch = BatchSettings::default()
+            .bytes(bytesize::mib(10u64))
+            .timeout(300)
+            .parse_config(config.batch)?;
+
+        let prefix = config.prefix.as_deref().unwrap_or("/vector/%F/");
+        let prefix = Template::try_from(prefix).context(PrefixTemplate)?;
+
+        let settings = self.settings.clone();
+
+        let svc = ServiceBuilder::new()
+            .map(move |req| RequestWrapper::new(req, &settings))
+            .settings(request, WebHdfsRetryLogic)
+            .service(self);
+
+        let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
+
+        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+            .ordered(ordered)
+            .sink_map_err(|error| error!(message = "Fatal webhdfs error.", %error))
+            .with_flat_map(move |e| stream::iter(encode_event(e, &prefix, &encoding)).map(Ok));
+
+        Ok(VectorSink::Sink(Box::new(sink)))
+    }
+
+    /// Checks the status of the root directory, which any authenticated user
+    /// may read.
+    async fn healthcheck(self) -> crate::Result<()> {
+        let uri = self.uri("/", "GETFILESTATUS", &[]);
+        let request = self.request(Method::GET, uri, Bytes::new(), true).await?;
+
+        let response = self.client.send(request).await?;
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(WebHdfsError::InvalidCredentials.into())
+            }
+            status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+        }
+    }
+
+    fn uri(&self, path: &str, op: &str, params: &[&str]) -> Uri {
+        let mut uri = format!("{}/webhdfs/v1", self.endpoint);
+        if !path.starts_with('/') {
+            uri.push('/');
+        }
+        uri.extend(utf8_percent_encode(path, PATH));
+        uri.push_str("?op=");
+        uri.push_str(op);
+        for param in params {
+            uri.push('&');
+            uri.push_str(param);
+        }
+        if let Some(user) = &self.user {
+            // Kerberos authenticated users impersonate the configured one,
+            // which Hadoop allows to proxy users only.
+            let param = match self.credentials {
+                Credentials::Simple => "user.name",
+                Credentials::Kerberos { .. } => "doas",
+            };
+            uri.push_str(&format!(
+                "&{}={}",
+                param,
+                utf8_percent_encode(user, NON_ALPHANUMERIC)
+            ));
+        }
+        uri.parse().expect("WebHDFS URIs are valid.")
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        uri: Uri,
+        body: Bytes,
+        authenticate: bool,
+    ) -> Result<Request<Body>, WebHdfsError> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        let headers = builder.headers_mut().unwrap();
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+        if !body.is_empty() {
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/octet-stream"),
+            );
+        }
+        if authenticate {
+            if let Some(authorization) = self.credentials.authorization().await? {
+                headers.insert(header::AUTHORIZATION, authorization);
+            }
+        }
+        Ok(builder.body(Body::from(body)).unwrap())
+    }
+
+    /// Creates the file of the batch in two steps: the name node, or the
+    /// HttpFS gateway, redirects the creation to the node the data is then
+    /// sent to.
+    async fn create(&self, request: RequestWrapper) -> Result<Response<Body>, WebHdfsError> {
+        let uri = self.uri(&request.path, "CREATE", &["overwrite=false"]);
+        let create = self.request(Method::PUT, uri, Bytes::new(), true).await?;
+        let response = self.client.send(create).await.context(SendRequest)?;
+        let status = response.status();
+        if status != StatusCode::TEMPORARY_REDIRECT {
+            return Ok(response);
+        }
+
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| location.parse::<Uri>().ok())
+            .ok_or(WebHdfsError::MissingLocation { status })?;
+        // Data nodes are authorized by the delegation token of the redirect,
+        // while HttpFS gateways redirect to themselves.
+        let authenticate = location.authority() == self.authority.as_ref();
+        let write = self
+            .request(Method::PUT, location, request.body, authenticate)
+            .await?;
+        self.client.send(write).await.context(SendRequest)
+    }
+}
+
+impl Service<RequestWrapper> for WebHdfsSink {
+    type Response = Response<Body>;
+    type Error = WebHdfsError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestWrapper) -> Self::Future {
+        let sink = self.clone();
+        Box::pin(async move { sink.create(request).await })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RequestWrapper {
+    body: Bytes,
+    path: String,
+}
+
+impl RequestWrapper {
+    fn new(req: PartitionInnerBuffer<Vec<u8>, String>, settings: &RequestSettings) -> Self {
+        let (body, prefix) = req.into_parts();
+
+        let filename = {
+            let time = Utc::now().format(&settings.time_format);
+
+            if settings.append_uuid {
+                let uuid = Uuid::new_v4();
+                format!("{}-{}", time, uuid.to_hyphenated())
+            } else {
+                time.to_string()
+            }
+        };
+
+        let path = format!("{}{}.{}", prefix, filename, settings.extension);
+
+        debug!(message = "Sending events.", bytes = ?body.len(), path = ?path);
+
+        Self {
+            body: body.into(),
+            path,
+        }
+    }
+}
+
+// Settings required to produce a request that do not change per
+// request. All possible values are pre-computed for direct use in
+// producing a request.
+#[derive(Clone, Debug)]
+struct RequestSettings {
+    extension: String,
+    time_format: String,
+    append_uuid: bool,
+}
+
+impl RequestSettings {
+    fn new(config: &WebHdfsSinkConfig) -> Self {
+        Self {
+            extension: config.compression.extension().into(),
+            time_format: config
+                .filename_time_format
+                .clone()
+                .unwrap_or_else(|| "%s".into()),
+            append_uuid: config.filename_append_uuid.unwrap_or(true),
+        }
+    }
+}
+
+fn encode_event(
+    mut event: Event,
+    prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, String>> {
+    let key = prefix
+        .render_string(&event)
+        .map_err(|missing_keys| {
+            warn!(
+                message = "Keys do not exist on the event; dropping event.",
+                ?missing_keys,
+                rate_limit_secs = 30,
+            );
+        })
+        .ok()?;
+    encoding.apply_rules(&mut event);
+    let log = event.into_log();
+    let bytes = match encoding.codec() {
+        Encoding::Ndjson => serde_json::to_vec(&log)
+            .map(|mut b| {
+                b.push(b'\n');
+                b
+            })
+            .expect("Failed to encode event as json, this is a bug!"),
+        Encoding::Text => {
+            let mut bytes = log
+                .get(crate::config::log_schema().message_key())
+                .map(|v| v.as_bytes().to_vec())
+                .unwrap_or_default();
+            bytes.push(b'\n');
+            bytes
+        }
+    };
+
+    Some(PartitionInnerBuffer::new(bytes, key))
+}
+
+#[derive(Clone)]
+struct WebHdfsRetryLogic;
+
+impl RetryLogic for WebHdfsRetryLogic {
+    type Error = WebHdfsError;
+    type Response = Response<Body>;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        !matches!(error, WebHdfsError::GssapiUnsupported)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        let status = response.status();
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            StatusCode::NOT_IMPLEMENTED => {
+                RetryAction::DontRetry("endpoint not implemented".into())
+            }
+            _ if status.is_server_error() => RetryAction::Retry(format!("{}", status)),
+            _ if status.is_success() => RetryAction::Successful,
+            _ => RetryAction::DontRetry(format!("response status: {}", status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sinks::util::test::load_sink, test_util::next_addr};
+    use bytes::Buf;
+    use flate2::read::GzDecoder;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Server,
+    };
+    use std::io::Read;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebHdfsSinkConfig>();
+    }
+
+    #[test]
+    fn webhdfs_encode_event_partitions() {
+        crate::test_util::trace_init();
+
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "api");
+
+        let prefix = Template::try_from("/logs/{{ app }}/date=%F/").unwrap();
+        let encoded = encode_event(event.clone(), &prefix, &Encoding::Text.into()).unwrap();
+
+        let (bytes, key) = encoded.into_parts();
+        assert_eq!(&bytes[..], b"hello world\n");
+        assert!(key.starts_with("/logs/api/date="));
+
+        let prefix = Template::try_from("/logs/{{ missing }}/").unwrap();
+        assert!(encode_event(event, &prefix, &Encoding::Text.into()).is_none());
+    }
+
+    #[test]
+    fn webhdfs_build_paths() {
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], "/logs/".to_owned());
+        let settings = |uuid| {
+            RequestSettings::new(&WebHdfsSinkConfig {
+                filename_time_format: Some("date".into()),
+                filename_append_uuid: uuid,
+                ..default_config(Encoding::Ndjson)
+            })
+        };
+
+        let req = RequestWrapper::new(buf.clone(), &settings(Some(false)));
+        assert_eq!(req.path, "/logs/date.log.gz");
+
+        let req = RequestWrapper::new(buf, &settings(None));
+        assert!(req.path.starts_with("/logs/date-"));
+    }
+
+    #[test]
+    fn webhdfs_build_uris() {
+        let sink = WebHdfsSink::new(&WebHdfsSinkConfig {
+            endpoint: "http://namenode:9870/".into(),
+            user: Some("hdfs user".into()),
+            ..default_config(Encoding::Ndjson)
+        })
+        .unwrap();
+        assert_eq!(
+            sink.uri("logs/a b.log", "CREATE", &["overwrite=false"])
+                .to_string(),
+            "http://namenode:9870/webhdfs/v1/logs/a%20b.log?op=CREATE&overwrite=false&user.name=hdfs%20user"
+        );
+    }
+
+    #[cfg(not(feature = "gssapi"))]
+    #[test]
+    fn webhdfs_kerberos_requires_gssapi() {
+        let config = WebHdfsSinkConfig {
+            auth: Auth::Kerberos {
+                service_principal: None,
+            },
+            ..default_config(Encoding::Ndjson)
+        };
+        assert!(WebHdfsSink::new(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn webhdfs_follows_redirects() {
+        let in_addr = next_addr();
+        let (config, cx) = load_sink::<WebHdfsSinkConfig>(&format!(
+            r#"
+            endpoint = "http://{}"
+            prefix = "/logs/{{{{ app }}}}/"
+            user = "vector"
+            encoding.codec = "ndjson"
+            batch.max_events = 10
+            batch.timeout_secs = 1
+            "#,
+            in_addr
+        ))
+        .unwrap();
+
+        // Redirects the creations to the gateway itself, as HttpFS does.
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let service = make_service_fn(move |_| {
+            let tx = tx.clone();
+            async move {
+                Ok::<_, crate::Error>(service_fn(move |req: Request<Body>| {
+                    let tx = tx.clone();
+                    async move {
+                        let query = req.uri().query().unwrap_or_default().to_owned();
+                        let mut response = Response::new(Body::empty());
+                        if query.contains("data=true") {
+                            let path = req.uri().path().to_owned();
+                            let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                            tx.send((path, query, body)).unwrap();
+                            *response.status_mut() = StatusCode::CREATED;
+                        } else {
+                            let location = format!(
+                                "http://{}{}?{}&data=true",
+                                in_addr,
+                                req.uri().path(),
+                                query
+                            );
+                            *response.status_mut() = StatusCode::TEMPORARY_REDIRECT;
+                            response
+                                .headers_mut()
+                                .insert(header::LOCATION, location.parse().unwrap());
+                        }
+                        Ok::<_, crate::Error>(response)
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&in_addr).serve(service));
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let events = (0..4).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            event
+                .as_mut_log()
+                .insert("app", if i % 2 == 0 { "api" } else { "web" });
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut requests = Vec::new();
+        for _ in 0..2 {
+            let (path, query, body) = rx.recv().await.unwrap();
+            assert!(query.contains("op=CREATE"));
+            assert!(query.contains("user.name=vector"));
+
+            let mut lines = String::new();
+            GzDecoder::new(body.reader())
+                .read_to_string(&mut lines)
+                .unwrap();
+            requests.push((path, lines));
+        }
+        requests.sort();
+
+        assert!(requests[0].0.starts_with("/webhdfs/v1/logs/api/"));
+        assert_eq!(requests[0].1.lines().count(), 2);
+        assert!(requests[0].1.contains("line 2"));
+        assert!(requests[1].0.starts_with("/webhdfs/v1/logs/web/"));
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{
+    buffers::Acker,
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    dns,
+    event::Event,
+    http::Auth,
+    internal_events::{
+        ConnectionOpen, OpenGauge, WebSocketConnectionError, WebSocketConnectionEstablished,
+        WebSocketConnectionFailed, WebSocketConnectionShutdown, WebSocketEventSent,
+        WebSocketFieldMissing,
+    },
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::ExponentialBackoff,
+        StreamSink,
+    },
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError, TlsOptions, TlsSettings},
+};
+use async_trait::async_trait;
+use futures::{
+    future,
+    stream::{BoxStream, Peekable},
+    FutureExt, SinkExt, Stream, StreamExt,
+};
+use http::{
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+    Request,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{net::SocketAddr, pin::Pin, time::Duration};
+use tokio::{net::TcpStream, time::delay_for};
+use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+use url::Url;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid uri {:?}: {}", uri, source))]
+    InvalidUri {
+        uri: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("Unsupported uri scheme {:?}, expected ws or wss", scheme))]
+    UnsupportedScheme { scheme: String },
+    #[snafu(display("Missing host in uri {:?}", uri))]
+    MissingHost { uri: String },
+    #[snafu(display("{}: {}", source, name))]
+    InvalidHeaderName {
+        name: String,
+        source: header::InvalidHeaderName,
+    },
+    #[snafu(display("{}: {}", source, value))]
+    InvalidHeaderValue {
+        value: String,
+        source: header::InvalidHeaderValue,
+    },
+    #[snafu(display("Authorization header can not be used with defined auth options"))]
+    AuthorizationHeader,
+}
+
+#[derive(Debug, Snafu)]
+enum WebSocketError {
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Resolve { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    Connect { source: TlsError },
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Handshake error: {}", source))]
+    Handshake {
+        source: tokio_tungstenite::tungstenite::Error,
+    },
+    #[snafu(display("Send error: {}", source))]
+    SendFrame {
+        source: tokio_tungstenite::tungstenite::Error,
+    },
+    #[snafu(display("Receive error: {}", source))]
+    ReceiveFrame {
+        source: tokio_tungstenite::tungstenite::Error,
+    },
+    #[snafu(display("Connection closed by the server."))]
+    Closed,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketSinkConfig {
+    pub uri: String,
+    pub encoding: EncodingConfig<Encoding>,
+    pub auth: Option<Auth>,
+    pub headers: Option<IndexMap<String, String>>,
+    pub tls: Option<TlsOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<WebSocketSinkConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            uri: "ws://127.0.0.1:9000/endpoint".to_owned(),
+            encoding: Encoding::Json.into(),
+            auth: None,
+            headers: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SinkConfig for WebSocketSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let connector = self.connector()?;
+        let sink = WebSocketSink {
+            connector: connector.clone(),
+            acker: cx.acker(),
+            encoding: self.encoding.clone(),
+        };
+
+        Ok((
+            super::VectorSink::Stream(Box::new(sink)),
+            Box::pin(async move { connector.healthcheck().await }),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "websocket"
+    }
+}
+
+impl WebSocketSinkConfig {
+    fn connector(&self) -> crate::Result<WebSocketConnector> {
+        let uri = Url::parse(&self.uri).context(InvalidUri { uri: &self.uri })?;
+        let tls = match uri.scheme() {
+            "ws" => MaybeTlsSettings::Raw(()),
+            "wss" => MaybeTlsSettings::Tls(TlsSettings::from_options(&self.tls)?),
+            scheme => {
+                return Err(BuildError::UnsupportedScheme {
+                    scheme: scheme.to_owned(),
+                }
+                .into())
+            }
+        };
+        let host = uri
+            .host_str()
+            .ok_or_else(|| BuildError::MissingHost {
+                uri: self.uri.clone(),
+            })?
+            .to_owned();
+        let port = uri.port_or_known_default().unwrap_or(80);
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers.iter().flatten() {
+            if self.auth.is_some() && name.eq_ignore_ascii_case("Authorization") {
+                return Err(BuildError::AuthorizationHeader.into());
+            }
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| InvalidHeaderName { name })?;
+            let value = HeaderValue::from_bytes(value.as_bytes())
+                .with_context(|| InvalidHeaderValue { value })?;
+            headers.insert(name, value);
+        }
+        if let Some(auth) = &self.auth {
+            auth.apply_headers_map(&mut headers);
+        }
+
+        Ok(WebSocketConnector {
+            uri,
+            host,
+            port,
+            tls,
+            headers,
+        })
+    }
+}
+
+#[derive(Clone)]
+struct WebSocketConnector {
+    uri: Url,
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+    headers: HeaderMap,
+}
+
+impl WebSocketConnector {
+    fn fresh_backoff() -> ExponentialBackoff {
+        ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60))
+    }
+
+    async fn connect(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WebSocketError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(Resolve)?
+            .next()
+            .ok_or(WebSocketError::NoAddresses)?;
+        let stream = self
+            .tls
+            .connect(&self.host, &SocketAddr::new(ip, self.port))
+            .await
+            .context(Connect)?;
+
+        let mut request = Request::get(self.uri.as_str())
+            .body(())
+            .context(BuildRequest)?;
+        request.headers_mut().extend(self.headers.clone());
+        let (socket, _) = tokio_tungstenite::client_async(request, stream)
+            .await
+            .context(Handshake)?;
+        Ok(socket)
+    }
+
+    async fn connect_backoff(&self) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+        let mut backoff = Self::fresh_backoff();
+        loop {
+            match self.connect().await {
+                Ok(socket) => {
+                    emit!(WebSocketConnectionEstablished);
+                    return socket;
+                }
+                Err(error) => {
+                    emit!(WebSocketConnectionFailed { error });
+                    delay_for(backoff.next().unwrap()).await;
+                }
+            }
+        }
+    }
+
+    async fn healthcheck(&self) -> crate::Result<()> {
+        let mut socket = self.connect().await?;
+        let _ = socket.close(None).await;
+        Ok(())
+    }
+}
+
+struct WebSocketSink {
+    connector: WebSocketConnector,
+    acker: Acker,
+    encoding: EncodingConfig<Encoding>,
+}
+
+impl WebSocketSink {
+    /// Sends the frames over the socket until the input ends, answering
+    /// the pings of the server in between. A frame is only taken from the
+    /// input once sent, so it is sent again over the next connection if
+    /// this one fails.
+    async fn send_frames<S>(
+        &self,
+        socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        input: &mut Peekable<S>,
+    ) -> Result<(), WebSocketError>
+    where
+        S: Stream<Item = String> + Unpin,
+    {
+        loop {
+            tokio::select! {
+                frame = Pin::new(&mut *input).peek().map(|frame| frame.cloned()) => match frame {
+                    Some(frame) => {
+                        let byte_size = frame.len();
+                        socket.send(Message::text(frame)).await.context(SendFrame)?;
+                        input.next().await;
+                        self.acker.ack(1);
+                        emit!(WebSocketEventSent { byte_size });
+                    }
+                    None => return Ok(()),
+                },
+                message = socket.next() => match message {
+                    Some(Ok(Message::Close(_))) | None => return Err(WebSocketError::Closed),
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => return Err(WebSocketError::ReceiveFrame { source: error }),
+                },
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink for WebSocketSink {
+    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let acker = self.acker.clone();
+        let encoding = self.encoding.clone();
+        let mut input = input
+            .filter_map(move |event| {
+                let frame = encode_event(event, &encoding);
+                if frame.is_none() {
+                    acker.ack(1);
+                }
+                future::ready(frame)
+            })
+            .peekable();
+
+        // Only connect once there is something to send, and again
+        // whenever the connection is lost.
+        while Pin::new(&mut input).peek().await.is_some() {
+            let mut socket = self.connector.connect_backoff().await;
+            let _open_token = OpenGauge::new().open(|count| emit!(ConnectionOpen { count }));
+
+            match self.send_frames(&mut socket, &mut input).await {
+                Ok(()) => {
+                    let _ = socket.close(None).await;
+                }
+                Err(WebSocketError::Closed) => emit!(WebSocketConnectionShutdown),
+                Err(error) => emit!(WebSocketConnectionError { error }),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<String> {
+    encoding.apply_rules(&mut event);
+    match event {
+        Event::Log(log) => match encoding.codec() {
+            Encoding::Json => serde_json::to_string(&log)
+                .map_err(|error| error!(message = "Error encoding json.", %error))
+                .ok(),
+            Encoding::Text => {
+                let field = log_schema().message_key();
+                match log.get(field) {
+                    Some(value) => Some(value.to_string_lossy()),
+                    None => {
+                        emit!(WebSocketFieldMissing { field });
+                        None
+                    }
+                }
+            }
+        },
+        Event::Metric(metric) => match encoding.codec() {
+            Encoding::Json => serde_json::to_string(&metric)
+                .map_err(|error| error!(message = "Error encoding json.", %error))
+                .ok(),
+            Encoding::Text => Some(metric.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::SinkContext,
+        test_util::{next_addr, random_lines_with_stream, trace_init},
+    };
+    use tokio::{net::TcpListener, task::JoinHandle};
+    use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSinkConfig>();
+    }
+
+    fn config(uri: String) -> WebSocketSinkConfig {
+        toml::from_str(&format!(
+            r#"
+            uri = "{}"
+            encoding.codec = "text"
+            headers.X-Source = "vector"
+            "#,
+            uri
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        let mut config = config("http://localhost:9000".to_owned());
+        assert!(config.connector().is_err());
+
+        config.uri = "ws://localhost:9000".to_owned();
+        assert!(config.connector().is_ok());
+
+        config.auth = Some(Auth::Bearer {
+            token: "token".to_owned(),
+        });
+        config.headers = Some(
+            vec![("Authorization".to_owned(), "token".to_owned())]
+                .into_iter()
+                .collect(),
+        );
+        assert!(config.connector().is_err());
+    }
+
+    fn check_headers(request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        assert_eq!(request.headers()["x-source"], "vector");
+        Ok(response)
+    }
+
+    /// Accepts websocket connections on `addr` until `count` text frames
+    /// were received.
+    fn receive_frames(addr: SocketAddr, count: usize) -> JoinHandle<Vec<String>> {
+        tokio::spawn(async move {
+            let mut listener = TcpListener::bind(&addr).await.unwrap();
+            let mut frames = Vec::new();
+            while frames.len() < count {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = tokio_tungstenite::accept_hdr_async(stream, check_headers)
+                    .await
+                    .unwrap();
+                while frames.len() < count {
+                    match socket.next().await {
+                        Some(Ok(Message::Text(text))) => frames.push(text),
+                        Some(Ok(_)) => {}
+                        _ => break,
+                    }
+                }
+            }
+            frames
+        })
+    }
+
+    #[tokio::test]
+    async fn websocket_sends_frames() {
+        trace_init();
+
+        let addr = next_addr();
+        let receiver = receive_frames(addr, 100);
+        crate::test_util::wait_for_tcp(addr).await;
+
+        let config = config(format!("ws://{}", addr));
+        let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+        healthcheck.await.unwrap();
+
+        let (lines, events) = random_lines_with_stream(10, 100);
+        sink.run(events).await.unwrap();
+
+        assert_eq!(lines, receiver.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn websocket_retries_until_connected() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = config(format!("ws://{}", addr));
+        let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+        assert!(healthcheck.await.is_err());
+
+        let (lines, events) = random_lines_with_stream(10, 10);
+        let sending = tokio::spawn(sink.run(events));
+
+        delay_for(Duration::from_millis(100)).await;
+        let receiver = receive_frames(addr, 10);
+
+        sending.await.unwrap().unwrap();
+        assert_eq!(lines, receiver.await.unwrap());
+    }
+}
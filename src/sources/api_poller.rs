@@ -0,0 +1,417 @@
+use super::util::api_poller::{
+    oauth2::ClientCredentials, ApiPoller, Authorizer, JsonPath, JsonPathError, Pagination,
+    PollerSpec, RateLimitHeaders, TemplateError, UrlTemplate,
+};
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    http::{Auth, HttpClient},
+    shutdown::ShutdownSignal,
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{path::PathBuf, time::Duration};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid URL: {}", source))]
+    InvalidUrl { source: TemplateError },
+    #[snafu(display("Invalid records_path: {}", source))]
+    InvalidRecordsPath { source: JsonPathError },
+    #[snafu(display("Invalid header name {:?}: {}", name, source))]
+    InvalidHeaderName {
+        name: String,
+        source: http::header::InvalidHeaderName,
+    },
+    #[snafu(display("Invalid value for header {:?}: {}", name, source))]
+    InvalidHeaderValue {
+        name: String,
+        source: http::header::InvalidHeaderValue,
+    },
+    #[snafu(display(
+        "Pagination strategy `{}` requires either `{}` or a `{{{{ {} }}}}` placeholder in `url`",
+        strategy,
+        param,
+        placeholder
+    ))]
+    MissingPaginationParam {
+        strategy: &'static str,
+        param: &'static str,
+        placeholder: &'static str,
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ApiPollerConfig {
+    url: String,
+    #[serde(default)]
+    headers: IndexMap<String, String>,
+    #[serde(default = "default_records_path")]
+    records_path: String,
+    #[serde(default)]
+    pagination: PaginationConfig,
+    timestamp_field: Option<String>,
+    message_field: Option<String>,
+    #[serde(default)]
+    rate_limit: RateLimitConfig,
+    auth: Option<AuthConfig>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsOptions>,
+}
+
+pub fn default_records_path() -> String {
+    "$".to_owned()
+}
+
+pub fn default_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum PaginationConfig {
+    None,
+    LinkHeader,
+    LastRecord {
+        field: String,
+        param: Option<String>,
+    },
+    PageToken {
+        token_field: String,
+        token_param: Option<String>,
+        field: Option<String>,
+        param: Option<String>,
+    },
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        PaginationConfig::None
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct RateLimitConfig {
+    retry_after_header: String,
+    remaining_header: String,
+    reset_header: String,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            retry_after_header: "retry-after".to_owned(),
+            remaining_header: "x-ratelimit-remaining".to_owned(),
+            reset_header: "x-ratelimit-reset".to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum AuthConfig {
+    Basic {
+        user: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    Oauth2 {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
+}
+
+inventory::submit! {
+    SourceDescription::new::<ApiPollerConfig>("api_poller")
+}
+
+impl GenerateConfig for ApiPollerConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            url: "https://api.example.com/v1/events?since={{ cursor }}".to_owned(),
+            headers: IndexMap::new(),
+            records_path: "$.data[*]".to_owned(),
+            pagination: PaginationConfig::LastRecord {
+                field: "created_at".to_owned(),
+                param: None,
+            },
+            timestamp_field: Some("created_at".to_owned()),
+            message_field: None,
+            rate_limit: RateLimitConfig::default(),
+            auth: None,
+            interval_secs: default_interval_secs(),
+            data_dir: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "api_poller")]
+impl SourceConfig for ApiPollerConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let spec = self.spec()?;
+        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let authorizer = match &self.auth {
+            Some(AuthConfig::Oauth2 {
+                token_url,
+                client_id,
+                client_secret,
+                scopes,
+            }) => Some(Box::new(ClientCredentials::new(
+                client.clone(),
+                token_url.clone(),
+                client_id.clone(),
+                client_secret.clone(),
+                scopes.clone(),
+            )) as Box<dyn Authorizer>),
+            _ => None,
+        };
+
+        let poller = ApiPoller::new(client, spec, authorizer, data_dir);
+        Ok(Box::pin(poller.run(out, shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "api_poller"
+    }
+}
+
+impl ApiPollerConfig {
+    fn spec(&self) -> crate::Result<PollerSpec> {
+        let url = UrlTemplate::new(self.url.as_str()).context(InvalidUrl)?;
+        let records = JsonPath::parse(&self.records_path).context(InvalidRecordsPath)?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).context(InvalidHeaderName { name })?,
+                HeaderValue::from_str(value).context(InvalidHeaderValue { name })?,
+            );
+        }
+        match &self.auth {
+            Some(AuthConfig::Basic { user, password }) => Auth::Basic {
+                user: user.clone(),
+                password: password.clone(),
+            }
+            .apply_headers_map(&mut headers),
+            Some(AuthConfig::Bearer { token }) => Auth::Bearer {
+                token: token.clone(),
+            }
+            .apply_headers_map(&mut headers),
+            Some(AuthConfig::Oauth2 { .. }) | None => (),
+        }
+
+        let pagination = match &self.pagination {
+            PaginationConfig::None => Pagination::None,
+            PaginationConfig::LinkHeader => Pagination::LinkHeader,
+            PaginationConfig::LastRecord { field, param } => {
+                if param.is_none() && !url.uses("cursor") {
+                    return Err(BuildError::MissingPaginationParam {
+                        strategy: "last_record",
+                        param: "param",
+                        placeholder: "cursor",
+                    }
+                    .into());
+                }
+                Pagination::LastRecord {
+                    field: field.clone(),
+                    param: param.clone(),
+                    cursor_params: Vec::new(),
+                    initial_params: Vec::new(),
+                }
+            }
+            PaginationConfig::PageToken {
+                token_field,
+                token_param,
+                field,
+                param,
+            } => {
+                if token_param.is_none() && !url.uses("page_token") {
+                    return Err(BuildError::MissingPaginationParam {
+                        strategy: "page_token",
+                        param: "token_param",
+                        placeholder: "page_token",
+                    }
+                    .into());
+                }
+                Pagination::PageToken {
+                    token_field: token_field.clone(),
+                    token_param: token_param.clone(),
+                    field: field.clone(),
+                    param: param.clone(),
+                }
+            }
+        };
+
+        let header_name = |name: &String| {
+            HeaderName::from_bytes(name.as_bytes()).context(InvalidHeaderName { name })
+        };
+        let rate_limit = RateLimitHeaders {
+            retry_after: header_name(&self.rate_limit.retry_after_header)?,
+            remaining: vec![header_name(&self.rate_limit.remaining_header)?],
+            reset: vec![header_name(&self.rate_limit.reset_header)?],
+        };
+
+        Ok(PollerSpec {
+            source_type: "api_poller",
+            url,
+            headers,
+            records,
+            pagination,
+            rate_limit,
+            timestamp_field: self.timestamp_field.clone(),
+            message_field: self.message_field.clone(),
+            interval: Duration::from_secs(self.interval_secs),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, event::Value, test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Request, Response, Server,
+    };
+    use tempfile::tempdir;
+    use tokio::time::{delay_for, timeout};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ApiPollerConfig>();
+    }
+
+    #[test]
+    fn requires_cursor_param() {
+        let mut config: ApiPollerConfig = toml::from_str(
+            r#"
+            url = "https://api.example.com/v1/events"
+            pagination.strategy = "last_record"
+            pagination.field = "id"
+            "#,
+        )
+        .unwrap();
+        assert!(config.spec().is_err());
+
+        config.url = "https://api.example.com/v1/events?after={{ cursor }}".to_owned();
+        assert!(config.spec().is_ok());
+    }
+
+    #[tokio::test]
+    async fn polls_with_oauth2() {
+        let addr = next_addr();
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|request: Request<Body>| async move {
+                let body = match (request.uri().path(), request.uri().query()) {
+                    ("/oauth/token", _) => {
+                        // "client:secret"
+                        assert_eq!(
+                            request.headers()["authorization"],
+                            "Basic Y2xpZW50OnNlY3JldA=="
+                        );
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        assert_eq!(
+                            &body[..],
+                            b"grant_type=client_credentials&scope=events%3Aread"
+                        );
+                        r#"{"access_token":"token","token_type":"bearer","expires_in":3600}"#
+                    }
+                    ("/v1/events", Some("limit=2")) => {
+                        assert_eq!(request.headers()["authorization"], "Bearer token");
+                        assert_eq!(request.headers()["x-tenant"], "acme");
+                        r#"{"data":[{"seq":1,"msg":"first","at":"2021-02-01T10:00:00Z"},{"seq":2,"msg":"second","at":"2021-02-01T10:00:01Z"}]}"#
+                    }
+                    ("/v1/events", Some("after=2&limit=2")) => r#"{"data":[]}"#,
+                    (path, query) => panic!("Unexpected request for {} {:?}", path, query),
+                };
+                Ok::<_, Error>(Response::new(Body::from(body)))
+            }))
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let data_dir = tempdir().unwrap();
+        let mut config: ApiPollerConfig = toml::from_str(&format!(
+            r#"
+            url = "http://{addr}/v1/events?after={{{{ cursor }}}}&limit=2"
+            headers.X-Tenant = "acme"
+            records_path = "$.data[*]"
+            timestamp_field = "at"
+            message_field = "msg"
+
+            [pagination]
+            strategy = "last_record"
+            field = "seq"
+
+            [auth]
+            strategy = "oauth2"
+            token_url = "http://{addr}/oauth/token"
+            client_id = "client"
+            client_secret = "secret"
+            scopes = ["events:read"]
+            "#,
+            addr = addr
+        ))
+        .unwrap();
+        config.data_dir = Some(data_dir.path().to_path_buf());
+
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = config
+            .build("poller", &GlobalOptions::default(), shutdown, tx)
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        delay_for(Duration::from_millis(500)).await;
+        drop(trigger);
+
+        let events = timeout(Duration::from_secs(1), rx.collect().compat())
+            .await
+            .expect("Unclosed channel")
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "second".into());
+        assert_eq!(log["seq"], Value::Integer(2));
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp("2021-02-01T10:00:01Z".parse().unwrap())
+        );
+        assert_eq!(log[log_schema().source_type_key()], "api_poller".into());
+    }
+}
@@ -0,0 +1,204 @@
+use super::util::api_poller::{ApiPoller, JsonPath, Pagination, PollerSpec, RateLimitHeaders};
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    http::HttpClient,
+    shutdown::ShutdownSignal,
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use http::{header, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{path::PathBuf, time::Duration};
+use url::Url;
+
+/// The largest page the log events API returns when using checkpoints.
+const PAGE_SIZE: &str = "100";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("Invalid API token: {}", source))]
+    InvalidToken {
+        source: http::header::InvalidHeaderValue,
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Auth0LogsConfig {
+    endpoint: String,
+    token: String,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsOptions>,
+}
+
+pub fn default_interval_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<Auth0LogsConfig>("auth0_logs")
+}
+
+impl GenerateConfig for Auth0LogsConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoint: "https://example.auth0.com".to_owned(),
+            token: "${AUTH0_API_TOKEN}".to_owned(),
+            interval_secs: default_interval_secs(),
+            data_dir: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "auth0_logs")]
+impl SourceConfig for Auth0LogsConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let poller = ApiPoller::new(client, self.spec()?, None, data_dir);
+        Ok(Box::pin(poller.run(out, shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "auth0_logs"
+    }
+}
+
+impl Auth0LogsConfig {
+    fn spec(&self) -> crate::Result<PollerSpec> {
+        let endpoint = format!("{}/api/v2/logs", self.endpoint.trim_end_matches('/'));
+        let url = Url::parse(&endpoint).context(InvalidEndpoint { endpoint })?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", self.token)).context(InvalidToken)?,
+        );
+
+        Ok(PollerSpec {
+            source_type: "auth0_logs",
+            url: url.into(),
+            headers,
+            records: JsonPath::root(),
+            // Without a checkpoint we start at the most recent log event,
+            // afterwards the API returns the events following the given
+            // `log_id` in chronological order.
+            pagination: Pagination::LastRecord {
+                field: "log_id".to_owned(),
+                param: Some("from".to_owned()),
+                cursor_params: vec![("take".to_owned(), PAGE_SIZE.to_owned())],
+                initial_params: vec![
+                    ("sort".to_owned(), "date:-1".to_owned()),
+                    ("per_page".to_owned(), "1".to_owned()),
+                ],
+            },
+            rate_limit: RateLimitHeaders::default(),
+            timestamp_field: Some("date".to_owned()),
+            message_field: Some("description".to_owned()),
+            interval: Duration::from_secs(self.interval_secs),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Request, Response, Server,
+    };
+    use tempfile::tempdir;
+    use tokio::time::{delay_for, timeout};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<Auth0LogsConfig>();
+    }
+
+    #[tokio::test]
+    async fn pulls_logs() {
+        let addr = next_addr();
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|request: Request<Body>| async move {
+                assert_eq!(request.headers()["authorization"], "Bearer secret");
+                let body = match request.uri().query() {
+                    Some("sort=date%3A-1&per_page=1") => {
+                        r#"[{"log_id":"1","type":"s","description":"Success Login","date":"2021-02-01T10:00:00.000Z"}]"#
+                    }
+                    Some("from=1&take=100") => {
+                        r#"[{"log_id":"2","type":"f","description":"Wrong password","date":"2021-02-01T10:01:00.000Z"}]"#
+                    }
+                    _ => "[]",
+                };
+                Ok::<_, Error>(Response::new(Body::from(body)))
+            }))
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let data_dir = tempdir().unwrap();
+        let config = Auth0LogsConfig {
+            endpoint: format!("http://{}", addr),
+            token: "secret".to_owned(),
+            interval_secs: 1,
+            data_dir: Some(data_dir.path().to_path_buf()),
+            tls: None,
+        };
+
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = config
+            .build("auth0", &GlobalOptions::default(), shutdown, tx)
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        delay_for(Duration::from_millis(500)).await;
+        drop(trigger);
+
+        let events = timeout(Duration::from_secs(1), rx.collect().compat())
+            .await
+            .expect("Unclosed channel")
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "Success Login".into()
+        );
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "Wrong password".into());
+        assert_eq!(log["log_id"], "2".into());
+        assert_eq!(log[log_schema().source_type_key()], "auth0_logs".into());
+    }
+}
@@ -29,6 +29,15 @@ pub enum RequestError {
         source: std::io::Error,
         request_id: String,
     },
+    #[snafu(display(
+        "Could not parse CloudWatch Logs message from incoming request {}: {}",
+        request_id,
+        source
+    ))]
+    ParseCloudwatchLogs {
+        source: serde_json::error::Error,
+        request_id: String,
+    },
     #[snafu(display("Could not decode record for request {}: {}", request_id, source))]
     Decode {
         source: std::io::Error,
@@ -63,6 +72,7 @@ impl RequestError {
             Parse { .. } => StatusCode::UNAUTHORIZED,
             UnsupportedEncoding { .. } => StatusCode::BAD_REQUEST,
             ParseRecords { .. } => StatusCode::BAD_REQUEST,
+            ParseCloudwatchLogs { .. } => StatusCode::BAD_REQUEST,
             Decode { .. } => StatusCode::BAD_REQUEST,
             ShuttingDown { .. } => StatusCode::SERVICE_UNAVAILABLE,
             UnsupportedProtocolVersion { .. } => StatusCode::BAD_REQUEST,
@@ -77,6 +87,7 @@ impl RequestError {
             Parse { ref request_id, .. } => Some(request_id),
             UnsupportedEncoding { ref request_id, .. } => Some(request_id),
             ParseRecords { ref request_id, .. } => Some(request_id),
+            ParseCloudwatchLogs { ref request_id, .. } => Some(request_id),
             Decode { ref request_id, .. } => Some(request_id),
             ShuttingDown { ref request_id, .. } => Some(request_id),
             UnsupportedProtocolVersion { .. } => None,
@@ -1,5 +1,9 @@
-use super::errors::{ParseRecords, RequestError};
-use super::models::{EncodedFirehoseRecord, FirehoseRequest, FirehoseResponse};
+use super::errors::{ParseCloudwatchLogs, ParseRecords, RequestError};
+use super::models::{
+    CloudwatchLogsMessage, CloudwatchLogsMessageType, EncodedFirehoseRecord, FirehoseRequest,
+    FirehoseResponse,
+};
+use super::RecordFormat;
 use crate::{config::log_schema, event::Event, Pipeline};
 use bytes::Bytes;
 use chrono::Utc;
@@ -15,13 +19,16 @@ pub async fn firehose(
     request_id: String,
     source_arn: String,
     request: FirehoseRequest,
+    record_format: RecordFormat,
     out: Pipeline,
 ) -> Result<impl warp::Reply, reject::Rejection> {
-    let events = parse_records(request, request_id.as_str(), source_arn.as_str())
-        .with_context(|| ParseRecords {
-            request_id: request_id.clone(),
-        })
-        .map_err(reject::custom)?;
+    let events = parse_records(
+        request,
+        record_format,
+        request_id.as_str(),
+        source_arn.as_str(),
+    )
+    .map_err(reject::custom)?;
 
     let request_id = request_id.clone();
     out.send_all(futures01::stream::iter_ok(events))
@@ -50,14 +57,17 @@ pub async fn firehose(
 /// Parses out events from the FirehoseRequest
 fn parse_records(
     request: FirehoseRequest,
+    record_format: RecordFormat,
     request_id: &str,
     source_arn: &str,
-) -> std::io::Result<Vec<Event>> {
-    request
-        .records
-        .iter()
-        .map(|record| {
-            decode_record(record).map(|record| {
+) -> Result<Vec<Event>, RequestError> {
+    let mut events = Vec::new();
+
+    for record in request.records.iter() {
+        let record = decode_record(record).context(ParseRecords { request_id })?;
+
+        match record_format {
+            RecordFormat::Raw => {
                 let mut event = Event::new_empty_log();
                 let log = event.as_mut_log();
 
@@ -66,10 +76,38 @@ fn parse_records(
                 log.insert("request_id", request_id.to_string());
                 log.insert("source_arn", source_arn.to_string());
 
-                event
-            })
-        })
-        .collect()
+                events.push(event);
+            }
+            RecordFormat::CloudwatchLogs => {
+                let message: CloudwatchLogsMessage =
+                    serde_json::from_slice(&record).context(ParseCloudwatchLogs { request_id })?;
+
+                // Control messages only check that the delivery stream is reachable
+                if message.message_type == CloudwatchLogsMessageType::ControlMessage {
+                    continue;
+                }
+
+                for log_event in message.log_events {
+                    let mut event = Event::new_empty_log();
+                    let log = event.as_mut_log();
+
+                    log.insert(log_schema().message_key(), log_event.message);
+                    log.insert(log_schema().timestamp_key(), log_event.timestamp);
+                    log.insert("id", log_event.id);
+                    log.insert("log_group", message.log_group.clone());
+                    log.insert("log_stream", message.log_stream.clone());
+                    log.insert("owner", message.owner.clone());
+                    log.insert("subscription_filters", message.subscription_filters.clone());
+                    log.insert("request_id", request_id.to_string());
+                    log.insert("source_arn", source_arn.to_string());
+
+                    events.push(event);
+                }
+            }
+        }
+    }
+
+    Ok(events)
 }
 
 /// Decodes a Firehose record from its base64 gzip format
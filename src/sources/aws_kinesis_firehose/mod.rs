@@ -17,9 +17,27 @@ mod models;
 pub struct AwsKinesisFirehoseConfig {
     address: SocketAddr,
     access_key: Option<String>,
+    #[serde(default)]
+    record_format: RecordFormat,
     tls: Option<TlsConfig>,
 }
 
+/// How the data of the incoming records is turned into events
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// One event per record, with the decoded data as message
+    Raw,
+    /// One event per log event of the CloudWatch Logs subscription message in each record
+    CloudwatchLogs,
+}
+
+impl Default for RecordFormat {
+    fn default() -> Self {
+        Self::Raw
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "aws_kinesis_firehose")]
 impl SourceConfig for AwsKinesisFirehoseConfig {
@@ -30,7 +48,7 @@ impl SourceConfig for AwsKinesisFirehoseConfig {
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
-        let svc = filters::firehose(self.access_key.clone(), out);
+        let svc = filters::firehose(self.access_key.clone(), self.record_format, out);
 
         let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
         let listener = tls.bind(&self.address).await?;
@@ -70,6 +88,7 @@ impl GenerateConfig for AwsKinesisFirehoseConfig {
         toml::Value::try_from(Self {
             address: "0.0.0.0:443".parse().unwrap(),
             access_key: None,
+            record_format: RecordFormat::Raw,
             tls: None,
         })
         .unwrap()
@@ -84,7 +103,7 @@ mod tests {
         log_event,
         test_util::{collect_ready, next_addr, wait_for_tcp},
     };
-    use chrono::{DateTime, SubsecRound, Utc};
+    use chrono::{DateTime, SubsecRound, TimeZone, Utc};
     use flate2::{read::GzEncoder, Compression};
     use futures01::sync::mpsc;
     use pretty_assertions::assert_eq;
@@ -98,7 +117,10 @@ mod tests {
         crate::test_util::test_generate_config::<AwsKinesisFirehoseConfig>();
     }
 
-    async fn source(access_key: Option<String>) -> (mpsc::Receiver<Event>, SocketAddr) {
+    async fn source(
+        access_key: Option<String>,
+        record_format: RecordFormat,
+    ) -> (mpsc::Receiver<Event>, SocketAddr) {
         let (sender, recv) = Pipeline::new_test();
         let address = next_addr();
         tokio::spawn(async move {
@@ -106,6 +128,7 @@ mod tests {
                 address,
                 tls: None,
                 access_key,
+                record_format,
             }
             .build(
                 "default",
@@ -214,7 +237,7 @@ mod tests {
 }
 "#;
 
-        let (rx, addr) = source(None).await;
+        let (rx, addr) = source(None, RecordFormat::Raw).await;
 
         let source_arn = "arn:aws:firehose:us-east-1:111111111111:deliverystream/test";
         let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
@@ -275,7 +298,7 @@ mod tests {
 }
 "#;
 
-        let (rx, addr) = source(None).await;
+        let (rx, addr) = source(None, RecordFormat::Raw).await;
 
         let source_arn = "arn:aws:firehose:us-east-1:111111111111:deliverystream/test";
         let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
@@ -311,7 +334,7 @@ mod tests {
 
     #[tokio::test]
     async fn aws_kinesis_firehose_rejects_bad_access_key() {
-        let (_rx, addr) = source(Some("an access key".to_string())).await;
+        let (_rx, addr) = source(Some("an access key".to_string()), RecordFormat::Raw).await;
 
         let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
 
@@ -331,4 +354,117 @@ mod tests {
         let response: models::FirehoseResponse = res.json().await.unwrap();
         assert_eq!(response.request_id, request_id);
     }
+
+    #[tokio::test]
+    async fn aws_kinesis_firehose_forwards_cloudwatch_logs_events() {
+        let record = r#"
+{
+  "messageType": "DATA_MESSAGE",
+  "owner": "071959437513",
+  "logGroup": "/jesse/test",
+  "logStream": "test",
+  "subscriptionFilters": [
+    "Destination"
+  ],
+  "logEvents": [
+    {
+      "id": "35683658089614582423604394983260738922885519999578275840",
+      "timestamp": 1600110569039,
+      "message": "first"
+    },
+    {
+      "id": "35683658089659183914001456229543810359430816722590236673",
+      "timestamp": 1600110569041,
+      "message": "second"
+    }
+  ]
+}
+"#;
+        let control = r#"
+{
+  "messageType": "CONTROL_MESSAGE",
+  "owner": "CloudwatchLogs",
+  "logGroup": "",
+  "logStream": "",
+  "subscriptionFilters": [],
+  "logEvents": [
+    {
+      "id": "",
+      "timestamp": 1600110003794,
+      "message": "CWL CONTROL MESSAGE: Checking health of destination Firehose."
+    }
+  ]
+}
+"#;
+
+        let (rx, addr) = source(None, RecordFormat::CloudwatchLogs).await;
+
+        let source_arn = "arn:aws:firehose:us-east-1:111111111111:deliverystream/test";
+        let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
+
+        let res = send(
+            addr,
+            Utc::now(),
+            vec![control, record],
+            None,
+            request_id,
+            source_arn,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(200, res.status().as_u16());
+
+        let events = collect_ready(rx).await.unwrap();
+        assert_eq!(
+            events,
+            vec![
+                log_event! {
+                    "timestamp" => Utc.timestamp_millis(1600110569039),
+                    "message" => "first",
+                    "id" => "35683658089614582423604394983260738922885519999578275840",
+                    "log_group" => "/jesse/test",
+                    "log_stream" => "test",
+                    "owner" => "071959437513",
+                    "subscription_filters" => vec!["Destination"],
+                    "request_id" => request_id,
+                    "source_arn" => source_arn,
+                },
+                log_event! {
+                    "timestamp" => Utc.timestamp_millis(1600110569041),
+                    "message" => "second",
+                    "id" => "35683658089659183914001456229543810359430816722590236673",
+                    "log_group" => "/jesse/test",
+                    "log_stream" => "test",
+                    "owner" => "071959437513",
+                    "subscription_filters" => vec!["Destination"],
+                    "request_id" => request_id,
+                    "source_arn" => source_arn,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn aws_kinesis_firehose_rejects_invalid_cloudwatch_logs_message() {
+        let (_rx, addr) = source(None, RecordFormat::CloudwatchLogs).await;
+
+        let request_id = "e17265d6-97af-4938-982e-90d5614c4242";
+
+        let res = send(
+            addr,
+            Utc::now(),
+            vec!["not a subscription message"],
+            None,
+            request_id,
+            "",
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(400, res.status().as_u16());
+
+        let response: models::FirehoseResponse = res.json().await.unwrap();
+        assert_eq!(response.request_id, request_id);
+    }
 }
@@ -24,6 +24,39 @@ pub struct EncodedFirehoseRecord {
     pub data: String,
 }
 
+/// Represents the message delivered to a Firehose delivery stream by an AWS CloudWatch Logs
+/// subscription, gzip'd in the record data
+///
+/// https://docs.aws.amazon.com/AmazonCloudWatch/latest/logs/SubscriptionFilters.html#FirehoseExample
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudwatchLogsMessage {
+    pub message_type: CloudwatchLogsMessageType,
+    pub owner: String,
+    pub log_group: String,
+    pub log_stream: String,
+    pub subscription_filters: Vec<String>,
+    pub log_events: Vec<CloudwatchLogEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CloudwatchLogsMessageType {
+    /// Sent by CloudWatch Logs to check that the destination is reachable
+    ControlMessage,
+    DataMessage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloudwatchLogEvent {
+    pub id: String,
+
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+
+    pub message: String,
+}
+
 /// Represents an AWS Kinesis Firehose response
 ///
 /// Represents protocol v1.0 (the only protocol as of writing)
@@ -54,6 +54,9 @@ pub struct DockerLogsConfig {
     include_containers: Option<Vec<String>>, // Starts with actually, not include
     include_labels: Option<Vec<String>>,
     include_images: Option<Vec<String>>,
+    exclude_containers: Option<Vec<String>>, // Starts with actually, not exclude
+    exclude_labels: Option<Vec<String>>,
+    exclude_images: Option<Vec<String>>,
     partial_event_marker_field: Option<String>,
     auto_partial_merge: bool,
     multiline: Option<MultilineConfig>,
@@ -66,6 +69,9 @@ impl Default for DockerLogsConfig {
             include_containers: None,
             include_labels: None,
             include_images: None,
+            exclude_containers: None,
+            exclude_labels: None,
+            exclude_images: None,
             partial_event_marker_field: Some(event::PARTIAL.to_string()),
             auto_partial_merge: true,
             multiline: None,
@@ -97,6 +103,62 @@ impl DockerLogsConfig {
         }
     }
 
+    /// True if the container matches any of the exclude filters. Docker only
+    /// supports filtering on what to include, so these are applied here.
+    fn container_excluded<'a>(
+        &self,
+        id: &str,
+        names: impl IntoIterator<Item = &'a str>,
+        image: Option<&str>,
+        label: impl Fn(&str) -> Option<&'a str>,
+    ) -> bool {
+        let container_flag = self
+            .exclude_containers
+            .as_ref()
+            .map(|exclude_containers| {
+                let mut names = names.into_iter();
+                exclude_containers
+                    .iter()
+                    .any(|exclude| id.starts_with(exclude))
+                    || names.any(|name| {
+                        exclude_containers
+                            .iter()
+                            .any(|exclude| name.starts_with(exclude))
+                    })
+            })
+            .unwrap_or(false);
+
+        // Labels are given as either `key` or `key=value`, the same as for
+        // the `label` filter of Docker.
+        let label_flag = self
+            .exclude_labels
+            .as_ref()
+            .map(|exclude_labels| {
+                exclude_labels.iter().any(|exclude| {
+                    let mut parts = exclude.splitn(2, '=');
+                    let key = parts.next().unwrap_or_default();
+                    match (label(key), parts.next()) {
+                        (Some(value), Some(expected)) => value == expected,
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    }
+                })
+            })
+            .unwrap_or(false);
+
+        // An image without a tag or digest matches every tag of that image.
+        let image_flag = match (&self.exclude_images, image) {
+            (Some(exclude_images), Some(image)) => exclude_images.iter().any(|exclude| {
+                image == exclude
+                    || (image.starts_with(exclude.as_str())
+                        && matches!(image[exclude.len()..].chars().next(), Some(':') | Some('@')))
+            }),
+            _ => false,
+        };
+
+        container_flag || label_flag || image_flag
+    }
+
     fn with_empty_partial_event_marker_field_as_none(mut self) -> Self {
         if let Some(val) = &self.partial_event_marker_field {
             if val.is_empty() {
@@ -376,6 +438,7 @@ impl DockerLogsSource {
                 let id = container.id.unwrap();
                 let names = container.names.unwrap();
                 let image = container.image.unwrap();
+                let labels = container.labels.unwrap_or_default();
 
                 trace!(message = "Found already running container.", id = %id, names = ?names);
 
@@ -383,18 +446,21 @@ impl DockerLogsSource {
                     return;
                 }
 
-                if !self.esb.core.config.container_name_included(
-                    id.as_str(),
-                    names.iter().map(|s| {
-                        // In this case bollard / shiplift gives names with starting '/' so it needs to be removed.
-                        let s = s.as_str();
-                        if s.starts_with('/') {
-                            s.split_at('/'.len_utf8()).1
-                        } else {
-                            s
-                        }
-                    }),
-                ) {
+                // In this case bollard / shiplift gives names with starting '/' so it needs to be removed.
+                let names = names.iter().map(|s| s.trim_start_matches('/'));
+
+                if !self
+                    .esb
+                    .core
+                    .config
+                    .container_name_included(id.as_str(), names.clone())
+                    || self.esb.core.config.container_excluded(
+                        id.as_str(),
+                        names,
+                        Some(image.as_str()),
+                        |key| labels.get(key).map(|s| s.as_str()),
+                    )
+                {
                     trace!(message = "Container excluded.", id = %id);
                     return;
                 }
@@ -476,7 +542,15 @@ impl DockerLogsSource {
                                             attributes.get("image").map(|s| s.as_str()),
                                         );
 
-                                        if include_name && self_check {
+                                        // Labels are part of the attributes of container events.
+                                        let excluded = self.esb.core.config.container_excluded(
+                                            id.as_str(),
+                                            attributes.get("name").map(|s| s.as_str()),
+                                            attributes.get("image").map(|s| s.as_str()),
+                                            |key| attributes.get(key).map(|s| s.as_str()),
+                                        );
+
+                                        if include_name && self_check && !excluded {
                                             self.containers.insert(id.clone(), self.esb.start(id, None));
                                         }
                                     }
@@ -595,8 +669,9 @@ impl EventStreamBuilder {
             container_id: info.id.as_str()
         });
 
-        // Create event streamer
-        let mut partial_event_merge_state = None;
+        // Create event streamer. Docker splits stdout and stderr lines
+        // independently, so each stream is merged on its own.
+        let mut partial_event_merge_states = HashMap::new();
 
         let events_stream = stream
             .map(|value| {
@@ -605,7 +680,7 @@ impl EventStreamBuilder {
                         message,
                         self.core.config.partial_event_marker_field.clone(),
                         self.core.config.auto_partial_merge,
-                        &mut partial_event_merge_state,
+                        &mut partial_event_merge_states,
                     )),
                     Err(error) => {
                         // On any error, restart connection
@@ -773,7 +848,7 @@ impl ContainerLogInfo {
         log_output: LogOutput,
         partial_event_marker_field: Option<String>,
         auto_partial_merge: bool,
-        partial_event_merge_state: &mut Option<LogEventMergeState>,
+        partial_event_merge_states: &mut HashMap<Bytes, LogEventMergeState>,
     ) -> Option<Event> {
         let (stream, mut bytes_message) = match log_output {
             LogOutput::StdErr { message } => (STDERR.clone(), message),
@@ -853,7 +928,7 @@ impl ContainerLogInfo {
             log_event.insert(log_schema().message_key(), bytes_message);
 
             // Stream we got the message from.
-            log_event.insert(STREAM, stream);
+            log_event.insert(STREAM, stream.clone());
 
             // Timestamp of the event.
             if let Some(timestamp) = timestamp {
@@ -894,11 +969,12 @@ impl ContainerLogInfo {
                 // message has to be merged into that existing state.
                 // Otherwise, create a new partial event merge state with the
                 // current message being the initial one.
-                if let Some(partial_event_merge_state) = partial_event_merge_state {
+                if let Some(partial_event_merge_state) = partial_event_merge_states.get_mut(&stream)
+                {
                     partial_event_merge_state
                         .merge_in_next_event(log_event, &[log_schema().message_key().to_string()]);
                 } else {
-                    *partial_event_merge_state = Some(LogEventMergeState::new(log_event));
+                    partial_event_merge_states.insert(stream, LogEventMergeState::new(log_event));
                 };
                 return None;
             };
@@ -907,7 +983,7 @@ impl ContainerLogInfo {
             // state from before, the current event must be a final event, that
             // would give us a merged event we can return.
             // Otherwise it's just a regular event that we return as-is.
-            match partial_event_merge_state.take() {
+            match partial_event_merge_states.remove(&stream) {
                 Some(partial_event_merge_state) => partial_event_merge_state
                     .merge_in_final_event(log_event, &[log_schema().message_key().to_string()]),
                 None => log_event,
@@ -1021,6 +1097,97 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<DockerLogsConfig>();
     }
+
+    fn container_excluded(
+        config: &DockerLogsConfig,
+        id: &str,
+        name: &str,
+        image: &str,
+        labels: &HashMap<String, String>,
+    ) -> bool {
+        config.container_excluded(id, Some(name), Some(image), |key| {
+            labels.get(key).map(|s| s.as_str())
+        })
+    }
+
+    #[test]
+    fn excludes_containers() {
+        let config = DockerLogsConfig {
+            exclude_containers: Some(vec!["sidecar".to_owned()]),
+            exclude_labels: Some(vec!["vector.exclude".to_owned(), "tier=debug".to_owned()]),
+            exclude_images: Some(vec!["busybox".to_owned()]),
+            ..DockerLogsConfig::default()
+        };
+        let labels: HashMap<String, String> = vec![("tier".to_owned(), "web".to_owned())]
+            .into_iter()
+            .collect();
+        let excluded = |id: &str, name: &str, image: &str, labels: &HashMap<String, String>| {
+            container_excluded(&config, id, name, image, labels)
+        };
+        let with_label = |key: &str, value: &str| {
+            let mut labels = labels.clone();
+            labels.insert(key.to_owned(), value.to_owned());
+            labels
+        };
+
+        assert!(!excluded("0123", "app", "nginx:1.19", &labels));
+        assert!(excluded("0123", "sidecar-1", "nginx:1.19", &labels));
+        assert!(excluded("0123", "app", "busybox", &labels));
+        assert!(excluded("0123", "app", "busybox:1.32", &labels));
+        assert!(!excluded("0123", "app", "busybox-extras", &labels));
+        assert!(excluded(
+            "0123",
+            "app",
+            "nginx",
+            &with_label("vector.exclude", "")
+        ));
+        assert!(excluded(
+            "0123",
+            "app",
+            "nginx",
+            &with_label("tier", "debug")
+        ));
+    }
+
+    #[test]
+    fn merges_partial_events_per_stream() {
+        let created = DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut info = ContainerLogInfo::new(
+            ContainerId::new("0123".to_owned()),
+            ContainerMetadata {
+                labels: Vec::new(),
+                name: "app".into(),
+                image: "busybox".into(),
+                created_at: created,
+            },
+            created,
+        );
+        let mut states = HashMap::new();
+        let mut event = |output| info.new_event(output, None, true, &mut states);
+
+        assert!(event(LogOutput::StdOut {
+            message: "2020-01-01T00:00:01Z hello ".into()
+        })
+        .is_none());
+        // A complete line on the other stream is not merged with the partial one.
+        let stderr = event(LogOutput::StdErr {
+            message: "2020-01-01T00:00:02Z oops\n".into(),
+        })
+        .unwrap();
+        let stdout = event(LogOutput::StdOut {
+            message: "2020-01-01T00:00:03Z world\n".into(),
+        })
+        .unwrap();
+
+        assert_eq!(stderr.as_log()[log_schema().message_key()], "oops".into());
+        assert_eq!(
+            stdout.as_log()[log_schema().message_key()],
+            "hello world".into()
+        );
+        assert_eq!(stdout.as_log()[STREAM], "stdout".into());
+    }
 }
 
 #[cfg(all(test, feature = "docker-logs-integration-tests"))]
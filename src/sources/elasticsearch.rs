@@ -0,0 +1,700 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    http::{Auth, HttpClient},
+    internal_events::{
+        ElasticSearchCheckpointError, ElasticSearchDocumentsReceived, ElasticSearchReadError,
+    },
+    shutdown::ShutdownSignal,
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{compat::Sink01CompatExt, future, stream, FutureExt, SinkExt, StreamExt};
+use futures01::Sink;
+use http::{header, Method, Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{sync::Mutex, time::delay_for};
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+const CHECKPOINT_TMP_FILENAME: &str = "checkpoint.json.tmp";
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`slices` must be at least 1"))]
+    NoSlices,
+    #[snafu(display("`batch_size` must be at least 1"))]
+    EmptyBatches,
+}
+
+#[derive(Debug, Snafu)]
+enum ReadError {
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Request failed: {}", source))]
+    SendRequest { source: crate::http::HttpError },
+    #[snafu(display("Failed to read response body: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Unexpected response status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[snafu(display("Failed to parse response: {}", source))]
+    ParseBody { source: serde_json::Error },
+    #[snafu(display("The response has no {}", field))]
+    MissingField { field: &'static str },
+}
+
+impl ReadError {
+    fn is_not_found(&self) -> bool {
+        matches!(self, Self::UnexpectedStatus { status, .. } if *status == StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Search a point in time, paging with `search_after`.
+    PointInTime,
+    /// Use the scroll API, for clusters without point in time search.
+    Scroll,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::PointInTime
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ElasticSearchConfig {
+    endpoint: String,
+    index: String,
+    #[serde(default)]
+    mode: Mode,
+    query: Option<JsonValue>,
+    sort_field: Option<String>,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    #[serde(default = "default_slices")]
+    slices: u32,
+    #[serde(default = "default_keep_alive")]
+    keep_alive: String,
+    #[serde(default = "default_id_key")]
+    id_key: String,
+    #[serde(default = "default_index_key")]
+    index_key: String,
+    data_dir: Option<PathBuf>,
+    auth: Option<Auth>,
+    tls: Option<TlsOptions>,
+}
+
+fn default_batch_size() -> usize {
+    1000
+}
+
+fn default_slices() -> u32 {
+    1
+}
+
+fn default_keep_alive() -> String {
+    "5m".to_owned()
+}
+
+fn default_id_key() -> String {
+    "_id".to_owned()
+}
+
+fn default_index_key() -> String {
+    "_index".to_owned()
+}
+
+inventory::submit! {
+    SourceDescription::new::<ElasticSearchConfig>("elasticsearch")
+}
+
+impl GenerateConfig for ElasticSearchConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoint: "http://localhost:9200".to_owned(),
+            index: "logs-*".to_owned(),
+            mode: Mode::default(),
+            query: None,
+            sort_field: None,
+            batch_size: default_batch_size(),
+            slices: default_slices(),
+            keep_alive: default_keep_alive(),
+            id_key: default_id_key(),
+            index_key: default_index_key(),
+            data_dir: None,
+            auth: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "elasticsearch")]
+impl SourceConfig for ElasticSearchConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        if self.slices == 0 {
+            return Err(BuildError::NoSlices.into());
+        }
+        if self.batch_size == 0 {
+            return Err(BuildError::EmptyBatches.into());
+        }
+
+        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+        let checkpoint = load_checkpoint(&data_dir).await;
+
+        let reader = Arc::new(Reader {
+            client,
+            config: self.clone(),
+            query: self
+                .query
+                .clone()
+                .unwrap_or_else(|| json!({ "match_all": {} })),
+            data_dir,
+            checkpoint: Mutex::new(checkpoint),
+        });
+        let run = reader.run(out).boxed();
+        Ok(Box::pin(future::select(run, shutdown).map(|_| Ok(()))))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "elasticsearch"
+    }
+}
+
+/// The position of the read, saved after every page so that reads resume
+/// after restarts.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+struct Checkpoint {
+    /// The ID of the point in time being searched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pit_id: Option<String>,
+    #[serde(default)]
+    slices: BTreeMap<u32, SliceCheckpoint>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+struct SliceCheckpoint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scroll_id: Option<String>,
+    /// The sort values of the last document read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    search_after: Option<Vec<JsonValue>>,
+    #[serde(default)]
+    done: bool,
+}
+
+impl Checkpoint {
+    fn is_done(&self, slices: u32) -> bool {
+        (0..slices).all(|slice| self.slices.get(&slice).map_or(false, |slice| slice.done))
+    }
+}
+
+struct Reader {
+    client: HttpClient,
+    config: ElasticSearchConfig,
+    query: JsonValue,
+    data_dir: PathBuf,
+    checkpoint: Mutex<Checkpoint>,
+}
+
+impl Reader {
+    /// Read the slices concurrently, then release the point in time.
+    async fn run(self: Arc<Self>, out: Pipeline) {
+        if self.checkpoint.lock().await.is_done(self.config.slices) {
+            info!(
+                message = "Index was read completely already, remove the checkpoint to read it again.",
+                data_dir = ?self.data_dir
+            );
+            return;
+        }
+        if self.config.mode == Mode::PointInTime {
+            self.ensure_pit(None).await;
+        }
+
+        let slices = (0..self.config.slices).map(|slice| {
+            let reader = Arc::clone(&self);
+            let out = out.clone();
+            async move { reader.read_slice(slice, out).await }
+        });
+        let results = future::join_all(slices).await;
+
+        if results.iter().all(Result::is_ok) {
+            let pit_id = self.checkpoint.lock().await.pit_id.clone();
+            if let Some(pit_id) = pit_id {
+                // It expires after `keep_alive` anyway.
+                let body = json!({ "id": pit_id });
+                if let Err(error) = self.send(Method::DELETE, "/_pit".into(), body).await {
+                    debug!(message = "Failed to close point in time.", %error);
+                }
+            }
+            info!(message = "Finished reading index.", index = %self.config.index);
+        }
+    }
+
+    /// Open a new point in time unless the checkpoint has one other than
+    /// the `expired` one.
+    async fn ensure_pit(&self, expired: Option<&str>) {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut checkpoint = self.checkpoint.lock().await;
+        if checkpoint.pit_id.is_some() && checkpoint.pit_id.as_deref() != expired {
+            return;
+        }
+
+        loop {
+            match self.open_pit().await {
+                Ok(pit_id) => {
+                    checkpoint.pit_id = Some(pit_id);
+                    return;
+                }
+                Err(error) => {
+                    emit!(ElasticSearchReadError {
+                        error: error.into()
+                    });
+                    delay_for(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    async fn open_pit(&self) -> Result<String, ReadError> {
+        let path = format!(
+            "/{}/_pit?keep_alive={}",
+            self.config.index, self.config.keep_alive
+        );
+        let body = self.send(Method::POST, path, JsonValue::Null).await?;
+        let response: PitResponse = serde_json::from_slice(&body).context(ParseBody)?;
+        Ok(response.id)
+    }
+
+    /// Returns an error if the pipeline was closed.
+    async fn read_slice(&self, slice: u32, out: Pipeline) -> Result<(), ()> {
+        let mut out = out
+            .sink_map_err(|error| error!(message = "Error sending documents.", %error))
+            .sink_compat();
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            let (pit_id, state) = {
+                let checkpoint = self.checkpoint.lock().await;
+                let state = checkpoint.slices.get(&slice).cloned().unwrap_or_default();
+                (checkpoint.pit_id.clone(), state)
+            };
+            if state.done {
+                return Ok(());
+            }
+
+            let page = match self.search(slice, pit_id.as_deref(), &state).await {
+                Ok(page) => page,
+                Err(error) if error.is_not_found() && pit_id.is_some() => {
+                    warn!(
+                        message = "Point in time expired, opening a new one.",
+                        slice,
+                        keep_alive = %self.config.keep_alive
+                    );
+                    self.ensure_pit(pit_id.as_deref()).await;
+                    continue;
+                }
+                Err(error) if error.is_not_found() && state.scroll_id.is_some() => {
+                    warn!(
+                        message = "Scroll expired, reading the slice again from the beginning.",
+                        slice,
+                        keep_alive = %self.config.keep_alive
+                    );
+                    self.update(slice, SliceCheckpoint::default(), None).await;
+                    continue;
+                }
+                Err(error) => {
+                    emit!(ElasticSearchReadError {
+                        error: error.into()
+                    });
+                    delay_for(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            };
+            backoff = INITIAL_BACKOFF;
+
+            let done = page.hits.hits.is_empty();
+            let search_after = page
+                .hits
+                .hits
+                .last()
+                .map(|hit| hit.sort.clone())
+                .or(state.search_after);
+            let mut events = stream::iter(page.hits.hits)
+                .map(|hit| self.create_event(hit))
+                .map(Ok);
+            out.send_all(&mut events).await?;
+
+            let scroll_id = page.scroll_id.or(state.scroll_id);
+            if done {
+                if let Some(scroll_id) = &scroll_id {
+                    let body = json!({ "scroll_id": scroll_id });
+                    if let Err(error) = self
+                        .send(Method::DELETE, "/_search/scroll".into(), body)
+                        .await
+                    {
+                        debug!(message = "Failed to clear scroll.", %error);
+                    }
+                }
+            }
+            let state = SliceCheckpoint {
+                scroll_id: if done { None } else { scroll_id },
+                search_after,
+                done,
+            };
+            self.update(slice, state, page.pit_id).await;
+            if done {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn update(&self, slice: u32, state: SliceCheckpoint, pit_id: Option<String>) {
+        let mut checkpoint = self.checkpoint.lock().await;
+        checkpoint.slices.insert(slice, state);
+        // Searches may return a new ID for the point in time.
+        if pit_id.is_some() {
+            checkpoint.pit_id = pit_id;
+        }
+        if let Err(error) = save_checkpoint(&self.data_dir, &checkpoint).await {
+            emit!(ElasticSearchCheckpointError { error });
+        }
+    }
+
+    async fn search(
+        &self,
+        slice: u32,
+        pit_id: Option<&str>,
+        state: &SliceCheckpoint,
+    ) -> Result<SearchResponse, ReadError> {
+        let (path, body) = match (self.config.mode, pit_id, &state.scroll_id) {
+            (Mode::PointInTime, Some(pit_id), _) => {
+                ("/_search".to_owned(), self.pit_search(slice, pit_id, state))
+            }
+            (Mode::PointInTime, None, _) => {
+                return Err(ReadError::MissingField { field: "pit_id" })
+            }
+            (Mode::Scroll, _, Some(scroll_id)) => (
+                "/_search/scroll".to_owned(),
+                json!({ "scroll": self.config.keep_alive, "scroll_id": scroll_id }),
+            ),
+            (Mode::Scroll, _, None) => (
+                format!(
+                    "/{}/_search?scroll={}",
+                    self.config.index, self.config.keep_alive
+                ),
+                self.scroll_search(slice),
+            ),
+        };
+
+        let body = self.send(Method::POST, path, body).await?;
+        let response: SearchResponse = serde_json::from_slice(&body).context(ParseBody)?;
+        emit!(ElasticSearchDocumentsReceived {
+            count: response.hits.hits.len(),
+            byte_size: body.len(),
+        });
+        Ok(response)
+    }
+
+    fn pit_search(&self, slice: u32, pit_id: &str, state: &SliceCheckpoint) -> JsonValue {
+        let mut sort = Vec::new();
+        if let Some(field) = &self.config.sort_field {
+            sort.push(json!({ field.as_str(): "asc" }));
+        }
+        sort.push(json!({ "_shard_doc": "asc" }));
+
+        let mut body = json!({
+            "size": self.config.batch_size,
+            "query": self.query,
+            "pit": { "id": pit_id, "keep_alive": self.config.keep_alive },
+            "sort": sort,
+            "track_total_hits": false,
+        });
+        if let Some(search_after) = &state.search_after {
+            body["search_after"] = json!(search_after);
+        }
+        self.add_slice(&mut body, slice);
+        body
+    }
+
+    fn scroll_search(&self, slice: u32) -> JsonValue {
+        let mut body = json!({
+            "size": self.config.batch_size,
+            "query": self.query,
+            "sort": ["_doc"],
+        });
+        self.add_slice(&mut body, slice);
+        body
+    }
+
+    fn add_slice(&self, body: &mut JsonValue, slice: u32) {
+        if self.config.slices > 1 {
+            body["slice"] = json!({ "id": slice, "max": self.config.slices });
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: String,
+        body: JsonValue,
+    ) -> Result<Bytes, ReadError> {
+        let uri = format!("{}{}", self.config.endpoint.trim_end_matches('/'), path);
+        let mut builder = Request::builder().method(method).uri(uri);
+        let body = if body.is_null() {
+            Body::empty()
+        } else {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        };
+        let mut request = builder.body(body).context(BuildRequest)?;
+        if let Some(auth) = &self.config.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await.context(SendRequest)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body).await.context(ReadBody)?;
+        if !parts.status.is_success() {
+            return Err(ReadError::UnexpectedStatus {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+        Ok(body)
+    }
+
+    fn create_event(&self, hit: Hit) -> Event {
+        let mut log = match hit.source.map(Value::from) {
+            Some(Value::Map(fields)) => LogEvent::from(fields),
+            Some(value) => {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().message_key(), value);
+                log
+            }
+            None => LogEvent::default(),
+        };
+        log.insert(&self.config.id_key, hit.id);
+        log.insert(&self.config.index_key, hit.index);
+        if !log.contains(log_schema().timestamp_key()) {
+            log.insert(log_schema().timestamp_key(), Utc::now());
+        }
+        log.insert(log_schema().source_type_key(), Bytes::from("elasticsearch"));
+        log.into()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct PitResponse {
+    id: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchResponse {
+    pit_id: Option<String>,
+    #[serde(rename = "_scroll_id")]
+    scroll_id: Option<String>,
+    hits: Hits,
+}
+
+#[derive(Deserialize, Debug)]
+struct Hits {
+    hits: Vec<Hit>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Hit {
+    #[serde(rename = "_index")]
+    index: String,
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_source")]
+    source: Option<JsonValue>,
+    #[serde(default)]
+    sort: Vec<JsonValue>,
+}
+
+async fn load_checkpoint(data_dir: &Path) -> Checkpoint {
+    let path = data_dir.join(CHECKPOINT_FILENAME);
+    match tokio::fs::read(&path).await {
+        Ok(contents) => serde_json::from_slice(&contents).unwrap_or_else(|error| {
+            error!(message = "Invalid checkpoint file, starting from the beginning.", ?path, %error);
+            Checkpoint::default()
+        }),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Checkpoint::default(),
+        Err(error) => {
+            error!(message = "Unable to read checkpoint file.", ?path, %error);
+            Checkpoint::default()
+        }
+    }
+}
+
+async fn save_checkpoint(data_dir: &Path, checkpoint: &Checkpoint) -> std::io::Result<()> {
+    let contents = serde_json::to_vec(checkpoint)?;
+    let tmp_path = data_dir.join(CHECKPOINT_TMP_FILENAME);
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, data_dir.join(CHECKPOINT_FILENAME)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::sync::Mutex as StdMutex;
+    use tempfile::tempdir;
+    use tokio::time::timeout;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ElasticSearchConfig>();
+    }
+
+    #[test]
+    fn checkpoint_knows_when_done() {
+        let mut checkpoint = Checkpoint::default();
+        assert!(!checkpoint.is_done(2));
+
+        let done = SliceCheckpoint {
+            done: true,
+            ..Default::default()
+        };
+        checkpoint.slices.insert(0, done.clone());
+        assert!(!checkpoint.is_done(2));
+        checkpoint.slices.insert(1, done);
+        assert!(checkpoint.is_done(2));
+    }
+
+    #[tokio::test]
+    async fn reads_point_in_time() {
+        let addr = next_addr();
+        let requests = Arc::new(StdMutex::new(Vec::new()));
+        let received = Arc::clone(&requests);
+        let make_svc = make_service_fn(move |_| {
+            let received = Arc::clone(&received);
+            async move {
+                Ok::<_, Error>(service_fn(move |request: Request<Body>| {
+                    let received = Arc::clone(&received);
+                    async move {
+                        let method = request.method().clone();
+                        let uri = request.uri().to_string();
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        let body: JsonValue = serde_json::from_slice(&body).unwrap_or_default();
+                        let response = match (method.as_str(), uri.as_str()) {
+                            ("POST", "/logs/_pit?keep_alive=5m") => json!({ "id": "pit-1" }),
+                            ("POST", "/_search") if body.get("search_after").is_none() => json!({
+                                "pit_id": "pit-2",
+                                "hits": { "hits": [
+                                    { "_index": "logs", "_id": "1", "_source": { "message": "one" }, "sort": [1] },
+                                    { "_index": "logs", "_id": "2", "_source": { "message": "two" }, "sort": [2] },
+                                ]}
+                            }),
+                            ("POST", "/_search") if body["search_after"] == json!([2]) => json!({
+                                "pit_id": "pit-2",
+                                "hits": { "hits": [
+                                    { "_index": "logs", "_id": "3", "_source": { "message": "three" }, "sort": [3] },
+                                ]}
+                            }),
+                            ("POST", "/_search") => {
+                                json!({ "pit_id": "pit-2", "hits": { "hits": [] } })
+                            }
+                            _ => json!({}),
+                        };
+                        received.lock().unwrap().push((method, uri, body));
+                        Ok::<_, Error>(Response::new(Body::from(response.to_string())))
+                    }
+                }))
+            }
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let data_dir = tempdir().unwrap();
+        let config: ElasticSearchConfig = toml::from_str(&format!(
+            r#"
+            endpoint = "http://{}"
+            index = "logs"
+            batch_size = 2
+            data_dir = {:?}
+            "#,
+            addr,
+            data_dir.path()
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config
+            .build("es", &GlobalOptions::default(), ShutdownSignal::noop(), tx)
+            .await
+            .unwrap();
+        timeout(Duration::from_secs(5), source)
+            .await
+            .expect("Read did not finish")
+            .unwrap();
+
+        let events = rx.collect().compat().await.unwrap();
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["one", "two", "three"]);
+        let log = events[2].as_log();
+        assert_eq!(log["_id"], "3".into());
+        assert_eq!(log["_index"], "logs".into());
+        assert_eq!(log[log_schema().source_type_key()], "elasticsearch".into());
+
+        let requests = requests.lock().unwrap();
+        // Searches use the latest ID of the point in time, which is closed
+        // at the end.
+        assert_eq!(requests[1].2["pit"]["id"], "pit-1");
+        assert_eq!(requests[2].2["pit"]["id"], "pit-2");
+        assert_eq!(requests[2].2["sort"], json!([{ "_shard_doc": "asc" }]));
+        let (method, uri, body) = requests.last().unwrap();
+        assert_eq!((method.as_str(), uri.as_str()), ("DELETE", "/_pit"));
+        assert_eq!(body["id"], "pit-2");
+
+        let checkpoint = load_checkpoint(&data_dir.path().join("es")).await;
+        assert!(checkpoint.is_done(1));
+    }
+}
@@ -1,4 +1,4 @@
-use super::util::MultilineConfig;
+use super::util::{MultilineConfig, NowSource};
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
     event::Event,
@@ -79,6 +79,9 @@ pub struct FileConfig {
     pub max_read_bytes: usize,
     pub oldest_first: bool,
     pub remove_after: Option<u64>,
+    /// Where the timestamps of the lines come from, as they carry none of
+    /// their own.
+    pub now_source: NowSource,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -135,6 +138,7 @@ impl Default for FileConfig {
             max_read_bytes: 2048,
             oldest_first: false,
             remove_after: None,
+            now_source: NowSource::System,
         }
     }
 }
@@ -225,6 +229,7 @@ pub fn file_source(
         .clone()
         .unwrap_or_else(|| log_schema().host_key().to_string());
     let hostname = crate::get_hostname().ok();
+    let now_source = config.now_source;
 
     let include = config.include.clone();
     let exclude = config.exclude.clone();
@@ -265,7 +270,9 @@ pub fn file_source(
             messages,
             move |(msg, file): (Bytes, String)| {
                 let _enter = span2.enter();
-                Ok::<_, ()>(create_event(msg, file, &host_key, &hostname, &file_key))
+                Ok::<_, ()>(create_event(
+                    msg, file, &host_key, &hostname, &file_key, now_source,
+                ))
             },
         ));
         tokio::spawn(
@@ -307,6 +314,7 @@ fn create_event(
     host_key: &str,
     hostname: &Option<String>,
     file_key: &Option<String>,
+    now_source: NowSource,
 ) -> Event {
     emit!(FileEventReceived {
         file: &file,
@@ -315,6 +323,11 @@ fn create_event(
 
     let mut event = Event::from(line);
 
+    match now_source.now() {
+        Some(now) => event.as_mut_log().insert(log_schema().timestamp_key(), now),
+        None => event.as_mut_log().remove(log_schema().timestamp_key()),
+    };
+
     // Add source type
     event
         .as_mut_log()
@@ -453,13 +466,31 @@ mod tests {
         let hostname = Some("Some.Machine".to_string());
         let file_key = Some("file".to_string());
 
-        let event = create_event(line, file, &host_key, &hostname, &file_key);
+        let event = create_event(
+            line.clone(),
+            file.clone(),
+            &host_key,
+            &hostname,
+            &file_key,
+            NowSource::System,
+        );
         let log = event.into_log();
 
         assert_eq!(log["file"], "some_file.rs".into());
         assert_eq!(log["host"], "Some.Machine".into());
         assert_eq!(log[log_schema().message_key()], "hello world".into());
         assert_eq!(log[log_schema().source_type_key()], "file".into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+
+        let event = create_event(
+            line,
+            file,
+            &host_key,
+            &hostname,
+            &file_key,
+            NowSource::Unset,
+        );
+        assert!(event.as_log().get(log_schema().timestamp_key()).is_none());
     }
 
     #[tokio::test]
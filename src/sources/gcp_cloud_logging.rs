@@ -0,0 +1,808 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    http::{HttpClient, HttpError},
+    internal_events::{
+        GcpCloudLoggingCheckpointError, GcpCloudLoggingEntriesReceived,
+        GcpCloudLoggingEntryParseError, GcpCloudLoggingRequestError,
+    },
+    shutdown::ShutdownSignal,
+    sinks::gcp::{GcpAuthConfig, GcpCredentials},
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{compat::Sink01CompatExt, stream, SinkExt, StreamExt};
+use futures01::Sink;
+use goauth::scopes::Scope;
+use http::{header, Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use snafu::{ResultExt, Snafu};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::time;
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+const CHECKPOINT_TMP_FILENAME: &str = "checkpoint.json.tmp";
+
+/// The `LogEntry` fields copied into the events, by their event field.
+/// https://cloud.google.com/logging/docs/reference/v2/rest/v2/LogEntry
+const ENTRY_FIELDS: &[(&str, &str)] = &[
+    ("logName", "log_name"),
+    ("insertId", "insert_id"),
+    ("severity", "severity"),
+    ("resource", "resource"),
+    ("labels", "labels"),
+    ("receiveTimestamp", "receive_timestamp"),
+    ("httpRequest", "http_request"),
+    ("operation", "operation"),
+    ("trace", "trace"),
+    ("spanId", "span_id"),
+    ("traceSampled", "trace_sampled"),
+    ("sourceLocation", "source_location"),
+    ("protoPayload", "proto_payload"),
+];
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`subscription` is required to pull log entries from Pub/Sub"))]
+    MissingSubscription,
+}
+
+#[derive(Debug, Snafu)]
+enum RequestError {
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Failed to make HTTP(S) request: {}", source))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Failed to read response body: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Unexpected response status {}: {}", status, body))]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[snafu(display("Failed to parse response body as JSON: {}", source))]
+    ParseBody { source: serde_json::Error },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GcpCloudLoggingConfig {
+    project: String,
+    #[serde(default)]
+    mode: Mode,
+    subscription: Option<String>,
+    filter: Option<String>,
+    #[serde(default)]
+    resource_names: Vec<String>,
+    endpoint: Option<String>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    #[serde(default = "default_batch_size")]
+    batch_size: u32,
+    #[serde(default)]
+    skip_authentication: bool,
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsOptions>,
+}
+
+/// How the log entries are pulled.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    /// Pull the messages of a Pub/Sub subscription to the topic a Cloud
+    /// Logging sink routes the entries to, acknowledging them once sent.
+    Pubsub,
+    /// Poll the `entries.list` API for the entries newer than the last one
+    /// seen, which is checkpointed in the `data_dir`.
+    EntriesList,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::Pubsub
+    }
+}
+
+pub fn default_interval_secs() -> u64 {
+    10
+}
+
+pub fn default_batch_size() -> u32 {
+    1000
+}
+
+inventory::submit! {
+    SourceDescription::new::<GcpCloudLoggingConfig>("gcp_cloud_logging")
+}
+
+impl GenerateConfig for GcpCloudLoggingConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            project: "my-project".to_owned(),
+            mode: Mode::default(),
+            subscription: Some("vector-logs".to_owned()),
+            filter: None,
+            resource_names: Vec::new(),
+            endpoint: None,
+            interval_secs: default_interval_secs(),
+            batch_size: default_batch_size(),
+            skip_authentication: false,
+            auth: GcpAuthConfig::default(),
+            data_dir: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_logging")]
+impl SourceConfig for GcpCloudLoggingConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let scope = match self.mode {
+            Mode::Pubsub => Scope::PubSub,
+            Mode::EntriesList => Scope::LoggingRead,
+        };
+        let creds = if self.skip_authentication {
+            None
+        } else {
+            self.auth.make_credentials(scope).await?
+        };
+        if let Some(creds) = &creds {
+            creds.spawn_regenerate_token();
+        }
+        let client = Client {
+            http: HttpClient::new(tls)?,
+            creds,
+            api_key: self.auth.api_key.clone(),
+        };
+
+        let out = out
+            .sink_map_err(|error| error!(message = "Error sending log entries.", %error))
+            .sink_compat();
+        let interval = Duration::from_secs(self.interval_secs);
+
+        let source: super::Source = match self.mode {
+            Mode::Pubsub => {
+                let subscription = self
+                    .subscription
+                    .as_ref()
+                    .ok_or(BuildError::MissingSubscription)?;
+                let base = format!(
+                    "{}/v1/projects/{}/subscriptions/{}",
+                    self.endpoint_or("https://pubsub.googleapis.com"),
+                    self.project,
+                    subscription
+                );
+                let puller = PubsubPuller {
+                    client,
+                    pull_url: format!("{}:pull", base),
+                    acknowledge_url: format!("{}:acknowledge", base),
+                    batch_size: self.batch_size,
+                };
+                Box::pin(puller.run(interval, out, shutdown))
+            }
+            Mode::EntriesList => {
+                let data_dir =
+                    globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+                let resource_names = if self.resource_names.is_empty() {
+                    vec![format!("projects/{}", self.project)]
+                } else {
+                    self.resource_names.clone()
+                };
+                let poller = EntriesPoller {
+                    client,
+                    url: format!(
+                        "{}/v2/entries:list",
+                        self.endpoint_or("https://logging.googleapis.com")
+                    ),
+                    resource_names,
+                    filter: self.filter.clone(),
+                    batch_size: self.batch_size,
+                    data_dir,
+                };
+                Box::pin(poller.run(interval, out, shutdown))
+            }
+        };
+        Ok(source)
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_cloud_logging"
+    }
+}
+
+impl GcpCloudLoggingConfig {
+    fn endpoint_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.endpoint
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+    }
+}
+
+struct Client {
+    http: HttpClient,
+    creds: Option<GcpCredentials>,
+    api_key: Option<String>,
+}
+
+impl Client {
+    /// Post the JSON `body`, returning the response body along with its size.
+    async fn post(&self, url: &str, body: JsonValue) -> Result<(JsonValue, usize), RequestError> {
+        let url = match &self.api_key {
+            Some(key) => format!("{}?key={}", url, key),
+            None => url.to_owned(),
+        };
+        let mut request = Request::post(url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .context(BuildRequest)?;
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        let response = self.http.send(request).await.context(SendRequest)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body).await.context(ReadBody)?;
+        if parts.status != StatusCode::OK {
+            return Err(RequestError::UnexpectedStatus {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+        let size = body.len();
+        Ok((serde_json::from_slice(&body).context(ParseBody)?, size))
+    }
+}
+
+struct PubsubPuller {
+    client: Client,
+    pull_url: String,
+    acknowledge_url: String,
+    batch_size: u32,
+}
+
+impl PubsubPuller {
+    async fn run<O>(
+        self,
+        interval: Duration,
+        mut out: O,
+        shutdown: ShutdownSignal,
+    ) -> Result<(), ()>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        let mut interval = time::interval(interval).take_until(shutdown);
+        while interval.next().await.is_some() {
+            // Keep pulling while the subscription has a backlog.
+            loop {
+                match self.pull(&mut out).await {
+                    Ok(count) if count >= self.batch_size as usize => continue,
+                    Ok(_) => break,
+                    Err(error) => {
+                        emit!(GcpCloudLoggingRequestError {
+                            error: error.into()
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pull a batch of messages, and acknowledge them once sent.
+    /// Returns the number of messages pulled.
+    async fn pull<O>(&self, out: &mut O) -> Result<usize, RequestError>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        let (response, byte_size) = self
+            .client
+            .post(&self.pull_url, json!({ "maxMessages": self.batch_size }))
+            .await?;
+        let messages = match response.get("receivedMessages") {
+            Some(JsonValue::Array(messages)) => messages.as_slice(),
+            _ => &[],
+        };
+
+        let mut ack_ids = Vec::with_capacity(messages.len());
+        let mut events = Vec::with_capacity(messages.len());
+        for message in messages {
+            if let Some(ack_id) = message["ackId"].as_str() {
+                ack_ids.push(ack_id.to_owned());
+            }
+            let data = message["message"]["data"].as_str().unwrap_or("");
+            let entry = base64::decode(data)
+                .map_err(|error| error.to_string())
+                .and_then(|data| serde_json::from_slice(&data).map_err(|error| error.to_string()));
+            match entry.map(create_event) {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => emit!(GcpCloudLoggingEntryParseError {
+                    error: "log entry is not a JSON object".to_owned()
+                }),
+                Err(error) => emit!(GcpCloudLoggingEntryParseError { error }),
+            }
+        }
+        emit!(GcpCloudLoggingEntriesReceived {
+            count: events.len(),
+            byte_size,
+        });
+
+        out.send_all(&mut stream::iter(events).map(Ok)).await.ok();
+
+        // Unparsable messages are acknowledged as well, they would only be
+        // redelivered over and over.
+        if !ack_ids.is_empty() {
+            self.client
+                .post(&self.acknowledge_url, json!({ "ackIds": ack_ids }))
+                .await?;
+        }
+        Ok(messages.len())
+    }
+}
+
+/// The position in the entry stream: the timestamp of the newest entry seen
+/// and the `insertId` of the entries seen with that timestamp, as the filter
+/// includes the entries with the timestamp again.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Checkpoint {
+    timestamp: DateTime<Utc>,
+    insert_ids: Vec<String>,
+}
+
+impl Checkpoint {
+    /// Whether the entry is newer than the checkpoint, moving the
+    /// checkpoint to it if so.
+    fn advance(&mut self, timestamp: DateTime<Utc>, insert_id: Option<&str>) -> bool {
+        if timestamp > self.timestamp {
+            self.timestamp = timestamp;
+            self.insert_ids = insert_id.map(ToOwned::to_owned).into_iter().collect();
+            true
+        } else if timestamp == self.timestamp {
+            match insert_id {
+                Some(id) if self.insert_ids.iter().any(|seen| seen == id) => false,
+                Some(id) => {
+                    self.insert_ids.push(id.to_owned());
+                    true
+                }
+                None => true,
+            }
+        } else {
+            false
+        }
+    }
+}
+
+struct EntriesPoller {
+    client: Client,
+    url: String,
+    resource_names: Vec<String>,
+    filter: Option<String>,
+    batch_size: u32,
+    data_dir: PathBuf,
+}
+
+impl EntriesPoller {
+    async fn run<O>(
+        self,
+        interval: Duration,
+        mut out: O,
+        shutdown: ShutdownSignal,
+    ) -> Result<(), ()>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        // Without a checkpoint, start with the entries from now on rather
+        // than the whole retention period.
+        let mut checkpoint = load_checkpoint(&self.data_dir)
+            .await
+            .unwrap_or_else(|| Checkpoint {
+                timestamp: Utc::now(),
+                insert_ids: Vec::new(),
+            });
+
+        let mut interval = time::interval(interval).take_until(shutdown);
+        while interval.next().await.is_some() {
+            if let Err(error) = self.poll(&mut checkpoint, &mut out).await {
+                emit!(GcpCloudLoggingRequestError {
+                    error: error.into()
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn filter(&self, checkpoint: &Checkpoint) -> String {
+        let timestamp = format!(
+            "timestamp >= \"{}\"",
+            checkpoint
+                .timestamp
+                .to_rfc3339_opts(SecondsFormat::Nanos, true)
+        );
+        match &self.filter {
+            Some(filter) => format!("({}) AND {}", filter, timestamp),
+            None => timestamp,
+        }
+    }
+
+    /// Fetch the pages of entries since the checkpoint.
+    async fn poll<O>(&self, checkpoint: &mut Checkpoint, out: &mut O) -> Result<(), RequestError>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        let filter = self.filter(checkpoint);
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut body = json!({
+                "resourceNames": self.resource_names,
+                "filter": filter,
+                "orderBy": "timestamp asc",
+                "pageSize": self.batch_size,
+            });
+            if let Some(page_token) = &page_token {
+                body["pageToken"] = json!(page_token);
+            }
+            let (mut response, byte_size) = self.client.post(&self.url, body).await?;
+
+            let entries = match response.get_mut("entries").map(JsonValue::take) {
+                Some(JsonValue::Array(entries)) => entries,
+                _ => Vec::new(),
+            };
+            let mut events = Vec::with_capacity(entries.len());
+            for entry in entries {
+                let timestamp = entry_timestamp(&entry);
+                let insert_id = entry["insertId"].as_str();
+                if let Some(timestamp) = timestamp {
+                    if !checkpoint.advance(timestamp, insert_id) {
+                        continue;
+                    }
+                }
+                match create_event(entry) {
+                    Some(event) => events.push(event),
+                    None => emit!(GcpCloudLoggingEntryParseError {
+                        error: "log entry is not a JSON object".to_owned()
+                    }),
+                }
+            }
+            emit!(GcpCloudLoggingEntriesReceived {
+                count: events.len(),
+                byte_size,
+            });
+            out.send_all(&mut stream::iter(events).map(Ok)).await.ok();
+
+            if let Err(error) = save_checkpoint(&self.data_dir, checkpoint).await {
+                emit!(GcpCloudLoggingCheckpointError { error });
+            }
+
+            page_token = response["nextPageToken"]
+                .as_str()
+                .filter(|token| !token.is_empty())
+                .map(ToOwned::to_owned);
+            if page_token.is_none() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn entry_timestamp(entry: &JsonValue) -> Option<DateTime<Utc>> {
+    ["timestamp", "receiveTimestamp"]
+        .iter()
+        .filter_map(|field| entry[field].as_str())
+        .find_map(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+/// Maps a `LogEntry` onto an event. The fields of the `jsonPayload` become
+/// the fields of the event, the `textPayload` becomes its message, and the
+/// other fields of the entry are copied under their snake case name.
+fn create_event(entry: JsonValue) -> Option<Event> {
+    let timestamp = entry_timestamp(&entry);
+    let mut entry = match entry {
+        JsonValue::Object(entry) => entry,
+        _ => return None,
+    };
+
+    let mut log = match entry.remove("jsonPayload").map(Value::from) {
+        Some(Value::Map(payload)) => LogEvent::from(payload),
+        _ => LogEvent::default(),
+    };
+    if let Some(JsonValue::String(text)) = entry.remove("textPayload") {
+        log.insert(log_schema().message_key(), text);
+    }
+    for (entry_field, field) in ENTRY_FIELDS {
+        if let Some(value) = entry.remove(*entry_field) {
+            log.insert(*field, Value::from(value));
+        }
+    }
+
+    log.insert(
+        log_schema().timestamp_key(),
+        timestamp.unwrap_or_else(Utc::now),
+    );
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("gcp_cloud_logging"),
+    );
+    Some(log.into())
+}
+
+async fn load_checkpoint(data_dir: &Path) -> Option<Checkpoint> {
+    let path = data_dir.join(CHECKPOINT_FILENAME);
+    match tokio::fs::read(&path).await {
+        Ok(contents) => serde_json::from_slice(&contents)
+            .map_err(|error| {
+                error!(message = "Invalid checkpoint file, starting from now.", ?path, %error);
+            })
+            .ok(),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => {
+            error!(message = "Unable to read checkpoint file.", ?path, %error);
+            None
+        }
+    }
+}
+
+async fn save_checkpoint(data_dir: &Path, checkpoint: &Checkpoint) -> std::io::Result<()> {
+    let contents = serde_json::to_vec(checkpoint)?;
+    let tmp_path = data_dir.join(CHECKPOINT_TMP_FILENAME);
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, data_dir.join(CHECKPOINT_FILENAME)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::next_addr, Error};
+    use chrono::TimeZone;
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::{
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+    use tempfile::tempdir;
+    use tokio::time::{delay_for, timeout};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GcpCloudLoggingConfig>();
+    }
+
+    #[test]
+    fn maps_log_entries() {
+        let event = create_event(json!({
+            "logName": "projects/my-project/logs/stdout",
+            "insertId": "abc",
+            "severity": "ERROR",
+            "timestamp": "2021-02-01T10:00:00.123456789Z",
+            "resource": { "type": "k8s_container", "labels": { "namespace_name": "default" } },
+            "labels": { "app": "web" },
+            "jsonPayload": { "message": "failed", "user": { "id": 42 } },
+        }))
+        .unwrap();
+        let log = event.as_log();
+        assert_eq!(log["message"], "failed".into());
+        assert_eq!(log["user.id"], 42.into());
+        assert_eq!(log["severity"], "ERROR".into());
+        assert_eq!(log["log_name"], "projects/my-project/logs/stdout".into());
+        assert_eq!(log["resource.type"], "k8s_container".into());
+        assert_eq!(log["resource.labels.namespace_name"], "default".into());
+        assert_eq!(log["labels.app"], "web".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2021, 2, 1)
+                .and_hms_nano(10, 0, 0, 123_456_789)
+                .into()
+        );
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "gcp_cloud_logging".into()
+        );
+
+        let event = create_event(json!({ "textPayload": "hello", "severity": "INFO" })).unwrap();
+        assert_eq!(event.as_log()[log_schema().message_key()], "hello".into());
+
+        assert!(create_event(json!("hello")).is_none());
+    }
+
+    #[test]
+    fn advances_checkpoint() {
+        let start = Utc.ymd(2021, 2, 1).and_hms(10, 0, 0);
+        let mut checkpoint = Checkpoint {
+            timestamp: start,
+            insert_ids: vec!["a".to_owned()],
+        };
+        assert!(!checkpoint.advance(start, Some("a")));
+        assert!(checkpoint.advance(start, Some("b")));
+        assert!(!checkpoint.advance(start - chrono::Duration::seconds(1), Some("c")));
+        let later = start + chrono::Duration::seconds(1);
+        assert!(checkpoint.advance(later, Some("d")));
+        assert_eq!(
+            checkpoint,
+            Checkpoint {
+                timestamp: later,
+                insert_ids: vec!["d".to_owned()],
+            }
+        );
+    }
+
+    /// Serve the `responses` by path, recording the request bodies.
+    fn serve(
+        addr: SocketAddr,
+        responses: Vec<(&'static str, JsonValue)>,
+    ) -> Arc<Mutex<Vec<(String, JsonValue)>>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        let make_svc = make_service_fn(move |_| {
+            let responses = responses.clone();
+            let requests = Arc::clone(&requests);
+            async move {
+                Ok::<_, Error>(service_fn(move |request: Request<Body>| {
+                    let responses = responses.clone();
+                    let requests = Arc::clone(&requests);
+                    async move {
+                        let path = request.uri().path().to_owned();
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        let body: JsonValue = serde_json::from_slice(&body).unwrap();
+                        requests.lock().unwrap().push((path.clone(), body));
+
+                        let response = responses
+                            .iter()
+                            .find(|(response_path, _)| *response_path == path)
+                            .map(|(_, response)| response.to_string())
+                            .unwrap_or_else(|| "{}".to_owned());
+                        Ok::<_, Error>(Response::new(Body::from(response)))
+                    }
+                }))
+            }
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+        recorded
+    }
+
+    async fn collect(config: GcpCloudLoggingConfig) -> Vec<Event> {
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = config
+            .build("logging", &GlobalOptions::default(), shutdown, tx)
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        delay_for(Duration::from_millis(500)).await;
+        drop(trigger);
+
+        timeout(Duration::from_secs(1), rx.collect().compat())
+            .await
+            .expect("Unclosed channel")
+            .unwrap()
+    }
+
+    fn config(addr: SocketAddr, mode: Mode, data_dir: &Path) -> GcpCloudLoggingConfig {
+        toml::from_str::<GcpCloudLoggingConfig>(&format!(
+            r#"
+            project = "my-project"
+            subscription = "logs"
+            endpoint = "http://{}"
+            skip_authentication = true
+            data_dir = "{}"
+            "#,
+            addr,
+            data_dir.display()
+        ))
+        .map(|config| GcpCloudLoggingConfig { mode, ..config })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pulls_pubsub_messages() {
+        let addr = next_addr();
+        let entry = json!({ "insertId": "1", "severity": "INFO", "textPayload": "hello" });
+        let requests = serve(
+            addr,
+            vec![(
+                "/v1/projects/my-project/subscriptions/logs:pull",
+                json!({ "receivedMessages": [{
+                    "ackId": "ack-1",
+                    "message": { "data": base64::encode(entry.to_string()), "messageId": "1" },
+                }]}),
+            )],
+        );
+
+        let dir = tempdir().unwrap();
+        let events = collect(config(addr, Mode::Pubsub, dir.path())).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "hello".into()
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            requests[0],
+            (
+                "/v1/projects/my-project/subscriptions/logs:pull".to_owned(),
+                json!({ "maxMessages": 1000 })
+            )
+        );
+        assert_eq!(
+            requests[1],
+            (
+                "/v1/projects/my-project/subscriptions/logs:acknowledge".to_owned(),
+                json!({ "ackIds": ["ack-1"] })
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_entries() {
+        let addr = next_addr();
+        let now = Utc::now();
+        let timestamp = |offset| {
+            (now + chrono::Duration::seconds(offset)).to_rfc3339_opts(SecondsFormat::Nanos, true)
+        };
+        let requests = serve(
+            addr,
+            vec![(
+                "/v2/entries:list",
+                json!({ "entries": [
+                    { "insertId": "1", "timestamp": timestamp(-3600), "textPayload": "old" },
+                    { "insertId": "2", "timestamp": timestamp(1), "textPayload": "new" },
+                ]}),
+            )],
+        );
+
+        let dir = tempdir().unwrap();
+        let events = collect(config(addr, Mode::EntriesList, dir.path())).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()[log_schema().message_key()], "new".into());
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0, "/v2/entries:list");
+        assert_eq!(
+            requests[0].1["resourceNames"],
+            json!(["projects/my-project"])
+        );
+        assert!(requests[0].1["filter"]
+            .as_str()
+            .unwrap()
+            .starts_with("timestamp >= "));
+
+        let checkpoint =
+            std::fs::read(dir.path().join("logging").join(CHECKPOINT_FILENAME)).unwrap();
+        let checkpoint: Checkpoint = serde_json::from_slice(&checkpoint).unwrap();
+        assert_eq!(checkpoint.insert_ids, vec!["2".to_owned()]);
+    }
+}
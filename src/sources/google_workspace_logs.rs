@@ -0,0 +1,353 @@
+use super::util::api_poller::{
+    oauth2::{request_token, AccessToken, TokenCache},
+    ApiPoller, Authorizer, JsonPath, Pagination, PollerSpec, RateLimitHeaders,
+};
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    http::HttpClient,
+    shutdown::ShutdownSignal,
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use chrono::Utc;
+use http::{header, HeaderMap, HeaderValue, Request};
+use hyper::Body;
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use url::{form_urlencoded, Url};
+
+const SCOPE: &str = "https://www.googleapis.com/auth/admin.reports.audit.readonly";
+const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("Failed to read credentials file {:?}: {}", path, source))]
+    ReadCredentials {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid credentials file {:?}: {}", path, source))]
+    ParseCredentials {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Invalid private key: {}", source))]
+    InvalidPrivateKey { source: openssl::error::ErrorStack },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GoogleWorkspaceLogsConfig {
+    credentials_path: PathBuf,
+    subject: String,
+    application: String,
+    #[serde(default = "default_user_key")]
+    user_key: String,
+    #[serde(default = "default_endpoint")]
+    endpoint: String,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsOptions>,
+}
+
+pub fn default_user_key() -> String {
+    "all".to_owned()
+}
+
+pub fn default_endpoint() -> String {
+    "https://admin.googleapis.com".to_owned()
+}
+
+pub fn default_interval_secs() -> u64 {
+    300
+}
+
+inventory::submit! {
+    SourceDescription::new::<GoogleWorkspaceLogsConfig>("google_workspace_logs")
+}
+
+impl GenerateConfig for GoogleWorkspaceLogsConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            credentials_path: "/path/to/credentials.json".into(),
+            subject: "admin@example.com".to_owned(),
+            application: "login".to_owned(),
+            user_key: default_user_key(),
+            endpoint: default_endpoint(),
+            interval_secs: default_interval_secs(),
+            data_dir: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "google_workspace_logs")]
+impl SourceConfig for GoogleWorkspaceLogsConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let authorizer = ServiceAccountAuthorizer::new(
+            client.clone(),
+            &self.credentials_path,
+            self.subject.clone(),
+        )?;
+        let poller = ApiPoller::new(client, self.spec()?, Some(Box::new(authorizer)), data_dir);
+        Ok(Box::pin(poller.run(out, shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "google_workspace_logs"
+    }
+}
+
+impl GoogleWorkspaceLogsConfig {
+    fn spec(&self) -> crate::Result<PollerSpec> {
+        let endpoint = format!(
+            "{}/admin/reports/v1/activity/users/{}/applications/{}",
+            self.endpoint.trim_end_matches('/'),
+            self.user_key,
+            self.application
+        );
+        let mut url = Url::parse(&endpoint).context(InvalidEndpoint { endpoint })?;
+        url.query_pairs_mut().append_pair("maxResults", "1000");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+
+        Ok(PollerSpec {
+            source_type: "google_workspace_logs",
+            url: url.into(),
+            headers,
+            records: JsonPath::parse("$.items")?,
+            // Activities are returned newest first, so each poll pages through
+            // everything since the newest activity of the previous poll.
+            pagination: Pagination::PageToken {
+                token_field: "nextPageToken".to_owned(),
+                token_param: Some("pageToken".to_owned()),
+                field: Some("id.time".to_owned()),
+                param: Some("startTime".to_owned()),
+            },
+            rate_limit: RateLimitHeaders::default(),
+            timestamp_field: Some("id.time".to_owned()),
+            message_field: Some("events.0.name".to_owned()),
+            interval: Duration::from_secs(self.interval_secs),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ServiceAccountCredentials {
+    client_email: String,
+    private_key: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    DEFAULT_TOKEN_URI.to_owned()
+}
+
+/// Authorizes requests with access tokens obtained for a service account
+/// with domain-wide delegation, impersonating `subject`.
+struct ServiceAccountAuthorizer {
+    client: HttpClient,
+    client_email: String,
+    token_uri: String,
+    key: PKey<Private>,
+    subject: String,
+    cache: TokenCache,
+}
+
+impl ServiceAccountAuthorizer {
+    fn new(client: HttpClient, path: &Path, subject: String) -> crate::Result<Self> {
+        let contents = std::fs::read(path).with_context(|| ReadCredentials { path })?;
+        let credentials: ServiceAccountCredentials =
+            serde_json::from_slice(&contents).with_context(|| ParseCredentials { path })?;
+        let key = PKey::private_key_from_pem(credentials.private_key.as_bytes())
+            .context(InvalidPrivateKey)?;
+
+        Ok(Self {
+            client,
+            client_email: credentials.client_email,
+            token_uri: credentials.token_uri,
+            key,
+            subject,
+            cache: TokenCache::default(),
+        })
+    }
+
+    /// Build the signed JWT exchanged for an access token.
+    fn assertion(&self) -> Result<String, openssl::error::ErrorStack> {
+        let now = Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "iss": self.client_email,
+            "sub": self.subject,
+            "scope": SCOPE,
+            "aud": self.token_uri,
+            "iat": now,
+            "exp": now + 3600,
+        });
+        let message = format!(
+            "{}.{}",
+            base64_url(br#"{"alg":"RS256","typ":"JWT"}"#),
+            base64_url(claims.to_string().as_bytes())
+        );
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key)?;
+        signer.update(message.as_bytes())?;
+        let signature = signer.sign_to_vec()?;
+
+        Ok(format!("{}.{}", message, base64_url(&signature)))
+    }
+
+    async fn fetch_token(&self) -> crate::Result<AccessToken> {
+        let body = form_urlencoded::Serializer::new(String::new())
+            .append_pair("grant_type", GRANT_TYPE)
+            .append_pair("assertion", &self.assertion()?)
+            .finish();
+
+        let token = request_token(&self.client, &self.token_uri, body, None).await?;
+        Ok(token)
+    }
+}
+
+#[async_trait::async_trait]
+impl Authorizer for ServiceAccountAuthorizer {
+    async fn authorize(&self, request: &mut Request<Body>) -> crate::Result<()> {
+        self.cache.authorize(request, || self.fetch_token()).await
+    }
+}
+
+fn base64_url(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use openssl::rsa::Rsa;
+    use tempfile::tempdir;
+    use tokio::time::{delay_for, timeout};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GoogleWorkspaceLogsConfig>();
+    }
+
+    #[tokio::test]
+    async fn pulls_activities() {
+        let addr = next_addr();
+        let make_svc = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|request: Request<Body>| async move {
+                let body = match request.uri().path() {
+                    "/token" => {
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        let form = form_urlencoded::parse(&body).collect::<Vec<_>>();
+                        assert_eq!(form[0].1, GRANT_TYPE);
+                        assert_eq!(form[1].1.split('.').count(), 3);
+                        r#"{"access_token":"token","expires_in":3600,"token_type":"Bearer"}"#
+                    }
+                    "/admin/reports/v1/activity/users/all/applications/login" => {
+                        assert_eq!(request.headers()["authorization"], "Bearer token");
+                        r#"{"kind":"admin#reports#activities","items":[{"id":{"time":"2021-02-01T10:00:00.000Z","applicationName":"login"},"actor":{"email":"user@example.com"},"events":[{"type":"login","name":"login_success"}]}]}"#
+                    }
+                    path => panic!("Unexpected request for {}", path),
+                };
+                Ok::<_, Error>(Response::new(Body::from(body)))
+            }))
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let dir = tempdir().unwrap();
+        let key = Rsa::generate(2048).unwrap().private_key_to_pem().unwrap();
+        let credentials_path = dir.path().join("credentials.json");
+        std::fs::write(
+            &credentials_path,
+            serde_json::json!({
+                "type": "service_account",
+                "client_email": "vector@example.iam.gserviceaccount.com",
+                "private_key": String::from_utf8(key).unwrap(),
+                "token_uri": format!("http://{}/token", addr),
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let config = GoogleWorkspaceLogsConfig {
+            credentials_path,
+            subject: "admin@example.com".to_owned(),
+            application: "login".to_owned(),
+            user_key: default_user_key(),
+            endpoint: format!("http://{}", addr),
+            interval_secs: 1,
+            data_dir: Some(dir.path().to_path_buf()),
+            tls: None,
+        };
+
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = config
+            .build("google", &GlobalOptions::default(), shutdown, tx)
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        delay_for(Duration::from_millis(500)).await;
+        drop(trigger);
+
+        let events = timeout(Duration::from_secs(1), rx.collect().compat())
+            .await
+            .expect("Unclosed channel")
+            .unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "login_success".into());
+        assert_eq!(log["actor.email"], "user@example.com".into());
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "google_workspace_logs".into()
+        );
+    }
+}
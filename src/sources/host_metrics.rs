@@ -18,6 +18,8 @@ use heim::memory::os::macos::MemoryExt;
 use heim::memory::os::SwapExt;
 #[cfg(target_os = "windows")]
 use heim::net::os::windows::IoCountersExt;
+#[cfg(target_os = "linux")]
+use heim::process::os::linux::ProcessExt;
 #[cfg(not(target_os = "windows"))]
 use heim::units::ratio::ratio;
 #[cfg(target_os = "linux")]
@@ -25,9 +27,11 @@ use heim::{
     cpu::os::linux::CpuTimeExt, memory::os::linux::MemoryExt, net::os::linux::IoCountersExt,
 };
 use heim::{
+    process::Process,
     units::{information::byte, time::second},
     Error,
 };
+use regex::Regex;
 use serde::{
     de::{self, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
@@ -46,6 +50,11 @@ enum Collector {
     Load,
     Memory,
     Network,
+    Nfs,
+    Pressure,
+    Process,
+    Tcp,
+    Udp,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -54,14 +63,26 @@ struct FilterList {
     excludes: Option<Vec<PatternWrapper>>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct CpuConfig {
+    scrape_interval_secs: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct MemoryConfig {
+    scrape_interval_secs: Option<u64>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct DiskConfig {
+    scrape_interval_secs: Option<u64>,
     #[serde(default)]
     devices: FilterList,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct FilesystemConfig {
+    scrape_interval_secs: Option<u64>,
     #[serde(default)]
     devices: FilterList,
     #[serde(default)]
@@ -72,10 +93,19 @@ struct FilesystemConfig {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct NetworkConfig {
+    scrape_interval_secs: Option<u64>,
     #[serde(default)]
     devices: FilterList,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct ProcessConfig {
+    #[serde(default)]
+    names: Vec<RegexWrapper>,
+    #[serde(default)]
+    cmdlines: Vec<RegexWrapper>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 struct Namespace(Option<String>);
 
@@ -95,18 +125,38 @@ pub struct HostMetricsConfig {
     #[serde(default)]
     namespace: Namespace,
 
+    #[serde(default)]
+    cpu: CpuConfig,
+    #[serde(default)]
+    memory: MemoryConfig,
     #[serde(default)]
     disk: DiskConfig,
     #[serde(default)]
     filesystem: FilesystemConfig,
     #[serde(default)]
     network: NetworkConfig,
+    #[serde(default)]
+    process: ProcessConfig,
 }
 
 const fn default_scrape_interval() -> u64 {
     15
 }
 
+const COLLECTORS: &[Collector] = &[
+    Collector::Cpu,
+    Collector::Disk,
+    Collector::Filesystem,
+    Collector::Load,
+    Collector::Memory,
+    Collector::Network,
+    Collector::Nfs,
+    Collector::Pressure,
+    Collector::Process,
+    Collector::Tcp,
+    Collector::Udp,
+];
+
 inventory::submit! {
     SourceDescription::new::<HostMetricsConfig>("host_metrics")
 }
@@ -123,6 +173,16 @@ impl SourceConfig for HostMetricsConfig {
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
+        for &collector in COLLECTORS {
+            if self.scrape_interval(collector) == 0 {
+                return Err(format!(
+                    "The scrape interval of the {:?} collector must be greater than zero.",
+                    collector
+                )
+                .into());
+            }
+        }
+
         let mut config = self.clone();
         config.namespace.0 = config.namespace.0.filter(|namespace| !namespace.is_empty());
 
@@ -153,11 +213,20 @@ impl HostMetricsConfig {
             .sink_map_err(|error| error!(message = "Error sending host metrics.", %error))
             .sink_compat();
 
-        let duration = time::Duration::from_secs(self.scrape_interval_secs);
+        // Ticks often enough for every collector to be scraped on time.
+        let tick = COLLECTORS
+            .iter()
+            .filter(|&&collector| self.has_collector(collector))
+            .map(|&collector| self.scrape_interval(collector))
+            .fold(0, gcd)
+            .max(1);
+        let duration = time::Duration::from_secs(tick);
         let mut interval = time::interval(duration).take_until(shutdown);
+        let mut elapsed = 0;
         while interval.next().await.is_some() {
-            let metrics = self.capture_metrics().await;
+            let metrics = self.capture_due_metrics(elapsed).await;
             out.send_all(&mut stream::iter(metrics).map(Ok)).await?;
+            elapsed += tick;
         }
 
         Ok(())
@@ -170,28 +239,66 @@ impl HostMetricsConfig {
         }
     }
 
+    /// The scrape interval of the collector, which defaults to the one of the
+    /// source for collectors without one of their own.
+    fn scrape_interval(&self, collector: Collector) -> u64 {
+        match collector {
+            Collector::Cpu => self.cpu.scrape_interval_secs,
+            Collector::Disk => self.disk.scrape_interval_secs,
+            Collector::Filesystem => self.filesystem.scrape_interval_secs,
+            Collector::Memory => self.memory.scrape_interval_secs,
+            Collector::Network => self.network.scrape_interval_secs,
+            _ => None,
+        }
+        .unwrap_or(self.scrape_interval_secs)
+    }
+
+    /// Whether the collector is scraped `elapsed` seconds after the start.
+    fn is_due(&self, collector: Collector, elapsed: u64) -> bool {
+        self.has_collector(collector) && elapsed % self.scrape_interval(collector) == 0
+    }
+
     async fn capture_metrics(&self) -> impl Iterator<Item = Event> {
+        self.capture_due_metrics(0).await
+    }
+
+    async fn capture_due_metrics(&self, elapsed: u64) -> impl Iterator<Item = Event> {
         let hostname = crate::get_hostname();
         let mut metrics = Vec::new();
-        if self.has_collector(Collector::Cpu) {
+        if self.is_due(Collector::Cpu, elapsed) {
             metrics.extend(add_collector("cpu", self.cpu_metrics().await));
         }
-        if self.has_collector(Collector::Disk) {
+        if self.is_due(Collector::Disk, elapsed) {
             metrics.extend(add_collector("disk", self.disk_metrics().await));
         }
-        if self.has_collector(Collector::Filesystem) {
+        if self.is_due(Collector::Filesystem, elapsed) {
             metrics.extend(add_collector("filesystem", self.filesystem_metrics().await));
         }
-        if self.has_collector(Collector::Load) {
+        if self.is_due(Collector::Load, elapsed) {
             metrics.extend(add_collector("load", self.loadavg_metrics().await));
         }
-        if self.has_collector(Collector::Memory) {
+        if self.is_due(Collector::Memory, elapsed) {
             metrics.extend(add_collector("memory", self.memory_metrics().await));
             metrics.extend(add_collector("memory", self.swap_metrics().await));
         }
-        if self.has_collector(Collector::Network) {
+        if self.is_due(Collector::Network, elapsed) {
             metrics.extend(add_collector("network", self.network_metrics().await));
         }
+        if self.is_due(Collector::Nfs, elapsed) {
+            metrics.extend(add_collector("nfs", self.nfs_metrics().await));
+        }
+        if self.is_due(Collector::Pressure, elapsed) {
+            metrics.extend(add_collector("pressure", self.pressure_metrics().await));
+        }
+        if self.is_due(Collector::Process, elapsed) {
+            metrics.extend(add_collector("process", self.process_metrics().await));
+        }
+        if self.is_due(Collector::Tcp, elapsed) {
+            metrics.extend(add_collector("tcp", self.tcp_metrics().await));
+        }
+        if self.is_due(Collector::Udp, elapsed) {
+            metrics.extend(add_collector("udp", self.udp_metrics().await));
+        }
         if let Ok(hostname) = &hostname {
             for metric in &mut metrics {
                 (metric.tags.as_mut().unwrap()).insert("host".into(), hostname.into());
@@ -505,15 +612,14 @@ impl HostMetricsConfig {
                     })
                     .filter_map(|partition| async { partition })
                     // Filter on configured devices
+                    // Partitions without a device are only kept when there
+                    // is no device filter.
                     .map(|partition| {
-                        (self.filesystem.devices.is_empty()
-                            && partition
-                                .device()
-                                .map(|device| {
-                                    self.filesystem.devices.contains_path(device.as_ref())
-                                })
-                                .unwrap_or(true))
-                        .and_some(partition)
+                        partition
+                            .device()
+                            .map(|device| self.filesystem.devices.contains_path(device.as_ref()))
+                            .unwrap_or_else(|| self.filesystem.devices.is_empty())
+                            .and_some(partition)
                     })
                     .filter_map(|partition| async { partition })
                     // Filter on configured filesystems
@@ -644,6 +750,248 @@ impl HostMetricsConfig {
         }
     }
 
+    pub async fn tcp_metrics(&self) -> Vec<Metric> {
+        #[cfg(target_os = "linux")]
+        let result = {
+            let mut counts = TCP_STATES
+                .iter()
+                .map(|&state| (state, 0))
+                .collect::<BTreeMap<_, _>>();
+            // Without IPv6 support there is no `tcp6` file.
+            for &(path, required) in &[("/proc/net/tcp", true), ("/proc/net/tcp6", false)] {
+                if let Some(contents) = read_proc_file(path, required).await {
+                    count_tcp_states(&contents, &mut counts);
+                }
+            }
+            let timestamp = Utc::now();
+            counts
+                .into_iter()
+                .map(|(state, count)| {
+                    self.gauge(
+                        "tcp_connections",
+                        timestamp,
+                        count as f64,
+                        tags!["state" => state],
+                    )
+                })
+                .collect()
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = vec![];
+
+        result
+    }
+
+    pub async fn udp_metrics(&self) -> Vec<Metric> {
+        #[cfg(target_os = "linux")]
+        let result = {
+            let mut metrics = Vec::new();
+            let timestamp = Utc::now();
+            let mut sockets = 0;
+            for &(path, required) in &[("/proc/net/udp", true), ("/proc/net/udp6", false)] {
+                if let Some(contents) = read_proc_file(path, required).await {
+                    // The first line holds the column names.
+                    sockets += contents.lines().skip(1).count();
+                }
+            }
+            metrics.push(self.gauge("udp_sockets", timestamp, sockets as f64, tags![]));
+
+            if let Some(contents) = read_proc_file("/proc/net/snmp", true).await {
+                let counters = parse_snmp(&contents, "Udp");
+                for &(field, name) in &[
+                    ("InDatagrams", "udp_datagrams_received_total"),
+                    ("OutDatagrams", "udp_datagrams_sent_total"),
+                    ("InErrors", "udp_receive_errors_total"),
+                    ("RcvbufErrors", "udp_receive_buffer_errors_total"),
+                    ("SndbufErrors", "udp_send_buffer_errors_total"),
+                ] {
+                    if let Some(value) = counters.get(field) {
+                        metrics.push(self.counter(name, timestamp, *value as f64, tags![]));
+                    }
+                }
+            }
+            metrics
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = vec![];
+
+        result
+    }
+
+    pub async fn nfs_metrics(&self) -> Vec<Metric> {
+        #[cfg(target_os = "linux")]
+        let result = match read_proc_file("/proc/net/rpc/nfs", false).await {
+            Some(contents) => match parse_nfs_rpc(&contents) {
+                Some((calls, retransmissions, auth_refreshes)) => {
+                    let timestamp = Utc::now();
+                    vec![
+                        self.counter("nfs_rpc_calls_total", timestamp, calls as f64, tags![]),
+                        self.counter(
+                            "nfs_rpc_retransmissions_total",
+                            timestamp,
+                            retransmissions as f64,
+                            tags![],
+                        ),
+                        self.counter(
+                            "nfs_rpc_auth_refreshes_total",
+                            timestamp,
+                            auth_refreshes as f64,
+                            tags![],
+                        ),
+                    ]
+                }
+                None => vec![],
+            },
+            // The NFS client is not loaded.
+            None => vec![],
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = vec![];
+
+        result
+    }
+
+    pub async fn pressure_metrics(&self) -> Vec<Metric> {
+        #[cfg(target_os = "linux")]
+        let result = {
+            let mut metrics = Vec::new();
+            for resource in &["cpu", "io", "memory"] {
+                // Kernels before 4.20, or without `CONFIG_PSI`, have no
+                // pressure stall information.
+                let path = format!("/proc/pressure/{}", resource);
+                let contents = match read_proc_file(&path, false).await {
+                    Some(contents) => contents,
+                    None => continue,
+                };
+                let timestamp = Utc::now();
+                for stall in parse_pressure(&contents) {
+                    for &(window, avg) in &[
+                        ("10s", stall.avg10),
+                        ("60s", stall.avg60),
+                        ("300s", stall.avg300),
+                    ] {
+                        metrics.push(self.gauge(
+                            "pressure_stall_ratio",
+                            timestamp,
+                            avg / 100.0,
+                            tags![
+                                "resource" => resource,
+                                "kind" => stall.kind,
+                                "window" => window
+                            ],
+                        ));
+                    }
+                    metrics.push(self.counter(
+                        "pressure_stall_seconds_total",
+                        timestamp,
+                        stall.total_micros as f64 / 1_000_000.0,
+                        tags!["resource" => resource, "kind" => stall.kind],
+                    ));
+                }
+            }
+            metrics
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = vec![];
+
+        result
+    }
+
+    pub async fn process_metrics(&self) -> Vec<Metric> {
+        // Scanning all processes is expensive, so nothing is collected unless
+        // the processes to monitor are configured.
+        if self.process.is_empty() {
+            return vec![];
+        }
+        match heim::process::processes().await {
+            Ok(processes) => {
+                // Processes may exit or deny access while they are listed and
+                // sampled, which is expected and not worth logging.
+                let processes = processes
+                    .filter_map(|result| async { result.ok() })
+                    .collect::<Vec<_>>()
+                    .await;
+                let mut metrics = Vec::new();
+                for process in processes {
+                    if let Some(name) = self.process.matches(&process).await {
+                        metrics.extend(self.single_process_metrics(&process, &name).await);
+                    }
+                }
+                metrics
+            }
+            Err(error) => {
+                error!(message = "Failed to load processes.", %error, rate_limit_secs = 60);
+                vec![]
+            }
+        }
+    }
+
+    async fn single_process_metrics(&self, process: &Process, name: &str) -> Vec<Metric> {
+        let timestamp = Utc::now();
+        let tags = tags!["pid" => process.pid(), "name" => name];
+        let mut metrics = Vec::new();
+
+        if let Ok(time) = process.cpu_time().await {
+            let mut user_tags = tags.clone();
+            user_tags.insert("mode".into(), "user".into());
+            let mut system_tags = tags.clone();
+            system_tags.insert("mode".into(), "system".into());
+            metrics.push(self.counter(
+                "process_cpu_seconds_total",
+                timestamp,
+                time.user().get::<second>(),
+                user_tags,
+            ));
+            metrics.push(self.counter(
+                "process_cpu_seconds_total",
+                timestamp,
+                time.system().get::<second>(),
+                system_tags,
+            ));
+        }
+        if let Ok(memory) = process.memory().await {
+            metrics.push(self.gauge(
+                "process_memory_rss_bytes",
+                timestamp,
+                memory.rss().get::<byte>() as f64,
+                tags.clone(),
+            ));
+            metrics.push(self.gauge(
+                "process_memory_virtual_bytes",
+                timestamp,
+                memory.vms().get::<byte>() as f64,
+                tags.clone(),
+            ));
+        }
+        #[cfg(target_os = "linux")]
+        {
+            if let Ok(fds) = std::fs::read_dir(format!("/proc/{}/fd", process.pid())) {
+                metrics.push(self.gauge(
+                    "process_open_fds",
+                    timestamp,
+                    fds.count() as f64,
+                    tags.clone(),
+                ));
+            }
+            if let Ok(counters) = process.io_counters().await {
+                metrics.push(self.counter(
+                    "process_io_read_bytes_total",
+                    timestamp,
+                    counters.bytes_read().get::<byte>() as f64,
+                    tags.clone(),
+                ));
+                metrics.push(self.counter(
+                    "process_io_written_bytes_total",
+                    timestamp,
+                    counters.bytes_written().get::<byte>() as f64,
+                    tags,
+                ));
+            }
+        }
+
+        metrics
+    }
+
     fn counter(
         &self,
         name: &str,
@@ -679,6 +1027,14 @@ impl HostMetricsConfig {
     }
 }
 
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 async fn filter_result<T>(result: Result<T, Error>, message: &'static str) -> Option<T> {
     result
         .map_err(|error| error!(message, %error, rate_limit_secs = 60))
@@ -733,6 +1089,150 @@ impl FilterList {
     }
 }
 
+/// TCP states by their number in `/proc/net/tcp`, offset by one.
+#[cfg(target_os = "linux")]
+const TCP_STATES: &[&str] = &[
+    "established",
+    "syn_sent",
+    "syn_recv",
+    "fin_wait1",
+    "fin_wait2",
+    "time_wait",
+    "close",
+    "close_wait",
+    "last_ack",
+    "listen",
+    "closing",
+    "new_syn_recv",
+];
+
+/// Reads a file of `procfs`. Missing files are only logged if `required`.
+#[cfg(target_os = "linux")]
+async fn read_proc_file(path: &str, required: bool) -> Option<String> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => Some(contents),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound && !required => None,
+        Err(error) => {
+            error!(message = "Failed to read proc file.", %path, %error, rate_limit_secs = 60);
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn count_tcp_states(contents: &str, counts: &mut BTreeMap<&'static str, u64>) {
+    // The first line holds the column names, the state is the fourth column.
+    for line in contents.lines().skip(1) {
+        let state = line
+            .split_whitespace()
+            .nth(3)
+            .and_then(|state| usize::from_str_radix(state, 16).ok())
+            .and_then(|state| state.checked_sub(1))
+            .and_then(|state| TCP_STATES.get(state).copied());
+        if let Some(state) = state {
+            *counts.entry(state).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Parses the counters of `protocol` from `/proc/net/snmp`, which lists them
+/// as a line of names followed by a line of values.
+#[cfg(target_os = "linux")]
+fn parse_snmp(contents: &str, protocol: &str) -> BTreeMap<String, u64> {
+    let prefix = format!("{}:", protocol);
+    let mut lines = contents.lines().filter(|line| line.starts_with(&prefix));
+    match (lines.next(), lines.next()) {
+        (Some(names), Some(values)) => names
+            .split_whitespace()
+            .zip(values.split_whitespace())
+            .skip(1)
+            .filter_map(|(name, value)| Some((name.to_owned(), value.parse().ok()?)))
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Parses the RPC calls, retransmissions and authentication refreshes of
+/// the NFS client from `/proc/net/rpc/nfs`.
+#[cfg(target_os = "linux")]
+fn parse_nfs_rpc(contents: &str) -> Option<(u64, u64, u64)> {
+    let line = contents.lines().find(|line| line.starts_with("rpc "))?;
+    let mut values = line
+        .split_whitespace()
+        .skip(1)
+        .map(|value| value.parse().ok());
+    Some((values.next()??, values.next()??, values.next()??))
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, PartialEq)]
+struct PressureStall<'a> {
+    kind: &'a str,
+    avg10: f64,
+    avg60: f64,
+    avg300: f64,
+    total_micros: u64,
+}
+
+/// Parses the `some` and `full` lines of a `/proc/pressure` file.
+#[cfg(target_os = "linux")]
+fn parse_pressure(contents: &str) -> Vec<PressureStall<'_>> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mut stall = PressureStall {
+                kind: fields.next()?,
+                avg10: 0.0,
+                avg60: 0.0,
+                avg300: 0.0,
+                total_micros: 0,
+            };
+            for field in fields {
+                let mut parts = field.splitn(2, '=');
+                match (parts.next()?, parts.next()?) {
+                    ("avg10", value) => stall.avg10 = value.parse().ok()?,
+                    ("avg60", value) => stall.avg60 = value.parse().ok()?,
+                    ("avg300", value) => stall.avg300 = value.parse().ok()?,
+                    ("total", value) => stall.total_micros = value.parse().ok()?,
+                    _ => (),
+                }
+            }
+            Some(stall)
+        })
+        .collect()
+}
+
+impl ProcessConfig {
+    fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.cmdlines.is_empty()
+    }
+
+    /// Returns the name of `process` if either its name or its command line
+    /// matches one of the configured patterns.
+    async fn matches(&self, process: &Process) -> Option<String> {
+        let name = process.name().await.ok()?;
+        if self.names.iter().any(|regex| regex.0.is_match(&name)) {
+            return Some(name);
+        }
+        if self.cmdlines.is_empty() {
+            return None;
+        }
+
+        let command = process.command().await.ok()?;
+        let cmdline = (&command)
+            .into_iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.cmdlines.iter().any(|regex| regex.0.is_match(&cmdline)) {
+            Some(name)
+        } else {
+            None
+        }
+    }
+}
+
 // Pattern doesn't implement Deserialize or Serialize, and we can't
 // implement them ourselves due the orphan rules, so make a wrapper.
 #[derive(Clone, Debug)]
@@ -778,6 +1278,36 @@ impl Serialize for PatternWrapper {
     }
 }
 
+// Same as above for Regex.
+#[derive(Clone, Debug)]
+struct RegexWrapper(Regex);
+
+impl<'de> Deserialize<'de> for RegexWrapper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(RegexVisitor)
+    }
+}
+
+struct RegexVisitor;
+
+impl<'de> Visitor<'de> for RegexVisitor {
+    type Value = RegexWrapper;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "a regular expression")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        Regex::new(s).map(RegexWrapper).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for RegexWrapper {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -856,6 +1386,11 @@ mod tests {
             Collector::Load,
             Collector::Memory,
             Collector::Network,
+            Collector::Nfs,
+            Collector::Pressure,
+            Collector::Process,
+            Collector::Tcp,
+            Collector::Udp,
         ] {
             let some_metrics = HostMetricsConfig {
                 collectors: Some(vec![*collector]),
@@ -872,6 +1407,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn scrapes_collectors_at_their_intervals() {
+        let config: HostMetricsConfig = toml::from_str(
+            r#"
+            scrape_interval_secs = 10
+            collectors = ["cpu", "disk", "load"]
+            cpu.scrape_interval_secs = 5
+            disk.scrape_interval_secs = 60
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.scrape_interval(Collector::Cpu), 5);
+        assert_eq!(config.scrape_interval(Collector::Disk), 60);
+        assert_eq!(config.scrape_interval(Collector::Load), 10);
+
+        let collectors = |elapsed| {
+            COLLECTORS
+                .iter()
+                .filter(|&&collector| config.is_due(collector, elapsed))
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            collectors(0),
+            vec![Collector::Cpu, Collector::Disk, Collector::Load]
+        );
+        assert_eq!(collectors(5), vec![Collector::Cpu]);
+        assert_eq!(collectors(10), vec![Collector::Cpu, Collector::Load]);
+        assert_eq!(
+            collectors(60),
+            vec![Collector::Cpu, Collector::Disk, Collector::Load]
+        );
+    }
+
     #[tokio::test]
     async fn are_taged_with_hostname() {
         let mut metrics = HostMetricsConfig::default().capture_metrics().await;
@@ -949,7 +1518,10 @@ mod tests {
     async fn filters_disk_metrics_on_device() {
         assert_filtered_metrics("device", |devices| async {
             HostMetricsConfig {
-                disk: DiskConfig { devices },
+                disk: DiskConfig {
+                    devices,
+                    ..Default::default()
+                },
                 ..Default::default()
             }
             .disk_metrics()
@@ -1057,7 +1629,10 @@ mod tests {
     async fn network_metrics_filters_on_device() {
         assert_filtered_metrics("device", |devices| async {
             HostMetricsConfig {
-                network: NetworkConfig { devices },
+                network: NetworkConfig {
+                    devices,
+                    ..Default::default()
+                },
                 ..Default::default()
             }
             .network_metrics()
@@ -1080,6 +1655,132 @@ mod tests {
             .any(|metric| !metric.name.starts_with("load")));
     }
 
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn generates_tcp_metrics() {
+        let metrics = HostMetricsConfig::default().tcp_metrics().await;
+        assert_eq!(metrics.len(), TCP_STATES.len());
+        assert!(all_gauges(&metrics));
+        assert_eq!(count_tag(&metrics, "state"), metrics.len());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn counts_tcp_states() {
+        let contents = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 20114 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:8D3C 0100007F:1F90 01 00000000:00000000 02:0000059A 00000000  1000        0 98240 2 0000000000000000 20 4 30 10 -1
+   2: 0100007F:1F90 0100007F:8D3C 01 00000000:00000000 02:0000059A 00000000  1000        0 98241 2 0000000000000000 20 4 30 10 -1
+";
+        let mut counts = BTreeMap::new();
+        count_tcp_states(contents, &mut counts);
+        assert_eq!(counts["established"], 2);
+        assert_eq!(counts["listen"], 1);
+        assert_eq!(counts.len(), 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_snmp_counters() {
+        let contents = "Ip: Forwarding DefaultTTL
+Ip: 1 64
+Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors
+Udp: 1204 12 3 1187 2 0
+UdpLite: InDatagrams NoPorts
+UdpLite: 0 0
+";
+        let counters = parse_snmp(contents, "Udp");
+        assert_eq!(counters["InDatagrams"], 1204);
+        assert_eq!(counters["OutDatagrams"], 1187);
+        assert_eq!(counters["RcvbufErrors"], 2);
+        assert_eq!(counters.len(), 6);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_nfs_rpc() {
+        let contents = "net 0 0 0 0\nrpc 5432 7 5432\nproc4 61 1 2 3\n";
+        assert_eq!(parse_nfs_rpc(contents), Some((5432, 7, 5432)));
+        assert_eq!(parse_nfs_rpc("net 0 0 0 0\n"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_pressure() {
+        let contents = "some avg10=1.50 avg60=0.25 avg300=0.00 total=2500000
+full avg10=0.00 avg60=0.00 avg300=0.00 total=12
+";
+        assert_eq!(
+            parse_pressure(contents),
+            vec![
+                PressureStall {
+                    kind: "some",
+                    avg10: 1.5,
+                    avg60: 0.25,
+                    avg300: 0.0,
+                    total_micros: 2_500_000,
+                },
+                PressureStall {
+                    kind: "full",
+                    avg10: 0.0,
+                    avg60: 0.0,
+                    avg300: 0.0,
+                    total_micros: 12,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn process_metrics_require_patterns() {
+        let metrics = HostMetricsConfig::default().process_metrics().await;
+        assert!(metrics.is_empty());
+    }
+
+    #[tokio::test]
+    async fn generates_process_metrics() {
+        let pid = std::process::id().to_string();
+        let name = heim::process::current()
+            .await
+            .unwrap()
+            .name()
+            .await
+            .unwrap();
+        let config = HostMetricsConfig {
+            process: ProcessConfig {
+                names: vec![RegexWrapper(
+                    Regex::new(&format!("^{}$", regex::escape(&name))).unwrap(),
+                )],
+                cmdlines: vec![],
+            },
+            ..Default::default()
+        };
+
+        let metrics = config
+            .process_metrics()
+            .await
+            .into_iter()
+            .filter(|metric| metric.tags.as_ref().unwrap()["pid"] == pid)
+            .collect::<Vec<_>>();
+        assert_eq!(count_name(&metrics, "process_cpu_seconds_total"), 2);
+        assert_eq!(count_name(&metrics, "process_memory_rss_bytes"), 1);
+        #[cfg(target_os = "linux")]
+        assert_eq!(count_name(&metrics, "process_open_fds"), 1);
+        assert!(all_tags_match(&metrics, "name", |s| s == name));
+    }
+
+    #[tokio::test]
+    async fn process_metrics_match_cmdline() {
+        let config = HostMetricsConfig {
+            process: ProcessConfig {
+                names: vec![],
+                cmdlines: vec![RegexWrapper(Regex::new("^no such command line$").unwrap())],
+            },
+            ..Default::default()
+        };
+        assert!(config.process_metrics().await.is_empty());
+    }
+
     fn all_counters(metrics: &[Metric]) -> bool {
         !metrics
             .iter()
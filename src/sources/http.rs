@@ -4,6 +4,7 @@ use crate::{
         SourceDescription,
     },
     event::{Event, Value},
+    http::{CorsConfig, TraceContext},
     shutdown::ShutdownSignal,
     sources::util::{add_query_parameters, ErrorMessage, HttpSource, HttpSourceAuthConfig},
     tls::TlsConfig,
@@ -13,8 +14,11 @@ use bytes::{Bytes, BytesMut};
 use chrono::Utc;
 use codec::BytesDelimitedCodec;
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
-use std::{collections::HashMap, net::SocketAddr};
+use serde_json::{json, Value as JsonValue};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+};
 
 use tokio_util::codec::Decoder;
 use warp::http::{HeaderMap, HeaderValue, StatusCode};
@@ -30,6 +34,78 @@ pub struct SimpleHttpConfig {
     query_parameters: Vec<String>,
     tls: Option<TlsConfig>,
     auth: Option<HttpSourceAuthConfig>,
+    cors: Option<CorsConfig>,
+    openapi: Option<OpenApiConfig>,
+    trace_context_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpenApiConfig {
+    #[serde(default = "default_openapi_title")]
+    title: String,
+    #[serde(default = "default_openapi_version")]
+    version: String,
+    description: Option<String>,
+    #[serde(default)]
+    fields: BTreeMap<String, FieldConfig>,
+    #[serde(default = "crate::serde::default_true")]
+    validate: bool,
+}
+
+fn default_openapi_title() -> String {
+    "Vector HTTP source".to_owned()
+}
+
+fn default_openapi_version() -> String {
+    "1.0.0".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FieldConfig {
+    #[serde(rename = "type")]
+    kind: Option<FieldType>,
+    #[serde(default)]
+    required: bool,
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object,
+    Array,
+}
+
+impl FieldType {
+    fn matches(self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (FieldType::String, Value::Bytes(_))
+                | (FieldType::Integer, Value::Integer(_))
+                | (FieldType::Number, Value::Integer(_))
+                | (FieldType::Number, Value::Float(_))
+                | (FieldType::Boolean, Value::Boolean(_))
+                | (FieldType::Object, Value::Map(_))
+                | (FieldType::Array, Value::Array(_))
+        )
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Integer => "integer",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::Object => "object",
+            FieldType::Array => "array",
+        }
+    }
 }
 
 inventory::submit! {
@@ -45,6 +121,9 @@ impl GenerateConfig for SimpleHttpConfig {
             query_parameters: Vec::new(),
             tls: None,
             auth: None,
+            cors: None,
+            openapi: None,
+            trace_context_key: None,
         })
         .unwrap()
     }
@@ -55,6 +134,15 @@ struct SimpleHttpSource {
     encoding: Encoding,
     headers: Vec<String>,
     query_parameters: Vec<String>,
+    openapi: Option<OpenApi>,
+    trace_context_key: Option<String>,
+}
+
+#[derive(Clone)]
+struct OpenApi {
+    document: JsonValue,
+    fields: BTreeMap<String, FieldConfig>,
+    validate: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative, Copy)]
@@ -74,9 +162,27 @@ impl HttpSource for SimpleHttpSource {
         header_map: HeaderMap,
         query_parameters: HashMap<String, String>,
     ) -> Result<Vec<Event>, ErrorMessage> {
+        let trace_context = self
+            .trace_context_key
+            .as_ref()
+            .and_then(|key| TraceContext::from_headers(&header_map).map(|context| (key, context)));
         decode_body(body, self.encoding)
+            .and_then(|events| match &self.openapi {
+                Some(openapi) if openapi.validate && self.encoding != Encoding::Text => {
+                    validate_events(&events, &openapi.fields).map(|_| events)
+                }
+                _ => Ok(events),
+            })
             .map(|events| add_headers(events, &self.headers, header_map))
             .map(|events| add_query_parameters(events, &self.query_parameters, query_parameters))
+            .map(|mut events| {
+                if let Some((key, context)) = trace_context {
+                    for event in events.iter_mut() {
+                        event.as_mut_log().insert(key, context.to_value());
+                    }
+                }
+                events
+            })
             .map(|mut events| {
                 // Add source type
                 let key = log_schema().source_type_key();
@@ -86,6 +192,12 @@ impl HttpSource for SimpleHttpSource {
                 events
             })
     }
+
+    fn openapi(&self) -> Option<JsonValue> {
+        self.openapi
+            .as_ref()
+            .map(|openapi| openapi.document.clone())
+    }
 }
 
 #[async_trait::async_trait]
@@ -102,8 +214,22 @@ impl SourceConfig for SimpleHttpConfig {
             encoding: self.encoding,
             headers: self.headers.clone(),
             query_parameters: self.query_parameters.clone(),
+            openapi: self.openapi.as_ref().map(|openapi| OpenApi {
+                document: openapi_document(self, openapi),
+                fields: openapi.fields.clone(),
+                validate: openapi.validate,
+            }),
+            trace_context_key: self.trace_context_key.clone(),
         };
-        source.run(self.address, "", &self.tls, &self.auth, out, shutdown)
+        source.run(
+            self.address,
+            "",
+            &self.tls,
+            &self.auth,
+            &self.cors,
+            out,
+            shutdown,
+        )
     }
 
     fn output_type(&self) -> DataType {
@@ -119,6 +245,137 @@ impl SourceConfig for SimpleHttpConfig {
     }
 }
 
+/// Describes the requests accepted by the source as an OpenAPI 3 document.
+fn openapi_document(config: &SimpleHttpConfig, openapi: &OpenApiConfig) -> JsonValue {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for (name, field) in &openapi.fields {
+        let mut schema = serde_json::Map::new();
+        if let Some(kind) = field.kind {
+            schema.insert("type".to_owned(), kind.as_str().into());
+        }
+        if let Some(description) = &field.description {
+            schema.insert("description".to_owned(), description.as_str().into());
+        }
+        properties.insert(name.clone(), schema.into());
+        if field.required {
+            required.push(JsonValue::from(name.as_str()));
+        }
+    }
+    let mut object = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        object["required"] = required.into();
+    }
+
+    let (media_type, schema) = match config.encoding {
+        Encoding::Text => (
+            "text/plain",
+            json!({
+                "type": "string",
+                "description": "Newline-delimited lines, each forming a message.",
+            }),
+        ),
+        Encoding::Ndjson => (
+            "application/x-ndjson",
+            json!({
+                "description": "Newline-delimited JSON objects, each forming an event.",
+                "allOf": [object],
+            }),
+        ),
+        Encoding::Json => (
+            "application/json",
+            json!({
+                "description": "A JSON object, or an array of JSON objects, each forming an event.",
+                "oneOf": [object, { "type": "array", "items": object }],
+            }),
+        ),
+    };
+
+    let parameters = config
+        .query_parameters
+        .iter()
+        .map(|name| (name, "query"))
+        .chain(config.headers.iter().map(|name| (name, "header")))
+        .map(|(name, location)| {
+            json!({
+                "name": name,
+                "in": location,
+                "required": false,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let error = json!({
+        "application/json": {
+            "schema": {
+                "type": "object",
+                "properties": {
+                    "code": { "type": "integer" },
+                    "message": { "type": "string" },
+                },
+            },
+        },
+    });
+    let mut responses = json!({
+        "200": { "description": "The events were accepted." },
+        "400": { "description": "The payload is malformed or invalid.", "content": error },
+    });
+    let mut operation = json!({
+        "summary": "Send events",
+        "parameters": parameters,
+        "requestBody": {
+            "required": true,
+            "content": { media_type: { "schema": schema } },
+        },
+    });
+
+    let mut document = json!({
+        "openapi": "3.0.3",
+        "info": { "title": openapi.title, "version": openapi.version },
+    });
+    if let Some(description) = &openapi.description {
+        document["info"]["description"] = description.as_str().into();
+    }
+    if config.auth.is_some() {
+        responses["401"] =
+            json!({ "description": "The credentials are missing or invalid.", "content": error });
+        operation["security"] = json!([{ "basic": [] }]);
+        document["components"] = json!({
+            "securitySchemes": { "basic": { "type": "http", "scheme": "basic" } },
+        });
+    }
+    operation["responses"] = responses;
+    document["paths"] = json!({ "/": { "post": operation } });
+    document
+}
+
+fn validate_events(
+    events: &[Event],
+    fields: &BTreeMap<String, FieldConfig>,
+) -> Result<(), ErrorMessage> {
+    for (index, event) in events.iter().enumerate() {
+        let log = event.as_log();
+        for (name, field) in fields {
+            let error = match (log.get_flat(name), field.kind) {
+                (None, _) if field.required => format!("field `{}` is required", name),
+                (Some(value), Some(kind)) if !kind.matches(value) => format!(
+                    "field `{}` must be of type {}, got {}",
+                    name,
+                    kind.as_str(),
+                    value.kind()
+                ),
+                _ => continue,
+            };
+            return Err(ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid event {}: {}", index, error),
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn add_headers(
     mut events: Vec<Event>,
     headers_config: &[String],
@@ -232,12 +489,13 @@ fn json_value_to_type_string(value: &JsonValue) -> &'static str {
 
 #[cfg(test)]
 mod tests {
-    use super::{Encoding, SimpleHttpConfig};
+    use super::{Encoding, OpenApiConfig, SimpleHttpConfig};
 
     use crate::shutdown::ShutdownSignal;
     use crate::{
         config::{log_schema, GlobalOptions, SourceConfig},
         event::{Event, Value},
+        http::CorsConfig,
         test_util::{collect_n, next_addr, trace_init, wait_for_tcp},
         Pipeline,
     };
@@ -256,6 +514,7 @@ mod tests {
         encoding: Encoding,
         headers: Vec<String>,
         query_parameters: Vec<String>,
+        cors: Option<CorsConfig>,
     ) -> (mpsc::Receiver<Event>, SocketAddr) {
         let (sender, recv) = Pipeline::new_test();
         let address = next_addr();
@@ -267,6 +526,9 @@ mod tests {
                 query_parameters,
                 tls: None,
                 auth: None,
+                cors,
+                openapi: None,
+                trace_context_key: None,
             }
             .build(
                 "default",
@@ -323,7 +585,7 @@ mod tests {
 
         let body = "test body\n\ntest body 2";
 
-        let (rx, addr) = source(Encoding::default(), vec![], vec![]).await;
+        let (rx, addr) = source(Encoding::default(), vec![], vec![], None).await;
 
         assert_eq!(200, send(addr, body).await);
 
@@ -351,7 +613,7 @@ mod tests {
         //same as above test but with a newline at the end
         let body = "test body\n\ntest body 2\n";
 
-        let (rx, addr) = source(Encoding::default(), vec![], vec![]).await;
+        let (rx, addr) = source(Encoding::default(), vec![], vec![], None).await;
 
         assert_eq!(200, send(addr, body).await);
 
@@ -376,7 +638,7 @@ mod tests {
     async fn http_json_parsing() {
         trace_init();
 
-        let (rx, addr) = source(Encoding::Json, vec![], vec![]).await;
+        let (rx, addr) = source(Encoding::Json, vec![], vec![], None).await;
 
         assert_eq!(400, send(addr, "{").await); //malformed
         assert_eq!(400, send(addr, r#"{"key"}"#).await); //key without value
@@ -401,7 +663,7 @@ mod tests {
     async fn http_json_values() {
         trace_init();
 
-        let (rx, addr) = source(Encoding::Json, vec![], vec![]).await;
+        let (rx, addr) = source(Encoding::Json, vec![], vec![], None).await;
 
         assert_eq!(200, send(addr, r#"[{"key":"value"}]"#).await);
         assert_eq!(200, send(addr, r#"{"key2":"value2"}"#).await);
@@ -427,7 +689,7 @@ mod tests {
     async fn http_json_dotted_keys() {
         trace_init();
 
-        let (rx, addr) = source(Encoding::Json, vec![], vec![]).await;
+        let (rx, addr) = source(Encoding::Json, vec![], vec![], None).await;
 
         assert_eq!(200, send(addr, r#"[{"dotted.key":"value"}]"#).await);
         assert_eq!(
@@ -454,7 +716,7 @@ mod tests {
     async fn http_ndjson() {
         trace_init();
 
-        let (rx, addr) = source(Encoding::Ndjson, vec![], vec![]).await;
+        let (rx, addr) = source(Encoding::Ndjson, vec![], vec![], None).await;
 
         assert_eq!(400, send(addr, r#"[{"key":"value"}]"#).await); //one object per line
 
@@ -496,6 +758,7 @@ mod tests {
                 "AbsentHeader".to_string(),
             ],
             vec![],
+            None,
         )
         .await;
 
@@ -528,6 +791,7 @@ mod tests {
                 "region".to_string(),
                 "absent".to_string(),
             ],
+            None,
         )
         .await;
 
@@ -548,4 +812,177 @@ mod tests {
             assert_eq!(log[log_schema().source_type_key()], "http".into());
         }
     }
+
+    #[tokio::test]
+    async fn http_trace_context() {
+        trace_init();
+        let (sender, rx) = Pipeline::new_test();
+        let address = next_addr();
+        tokio::spawn(async move {
+            SimpleHttpConfig {
+                address,
+                encoding: Encoding::Ndjson,
+                headers: vec![],
+                query_parameters: vec![],
+                tls: None,
+                auth: None,
+                cors: None,
+                openapi: None,
+                trace_context_key: Some("trace".to_string()),
+            }
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                sender,
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let traceparent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", traceparent.parse().unwrap());
+        headers.insert("tracestate", "congo=t61rcWkgMzE".parse().unwrap());
+        assert_eq!(
+            200,
+            send_with_headers(
+                address,
+                "{\"key1\":\"value1\"}\n{\"key1\":\"value2\"}",
+                headers
+            )
+            .await
+        );
+
+        let mut invalid = HeaderMap::new();
+        invalid.insert("traceparent", "00-invalid".parse().unwrap());
+        assert_eq!(
+            200,
+            send_with_headers(address, "{\"key1\":\"value3\"}", invalid).await
+        );
+
+        let events = collect_n(rx, 3).await.unwrap();
+        for event in &events[..2] {
+            let log = event.as_log();
+            assert_eq!(log["trace.traceparent"], traceparent.into());
+            assert_eq!(log["trace.tracestate"], "congo=t61rcWkgMzE".into());
+        }
+        assert!(!events[2].as_log().contains("trace"));
+    }
+
+    #[tokio::test]
+    async fn http_cors_preflight() {
+        trace_init();
+        let (_rx, addr) = source(
+            Encoding::default(),
+            vec![],
+            vec![],
+            Some(CorsConfig {
+                allowed_origins: vec!["https://example.com".to_string()],
+                allowed_headers: vec!["Content-Type".to_string()],
+                max_age_secs: Some(600),
+            }),
+        )
+        .await;
+
+        let preflight = |origin: &'static str| {
+            reqwest::Client::new()
+                .request(reqwest::Method::OPTIONS, &format!("http://{}/", addr))
+                .header("Origin", origin)
+                .header("Access-Control-Request-Method", "POST")
+                .header("Access-Control-Request-Headers", "content-type")
+                .send()
+        };
+
+        let response = preflight("https://example.com").await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let headers = response.headers();
+        assert_eq!(
+            headers["access-control-allow-origin"],
+            "https://example.com"
+        );
+        assert_eq!(headers["access-control-max-age"], "600");
+
+        let response = preflight("https://other.example.com").await.unwrap();
+        assert_eq!(response.status().as_u16(), 403);
+    }
+
+    #[tokio::test]
+    async fn http_openapi() {
+        trace_init();
+
+        let openapi: OpenApiConfig = toml::from_str(
+            r#"
+            title = "Checkout events"
+
+            [fields.user_id]
+            type = "string"
+            required = true
+
+            [fields.amount]
+            type = "number"
+            "#,
+        )
+        .unwrap();
+        let (sender, rx) = Pipeline::new_test();
+        let address = next_addr();
+        tokio::spawn(async move {
+            SimpleHttpConfig {
+                address,
+                encoding: Encoding::Json,
+                headers: vec!["User-Agent".to_string()],
+                query_parameters: vec![],
+                tls: None,
+                auth: None,
+                cors: None,
+                openapi: Some(openapi),
+                trace_context_key: None,
+            }
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                sender,
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        });
+        wait_for_tcp(address).await;
+
+        let document: serde_json::Value = reqwest::get(&format!("http://{}/openapi.json", address))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(document["info"]["title"], "Checkout events");
+        let operation = &document["paths"]["/"]["post"];
+        assert_eq!(operation["parameters"][0]["name"], "User-Agent");
+        let object = &operation["requestBody"]["content"]["application/json"]["schema"]["oneOf"][0];
+        assert_eq!(object["properties"]["amount"]["type"], "number");
+        assert_eq!(object["required"], serde_json::json!(["user_id"]));
+
+        assert_eq!(400, send(address, r#"{"amount":12.5}"#).await);
+        assert_eq!(
+            400,
+            send(
+                address,
+                r#"[{"user_id":"a"},{"user_id":"b","amount":"12"}]"#
+            )
+            .await
+        );
+        assert_eq!(
+            200,
+            send(address, r#"[{"user_id":"a","amount":12},{"user_id":"b"}]"#).await
+        );
+
+        let events = collect_n(rx, 2).await.unwrap();
+        assert_eq!(events[0].as_log()["user_id"], "a".into());
+        assert_eq!(events[1].as_log()["user_id"], "b".into());
+    }
 }
@@ -0,0 +1,130 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
+    internal_events::{InternalLogsEventReceived, InternalLogsEventsDropped},
+    shutdown::ShutdownSignal,
+    trace, Pipeline,
+};
+use bytes::Bytes;
+use futures::{compat::Sink01CompatExt, SinkExt, StreamExt};
+use futures01::Sink;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::RecvError;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct InternalLogsConfig {
+    pub host_key: Option<String>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<InternalLogsConfig>("internal_logs")
+}
+
+impl_generate_config_from_default!(InternalLogsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "internal_logs")]
+impl SourceConfig for InternalLogsConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_owned());
+        // Subscribe right away to not miss the logs of the topology starting up.
+        let logs = trace::subscribe();
+
+        Ok(Box::pin(async move {
+            let mut out = out
+                .sink_map_err(|error| error!(message = "Error sending log.", %error))
+                .sink_compat();
+            let hostname = crate::get_hostname().ok();
+
+            let mut logs = logs.into_stream().take_until(shutdown);
+            while let Some(log) = logs.next().await {
+                let mut event = match log {
+                    Ok(event) => event,
+                    // The oldest logs are dropped if the source falls behind.
+                    Err(RecvError::Lagged(count)) => {
+                        emit!(InternalLogsEventsDropped { count });
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                emit!(InternalLogsEventReceived);
+
+                let log = event.as_mut_log();
+                log.insert(log_schema().source_type_key(), Bytes::from("internal_logs"));
+                if let Some(hostname) = &hostname {
+                    log.insert(&host_key, hostname.clone());
+                }
+                out.send(event).await?;
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "internal_logs"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::collect_n, trace::BroadcastLayer};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<InternalLogsConfig>();
+    }
+
+    #[tokio::test]
+    async fn receives_logs() {
+        let (tx, rx) = Pipeline::new_test();
+        let source = InternalLogsConfig::default()
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        let subscriber = tracing_subscriber::registry().with(BroadcastLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            error!(
+                message = "Something failed.",
+                attempts = 3,
+                rate_limit_secs = 30
+            );
+        });
+
+        let events = collect_n(rx, 1).await.unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["message"], "Something failed.".into());
+        assert_eq!(log["attempts"], 3.into());
+        assert_eq!(log["metadata.level"], "ERROR".into());
+        assert_eq!(
+            log["metadata.module_path"],
+            "vector::sources::internal_logs::tests".into()
+        );
+        assert_eq!(log["source_type"], "internal_logs".into());
+        assert!(log.get("rate_limit_secs").is_none());
+        assert!(log.get("timestamp").is_some());
+    }
+}
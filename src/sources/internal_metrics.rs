@@ -1,5 +1,5 @@
 use crate::{
-    config::{DataType, GlobalOptions, SourceConfig, SourceDescription},
+    config::{DataType, GlobalOptions, GlobalTags, SourceConfig, SourceDescription},
     metrics::Controller,
     metrics::{capture_metrics, get_controller},
     shutdown::ShutdownSignal,
@@ -41,12 +41,13 @@ impl SourceConfig for InternalMetricsConfig {
     async fn build(
         &self,
         _name: &str,
-        _globals: &GlobalOptions,
+        globals: &GlobalOptions,
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
         Ok(Box::pin(run(
             get_controller()?,
+            globals.global_tags(),
             self.scrape_interval_secs,
             out,
             shutdown,
@@ -64,6 +65,7 @@ impl SourceConfig for InternalMetricsConfig {
 
 async fn run(
     controller: &Controller,
+    tags: GlobalTags,
     interval: u64,
     out: Pipeline,
     shutdown: ShutdownSignal,
@@ -75,7 +77,10 @@ async fn run(
     let duration = time::Duration::from_secs(interval);
     let mut interval = time::interval(duration).take_until(shutdown);
     while interval.next().await.is_some() {
-        let metrics = capture_metrics(controller);
+        let metrics = capture_metrics(controller).map(|mut event| {
+            tags.tag(&mut event);
+            event
+        });
         out.send_all(&mut stream::iter(metrics).map(Ok)).await?;
     }
 
@@ -0,0 +1,545 @@
+use crate::{
+    config::{DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        LogEvent,
+    },
+    http::{Auth, HttpClient},
+    internal_events::{
+        JmxMetricsCollectCompleted, JmxMetricsParseError, JmxMetricsReadError,
+        JmxMetricsRequestError,
+    },
+    shutdown::ShutdownSignal,
+    template::{Template, TemplateError},
+    tls::{TlsOptions, TlsSettings},
+    Event, Pipeline,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{
+    compat::Sink01CompatExt, future::join_all, stream, SinkExt, StreamExt, TryFutureExt,
+};
+use futures01::Sink;
+use http::{header, Request, StatusCode};
+use hyper::{body::to_bytes as body_to_bytes, Body, Uri};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::BTreeMap, convert::TryFrom, future::ready, time::Instant};
+use tokio::time;
+
+pub mod parser;
+use parser::{is_pattern, numeric_values, JolokiaResponse, ObjectName};
+
+macro_rules! gauge {
+    ($value:expr) => {
+        MetricValue::Gauge {
+            value: $value as f64,
+        }
+    };
+}
+
+#[derive(Debug, Snafu)]
+enum JmxBuildError {
+    #[snafu(display("Failed to parse endpoint: {}", source))]
+    HostInvalidUri { source: http::uri::InvalidUri },
+    #[snafu(display("At least one rule is required"))]
+    NoRules,
+    #[snafu(display("Invalid template {:?} of rule for {:?}: {}", template, mbean, source))]
+    InvalidTemplate {
+        mbean: String,
+        template: String,
+        source: TemplateError,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum JmxError {
+    #[snafu(display("Invalid response status: {}", status))]
+    InvalidResponseStatus { status: StatusCode },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct JmxMetricsConfig {
+    endpoints: Vec<String>,
+    #[serde(default = "default_scrape_interval_secs")]
+    scrape_interval_secs: u64,
+    #[serde(default = "default_namespace")]
+    namespace: String,
+    rules: Vec<RuleConfig>,
+    tls: Option<TlsOptions>,
+    auth: Option<Auth>,
+}
+
+/// Which MBeans and attributes to read, and how to map them to metrics.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct RuleConfig {
+    /// The name of the MBean, or a pattern such as `java.lang:type=GarbageCollector,*`.
+    mbean: String,
+    /// The attributes to read, or all of them if empty.
+    #[serde(default)]
+    attributes: Vec<String>,
+    /// The template of the metric names, rendered with the keys of the MBean
+    /// name, `domain` and `attribute`.
+    #[serde(default = "default_name")]
+    name: String,
+    #[serde(default, rename = "type")]
+    metric_type: JmxMetricType,
+    /// The templates of the tags. By default, the keys of the MBean name
+    /// which aren't part of the metric name are the tags.
+    tags: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum JmxMetricType {
+    Gauge,
+    Counter,
+}
+
+impl Default for JmxMetricType {
+    fn default() -> Self {
+        Self::Gauge
+    }
+}
+
+pub fn default_scrape_interval_secs() -> u64 {
+    15
+}
+
+pub fn default_namespace() -> String {
+    "jmx".to_string()
+}
+
+fn default_name() -> String {
+    "{{ domain }}_{{ type }}_{{ attribute }}".to_string()
+}
+
+inventory::submit! {
+    SourceDescription::new::<JmxMetricsConfig>("jmx_metrics")
+}
+
+impl_generate_config_from_default!(JmxMetricsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "jmx_metrics")]
+impl SourceConfig for JmxMetricsConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let http_client = HttpClient::new(tls)?;
+
+        if self.rules.is_empty() {
+            return Err(JmxBuildError::NoRules.into());
+        }
+        let rules = self
+            .rules
+            .iter()
+            .map(Rule::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let body = Bytes::from(
+            serde_json::to_vec(&rules.iter().map(Rule::request).collect::<Vec<_>>())
+                .expect("Jolokia requests are serializable."),
+        );
+
+        let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
+        let mut sources = Vec::with_capacity(self.endpoints.len());
+        for endpoint in self.endpoints.iter() {
+            sources.push(JmxMetrics::new(
+                http_client.clone(),
+                endpoint.clone(),
+                self.auth.clone(),
+                namespace.clone(),
+                rules.clone(),
+                body.clone(),
+            )?);
+        }
+
+        let mut out = out
+            .sink_map_err(|error| error!(message = "Error sending jmx metrics.", %error))
+            .sink_compat();
+
+        let duration = time::Duration::from_secs(self.scrape_interval_secs);
+        Ok(Box::pin(async move {
+            let mut interval = time::interval(duration).take_until(shutdown);
+            while interval.next().await.is_some() {
+                let start = Instant::now();
+                let metrics = join_all(sources.iter().map(|jmx| jmx.collect())).await;
+                emit!(JmxMetricsCollectCompleted {
+                    start,
+                    end: Instant::now()
+                });
+
+                let mut stream = stream::iter(metrics).flatten().map(Event::Metric).map(Ok);
+                out.send_all(&mut stream).await?;
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        "jmx_metrics"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    mbean: String,
+    attributes: Vec<String>,
+    name: Template,
+    metric_type: JmxMetricType,
+    tags: Option<BTreeMap<String, Template>>,
+}
+
+impl Rule {
+    fn new(config: &RuleConfig) -> Result<Self, JmxBuildError> {
+        let template = |template: &str| {
+            Template::try_from(template).context(InvalidTemplate {
+                mbean: config.mbean.clone(),
+                template: template.to_owned(),
+            })
+        };
+
+        Ok(Self {
+            mbean: config.mbean.clone(),
+            attributes: config.attributes.clone(),
+            name: template(&config.name)?,
+            metric_type: config.metric_type,
+            tags: config
+                .tags
+                .as_ref()
+                .map(|tags| {
+                    tags.iter()
+                        .map(|(key, value)| Ok((key.clone(), template(value)?)))
+                        .collect::<Result<_, JmxBuildError>>()
+                })
+                .transpose()?,
+        })
+    }
+
+    /// The read request of the rule, reading all attributes if none are
+    /// specified. Errors reading single attributes, such as unsupported
+    /// operations, are reported in place of their values rather than failing
+    /// the whole request.
+    /// https://jolokia.org/reference/html/protocol.html#read
+    fn request(&self) -> serde_json::Value {
+        let mut request = serde_json::json!({
+            "type": "read",
+            "mbean": self.mbean,
+            "config": { "ignoreErrors": true },
+        });
+        if !self.attributes.is_empty() {
+            request["attribute"] = serde_json::json!(self.attributes);
+        }
+        request
+    }
+
+    /// The values of the response, as the MBean names and attributes with
+    /// their values.
+    fn values<'a>(
+        &self,
+        value: &'a serde_json::Value,
+    ) -> Vec<(String, &'a serde_json::Map<String, serde_json::Value>)> {
+        match value {
+            serde_json::Value::Object(map) if is_pattern(&self.mbean) => map
+                .iter()
+                .filter_map(|(name, attributes)| Some((name.clone(), attributes.as_object()?)))
+                .collect(),
+            serde_json::Value::Object(attributes) => vec![(self.mbean.clone(), attributes)],
+            _ => vec![],
+        }
+    }
+
+    /// The metrics of the value of the response, by name and tags. The
+    /// metrics of MBeans missing any of the keys used in the name are
+    /// skipped, as are the tags missing any of the keys used in them.
+    fn metrics(
+        &self,
+        value: &serde_json::Value,
+        endpoint: &str,
+    ) -> Vec<(String, BTreeMap<String, String>, MetricValue)> {
+        let name_keys = self.name.get_fields().unwrap_or_default();
+        let mut metrics = Vec::new();
+        for (mbean, attributes) in self.values(value) {
+            let object_name = match ObjectName::parse(&mbean) {
+                Ok(object_name) => object_name,
+                Err(error) => {
+                    emit!(JmxMetricsParseError { error, endpoint });
+                    continue;
+                }
+            };
+
+            for (attribute, value) in attributes {
+                for (attribute, value) in numeric_values(attribute, value) {
+                    let mut fields = LogEvent::default();
+                    for (key, value) in &object_name.properties {
+                        fields.insert(key.as_str(), value.clone());
+                    }
+                    fields.insert("domain", object_name.domain.clone());
+                    fields.insert("attribute", attribute);
+                    let fields = Event::Log(fields);
+
+                    let name = match self.name.render_string(&fields) {
+                        Ok(name) => sanitize_name(&name),
+                        Err(_) => continue,
+                    };
+                    let tags = match &self.tags {
+                        Some(tags) => tags
+                            .iter()
+                            .filter_map(|(key, template)| {
+                                Some((key.clone(), template.render_string(&fields).ok()?))
+                            })
+                            .collect(),
+                        None => object_name
+                            .properties
+                            .iter()
+                            .filter(|(key, _)| !name_keys.contains(key))
+                            .map(|(key, value)| (key.clone(), value.clone()))
+                            .collect(),
+                    };
+                    let value = match self.metric_type {
+                        JmxMetricType::Gauge => MetricValue::Gauge { value },
+                        JmxMetricType::Counter => MetricValue::Counter { value },
+                    };
+                    metrics.push((name, tags, value));
+                }
+            }
+        }
+        metrics
+    }
+}
+
+/// Replaces the characters which aren't allowed in metric names by most
+/// sinks, such as the `.` of the domains, with `_`.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[derive(Debug)]
+struct JmxMetrics {
+    http_client: HttpClient,
+    endpoint: String,
+    auth: Option<Auth>,
+    namespace: Option<String>,
+    rules: Vec<Rule>,
+    body: Bytes,
+    tags: BTreeMap<String, String>,
+}
+
+impl JmxMetrics {
+    fn new(
+        http_client: HttpClient,
+        endpoint: String,
+        auth: Option<Auth>,
+        namespace: Option<String>,
+        rules: Vec<Rule>,
+        body: Bytes,
+    ) -> crate::Result<Self> {
+        let mut tags = BTreeMap::new();
+        tags.insert("endpoint".into(), endpoint.clone());
+        tags.insert("host".into(), Self::get_endpoint_host(&endpoint)?);
+
+        Ok(Self {
+            http_client,
+            endpoint,
+            auth,
+            namespace,
+            rules,
+            body,
+            tags,
+        })
+    }
+
+    fn get_endpoint_host(endpoint: &str) -> crate::Result<String> {
+        let uri: Uri = endpoint.parse().context(HostInvalidUri)?;
+        Ok(match (uri.host().unwrap_or(""), uri.port()) {
+            (host, None) => host.to_owned(),
+            (host, Some(port)) => format!("{}:{}", host, port),
+        })
+    }
+
+    async fn collect(&self) -> stream::BoxStream<'static, Metric> {
+        let (up_value, metrics) = match self.collect_metrics().await {
+            Ok(metrics) => (1.0, metrics),
+            Err(()) => (0.0, vec![]),
+        };
+
+        stream::once(ready(self.create_metric(
+            "up".to_owned(),
+            self.tags.clone(),
+            gauge!(up_value),
+        )))
+        .chain(stream::iter(metrics))
+        .boxed()
+    }
+
+    async fn collect_metrics(&self) -> Result<Vec<Metric>, ()> {
+        let body = self.send_request().await.map_err(|error| {
+            emit!(JmxMetricsRequestError {
+                error,
+                endpoint: &self.endpoint,
+            })
+        })?;
+        let responses = JolokiaResponse::parse_bulk(&body).map_err(|error| {
+            emit!(JmxMetricsParseError {
+                error,
+                endpoint: &self.endpoint,
+            })
+        })?;
+
+        let mut metrics = Vec::new();
+        for (rule, response) in self.rules.iter().zip(responses) {
+            if response.status != 200 {
+                emit!(JmxMetricsReadError {
+                    mbean: &rule.mbean,
+                    status: response.status,
+                    error: response.error.as_deref().unwrap_or(""),
+                    endpoint: &self.endpoint,
+                });
+                continue;
+            }
+
+            for (name, tags, value) in rule.metrics(&response.value, &self.endpoint) {
+                let mut all_tags = self.tags.clone();
+                all_tags.extend(tags);
+                metrics.push(self.create_metric(name, all_tags, value));
+            }
+        }
+        Ok(metrics)
+    }
+
+    async fn send_request(&self) -> crate::Result<Bytes> {
+        let mut request = Request::post(&self.endpoint)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(self.body.clone()))?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.http_client.send(request).await?;
+        let (parts, body) = response.into_parts();
+        match parts.status {
+            StatusCode::OK => body_to_bytes(body).err_into().await,
+            status => Err(Box::new(JmxError::InvalidResponseStatus { status })),
+        }
+    }
+
+    fn create_metric(
+        &self,
+        name: String,
+        tags: BTreeMap<String, String>,
+        value: MetricValue,
+    ) -> Metric {
+        Metric {
+            name,
+            namespace: self.namespace.clone(),
+            timestamp: Some(Utc::now()),
+            tags: Some(tags),
+            kind: MetricKind::Absolute,
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JmxMetricsConfig>();
+    }
+
+    fn rule(config: &str) -> Rule {
+        Rule::new(&toml::from_str::<RuleConfig>(config).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn maps_pattern_to_metrics() {
+        let rule = rule(
+            r#"
+            mbean = "kafka.server:type=BrokerTopicMetrics,name=*,topic=*"
+            attributes = ["Count"]
+            name = "kafka_server_{{ name }}_{{ attribute }}"
+            type = "counter"
+            "#,
+        );
+        assert_eq!(
+            rule.request(),
+            json!({
+                "type": "read",
+                "mbean": "kafka.server:type=BrokerTopicMetrics,name=*,topic=*",
+                "attribute": ["Count"],
+                "config": { "ignoreErrors": true },
+            })
+        );
+
+        let metrics = rule.metrics(&json!({
+            "kafka.server:name=BytesInPerSec,topic=logs,type=BrokerTopicMetrics": { "Count": 42 },
+        }), "");
+        assert_eq!(
+            metrics,
+            vec![(
+                "kafka_server_BytesInPerSec_Count".to_owned(),
+                vec![
+                    ("topic".to_owned(), "logs".to_owned()),
+                    ("type".to_owned(), "BrokerTopicMetrics".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+                MetricValue::Counter { value: 42.0 },
+            )]
+        );
+    }
+
+    #[test]
+    fn maps_composite_attributes() {
+        let rule = rule(
+            r#"
+            mbean = "java.lang:type=Memory"
+            attributes = ["HeapMemoryUsage"]
+            tags = { area = "heap", missing = "{{ nope }}" }
+            "#,
+        );
+
+        let mut metrics = rule.metrics(
+            &json!({
+                "HeapMemoryUsage": { "used": 100, "max": 200, "name": "heap" },
+            }),
+            "",
+        );
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        let tags: BTreeMap<_, _> = vec![("area".to_owned(), "heap".to_owned())]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            metrics,
+            vec![
+                (
+                    "java_lang_Memory_HeapMemoryUsage_max".to_owned(),
+                    tags.clone(),
+                    MetricValue::Gauge { value: 200.0 },
+                ),
+                (
+                    "java_lang_Memory_HeapMemoryUsage_used".to_owned(),
+                    tags,
+                    MetricValue::Gauge { value: 100.0 },
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use serde::Deserialize;
+use snafu::Snafu;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("failed to parse Jolokia response: {}", reason))]
+    JolokiaResponseParseError { reason: String },
+    #[snafu(display("invalid MBean name {:?}: {}", name, reason))]
+    ObjectNameParseError { name: String, reason: &'static str },
+}
+
+/// The response to a request of a bulk request to the Jolokia agent.
+/// https://jolokia.org/reference/html/protocol.html#responses
+#[derive(Debug, Deserialize)]
+pub struct JolokiaResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub value: serde_json::Value,
+    pub error: Option<String>,
+}
+
+impl JolokiaResponse {
+    /// The responses to the bulk request, in the order of its requests.
+    pub fn parse_bulk(body: &[u8]) -> Result<Vec<Self>, ParseError> {
+        serde_json::from_slice(body).map_err(|error| ParseError::JolokiaResponseParseError {
+            reason: error.to_string(),
+        })
+    }
+}
+
+/// Whether the MBean name is a pattern matching any number of MBeans, in
+/// which case Jolokia responds with the attributes by MBean name.
+pub fn is_pattern(name: &str) -> bool {
+    name.contains(|c| c == '*' || c == '?')
+}
+
+/// The name of an MBean, `domain:key=value,...`.
+/// https://docs.oracle.com/javase/8/docs/api/javax/management/ObjectName.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectName {
+    pub domain: String,
+    pub properties: BTreeMap<String, String>,
+}
+
+impl ObjectName {
+    pub fn parse(name: &str) -> Result<Self, ParseError> {
+        let error = |reason| ParseError::ObjectNameParseError {
+            name: name.to_owned(),
+            reason,
+        };
+
+        let colon = name.find(':').ok_or_else(|| error("missing `:`"))?;
+        let domain = name[..colon].to_owned();
+
+        let mut properties = BTreeMap::new();
+        let mut chars = name[colon + 1..].chars().peekable();
+        while chars.peek().is_some() {
+            let key = chars.by_ref().take_while(|c| *c != '=').collect::<String>();
+            if key.is_empty() {
+                return Err(error("empty key"));
+            }
+
+            let mut value = String::new();
+            if chars.peek() == Some(&'"') {
+                // Quoted values may hold `,`, `=` and `:`, and escape `"`
+                // and `\` with a backslash.
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => return Err(error("unterminated quoted value")),
+                    }
+                }
+                match chars.next() {
+                    None | Some(',') => (),
+                    Some(_) => return Err(error("characters after quoted value")),
+                }
+            } else {
+                value = chars.by_ref().take_while(|c| *c != ',').collect();
+            }
+            properties.insert(key, value);
+        }
+        if properties.is_empty() {
+            return Err(error("missing properties"));
+        }
+
+        Ok(Self { domain, properties })
+    }
+}
+
+/// The numeric values of an attribute, by their path. The values of
+/// composite attributes, such as the `HeapMemoryUsage` of the
+/// `java.lang:type=Memory` MBean, are reported with their keys appended to
+/// the name of the attribute, such as `HeapMemoryUsage.used`. Booleans are
+/// `1` if true and `0` otherwise, and other values are skipped.
+pub fn numeric_values(attribute: &str, value: &serde_json::Value) -> Vec<(String, f64)> {
+    let mut values = Vec::new();
+    collect_numeric_values(attribute.to_owned(), value, &mut values);
+    values
+}
+
+fn collect_numeric_values(
+    path: String,
+    value: &serde_json::Value,
+    values: &mut Vec<(String, f64)>,
+) {
+    match value {
+        serde_json::Value::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                values.push((path, number));
+            }
+        }
+        serde_json::Value::Bool(boolean) => values.push((path, if *boolean { 1.0 } else { 0.0 })),
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                collect_numeric_values(format!("{}.{}", path, key), value, values);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_object_name() {
+        assert_eq!(
+            ObjectName::parse("kafka.server:type=BrokerTopicMetrics,name=BytesInPerSec,topic=logs"),
+            Ok(ObjectName {
+                domain: "kafka.server".to_owned(),
+                properties: vec![
+                    ("name".to_owned(), "BytesInPerSec".to_owned()),
+                    ("topic".to_owned(), "logs".to_owned()),
+                    ("type".to_owned(), "BrokerTopicMetrics".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            })
+        );
+        assert_eq!(
+            ObjectName::parse(r#"org.example:name="a,b=\"c\"",type=Test"#)
+                .unwrap()
+                .properties["name"],
+            r#"a,b="c""#
+        );
+        assert!(ObjectName::parse("java.lang").is_err());
+        assert!(ObjectName::parse("java.lang:").is_err());
+        assert!(ObjectName::parse(r#"java.lang:name="open"#).is_err());
+    }
+
+    #[test]
+    fn parse_bulk_response() {
+        let responses = JolokiaResponse::parse_bulk(
+            br#"[
+                {"request": {"type": "read"}, "value": {"Count": 10}, "status": 200, "timestamp": 1},
+                {"request": {"type": "read"}, "error": "javax.management.InstanceNotFoundException", "status": 404}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, 200);
+        assert_eq!(responses[0].value, json!({"Count": 10}));
+        assert_eq!(responses[1].status, 404);
+        assert!(responses[1].error.is_some());
+
+        assert!(JolokiaResponse::parse_bulk(b"<html>").is_err());
+    }
+
+    #[test]
+    fn flattens_numeric_values() {
+        let value = json!({"committed": 10, "used": 5.5, "name": "heap", "nested": {"on": true}});
+        let mut values = numeric_values("HeapMemoryUsage", &value);
+        values.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            values,
+            vec![
+                ("HeapMemoryUsage.committed".to_owned(), 10.0),
+                ("HeapMemoryUsage.nested.on".to_owned(), 1.0),
+                ("HeapMemoryUsage.used".to_owned(), 5.5),
+            ]
+        );
+        assert!(numeric_values("Name", &json!("kafka")).is_empty());
+    }
+}
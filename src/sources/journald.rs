@@ -1,7 +1,7 @@
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
     event::{Event, LogEvent, Value},
-    internal_events::{JournaldEventReceived, JournaldInvalidRecord},
+    internal_events::{JournaldEventReceived, JournaldInvalidRecord, JournaldLag},
     shutdown::ShutdownSignal,
     Pipeline,
 };
@@ -29,7 +29,7 @@ use std::{
     path::PathBuf,
     process::Stdio,
     str::FromStr,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio_util::codec::FramedRead;
 
@@ -52,6 +52,8 @@ const SOURCE_TIMESTAMP: &str = "_SOURCE_REALTIME_TIMESTAMP";
 const RECEIVED_TIMESTAMP: &str = "__REALTIME_TIMESTAMP";
 
 const BACKOFF_DURATION: Duration = Duration::from_secs(1);
+/// How often the end of the journal is queried to report the lag.
+const LAG_INTERVAL: Duration = Duration::from_secs(10);
 
 lazy_static! {
     static ref JOURNALCTL: PathBuf = "journalctl".into();
@@ -135,6 +137,7 @@ impl SourceConfig for JournaldConfig {
         let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
         let current_boot_only = self.current_boot_only.unwrap_or(true);
 
+        let lag_journalctl_path = Some(journalctl_path.clone());
         let start: StartJournalctlFn =
             Box::new(move |cursor| start_journalctl(&journalctl_path, current_boot_only, cursor));
 
@@ -145,6 +148,8 @@ impl SourceConfig for JournaldConfig {
                 checkpoint_path,
                 batch_size,
                 remap_priority: self.remap_priority,
+                journalctl_path: lag_journalctl_path,
+                lag_checked_at: None,
                 out: out.sink_compat(),
             }
             .run_shutdown(shutdown, start)
@@ -167,6 +172,9 @@ struct JournaldSource {
     checkpoint_path: PathBuf,
     batch_size: usize,
     remap_priority: bool,
+    /// Queries the end of the journal to report the lag, if set.
+    journalctl_path: Option<PathBuf>,
+    lag_checked_at: Option<Instant>,
     out: Compat01As03Sink<Pipeline, Event>,
 }
 
@@ -310,10 +318,34 @@ impl JournaldSource {
 
             if saw_record {
                 Self::save_checkpoint(checkpointer, &*cursor).await;
+                self.report_lag(&*cursor).await;
             }
         }
     }
 
+    /// Reports how many entries the cursor is behind the end of the journal,
+    /// at most every `LAG_INTERVAL`.
+    async fn report_lag(&mut self, cursor: &Option<String>) {
+        let (path, cursor) = match (&self.journalctl_path, cursor) {
+            (Some(path), Some(cursor)) => (path, cursor),
+            _ => return,
+        };
+        if matches!(self.lag_checked_at, Some(at) if at.elapsed() < LAG_INTERVAL) {
+            return;
+        }
+        self.lag_checked_at = Some(Instant::now());
+
+        match end_cursor(path).await {
+            Ok(Some(end)) => {
+                if let Some(entries_behind) = entries_behind(cursor, &end) {
+                    emit!(JournaldLag { entries_behind });
+                }
+            }
+            Ok(None) => {}
+            Err(error) => debug!(message = "Could not query the end of the journal.", %error),
+        }
+    }
+
     async fn save_checkpoint(checkpointer: &mut Checkpointer, cursor: &Option<String>) {
         if let Some(cursor) = cursor {
             if let Err(error) = checkpointer.set(cursor).await {
@@ -381,6 +413,50 @@ fn start_journalctl(
     Ok((stream, stop))
 }
 
+/// The cursor of the newest entry of the journal, if any.
+async fn end_cursor(path: &PathBuf) -> crate::Result<Option<String>> {
+    let output = Command::new(path)
+        .arg("--lines=1")
+        .arg("--output=json")
+        .arg("--no-pager")
+        .output()
+        .await?;
+    match output
+        .stdout
+        .split(|&byte| byte == b'\n')
+        .find(|line| !line.is_empty())
+    {
+        Some(line) => Ok(decode_record(line, false)?.remove(CURSOR)),
+        None => Ok(None),
+    }
+}
+
+/// Counts the entries between two cursors by their sequence numbers, which
+/// are only comparable within the same journal, as given by the sequence
+/// number ID of cursors like `s=739ad463348b4ceca5a9e69c95a3c93f;i=4ece7;...`.
+fn entries_behind(cursor: &str, end: &str) -> Option<u64> {
+    let (id, seqnum) = cursor_seqnum(cursor)?;
+    let (end_id, end_seqnum) = cursor_seqnum(end)?;
+    if id == end_id {
+        Some(end_seqnum.saturating_sub(seqnum))
+    } else {
+        None
+    }
+}
+
+fn cursor_seqnum(cursor: &str) -> Option<(&str, u64)> {
+    let mut id = None;
+    let mut seqnum = None;
+    for field in cursor.split(';') {
+        if let Some(value) = field.strip_prefix("s=") {
+            id = Some(value);
+        } else if let Some(value) = field.strip_prefix("i=") {
+            seqnum = u64::from_str_radix(value, 16).ok();
+        }
+    }
+    Some((id?, seqnum?))
+}
+
 fn create_event(record: Record) -> Event {
     let mut log = LogEvent::from_iter(record);
     // Convert some journald-specific field names into Vector standard ones.
@@ -671,6 +747,8 @@ mod tests {
             checkpoint_path,
             batch_size: DEFAULT_BATCH_SIZE,
             remap_priority: true,
+            journalctl_path: None,
+            lag_checked_at: None,
             out: tx.sink_compat(),
         }
         .run_shutdown(shutdown, Box::new(FakeJournal::new));
@@ -763,6 +841,18 @@ mod tests {
         assert_eq!(timestamp(&received[1]), value_ts(1578529839, 140005000));
     }
 
+    #[test]
+    fn counts_entries_behind() {
+        let cursor = "s=739ad463348b4ceca5a9e69c95a3c93f;i=4ece7;b=f5ed0fbee0b64e0d8c1f8e17e8e8a1d4;m=1bc2c4;t=5a8e0dbd2d4e1;x=c9e67d39a4dc9f3b";
+        let end = "s=739ad463348b4ceca5a9e69c95a3c93f;i=4ecf1;b=f5ed0fbee0b64e0d8c1f8e17e8e8a1d4;m=1bd0a1;t=5a8e0dbd3b2c9;x=0f6c0d7e1c2d803b";
+        assert_eq!(entries_behind(cursor, end), Some(10));
+        assert_eq!(entries_behind(end, end), Some(0));
+
+        let other = "s=a2d87af6b7464fd0b9c4b0bea6e7de0b;i=12;b=f5ed0fbee0b64e0d8c1f8e17e8e8a1d4;m=1bd0a1;t=5a8e0dbd3b2c9;x=1";
+        assert_eq!(entries_behind(cursor, other), None);
+        assert_eq!(entries_behind("1", end), None);
+    }
+
     #[test]
     fn filter_unit_works_correctly() {
         let empty: HashSet<String> = vec![].into_iter().collect();
@@ -1,7 +1,10 @@
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
     event::{Event, Value},
-    internal_events::{KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed},
+    internal_events::{
+        KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed, KafkaPartitionsAssigned,
+        KafkaPartitionsRevoked,
+    },
     kafka::KafkaAuthConfig,
     shutdown::ShutdownSignal,
     Pipeline,
@@ -12,8 +15,9 @@ use futures::{compat::Future01CompatExt, StreamExt};
 use futures01::Sink;
 use rdkafka::{
     config::ClientConfig,
-    consumer::{Consumer, StreamConsumer},
+    consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer},
     message::Message,
+    ClientContext,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -25,6 +29,8 @@ enum BuildError {
     KafkaCreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     KafkaSubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Invalid topic pattern {:?}: {}", topic, source))]
+    InvalidTopicPattern { topic: String, source: regex::Error },
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -43,6 +49,7 @@ pub struct KafkaSourceConfig {
     fetch_wait_max_ms: u64,
     #[serde(default = "default_commit_interval_ms")]
     commit_interval_ms: u64,
+    topic_refresh_interval_ms: Option<u64>,
     key_field: Option<String>,
     topic_key: Option<String>,
     partition_key: Option<String>,
@@ -112,8 +119,9 @@ fn kafka_source(
     let consumer = Arc::new(create_consumer(config)?);
 
     Ok(Box::pin(async move {
-        Arc::clone(&consumer)
-            .start()
+        let stream_consumer = Arc::clone(&consumer);
+        stream_consumer
+            .stream()
             .take_until(shutdown.clone())
             .then(move |message| {
                 let key_field = key_field.clone();
@@ -210,7 +218,42 @@ fn kafka_source(
     }))
 }
 
-fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer> {
+/// Logs the partitions assigned to the consumer, which change when topics
+/// matching a pattern are created, or when members join or leave the group.
+struct KafkaSourceContext;
+
+impl ClientContext for KafkaSourceContext {}
+
+impl ConsumerContext for KafkaSourceContext {
+    fn post_rebalance(&self, rebalance: &Rebalance) {
+        match rebalance {
+            Rebalance::Assign(partitions) => {
+                let mut topics = partitions
+                    .elements()
+                    .iter()
+                    .map(|element| element.topic().to_owned())
+                    .collect::<Vec<_>>();
+                topics.sort();
+                topics.dedup();
+                emit!(KafkaPartitionsAssigned {
+                    partitions: partitions.count(),
+                    topics,
+                });
+            }
+            _ => emit!(KafkaPartitionsRevoked),
+        }
+    }
+}
+
+fn create_consumer(
+    config: &KafkaSourceConfig,
+) -> crate::Result<StreamConsumer<KafkaSourceContext>> {
+    // Topics starting with `^` are regular expressions matched by librdkafka,
+    // check them here to get a readable error.
+    for topic in config.topics.iter().filter(|topic| topic.starts_with('^')) {
+        regex::Regex::new(topic).context(InvalidTopicPattern { topic })?;
+    }
+
     let mut client_config = ClientConfig::new();
     client_config
         .set("group.id", &config.group_id)
@@ -228,6 +271,10 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer>
         .set("enable.auto.offset.store", "false")
         .set("client.id", "vector");
 
+    if let Some(interval) = config.topic_refresh_interval_ms {
+        client_config.set("topic.metadata.refresh.interval.ms", &interval.to_string());
+    }
+
     config.auth.apply(&mut client_config)?;
 
     if let Some(librdkafka_options) = &config.librdkafka_options {
@@ -236,7 +283,9 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer>
         }
     }
 
-    let consumer: StreamConsumer = client_config.create().context(KafkaCreateError)?;
+    let consumer = client_config
+        .create_with_context(KafkaSourceContext)
+        .context(KafkaCreateError)?;
     let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
     consumer.subscribe(&topics).context(KafkaSubscribeError)?;
 
@@ -285,6 +334,25 @@ mod test {
         };
         assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
     }
+
+    #[test]
+    fn kafka_source_create_with_topic_pattern() {
+        let config = KafkaSourceConfig {
+            topics: vec!["^logs-.+".to_string()],
+            topic_refresh_interval_ms: Some(10000),
+            ..make_config()
+        };
+        assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_ok());
+    }
+
+    #[test]
+    fn kafka_source_create_invalid_topic_pattern() {
+        let config = KafkaSourceConfig {
+            topics: vec!["^logs-(".to_string()],
+            ..make_config()
+        };
+        assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -0,0 +1,447 @@
+//! This mod implements `kubernetes_events` source.
+//! The scope of this source is to watch the Kubernetes Events API and emit
+//! every cluster event as a structured log event, which removes the need to
+//! run a dedicated event exporter alongside Vector.
+
+#![deny(missing_docs)]
+
+use crate::internal_events::{KubernetesEventsDuplicate, KubernetesEventsEventReceived};
+use crate::kubernetes::{
+    self as k8s,
+    watch_request_builder::{NamespaceWatchRequestBuilder, WatchRequestResult, Watchable},
+};
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    shutdown::ShutdownSignal,
+    sources, Pipeline,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{
+    compat::Sink01CompatExt,
+    future::{select, Either},
+    pin_mut, SinkExt,
+};
+use k8s_openapi::{
+    api::{core::v1::Event as CoreEvent, events::v1beta1::Event as EventsEvent},
+    Metadata, WatchOptional,
+};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{marker::PhantomData, time::Duration};
+
+const COMPONENT_NAME: &str = "kubernetes_events";
+
+/// The amount of objects to remember when de-duplicating the events
+/// redelivered by the watch API.
+const DEDUPE_CACHE_SIZE: usize = 4096;
+
+/// Configuration for the `kubernetes_events` source.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    /// Which of the Kubernetes Events APIs to watch.
+    api: Api,
+
+    /// The namespace to watch the events in. All namespaces are watched when
+    /// unset.
+    namespace: Option<String>,
+
+    /// Specifies the field selector to filter the events with.
+    field_selector: Option<String>,
+
+    /// Specifies the label selector to filter the events with.
+    label_selector: Option<String>,
+
+    /// Emit the events that already existed when the source started.
+    /// By default only the events that occurred after the start are emitted.
+    include_previous_events: bool,
+}
+
+/// The Kubernetes API group to read the events from.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Api {
+    /// The `v1` core API `Event` object.
+    Core,
+    /// The `events.k8s.io` API `Event` object.
+    Events,
+}
+
+impl Default for Api {
+    fn default() -> Self {
+        Api::Core
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<Config>(COMPONENT_NAME)
+}
+
+impl GenerateConfig for Config {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(&Self::default()).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kubernetes_events")]
+impl SourceConfig for Config {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<sources::Source> {
+        let k8s_config = k8s::client::config::Config::in_cluster()?;
+        let client = k8s::client::Client::new(k8s_config)?;
+
+        let source = match self.api {
+            Api::Core => {
+                Box::pin(run::<CoreEvent>(self.clone(), client, out, shutdown)) as sources::Source
+            }
+            Api::Events => Box::pin(run::<EventsEvent>(self.clone(), client, out, shutdown)),
+        };
+        Ok(source)
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        COMPONENT_NAME
+    }
+}
+
+async fn run<T>(
+    config: Config,
+    client: k8s::client::Client,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+) -> Result<(), ()>
+where
+    T: KubernetesEvent,
+{
+    let watcher = k8s::api_watcher::ApiWatcher::new(
+        client,
+        NamespaceWatchRequestBuilder::<T>::new(config.namespace.clone()),
+    );
+    let watcher = k8s::instrumenting_watcher::InstrumentingWatcher::new(watcher);
+
+    let ignore_before = if config.include_previous_events {
+        None
+    } else {
+        Some(Utc::now())
+    };
+    let state_writer = Writer::new(out.sink_compat(), ignore_before);
+    let state_writer = k8s::state::instrumenting::Writer::new(state_writer);
+
+    let mut reflector = k8s::reflector::Reflector::new(
+        watcher,
+        state_writer,
+        config.field_selector,
+        config.label_selector,
+        Duration::from_secs(1),
+    );
+    let reflector_process = reflector.run();
+    pin_mut!(reflector_process);
+
+    match select(reflector_process, shutdown).await {
+        Either::Left((Ok(_infallible), _)) => unreachable!("ok value is infallible"),
+        Either::Left((Err(error), _)) => {
+            error!(message = "Reflector process exited with an error.", %error);
+            Err(())
+        }
+        Either::Right(_) => {
+            info!(message = "Reflector process completed gracefully.");
+            Ok(())
+        }
+    }
+}
+
+/// The common behavior of the event objects of the different Kubernetes APIs.
+pub trait KubernetesEvent: Watchable + Serialize {
+    /// The human readable description of the event.
+    fn message(&self) -> Option<&str>;
+
+    /// The time the event was most recently observed.
+    fn last_observed(&self) -> Option<DateTime<Utc>>;
+}
+
+impl Watchable for CoreEvent {
+    fn watch_all(optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        CoreEvent::watch_event_for_all_namespaces(optional)
+    }
+
+    fn watch_namespaced(namespace: &str, optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        CoreEvent::watch_namespaced_event(namespace, optional)
+    }
+}
+
+impl KubernetesEvent for CoreEvent {
+    fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    fn last_observed(&self) -> Option<DateTime<Utc>> {
+        self.series
+            .as_ref()
+            .and_then(|series| series.last_observed_time.as_ref())
+            .map(|time| time.0)
+            .or_else(|| self.last_timestamp.as_ref().map(|time| time.0))
+            .or_else(|| self.event_time.as_ref().map(|time| time.0))
+            .or_else(|| self.first_timestamp.as_ref().map(|time| time.0))
+            .or_else(|| self.metadata.creation_timestamp.as_ref().map(|time| time.0))
+    }
+}
+
+impl Watchable for EventsEvent {
+    fn watch_all(optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        EventsEvent::watch_event_for_all_namespaces(optional)
+    }
+
+    fn watch_namespaced(namespace: &str, optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        EventsEvent::watch_namespaced_event(namespace, optional)
+    }
+}
+
+impl KubernetesEvent for EventsEvent {
+    fn message(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    fn last_observed(&self) -> Option<DateTime<Utc>> {
+        Some(
+            self.series
+                .as_ref()
+                .map(|series| series.last_observed_time.0)
+                .unwrap_or(self.event_time.0),
+        )
+    }
+}
+
+/// A state writer that, instead of caching the objects, emits them as log
+/// events.
+///
+/// The watch API redelivers the objects it has already sent to us when the
+/// watch is restarted after a desync, so the objects are de-duplicated by their
+/// `uid` and `resourceVersion`.
+struct Writer<T, O> {
+    out: O,
+    ignore_before: Option<DateTime<Utc>>,
+    seen: LruCache<String, String>,
+    _object: PhantomData<fn() -> T>,
+}
+
+impl<T, O> Writer<T, O>
+where
+    T: KubernetesEvent,
+    O: futures::Sink<Event> + Send + Unpin,
+    <O as futures::Sink<Event>>::Error: std::fmt::Display,
+{
+    fn new(out: O, ignore_before: Option<DateTime<Utc>>) -> Self {
+        Self {
+            out,
+            ignore_before,
+            seen: LruCache::new(DEDUPE_CACHE_SIZE),
+            _object: PhantomData,
+        }
+    }
+
+    async fn emit(&mut self, item: T) {
+        let metadata = item.metadata();
+        if let (Some(uid), Some(resource_version)) = (&metadata.uid, &metadata.resource_version) {
+            if self.seen.get(uid) == Some(resource_version) {
+                emit!(KubernetesEventsDuplicate {
+                    uid,
+                    resource_version
+                });
+                return;
+            }
+            self.seen.put(uid.clone(), resource_version.clone());
+        }
+
+        let last_observed = item.last_observed();
+        if let (Some(ignore_before), Some(last_observed)) = (self.ignore_before, last_observed) {
+            if last_observed < ignore_before {
+                return;
+            }
+        }
+
+        let event = match create_event(&item, last_observed) {
+            Ok(event) => event,
+            Err(error) => {
+                error!(message = "Failed to serialize Kubernetes event.", %error);
+                return;
+            }
+        };
+        emit!(KubernetesEventsEventReceived {
+            name: metadata.name.as_deref().unwrap_or_default(),
+        });
+
+        if let Err(error) = self.out.send(event).await {
+            error!(message = "Could not send Kubernetes event.", %error);
+        }
+    }
+}
+
+#[async_trait]
+impl<T, O> k8s::state::Write for Writer<T, O>
+where
+    T: KubernetesEvent,
+    O: futures::Sink<Event> + Send + Unpin,
+    <O as futures::Sink<Event>>::Error: std::fmt::Display,
+{
+    type Item = T;
+
+    async fn add(&mut self, item: Self::Item) {
+        self.emit(item).await;
+    }
+
+    async fn update(&mut self, item: Self::Item) {
+        self.emit(item).await;
+    }
+
+    async fn delete(&mut self, _item: Self::Item) {
+        // Events are deleted when they expire, there's nothing to report.
+    }
+
+    async fn resync(&mut self) {
+        // The de-duplication cache is intentionally preserved across resyncs,
+        // the objects relisted after the resync have been seen already.
+    }
+}
+
+#[async_trait]
+impl<T, O> k8s::state::MaintainedWrite for Writer<T, O>
+where
+    T: KubernetesEvent,
+    O: futures::Sink<Event> + Send + Unpin,
+    <O as futures::Sink<Event>>::Error: std::fmt::Display,
+{
+    fn maintenance_request(&mut self) -> Option<futures::future::BoxFuture<'_, ()>> {
+        None
+    }
+
+    async fn perform_maintenance(&mut self) {}
+}
+
+fn create_event<T: KubernetesEvent>(
+    item: &T,
+    last_observed: Option<DateTime<Utc>>,
+) -> Result<Event, serde_json::Error> {
+    let mut log = match Value::from(serde_json::to_value(item)?) {
+        Value::Map(fields) => LogEvent::from(fields),
+        _ => LogEvent::default(),
+    };
+
+    if let Some(message) = item.message() {
+        log.insert(log_schema().message_key(), message.to_owned());
+    }
+    log.insert(
+        log_schema().timestamp_key(),
+        last_observed.unwrap_or_else(Utc::now),
+    );
+    log.insert(log_schema().source_type_key(), Bytes::from(COMPONENT_NAME));
+
+    Ok(log.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::state::Write;
+    use chrono::TimeZone;
+    use futures::{channel::mpsc, StreamExt};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<Config>();
+    }
+
+    fn make_event(uid: &str, resource_version: &str, message: &str, seconds: i64) -> CoreEvent {
+        CoreEvent {
+            metadata: ObjectMeta {
+                name: Some(format!("{}.{}", uid, resource_version)),
+                namespace: Some("default".to_owned()),
+                uid: Some(uid.to_owned()),
+                resource_version: Some(resource_version.to_owned()),
+                ..ObjectMeta::default()
+            },
+            message: Some(message.to_owned()),
+            reason: Some("Scheduled".to_owned()),
+            last_timestamp: Some(Time(Utc.timestamp(seconds, 0))),
+            ..CoreEvent::default()
+        }
+    }
+
+    async fn write_events(
+        ignore_before: Option<DateTime<Utc>>,
+        events: Vec<CoreEvent>,
+    ) -> Vec<Event> {
+        let (tx, rx) = mpsc::channel(10);
+        let mut writer = Writer::<CoreEvent, _>::new(tx, ignore_before);
+        for event in events {
+            writer.add(event).await;
+        }
+        drop(writer);
+        rx.collect().await
+    }
+
+    #[tokio::test]
+    async fn emits_events() {
+        let events = write_events(None, vec![make_event("a", "1", "Assigned pod", 100)]).await;
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "Assigned pod".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.timestamp(100, 0))
+        );
+        assert_eq!(log["reason"], "Scheduled".into());
+        assert_eq!(log["metadata.namespace"], "default".into());
+        assert_eq!(log["kind"], "Event".into());
+        assert_eq!(log[log_schema().source_type_key()], COMPONENT_NAME.into());
+    }
+
+    #[tokio::test]
+    async fn dedupes_by_resource_version() {
+        let events = write_events(
+            None,
+            vec![
+                make_event("a", "1", "first", 100),
+                make_event("a", "1", "first", 100),
+                make_event("b", "2", "second", 100),
+                make_event("a", "3", "first again", 101),
+            ],
+        )
+        .await;
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["first", "second", "first again"]);
+    }
+
+    #[tokio::test]
+    async fn ignores_previous_events() {
+        let events = write_events(
+            Some(Utc.timestamp(100, 0)),
+            vec![
+                make_event("a", "1", "old", 99),
+                make_event("b", "2", "new", 100),
+            ],
+        )
+        .await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()[log_schema().message_key()], "new".into());
+    }
+}
@@ -5,55 +5,57 @@ use crate::{
     transforms::FunctionTransform,
 };
 
+/// Picks the parser of each line by its format.
+///
+/// The format is detected for every line rather than once for the source, as
+/// the files of a node may be written by runtimes using different formats,
+/// for instance while migrating a node from Docker to containerd. A line in
+/// the docker format is a JSON object, while a CRI line starts with its
+/// timestamp, so detecting the format of a line is cheap.
 #[derive(Clone, Debug)]
-pub enum Picker {
-    Init,
-    Docker(Docker),
-    Cri(Cri),
+pub struct Picker {
+    docker: Docker,
+    cri: Cri,
 }
 
 impl Picker {
     pub fn new() -> Self {
-        Picker::Init
+        Self {
+            docker: Docker,
+            cri: Cri::new(),
+        }
     }
 }
 
 impl FunctionTransform for Picker {
     fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
-        match self {
-            Picker::Init => {
-                let message = match event
-                    .as_log()
-                    .get(crate::config::log_schema().message_key())
-                {
-                    Some(message) => message,
-                    None => {
-                        emit!(KubernetesLogsFormatPickerEdgeCase {
-                            what: "got an event with no message field"
-                        });
-                        return;
-                    }
-                };
-
-                let bytes = match message {
-                    Value::Bytes(bytes) => bytes,
-                    _ => {
-                        emit!(KubernetesLogsFormatPickerEdgeCase {
-                            what: "got an event with non-bytes message field"
-                        });
-                        return;
-                    }
-                };
-
-                if bytes.len() > 1 && bytes[0] == b'{' {
-                    *self = Picker::Docker(Docker)
-                } else {
-                    *self = Picker::Cri(Cri::new())
-                }
-                self.transform(output, event)
+        let message = match event
+            .as_log()
+            .get(crate::config::log_schema().message_key())
+        {
+            Some(message) => message,
+            None => {
+                emit!(KubernetesLogsFormatPickerEdgeCase {
+                    what: "got an event with no message field"
+                });
+                return;
+            }
+        };
+
+        let bytes = match message {
+            Value::Bytes(bytes) => bytes,
+            _ => {
+                emit!(KubernetesLogsFormatPickerEdgeCase {
+                    what: "got an event with non-bytes message field"
+                });
+                return;
             }
-            Picker::Docker(t) => t.transform(output, event),
-            Picker::Cri(t) => t.transform(output, event),
+        };
+
+        if bytes.len() > 1 && bytes[0] == b'{' {
+            self.docker.transform(output, event)
+        } else {
+            self.cri.transform(output, event)
         }
     }
 }
@@ -78,6 +80,23 @@ mod tests {
         test_util::test_parser(|| Transform::function(Picker::new()), cases());
     }
 
+    #[test]
+    fn test_parsing_files_of_both_formats() {
+        trace_init();
+
+        let mut picker = Picker::new();
+        let mut output = Vec::new();
+        for (message, expected) in cri::tests::cases()
+            .into_iter()
+            .zip(docker::tests::cases())
+            .flat_map(|(cri, docker)| vec![cri, docker])
+        {
+            picker.transform(&mut output, Event::from(message));
+            let events = output.drain(..).map(Event::into_log).collect::<Vec<_>>();
+            assert_eq!(events, expected);
+        }
+    }
+
     #[test]
     fn test_parsing_invalid() {
         trace_init();
@@ -5,6 +5,9 @@ use super::FILE_KEY;
 use crate::event;
 use crate::transforms::merge::{Merge, MergeConfig};
 
+/// The field both parsers write the stream of a line to.
+const STREAM_KEY: &str = "stream";
+
 /// Partial event merger.
 pub type PartialEventsMerger = Optional<Merge>;
 
@@ -14,7 +17,10 @@ pub fn build(enabled: bool) -> PartialEventsMerger {
             MergeConfig {
                 partial_event_marker_field: event::PARTIAL.to_string(),
                 fields: vec![crate::config::log_schema().message_key().to_string()],
-                stream_discriminant_fields: vec![(&*FILE_KEY).to_string()],
+                // CRI files interleave the lines of both streams of a
+                // container, so a partial line of one stream may be followed
+                // by lines of the other before it's completed.
+                stream_discriminant_fields: vec![(&*FILE_KEY).to_string(), STREAM_KEY.to_string()],
             }
             .into(),
         )
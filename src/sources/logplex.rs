@@ -14,7 +14,7 @@ use bytes::{buf::BufExt, Bytes};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io::{BufRead, BufReader},
     net::SocketAddr,
     str::FromStr,
@@ -27,6 +27,10 @@ pub struct LogplexConfig {
     address: SocketAddr,
     #[serde(default)]
     query_parameters: Vec<String>,
+    #[serde(default)]
+    drain_tokens: Vec<String>,
+    app_name_query_parameter: Option<String>,
+    dyno_query_parameter: Option<String>,
     tls: Option<TlsConfig>,
     auth: Option<HttpSourceAuthConfig>,
 }
@@ -40,6 +44,9 @@ impl GenerateConfig for LogplexConfig {
         toml::Value::try_from(Self {
             address: "0.0.0.0:80".parse().unwrap(),
             query_parameters: Vec::new(),
+            drain_tokens: Vec::new(),
+            app_name_query_parameter: None,
+            dyno_query_parameter: None,
             tls: None,
             auth: None,
         })
@@ -50,6 +57,9 @@ impl GenerateConfig for LogplexConfig {
 #[derive(Clone, Default)]
 struct LogplexSource {
     query_parameters: Vec<String>,
+    drain_tokens: HashSet<String>,
+    app_name_query_parameter: Option<String>,
+    dyno_query_parameter: Option<String>,
 }
 
 impl HttpSource for LogplexSource {
@@ -59,11 +69,48 @@ impl HttpSource for LogplexSource {
         header_map: HeaderMap,
         query_parameters: HashMap<String, String>,
     ) -> Result<Vec<Event>, ErrorMessage> {
+        if !self.drain_tokens.is_empty() {
+            let drain_token = get_header(&header_map, "Logplex-Drain-Token")?;
+            if !self.drain_tokens.contains(drain_token) {
+                return Err(ErrorMessage::new(
+                    StatusCode::UNAUTHORIZED,
+                    format!("Unknown drain token {:?}", drain_token),
+                ));
+            }
+        }
+
         decode_message(body, header_map)
+            .map(|events| self.add_hints(events, &query_parameters))
             .map(|events| add_query_parameters(events, &self.query_parameters, query_parameters))
     }
 }
 
+impl LogplexSource {
+    /// Attaches the app and dyno the drain was set up for, as named by the
+    /// query parameters of its URL.
+    fn add_hints(
+        &self,
+        mut events: Vec<Event>,
+        query_parameters: &HashMap<String, String>,
+    ) -> Vec<Event> {
+        let hints = [
+            ("heroku_app", &self.app_name_query_parameter),
+            ("heroku_dyno", &self.dyno_query_parameter),
+        ];
+        for (field, parameter) in hints.iter() {
+            let value = parameter
+                .as_ref()
+                .and_then(|parameter| query_parameters.get(parameter));
+            if let Some(value) = value {
+                for event in events.iter_mut() {
+                    event.as_mut_log().insert(*field, value.clone());
+                }
+            }
+        }
+        events
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "logplex")]
 impl SourceConfig for LogplexConfig {
@@ -76,8 +123,19 @@ impl SourceConfig for LogplexConfig {
     ) -> crate::Result<super::Source> {
         let source = LogplexSource {
             query_parameters: self.query_parameters.clone(),
+            drain_tokens: self.drain_tokens.iter().cloned().collect(),
+            app_name_query_parameter: self.app_name_query_parameter.clone(),
+            dyno_query_parameter: self.dyno_query_parameter.clone(),
         };
-        source.run(self.address, "events", &self.tls, &self.auth, out, shutdown)
+        source.run(
+            self.address,
+            "events",
+            &self.tls,
+            &self.auth,
+            &None,
+            out,
+            shutdown,
+        )
     }
 
     fn output_type(&self) -> DataType {
@@ -220,25 +278,33 @@ mod tests {
         auth: Option<HttpSourceAuthConfig>,
         query_parameters: Vec<String>,
     ) -> (mpsc::Receiver<Event>, SocketAddr) {
+        source_with_config(LogplexConfig {
+            address: next_addr(),
+            query_parameters,
+            drain_tokens: vec![],
+            app_name_query_parameter: None,
+            dyno_query_parameter: None,
+            tls: None,
+            auth,
+        })
+        .await
+    }
+
+    async fn source_with_config(config: LogplexConfig) -> (mpsc::Receiver<Event>, SocketAddr) {
         let (sender, recv) = Pipeline::new_test();
-        let address = next_addr();
+        let address = config.address;
         tokio::spawn(async move {
-            LogplexConfig {
-                address,
-                query_parameters,
-                tls: None,
-                auth,
-            }
-            .build(
-                "default",
-                &GlobalOptions::default(),
-                ShutdownSignal::noop(),
-                sender,
-            )
-            .await
-            .unwrap()
-            .await
-            .unwrap()
+            config
+                .build(
+                    "default",
+                    &GlobalOptions::default(),
+                    ShutdownSignal::noop(),
+                    sender,
+                )
+                .await
+                .unwrap()
+                .await
+                .unwrap()
         });
         wait_for_tcp(address).await;
         (recv, address)
@@ -249,6 +315,16 @@ mod tests {
         body: &str,
         auth: Option<HttpSourceAuthConfig>,
         query: &str,
+    ) -> u16 {
+        send_with_token(address, body, auth, query, "drain-bar").await
+    }
+
+    async fn send_with_token(
+        address: SocketAddr,
+        body: &str,
+        auth: Option<HttpSourceAuthConfig>,
+        query: &str,
+        drain_token: &str,
     ) -> u16 {
         let len = body.lines().count();
         let mut req = reqwest::Client::new().post(&format!("http://{}/events?{}", address, query));
@@ -257,7 +333,7 @@ mod tests {
         }
         req.header("Logplex-Msg-Count", len)
             .header("Logplex-Frame-Id", "frame-foo")
-            .header("Logplex-Drain-Token", "drain-bar")
+            .header("Logplex-Drain-Token", drain_token)
             .body(body.to_owned())
             .send()
             .await
@@ -309,6 +385,43 @@ mod tests {
         assert_eq!(log["absent"], Value::Null);
     }
 
+    #[tokio::test]
+    async fn logplex_routes_drains() {
+        trace_init();
+
+        let body = "267 <158>1 2020-01-08T22:33:57.353034+00:00 host app web.1 - foo bar baz";
+
+        let (rx, addr) = source_with_config(LogplexConfig {
+            address: next_addr(),
+            query_parameters: vec![],
+            drain_tokens: vec!["drain-foo".to_owned(), "drain-bar".to_owned()],
+            app_name_query_parameter: Some("app".to_owned()),
+            dyno_query_parameter: Some("dyno".to_owned()),
+            tls: None,
+            auth: None,
+        })
+        .await;
+
+        assert_eq!(
+            401,
+            send_with_token(addr, body, None, "app=shop", "drain-baz").await
+        );
+        assert_eq!(200, send(addr, body, None, "app=shop&dyno=web").await);
+        assert_eq!(
+            200,
+            send_with_token(addr, body, None, "app=checkout", "drain-foo").await
+        );
+
+        let events = collect_n(rx, 2).await.unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["heroku_app"], "shop".into());
+        assert_eq!(log["heroku_dyno"], "web".into());
+        assert_eq!(log["proc_id"], "web.1".into());
+        let log = events[1].as_log();
+        assert_eq!(log["heroku_app"], "checkout".into());
+        assert!(log.get("heroku_dyno").is_none());
+    }
+
     #[test]
     fn logplex_handles_normal_lines() {
         let body = "267 <158>1 2020-01-08T22:33:57.353034+00:00 host heroku router - foo bar baz";
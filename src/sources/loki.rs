@@ -0,0 +1,707 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    dns,
+    event::{Event, LogEvent, Value},
+    http::{Auth, HttpClient},
+    internal_events::{LokiEventsReceived, LokiRequestError, LokiTailDroppedEntries},
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError, TlsOptions, TlsSettings},
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{compat::Sink01CompatExt, future, stream, FutureExt, SinkExt, StreamExt};
+use futures01::Sink;
+use http::{header::HeaderName, HeaderValue, Request, StatusCode};
+use hyper::Body;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio::{net::TcpStream, time::delay_for};
+use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+use url::Url;
+
+const QUERY_RANGE_PATH: &str = "/loki/api/v1/query_range";
+const TAIL_PATH: &str = "/loki/api/v1/tail";
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("Unsupported endpoint scheme {:?}, expected http or https", scheme))]
+    UnsupportedScheme { scheme: String },
+    #[snafu(display("Invalid tenant_id: {}", source))]
+    InvalidTenantId {
+        source: http::header::InvalidHeaderValue,
+    },
+    #[snafu(display("The start of the query must be before its end"))]
+    EmptyRange,
+}
+
+#[derive(Debug, Snafu)]
+enum LokiError {
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Request failed: {}", source))]
+    SendRequest { source: crate::http::HttpError },
+    #[snafu(display("Unexpected response status: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("Failed to read response body: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Failed to parse response: {}", source))]
+    ParseBody { source: serde_json::Error },
+    #[snafu(display("Invalid entry timestamp {:?}", timestamp))]
+    InvalidTimestamp { timestamp: String },
+    #[snafu(display("Unable to resolve {:?}: {}", host, source))]
+    Resolve { host: String, source: dns::DnsError },
+    #[snafu(display("No addresses found for {:?}", host))]
+    NoAddresses { host: String },
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: TlsError },
+    #[snafu(display("WebSocket error: {}", source))]
+    WebSocket {
+        source: tokio_tungstenite::tungstenite::Error,
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Stream the entries as Loki receives them.
+    Tail,
+    /// Page through the entries of a time range, then stop.
+    Query,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::Tail
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LokiConfig {
+    endpoint: String,
+    query: String,
+    #[serde(default)]
+    mode: Mode,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    delay_for_secs: u64,
+    #[serde(default = "default_labels_key")]
+    labels_key: String,
+    tenant_id: Option<String>,
+    auth: Option<Auth>,
+    tls: Option<TlsOptions>,
+}
+
+fn default_limit() -> usize {
+    1000
+}
+
+fn default_labels_key() -> String {
+    "labels".to_owned()
+}
+
+inventory::submit! {
+    SourceDescription::new::<LokiConfig>("loki")
+}
+
+impl GenerateConfig for LokiConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoint: "http://localhost:3100".to_owned(),
+            query: r#"{job="varlogs"}"#.to_owned(),
+            mode: Mode::default(),
+            start: None,
+            end: None,
+            limit: default_limit(),
+            delay_for_secs: 0,
+            labels_key: default_labels_key(),
+            tenant_id: None,
+            auth: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "loki")]
+impl SourceConfig for LokiConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let endpoint = Url::parse(&self.endpoint).context(InvalidEndpoint {
+            endpoint: self.endpoint.clone(),
+        })?;
+        let tls = match endpoint.scheme() {
+            "http" => MaybeTlsSettings::Raw(()),
+            "https" => MaybeTlsSettings::Tls(TlsSettings::from_options(&self.tls)?),
+            scheme => {
+                return Err(BuildError::UnsupportedScheme {
+                    scheme: scheme.to_owned(),
+                }
+                .into())
+            }
+        };
+        let tenant_id = self
+            .tenant_id
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()
+            .context(InvalidTenantId)?;
+
+        let client = match self.mode {
+            Mode::Query => Some(HttpClient::new(tls.clone())?),
+            Mode::Tail => None,
+        };
+        let end = self.end.unwrap_or_else(Utc::now);
+        let start = match self.mode {
+            Mode::Query => self
+                .start
+                .unwrap_or_else(|| end - chrono::Duration::hours(1)),
+            Mode::Tail => self.start.unwrap_or_else(Utc::now),
+        };
+        if self.mode == Mode::Query && start >= end {
+            return Err(BuildError::EmptyRange.into());
+        }
+
+        let source = LokiSource {
+            endpoint,
+            query: self.query.clone(),
+            limit: self.limit.max(1),
+            delay_for: self.delay_for_secs,
+            labels_key: self.labels_key.clone(),
+            tenant_id,
+            auth: self.auth.clone(),
+            tls,
+            client,
+        };
+        let out = out.sink_map_err(|error| error!(message = "Error sending entries.", %error));
+        let run = match self.mode {
+            Mode::Query => source.query(Cursor::new(start), end, out).boxed(),
+            Mode::Tail => source.tail(Cursor::new(start), out).boxed(),
+        };
+        Ok(Box::pin(future::select(run, shutdown).map(|_| Ok(()))))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "loki"
+    }
+}
+
+struct LokiSource {
+    endpoint: Url,
+    query: String,
+    limit: usize,
+    delay_for: u64,
+    labels_key: String,
+    tenant_id: Option<HeaderValue>,
+    auth: Option<Auth>,
+    tls: MaybeTlsSettings,
+    client: Option<HttpClient>,
+}
+
+impl LokiSource {
+    /// Page through the entries up to `end`, then stop.
+    async fn query<O>(self, mut cursor: Cursor, end: DateTime<Utc>, out: O)
+    where
+        O: Sink<SinkItem = Event, SinkError = ()> + Send + 'static,
+    {
+        let mut out = out.sink_compat();
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            let entries = match self.query_page(&cursor, end).await {
+                Ok(entries) => entries,
+                Err(error) => {
+                    emit!(LokiRequestError {
+                        error: error.into()
+                    });
+                    delay_for(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            };
+            backoff = INITIAL_BACKOFF;
+
+            let full = entries.len() >= self.limit;
+            let entries = cursor.advance(entries);
+            if full && entries.is_empty() {
+                // The page holds nothing but entries at the timestamp of the
+                // cursor which we have seen, so move past it.
+                cursor.skip();
+            }
+            if self.send(entries, &mut out).await.is_err() || !full {
+                return;
+            }
+        }
+    }
+
+    async fn query_page(
+        &self,
+        cursor: &Cursor,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Entry>, LokiError> {
+        let mut url = self.url(QUERY_RANGE_PATH);
+        url.query_pairs_mut()
+            .append_pair("query", &self.query)
+            .append_pair("start", &cursor.timestamp.to_string())
+            .append_pair("end", &end.timestamp_nanos().to_string())
+            .append_pair("limit", &self.limit.to_string())
+            .append_pair("direction", "forward");
+
+        let mut request = Request::get(url.as_str())
+            .body(Body::empty())
+            .context(BuildRequest)?;
+        self.authorize(&mut request);
+
+        let client = self.client.as_ref().expect("Query mode has a client.");
+        let response = client.send(request).await.context(SendRequest)?;
+        let (parts, body) = response.into_parts();
+        if !parts.status.is_success() {
+            return Err(LokiError::UnexpectedStatus {
+                status: parts.status,
+            });
+        }
+
+        let body = hyper::body::to_bytes(body).await.context(ReadBody)?;
+        let response: QueryResponse = serde_json::from_slice(&body).context(ParseBody)?;
+        emit!(LokiEventsReceived {
+            count: response.data.result.iter().map(|s| s.values.len()).sum(),
+            byte_size: body.len(),
+        });
+        entries(response.data.result)
+    }
+
+    /// Stream the entries from the tail endpoint, resuming from the last
+    /// entry when the connection is lost.
+    async fn tail<O>(self, mut cursor: Cursor, out: O)
+    where
+        O: Sink<SinkItem = Event, SinkError = ()> + Send + 'static,
+    {
+        let mut out = out.sink_compat();
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            match self
+                .tail_connection(&mut cursor, &mut out, &mut backoff)
+                .await
+            {
+                Ok(true) => debug!(message = "Loki closed the tail connection."),
+                Ok(false) => return,
+                Err(error) => {
+                    emit!(LokiRequestError {
+                        error: error.into()
+                    });
+                }
+            }
+            delay_for(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// Returns whether we should reconnect.
+    async fn tail_connection<O>(
+        &self,
+        cursor: &mut Cursor,
+        out: &mut O,
+        backoff: &mut Duration,
+    ) -> Result<bool, LokiError>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        let mut socket = self.connect(cursor).await?;
+        *backoff = INITIAL_BACKOFF;
+
+        while let Some(message) = socket.next().await {
+            let text = match message.context(WebSocket)? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+
+            let response: TailResponse = serde_json::from_str(&text).context(ParseBody)?;
+            emit!(LokiEventsReceived {
+                count: response.streams.iter().map(|s| s.values.len()).sum(),
+                byte_size: text.len(),
+            });
+            if let Some(dropped) = response.dropped_entries.filter(|d| !d.is_empty()) {
+                emit!(LokiTailDroppedEntries {
+                    count: dropped.len()
+                });
+            }
+
+            let entries = cursor.advance(entries(response.streams)?);
+            if self.send(entries, out).await.is_err() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn connect(
+        &self,
+        cursor: &Cursor,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, LokiError> {
+        let mut url = self.url(TAIL_PATH);
+        let scheme = match url.scheme() {
+            "https" => "wss",
+            _ => "ws",
+        };
+        url.set_scheme(scheme)
+            .expect("WebSocket schemes are valid.");
+        url.query_pairs_mut()
+            .append_pair("query", &self.query)
+            .append_pair("start", &cursor.timestamp.to_string())
+            .append_pair("limit", &self.limit.to_string())
+            .append_pair("delay_for", &self.delay_for.to_string());
+
+        let host = url.host_str().unwrap_or("localhost").to_owned();
+        let port = url.port_or_known_default().unwrap_or(80);
+        let ip = dns::Resolver
+            .lookup_ip(host.clone())
+            .await
+            .context(Resolve { host: host.clone() })?
+            .next()
+            .ok_or_else(|| LokiError::NoAddresses { host: host.clone() })?;
+        let stream = self
+            .tls
+            .connect(&host, &SocketAddr::new(ip, port))
+            .await
+            .context(Connect)?;
+
+        let mut request = Request::get(url.as_str()).body(()).context(BuildRequest)?;
+        self.authorize(&mut request);
+        let (socket, _) = tokio_tungstenite::client_async(request, stream)
+            .await
+            .context(WebSocket)?;
+        Ok(socket)
+    }
+
+    fn url(&self, path: &str) -> Url {
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "{}{}",
+            self.endpoint.path().trim_end_matches('/'),
+            path
+        ));
+        url
+    }
+
+    fn authorize<B>(&self, request: &mut Request<B>) {
+        if let Some(auth) = &self.auth {
+            auth.apply(request);
+        }
+        if let Some(tenant_id) = &self.tenant_id {
+            request
+                .headers_mut()
+                .insert(HeaderName::from_static("x-scope-orgid"), tenant_id.clone());
+        }
+    }
+
+    async fn send<O>(&self, entries: Vec<Entry>, out: &mut O) -> Result<(), ()>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        let mut events = stream::iter(entries)
+            .map(|entry| self.create_event(entry))
+            .map(Ok);
+        out.send_all(&mut events).await
+    }
+
+    fn create_event(&self, entry: Entry) -> Event {
+        let labels = entry
+            .labels
+            .into_iter()
+            .map(|(name, value)| (name, Value::from(value)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut log = LogEvent::default();
+        log.insert(log_schema().message_key(), entry.line);
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.timestamp_nanos(entry.timestamp),
+        );
+        log.insert(log_schema().source_type_key(), Bytes::from("loki"));
+        log.insert(&self.labels_key, Value::Map(labels));
+        log.into()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct QueryResponse {
+    data: QueryData,
+}
+
+#[derive(Deserialize, Debug)]
+struct QueryData {
+    result: Vec<LokiStream>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TailResponse {
+    #[serde(default)]
+    streams: Vec<LokiStream>,
+    dropped_entries: Option<Vec<IgnoredAny>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LokiStream {
+    stream: BTreeMap<String, String>,
+    values: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    /// Nanoseconds since the Unix epoch.
+    timestamp: i64,
+    labels: BTreeMap<String, String>,
+    line: String,
+}
+
+/// Flatten the streams into their entries, ordered by timestamp.
+fn entries(streams: Vec<LokiStream>) -> Result<Vec<Entry>, LokiError> {
+    let mut entries = Vec::new();
+    for stream in streams {
+        for (timestamp, line) in stream.values {
+            let timestamp = timestamp
+                .parse()
+                .map_err(|_| LokiError::InvalidTimestamp { timestamp })?;
+            entries.push(Entry {
+                timestamp,
+                labels: stream.stream.clone(),
+                line,
+            });
+        }
+    }
+    entries.sort_by_key(|entry| entry.timestamp);
+    Ok(entries)
+}
+
+/// The position in the entries, which the requests start from. As the start
+/// is inclusive, we remember the entries at its timestamp to not emit those
+/// twice.
+#[derive(Debug)]
+struct Cursor {
+    timestamp: i64,
+    seen: HashSet<(BTreeMap<String, String>, String)>,
+}
+
+impl Cursor {
+    fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            timestamp: start.timestamp_nanos(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Move past the ordered `entries`, returning those not seen before.
+    fn advance(&mut self, entries: Vec<Entry>) -> Vec<Entry> {
+        entries
+            .into_iter()
+            .filter(|entry| {
+                if entry.timestamp < self.timestamp {
+                    return false;
+                }
+                if entry.timestamp > self.timestamp {
+                    self.timestamp = entry.timestamp;
+                    self.seen.clear();
+                }
+                self.seen.insert((entry.labels.clone(), entry.line.clone()))
+            })
+            .collect()
+    }
+
+    /// Move past the timestamp of the cursor, giving up on any entries at it
+    /// we haven't seen.
+    fn skip(&mut self) {
+        self.timestamp += 1;
+        self.seen.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use std::sync::{Arc, Mutex};
+    use tokio::time::timeout;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LokiConfig>();
+    }
+
+    fn entry(timestamp: i64, line: &str) -> Entry {
+        let mut labels = BTreeMap::new();
+        labels.insert("job".to_owned(), "varlogs".to_owned());
+        Entry {
+            timestamp,
+            labels,
+            line: line.to_owned(),
+        }
+    }
+
+    #[test]
+    fn parses_entries() {
+        let response: QueryResponse = serde_json::from_str(
+            r#"{"status":"success","data":{"resultType":"streams","result":[
+                {"stream":{"job":"varlogs"},"values":[["3","three"],["1","one"]]},
+                {"stream":{"job":"varlogs"},"values":[["2","two"]]}
+            ]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            entries(response.data.result).unwrap(),
+            vec![entry(1, "one"), entry(2, "two"), entry(3, "three")]
+        );
+
+        let response: TailResponse = serde_json::from_str(
+            r#"{"streams":[{"stream":{"job":"varlogs"},"values":[["x","bad"]]}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            entries(response.streams),
+            Err(LokiError::InvalidTimestamp { .. })
+        ));
+    }
+
+    #[test]
+    fn cursor_skips_seen_entries() {
+        let mut cursor = Cursor::new(Utc.timestamp_nanos(1));
+        assert_eq!(
+            cursor.advance(vec![entry(0, "old"), entry(1, "a"), entry(2, "b")]),
+            vec![entry(1, "a"), entry(2, "b")]
+        );
+        assert_eq!(cursor.timestamp, 2);
+
+        // The next page starts at the timestamp of the last entry.
+        assert_eq!(
+            cursor.advance(vec![entry(2, "b"), entry(2, "c"), entry(3, "d")]),
+            vec![entry(2, "c"), entry(3, "d")]
+        );
+
+        cursor.skip();
+        assert_eq!(cursor.timestamp, 4);
+        assert_eq!(cursor.advance(vec![entry(3, "d")]), vec![]);
+    }
+
+    #[tokio::test]
+    async fn queries_ranges() {
+        let addr = next_addr();
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let requests = Arc::clone(&queries);
+        let make_svc = make_service_fn(move |_| {
+            let requests = Arc::clone(&requests);
+            async move {
+                Ok::<_, Error>(service_fn(move |request: Request<Body>| {
+                    let requests = Arc::clone(&requests);
+                    async move {
+                        assert_eq!(request.uri().path(), QUERY_RANGE_PATH);
+                        assert_eq!(request.headers()["x-scope-orgid"], "tenant");
+                        let query = request.uri().query().unwrap_or_default().to_owned();
+                        let values = if query.contains("start=1000000000&") {
+                            r#"[["1000000000","one"],["2000000000","two"]]"#
+                        } else if query.contains("start=2000000000&") {
+                            r#"[["2000000000","two"],["3000000000","three"]]"#
+                        } else {
+                            r#"[["3000000000","three"]]"#
+                        };
+                        let body = format!(
+                            r#"{{"data":{{"result":[{{"stream":{{"job":"varlogs"}},"values":{}}}]}}}}"#,
+                            values
+                        );
+                        requests.lock().unwrap().push(query);
+                        Ok::<_, Error>(Response::new(Body::from(body)))
+                    }
+                }))
+            }
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let config: LokiConfig = toml::from_str(&format!(
+            r#"
+            endpoint = "http://{}"
+            query = '{{job="varlogs"}}'
+            mode = "query"
+            start = "1970-01-01T00:00:01Z"
+            end = "1970-01-01T00:00:10Z"
+            limit = 2
+            tenant_id = "tenant"
+            "#,
+            addr
+        ))
+        .unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let source = config
+            .build(
+                "loki",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        timeout(Duration::from_secs(5), source)
+            .await
+            .expect("Query did not finish")
+            .unwrap();
+
+        let events = rx.collect().compat().await.unwrap();
+        let lines = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec!["one", "two", "three"]);
+
+        let log = events[0].as_log();
+        assert_eq!(log["labels.job"], "varlogs".into());
+        assert_eq!(log[log_schema().source_type_key()], "loki".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1, 0).into()
+        );
+
+        // Each page starts at the last entry, and the query is done once a
+        // page isn't full.
+        let queries = queries.lock().unwrap();
+        assert_eq!(queries.len(), 3);
+        assert!(queries[1].contains("start=2000000000&"));
+        assert!(queries[2].contains("start=3000000000&"));
+    }
+}
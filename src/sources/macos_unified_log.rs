@@ -0,0 +1,375 @@
+use crate::{
+    config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{MacosUnifiedLogEventReceived, MacosUnifiedLogInvalidRecord},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use codec::BytesDelimitedCodec;
+use futures::{
+    compat::{Compat01As03Sink, Sink01CompatExt},
+    future,
+    stream::BoxStream,
+    SinkExt, StreamExt,
+};
+use lazy_static::lazy_static;
+use nix::{
+    sys::signal::{kill, Signal},
+    unistd::Pid,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use snafu::{ResultExt, Snafu};
+use std::{path::PathBuf, process::Stdio, time::Duration};
+use tokio::{io, process::Command, time::delay_for};
+use tokio_util::codec::FramedRead;
+use tracing_futures::Instrument;
+
+const MESSAGE: &str = "eventMessage";
+const TIMESTAMP: &str = "timestamp";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%z";
+
+const BACKOFF_DURATION: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    static ref LOG: PathBuf = "/usr/bin/log".into();
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("log failed to execute: {}", source))]
+    LogSpawn { source: io::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Default,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Default => "default",
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level::Default
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct MacosUnifiedLogConfig {
+    pub predicate: Option<String>,
+    pub level: Level,
+    pub log_path: Option<PathBuf>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<MacosUnifiedLogConfig>("macos_unified_log")
+}
+
+impl_generate_config_from_default!(MacosUnifiedLogConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "macos_unified_log")]
+impl SourceConfig for MacosUnifiedLogConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let log_path = self.log_path.clone().unwrap_or_else(|| LOG.clone());
+        let predicate = self.predicate.clone();
+        let level = self.level;
+
+        let start: StartLogFn =
+            Box::new(move || start_log_stream(&log_path, level, predicate.as_deref()));
+
+        Ok(Box::pin(
+            MacosUnifiedLogSource {
+                out: out.sink_compat(),
+            }
+            .run_shutdown(shutdown, start)
+            .instrument(info_span!("macos-unified-log-server")),
+        ))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "macos_unified_log"
+    }
+}
+
+struct MacosUnifiedLogSource {
+    out: Compat01As03Sink<Pipeline, Event>,
+}
+
+impl MacosUnifiedLogSource {
+    async fn run_shutdown(self, shutdown: ShutdownSignal, start_log: StartLogFn) -> Result<(), ()> {
+        let mut on_stop = None;
+        let run = Box::pin(self.run(&mut on_stop, start_log));
+        future::select(run, shutdown).await;
+
+        if let Some(stop) = on_stop {
+            stop();
+        }
+
+        Ok(())
+    }
+
+    async fn run(mut self, on_stop: &mut Option<StopLogFn>, start_log: StartLogFn) {
+        loop {
+            info!("Starting log stream.");
+            match start_log() {
+                Ok((stream, stop)) => {
+                    *on_stop = Some(stop);
+                    let should_restart = self.run_stream(stream).await;
+                    if let Some(stop) = on_stop.take() {
+                        stop();
+                    }
+                    if !should_restart {
+                        return;
+                    }
+                }
+                Err(error) => {
+                    error!(message = "Error starting log stream process.", %error);
+                }
+            };
+
+            // `log stream` runs until it is killed, so it is an error if we
+            // reach here.
+            delay_for(BACKOFF_DURATION).await;
+        }
+    }
+
+    /// Process `log stream` output until some error occurs.
+    /// Return `true` if should restart `log stream`.
+    async fn run_stream(&mut self, mut stream: BoxStream<'static, io::Result<Bytes>>) -> bool {
+        loop {
+            let bytes = match stream.next().await {
+                None => {
+                    warn!("Log stream process stopped.");
+                    return true;
+                }
+                Some(Ok(bytes)) => bytes,
+                Some(Err(error)) => {
+                    error!(
+                        message = "Could not read from macOS unified log source.",
+                        %error,
+                    );
+                    continue;
+                }
+            };
+
+            // `log stream` prints a plain text banner describing the active
+            // filter before it starts emitting records.
+            if !bytes.starts_with(b"{") {
+                continue;
+            }
+
+            let record = match decode_record(&bytes) {
+                Ok(record) => record,
+                Err(error) => {
+                    emit!(MacosUnifiedLogInvalidRecord {
+                        error,
+                        text: String::from_utf8_lossy(&bytes).into_owned()
+                    });
+                    continue;
+                }
+            };
+
+            emit!(MacosUnifiedLogEventReceived {
+                byte_size: bytes.len()
+            });
+
+            if let Err(error) = self.out.send(create_event(record)).await {
+                error!(message = "Could not send macOS unified log.", %error);
+                // `out` channel is closed, don't restart `log stream`.
+                return false;
+            }
+        }
+    }
+}
+
+/// A function that starts the `log stream` process.
+/// Return a stream of output splitted by '\n', and a `StopLogFn`.
+///
+/// Code uses `start_log_stream` below,
+/// but we need this type to implement a fake log source in testing.
+type StartLogFn = Box<
+    dyn Fn() -> crate::Result<(BoxStream<'static, io::Result<Bytes>>, StopLogFn)> + Send + Sync,
+>;
+
+type StopLogFn = Box<dyn FnOnce() + Send>;
+
+fn start_log_stream(
+    path: &PathBuf,
+    level: Level,
+    predicate: Option<&str>,
+) -> crate::Result<(BoxStream<'static, io::Result<Bytes>>, StopLogFn)> {
+    let mut command = Command::new(path);
+    command.stdout(Stdio::piped());
+    command.arg("stream");
+    command.arg("--style").arg("ndjson");
+    command.arg("--level").arg(level.as_str());
+
+    if let Some(predicate) = predicate {
+        command.arg("--predicate").arg(predicate);
+    }
+
+    let mut child = command.spawn().context(LogSpawn)?;
+
+    let stream = FramedRead::new(
+        child.stdout.take().unwrap(),
+        BytesDelimitedCodec::new(b'\n'),
+    )
+    .boxed();
+
+    let pid = Pid::from_raw(child.id() as i32);
+    let stop = Box::new(move || {
+        let _ = kill(pid, Signal::SIGTERM);
+    });
+
+    Ok((stream, stop))
+}
+
+fn decode_record(line: &[u8]) -> Result<Map<String, JsonValue>, serde_json::Error> {
+    serde_json::from_slice(line)
+}
+
+fn create_event(record: Map<String, JsonValue>) -> Event {
+    let mut log = LogEvent::default();
+    for (key, value) in record {
+        match key.as_str() {
+            MESSAGE => {
+                log.insert(log_schema().message_key(), Value::from(value));
+            }
+            TIMESTAMP => match value.as_str().and_then(parse_timestamp) {
+                Some(timestamp) => {
+                    log.insert(log_schema().timestamp_key(), Value::Timestamp(timestamp));
+                }
+                None => {
+                    log.insert_flat(key, value);
+                }
+            },
+            _ => {
+                log.insert_flat(key, value);
+            }
+        }
+    }
+    log.try_insert(log_schema().timestamp_key(), Value::Timestamp(Utc::now()));
+    log.try_insert(
+        log_schema().source_type_key(),
+        Bytes::from("macos_unified_log"),
+    );
+
+    log.into()
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use tokio::time::timeout;
+
+    const FAKE_LOG: &str = r#"Filtering the log data using "subsystem == "com.apple.example""
+{"traceID":51824,"eventMessage":"Connection established","eventType":"logEvent","processImagePath":"/usr/libexec/exampled","subsystem":"com.apple.example","category":"network","messageType":"Default","processID":412,"timestamp":"2021-01-27 15:03:08.487571-0800"}
+{"traceID":51825,"eventMessage":"Not JSON
+{"traceID":51826,"eventMessage":"Connection closed","eventType":"logEvent","processImagePath":"/usr/libexec/exampled","subsystem":"com.apple.example","category":"network","messageType":"Info","processID":412,"timestamp":"2021-01-27 15:03:09.000001-0800"}
+"#;
+
+    fn fake_log() -> crate::Result<(BoxStream<'static, io::Result<Bytes>>, StopLogFn)> {
+        let lines = FAKE_LOG
+            .lines()
+            .map(|line| Ok(Bytes::from(line)))
+            .collect::<Vec<_>>();
+        // Keep the fake process "running" after its output is exhausted.
+        let stream = futures::stream::iter(lines)
+            .chain(futures::stream::pending())
+            .boxed();
+        Ok((stream, Box::new(|| ())))
+    }
+
+    async fn run_log() -> Vec<Event> {
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+
+        let source = MacosUnifiedLogSource {
+            out: tx.sink_compat(),
+        }
+        .run_shutdown(shutdown, Box::new(fake_log));
+        tokio::spawn(source);
+
+        delay_for(Duration::from_millis(100)).await;
+        drop(trigger);
+
+        timeout(Duration::from_secs(1), rx.collect().compat())
+            .await
+            .expect("Unclosed channel")
+            .unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MacosUnifiedLogConfig>();
+    }
+
+    #[tokio::test]
+    async fn reads_log_stream() {
+        let received = run_log().await;
+        assert_eq!(received.len(), 2);
+
+        let log = received[0].as_log();
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Connection established".into()
+        );
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2021, 1, 27).and_hms_micro(23, 3, 8, 487571))
+        );
+        assert_eq!(log["subsystem"], "com.apple.example".into());
+        assert_eq!(log["processID"], Value::Integer(412));
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "macos_unified_log".into()
+        );
+
+        let log = received[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "Connection closed".into());
+        assert_eq!(log["messageType"], "Info".into());
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        assert_eq!(
+            parse_timestamp("2021-01-27 15:03:08.487571-0800"),
+            Some(Utc.ymd(2021, 1, 27).and_hms_micro(23, 3, 8, 487571))
+        );
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+}
@@ -3,6 +3,10 @@ use snafu::Snafu;
 
 #[cfg(feature = "sources-apache_metrics")]
 pub mod apache_metrics;
+#[cfg(feature = "sources-api_poller")]
+pub mod api_poller;
+#[cfg(feature = "sources-auth0_logs")]
+pub mod auth0_logs;
 #[cfg(feature = "sources-aws_ecs_metrics")]
 pub mod aws_ecs_metrics;
 #[cfg(feature = "sources-aws_kinesis_firehose")]
@@ -11,34 +15,56 @@ pub mod aws_kinesis_firehose;
 pub mod aws_s3;
 #[cfg(feature = "sources-docker_logs")]
 pub mod docker_logs;
+#[cfg(feature = "sources-elasticsearch")]
+pub mod elasticsearch;
 #[cfg(feature = "sources-file")]
 pub mod file;
+#[cfg(feature = "sources-gcp_cloud_logging")]
+pub mod gcp_cloud_logging;
 #[cfg(feature = "sources-generator")]
 pub mod generator;
+#[cfg(feature = "sources-google_workspace_logs")]
+pub mod google_workspace_logs;
 #[cfg(feature = "sources-host_metrics")]
 pub mod host_metrics;
 #[cfg(feature = "sources-http")]
 pub mod http;
+#[cfg(feature = "sources-internal_logs")]
+pub mod internal_logs;
 #[cfg(feature = "sources-internal_metrics")]
 pub mod internal_metrics;
+#[cfg(feature = "sources-jmx_metrics")]
+pub mod jmx_metrics;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub mod journald;
 #[cfg(all(feature = "sources-kafka", feature = "rdkafka"))]
 pub mod kafka;
+#[cfg(feature = "sources-kubernetes-events")]
+pub mod kubernetes_events;
 #[cfg(feature = "sources-kubernetes-logs")]
 pub mod kubernetes_logs;
 #[cfg(feature = "sources-logplex")]
 pub mod logplex;
+#[cfg(feature = "sources-loki")]
+pub mod loki;
+#[cfg(all(target_os = "macos", feature = "sources-macos_unified_log"))]
+pub mod macos_unified_log;
 #[cfg(feature = "sources-mongodb_metrics")]
 pub mod mongodb_metrics;
 #[cfg(feature = "sources-nginx_metrics")]
 pub mod nginx_metrics;
+#[cfg(feature = "sources-okta_logs")]
+pub mod okta_logs;
+#[cfg(feature = "sources-postgresql_metrics")]
+pub mod postgresql_metrics;
 #[cfg(feature = "sources-prometheus")]
 pub mod prometheus;
 #[cfg(feature = "sources-socket")]
 pub mod socket;
 #[cfg(feature = "sources-splunk_hec")]
 pub mod splunk_hec;
+#[cfg(feature = "sources-spool")]
+pub mod spool;
 #[cfg(feature = "sources-statsd")]
 pub mod statsd;
 #[cfg(feature = "sources-stdin")]
@@ -48,7 +74,7 @@ pub mod syslog;
 #[cfg(feature = "sources-vector")]
 pub mod vector;
 
-mod util;
+pub(crate) mod util;
 
 pub type Source = BoxFuture<'static, Result<(), ()>>;
 
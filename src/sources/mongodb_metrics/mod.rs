@@ -26,7 +26,10 @@ use std::{collections::BTreeMap, future::ready, time::Instant};
 use tokio::time;
 
 mod types;
-use types::{CommandBuildInfo, CommandIsMaster, CommandServerStatus, NodeType};
+use types::{
+    CommandBuildInfo, CommandDbStats, CommandIsMaster, CommandReplSetGetStatus,
+    CommandServerStatus, NodeType,
+};
 
 macro_rules! tags {
     ($tags:expr) => { $tags.clone() };
@@ -94,6 +97,7 @@ struct MongoDBMetricsConfig {
 #[derive(Debug)]
 struct MongoDBMetrics {
     client: Client,
+    node_type: NodeType,
     endpoint: String,
     namespace: Option<String>,
     tags: BTreeMap<String, String>,
@@ -189,6 +193,7 @@ impl MongoDBMetrics {
 
         Ok(Self {
             client,
+            node_type,
             endpoint,
             namespace,
             tags,
@@ -305,7 +310,7 @@ impl MongoDBMetrics {
 
     async fn collect(&self) -> stream::BoxStream<'static, Metric> {
         // `up` metric is `1` if collection is successful, otherwise `0`.
-        let (up_value, metrics) = match self.collect_server_status().await {
+        let (up_value, metrics) = match self.collect_metrics().await {
             Ok(metrics) => (1.0, metrics),
             Err(error) => {
                 match error {
@@ -332,6 +337,98 @@ impl MongoDBMetrics {
         .boxed()
     }
 
+    async fn collect_metrics(&self) -> Result<Vec<Metric>, CollectError> {
+        let mut metrics = self.collect_server_status().await?;
+        metrics.extend(self.collect_db_stats().await?);
+        if self.node_type == NodeType::Replset {
+            metrics.extend(self.collect_repl_set_status().await?);
+        }
+        Ok(metrics)
+    }
+
+    /// Collect metrics from `dbStats` command for every database.
+    /// https://docs.mongodb.com/manual/reference/command/dbStats/
+    async fn collect_db_stats(&self) -> Result<Vec<Metric>, CollectError> {
+        let mut metrics = vec![];
+
+        let names = self
+            .client
+            .list_database_names(None, None)
+            .await
+            .map_err(CollectError::Mongo)?;
+        for name in names {
+            let doc = self
+                .client
+                .database(&name)
+                .run_command(doc! { "dbStats": 1 }, None)
+                .await
+                .map_err(CollectError::Mongo)?;
+            let stats: CommandDbStats = from_document(doc).map_err(CollectError::Bson)?;
+
+            metrics.push(self.create_metric(
+                "db_stats_collections",
+                gauge!(stats.collections),
+                tags!(self.tags, "database" => name.clone()),
+            ));
+            metrics.push(self.create_metric(
+                "db_stats_objects",
+                gauge!(stats.objects),
+                tags!(self.tags, "database" => name.clone()),
+            ));
+            metrics.push(self.create_metric(
+                "db_stats_data_size_bytes",
+                gauge!(stats.data_size),
+                tags!(self.tags, "database" => name.clone()),
+            ));
+            metrics.push(self.create_metric(
+                "db_stats_storage_size_bytes",
+                gauge!(stats.storage_size),
+                tags!(self.tags, "database" => name.clone()),
+            ));
+            metrics.push(self.create_metric(
+                "db_stats_indexes",
+                gauge!(stats.indexes),
+                tags!(self.tags, "database" => name.clone()),
+            ));
+            metrics.push(self.create_metric(
+                "db_stats_index_size_bytes",
+                gauge!(stats.index_size),
+                tags!(self.tags, "database" => name),
+            ));
+        }
+
+        Ok(metrics)
+    }
+
+    /// Collect the replication lag of the members from `replSetGetStatus` command.
+    /// https://docs.mongodb.com/manual/reference/command/replSetGetStatus/
+    async fn collect_repl_set_status(&self) -> Result<Vec<Metric>, CollectError> {
+        let doc = self
+            .client
+            .database("admin")
+            .run_command(doc! { "replSetGetStatus": 1 }, None)
+            .await
+            .map_err(CollectError::Mongo)?;
+        let status: CommandReplSetGetStatus = from_document(doc).map_err(CollectError::Bson)?;
+
+        Ok(status
+            .lags()
+            .into_iter()
+            .map(|(member, lag)| {
+                self.create_metric(
+                    "mongod_repl_lag_seconds",
+                    gauge!(lag),
+                    tags!(
+                        self.tags,
+                        "set" => status.set.clone(),
+                        "member" => member.name.clone(),
+                        "state" => member.state_str.clone()
+                    ),
+                )
+            })
+            .collect())
+    }
+
     /// Collect metrics from `serverStatus` command.
     /// https://docs.mongodb.com/manual/reference/command/serverStatus/
     async fn collect_server_status(&self) -> Result<Vec<Metric>, CollectError> {
@@ -1002,7 +1099,7 @@ impl MongoDBMetrics {
         ));
 
         // op_counters_repl_total
-        for (r#type, value) in status.opcounters {
+        for (r#type, value) in status.opcounters_repl {
             metrics.push(self.create_metric(
                 "op_counters_repl_total",
                 counter!(value),
@@ -1011,7 +1108,7 @@ impl MongoDBMetrics {
         }
 
         // op_counters_total
-        for (r#type, value) in status.opcounters_repl {
+        for (r#type, value) in status.opcounters {
             metrics.push(self.create_metric(
                 "op_counters_total",
                 counter!(value),
@@ -1039,6 +1136,28 @@ mod tests {
         let endpoint = MongoDBMetrics::sanitize_endpoint(endpoint, &client_options);
         assert_eq!(&endpoint, "mongodb://mongos0.example.com:27017,mongos1.example.com:27017,mongos2.example.com:27017/?tls=true");
     }
+
+    #[test]
+    fn repl_set_lags() {
+        use chrono::TimeZone;
+
+        let status: CommandReplSetGetStatus = from_document(doc! {
+            "set": "vector",
+            "members": [
+                { "name": "mongo1:27017", "stateStr": "SECONDARY", "optimeDate": Utc.timestamp(98, 500_000_000) },
+                { "name": "mongo2:27017", "stateStr": "PRIMARY", "optimeDate": Utc.timestamp(100, 0) },
+                { "name": "mongo3:27017", "stateStr": "ARBITER" },
+            ],
+        })
+        .unwrap();
+
+        let lags = status
+            .lags()
+            .into_iter()
+            .map(|(member, lag)| (member.name.as_str(), lag))
+            .collect::<Vec<_>>();
+        assert_eq!(lags, vec![("mongo1:27017", 1.5), ("mongo2:27017", 0.0)]);
+    }
 }
 
 #[cfg(all(test, feature = "mongodb_metrics-integration-tests"))]
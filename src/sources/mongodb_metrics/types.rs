@@ -429,3 +429,55 @@ pub struct CommandServerStatusNetwork {
     pub bytes_out: i64,
     pub num_requests: i64,
 }
+
+/// https://docs.mongodb.com/manual/reference/command/dbStats/
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandDbStats {
+    pub collections: i64,
+    pub objects: i64,
+    pub data_size: f64,
+    pub storage_size: f64,
+    pub indexes: i64,
+    pub index_size: f64,
+}
+
+/// https://docs.mongodb.com/manual/reference/command/replSetGetStatus/
+#[derive(Debug, Deserialize)]
+pub struct CommandReplSetGetStatus {
+    pub set: String,
+    pub members: Vec<CommandReplSetGetStatusMember>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandReplSetGetStatusMember {
+    pub name: String,
+    pub state_str: String,
+    /// Missing for arbiters, which don't hold data.
+    pub optime_date: Option<DateTime>,
+}
+
+impl CommandReplSetGetStatus {
+    /// The seconds the members are behind the primary, which is unknown while
+    /// the set has no primary.
+    pub fn lags(&self) -> Vec<(&CommandReplSetGetStatusMember, f64)> {
+        let primary = self
+            .members
+            .iter()
+            .find(|member| member.state_str == "PRIMARY")
+            .and_then(|member| member.optime_date.as_ref());
+        match primary {
+            Some(primary) => self
+                .members
+                .iter()
+                .filter_map(|member| {
+                    let optime = member.optime_date.as_ref()?;
+                    let lag = primary.timestamp_millis() - optime.timestamp_millis();
+                    Some((member, lag as f64 / 1000.0))
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+}
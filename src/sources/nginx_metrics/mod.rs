@@ -12,7 +12,9 @@ use crate::{
 use bytes::Bytes;
 use chrono::Utc;
 use futures::{
-    compat::Sink01CompatExt, future::join_all, stream, SinkExt, StreamExt, TryFutureExt,
+    compat::Sink01CompatExt,
+    future::{join, join_all},
+    stream, SinkExt, StreamExt, TryFutureExt,
 };
 use futures01::Sink;
 use http::{Request, StatusCode};
@@ -23,7 +25,7 @@ use std::{collections::BTreeMap, convert::TryFrom, future::ready, time::Instant}
 use tokio::time;
 
 pub mod parser;
-use parser::NginxStubStatus;
+use parser::{NginxPlusConnections, NginxPlusHttpRequests, NginxStubStatus};
 
 macro_rules! counter {
     ($value:expr) => {
@@ -63,6 +65,25 @@ struct NginxMetricsConfig {
     namespace: String,
     tls: Option<TlsOptions>,
     auth: Option<Auth>,
+    #[serde(default)]
+    api: NginxApi,
+}
+
+/// The module the status is read from.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum NginxApi {
+    /// The text page of `ngx_http_stub_status_module`.
+    StubStatus,
+    /// The JSON API of NGINX Plus, with the endpoint being its versioned
+    /// base path such as `/api/6`.
+    Plus,
+}
+
+impl Default for NginxApi {
+    fn default() -> Self {
+        Self::StubStatus
+    }
 }
 
 pub fn default_scrape_interval_secs() -> u64 {
@@ -100,6 +121,7 @@ impl SourceConfig for NginxMetricsConfig {
                 endpoint.clone(),
                 self.auth.clone(),
                 namespace.clone(),
+                self.api,
             )?);
         }
 
@@ -141,6 +163,7 @@ struct NginxMetrics {
     endpoint: String,
     auth: Option<Auth>,
     namespace: Option<String>,
+    api: NginxApi,
     tags: BTreeMap<String, String>,
 }
 
@@ -150,6 +173,7 @@ impl NginxMetrics {
         endpoint: String,
         auth: Option<Auth>,
         namespace: Option<String>,
+        api: NginxApi,
     ) -> crate::Result<Self> {
         let mut tags = BTreeMap::new();
         tags.insert("endpoint".into(), endpoint.clone());
@@ -160,6 +184,7 @@ impl NginxMetrics {
             endpoint,
             auth,
             namespace,
+            api,
             tags,
         })
     }
@@ -184,20 +209,17 @@ impl NginxMetrics {
     }
 
     async fn collect_metrics(&self) -> Result<Vec<Metric>, ()> {
-        let response = self.get_nginx_response().await.map_err(|error| {
-            emit!(NginxMetricsRequestError {
-                error,
-                endpoint: &self.endpoint,
-            })
-        })?;
+        match self.api {
+            NginxApi::StubStatus => self.collect_stub_status().await,
+            NginxApi::Plus => self.collect_plus().await,
+        }
+    }
+
+    async fn collect_stub_status(&self) -> Result<Vec<Metric>, ()> {
+        let response = self.get_nginx_response(&self.endpoint).await?;
 
         let status = NginxStubStatus::try_from(String::from_utf8_lossy(&response).as_ref())
-            .map_err(|error| {
-                emit!(NginxMetricsStubStatusParseError {
-                    error,
-                    endpoint: &self.endpoint,
-                })
-            })?;
+            .map_err(|error| self.emit_parse_error(error))?;
 
         Ok(vec![
             self.create_metric("connections_active", gauge!(status.active)),
@@ -210,8 +232,42 @@ impl NginxMetrics {
         ])
     }
 
-    async fn get_nginx_response(&self) -> crate::Result<Bytes> {
-        let mut request = Request::get(&self.endpoint).body(Body::empty())?;
+    async fn collect_plus(&self) -> Result<Vec<Metric>, ()> {
+        let base = self.endpoint.trim_end_matches('/');
+        let connections_url = format!("{}/connections", base);
+        let requests_url = format!("{}/http/requests", base);
+        let (connections, requests) = join(
+            self.get_nginx_response(&connections_url),
+            self.get_nginx_response(&requests_url),
+        )
+        .await;
+
+        let connections = NginxPlusConnections::try_from(&connections?[..])
+            .map_err(|error| self.emit_parse_error(error))?;
+        let requests = NginxPlusHttpRequests::try_from(&requests?[..])
+            .map_err(|error| self.emit_parse_error(error))?;
+
+        Ok(vec![
+            self.create_metric("connections_active", gauge!(connections.active)),
+            self.create_metric("connections_accepted_total", counter!(connections.accepted)),
+            self.create_metric("connections_dropped_total", counter!(connections.dropped)),
+            self.create_metric("connections_waiting", gauge!(connections.idle)),
+            self.create_metric("http_requests_total", counter!(requests.total)),
+            self.create_metric("http_requests_current", gauge!(requests.current)),
+        ])
+    }
+
+    async fn get_nginx_response(&self, url: &str) -> Result<Bytes, ()> {
+        self.send_request(url).await.map_err(|error| {
+            emit!(NginxMetricsRequestError {
+                error,
+                endpoint: url,
+            })
+        })
+    }
+
+    async fn send_request(&self, url: &str) -> crate::Result<Bytes> {
+        let mut request = Request::get(url).body(Body::empty())?;
         if let Some(auth) = &self.auth {
             auth.apply(&mut request);
         }
@@ -224,6 +280,13 @@ impl NginxMetrics {
         }
     }
 
+    fn emit_parse_error(&self, error: parser::ParseError) {
+        emit!(NginxMetricsStubStatusParseError {
+            error,
+            endpoint: &self.endpoint,
+        })
+    }
+
     fn create_metric(&self, name: &str, value: MetricValue) -> Metric {
         Metric {
             name: name.into(),
@@ -265,6 +328,7 @@ mod integration_tests {
                 namespace: "vector_nginx".to_owned(),
                 tls: None,
                 auth,
+                api: NginxApi::StubStatus,
             }
             .build(
                 "default",
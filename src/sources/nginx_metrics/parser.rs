@@ -6,6 +6,7 @@ use nom::{
     sequence::{preceded, terminated, tuple},
     take_while_m_n,
 };
+use serde::Deserialize;
 use snafu::Snafu;
 use std::convert::TryFrom;
 
@@ -13,6 +14,8 @@ use std::convert::TryFrom;
 pub enum ParseError {
     #[snafu(display("failed to parse NginxStubStatus, kind: `{:?}`", kind))]
     NginxStubStatusParseError { kind: ErrorKind },
+    #[snafu(display("failed to parse NGINX Plus API response: {}", reason))]
+    NginxPlusApiParseError { reason: String },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -72,6 +75,46 @@ impl<'a> TryFrom<&'a str> for NginxStubStatus {
     }
 }
 
+fn parse_json<'a, T: Deserialize<'a>>(input: &'a [u8]) -> Result<T, ParseError> {
+    serde_json::from_slice(input).map_err(|error| ParseError::NginxPlusApiParseError {
+        reason: error.to_string(),
+    })
+}
+
+/// The `/connections` endpoint of the NGINX Plus API:
+/// http://nginx.org/en/docs/http/ngx_http_api_module.html#def_nginx_connections
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct NginxPlusConnections {
+    pub accepted: usize,
+    pub dropped: usize,
+    pub active: usize,
+    pub idle: usize,
+}
+
+impl<'a> TryFrom<&'a [u8]> for NginxPlusConnections {
+    type Error = ParseError;
+
+    fn try_from(input: &'a [u8]) -> Result<Self, Self::Error> {
+        parse_json(input)
+    }
+}
+
+/// The `/http/requests` endpoint of the NGINX Plus API:
+/// http://nginx.org/en/docs/http/ngx_http_api_module.html#def_nginx_http_requests
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct NginxPlusHttpRequests {
+    pub total: usize,
+    pub current: usize,
+}
+
+impl<'a> TryFrom<&'a [u8]> for NginxPlusHttpRequests {
+    type Error = ParseError;
+
+    fn try_from(input: &'a [u8]) -> Result<Self, Self::Error> {
+        parse_json(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +139,32 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn nginx_plus_try_from() {
+        let data = br#"{"accepted":4968119,"dropped":0,"active":5,"idle":117}"#;
+        assert_eq!(
+            NginxPlusConnections::try_from(&data[..]).expect("valid data"),
+            NginxPlusConnections {
+                accepted: 4968119,
+                dropped: 0,
+                active: 5,
+                idle: 117
+            }
+        );
+
+        let data = br#"{"total":10624511,"current":4}"#;
+        assert_eq!(
+            NginxPlusHttpRequests::try_from(&data[..]).expect("valid data"),
+            NginxPlusHttpRequests {
+                total: 10624511,
+                current: 4
+            }
+        );
+
+        assert!(matches!(
+            NginxPlusHttpRequests::try_from(&b"{}"[..]),
+            Err(ParseError::NginxPlusApiParseError { .. })
+        ));
+    }
 }
@@ -0,0 +1,209 @@
+use super::util::api_poller::{ApiPoller, JsonPath, Pagination, PollerSpec, RateLimitHeaders};
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    http::HttpClient,
+    shutdown::ShutdownSignal,
+    tls::{TlsOptions, TlsSettings},
+    Pipeline,
+};
+use http::{header, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{path::PathBuf, time::Duration};
+use url::Url;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("Invalid API token: {}", source))]
+    InvalidToken {
+        source: http::header::InvalidHeaderValue,
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OktaLogsConfig {
+    endpoint: String,
+    token: String,
+    filter: Option<String>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsOptions>,
+}
+
+pub fn default_interval_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<OktaLogsConfig>("okta_logs")
+}
+
+impl GenerateConfig for OktaLogsConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoint: "https://example.okta.com".to_owned(),
+            token: "${OKTA_API_TOKEN}".to_owned(),
+            filter: None,
+            interval_secs: default_interval_secs(),
+            data_dir: None,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "okta_logs")]
+impl SourceConfig for OktaLogsConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let data_dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), name)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let poller = ApiPoller::new(client, self.spec()?, None, data_dir);
+        Ok(Box::pin(poller.run(out, shutdown)))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "okta_logs"
+    }
+}
+
+impl OktaLogsConfig {
+    fn spec(&self) -> crate::Result<PollerSpec> {
+        let endpoint = format!("{}/api/v1/logs", self.endpoint.trim_end_matches('/'));
+        let mut url = Url::parse(&endpoint).context(InvalidEndpoint { endpoint })?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("sortOrder", "ASCENDING");
+            query.append_pair("limit", "1000");
+            if let Some(filter) = &self.filter {
+                query.append_pair("filter", filter);
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("SSWS {}", self.token)).context(InvalidToken)?,
+        );
+
+        Ok(PollerSpec {
+            source_type: "okta_logs",
+            url: url.into(),
+            headers,
+            records: JsonPath::root(),
+            // The System Log API always returns a `next` link, when polling
+            // it points at the end of the log so far.
+            pagination: Pagination::LinkHeader,
+            rate_limit: RateLimitHeaders::default(),
+            timestamp_field: Some("published".to_owned()),
+            message_field: Some("displayMessage".to_owned()),
+            interval: Duration::from_secs(self.interval_secs),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Request, Response, Server,
+    };
+    use tempfile::tempdir;
+    use tokio::time::{delay_for, timeout};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OktaLogsConfig>();
+    }
+
+    #[tokio::test]
+    async fn pulls_system_log() {
+        let addr = next_addr();
+        let make_svc = make_service_fn(move |_| async move {
+            Ok::<_, Error>(service_fn(move |request: Request<Body>| async move {
+                assert_eq!(request.headers()["authorization"], "SSWS secret");
+                let body = match request.uri().query() {
+                    Some("sortOrder=ASCENDING&limit=1000&filter=eventType+eq+%22user.session.start%22") => {
+                        r#"[{"uuid":"1","displayMessage":"User login to Okta","eventType":"user.session.start","published":"2021-02-01T10:00:00.000Z"}]"#
+                    }
+                    _ => "[]",
+                };
+                Ok::<_, Error>(
+                    Response::builder()
+                        .header(
+                            "Link",
+                            format!("<http://{}/api/v1/logs?after=1>; rel=\"next\"", addr),
+                        )
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+            }))
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        let data_dir = tempdir().unwrap();
+        let config = OktaLogsConfig {
+            endpoint: format!("http://{}", addr),
+            token: "secret".to_owned(),
+            filter: Some("eventType eq \"user.session.start\"".to_owned()),
+            interval_secs: 1,
+            data_dir: Some(data_dir.path().to_path_buf()),
+            tls: None,
+        };
+
+        let (tx, rx) = Pipeline::new_test();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        let source = config
+            .build("okta", &GlobalOptions::default(), shutdown, tx)
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        delay_for(Duration::from_millis(500)).await;
+        drop(trigger);
+
+        let events = timeout(Duration::from_secs(1), rx.collect().compat())
+            .await
+            .expect("Unclosed channel")
+            .unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "User login to Okta".into());
+        assert_eq!(log["eventType"], "user.session.start".into());
+        assert_eq!(log[log_schema().source_type_key()], "okta_logs".into());
+        assert!(data_dir
+            .path()
+            .join("okta")
+            .join("checkpoint.json")
+            .exists());
+    }
+}
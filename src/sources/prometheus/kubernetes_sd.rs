@@ -0,0 +1,767 @@
+//! Discovery of the targets to scrape from the Kubernetes API.
+//!
+//! Pods or endpoints are watched the same way the `kubernetes_logs` source
+//! watches pods, and every object is turned into targets described by
+//! `__meta_kubernetes_*` labels, named like their Prometheus counterparts.
+//! The `prometheus.io/*` annotations and the relabeling rules then select the
+//! targets and decide on the tags added to the scraped metrics.
+
+use crate::kubernetes::{
+    self as k8s,
+    watch_request_builder::{NamespaceWatchRequestBuilder, WatchRequestResult, Watchable},
+};
+use evmap::ReadHandle;
+use futures::{
+    future::{select, Either},
+    pin_mut,
+};
+use k8s_openapi::{
+    api::core::v1::{Endpoints, Pod},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+    WatchOptional,
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{collections::BTreeMap, future::Future, time::Duration};
+
+/// Labels describing a target, those starting with `__` are removed after
+/// relabeling.
+pub type Labels = BTreeMap<String, String>;
+
+const ADDRESS: &str = "__address__";
+const SCHEME: &str = "__scheme__";
+const METRICS_PATH: &str = "__metrics_path__";
+
+const SCRAPE_ANNOTATION: &str = "prometheus.io/scrape";
+const PORT_ANNOTATION: &str = "prometheus.io/port";
+const PATH_ANNOTATION: &str = "prometheus.io/path";
+const SCHEME_ANNOTATION: &str = "prometheus.io/scheme";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid relabeling regex {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+    #[snafu(display("Relabeling action {:?} requires `target_label`", action))]
+    MissingTargetLabel { action: Action },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KubernetesSdConfig {
+    #[serde(default)]
+    role: Role,
+    /// The namespace to discover the targets in. All namespaces are watched
+    /// when unset.
+    namespace: Option<String>,
+    field_selector: Option<String>,
+    label_selector: Option<String>,
+    /// Honor the `prometheus.io/scrape`, `prometheus.io/port`,
+    /// `prometheus.io/path` and `prometheus.io/scheme` annotations.
+    #[serde(default = "crate::serde::default_true")]
+    annotations: bool,
+    #[serde(default)]
+    relabel: Vec<RelabelRule>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Pod,
+    Endpoints,
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::Pod
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelRule {
+    #[serde(default)]
+    source_labels: Vec<String>,
+    #[serde(default = "default_separator")]
+    separator: String,
+    #[serde(default = "default_regex")]
+    regex: String,
+    target_label: Option<String>,
+    #[serde(default = "default_replacement")]
+    replacement: String,
+    #[serde(default)]
+    action: Action,
+}
+
+fn default_separator() -> String {
+    ";".to_owned()
+}
+
+fn default_regex() -> String {
+    "(.*)".to_owned()
+}
+
+fn default_replacement() -> String {
+    "$1".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Set `target_label` to `replacement` if the regex matches.
+    Replace,
+    /// Drop the targets the regex doesn't match.
+    Keep,
+    /// Drop the targets the regex matches.
+    Drop,
+    /// Copy the labels whose names match the regex to the label named by
+    /// `replacement`.
+    Labelmap,
+    /// Remove the labels whose names match the regex.
+    Labeldrop,
+    /// Remove the labels whose names don't match the regex.
+    Labelkeep,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Action::Replace
+    }
+}
+
+/// A relabeling rule with its regex compiled.
+#[derive(Clone, Debug)]
+struct Rule {
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: Option<String>,
+    replacement: String,
+    action: Action,
+}
+
+impl RelabelRule {
+    fn build(&self) -> crate::Result<Rule> {
+        if self.action == Action::Replace && self.target_label.is_none() {
+            return Err(BuildError::MissingTargetLabel {
+                action: self.action,
+            }
+            .into());
+        }
+        // Like in Prometheus, the regex is anchored at both ends.
+        let regex = Regex::new(&format!("^(?:{})$", self.regex)).context(InvalidRegex {
+            regex: self.regex.clone(),
+        })?;
+        Ok(Rule {
+            source_labels: self.source_labels.clone(),
+            separator: self.separator.clone(),
+            regex,
+            target_label: self.target_label.clone(),
+            replacement: self.replacement.clone(),
+            action: self.action,
+        })
+    }
+}
+
+impl Rule {
+    /// Apply the rule to the labels of a target, `false` if the target is
+    /// dropped.
+    fn apply(&self, labels: &mut Labels) -> bool {
+        match self.action {
+            Action::Replace | Action::Keep | Action::Drop => {
+                let value = self
+                    .source_labels
+                    .iter()
+                    .map(|name| labels.get(name).map(String::as_str).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(&self.separator);
+                let captures = self.regex.captures(&value);
+                match self.action {
+                    Action::Keep => return captures.is_some(),
+                    Action::Drop => return captures.is_none(),
+                    _ => (),
+                }
+                if let (Some(captures), Some(target_label)) = (captures, &self.target_label) {
+                    let mut replaced = String::new();
+                    captures.expand(&self.replacement, &mut replaced);
+                    if replaced.is_empty() {
+                        labels.remove(target_label);
+                    } else {
+                        labels.insert(target_label.clone(), replaced);
+                    }
+                }
+            }
+            Action::Labelmap => {
+                let mapped = labels
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        self.regex.captures(name).map(|captures| {
+                            let mut replaced = String::new();
+                            captures.expand(&self.replacement, &mut replaced);
+                            (replaced, value.clone())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                labels.extend(mapped);
+            }
+            Action::Labeldrop => {
+                let regex = &self.regex;
+                *labels = std::mem::take(labels)
+                    .into_iter()
+                    .filter(|(name, _)| !regex.is_match(name))
+                    .collect();
+            }
+            Action::Labelkeep => {
+                let regex = &self.regex;
+                *labels = std::mem::take(labels)
+                    .into_iter()
+                    .filter(|(name, _)| regex.is_match(name))
+                    .collect();
+            }
+        }
+        true
+    }
+}
+
+/// A target to scrape, along with the tags to add to its metrics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub url: http::Uri,
+    pub tags: BTreeMap<String, String>,
+}
+
+/// The targets currently discovered.
+pub struct Discovery {
+    objects: Box<dyn Fn() -> Vec<Labels> + Send>,
+    annotations: bool,
+    rules: Vec<Rule>,
+}
+
+impl KubernetesSdConfig {
+    /// Build the discovery along with the future watching the Kubernetes API,
+    /// which completes on `shutdown`.
+    pub fn build<S>(
+        &self,
+        shutdown: S,
+    ) -> crate::Result<(Discovery, impl Future<Output = ()> + Send + 'static)>
+    where
+        S: Future + Send + Unpin + 'static,
+    {
+        let rules = self
+            .relabel
+            .iter()
+            .map(RelabelRule::build)
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let k8s_config = k8s::client::config::Config::in_cluster()?;
+        let client = k8s::client::Client::new(k8s_config)?;
+
+        let (objects, reflector): (Box<dyn Fn() -> Vec<Labels> + Send>, _) = match self.role {
+            Role::Pod => {
+                let (reader, reflector) = self.watch::<Pod, _>(client, shutdown);
+                (
+                    Box::new(move || read_targets(&reader)),
+                    Either::Left(reflector),
+                )
+            }
+            Role::Endpoints => {
+                let (reader, reflector) = self.watch::<Endpoints, _>(client, shutdown);
+                (
+                    Box::new(move || read_targets(&reader)),
+                    Either::Right(reflector),
+                )
+            }
+        };
+
+        let discovery = Discovery {
+            objects,
+            annotations: self.annotations,
+            rules,
+        };
+        Ok((discovery, reflector))
+    }
+
+    fn watch<T, S>(
+        &self,
+        client: k8s::client::Client,
+        shutdown: S,
+    ) -> (
+        ReadHandle<String, k8s::state::evmap::Value<T>>,
+        impl Future<Output = ()> + Send + 'static,
+    )
+    where
+        T: Discoverable,
+        S: Future + Send + Unpin + 'static,
+    {
+        let watcher = k8s::api_watcher::ApiWatcher::new(
+            client,
+            NamespaceWatchRequestBuilder::<T>::new(self.namespace.clone()),
+        );
+        let watcher = k8s::instrumenting_watcher::InstrumentingWatcher::new(watcher);
+        let (state_reader, state_writer) = evmap::new();
+        let state_writer =
+            k8s::state::evmap::Writer::new(state_writer, Some(Duration::from_millis(10)));
+        let state_writer = k8s::state::instrumenting::Writer::new(state_writer);
+
+        let field_selector = self.field_selector.clone();
+        let label_selector = self.label_selector.clone();
+        let reflector = async move {
+            let mut reflector = k8s::reflector::Reflector::new(
+                watcher,
+                state_writer,
+                field_selector,
+                label_selector,
+                Duration::from_secs(1),
+            );
+            let reflector_process = reflector.run();
+            pin_mut!(reflector_process);
+
+            match select(reflector_process, shutdown).await {
+                Either::Left((Ok(_infallible), _)) => unreachable!("ok value is infallible"),
+                Either::Left((Err(error), _)) => {
+                    error!(message = "Reflector process exited with an error.", %error);
+                }
+                Either::Right(_) => {
+                    info!(message = "Reflector process completed gracefully.");
+                }
+            }
+        };
+        (state_reader, reflector)
+    }
+}
+
+impl Discovery {
+    pub fn targets(&self) -> Vec<Target> {
+        (self.objects)()
+            .into_iter()
+            .filter_map(|labels| self.target(labels))
+            .collect()
+    }
+
+    fn target(&self, mut labels: Labels) -> Option<Target> {
+        if self.annotations && !apply_annotations(&mut labels) {
+            return None;
+        }
+        for rule in &self.rules {
+            if !rule.apply(&mut labels) {
+                return None;
+            }
+        }
+
+        let address = labels.get(ADDRESS)?.clone();
+        let url = format!(
+            "{}://{}{}",
+            labels.get(SCHEME).map(String::as_str).unwrap_or("http"),
+            address,
+            labels
+                .get(METRICS_PATH)
+                .map(String::as_str)
+                .unwrap_or("/metrics")
+        );
+        let url: http::Uri = match url.parse() {
+            Ok(url) => url,
+            Err(error) => {
+                warn!(message = "Invalid discovered target.", %url, %error, rate_limit_secs = 30);
+                return None;
+            }
+        };
+
+        let mut tags = labels
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("__"))
+            .collect::<BTreeMap<_, _>>();
+        tags.entry("instance".to_owned()).or_insert(address);
+        Some(Target { url, tags })
+    }
+}
+
+/// Drop the targets not annotated with `prometheus.io/scrape: "true"`, and let
+/// the other annotations override the address, path and scheme.
+fn apply_annotations(labels: &mut Labels) -> bool {
+    let annotation = |labels: &Labels, name: &str| {
+        let name = sanitize(name);
+        ["pod", "endpoints"]
+            .iter()
+            .find_map(|role| labels.get(&format!("__meta_kubernetes_{}_annotation_{}", role, name)))
+            .cloned()
+    };
+
+    if annotation(labels, SCRAPE_ANNOTATION).as_deref() != Some("true") {
+        return false;
+    }
+    if let Some(port) = annotation(labels, PORT_ANNOTATION) {
+        // Endpoints are a target per port already, only the annotated one is
+        // kept.
+        if let Some(number) = labels.get("__meta_kubernetes_endpoint_port_number") {
+            if *number != port {
+                return false;
+            }
+        } else if let Some(ip) = labels.get("__meta_kubernetes_pod_ip").cloned() {
+            labels.insert(ADDRESS.to_owned(), format!("{}:{}", ip, port));
+        }
+    }
+    if let Some(path) = annotation(labels, PATH_ANNOTATION) {
+        labels.insert(METRICS_PATH.to_owned(), path);
+    }
+    if let Some(scheme) = annotation(labels, SCHEME_ANNOTATION) {
+        labels.insert(SCHEME.to_owned(), scheme);
+    }
+    true
+}
+
+/// Replace the characters that are not allowed in label names, the same as
+/// Prometheus does for Kubernetes labels and annotations.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn read_targets<T: Discoverable>(
+    reader: &ReadHandle<String, k8s::state::evmap::Value<T>>,
+) -> Vec<Labels> {
+    let read_ref = match reader.read() {
+        Some(read_ref) => read_ref,
+        None => {
+            warn!(message = "Unable to read the state of the discovered objects.");
+            return Vec::new();
+        }
+    };
+    read_ref
+        .into_iter()
+        .filter_map(|(_, values)| values.get_one())
+        .flat_map(|object| object.targets())
+        .collect()
+}
+
+/// The common labels of every object, prefixed with the `role`.
+fn object_labels(metadata: &ObjectMeta, role: &str) -> Labels {
+    let mut labels = Labels::new();
+    if let Some(namespace) = &metadata.namespace {
+        labels.insert("__meta_kubernetes_namespace".to_owned(), namespace.clone());
+    }
+    if let Some(name) = &metadata.name {
+        labels.insert(format!("__meta_kubernetes_{}_name", role), name.clone());
+    }
+    for (kind, map) in &[
+        ("label", &metadata.labels),
+        ("annotation", &metadata.annotations),
+    ] {
+        for (name, value) in map.iter().flatten() {
+            labels.insert(
+                format!("__meta_kubernetes_{}_{}_{}", role, kind, sanitize(name)),
+                value.clone(),
+            );
+        }
+    }
+    labels
+}
+
+/// The Kubernetes objects targets are discovered from.
+pub trait Discoverable: Watchable + Sync {
+    /// The labels of the targets of this object, before relabeling.
+    fn targets(&self) -> Vec<Labels>;
+}
+
+impl Watchable for Pod {
+    fn watch_all(optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        Pod::watch_pod_for_all_namespaces(optional)
+    }
+
+    fn watch_namespaced(namespace: &str, optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        Pod::watch_namespaced_pod(namespace, optional)
+    }
+}
+
+impl Discoverable for Pod {
+    fn targets(&self) -> Vec<Labels> {
+        let status = match &self.status {
+            Some(status) => status,
+            None => return Vec::new(),
+        };
+        let ip = match &status.pod_ip {
+            Some(ip) => ip,
+            None => return Vec::new(),
+        };
+        // Completed pods are not scraped.
+        if matches!(status.phase.as_deref(), Some("Succeeded") | Some("Failed")) {
+            return Vec::new();
+        }
+
+        let mut labels = object_labels(&self.metadata, "pod");
+        labels.insert("__meta_kubernetes_pod_ip".to_owned(), ip.clone());
+        if let Some(phase) = &status.phase {
+            labels.insert("__meta_kubernetes_pod_phase".to_owned(), phase.clone());
+        }
+
+        // A pod is a single target, on its first declared container port
+        // unless the annotations say otherwise.
+        let mut address = ip.clone();
+        if let Some(spec) = &self.spec {
+            if let Some(node_name) = &spec.node_name {
+                labels.insert(
+                    "__meta_kubernetes_pod_node_name".to_owned(),
+                    node_name.clone(),
+                );
+            }
+            let port = spec
+                .containers
+                .iter()
+                .flat_map(|container| container.ports.iter().flatten())
+                .next();
+            if let Some(port) = port {
+                address = format!("{}:{}", ip, port.container_port);
+                labels.insert(
+                    "__meta_kubernetes_pod_container_port_number".to_owned(),
+                    port.container_port.to_string(),
+                );
+                if let Some(name) = &port.name {
+                    labels.insert(
+                        "__meta_kubernetes_pod_container_port_name".to_owned(),
+                        name.clone(),
+                    );
+                }
+            }
+        }
+        labels.insert(ADDRESS.to_owned(), address);
+        vec![labels]
+    }
+}
+
+impl Watchable for Endpoints {
+    fn watch_all(optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        Endpoints::watch_endpoints_for_all_namespaces(optional)
+    }
+
+    fn watch_namespaced(namespace: &str, optional: WatchOptional<'_>) -> WatchRequestResult<Self> {
+        Endpoints::watch_namespaced_endpoints(namespace, optional)
+    }
+}
+
+impl Discoverable for Endpoints {
+    fn targets(&self) -> Vec<Labels> {
+        let common = object_labels(&self.metadata, "endpoints");
+        let mut targets = Vec::new();
+        for subset in self.subsets.iter().flatten() {
+            for address in subset.addresses.iter().flatten() {
+                for port in subset.ports.iter().flatten() {
+                    let mut labels = common.clone();
+                    labels.insert(ADDRESS.to_owned(), format!("{}:{}", address.ip, port.port));
+                    labels.insert(
+                        "__meta_kubernetes_endpoint_port_number".to_owned(),
+                        port.port.to_string(),
+                    );
+                    if let Some(name) = &port.name {
+                        labels.insert(
+                            "__meta_kubernetes_endpoint_port_name".to_owned(),
+                            name.clone(),
+                        );
+                    }
+                    if let Some(node_name) = &address.node_name {
+                        labels.insert(
+                            "__meta_kubernetes_endpoint_node_name".to_owned(),
+                            node_name.clone(),
+                        );
+                    }
+                    if let Some(target) = &address.target_ref {
+                        if let Some(kind) = &target.kind {
+                            labels.insert(
+                                "__meta_kubernetes_endpoint_address_target_kind".to_owned(),
+                                kind.clone(),
+                            );
+                        }
+                        if let Some(name) = &target.name {
+                            labels.insert(
+                                "__meta_kubernetes_endpoint_address_target_name".to_owned(),
+                                name.clone(),
+                            );
+                        }
+                    }
+                    targets.push(labels);
+                }
+            }
+        }
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{
+        Container, ContainerPort, EndpointAddress, EndpointPort, EndpointSubset, PodSpec, PodStatus,
+    };
+
+    fn pod(annotations: &[(&str, &str)]) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some("api-0".to_owned()),
+                namespace: Some("prod".to_owned()),
+                labels: Some(
+                    vec![("app".to_owned(), "api".to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+                annotations: Some(
+                    annotations
+                        .iter()
+                        .map(|(name, value)| (name.to_string(), value.to_string()))
+                        .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    ports: Some(vec![ContainerPort {
+                        container_port: 8080,
+                        ..ContainerPort::default()
+                    }]),
+                    ..Container::default()
+                }],
+                ..PodSpec::default()
+            }),
+            status: Some(PodStatus {
+                pod_ip: Some("10.0.0.1".to_owned()),
+                phase: Some("Running".to_owned()),
+                ..PodStatus::default()
+            }),
+        }
+    }
+
+    fn discovery(annotations: bool, rules: &str) -> Discovery {
+        let rules: Vec<RelabelRule> = toml::from_str::<toml::Value>(rules)
+            .unwrap()
+            .get("relabel")
+            .cloned()
+            .map(|rules| rules.try_into().unwrap())
+            .unwrap_or_default();
+        Discovery {
+            objects: Box::new(Vec::<Labels>::new),
+            annotations,
+            rules: rules.iter().map(|rule| rule.build().unwrap()).collect(),
+        }
+    }
+
+    fn targets(discovery: &Discovery, object: &impl Discoverable) -> Vec<Target> {
+        object
+            .targets()
+            .into_iter()
+            .filter_map(|labels| discovery.target(labels))
+            .collect()
+    }
+
+    #[test]
+    fn honors_scrape_annotations() {
+        let discovery = discovery(true, "");
+
+        assert!(targets(&discovery, &pod(&[])).is_empty());
+        assert_eq!(
+            targets(
+                &discovery,
+                &pod(&[
+                    ("prometheus.io/scrape", "true"),
+                    ("prometheus.io/port", "9102"),
+                    ("prometheus.io/path", "/stats"),
+                ])
+            ),
+            vec![Target {
+                url: "http://10.0.0.1:9102/stats".parse().unwrap(),
+                tags: vec![("instance".to_owned(), "10.0.0.1:9102".to_owned())]
+                    .into_iter()
+                    .collect(),
+            }]
+        );
+    }
+
+    #[test]
+    fn relabels_targets() {
+        let discovery = discovery(
+            false,
+            r#"
+            [[relabel]]
+            source_labels = ["__meta_kubernetes_namespace"]
+            regex = "kube-.*"
+            action = "drop"
+
+            [[relabel]]
+            regex = "__meta_kubernetes_pod_label_(.+)"
+            action = "labelmap"
+
+            [[relabel]]
+            source_labels = ["__meta_kubernetes_namespace", "__meta_kubernetes_pod_name"]
+            separator = "/"
+            target_label = "pod"
+            "#,
+        );
+
+        let target = targets(&discovery, &pod(&[])).pop().unwrap();
+        assert_eq!(target.url, "http://10.0.0.1:8080/metrics");
+        assert_eq!(
+            target.tags,
+            vec![
+                ("app".to_owned(), "api".to_owned()),
+                ("instance".to_owned(), "10.0.0.1:8080".to_owned()),
+                ("pod".to_owned(), "prod/api-0".to_owned()),
+            ]
+            .into_iter()
+            .collect()
+        );
+
+        let mut system_pod = pod(&[]);
+        system_pod.metadata.namespace = Some("kube-system".to_owned());
+        assert!(targets(&discovery, &system_pod).is_empty());
+    }
+
+    #[test]
+    fn discovers_endpoints() {
+        let endpoints = Endpoints {
+            metadata: ObjectMeta {
+                name: Some("api".to_owned()),
+                namespace: Some("prod".to_owned()),
+                annotations: Some(
+                    vec![
+                        ("prometheus.io/scrape".to_owned(), "true".to_owned()),
+                        ("prometheus.io/port".to_owned(), "9102".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            subsets: Some(vec![EndpointSubset {
+                addresses: Some(vec![EndpointAddress {
+                    ip: "10.0.0.2".to_owned(),
+                    ..EndpointAddress::default()
+                }]),
+                ports: Some(vec![
+                    EndpointPort {
+                        port: 8080,
+                        ..EndpointPort::default()
+                    },
+                    EndpointPort {
+                        port: 9102,
+                        ..EndpointPort::default()
+                    },
+                ]),
+                ..EndpointSubset::default()
+            }]),
+        };
+
+        let targets = targets(&discovery(true, ""), &endpoints);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].url, "http://10.0.0.2:9102/metrics");
+    }
+
+    #[test]
+    fn rejects_replace_without_target() {
+        let rule = RelabelRule {
+            source_labels: vec![ADDRESS.to_owned()],
+            separator: default_separator(),
+            regex: default_regex(),
+            target_label: None,
+            replacement: default_replacement(),
+            action: Action::Replace,
+        };
+        assert!(rule.build().is_err());
+    }
+}
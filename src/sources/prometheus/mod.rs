@@ -1,3 +1,4 @@
+mod kubernetes_sd;
 pub(crate) mod parser;
 mod remote_write;
 mod scrape;
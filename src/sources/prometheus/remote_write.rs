@@ -30,11 +30,18 @@ const SOURCE_NAME: &str = "prometheus_remote_write";
 struct PrometheusRemoteWriteConfig {
     address: SocketAddr,
 
+    #[serde(default = "default_path")]
+    path: String,
+
     tls: Option<TlsConfig>,
 
     auth: Option<HttpSourceAuthConfig>,
 }
 
+fn default_path() -> String {
+    "/".to_owned()
+}
+
 inventory::submit! {
     SourceDescription::new::<PrometheusRemoteWriteConfig>(SOURCE_NAME)
 }
@@ -43,6 +50,7 @@ impl GenerateConfig for PrometheusRemoteWriteConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             address: "127.0.0.1:9090".parse().unwrap(),
+            path: default_path(),
             tls: None,
             auth: None,
         })
@@ -63,7 +71,15 @@ impl SourceConfig for PrometheusRemoteWriteConfig {
         let source = RemoteWriteSource {
             decompressor: snap::raw::Decoder::new(),
         };
-        source.run(self.address, "", &self.tls, &self.auth, out, shutdown)
+        source.run(
+            self.address,
+            &self.path,
+            &self.tls,
+            &self.auth,
+            &None,
+            out,
+            shutdown,
+        )
     }
 
     fn output_type(&self) -> crate::config::DataType {
@@ -195,21 +211,27 @@ mod test {
 
     #[tokio::test]
     async fn receives_metrics_over_http() {
-        receives_metrics(None).await;
+        receives_metrics(None, "/").await;
     }
 
     #[tokio::test]
     async fn receives_metrics_over_https() {
-        receives_metrics(Some(TlsConfig::test_config())).await;
+        receives_metrics(Some(TlsConfig::test_config()), "/").await;
+    }
+
+    #[tokio::test]
+    async fn receives_metrics_on_path() {
+        receives_metrics(None, "/api/v1/write").await;
     }
 
-    async fn receives_metrics(tls: Option<TlsConfig>) {
+    async fn receives_metrics(tls: Option<TlsConfig>, path: &str) {
         let address = test_util::next_addr();
         let (tx, rx) = Pipeline::new_test();
 
         let proto = if tls.is_none() { "http" } else { "https" };
         let source = PrometheusRemoteWriteConfig {
             address,
+            path: path.to_owned(),
             auth: None,
             tls: tls.clone(),
         };
@@ -225,7 +247,7 @@ mod test {
         tokio::spawn(source);
 
         let sink = RemoteWriteConfig {
-            endpoint: format!("{}://localhost:{}/", proto, address.port()),
+            endpoint: format!("{}://localhost:{}{}", proto, address.port(), path),
             tls: tls.map(|tls| tls.options),
             ..Default::default()
         };
@@ -1,4 +1,7 @@
-use super::parser;
+use super::{
+    kubernetes_sd::{Discovery, KubernetesSdConfig, Target},
+    parser,
+};
 use crate::{
     config::{self, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
     http::Auth,
@@ -12,12 +15,13 @@ use crate::{
     tls::{TlsOptions, TlsSettings},
     Event, Pipeline,
 };
-use futures::{compat::Sink01CompatExt, stream, FutureExt, StreamExt, TryFutureExt};
+use futures::{compat::Sink01CompatExt, future, stream, FutureExt, StreamExt, TryFutureExt};
 use futures01::Sink;
 use hyper::{Body, Request};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::{
+    collections::BTreeMap,
     future::ready,
     time::{Duration, Instant},
 };
@@ -26,16 +30,20 @@ use std::{
 enum ConfigError {
     #[snafu(display("Cannot set both `endpoints` and `hosts`"))]
     BothEndpointsAndHosts,
+    #[snafu(display("One of `endpoints` or `kubernetes` must be set"))]
+    NoEndpoints,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct PrometheusScrapeConfig {
     // Deprecated name
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
 
+    kubernetes: Option<KubernetesSdConfig>,
+
     tls: Option<TlsOptions>,
 
     auth: Option<Auth>,
@@ -58,6 +66,7 @@ impl GenerateConfig for PrometheusScrapeConfig {
         toml::Value::try_from(Self {
             endpoints: vec!["http://localhost:9090/metrics".to_string()],
             scrape_interval_secs: default_scrape_interval_secs(),
+            kubernetes: None,
             tls: None,
             auth: None,
         })
@@ -81,14 +90,32 @@ impl SourceConfig for PrometheusScrapeConfig {
             .map(|s| s.parse::<http::Uri>().context(sources::UriParseError))
             .collect::<Result<Vec<http::Uri>, sources::BuildError>>()?;
         let tls = TlsSettings::from_options(&self.tls)?;
-        Ok(prometheus(
-            urls,
-            tls,
-            self.auth.clone(),
-            self.scrape_interval_secs,
-            shutdown,
-            out,
-        ))
+
+        match &self.kubernetes {
+            None if urls.is_empty() => Err(ConfigError::NoEndpoints.into()),
+            None => Ok(prometheus(
+                Targets::Static(urls),
+                tls,
+                self.auth.clone(),
+                self.scrape_interval_secs,
+                shutdown,
+                out,
+            )),
+            Some(kubernetes) => {
+                let (discovery, reflector) = kubernetes.build(shutdown.clone())?;
+                let scrape = prometheus(
+                    Targets::Kubernetes { urls, discovery },
+                    tls,
+                    self.auth.clone(),
+                    self.scrape_interval_secs,
+                    shutdown,
+                    out,
+                );
+                Ok(Box::pin(
+                    future::join(scrape, reflector).map(|(result, ())| result),
+                ))
+            }
+        }
     }
 
     fn output_type(&self) -> config::DataType {
@@ -106,11 +133,13 @@ impl SourceConfig for PrometheusScrapeConfig {
 struct PrometheusCompatConfig {
     // Clone of PrometheusScrapeConfig to work around serde bug
     // https://github.com/serde-rs/serde/issues/1504
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
 
+    kubernetes: Option<KubernetesSdConfig>,
+
     tls: Option<TlsOptions>,
 
     auth: Option<Auth>,
@@ -131,6 +160,7 @@ impl SourceConfig for PrometheusCompatConfig {
         PrometheusScrapeConfig {
             endpoints: self.endpoints.clone(),
             scrape_interval_secs: self.scrape_interval_secs,
+            kubernetes: self.kubernetes.clone(),
             tls: self.tls.clone(),
             auth: self.auth.clone(),
         }
@@ -147,8 +177,38 @@ impl SourceConfig for PrometheusCompatConfig {
     }
 }
 
+/// The targets to scrape on every interval.
+enum Targets {
+    Static(Vec<http::Uri>),
+    /// The static endpoints, if any, are scraped alongside the discovered ones.
+    Kubernetes {
+        urls: Vec<http::Uri>,
+        discovery: Discovery,
+    },
+}
+
+impl Targets {
+    fn get(&self) -> Vec<Target> {
+        let (urls, discovery) = match self {
+            Targets::Static(urls) => (urls, None),
+            Targets::Kubernetes { urls, discovery } => (urls, Some(discovery)),
+        };
+        let mut targets = urls
+            .iter()
+            .map(|url| Target {
+                url: url.clone(),
+                tags: BTreeMap::new(),
+            })
+            .collect::<Vec<_>>();
+        if let Some(discovery) = discovery {
+            targets.extend(discovery.targets());
+        }
+        targets
+    }
+}
+
 fn prometheus(
-    urls: Vec<http::Uri>,
+    targets: Targets,
     tls: TlsSettings,
     auth: Option<Auth>,
     interval: u64,
@@ -160,9 +220,9 @@ fn prometheus(
         .sink_compat();
     Box::pin(tokio::time::interval(Duration::from_secs(interval))
         .take_until(shutdown)
-        .map(move |_| stream::iter(urls.clone()))
+        .map(move |_| stream::iter(targets.get()))
         .flatten()
-        .map(move |url| {
+        .map(move |Target { url, tags }| {
             let client = HttpClient::new(tls.clone()).expect("Building HTTP client failed");
 
             let mut request = Request::get(&url)
@@ -194,11 +254,24 @@ fn prometheus(
                             let body = String::from_utf8_lossy(&body);
 
                             match parser::parse(&body) {
-                                Ok(metrics) => {
+                                Ok(mut metrics) => {
                                     emit!(PrometheusEventReceived {
                                         byte_size,
                                         count: metrics.len(),
                                     });
+                                    // The tags of discovered targets don't
+                                    // override the scraped ones.
+                                    if !tags.is_empty() {
+                                        for metric in &mut metrics {
+                                            let metric_tags =
+                                                metric.tags.get_or_insert_with(BTreeMap::new);
+                                            for (name, value) in &tags {
+                                                metric_tags
+                                                    .entry(name.clone())
+                                                    .or_insert_with(|| value.clone());
+                                            }
+                                        }
+                                    }
                                     Some(stream::iter(metrics).map(Event::Metric).map(Ok))
                                 }
                                 Err(error) => {
@@ -322,6 +395,7 @@ mod test {
             PrometheusScrapeConfig {
                 endpoints: vec![format!("http://{}", in_addr)],
                 scrape_interval_secs: 1,
+                kubernetes: None,
                 tls: None,
                 auth: None,
             },
@@ -335,6 +409,7 @@ mod test {
                 buckets: vec![1.0, 2.0, 4.0],
                 quantiles: vec![],
                 flush_period_secs: 1,
+                ..Default::default()
             },
         );
 
@@ -401,6 +476,7 @@ mod integration_tests {
         let config = PrometheusScrapeConfig {
             endpoints: vec!["http://localhost:9090/metrics".into()],
             scrape_interval_secs: 1,
+            kubernetes: None,
             auth: None,
             tls: None,
         };
@@ -97,6 +97,7 @@ impl SourceConfig for SocketConfig {
                     config.keepalive,
                     config.shutdown_timeout_secs,
                     tls,
+                    config.proxy_protocol,
                     shutdown,
                     out,
                 )
@@ -121,6 +122,7 @@ impl SourceConfig for SocketConfig {
                 Ok(unix::unix_datagram(
                     config.path,
                     config.max_length,
+                    config.receive_buffer_bytes,
                     host_key,
                     shutdown,
                     out,
@@ -236,6 +238,40 @@ mod test {
         assert_eq!(event.as_log()[log_schema().host_key()], "127.0.0.1".into());
     }
 
+    #[tokio::test]
+    async fn tcp_it_reads_proxy_protocol_header() {
+        let (tx, rx) = Pipeline::new_test();
+        let addr = next_addr();
+
+        let server = SocketConfig::from(TcpConfig {
+            proxy_protocol: true,
+            ..TcpConfig::new(addr.into())
+        })
+        .build(
+            "default",
+            &GlobalOptions::default(),
+            ShutdownSignal::noop(),
+            tx,
+        )
+        .await
+        .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        let lines = vec![
+            "PROXY TCP4 192.168.0.1 10.0.0.1 56324 514\r".to_owned(),
+            "test".to_owned(),
+        ];
+        send_lines(addr, lines.into_iter()).await.unwrap();
+
+        let event = rx.compat().next().await.unwrap().unwrap();
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "test".into());
+        assert_eq!(log[log_schema().host_key()], "192.168.0.1".into());
+        assert_eq!(log["client_metadata.source_port"], 56324.into());
+        assert_eq!(log["client_metadata.destination_ip"], "10.0.0.1".into());
+    }
+
     #[tokio::test]
     async fn tcp_it_includes_source_type() {
         let (tx, rx) = Pipeline::new_test();
@@ -855,6 +891,43 @@ mod test {
         assert!(matches!(config.mode,Mode::UnixDatagram { .. }));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_datagram_receive_buffer() {
+        let (tx, rx) = Pipeline::new_test();
+        let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
+
+        let mut config = UnixConfig::new(in_path.clone());
+        config.receive_buffer_bytes = Some(1024 * 1024);
+        let server = SocketConfig {
+            mode: Mode::UnixDatagram(config),
+        }
+        .build(
+            "default",
+            &GlobalOptions::default(),
+            ShutdownSignal::noop(),
+            tx,
+        )
+        .await
+        .unwrap();
+        tokio::spawn(server);
+
+        while std::os::unix::net::UnixDatagram::unbound()
+            .unwrap()
+            .connect(&in_path)
+            .is_err()
+        {
+            yield_now().await;
+        }
+
+        send_lines_unix_datagram(in_path, &["test"]).await;
+        let events = collect_n(rx, 1).await.unwrap();
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "test".into()
+        );
+    }
+
     ////////////// UNIX STREAM TESTS //////////////
     #[cfg(unix)]
     async fn send_lines_unix_stream(path: PathBuf, lines: &[&str]) {
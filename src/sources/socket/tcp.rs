@@ -20,6 +20,8 @@ pub struct TcpConfig {
     pub shutdown_timeout_secs: u64,
     pub host_key: Option<String>,
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub proxy_protocol: bool,
 }
 
 fn default_max_length() -> usize {
@@ -39,6 +41,7 @@ impl TcpConfig {
             host_key: None,
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             tls: Default::default(),
+            proxy_protocol: false,
         }
     }
 }
@@ -20,6 +20,7 @@ pub struct UnixConfig {
     #[serde(default = "default_max_length")]
     pub max_length: usize,
     pub host_key: Option<String>,
+    pub receive_buffer_bytes: Option<usize>,
 }
 
 fn default_max_length() -> usize {
@@ -32,6 +33,7 @@ impl UnixConfig {
             path,
             max_length: default_max_length(),
             host_key: None,
+            receive_buffer_bytes: None,
         }
     }
 }
@@ -60,6 +62,7 @@ fn build_event(host_key: &str, received_from: Option<Bytes>, line: &str) -> Opti
 pub(super) fn unix_datagram(
     path: PathBuf,
     max_length: usize,
+    receive_buffer_bytes: Option<usize>,
     host_key: String,
     shutdown: ShutdownSignal,
     out: Pipeline,
@@ -67,6 +70,7 @@ pub(super) fn unix_datagram(
     build_unix_datagram_source(
         path,
         max_length,
+        receive_buffer_bytes,
         host_key,
         LinesCodec::new_with_max_length(max_length),
         shutdown,
@@ -1,6 +1,6 @@
 use crate::{
     config::{log_schema, DataType, GlobalOptions, Resource, SourceConfig, SourceDescription},
-    event::{Event, LogEvent, Value},
+    event::{in_flight::InFlightToken, Event, LogEvent, Value},
     internal_events::{
         SplunkHECEventReceived, SplunkHECRequestBodyInvalid, SplunkHECRequestError,
         SplunkHECRequestReceived,
@@ -19,8 +19,11 @@ use serde::{de, Deserialize, Serialize};
 use serde_json::{de::IoRead, json, Deserializer, Value as JsonValue};
 use snafu::Snafu;
 use std::{
+    collections::{BTreeMap, HashMap},
     io::Read,
     net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use warp::{filters::BoxedFilter, path, reject::Rejection, reply::Response, Filter, Reply};
@@ -41,6 +44,31 @@ pub struct SplunkConfig {
     /// Splunk HEC token
     token: Option<String>,
     tls: Option<TlsConfig>,
+    /// Indexer acknowledgements
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct AcknowledgementsConfig {
+    enabled: bool,
+    /// The oldest acknowledgements of a channel are forgotten past this limit
+    max_pending_acks_per_channel: usize,
+    /// The least recently used channel is forgotten past this limit
+    max_channels: usize,
+    /// Channels and acknowledgements unused for this long are forgotten
+    max_idle_secs: u64,
+}
+
+impl Default for AcknowledgementsConfig {
+    fn default() -> Self {
+        AcknowledgementsConfig {
+            enabled: false,
+            max_pending_acks_per_channel: 1000,
+            max_channels: 1000,
+            max_idle_secs: 300,
+        }
+    }
 }
 
 inventory::submit! {
@@ -65,6 +93,7 @@ impl Default for SplunkConfig {
             address: default_socket_address(),
             token: None,
             tls: None,
+            acknowledgements: AcknowledgementsConfig::default(),
         }
     }
 }
@@ -88,6 +117,7 @@ impl SourceConfig for SplunkConfig {
         let event_service = source.event_service(out.clone());
         let raw_service = source.raw_service(out.clone());
         let health_service = source.health_service(out);
+        let ack_service = source.ack_service();
         let options = SplunkSource::options();
 
         let services = path!("services" / "collector" / ..)
@@ -106,6 +136,8 @@ impl SourceConfig for SplunkConfig {
                     .unify()
                     .or(health_service)
                     .unify()
+                    .or(ack_service)
+                    .unify()
                     .or(options)
                     .unify(),
             )
@@ -143,6 +175,7 @@ impl SourceConfig for SplunkConfig {
 /// Shared data for responding to requests.
 struct SplunkSource {
     credentials: Option<Bytes>,
+    acknowledgements: Option<Arc<Acknowledgements>>,
 }
 
 impl SplunkSource {
@@ -152,10 +185,16 @@ impl SplunkSource {
                 .token
                 .as_ref()
                 .map(|token| format!("Splunk {}", token).into()),
+            acknowledgements: if config.acknowledgements.enabled {
+                Some(Arc::new(Acknowledgements::new(&config.acknowledgements)))
+            } else {
+                None
+            },
         }
     }
 
     fn event_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        let acknowledgements = self.acknowledgements.clone();
         warp::post()
             .and(path!("event").or(path!("event" / "1.0")))
             .and(self.authorization())
@@ -170,7 +209,11 @@ impl SplunkSource {
                       host: Option<String>,
                       gzip: bool,
                       body: Bytes| {
-                    process_service_request(out.clone(), channel, host, gzip, body)
+                    let out = out.clone();
+                    let request_channel = channel.clone();
+                    acknowledge(acknowledgements.clone(), channel, move |token| {
+                        process_service_request(out, request_channel, host, gzip, body, token)
+                    })
                 },
             )
             .map(finish_ok)
@@ -178,40 +221,55 @@ impl SplunkSource {
     }
 
     fn raw_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        let acknowledgements = self.acknowledgements.clone();
         warp::post()
             .and(path!("raw" / "1.0").or(path!("raw")))
             .and(self.authorization())
-            .and(
-                warp::header::optional::<String>("x-splunk-request-channel").and_then(
-                    |channel: Option<String>| async {
-                        if let Some(channel) = channel {
-                            Ok(channel)
-                        } else {
-                            Err(Rejection::from(ApiError::MissingChannel))
-                        }
-                    },
-                ),
-            )
+            .and(Self::required_channel())
             .and(warp::header::optional::<String>("host"))
             .and(self.gzip())
             .and(warp::body::bytes())
             .and_then(
                 move |_, _, channel: String, host: Option<String>, gzip: bool, body: Bytes| {
                     let out = out.clone();
-                    async move {
+                    let request_channel = channel.clone();
+                    acknowledge(acknowledgements.clone(), Some(channel), move |token| {
                         // Construct event parser
-                        futures01::stream::once(raw_event(body, gzip, channel, host))
-                            .forward(out.clone().sink_map_err(|_| ApiError::ServerShutdown))
+                        let event = raw_event(body, gzip, request_channel, host)
+                            .map(|event| add_in_flight(event, token.as_ref()));
+                        futures01::stream::once(event)
+                            .forward(out.sink_map_err(|_| ApiError::ServerShutdown))
                             .map(|_| ())
                             .compat()
-                            .await
-                    }
+                    })
                 },
             )
             .map(finish_ok)
             .boxed()
     }
 
+    /// Responds to queries of the acknowledgement status of requests.
+    fn ack_service(&self) -> BoxedFilter<(Response,)> {
+        let acknowledgements = self.acknowledgements.clone();
+        warp::post()
+            .and(path!("ack"))
+            .and(self.authorization())
+            .and(Self::required_channel())
+            .and(warp::body::bytes())
+            .and_then(move |_, channel: String, body: Bytes| {
+                let acknowledgements = acknowledgements.clone();
+                async move {
+                    let acknowledgements =
+                        acknowledgements.ok_or_else(|| Rejection::from(ApiError::AckIsDisabled))?;
+                    let query = serde_json::from_slice::<AckQuery>(&body)
+                        .map_err(|_| Rejection::from(ApiError::InvalidDataFormat { event: 0 }))?;
+                    let acks = acknowledgements.query(&channel, &query.acks, Instant::now());
+                    Ok::<_, Rejection>(response_json(StatusCode::OK, json!({ "acks": acks })))
+                }
+            })
+            .boxed()
+    }
+
     fn health_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
         let credentials = self.credentials.clone();
         let authorize =
@@ -257,7 +315,8 @@ impl SplunkSource {
                 path!("event")
                     .or(path!("event" / "1.0"))
                     .or(path!("raw" / "1.0"))
-                    .or(path!("raw")),
+                    .or(path!("raw"))
+                    .or(path!("ack")),
             )
             .map(|_| warp::reply::with_header(warp::reply(), "Allow", "POST").into_response());
 
@@ -288,6 +347,19 @@ impl SplunkSource {
             .boxed()
     }
 
+    /// Channel of the request, which is required by the raw and ack endpoints
+    fn required_channel() -> BoxedFilter<(String,)> {
+        warp::header::optional::<String>("x-splunk-request-channel")
+            .and_then(|channel: Option<String>| async {
+                if let Some(channel) = channel {
+                    Ok(channel)
+                } else {
+                    Err(Rejection::from(ApiError::MissingChannel))
+                }
+            })
+            .boxed()
+    }
+
     /// Is body encoded with gzip
     fn gzip(&self) -> BoxedFilter<(bool,)> {
         warp::header::optional::<String>("Content-Encoding")
@@ -308,6 +380,7 @@ async fn process_service_request(
     host: Option<String>,
     gzip: bool,
     body: Bytes,
+    token: Option<InFlightToken>,
 ) -> Result<(), Rejection> {
     use futures::compat::Sink01CompatExt;
     use futures::compat::Stream01CompatExt;
@@ -323,7 +396,9 @@ async fn process_service_request(
         Box::new(body.reader())
     };
 
-    let stream = EventStream::new(reader, channel, host).compat();
+    let stream = EventStream::new(reader, channel, host)
+        .map(move |event| add_in_flight(event, token.as_ref()))
+        .compat();
 
     let res = stream.forward(&mut out).await;
 
@@ -334,6 +409,173 @@ async fn process_service_request(
     res.map(|_| ())
 }
 
+/// Ties an event to the acknowledgement of its request, if any.
+fn add_in_flight(mut event: Event, token: Option<&InFlightToken>) -> Event {
+    if let Some(token) = token {
+        event.as_mut_log().add_in_flight(token.clone());
+    }
+    event
+}
+
+/// Runs a request, registering an acknowledgement for it if they are
+/// enabled. The acknowledgement is only confirmed once all the events of the
+/// request have been processed by the sinks, that is acked by all of them,
+/// which clients querying it rely on to resend the requests that failed.
+async fn acknowledge<F, R>(
+    acknowledgements: Option<Arc<Acknowledgements>>,
+    channel: Option<String>,
+    request: F,
+) -> Result<Option<u64>, Rejection>
+where
+    F: FnOnce(Option<InFlightToken>) -> R,
+    R: std::future::Future<Output = Result<(), Rejection>>,
+{
+    match (acknowledgements, channel) {
+        (None, _) => request(None).await.map(|_| None),
+        (Some(_), None) => Err(ApiError::MissingChannel.into()),
+        (Some(acknowledgements), Some(channel)) => {
+            let id = acknowledgements.register(&channel, Instant::now());
+            let (token, released) = InFlightToken::notify();
+            match request(Some(token)).await {
+                Ok(()) => {
+                    tokio::spawn(async move {
+                        if released.await.is_ok() {
+                            acknowledgements.confirm(&channel, id);
+                        }
+                    });
+                    Ok(Some(id))
+                }
+                Err(error) => {
+                    // The client never learns about the acknowledgement.
+                    acknowledgements.remove(&channel, id);
+                    Err(error)
+                }
+            }
+        }
+    }
+}
+
+/// Acknowledgement status of the requests of each channel
+struct Acknowledgements {
+    max_pending: usize,
+    max_channels: usize,
+    max_idle: Duration,
+    channels: Mutex<HashMap<String, Channel>>,
+}
+
+struct Channel {
+    next_id: u64,
+    last_used: Instant,
+    /// Whether each request not queried as acknowledged yet is acknowledged,
+    /// and when it was registered
+    pending: BTreeMap<u64, (bool, Instant)>,
+}
+
+impl Acknowledgements {
+    fn new(config: &AcknowledgementsConfig) -> Self {
+        Acknowledgements {
+            max_pending: config.max_pending_acks_per_channel,
+            max_channels: config.max_channels,
+            max_idle: Duration::from_secs(config.max_idle_secs),
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn register(&self, channel: &str, now: Instant) -> u64 {
+        let mut channels = self.channels.lock().unwrap();
+        self.expire(&mut channels, now);
+
+        if !channels.contains_key(channel) && channels.len() >= self.max_channels {
+            let least_recently_used = channels
+                .iter()
+                .min_by_key(|(_, channel)| channel.last_used)
+                .map(|(name, _)| name.clone());
+            if let Some(name) = least_recently_used {
+                channels.remove(&name);
+            }
+        }
+
+        let channel = channels
+            .entry(channel.to_owned())
+            .or_insert_with(|| Channel {
+                next_id: 0,
+                last_used: now,
+                pending: BTreeMap::new(),
+            });
+        channel.last_used = now;
+
+        let id = channel.next_id;
+        channel.next_id += 1;
+        channel.pending.insert(id, (false, now));
+        while channel.pending.len() > self.max_pending {
+            let oldest = *channel.pending.keys().next().unwrap();
+            channel.pending.remove(&oldest);
+        }
+
+        id
+    }
+
+    fn confirm(&self, channel: &str, id: u64) {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some((acked, _)) = channels
+            .get_mut(channel)
+            .and_then(|channel| channel.pending.get_mut(&id))
+        {
+            *acked = true;
+        }
+    }
+
+    fn remove(&self, channel: &str, id: u64) {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(channel) = channels.get_mut(channel) {
+            channel.pending.remove(&id);
+        }
+    }
+
+    /// Acknowledgements are forgotten once they have been reported as
+    /// confirmed, as Splunk does.
+    fn query(&self, channel: &str, ids: &[u64], now: Instant) -> BTreeMap<u64, bool> {
+        let mut channels = self.channels.lock().unwrap();
+        self.expire(&mut channels, now);
+
+        let channel = channels.get_mut(channel);
+        let mut acks = BTreeMap::new();
+        if let Some(channel) = channel {
+            channel.last_used = now;
+            for id in ids {
+                let acked = matches!(channel.pending.get(id), Some((true, _)));
+                if acked {
+                    channel.pending.remove(id);
+                }
+                acks.insert(*id, acked);
+            }
+        } else {
+            acks.extend(ids.iter().map(|id| (*id, false)));
+        }
+        acks
+    }
+
+    /// Forgets the channels and the acknowledgements unused for too long.
+    fn expire(&self, channels: &mut HashMap<String, Channel>, now: Instant) {
+        let max_idle = self.max_idle;
+        channels.retain(|_, channel| now.saturating_duration_since(channel.last_used) < max_idle);
+        for channel in channels.values_mut() {
+            // The acknowledgements are registered in the order of their IDs.
+            while let Some((&id, &(_, registered))) = channel.pending.iter().next() {
+                if now.saturating_duration_since(registered) < max_idle {
+                    break;
+                }
+                channel.pending.remove(&id);
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AckQuery {
+    acks: Vec<u64>,
+}
+
 /// Constructs one ore more events from json-s coming from reader.
 /// If errors, it's done with input.
 struct EventStream<R: Read> {
@@ -647,6 +889,7 @@ pub(crate) enum ApiError {
     EmptyEventField { event: usize },
     MissingEventField { event: usize },
     BadRequest,
+    AckIsDisabled,
 }
 
 impl From<ApiError> for Rejection {
@@ -682,11 +925,19 @@ mod splunk_response {
             json_to_bytes(json!({"text":"unsupported content encoding"}));
         pub static ref NO_CHANNEL: Bytes =
             json_to_bytes(json!({"text":"Data channel is missing","code":10}));
+        pub static ref ACK_IS_DISABLED: Bytes =
+            json_to_bytes(json!({"text":"ACK is disabled","code":14}));
     }
 }
 
-fn finish_ok(_: ()) -> Response {
-    response_json(StatusCode::OK, splunk_response::SUCCESS.as_ref())
+fn finish_ok(ack_id: Option<u64>) -> Response {
+    match ack_id {
+        Some(ack_id) => response_json(
+            StatusCode::OK,
+            json!({"text":"Success","code":0,"ackId":ack_id}),
+        ),
+        None => response_json(StatusCode::OK, splunk_response::SUCCESS.as_ref()),
+    }
 }
 
 async fn finish_err(rejection: Rejection) -> Result<(Response,), Rejection> {
@@ -724,6 +975,10 @@ async fn finish_err(rejection: Rejection) -> Result<(Response,), Rejection> {
                 event_error("Event field is required", 12, event)
             }
             ApiError::BadRequest => empty_response(StatusCode::BAD_REQUEST),
+            ApiError::AckIsDisabled => response_json(
+                StatusCode::BAD_REQUEST,
+                splunk_response::ACK_IS_DISABLED.as_ref(),
+            ),
         },))
     } else {
         Err(rejection)
@@ -765,7 +1020,7 @@ fn event_error(text: &str, code: u16, event: usize) -> Response {
 #[cfg(feature = "sinks-splunk_hec")]
 #[cfg(test)]
 mod tests {
-    use super::{parse_timestamp, SplunkConfig};
+    use super::{parse_timestamp, Acknowledgements, AcknowledgementsConfig, SplunkConfig};
     use crate::{
         config::{log_schema, GlobalOptions, SinkConfig, SinkContext, SourceConfig},
         event::Event,
@@ -781,7 +1036,11 @@ mod tests {
     use chrono::{TimeZone, Utc};
     use futures::{stream, StreamExt};
     use futures01::sync::mpsc;
-    use std::{future::ready, net::SocketAddr};
+    use std::{
+        future::ready,
+        net::SocketAddr,
+        time::{Duration, Instant},
+    };
 
     #[test]
     fn generate_config() {
@@ -796,6 +1055,13 @@ mod tests {
     }
 
     async fn source_with(token: Option<String>) -> (mpsc::Receiver<Event>, SocketAddr) {
+        source_with_acknowledgements(token, AcknowledgementsConfig::default()).await
+    }
+
+    async fn source_with_acknowledgements(
+        token: Option<String>,
+        acknowledgements: AcknowledgementsConfig,
+    ) -> (mpsc::Receiver<Event>, SocketAddr) {
         let (sender, recv) = Pipeline::new_test();
         let address = next_addr();
         tokio::spawn(async move {
@@ -803,6 +1069,7 @@ mod tests {
                 address,
                 token,
                 tls: None,
+                acknowledgements,
             }
             .build(
                 "default",
@@ -1120,6 +1387,109 @@ mod tests {
         assert_eq!(events[2].as_log()[&super::SOURCE], "secondary".into());
     }
 
+    async fn post_json(address: SocketAddr, api: &str, message: &str) -> (u16, serde_json::Value) {
+        let response = reqwest::Client::new()
+            .post(&format!("http://{}/{}", address, api))
+            .header("Authorization", format!("Splunk {}", TOKEN))
+            .header("x-splunk-request-channel", "guid")
+            .body(message.to_owned())
+            .send()
+            .await
+            .unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn acknowledgements() {
+        trace_init();
+
+        let acknowledgements = AcknowledgementsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let (source, address) =
+            source_with_acknowledgements(Some(TOKEN.to_owned()), acknowledgements).await;
+
+        let (status, body) =
+            post_json(address, "services/collector/event", r#"{"event":"first"}"#).await;
+        assert_eq!(200, status);
+        assert_eq!(body["ackId"], 0);
+        let (status, body) = post_json(address, "services/collector/raw", "second").await;
+        assert_eq!(200, status);
+        assert_eq!(body["ackId"], 1);
+
+        let events = collect_n(source, 2).await.unwrap();
+
+        // Not acknowledged while the events are still in flight.
+        let (_, body) = post_json(address, "services/collector/ack", r#"{"acks":[0,1]}"#).await;
+        assert_eq!(body, serde_json::json!({"acks": {"0": false, "1": false}}));
+
+        drop(events);
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        let (status, body) =
+            post_json(address, "services/collector/ack", r#"{"acks":[0,1,2]}"#).await;
+        assert_eq!(200, status);
+        assert_eq!(
+            body,
+            serde_json::json!({"acks": {"0": true, "1": true, "2": false}})
+        );
+
+        // Confirmed acknowledgements are only reported once.
+        let (_, body) = post_json(address, "services/collector/ack", r#"{"acks":[0]}"#).await;
+        assert_eq!(body, serde_json::json!({"acks": {"0": false}}));
+    }
+
+    #[test]
+    fn expires_acknowledgements() {
+        let acknowledgements = Acknowledgements::new(&AcknowledgementsConfig {
+            enabled: true,
+            max_pending_acks_per_channel: 2,
+            max_channels: 2,
+            max_idle_secs: 10,
+        });
+        let start = Instant::now();
+        let after = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(acknowledgements.register("a", start), 0);
+        assert_eq!(acknowledgements.register("a", after(1)), 1);
+        assert_eq!(acknowledgements.register("a", after(2)), 2);
+        acknowledgements.confirm("a", 0);
+        acknowledgements.confirm("a", 2);
+        // The oldest acknowledgement went over the per channel limit.
+        assert_eq!(
+            acknowledgements.query("a", &[0, 2], after(3)),
+            vec![(0, false), (2, true)].into_iter().collect()
+        );
+
+        acknowledgements.register("b", after(4));
+        acknowledgements.register("c", after(5));
+        // The least recently used channel went over the channel limit.
+        assert!(!acknowledgements.channels.lock().unwrap().contains_key("a"));
+
+        acknowledgements.confirm("b", 0);
+        acknowledgements.confirm("c", 0);
+        assert_eq!(
+            acknowledgements.query("c", &[0], after(14)),
+            vec![(0, true)].into_iter().collect()
+        );
+        // Idle channels are forgotten.
+        let channels = acknowledgements.channels.lock().unwrap();
+        assert!(!channels.contains_key("b"));
+        assert!(channels.contains_key("c"));
+    }
+
+    #[tokio::test]
+    async fn acknowledgements_disabled() {
+        trace_init();
+
+        let (_source, address) = source().await;
+
+        let (status, _) = post_json(address, "services/collector/ack", r#"{"acks":[0]}"#).await;
+        assert_eq!(400, status);
+    }
+
     #[test]
     fn parse_timestamps() {
         let cases = vec![
@@ -0,0 +1,253 @@
+use crate::{
+    config::{DataType, GlobalOptions, SourceConfig, SourceDescription},
+    event::{in_flight::InFlightToken, Event},
+    internal_events::{
+        SpoolEventReceived, SpoolIoError, SpoolSegmentCorrupted, SpoolSegmentForwarded,
+    },
+    shutdown::ShutdownSignal,
+    spool::{self, Segment},
+    Pipeline,
+};
+use futures::{compat::Sink01CompatExt, stream, SinkExt, StreamExt};
+use futures01::Sink;
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+use tokio::{fs, time};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpoolSourceConfig {
+    pub path: PathBuf,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+}
+
+const fn default_poll_interval_secs() -> u64 {
+    1
+}
+
+inventory::submit! {
+    SourceDescription::new::<SpoolSourceConfig>("spool")
+}
+
+impl_generate_config_from_default!(SpoolSourceConfig);
+
+impl Default for SpoolSourceConfig {
+    fn default() -> Self {
+        Self {
+            path: "/var/lib/vector/spool".into(),
+            poll_interval_secs: default_poll_interval_secs(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "spool")]
+impl SourceConfig for SpoolSourceConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        Ok(Box::pin(run(
+            self.path.clone(),
+            self.poll_interval_secs,
+            out,
+            shutdown,
+        )))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn source_type(&self) -> &'static str {
+        "spool"
+    }
+}
+
+async fn run(
+    path: PathBuf,
+    interval: u64,
+    out: Pipeline,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let mut out = out
+        .sink_map_err(|error| error!(message = "Error sending spooled events.", %error))
+        .sink_compat();
+
+    let mut interval = time::interval(time::Duration::from_secs(interval));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = &mut shutdown => return Ok(()),
+        }
+
+        let segments = match spool::closed_segments(&path).await {
+            Ok(segments) => segments,
+            // The sink creates the directory once it starts.
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => {
+                emit!(SpoolIoError {
+                    path: &path,
+                    operation: "list",
+                    error,
+                });
+                continue;
+            }
+        };
+
+        for segment in segments {
+            // Segments being forwarded on shutdown are kept, and sent again
+            // in full once Vector restarts.
+            tokio::select! {
+                result = forward(&segment, &mut out) => result?,
+                _ = &mut shutdown => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Sends the events of the segment, deleting it once all of them have been
+/// processed, that is acked by the sinks receiving them or dropped on the
+/// way. Metric events aren't tracked, so segments only holding metrics are
+/// deleted once they are sent.
+async fn forward(
+    segment: &Segment,
+    out: &mut (impl futures::Sink<Event, Error = ()> + Unpin),
+) -> Result<(), ()> {
+    let bytes = match fs::read(&segment.path).await {
+        Ok(bytes) => bytes,
+        // The segment was discarded by the sink in the meantime.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => {
+            emit!(SpoolIoError {
+                path: &segment.path,
+                operation: "read",
+                error,
+            });
+            return Ok(());
+        }
+    };
+
+    let byte_size = bytes.len() / segment.events.max(1);
+    let (events, error) = spool::decode(bytes.into());
+    if let Some(error) = error {
+        emit!(SpoolSegmentCorrupted {
+            path: &segment.path,
+            events: events.len(),
+            error,
+        });
+    }
+    let count = events.len();
+    let (token, released) = InFlightToken::notify();
+    out.send_all(&mut stream::iter(events).map(|mut event| {
+        emit!(SpoolEventReceived { byte_size });
+        if let Event::Log(log) = &mut event {
+            log.add_in_flight(token.clone());
+        }
+        Ok(event)
+    }))
+    .await?;
+    drop(token);
+    // The token notifies once dropped, so this can't be canceled.
+    let _ = released.await;
+
+    match fs::remove_file(&segment.path).await {
+        Ok(()) => emit!(SpoolSegmentForwarded {
+            path: &segment.path,
+            events: count,
+        }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => emit!(SpoolIoError {
+            path: &segment.path,
+            operation: "delete",
+            error,
+        }),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use bytes::BytesMut;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SpoolSourceConfig>();
+    }
+
+    async fn write_segment(path: &PathBuf, created: u64, messages: &[&str]) -> PathBuf {
+        let mut buf = BytesMut::new();
+        for message in messages {
+            spool::encode(Event::from(*message), &mut buf);
+        }
+        let segment = spool::closed_path(path, created, messages.len());
+        fs::write(&segment, &buf).await.unwrap();
+        segment
+    }
+
+    async fn next_message(
+        rx: &mut (impl futures::Stream<Item = Result<Event, ()>> + Unpin),
+    ) -> (String, Event) {
+        let event = rx.next().await.unwrap().unwrap();
+        (event.as_log()["message"].to_string_lossy(), event)
+    }
+
+    #[tokio::test]
+    async fn forwards_and_deletes_segments() {
+        let path = temp_dir();
+        fs::create_dir_all(&path).await.unwrap();
+        let newer = write_segment(&path, 2, &["three"]).await;
+        let older = write_segment(&path, 1, &["one", "two"]).await;
+        // Open segments are left to the sink.
+        fs::write(spool::open_path(&path, 3), b"").await.unwrap();
+
+        let (tx, rx) = Pipeline::new_test();
+        let config = SpoolSourceConfig {
+            path: path.clone(),
+            poll_interval_secs: 1,
+        };
+        let source = config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        let mut rx = rx.compat();
+
+        let (one, first) = next_message(&mut rx).await;
+        let (two, second) = next_message(&mut rx).await;
+        assert_eq!((one.as_str(), two.as_str()), ("one", "two"));
+
+        // Segments are kept while their events are in flight, and the next
+        // ones are only forwarded once they're deleted.
+        time::delay_for(time::Duration::from_millis(100)).await;
+        assert!(older.exists());
+        assert!(newer.exists());
+        drop(first);
+        time::delay_for(time::Duration::from_millis(100)).await;
+        assert!(older.exists());
+        drop(second);
+        time::delay_for(time::Duration::from_millis(100)).await;
+        assert!(!older.exists());
+
+        let (three, third) = next_message(&mut rx).await;
+        assert_eq!(three, "three");
+        time::delay_for(time::Duration::from_millis(100)).await;
+        assert!(newer.exists());
+        drop(third);
+        time::delay_for(time::Duration::from_millis(100)).await;
+        assert!(!newer.exists());
+        assert!(spool::open_path(&path, 3).exists());
+    }
+}
@@ -1,4 +1,4 @@
-use super::util::{SocketListenAddr, TcpSource};
+use super::util::{Clock, SocketListenAddr, TcpSource};
 #[cfg(unix)]
 use crate::sources::util::build_unix_stream_source;
 use crate::{
@@ -6,7 +6,7 @@ use crate::{
         log_schema, DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig,
         SourceDescription,
     },
-    event::{Event, Value},
+    event::{Event, LogEvent, Value},
     internal_events::{SyslogEventReceived, SyslogUdpReadError, SyslogUdpUtf8Error},
     shutdown::ShutdownSignal,
     tcp::TcpKeepaliveConfig,
@@ -14,16 +14,18 @@ use crate::{
     Pipeline,
 };
 use bytes::{Buf, Bytes, BytesMut};
-use chrono::{Datelike, Utc};
+use chrono::Datelike;
 use derive_is_enum_variant::is_enum_variant;
 use futures::{compat::Sink01CompatExt, StreamExt};
 use futures01::Sink;
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::io;
 use std::net::SocketAddr;
 #[cfg(unix)]
 use std::path::PathBuf;
-use syslog_loose::{IncompleteDate, Message, ProcId, Protocol};
+use syslog_loose::{IncompleteDate, Message, ProcId, Protocol, StructuredElement};
 use tokio::net::UdpSocket;
 use tokio_util::{
     codec::{BytesCodec, Decoder, LinesCodec, LinesCodecError},
@@ -40,6 +42,9 @@ pub struct SyslogConfig {
     pub max_length: usize,
     /// The host key of the log. (This differs from `hostname`)
     pub host_key: Option<String>,
+    /// The time zone and clock of timestamps without zone information.
+    #[serde(flatten)]
+    pub clock: Clock,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, is_enum_variant)]
@@ -49,6 +54,8 @@ pub enum Mode {
         address: SocketListenAddr,
         keepalive: Option<TcpKeepaliveConfig>,
         tls: Option<TlsConfig>,
+        #[serde(default)]
+        proxy_protocol: bool,
     },
     Udp {
         address: SocketAddr,
@@ -69,6 +76,7 @@ impl SyslogConfig {
             mode,
             host_key: None,
             max_length: default_max_length(),
+            clock: Clock::default(),
         }
     }
 }
@@ -84,9 +92,11 @@ impl GenerateConfig for SyslogConfig {
                 address: SocketListenAddr::SocketAddr("0.0.0.0:514".parse().unwrap()),
                 keepalive: None,
                 tls: None,
+                proxy_protocol: false,
             },
             host_key: None,
             max_length: default_max_length(),
+            clock: Clock::default(),
         })
         .unwrap()
     }
@@ -106,22 +116,40 @@ impl SourceConfig for SyslogConfig {
             .host_key
             .clone()
             .unwrap_or_else(|| log_schema().host_key().to_string());
+        let clock = self.clock;
 
         match self.mode.clone() {
             Mode::Tcp {
                 address,
                 keepalive,
                 tls,
+                proxy_protocol,
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
                     host_key,
+                    clock,
                 };
                 let shutdown_secs = 30;
                 let tls = MaybeTlsSettings::from_config(&tls, true)?;
-                source.run(address, keepalive, shutdown_secs, tls, shutdown, out)
+                source.run(
+                    address,
+                    keepalive,
+                    shutdown_secs,
+                    tls,
+                    proxy_protocol,
+                    shutdown,
+                    out,
+                )
             }
-            Mode::Udp { address } => Ok(udp(address, self.max_length, host_key, shutdown, out)),
+            Mode::Udp { address } => Ok(udp(
+                address,
+                self.max_length,
+                host_key,
+                clock,
+                shutdown,
+                out,
+            )),
             #[cfg(unix)]
             Mode::Unix { path } => Ok(build_unix_stream_source(
                 path,
@@ -129,7 +157,9 @@ impl SourceConfig for SyslogConfig {
                 host_key,
                 shutdown,
                 out,
-                event_from_str,
+                move |host_key, default_host, line| {
+                    event_from_str(host_key, default_host, line, &clock)
+                },
             )),
         }
     }
@@ -156,6 +186,7 @@ impl SourceConfig for SyslogConfig {
 struct SyslogTcpSource {
     max_length: usize,
     host_key: String,
+    clock: Clock,
 }
 
 impl TcpSource for SyslogTcpSource {
@@ -167,7 +198,7 @@ impl TcpSource for SyslogTcpSource {
     }
 
     fn build_event(&self, frame: String, host: Bytes) -> Option<Event> {
-        event_from_str(&self.host_key, Some(host), &frame)
+        event_from_str(&self.host_key, Some(host), &frame, &self.clock)
     }
 }
 
@@ -277,6 +308,7 @@ pub fn udp(
     addr: SocketAddr,
     _max_length: usize,
     host_key: String,
+    clock: Clock,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> super::Source {
@@ -305,7 +337,8 @@ pub fn udp(
                                 .map_err(|error| emit!(SyslogUdpUtf8Error { error }))
                                 .ok()
                                 .and_then(|s| {
-                                    event_from_str(&host_key, Some(received_from), s).map(Ok)
+                                    event_from_str(&host_key, Some(received_from), s, &clock)
+                                        .map(Ok)
                                 })
                         }
                         Err(error) => {
@@ -325,9 +358,9 @@ pub fn udp(
 
 /// Function used to resolve the year for syslog messages that don't include the year.
 /// If the current month is January, and the syslog message is for December, it will take the previous year.
-/// Otherwise, take the current year.
-fn resolve_year((month, _date, _hour, _min, _sec): IncompleteDate) -> i32 {
-    let now = Utc::now();
+/// Otherwise, take the current year, in the time zone of the messages.
+fn resolve_year((month, _date, _hour, _min, _sec): IncompleteDate, clock: &Clock) -> i32 {
+    let now = clock.timezone.now();
     if now.month() == 1 && month == 12 {
         now.year() - 1
     } else {
@@ -342,9 +375,20 @@ fn resolve_year((month, _date, _hour, _min, _sec): IncompleteDate) -> i32 {
 // TODO: many more cases to handle:
 // octet framing (i.e. num bytes as ascii string prefix) with and without delimiters
 // null byte delimiter in place of newline
-fn event_from_str(host_key: &str, default_host: Option<Bytes>, line: &str) -> Option<Event> {
+fn event_from_str(
+    host_key: &str,
+    default_host: Option<Bytes>,
+    line: &str,
+    clock: &Clock,
+) -> Option<Event> {
     let line = line.trim();
-    let parsed = syslog_loose::parse_message_with_year(line, resolve_year);
+    // The year is only resolved for timestamps without year and zone, which
+    // are parsed as local time and then moved to the zone of the source.
+    let incomplete = Cell::new(false);
+    let parsed = syslog_loose::parse_message_with_year(line, |date| {
+        incomplete.set(true);
+        resolve_year(date, clock)
+    });
     let mut event = Event::from(&parsed.msg[..]);
 
     // Add source type
@@ -361,13 +405,19 @@ fn event_from_str(host_key: &str, default_host: Option<Bytes>, line: &str) -> Op
         event.as_mut_log().insert(host_key, parsed_host);
     }
 
-    let timestamp = parsed
-        .timestamp
-        .map(|ts| ts.into())
-        .unwrap_or_else(Utc::now);
-    event
-        .as_mut_log()
-        .insert(log_schema().timestamp_key(), timestamp);
+    let timestamp = match parsed.timestamp {
+        Some(ts) if incomplete.get() => clock
+            .timezone
+            .localize(ts.naive_local())
+            .or(Some(ts.into())),
+        Some(ts) => Some(ts.into()),
+        None => clock.now(),
+    };
+    if let Some(timestamp) = timestamp {
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), timestamp);
+    }
 
     insert_fields_from_syslog(&mut event, parsed);
 
@@ -412,19 +462,50 @@ fn insert_fields_from_syslog(event: &mut Event, parsed: Message<&str>) {
         log.insert("procid", value);
     }
 
-    for element in parsed.structured_data.into_iter() {
-        for (name, value) in element.params.into_iter() {
-            let key = format!("{}.{}", element.id, name);
-            log.insert(key, value.to_string());
+    insert_structured_data(log, parsed.structured_data);
+}
+
+/// Inserts each structured data element as a map keyed by its SD-ID. Neither
+/// SD-IDs, which may carry a dotted private enterprise number as in
+/// `exampleSDID@32473.1`, nor parameter names are split into paths. Repeated
+/// parameters, such as `ip` of the `origin` element, become arrays.
+fn insert_structured_data(log: &mut LogEvent, elements: Vec<StructuredElement<&str>>) {
+    let mut structured_data = BTreeMap::<String, BTreeMap<String, Value>>::new();
+    for element in elements {
+        if element.params.is_empty() {
+            continue;
+        }
+        let params = structured_data.entry(element.id.to_owned()).or_default();
+        for (name, value) in element.params {
+            let value = Value::from(value.to_string());
+            match params.remove(name) {
+                None => params.insert(name.to_owned(), value),
+                Some(Value::Array(mut values)) => {
+                    values.push(value);
+                    params.insert(name.to_owned(), Value::Array(values))
+                }
+                Some(previous) => {
+                    params.insert(name.to_owned(), Value::Array(vec![previous, value]))
+                }
+            };
         }
     }
+
+    for (id, params) in structured_data {
+        log.insert_flat(id, Value::Map(params));
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::{event_from_str, Mode, SyslogConfig};
-    use crate::{config::log_schema, event::Event};
+    use crate::sources::util::{Clock, NowSource, TimeZone};
+    use crate::{
+        config::log_schema,
+        event::{Event, Value},
+    };
     use chrono::prelude::*;
+    use std::collections::BTreeMap;
 
     #[test]
     fn generate_config() {
@@ -545,7 +626,7 @@ mod test {
         }
 
         assert_eq!(
-            event_from_str(&"host".to_string(), None, &raw).unwrap(),
+            event_from_str(&"host".to_string(), None, &raw, &Clock::default()).unwrap(),
             expected
         );
     }
@@ -575,7 +656,7 @@ mod test {
             expected.insert("procid", 8449);
         }
 
-        let event = event_from_str(&"host".to_string(), None, &raw);
+        let event = event_from_str(&"host".to_string(), None, &raw, &Clock::default());
         assert_eq!(event, Some(expected.clone()));
 
         let raw = format!(
@@ -583,7 +664,7 @@ mod test {
             r#"[incorrect x=]"#, msg
         );
 
-        let event = event_from_str(&"host".to_string(), None, &raw);
+        let event = event_from_str(&"host".to_string(), None, &raw, &Clock::default());
         assert_eq!(event, Some(expected));
     }
 
@@ -602,7 +683,7 @@ mod test {
             r#"[empty]"#
         );
 
-        let event = event_from_str(&"host".to_string(), None, &msg).unwrap();
+        let event = event_from_str(&"host".to_string(), None, &msg, &Clock::default()).unwrap();
         assert!(there_is_map_called_empty(event));
 
         let msg = format!(
@@ -610,7 +691,7 @@ mod test {
             r#"[non_empty x="1"][empty]"#
         );
 
-        let event = event_from_str(&"host".to_string(), None, &msg).unwrap();
+        let event = event_from_str(&"host".to_string(), None, &msg, &Clock::default()).unwrap();
         assert!(there_is_map_called_empty(event));
 
         let msg = format!(
@@ -618,7 +699,7 @@ mod test {
             r#"[empty][non_empty x="1"]"#
         );
 
-        let event = event_from_str(&"host".to_string(), None, &msg).unwrap();
+        let event = event_from_str(&"host".to_string(), None, &msg, &Clock::default()).unwrap();
         assert!(there_is_map_called_empty(event));
 
         let msg = format!(
@@ -626,10 +707,37 @@ mod test {
             r#"[empty not_really="testing the test"]"#
         );
 
-        let event = event_from_str(&"host".to_string(), None, &msg).unwrap();
+        let event = event_from_str(&"host".to_string(), None, &msg, &Clock::default()).unwrap();
         assert!(!there_is_map_called_empty(event));
     }
 
+    #[test]
+    fn keeps_structured_data_ids_and_repeated_params() {
+        let raw = format!(
+            r#"<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - {} qwerty"#,
+            r#"[exampleSDID@32473.1 iut="3" x.y="z"][origin ip="10.0.0.1" ip="10.0.0.2" ip="10.0.0.3"]"#
+        );
+
+        let event = event_from_str(&"host".to_string(), None, &raw, &Clock::default()).unwrap();
+        let log = event.as_log();
+
+        let mut element = BTreeMap::new();
+        element.insert("iut".to_owned(), Value::from("3"));
+        element.insert("x.y".to_owned(), Value::from("z"));
+        assert_eq!(
+            log.get_flat("exampleSDID@32473.1"),
+            Some(&Value::Map(element))
+        );
+        assert_eq!(
+            log["origin.ip"],
+            Value::Array(vec![
+                "10.0.0.1".into(),
+                "10.0.0.2".into(),
+                "10.0.0.3".into()
+            ])
+        );
+    }
+
     #[test]
     fn handles_weird_whitespace() {
         // this should also match rsyslog omfwd with template=RSYSLOG_SyslogProtocol23Format
@@ -639,8 +747,8 @@ mod test {
         let cleaned = r#"<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - [meta sequenceId="1"] i am foobar"#;
 
         assert_eq!(
-            event_from_str(&"host".to_string(), None, raw).unwrap(),
-            event_from_str(&"host".to_string(), None, cleaned).unwrap()
+            event_from_str(&"host".to_string(), None, raw, &Clock::default()).unwrap(),
+            event_from_str(&"host".to_string(), None, cleaned, &Clock::default()).unwrap()
         );
     }
 
@@ -665,7 +773,7 @@ mod test {
         }
 
         assert_eq!(
-            event_from_str(&"host".to_string(), None, &raw).unwrap(),
+            event_from_str(&"host".to_string(), None, &raw, &Clock::default()).unwrap(),
             expected
         );
     }
@@ -697,7 +805,7 @@ mod test {
         }
 
         assert_eq!(
-            event_from_str(&"host".to_string(), None, &raw).unwrap(),
+            event_from_str(&"host".to_string(), None, &raw, &Clock::default()).unwrap(),
             expected
         );
     }
@@ -732,8 +840,67 @@ mod test {
         }
 
         assert_eq!(
-            event_from_str(&"host".to_string(), None, &raw).unwrap(),
+            event_from_str(&"host".to_string(), None, &raw, &Clock::default()).unwrap(),
             expected
         );
     }
+
+    #[test]
+    fn config_clock() {
+        let config: SyslogConfig = toml::from_str(
+            r#"
+            mode = "udp"
+            address = "127.0.0.1:1235"
+            timezone = "America/New_York"
+            now_source = "none"
+          "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.clock,
+            Clock {
+                timezone: TimeZone::Named(chrono_tz::Tz::America__New_York),
+                now_source: NowSource::Unset,
+            }
+        );
+    }
+
+    #[test]
+    fn applies_timezone_to_timestamps_without_zone() {
+        let clock = Clock {
+            timezone: TimeZone::Named(chrono_tz::Tz::America__New_York),
+            now_source: NowSource::System,
+        };
+
+        let raw = "<13>Feb 13 20:07:26 74794bfb6795 root[8539]: i am foobar";
+        let event = event_from_str(&"host".to_string(), None, raw, &clock).unwrap();
+        let year = clock.timezone.now().year();
+        assert_eq!(
+            event.as_log()[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(year, 2, 14).and_hms(1, 7, 26))
+        );
+
+        // Timestamps with a zone keep it.
+        let raw = "<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - - i am foobar";
+        let event = event_from_str(&"host".to_string(), None, raw, &clock).unwrap();
+        assert_eq!(
+            event.as_log()[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2019, 2, 13).and_hms(19, 48, 34))
+        );
+    }
+
+    #[test]
+    fn leaves_missing_timestamps_unset() {
+        let clock = Clock {
+            timezone: TimeZone::Local,
+            now_source: NowSource::Unset,
+        };
+
+        let raw = "<13>1 - 74794bfb6795 root 8449 - - i am foobar";
+        let event = event_from_str(&"host".to_string(), None, raw, &clock).unwrap();
+        assert!(!event.as_log().contains(log_schema().timestamp_key()));
+
+        let event = event_from_str(&"host".to_string(), None, raw, &Clock::default()).unwrap();
+        assert!(event.as_log().contains(log_schema().timestamp_key()));
+    }
 }
@@ -0,0 +1,197 @@
+//! A subset of [JSONPath](https://goessner.net/articles/JsonPath/) for
+//! selecting the records in API responses: the root `$`, child fields as
+//! `.name` or `['name']`, array indices as `[0]` and wildcards as `.*` or
+//! `[*]`.
+
+use serde_json::Value as JsonValue;
+use snafu::Snafu;
+use std::fmt;
+
+#[derive(Debug, Snafu, PartialEq)]
+#[snafu(display("Invalid JSONPath {:?} at position {}: {}", path, position, reason))]
+pub struct JsonPathError {
+    path: String,
+    position: usize,
+    reason: &'static str,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    path: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// The path selecting the whole document.
+    pub fn root() -> Self {
+        Self {
+            path: "$".to_owned(),
+            segments: Vec::new(),
+        }
+    }
+
+    pub fn parse(path: &str) -> Result<Self, JsonPathError> {
+        let error = |position, reason| JsonPathError {
+            path: path.to_owned(),
+            position,
+            reason,
+        };
+
+        let chars = path.char_indices().collect::<Vec<_>>();
+        let mut segments = Vec::new();
+        let mut i = 0;
+        if let Some((_, '$')) = chars.first() {
+            i += 1;
+        }
+
+        // A leading field name may omit the `.`, as in `items.id`.
+        let mut expect_name = i == 0;
+        while i < chars.len() || expect_name {
+            if expect_name {
+                let start = i;
+                while i < chars.len() && chars[i].1 != '.' && chars[i].1 != '[' {
+                    i += 1;
+                }
+                let name = chars[start..i].iter().map(|(_, c)| c).collect::<String>();
+                if name.is_empty() {
+                    return Err(error(start, "expected a field name"));
+                }
+                segments.push(if name == "*" {
+                    Segment::Wildcard
+                } else {
+                    Segment::Field(name)
+                });
+                expect_name = false;
+                continue;
+            }
+
+            match chars[i].1 {
+                '.' => {
+                    i += 1;
+                    expect_name = true;
+                }
+                '[' => {
+                    let start = i;
+                    let end = chars[i..]
+                        .iter()
+                        .position(|(_, c)| *c == ']')
+                        .map(|offset| i + offset)
+                        .ok_or_else(|| error(start, "unclosed `[`"))?;
+                    let inner = chars[i + 1..end].iter().map(|(_, c)| c).collect::<String>();
+                    let inner = inner.trim();
+                    segments.push(if inner == "*" {
+                        Segment::Wildcard
+                    } else if let Ok(index) = inner.parse::<usize>() {
+                        Segment::Index(index)
+                    } else if inner.len() >= 2
+                        && ((inner.starts_with('\'') && inner.ends_with('\''))
+                            || (inner.starts_with('"') && inner.ends_with('"')))
+                    {
+                        Segment::Field(inner[1..inner.len() - 1].to_owned())
+                    } else {
+                        return Err(error(start, "expected `*`, an index or a quoted name"));
+                    });
+                    i = end + 1;
+                }
+                _ => return Err(error(i, "expected `.` or `[`")),
+            }
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            segments,
+        })
+    }
+
+    /// Whether the path selects at most one value.
+    pub fn is_definite(&self) -> bool {
+        !self.segments.contains(&Segment::Wildcard)
+    }
+
+    pub fn select<'a>(&self, value: &'a JsonValue) -> Vec<&'a JsonValue> {
+        self.segments
+            .iter()
+            .fold(vec![value], |values, segment| match segment {
+                Segment::Field(name) => values
+                    .into_iter()
+                    .filter_map(|value| value.get(name.as_str()))
+                    .collect(),
+                Segment::Index(index) => values
+                    .into_iter()
+                    .filter_map(|value| value.get(*index))
+                    .collect(),
+                Segment::Wildcard => values
+                    .into_iter()
+                    .flat_map(|value| match value {
+                        JsonValue::Array(array) => array.iter().collect(),
+                        JsonValue::Object(map) => map.values().collect(),
+                        _ => Vec::new(),
+                    })
+                    .collect(),
+            })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(path: &str, value: &JsonValue) -> Vec<JsonValue> {
+        JsonPath::parse(path)
+            .unwrap()
+            .select(value)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn selects_values() {
+        let value = json!({
+            "data": {"events": [{"id": 1}, {"id": 2}]},
+            "odd key": true,
+        });
+
+        assert_eq!(select("$", &value), vec![value.clone()]);
+        assert_eq!(
+            select("$.data.events", &value),
+            vec![json!([{"id": 1}, {"id": 2}])]
+        );
+        assert_eq!(select("data.events[1].id", &value), vec![json!(2)]);
+        assert_eq!(
+            select("$.data.events[*].id", &value),
+            vec![json!(1), json!(2)]
+        );
+        assert_eq!(select("$['odd key']", &value), vec![json!(true)]);
+        assert_eq!(select("$.missing.*", &value), Vec::<JsonValue>::new());
+    }
+
+    #[test]
+    fn definite_paths() {
+        assert!(JsonPath::parse("$.data[0]").unwrap().is_definite());
+        assert!(!JsonPath::parse("$.data[*]").unwrap().is_definite());
+        assert!(!JsonPath::parse("$.*.id").unwrap().is_definite());
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        assert!(JsonPath::parse("$.").is_err());
+        assert!(JsonPath::parse("$.data[0").is_err());
+        assert!(JsonPath::parse("$.data[foo]").is_err());
+        assert!(JsonPath::parse("$data").is_err());
+    }
+}
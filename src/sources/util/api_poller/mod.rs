@@ -0,0 +1,1004 @@
+//! Shared machinery for sources that periodically poll a paginated HTTP API
+//! for records, such as the audit log APIs of SaaS products.
+//!
+//! A source describes the API it polls with a [`PollerSpec`] and the poller
+//! takes care of paginating, checkpointing the position in the record stream
+//! in the `data_dir`, backing off when the API reports rate limiting, and
+//! turning the records into log events.
+
+mod json_path;
+pub mod oauth2;
+mod template;
+
+pub use json_path::{JsonPath, JsonPathError};
+pub use template::{TemplateError, UrlTemplate};
+
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    http::HttpClient,
+    internal_events::{
+        ApiPollerCheckpointError, ApiPollerRateLimited, ApiPollerRecordsReceived,
+        ApiPollerRequestError,
+    },
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{compat::Sink01CompatExt, stream, SinkExt, StreamExt};
+use futures01::Sink;
+use http::{header::HeaderName, HeaderMap, Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use snafu::{ResultExt, Snafu};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::time::{self, delay_for};
+use url::Url;
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+const CHECKPOINT_TMP_FILENAME: &str = "checkpoint.json.tmp";
+
+/// The longest we are willing to wait for a rate limit to reset.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
+
+/// Rate limit resets below this are seconds from now rather than Unix
+/// timestamps.
+const RELATIVE_RESET_LIMIT: u64 = 1_000_000_000;
+
+/// How long to wait after a `429 Too Many Requests` response that doesn't
+/// tell us when to retry.
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Snafu)]
+pub enum PollerError {
+    #[snafu(display("Failed to build request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Failed to make HTTP(S) request: {}", source))]
+    Request { source: crate::http::HttpError },
+    #[snafu(display("Failed to read response body: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Unexpected response status: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("Failed to parse response body as JSON: {}", source))]
+    ParseBody { source: serde_json::Error },
+    #[snafu(display("Expected an array of records at {}", path))]
+    MissingRecords { path: JsonPath },
+    #[snafu(display("Failed to render request URL: {}", source))]
+    RenderUrl { source: TemplateError },
+    #[snafu(display("Invalid next page URL {:?}: {}", url, source))]
+    InvalidNextUrl {
+        url: String,
+        source: url::ParseError,
+    },
+    #[snafu(display("Failed to authorize request: {}", source))]
+    Authorize { source: crate::Error },
+    #[snafu(display("Failed to forward events, downstream is closed"))]
+    Forward,
+}
+
+/// How to move through the record stream of an API.
+///
+/// The cursor and page token are available to the URL template as
+/// `{{ cursor }}` and `{{ page_token }}`, and are additionally appended as the
+/// named query parameters if given.
+#[derive(Clone, Debug)]
+pub enum Pagination {
+    /// Fetch a single page per poll.
+    None,
+    /// Follow the RFC 8288 `Link` response header with `rel="next"`. The last
+    /// seen link is the checkpoint, so polls resume where the previous one
+    /// stopped.
+    LinkHeader,
+    /// Use the value of `field` of the last record as the cursor of the next
+    /// request. `cursor_params` are only sent along with the cursor,
+    /// `initial_params` are only sent while there is no checkpoint yet.
+    LastRecord {
+        field: String,
+        param: Option<String>,
+        cursor_params: Vec<(String, String)>,
+        initial_params: Vec<(String, String)>,
+    },
+    /// Follow the page token found at `token_field` of the response body.
+    /// Each poll starts at the greatest value of the record `field` seen so
+    /// far, if given. APIs include the records at that value, which have
+    /// already been emitted and are skipped.
+    PageToken {
+        token_field: String,
+        token_param: Option<String>,
+        field: Option<String>,
+        param: Option<String>,
+    },
+}
+
+/// The response headers an API reports its rate limit with.
+#[derive(Clone, Debug)]
+pub struct RateLimitHeaders {
+    /// The seconds to wait before retrying.
+    pub retry_after: HeaderName,
+    /// The number of requests left in the current window.
+    pub remaining: Vec<HeaderName>,
+    /// When the current window ends, either as a Unix timestamp or as the
+    /// number of seconds from now.
+    pub reset: Vec<HeaderName>,
+}
+
+impl Default for RateLimitHeaders {
+    fn default() -> Self {
+        Self {
+            retry_after: http::header::RETRY_AFTER,
+            remaining: vec![
+                HeaderName::from_static("x-rate-limit-remaining"),
+                HeaderName::from_static("x-ratelimit-remaining"),
+            ],
+            reset: vec![
+                HeaderName::from_static("x-rate-limit-reset"),
+                HeaderName::from_static("x-ratelimit-reset"),
+            ],
+        }
+    }
+}
+
+/// Describes the API a source polls.
+#[derive(Clone, Debug)]
+pub struct PollerSpec {
+    /// The `source_type` of the emitted events.
+    pub source_type: &'static str,
+    /// The URL of the requests, including any static query parameters.
+    pub url: UrlTemplate,
+    /// Headers sent with every request.
+    pub headers: HeaderMap,
+    /// Selects the records in the response body. A path selecting a single
+    /// array selects its elements.
+    pub records: JsonPath,
+    pub pagination: Pagination,
+    pub rate_limit: RateLimitHeaders,
+    /// The path of the record field holding the RFC 3339 event timestamp.
+    pub timestamp_field: Option<String>,
+    /// The path of the record field copied into the message field.
+    pub message_field: Option<String>,
+    /// The time between polls.
+    pub interval: Duration,
+}
+
+/// Adds credentials to the requests of a poller, for APIs that require more
+/// than static headers.
+#[async_trait]
+pub trait Authorizer: Send + Sync {
+    async fn authorize(&self, request: &mut Request<Body>) -> crate::Result<()>;
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+struct Checkpoint {
+    cursor: Option<String>,
+}
+
+/// A fetched page, or how long to wait before asking for it again.
+enum Page {
+    Records(HeaderMap, Bytes),
+    RateLimited(Duration),
+}
+
+pub struct ApiPoller {
+    client: HttpClient,
+    spec: PollerSpec,
+    authorizer: Option<Box<dyn Authorizer>>,
+    data_dir: PathBuf,
+}
+
+impl ApiPoller {
+    pub fn new(
+        client: HttpClient,
+        spec: PollerSpec,
+        authorizer: Option<Box<dyn Authorizer>>,
+        data_dir: PathBuf,
+    ) -> Self {
+        Self {
+            client,
+            spec,
+            authorizer,
+            data_dir,
+        }
+    }
+
+    pub async fn run(self, out: Pipeline, shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut out = out
+            .sink_map_err(|error| error!(message = "Error sending records.", %error))
+            .sink_compat();
+
+        let mut checkpoint = load_checkpoint(&self.data_dir).await;
+        let mut interval = time::interval(self.spec.interval).take_until(shutdown.clone());
+        while interval.next().await.is_some() {
+            match self.poll(&mut checkpoint, &mut out, &shutdown).await {
+                Ok(()) => (),
+                // The error has already been logged by the sink.
+                Err(PollerError::Forward) => return Err(()),
+                Err(error) => emit!(ApiPollerRequestError {
+                    error: error.into()
+                }),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch pages until the API has no more records for us. The checkpoint
+    /// only advances once the records of a page have been sent on.
+    async fn poll<O>(
+        &self,
+        checkpoint: &mut Checkpoint,
+        out: &mut O,
+        shutdown: &ShutdownSignal,
+    ) -> Result<(), PollerError>
+    where
+        O: futures::Sink<Event, Error = ()> + Unpin,
+    {
+        let mut page_token: Option<String> = None;
+        let mut greatest = checkpoint.cursor.clone();
+        loop {
+            let url = self.page_url(checkpoint.cursor.as_deref(), page_token.as_deref())?;
+            let (headers, body) = match self.fetch(&url).await? {
+                Page::Records(headers, body) => (headers, body),
+                Page::RateLimited(wait) => {
+                    emit!(ApiPollerRateLimited { wait });
+                    if wait_or_shutdown(wait, shutdown).await {
+                        return Ok(());
+                    }
+                    // Try the same page again.
+                    continue;
+                }
+            };
+
+            let byte_size = body.len();
+            let body: JsonValue = serde_json::from_slice(&body).context(ParseBody)?;
+            let mut records = extract_records(&body, &self.spec.records)?;
+            emit!(ApiPollerRecordsReceived {
+                count: records.len(),
+                byte_size,
+            });
+
+            let mut cursor = checkpoint.cursor.clone();
+            let done = match &self.spec.pagination {
+                Pagination::None => true,
+                Pagination::LinkHeader => match next_link(&headers) {
+                    Some(next) if next != url.as_str() => {
+                        cursor = Some(next);
+                        records.is_empty()
+                    }
+                    _ => true,
+                },
+                Pagination::LastRecord { field, .. } => {
+                    match records
+                        .last()
+                        .and_then(|record| lookup_string(record, field))
+                    {
+                        Some(last) if Some(&last) != checkpoint.cursor.as_ref() => {
+                            cursor = Some(last);
+                            false
+                        }
+                        _ => true,
+                    }
+                }
+                Pagination::PageToken {
+                    token_field, field, ..
+                } => {
+                    if let (Some(field), Some(resumed)) = (field, &checkpoint.cursor) {
+                        records.retain(|record| {
+                            lookup_string(record, field).map_or(true, |value| &value > resumed)
+                        });
+                    }
+                    for value in field.iter().flat_map(|field| {
+                        records
+                            .iter()
+                            .filter_map(move |record| lookup_string(record, field))
+                    }) {
+                        if greatest.as_ref().map_or(true, |greatest| &value > greatest) {
+                            greatest = Some(value);
+                        }
+                    }
+                    page_token = lookup_string(&body, token_field);
+                    if page_token.is_none() {
+                        cursor = greatest.clone();
+                        true
+                    } else {
+                        false
+                    }
+                }
+            };
+
+            let mut events = stream::iter(records)
+                .map(|record| self.create_event(record))
+                .map(Ok);
+            out.send_all(&mut events)
+                .await
+                .map_err(|()| PollerError::Forward)?;
+
+            if cursor != checkpoint.cursor {
+                checkpoint.cursor = cursor;
+                if let Err(error) = save_checkpoint(&self.data_dir, checkpoint).await {
+                    emit!(ApiPollerCheckpointError { error });
+                }
+            }
+
+            if done {
+                return Ok(());
+            }
+
+            if let Some(wait) = rate_limit_wait(&headers, &self.spec.rate_limit, Utc::now()) {
+                emit!(ApiPollerRateLimited { wait });
+                if wait_or_shutdown(wait, shutdown).await {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// The URL of the next page to fetch.
+    fn page_url(&self, cursor: Option<&str>, page_token: Option<&str>) -> Result<Url, PollerError> {
+        if let (Pagination::LinkHeader, Some(cursor)) = (&self.spec.pagination, cursor) {
+            return Url::parse(cursor).with_context(|| InvalidNextUrl { url: cursor });
+        }
+
+        let mut url = self
+            .spec
+            .url
+            .render(cursor, page_token)
+            .context(RenderUrl)?;
+        match &self.spec.pagination {
+            Pagination::None | Pagination::LinkHeader => (),
+            Pagination::LastRecord {
+                param,
+                cursor_params,
+                initial_params,
+                ..
+            } => {
+                let mut query = url.query_pairs_mut();
+                match cursor {
+                    Some(cursor) => {
+                        if let Some(param) = param {
+                            query.append_pair(param, cursor);
+                        }
+                        query.extend_pairs(cursor_params);
+                    }
+                    None => {
+                        query.extend_pairs(initial_params);
+                    }
+                }
+            }
+            Pagination::PageToken {
+                token_param, param, ..
+            } => {
+                let mut query = url.query_pairs_mut();
+                if let (Some(param), Some(cursor)) = (param, cursor) {
+                    query.append_pair(param, cursor);
+                }
+                if let (Some(token_param), Some(page_token)) = (token_param, page_token) {
+                    query.append_pair(token_param, page_token);
+                }
+            }
+        }
+        // `query_pairs_mut` leaves an empty `?` behind when nothing was added.
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+        Ok(url)
+    }
+
+    /// Fetch a single page.
+    async fn fetch(&self, url: &Url) -> Result<Page, PollerError> {
+        let mut builder = Request::get(url.as_str());
+        for (name, value) in self.spec.headers.iter() {
+            builder = builder.header(name, value);
+        }
+        let mut request = builder.body(Body::empty()).context(BuildRequest)?;
+        if let Some(authorizer) = &self.authorizer {
+            authorizer
+                .authorize(&mut request)
+                .await
+                .context(Authorize)?;
+        }
+
+        let response = self.client.send(request).await.context(Request)?;
+        let (parts, body) = response.into_parts();
+
+        if parts.status == StatusCode::TOO_MANY_REQUESTS {
+            let wait = rate_limit_wait(&parts.headers, &self.spec.rate_limit, Utc::now())
+                .unwrap_or(DEFAULT_RATE_LIMIT_WAIT);
+            return Ok(Page::RateLimited(wait));
+        }
+        if !parts.status.is_success() {
+            return Err(PollerError::UnexpectedStatus {
+                status: parts.status,
+            });
+        }
+
+        let body = hyper::body::to_bytes(body).await.context(ReadBody)?;
+        Ok(Page::Records(parts.headers, body))
+    }
+
+    fn create_event(&self, record: JsonValue) -> Event {
+        let timestamp = self
+            .spec
+            .timestamp_field
+            .as_ref()
+            .and_then(|field| lookup_string(&record, field))
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(&timestamp).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc));
+        let message = self
+            .spec
+            .message_field
+            .as_ref()
+            .and_then(|field| lookup(&record, field))
+            .cloned();
+
+        let mut log = match Value::from(record) {
+            Value::Map(fields) => LogEvent::from(fields),
+            value => {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().message_key(), value);
+                log
+            }
+        };
+        if let Some(message) = message {
+            log.insert(log_schema().message_key(), Value::from(message));
+        }
+        log.insert(
+            log_schema().timestamp_key(),
+            timestamp.unwrap_or_else(Utc::now),
+        );
+        log.insert(
+            log_schema().source_type_key(),
+            Bytes::from(self.spec.source_type),
+        );
+        log.into()
+    }
+}
+
+/// Wait for `wait`, returning whether the source is shutting down instead.
+async fn wait_or_shutdown(wait: Duration, shutdown: &ShutdownSignal) -> bool {
+    tokio::select! {
+        _ = delay_for(wait) => false,
+        _ = shutdown.clone() => true,
+    }
+}
+
+/// Look up a dot separated `path` in a JSON value.
+pub fn lookup<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    path.split('.')
+        .try_fold(value, |value, segment| match value {
+            JsonValue::Object(map) => map.get(segment),
+            JsonValue::Array(array) => segment.parse::<usize>().ok().and_then(|i| array.get(i)),
+            _ => None,
+        })
+}
+
+fn lookup_string(value: &JsonValue, path: &str) -> Option<String> {
+    match lookup(value, path)? {
+        JsonValue::String(string) => Some(string.clone()),
+        JsonValue::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn extract_records(body: &JsonValue, path: &JsonPath) -> Result<Vec<JsonValue>, PollerError> {
+    let selected = path.select(body);
+    if !path.is_definite() {
+        return Ok(selected.into_iter().cloned().collect());
+    }
+    match selected.first() {
+        Some(JsonValue::Array(records)) => Ok(records.clone()),
+        // APIs commonly omit the records field of an empty page.
+        None | Some(JsonValue::Null) => Ok(Vec::new()),
+        Some(_) => Err(PollerError::MissingRecords { path: path.clone() }),
+    }
+}
+
+/// Find the `rel="next"` target of the `Link` headers.
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(http::header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let mut parts = link.split(';').map(str::trim);
+            let target = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+            if parts.any(|param| param == "rel=\"next\"" || param == "rel=next") {
+                Some(target.to_owned())
+            } else {
+                None
+            }
+        })
+}
+
+/// How long to wait before the next request according to the rate limit
+/// headers of the last response, if at all.
+fn rate_limit_wait(
+    headers: &HeaderMap,
+    names: &RateLimitHeaders,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    fn header<'a>(headers: &'a HeaderMap, names: &[HeaderName]) -> Option<u64> {
+        names
+            .iter()
+            .filter_map(|name| headers.get(name))
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| value.trim().parse().ok())
+    }
+
+    if let Some(seconds) = header(headers, std::slice::from_ref(&names.retry_after)) {
+        return Some(Duration::from_secs(seconds).min(MAX_RATE_LIMIT_WAIT));
+    }
+
+    if header(headers, &names.remaining)? > 0 {
+        return None;
+    }
+    let reset = header(headers, &names.reset)?;
+    // Small values are relative, anything else is a point in time.
+    let wait = if reset < RELATIVE_RESET_LIMIT {
+        reset
+    } else {
+        (reset as i64 - now.timestamp()).max(1) as u64
+    };
+    Some(Duration::from_secs(wait.max(1)).min(MAX_RATE_LIMIT_WAIT))
+}
+
+async fn load_checkpoint(data_dir: &Path) -> Checkpoint {
+    let path = data_dir.join(CHECKPOINT_FILENAME);
+    match tokio::fs::read(&path).await {
+        Ok(contents) => serde_json::from_slice(&contents).unwrap_or_else(|error| {
+            error!(message = "Invalid checkpoint file, starting from the beginning.", ?path, %error);
+            Checkpoint::default()
+        }),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Checkpoint::default(),
+        Err(error) => {
+            error!(message = "Unable to read checkpoint file.", ?path, %error);
+            Checkpoint::default()
+        }
+    }
+}
+
+async fn save_checkpoint(data_dir: &Path, checkpoint: &Checkpoint) -> std::io::Result<()> {
+    let contents = serde_json::to_vec(checkpoint)?;
+    let tmp_path = data_dir.join(CHECKPOINT_TMP_FILENAME);
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, data_dir.join(CHECKPOINT_FILENAME)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::next_addr, Error};
+    use futures::compat::Future01CompatExt;
+    use futures01::Stream as _;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+    use pretty_assertions::assert_eq;
+    use std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+    use tempfile::tempdir;
+
+    type Pages = Arc<Mutex<HashMap<String, Response<Body>>>>;
+
+    /// Serve canned responses keyed by the request path and query, recording
+    /// the requested URLs.
+    fn serve(addr: SocketAddr, pages: Vec<(&str, Response<Body>)>) -> Arc<Mutex<Vec<String>>> {
+        let pages: Pages = Arc::new(Mutex::new(
+            pages
+                .into_iter()
+                .map(|(url, response)| (url.to_owned(), response))
+                .collect(),
+        ));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let requested = Arc::clone(&requests);
+
+        let make_svc = make_service_fn(move |_| {
+            let pages = Arc::clone(&pages);
+            let requests = Arc::clone(&requests);
+            async move {
+                Ok::<_, Error>(service_fn(move |request: Request<Body>| {
+                    let url = request.uri().to_string();
+                    requests.lock().unwrap().push(url.clone());
+                    let response = pages
+                        .lock()
+                        .unwrap()
+                        .remove(&url)
+                        .unwrap_or_else(|| Response::new(Body::from("[]")));
+                    async move { Ok::<_, Error>(response) }
+                }))
+            }
+        });
+        tokio::spawn(async move {
+            if let Err(error) = Server::bind(&addr).serve(make_svc).await {
+                error!(message = "Server error.", %error);
+            }
+        });
+
+        requested
+    }
+
+    fn spec(addr: SocketAddr, path: &str, pagination: Pagination) -> PollerSpec {
+        PollerSpec {
+            source_type: "test",
+            url: UrlTemplate::new(format!("http://{}{}", addr, path)).unwrap(),
+            headers: HeaderMap::new(),
+            records: JsonPath::root(),
+            pagination,
+            rate_limit: RateLimitHeaders::default(),
+            timestamp_field: Some("published".into()),
+            message_field: Some("text".into()),
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    async fn poll_once(spec: PollerSpec, data_dir: &Path) -> Vec<Event> {
+        let poller = ApiPoller::new(
+            HttpClient::new(None).unwrap(),
+            spec,
+            None,
+            data_dir.to_path_buf(),
+        );
+        let (tx, rx) = Pipeline::new_test();
+        let mut out = tx.sink_map_err(|_| ()).sink_compat();
+        let mut checkpoint = load_checkpoint(data_dir).await;
+        poller
+            .poll(&mut checkpoint, &mut out, &ShutdownSignal::noop())
+            .await
+            .unwrap();
+        drop(out);
+        drop(poller);
+        rx.collect().compat().await.unwrap()
+    }
+
+    fn messages(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn follows_link_headers() {
+        let addr = next_addr();
+        let next = format!("http://{}/logs?after=2", addr);
+        let requests = serve(
+            addr,
+            vec![
+                (
+                    "/logs",
+                    Response::builder()
+                        .header("Link", format!("<http://{}/logs>; rel=\"self\"", addr))
+                        .header("Link", format!("<{}>; rel=\"next\"", next))
+                        .body(Body::from(
+                            r#"[{"text":"one","published":"2021-01-01T00:00:00.000Z"},{"text":"two"}]"#,
+                        ))
+                        .unwrap(),
+                ),
+                (
+                    "/logs?after=2",
+                    Response::builder()
+                        .header("Link", format!("<{}>; rel=\"next\"", next))
+                        .body(Body::from("[]"))
+                        .unwrap(),
+                ),
+            ],
+        );
+
+        let data_dir = tempdir().unwrap();
+        let events = poll_once(spec(addr, "/logs", Pagination::LinkHeader), data_dir.path()).await;
+
+        assert_eq!(messages(&events), vec!["one", "two"]);
+        assert_eq!(
+            events[0].as_log()[log_schema().timestamp_key()],
+            Value::Timestamp("2021-01-01T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            events[0].as_log()[log_schema().source_type_key()],
+            "test".into()
+        );
+        assert_eq!(*requests.lock().unwrap(), vec!["/logs", "/logs?after=2"]);
+        assert_eq!(
+            load_checkpoint(data_dir.path()).await,
+            Checkpoint { cursor: Some(next) }
+        );
+    }
+
+    #[tokio::test]
+    async fn resumes_from_last_record() {
+        let addr = next_addr();
+        let requests = serve(
+            addr,
+            vec![(
+                "/logs?from=1&take=2",
+                Response::new(Body::from(
+                    r#"[{"id":2,"text":"two"},{"id":3,"text":"three"}]"#,
+                )),
+            )],
+        );
+
+        let data_dir = tempdir().unwrap();
+        save_checkpoint(
+            data_dir.path(),
+            &Checkpoint {
+                cursor: Some("1".into()),
+            },
+        )
+        .await
+        .unwrap();
+
+        let pagination = Pagination::LastRecord {
+            field: "id".into(),
+            param: Some("from".into()),
+            cursor_params: vec![("take".into(), "2".into())],
+            initial_params: vec![],
+        };
+        let events = poll_once(spec(addr, "/logs", pagination), data_dir.path()).await;
+
+        assert_eq!(messages(&events), vec!["two", "three"]);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec!["/logs?from=1&take=2", "/logs?from=3&take=2"]
+        );
+        assert_eq!(
+            load_checkpoint(data_dir.path()).await,
+            Checkpoint {
+                cursor: Some("3".into())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_checkpoint_when_downstream_is_closed() {
+        let addr = next_addr();
+        serve(
+            addr,
+            vec![(
+                "/logs?from=1",
+                Response::new(Body::from(r#"[{"id":2,"text":"two"}]"#)),
+            )],
+        );
+
+        let data_dir = tempdir().unwrap();
+        let checkpoint = Checkpoint {
+            cursor: Some("1".into()),
+        };
+        save_checkpoint(data_dir.path(), &checkpoint).await.unwrap();
+
+        let pagination = Pagination::LastRecord {
+            field: "id".into(),
+            param: Some("from".into()),
+            cursor_params: vec![],
+            initial_params: vec![],
+        };
+        let poller = ApiPoller::new(
+            HttpClient::new(None).unwrap(),
+            spec(addr, "/logs", pagination),
+            None,
+            data_dir.path().to_path_buf(),
+        );
+        let (tx, rx) = Pipeline::new_test();
+        drop(rx);
+        let mut out = tx.sink_map_err(|_| ()).sink_compat();
+        let mut current = load_checkpoint(data_dir.path()).await;
+        let result = poller
+            .poll(&mut current, &mut out, &ShutdownSignal::noop())
+            .await;
+
+        assert!(matches!(result, Err(PollerError::Forward)));
+        assert_eq!(load_checkpoint(data_dir.path()).await, checkpoint);
+    }
+
+    #[tokio::test]
+    async fn stops_waiting_for_rate_limits_on_shutdown() {
+        let addr = next_addr();
+        serve(
+            addr,
+            vec![(
+                "/logs",
+                Response::builder()
+                    .status(StatusCode::TOO_MANY_REQUESTS)
+                    .header("retry-after", "300")
+                    .body(Body::empty())
+                    .unwrap(),
+            )],
+        );
+
+        let data_dir = tempdir().unwrap();
+        let poller = ApiPoller::new(
+            HttpClient::new(None).unwrap(),
+            spec(addr, "/logs", Pagination::None),
+            None,
+            data_dir.path().to_path_buf(),
+        );
+        let (tx, _rx) = Pipeline::new_test();
+        let mut out = tx.sink_map_err(|_| ()).sink_compat();
+        let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+        drop(trigger);
+
+        let mut checkpoint = Checkpoint::default();
+        let poll = poller.poll(&mut checkpoint, &mut out, &shutdown);
+        let result = time::timeout(Duration::from_secs(5), poll).await;
+        assert!(matches!(result, Ok(Ok(()))));
+    }
+
+    #[tokio::test]
+    async fn follows_page_tokens() {
+        let addr = next_addr();
+        let requests = serve(
+            addr,
+            vec![
+                (
+                    "/activity?startTime=a",
+                    Response::new(Body::from(
+                        r#"{"items":[{"id":{"time":"c"},"text":"c"},{"id":{"time":"b"},"text":"b"}],"nextPageToken":"next"}"#,
+                    )),
+                ),
+                (
+                    "/activity?startTime=a&pageToken=next",
+                    Response::new(Body::from(r#"{"items":[{"id":{"time":"a"},"text":"a"}]}"#)),
+                ),
+            ],
+        );
+
+        let data_dir = tempdir().unwrap();
+        save_checkpoint(
+            data_dir.path(),
+            &Checkpoint {
+                cursor: Some("a".into()),
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut spec = spec(
+            addr,
+            "/activity",
+            Pagination::PageToken {
+                token_field: "nextPageToken".into(),
+                token_param: Some("pageToken".into()),
+                field: Some("id.time".into()),
+                param: Some("startTime".into()),
+            },
+        );
+        spec.records = JsonPath::parse("$.items").unwrap();
+        let events = poll_once(spec, data_dir.path()).await;
+
+        // The record at the checkpoint was emitted by the previous poll.
+        assert_eq!(messages(&events), vec!["c", "b"]);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                "/activity?startTime=a",
+                "/activity?startTime=a&pageToken=next"
+            ]
+        );
+        assert_eq!(
+            load_checkpoint(data_dir.path()).await,
+            Checkpoint {
+                cursor: Some("c".into())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn renders_url_templates() {
+        let addr = next_addr();
+        let requests = serve(
+            addr,
+            vec![(
+                "/events?limit=10",
+                Response::new(Body::from(
+                    r#"{"data":[{"id":{"seq":"7"},"text":"seven"}],"meta":{}}"#,
+                )),
+            )],
+        );
+
+        let data_dir = tempdir().unwrap();
+        let mut spec = spec(
+            addr,
+            "/events?after={{ cursor }}&limit=10",
+            Pagination::LastRecord {
+                field: "id.seq".into(),
+                param: None,
+                cursor_params: vec![],
+                initial_params: vec![],
+            },
+        );
+        spec.records = JsonPath::parse("$.data[*]").unwrap();
+        let events = poll_once(spec, data_dir.path()).await;
+
+        assert_eq!(messages(&events), vec!["seven"]);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec!["/events?limit=10", "/events?after=7&limit=10"]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetches_single_page() {
+        let addr = next_addr();
+        let requests = serve(
+            addr,
+            vec![(
+                "/status",
+                Response::builder()
+                    .header(
+                        "Link",
+                        format!("<http://{}/status?page=2>; rel=\"next\"", addr),
+                    )
+                    .body(Body::from(r#"[{"text":"ok"}]"#))
+                    .unwrap(),
+            )],
+        );
+
+        let data_dir = tempdir().unwrap();
+        let events = poll_once(spec(addr, "/status", Pagination::None), data_dir.path()).await;
+
+        assert_eq!(messages(&events), vec!["ok"]);
+        assert_eq!(*requests.lock().unwrap(), vec!["/status"]);
+    }
+
+    #[test]
+    fn parses_link_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            http::header::LINK,
+            "<https://example.com/a>; rel=\"self\", <https://example.com/b>; rel=\"next\""
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(next_link(&headers), Some("https://example.com/b".into()));
+        assert_eq!(next_link(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn computes_rate_limit_wait() {
+        let now = Utc::now();
+        let names = RateLimitHeaders::default();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rate-limit-remaining", "10".parse().unwrap());
+        headers.insert(
+            "x-rate-limit-reset",
+            (now.timestamp() + 30).to_string().parse().unwrap(),
+        );
+        assert_eq!(rate_limit_wait(&headers, &names, now), None);
+
+        headers.insert("x-rate-limit-remaining", "0".parse().unwrap());
+        assert_eq!(
+            rate_limit_wait(&headers, &names, now),
+            Some(Duration::from_secs(30))
+        );
+
+        headers.insert("x-rate-limit-reset", "20".parse().unwrap());
+        assert_eq!(
+            rate_limit_wait(&headers, &names, now),
+            Some(Duration::from_secs(20))
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", "5".parse().unwrap());
+        assert_eq!(
+            rate_limit_wait(&headers, &names, now),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn looks_up_paths() {
+        let value: JsonValue = serde_json::from_str(r#"{"a":{"b":[{"c":1}]},"d":"e"}"#).unwrap();
+        assert_eq!(lookup_string(&value, "a.b.0.c"), Some("1".into()));
+        assert_eq!(lookup_string(&value, "d"), Some("e".into()));
+        assert_eq!(lookup(&value, "a.x"), None);
+    }
+}
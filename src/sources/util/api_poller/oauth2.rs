@@ -0,0 +1,172 @@
+//! OAuth 2.0 access tokens for polled APIs.
+
+use super::Authorizer;
+use crate::http::{Auth, HttpClient};
+use http::{header, HeaderValue, Request, StatusCode};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+use url::form_urlencoded;
+
+/// Refresh access tokens this long before they expire.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Snafu)]
+pub enum TokenError {
+    #[snafu(display("Failed to build access token request: {}", source))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Failed to request access token: {}", source))]
+    Request { source: crate::http::HttpError },
+    #[snafu(display("Failed to read access token response: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Access token request failed with status {}", status))]
+    Status { status: StatusCode },
+    #[snafu(display("Invalid access token response: {}", source))]
+    Parse { source: serde_json::Error },
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+pub struct AccessToken {
+    token: String,
+    expires_at: Option<Instant>,
+}
+
+impl AccessToken {
+    fn is_fresh(&self) -> bool {
+        self.expires_at.map_or(true, |expires_at| {
+            Instant::now() + REFRESH_MARGIN < expires_at
+        })
+    }
+}
+
+/// Request an access token from an authorization server with the given form
+/// encoded `body`.
+pub async fn request_token(
+    client: &HttpClient,
+    token_url: &str,
+    body: String,
+    auth: Option<&Auth>,
+) -> Result<AccessToken, TokenError> {
+    let mut request = Request::post(token_url)
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(header::ACCEPT, "application/json")
+        .body(Body::from(body))
+        .context(BuildRequest)?;
+    if let Some(auth) = auth {
+        auth.apply(&mut request);
+    }
+
+    let response = client.send(request).await.context(Request)?;
+    let (parts, body) = response.into_parts();
+    if !parts.status.is_success() {
+        return Err(TokenError::Status {
+            status: parts.status,
+        });
+    }
+    let body = hyper::body::to_bytes(body).await.context(ReadBody)?;
+    let response: TokenResponse = serde_json::from_slice(&body).context(Parse)?;
+
+    Ok(AccessToken {
+        token: response.access_token,
+        expires_at: response
+            .expires_in
+            .map(|expires_in| Instant::now() + Duration::from_secs(expires_in)),
+    })
+}
+
+/// Caches an access token until shortly before it expires.
+#[derive(Default)]
+pub struct TokenCache {
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl TokenCache {
+    /// Add the cached access token to `request`, calling `fetch` for a new
+    /// one if there is no fresh token.
+    pub async fn authorize<F, Fut>(
+        &self,
+        request: &mut Request<Body>,
+        fetch: F,
+    ) -> crate::Result<()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = crate::Result<AccessToken>>,
+    {
+        let mut token = self.token.lock().await;
+        if !token.as_ref().map_or(false, AccessToken::is_fresh) {
+            *token = Some(fetch().await?);
+        }
+        let token = token.as_ref().expect("token was just fetched");
+
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token.token))?,
+        );
+        Ok(())
+    }
+}
+
+/// Authorizes requests with tokens obtained through the OAuth 2.0 client
+/// credentials grant.
+pub struct ClientCredentials {
+    client: HttpClient,
+    token_url: String,
+    auth: Auth,
+    scopes: Vec<String>,
+    cache: TokenCache,
+}
+
+impl ClientCredentials {
+    pub fn new(
+        client: HttpClient,
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        scopes: Vec<String>,
+    ) -> Self {
+        Self {
+            client,
+            token_url,
+            auth: Auth::Basic {
+                user: client_id,
+                password: client_secret,
+            },
+            scopes,
+            cache: TokenCache::default(),
+        }
+    }
+
+    async fn fetch_token(&self) -> crate::Result<AccessToken> {
+        let mut body = form_urlencoded::Serializer::new(String::new());
+        body.append_pair("grant_type", "client_credentials");
+        if !self.scopes.is_empty() {
+            body.append_pair("scope", &self.scopes.join(" "));
+        }
+
+        let token = request_token(
+            &self.client,
+            &self.token_url,
+            body.finish(),
+            Some(&self.auth),
+        )
+        .await?;
+        Ok(token)
+    }
+}
+
+#[async_trait::async_trait]
+impl Authorizer for ClientCredentials {
+    async fn authorize(&self, request: &mut Request<Body>) -> crate::Result<()> {
+        self.cache.authorize(request, || self.fetch_token()).await
+    }
+}
@@ -0,0 +1,164 @@
+//! Request URLs with `{{ cursor }}` and `{{ page_token }}` placeholders that
+//! are filled in before every request.
+
+use snafu::Snafu;
+use url::{form_urlencoded, Url};
+
+const PLACEHOLDERS: &[&str] = &["cursor", "page_token"];
+
+#[derive(Debug, Snafu)]
+pub enum TemplateError {
+    #[snafu(display("Unknown placeholder {{{{ {} }}}} in URL template, expected one of: {}", name, PLACEHOLDERS.join(", ")))]
+    UnknownPlaceholder { name: String },
+    #[snafu(display("Unclosed placeholder in URL template {:?}", template))]
+    UnclosedPlaceholder { template: String },
+    #[snafu(display("Invalid URL template {:?}: {}", template, source))]
+    InvalidUrl {
+        template: String,
+        source: url::ParseError,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct UrlTemplate {
+    template: String,
+    placeholders: Vec<&'static str>,
+}
+
+impl UrlTemplate {
+    pub fn new(template: impl Into<String>) -> Result<Self, TemplateError> {
+        let template = template.into();
+        let mut placeholders = Vec::new();
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find("{{") {
+            let end =
+                rest[start..]
+                    .find("}}")
+                    .ok_or_else(|| TemplateError::UnclosedPlaceholder {
+                        template: template.clone(),
+                    })?;
+            let name = rest[start + 2..start + end].trim();
+            match PLACEHOLDERS
+                .iter()
+                .find(|placeholder| **placeholder == name)
+            {
+                Some(placeholder) => placeholders.push(*placeholder),
+                None => return Err(TemplateError::UnknownPlaceholder { name: name.into() }),
+            }
+            rest = &rest[start + end + 2..];
+        }
+
+        let template = Self {
+            template,
+            placeholders,
+        };
+        template.render(None, None)?;
+        Ok(template)
+    }
+
+    /// Whether the template references the placeholder `name`.
+    pub fn uses(&self, name: &str) -> bool {
+        self.placeholders.contains(&name)
+    }
+
+    /// Fill in the placeholders. Query parameters referencing a missing value
+    /// are left out, such as `since={{ cursor }}` before the first request.
+    pub fn render(
+        &self,
+        cursor: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<Url, TemplateError> {
+        let url = if !self.placeholders.is_empty() {
+            let values = |name: &str| match name {
+                "cursor" => cursor,
+                "page_token" => page_token,
+                _ => None,
+            };
+            let (base, query) = match self.template.find('?') {
+                Some(i) => (&self.template[..i], Some(&self.template[i + 1..])),
+                None => (self.template.as_str(), None),
+            };
+
+            // Missing values in the path render as empty segments.
+            let mut url =
+                render(base, &|name: &str| Some(values(name).unwrap_or(""))).unwrap_or_default();
+            if let Some(query) = query {
+                let pairs = query
+                    .split('&')
+                    .filter_map(|pair| render(pair, &values))
+                    .collect::<Vec<_>>();
+                if !pairs.is_empty() {
+                    url.push('?');
+                    url.push_str(&pairs.join("&"));
+                }
+            }
+            url
+        } else {
+            self.template.clone()
+        };
+
+        Url::parse(&url).map_err(|source| TemplateError::InvalidUrl {
+            template: self.template.clone(),
+            source,
+        })
+    }
+}
+
+impl From<Url> for UrlTemplate {
+    fn from(url: Url) -> Self {
+        Self {
+            template: url.as_str().to_owned(),
+            placeholders: Vec::new(),
+        }
+    }
+}
+
+/// Replace the placeholders of `text` with their URL encoded values,
+/// returning `None` if any of them is missing.
+fn render<'a>(text: &str, values: &impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = start + rest[start..].find("}}")?;
+        let value = values(rest[start + 2..end].trim())?;
+        rendered.push_str(&rest[..start]);
+        rendered.extend(form_urlencoded::byte_serialize(value.as_bytes()));
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+    Some(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders() {
+        let template = UrlTemplate::new(
+            "https://example.com/events?since={{ cursor }}&limit=100&page={{page_token}}",
+        )
+        .unwrap();
+        assert!(template.uses("cursor"));
+        assert!(template.uses("page_token"));
+
+        assert_eq!(
+            template.render(None, None).unwrap().as_str(),
+            "https://example.com/events?limit=100"
+        );
+        assert_eq!(
+            template
+                .render(Some("2021-01-01T00:00:00+00:00"), Some("2"))
+                .unwrap()
+                .as_str(),
+            "https://example.com/events?since=2021-01-01T00%3A00%3A00%2B00%3A00&limit=100&page=2"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        assert!(UrlTemplate::new("https://example.com/?since={{ since }}").is_err());
+        assert!(UrlTemplate::new("https://example.com/?since={{ cursor").is_err());
+        assert!(UrlTemplate::new("example.com/{{ cursor }}").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use super::TimeZone;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where the timestamps of events without one in their message come from.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NowSource {
+    /// The clock of the host Vector runs on, when the event is received.
+    System,
+    /// The timestamp of the events is left unset.
+    #[serde(rename = "none")]
+    Unset,
+}
+
+impl Default for NowSource {
+    fn default() -> Self {
+        Self::System
+    }
+}
+
+impl NowSource {
+    /// The timestamp of events without one in their message.
+    pub fn now(self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::System => Some(Utc::now()),
+            Self::Unset => None,
+        }
+    }
+}
+
+/// How a source resolves the timestamps of its events.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Clock {
+    #[serde(default)]
+    pub timezone: TimeZone,
+    #[serde(default)]
+    pub now_source: NowSource,
+}
+
+impl Clock {
+    /// The timestamp of events without one in their message.
+    pub fn now(&self) -> Option<DateTime<Utc>> {
+        self.now_source.now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Tz;
+
+    #[test]
+    fn parses_time_zones() {
+        assert_eq!(TimeZone::parse("local"), Ok(TimeZone::Local));
+        assert_eq!(
+            TimeZone::parse("America/New_York"),
+            Ok(TimeZone::Named(Tz::America__New_York))
+        );
+        assert!(TimeZone::parse("Mars/Olympus_Mons").is_err());
+
+        let clock: Clock = toml::from_str(r#"timezone = "Europe/Berlin""#).unwrap();
+        assert_eq!(clock.timezone, TimeZone::Named(Tz::Europe__Berlin));
+        assert_eq!(clock.now_source, NowSource::System);
+        assert!(toml::from_str::<Clock>(r#"timezone = "CEST+2""#).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     event::Event,
+    http::CorsConfig,
     internal_events::{HTTPBadRequest, HTTPEventsReceived},
     shutdown::ShutdownSignal,
     tls::{MaybeTlsSettings, TlsConfig},
@@ -17,7 +18,7 @@ use warp::{
     filters::BoxedFilter,
     http::{HeaderMap, StatusCode},
     reject::Rejection,
-    Filter,
+    Filter, Reply,
 };
 
 #[cfg(any(feature = "sources-http", feature = "sources-logplex"))]
@@ -134,27 +135,34 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         query_parameters: HashMap<String, String>,
     ) -> Result<Vec<Event>, ErrorMessage>;
 
+    /// The OpenAPI document describing the accepted requests, served on
+    /// `GET <path>/openapi.json` if any.
+    fn openapi(&self) -> Option<serde_json::Value> {
+        None
+    }
+
     fn run(
         self,
         address: SocketAddr,
-        path: &'static str,
+        path: &str,
         tls: &Option<TlsConfig>,
         auth: &Option<HttpSourceAuthConfig>,
+        cors: &Option<CorsConfig>,
         out: Pipeline,
         shutdown: ShutdownSignal,
     ) -> crate::Result<crate::sources::Source> {
         let tls = MaybeTlsSettings::from_config(tls, true)?;
         let auth = HttpSourceAuth::try_from(auth.as_ref())?;
+        let cors = cors
+            .as_ref()
+            .map(|cors| cors.build(&["POST", "GET"]))
+            .transpose()?;
+        let path = path.to_owned();
+        let openapi = self.openapi();
         Ok(Box::pin(async move {
             let span = crate::trace::current_span();
 
-            let mut filter: BoxedFilter<()> = warp::post().boxed();
-            if !path.is_empty() && path != "/" {
-                for s in path.split('/') {
-                    filter = filter.and(warp::path(s)).boxed();
-                }
-            }
-            let svc = filter
+            let svc = path_filter(warp::post().boxed(), &path)
                 .and(warp::path::end())
                 .and(warp::header::optional::<String>("authorization"))
                 .and(warp::header::headers_cloned())
@@ -208,7 +216,18 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                 );
 
             let ping = warp::get().and(warp::path("ping")).map(|| "pong");
-            let routes = svc.or(ping).recover(|r: Rejection| async move {
+            let openapi = path_filter(warp::get().boxed(), &path)
+                .and(warp::path("openapi.json"))
+                .and(warp::path::end())
+                .and_then(move || {
+                    let openapi = openapi.clone();
+                    async move {
+                        openapi
+                            .map(|document| warp::reply::json(&document))
+                            .ok_or_else(warp::reject::not_found)
+                    }
+                });
+            let routes = svc.or(ping).or(openapi).recover(|r: Rejection| async move {
                 if let Some(e_msg) = r.find::<ErrorMessage>() {
                     let json = warp::reply::json(e_msg);
                     Ok(warp::reply::with_status(
@@ -221,6 +240,10 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                     Err(r)
                 }
             });
+            let routes = match cors {
+                Some(cors) => routes.with(cors).map(Reply::into_response).boxed(),
+                None => routes.map(Reply::into_response).boxed(),
+            };
 
             info!(message = "Building HTTP server.", address = %address);
 
@@ -237,3 +260,10 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         }))
     }
 }
+
+fn path_filter(mut filter: BoxedFilter<()>, path: &str) -> BoxedFilter<()> {
+    for s in path.split('/').filter(|s| !s.is_empty()) {
+        filter = filter.and(warp::path(s.to_owned())).boxed();
+    }
+    filter
+}
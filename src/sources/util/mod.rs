@@ -1,8 +1,16 @@
+#[cfg(feature = "sources-utils-api-poller")]
+pub mod api_poller;
+mod clock;
 #[cfg(feature = "sources-utils-http")]
 mod http;
 pub mod multiline_config;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+mod peer_certificate;
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+mod proxy_protocol;
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 mod tcp;
+mod timezone;
 #[cfg(all(unix, feature = "sources-socket"))]
 mod unix_datagram;
 #[cfg(all(unix, feature = "sources-utils-unix"))]
@@ -12,9 +20,13 @@ mod unix_stream;
 pub(crate) use self::http::add_query_parameters;
 #[cfg(feature = "sources-utils-http")]
 pub(crate) use self::http::{ErrorMessage, HttpSource, HttpSourceAuthConfig};
+pub use clock::{Clock, NowSource};
 pub use multiline_config::MultilineConfig;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+pub use proxy_protocol::ProxyProtocolError;
+#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 pub use tcp::{SocketListenAddr, TcpSource};
+pub use timezone::TimeZone;
 #[cfg(all(unix, feature = "sources-socket",))]
 pub use unix_datagram::build_unix_datagram_source;
 #[cfg(all(unix, feature = "sources-utils-unix",))]
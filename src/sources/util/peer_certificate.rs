@@ -0,0 +1,137 @@
+//! Details of the certificate clients authenticate with over mutual TLS, so
+//! that events can be authorized or routed by the identity of their sender.
+
+use crate::event::{LogEvent, Value};
+use openssl::{
+    hash::MessageDigest,
+    x509::{X509NameRef, X509Ref},
+};
+use std::net::IpAddr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerCertificate {
+    pub subject: String,
+    pub issuer: String,
+    pub subject_alt_names: Vec<String>,
+    /// The hex encoded SHA-256 digest of the certificate.
+    pub fingerprint: String,
+}
+
+impl PeerCertificate {
+    pub fn new(certificate: &X509Ref) -> Self {
+        let subject_alt_names = certificate
+            .subject_alt_names()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| {
+                        name.dnsname()
+                            .map(|dns| format!("DNS:{}", dns))
+                            .or_else(|| name.email().map(|email| format!("email:{}", email)))
+                            .or_else(|| name.uri().map(|uri| format!("URI:{}", uri)))
+                            .or_else(|| name.ipaddress().and_then(format_ip))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let fingerprint = certificate
+            .digest(MessageDigest::sha256())
+            .map(|digest| hex::encode(&*digest))
+            .unwrap_or_default();
+
+        Self {
+            subject: format_name(certificate.subject_name()),
+            issuer: format_name(certificate.issuer_name()),
+            subject_alt_names,
+            fingerprint,
+        }
+    }
+
+    pub fn insert_into(&self, log: &mut LogEvent) {
+        log.insert("client_metadata.certificate.subject", self.subject.clone());
+        log.insert("client_metadata.certificate.issuer", self.issuer.clone());
+        log.insert(
+            "client_metadata.certificate.subject_alt_names",
+            self.subject_alt_names
+                .iter()
+                .cloned()
+                .map(Value::from)
+                .collect::<Vec<_>>(),
+        );
+        log.insert(
+            "client_metadata.certificate.fingerprint_sha256",
+            self.fingerprint.clone(),
+        );
+    }
+}
+
+/// Formats the name as a comma separated list of its attributes, such as
+/// `C=US,O=Timber.io,CN=localhost`.
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry
+                .object()
+                .nid()
+                .short_name()
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|_| entry.object().to_string());
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn format_ip(bytes: &[u8]) -> Option<String> {
+    let ip = match bytes.len() {
+        4 => {
+            let mut octets = [0; 4];
+            octets.copy_from_slice(bytes);
+            IpAddr::from(octets)
+        }
+        16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(bytes);
+            IpAddr::from(octets)
+        }
+        _ => return None,
+    };
+    Some(format!("IP:{}", ip))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use openssl::x509::X509;
+
+    #[test]
+    fn reads_certificate_details() {
+        let certificate =
+            X509::from_pem(&std::fs::read("tests/data/localhost.crt").unwrap()).unwrap();
+        let peer = PeerCertificate::new(&certificate);
+
+        assert_eq!(peer.subject, "CN=localhost");
+        assert_eq!(
+            peer.issuer,
+            "C=US,ST=New York,L=Brooklyn,O=Timber.io,CN=Timber.io Vector Test CA"
+        );
+        assert!(peer.subject_alt_names.is_empty());
+        assert_eq!(peer.fingerprint.len(), 64);
+
+        let mut log = LogEvent::default();
+        peer.insert_into(&mut log);
+        assert_eq!(
+            log["client_metadata.certificate.subject"],
+            "CN=localhost".into()
+        );
+        assert_eq!(
+            log["client_metadata.certificate.fingerprint_sha256"],
+            peer.fingerprint.clone().into()
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Parsing of the [PROXY protocol] headers load balancers such as HAProxy and
+//! AWS NLB prepend to connections to pass on the address of the client.
+//!
+//! [PROXY protocol]: https://www.haproxy.org/download/2.3/doc/proxy-protocol.txt
+
+use crate::event::LogEvent;
+use snafu::{ResultExt, Snafu};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str,
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// Including the CRLF.
+const V1_MAX_LENGTH: usize = 107;
+const V2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+const V2_HEADER_LENGTH: usize = 16;
+
+#[derive(Debug, Snafu)]
+pub enum ProxyProtocolError {
+    #[snafu(display("Failed to read PROXY protocol header: {}", source))]
+    Read { source: std::io::Error },
+    #[snafu(display("Connection doesn't start with a PROXY protocol header"))]
+    MissingHeader,
+    #[snafu(display("Invalid PROXY protocol header: {}", reason))]
+    InvalidHeader { reason: &'static str },
+}
+
+/// The addresses of the original connection, which are unknown for health
+/// checks of the load balancer and connections that aren't over TCP.
+#[derive(Debug, Default, PartialEq)]
+pub struct ProxyHeader {
+    pub source: Option<SocketAddr>,
+    pub destination: Option<SocketAddr>,
+}
+
+impl ProxyHeader {
+    pub fn insert_into(&self, log: &mut LogEvent) {
+        if let Some(source) = self.source {
+            log.insert("client_metadata.source_ip", source.ip().to_string());
+            log.insert("client_metadata.source_port", source.port() as i64);
+        }
+        if let Some(destination) = self.destination {
+            log.insert(
+                "client_metadata.destination_ip",
+                destination.ip().to_string(),
+            );
+            log.insert(
+                "client_metadata.destination_port",
+                destination.port() as i64,
+            );
+        }
+    }
+}
+
+/// Read either version of the header, without reading past it.
+pub async fn read_header<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<ProxyHeader, ProxyProtocolError> {
+    let mut header = vec![0; V1_PREFIX.len()];
+    reader.read_exact(&mut header).await.context(Read)?;
+
+    if header == V1_PREFIX {
+        // The header is short, and mustn't be read past.
+        while !header.ends_with(b"\r\n") {
+            if header.len() == V1_MAX_LENGTH {
+                return Err(ProxyProtocolError::InvalidHeader {
+                    reason: "line too long",
+                });
+            }
+            header.push(reader.read_u8().await.context(Read)?);
+        }
+        parse_v1(&header)
+    } else if V2_SIGNATURE.starts_with(&header) {
+        header.resize(V2_HEADER_LENGTH, 0);
+        reader
+            .read_exact(&mut header[V1_PREFIX.len()..])
+            .await
+            .context(Read)?;
+        let length = u16::from_be_bytes([header[14], header[15]]) as usize;
+        header.resize(V2_HEADER_LENGTH + length, 0);
+        reader
+            .read_exact(&mut header[V2_HEADER_LENGTH..])
+            .await
+            .context(Read)?;
+        parse_v2(&header)
+    } else {
+        Err(ProxyProtocolError::MissingHeader)
+    }
+}
+
+fn parse_v1(header: &[u8]) -> Result<ProxyHeader, ProxyProtocolError> {
+    let invalid = |reason| ProxyProtocolError::InvalidHeader { reason };
+
+    let line = str::from_utf8(&header[V1_PREFIX.len()..header.len() - 2])
+        .map_err(|_| invalid("not ASCII"))?;
+    let mut fields = line.split(' ');
+    match fields.next() {
+        Some("TCP4") | Some("TCP6") => (),
+        Some("UNKNOWN") => return Ok(ProxyHeader::default()),
+        _ => return Err(invalid("unknown protocol")),
+    }
+
+    let mut field = |reason| fields.next().ok_or_else(|| invalid(reason));
+    let source_ip = field("missing source address")?;
+    let destination_ip = field("missing destination address")?;
+    let source_port = field("missing source port")?;
+    let destination_port = field("missing destination port")?;
+
+    let address = |ip: &str, port: &str| -> Result<SocketAddr, ProxyProtocolError> {
+        let ip = ip
+            .parse::<IpAddr>()
+            .map_err(|_| invalid("invalid address"))?;
+        let port = port.parse::<u16>().map_err(|_| invalid("invalid port"))?;
+        Ok(SocketAddr::new(ip, port))
+    };
+    Ok(ProxyHeader {
+        source: Some(address(source_ip, source_port)?),
+        destination: Some(address(destination_ip, destination_port)?),
+    })
+}
+
+fn parse_v2(header: &[u8]) -> Result<ProxyHeader, ProxyProtocolError> {
+    let invalid = |reason| ProxyProtocolError::InvalidHeader { reason };
+
+    if header[..V2_SIGNATURE.len()] != *V2_SIGNATURE {
+        return Err(invalid("invalid signature"));
+    }
+    if header[12] >> 4 != 2 {
+        return Err(invalid("unsupported version"));
+    }
+    match header[12] & 0x0f {
+        // LOCAL, such as the health checks of the load balancer.
+        0 => return Ok(ProxyHeader::default()),
+        1 => (),
+        _ => return Err(invalid("unknown command")),
+    }
+
+    let addresses = &header[V2_HEADER_LENGTH..];
+    let port = |bytes: &[u8]| u16::from_be_bytes([bytes[0], bytes[1]]);
+    // Only TCP and UDP over IPv4 and IPv6 have addresses of interest.
+    match header[13] >> 4 {
+        1 if addresses.len() >= 12 => {
+            let ip = |bytes: &[u8]| Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+            Ok(ProxyHeader {
+                source: Some(SocketAddr::new(
+                    ip(&addresses[0..4]).into(),
+                    port(&addresses[8..10]),
+                )),
+                destination: Some(SocketAddr::new(
+                    ip(&addresses[4..8]).into(),
+                    port(&addresses[10..12]),
+                )),
+            })
+        }
+        2 if addresses.len() >= 36 => {
+            let ip = |bytes: &[u8]| {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(bytes);
+                Ipv6Addr::from(octets)
+            };
+            Ok(ProxyHeader {
+                source: Some(SocketAddr::new(
+                    ip(&addresses[0..16]).into(),
+                    port(&addresses[32..34]),
+                )),
+                destination: Some(SocketAddr::new(
+                    ip(&addresses[16..32]).into(),
+                    port(&addresses[34..36]),
+                )),
+            })
+        }
+        1 | 2 => Err(invalid("addresses too short")),
+        _ => Ok(ProxyHeader::default()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn read(mut data: &[u8]) -> (Result<ProxyHeader, ProxyProtocolError>, &[u8]) {
+        let header = read_header(&mut data).await;
+        (header, data)
+    }
+
+    #[tokio::test]
+    async fn reads_v1_header() {
+        let (header, rest) = read(b"PROXY TCP4 192.168.0.1 10.0.0.1 56324 514\r\nmessage\n").await;
+        assert_eq!(
+            header.unwrap(),
+            ProxyHeader {
+                source: Some("192.168.0.1:56324".parse().unwrap()),
+                destination: Some("10.0.0.1:514".parse().unwrap()),
+            }
+        );
+        assert_eq!(rest, b"message\n");
+
+        let (header, rest) = read(b"PROXY UNKNOWN\r\nmessage\n").await;
+        assert_eq!(header.unwrap(), ProxyHeader::default());
+        assert_eq!(rest, b"message\n");
+    }
+
+    #[tokio::test]
+    async fn reads_v2_header() {
+        let mut data = V2_SIGNATURE.to_vec();
+        // PROXY over TCP over IPv6.
+        data.extend_from_slice(&[0x21, 0x21, 0, 36]);
+        data.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend_from_slice(&56324u16.to_be_bytes());
+        data.extend_from_slice(&514u16.to_be_bytes());
+        data.extend_from_slice(b"message\n");
+
+        let (header, rest) = read(&data).await;
+        assert_eq!(
+            header.unwrap(),
+            ProxyHeader {
+                source: Some("[2001:db8::1]:56324".parse().unwrap()),
+                destination: Some("[2001:db8::2]:514".parse().unwrap()),
+            }
+        );
+        assert_eq!(rest, b"message\n");
+
+        let mut data = V2_SIGNATURE.to_vec();
+        // LOCAL, without addresses.
+        data.extend_from_slice(&[0x20, 0x00, 0, 0]);
+        let (header, rest) = read(&data).await;
+        assert_eq!(header.unwrap(), ProxyHeader::default());
+        assert!(rest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_header() {
+        let (header, _) = read(b"<13>Feb 13 20:07:26 host app: message\n").await;
+        assert!(matches!(header, Err(ProxyProtocolError::MissingHeader)));
+
+        let (header, _) = read(b"PROXY TCP4 192.168.0.1\r\n").await;
+        assert!(matches!(
+            header,
+            Err(ProxyProtocolError::InvalidHeader { .. })
+        ));
+    }
+}
@@ -1,6 +1,9 @@
+use super::{peer_certificate::PeerCertificate, proxy_protocol};
 use crate::{
     config::Resource,
-    internal_events::{ConnectionOpen, OpenGauge, TcpSocketConnectionError},
+    internal_events::{
+        ConnectionOpen, OpenGauge, ProxyProtocolHeaderError, TcpSocketConnectionError,
+    },
     shutdown::ShutdownSignal,
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
@@ -70,6 +73,7 @@ pub trait TcpSource: Clone + Send + Sync + 'static {
         keepalive: Option<TcpKeepaliveConfig>,
         shutdown_timeout_secs: u64,
         tls: MaybeTlsSettings,
+        proxy_protocol: bool,
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<crate::sources::Source> {
@@ -124,7 +128,6 @@ pub trait TcpSource: Clone + Send + Sync + 'static {
 
                         let peer_addr = socket.peer_addr().ip().to_string();
                         let span = info_span!("connection", %peer_addr);
-                        let host = Bytes::from(peer_addr);
 
                         let tripwire = tripwire
                             .map(move |_| {
@@ -143,7 +146,13 @@ pub trait TcpSource: Clone + Send + Sync + 'static {
                                 connection_gauge.open(|count| emit!(ConnectionOpen { count }));
 
                             let fut = handle_stream(
-                                shutdown, socket, keepalive, source, tripwire, host, out,
+                                shutdown,
+                                socket,
+                                keepalive,
+                                proxy_protocol,
+                                source,
+                                tripwire,
+                                out,
                             );
                             tokio::spawn(
                                 fut.map(move |()| drop(open_token)).instrument(span.clone()),
@@ -161,11 +170,38 @@ async fn handle_stream(
     mut shutdown: ShutdownSignal,
     mut socket: MaybeTlsIncomingStream<TcpStream>,
     keepalive: Option<TcpKeepaliveConfig>,
+    proxy_protocol: bool,
     source: impl TcpSource,
     tripwire: BoxFuture<'static, ()>,
-    host: Bytes,
     out: impl Sink<SinkItem = Event, SinkError = ()> + Send + 'static,
 ) {
+    let mut host = Bytes::from(socket.peer_addr().ip().to_string());
+
+    // The header is sent ahead of any TLS handshake.
+    let mut proxy_header = None;
+    if proxy_protocol {
+        let stream = socket
+            .raw_mut()
+            .expect("Nothing has been read from the socket yet.");
+        tokio::select! {
+            result = proxy_protocol::read_header(stream) => match result {
+                Ok(header) => {
+                    if let Some(source) = header.source {
+                        host = Bytes::from(source.ip().to_string());
+                    }
+                    proxy_header = Some(header);
+                }
+                Err(error) => {
+                    emit!(ProxyProtocolHeaderError { error });
+                    return;
+                }
+            },
+            _ = &mut shutdown => {
+                return;
+            }
+        };
+    }
+
     tokio::select! {
         result = socket.handshake() => {
             if let Err(error) = result {
@@ -178,6 +214,10 @@ async fn handle_stream(
         }
     };
 
+    let peer_certificate = socket
+        .peer_certificate()
+        .map(|certificate| PeerCertificate::new(&certificate));
+
     if let Some(keepalive) = keepalive {
         if let Err(error) = socket.set_keepalive(keepalive) {
             warn!(message = "Failed configuring TCP keepalive.", %error);
@@ -218,7 +258,17 @@ async fn handle_stream(
     .filter_map(move |frame| ready(match frame {
         Ok(frame) => {
             let host = host.clone();
-            source.build_event(frame, host).map(Ok)
+            source.build_event(frame, host).map(|mut event| {
+                if let Event::Log(log) = &mut event {
+                    if let Some(header) = &proxy_header {
+                        header.insert_into(log);
+                    }
+                    if let Some(certificate) = &peer_certificate {
+                        certificate.insert_into(log);
+                    }
+                }
+                Ok(event)
+            })
         }
         Err(error) => {
             warn!(message = "Failed to read data from TCP source.", %error);
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone as _, Utc};
+use chrono_tz::Tz;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The time zone of timestamps parsed from formats without zone information.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeZone {
+    /// The time zone of the host Vector runs on.
+    Local,
+    /// A time zone of the IANA database, such as `Europe/Berlin`.
+    Named(Tz),
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::Local
+    }
+}
+
+impl TimeZone {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "local" => Ok(Self::Local),
+            name => name.parse().map(Self::Named),
+        }
+    }
+
+    /// The timestamp of a wall-clock time of the zone. Wall-clock times
+    /// repeated when clocks are set back resolve to the earliest, and those
+    /// skipped when clocks are set forward resolve to `None`.
+    pub fn localize(self, datetime: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Local => Local
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(Into::into),
+            Self::Named(tz) => tz
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&Utc)),
+        }
+    }
+
+    /// The current wall-clock time of the zone.
+    pub fn now(self) -> NaiveDateTime {
+        match self {
+            Self::Local => Local::now().naive_local(),
+            Self::Named(tz) => Utc::now().with_timezone(&tz).naive_local(),
+        }
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => f.write_str("local"),
+            Self::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+impl Serialize for TimeZone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeZone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::parse(&name).map_err(|_| {
+            de::Error::custom(format!(
+                "unknown time zone {:?}, expected \"local\" or an IANA time zone name",
+                name
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone as _};
+
+    #[test]
+    fn localizes_wall_clock_times() {
+        let berlin = TimeZone::Named(Tz::Europe__Berlin);
+        let datetime = NaiveDate::from_ymd(2020, 7, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            berlin.localize(datetime),
+            Some(Utc.ymd(2020, 7, 1).and_hms(10, 0, 0))
+        );
+
+        // Skipped when clocks were set forward.
+        let skipped = NaiveDate::from_ymd(2020, 3, 29).and_hms(2, 30, 0);
+        assert_eq!(berlin.localize(skipped), None);
+
+        // Repeated when clocks were set back.
+        let repeated = NaiveDate::from_ymd(2020, 10, 25).and_hms(2, 30, 0);
+        assert_eq!(
+            berlin.localize(repeated),
+            Some(Utc.ymd(2020, 10, 25).and_hms(0, 30, 0))
+        );
+    }
+}
@@ -8,7 +8,8 @@ use crate::{
 };
 use bytes::{Bytes, BytesMut};
 use futures::{compat::Sink01CompatExt, SinkExt};
-use std::path::PathBuf;
+use nix::sys::socket::{setsockopt, sockopt};
+use std::{os::unix::io::AsRawFd, path::PathBuf};
 use tokio::net::UnixDatagram;
 use tokio_util::codec::Decoder;
 use tracing::field;
@@ -20,6 +21,7 @@ use tracing::field;
 pub fn build_unix_datagram_source<D>(
     listen_path: PathBuf,
     max_length: usize,
+    receive_buffer_bytes: Option<usize>,
     host_key: String,
     mut decoder: D,
     mut shutdown: ShutdownSignal,
@@ -37,6 +39,15 @@ where
     Box::pin(async move {
         let mut socket =
             UnixDatagram::bind(&listen_path).expect("Failed to bind to datagram socket");
+        if let Some(receive_buffer_bytes) = receive_buffer_bytes {
+            // Writers block, or drop their datagrams when non-blocking, while
+            // the buffer is full, so bursts may need more than the default.
+            if let Err(error) =
+                setsockopt(socket.as_raw_fd(), sockopt::RcvBuf, &receive_buffer_bytes)
+            {
+                warn!(message = "Failed configuring receive buffer size on unix datagram socket.", %error);
+            }
+        }
         info!(message = "Listening.", path = ?listen_path, r#type = "unix_datagram");
 
         let mut buf = BytesMut::with_capacity(max_length);
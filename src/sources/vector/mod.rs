@@ -0,0 +1,359 @@
+//! Receives events from the `vector` sink of other Vector instances. Version 1
+//! of the protocol streams length delimited events over TCP, version 2 pushes
+//! batches over gRPC and acknowledges them once they are passed on.
+
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, Resource, SourceConfig, SourceDescription},
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use serde::{Deserialize, Serialize};
+
+pub mod v1;
+pub mod v2;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum VectorConfig {
+    V1(v1::VectorConfig),
+    V2(v2::VectorConfig),
+}
+
+inventory::submit! {
+    SourceDescription::new::<VectorConfig>("vector")
+}
+
+impl GenerateConfig for VectorConfig {
+    fn generate_config() -> toml::Value {
+        v1::VectorConfig::generate_config()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "vector")]
+impl SourceConfig for VectorConfig {
+    async fn build(
+        &self,
+        name: &str,
+        globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        match self {
+            VectorConfig::V1(v1) => v1.build(name, globals, shutdown, out).await,
+            VectorConfig::V2(v2) => v2.build(shutdown, out).await,
+        }
+    }
+
+    fn output_type(&self) -> DataType {
+        match self {
+            VectorConfig::V1(v1) => v1.output_type(),
+            VectorConfig::V2(v2) => v2.output_type(),
+        }
+    }
+
+    fn source_type(&self) -> &'static str {
+        "vector"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        match self {
+            VectorConfig::V1(v1) => v1.resources(),
+            VectorConfig::V2(v2) => v2.resources(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<VectorConfig>();
+        crate::test_util::test_generate_config::<v2::VectorConfig>();
+    }
+
+    #[test]
+    fn parses_versions() {
+        let config: VectorConfig = toml::from_str(r#"address = "0.0.0.0:9000""#).unwrap();
+        assert!(matches!(config, VectorConfig::V1(_)));
+
+        let config: VectorConfig = toml::from_str(
+            r#"
+            version = "1"
+            address = "0.0.0.0:9000"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config, VectorConfig::V1(_)));
+
+        let config: VectorConfig = toml::from_str(
+            r#"
+            version = "2"
+            address = "0.0.0.0:6000"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config, VectorConfig::V2(_)));
+
+        toml::from_str::<VectorConfig>(
+            r#"
+            version = "3"
+            address = "0.0.0.0:6000"
+            "#,
+        )
+        .unwrap_err();
+    }
+}
+
+#[cfg(feature = "sinks-vector")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        config::{SinkConfig, SinkContext},
+        event::{
+            metric::{MetricKind, MetricValue},
+            Metric,
+        },
+        sinks::vector::{
+            v1::VectorSinkConfig as V1SinkConfig,
+            v2::{CompressionAlgorithm, CompressionConfig, VectorSinkConfig as V2SinkConfig},
+            VectorSinkConfig,
+        },
+        test_util::{collect_ready, next_addr, wait_for_tcp},
+        tls::{TlsConfig, TlsOptions},
+        Event,
+    };
+    use futures::stream;
+    use std::net::SocketAddr;
+    use tokio::time::{delay_for, Duration};
+
+    async fn stream_test(addr: SocketAddr, source: VectorConfig, sink: VectorSinkConfig) {
+        let events = vec![
+            Event::from("test"),
+            Event::from("events"),
+            Event::from("to roundtrip"),
+            Event::from("through"),
+            Event::from("the native"),
+            Event::from("sink"),
+            Event::from("and"),
+            Event::from("source"),
+            Event::Metric(Metric {
+                name: String::from("also test a metric"),
+                namespace: None,
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Absolute,
+                value: MetricValue::Counter { value: 1.0 },
+            }),
+        ];
+        roundtrip_test(addr, source, sink, events).await;
+    }
+
+    async fn roundtrip_test(
+        addr: SocketAddr,
+        source: VectorConfig,
+        sink: VectorSinkConfig,
+        events: Vec<Event>,
+    ) {
+        let (tx, rx) = Pipeline::new_test();
+
+        let server = source
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(server);
+        wait_for_tcp(addr).await;
+
+        let cx = SinkContext::new_test();
+        let (sink, _) = sink.build(cx).await.unwrap();
+
+        sink.run(stream::iter(events.clone())).await.unwrap();
+
+        delay_for(Duration::from_millis(50)).await;
+
+        let output = collect_ready(rx).await.unwrap();
+        assert_eq!(events, output);
+    }
+
+    fn tls_without_verification() -> TlsConfig {
+        TlsConfig {
+            enabled: Some(true),
+            options: TlsOptions {
+                verify_certificate: Some(false),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink() {
+        let addr = next_addr();
+        stream_test(
+            addr,
+            VectorConfig::V1(v1::VectorConfig::new(addr.into(), None, None)),
+            VectorSinkConfig::V1(V1SinkConfig {
+                version: None,
+                address: format!("localhost:{}", addr.port()),
+                keepalive: None,
+                tls: None,
+            }),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink_tls() {
+        let addr = next_addr();
+        stream_test(
+            addr,
+            VectorConfig::V1(v1::VectorConfig::new(
+                addr.into(),
+                None,
+                Some(TlsConfig::test_config()),
+            )),
+            VectorSinkConfig::V1(V1SinkConfig {
+                version: None,
+                address: format!("localhost:{}", addr.port()),
+                keepalive: None,
+                tls: Some(tls_without_verification()),
+            }),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink_v2() {
+        let addr = next_addr();
+        stream_test(
+            addr,
+            VectorConfig::V2(v2::VectorConfig::new(addr, None)),
+            VectorSinkConfig::V2(V2SinkConfig::new(
+                format!("localhost:{}", addr.port()),
+                false,
+                None,
+            )),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink_v2_compression() {
+        let addr = next_addr();
+        stream_test(
+            addr,
+            VectorConfig::V2(v2::VectorConfig::new(addr, None)),
+            VectorSinkConfig::V2(V2SinkConfig::new(
+                format!("localhost:{}", addr.port()),
+                true,
+                None,
+            )),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink_v2_zstd_delta_encoding() {
+        let addr = next_addr();
+        let mut events = Vec::new();
+        for i in 0..6 {
+            let mut event = Event::from(format!("event {}", i));
+            let log = event.as_mut_log();
+            log.insert("host", "edge-1");
+            log.insert("kubernetes.pod_name", format!("pod-{}", i / 2));
+            if i % 3 != 0 {
+                log.insert("status", 200);
+            }
+            events.push(event);
+            if i == 3 {
+                events.push(Event::Metric(Metric {
+                    name: String::from("interleaved metric"),
+                    namespace: None,
+                    timestamp: None,
+                    tags: None,
+                    kind: MetricKind::Absolute,
+                    value: MetricValue::Gauge { value: 1.0 },
+                }));
+            }
+        }
+
+        roundtrip_test(
+            addr,
+            VectorConfig::V2(v2::VectorConfig::new(addr, None)),
+            VectorSinkConfig::V2(V2SinkConfig {
+                compression: CompressionConfig::Algorithm(CompressionAlgorithm::Zstd),
+                delta_encoding: true,
+                ..V2SinkConfig::new(format!("localhost:{}", addr.port()), false, None)
+            }),
+            events,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink_v2_tls() {
+        let addr = next_addr();
+        stream_test(
+            addr,
+            VectorConfig::V2(v2::VectorConfig::new(addr, Some(TlsConfig::test_config()))),
+            VectorSinkConfig::V2(V2SinkConfig::new(
+                format!("localhost:{}", addr.port()),
+                false,
+                Some(tls_without_verification()),
+            )),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_works_with_vector_sink_v2_pre_shared_key() {
+        let addr = next_addr();
+        stream_test(
+            addr,
+            VectorConfig::V2(v2::VectorConfig {
+                pre_shared_key: Some("secret".into()),
+                ..v2::VectorConfig::new(addr, None)
+            }),
+            VectorSinkConfig::V2(V2SinkConfig {
+                pre_shared_key: Some("secret".into()),
+                ..V2SinkConfig::new(format!("localhost:{}", addr.port()), false, None)
+            }),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn vector_source_v2_rejects_other_keys() {
+        let addr = next_addr();
+        let (tx, _rx) = Pipeline::new_test();
+        let source = VectorConfig::V2(v2::VectorConfig {
+            pre_shared_key: Some("secret".into()),
+            ..v2::VectorConfig::new(addr, None)
+        });
+        let server = source
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(server);
+        wait_for_tcp(addr).await;
+
+        let sink = VectorSinkConfig::V2(V2SinkConfig {
+            pre_shared_key: Some("guess".into()),
+            ..V2SinkConfig::new(format!("localhost:{}", addr.port()), false, None)
+        });
+        let (_, healthcheck) = sink.build(SinkContext::new_test()).await.unwrap();
+        assert!(healthcheck.await.is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, Resource},
+    event::proto,
+    internal_events::{VectorEventReceived, VectorProtoDecodeError},
+    shutdown::ShutdownSignal,
+    sources::util::{SocketListenAddr, TcpSource},
+    tcp::TcpKeepaliveConfig,
+    tls::{MaybeTlsSettings, TlsConfig},
+    Event, Pipeline,
+};
+use bytes::{Bytes, BytesMut};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::LengthDelimitedCodec;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VectorConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    pub address: SocketListenAddr,
+    pub keepalive: Option<TcpKeepaliveConfig>,
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    tls: Option<TlsConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub enum Version {
+    #[serde(rename = "1")]
+    V1,
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+#[cfg(test)]
+impl VectorConfig {
+    pub fn new(
+        address: SocketListenAddr,
+        keepalive: Option<TcpKeepaliveConfig>,
+        tls: Option<TlsConfig>,
+    ) -> Self {
+        Self {
+            version: None,
+            address,
+            keepalive,
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            tls,
+        }
+    }
+}
+
+impl GenerateConfig for VectorConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            version: None,
+            address: SocketListenAddr::SocketAddr("0.0.0.0:9000".parse().unwrap()),
+            keepalive: None,
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+impl VectorConfig {
+    pub(super) async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<crate::sources::Source> {
+        let vector = VectorSource;
+        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        vector.run(
+            self.address,
+            self.keepalive,
+            self.shutdown_timeout_secs,
+            tls,
+            false,
+            shutdown,
+            out,
+        )
+    }
+
+    pub(super) fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    pub(super) fn resources(&self) -> Vec<Resource> {
+        vec![self.address.into()]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VectorSource;
+
+impl TcpSource for VectorSource {
+    type Error = std::io::Error;
+    type Decoder = LengthDelimitedCodec;
+
+    fn decoder(&self) -> Self::Decoder {
+        LengthDelimitedCodec::new()
+    }
+
+    fn build_event(&self, frame: BytesMut, _host: Bytes) -> Option<Event> {
+        let byte_size = frame.len();
+        match proto::EventWrapper::decode(frame).map(Event::from) {
+            Ok(event) => {
+                emit!(VectorEventReceived { byte_size });
+                Some(event)
+            }
+            Err(error) => {
+                emit!(VectorProtoDecodeError { error });
+                None
+            }
+        }
+    }
+}
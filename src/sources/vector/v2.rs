@@ -0,0 +1,285 @@
+use crate::{
+    config::{DataType, GenerateConfig, Resource, SecretString},
+    event::proto::{event_wrapper, EventWrapper},
+    internal_events::{
+        VectorAuthenticationFailed, VectorEventReceived, VectorProtoDecodeError,
+        VectorRequestDecompressError, VectorRequestTooLarge,
+    },
+    proto::{
+        auth::{PreSharedKey, NONCE_LEN, PROOF_KEY, SESSION_KEY},
+        vector_server::{Vector, VectorServer},
+        AuthenticateRequest, AuthenticateResponse, Compression, EventBatch, FieldNames,
+        HealthCheckRequest, HealthCheckResponse, PushEventsRequest, PushEventsResponse,
+        ServingStatus,
+    },
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsSettings, TlsConfig},
+    Event, Pipeline,
+};
+use flate2::read::GzDecoder;
+use futures::{compat::Future01CompatExt, FutureExt, TryFutureExt};
+use futures01::Sink;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io::Read, net::SocketAddr, time::SystemTime};
+use tonic::{transport::Server, Request, Response, Status};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VectorConfig {
+    pub version: Version,
+    pub address: SocketAddr,
+    tls: Option<TlsConfig>,
+    /// Requires the sinks to authenticate with this key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_shared_key: Option<SecretString>,
+    /// Rejects the batches larger than this once decompressed.
+    #[serde(default = "default_max_batch_bytes")]
+    pub max_batch_bytes: usize,
+}
+
+const fn default_max_batch_bytes() -> usize {
+    100_000_000
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub enum Version {
+    #[serde(rename = "2")]
+    V2,
+}
+
+#[cfg(test)]
+impl VectorConfig {
+    pub fn new(address: SocketAddr, tls: Option<TlsConfig>) -> Self {
+        Self {
+            version: Version::V2,
+            address,
+            tls,
+            pre_shared_key: None,
+            max_batch_bytes: default_max_batch_bytes(),
+        }
+    }
+}
+
+impl GenerateConfig for VectorConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            version: Version::V2,
+            address: "0.0.0.0:6000".parse().unwrap(),
+            tls: None,
+            pre_shared_key: None,
+            max_batch_bytes: default_max_batch_bytes(),
+        })
+        .unwrap()
+    }
+}
+
+impl VectorConfig {
+    pub(super) async fn build(
+        &self,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<crate::sources::Source> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let key = self
+            .pre_shared_key
+            .as_ref()
+            .map(|key| PreSharedKey::new(&key.resolve()?))
+            .transpose()?;
+        let listener = tls.bind(&self.address).await?;
+        let service = VectorServer::new(Service {
+            pipeline: out,
+            key,
+            max_batch_bytes: self.max_batch_bytes,
+        });
+
+        Ok(Box::pin(async move {
+            let result = Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(
+                    listener.accept_stream(),
+                    shutdown.clone().map(|_| ()),
+                )
+                .await;
+            // We need to drop the last copy of ShutdownSignalToken only after server has shut down.
+            drop(shutdown);
+            result.map_err(|error| error!(message = "Source future failed.", %error))
+        }))
+    }
+
+    pub(super) fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    pub(super) fn resources(&self) -> Vec<Resource> {
+        vec![self.address.into()]
+    }
+}
+
+#[derive(Clone)]
+struct Service {
+    pipeline: Pipeline,
+    key: Option<PreSharedKey>,
+    max_batch_bytes: usize,
+}
+
+impl Service {
+    /// Checks that requests carry a valid session and proof for `payload`, if
+    /// a key is required.
+    fn authorize<T>(&self, request: &Request<T>, payload: &[u8]) -> Result<(), Status> {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+        let metadata = request.metadata();
+        let session = metadata
+            .get_bin(SESSION_KEY)
+            .and_then(|value| value.to_bytes().ok());
+        let proof = metadata
+            .get_bin(PROOF_KEY)
+            .and_then(|value| value.to_bytes().ok());
+        match (session, proof) {
+            (Some(session), Some(proof))
+                if key.verify_sink(&session, payload, &proof, SystemTime::now()) =>
+            {
+                Ok(())
+            }
+            _ => {
+                emit!(VectorAuthenticationFailed);
+                Err(Status::unauthenticated(
+                    "Missing, invalid or expired session.",
+                ))
+            }
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Vector for Service {
+    async fn push_events(
+        &self,
+        request: Request<PushEventsRequest>,
+    ) -> Result<Response<PushEventsResponse>, Status> {
+        self.authorize(&request, &request.get_ref().batch)?;
+        let events = decode(request.into_inner(), self.max_batch_bytes)?;
+
+        // The response is the acknowledgement of the events, so it's only
+        // sent once they have been passed on.
+        self.pipeline
+            .clone()
+            .send_all(futures01::stream::iter_ok(events))
+            .compat()
+            .map_err(|error| {
+                error!(message = "Failed to forward events, downstream is closed.", %error);
+                Status::unavailable("The source is shutting down.")
+            })
+            .await?;
+
+        Ok(Response::new(PushEventsResponse {}))
+    }
+
+    async fn health_check(
+        &self,
+        _request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        Ok(Response::new(HealthCheckResponse {
+            status: ServingStatus::Serving.into(),
+        }))
+    }
+
+    async fn authenticate(
+        &self,
+        request: Request<AuthenticateRequest>,
+    ) -> Result<Response<AuthenticateResponse>, Status> {
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("Authentication is not enabled."))?;
+        let nonce = request.into_inner().nonce;
+        if nonce.len() != NONCE_LEN {
+            return Err(Status::invalid_argument("Invalid nonce."));
+        }
+
+        let (session, proof) = key.issue_session(&nonce, SystemTime::now());
+        Ok(Response::new(AuthenticateResponse { session, proof }))
+    }
+}
+
+/// Reads a decompressed batch, rejecting it once it's larger than
+/// `max_bytes` rather than holding all of it in memory.
+fn decompress(reader: impl Read, max_bytes: usize) -> Result<Vec<u8>, Status> {
+    let mut batch = Vec::new();
+    reader
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut batch)
+        .map_err(|error| {
+            emit!(VectorRequestDecompressError { error });
+            Status::invalid_argument("Failed to decompress batch.")
+        })?;
+    if batch.len() > max_bytes {
+        emit!(VectorRequestTooLarge { max_bytes });
+        return Err(Status::invalid_argument(format!(
+            "Decompressed batch is larger than {} bytes.",
+            max_bytes
+        )));
+    }
+    Ok(batch)
+}
+
+fn decode(request: PushEventsRequest, max_batch_bytes: usize) -> Result<Vec<Event>, Status> {
+    let batch = match Compression::from_i32(request.compression) {
+        Some(Compression::None) => request.batch,
+        Some(Compression::Gzip) => decompress(GzDecoder::new(&request.batch[..]), max_batch_bytes)?,
+        Some(Compression::Zstd) => {
+            let decoder = zstd::Decoder::new(&request.batch[..]).map_err(|error| {
+                emit!(VectorRequestDecompressError { error });
+                Status::invalid_argument("Failed to decompress batch.")
+            })?;
+            decompress(decoder, max_batch_bytes)?
+        }
+        None => {
+            return Err(Status::invalid_argument(format!(
+                "Unknown compression {}.",
+                request.compression
+            )))
+        }
+    };
+
+    let mut batch = EventBatch::decode(&batch[..]).map_err(|error| {
+        emit!(VectorProtoDecodeError { error });
+        Status::invalid_argument("Failed to decode batch.")
+    })?;
+    if batch.delta_encoded {
+        delta_decode(&mut batch.events, batch.removed_fields);
+    }
+
+    Ok(batch
+        .events
+        .into_iter()
+        .map(|event| {
+            emit!(VectorEventReceived {
+                byte_size: event.encoded_len()
+            });
+            Event::from(event)
+        })
+        .collect())
+}
+
+/// Restores the fields the sink stripped from the log events for being the
+/// same as in the previous log event.
+fn delta_decode(events: &mut [EventWrapper], mut removed_fields: BTreeMap<u32, FieldNames>) {
+    let mut previous = BTreeMap::new();
+    for (index, event) in events.iter_mut().enumerate() {
+        if let Some(event_wrapper::Event::Log(log)) = &mut event.event {
+            let mut fields = std::mem::take(&mut previous);
+            if let Some(removed) = removed_fields.remove(&(index as u32)) {
+                for name in removed.names {
+                    fields.remove(&name);
+                }
+            }
+            fields.append(&mut log.fields);
+            log.fields = fields;
+            previous = log.fields.clone();
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! The segments of the spool directories shared by the `spool` sink and
+//! source. The sink appends the events to an open segment, named
+//! `<created>.open`, and renames it to `<created>-<events>.spool` once
+//! closed, so the source only ever reads complete segments. The events are
+//! length delimited, in the native encoding of the disk buffers.
+
+use crate::event::{proto::EventWrapper, Event};
+use bytes::{Buf, Bytes, BytesMut};
+use prost::Message;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::fs;
+
+pub const OPEN_EXTENSION: &str = "open";
+pub const CLOSED_EXTENSION: &str = "spool";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub path: PathBuf,
+    /// When the segment was opened, in microseconds since the epoch.
+    pub created: u64,
+    pub events: usize,
+    pub bytes: u64,
+}
+
+pub fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_micros() as u64)
+        .unwrap_or_default()
+}
+
+pub fn open_path(dir: &Path, created: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", created, OPEN_EXTENSION))
+}
+
+pub fn closed_path(dir: &Path, created: u64, events: usize) -> PathBuf {
+    dir.join(format!("{:020}-{}.{}", created, events, CLOSED_EXTENSION))
+}
+
+/// Parses the name of a closed segment into when it was opened and how many
+/// events it holds.
+fn parse_closed(path: &Path) -> Option<(u64, usize)> {
+    if path.extension()? != CLOSED_EXTENSION {
+        return None;
+    }
+    let mut parts = path.file_stem()?.to_str()?.splitn(2, '-');
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+fn parse_open(path: &Path) -> Option<u64> {
+    if path.extension()? != OPEN_EXTENSION {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Lists the closed segments of the directory, oldest first.
+pub async fn closed_segments(dir: &Path) -> io::Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if let Some((created, events)) = parse_closed(&path) {
+            // Segments forwarded since they were listed are skipped.
+            if let Ok(metadata) = entry.metadata().await {
+                segments.push(Segment {
+                    path,
+                    created,
+                    events,
+                    bytes: metadata.len(),
+                });
+            }
+        }
+    }
+    segments.sort_by_key(|segment| segment.created);
+    Ok(segments)
+}
+
+/// Lists the segments left open by a sink that stopped without closing them,
+/// with when they were opened.
+pub async fn open_segments(dir: &Path) -> io::Result<Vec<(PathBuf, u64)>> {
+    let mut segments = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if let Some(created) = parse_open(&path) {
+            segments.push((path, created));
+        }
+    }
+    Ok(segments)
+}
+
+pub fn encode(event: Event, buf: &mut BytesMut) {
+    let event = EventWrapper::from(event);
+    let len = event.encoded_len();
+    buf.reserve(prost::length_delimiter_len(len) + len);
+    event
+        .encode_length_delimited(buf)
+        .expect("Encoding into a buffer with enough capacity can't fail.");
+}
+
+/// Decodes the events of a segment, up to the first one that fails to
+/// decode, like the last one of a segment a sink stopped writing midway.
+pub fn decode(mut bytes: Bytes) -> (Vec<Event>, Option<prost::DecodeError>) {
+    let mut events = Vec::new();
+    while bytes.has_remaining() {
+        match EventWrapper::decode_length_delimited(&mut bytes) {
+            Ok(event) => events.push(event.into()),
+            Err(error) => return (events, Some(error)),
+        }
+    }
+    (events, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::{Metric, MetricKind, MetricValue};
+
+    #[test]
+    fn names_segments() {
+        let dir = Path::new("/spool");
+        let open = open_path(dir, 1_607_000_000_000_000);
+        let closed = closed_path(dir, 1_607_000_000_000_000, 42);
+
+        assert_eq!(open, Path::new("/spool/00001607000000000000.open"));
+        assert_eq!(closed, Path::new("/spool/00001607000000000000-42.spool"));
+        assert_eq!(parse_open(&open), Some(1_607_000_000_000_000));
+        assert_eq!(parse_closed(&open), None);
+        assert_eq!(parse_closed(&closed), Some((1_607_000_000_000_000, 42)));
+        assert_eq!(parse_closed(Path::new("/spool/notes.spool")), None);
+    }
+
+    #[test]
+    fn decodes_encoded_events() {
+        let events = vec![
+            Event::from("hello"),
+            Event::Metric(Metric {
+                name: "requests".into(),
+                namespace: None,
+                timestamp: None,
+                tags: None,
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 1.0 },
+            }),
+        ];
+        let mut buf = BytesMut::new();
+        encode(events[0].clone(), &mut buf);
+        let first_len = buf.len();
+        encode(events[1].clone(), &mut buf);
+
+        let (decoded, error) = decode(buf.clone().freeze());
+        assert_eq!(decoded, events);
+        assert!(error.is_none());
+
+        // Segments cut in the middle of an event keep the events before it.
+        let (decoded, error) = decode(buf.freeze().slice(..first_len + 4));
+        assert_eq!(decoded, events[..1].to_vec());
+        assert!(error.is_some());
+    }
+}
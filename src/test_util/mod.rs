@@ -284,6 +284,19 @@ pub fn lines_from_gzip_file<P: AsRef<Path>>(path: P) -> Vec<String> {
     output.lines().map(|s| s.to_owned()).collect()
 }
 
+pub fn lines_from_zstd_file<P: AsRef<Path>>(path: P) -> Vec<String> {
+    trace!(message = "Reading zstd file.", path = %path.as_ref().display());
+    let mut file = File::open(path).unwrap();
+    let mut zstd_bytes = Vec::new();
+    file.read_to_end(&mut zstd_bytes).unwrap();
+    let output = zstd::stream::decode_all(&zstd_bytes[..]).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|s| s.to_owned())
+        .collect()
+}
+
 pub fn runtime() -> runtime::Runtime {
     runtime::Builder::new()
         .threaded_scheduler()
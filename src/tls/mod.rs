@@ -8,13 +8,13 @@ use std::{fmt::Debug, net::SocketAddr, path::PathBuf, time::Duration};
 use tokio::net::TcpStream;
 use tokio_openssl::{HandshakeError, SslStream};
 
-#[cfg(feature = "sources-utils-tls")]
+#[cfg(feature = "tls")]
 mod incoming;
 mod maybe_tls;
 mod outgoing;
 mod settings;
 
-#[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
+#[cfg(all(feature = "tls", feature = "listenfd"))]
 pub(crate) use incoming::{MaybeTlsIncomingStream, MaybeTlsListener};
 pub(crate) use maybe_tls::MaybeTls;
 pub use settings::{MaybeTlsSettings, TlsConfig, TlsOptions, TlsSettings};
@@ -41,10 +41,14 @@ pub enum TlsError {
     },
     #[snafu(display("Could not build TLS connector: {}", source))]
     TlsBuildConnector { source: ErrorStack },
+    #[snafu(display("Could not set the ALPN protocols: {}", source))]
+    SetAlpnProtocols { source: ErrorStack },
     #[snafu(display("Could not set TCP TLS identity: {}", source))]
     TlsIdentityError { source: ErrorStack },
     #[snafu(display("Could not export identity to DER: {}", source))]
     DerExportError { source: ErrorStack },
+    #[snafu(display("Could not export certificate to PEM: {}", source))]
+    PemExportError { source: ErrorStack },
     #[snafu(display("Identity certificate is missing a key"))]
     MissingKey,
     #[snafu(display("Certificate file contains no certificates"))]
@@ -147,14 +151,14 @@ pub(crate) fn tls_connector_builder(settings: &MaybeTlsSettings) -> Result<SslCo
 }
 
 fn tls_connector(settings: &MaybeTlsSettings) -> Result<ConnectConfiguration> {
-    let verify_hostname = settings
-        .tls()
-        .map(|settings| settings.verify_hostname)
-        .unwrap_or(true);
-    let configure = tls_connector_builder(settings)?
+    let mut configure = tls_connector_builder(settings)?
         .build()
         .configure()
-        .context(TlsBuildConnector)?
-        .verify_hostname(verify_hostname);
+        .context(TlsBuildConnector)?;
+    if let Some(settings) = settings.tls() {
+        settings
+            .apply_connect_configuration(&mut configure)
+            .context(TlsBuildConnector)?;
+    }
     Ok(configure)
 }
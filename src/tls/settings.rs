@@ -1,14 +1,16 @@
 use super::{
     AddCertToStore, AddExtraChainCert, CaStackPush, DerExportError, FileOpenFailed, FileReadFailed,
-    MaybeTls, NewCaStack, NewStoreBuilder, ParsePkcs12, Pkcs12Error, PrivateKeyParseError, Result,
-    SetCertificate, SetPrivateKey, SetVerifyCert, TlsError, TlsIdentityError, X509ParseError,
+    MaybeTls, NewCaStack, NewStoreBuilder, ParsePkcs12, PemExportError, Pkcs12Error,
+    PrivateKeyParseError, Result, SetAlpnProtocols, SetCertificate, SetPrivateKey, SetVerifyCert,
+    TlsError, TlsIdentityError, X509ParseError,
 };
 use openssl::{
+    error::ErrorStack,
     pkcs12::{ParsedPkcs12, Pkcs12},
     pkey::{PKey, Private},
     ssl::{ConnectConfiguration, SslContextBuilder, SslVerifyMode},
     stack::Stack,
-    x509::{store::X509StoreBuilder, X509},
+    x509::{store::X509StoreBuilder, verify::X509CheckFlags, X509},
 };
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
@@ -16,6 +18,7 @@ use std::{
     fmt,
     fs::File,
     io::Read,
+    net::IpAddr,
     path::{Path, PathBuf},
 };
 
@@ -59,6 +62,11 @@ pub struct TlsOptions {
     pub verify_hostname: Option<bool>,
     #[serde(alias = "ca_path")]
     pub ca_file: Option<PathBuf>,
+    /// CA certificates trusted on top of the system ones, or of `ca_file`.
+    pub extra_ca_file: Option<PathBuf>,
+    /// The name sent as SNI and verified against the certificate of the
+    /// remote, instead of the host connected to.
+    pub server_name: Option<String>,
     #[serde(alias = "crt_path")]
     pub crt_file: Option<PathBuf>,
     #[serde(alias = "key_path")]
@@ -84,7 +92,10 @@ pub struct TlsSettings {
     verify_certificate: bool,
     pub(super) verify_hostname: bool,
     authorities: Vec<X509>,
+    extra_authorities: Vec<X509>,
+    server_name: Option<String>,
     pub(super) identity: Option<IdentityStore>, // openssl::pkcs12::ParsedPkcs12 doesn't impl Clone yet
+    alpn_protocols: Option<Vec<u8>>,
 }
 
 #[derive(Clone)]
@@ -119,11 +130,41 @@ impl TlsSettings {
         Ok(Self {
             verify_certificate: options.verify_certificate.unwrap_or(!for_server),
             verify_hostname: options.verify_hostname.unwrap_or(!for_server),
-            authorities: options.load_authorities()?,
+            authorities: load_authorities(&options.ca_file)?,
+            extra_authorities: load_authorities(&options.extra_ca_file)?,
+            server_name: options.server_name.clone(),
             identity: options.load_identity()?,
+            alpn_protocols: None,
         })
     }
 
+    /// Offers the protocols, in the wire format of ALPN, to the servers
+    /// connected to, such as the gRPC services only speaking HTTP/2.
+    pub fn alpn_protocols(mut self, protocols: &[u8]) -> Self {
+        self.alpn_protocols = Some(protocols.to_vec());
+        self
+    }
+
+    /// The identity as DER encoded PKCS#12 archive and its password, for
+    /// the clients setting up their TLS connections themselves.
+    pub fn identity_pkcs12(&self) -> Option<(&[u8], &str)> {
+        self.identity
+            .as_ref()
+            .map(|identity| (identity.0.as_slice(), identity.1.as_str()))
+    }
+
+    /// The configured certificate authorities, concatenated as PEM.
+    pub fn authorities_pem(&self) -> Result<Option<String>> {
+        if self.authorities.is_empty() && self.extra_authorities.is_empty() {
+            return Ok(None);
+        }
+        let mut pem = Vec::new();
+        for authority in self.authorities.iter().chain(&self.extra_authorities) {
+            pem.extend(authority.to_pem().context(PemExportError)?);
+        }
+        Ok(Some(String::from_utf8_lossy(&pem).into_owned()))
+    }
+
     fn identity(&self) -> Option<ParsedPkcs12> {
         // This data was test-built previously, so we can just use it
         // here and expect the results will not fail. This can all be
@@ -158,9 +199,14 @@ impl TlsSettings {
                 }
             }
         }
+        if let Some(protocols) = &self.alpn_protocols {
+            context
+                .set_alpn_protos(protocols)
+                .context(SetAlpnProtocols)?;
+        }
         if !self.authorities.is_empty() {
             let mut store = X509StoreBuilder::new().context(NewStoreBuilder)?;
-            for authority in &self.authorities {
+            for authority in self.authorities.iter().chain(&self.extra_authorities) {
                 store.add_cert(authority.clone()).context(AddCertToStore)?;
             }
             context
@@ -170,40 +216,72 @@ impl TlsSettings {
             debug!("Fetching system root certs.");
 
             #[cfg(windows)]
-            load_windows_certs(context).unwrap();
+            load_windows_certs(context, &self.extra_authorities).unwrap();
 
             #[cfg(target_os = "macos")]
-            load_mac_certs(context).unwrap();
+            load_mac_certs(context, &self.extra_authorities).unwrap();
+
+            #[cfg(not(any(windows, target_os = "macos")))]
+            for authority in &self.extra_authorities {
+                context
+                    .cert_store_mut()
+                    .add_cert(authority.clone())
+                    .context(AddCertToStore)?;
+            }
         }
 
         Ok(())
     }
 
-    pub fn apply_connect_configuration(&self, connection: &mut ConnectConfiguration) {
+    pub fn apply_connect_configuration(
+        &self,
+        connection: &mut ConnectConfiguration,
+    ) -> std::result::Result<(), ErrorStack> {
         connection.set_verify_hostname(self.verify_hostname);
+        if let Some(server_name) = &self.server_name {
+            // The connector would otherwise send and verify the host
+            // connected to once the connection is configured.
+            connection.set_use_server_name_indication(false);
+            connection.set_verify_hostname(false);
+
+            let ip = server_name.parse::<IpAddr>();
+            if ip.is_err() {
+                connection.set_hostname(server_name)?;
+            }
+            if self.verify_hostname {
+                let param = connection.param_mut();
+                param.set_hostflags(X509CheckFlags::NO_PARTIAL_WILDCARDS);
+                match ip {
+                    Ok(ip) => param.set_ip(ip)?,
+                    Err(_) => param.set_host(server_name)?,
+                }
+            }
+        }
+        Ok(())
     }
 }
 
-impl TlsOptions {
-    fn load_authorities(&self) -> Result<Vec<X509>> {
-        match &self.ca_file {
-            None => Ok(vec![]),
-            Some(filename) => {
-                let (data, filename) = open_read(filename, "certificate")?;
-                der_or_pem(
-                    data,
-                    |der| X509::from_der(&der).map(|x509| vec![x509]),
-                    |pem| {
-                        pem.match_indices(PEM_START_MARKER)
-                            .map(|(start, _)| X509::from_pem(pem[start..].as_bytes()))
-                            .collect()
-                    },
-                )
-                .with_context(|| X509ParseError { filename })
-            }
+/// Load the CA certificates of a file, if any
+fn load_authorities(filename: &Option<PathBuf>) -> Result<Vec<X509>> {
+    match filename {
+        None => Ok(vec![]),
+        Some(filename) => {
+            let (data, filename) = open_read(filename, "certificate")?;
+            der_or_pem(
+                data,
+                |der| X509::from_der(&der).map(|x509| vec![x509]),
+                |pem| {
+                    pem.match_indices(PEM_START_MARKER)
+                        .map(|(start, _)| X509::from_pem(pem[start..].as_bytes()))
+                        .collect()
+                },
+            )
+            .with_context(|| X509ParseError { filename })
         }
     }
+}
 
+impl TlsOptions {
     fn load_identity(&self) -> Result<Option<IdentityStore>> {
         match (&self.crt_file, &self.key_file) {
             (None, Some(_)) => Err(TlsError::MissingCrtKeyFile),
@@ -269,7 +347,7 @@ impl TlsOptions {
 /// Load the system default certs from `schannel` this should be in place
 /// of openssl-probe on linux.
 #[cfg(windows)]
-fn load_windows_certs(builder: &mut SslContextBuilder) -> Result<()> {
+fn load_windows_certs(builder: &mut SslContextBuilder, extra: &[X509]) -> Result<()> {
     use super::Schannel;
 
     let mut store = X509StoreBuilder::new().context(NewStoreBuilder)?;
@@ -282,6 +360,9 @@ fn load_windows_certs(builder: &mut SslContextBuilder) -> Result<()> {
         let cert = X509::from_der(&cert[..]).context(super::X509SystemParseError)?;
         store.add_cert(cert).context(AddCertToStore)?;
     }
+    for cert in extra {
+        store.add_cert(cert.clone()).context(AddCertToStore)?;
+    }
 
     builder
         .set_verify_cert_store(store.build())
@@ -291,7 +372,7 @@ fn load_windows_certs(builder: &mut SslContextBuilder) -> Result<()> {
 }
 
 #[cfg(target_os = "macos")]
-fn load_mac_certs(builder: &mut SslContextBuilder) -> Result<()> {
+fn load_mac_certs(builder: &mut SslContextBuilder, extra: &[X509]) -> Result<()> {
     use super::SecurityFramework;
     use security_framework::trust_settings::{Domain, TrustSettings, TrustSettingsForCertificate};
     use std::collections::HashMap;
@@ -338,6 +419,9 @@ fn load_mac_certs(builder: &mut SslContextBuilder) -> Result<()> {
             store.add_cert(cert).context(AddCertToStore)?;
         }
     }
+    for cert in extra {
+        store.add_cert(cert.clone()).context(AddCertToStore)?;
+    }
 
     builder
         .set_verify_cert_store(store.build())
@@ -351,6 +435,7 @@ impl fmt::Debug for TlsSettings {
         f.debug_struct("TlsSettings")
             .field("verify_certificate", &self.verify_certificate)
             .field("verify_hostname", &self.verify_hostname)
+            .field("server_name", &self.server_name)
             .finish()
     }
 }
@@ -549,6 +634,39 @@ mod test {
         assert_eq!(settings.authorities.len(), 2);
     }
 
+    #[test]
+    fn from_options_extra_ca() {
+        let options = TlsOptions {
+            extra_ca_file: Some("tests/data/Vector_CA.crt".into()),
+            ..Default::default()
+        };
+        let settings = TlsSettings::from_options(&Some(options))
+            .expect("Failed to load authority certificate");
+        assert_eq!(settings.authorities.len(), 0);
+        assert_eq!(settings.extra_authorities.len(), 1);
+    }
+
+    #[test]
+    fn connect_configuration_server_name() {
+        use openssl::ssl::{NameType, SslConnector, SslMethod};
+
+        let options = TlsOptions {
+            server_name: Some("localhost".into()),
+            ..Default::default()
+        };
+        let settings = TlsSettings::from_options(&Some(options)).unwrap();
+        let mut connection = SslConnector::builder(SslMethod::tls())
+            .unwrap()
+            .build()
+            .configure()
+            .unwrap();
+        settings
+            .apply_connect_configuration(&mut connection)
+            .unwrap();
+        let ssl = connection.into_ssl("127.0.0.1").unwrap();
+        assert_eq!(ssl.servername(NameType::HOST_NAME), Some("localhost"));
+    }
+
     #[test]
     fn from_options_none() {
         let settings = TlsSettings::from_options(&None).expect("Failed to generate null settings");
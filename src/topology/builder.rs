@@ -4,20 +4,34 @@ use super::{
     ConfigDiff,
 };
 use crate::{
-    buffers,
-    config::{DataType, SinkContext},
-    event::Event,
+    buffers::{self, Acker},
+    config::{log_schema, DataType, PanicOptions, PanicPolicy, SinkContext, TransformConfig},
+    emit,
+    event::{in_flight::InFlightLimit, Event, Value},
+    internal_events::{ComponentRestarted, ComponentRestartsExhausted, EventExpired},
+    process,
     shutdown::SourceShutdownCoordinator,
     transforms::Transform,
-    Pipeline,
+    tuning, Pipeline,
 };
+use chrono::Utc;
 use futures::{
     compat::{Future01CompatExt, Stream01CompatExt},
-    future, FutureExt, StreamExt, TryFutureExt,
+    future, Future, FutureExt, StreamExt, TryFutureExt,
 };
-use futures01::{sync::mpsc, Future as Future01, Stream as Stream01};
-use std::{collections::HashMap, future::ready};
-use tokio::time::{timeout, Duration};
+use futures01::{
+    sync::mpsc, Future as Future01, Poll as Poll01, Sink as Sink01, StartSend, Stream as Stream01,
+};
+use std::{
+    collections::HashMap,
+    future::ready,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+use tokio::time::{delay_for, timeout, Duration};
+
+const RESTART_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
 
 pub struct Pieces {
     pub inputs: HashMap<String, (buffers::BufferInputCloner, Vec<String>)>,
@@ -49,13 +63,30 @@ pub async fn build_pieces(
         .filter(|(name, _)| diff.sources.contains_new(&name))
     {
         let (tx, rx) = mpsc::channel(1000);
-        let pipeline = Pipeline::from_sender(tx, vec![]);
+        let timestamp = match source.timestamp.as_ref().map(|timestamp| timestamp.build()) {
+            Some(Err(error)) => {
+                errors.push(format!("Source \"{}\": {}", name, error));
+                continue;
+            }
+            Some(Ok(timestamp)) => Some(timestamp),
+            None => None,
+        };
+        let inlines = timestamp
+            .into_iter()
+            .chain(config.global.event_tags())
+            .chain(config.global.timestamp_skew.build())
+            .collect();
+        let in_flight = source
+            .max_in_flight_events
+            .map(|max| InFlightLimit::new(max.get()));
+        let pipeline = Pipeline::from_sender(tx, inlines).with_in_flight_limit(in_flight);
 
-        let typetag = source.source_type();
+        let typetag = source.inner.source_type();
 
         let (shutdown_signal, force_shutdown_tripwire) = shutdown_coordinator.register_source(name);
 
         let server = match source
+            .inner
             .build(&name, &config.global, shutdown_signal, pipeline)
             .await
         {
@@ -96,44 +127,33 @@ pub async fn build_pieces(
         let typetag = transform.inner.transform_type();
 
         let input_type = transform.inner.input_type();
-        let transform = match transform.inner.build().await {
+        let qualified_name = config.qualified_name(name);
+        let built = match tuning::scope(&qualified_name, transform.inner.build()).await {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", name, error));
                 continue;
             }
-            Ok(transform) => transform,
+            Ok(built) => built,
         };
 
         let (input_tx, input_rx) = futures01::sync::mpsc::channel(100);
-        let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block);
+        let input_tx = buffers::BufferInputCloner::memory(input_tx, buffers::WhenFull::Block);
 
         let (output, control) = Fanout::new();
 
-        let transform = match transform {
-            Transform::Function(mut t) => {
-                let filtered = filter_event_type(input_rx, input_type);
-                #[allow(deprecated)]
-                // `boxed()` here is deprecated, but the replacement won't work until we adopt futures 0.3 here.
-                let transformed = filtered
-                    .map(move |v| {
-                        let mut buf = Vec::with_capacity(1);
-                        t.transform(&mut buf, v);
-                        futures01::stream::iter_ok(buf.into_iter())
-                    })
-                    .flatten()
-                    .boxed();
-                transformed.forward(output)
-            }
-            Transform::Task(t) => {
-                let filtered = filter_event_type(input_rx, input_type);
-                let transformed: Box<dyn futures01::Stream<Item = _, Error = _> + Send> =
-                    t.transform(filtered);
-                transformed.forward(output)
-            }
-        }
-        .map(|_| debug!("Finished."))
-        .compat();
-        let task = Task::new(name, typetag, transform);
+        let task = match transform.on_panic.policy {
+            PanicPolicy::Abort => run_transform(built, input_rx, input_type, output).boxed(),
+            PanicPolicy::Restart => supervise_transform(
+                qualified_name,
+                dyn_clone::clone_box(&*transform.inner),
+                transform.on_panic,
+                built,
+                Shared::new(input_rx),
+                Shared::new(output),
+            )
+            .boxed(),
+        };
+        let task = Task::new(name, typetag, task);
 
         inputs.insert(name.clone(), (input_tx, trans_inputs.clone()));
         outputs.insert(name.clone(), control);
@@ -151,6 +171,10 @@ pub async fn build_pieces(
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input_type();
+        let max_event_age = sink
+            .max_event_age_secs
+            .or(config.global.max_event_age_secs)
+            .map(Duration::from_secs);
 
         let buffer = sink.buffer.build(&config.global.data_dir, &name);
         let (tx, rx, acker) = match buffer {
@@ -160,10 +184,34 @@ pub async fn build_pieces(
             }
             Ok(buffer) => buffer,
         };
+        let qualified_name = config.qualified_name(name);
+        let usage = buffers::BufferUsage::new(error_span!(
+            "sink",
+            component_kind = "sink",
+            component_id = %qualified_name,
+            component_name = %qualified_name,
+            component_type = %typetag,
+        ));
+        let tx = tx.with_usage(usage.clone());
+        let rx = rx.inspect(move |_| usage.pop());
 
-        let cx = SinkContext { acker };
+        // The sources waiting for their events to be processed are notified
+        // once the sink acked them.
+        let acker = acker.holding();
+        let holding_acker = acker.clone();
+        let acker = match max_event_age {
+            Some(_) => acker.skipping(),
+            None => acker,
+        };
+        let expired_acker = acker.clone();
+        let cx = SinkContext {
+            acker,
+            name: name.clone(),
+            data_dir: config.global.data_dir.clone(),
+            request: config.global.request.clone(),
+        };
 
-        let (sink, healthcheck) = match sink.inner.build(cx).await {
+        let (sink, healthcheck) = match tuning::scope(&qualified_name, sink.inner.build(cx)).await {
             Err(error) => {
                 errors.push(format!("Sink \"{}\": {}", name, error));
                 continue;
@@ -173,10 +221,17 @@ pub async fn build_pieces(
 
         let sink = sink
             .run(
-                filter_event_type(rx, input_type)
-                    .compat()
-                    .take_while(|e| ready(e.is_ok()))
-                    .map(|x| x.unwrap()),
+                filter_event_age(
+                    filter_event_type(rx, input_type).map(move |mut event| {
+                        holding_acker.hold(&mut event);
+                        event
+                    }),
+                    max_event_age,
+                    expired_acker,
+                )
+                .compat()
+                .take_while(|e| ready(e.is_ok()))
+                .map(|x| x.unwrap()),
             )
             .inspect(|_| debug!("Finished."));
         let task = Task::new(name, typetag, sink);
@@ -228,6 +283,140 @@ pub async fn build_pieces(
     }
 }
 
+fn run_transform(
+    transform: Transform,
+    input_rx: impl Stream01<Item = Event, Error = ()> + Send + 'static,
+    input_type: DataType,
+    output: impl Sink01<SinkItem = Event, SinkError = ()> + Send + 'static,
+) -> impl Future<Output = Result<(), ()>> + Send + 'static {
+    match transform {
+        Transform::Function(mut t) => {
+            let filtered = filter_event_type(input_rx, input_type);
+            #[allow(deprecated)]
+            // `boxed()` here is deprecated, but the replacement won't work until we adopt futures 0.3 here.
+            let transformed = filtered
+                .map(move |v| {
+                    let mut buf = Vec::with_capacity(1);
+                    t.transform(&mut buf, v);
+                    futures01::stream::iter_ok(buf.into_iter())
+                })
+                .flatten()
+                .boxed();
+            transformed.forward(output)
+        }
+        Transform::Task(t) => {
+            let filtered = filter_event_type(input_rx, input_type);
+            let transformed: Box<dyn futures01::Stream<Item = _, Error = _> + Send> =
+                t.transform(filtered);
+            transformed.forward(output)
+        }
+    }
+    .map(|_| debug!("Finished."))
+    .compat()
+}
+
+/// Runs the transform, building it again whenever it panics, until it
+/// panicked more often than allowed by `options`. The input and output are
+/// shared between the instances, so neither the upstream nor the downstream
+/// components notice a restart.
+async fn supervise_transform(
+    qualified_name: String,
+    config: Box<dyn TransformConfig>,
+    options: PanicOptions,
+    transform: Transform,
+    input_rx: Shared<mpsc::Receiver<Event>>,
+    output: Shared<Fanout>,
+) -> Result<(), ()> {
+    let input_type = config.input_type();
+    let mut transform = transform;
+    let mut restarts = 0;
+    let mut backoff = RESTART_BACKOFF_MIN;
+    loop {
+        let task = run_transform(transform, input_rx.clone(), input_type, output.clone());
+        // Only the panic exhausting the restarts warrants a crash report.
+        let task = if restarts < options.max_restarts {
+            process::without_crash_reports(task).boxed()
+        } else {
+            task.boxed()
+        };
+        match AssertUnwindSafe(task).catch_unwind().await {
+            Ok(result) => return result,
+            Err(_) if restarts < options.max_restarts => {
+                restarts += 1;
+                emit!(ComponentRestarted {
+                    restarts,
+                    max_restarts: options.max_restarts,
+                    backoff,
+                });
+                delay_for(backoff).await;
+                backoff = std::cmp::min(backoff * 2, RESTART_BACKOFF_MAX);
+
+                transform = match tuning::scope(&qualified_name, config.build()).await {
+                    Ok(transform) => transform,
+                    Err(error) => {
+                        error!(message = "Failed to rebuild the transform.", %error);
+                        return Err(());
+                    }
+                };
+            }
+            Err(_) => {
+                emit!(ComponentRestartsExhausted {
+                    max_restarts: options.max_restarts,
+                });
+                return Err(());
+            }
+        }
+    }
+}
+
+/// A stream or sink that is shared between the instances of a restarted
+/// component.
+struct Shared<T>(Arc<Mutex<T>>);
+
+impl<T> Shared<T> {
+    fn new(inner: T) -> Self {
+        Self(Arc::new(Mutex::new(inner)))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, T> {
+        // A component panicking while polling leaves the channels behind
+        // it intact, so the poisoning can be ignored.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: Stream01> Stream01 for Shared<T> {
+    type Item = T::Item;
+    type Error = T::Error;
+
+    fn poll(&mut self) -> Poll01<Option<Self::Item>, Self::Error> {
+        self.lock().poll()
+    }
+}
+
+impl<T: Sink01> Sink01 for Shared<T> {
+    type SinkItem = T::SinkItem;
+    type SinkError = T::SinkError;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        self.lock().start_send(item)
+    }
+
+    fn poll_complete(&mut self) -> Poll01<(), Self::SinkError> {
+        self.lock().poll_complete()
+    }
+
+    fn close(&mut self) -> Poll01<(), Self::SinkError> {
+        self.lock().close()
+    }
+}
+
 fn filter_event_type<S>(
     stream: S,
     data_type: DataType,
@@ -241,3 +430,71 @@ where
         DataType::Metric => Box::new(stream.filter(|event| matches!(event, Event::Metric(_)))),
     }
 }
+
+/// Drops the events whose timestamp is older than `max_age`, acking them
+/// once the sink acked the events preceding them, so disk buffers neither
+/// replay them nor drop events still in flight.
+fn filter_event_age<S>(
+    stream: S,
+    max_age: Option<Duration>,
+    acker: Acker,
+) -> Box<dyn Stream01<Item = Event, Error = ()> + Send>
+where
+    S: Stream01<Item = Event, Error = ()> + Send + 'static,
+{
+    match max_age {
+        None => Box::new(stream),
+        Some(max_age) => Box::new(stream.filter(move |event| match event_age(event) {
+            Some(age) if age > max_age => {
+                emit!(EventExpired { age, max_age });
+                acker.skip();
+                false
+            }
+            _ => {
+                acker.pass();
+                true
+            }
+        })),
+    }
+}
+
+fn event_age(event: &Event) -> Option<Duration> {
+    let timestamp = match event {
+        Event::Log(log) => match log.get(log_schema().timestamp_key()) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => return None,
+        },
+        Event::Metric(metric) => metric.timestamp?,
+    };
+    (Utc::now() - timestamp).to_std().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Metric, MetricKind, MetricValue};
+
+    #[test]
+    fn event_age_of_logs_and_metrics() {
+        let mut log = Event::from("hello");
+        log.as_mut_log().remove(log_schema().timestamp_key());
+        assert_eq!(event_age(&log), None);
+
+        log.as_mut_log().insert(
+            log_schema().timestamp_key(),
+            Utc::now() - chrono::Duration::hours(1),
+        );
+        let age = event_age(&log).unwrap();
+        assert!(age >= Duration::from_secs(3600) && age < Duration::from_secs(3610));
+
+        let metric = Event::Metric(Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: Some(Utc::now() + chrono::Duration::minutes(5)),
+            tags: None,
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        });
+        assert_eq!(event_age(&metric), None);
+    }
+}
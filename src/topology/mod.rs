@@ -226,7 +226,7 @@ impl RunningTopology {
         let add_source = diff
             .sources
             .changed_and_added()
-            .map(|name| (name, new_config.sources[name].resources()));
+            .map(|name| (name, new_config.sources[name].inner.resources()));
         let add_sink = diff
             .sinks
             .changed_and_added()
@@ -489,10 +489,12 @@ impl RunningTopology {
 
     fn spawn_sink(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
         let task = new_pieces.tasks.remove(name).unwrap();
+        let qualified_name = self.config.qualified_name(name);
         let span = error_span!(
             "sink",
             component_kind = "sink",
-            component_name = %task.name(),
+            component_id = %qualified_name,
+            component_name = %qualified_name,
             component_type = %task.typetag(),
         );
         let task = handle_errors(task.compat(), self.abort_tx.clone()).instrument(span);
@@ -504,10 +506,12 @@ impl RunningTopology {
 
     fn spawn_transform(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
         let task = new_pieces.tasks.remove(name).unwrap();
+        let qualified_name = self.config.qualified_name(name);
         let span = error_span!(
             "transform",
             component_kind = "transform",
-            component_name = %task.name(),
+            component_id = %qualified_name,
+            component_name = %qualified_name,
             component_type = %task.typetag(),
         );
         let task = handle_errors(task.compat(), self.abort_tx.clone()).instrument(span);
@@ -519,10 +523,12 @@ impl RunningTopology {
 
     fn spawn_source(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
         let task = new_pieces.tasks.remove(name).unwrap();
+        let qualified_name = self.config.qualified_name(name);
         let span = error_span!(
             "source",
             component_kind = "source",
-            component_name = %task.name(),
+            component_id = %qualified_name,
+            component_name = %qualified_name,
             component_type = %task.typetag(),
         );
         let task = handle_errors(task.compat(), self.abort_tx.clone()).instrument(span.clone());
@@ -713,6 +719,9 @@ mod tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
         old_config.global.data_dir = Some(Path::new("/asdf").to_path_buf());
@@ -749,6 +758,7 @@ mod reload_tests {
     use crate::sources::generator::GeneratorConfig;
     use crate::sources::splunk_hec::SplunkConfig;
     use crate::test_util::{next_addr, start_topology, wait_for_tcp};
+    use crate::tls::TlsConfig;
     use crate::transforms::log_to_metric::{GaugeConfig, LogToMetricConfig, MetricConfig};
     use futures::{compat::Stream01CompatExt, StreamExt};
     use std::net::{SocketAddr, TcpListener};
@@ -767,6 +777,9 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
 
@@ -778,6 +791,9 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
 
@@ -801,6 +817,9 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
 
@@ -812,6 +831,9 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
 
@@ -837,6 +859,9 @@ mod reload_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
 
@@ -898,6 +923,47 @@ mod reload_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn topology_reuse_old_port_sink_tls_reload() {
+        let address = next_addr();
+
+        let source =
+            GeneratorConfig::repeat(vec!["msg".to_string()], usize::max_value(), Some(0.001));
+        let transform = LogToMetricConfig {
+            metrics: vec![MetricConfig::Gauge(GaugeConfig {
+                field: "message".to_string(),
+                name: None,
+                namespace: None,
+                tags: None,
+            })],
+        };
+        let sink = |tls_reload_secs| PrometheusExporterConfig {
+            address,
+            flush_period_secs: 1,
+            tls: Some(TlsConfig::test_config()),
+            tls_reload_secs: Some(tls_reload_secs),
+            ..PrometheusExporterConfig::default()
+        };
+
+        let mut old_config = Config::builder();
+        old_config.add_source("in", source.clone());
+        old_config.add_transform("trans", &[&"in"], transform.clone());
+        old_config.add_sink("out1", &[&"trans"], sink(1));
+
+        let mut new_config = Config::builder();
+        new_config.add_source("in", source);
+        new_config.add_transform("trans", &[&"in"], transform);
+        new_config.add_sink("out1", &[&"trans"], sink(2));
+
+        reload_sink_test(
+            old_config.build().unwrap(),
+            new_config.build().unwrap(),
+            address,
+            address,
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn topology_reuse_old_port_cross_dependecy() {
         // Reload with source that uses address of changed sink.
@@ -999,6 +1065,9 @@ mod source_finished_tests {
             ConsoleSinkConfig {
                 target: Target::Stdout,
                 encoding: Encoding::Text.into(),
+                columns: None,
+                column_width: 24,
+                color: None,
             },
         );
 
@@ -1,15 +1,37 @@
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    metrics::ComponentErrorsLayer,
+};
+use chrono::Utc;
+use lazy_static::lazy_static;
 use metrics_tracing_context::MetricsLayer;
+use std::fmt;
+use tokio::sync::broadcast::{self, Receiver, Sender};
 use tracing::{
     dispatcher::{set_global_default, Dispatch},
+    field::{Field, Visit},
     span::Span,
+    Subscriber,
 };
 use tracing_limit::Limit;
 use tracing_log::LogTracer;
-use tracing_subscriber::{layer::SubscriberExt, FmtSubscriber};
+use tracing_subscriber::{
+    layer::{Context, Layer, SubscriberExt},
+    FmtSubscriber,
+};
 
 pub use tracing_futures::Instrument;
 pub use tracing_tower::{InstrumentableService, InstrumentedService};
 
+lazy_static! {
+    /// Vector's own log output, for the `internal_logs` source.
+    static ref LOGS: Sender<Event> = {
+        let (tx, _) = broadcast::channel(1000);
+        tx
+    };
+}
+
 pub fn init(color: bool, json: bool, levels: &str) {
     let dispatch = if json {
         let subscriber = FmtSubscriber::builder()
@@ -18,7 +40,9 @@ pub fn init(color: bool, json: bool, levels: &str) {
             .flatten_event(true)
             .finish()
             .with(Limit::default())
-            .with(MetricsLayer::new());
+            .with(MetricsLayer::new())
+            .with(ComponentErrorsLayer)
+            .with(BroadcastLayer);
 
         Dispatch::new(subscriber)
     } else {
@@ -27,7 +51,9 @@ pub fn init(color: bool, json: bool, levels: &str) {
             .with_env_filter(levels)
             .finish()
             .with(Limit::default())
-            .with(MetricsLayer::new());
+            .with(MetricsLayer::new())
+            .with(ComponentErrorsLayer)
+            .with(BroadcastLayer);
 
         Dispatch::new(subscriber)
     };
@@ -39,3 +65,75 @@ pub fn init(color: bool, json: bool, levels: &str) {
 pub fn current_span() -> Span {
     Span::current()
 }
+
+/// Receive the log events Vector emits from now on.
+pub fn subscribe() -> Receiver<Event> {
+    LOGS.subscribe()
+}
+
+/// Turns every enabled tracing event into a log event, as long as someone is
+/// subscribed to them.
+pub(crate) struct BroadcastLayer;
+
+impl<S: Subscriber> Layer<S> for BroadcastLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        if LOGS.receiver_count() == 0 {
+            return;
+        }
+
+        let metadata = event.metadata();
+        let mut log = LogEvent::default();
+        log.insert(log_schema().timestamp_key(), Utc::now());
+        log.insert("metadata.kind", "event");
+        log.insert("metadata.level", metadata.level().to_string());
+        log.insert("metadata.target", metadata.target());
+        if let Some(module_path) = metadata.module_path() {
+            log.insert("metadata.module_path", module_path);
+        }
+        event.record(&mut LogVisitor { log: &mut log });
+
+        // Sending only fails if the last receiver went away in the meantime.
+        let _ = LOGS.send(log.into());
+    }
+}
+
+struct LogVisitor<'a> {
+    log: &'a mut LogEvent,
+}
+
+impl LogVisitor<'_> {
+    fn insert(&mut self, field: &Field, value: impl Into<Value> + fmt::Debug) {
+        match field.name() {
+            "message" => {
+                self.log.insert(log_schema().message_key(), value);
+            }
+            // Only used to configure the rate limiting.
+            "rate_limit_secs" => (),
+            name => {
+                self.log.insert(name, value);
+            }
+        }
+    }
+}
+
+impl Visit for LogVisitor<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value as i64);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{:?}", value));
+    }
+}
@@ -57,6 +57,8 @@ pub mod rename_fields;
 pub mod sampler;
 #[cfg(feature = "transforms-split")]
 pub mod split;
+#[cfg(feature = "transforms-sql")]
+pub mod sql;
 #[cfg(feature = "transforms-swimlanes")]
 pub mod swimlanes;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
@@ -5,6 +5,7 @@ use crate::{
         RegexParserConversionFailed, RegexParserEventProcessed, RegexParserFailedMatch,
         RegexParserMissingField, RegexParserTargetExists,
     },
+    sources::util::TimeZone,
     transforms::{FunctionTransform, Transform},
     types::{parse_check_conversion_map, Conversion},
 };
@@ -32,6 +33,8 @@ pub struct RegexParserConfig {
     #[derivative(Default(value = "true"))]
     pub overwrite_target: bool,
     pub types: HashMap<String, String>,
+    /// The time zone of timestamps converted without zone information.
+    pub timezone: TimeZone,
 }
 
 inventory::submit! {
@@ -76,10 +79,11 @@ struct CompiledRegex {
     regex: Regex,
     capture_names: Vec<(usize, String, Conversion)>,
     capture_locs: CaptureLocations,
+    timezone: TimeZone,
 }
 
 impl CompiledRegex {
-    fn new(regex: Regex, types: &HashMap<String, Conversion>) -> CompiledRegex {
+    fn new(regex: Regex, types: &HashMap<String, Conversion>, timezone: TimeZone) -> CompiledRegex {
         // Calculate the location (index into the capture locations) of
         // each named capture, and the required type coercion.
         let capture_names = regex
@@ -98,6 +102,7 @@ impl CompiledRegex {
             regex,
             capture_names,
             capture_locs,
+            timezone,
         }
     }
 
@@ -110,6 +115,7 @@ impl CompiledRegex {
         match self.regex.captures_read(&mut self.capture_locs, value) {
             Some(_) => {
                 let capture_locs = &self.capture_locs;
+                let timezone = self.timezone;
                 let values =
                     self.capture_names
                         .iter()
@@ -118,7 +124,7 @@ impl CompiledRegex {
                                 let capture: Value =
                                     Value::from(Bytes::from(value[start..end].to_owned()));
 
-                                match conversion.convert(capture) {
+                                match conversion.convert_in(capture, timezone) {
                                     Ok(value) => Some((name.clone(), value)),
                                     Err(error) => {
                                         emit!(RegexParserConversionFailed { name, error });
@@ -196,6 +202,7 @@ impl RegexParser {
             config.target_field.clone(),
             config.overwrite_target,
             types,
+            config.timezone,
         )))
     }
 
@@ -208,12 +215,13 @@ impl RegexParser {
         target_field: Option<String>,
         overwrite_target: bool,
         types: HashMap<String, Conversion>,
+        timezone: TimeZone,
     ) -> Self {
         // Build a buffer of the regex capture locations and names to avoid
         // repeated allocations.
         let patterns: Vec<CompiledRegex> = patterns
             .into_iter()
-            .map(|regex| CompiledRegex::new(regex, &types))
+            .map(|regex| CompiledRegex::new(regex, &types, timezone))
             .collect();
 
         // Pre-calculate if the source field name should be dropped.
@@ -1,10 +1,11 @@
 use crate::{
     config::{DataType, TransformConfig, TransformDescription},
     event::Event,
-    internal_events::{RemapEventProcessed, RemapMappingError},
+    internal_events::{RemapEventAborted, RemapEventProcessed, RemapMappingError},
     transforms::{FunctionTransform, Transform},
     Result,
 };
+use indexmap::IndexMap;
 use remap::{value, Program, Runtime, TypeConstraint, TypeDef};
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +15,34 @@ use serde::{Deserialize, Serialize};
 pub struct RemapConfig {
     pub source: String,
     pub drop_on_err: bool,
+    /// Sends the events the program aborted to the `<name>.aborted` output
+    /// rather than dropping them.
+    pub reroute_aborted: bool,
+    /// The field the reason of an abort is written to, on the events of the
+    /// aborted output.
+    #[derivative(Default(value = "default_abort_reason_field()"))]
+    pub abort_reason_field: String,
+}
+
+fn default_abort_reason_field() -> String {
+    "abort_reason".to_owned()
+}
+
+/// One of the outputs of a remap transform rerouting its aborted events.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemapOutputConfig {
+    remap: RemapConfig,
+    output: RemapOutput,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemapOutput {
+    /// The events the program ran to completion or failed on.
+    Processed,
+    /// The events the program aborted, with the reason of the abort.
+    Aborted,
 }
 
 inventory::submit! {
@@ -29,6 +58,30 @@ impl TransformConfig for RemapConfig {
         Remap::new(self.clone()).map(Transform::function)
     }
 
+    /// Expands into the transform itself, keeping its name, and its aborted
+    /// output, if the aborted events are rerouted. Both run the program on
+    /// each event.
+    fn expand(&mut self) -> Result<Option<IndexMap<String, Box<dyn TransformConfig>>>> {
+        if !self.reroute_aborted {
+            return Ok(None);
+        }
+
+        let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        for (name, output) in &[
+            ("", RemapOutput::Processed),
+            ("aborted", RemapOutput::Aborted),
+        ] {
+            map.insert(
+                name.to_string(),
+                Box::new(RemapOutputConfig {
+                    remap: self.clone(),
+                    output: *output,
+                }),
+            );
+        }
+        Ok(Some(map))
+    }
+
     fn input_type(&self) -> DataType {
         DataType::Log
     }
@@ -42,14 +95,41 @@ impl TransformConfig for RemapConfig {
     }
 }
 
+#[async_trait::async_trait]
+#[typetag::serde(name = "remap_output")]
+impl TransformConfig for RemapOutputConfig {
+    async fn build(&self) -> Result<Transform> {
+        Remap::with_output(self.remap.clone(), self.output).map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "remap_output"
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Remap {
     program: Program,
     drop_on_err: bool,
+    abort_reason_field: String,
+    output: RemapOutput,
+    rerouted: bool,
 }
 
 impl Remap {
     pub fn new(config: RemapConfig) -> crate::Result<Remap> {
+        Self::with_output(config, RemapOutput::Processed)
+    }
+
+    fn with_output(config: RemapConfig, output: RemapOutput) -> crate::Result<Remap> {
         let accepts = TypeConstraint {
             allow_any: true,
             type_def: TypeDef {
@@ -63,17 +143,44 @@ impl Remap {
         Ok(Remap {
             program,
             drop_on_err: config.drop_on_err,
+            abort_reason_field: config.abort_reason_field,
+            output,
+            rerouted: config.reroute_aborted,
         })
     }
 }
 
 impl FunctionTransform for Remap {
     fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        let mut runtime = Runtime::default();
+        let result = runtime.execute(&mut event, &self.program);
+
+        // The events of a rerouting transform are run by both of its outputs,
+        // of which only the processed one reports them.
+        if self.output == RemapOutput::Aborted {
+            if let Err(error) = &result {
+                if let Some(reason) = error.abort_reason() {
+                    if !reason.is_null() {
+                        event
+                            .as_mut_log()
+                            .insert(&self.abort_reason_field, reason.clone());
+                    }
+                    output.push(event);
+                }
+            }
+            return;
+        }
+
         emit!(RemapEventProcessed);
 
-        let mut runtime = Runtime::default();
+        if let Err(error) = result {
+            if error.abort_reason().is_some() {
+                emit!(RemapEventAborted {
+                    rerouted: self.rerouted
+                });
+                return;
+            }
 
-        if let Err(error) = runtime.execute(&mut event, &self.program) {
             emit!(RemapMappingError {
                 error: error.to_string(),
                 event_dropped: self.drop_on_err,
@@ -116,6 +223,7 @@ mod tests {
 "#
             .to_string(),
             drop_on_err: true,
+            ..Default::default()
         };
         let mut tform = Remap::new(conf).unwrap();
 
@@ -126,4 +234,58 @@ mod tests {
         assert_eq!(get_field_string(&result, "bar"), "baz");
         assert_eq!(get_field_string(&result, "copy"), "buz");
     }
+
+    #[test]
+    fn aborts_with_reason() {
+        let config = RemapConfig {
+            source: r#"
+                if .status == 403 {
+                    abort(["denied", .status])
+                }
+                .visited = true
+            "#
+            .to_string(),
+            reroute_aborted: true,
+            ..Default::default()
+        };
+        let mut processed = Remap::new(config.clone()).unwrap();
+        let mut aborted = Remap::with_output(config, RemapOutput::Aborted).unwrap();
+
+        let denied = {
+            let mut event = Event::from("denied");
+            event.as_mut_log().insert("status", 403);
+            event
+        };
+        assert!(processed.transform_one(denied.clone()).is_none());
+        let result = aborted.transform_one(denied).unwrap();
+        assert_eq!(get_field_string(&result, "abort_reason[0]"), "denied");
+        assert_eq!(get_field_string(&result, "abort_reason[1]"), "403");
+        assert!(!result.as_log().contains("visited"));
+
+        let allowed = {
+            let mut event = Event::from("allowed");
+            event.as_mut_log().insert("status", 200);
+            event
+        };
+        assert!(aborted.transform_one(allowed.clone()).is_none());
+        let result = processed.transform_one(allowed).unwrap();
+        assert_eq!(get_field_string(&result, "visited"), "true");
+    }
+
+    #[test]
+    fn expands_into_aborted_output() {
+        let mut config: RemapConfig = toml::from_str(
+            r#"
+            source = "abort()"
+            reroute_aborted = true
+            "#,
+        )
+        .unwrap();
+
+        let expanded = config.expand().unwrap().unwrap();
+        assert_eq!(expanded.keys().collect::<Vec<_>>(), vec!["", "aborted"]);
+
+        config.reroute_aborted = false;
+        assert!(config.expand().unwrap().is_none());
+    }
 }
@@ -4,6 +4,7 @@ use crate::{
     event::Event,
     internal_events::{SamplerEventDiscarded, SamplerEventProcessed},
     transforms::{FunctionTransform, Transform},
+    tuning::{self, Tunable},
 };
 use serde::{Deserialize, Serialize};
 
@@ -60,6 +61,7 @@ impl TransformConfig for SamplerConfig {
 #[derive(Clone)]
 pub struct Sampler {
     rate: u64,
+    rate_override: Tunable,
     key_field: Option<String>,
     exclude: Option<Box<dyn Condition>>,
     count: u64,
@@ -69,6 +71,7 @@ impl Sampler {
     pub fn new(rate: u64, key_field: Option<String>, exclude: Option<Box<dyn Condition>>) -> Self {
         Self {
             rate,
+            rate_override: tuning::register(tuning::SAMPLE_RATE),
             key_field,
             exclude,
             count: 0,
@@ -99,12 +102,11 @@ impl FunctionTransform for Sampler {
             self.count
         };
 
-        self.count = (self.count + 1) % self.rate;
+        let rate = self.rate_override.get().unwrap_or(self.rate);
+        self.count = (self.count + 1) % rate;
 
-        if num % self.rate == 0 {
-            event
-                .as_mut_log()
-                .insert("sample_rate", self.rate.to_string());
+        if num % rate == 0 {
+            event.as_mut_log().insert("sample_rate", rate.to_string());
             output.push(event);
         } else {
             emit!(SamplerEventDiscarded);
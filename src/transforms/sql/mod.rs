@@ -0,0 +1,690 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, TransformConfig, TransformDescription},
+    event::{discriminant::Discriminant, Event, LogEvent, Value},
+    internal_events::{SqlAggregateValueInvalid, SqlEventProcessed},
+    transforms::{
+        util::runtime_transform::{RuntimeTransform, Timer},
+        FunctionTransform, Transform,
+    },
+};
+use chrono::{DateTime, TimeZone, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{cmp::Ordering, collections::BTreeMap};
+
+mod parser;
+
+use parser::*;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid query: {}", source))]
+    InvalidQuery { source: ParseError },
+    #[snafu(display(
+        "Column {:?} must appear in GROUP BY or be used in an aggregate function",
+        field
+    ))]
+    UngroupedColumn { field: String },
+    #[snafu(display("Aggregate functions and GROUP BY require a `tumble` window"))]
+    MissingWindow,
+    #[snafu(display("`SELECT *` can't be used in an aggregating query"))]
+    AggregateAllColumns,
+    #[snafu(display("Duplicate column name {:?}", name))]
+    DuplicateColumn { name: String },
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SqlConfig {
+    pub query: String,
+
+    /// How often windows are checked for (and flushed) once they ended,
+    /// rounded up to whole seconds.
+    pub flush_period_ms: Option<u64>,
+
+    /// How long results are held back after their window ended, to include
+    /// events that arrive late.
+    pub allowed_lateness_ms: Option<u64>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<SqlConfig>("sql")
+}
+
+impl GenerateConfig for SqlConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"query = """
+SELECT count(*), service FROM input
+WHERE level = 'error'
+GROUP BY tumble(interval '1m'), service
+""""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sql")]
+impl TransformConfig for SqlConfig {
+    async fn build(&self) -> crate::Result<Transform> {
+        let query = parser::parse(&self.query).context(InvalidQuery)?;
+        validate(&query)?;
+
+        Ok(match query.window {
+            None => Transform::function(SqlSelect {
+                columns: query.columns,
+                filter: query.filter,
+            }),
+            Some(window) => {
+                let size = window.size.as_millis() as i64;
+                Transform::task(SqlAggregate {
+                    columns: query.columns.unwrap_or_default(),
+                    filter: query.filter,
+                    timestamp_field: window
+                        .field
+                        .unwrap_or_else(|| log_schema().timestamp_key().to_owned()),
+                    size,
+                    group_by: query.group_by,
+                    flush_period_secs: ((self.flush_period_ms.unwrap_or(1000) + 999) / 1000).max(1),
+                    allowed_lateness: self.allowed_lateness_ms.unwrap_or(1000) as i64,
+                    windows: BTreeMap::new(),
+                })
+            }
+        })
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sql"
+    }
+}
+
+fn validate(query: &Query) -> Result<(), BuildError> {
+    let columns = query.columns.as_deref().unwrap_or_default();
+
+    let mut names = Vec::new();
+    for column in columns {
+        if names.contains(&&column.name) {
+            return Err(BuildError::DuplicateColumn {
+                name: column.name.clone(),
+            });
+        }
+        names.push(&column.name);
+    }
+
+    let aggregating = query.window.is_some()
+        || !query.group_by.is_empty()
+        || columns
+            .iter()
+            .any(|column| !matches!(column.expr, ColumnExpr::Field(_)));
+    if !aggregating {
+        return Ok(());
+    }
+
+    if query.window.is_none() {
+        return Err(BuildError::MissingWindow);
+    }
+    if query.columns.is_none() {
+        return Err(BuildError::AggregateAllColumns);
+    }
+    for column in columns {
+        if let ColumnExpr::Field(field) = &column.expr {
+            if !query.group_by.contains(field) {
+                return Err(BuildError::UngroupedColumn {
+                    field: field.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
+/// A query without a window, filtering and projecting every event on its own.
+#[derive(Clone, Debug)]
+struct SqlSelect {
+    columns: Option<Vec<Column>>,
+    filter: Option<Expr>,
+}
+
+impl FunctionTransform for SqlSelect {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        emit!(SqlEventProcessed);
+
+        let log = event.into_log();
+        if let Some(filter) = &self.filter {
+            if !evaluate(filter, &log) {
+                return;
+            }
+        }
+
+        let log = match &self.columns {
+            None => log,
+            Some(columns) => {
+                let mut projected = LogEvent::default();
+                for column in columns {
+                    if let ColumnExpr::Field(field) = &column.expr {
+                        if let Some(value) = log.get(field) {
+                            projected.insert(&column.name, value.clone());
+                        }
+                    }
+                }
+                projected
+            }
+        };
+        output.push(Event::from(log));
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A query aggregating the events of every group in a window, emitting the
+/// results once the window ended.
+struct SqlAggregate {
+    columns: Vec<Column>,
+    filter: Option<Expr>,
+    timestamp_field: String,
+    /// The window size in milliseconds.
+    size: i64,
+    group_by: Vec<String>,
+    /// How often ended windows are flushed, the timers of the runtime
+    /// transforms having a resolution of a second.
+    flush_period_secs: u64,
+    allowed_lateness: i64,
+    /// The groups of every window, by the start of the window in milliseconds.
+    windows: BTreeMap<i64, IndexMap<Discriminant, Vec<Accumulator>>>,
+}
+
+impl SqlAggregate {
+    fn transform_one(&mut self, event: Event) {
+        emit!(SqlEventProcessed);
+
+        let log = event.into_log();
+        if let Some(filter) = &self.filter {
+            if !evaluate(filter, &log) {
+                return;
+            }
+        }
+
+        let timestamp = match log.get(&self.timestamp_field) {
+            Some(Value::Timestamp(timestamp)) => *timestamp,
+            _ => Utc::now(),
+        }
+        .timestamp_millis();
+        let start = timestamp - timestamp.rem_euclid(self.size);
+
+        let columns = &self.columns;
+        let accumulators = self
+            .windows
+            .entry(start)
+            .or_default()
+            .entry(Discriminant::from_log_event(&log, &self.group_by))
+            .or_insert_with(|| {
+                columns
+                    .iter()
+                    .map(|column| Accumulator::new(&column.expr))
+                    .collect()
+            });
+        for (accumulator, column) in accumulators.iter_mut().zip(columns) {
+            accumulator.add(&column.expr, &log);
+        }
+    }
+
+    fn flush_into<F>(&mut self, mut emit_fn: F, all: bool)
+    where
+        F: FnMut(Event),
+    {
+        let now = Utc::now().timestamp_millis() - self.allowed_lateness;
+        let size = self.size;
+        let ended = self
+            .windows
+            .keys()
+            .copied()
+            .take_while(|start| all || start + size <= now)
+            .collect::<Vec<_>>();
+
+        for start in ended {
+            let groups = match self.windows.remove(&start) {
+                Some(groups) => groups,
+                None => continue,
+            };
+            let window_start = Utc.timestamp_millis(start);
+            let window_end = Utc.timestamp_millis(start + size);
+            for (_, accumulators) in groups {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().timestamp_key(), window_start);
+                for (column, accumulator) in self.columns.iter().zip(accumulators) {
+                    if let Some(value) = accumulator.finish(window_start, window_end) {
+                        log.insert(&column.name, value);
+                    }
+                }
+                emit_fn(Event::from(log));
+            }
+        }
+    }
+}
+
+impl RuntimeTransform for SqlAggregate {
+    fn hook_process<F>(&mut self, event: Event, _emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.transform_one(event);
+    }
+
+    fn hook_shutdown<F>(&mut self, emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.flush_into(emit_fn, true);
+    }
+
+    fn timer_handler<F>(&mut self, _timer: Timer, emit_fn: F)
+    where
+        F: FnMut(Event),
+    {
+        self.flush_into(emit_fn, false);
+    }
+
+    fn timers(&self) -> Vec<Timer> {
+        vec![Timer {
+            id: 0,
+            interval_seconds: self.flush_period_secs,
+        }]
+    }
+}
+
+/// The state of a column for one group of a window.
+#[derive(Debug)]
+enum Accumulator {
+    Key(Option<Value>),
+    Count(i64),
+    Sum(Option<Value>),
+    Avg { sum: f64, count: u64 },
+    Min(Option<Value>),
+    Max(Option<Value>),
+    WindowStart,
+    WindowEnd,
+}
+
+impl Accumulator {
+    fn new(expr: &ColumnExpr) -> Self {
+        match expr {
+            ColumnExpr::Field(_) => Accumulator::Key(None),
+            ColumnExpr::Aggregate(AggregateFunction::Count, _) => Accumulator::Count(0),
+            ColumnExpr::Aggregate(AggregateFunction::Sum, _) => Accumulator::Sum(None),
+            ColumnExpr::Aggregate(AggregateFunction::Avg, _) => {
+                Accumulator::Avg { sum: 0.0, count: 0 }
+            }
+            ColumnExpr::Aggregate(AggregateFunction::Min, _) => Accumulator::Min(None),
+            ColumnExpr::Aggregate(AggregateFunction::Max, _) => Accumulator::Max(None),
+            ColumnExpr::WindowStart => Accumulator::WindowStart,
+            ColumnExpr::WindowEnd => Accumulator::WindowEnd,
+        }
+    }
+
+    fn add(&mut self, expr: &ColumnExpr, log: &LogEvent) {
+        let (function, field) = match expr {
+            ColumnExpr::Aggregate(function, field) => (*function, field),
+            ColumnExpr::Field(field) => {
+                // Every event of a group has the same value.
+                if let Accumulator::Key(key) = self {
+                    if key.is_none() {
+                        *key = log.get(field).cloned();
+                    }
+                }
+                return;
+            }
+            ColumnExpr::WindowStart | ColumnExpr::WindowEnd => return,
+        };
+
+        // Like in SQL, only `count(*)` includes missing and null values.
+        let (field, value) = match field {
+            None => {
+                if let Accumulator::Count(count) = self {
+                    *count += 1;
+                }
+                return;
+            }
+            Some(field) => match log.get(field) {
+                None | Some(Value::Null) => return,
+                Some(value) => (field, value),
+            },
+        };
+
+        if matches!(self, Accumulator::Sum(_) | Accumulator::Avg { .. }) && as_f64(value).is_none()
+        {
+            emit!(SqlAggregateValueInvalid {
+                function: function.name(),
+                field,
+            });
+            return;
+        }
+
+        match self {
+            Accumulator::Count(count) => *count += 1,
+            Accumulator::Sum(sum) => {
+                *sum = Some(match (sum.take(), value) {
+                    (None, value) => value.clone(),
+                    (Some(Value::Integer(sum)), Value::Integer(value)) => sum
+                        .checked_add(*value)
+                        .map(Value::Integer)
+                        .unwrap_or_else(|| Value::Float(sum as f64 + *value as f64)),
+                    (Some(sum), value) => Value::Float(
+                        as_f64(&sum).unwrap_or_default() + as_f64(value).unwrap_or_default(),
+                    ),
+                })
+            }
+            Accumulator::Avg { sum, count } => {
+                *sum += as_f64(value).unwrap_or_default();
+                *count += 1;
+            }
+            Accumulator::Min(min) => {
+                if min
+                    .as_ref()
+                    .map_or(true, |min| compare(value, min) == Some(Ordering::Less))
+                {
+                    *min = Some(value.clone());
+                }
+            }
+            Accumulator::Max(max) => {
+                if max
+                    .as_ref()
+                    .map_or(true, |max| compare(value, max) == Some(Ordering::Greater))
+                {
+                    *max = Some(value.clone());
+                }
+            }
+            Accumulator::Key(_) | Accumulator::WindowStart | Accumulator::WindowEnd => (),
+        }
+    }
+
+    fn finish(self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<Value> {
+        match self {
+            Accumulator::Key(value)
+            | Accumulator::Sum(value)
+            | Accumulator::Min(value)
+            | Accumulator::Max(value) => value,
+            Accumulator::Count(count) => Some(Value::Integer(count)),
+            Accumulator::Avg { sum, count } => {
+                if count == 0 {
+                    None
+                } else {
+                    Some(Value::Float(sum / count as f64))
+                }
+            }
+            Accumulator::WindowStart => Some(Value::Timestamp(start)),
+            Accumulator::WindowEnd => Some(Value::Timestamp(end)),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Evaluate a `WHERE` clause. Comparisons involving missing fields, nulls or
+/// values of different types are false.
+fn evaluate(expr: &Expr, log: &LogEvent) -> bool {
+    match expr {
+        Expr::And(left, right) => evaluate(left, log) && evaluate(right, log),
+        Expr::Or(left, right) => evaluate(left, log) || evaluate(right, log),
+        Expr::Not(expr) => !evaluate(expr, log),
+        Expr::Compare { left, op, right } => match (resolve(left, log), resolve(right, log)) {
+            (Some(left), Some(right)) => compare(left, right).map_or(false, |ordering| match op {
+                CompareOp::Eq => ordering == Ordering::Equal,
+                CompareOp::Ne => ordering != Ordering::Equal,
+                CompareOp::Lt => ordering == Ordering::Less,
+                CompareOp::Le => ordering != Ordering::Greater,
+                CompareOp::Gt => ordering == Ordering::Greater,
+                CompareOp::Ge => ordering != Ordering::Less,
+            }),
+            _ => false,
+        },
+        Expr::IsNull { operand, negated } => {
+            matches!(resolve(operand, log), None | Some(Value::Null)) != *negated
+        }
+        Expr::In {
+            operand,
+            values,
+            negated,
+        } => match resolve(operand, log) {
+            None | Some(Value::Null) => false,
+            Some(value) => {
+                values
+                    .iter()
+                    .any(|candidate| compare(value, candidate) == Some(Ordering::Equal))
+                    != *negated
+            }
+        },
+    }
+}
+
+fn resolve<'a>(operand: &'a Operand, log: &'a LogEvent) -> Option<&'a Value> {
+    match operand {
+        Operand::Field(field) => log.get(field),
+        Operand::Literal(value) => Some(value),
+    }
+}
+
+/// Numbers compare regardless of their type, and timestamps compare with
+/// strings in RFC 3339 format.
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => Some(left.cmp(right)),
+        (Value::Integer(left), Value::Float(right)) => (*left as f64).partial_cmp(right),
+        (Value::Float(left), Value::Integer(right)) => left.partial_cmp(&(*right as f64)),
+        (Value::Float(left), Value::Float(right)) => left.partial_cmp(right),
+        (Value::Bytes(left), Value::Bytes(right)) => Some(left.cmp(right)),
+        (Value::Boolean(left), Value::Boolean(right)) => Some(left.cmp(right)),
+        (Value::Timestamp(left), Value::Timestamp(right)) => Some(left.cmp(right)),
+        (Value::Timestamp(left), Value::Bytes(right)) => {
+            parse_timestamp(right).map(|right| left.cmp(&right))
+        }
+        (Value::Bytes(left), Value::Timestamp(right)) => {
+            parse_timestamp(left).map(|left| left.cmp(right))
+        }
+        _ => None,
+    }
+}
+
+fn parse_timestamp(bytes: &[u8]) -> Option<DateTime<Utc>> {
+    let timestamp = std::str::from_utf8(bytes).ok()?;
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(value) => Some(*value as f64),
+        Value::Float(value) => Some(*value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::compat::Stream01CompatExt;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqlConfig>();
+    }
+
+    async fn build(query: &str, allowed_lateness_ms: Option<u64>) -> crate::Result<Transform> {
+        SqlConfig {
+            query: query.to_owned(),
+            flush_period_ms: None,
+            allowed_lateness_ms,
+        }
+        .build()
+        .await
+    }
+
+    fn log(fields: &[(&str, Value)]) -> Event {
+        let mut event = Event::from("message");
+        for (field, value) in fields {
+            event.as_mut_log().insert(field, value.clone());
+        }
+        event
+    }
+
+    #[tokio::test]
+    async fn filters_and_projects() {
+        let mut transform = build(
+            "SELECT service, status AS code FROM input \
+             WHERE level = 'error' AND (status >= 500 OR status IS NULL)",
+            None,
+        )
+        .await
+        .unwrap()
+        .into_function();
+
+        let mut output = Vec::new();
+        for event in vec![
+            log(&[
+                ("level", "error".into()),
+                ("service", "api".into()),
+                ("status", 502.into()),
+            ]),
+            log(&[
+                ("level", "error".into()),
+                ("service", "web".into()),
+                ("status", 404.into()),
+            ]),
+            log(&[
+                ("level", "info".into()),
+                ("service", "api".into()),
+                ("status", 500.into()),
+            ]),
+            log(&[("level", "error".into()), ("service", "db".into())]),
+        ] {
+            transform.transform(&mut output, event);
+        }
+
+        assert_eq!(output.len(), 2);
+        let fields = output[0].as_log().keys().collect::<Vec<_>>();
+        assert_eq!(fields, vec!["code", "service"]);
+        assert_eq!(output[0].as_log()["service"], "api".into());
+        assert_eq!(output[0].as_log()["code"], 502.into());
+        assert_eq!(output[1].as_log()["service"], "db".into());
+        assert!(output[1].as_log().get("code").is_none());
+    }
+
+    #[tokio::test]
+    async fn aggregates_windows() {
+        let transform = build(
+            "SELECT count(*), service, sum(bytes), avg(bytes) AS mean, max(status), \
+             window_end() FROM input WHERE level IS NULL OR level != 'debug' \
+             GROUP BY tumble(interval '1m'), service",
+            // Only flush the windows once the input ended.
+            Some(u32::MAX as u64 * 1000),
+        )
+        .await
+        .unwrap()
+        .into_task();
+
+        let minute = |minute, second| Value::from(Utc.ymd(2020, 12, 1).and_hms(10, minute, second));
+        let inputs = vec![
+            log(&[
+                ("timestamp", minute(0, 5)),
+                ("service", "api".into()),
+                ("bytes", 10.into()),
+                ("status", 200.into()),
+            ]),
+            log(&[
+                ("timestamp", minute(0, 10)),
+                ("service", "web".into()),
+                ("bytes", 5.into()),
+            ]),
+            log(&[
+                ("timestamp", minute(0, 20)),
+                ("service", "api".into()),
+                ("bytes", 2.5.into()),
+                ("status", 503.into()),
+            ]),
+            log(&[
+                ("timestamp", minute(0, 30)),
+                ("service", "api".into()),
+                ("level", "debug".into()),
+            ]),
+            log(&[
+                ("timestamp", minute(1, 0)),
+                ("service", "api".into()),
+                ("bytes", "many".into()),
+            ]),
+        ];
+        let output = transform
+            .transform(Box::new(futures01::stream::iter_ok(inputs)))
+            .compat()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(output.len(), 3);
+
+        let api = output[0].as_log();
+        assert_eq!(api["timestamp"], minute(0, 0));
+        assert_eq!(api["service"], "api".into());
+        assert_eq!(api["count"], 2.into());
+        assert_eq!(api["sum"], 12.5.into());
+        assert_eq!(api["mean"], 6.25.into());
+        assert_eq!(api["max"], 503.into());
+        assert_eq!(api["window_end"], minute(1, 0));
+
+        let web = output[1].as_log();
+        assert_eq!(web["service"], "web".into());
+        assert_eq!(web["count"], 1.into());
+        assert_eq!(web["sum"], 5.into());
+        assert!(web.get("max").is_none());
+
+        let next = output[2].as_log();
+        assert_eq!(next["timestamp"], minute(1, 0));
+        assert_eq!(next["count"], 1.into());
+        assert!(next.get("sum").is_none());
+        assert!(next.get("mean").is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_queries() {
+        let error = |query: &'static str| async move {
+            match build(query, None).await {
+                Ok(_) => panic!("query {:?} should be invalid", query),
+                Err(error) => error.to_string(),
+            }
+        };
+
+        assert_eq!(
+            error("SELECT count(*), service FROM input GROUP BY tumble(interval '1m')").await,
+            r#"Column "service" must appear in GROUP BY or be used in an aggregate function"#
+        );
+        assert_eq!(
+            error("SELECT count(*) FROM input").await,
+            "Aggregate functions and GROUP BY require a `tumble` window"
+        );
+        assert_eq!(
+            error("SELECT * FROM input GROUP BY tumble(interval '1m')").await,
+            "`SELECT *` can't be used in an aggregating query"
+        );
+        assert_eq!(
+            error("SELECT a, b AS a FROM input").await,
+            r#"Duplicate column name "a""#
+        );
+        assert_eq!(
+            error("SELECT FROM input").await,
+            "Invalid query: Expected a column at offset 7, found `FROM`"
+        );
+    }
+}
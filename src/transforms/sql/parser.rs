@@ -0,0 +1,758 @@
+use crate::event::Value;
+use snafu::Snafu;
+use std::{fmt, time::Duration};
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Unexpected character {:?} at offset {}", character, offset))]
+    InvalidCharacter { character: char, offset: usize },
+    #[snafu(display("Unterminated {} starting at offset {}", kind, offset))]
+    Unterminated { kind: &'static str, offset: usize },
+    #[snafu(display("Invalid number {:?} at offset {}", number, offset))]
+    InvalidNumber { number: String, offset: usize },
+    #[snafu(display("Expected {} at offset {}, found {}", expected, offset, found))]
+    Unexpected {
+        expected: String,
+        found: String,
+        offset: usize,
+    },
+    #[snafu(display("Expected {} at the end of the query", expected))]
+    UnexpectedEnd { expected: String },
+    #[snafu(display("Unknown function {:?} at offset {}", name, offset))]
+    UnknownFunction { name: String, offset: usize },
+    #[snafu(display("Invalid interval {:?} at offset {}", interval, offset))]
+    InvalidInterval { interval: String, offset: usize },
+    #[snafu(display(
+        "Only one window can be defined, found another one at offset {}",
+        offset
+    ))]
+    DuplicateWindow { offset: usize },
+}
+
+/// A parsed `SELECT` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    /// The selected columns, `None` for `SELECT *`.
+    pub columns: Option<Vec<Column>>,
+    pub filter: Option<Expr>,
+    pub window: Option<Window>,
+    pub group_by: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub expr: ColumnExpr,
+    /// The field the column is written to.
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnExpr {
+    Field(String),
+    /// An aggregate function of a field, or of every event for `count(*)`.
+    Aggregate(AggregateFunction, Option<String>),
+    WindowStart,
+    WindowEnd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// A `tumble` window, grouping events into consecutive, non-overlapping
+/// intervals of their timestamp.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Window {
+    /// The timestamp field, the global `timestamp_key` if not given.
+    pub field: Option<String>,
+    pub size: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        left: Operand,
+        op: CompareOp,
+        right: Operand,
+    },
+    IsNull {
+        operand: Operand,
+        negated: bool,
+    },
+    In {
+        operand: Operand,
+        values: Vec<Value>,
+        negated: bool,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    Field(String),
+    Literal(Value),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl ColumnExpr {
+    fn default_name(&self) -> String {
+        match self {
+            ColumnExpr::Field(field) => field.clone(),
+            ColumnExpr::Aggregate(function, _) => function.name().to_owned(),
+            ColumnExpr::WindowStart => "window_start".to_owned(),
+            ColumnExpr::WindowEnd => "window_end".to_owned(),
+        }
+    }
+}
+
+impl AggregateFunction {
+    pub fn name(self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        }
+    }
+}
+
+/// Words that have to be quoted to be used as field names.
+const KEYWORDS: &[&str] = &[
+    "and", "as", "by", "false", "from", "group", "in", "interval", "is", "not", "null", "or",
+    "select", "true", "where",
+];
+
+const SYMBOLS: &[&str] = &[
+    "<>", "!=", "<=", ">=", "=", "<", ">", "(", ")", ",", "*", ";",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{}`", ident),
+            Token::QuotedIdent(ident) => write!(f, "`\"{}\"`", ident),
+            Token::String(string) => write!(f, "`'{}'`", string),
+            Token::Integer(integer) => write!(f, "`{}`", integer),
+            Token::Float(float) => write!(f, "`{}`", float),
+            Token::Symbol(symbol) => write!(f, "`{}`", symbol),
+        }
+    }
+}
+
+pub fn parse(query: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        position: 0,
+    };
+    let query = parser.query()?;
+    parser.eat_symbol(";");
+    match parser.peek() {
+        None => Ok(query),
+        Some(_) => Err(parser.unexpected("the end of the query")),
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some(&(offset, c)) = chars.peek() {
+        let rest = &query[offset..];
+        if c.is_whitespace() {
+            chars.next();
+        } else if rest.starts_with("--") {
+            // Comments run to the end of the line.
+            for (_, c) in &mut chars {
+                if c == '\n' {
+                    break;
+                }
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                    ident.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push((offset, Token::Ident(ident)));
+        } else if c.is_ascii_digit()
+            || (c == '-' && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            let mut number = String::new();
+            number.push(c);
+            chars.next();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let token = if number.contains('.') {
+                number.parse().map(Token::Float).ok()
+            } else {
+                number.parse().map(Token::Integer).ok()
+            };
+            match token {
+                Some(token) => tokens.push((offset, token)),
+                None => return Err(ParseError::InvalidNumber { number, offset }),
+            }
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    // Quotes are escaped by doubling them.
+                    Some((_, quote)) if quote == c => match chars.peek() {
+                        Some(&(_, next)) if next == c => {
+                            value.push(c);
+                            chars.next();
+                        }
+                        _ => break,
+                    },
+                    Some((_, other)) => value.push(other),
+                    None => {
+                        let kind = if c == '\'' { "string" } else { "identifier" };
+                        return Err(ParseError::Unterminated { kind, offset });
+                    }
+                }
+            }
+            let token = if c == '\'' {
+                Token::String(value)
+            } else {
+                Token::QuotedIdent(value)
+            };
+            tokens.push((offset, token));
+        } else {
+            match SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+                Some(symbol) => {
+                    for _ in 0..symbol.len() {
+                        chars.next();
+                    }
+                    tokens.push((offset, Token::Symbol(*symbol)));
+                }
+                None => {
+                    return Err(ParseError::InvalidCharacter {
+                        character: c,
+                        offset,
+                    })
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+}
+
+impl Parser {
+    fn query(&mut self) -> Result<Query, ParseError> {
+        self.expect_keyword("SELECT")?;
+        let columns = if self.eat_symbol("*") {
+            None
+        } else {
+            let mut columns = vec![self.column()?];
+            while self.eat_symbol(",") {
+                columns.push(self.column()?);
+            }
+            Some(columns)
+        };
+
+        // Events are read from the `inputs` of the transform, the name in the
+        // `FROM` clause is only there for readability.
+        self.expect_keyword("FROM")?;
+        self.name("an input name")?;
+
+        let filter = if self.eat_keyword("WHERE") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut window = None;
+        let mut group_by = Vec::new();
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            loop {
+                if self.is_call("tumble") {
+                    let offset = self.offset();
+                    if window.replace(self.window()?).is_some() {
+                        return Err(ParseError::DuplicateWindow { offset });
+                    }
+                } else {
+                    group_by.push(self.name("a field name")?);
+                }
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+
+        Ok(Query {
+            columns,
+            filter,
+            window,
+            group_by,
+        })
+    }
+
+    fn column(&mut self) -> Result<Column, ParseError> {
+        let call = match self.peek() {
+            Some(Token::Ident(name)) if self.is_call(name) => Some(name.to_ascii_lowercase()),
+            _ => None,
+        };
+        let expr = match call {
+            Some(name) => self.function(&name)?,
+            None => ColumnExpr::Field(self.name("a column")?),
+        };
+        let name = if self.eat_keyword("AS") {
+            self.name("a column name")?
+        } else {
+            expr.default_name()
+        };
+        Ok(Column { expr, name })
+    }
+
+    fn function(&mut self, name: &str) -> Result<ColumnExpr, ParseError> {
+        let offset = self.offset();
+        // Skip the name and the opening parenthesis.
+        self.position += 2;
+        let function = match name {
+            "count" => AggregateFunction::Count,
+            "sum" => AggregateFunction::Sum,
+            "avg" => AggregateFunction::Avg,
+            "min" => AggregateFunction::Min,
+            "max" => AggregateFunction::Max,
+            "window_start" | "window_end" => {
+                self.expect_symbol(")")?;
+                return Ok(if name == "window_start" {
+                    ColumnExpr::WindowStart
+                } else {
+                    ColumnExpr::WindowEnd
+                });
+            }
+            _ => {
+                return Err(ParseError::UnknownFunction {
+                    name: name.to_owned(),
+                    offset,
+                })
+            }
+        };
+        let field = if function == AggregateFunction::Count && self.eat_symbol("*") {
+            None
+        } else {
+            Some(self.name("a field name")?)
+        };
+        self.expect_symbol(")")?;
+        Ok(ColumnExpr::Aggregate(function, field))
+    }
+
+    fn window(&mut self) -> Result<Window, ParseError> {
+        // Skip `tumble` and the opening parenthesis.
+        self.position += 2;
+        let field = if self.is_keyword("INTERVAL") {
+            None
+        } else {
+            let field = self.name("a timestamp field or INTERVAL")?;
+            self.expect_symbol(",")?;
+            Some(field)
+        };
+        self.expect_keyword("INTERVAL")?;
+
+        let (offset, mut interval) = match self.tokens.get(self.position) {
+            Some((offset, Token::String(interval))) => (*offset, interval.clone()),
+            _ => return Err(self.unexpected("an interval string")),
+        };
+        self.position += 1;
+        // The unit may follow the string, as in `INTERVAL '5' MINUTE`.
+        if let Some(Token::Ident(unit)) = self.peek() {
+            if unit_millis(unit).is_some() {
+                interval = format!("{} {}", interval, unit);
+                self.position += 1;
+            }
+        }
+        let size = parse_interval(&interval)
+            .ok_or_else(|| ParseError::InvalidInterval { interval, offset })?;
+
+        self.expect_symbol(")")?;
+        Ok(Window { field, size })
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.conjunction()?;
+        while self.eat_keyword("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.conjunction()?));
+        }
+        Ok(expr)
+    }
+
+    fn conjunction(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.negation()?;
+        while self.eat_keyword("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.negation()?));
+        }
+        Ok(expr)
+    }
+
+    fn negation(&mut self) -> Result<Expr, ParseError> {
+        if self.eat_keyword("NOT") {
+            Ok(Expr::Not(Box::new(self.negation()?)))
+        } else {
+            self.predicate()
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Expr, ParseError> {
+        if self.eat_symbol("(") {
+            let expr = self.expr()?;
+            self.expect_symbol(")")?;
+            return Ok(expr);
+        }
+
+        let operand = self.operand()?;
+
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull { operand, negated });
+        }
+
+        let negated = self.eat_keyword("NOT");
+        if self.eat_keyword("IN") {
+            self.expect_symbol("(")?;
+            let mut values = vec![self.literal()?];
+            while self.eat_symbol(",") {
+                values.push(self.literal()?);
+            }
+            self.expect_symbol(")")?;
+            return Ok(Expr::In {
+                operand,
+                values,
+                negated,
+            });
+        } else if negated {
+            return Err(self.unexpected("IN"));
+        }
+
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => CompareOp::Eq,
+            Some(Token::Symbol("!=")) | Some(Token::Symbol("<>")) => CompareOp::Ne,
+            Some(Token::Symbol("<")) => CompareOp::Lt,
+            Some(Token::Symbol("<=")) => CompareOp::Le,
+            Some(Token::Symbol(">")) => CompareOp::Gt,
+            Some(Token::Symbol(">=")) => CompareOp::Ge,
+            _ => return Err(self.unexpected("a comparison operator")),
+        };
+        self.position += 1;
+
+        let right = self.operand()?;
+        Ok(Expr::Compare {
+            left: operand,
+            op,
+            right,
+        })
+    }
+
+    fn operand(&mut self) -> Result<Operand, ParseError> {
+        match self.try_literal() {
+            Some(value) => Ok(Operand::Literal(value)),
+            None => self.name("a field name or a literal").map(Operand::Field),
+        }
+    }
+
+    fn literal(&mut self) -> Result<Value, ParseError> {
+        self.try_literal()
+            .ok_or_else(|| self.unexpected("a literal"))
+    }
+
+    fn try_literal(&mut self) -> Option<Value> {
+        let value = match self.peek()? {
+            Token::String(string) => Value::from(string.clone()),
+            Token::Integer(integer) => Value::Integer(*integer),
+            Token::Float(float) => Value::Float(*float),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("true") => Value::Boolean(true),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("false") => Value::Boolean(false),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("null") => Value::Null,
+            _ => return None,
+        };
+        self.position += 1;
+        Some(value)
+    }
+
+    /// A field or column name, either a bare identifier that isn't a keyword
+    /// or a double quoted one.
+    fn name(&mut self, expected: &str) -> Result<String, ParseError> {
+        let name = match self.peek() {
+            Some(Token::QuotedIdent(name)) => name.clone(),
+            Some(Token::Ident(name)) if !is_keyword(name) => name.clone(),
+            _ => return Err(self.unexpected(expected)),
+        };
+        self.position += 1;
+        Ok(name)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(0, |(offset, _)| *offset)
+    }
+
+    /// Whether the next tokens are the named function followed by an opening
+    /// parenthesis.
+    fn is_call(&self, name: &str) -> bool {
+        self.is_keyword(name)
+            && matches!(
+                self.tokens.get(self.position + 1),
+                Some((_, Token::Symbol("(")))
+            )
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(found)) if *found == symbol);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", symbol)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        match self.tokens.get(self.position) {
+            Some((offset, token)) => ParseError::Unexpected {
+                expected: expected.to_owned(),
+                found: token.to_string(),
+                offset: *offset,
+            },
+            None => ParseError::UnexpectedEnd {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+}
+
+fn is_keyword(ident: &str) -> bool {
+    KEYWORDS
+        .iter()
+        .any(|keyword| ident.eq_ignore_ascii_case(keyword))
+}
+
+/// Parse intervals such as `1m`, `30 seconds` or `1 hour`.
+fn parse_interval(interval: &str) -> Option<Duration> {
+    let interval = interval.trim();
+    let split = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(interval.len());
+    let (amount, unit) = interval.split_at(split);
+    let millis = amount
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(unit_millis(unit.trim())?)?;
+    if millis == 0 {
+        None
+    } else {
+        Some(Duration::from_millis(millis))
+    }
+}
+
+fn unit_millis(unit: &str) -> Option<u64> {
+    match unit.to_ascii_lowercase().as_str() {
+        "ms" | "millisecond" | "milliseconds" => Some(1),
+        "s" | "sec" | "second" | "seconds" => Some(1_000),
+        "m" | "min" | "minute" | "minutes" => Some(60_000),
+        "h" | "hour" | "hours" => Some(3_600_000),
+        "d" | "day" | "days" => Some(86_400_000),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> Operand {
+        Operand::Field(name.to_owned())
+    }
+
+    #[test]
+    fn parses_aggregating_query() {
+        let query = parse(
+            "SELECT count(*), service, avg(duration) AS mean FROM input \
+             WHERE level = 'error' GROUP BY tumble(interval '1m'), service",
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            Query {
+                columns: Some(vec![
+                    Column {
+                        expr: ColumnExpr::Aggregate(AggregateFunction::Count, None),
+                        name: "count".to_owned(),
+                    },
+                    Column {
+                        expr: ColumnExpr::Field("service".to_owned()),
+                        name: "service".to_owned(),
+                    },
+                    Column {
+                        expr: ColumnExpr::Aggregate(
+                            AggregateFunction::Avg,
+                            Some("duration".to_owned())
+                        ),
+                        name: "mean".to_owned(),
+                    },
+                ]),
+                filter: Some(Expr::Compare {
+                    left: field("level"),
+                    op: CompareOp::Eq,
+                    right: Operand::Literal("error".into()),
+                }),
+                window: Some(Window {
+                    field: None,
+                    size: Duration::from_secs(60),
+                }),
+                group_by: vec!["service".to_owned()],
+            }
+        );
+    }
+
+    #[test]
+    fn parses_filter_precedence() {
+        let query = parse(
+            r#"select * from "my-input" where not a is null or b in (1, 2.5) and "c d" <> 'x';"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.columns, None);
+        assert_eq!(
+            query.filter,
+            Some(Expr::Or(
+                Box::new(Expr::Not(Box::new(Expr::IsNull {
+                    operand: field("a"),
+                    negated: false,
+                }))),
+                Box::new(Expr::And(
+                    Box::new(Expr::In {
+                        operand: field("b"),
+                        values: vec![Value::Integer(1), Value::Float(2.5)],
+                        negated: false,
+                    }),
+                    Box::new(Expr::Compare {
+                        left: field("c d"),
+                        op: CompareOp::Ne,
+                        right: Operand::Literal("x".into()),
+                    }),
+                )),
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_intervals() {
+        let size = |query: &str| parse(query).unwrap().window.unwrap().size;
+
+        assert_eq!(
+            size("SELECT count(*) FROM input GROUP BY tumble(interval '30s')"),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            size("SELECT count(*) FROM input GROUP BY tumble(ts, INTERVAL '5' MINUTES)"),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            size("SELECT count(*) FROM input GROUP BY tumble(interval '1 hour')"),
+            Duration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        let error = |query: &str| parse(query).unwrap_err().to_string();
+
+        assert_eq!(
+            error("SELECT count(*) input"),
+            "Expected FROM at offset 16, found `input`"
+        );
+        assert_eq!(
+            error("SELECT median(x) FROM input"),
+            r#"Unknown function "median" at offset 7"#
+        );
+        assert_eq!(
+            error("SELECT * FROM input WHERE level = 'error"),
+            "Unterminated string starting at offset 34"
+        );
+        assert_eq!(
+            error("SELECT count(*) FROM input GROUP BY tumble(interval '0s')"),
+            r#"Invalid interval "0s" at offset 52"#
+        );
+        assert_eq!(
+            error("SELECT * FROM input WHERE level ="),
+            "Expected a field name or a literal at the end of the query"
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     config::{DataType, TransformConfig, TransformDescription},
     event::{Event, PathComponent, PathIter, Value},
     internal_events::{TokenizerConvertFailed, TokenizerEventProcessed, TokenizerFieldMissing},
+    sources::util::TimeZone,
     transforms::{FunctionTransform, Transform},
     types::{parse_check_conversion_map, Conversion},
 };
@@ -17,6 +18,8 @@ pub struct TokenizerConfig {
     pub field: Option<String>,
     pub drop_field: bool,
     pub types: HashMap<String, String>,
+    /// The time zone of timestamps converted without zone information.
+    pub timezone: TimeZone,
 }
 
 inventory::submit! {
@@ -44,6 +47,7 @@ impl TransformConfig for TokenizerConfig {
             field,
             drop_field,
             types,
+            self.timezone,
         )))
     }
 
@@ -65,6 +69,7 @@ pub struct Tokenizer {
     field_names: Vec<(String, Vec<PathComponent>, Conversion)>,
     field: String,
     drop_field: bool,
+    timezone: TimeZone,
 }
 
 impl Tokenizer {
@@ -73,6 +78,7 @@ impl Tokenizer {
         field: String,
         drop_field: bool,
         types: HashMap<String, Conversion>,
+        timezone: TimeZone,
     ) -> Self {
         let field_names = field_names
             .into_iter()
@@ -87,6 +93,7 @@ impl Tokenizer {
             field_names,
             field,
             drop_field,
+            timezone,
         }
     }
 }
@@ -99,7 +106,7 @@ impl FunctionTransform for Tokenizer {
             for ((name, path, conversion), value) in
                 self.field_names.iter().zip(parse(value).into_iter())
             {
-                match conversion.convert(Value::from(value.to_owned())) {
+                match conversion.convert_in(Value::from(value.to_owned()), self.timezone) {
                     Ok(value) => {
                         event.as_mut_log().insert_path(path.clone(), value);
                     }
@@ -147,6 +154,7 @@ mod tests {
             field,
             drop_field,
             types: types.iter().map(|&(k, v)| (k.into(), v.into())).collect(),
+            timezone: Default::default(),
         }
         .build()
         .await
@@ -1,4 +1,4 @@
-#[cfg(any(feature = "transforms-lua"))]
+#[cfg(any(feature = "transforms-lua", feature = "transforms-sql"))]
 pub mod runtime_transform;
 
 pub mod tokenize;
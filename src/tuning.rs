@@ -0,0 +1,225 @@
+//! Options of running components which can be changed at runtime, such as
+//! through the API, to react to incidents without reloading the config.
+//!
+//! Components register their tunable options while being built and read the
+//! overrides on use. All of these options are positive integers, so an unset
+//! override is stored as zero, in which case the configured value applies.
+
+use lazy_static::lazy_static;
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    fs,
+    future::Future,
+    io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+pub const BATCH_MAX_EVENTS: &str = "batch.max_events";
+pub const BATCH_TIMEOUT_SECS: &str = "batch.timeout_secs";
+pub const REQUEST_CONCURRENCY: &str = "request.concurrency";
+pub const SAMPLE_RATE: &str = "rate";
+
+const INVARIANT: &str = "Couldn't acquire lock on tunable options. Please report this.";
+
+tokio::task_local! {
+    static COMPONENT: String;
+}
+
+lazy_static! {
+    /// The overrides by component and option.
+    static ref REGISTRY: RwLock<BTreeMap<String, BTreeMap<String, Tunable>>> =
+        RwLock::new(BTreeMap::new());
+    static ref OVERRIDES_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+#[derive(Debug, Snafu)]
+pub enum TuningError {
+    #[snafu(display("Component {:?} has no tunable option {:?}", component, option))]
+    UnknownOption { component: String, option: String },
+    #[snafu(display("The value of {:?} must be positive", option))]
+    NotPositive { option: String },
+    #[snafu(display("No `api.overrides_path` is configured to persist the overrides to"))]
+    NoOverridesPath,
+    #[snafu(display("Failed to read overrides from {:?}: {}", path, source))]
+    ReadOverrides { path: PathBuf, source: io::Error },
+    #[snafu(display("Failed to parse overrides from {:?}: {}", path, source))]
+    ParseOverrides {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[snafu(display("Failed to write overrides to {:?}: {}", path, source))]
+    WriteOverrides { path: PathBuf, source: io::Error },
+}
+
+/// Override of an option, shared between the component and the registry.
+#[derive(Clone, Debug, Default)]
+pub struct Tunable(Arc<AtomicU64>);
+
+impl Tunable {
+    pub fn get(&self) -> Option<u64> {
+        match self.0.load(Ordering::Relaxed) {
+            0 => None,
+            value => Some(value),
+        }
+    }
+
+    fn set(&self, value: Option<u64>) {
+        self.0.store(value.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Whether a running component reads the override, rather than only the
+    /// registry holding it.
+    fn is_used(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+}
+
+/// Builds the component `name`, so that the options it registers are tunable
+/// under its name.
+pub async fn scope<F: Future>(name: &str, build: F) -> F::Output {
+    COMPONENT.scope(name.to_owned(), build).await
+}
+
+/// Registers the option of the component being built. The override is kept
+/// across rebuilds of the component on reload. Outside of the building of a
+/// component, such as in tests, the option is never overridden.
+pub fn register(option: &str) -> Tunable {
+    COMPONENT
+        .try_with(|component| {
+            REGISTRY
+                .write()
+                .expect(INVARIANT)
+                .entry(component.clone())
+                .or_default()
+                .entry(option.to_owned())
+                .or_default()
+                .clone()
+        })
+        .unwrap_or_default()
+}
+
+/// The tunable options of the running components, with their overrides.
+pub fn options() -> Vec<(String, String, Option<u64>)> {
+    REGISTRY
+        .read()
+        .expect(INVARIANT)
+        .iter()
+        .flat_map(|(component, options)| {
+            options
+                .iter()
+                .filter(|(_, tunable)| tunable.is_used())
+                .map(move |(option, tunable)| (component.clone(), option.clone(), tunable.get()))
+        })
+        .collect()
+}
+
+/// Overrides the option of a running component, or resets it to the
+/// configured value if `value` is `None`.
+pub fn set_override(component: &str, option: &str, value: Option<u64>) -> Result<(), TuningError> {
+    if value == Some(0) {
+        return Err(TuningError::NotPositive {
+            option: option.to_owned(),
+        });
+    }
+
+    let registry = REGISTRY.read().expect(INVARIANT);
+    let tunable = registry
+        .get(component)
+        .and_then(|options| options.get(option))
+        .filter(|tunable| tunable.is_used())
+        .ok_or_else(|| TuningError::UnknownOption {
+            component: component.to_owned(),
+            option: option.to_owned(),
+        })?;
+    tunable.set(value);
+    Ok(())
+}
+
+/// Sets the file the overrides are persisted to, and applies the overrides
+/// already in it to the components built from now on.
+pub fn load_overrides(path: PathBuf) -> Result<(), TuningError> {
+    let overrides = match fs::read_to_string(&path) {
+        Ok(overrides) => toml::from_str::<BTreeMap<String, BTreeMap<String, u64>>>(&overrides)
+            .context(ParseOverrides { path: &path })?,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(error) => return Err(error).context(ReadOverrides { path }),
+    };
+
+    let mut registry = REGISTRY.write().expect(INVARIANT);
+    for (component, options) in overrides {
+        for (option, value) in options {
+            registry
+                .entry(component.clone())
+                .or_default()
+                .entry(option)
+                .or_default()
+                .set(Some(value));
+        }
+    }
+
+    *OVERRIDES_PATH.write().expect(INVARIANT) = Some(path);
+    Ok(())
+}
+
+/// Writes the current overrides to the file they were loaded from.
+pub fn persist() -> Result<(), TuningError> {
+    let path = OVERRIDES_PATH
+        .read()
+        .expect(INVARIANT)
+        .clone()
+        .ok_or(TuningError::NoOverridesPath)?;
+
+    let overrides = REGISTRY
+        .read()
+        .expect(INVARIANT)
+        .iter()
+        .map(|(component, options)| {
+            let options = options
+                .iter()
+                .filter_map(|(option, tunable)| Some((option.clone(), tunable.get()?)))
+                .collect::<BTreeMap<_, _>>();
+            (component.clone(), options)
+        })
+        .filter(|(_, options)| !options.is_empty())
+        .collect::<BTreeMap<_, _>>();
+    let overrides = toml::to_string(&overrides).expect("Overrides are always serializable.");
+
+    fs::write(&path, overrides).context(WriteOverrides { path })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn overrides_registered_options() {
+        let tunable = scope("tuning_test", async { register(SAMPLE_RATE) }).await;
+        assert_eq!(tunable.get(), None);
+        assert!(options().contains(&("tuning_test".to_owned(), SAMPLE_RATE.to_owned(), None)));
+
+        set_override("tuning_test", SAMPLE_RATE, Some(5)).unwrap();
+        assert_eq!(tunable.get(), Some(5));
+        // Rebuilding the component keeps the override.
+        let rebuilt = scope("tuning_test", async { register(SAMPLE_RATE) }).await;
+        assert_eq!(rebuilt.get(), Some(5));
+
+        set_override("tuning_test", SAMPLE_RATE, None).unwrap();
+        assert_eq!(tunable.get(), None);
+
+        assert!(matches!(
+            set_override("tuning_test", SAMPLE_RATE, Some(0)),
+            Err(TuningError::NotPositive { .. })
+        ));
+        assert!(matches!(
+            set_override("tuning_test", BATCH_MAX_EVENTS, Some(1)),
+            Err(TuningError::UnknownOption { .. })
+        ));
+        // Outside of a component, options aren't tunable.
+        assert!(!register(SAMPLE_RATE).is_used());
+    }
+}
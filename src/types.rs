@@ -1,5 +1,5 @@
-use crate::event::Value;
-use chrono::{DateTime, Local, ParseError as ChronoParseError, TimeZone, Utc};
+use crate::{event::Value, sources::util::TimeZone};
+use chrono::{DateTime, NaiveDateTime, ParseError as ChronoParseError, TimeZone as _, Utc};
 use lazy_static::lazy_static;
 use snafu::{ResultExt, Snafu};
 use std::collections::{HashMap, HashSet};
@@ -43,7 +43,8 @@ impl FromStr for Conversion {
     ///  * `"timestamp"` => Timestamp, guessed using a set of formats
     ///  * `"timestamp|FORMAT"` => Timestamp using the given format
     ///
-    /// Timestamp parsing does not yet support time zones.
+    /// Timestamps without zone information are in the time zone given to
+    /// `convert_in`, or the local one.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "asis" | "bytes" | "string" => Ok(Conversion::Bytes),
@@ -113,6 +114,8 @@ pub enum Error {
     TimestampParseError { s: String, source: ChronoParseError },
     #[snafu(display("No matching timestamp format found for {:?}", s))]
     AutoTimestampParseError { s: String },
+    #[snafu(display("Timestamp {:?} doesn't exist in the time zone", s))]
+    NonexistentTimestamp { s: String },
 }
 
 impl Conversion {
@@ -120,6 +123,12 @@ impl Conversion {
     /// new `Value`. This will fail in unexpected ways if the
     /// `value` is not currently a `Value::Bytes`.
     pub fn convert(&self, value: Value) -> Result<Value, Error> {
+        self.convert_in(value, TimeZone::Local)
+    }
+
+    /// Like `convert`, with timestamps without zone information in the
+    /// given time zone.
+    pub fn convert_in(&self, value: Value, timezone: TimeZone) -> Result<Value, Error> {
         let bytes = value.as_bytes();
         Ok(match self {
             Conversion::Bytes => value,
@@ -133,16 +142,15 @@ impl Conversion {
             }
             Conversion::Boolean => Value::Boolean(parse_bool(&String::from_utf8_lossy(&bytes))?),
 
-            Conversion::Timestamp => {
-                Value::Timestamp(parse_timestamp(&String::from_utf8_lossy(&bytes))?)
-            }
+            Conversion::Timestamp => Value::Timestamp(parse_timestamp_in(
+                &String::from_utf8_lossy(&bytes),
+                timezone,
+            )?),
             Conversion::TimestampFmt(format) => {
                 let s = String::from_utf8_lossy(&bytes);
-                Value::Timestamp(datetime_to_utc(
-                    Local
-                        .datetime_from_str(&s, &format)
-                        .with_context(|| TimestampParseError { s })?,
-                ))
+                let datetime = NaiveDateTime::parse_from_str(&s, &format)
+                    .with_context(|| TimestampParseError { s: s.clone() })?;
+                Value::Timestamp(localize(datetime, timezone, &s)?)
             }
             Conversion::TimestampTZFmt(format) => {
                 let s = String::from_utf8_lossy(&bytes);
@@ -188,7 +196,7 @@ fn parse_bool(s: &str) -> Result<bool, Error> {
 }
 
 /// Does the format specifier have a time zone option?
-fn format_has_zone(fmt: &str) -> bool {
+pub fn format_has_zone(fmt: &str) -> bool {
     fmt.find("%Z").is_some()
         || fmt.find("%z").is_some()
         || fmt.find("%:z").is_some()
@@ -197,10 +205,17 @@ fn format_has_zone(fmt: &str) -> bool {
 }
 
 /// Convert a timestamp with a non-UTC time zone into UTC
-fn datetime_to_utc<TZ: TimeZone>(ts: DateTime<TZ>) -> DateTime<Utc> {
+fn datetime_to_utc<TZ: chrono::TimeZone>(ts: DateTime<TZ>) -> DateTime<Utc> {
     Utc.timestamp(ts.timestamp(), ts.timestamp_subsec_nanos())
 }
 
+/// Resolve a wall-clock time of the time zone.
+fn localize(datetime: NaiveDateTime, timezone: TimeZone, s: &str) -> Result<DateTime<Utc>, Error> {
+    timezone
+        .localize(datetime)
+        .ok_or_else(|| Error::NonexistentTimestamp { s: s.into() })
+}
+
 /// The list of allowed "automatic" timestamp formats
 const TIMESTAMP_FORMATS: &[&str] = &[
     "%F %T",           // YYYY-MM-DD HH:MM:SS
@@ -229,9 +244,15 @@ const TIMESTAMP_TZ_FORMATS: &[&str] = &[
 
 /// Parse a string into a timestamp using one of a set of formats
 pub fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, Error> {
+    parse_timestamp_in(s, TimeZone::Local)
+}
+
+/// Like `parse_timestamp`, with timestamps without zone information in the
+/// given time zone.
+pub fn parse_timestamp_in(s: &str, timezone: TimeZone) -> Result<DateTime<Utc>, Error> {
     for format in TIMESTAMP_FORMATS {
-        if let Ok(result) = Local.datetime_from_str(s, format) {
-            return Ok(datetime_to_utc(result));
+        if let Ok(result) = NaiveDateTime::parse_from_str(s, format) {
+            return localize(result, timezone, s);
         }
     }
     for format in TIMESTAMP_UTC_FORMATS {
@@ -356,4 +377,27 @@ mod tests {
         assert!(parse_bool("yes or no").is_err());
         assert!(parse_bool("123.4").is_err());
     }
+
+    #[test]
+    fn timestamp_conversion_in_timezone() {
+        use super::{Conversion, TimeZone};
+        use chrono::{TimeZone as _, Utc};
+        use chrono_tz::Tz;
+
+        let berlin = TimeZone::Named(Tz::Europe__Berlin);
+        let expected = Utc.ymd(2020, 7, 1).and_hms(10, 0, 0).into();
+        for (conversion, value) in &[
+            ("timestamp", "2020-07-01 12:00:00"),
+            ("timestamp|%Y-%m-%d %H:%M:%S", "2020-07-01 12:00:00"),
+            ("timestamp|%+", "2020-07-01T10:00:00Z"),
+        ] {
+            let conversion = conversion.parse::<Conversion>().unwrap();
+            assert_eq!(
+                conversion.convert_in((*value).into(), berlin),
+                Ok(expected),
+                "{:?}",
+                conversion
+            );
+        }
+    }
 }
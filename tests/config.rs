@@ -545,6 +545,53 @@ async fn disabled_healthcheck() {
     .unwrap();
 }
 
+#[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
+#[tokio::test]
+async fn disabled_components() {
+    std::env::set_var("VECTOR_TEST_ENABLE_DEBUG", "false");
+    let config = config::load_from_str(
+        r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1234"
+
+        [sources.debug_in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+        enabled = "${VECTOR_TEST_ENABLE_DEBUG:-true}"
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in", "debug_in"]
+        address = "0.0.0.0:0"
+        encoding = "text"
+
+        [sinks.debug_out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        address = "0.0.0.0:1"
+        encoding = "text"
+        enabled = "${VECTOR_TEST_ENABLE_DEBUG}"
+        "#,
+        Some(Format::TOML),
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.sources.keys().collect::<Vec<_>>(),
+        vec![&"in".to_owned()]
+    );
+    assert_eq!(
+        config.sinks.keys().collect::<Vec<_>>(),
+        vec![&"out".to_owned()]
+    );
+    assert_eq!(config.sinks["out"].inputs, vec!["in".to_owned()]);
+}
+
 #[cfg(all(feature = "sources-stdin", feature = "sinks-http"))]
 #[tokio::test]
 async fn parses_sink_no_request() {
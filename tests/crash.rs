@@ -2,18 +2,26 @@
 #![cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
 
 use async_trait::async_trait;
-use futures::{compat::Future01CompatExt, future, FutureExt, Sink};
+use futures::{
+    compat::{Future01CompatExt, Stream01CompatExt},
+    future, FutureExt, Sink, StreamExt,
+};
 use futures01::Stream;
 use serde::{Deserialize, Serialize};
 use std::{
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
 };
 use tokio::time::{delay_for, Duration};
 use vector::{
-    config::{self, GlobalOptions, SinkConfig, SinkContext, SourceConfig},
+    config::{
+        self, log_schema, GlobalOptions, PanicOptions, PanicPolicy, SinkConfig, SinkContext,
+        SourceConfig, TransformConfig,
+    },
     shutdown::ShutdownSignal,
     test_util::{next_addr, random_lines, send_lines, start_topology, wait_for_tcp, CountReceiver},
+    transforms::{FunctionTransform, Transform},
     Event, Pipeline,
     {
         sinks::{self, Healthcheck, VectorSink},
@@ -326,3 +334,147 @@ async fn test_source_panic() {
     assert_eq!(num_lines, output_lines.len());
     assert_eq!(input_lines, output_lines);
 }
+
+/// Panics on events with the message "panic", counting its builds.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct PanicTransformConfig {
+    #[serde(skip)]
+    builds: Arc<Mutex<usize>>,
+}
+
+impl PanicTransformConfig {
+    fn builds(&self) -> usize {
+        *self.builds.lock().unwrap()
+    }
+}
+
+#[async_trait]
+#[typetag::serde(name = "panic")]
+impl TransformConfig for PanicTransformConfig {
+    async fn build(&self) -> vector::Result<Transform> {
+        *self.builds.lock().unwrap() += 1;
+        Ok(Transform::function(PanicTransform))
+    }
+
+    fn input_type(&self) -> config::DataType {
+        config::DataType::Log
+    }
+
+    fn output_type(&self) -> config::DataType {
+        config::DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "panic"
+    }
+}
+
+#[derive(Clone)]
+struct PanicTransform;
+
+impl FunctionTransform for PanicTransform {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        if event.as_log()[log_schema().message_key()].to_string_lossy() == "panic" {
+            panic!("panic event");
+        }
+        output.push(event);
+    }
+}
+
+fn restart_topology_config(
+    in_addr: std::net::SocketAddr,
+    out_addr: std::net::SocketAddr,
+    transform: PanicTransformConfig,
+    max_restarts: u32,
+) -> config::Config {
+    let mut config = config::Config::builder();
+    config.add_source(
+        "in",
+        sources::socket::SocketConfig::make_basic_tcp_config(in_addr),
+    );
+    config.add_transform("panic", &["in"], transform);
+    config.transforms["panic"].on_panic = PanicOptions {
+        policy: PanicPolicy::Restart,
+        max_restarts,
+    };
+    config.add_sink(
+        "out",
+        &["panic"],
+        sinks::socket::SocketSinkConfig::make_basic_tcp_config(out_addr.to_string()),
+    );
+    config.build().unwrap()
+}
+
+#[tokio::test]
+async fn test_transform_panic_restart() {
+    let in_addr = next_addr();
+    let out_addr = next_addr();
+
+    let transform = PanicTransformConfig::default();
+    let config = restart_topology_config(in_addr, out_addr, transform.clone(), 1);
+
+    let mut output_lines = CountReceiver::receive_lines(out_addr);
+
+    std::panic::set_hook(Box::new(|_| {})); // Suppress panic print on background thread
+    let (topology, crash) = start_topology(config, false).await;
+    // Wait for server to accept traffic
+    wait_for_tcp(in_addr).await;
+    delay_for(Duration::from_millis(100)).await;
+
+    // Wait for output to connect
+    output_lines.connected().await;
+    assert_eq!(transform.builds(), 1);
+
+    let input_lines = vec!["before".to_owned(), "panic".to_owned(), "after".to_owned()];
+    send_lines(in_addr, input_lines).await.unwrap();
+
+    // The transform is only built again after the backoff.
+    delay_for(Duration::from_millis(500)).await;
+    assert_eq!(transform.builds(), 1);
+    delay_for(Duration::from_millis(1000)).await;
+    assert_eq!(transform.builds(), 2);
+    let _ = std::panic::take_hook();
+
+    let mut crash = crash.compat();
+    assert!(crash.next().now_or_never().is_none());
+    topology.stop().compat().await.unwrap();
+    delay_for(Duration::from_millis(100)).await;
+
+    let output_lines = output_lines.await;
+    assert_eq!(output_lines, vec!["before".to_owned(), "after".to_owned()]);
+}
+
+#[tokio::test]
+async fn test_transform_panic_restarts_exhausted() {
+    let in_addr = next_addr();
+    let out_addr = next_addr();
+
+    let transform = PanicTransformConfig::default();
+    let config = restart_topology_config(in_addr, out_addr, transform.clone(), 1);
+
+    let mut output_lines = CountReceiver::receive_lines(out_addr);
+
+    std::panic::set_hook(Box::new(|_| {})); // Suppress panic print on background thread
+    let (topology, crash) = start_topology(config, false).await;
+    // Wait for server to accept traffic
+    wait_for_tcp(in_addr).await;
+    delay_for(Duration::from_millis(100)).await;
+
+    // Wait for output to connect
+    output_lines.connected().await;
+
+    send_lines(in_addr, vec!["panic".to_owned()]).await.unwrap();
+    delay_for(Duration::from_millis(1500)).await;
+    assert_eq!(transform.builds(), 2);
+
+    send_lines(in_addr, vec!["panic".to_owned()]).await.unwrap();
+    delay_for(Duration::from_millis(100)).await;
+    let _ = std::panic::take_hook();
+
+    assert!(crash.wait().next().is_some());
+    assert_eq!(transform.builds(), 2);
+    topology.stop().compat().await.unwrap();
+    delay_for(Duration::from_millis(100)).await;
+
+    assert!(output_lines.await.is_empty());
+}
@@ -5,8 +5,8 @@ use futures::compat::Future01CompatExt;
 use rand::{thread_rng, Rng};
 use serde::Deserialize;
 use serde_json::Value;
-use sinks::socket::{self, SocketSinkConfig};
-use sinks::util::{encoding::EncodingConfig, tcp::TcpSinkConfig, Encoding};
+use sinks::socket::{self, Encoding, SocketSinkConfig};
+use sinks::util::{encoding::EncodingConfig, tcp::TcpSinkConfig};
 use std::{collections::HashMap, fmt, str::FromStr};
 use tokio_util::codec::BytesCodec;
 use vector::{
@@ -32,6 +32,7 @@ async fn test_tcp_syslog() {
             address: in_addr.into(),
             keepalive: None,
             tls: None,
+            proxy_protocol: false,
         }),
     );
     config.add_sink("out", &["in"], tcp_json_sink(out_addr.to_string()));
@@ -149,6 +150,7 @@ async fn test_octet_counting_syslog() {
             address: in_addr.into(),
             keepalive: None,
             tls: None,
+            proxy_protocol: false,
         }),
     );
     config.add_sink("out", &["in"], tcp_json_sink(out_addr.to_string()));